@@ -0,0 +1,312 @@
+//! # Arguments
+//!
+//! The command line, parsed into a [`Command`]. Options may come before or
+//! after the files; `--` ends the options.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use c4d::args::{parse, Command};
+//!
+//! let args: Vec<String> = ["render", "model.scad", "-o", "model.3mf"].map(String::from).to_vec();
+//! let Command::Render { input, output, format, .. } = parse(&args).unwrap() else { unreachable!() };
+//! assert_eq!(input.to_str(), Some("model.scad"));
+//! assert_eq!(output.unwrap().to_str(), Some("model.3mf"));
+//! assert_eq!(format, None);
+//! ```
+
+use std::path::PathBuf;
+
+use openscad_eval::options::parse_override;
+use openscad_eval::Overrides;
+
+use crate::error::{CliError, CliResult};
+use crate::watch::DEFAULT_PORT;
+
+/// Help text.
+pub const USAGE: &str = "\
+Usage: c4d <command> [options]
+
+Commands:
+  render <file> [-o <out>] [-f <format>] [--verify-determinism]
+                        Render a model and export it. The format comes from
+                        --format, else the output's extension, else stl.
+                        `-o -` writes to stdout. --verify-determinism
+                        renders twice and fails unless both are identical.
+  watch <file> [--serve] [--port <n>] [-o <out>] [-f <format>]
+                        Render again whenever the model or a file it
+                        includes changes. --serve serves the mesh and a
+                        viewer on http://127.0.0.1:8000/ (or --port).
+  check <files...> [--strict]
+                        Report problems as JSON. Fails on errors, or on
+                        warnings too with --strict.
+  fmt <files...> [--check]
+                        Format files in place, or with --check list where
+                        they are not formatted and fail.
+  measure <file> [--json]
+                        Print volume, surface area, bounding box and
+                        centroid of the rendered model.
+
+Options:
+  -D <name>=<value>     Set a top-level variable of the model, overriding
+                        its assignment (render, watch and measure)
+  -h, --help            Print this help
+  -V, --version         Print the version
+
+Exit codes: 0 passed, 1 a model failed, 2 bad arguments or unreadable file.
+Library directories come from OPENSCADPATH.
+";
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Render a model to a file.
+    Render {
+        /// The model.
+        input: PathBuf,
+        /// Where to write, `-` for stdout; next to the input by default.
+        output: Option<PathBuf>,
+        /// Export format, overriding the output's extension.
+        format: Option<String>,
+        /// Values of top-level variables, from `-D`.
+        overrides: Overrides,
+        /// Render twice and require identical results.
+        verify: bool,
+    },
+    /// Render a model on every change.
+    Watch {
+        /// The model.
+        input: PathBuf,
+        /// Where to write each render.
+        output: Option<PathBuf>,
+        /// Export format, overriding the output's extension.
+        format: Option<String>,
+        /// Port to serve the mesh and viewer on, if serving.
+        port: Option<u16>,
+        /// Values of top-level variables, from `-D`.
+        overrides: Overrides,
+    },
+    /// Report problems of models.
+    Check {
+        /// The models.
+        files: Vec<PathBuf>,
+        /// Fail on warnings as well as errors.
+        strict: bool,
+    },
+    /// Format models.
+    Fmt {
+        /// The models.
+        files: Vec<PathBuf>,
+        /// Only report where files are not formatted.
+        check: bool,
+    },
+    /// Measure a rendered model.
+    Measure {
+        /// The model.
+        input: PathBuf,
+        /// Print JSON instead of a table.
+        json: bool,
+        /// Values of top-level variables, from `-D`.
+        overrides: Overrides,
+    },
+    /// Print the help.
+    Help,
+    /// Print the version.
+    Version,
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Parse the arguments after the program name.
+///
+/// ## Errors
+///
+/// `CliError::Usage` for an unknown command or option, a missing or
+/// malformed value, or the wrong number of files.
+pub fn parse(args: &[String]) -> CliResult<Command> {
+    let Some((command, rest)) = args.split_first() else { return Ok(Command::Help) };
+    match command.as_str() {
+        "-h" | "--help" | "help" => return Ok(Command::Help),
+        "-V" | "--version" => return Ok(Command::Version),
+        _ => {}
+    }
+
+    let mut files = Vec::new();
+    let mut flags = Vec::new();
+    let mut output = None;
+    let mut format = None;
+    let mut port = None;
+    let mut overrides = Overrides::new();
+    let mut rest = rest.iter();
+    let mut options = true;
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--" if options => options = false,
+            "-h" | "--help" if options => return Ok(Command::Help),
+            "-o" | "--output" if options => output = Some(value(arg, rest.next())?),
+            "-f" | "--format" if options => format = Some(value(arg, rest.next())?),
+            "-p" | "--port" if options => {
+                let value = value(arg, rest.next())?;
+                port = Some(value.parse::<u16>().map_err(|_| CliError::Usage(format!("`{}` is not a port", value)))?);
+            }
+            "-D" | "--define" if options => {
+                let (name, value) = define(&value(arg, rest.next())?)?;
+                overrides.insert(name, value);
+            }
+            define_arg if options && define_arg.starts_with("-D") => {
+                let (name, value) = define(&define_arg[2..])?;
+                overrides.insert(name, value);
+            }
+            flag if options && flag.starts_with('-') && flag != "-" => flags.push(flag),
+            file => files.push(PathBuf::from(file)),
+        }
+    }
+
+    let allowed: &[&str] = match command.as_str() {
+        "render" => &["--verify-determinism"],
+        "watch" => &["--serve"],
+        "check" => &["--strict"],
+        "fmt" => &["--check"],
+        "measure" => &["--json"],
+        other => return Err(CliError::Usage(format!("unknown command `{}`", other))),
+    };
+    if let Some(flag) = flags.iter().find(|f| !allowed.contains(f)) {
+        return Err(CliError::Usage(format!("unknown option `{}` for `{}`", flag, command)));
+    }
+    let renders = command == "render" || command == "watch";
+    if !renders && (output.is_some() || format.is_some()) {
+        return Err(CliError::Usage(format!("`{}` takes no --output or --format", command)));
+    }
+    if command != "watch" && port.is_some() {
+        return Err(CliError::Usage(format!("`{}` takes no --port", command)));
+    }
+    if !renders && command != "measure" && !overrides.is_empty() {
+        return Err(CliError::Usage(format!("`{}` takes no -D", command)));
+    }
+    let flag = |name: &str| flags.contains(&name);
+    Ok(match command.as_str() {
+        "render" => Command::Render {
+            input: single(command, files)?,
+            output: output.map(PathBuf::from),
+            format,
+            overrides,
+            verify: flag("--verify-determinism"),
+        },
+        "watch" => Command::Watch {
+            input: single(command, files)?,
+            output: output.map(PathBuf::from),
+            format,
+            port: port.or(flag("--serve").then_some(DEFAULT_PORT)),
+            overrides,
+        },
+        "check" => Command::Check { files: some(command, files)?, strict: flag("--strict") },
+        "fmt" => Command::Fmt { files: some(command, files)?, check: flag("--check") },
+        _ => Command::Measure { input: single(command, files)?, json: flag("--json"), overrides },
+    })
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// The value following an option.
+fn value(option: &str, value: Option<&String>) -> CliResult<String> {
+    value.cloned().ok_or_else(|| CliError::Usage(format!("`{}` needs a value", option)))
+}
+
+/// A `name=value` override given to `-D`.
+fn define(text: &str) -> CliResult<(String, openscad_eval::Value)> {
+    parse_override(text).map_err(|error| CliError::Usage(format!("bad -D `{}`: {}", text, error)))
+}
+
+/// The one file a command takes.
+fn single(command: &str, mut files: Vec<PathBuf>) -> CliResult<PathBuf> {
+    match files.len() {
+        1 => Ok(files.remove(0)),
+        0 => Err(CliError::Usage(format!("`{}` needs a file", command))),
+        _ => Err(CliError::Usage(format!("`{}` takes one file", command))),
+    }
+}
+
+/// The files of a command taking at least one.
+fn some(command: &str, files: Vec<PathBuf>) -> CliResult<Vec<PathBuf>> {
+    if files.is_empty() {
+        return Err(CliError::Usage(format!("`{}` needs at least one file", command)));
+    }
+    Ok(files)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openscad_eval::Value;
+
+    fn args(line: &str) -> CliResult<Command> {
+        parse(&line.split_whitespace().map(String::from).collect::<Vec<_>>())
+    }
+
+    /// Test each command parses with its options in any position.
+    #[test]
+    fn test_commands() {
+        assert_eq!(args("render --format obj a.scad").unwrap(), Command::Render {
+            input: PathBuf::from("a.scad"),
+            output: None,
+            format: Some("obj".to_string()),
+            overrides: Overrides::new(),
+            verify: false,
+        });
+        assert_eq!(args("check a.scad --strict b.scad").unwrap(), Command::Check {
+            files: vec![PathBuf::from("a.scad"), PathBuf::from("b.scad")],
+            strict: true,
+        });
+        assert_eq!(args("fmt -- --check").unwrap(), Command::Fmt { files: vec![PathBuf::from("--check")], check: false });
+        assert_eq!(args("measure --json a.scad").unwrap(), Command::Measure {
+            input: PathBuf::from("a.scad"),
+            json: true,
+            overrides: Overrides::new(),
+        });
+        assert_eq!(args("watch a.scad --serve").unwrap(), Command::Watch {
+            input: PathBuf::from("a.scad"),
+            output: None,
+            format: None,
+            port: Some(DEFAULT_PORT),
+            overrides: Overrides::new(),
+        });
+        assert!(matches!(args("watch a.scad --port 0").unwrap(), Command::Watch { port: Some(0), .. }));
+        assert!(matches!(args("watch a.scad").unwrap(), Command::Watch { port: None, .. }));
+        assert!(matches!(args("render a.scad --verify-determinism").unwrap(), Command::Render { verify: true, .. }));
+        assert_eq!(args("").unwrap(), Command::Help);
+        assert_eq!(args("--version").unwrap(), Command::Version);
+    }
+
+    /// Test -D sets overrides, separate or joined, with any constant value.
+    #[test]
+    fn test_defines() {
+        let Command::Render { overrides, .. } = args("render -D size=20 a.scad -Dlabel=\"A-01\" --define dims=[1,2]").unwrap() else {
+            panic!("expected render");
+        };
+        assert_eq!(overrides["size"], Value::Number(20.0));
+        assert_eq!(overrides["label"], Value::String("A-01".to_string()));
+        assert_eq!(overrides["dims"], Value::from([1.0, 2.0]));
+        assert!(matches!(args("measure -D h=1 a.scad").unwrap(), Command::Measure { overrides, .. } if overrides.len() == 1));
+        assert!(matches!(args("watch -D h=1 a.scad").unwrap(), Command::Watch { overrides, .. } if overrides.len() == 1));
+    }
+
+    /// Test malformed command lines are usage errors.
+    #[test]
+    fn test_usage_errors() {
+        for line in [
+            "draw a.scad", "render", "render a.scad b.scad", "render a.scad -o", "check", "fmt --strict a.scad", "measure -o x a.scad",
+            "render --port 80 a.scad", "watch --port http a.scad", "render a.scad -D", "render a.scad -D size", "render a.scad -D x=[1,",
+            "check a.scad -D x=1", "fmt a.scad -Dx=1", "measure a.scad --verify-determinism",
+        ] {
+            assert!(matches!(args(line), Err(CliError::Usage(_))), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! # Check
+//!
+//! `c4d check <files...> [--strict]`: the problems the language server
+//! would show for each model, as a JSON array on stdout, for CI to fail on
+//! or annotate from:
+//!
+//! ```json
+//! [{"file": "model.scad", "line": 3, "column": 1, "end_line": 3, "end_column": 9,
+//!   "severity": "warning", "code": "degenerate", "message": "cube(): size is zero"}]
+//! ```
+//!
+//! Lines and columns count from 1; columns are in UTF-16 units, as in the
+//! editor. `code` is one of the [diagnostic codes](openscad_lsp::diagnostics),
+//! `eval-error` for an error that stops evaluation (a failed `assert()`,
+//! an unknown variable in a function), or `null` for other parse errors.
+//!
+//! Files are checked together, so a model and the library it uses are
+//! each checked once. The check fails on any error, and with `--strict`
+//! on warnings too; hints such as unused declarations never fail it.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Range};
+use openscad_eval::EvalError;
+use openscad_lsp::diagnostics::workspace_findings;
+use openscad_lsp::document::Document;
+use openscad_lsp::workspace::{to_uri, Workspace};
+use serde_json::{json, Value};
+
+use crate::error::{CliError, CliResult, EXIT_FAILED, EXIT_OK};
+use crate::model::{eval_options, library_paths, read};
+use crate::print;
+
+/// Code of an error that stops evaluation.
+pub const EVAL_ERROR: &str = "eval-error";
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Check `files` and write their problems to `out` as JSON.
+///
+/// ## Returns
+///
+/// `EXIT_FAILED` if there is an error, or a warning when `strict`.
+///
+/// ## Errors
+///
+/// `CliError::Io` if a file cannot be read or its output written.
+pub fn check(files: &[PathBuf], strict: bool, out: &mut dyn Write) -> CliResult<u8> {
+    let mut workspace = library_paths().into_iter().fold(Workspace::new(), Workspace::with_library_path);
+    let mut opened = Vec::new();
+    for path in files {
+        let text = read(path)?;
+        let absolute = path.canonicalize().map_err(|source| CliError::Io { path: path.clone(), source })?;
+        let uri = to_uri(&absolute).ok_or_else(|| CliError::Usage(format!("{}: not a valid file path", path.display())))?;
+        workspace.open(uri.clone(), Document::new(text, 1));
+        opened.push((path, uri));
+    }
+
+    let mut problems = Vec::new();
+    for (path, uri) in &opened {
+        let Some(document) = workspace.document(uri) else { continue };
+        let mut diagnostics: Vec<Diagnostic> = workspace_findings(&workspace, uri).into_iter().map(|f| f.diagnostic).collect();
+        // An evaluation error would only repeat a parse or link error
+        if diagnostics.iter().all(|d| d.severity != Some(DiagnosticSeverity::ERROR)) {
+            diagnostics.extend(eval_error(path, document));
+        }
+        problems.extend(diagnostics.iter().map(|d| problem(path, d)));
+    }
+
+    let failed = problems.iter().any(|p| p["severity"] == "error" || strict && p["severity"] == "warning");
+    let text = serde_json::to_string_pretty(&problems).unwrap_or_default();
+    print(out, &text)?;
+    Ok(if failed { EXIT_FAILED } else { EXIT_OK })
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// The error that stops a full evaluation of a document, if any, at the
+/// failed `assert()` or else the start of the file.
+fn eval_error(path: &Path, document: &Document) -> Option<Diagnostic> {
+    let error = openscad_eval::evaluate_with_options(&document.text, &eval_options(path)).err()?;
+    let range = match &error {
+        EvalError::AssertionFailed { span, .. } => document.range_of(*span),
+        _ => Range::default(),
+    };
+    Some(Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(EVAL_ERROR.to_string())),
+        source: Some("openscad".to_string()),
+        message: error.to_string(),
+        ..Diagnostic::default()
+    })
+}
+
+/// A diagnostic as reported.
+fn problem(path: &Path, diagnostic: &Diagnostic) -> Value {
+    let severity = match diagnostic.severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::INFORMATION) => "information",
+        _ => "hint",
+    };
+    let code = match &diagnostic.code {
+        Some(NumberOrString::String(code)) => Value::from(code.as_str()),
+        Some(NumberOrString::Number(code)) => Value::from(*code),
+        None => Value::Null,
+    };
+    let Range { start, end } = diagnostic.range;
+    json!({
+        "file": path.display().to_string(),
+        "line": start.line + 1,
+        "column": start.character + 1,
+        "end_line": end.line + 1,
+        "end_column": end.character + 1,
+        "severity": severity,
+        "code": code,
+        "message": diagnostic.message,
+    })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checked `files` of a temp dir, with the exit code.
+    fn run(name: &str, files: &[(&str, &str)], strict: bool) -> (u8, Vec<Value>) {
+        let dir = crate::temp_dir(name);
+        for (file, text) in files {
+            std::fs::write(dir.join(file), text).unwrap();
+        }
+        let paths: Vec<PathBuf> = files.iter().map(|(file, _)| dir.join(file)).collect();
+        let mut out = Vec::new();
+        let code = check(&paths, strict, &mut out).unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+        (code, serde_json::from_slice(&out).unwrap())
+    }
+
+    /// Test clean models pass and warnings only fail when strict.
+    #[test]
+    fn test_severities() {
+        let files = [("lib.scad", "module peg() { cube(1); }"), ("main.scad", "use <lib.scad>\npeg();")];
+        assert_eq!(run("clean", &files, true), (EXIT_OK, Vec::new()));
+
+        let files = [("main.scad", "cube(0);")];
+        let (code, problems) = run("warning", &files, false);
+        assert_eq!(code, EXIT_OK);
+        assert_eq!(problems[0]["severity"], "warning");
+        assert_eq!(problems[0]["code"], "degenerate");
+        assert_eq!(problems[0]["line"], 1);
+        assert_eq!(run("strict", &files, true).0, EXIT_FAILED);
+    }
+
+    /// Test parse, link and evaluation errors fail the check.
+    #[test]
+    fn test_errors() {
+        let (code, problems) = run("errors", &[("main.scad", "include <missing.scad>\nx = 1\ncube(x);")], false);
+        assert_eq!(code, EXIT_FAILED);
+        assert!(problems.iter().any(|p| p["code"] == "missing-file" && p["severity"] == "warning"));
+        assert!(problems.iter().any(|p| p["code"] == "missing-semicolon" && p["severity"] == "error"));
+
+        let (code, problems) = run("assert", &[("main.scad", "cube(1);\nassert(1 > 2, \"too small\");")], false);
+        assert_eq!(code, EXIT_FAILED);
+        assert_eq!(problems[0]["code"], EVAL_ERROR);
+        assert_eq!(problems[0]["line"], 2);
+    }
+}
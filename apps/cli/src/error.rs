@@ -0,0 +1,57 @@
+//! # CLI Errors
+//!
+//! Errors that stop a command, and the exit code each maps to.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// The command ran and everything passed.
+pub const EXIT_OK: u8 = 0;
+
+/// The command ran and found a problem with a model: an error, a failed
+/// render, an unformatted file.
+pub const EXIT_FAILED: u8 = 1;
+
+/// The command could not run: bad arguments or an unreadable file.
+pub const EXIT_USAGE: u8 = 2;
+
+/// Errors that stop a command.
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// Arguments that do not form a command.
+    #[error("{0}")]
+    Usage(String),
+
+    /// A file could not be read or written.
+    #[error("{}: {source}", path.display())]
+    Io {
+        /// The file.
+        path: PathBuf,
+        /// What went wrong.
+        source: std::io::Error,
+    },
+
+    /// The model could not be evaluated or rendered.
+    #[error("{}: {message}", path.display())]
+    Model {
+        /// The model's file.
+        path: PathBuf,
+        /// What went wrong.
+        message: String,
+    },
+}
+
+impl CliError {
+    /// The process exit code for this error.
+    #[must_use]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Usage(_) | Self::Io { .. } => EXIT_USAGE,
+            Self::Model { .. } => EXIT_FAILED,
+        }
+    }
+}
+
+/// Result type for commands.
+pub type CliResult<T> = Result<T, CliError>;
@@ -0,0 +1,115 @@
+//! # Format
+//!
+//! `c4d fmt <files...> [--check]`: rewrite models with the parser's
+//! [formatter](openscad_parser::format), as the editor's format command
+//! does. With `--check` nothing is written; each spot that would change
+//! is listed as `path:line:column`, found with a
+//! [`SourceMap`](openscad_parser::SourceMap) between the file and its
+//! formatted text, and the command fails if there are any, for CI.
+//!
+//! Files that do not parse are reported and left alone, and fail the
+//! command either way.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use openscad_parser::SourceMap;
+
+use crate::error::{CliError, CliResult, EXIT_FAILED, EXIT_OK};
+use crate::model::read;
+use crate::print;
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Format `files` in place, or only list where they are not formatted
+/// when `check`, writing what happened to `out`.
+///
+/// ## Returns
+///
+/// `EXIT_FAILED` if a file does not parse, or is not formatted when
+/// `check`.
+///
+/// ## Errors
+///
+/// `CliError::Io` if a file cannot be read or written.
+pub fn fmt(files: &[PathBuf], check: bool, out: &mut dyn Write) -> CliResult<u8> {
+    let mut failed = false;
+    for path in files {
+        let source = read(path)?;
+        let line = match openscad_parser::format::format(&source) {
+            Err(error) => {
+                failed = true;
+                let at = error.span.start;
+                format!("{}:{}:{}: {}", path.display(), at.line + 1, at.column + 1, error.kind)
+            }
+            Ok(formatted) if formatted == source => continue,
+            Ok(formatted) if check => {
+                failed = true;
+                unformatted(path, &source, &formatted)
+            }
+            Ok(formatted) => {
+                write(path, &formatted)?;
+                format!("{}: formatted", path.display())
+            }
+        };
+        print(out, &line)?;
+    }
+    Ok(if failed { EXIT_FAILED } else { EXIT_OK })
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// One line per spot of `source` the formatter rewrites, or one for the
+/// whole file if the rewrite cannot be mapped.
+fn unformatted(path: &Path, source: &str, formatted: &str) -> String {
+    let Some(map) = SourceMap::between(source, formatted) else {
+        return format!("{}: not formatted", path.display());
+    };
+    let lines: Vec<String> = map.changes()
+        .iter()
+        .map(|change| format!("{}:{}:{}: not formatted", path.display(), change.old.start.line + 1, change.old.start.column + 1))
+        .collect();
+    lines.join("\n")
+}
+
+/// Replace the text of a file.
+fn write(path: &Path, text: &str) -> CliResult<()> {
+    std::fs::write(path, text).map_err(|source| CliError::Io { path: path.to_path_buf(), source })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test `--check` lists where files are not formatted without touching
+    /// them, and a plain run formats them.
+    #[test]
+    fn test_fmt() {
+        let dir = crate::temp_dir("fmt");
+        let (clean, messy, broken) = (dir.join("clean.scad"), dir.join("messy.scad"), dir.join("broken.scad"));
+        std::fs::write(&clean, "cube(1);\n").unwrap();
+        std::fs::write(&messy, "cube( 1 ) ;").unwrap();
+        std::fs::write(&broken, "cube(1").unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(fmt(&[clean.clone(), messy.clone()], true, &mut out).unwrap(), EXIT_FAILED);
+        assert_eq!(String::from_utf8(out).unwrap(), format!("{0}:1:6: not formatted\n{0}:1:8: not formatted\n{0}:1:10: not formatted\n{0}:1:12: not formatted\n", messy.display()));
+        assert_eq!(std::fs::read_to_string(&messy).unwrap(), "cube( 1 ) ;");
+
+        assert_eq!(fmt(&[clean.clone(), messy.clone()], false, &mut Vec::new()).unwrap(), EXIT_OK);
+        assert_eq!(std::fs::read_to_string(&messy).unwrap(), "cube(1);\n");
+        assert_eq!(fmt(&[clean, messy], true, &mut Vec::new()).unwrap(), EXIT_OK);
+
+        assert_eq!(fmt(std::slice::from_ref(&broken), false, &mut Vec::new()).unwrap(), EXIT_FAILED);
+        assert_eq!(std::fs::read_to_string(&broken).unwrap(), "cube(1");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
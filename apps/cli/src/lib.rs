@@ -0,0 +1,96 @@
+//! # c4d
+//!
+//! Command-line front end of the pipeline, for scripts and for CI in
+//! repositories of models.
+//!
+//! ## Architecture
+//!
+//! ```text
+//! main ─ args (command line → Command)
+//!          └── run
+//!               ├── render  (evaluate, mesh, export)
+//!               ├── watch   (render on change, serve a live viewer)
+//!               ├── check   (language server diagnostics as JSON)
+//!               ├── fmt     (parser's formatter)
+//!               └── measure (volume, area, bounds of the mesh)
+//! ```
+//!
+//! ## Exit codes
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0 | Passed |
+//! | 1 | A model failed: an error, a failed render, an unformatted file |
+//! | 2 | Bad arguments, or a file that cannot be read or written |
+//!
+//! ## Example
+//!
+//! ```rust
+//! use c4d::error::EXIT_USAGE;
+//!
+//! let args = vec!["render".to_string()];
+//! let mut stderr = Vec::new();
+//! assert_eq!(c4d::run(&args, &mut Vec::new(), &mut stderr), EXIT_USAGE);
+//! assert!(String::from_utf8(stderr).unwrap().starts_with("error: `render` needs a file"));
+//! ```
+
+pub mod args;
+pub mod check;
+pub mod error;
+pub mod fmt;
+pub mod measure;
+pub mod model;
+pub mod render;
+pub mod watch;
+
+use std::io::Write;
+
+use args::{Command, USAGE};
+use error::{CliError, CliResult, EXIT_OK};
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Run a command line, without the program name, writing results to `out`
+/// and messages to `log`.
+///
+/// ## Returns
+///
+/// The exit code; see the [crate docs](crate).
+pub fn run(args: &[String], out: &mut dyn Write, log: &mut dyn Write) -> u8 {
+    let result = args::parse(args).and_then(|command| match command {
+        Command::Render { input, output, format, overrides, verify } => {
+            render::render(&input, output.as_deref(), format.as_deref(), &overrides, verify, out, log)
+        }
+        Command::Watch { input, output, format, port, overrides } => {
+            watch::watch(&input, output.as_deref(), format.as_deref(), port, &overrides, log)
+        }
+        Command::Check { files, strict } => check::check(&files, strict, out),
+        Command::Fmt { files, check } => fmt::fmt(&files, check, out),
+        Command::Measure { input, json, overrides } => measure::measure(&input, json, &overrides, out, log),
+        Command::Help => print(out, USAGE.trim_end()).map(|()| EXIT_OK),
+        Command::Version => print(out, &format!("c4d {}", env!("CARGO_PKG_VERSION"))).map(|()| EXIT_OK),
+    });
+    result.unwrap_or_else(|error| {
+        let _ = writeln!(log, "error: {}", error);
+        if matches!(error, CliError::Usage(_)) {
+            let _ = writeln!(log, "Run `c4d --help` for usage.");
+        }
+        error.exit_code()
+    })
+}
+
+/// Write a line of results to `out`.
+pub(crate) fn print(out: &mut dyn Write, text: &str) -> CliResult<()> {
+    writeln!(out, "{}", text).map_err(|source| CliError::Io { path: "-".into(), source })
+}
+
+/// A fresh directory for a test's files.
+#[cfg(test)]
+pub(crate) fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("c4d-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,15 @@
+//! # c4d
+//!
+//! Renders, checks, formats and measures OpenSCAD models from the command
+//! line; see `c4d --help`.
+
+use std::io::Write;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut stdout = std::io::stdout().lock();
+    let code = c4d::run(&args, &mut stdout, &mut std::io::stderr());
+    let _ = stdout.flush();
+    ExitCode::from(code)
+}
@@ -0,0 +1,147 @@
+//! # Measure
+//!
+//! `c4d measure <file> [--json] [-D name=value]`: render a model and print its
+//! [measurements](manifold_rs::mesh::measure), for checking print volume
+//! and size in CI:
+//!
+//! ```text
+//! triangles     12
+//! volume        1000
+//! surface_area  600
+//! min           0 0 0
+//! max           10 10 10
+//! size          10 10 10
+//! centroid      5 5 5
+//! genus         0
+//! ```
+//!
+//! `--json` prints the same as an object, with `min`, `max`, `size` and
+//! `centroid` as arrays. The centroid and genus are `-` (`null`) for a
+//! mesh that is not closed. A model that renders nothing fails.
+
+use std::io::Write;
+use std::path::Path;
+
+use openscad_eval::{Bounds, Overrides};
+use serde_json::{json, Value};
+
+use crate::error::{CliError, CliResult, EXIT_OK};
+use crate::model::render_mesh;
+use crate::print;
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Render `input` with `overrides` and write its measurements to `out`,
+/// its console output to `log`.
+///
+/// ## Errors
+///
+/// `CliError::Io` if the file cannot be read, `CliError::Model` if it
+/// fails to render or renders nothing.
+pub fn measure(input: &Path, json: bool, overrides: &Overrides, out: &mut dyn Write, log: &mut dyn Write) -> CliResult<u8> {
+    let mesh = render_mesh(input, overrides, log)?;
+    if mesh.is_empty() {
+        return Err(CliError::Model { path: input.to_path_buf(), message: "the model has no 3D geometry".to_string() });
+    }
+    let points: Vec<[f64; 3]> = mesh.vertices.chunks_exact(3).map(|v| [v[0], v[1], v[2]].map(f64::from)).collect();
+    let bounds = Bounds::of_points(&points);
+    let size: [f64; 3] = std::array::from_fn(|i| bounds.max[i] - bounds.min[i]);
+    let measurements = [
+        ("triangles", json!(mesh.triangle_count())),
+        ("volume", json!(mesh.volume())),
+        ("surface_area", json!(mesh.surface_area())),
+        ("min", json!(bounds.min)),
+        ("max", json!(bounds.max)),
+        ("size", json!(size)),
+        ("centroid", json!(mesh.centroid())),
+        ("genus", json!(mesh.genus())),
+    ];
+
+    let text = if json {
+        let object: serde_json::Map<String, Value> = measurements.into_iter().map(|(name, value)| (name.to_string(), value)).collect();
+        serde_json::to_string_pretty(&object).unwrap_or_default()
+    } else {
+        table(&measurements)
+    };
+    print(out, &text)?;
+    Ok(EXIT_OK)
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Measurements as aligned `name value` lines.
+fn table(measurements: &[(&str, Value)]) -> String {
+    let lines: Vec<String> = measurements.iter().map(|(name, value)| {
+        let value = match value {
+            Value::Array(items) => items.iter().map(number).collect::<Vec<_>>().join(" "),
+            value => number(value),
+        };
+        format!("{:<13} {}", name, value)
+    }).collect();
+    lines.join("\n")
+}
+
+/// A number rounded to 6 decimals without trailing zeros, `-` for null.
+fn number(value: &Value) -> String {
+    match value.as_f64() {
+        Some(n) => {
+            let text = format!("{:.6}", n);
+            let text = text.trim_end_matches('0').trim_end_matches('.');
+            // Rounding leaves "-0" for tiny negative numbers
+            if text == "-0" { "0".to_string() } else { text.to_string() }
+        }
+        None => "-".to_string(),
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test a cube measures as expected, as a table and as JSON.
+    #[test]
+    fn test_measure() {
+        let dir = crate::temp_dir("measure");
+        let model = dir.join("model.scad");
+        std::fs::write(&model, "x = 1;\ntranslate([x, 0, 0]) cube([2, 3, 4]);").unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(measure(&model, false, &Overrides::new(), &mut out, &mut Vec::new()).unwrap(), EXIT_OK);
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("volume        24\n"), "{}", text);
+        assert!(text.contains("min           1 0 0\nmax           3 3 4\nsize          2 3 4\ncentroid      2 1.5 2\n"), "{}", text);
+
+        let mut out = Vec::new();
+        measure(&model, true, &Overrides::new(), &mut out, &mut Vec::new()).unwrap();
+        let value: Value = serde_json::from_slice(&out).unwrap();
+        assert!((value["surface_area"].as_f64().unwrap() - 52.0).abs() < 1e-6);
+        assert_eq!(value["genus"], 0);
+
+        let mut out = Vec::new();
+        let overrides = Overrides::from([("x".to_string(), openscad_eval::Value::Number(0.0))]);
+        measure(&model, false, &overrides, &mut out, &mut Vec::new()).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("min           0 0 0\n"));
+
+        std::fs::write(&model, "cube(0);").unwrap();
+        assert!(measure(&model, false, &Overrides::new(), &mut Vec::new(), &mut Vec::new()).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Test numbers print without float noise.
+    #[test]
+    fn test_number() {
+        assert_eq!(number(&json!(24.0)), "24");
+        assert_eq!(number(&json!(1.0 / 3.0)), "0.333333");
+        assert_eq!(number(&json!(2.5)), "2.5");
+        assert_eq!(number(&json!(-1e-9)), "0");
+        assert_eq!(number(&Value::Null), "-");
+    }
+}
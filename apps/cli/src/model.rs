@@ -0,0 +1,155 @@
+//! # Models
+//!
+//! Reading a model from disk and evaluating it the way OpenSCAD would:
+//! `include` and `use` paths resolve next to the including file, then in
+//! the `OPENSCADPATH` directories; `import()` and `surface()` files next to
+//! the file naming them. `echo()` output and warnings go to the
+//! log as `ECHO:` and `WARNING:` lines, even when the render fails.
+//! Values given with `-D` replace the model's top-level assignments.
+//!
+//! [`ModelFiles`] records the files an evaluation loaded, which `watch`
+//! watches along with the model.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use manifold_rs::Mesh;
+use openscad_eval::files::FsFileProvider;
+use openscad_eval::{EvalOptions, FileProvider, LogEntry, Overrides, SourceFile};
+
+use crate::error::{CliError, CliResult};
+
+/// The `include`, `use`, `import()` and `surface()` files of a model,
+/// from disk, remembering each one loaded.
+#[derive(Debug)]
+pub struct ModelFiles {
+    files: FsFileProvider,
+    loaded: Mutex<Vec<PathBuf>>,
+}
+
+impl ModelFiles {
+    /// Files of the model at `path`: next to it, then in `OPENSCADPATH`.
+    #[must_use]
+    pub fn new(path: &Path) -> Self {
+        let root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let files = library_paths().into_iter().fold(FsFileProvider::new(root), FsFileProvider::with_library_path);
+        Self { files, loaded: Mutex::new(Vec::new()) }
+    }
+
+    /// The files loaded so far, in load order, each once.
+    #[must_use]
+    pub fn loaded(&self) -> Vec<PathBuf> {
+        self.loaded.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+impl ModelFiles {
+    /// Remember a loaded file.
+    fn record(&self, name: &str) {
+        let mut loaded = self.loaded.lock().unwrap_or_else(PoisonError::into_inner);
+        let name = PathBuf::from(name);
+        if !loaded.contains(&name) {
+            loaded.push(name);
+        }
+    }
+}
+
+impl FileProvider for ModelFiles {
+    fn load(&self, path: &str, from: Option<&str>) -> Option<SourceFile> {
+        let file = self.files.load(path, from)?;
+        self.record(&file.name);
+        Some(file)
+    }
+
+    fn resolve(&self, path: &str, from: Option<&str>) -> Option<String> {
+        let name = self.files.resolve(path, from)?;
+        self.record(&name);
+        Some(name)
+    }
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// The text of a file.
+///
+/// ## Errors
+///
+/// `CliError::Io` if it cannot be read.
+pub fn read(path: &Path) -> CliResult<String> {
+    std::fs::read_to_string(path).map_err(|source| CliError::Io { path: path.to_path_buf(), source })
+}
+
+/// Library directories from `OPENSCADPATH`.
+#[must_use]
+pub fn library_paths() -> Vec<PathBuf> {
+    std::env::var_os("OPENSCADPATH").map(|paths| std::env::split_paths(&paths).collect()).unwrap_or_default()
+}
+
+/// Evaluation options for the model at `path`.
+#[must_use]
+pub fn eval_options(path: &Path) -> EvalOptions {
+    EvalOptions { file_provider: Some(Arc::new(ModelFiles::new(path))), ..EvalOptions::default() }
+}
+
+/// Run `render` with the options of the model at `path` and `overrides`,
+/// writing its console output and warnings to `log`.
+///
+/// ## Errors
+///
+/// `CliError::Model` with the evaluation or render error.
+pub fn with_log<T>(
+    path: &Path,
+    overrides: &Overrides,
+    log: &mut dyn Write,
+    render: impl FnOnce(&EvalOptions) -> Result<T, manifold_rs::ManifoldError>,
+) -> CliResult<T> {
+    with_files(path, Arc::new(ModelFiles::new(path)), overrides, log, render)
+}
+
+/// [`with_log`], loading the model's files through `files`.
+///
+/// ## Errors
+///
+/// `CliError::Model` with the evaluation or render error.
+pub fn with_files<T>(
+    path: &Path,
+    files: Arc<ModelFiles>,
+    overrides: &Overrides,
+    log: &mut dyn Write,
+    render: impl FnOnce(&EvalOptions) -> Result<T, manifold_rs::ManifoldError>,
+) -> CliResult<T> {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&lines);
+    let options = EvalOptions {
+        log: Some(Arc::new(move |entry: LogEntry| {
+            let line = match entry {
+                LogEntry::Message(message) => format!("ECHO: {}", message.text),
+                LogEntry::Diagnostic(diagnostic) => format!("WARNING: {}", diagnostic.text),
+            };
+            sink.lock().unwrap_or_else(PoisonError::into_inner).push(line);
+        })),
+        file_provider: Some(files),
+        overrides: overrides.clone(),
+        ..EvalOptions::default()
+    };
+    let result = render(&options);
+    for line in lines.lock().unwrap_or_else(PoisonError::into_inner).iter() {
+        // The log is best effort; a closed stderr must not fail the render
+        let _ = writeln!(log, "{}", line);
+    }
+    result.map_err(|error| CliError::Model { path: path.to_path_buf(), message: error.to_string() })
+}
+
+/// The mesh of the model at `path` with `overrides`.
+///
+/// ## Errors
+///
+/// `CliError::Io` if the file cannot be read, `CliError::Model` if it
+/// does not render.
+pub fn render_mesh(path: &Path, overrides: &Overrides, log: &mut dyn Write) -> CliResult<Mesh> {
+    let source = read(path)?;
+    with_log(path, overrides, log, |options| manifold_rs::render_with_eval_options(&source, options))
+}
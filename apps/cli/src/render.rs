@@ -0,0 +1,220 @@
+//! # Render
+//!
+//! `c4d render model.scad [-o out] [--format fmt] [-D name=value]
+//! [--verify-determinism]`: mesh a model and write it out. Meshes export
+//! to any of the mesh formats (`stl`, `3mf`, `amf`, `glb`, `obj`, `off`),
+//! 2D models to `svg` or `dxf`.
+//!
+//! The format is `--format` if given, else the output's extension, else
+//! `stl`. Without `-o` the file goes next to the model with the format's
+//! extension; `-o -` writes it to stdout.
+//!
+//! A model that renders nothing fails rather than writing an empty file,
+//! and so does a 2D model written to a mesh format.
+//!
+//! `--verify-determinism` renders the model twice from scratch and fails
+//! unless the two meshes, or drawings, are byte-identical and the console
+//! output matches (see [`manifold_rs::determinism`]); CI runs it to catch
+//! nondeterminism before it shows up as a flaky diff.
+
+use std::io::Write;
+use std::path::Path;
+
+use manifold_rs::mesh::EXPORT_FORMATS;
+use manifold_rs::Mesh;
+use manifold_rs::determinism::{verify_determinism_with_eval_options, verify_drawing_determinism};
+use manifold_rs::openscad::drawing::EXPORT_2D_FORMATS;
+use openscad_eval::Overrides;
+
+use crate::error::{CliError, CliResult, EXIT_OK};
+use crate::model::{read, with_log};
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Render `input` with `overrides` and write it to `output`, or to `out`
+/// for `-`; with `verify`, render it twice and require identical results.
+/// Progress and the model's console output go to `log`.
+///
+/// ## Errors
+///
+/// `CliError::Usage` for an unknown format, `CliError::Io` if a file
+/// cannot be read or written, `CliError::Model` if the model fails to
+/// render, renders nothing, is 2D for a mesh format, or with `verify`
+/// renders differently twice.
+pub fn render(
+    input: &Path,
+    output: Option<&Path>,
+    format: Option<&str>,
+    overrides: &Overrides,
+    verify: bool,
+    out: &mut dyn Write,
+    log: &mut dyn Write,
+) -> CliResult<u8> {
+    let format = export_format(output, format)?;
+    let source = read(input)?;
+    let (bytes, summary) = if EXPORT_2D_FORMATS.contains(&format.as_str()) {
+        let bytes = with_log(input, overrides, log, |options| {
+            if verify {
+                verify_drawing_determinism(&source, &format, options)
+            } else {
+                manifold_rs::render_2d_with_eval_options(&source, &format, options)
+            }
+        })?;
+        (bytes, "2D outline".to_string())
+    } else {
+        let mesh = with_log(input, overrides, log, |options| {
+            if verify {
+                verify_determinism_with_eval_options(&source, options)
+            } else {
+                manifold_rs::render_with_eval_options(&source, options)
+            }
+        })?;
+        let bytes = export_mesh(input, &mesh, &format)?;
+        (bytes, format!("{} triangles", mesh.triangle_count()))
+    };
+
+    let output = output.map_or_else(|| input.with_extension(&format), Path::to_path_buf);
+    if output == Path::new("-") {
+        out.write_all(&bytes).map_err(|source| CliError::Io { path: output, source })?;
+    } else {
+        std::fs::write(&output, &bytes).map_err(|source| CliError::Io { path: output.clone(), source })?;
+        let verified = if verify { ", deterministic" } else { "" };
+        let _ = writeln!(log, "Wrote {} ({}{})", output.display(), summary, verified);
+    }
+    Ok(EXIT_OK)
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// The export format, lowercase: `format`, else the output's extension,
+/// else `stl`.
+pub(crate) fn export_format(output: Option<&Path>, format: Option<&str>) -> CliResult<String> {
+    let extension = output.filter(|o| *o != Path::new("-")).and_then(Path::extension).and_then(|e| e.to_str());
+    let format = format.or(extension).unwrap_or("stl").to_ascii_lowercase();
+    if !EXPORT_FORMATS.contains(&format.as_str()) && !EXPORT_2D_FORMATS.contains(&format.as_str()) {
+        let known: Vec<&str> = EXPORT_FORMATS.iter().chain(EXPORT_2D_FORMATS).copied().collect();
+        return Err(CliError::Usage(format!("unknown format `{}` (expected one of {})", format, known.join(", "))));
+    }
+    Ok(format)
+}
+
+/// The bytes of `mesh` in the mesh format `format`.
+///
+/// A flat mesh, which is what a 2D model meshes to, is rejected: it is not
+/// a solid, so the user is pointed to the 2D formats instead.
+///
+/// ## Errors
+///
+/// `CliError::Model` if the mesh is empty or flat, or cannot be written
+/// as `format`.
+pub(crate) fn export_mesh(input: &Path, mesh: &Mesh, format: &str) -> CliResult<Vec<u8>> {
+    let error = |message: String| CliError::Model { path: input.to_path_buf(), message };
+    let Some((min, max)) = mesh.bounding_box().filter(|_| !mesh.is_empty()) else {
+        return Err(error("the model has no 3D geometry".to_string()));
+    };
+    if (0..3).any(|axis| max[axis] - min[axis] <= 0.0) {
+        return Err(error(format!(
+            "the model is 2D and cannot be exported as {}; use {} instead",
+            format,
+            EXPORT_2D_FORMATS.join(" or "),
+        )));
+    }
+    mesh.export(format).ok_or_else(|| error(format!("cannot export a mesh as {}", format)))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the format comes from the flag, then the extension, then stl.
+    #[test]
+    fn test_export_format() {
+        assert_eq!(export_format(Some(Path::new("a.obj")), Some("3MF")).unwrap(), "3mf");
+        assert_eq!(export_format(Some(Path::new("a.OBJ")), None).unwrap(), "obj");
+        assert_eq!(export_format(Some(Path::new("-")), None).unwrap(), "stl");
+        assert_eq!(export_format(None, Some("svg")).unwrap(), "svg");
+        assert!(matches!(export_format(Some(Path::new("a.png")), None), Err(CliError::Usage(_))));
+    }
+
+    /// Test mesh export fails rather than writing nothing.
+    #[test]
+    fn test_export_mesh() {
+        let path = Path::new("model.scad");
+        let cube = manifold_rs::render("cube(1);").unwrap();
+        assert!(export_mesh(path, &cube, "off").unwrap().starts_with(b"NOFF"));
+        assert!(matches!(export_mesh(path, &cube, "ply"), Err(CliError::Model { .. })));
+        let square = manifold_rs::render("square(1);").unwrap();
+        assert!(matches!(export_mesh(path, &square, "stl"), Err(CliError::Model { .. })));
+    }
+
+    /// Test a model renders next to itself, to stdout, and fails when
+    /// empty or 2D for a mesh format.
+    #[test]
+    fn test_render() {
+        let dir = crate::temp_dir("render");
+        let model = dir.join("model.scad");
+        std::fs::write(dir.join("part.scad"), "module part() { cube(2); }").unwrap();
+        std::fs::write(&model, "use <part.scad>\necho(\"hi\");\npart();").unwrap();
+
+        let (mut out, mut log) = (Vec::new(), Vec::new());
+        assert_eq!(render(&model, None, None, &Overrides::new(), false, &mut out, &mut log).unwrap(), EXIT_OK);
+        let stl = manifold_rs::Mesh::from_stl(&std::fs::read(dir.join("model.stl")).unwrap()).unwrap();
+        assert_eq!(stl.triangle_count(), 12);
+        let log = String::from_utf8(log).unwrap();
+        assert!(log.starts_with("ECHO: \"hi\"\nWrote"), "{}", log);
+
+        render(&model, Some(Path::new("-")), Some("obj"), &Overrides::new(), false, &mut out, &mut Vec::new()).unwrap();
+        assert!(out.starts_with(b"o "));
+
+        // import() finds files next to the model, whatever the working directory
+        std::fs::write(dir.join("part.stl"), manifold_rs::render("cube(3);").unwrap().to_stl_binary()).unwrap();
+        std::fs::write(&model, "import(\"part.stl\");").unwrap();
+        let mut out = Vec::new();
+        render(&model, Some(Path::new("-")), Some("stl"), &Overrides::new(), false, &mut out, &mut Vec::new()).unwrap();
+        assert_eq!(manifold_rs::Mesh::from_stl(&out).unwrap().triangle_count(), 12);
+
+        // a 2D model is not written as a mesh
+        std::fs::write(&model, "square(1);").unwrap();
+        let error = render(&model, None, Some("3mf"), &Overrides::new(), false, &mut Vec::new(), &mut Vec::new()).unwrap_err();
+        assert_eq!(error.exit_code(), crate::error::EXIT_FAILED);
+        assert!(error.to_string().contains("use svg or dxf"), "{}", error);
+        assert!(!dir.join("model.3mf").exists());
+
+        std::fs::write(&model, "cube(0);").unwrap();
+        let error = render(&model, None, None, &Overrides::new(), false, &mut Vec::new(), &mut Vec::new()).unwrap_err();
+        assert_eq!(error.exit_code(), crate::error::EXIT_FAILED);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Test -D values reach the model and a verified render writes the
+    /// same file, logging the model's output once.
+    #[test]
+    fn test_render_overrides_verified() {
+        let dir = crate::temp_dir("render-verify");
+        let model = dir.join("model.scad");
+        std::fs::write(&model, "size = 1;\necho(size);\ncube(size);").unwrap();
+        let overrides = Overrides::from([("size".to_string(), openscad_eval::Value::Number(3.0))]);
+
+        let mut log = Vec::new();
+        render(&model, None, None, &overrides, true, &mut Vec::new(), &mut log).unwrap();
+        let log = String::from_utf8(log).unwrap();
+        assert!(log.starts_with("ECHO: 3\nWrote"), "{}", log);
+        assert!(log.ends_with("(12 triangles, deterministic)\n"), "{}", log);
+        let stl = manifold_rs::Mesh::from_stl(&std::fs::read(dir.join("model.stl")).unwrap()).unwrap();
+        assert_eq!(stl.bounding_box().unwrap().1, [3.0; 3]);
+
+        std::fs::write(&model, "size = 1;\nsquare(size);").unwrap();
+        let mut out = Vec::new();
+        render(&model, Some(Path::new("-")), Some("svg"), &overrides, true, &mut out, &mut Vec::new()).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains(r#"width="3mm""#));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,305 @@
+//! # Watch
+//!
+//! `c4d watch model.scad [--serve] [--port n] [-o out] [-f fmt] [-D name=value]`:
+//! render a model, then render it again each time it or a file it
+//! includes, uses or imports changes, until interrupted.
+//!
+//! Each render writes the model out like `render` does. With `--serve`
+//! the mesh is served instead, with a viewer page, on
+//! `http://127.0.0.1:<port>/` (8000 by default, `--port 0` for any free
+//! port); `-o` still writes a file as well. The page orbits the model and
+//! reloads it after each render, showing the error when one fails:
+//!
+//! | Path | Response |
+//! |------|----------|
+//! | `/` | The viewer page |
+//! | `/model.stl` | The last good mesh, binary STL |
+//! | `/status` | `{"version": n, "triangles": n, "error": "..." or null}` |
+//!
+//! `version` counts renders, failed ones included. Files are polled for
+//! changes, so editors that save by replacing the file are picked up too.
+//! Files read by `import()` and `surface()` are watched too.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+use manifold_rs::mesh::EXPORT_FORMATS;
+use serde_json::json;
+
+use crate::error::{CliError, CliResult};
+use openscad_eval::Overrides;
+
+use crate::model::{read, with_files, ModelFiles};
+use crate::render::{export_format, export_mesh};
+
+/// Port served on without `--port`.
+pub const DEFAULT_PORT: u16 = 8000;
+
+/// How often the files are checked for changes.
+const POLL: Duration = Duration::from_millis(250);
+
+/// The viewer page.
+const VIEWER: &str = include_str!("viewer.html");
+
+/// What the server hands out: the last render.
+#[derive(Debug, Clone, Default)]
+pub struct Served {
+    /// Number of renders so far.
+    pub version: u64,
+    /// The last good mesh as binary STL, empty before the first.
+    pub stl: Vec<u8>,
+    /// Triangles of that mesh.
+    pub triangles: usize,
+    /// Why the last render failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Modification times of the files a render read.
+#[derive(Debug, Clone, Default)]
+pub struct Watched {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Watched {
+    /// Remember the current modification times of `files`.
+    #[must_use]
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        Self { files: files.into_iter().map(|path| { let time = modified(&path); (path, time) }).collect() }
+    }
+
+    /// Whether a file was modified, created or deleted since.
+    #[must_use]
+    pub fn changed(&self) -> bool {
+        self.files.iter().any(|(path, time)| modified(path) != *time)
+    }
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Render `input` on every change, writing files and serving on `port`
+/// as the options ask; see the [module docs](self). Only returns on error.
+///
+/// ## Errors
+///
+/// `CliError::Usage` for a 2D or unknown format, `CliError::Io` if the
+/// port cannot be bound. Failed renders are reported to `log` and do not
+/// stop the watch.
+pub fn watch(
+    input: &Path,
+    output: Option<&Path>,
+    format: Option<&str>,
+    port: Option<u16>,
+    overrides: &Overrides,
+    log: &mut dyn Write,
+) -> CliResult<u8> {
+    let format = export_format(output, format)?;
+    if !EXPORT_FORMATS.contains(&format.as_str()) {
+        return Err(CliError::Usage(format!("`watch` exports meshes, not `{}`", format)));
+    }
+    // Without --serve the file is the point; with it, only on request
+    let output = match (output, port) {
+        (Some(output), _) => Some(output.to_path_buf()),
+        (None, None) => Some(input.with_extension(&format)),
+        (None, Some(_)) => None,
+    };
+
+    let served = Arc::new(Mutex::new(Served::default()));
+    if let Some(port) = port {
+        let address = format!("127.0.0.1:{}", port);
+        let listener = TcpListener::bind(&address).map_err(|source| CliError::Io { path: PathBuf::from(address), source })?;
+        if let Ok(address) = listener.local_addr() {
+            let _ = writeln!(log, "Serving http://{}/", address);
+        }
+        let served = Arc::clone(&served);
+        std::thread::spawn(move || serve(&listener, &served));
+    }
+
+    let mut watched = Watched::new(rebuild(input, output.as_deref(), &format, overrides, &served, log));
+    let _ = writeln!(log, "Watching {} (Ctrl-C to stop)", input.display());
+    loop {
+        std::thread::sleep(POLL);
+        if watched.changed() {
+            // A render that fails early loads fewer files; keep watching
+            // those of earlier renders for the fix
+            let mut files = rebuild(input, output.as_deref(), &format, overrides, &served, log);
+            for (path, _) in watched.files {
+                if !files.contains(&path) {
+                    files.push(path);
+                }
+            }
+            watched = Watched::new(files);
+        }
+    }
+}
+
+/// Render `input` with `overrides` once into `served`, and to `output` in
+/// `format` if given, reporting to `log`.
+///
+/// ## Returns
+///
+/// The files the render read, the model first.
+pub fn rebuild(
+    input: &Path,
+    output: Option<&Path>,
+    format: &str,
+    overrides: &Overrides,
+    served: &Mutex<Served>,
+    log: &mut dyn Write,
+) -> Vec<PathBuf> {
+    let started = Instant::now();
+    let files = Arc::new(ModelFiles::new(input));
+    let result = read(input).and_then(|source| {
+        let mesh = with_files(input, Arc::clone(&files), overrides, log, |options| manifold_rs::render_with_eval_options(&source, options))?;
+        if mesh.is_empty() {
+            return Err(CliError::Model { path: input.to_path_buf(), message: "the model has no 3D geometry".to_string() });
+        }
+        if let Some(output) = output {
+            let bytes = export_mesh(input, &mesh, format)?;
+            std::fs::write(output, bytes).map_err(|source| CliError::Io { path: output.to_path_buf(), source })?;
+        }
+        Ok(mesh)
+    });
+
+    let mut served = served.lock().unwrap_or_else(PoisonError::into_inner);
+    served.version += 1;
+    match result {
+        Ok(mesh) => {
+            served.stl = mesh.to_stl_binary();
+            served.triangles = mesh.triangle_count();
+            served.error = None;
+            let to = output.map(|o| format!(" to {}", o.display())).unwrap_or_default();
+            let _ = writeln!(log, "Rendered{} ({} triangles, {} ms)", to, mesh.triangle_count(), started.elapsed().as_millis());
+        }
+        Err(error) => {
+            let _ = writeln!(log, "error: {}", error);
+            served.error = Some(error.to_string());
+        }
+    }
+    std::iter::once(input.to_path_buf()).chain(files.loaded()).collect()
+}
+
+/// The status, content type and body answering a `GET` of `path`.
+#[must_use]
+pub fn respond(path: &str, served: &Served) -> (&'static str, &'static str, Vec<u8>) {
+    match path.split('?').next().unwrap_or_default() {
+        "/" | "/index.html" => ("200 OK", "text/html; charset=utf-8", VIEWER.as_bytes().to_vec()),
+        "/model.stl" if !served.stl.is_empty() => ("200 OK", "model/stl", served.stl.clone()),
+        "/status" => {
+            let status = json!({ "version": served.version, "triangles": served.triangles, "error": served.error });
+            ("200 OK", "application/json", status.to_string().into_bytes())
+        }
+        _ => ("404 Not Found", "text/plain", b"Not found".to_vec()),
+    }
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// When a file was last modified, `None` if it is missing.
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Answer requests until the listener fails.
+fn serve(listener: &TcpListener, served: &Mutex<Served>) {
+    for stream in listener.incoming().flatten() {
+        // A client that hangs up mid-request only loses its own answer
+        let _ = answer(stream, served);
+    }
+}
+
+/// Answer one request on `stream`.
+fn answer(mut stream: TcpStream, served: &Mutex<Served>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut request = String::new();
+    BufReader::new(&stream).read_line(&mut request)?;
+    let mut words = request.split_whitespace();
+    let (status, content_type, body) = match (words.next(), words.next()) {
+        (Some("GET"), Some(path)) => respond(path, &served.lock().unwrap_or_else(PoisonError::into_inner)),
+        _ => ("405 Method Not Allowed", "text/plain", b"Only GET is served".to_vec()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len(),
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// Test a render records its files, and a change to any is noticed.
+    #[test]
+    fn test_rebuild() {
+        let dir = crate::temp_dir("watch");
+        let (model, part) = (dir.join("model.scad"), dir.join("part.scad"));
+        std::fs::write(&part, "module part() { cube(2); }").unwrap();
+        std::fs::write(&model, "use <part.scad>\npart();").unwrap();
+
+        let served = Mutex::new(Served::default());
+        let files = rebuild(&model, None, "stl", &Overrides::new(), &served, &mut Vec::new());
+        assert_eq!(files.len(), 2);
+        assert!(files[1].ends_with("part.scad"));
+        assert_eq!(served.lock().unwrap().triangles, 12);
+
+        let watched = Watched::new(files);
+        assert!(!watched.changed());
+        let later = SystemTime::now() + Duration::from_secs(10);
+        std::fs::File::options().write(true).open(&part).unwrap().set_modified(later).unwrap();
+        assert!(watched.changed());
+
+        // A failed render keeps the last mesh and reports the error
+        std::fs::write(&model, "use <part.scad>\npart(").unwrap();
+        let mut log = Vec::new();
+        rebuild(&model, Some(&dir.join("out.stl")), "stl", &Overrides::new(), &served, &mut log);
+        let served = served.into_inner().unwrap();
+        assert_eq!((served.version, served.triangles), (2, 12));
+        assert!(served.error.is_some());
+        assert!(String::from_utf8(log).unwrap().starts_with("error: "));
+        assert!(!dir.join("out.stl").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Test the server answers the page, the mesh and the status.
+    #[test]
+    fn test_serve() {
+        let served = Arc::new(Mutex::new(Served { version: 3, stl: vec![1, 2, 3], triangles: 1, error: None }));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = Arc::clone(&served);
+        std::thread::spawn(move || serve(&listener, &server));
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).unwrap();
+            response
+        };
+        let status = String::from_utf8(get("/status")).unwrap();
+        assert!(status.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(status.ends_with(r#"{"error":null,"triangles":1,"version":3}"#), "{}", status);
+        assert!(get("/model.stl").ends_with(&[1, 2, 3]));
+        assert!(String::from_utf8(get("/")).unwrap().contains("<canvas"));
+        assert!(get("/missing").starts_with(b"HTTP/1.1 404"));
+
+        // No mesh is served before the first good render
+        assert_eq!(respond("/model.stl", &Served::default()).0, "404 Not Found");
+    }
+}
@@ -9,6 +9,7 @@
  * - Orbit controls for camera manipulation
  * - Grid and axes helpers
  * - Mesh rendering with proper lighting
+ * - Preview layers for `#` (highlighted) and `%` (transparent) geometry
  *
  * ## Usage
  *
@@ -20,6 +21,7 @@
 
 import * as THREE from 'three';
 import { OrbitControls } from 'three/examples/jsm/controls/OrbitControls.js';
+import type { PreviewMesh } from '../wasm/loader';
 
 // =============================================================================
 // CONFIGURATION
@@ -40,6 +42,14 @@ const EDGE_COLOR = 0x000000;
 /** Background color */
 const BACKGROUND_COLOR = 0x2d2d2d;
 
+/** Color and opacity of `#` geometry (OpenSCAD's debug pink) */
+const HIGHLIGHT_COLOR = 0xff5151;
+const HIGHLIGHT_OPACITY = 0.5;
+
+/** Color and opacity of `%` geometry */
+const TRANSPARENT_COLOR = 0xc0c0c0;
+const TRANSPARENT_OPACITY = 0.2;
+
 // =============================================================================
 // SCENE MANAGER CLASS
 // =============================================================================
@@ -76,6 +86,9 @@ export class SceneManager {
   /** Mesh edges wireframe */
   private edges: THREE.LineSegments | null = null;
 
+  /** Preview layers drawn over the mesh */
+  private layers: THREE.Mesh[] = [];
+
   /** Animation frame ID */
   private animationId: number | null = null;
 
@@ -186,16 +199,18 @@ export class SceneManager {
    * @param vertices - Vertex positions (x, y, z)
    * @param indices - Triangle indices
    * @param normals - Vertex normals (x, y, z)
+   * @param colors - Vertex colors (r, g, b, a), if the model has any
    *
    * @example
    * ```typescript
-   * scene.updateMesh(result.vertices, result.indices, result.normals);
+   * scene.updateMesh(result.vertices, result.indices, result.normals, result.colors);
    * ```
    */
   updateMesh(
     vertices: Float32Array,
     indices: Uint32Array,
-    normals: Float32Array
+    normals: Float32Array,
+    colors?: Float32Array | null
   ): void {
     // Remove existing mesh
     this.clearMesh();
@@ -205,10 +220,14 @@ export class SceneManager {
     geometry.setAttribute('position', new THREE.BufferAttribute(vertices, 3));
     geometry.setAttribute('normal', new THREE.BufferAttribute(normals, 3));
     geometry.setIndex(new THREE.BufferAttribute(indices, 1));
+    if (colors) {
+      geometry.setAttribute('color', new THREE.BufferAttribute(colors, 4));
+    }
 
-    // Create material
+    // Create material; vertex colors multiply the material color, so it is white when present
     const material = new THREE.MeshPhongMaterial({
-      color: MESH_COLOR,
+      color: colors ? 0xffffff : MESH_COLOR,
+      vertexColors: Boolean(colors),
       side: THREE.DoubleSide,
       flatShading: false,
     });
@@ -233,6 +252,57 @@ export class SceneManager {
     this.centerCamera();
   }
 
+  /**
+   * Update the preview layers drawn over the mesh.
+   *
+   * @param highlighted - Geometry under `#`, drawn in translucent pink
+   * @param transparent - Geometry under `%`, drawn as a faint ghost
+   *
+   * @example
+   * ```typescript
+   * scene.updatePreview(result.highlighted, result.transparent);
+   * ```
+   */
+  updatePreview(highlighted?: PreviewMesh | null, transparent?: PreviewMesh | null): void {
+    this.clearPreview();
+
+    const layers: [PreviewMesh | null | undefined, number, number][] = [
+      [highlighted, HIGHLIGHT_COLOR, HIGHLIGHT_OPACITY],
+      [transparent, TRANSPARENT_COLOR, TRANSPARENT_OPACITY],
+    ];
+    for (const [layer, color, opacity] of layers) {
+      if (!layer) continue;
+      const geometry = new THREE.BufferGeometry();
+      geometry.setAttribute('position', new THREE.BufferAttribute(layer.vertices, 3));
+      geometry.setAttribute('normal', new THREE.BufferAttribute(layer.normals, 3));
+      geometry.setIndex(new THREE.BufferAttribute(layer.indices, 1));
+
+      // No depth writes, so the model stays visible through the layer
+      const material = new THREE.MeshPhongMaterial({
+        color,
+        transparent: true,
+        opacity,
+        depthWrite: false,
+        side: THREE.DoubleSide,
+      });
+      const mesh = new THREE.Mesh(geometry, material);
+      this.layers.push(mesh);
+      this.scene.add(mesh);
+    }
+  }
+
+  /**
+   * Clear the preview layers.
+   */
+  clearPreview(): void {
+    for (const layer of this.layers) {
+      this.scene.remove(layer);
+      layer.geometry.dispose();
+      (layer.material as THREE.Material).dispose();
+    }
+    this.layers = [];
+  }
+
   /**
    * Clear the current mesh.
    */
@@ -291,6 +361,7 @@ export class SceneManager {
     }
 
     this.clearMesh();
+    this.clearPreview();
     this.controls.dispose();
     this.renderer.dispose();
   }
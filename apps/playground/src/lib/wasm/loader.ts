@@ -23,6 +23,48 @@
 // TYPES
 // =============================================================================
 
+/**
+ * A preview layer of a render: geometry under `#` or `%`.
+ */
+export interface PreviewMesh {
+  /** Vertex positions (x, y, z) */
+  vertices: Float32Array;
+
+  /** Triangle indices */
+  indices: Uint32Array;
+
+  /** Vertex normals (x, y, z) */
+  normals: Float32Array;
+}
+
+/**
+ * A non-fatal problem found while evaluating a script.
+ */
+export interface Diagnostic {
+  /** What the warning is about */
+  kind: 'ignored_argument' | 'degenerate' | 'empty_geometry' | 'no_effect' | 'deprecated' | 'general';
+
+  /** The warning text */
+  text: string;
+
+  /** Statement being evaluated (0-based lines and columns), or null */
+  span: { start: { byte: number; line: number; column: number }; end: { byte: number; line: number; column: number } } | null;
+}
+
+/**
+ * A line of `echo()` output or a warning, streamed while a script evaluates.
+ */
+export interface LogLine {
+  /** How to show the line */
+  level: 'echo' | 'warning' | 'deprecated';
+
+  /** The text, without an `ECHO:` or `WARNING:` prefix */
+  text: string;
+
+  /** Statement that produced it (0-based lines and columns), or null */
+  span: Diagnostic['span'];
+}
+
 /**
  * Render result from WASM.
  *
@@ -44,6 +86,9 @@ export interface RenderResult {
   /** Vertex normals (x, y, z) */
   normals?: Float32Array;
 
+  /** Vertex colors (r, g, b, a) from `color()`, or null if nothing is colored */
+  colors?: Float32Array | null;
+
   /** Vertex count */
   vertexCount: number;
 
@@ -52,6 +97,103 @@ export interface RenderResult {
 
   /** Render time in milliseconds */
   renderTimeMs: number;
+
+  /** Warnings of the evaluation, in order */
+  diagnostics?: Diagnostic[];
+
+  /** Geometry under `#`, also part of the mesh, or null */
+  highlighted?: PreviewMesh | null;
+
+  /** Geometry under `%`, not part of the mesh, or null */
+  transparent?: PreviewMesh | null;
+}
+
+/**
+ * A rendered model encoded as a file by `exportModel()`.
+ */
+export interface ExportedFile {
+  /** File contents */
+  data: Uint8Array;
+
+  /** Suggested file name, e.g. `model.glb` */
+  filename: string;
+}
+
+/**
+ * Options accepted by `render()`.
+ */
+export interface RenderOptions {
+  /** Top-level variable overrides, like OpenSCAD's `-D name=value` */
+  overrides?: Record<string, number | boolean | string | null | unknown[]>;
+
+  /** `$t`: animation time from 0 to 1; render once per frame to animate */
+  t?: number;
+
+  /** `$preview`: true (the default) for a preview, false for a final render */
+  preview?: boolean;
+
+  /** Segment multiplier while `preview` is true, e.g. 0.5 to keep dragging responsive (default 1) */
+  previewQuality?: number;
+
+  /** Segment multiplier while `preview` is false, e.g. 2 for a finer export (default 1) */
+  renderQuality?: number;
+
+  /** `$vpr`: viewport rotation in degrees */
+  vpr?: [number, number, number];
+
+  /** `$vpt`: viewport translation */
+  vpt?: [number, number, number];
+
+  /** `$vpd`: viewport camera distance */
+  vpd?: number;
+
+  /** `$vpf`: viewport field of view in degrees */
+  vpf?: number;
+
+  /** Seed of unseeded `rands()` calls, for the same output on every render */
+  seed?: number;
+
+  /** Merge adjacent coplanar triangles of the finished mesh */
+  mergeCoplanar?: boolean;
+
+  /** Decimate the finished mesh down to at most this many triangles */
+  targetTriangles?: number;
+
+  /** Abort the render after this many milliseconds */
+  maxTimeMs?: number;
+
+  /** Abort after this many loop iterations, over all loops */
+  maxIterations?: number;
+
+  /** Abort after this many geometry nodes */
+  maxNodes?: number;
+
+  /** Abort if the final mesh has more triangles */
+  maxTriangles?: number;
+
+  /** Abort if the final mesh buffers take more bytes */
+  maxMemoryBytes?: number;
+
+  /** Deepest nesting of user function and module calls (default 10,000) */
+  maxRecursionDepth?: number;
+
+  /** Boolean algorithm: BSP trees (the default), intersection splitting, or chosen by size */
+  backend?: 'bsp' | 'intersect' | 'auto';
+
+  /** Operand triangles from which `auto` splits by intersection (default 1000) */
+  autoThreshold?: number;
+
+  /** Distance within which boolean results weld vertices (default 1e-4) */
+  weldTolerance?: number;
+
+  /** Most subtree meshes a render session keeps between updates */
+  cacheSize?: number;
+
+  /** Sphere tessellation: OpenSCAD's rings (the default), or evenly sized triangles for cleaner booleans */
+  sphereStyle?: 'uv' | 'icosphere' | 'cube_sphere';
+
+  /** Enable `torus()`, `prism()` and `rounded_cube()`, which OpenSCAD lacks (off by default) */
+  extensions?: boolean;
 }
 
 /**
@@ -62,7 +204,29 @@ interface WasmModule {
   get_version: () => string;
 
   /** Render from source code (full pipeline) */
-  render: (source: string) => RenderResult;
+  render: (source: string, options?: RenderOptions) => RenderResult;
+
+  /** Render with top-level variable values, reusing the last parse */
+  render_with_overrides: (
+    source: string,
+    overrides: RenderOptions['overrides'],
+    options?: RenderOptions
+  ) => RenderResult;
+
+  /** Evaluate to the geometry tree as JSON */
+  eval_to_json: (source: string, options?: RenderOptions) => string;
+
+  /** Mesh a geometry tree from eval_to_json */
+  render_evaluated: (json: string, options?: RenderOptions) => RenderResult;
+
+  /** Stream log lines of every render to a handler, or stop with null */
+  set_log_handler: (handler: ((line: LogLine) => void) | null) => void;
+
+  /** Render to a file in one of the export formats */
+  export_model: (source: string, format: string, options?: RenderOptions) => ExportedFile;
+
+  /** Render a 2D result to an SVG or DXF file */
+  render_2d: (source: string, format: string, options?: RenderOptions) => ExportedFile;
 }
 
 /**
@@ -177,6 +341,7 @@ export function getVersion(): string {
  * All processing done in pure Rust WASM.
  *
  * @param source - OpenSCAD source code
+ * @param options - Optional render options (e.g. variable overrides)
  * @returns Render result with mesh data
  * @throws Error if WASM not initialized
  *
@@ -188,13 +353,96 @@ export function getVersion(): string {
  * }
  * ```
  */
-export function render(source: string): RenderResult {
+export function render(source: string, options?: RenderOptions): RenderResult {
+  if (!wasmModule) {
+    throw new Error('WASM not initialized. Call initWasm() first.');
+  }
+
+  try {
+    return wasmModule.render(source, options);
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : 'Unknown WASM error',
+      vertexCount: 0,
+      triangleCount: 0,
+      renderTimeMs: 0,
+    };
+  }
+}
+
+/**
+ * Render OpenSCAD source code with values for its top-level variables.
+ *
+ * The source is parsed again only when it changed since the last call,
+ * so customizer sliders can re-render without editing the source text.
+ *
+ * @param source - OpenSCAD source code
+ * @param overrides - Values by variable name, like OpenSCAD's `-D name=value`
+ * @param options - Optional render options
+ * @returns Render result with mesh data
+ * @throws Error if WASM not initialized
+ *
+ * @example
+ * ```typescript
+ * const result = renderWithOverrides('size = 10; cube(size);', { size: 20 });
+ * ```
+ */
+export function renderWithOverrides(
+  source: string,
+  overrides: RenderOptions['overrides'],
+  options?: RenderOptions
+): RenderResult {
+  if (!wasmModule) {
+    throw new Error('WASM not initialized. Call initWasm() first.');
+  }
+
+  try {
+    return wasmModule.render_with_overrides(source, overrides ?? {}, options);
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : 'Unknown WASM error',
+      vertexCount: 0,
+      triangleCount: 0,
+      renderTimeMs: 0,
+    };
+  }
+}
+
+/**
+ * Evaluate OpenSCAD source code to its geometry tree as JSON.
+ *
+ * The tree can be inspected, stored, and meshed later with
+ * `renderEvaluated()` without evaluating the source again.
+ *
+ * @param source - OpenSCAD source code
+ * @param options - Optional render options
+ * @returns JSON text of the evaluated tree
+ * @throws Error if WASM not initialized or the source fails to evaluate
+ */
+export function evalToJson(source: string, options?: RenderOptions): string {
+  if (!wasmModule) {
+    throw new Error('WASM not initialized. Call initWasm() first.');
+  }
+  return wasmModule.eval_to_json(source, options);
+}
+
+/**
+ * Mesh a geometry tree produced by `evalToJson()`.
+ *
+ * @param json - JSON text from `evalToJson()`
+ * @param options - Optional render options; only the mesh options apply
+ * @returns Render result with mesh data
+ * @throws Error if WASM not initialized
+ */
+export function renderEvaluated(json: string, options?: RenderOptions): RenderResult {
   if (!wasmModule) {
     throw new Error('WASM not initialized. Call initWasm() first.');
   }
 
   try {
-    return wasmModule.render(source);
+    return wasmModule.render_evaluated(json, options);
   } catch (error) {
     return {
       success: false,
@@ -205,3 +453,66 @@ export function render(source: string): RenderResult {
     };
   }
 }
+
+/**
+ * Stream `echo()` output and warnings to a handler while scripts evaluate.
+ *
+ * Lines arrive during the render, before its result; pass null to stop.
+ *
+ * @param handler - Called with each line, in order
+ * @throws Error if WASM not initialized
+ *
+ * @example
+ * ```typescript
+ * setLogHandler((line) => console.log(`${line.level}: ${line.text}`));
+ * ```
+ */
+export function setLogHandler(handler: ((line: LogLine) => void) | null): void {
+  if (!wasmModule) {
+    throw new Error('WASM not initialized. Call initWasm() first.');
+  }
+  wasmModule.set_log_handler(handler);
+}
+
+/**
+ * Render OpenSCAD source code to a file for download.
+ *
+ * @param source - OpenSCAD source code
+ * @param format - `'glb'` for web viewers, or `'stl'`, `'3mf'`, `'amf'`, `'obj'`, `'off'`
+ * @param options - Optional render options
+ * @returns File contents with a suggested file name
+ * @throws Error if WASM not initialized, the render fails, or the format is unknown
+ *
+ * @example
+ * ```typescript
+ * const { data, filename } = exportModel('cube(10);', 'glb');
+ * const url = URL.createObjectURL(new Blob([data], { type: 'model/gltf-binary' }));
+ * ```
+ */
+export function exportModel(source: string, format: string, options?: RenderOptions): ExportedFile {
+  if (!wasmModule) {
+    throw new Error('WASM not initialized. Call initWasm() first.');
+  }
+  return wasmModule.export_model(source, format, options);
+}
+
+/**
+ * Render a 2D design to a drawing for laser cutting.
+ *
+ * @param source - OpenSCAD source code with a 2D result, e.g. a `projection()`
+ * @param format - `'svg'` or `'dxf'`
+ * @param options - Optional render options
+ * @returns File contents with a suggested file name
+ * @throws Error if WASM not initialized, the result is 3D, or the format is unknown
+ *
+ * @example
+ * ```typescript
+ * const { data, filename } = render2d('projection() sphere(10);', 'svg');
+ * ```
+ */
+export function render2d(source: string, format: string, options?: RenderOptions): ExportedFile {
+  if (!wasmModule) {
+    throw new Error('WASM not initialized. Call initWasm() first.');
+  }
+  return wasmModule.render_2d(source, format, options);
+}
@@ -13,7 +13,7 @@
  * ```
  */
 
-import { initWasm, render, getVersion, isWasmReady } from './lib/wasm/loader';
+import { initWasm, render, exportModel, getVersion, isWasmReady, setLogHandler, type RenderOptions } from './lib/wasm/loader';
 import { SceneManager } from './lib/viewer/scene-manager';
 
 // =============================================================================
@@ -26,6 +26,9 @@ const editorElement = document.getElementById('editor') as HTMLTextAreaElement;
 /** Render button */
 const renderButton = document.getElementById('render-btn') as HTMLButtonElement;
 
+/** GLB download button */
+const exportButton = document.getElementById('export-btn') as HTMLButtonElement;
+
 /** Status bar */
 const statusBar = document.getElementById('status-bar') as HTMLDivElement;
 
@@ -38,6 +41,17 @@ const loadingOverlay = document.getElementById('loading-overlay') as HTMLDivElem
 /** Viewer canvas */
 const viewerCanvas = document.getElementById('viewer-canvas') as HTMLCanvasElement;
 
+// =============================================================================
+// CONFIGURATION
+// =============================================================================
+
+/** Budgets that stop a runaway script instead of hanging the page */
+const RENDER_LIMITS: RenderOptions = {
+  maxTimeMs: 30_000,
+  maxIterations: 10_000_000,
+  maxTriangles: 5_000_000,
+};
+
 // =============================================================================
 // APPLICATION STATE
 // =============================================================================
@@ -61,12 +75,13 @@ function setStatus(message: string, isError = false): void {
 }
 
 /**
- * Enable or disable render button.
+ * Enable or disable the render and download buttons.
  *
  * @param enabled - Whether to enable
  */
 function setRenderEnabled(enabled: boolean): void {
   renderButton.disabled = !enabled;
+  exportButton.disabled = !enabled;
 }
 
 /**
@@ -99,16 +114,19 @@ async function handleRender(): Promise<void> {
     const startTime = performance.now();
 
     // Full pipeline in WASM: parse → AST → eval → mesh
-    const result = render(source);
+    const result = render(source, RENDER_LIMITS);
     const totalTime = performance.now() - startTime;
 
     if (result.success && result.vertices && result.indices && result.normals) {
       // Update Three.js scene
-      sceneManager.updateMesh(result.vertices, result.indices, result.normals);
+      sceneManager.updateMesh(result.vertices, result.indices, result.normals, result.colors);
+      sceneManager.updatePreview(result.highlighted, result.transparent);
 
+      const warnings = result.diagnostics ?? [];
       setStatus(
         `✓ ${result.vertexCount} vertices, ${result.triangleCount} triangles | ` +
-        `Total: ${totalTime.toFixed(1)}ms`
+        `Total: ${totalTime.toFixed(1)}ms` +
+        (warnings.length > 0 ? ` | ${warnings.length} warning(s)` : '')
       );
     } else {
       setStatus(`Error: ${result.error ?? 'Unknown error'}`, true);
@@ -122,6 +140,37 @@ async function handleRender(): Promise<void> {
   }
 }
 
+/**
+ * Render the current editor content to GLB and download it.
+ *
+ * The file opens in Three.js-based viewers, Blender and `<model-viewer>`
+ * without conversion.
+ */
+function handleExport(): void {
+  if (!isWasmReady()) {
+    setStatus('Not ready', true);
+    return;
+  }
+
+  setStatus('Exporting...');
+  setRenderEnabled(false);
+
+  try {
+    const { data, filename } = exportModel(editorElement.value, 'glb', RENDER_LIMITS);
+    const url = URL.createObjectURL(new Blob([data], { type: 'model/gltf-binary' }));
+    const link = Object.assign(document.createElement('a'), { href: url, download: filename });
+    link.click();
+    URL.revokeObjectURL(url);
+    setStatus(`✓ Saved ${filename} (${(data.length / 1024).toFixed(1)} KB)`);
+  } catch (error) {
+    const message = error instanceof Error ? error.message : String(error);
+    setStatus(`Export error: ${message}`, true);
+    console.error('Export error:', error);
+  } finally {
+    setRenderEnabled(true);
+  }
+}
+
 // =============================================================================
 // INITIALIZATION
 // =============================================================================
@@ -140,6 +189,17 @@ async function init(): Promise<void> {
     // Update version display
     versionElement.textContent = `WASM v${getVersion()}`;
 
+    // Script output and warnings go to the console as they happen
+    setLogHandler((line) => {
+      const where = line.span ? ` (line ${line.span.start.line + 1})` : '';
+      const text = `[Script] ${line.text}${where}`;
+      if (line.level === 'echo') {
+        console.log(text);
+      } else {
+        console.warn(text);
+      }
+    });
+
     // Initialize Three.js scene
     sceneManager = new SceneManager(viewerCanvas);
     console.log('[App] Scene initialized');
@@ -151,6 +211,7 @@ async function init(): Promise<void> {
 
     // Set up event handlers
     renderButton.addEventListener('click', handleRender);
+    exportButton.addEventListener('click', handleExport);
 
     // Keyboard shortcut: Ctrl+Enter to render
     editorElement.addEventListener('keydown', (event) => {
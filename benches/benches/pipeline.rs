@@ -0,0 +1,45 @@
+//! # Pipeline Benchmarks
+//!
+//! Criterion timings of each model: evaluation to geometry, then meshing
+//! and booleans from the evaluated geometry under each CSG backend.
+//!
+//! ```text
+//! cargo bench -p c4d-benches
+//! cargo bench -p c4d-benches -- csg/boolean_tower
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use c4d_benches::{backend_name, options, BACKENDS, MODELS};
+use openscad_eval::EvalOptions;
+
+/// Evaluation alone, which no backend affects.
+fn eval(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval");
+    let options = EvalOptions::default();
+    for model in MODELS {
+        group.bench_function(model.name, |b| {
+            b.iter(|| openscad_eval::evaluate_with_options(model.source, &options).unwrap());
+        });
+    }
+    group.finish();
+}
+
+/// Meshing and booleans of the evaluated model, per backend.
+fn csg(c: &mut Criterion) {
+    let mut group = c.benchmark_group("csg");
+    group.sample_size(10);
+    for model in MODELS {
+        let evaluated = openscad_eval::evaluate(model.source).unwrap();
+        for &backend in BACKENDS {
+            let options = options(backend);
+            group.bench_with_input(BenchmarkId::new(model.name, backend_name(backend)), &evaluated, |b, evaluated| {
+                b.iter(|| manifold_rs::render_evaluated(evaluated, &options, 0.0).unwrap());
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, eval, csg);
+criterion_main!(benches);
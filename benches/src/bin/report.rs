@@ -0,0 +1,135 @@
+//! # Benchmark Report
+//!
+//! Eval time, CSG time, triangles and peak memory of each model under each
+//! backend, as a table or JSON, saved as a baseline or compared against
+//! one:
+//!
+//! ```text
+//! cargo run --release -p c4d-benches --bin report -- --save baseline.json
+//! # ... change a backend ...
+//! cargo run --release -p c4d-benches --bin report -- --compare baseline.json
+//! ```
+//!
+//! Exits with 1 when a comparison finds a regression (see
+//! [`c4d_benches::compare`]) or a model fails to render, 2 for bad
+//! arguments or an unreadable baseline.
+
+use std::process::ExitCode;
+
+use c4d_benches::{backend_name, compare, median, mib, sample, PeakAlloc, Sample, BACKENDS, MODELS};
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc::new();
+
+/// Help text.
+const USAGE: &str = "\
+Usage: report [options]
+
+  --runs <n>           Renders per model and backend, median taken (3)
+  --model <name>       Only this model
+  --backend <name>     Only this backend: bsp, intersect or auto
+  --save <file>        Write the samples as JSON
+  --compare <file>     Report regressions against saved samples
+  --tolerance <f>      Growth allowed before a regression (0.1 = 10%)
+  --json               Print JSON instead of a table";
+
+/// Parsed command line.
+struct Options {
+    runs: usize,
+    model: Option<String>,
+    backend: Option<String>,
+    save: Option<String>,
+    baseline: Option<String>,
+    tolerance: f64,
+    json: bool,
+}
+
+fn main() -> ExitCode {
+    let options = match parse(std::env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let baseline: Option<Vec<Sample>> = match &options.baseline {
+        Some(path) => match std::fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string())) {
+            Ok(samples) => Some(samples),
+            Err(error) => {
+                eprintln!("{}: {}", path, error);
+                return ExitCode::from(2);
+            }
+        },
+        None => None,
+    };
+
+    let mut samples = Vec::new();
+    for model in MODELS.iter().filter(|m| options.model.as_deref().is_none_or(|name| name == m.name)) {
+        for &backend in BACKENDS.iter().filter(|&&b| options.backend.as_deref().is_none_or(|name| name == backend_name(b))) {
+            let runs: Result<Vec<Sample>, _> = (0..options.runs).map(|_| sample(model, backend, Some(&ALLOC))).collect();
+            match runs {
+                Ok(runs) => samples.extend(median(&runs)),
+                Err(error) => {
+                    eprintln!("{} ({}): {}", model.name, backend_name(backend), error);
+                    return ExitCode::from(1);
+                }
+            }
+        }
+    }
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&samples).unwrap_or_default());
+    } else {
+        println!("{:<16} {:<10} {:>10} {:>10} {:>10} {:>10}", "model", "backend", "eval ms", "csg ms", "triangles", "peak");
+        for s in &samples {
+            let peak = s.peak_bytes.map(mib).unwrap_or_default();
+            println!("{:<16} {:<10} {:>10.1} {:>10.1} {:>10} {:>10}", s.model, backend_name(s.backend), s.eval_ms, s.csg_ms, s.triangles, peak);
+        }
+    }
+    if let Some(path) = &options.save {
+        if let Err(error) = std::fs::write(path, serde_json::to_string_pretty(&samples).unwrap_or_default()) {
+            eprintln!("{}: {}", path, error);
+            return ExitCode::from(2);
+        }
+    }
+    if let Some(baseline) = baseline {
+        let regressions = compare(&baseline, &samples, options.tolerance);
+        for regression in &regressions {
+            eprintln!("regression: {}", regression);
+        }
+        if !regressions.is_empty() {
+            return ExitCode::from(1);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Parse the arguments after the program name.
+fn parse(args: Vec<String>) -> Result<Options, String> {
+    let mut options = Options { runs: 3, model: None, backend: None, save: None, baseline: None, tolerance: 0.1, json: false };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("`{}` needs a value", arg));
+        match arg.as_str() {
+            "--runs" => options.runs = value()?.parse().ok().filter(|&n| n > 0).ok_or("`--runs` takes a positive count")?,
+            "--model" => options.model = Some(value()?),
+            "--backend" => options.backend = Some(value()?),
+            "--save" => options.save = Some(value()?),
+            "--compare" => options.baseline = Some(value()?),
+            "--tolerance" => options.tolerance = value()?.parse().map_err(|_| "`--tolerance` takes a number")?,
+            "--json" => options.json = true,
+            other => return Err(format!("unknown argument `{}`", other)),
+        }
+    }
+    if let Some(name) = &options.model {
+        if !MODELS.iter().any(|m| m.name == name) {
+            return Err(format!("unknown model `{}`", name));
+        }
+    }
+    if let Some(name) = &options.backend {
+        if !BACKENDS.iter().any(|&b| backend_name(b) == name) {
+            return Err(format!("unknown backend `{}`", name));
+        }
+    }
+    Ok(options)
+}
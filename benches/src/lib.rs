@@ -0,0 +1,305 @@
+//! # Benchmarks
+//!
+//! Representative models and the measurements taken of them, shared by the
+//! criterion benches and the `report` binary.
+//!
+//! ## Models
+//!
+//! | Model | Stresses |
+//! |-------|----------|
+//! | `hull_grid` | Many small hulls, joined by one large union |
+//! | `boolean_tower` | Differences of high-`$fn` cylinders and spheres |
+//! | `union_loop` | A union of many small operands from a deep `for` loop |
+//!
+//! ## Measurements
+//!
+//! A [`Sample`] times evaluation and CSG separately, counts the triangles
+//! of the result, and records the peak heap use of the whole render when
+//! the binary counts allocations with [`PeakAlloc`]. Samples of two runs,
+//! say before and after a backend change, are compared with [`compare`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! use c4d_benches::{sample, Model};
+//! use openscad_eval::CsgBackend;
+//!
+//! let model = Model { name: "cube", source: "cube(10);" };
+//! let sample = sample(&model, CsgBackend::Bsp, None).unwrap();
+//! assert_eq!(sample.triangles, 12);
+//! assert_eq!(sample.peak_bytes, None);
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use manifold_rs::ManifoldError;
+use openscad_eval::{CsgBackend, CsgOptions, EvalOptions};
+use serde::{Deserialize, Serialize};
+
+/// A benchmark model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Model {
+    /// Short name, the file name without `.scad`.
+    pub name: &'static str,
+    /// OpenSCAD source.
+    pub source: &'static str,
+}
+
+/// The benchmark models.
+pub const MODELS: &[Model] = &[
+    Model { name: "hull_grid", source: include_str!("../models/hull_grid.scad") },
+    Model { name: "boolean_tower", source: include_str!("../models/boolean_tower.scad") },
+    Model { name: "union_loop", source: include_str!("../models/union_loop.scad") },
+];
+
+/// The CSG backends compared.
+pub const BACKENDS: &[CsgBackend] = &[CsgBackend::Bsp, CsgBackend::Intersect, CsgBackend::Auto];
+
+/// Time differences below this many milliseconds are noise, whatever the
+/// tolerance.
+pub const NOISE_MS: f64 = 5.0;
+
+/// Measurements of one render of a model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    /// The model's name.
+    pub model: String,
+    /// The CSG backend used.
+    pub backend: CsgBackend,
+    /// Evaluation to geometry, in milliseconds.
+    pub eval_ms: f64,
+    /// Meshing and booleans, in milliseconds.
+    pub csg_ms: f64,
+    /// Triangles of the resulting mesh.
+    pub triangles: usize,
+    /// Most heap in use at once during the render, in bytes; `None` when
+    /// allocations are not counted.
+    pub peak_bytes: Option<u64>,
+}
+
+// =============================================================================
+// PEAK MEMORY
+// =============================================================================
+
+/// The system allocator, counting bytes in use and their peak.
+///
+/// Install it as the global allocator of a binary to get
+/// [`Sample::peak_bytes`]:
+///
+/// ```rust,ignore
+/// #[global_allocator]
+/// static ALLOC: c4d_benches::PeakAlloc = c4d_benches::PeakAlloc::new();
+/// ```
+#[derive(Debug, Default)]
+pub struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl PeakAlloc {
+    /// An allocator with nothing counted.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { current: AtomicUsize::new(0), peak: AtomicUsize::new(0) }
+    }
+
+    /// Start a new peak from what is in use now.
+    pub fn reset_peak(&self) {
+        self.peak.store(self.current.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    /// Most bytes in use at once since the last reset.
+    #[must_use]
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    fn add(&self, bytes: usize) {
+        let current = self.current.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn sub(&self, bytes: usize) {
+        self.current.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: every call goes straight to `System`, which upholds the
+// `GlobalAlloc` contract; the counting has no effect on the memory.
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.sub(layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            self.sub(layout.size());
+            self.add(new_size);
+        }
+        new
+    }
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// A backend's name as in render options: `bsp`, `intersect`, `auto`.
+#[must_use]
+pub fn backend_name(backend: CsgBackend) -> &'static str {
+    match backend {
+        CsgBackend::Bsp => "bsp",
+        CsgBackend::Intersect => "intersect",
+        CsgBackend::Auto => "auto",
+    }
+}
+
+/// Evaluation options rendering with `backend`.
+#[must_use]
+pub fn options(backend: CsgBackend) -> EvalOptions {
+    EvalOptions { csg: CsgOptions { backend: Some(backend), ..CsgOptions::default() }, ..EvalOptions::default() }
+}
+
+/// Render `model` once with `backend` and measure it, counting peak
+/// memory with `alloc` if given.
+///
+/// ## Errors
+///
+/// The evaluation or render error.
+pub fn sample(model: &Model, backend: CsgBackend, alloc: Option<&PeakAlloc>) -> Result<Sample, ManifoldError> {
+    let options = options(backend);
+    if let Some(alloc) = alloc {
+        alloc.reset_peak();
+    }
+    let started = Instant::now();
+    let evaluated = openscad_eval::evaluate_with_options(model.source, &options)?;
+    let evaluated_at = Instant::now();
+    let mesh = manifold_rs::render_evaluated(&evaluated, &options, openscad_eval::limits::now_ms())?;
+    let finished = Instant::now();
+    Ok(Sample {
+        model: model.name.to_string(),
+        backend,
+        eval_ms: (evaluated_at - started).as_secs_f64() * 1000.0,
+        csg_ms: (finished - evaluated_at).as_secs_f64() * 1000.0,
+        triangles: mesh.triangle_count(),
+        peak_bytes: alloc.map(|a| a.peak() as u64),
+    })
+}
+
+/// The median times and largest peak of several samples of one model and
+/// backend.
+#[must_use]
+pub fn median(samples: &[Sample]) -> Option<Sample> {
+    let middle = |mut values: Vec<f64>| {
+        values.sort_by(f64::total_cmp);
+        values[values.len() / 2]
+    };
+    let first = samples.first()?;
+    Some(Sample {
+        eval_ms: middle(samples.iter().map(|s| s.eval_ms).collect()),
+        csg_ms: middle(samples.iter().map(|s| s.csg_ms).collect()),
+        peak_bytes: samples.iter().filter_map(|s| s.peak_bytes).max(),
+        ..first.clone()
+    })
+}
+
+/// What got worse from `baseline` to `current`, one line per regression.
+///
+/// Evaluation time, CSG time, triangles and peak memory regress when they
+/// grow by more than `tolerance` (0.1 for 10%); times must also grow by
+/// [`NOISE_MS`]. Samples missing from either side are skipped.
+#[must_use]
+pub fn compare(baseline: &[Sample], current: &[Sample], tolerance: f64) -> Vec<String> {
+    let mut regressions = Vec::new();
+    for now in current {
+        let Some(before) = baseline.iter().find(|b| b.model == now.model && b.backend == now.backend) else { continue };
+        let name = format!("{} ({})", now.model, backend_name(now.backend));
+        let grew = |before: f64, now: f64| now > before * (1.0 + tolerance);
+        for (what, before, now) in [("eval", before.eval_ms, now.eval_ms), ("csg", before.csg_ms, now.csg_ms)] {
+            if grew(before, now) && now - before > NOISE_MS {
+                regressions.push(format!("{}: {} time {:.1} ms -> {:.1} ms", name, what, before, now));
+            }
+        }
+        if grew(before.triangles as f64, now.triangles as f64) {
+            regressions.push(format!("{}: triangles {} -> {}", name, before.triangles, now.triangles));
+        }
+        if let (Some(before), Some(now)) = (before.peak_bytes, now.peak_bytes) {
+            if grew(before as f64, now as f64) {
+                regressions.push(format!("{}: peak memory {} -> {}", name, mib(before), mib(now)));
+            }
+        }
+    }
+    regressions
+}
+
+/// Bytes in MiB, for display.
+#[must_use]
+pub fn mib(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(model: &str, eval_ms: f64, triangles: usize, peak_bytes: Option<u64>) -> Sample {
+        Sample { model: model.to_string(), backend: CsgBackend::Bsp, eval_ms, csg_ms: 100.0, triangles, peak_bytes }
+    }
+
+    /// Test each model evaluates to geometry.
+    #[test]
+    fn test_models_evaluate() {
+        for model in MODELS {
+            let evaluated = openscad_eval::evaluate(model.source).unwrap();
+            assert!(evaluated.warnings.is_empty(), "{}: {:?}", model.name, evaluated.warnings);
+            assert!(!evaluated.geometry.bounds().unwrap().is_empty(), "{}", model.name);
+        }
+    }
+
+    /// Test regressions beyond the tolerance are reported, and noise is
+    /// not.
+    #[test]
+    fn test_compare() {
+        let baseline = [sample("a", 20.0, 100, Some(1 << 20)), sample("b", 1.0, 100, None)];
+        let current = [sample("a", 30.0, 130, Some(1 << 21)), sample("b", 4.0, 105, None), sample("c", 1.0, 1, None)];
+        assert_eq!(compare(&baseline, &current, 0.1), [
+            "a (bsp): eval time 20.0 ms -> 30.0 ms",
+            "a (bsp): triangles 100 -> 130",
+            "a (bsp): peak memory 1.0 MiB -> 2.0 MiB",
+        ]);
+        assert!(compare(&baseline, &current, 0.5).len() == 1);
+    }
+
+    /// Test the median takes the middle time and the largest peak.
+    #[test]
+    fn test_median() {
+        let samples = [sample("a", 3.0, 10, Some(5)), sample("a", 1.0, 10, Some(9)), sample("a", 2.0, 10, None)];
+        let median = median(&samples).unwrap();
+        assert_eq!((median.eval_ms, median.peak_bytes), (2.0, Some(9)));
+        assert!(super::median(&[]).is_none());
+    }
+}
@@ -3,6 +3,9 @@
 //! Unit tests verifying the correctness of configuration constants
 //! and helper functions.
 
+// These tests deliberately assert relationships between constants.
+#![allow(clippy::assertions_on_constants)]
+
 use crate::constants::*;
 
 // =============================================================================
@@ -0,0 +1,444 @@
+//! # 2D Booleans
+//!
+//! Union, difference and intersection of filled 2D regions, for 2D
+//! `union()`, `difference()` and `intersection()` and for merging the
+//! loops `offset()` produces.
+//!
+//! ## Algorithm
+//!
+//! ```text
+//! 1. Split every edge of every operand where it meets another edge
+//! 2. Classify each piece by the operands' winding numbers just left and
+//!    right of it
+//! 3. Keep the pieces with the result filled on one side only, turned so
+//!    the filled side is on the left
+//! 4. Chain the pieces into loops and nest them into regions
+//! ```
+//!
+//! A point is inside an operand where the operand's loops wind around it a
+//! positive number of times, so regions overlapping within one operand
+//! (the children of a group) are merged, as in OpenSCAD.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::cross_section::boolean::{boolean_polygons, BooleanOp2D};
+//! use manifold_rs::font::Polygon2D;
+//!
+//! let square = |x: f64| Polygon2D { outer: vec![[x, 0.0], [x + 2.0, 0.0], [x + 2.0, 2.0], [x, 2.0]], holes: vec![] };
+//! let union = boolean_polygons(&[vec![square(0.0)], vec![square(1.0)]], BooleanOp2D::Union);
+//! assert_eq!(union.len(), 1);
+//! assert_eq!(union[0].outer.len(), 4);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use crate::font::outline::group_contours;
+use crate::font::Polygon2D;
+
+use super::ops::{crossing, dedup_loop, dot, sub, winding_number};
+
+// =============================================================================
+// OPERATIONS
+// =============================================================================
+
+/// A boolean operation on filled 2D regions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp2D {
+    /// Area inside any operand.
+    Union,
+    /// Area inside the first operand and outside all the others.
+    Difference,
+    /// Area inside every operand.
+    Intersection,
+}
+
+impl BooleanOp2D {
+    /// Whether the result covers a point the operands wind around
+    /// `windings` times.
+    fn fills(self, windings: &[i32]) -> bool {
+        match self {
+            Self::Union => windings.iter().any(|&w| w > 0),
+            Self::Difference => windings.first().is_some_and(|&w| w > 0) && windings[1..].iter().all(|&w| w <= 0),
+            Self::Intersection => !windings.is_empty() && windings.iter().all(|&w| w > 0),
+        }
+    }
+}
+
+/// Combine filled regions.
+///
+/// ## Parameters
+///
+/// - `operands`: Regions of each operand, CCW outlines with CW holes;
+///   regions of one operand may overlap
+/// - `op`: Operation; a difference subtracts every later operand from the
+///   first
+///
+/// ## Returns
+///
+/// The result, CCW outlines with CW holes. Regions that only touch at a
+/// point are kept apart.
+pub fn boolean_polygons(operands: &[Vec<Polygon2D>], op: BooleanOp2D) -> Vec<Polygon2D> {
+    let operands: Vec<Vec<Vec<[f64; 2]>>> = operands.iter()
+        .map(|regions| {
+            regions.iter()
+                .flat_map(|polygon| std::iter::once(&polygon.outer).chain(&polygon.holes))
+                .cloned()
+                .collect()
+        })
+        .collect();
+    group_contours(boolean_contours(&operands, op))
+}
+
+/// Merge overlapping regions into disjoint ones.
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::cross_section::boolean::union_polygons;
+/// use manifold_rs::font::Polygon2D;
+///
+/// let square = |x: f64| Polygon2D { outer: vec![[x, 0.0], [x + 2.0, 0.0], [x + 2.0, 2.0], [x, 2.0]], holes: vec![] };
+/// assert_eq!(union_polygons(&[square(0.0), square(1.0), square(5.0)]).len(), 2);
+/// ```
+pub fn union_polygons(polygons: &[Polygon2D]) -> Vec<Polygon2D> {
+    boolean_polygons(&[polygons.to_vec()], BooleanOp2D::Union)
+}
+
+/// Combine operands given as loose loops, filled where they wind around a
+/// point a positive number of times.
+///
+/// ## Returns
+///
+/// The boundary loops of the result, filled side on the left: CCW
+/// outlines and CW holes, not yet nested.
+pub(crate) fn boolean_contours(operands: &[Vec<Vec<[f64; 2]>>], op: BooleanOp2D) -> Vec<Vec<[f64; 2]>> {
+    let loops: Vec<Vec<Vec<[f64; 2]>>> = operands.iter()
+        .map(|contours| {
+            contours.iter()
+                .map(|contour| dedup_loop(contour.clone()))
+                .filter(|contour| contour.len() >= 3)
+                .collect()
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for contours in &loops {
+        for contour in contours {
+            let n = contour.len();
+            edges.extend((0..n).map(|i| (contour[i], contour[(i + 1) % n])));
+        }
+    }
+    if edges.is_empty() {
+        return Vec::new();
+    }
+
+    // Sample distance off each piece, far below any feature but well
+    // above rounding
+    let scale = edges.iter()
+        .flat_map(|(a, _)| a.iter())
+        .fold(1.0f64, |m, c| m.max(c.abs()));
+    let h = scale * 1e-9;
+    let windings = |p: [f64; 2]| -> Vec<i32> {
+        loops.iter()
+            .map(|contours| contours.iter().map(|contour| winding_number(contour, p)).sum())
+            .collect()
+    };
+
+    let mut kept = Vec::new();
+    let mut seen = HashSet::new();
+    for (a, b) in split_edges(&edges) {
+        let e = sub(b, a);
+        let length = dot(e, e).sqrt();
+        let normal = [-e[1] / length * h, e[0] / length * h];
+        let mid = [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0];
+        let left = op.fills(&windings([mid[0] + normal[0], mid[1] + normal[1]]));
+        let right = op.fills(&windings([mid[0] - normal[0], mid[1] - normal[1]]));
+        if left == right {
+            continue;
+        }
+        // Pieces shared by several operands are kept once
+        let segment = if left { (a, b) } else { (b, a) };
+        if seen.insert([key(segment.0), key(segment.1)]) {
+            kept.push(segment);
+        }
+    }
+
+    chain_segments(&kept)
+        .into_iter()
+        .map(drop_collinear)
+        .filter(|contour| contour.len() >= 3)
+        .collect()
+}
+
+// =============================================================================
+// SPLITTING
+// =============================================================================
+
+/// Split edges wherever they cross, touch or overlap another edge.
+///
+/// Edges are swept by their lowest x, so only pairs whose x ranges
+/// overlap are tested. A crossing point is computed once and given to both
+/// edges, so the pieces meet exactly.
+fn split_edges(edges: &[([f64; 2], [f64; 2])]) -> Vec<([f64; 2], [f64; 2])> {
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    let min_x = |i: usize| edges[i].0[0].min(edges[i].1[0]);
+    let max_x = |i: usize| edges[i].0[0].max(edges[i].1[0]);
+    order.sort_by(|&i, &j| min_x(i).total_cmp(&min_x(j)));
+
+    let mut splits: Vec<Vec<[f64; 2]>> = vec![Vec::new(); edges.len()];
+    for (n, &i) in order.iter().enumerate() {
+        let (a, b) = edges[i];
+        for &j in &order[n + 1..] {
+            if min_x(j) > max_x(i) {
+                break;
+            }
+            let (c, d) = edges[j];
+            if a[1].max(b[1]) < c[1].min(d[1]) || a[1].min(b[1]) > c[1].max(d[1]) {
+                continue;
+            }
+            match crossing(a, b, c, d) {
+                Some(x) => {
+                    if x != a && x != b {
+                        splits[i].push(x);
+                    }
+                    if x != c && x != d {
+                        splits[j].push(x);
+                    }
+                }
+                None if collinear(a, b, c, d) => {
+                    splits[i].extend([c, d].into_iter().filter(|&p| strictly_within(a, b, p)));
+                    splits[j].extend([a, b].into_iter().filter(|&p| strictly_within(c, d, p)));
+                }
+                None => {}
+            }
+        }
+    }
+
+    let mut pieces = Vec::new();
+    for (&(a, b), mut points) in edges.iter().zip(splits) {
+        let r = sub(b, a);
+        points.sort_by(|p, q| dot(sub(*p, a), r).total_cmp(&dot(sub(*q, a), r)));
+        points.dedup();
+        let mut start = a;
+        for point in points.into_iter().chain(std::iter::once(b)) {
+            if point != start {
+                pieces.push((start, point));
+                start = point;
+            }
+        }
+    }
+    pieces
+}
+
+/// Check segments `ab` and `cd` lie on one line.
+fn collinear(a: [f64; 2], b: [f64; 2], c: [f64; 2], d: [f64; 2]) -> bool {
+    let r = sub(b, a);
+    let tolerance = 1e-12 * dot(r, r).sqrt();
+    [c, d].iter().all(|&p| {
+        let ap = sub(p, a);
+        (r[0] * ap[1] - r[1] * ap[0]).abs() <= tolerance * dot(ap, ap).sqrt().max(1.0)
+    })
+}
+
+/// Check `p`, on the line through `ab`, lies strictly between `a` and `b`.
+fn strictly_within(a: [f64; 2], b: [f64; 2], p: [f64; 2]) -> bool {
+    const EPS: f64 = 1e-9;
+    let r = sub(b, a);
+    let t = dot(sub(p, a), r) / dot(r, r);
+    t > EPS && t < 1.0 - EPS
+}
+
+// =============================================================================
+// CHAINING
+// =============================================================================
+
+/// Exact hash key of a point; `-0.0` and `0.0` are the same point.
+fn key(p: [f64; 2]) -> [u64; 2] {
+    [(p[0] + 0.0).to_bits(), (p[1] + 0.0).to_bits()]
+}
+
+/// Join directed segments end to start into closed loops.
+///
+/// Where several segments leave a point, the walk takes the sharpest left
+/// turn, staying in the filled corner it arrived along. A walk that comes
+/// back to a point it already passed closes the loop in between, so loops
+/// touching at a point come out separate. Chains that cannot be closed are
+/// dropped.
+fn chain_segments(segments: &[([f64; 2], [f64; 2])]) -> Vec<Vec<[f64; 2]>> {
+    let mut outgoing: HashMap<[u64; 2], Vec<usize>> = HashMap::new();
+    for (i, (a, _)) in segments.iter().enumerate() {
+        outgoing.entry(key(*a)).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut loops = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        let mut path: Vec<[f64; 2]> = Vec::new();
+        let mut position: HashMap<[u64; 2], usize> = HashMap::new();
+        let mut visit = |p: [f64; 2], path: &mut Vec<[f64; 2]>, loops: &mut Vec<Vec<[f64; 2]>>| {
+            if let Some(i) = position.get(&key(p)).copied() {
+                for q in &path[i..] {
+                    position.remove(&key(*q));
+                }
+                loops.push(path.split_off(i));
+            }
+            position.insert(key(p), path.len());
+            path.push(p);
+        };
+
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let (a, b) = segments[current];
+            visit(a, &mut path, &mut loops);
+            let incoming = sub(b, a);
+            let next = outgoing.get(&key(b)).and_then(|candidates| {
+                candidates.iter()
+                    .copied()
+                    .filter(|&i| !used[i])
+                    .max_by(|&i, &j| {
+                        let turn = |k: usize| {
+                            let out = sub(segments[k].1, segments[k].0);
+                            (incoming[0] * out[1] - incoming[1] * out[0]).atan2(dot(incoming, out))
+                        };
+                        turn(i).total_cmp(&turn(j))
+                    })
+            });
+            match next {
+                Some(i) => current = i,
+                None => {
+                    // Closes the loop if b was passed, else the chain is open
+                    visit(b, &mut path, &mut loops);
+                    break;
+                }
+            }
+        }
+    }
+    loops
+}
+
+/// Remove points where a loop runs straight on, left by splitting.
+fn drop_collinear(points: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    let mut kept: Vec<[f64; 2]> = Vec::with_capacity(points.len());
+    for p in points {
+        while kept.len() >= 2 && straight(kept[kept.len() - 2], kept[kept.len() - 1], p) {
+            kept.pop();
+        }
+        kept.push(p);
+    }
+    // Around the seam
+    while kept.len() >= 3 && straight(kept[kept.len() - 2], kept[kept.len() - 1], kept[0]) {
+        kept.pop();
+    }
+    while kept.len() >= 3 && straight(kept[kept.len() - 1], kept[0], kept[1]) {
+        kept.remove(0);
+    }
+    kept
+}
+
+/// Check `b` lies on the segment from `a` to `c`, going the same way.
+fn straight(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> bool {
+    let (e1, e2) = (sub(b, a), sub(c, b));
+    let cross = e1[0] * e2[1] - e1[1] * e2[0];
+    cross.abs() <= 1e-12 * dot(e1, e1).sqrt() * dot(e2, e2).sqrt() && dot(e1, e2) > 0.0
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::ops::signed_area;
+
+    /// Axis-aligned rectangle region.
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> Polygon2D {
+        Polygon2D { outer: vec![[x, y], [x + w, y], [x + w, y + h], [x, y + h]], holes: vec![] }
+    }
+
+    /// Filled area of regions.
+    fn area(polygons: &[Polygon2D]) -> f64 {
+        polygons.iter()
+            .flat_map(|p| std::iter::once(&p.outer).chain(&p.holes))
+            .map(|c| signed_area(c) / 2.0)
+            .sum()
+    }
+
+    /// Test each operation on two overlapping squares.
+    #[test]
+    fn test_overlapping_squares() {
+        let (a, b) = (vec![rect(0.0, 0.0, 2.0, 2.0)], vec![rect(1.0, 1.0, 2.0, 2.0)]);
+        let operands = [a, b];
+
+        let union = boolean_polygons(&operands, BooleanOp2D::Union);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].outer.len(), 8);
+        assert!((area(&union) - 7.0).abs() < 1e-12);
+
+        let difference = boolean_polygons(&operands, BooleanOp2D::Difference);
+        assert_eq!(difference.len(), 1);
+        assert_eq!(difference[0].outer.len(), 6);
+        assert!((area(&difference) - 3.0).abs() < 1e-12);
+
+        let intersection = boolean_polygons(&operands, BooleanOp2D::Intersection);
+        assert_eq!(intersection.len(), 1);
+        assert_eq!(intersection[0].outer.len(), 4);
+        assert!((area(&intersection) - 1.0).abs() < 1e-12);
+    }
+
+    /// Test a difference inside the first operand makes a hole, and one
+    /// across it splits it in two.
+    #[test]
+    fn test_difference_holes_and_splits() {
+        let plate = vec![rect(0.0, 0.0, 10.0, 10.0)];
+        let holed = boolean_polygons(&[plate.clone(), vec![rect(3.0, 3.0, 4.0, 4.0)]], BooleanOp2D::Difference);
+        assert_eq!(holed.len(), 1);
+        assert_eq!(holed[0].holes.len(), 1);
+        assert!((area(&holed) - 84.0).abs() < 1e-12);
+
+        let split = boolean_polygons(&[plate, vec![rect(4.0, -1.0, 2.0, 12.0)]], BooleanOp2D::Difference);
+        assert_eq!(split.len(), 2);
+        assert!(split.iter().all(|p| p.outer.len() == 4 && p.holes.is_empty()));
+        assert!((area(&split) - 80.0).abs() < 1e-12);
+    }
+
+    /// Test shared edges merge and shared corners stay apart.
+    #[test]
+    fn test_touching_squares() {
+        let side = union_polygons(&[rect(0.0, 0.0, 1.0, 1.0), rect(1.0, 0.0, 1.0, 1.0)]);
+        assert_eq!(side.len(), 1);
+        assert_eq!(side[0].outer.len(), 4);
+
+        let corner = union_polygons(&[rect(0.0, 0.0, 1.0, 1.0), rect(1.0, 1.0, 1.0, 1.0)]);
+        assert_eq!(corner.len(), 2);
+        assert!(corner.iter().all(|p| p.outer.len() == 4));
+
+        // Holes touching at a corner are two holes
+        let holes = boolean_polygons(
+            &[vec![rect(0.0, 0.0, 4.0, 4.0)], vec![rect(1.0, 1.0, 1.0, 1.0), rect(2.0, 2.0, 1.0, 1.0)]],
+            BooleanOp2D::Difference,
+        );
+        assert_eq!(holes.len(), 1);
+        assert_eq!(holes[0].holes.len(), 2);
+    }
+
+    /// Test identical and disjoint operands.
+    #[test]
+    fn test_degenerate_operands() {
+        let square = vec![rect(0.0, 0.0, 1.0, 1.0)];
+        let same = [square.clone(), square.clone()];
+        assert_eq!(boolean_polygons(&same, BooleanOp2D::Union), square);
+        assert_eq!(boolean_polygons(&same, BooleanOp2D::Intersection), square);
+        assert!(boolean_polygons(&same, BooleanOp2D::Difference).is_empty());
+
+        let apart = [square.clone(), vec![rect(5.0, 0.0, 1.0, 1.0)]];
+        assert!(boolean_polygons(&apart, BooleanOp2D::Intersection).is_empty());
+        assert_eq!(boolean_polygons(&apart, BooleanOp2D::Difference), square);
+        assert!(boolean_polygons(&[], BooleanOp2D::Union).is_empty());
+    }
+}
@@ -4,14 +4,17 @@
 //!
 //! ## OpenSCAD Compatibility
 //!
-//! - `linear_extrude`: height, center, twist, scale, slices
-//! - `rotate_extrude`: angle, segments
+//! - `linear_extrude`: height, center, twist, scale (per axis), slices
+//! - `rotate_extrude`: angle, segments; partial angles are capped and
+//!   profiles may touch the Z axis
+
+use std::collections::HashSet;
 
 use openscad_eval::GeometryNode;
-use crate::error::ManifoldResult;
+use crate::error::{ManifoldError, ManifoldResult};
 use crate::mesh::Mesh;
 use crate::openscad::SegmentParams;
-use std::f32::consts::PI;
+use super::primitives::build_polygon_mesh;
 
 // =============================================================================
 // LINEAR EXTRUDE
@@ -35,6 +38,7 @@ use std::f32::consts::PI;
 /// - `scale`: Scale factor at top (default 1.0)
 /// - `slices`: Number of vertical slices
 /// - `params`: Segment parameters for children
+#[allow(clippy::too_many_arguments)]
 pub fn linear_extrude(
     mesh: &mut Mesh,
     children: &[GeometryNode],
@@ -45,107 +49,79 @@ pub fn linear_extrude(
     slices: u32,
     params: &SegmentParams,
 ) -> ManifoldResult<()> {
-    let h = height as f32;
-    let twist_rad = (twist as f32).to_radians();
-    let scale_factor = scale as f64;
-    let num_slices = slices.max(1) as usize;
-    
-    let z_offset = if center { -h / 2.0 } else { 0.0 };
-    
-    // Process each 2D child
     for child in children {
         let polygon = extract_2d_points(child, params)?;
         if polygon.len() < 3 {
             continue;
         }
-        
-        // Generate layers
-        for slice in 0..num_slices {
-            let t0 = slice as f32 / num_slices as f32;
-            let t1 = (slice + 1) as f32 / num_slices as f32;
-            
-            let z0 = z_offset + h * t0;
-            let z1 = z_offset + h * t1;
-            
-            let angle0 = twist_rad * t0;
-            let angle1 = twist_rad * t1;
-            
-            let s0 = 1.0 + (scale_factor - 1.0) * t0 as f64;
-            let s1 = 1.0 + (scale_factor - 1.0) * t1 as f64;
-            
-            // Create side faces between layers
-            for i in 0..polygon.len() {
-                let next = (i + 1) % polygon.len();
-                
-                let p0 = &polygon[i];
-                let p1 = &polygon[next];
-                
-                // Bottom layer vertices
-                let (x00, y00) = rotate_point(p0[0] * s0, p0[1] * s0, angle0);
-                let (x01, y01) = rotate_point(p1[0] * s0, p1[1] * s0, angle0);
-                
-                // Top layer vertices
-                let (x10, y10) = rotate_point(p0[0] * s1, p0[1] * s1, angle1);
-                let (x11, y11) = rotate_point(p1[0] * s1, p1[1] * s1, angle1);
-                
-                // Compute normal (approximation)
-                let nx = (y01 - y00) as f32;
-                let ny = -(x01 - x00) as f32;
-                let nz = 0.0;
-                let len = (nx * nx + ny * ny).sqrt();
-                let (nx, ny) = if len > 0.0 { (nx / len, ny / len) } else { (0.0, 1.0) };
-                
-                let v0 = mesh.add_vertex(x00 as f32, y00 as f32, z0, nx, ny, nz);
-                let v1 = mesh.add_vertex(x01 as f32, y01 as f32, z0, nx, ny, nz);
-                let v2 = mesh.add_vertex(x11 as f32, y11 as f32, z1, nx, ny, nz);
-                let v3 = mesh.add_vertex(x10 as f32, y10 as f32, z1, nx, ny, nz);
-                
-                mesh.add_triangle(v0, v1, v2);
-                mesh.add_triangle(v0, v2, v3);
-            }
-        }
-        
-        // Add bottom cap
-        add_cap(mesh, &polygon, z_offset, 0.0, 1.0, -1.0);
-        
-        // Add top cap
-        let top_angle = twist_rad;
-        add_cap(mesh, &polygon, z_offset + h, top_angle, scale_factor as f32, 1.0);
+        let mut profile = Mesh::new();
+        build_polygon_mesh(&mut profile, &polygon, None);
+        mesh.merge(&extrude_profile(&profile, height, center, twist, [scale, scale], slices));
     }
-    
     Ok(())
 }
 
-/// Add a cap (top or bottom) to the extrusion.
-fn add_cap(mesh: &mut Mesh, polygon: &[[f64; 2]], z: f32, angle: f32, scale: f32, nz: f32) {
-    if polygon.len() < 3 {
-        return;
+/// Extrude a 2D profile mesh along Z.
+///
+/// The profile is copied to `slices + 1` levels. At fraction `t` of the
+/// height it is scaled by `1 + (scale - 1) * t` per axis and then rotated
+/// by `-twist * t` degrees (positive twist turns clockwise going up, as
+/// in OpenSCAD). The profile's boundary edges form the walls between
+/// levels and its triangles the bottom and top caps. A scale of 0 on both
+/// axes collapses the top to a point (a pyramid or cone); faces that
+/// collapse are dropped, so the result stays watertight once welded.
+///
+/// ## Parameters
+///
+/// - `profile`: 2D mesh in the XY plane
+/// - `height`: Extrusion height; nothing is produced if not positive
+/// - `center`: Center the extrusion on z = 0
+/// - `twist`: Rotation over the height in degrees
+/// - `scale`: Top scale per axis, clamped to 0 or more
+/// - `slices`: Number of levels between bottom and top (at least 1)
+pub fn extrude_profile(profile: &Mesh, height: f64, center: bool, twist: f64, scale: [f64; 2], slices: u32) -> Mesh {
+    let mut mesh = Mesh::new();
+    let (points, triangles) = profile_triangles(profile);
+    if triangles.is_empty() || height <= 0.0 {
+        return mesh;
     }
-    
-    // Transform first point
-    let (x0, y0) = rotate_point(polygon[0][0] * scale as f64, polygon[0][1] * scale as f64, angle);
-    let first = mesh.add_vertex(x0 as f32, y0 as f32, z, 0.0, 0.0, nz);
-    
-    // Fan triangulation
-    for i in 1..polygon.len() - 1 {
-        let (x1, y1) = rotate_point(polygon[i][0] * scale as f64, polygon[i][1] * scale as f64, angle);
-        let (x2, y2) = rotate_point(polygon[i + 1][0] * scale as f64, polygon[i + 1][1] * scale as f64, angle);
-        
-        let v1 = mesh.add_vertex(x1 as f32, y1 as f32, z, 0.0, 0.0, nz);
-        let v2 = mesh.add_vertex(x2 as f32, y2 as f32, z, 0.0, 0.0, nz);
-        
-        if nz > 0.0 {
-            mesh.add_triangle(first, v1, v2);
-        } else {
-            mesh.add_triangle(first, v2, v1);
+
+    let slices = slices.max(1) as usize;
+    let scale = scale.map(|s| s.max(0.0));
+    let z_offset = if center { -height / 2.0 } else { 0.0 };
+    let at = |p: [f64; 2], level: usize| -> [f64; 3] {
+        let t = level as f64 / slices as f64;
+        let (x, y) = rotate_point(
+            p[0] * (1.0 + (scale[0] - 1.0) * t),
+            p[1] * (1.0 + (scale[1] - 1.0) * t),
+            (-twist * t).to_radians(),
+        );
+        [x, y, z_offset + height * t]
+    };
+
+    // Walls from the profile outline
+    for [a, b] in boundary_edges(&triangles) {
+        let (a, b) = (points[a as usize], points[b as usize]);
+        for i in 0..slices {
+            let (a0, a1, b0, b1) = (at(a, i), at(a, i + 1), at(b, i), at(b, i + 1));
+            add_face(&mut mesh, [a0, b0, b1], false);
+            add_face(&mut mesh, [a0, b1, a1], false);
         }
     }
+
+    // Bottom cap faces down, top cap up
+    for t in &triangles {
+        let [p0, p1, p2] = t.map(|i| points[i as usize]);
+        add_face(&mut mesh, [at(p0, 0), at(p1, 0), at(p2, 0)], true);
+        add_face(&mut mesh, [at(p0, slices), at(p1, slices), at(p2, slices)], false);
+    }
+
+    mesh
 }
 
 /// Rotate a 2D point by angle (radians).
-fn rotate_point(x: f64, y: f64, angle: f32) -> (f64, f64) {
-    let cos_a = (angle as f64).cos();
-    let sin_a = (angle as f64).sin();
+fn rotate_point(x: f64, y: f64, angle: f64) -> (f64, f64) {
+    let (sin_a, cos_a) = angle.sin_cos();
     (x * cos_a - y * sin_a, x * sin_a + y * cos_a)
 }
 
@@ -167,123 +143,176 @@ fn rotate_point(x: f64, y: f64, angle: f32) -> (f64, f64) {
 /// - `children`: 2D child geometry nodes
 /// - `angle`: Rotation angle in degrees (360 for full revolution)
 /// - `params`: Segment parameters
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::GeometryError` if a child has points on both
+/// sides of the Z axis.
 pub fn rotate_extrude(
     mesh: &mut Mesh,
     children: &[GeometryNode],
     angle: f64,
     params: &SegmentParams,
 ) -> ManifoldResult<()> {
-    let angle_rad = (angle as f32).to_radians();
-    let segments = params.calculate_segments(10.0); // Use reasonable default radius
-    let num_segments = segments.max(3) as usize;
-    
-    // Process each 2D child
     for child in children {
         let polygon = extract_2d_points(child, params)?;
-        if polygon.len() < 2 {
+        if polygon.len() < 3 {
             continue;
         }
-        
-        // Generate rotated layers
-        for seg in 0..num_segments {
-            let t0 = seg as f32 / num_segments as f32;
-            let t1 = (seg + 1) as f32 / num_segments as f32;
-            
-            let theta0 = angle_rad * t0;
-            let theta1 = angle_rad * t1;
-            
-            let (cos0, sin0) = (theta0.cos(), theta0.sin());
-            let (cos1, sin1) = (theta1.cos(), theta1.sin());
-            
-            // Create quads between layers
-            for i in 0..polygon.len() - 1 {
-                let p0 = &polygon[i];
-                let p1 = &polygon[i + 1];
-                
-                // p0 and p1 are in XY plane, we rotate around Z
-                // X in polygon becomes radius, Y becomes Z
-                let r0 = p0[0] as f32;
-                let r1 = p1[0] as f32;
-                let z0 = p0[1] as f32;
-                let z1 = p1[1] as f32;
-                
-                if r0 < 0.0 || r1 < 0.0 {
-                    continue; // Skip points on wrong side of axis
-                }
-                
-                // Four corners of quad
-                let x00 = r0 * cos0;
-                let y00 = r0 * sin0;
-                let x01 = r0 * cos1;
-                let y01 = r0 * sin1;
-                let x10 = r1 * cos0;
-                let y10 = r1 * sin0;
-                let x11 = r1 * cos1;
-                let y11 = r1 * sin1;
-                
-                // Compute normals
-                let nx0 = cos0;
-                let ny0 = sin0;
-                let nx1 = cos1;
-                let ny1 = sin1;
-                
-                let v00 = mesh.add_vertex(x00, y00, z0, nx0, ny0, 0.0);
-                let v01 = mesh.add_vertex(x01, y01, z0, nx1, ny1, 0.0);
-                let v10 = mesh.add_vertex(x10, y10, z1, nx0, ny0, 0.0);
-                let v11 = mesh.add_vertex(x11, y11, z1, nx1, ny1, 0.0);
-                
-                mesh.add_triangle(v00, v10, v11);
-                mesh.add_triangle(v00, v11, v01);
-            }
-        }
-        
-        // Add caps if not full revolution
-        if (angle - 360.0).abs() > 0.1 {
-            // Start cap
-            add_revolve_cap(mesh, &polygon, 0.0, -1.0);
-            // End cap
-            add_revolve_cap(mesh, &polygon, angle_rad, 1.0);
-        }
+        let mut profile = Mesh::new();
+        build_polygon_mesh(&mut profile, &polygon, None);
+        mesh.merge(&revolve_profile(&profile, angle, 0, params)?);
     }
-    
     Ok(())
 }
 
-/// Add a cap for rotate_extrude at given angle.
-fn add_revolve_cap(mesh: &mut Mesh, polygon: &[[f64; 2]], angle: f32, normal_dir: f32) {
-    if polygon.len() < 3 {
-        return;
+/// Revolve a 2D profile mesh around the Z axis.
+///
+/// The profile's X coordinate becomes the radius and its Y coordinate the
+/// height. Its boundary edges sweep out the side surface and, for partial
+/// angles, its triangles close the start and end of the sweep. Profile
+/// points on the Z axis stay single vertices, so profiles touching the
+/// axis revolve without slivers. The result is watertight once welded.
+///
+/// The sweep starts on +X and runs counter-clockwise for positive angles.
+/// Like OpenSCAD, it uses `ceil(fragments * |angle| / 360)` segments,
+/// where `fragments` is `fn_` or, if that is 0, derived from the largest
+/// radius; a profile entirely at negative X is mirrored first.
+///
+/// ## Parameters
+///
+/// - `profile`: 2D mesh in the XY plane
+/// - `angle`: Sweep in degrees, clamped to [-360, 360]
+/// - `fn_`: Fragments for a full revolution, 0 to use `params`
+/// - `params`: Segment parameters
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::GeometryError` if the profile has points on
+/// both sides of the Z axis.
+pub fn revolve_profile(profile: &Mesh, angle: f64, fn_: u32, params: &SegmentParams) -> ManifoldResult<Mesh> {
+    let mut mesh = Mesh::new();
+    let (mut points, mut triangles) = profile_triangles(profile);
+
+    let min_x = points.iter().fold(0.0f64, |m, p| m.min(p[0]));
+    let max_x = points.iter().fold(0.0f64, |m, p| m.max(p[0]));
+    if min_x < 0.0 && max_x > 0.0 {
+        return Err(ManifoldError::GeometryError(
+            "rotate_extrude: all points must have the same X coordinate sign".to_string(),
+        ));
     }
-    
-    let (cos_a, sin_a) = (angle.cos(), angle.sin());
-    let nx = -sin_a * normal_dir;
-    let ny = cos_a * normal_dir;
-    
-    // Transform polygon to 3D at this angle
-    let pts: Vec<(f32, f32, f32)> = polygon.iter()
-        .map(|p| {
-            let r = p[0] as f32;
-            let z = p[1] as f32;
-            (r * cos_a, r * sin_a, z)
+    if min_x < 0.0 {
+        // Mirroring flips the winding; swap corners to keep it counter-clockwise
+        for p in &mut points {
+            p[0] = -p[0];
+        }
+        for t in &mut triangles {
+            t.swap(1, 2);
+        }
+    }
+
+    let angle = angle.clamp(-360.0, 360.0);
+    if triangles.is_empty() || angle == 0.0 {
+        return Ok(mesh);
+    }
+
+    let full = angle.abs() >= 360.0;
+    let max_radius = points.iter().fold(0.0f64, |m, p| m.max(p[0]));
+    let fragments = if fn_ > 0 { fn_ } else { params.calculate_segments(max_radius) };
+    let segments = ((fragments as f64 * angle.abs() / 360.0).ceil() as usize).max(1);
+
+    // The last ring of a full revolution is the first one
+    let ring_count = if full { segments } else { segments + 1 };
+    let rings: Vec<(f64, f64)> = (0..ring_count)
+        .map(|i| {
+            let theta = angle.to_radians() * i as f64 / segments as f64;
+            (theta.cos(), theta.sin())
         })
         .collect();
-    
-    if pts.len() < 3 {
-        return;
+    let at = |p: [f64; 2], ring: usize| -> [f64; 3] {
+        let (cos, sin) = rings[ring % ring_count];
+        [p[0] * cos, p[0] * sin, p[1]]
+    };
+    // Clockwise sweeps mirror the surface, so every face flips
+    let flip = angle < 0.0;
+
+    // Side surface from the profile outline
+    for [a, b] in boundary_edges(&triangles) {
+        let (a, b) = (points[a as usize], points[b as usize]);
+        for i in 0..segments {
+            let (a0, a1, b0, b1) = (at(a, i), at(a, i + 1), at(b, i), at(b, i + 1));
+            add_face(&mut mesh, [a0, a1, b1], flip);
+            add_face(&mut mesh, [a0, b1, b0], flip);
+        }
     }
-    
-    let first = mesh.add_vertex(pts[0].0, pts[0].1, pts[0].2, nx, ny, 0.0);
-    
-    for i in 1..pts.len() - 1 {
-        let v1 = mesh.add_vertex(pts[i].0, pts[i].1, pts[i].2, nx, ny, 0.0);
-        let v2 = mesh.add_vertex(pts[i + 1].0, pts[i + 1].1, pts[i + 1].2, nx, ny, 0.0);
-        
-        if normal_dir > 0.0 {
-            mesh.add_triangle(first, v1, v2);
-        } else {
-            mesh.add_triangle(first, v2, v1);
+
+    // Start and end caps
+    if !full {
+        for t in &triangles {
+            let [p0, p1, p2] = t.map(|i| points[i as usize]);
+            add_face(&mut mesh, [at(p0, 0), at(p1, 0), at(p2, 0)], flip);
+            add_face(&mut mesh, [at(p0, segments), at(p1, segments), at(p2, segments)], !flip);
         }
     }
+
+    Ok(mesh)
+}
+
+/// Profile points and the triangles indexing them.
+type Profile = (Vec<[f64; 2]>, Vec<[u32; 3]>);
+
+/// Welded profile points and counter-clockwise triangles.
+fn profile_triangles(profile: &Mesh) -> Profile {
+    let (positions, remap) = profile.welded_positions();
+    let points: Vec<[f64; 2]> = positions.iter().map(|p| [p[0], p[1]]).collect();
+
+    let triangles = profile.indices.chunks_exact(3)
+        .filter_map(|t| {
+            let [i0, i1, i2] = [t[0], t[1], t[2]].map(|i| remap[i as usize]);
+            let [p0, p1, p2] = [i0, i1, i2].map(|i| points[i as usize]);
+            let area = (p1[0] - p0[0]) * (p2[1] - p0[1]) - (p2[0] - p0[0]) * (p1[1] - p0[1]);
+            if area > 0.0 {
+                Some([i0, i1, i2])
+            } else if area < 0.0 {
+                Some([i0, i2, i1])
+            } else {
+                None
+            }
+        })
+        .collect();
+    (points, triangles)
+}
+
+/// Directed edges with no opposite edge, in triangle order.
+fn boundary_edges(triangles: &[[u32; 3]]) -> Vec<[u32; 2]> {
+    let edges = |t: &[u32; 3]| [[t[0], t[1]], [t[1], t[2]], [t[2], t[0]]];
+    let all: HashSet<[u32; 2]> = triangles.iter().flat_map(edges).collect();
+    triangles.iter()
+        .flat_map(edges)
+        .filter(|[a, b]| !all.contains(&[*b, *a]))
+        .collect()
+}
+
+/// Add a flat-shaded triangle, skipping it if two corners coincide.
+fn add_face(mesh: &mut Mesh, corners: [[f64; 3]; 3], flip: bool) {
+    let [p0, p1, p2] = if flip { [corners[0], corners[2], corners[1]] } else { corners };
+    if p0 == p1 || p1 == p2 || p0 == p2 {
+        return;
+    }
+    let e1 = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+    let e2 = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+    let n = [
+        e1[1] * e2[2] - e1[2] * e2[1],
+        e1[2] * e2[0] - e1[0] * e2[2],
+        e1[0] * e2[1] - e1[1] * e2[0],
+    ];
+    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    let n = if len > 0.0 { n.map(|c| (c / len) as f32) } else { [0.0, 0.0, 1.0] };
+
+    let v0 = mesh.add_vertex(p0[0], p0[1], p0[2], n[0], n[1], n[2]);
+    let v1 = mesh.add_vertex(p1[0], p1[1], p1[2], n[0], n[1], n[2]);
+    let v2 = mesh.add_vertex(p2[0], p2[1], p2[2], n[0], n[1], n[2]);
+    mesh.add_triangle(v0, v1, v2);
 }
 
 // =============================================================================
@@ -365,8 +394,109 @@ mod tests {
     /// Test rotate_point.
     #[test]
     fn test_rotate_point() {
-        let (x, y) = rotate_point(1.0, 0.0, std::f32::consts::PI / 2.0);
+        let (x, y) = rotate_point(1.0, 0.0, std::f64::consts::PI / 2.0);
         assert!((x - 0.0).abs() < 0.01);
         assert!((y - 1.0).abs() < 0.01);
     }
+
+    /// Welded vertex count, triangle count and manifoldness of a render.
+    fn mesh_stats(source: &str) -> (usize, usize, bool) {
+        let mesh = crate::render(source).unwrap();
+        let (_, report) = crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&mesh);
+        (mesh.welded_positions().0.len(), mesh.triangle_count(), report.is_manifold())
+    }
+
+    /// Test a full revolution matches OpenSCAD's counts and is closed.
+    #[test]
+    fn test_revolve_full() {
+        let stats = mesh_stats("rotate_extrude($fn = 16) translate([10, 0]) square(2);");
+        assert_eq!(stats, (64, 128, true));
+    }
+
+    /// Test a partial revolution gets caps and ceil(fn * angle / 360) segments.
+    #[test]
+    fn test_revolve_partial() {
+        let stats = mesh_stats("rotate_extrude(angle = 90, $fn = 16) translate([10, 0]) square(2);");
+        assert_eq!(stats, (20, 36, true));
+
+        let stats = mesh_stats("rotate_extrude(angle = 100, $fn = 16) translate([10, 0]) square(2);");
+        assert_eq!(stats, (24, 44, true));
+
+        let stats = mesh_stats("rotate_extrude(angle = -90, $fn = 16) translate([10, 0]) square(2);");
+        assert_eq!(stats, (20, 36, true));
+    }
+
+    /// Test profiles touching the axis keep a single vertex there.
+    #[test]
+    fn test_revolve_touching_axis() {
+        let stats = mesh_stats("rotate_extrude($fn = 8) square(2);");
+        assert_eq!(stats, (18, 32, true));
+
+        let stats = mesh_stats("rotate_extrude(angle = 90, $fn = 8) square(2);");
+        assert_eq!(stats, (8, 12, true));
+
+        let stats = mesh_stats("rotate_extrude(angle = 180, $fn = 12) polygon([[0, -5], [5, 0], [0, 5]]);");
+        assert_eq!(stats, (9, 14, true));
+    }
+
+    /// Test without `$fn` the sweep takes its segments from `$fa` and
+    /// `$fs`, not the three of a minimal polygon.
+    #[test]
+    fn test_revolve_default_segments() {
+        let mesh = crate::render("rotate_extrude() translate([10, 0]) circle(3);").unwrap();
+        // A torus of radii 10 and 3 holds 2π² · 10 · 9 ≈ 1776.5
+        let volume = mesh.volume();
+        assert!(volume > 1600.0 && volume < 1777.0, "volume {}", volume);
+    }
+
+    /// Test negative-X profiles are mirrored and mixed ones rejected.
+    #[test]
+    fn test_revolve_x_sign() {
+        let stats = mesh_stats("rotate_extrude(angle = 90, $fn = 16) translate([-12, 0]) square(2);");
+        assert_eq!(stats, (20, 36, true));
+
+        let err = crate::render("rotate_extrude() translate([-1, 0]) square(2);").unwrap_err();
+        assert!(err.to_string().contains("same X coordinate sign"));
+    }
+
+    /// Test a plain extrusion is a closed box.
+    #[test]
+    fn test_extrude_plain() {
+        assert_eq!(mesh_stats("linear_extrude(10) square(2);"), (8, 12, true));
+        assert_eq!(mesh_stats("linear_extrude(10, slices = 3) square(2);"), (16, 28, true));
+    }
+
+    /// Test twisted slices rotate clockwise going up.
+    #[test]
+    fn test_extrude_twist() {
+        let source = "linear_extrude(10, twist = 90, slices = 4) square(2, center = true);";
+        assert_eq!(mesh_stats(source), (20, 36, true));
+
+        // The corner at (1, 1) ends up at (1, -1) on top
+        let mesh = crate::render(source).unwrap();
+        let top: Vec<_> = mesh.welded_positions().0.into_iter().filter(|p| (p[2] - 10.0).abs() < 1e-4).collect();
+        assert!(top.iter().any(|p| (p[0] - 1.0).abs() < 1e-4 && (p[1] + 1.0).abs() < 1e-4));
+    }
+
+    /// Test scaling to zero makes a closed pyramid with one apex.
+    #[test]
+    fn test_extrude_pyramid() {
+        assert_eq!(mesh_stats("linear_extrude(10, scale = 0) square(2, center = true);"), (5, 6, true));
+        assert_eq!(mesh_stats("linear_extrude(10, scale = 0, slices = 2) square(2, center = true);"), (9, 14, true));
+    }
+
+    /// Test non-uniform scale per axis.
+    #[test]
+    fn test_extrude_scale_xy() {
+        let mut profile = Mesh::new();
+        build_polygon_mesh(&mut profile, &square_points([2.0, 2.0], true), None);
+        let mesh = extrude_profile(&profile, 10.0, true, 0.0, [2.0, 0.5], 1);
+        let (positions, _) = mesh.welded_positions();
+        assert_eq!(positions.len(), 8);
+        assert!(crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&mesh).1.is_manifold());
+
+        let top: Vec<_> = positions.iter().filter(|p| (p[2] - 5.0).abs() < 1e-6).collect();
+        assert_eq!(top.len(), 4);
+        assert!(top.iter().all(|p| (p[0].abs() - 2.0).abs() < 1e-6 && (p[1].abs() - 0.5).abs() < 1e-6));
+    }
 }
@@ -7,6 +7,8 @@
 //! - `primitives`: Circle, Square, Polygon mesh builders
 //! - `extrude`: Linear and rotate extrusions
 //! - `ops`: Offset, Projection operations
+//! - `boolean`: Union, Difference, Intersection of regions
+//! - `svg`: SVG shapes as regions, for `import()`
 //!
 //! ## OpenSCAD Compatibility
 //!
@@ -18,6 +20,8 @@
 pub mod primitives;
 pub mod extrude;
 pub mod ops;
+pub mod boolean;
+pub mod svg;
 
 // =============================================================================
 // CROSSSECTION STRUCT
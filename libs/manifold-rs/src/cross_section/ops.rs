@@ -4,11 +4,17 @@
 //!
 //! ## Operations
 //!
-//! - `offset`: Expand or shrink a polygon
+//! - `offset`: Expand or shrink polygons with round, mitered or chamfered
+//!   corners
 //! - `projection`: Project 3D geometry to 2D
 
+use std::f64::consts::TAU;
+
 use openscad_eval::GeometryNode;
+use crate::cross_section::boolean::{boolean_contours, BooleanOp2D};
 use crate::error::ManifoldResult;
+use crate::font::outline::group_contours;
+use crate::font::Polygon2D;
 use crate::mesh::Mesh;
 use crate::openscad::SegmentParams;
 
@@ -16,79 +22,337 @@ use crate::openscad::SegmentParams;
 // OFFSET
 // =============================================================================
 
-/// Offset 2D children by delta amount.
-///
-/// ## OpenSCAD Equivalent
+/// How `offset()` fills the gap at a corner the offset moves away from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetJoin {
+    /// Circular arc around the corner, as `offset(r)`; `segments` is the
+    /// segment count of a full circle.
+    Round {
+        /// Segments per full turn.
+        segments: u32,
+    },
+    /// Offset edges extended until they meet, as `offset(delta)`.
+    Miter,
+    /// Corner cut flat at the offset distance, as
+    /// `offset(delta, chamfer = true)`.
+    Chamfer,
+}
+
+impl OffsetJoin {
+    /// Join for an `offset()` node: round for `r`, else chamfered or
+    /// mitered `delta`.
+    #[must_use]
+    pub fn from_params(round: bool, chamfer: bool, segments: u32) -> Self {
+        match (round, chamfer) {
+            (true, _) => Self::Round { segments },
+            (false, true) => Self::Chamfer,
+            (false, false) => Self::Miter,
+        }
+    }
+}
+
+/// Offset filled regions by `delta` (positive = expand, negative = shrink).
 ///
-/// ```text
-/// offset(delta, chamfer) { children }
-/// ```
+/// Every contour is offset to the side away from the material, so outer
+/// loops grow and holes shrink for a positive `delta`. Corners the offset
+/// moves away from are filled by `join`; where the offset folds back on
+/// itself (inside corners, features narrower than the offset, holes or
+/// outlines that vanish) the folds are cut away by the positive winding
+/// rule, and a gap the offset closes off becomes a hole. Results of
+/// different contours that meet are merged.
 ///
 /// ## Parameters
 ///
-/// - `mesh`: Output mesh
-/// - `children`: 2D child geometry nodes
-/// - `delta`: Offset amount (positive = expand, negative = shrink)
-/// - `chamfer`: If true, use chamfered corners instead of rounded
-/// - `params`: Segment parameters
-pub fn offset(
-    mesh: &mut Mesh,
-    children: &[GeometryNode],
+/// - `polygons`: Regions with CCW outlines and CW holes
+/// - `delta`: Offset distance
+/// - `join`: Corner treatment
+///
+/// ## Returns
+///
+/// The offset regions, CCW outlines with CW holes.
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::cross_section::ops::{offset_polygons, OffsetJoin};
+/// use manifold_rs::font::Polygon2D;
+///
+/// let square = Polygon2D { outer: vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]], holes: vec![] };
+/// let grown = offset_polygons(&[square], 1.0, OffsetJoin::Miter);
+/// assert_eq!(grown[0].outer, vec![[-1.0, -1.0], [3.0, -1.0], [3.0, 3.0], [-1.0, 3.0]]);
+/// ```
+pub fn offset_polygons(polygons: &[Polygon2D], delta: f64, join: OffsetJoin) -> Vec<Polygon2D> {
+    if delta == 0.0 {
+        return polygons.to_vec();
+    }
+    let contours = polygons.iter()
+        .flat_map(|polygon| std::iter::once(&polygon.outer).chain(&polygon.holes))
+        .flat_map(|contour| offset_contour(contour, delta, join))
+        .collect();
+    group_contours(boolean_contours(&[contours], BooleanOp2D::Union))
+}
+
+/// Offset one closed contour to its right (away from the material of a
+/// CCW outline or CW hole), returning the loops left after cleanup.
+///
+/// A hole is offset as the outline of the void it bounds, shrunk by
+/// `delta`, so the same cleanup applies; islands left where the void
+/// closes up come back as outlines.
+fn offset_contour(contour: &[[f64; 2]], delta: f64, join: OffsetJoin) -> Vec<Vec<[f64; 2]>> {
+    let mut points = dedup_loop(contour.to_vec());
+    let area = signed_area(&points);
+    if points.len() < 3 || area.abs() < f64::EPSILON {
+        return Vec::new();
+    }
+    if area < 0.0 {
+        points.reverse();
+        let mut loops = offset_outline(&points, -delta, join);
+        loops.iter_mut().for_each(|l| l.reverse());
+        return loops;
+    }
+    offset_outline(&points, delta, join)
+}
+
+/// Offset a CCW loop to its right, outward for a positive `delta`.
+fn offset_outline(points: &[[f64; 2]], delta: f64, join: OffsetJoin) -> Vec<Vec<[f64; 2]>> {
+    let n = points.len();
+
+    // Unit direction and right-hand normal of the edge leaving each point
+    let directions: Vec<[f64; 2]> = (0..n)
+        .map(|i| normalize_2d(sub(points[(i + 1) % n], points[i])))
+        .collect();
+    let normal = |d: [f64; 2]| [d[1], -d[0]];
+
+    let mut raw = Vec::with_capacity(n * 2);
+    for i in 0..n {
+        let p = points[i];
+        let (e1, e2) = (directions[(i + n - 1) % n], directions[i]);
+        let (n1, n2) = (normal(e1), normal(e2));
+        let sin = e1[0] * e2[1] - e1[1] * e2[0];
+        let cos = dot(e1, e2);
+        let at = |v: [f64; 2], k: f64| [p[0] + v[0] * k, p[1] + v[1] * k];
+
+        if sin.abs() < 1e-12 && cos > 0.0 {
+            // Straight through
+            raw.push(at(n1, delta));
+        } else if sin * delta > 0.0 || (sin.abs() < 1e-12 && cos < 0.0) {
+            // The offset edges part: fill the gap
+            join_corner(&mut raw, p, n1, n2, e1, e2, delta, join);
+        } else {
+            // The offset edges overlap: route through the corner and let
+            // the cleanup cut away the fold
+            raw.push(at(n1, delta));
+            raw.push(p);
+            raw.push(at(n2, delta));
+        }
+    }
+
+    clean_offset_loop(dedup_loop(raw))
+}
+
+/// Append the points filling the gap at corner `p` between the offset
+/// edges with normals `n1` and `n2`.
+#[allow(clippy::too_many_arguments)]
+fn join_corner(
+    out: &mut Vec<[f64; 2]>,
+    p: [f64; 2],
+    n1: [f64; 2],
+    n2: [f64; 2],
+    e1: [f64; 2],
+    e2: [f64; 2],
     delta: f64,
-    chamfer: bool,
-    params: &SegmentParams,
-) -> ManifoldResult<()> {
-    for child in children {
-        let polygon = extract_2d_points(child, params)?;
-        if polygon.len() < 3 {
-            continue;
+    join: OffsetJoin,
+) {
+    let at = |v: [f64; 2], k: f64| [p[0] + v[0] * k, p[1] + v[1] * k];
+    // Signed turn from n1 to n2, in (-PI, PI]
+    let angle = (n1[0] * n2[1] - n1[1] * n2[0]).atan2(dot(n1, n2));
+    let cos = dot(n1, n2);
+
+    match join {
+        OffsetJoin::Round { segments } => {
+            let steps = ((f64::from(segments.max(3)) * angle.abs() / TAU).ceil() as usize).max(1);
+            for k in 0..=steps {
+                let (s, c) = (angle * k as f64 / steps as f64).sin_cos();
+                out.push(at([n1[0] * c - n1[1] * s, n1[0] * s + n1[1] * c], delta));
+            }
+        }
+        // The miter point runs off to infinity as the corner folds back;
+        // square it off instead
+        OffsetJoin::Miter if 1.0 + cos > 1e-6 => {
+            out.push(at([n1[0] + n2[0], n1[1] + n2[1]], delta / (1.0 + cos)));
+        }
+        OffsetJoin::Miter | OffsetJoin::Chamfer => {
+            // Flat at distance |delta| from the corner, across the bisector
+            let t = (angle.abs() / 4.0).tan() * delta.abs();
+            let q1 = at(n1, delta);
+            let q2 = at(n2, delta);
+            out.push([q1[0] + e1[0] * t, q1[1] + e1[1] * t]);
+            out.push([q2[0] - e2[0] * t, q2[1] - e2[1] * t]);
         }
-        
-        // Offset the polygon
-        let offset_polygon = offset_polygon_by_delta(&polygon, delta, chamfer);
-        
-        // Build mesh from offset polygon
-        build_polygon_mesh(mesh, &offset_polygon);
     }
-    
-    Ok(())
 }
 
-/// Offset a polygon by delta.
-fn offset_polygon_by_delta(polygon: &[[f64; 2]], delta: f64, _chamfer: bool) -> Vec<[f64; 2]> {
-    if polygon.len() < 3 || delta.abs() < 1e-10 {
-        return polygon.to_vec();
+/// Split a raw offset loop at its self-crossings and keep the pieces that
+/// bound the area of positive winding.
+///
+/// A CCW piece is kept where the winding just inside it is 1 (it is an
+/// outline); a CW piece where the winding inside is 0 (it is a hole).
+/// Pieces inside folds have other windings and are dropped.
+fn clean_offset_loop(raw: Vec<[f64; 2]>) -> Vec<Vec<[f64; 2]>> {
+    let pieces = split_at_crossings(raw.clone());
+    pieces.into_iter()
+        .filter(|piece| {
+            let area = signed_area(piece);
+            if piece.len() < 3 || area.abs() < 1e-12 {
+                return false;
+            }
+            let inside = point_inside_edge(piece, area > 0.0);
+            let winding = winding_number(&raw, inside);
+            if area > 0.0 { winding == 1 } else { winding == 0 }
+        })
+        .collect()
+}
+
+/// Split a closed polyline into loops that neither cross nor touch
+/// themselves.
+///
+/// Both halves of a split have fewer points than the loop split, so this
+/// terminates.
+fn split_at_crossings(points: Vec<[f64; 2]>) -> Vec<Vec<[f64; 2]>> {
+    let mut pending = vec![points];
+    let mut done = Vec::new();
+    while let Some(points) = pending.pop() {
+        match first_crossing(&points) {
+            Some((i, j, x)) => {
+                // x, points[i + 1..=j] and points[j + 1..], points[..=i], x
+                let mut inner = vec![x];
+                inner.extend_from_slice(&points[i + 1..=j]);
+                let mut outer = points[j + 1..].to_vec();
+                outer.extend_from_slice(&points[..=i]);
+                outer.push(x);
+                pending.push(dedup_loop(inner));
+                pending.push(dedup_loop(outer));
+            }
+            None => done.push(points),
+        }
+    }
+    done
+}
+
+/// Remove repeated points, including a last point equal to the first.
+pub(super) fn dedup_loop(mut points: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    points.dedup();
+    while points.len() > 1 && points.first() == points.last() {
+        points.pop();
     }
-    
-    let n = polygon.len();
-    let mut result = Vec::with_capacity(n);
-    
+    points
+}
+
+/// First pair of non-adjacent edges `i < j` that cross or touch, with the
+/// meeting point. Edge `k` runs from point `k` to point `k + 1`.
+fn first_crossing(points: &[[f64; 2]]) -> Option<(usize, usize, [f64; 2])> {
+    let n = points.len();
+    if n < 4 {
+        return None;
+    }
+    let edge = |k: usize| (points[k], points[(k + 1) % n]);
+    for i in 0..n - 2 {
+        let (a, b) = edge(i);
+        let (min_a, max_a) = (min_2d(a, b), max_2d(a, b));
+        for j in i + 2..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            let (c, d) = edge(j);
+            if max_2d(c, d)[0] < min_a[0] || min_2d(c, d)[0] > max_a[0]
+                || max_2d(c, d)[1] < min_a[1] || min_2d(c, d)[1] > max_a[1] {
+                continue;
+            }
+            if let Some(x) = crossing(a, b, c, d) {
+                return Some((i, j, x));
+            }
+        }
+    }
+    None
+}
+
+/// Point where segments `ab` and `cd` cross or touch, snapped to an
+/// endpoint it is within rounding of. Parallel segments never meet.
+pub(super) fn crossing(a: [f64; 2], b: [f64; 2], c: [f64; 2], d: [f64; 2]) -> Option<[f64; 2]> {
+    const EPS: f64 = 1e-9;
+    let r = sub(b, a);
+    let s = sub(d, c);
+    let denom = r[0] * s[1] - r[1] * s[0];
+    if denom.abs() < 1e-15 {
+        return None;
+    }
+    let ac = sub(c, a);
+    let t = (ac[0] * s[1] - ac[1] * s[0]) / denom;
+    let u = (ac[0] * r[1] - ac[1] * r[0]) / denom;
+    let within = |v: f64| (-EPS..=1.0 + EPS).contains(&v);
+    if !within(t) || !within(u) {
+        return None;
+    }
+    Some(if t < EPS {
+        a
+    } else if t > 1.0 - EPS {
+        b
+    } else if u < EPS {
+        c
+    } else if u > 1.0 - EPS {
+        d
+    } else {
+        [a[0] + r[0] * t, a[1] + r[1] * t]
+    })
+}
+
+/// A point just inside a simple loop, next to the middle of its longest
+/// edge.
+///
+/// Other pieces may lie inside the loop, so a point deep inside it could
+/// fall in one of them; next to the edge, the raw loop winds around the
+/// point as it does around the piece.
+fn point_inside_edge(points: &[[f64; 2]], ccw: bool) -> [f64; 2] {
+    let n = points.len();
+    let length = |i: &usize| {
+        let e = sub(points[(i + 1) % n], points[*i]);
+        dot(e, e)
+    };
+    let i = (0..n).max_by(|a, b| length(a).total_cmp(&length(b))).unwrap_or(0);
+    let (a, b) = (points[i], points[(i + 1) % n]);
+    let e = sub(b, a);
+    // Left of the edge for a CCW loop, right for a CW one
+    let side = if ccw { 1e-6 } else { -1e-6 };
+    [(a[0] + b[0]) / 2.0 - e[1] * side, (a[1] + b[1]) / 2.0 + e[0] * side]
+}
+
+/// Winding number of a closed polyline around `p`.
+pub(super) fn winding_number(points: &[[f64; 2]], p: [f64; 2]) -> i32 {
+    let n = points.len();
+    let mut winding = 0;
     for i in 0..n {
-        let prev = (i + n - 1) % n;
-        let next = (i + 1) % n;
-        
-        let p0 = polygon[prev];
-        let p1 = polygon[i];
-        let p2 = polygon[next];
-        
-        // Compute edge normals
-        let e1 = normalize_2d([p1[0] - p0[0], p1[1] - p0[1]]);
-        let e2 = normalize_2d([p2[0] - p1[0], p2[1] - p1[1]]);
-        
-        let n1 = [-e1[1], e1[0]]; // Perpendicular
-        let n2 = [-e2[1], e2[0]];
-        
-        // Average normal at vertex
-        let avg = normalize_2d([n1[0] + n2[0], n1[1] + n2[1]]);
-        
-        // Offset point
-        result.push([
-            p1[0] + avg[0] * delta,
-            p1[1] + avg[1] * delta,
-        ]);
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let side = (b[0] - a[0]) * (p[1] - a[1]) - (p[0] - a[0]) * (b[1] - a[1]);
+        if a[1] <= p[1] {
+            if b[1] > p[1] && side > 0.0 {
+                winding += 1;
+            }
+        } else if b[1] <= p[1] && side < 0.0 {
+            winding -= 1;
+        }
     }
-    
-    result
+    winding
+}
+
+/// Twice the signed area of a loop; positive when CCW.
+pub(super) fn signed_area(points: &[[f64; 2]]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (p, q) = (points[i], points[(i + 1) % n]);
+            p[0] * q[1] - q[0] * p[1]
+        })
+        .sum()
 }
 
 /// Normalize a 2D vector.
@@ -101,37 +365,24 @@ fn normalize_2d(v: [f64; 2]) -> [f64; 2] {
     }
 }
 
-/// Build mesh from 2D polygon.
-fn build_polygon_mesh(mesh: &mut Mesh, polygon: &[[f64; 2]]) {
-    if polygon.len() < 3 {
-        return;
-    }
-    
-    let z = 0.0;
-    
-    // Fan triangulation
-    let first = mesh.add_vertex(
-        polygon[0][0] as f32,
-        polygon[0][1] as f32,
-        z,
-        0.0, 0.0, 1.0,
-    );
-    
-    for i in 1..polygon.len() - 1 {
-        let v1 = mesh.add_vertex(
-            polygon[i][0] as f32,
-            polygon[i][1] as f32,
-            z,
-            0.0, 0.0, 1.0,
-        );
-        let v2 = mesh.add_vertex(
-            polygon[i + 1][0] as f32,
-            polygon[i + 1][1] as f32,
-            z,
-            0.0, 0.0, 1.0,
-        );
-        mesh.add_triangle(first, v1, v2);
-    }
+/// `a - b`.
+pub(super) fn sub(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+/// Dot product.
+pub(super) fn dot(a: [f64; 2], b: [f64; 2]) -> f64 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+/// Componentwise minimum.
+fn min_2d(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0].min(b[0]), a[1].min(b[1])]
+}
+
+/// Componentwise maximum.
+fn max_2d(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0].max(b[0]), a[1].max(b[1])]
 }
 
 // =============================================================================
@@ -156,7 +407,7 @@ pub fn projection(
     mesh: &mut Mesh,
     children: &[GeometryNode],
     cut: bool,
-    params: &SegmentParams,
+    _params: &SegmentParams,
 ) -> ManifoldResult<()> {
     // First, build 3D mesh from children
     let mut child_mesh = Mesh::new();
@@ -174,7 +425,7 @@ pub fn projection(
     let polygon = project_mesh_to_2d(&child_mesh, cut);
     
     // Build 2D mesh from projection
-    build_polygon_mesh(mesh, &polygon);
+    super::primitives::build_polygon_mesh(mesh, &polygon, None);
     
     Ok(())
 }
@@ -184,9 +435,9 @@ fn project_mesh_to_2d(mesh: &Mesh, cut: bool) -> Vec<[f64; 2]> {
     let mut points: Vec<[f64; 2]> = Vec::new();
     
     for i in (0..mesh.vertices.len()).step_by(3) {
-        let x = mesh.vertices[i] as f64;
-        let y = mesh.vertices[i + 1] as f64;
-        let z = mesh.vertices[i + 2] as f64;
+        let x = mesh.vertices[i];
+        let y = mesh.vertices[i + 1];
+        let z = mesh.vertices[i + 2];
         
         if cut {
             // Only include points near z=0
@@ -222,75 +473,104 @@ fn dedup_points_2d(points: &[[f64; 2]]) -> Vec<[f64; 2]> {
 }
 
 // =============================================================================
-// HELPER FUNCTIONS
+// TESTS
 // =============================================================================
 
-/// Extract 2D points from a geometry node.
-fn extract_2d_points(node: &GeometryNode, params: &SegmentParams) -> ManifoldResult<Vec<[f64; 2]>> {
-    match node {
-        GeometryNode::Circle { radius, fn_ } => {
-            let segments = if *fn_ > 0 { *fn_ } else { params.calculate_segments(*radius) };
-            Ok(circle_points(*radius, segments))
-        }
-        
-        GeometryNode::Square { size, center } => {
-            Ok(square_points(*size, *center))
-        }
-        
-        GeometryNode::Polygon { points, .. } => {
-            Ok(points.clone())
-        }
-        
-        _ => Ok(Vec::new()),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Square from (0, 0) to (size, size).
+    fn square(size: f64) -> Vec<[f64; 2]> {
+        vec![[0.0, 0.0], [size, 0.0], [size, size], [0.0, size]]
     }
-}
 
-/// Generate circle points.
-fn circle_points(radius: f64, segments: u32) -> Vec<[f64; 2]> {
-    let n = segments.max(3) as usize;
-    let mut points = Vec::with_capacity(n);
-    
-    for i in 0..n {
-        let theta = 2.0 * std::f64::consts::PI * i as f64 / n as f64;
-        points.push([
-            radius * theta.cos(),
-            radius * theta.sin(),
-        ]);
+    /// Offset a single contour, returning the regions.
+    fn offset(contour: Vec<[f64; 2]>, delta: f64, join: OffsetJoin) -> Vec<Polygon2D> {
+        offset_polygons(&group_contours(vec![contour]), delta, join)
     }
-    
-    points
-}
 
-/// Generate square points.
-fn square_points(size: [f64; 2], center: bool) -> Vec<[f64; 2]> {
-    let [w, h] = size;
-    let (x0, x1) = if center { (-w / 2.0, w / 2.0) } else { (0.0, w) };
-    let (y0, y1) = if center { (-h / 2.0, h / 2.0) } else { (0.0, h) };
-    
-    vec![
-        [x0, y0],
-        [x1, y0],
-        [x1, y1],
-        [x0, y1],
-    ]
-}
+    /// Area of a loop.
+    fn area(points: &[[f64; 2]]) -> f64 {
+        signed_area(points).abs() / 2.0
+    }
 
-// =============================================================================
-// TESTS
-// =============================================================================
+    /// Test the three corner joins when growing a square.
+    #[test]
+    fn test_offset_joins() {
+        let mitered = offset(square(2.0), 1.0, OffsetJoin::Miter);
+        assert!((area(&mitered[0].outer) - 16.0).abs() < 1e-9);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Flat at distance 1 from each corner
+        let chamfered = offset(square(2.0), 1.0, OffsetJoin::Chamfer);
+        assert_eq!(chamfered[0].outer.len(), 8);
+        let corner = (2.0f64.sqrt() - 1.0).powi(2);
+        assert!((area(&chamfered[0].outer) - (16.0 - 4.0 * corner)).abs() < 1e-9);
+
+        // Quarter arcs: 16 segments per turn gives 4 per corner
+        let rounded = offset(square(2.0), 1.0, OffsetJoin::Round { segments: 16 });
+        assert_eq!(rounded[0].outer.len(), 20);
+        let arcs = 16.0 / 2.0 * (std::f64::consts::TAU / 16.0).sin();
+        assert!((area(&rounded[0].outer) - (4.0 + 8.0 + arcs)).abs() < 1e-9);
+        // Every arc point is at distance 1 from the square
+        assert!(rounded[0].outer.iter().all(|p| {
+            let dx = (p[0] - p[0].clamp(0.0, 2.0)).abs();
+            let dy = (p[1] - p[1].clamp(0.0, 2.0)).abs();
+            ((dx * dx + dy * dy).sqrt() - 1.0).abs() < 1e-9
+        }));
+    }
 
-    /// Test polygon offset.
+    /// Test shrinking cuts the corner folds away and can remove a
+    /// contour entirely.
     #[test]
-    fn test_offset_polygon() {
-        let polygon = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
-        let offset = offset_polygon_by_delta(&polygon, 1.0, false);
-        
-        // Offset polygon should be larger
-        assert_eq!(offset.len(), 4);
+    fn test_offset_shrink() {
+        for join in [OffsetJoin::Miter, OffsetJoin::Round { segments: 32 }] {
+            let shrunk = offset(square(2.0), -0.5, join);
+            assert_eq!(shrunk.len(), 1);
+            assert!((area(&shrunk[0].outer) - 1.0).abs() < 1e-9);
+            assert!(offset(square(2.0), -1.5, join).is_empty());
+        }
+    }
+
+    /// Test holes shrink with the outline growing, and vanish when the
+    /// offset closes them.
+    #[test]
+    fn test_offset_holes() {
+        let hole = vec![[3.0, 3.0], [3.0, 7.0], [7.0, 7.0], [7.0, 3.0]];
+        let regions = group_contours(vec![square(10.0), hole]);
+
+        let grown = offset_polygons(&regions, 1.0, OffsetJoin::Round { segments: 32 });
+        assert_eq!(grown.len(), 1);
+        assert_eq!(grown[0].holes.len(), 1);
+        assert!((area(&grown[0].holes[0]) - 4.0).abs() < 1e-9);
+        assert!(signed_area(&grown[0].holes[0]) < 0.0);
+
+        let closed = offset_polygons(&regions, 2.5, OffsetJoin::Round { segments: 32 });
+        assert!(closed[0].holes.is_empty());
+    }
+
+    /// Test offsets of separate regions merge where they meet.
+    #[test]
+    fn test_offset_merges_regions() {
+        let shifted: Vec<[f64; 2]> = square(2.0).iter().map(|p| [p[0] + 3.0, p[1]]).collect();
+        let regions = group_contours(vec![square(2.0), shifted]);
+        let grown = offset_polygons(&regions, 1.0, OffsetJoin::Miter);
+        assert_eq!(grown.len(), 1);
+        assert!((area(&grown[0].outer) - 7.0 * 4.0).abs() < 1e-9);
+    }
+
+    /// Test closing the mouth of a C shape turns its inside into a hole.
+    #[test]
+    fn test_offset_closes_gap() {
+        let c_shape = vec![
+            [0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [5.5, 10.0], [5.5, 8.0], [8.0, 8.0],
+            [8.0, 2.0], [2.0, 2.0], [2.0, 8.0], [4.5, 8.0], [4.5, 10.0], [0.0, 10.0],
+        ];
+        let grown = offset(c_shape, 0.75, OffsetJoin::Miter);
+        assert_eq!(grown.len(), 1);
+        assert!((area(&grown[0].outer) - 11.5 * 11.5).abs() < 1e-9);
+        assert_eq!(grown[0].holes.len(), 1);
+        assert!((area(&grown[0].holes[0]) - 4.5 * 4.5).abs() < 1e-9);
     }
 
     /// Test normalize_2d.
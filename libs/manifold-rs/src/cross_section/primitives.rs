@@ -7,8 +7,11 @@
 //! These create thin slabs (z=0 to z=0.01) for 2D shape visualization.
 //! For actual 3D geometry, use extrusions.
 
+use crate::error::ManifoldResult;
+use crate::font::{text_polygons, Polygon2D, TextParams};
+use crate::mesh::triangulate::triangulate_with_holes;
 use crate::mesh::Mesh;
-use std::f32::consts::PI;
+use std::f64::consts::PI;
 
 // =============================================================================
 // CIRCLE
@@ -28,7 +31,7 @@ use std::f32::consts::PI;
 /// - `radius`: Circle radius
 /// - `segments`: Number of segments
 pub fn build_circle_mesh(mesh: &mut Mesh, radius: f64, segments: u32) {
-    let r = radius as f32;
+    let r = radius;
     let n = segments.max(3) as usize;
     let z = 0.0; // Thin slab at z=0
     
@@ -36,7 +39,7 @@ pub fn build_circle_mesh(mesh: &mut Mesh, radius: f64, segments: u32) {
     let mut ring: Vec<u32> = Vec::with_capacity(n);
     
     for i in 0..n {
-        let theta = 2.0 * PI * i as f32 / n as f32;
+        let theta = 2.0 * PI * i as f64 / n as f64;
         let x = r * theta.cos();
         let y = r * theta.sin();
         let v = mesh.add_vertex(x, y, z, 0.0, 0.0, 1.0);
@@ -67,7 +70,7 @@ pub fn build_circle_mesh(mesh: &mut Mesh, radius: f64, segments: u32) {
 /// - `size`: [width, height]
 /// - `center`: If true, center at origin
 pub fn build_square_mesh(mesh: &mut Mesh, size: [f64; 2], center: bool) {
-    let [w, h] = [size[0] as f32, size[1] as f32];
+    let [w, h] = size;
     let z = 0.0;
     
     let (x0, x1) = if center { (-w / 2.0, w / 2.0) } else { (0.0, w) };
@@ -111,15 +114,11 @@ pub fn build_polygon_mesh(mesh: &mut Mesh, points: &[[f64; 2]], paths: Option<&[
     
     if paths.is_empty() {
         // Simple polygon - fan triangulation
-        let pts: Vec<[f32; 2]> = points.iter()
-            .map(|p| [p[0] as f32, p[1] as f32])
-            .collect();
+        let first = mesh.add_vertex(points[0][0], points[0][1], z, 0.0, 0.0, 1.0);
         
-        let first = mesh.add_vertex(pts[0][0], pts[0][1], z, 0.0, 0.0, 1.0);
-        
-        for i in 1..pts.len() - 1 {
-            let v1 = mesh.add_vertex(pts[i][0], pts[i][1], z, 0.0, 0.0, 1.0);
-            let v2 = mesh.add_vertex(pts[i + 1][0], pts[i + 1][1], z, 0.0, 0.0, 1.0);
+        for i in 1..points.len() - 1 {
+            let v1 = mesh.add_vertex(points[i][0], points[i][1], z, 0.0, 0.0, 1.0);
+            let v2 = mesh.add_vertex(points[i + 1][0], points[i + 1][1], z, 0.0, 0.0, 1.0);
             mesh.add_triangle(first, v1, v2);
         }
     } else {
@@ -129,8 +128,8 @@ pub fn build_polygon_mesh(mesh: &mut Mesh, points: &[[f64; 2]], paths: Option<&[
                 continue;
             }
             
-            let pts: Vec<[f32; 2]> = path.iter()
-                .filter_map(|&i| points.get(i).map(|p| [p[0] as f32, p[1] as f32]))
+            let pts: Vec<[f64; 2]> = path.iter()
+                .filter_map(|&i| points.get(i).copied())
                 .collect();
             
             if pts.len() < 3 {
@@ -148,6 +147,53 @@ pub fn build_polygon_mesh(mesh: &mut Mesh, points: &[[f64; 2]], paths: Option<&[
     }
 }
 
+// =============================================================================
+// TEXT
+// =============================================================================
+
+/// Build text mesh from font glyph outlines.
+///
+/// ## OpenSCAD Equivalent
+///
+/// ```text
+/// text("Hello", size=10, halign="center");
+/// ```
+///
+/// ## Parameters
+///
+/// - `mesh`: Output mesh
+/// - `text`: Text to render
+/// - `params`: Font and layout parameters
+pub fn build_text_mesh(mesh: &mut Mesh, text: &str, params: &TextParams<'_>) -> ManifoldResult<()> {
+    build_regions_mesh(mesh, &text_polygons(text, params)?);
+    Ok(())
+}
+
+// =============================================================================
+// REGIONS
+// =============================================================================
+
+/// Build a flat mesh from regions with holes.
+///
+/// ## Parameters
+///
+/// - `mesh`: Output mesh
+/// - `polygons`: Regions, each an outline with the holes inside it
+pub fn build_regions_mesh(mesh: &mut Mesh, polygons: &[Polygon2D]) {
+    let z = 0.0;
+
+    for polygon in polygons {
+        let (points, tris) = triangulate_with_holes(&polygon.outer, &polygon.holes);
+        let base = mesh.vertex_count() as u32;
+        for p in &points {
+            mesh.add_vertex(p[0], p[1], z, 0.0, 0.0, 1.0);
+        }
+        for [a, b, c] in tris {
+            mesh.add_triangle(base + a as u32, base + b as u32, base + c as u32);
+        }
+    }
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -174,6 +220,14 @@ mod tests {
         assert_eq!(mesh.triangle_count(), 2);
     }
 
+    /// Test text mesh covers glyph outlines.
+    #[test]
+    fn test_build_text() {
+        let mut mesh = Mesh::new();
+        build_text_mesh(&mut mesh, "Ab", &TextParams::default()).unwrap();
+        assert!(mesh.triangle_count() > 10);
+    }
+
     /// Test polygon mesh.
     #[test]
     fn test_build_polygon() {
@@ -0,0 +1,818 @@
+//! # SVG Import
+//!
+//! Reads the shapes of an SVG file as filled 2D regions for `import()`, so
+//! logos and drawings can be extruded.
+//!
+//! ## Supported Content
+//!
+//! - `<path>` with every command, including arcs; curves are flattened
+//!   with the segment count `$fn`/`$fa`/`$fs` give a circle of similar
+//!   size, a quarter of it per curve
+//! - `<rect>` (with rounded corners), `<circle>`, `<ellipse>`,
+//!   `<polygon>` and `<polyline>`
+//! - `transform` on shapes and nested `<g>` groups
+//! - `width`, `height` and `viewBox` of the root `<svg>`
+//!
+//! Contents of `<defs>`, `<clipPath>`, `<mask>`, `<symbol>`, `<pattern>`
+//! and `<marker>` are skipped. Styles are ignored: every shape counts as
+//! filled, open subpaths close back to their start, and lines have no
+//! area.
+//!
+//! ## Coordinates
+//!
+//! As in OpenSCAD, user units are pixels at 96 dpi, converted to
+//! millimeters, and Y is flipped so the bottom-left corner of the page
+//! lands on the origin.
+//!
+//! ## Fill
+//!
+//! The subpaths of one shape nest even-odd, so a ring drawn as two
+//! circles gets its hole. Separate shapes are unioned.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::cross_section::svg::svg_polygons;
+//! use manifold_rs::openscad::SegmentParams;
+//!
+//! let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="20mm" height="10mm" viewBox="0 0 20 10">
+//!     <path d="M0 0 H10 V10 H0 Z M2 2 V8 H8 V2 Z"/>
+//! </svg>"#;
+//! let regions = svg_polygons(svg, &SegmentParams::default()).unwrap();
+//! assert_eq!(regions.len(), 1);
+//! assert_eq!(regions[0].holes.len(), 1);
+//! ```
+
+use std::f64::consts::PI;
+
+use super::boolean::{boolean_polygons, BooleanOp2D};
+use crate::error::{ManifoldError, ManifoldResult};
+use crate::font::outline::group_contours;
+use crate::font::Polygon2D;
+use crate::openscad::SegmentParams;
+
+/// Millimeters per SVG user unit (CSS pixel at 96 dpi).
+const MM_PER_PX: f64 = 25.4 / 96.0;
+
+/// Elements whose contents are not drawn.
+const HIDDEN: &[&str] = &["defs", "clipPath", "mask", "symbol", "pattern", "marker"];
+
+/// Affine transform `[a, b, c, d, e, f]`: `x' = a x + c y + e`,
+/// `y' = b x + d y + f`, as in SVG.
+type Matrix = [f64; 6];
+
+/// The identity transform.
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Read the shapes of an SVG file as regions in millimeters.
+///
+/// ## Parameters
+///
+/// - `data`: SVG file contents
+/// - `params`: Segment parameters for curves, arcs and circles
+///
+/// ## Returns
+///
+/// CCW outlines with CW holes; empty if the file has no shapes.
+///
+/// ## Errors
+///
+/// `ManifoldError::ImportError` if the data is not text, has no `<svg>`
+/// element, or a shape's geometry does not parse.
+pub fn svg_polygons(data: &[u8], params: &SegmentParams) -> ManifoldResult<Vec<Polygon2D>> {
+    let text = std::str::from_utf8(data)
+        .map_err(|_| ManifoldError::ImportError("SVG is not text".to_string()))?;
+
+    // Transform and hidden flag of each open element
+    let mut stack: Vec<(Matrix, bool)> = Vec::new();
+    let mut seen_root = false;
+    let mut operands = Vec::new();
+    for tag in Tags::new(text) {
+        let tag = tag?;
+        if tag.closing {
+            stack.pop();
+            continue;
+        }
+        let (parent, hidden) = stack.last().copied().unwrap_or((IDENTITY, false));
+        let hidden = hidden || HIDDEN.contains(&tag.name);
+        let mut matrix = parent;
+        if tag.name == "svg" && !seen_root {
+            seen_root = true;
+            matrix = viewport(&tag);
+        }
+        if let Some(transform) = tag.attribute("transform") {
+            matrix = multiply(&matrix, &parse_transform(transform));
+        }
+
+        if !hidden {
+            let contours = shape_contours(&tag, params)
+                .map_err(|message| ManifoldError::ImportError(format!("<{}>: {}", tag.name, message)))?;
+            let contours: Vec<Vec<[f64; 2]>> = contours.into_iter()
+                .map(|contour| contour.into_iter().map(|p| apply(&matrix, p)).collect())
+                .collect();
+            let regions = group_contours(contours);
+            if !regions.is_empty() {
+                operands.push(regions);
+            }
+        }
+        if !tag.self_closing {
+            stack.push((matrix, hidden));
+        }
+    }
+
+    if !seen_root {
+        return Err(ManifoldError::ImportError("no <svg> element".to_string()));
+    }
+    Ok(match operands.len() {
+        0 => Vec::new(),
+        1 => operands.remove(0),
+        _ => boolean_polygons(&operands, BooleanOp2D::Union),
+    })
+}
+
+// =============================================================================
+// DOCUMENT
+// =============================================================================
+
+/// Map from the root element's user units to millimeters with Y up.
+fn viewport(svg: &Tag<'_>) -> Matrix {
+    let width = svg.attribute("width").and_then(length_px);
+    let height = svg.attribute("height").and_then(length_px);
+    let view_box = svg.attribute("viewBox").and_then(|v| {
+        let values = numbers(v)?;
+        match values[..] {
+            [x, y, w, h] if w > 0.0 && h > 0.0 => Some([x, y, w, h]),
+            _ => None,
+        }
+    });
+    let (sx, sy, x0, y0, page_height) = match view_box {
+        Some([x, y, w, h]) => {
+            let (width, height) = (width.unwrap_or(w), height.unwrap_or(h));
+            (width / w, height / h, x, y, height)
+        }
+        None => (1.0, 1.0, 0.0, 0.0, height.unwrap_or(0.0)),
+    };
+    let (sx, sy, page_height) = (sx * MM_PER_PX, sy * MM_PER_PX, page_height * MM_PER_PX);
+    [sx, 0.0, 0.0, -sy, -sx * x0, page_height + sy * y0]
+}
+
+/// A length attribute in user units (pixels); `None` for percentages and
+/// unknown units.
+fn length_px(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let split = value.find(|c: char| c.is_ascii_alphabetic() || c == '%').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+    let scale = match unit {
+        "" | "px" => 1.0,
+        "mm" => 96.0 / 25.4,
+        "cm" => 96.0 / 2.54,
+        "in" => 96.0,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        _ => return None,
+    };
+    Some(number * scale)
+}
+
+/// Contours of a shape element in its own user units; empty for other
+/// elements.
+fn shape_contours(tag: &Tag<'_>, params: &SegmentParams) -> Result<Vec<Vec<[f64; 2]>>, String> {
+    let number = |name: &str| tag.attribute(name).and_then(length_px).unwrap_or(0.0);
+    Ok(match tag.name {
+        "path" => path_contours(tag.attribute("d").unwrap_or_default(), params)?,
+        "polygon" | "polyline" => {
+            let values = numbers(tag.attribute("points").unwrap_or_default()).ok_or("bad points")?;
+            vec![values.chunks_exact(2).map(|p| [p[0], p[1]]).collect()]
+        }
+        "rect" => {
+            let (x, y, w, h) = (number("x"), number("y"), number("width"), number("height"));
+            // A missing corner radius takes the other one
+            let (rx, ry) = match (tag.attribute("rx").and_then(length_px), tag.attribute("ry").and_then(length_px)) {
+                (Some(rx), Some(ry)) => (rx, ry),
+                (Some(r), None) | (None, Some(r)) => (r, r),
+                (None, None) => (0.0, 0.0),
+            };
+            let (rx, ry) = (rx.clamp(0.0, w / 2.0), ry.clamp(0.0, h / 2.0));
+            if w <= 0.0 || h <= 0.0 {
+                Vec::new()
+            } else if rx == 0.0 || ry == 0.0 {
+                vec![vec![[x, y], [x + w, y], [x + w, y + h], [x, y + h]]]
+            } else {
+                let n = (params.calculate_segments(rx.max(ry)) / 4).max(1);
+                let corners = [(x + w - rx, y + ry, -0.5), (x + w - rx, y + h - ry, 0.0), (x + rx, y + h - ry, 0.5), (x + rx, y + ry, 1.0)];
+                let contour = corners.iter()
+                    .flat_map(|&(cx, cy, start)| (0..=n).map(move |k| {
+                        let angle = PI * (start + 0.5 * f64::from(k) / f64::from(n));
+                        [cx + rx * angle.cos(), cy + ry * angle.sin()]
+                    }))
+                    .collect();
+                vec![contour]
+            }
+        }
+        "circle" => ellipse(number("cx"), number("cy"), number("r"), number("r"), params),
+        "ellipse" => ellipse(number("cx"), number("cy"), number("rx"), number("ry"), params),
+        _ => Vec::new(),
+    })
+}
+
+/// An axis-aligned ellipse as one contour; empty for a zero radius.
+fn ellipse(cx: f64, cy: f64, rx: f64, ry: f64, params: &SegmentParams) -> Vec<Vec<[f64; 2]>> {
+    if rx <= 0.0 || ry <= 0.0 {
+        return Vec::new();
+    }
+    let n = params.calculate_segments(rx.max(ry));
+    let contour = (0..n)
+        .map(|k| {
+            let angle = 2.0 * PI * f64::from(k) / f64::from(n);
+            [cx + rx * angle.cos(), cy + ry * angle.sin()]
+        })
+        .collect();
+    vec![contour]
+}
+
+// =============================================================================
+// PATHS
+// =============================================================================
+
+/// Flatten path data into closed contours.
+fn path_contours(d: &str, params: &SegmentParams) -> Result<Vec<Vec<[f64; 2]>>, String> {
+    let mut lexer = Lexer { bytes: d.as_bytes(), at: 0 };
+    let mut path = PathBuilder::default();
+    let mut command = None;
+    loop {
+        lexer.skip_separators();
+        let Some(&next) = lexer.bytes.get(lexer.at) else { break };
+        if next.is_ascii_alphabetic() {
+            lexer.at += 1;
+            command = Some(next);
+        }
+        let Some(c) = command else {
+            return Err("expected a path command".to_string());
+        };
+        let relative = c.is_ascii_lowercase();
+        let origin = if relative { path.pos } else { [0.0, 0.0] };
+        let point = |lexer: &mut Lexer<'_>| -> Result<[f64; 2], String> {
+            let x = lexer.number()?;
+            let y = lexer.number()?;
+            Ok([origin[0] + x, origin[1] + y])
+        };
+        match c.to_ascii_uppercase() {
+            b'M' => {
+                let p = point(&mut lexer)?;
+                path.move_to(p);
+                // Further pairs are lines
+                command = Some(if relative { b'l' } else { b'L' });
+            }
+            b'L' => {
+                let p = point(&mut lexer)?;
+                path.line_to(p);
+            }
+            b'H' => {
+                let x = lexer.number()? + origin[0];
+                path.line_to([x, path.pos[1]]);
+            }
+            b'V' => {
+                let y = lexer.number()? + origin[1];
+                path.line_to([path.pos[0], y]);
+            }
+            b'C' => {
+                let (c1, c2, p) = (point(&mut lexer)?, point(&mut lexer)?, point(&mut lexer)?);
+                path.cubic_to(c1, c2, p, params);
+            }
+            b'S' => {
+                let c1 = path.reflected(path.last_cubic);
+                let (c2, p) = (point(&mut lexer)?, point(&mut lexer)?);
+                path.cubic_to(c1, c2, p, params);
+            }
+            b'Q' => {
+                let (c1, p) = (point(&mut lexer)?, point(&mut lexer)?);
+                path.quad_to(c1, p, params);
+            }
+            b'T' => {
+                let c1 = path.reflected(path.last_quad);
+                let p = point(&mut lexer)?;
+                path.quad_to(c1, p, params);
+            }
+            b'A' => {
+                let (rx, ry, rotation) = (lexer.number()?, lexer.number()?, lexer.number()?);
+                let (large, sweep) = (lexer.flag()?, lexer.flag()?);
+                let p = point(&mut lexer)?;
+                path.arc_to(rx, ry, rotation, large, sweep, p, params);
+            }
+            b'Z' => {
+                path.close();
+                // Z takes no numbers, so it cannot repeat
+                command = None;
+            }
+            _ => return Err(format!("unknown path command {}", c as char)),
+        }
+    }
+    Ok(path.finish())
+}
+
+/// Contours under construction, with the pen state.
+#[derive(Default)]
+struct PathBuilder {
+    contours: Vec<Vec<[f64; 2]>>,
+    current: Vec<[f64; 2]>,
+    pos: [f64; 2],
+    start: [f64; 2],
+    /// Second control point of the previous command, if it was a cubic.
+    last_cubic: Option<[f64; 2]>,
+    /// Control point of the previous command, if it was a quadratic.
+    last_quad: Option<[f64; 2]>,
+}
+
+impl PathBuilder {
+    fn move_to(&mut self, p: [f64; 2]) {
+        self.flush();
+        self.current.push(p);
+        (self.pos, self.start) = (p, p);
+        (self.last_cubic, self.last_quad) = (None, None);
+    }
+
+    fn line_to(&mut self, p: [f64; 2]) {
+        if self.current.is_empty() {
+            self.current.push(self.pos);
+        }
+        self.current.push(p);
+        self.pos = p;
+        (self.last_cubic, self.last_quad) = (None, None);
+    }
+
+    /// The current point mirrored through `control`'s reflection, or the
+    /// current point if the previous command had no such control.
+    fn reflected(&self, control: Option<[f64; 2]>) -> [f64; 2] {
+        control.map_or(self.pos, |c| [2.0 * self.pos[0] - c[0], 2.0 * self.pos[1] - c[1]])
+    }
+
+    fn cubic_to(&mut self, c1: [f64; 2], c2: [f64; 2], p: [f64; 2], params: &SegmentParams) {
+        let p0 = self.pos;
+        let n = curve_segments(&[p0, c1, c2, p], params);
+        let points: Vec<[f64; 2]> = (1..=n)
+            .map(|i| {
+                let t = f64::from(i) / f64::from(n);
+                let u = 1.0 - t;
+                let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                [
+                    a * p0[0] + b * c1[0] + c * c2[0] + d * p[0],
+                    a * p0[1] + b * c1[1] + c * c2[1] + d * p[1],
+                ]
+            })
+            .collect();
+        self.extend(points);
+        self.last_cubic = Some(c2);
+    }
+
+    fn quad_to(&mut self, c1: [f64; 2], p: [f64; 2], params: &SegmentParams) {
+        let p0 = self.pos;
+        let n = curve_segments(&[p0, c1, p], params);
+        let points: Vec<[f64; 2]> = (1..=n)
+            .map(|i| {
+                let t = f64::from(i) / f64::from(n);
+                let u = 1.0 - t;
+                [
+                    u * u * p0[0] + 2.0 * u * t * c1[0] + t * t * p[0],
+                    u * u * p0[1] + 2.0 * u * t * c1[1] + t * t * p[1],
+                ]
+            })
+            .collect();
+        self.extend(points);
+        self.last_quad = Some(c1);
+    }
+
+    /// Elliptical arc from the current point, converted to center form as
+    /// in the SVG specification's implementation notes.
+    #[allow(clippy::too_many_arguments)]
+    fn arc_to(&mut self, rx: f64, ry: f64, rotation: f64, large: bool, sweep: bool, p: [f64; 2], params: &SegmentParams) {
+        let p0 = self.pos;
+        let (mut rx, mut ry) = (rx.abs(), ry.abs());
+        if p0 == p {
+            return;
+        }
+        if rx == 0.0 || ry == 0.0 {
+            self.line_to(p);
+            return;
+        }
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let (dx, dy) = ((p0[0] - p[0]) / 2.0, (p0[1] - p[1]) / 2.0);
+        let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+
+        // Grow radii too small to reach the end point
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            (rx, ry) = (rx * lambda.sqrt(), ry * lambda.sqrt());
+        }
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let sign = if large == sweep { -1.0 } else { 1.0 };
+        let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+        let (cx1, cy1) = (coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx);
+        let center = [
+            cos * cx1 - sin * cy1 + (p0[0] + p[0]) / 2.0,
+            sin * cx1 + cos * cy1 + (p0[1] + p[1]) / 2.0,
+        ];
+
+        let angle = |u: [f64; 2], v: [f64; 2]| (u[0] * v[1] - u[1] * v[0]).atan2(u[0] * v[0] + u[1] * v[1]);
+        let u = [(x1 - cx1) / rx, (y1 - cy1) / ry];
+        let v = [(-x1 - cx1) / rx, (-y1 - cy1) / ry];
+        let start = angle([1.0, 0.0], u);
+        let mut delta = angle(u, v);
+        if !sweep && delta > 0.0 {
+            delta -= 2.0 * PI;
+        } else if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        }
+
+        let full = f64::from(params.calculate_segments(rx.max(ry)));
+        let n = ((full * delta.abs() / (2.0 * PI)).ceil() as u32).max(1);
+        let mut points: Vec<[f64; 2]> = (1..n)
+            .map(|k| {
+                let (s, c) = (start + delta * f64::from(k) / f64::from(n)).sin_cos();
+                [
+                    center[0] + rx * c * cos - ry * s * sin,
+                    center[1] + rx * c * sin + ry * s * cos,
+                ]
+            })
+            .collect();
+        // End exactly on the given point
+        points.push(p);
+        self.extend(points);
+    }
+
+    fn close(&mut self) {
+        self.flush();
+        self.pos = self.start;
+        (self.last_cubic, self.last_quad) = (None, None);
+    }
+
+    /// Append curve points, starting a contour at the pen if needed.
+    fn extend(&mut self, points: Vec<[f64; 2]>) {
+        if self.current.is_empty() {
+            self.current.push(self.pos);
+        }
+        if let Some(&last) = points.last() {
+            self.pos = last;
+        }
+        self.current.extend(points);
+        (self.last_cubic, self.last_quad) = (None, None);
+    }
+
+    /// Close the current contour into the finished list.
+    fn flush(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+        if contour.len() > 1 && contour.first() == contour.last() {
+            contour.pop();
+        }
+        if contour.len() >= 3 {
+            self.contours.push(contour);
+        }
+    }
+
+    fn finish(mut self) -> Vec<Vec<[f64; 2]>> {
+        self.flush();
+        self.contours
+    }
+}
+
+/// Segments for a curve: a quarter of a circle's count, the circle having
+/// a quarter turn as long as the curve's control polygon.
+fn curve_segments(controls: &[[f64; 2]], params: &SegmentParams) -> u32 {
+    let length: f64 = controls.windows(2).map(|w| (w[1][0] - w[0][0]).hypot(w[1][1] - w[0][1])).sum();
+    (params.calculate_segments(2.0 * length / PI) / 4).max(1)
+}
+
+/// Reader of numbers and flags in path data.
+struct Lexer<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl Lexer<'_> {
+    fn skip_separators(&mut self) {
+        while self.bytes.get(self.at).is_some_and(|b| b.is_ascii_whitespace() || *b == b',') {
+            self.at += 1;
+        }
+    }
+
+    /// The next number; numbers may run together, as in `1.5.5` or `1-2`.
+    fn number(&mut self) -> Result<f64, String> {
+        self.skip_separators();
+        let start = self.at;
+        let digits = |lexer: &mut Self| {
+            while lexer.bytes.get(lexer.at).is_some_and(u8::is_ascii_digit) {
+                lexer.at += 1;
+            }
+        };
+        if matches!(self.bytes.get(self.at), Some(b'+' | b'-')) {
+            self.at += 1;
+        }
+        digits(self);
+        if self.bytes.get(self.at) == Some(&b'.') {
+            self.at += 1;
+            digits(self);
+        }
+        if matches!(self.bytes.get(self.at), Some(b'e' | b'E')) {
+            self.at += 1;
+            if matches!(self.bytes.get(self.at), Some(b'+' | b'-')) {
+                self.at += 1;
+            }
+            digits(self);
+        }
+        std::str::from_utf8(&self.bytes[start..self.at])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| format!("expected a number at offset {}", start))
+    }
+
+    /// An arc flag, a single `0` or `1` that may touch the next number.
+    fn flag(&mut self) -> Result<bool, String> {
+        self.skip_separators();
+        let flag = match self.bytes.get(self.at) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(format!("expected an arc flag at offset {}", self.at)),
+        };
+        self.at += 1;
+        Ok(flag)
+    }
+}
+
+/// Whitespace or comma separated numbers; `None` if any does not parse.
+fn numbers(text: &str) -> Option<Vec<f64>> {
+    let mut lexer = Lexer { bytes: text.as_bytes(), at: 0 };
+    let mut values = Vec::new();
+    loop {
+        lexer.skip_separators();
+        if lexer.at >= lexer.bytes.len() {
+            return Some(values);
+        }
+        values.push(lexer.number().ok()?);
+    }
+}
+
+// =============================================================================
+// TRANSFORMS
+// =============================================================================
+
+/// `m` applied after `n`.
+fn multiply(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[2] * n[1],
+        m[1] * n[0] + m[3] * n[1],
+        m[0] * n[2] + m[2] * n[3],
+        m[1] * n[2] + m[3] * n[3],
+        m[0] * n[4] + m[2] * n[5] + m[4],
+        m[1] * n[4] + m[3] * n[5] + m[5],
+    ]
+}
+
+fn apply(m: &Matrix, [x, y]: [f64; 2]) -> [f64; 2] {
+    [m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5]]
+}
+
+/// Parse a `transform` list; unknown or malformed entries are skipped.
+fn parse_transform(text: &str) -> Matrix {
+    let mut matrix = IDENTITY;
+    for entry in text.split(')') {
+        let Some((name, arguments)) = entry.split_once('(') else { continue };
+        let Some(values) = numbers(arguments) else { continue };
+        let name = name.trim_matches(|c: char| c.is_whitespace() || c == ',');
+        let step = match (name, values.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => [a, b, c, d, e, f],
+            ("translate", &[x]) => [1.0, 0.0, 0.0, 1.0, x, 0.0],
+            ("translate", &[x, y]) => [1.0, 0.0, 0.0, 1.0, x, y],
+            ("scale", &[s]) => [s, 0.0, 0.0, s, 0.0, 0.0],
+            ("scale", &[sx, sy]) => [sx, 0.0, 0.0, sy, 0.0, 0.0],
+            ("rotate", &[a]) => rotation(a),
+            ("rotate", &[a, cx, cy]) => {
+                let to = [1.0, 0.0, 0.0, 1.0, cx, cy];
+                let back = [1.0, 0.0, 0.0, 1.0, -cx, -cy];
+                multiply(&multiply(&to, &rotation(a)), &back)
+            }
+            ("skewX", &[a]) => [1.0, 0.0, a.to_radians().tan(), 1.0, 0.0, 0.0],
+            ("skewY", &[a]) => [1.0, a.to_radians().tan(), 0.0, 1.0, 0.0, 0.0],
+            _ => continue,
+        };
+        matrix = multiply(&matrix, &step);
+    }
+    matrix
+}
+
+/// Rotation by `degrees`, clockwise on screen as SVG defines it.
+fn rotation(degrees: f64) -> Matrix {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    [cos, sin, -sin, cos, 0.0, 0.0]
+}
+
+// =============================================================================
+// TAGS
+// =============================================================================
+
+/// An opening, closing or self-closing element tag.
+struct Tag<'a> {
+    /// Local name, without a namespace prefix.
+    name: &'a str,
+    attributes: Vec<(&'a str, &'a str)>,
+    closing: bool,
+    self_closing: bool,
+}
+
+impl<'a> Tag<'a> {
+    fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes.iter().find(|(key, _)| *key == name).map(|&(_, value)| value)
+    }
+}
+
+/// Element tags of an XML document in order, skipping text, comments,
+/// CDATA, processing instructions and the doctype.
+struct Tags<'a> {
+    text: &'a str,
+    at: usize,
+}
+
+impl<'a> Tags<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, at: 0 }
+    }
+
+    /// Move past `end`, or to the end of the text if it never comes.
+    fn skip_past(&mut self, end: &str) {
+        self.at = self.text[self.at..].find(end).map_or(self.text.len(), |i| self.at + i + end.len());
+    }
+}
+
+impl<'a> Iterator for Tags<'a> {
+    type Item = ManifoldResult<Tag<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.at += self.text[self.at..].find('<')?;
+            let rest = &self.text[self.at..];
+            if rest.starts_with("<!--") {
+                self.skip_past("-->");
+            } else if rest.starts_with("<![CDATA[") {
+                self.skip_past("]]>");
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>");
+            } else if rest.starts_with("<!") {
+                self.skip_past(">");
+            } else {
+                break;
+            }
+        }
+
+        // Find the end of the tag outside quoted values
+        let bytes = self.text.as_bytes();
+        let start = self.at + 1;
+        let mut quote = None;
+        let mut end = start;
+        while end < bytes.len() {
+            match (quote, bytes[end]) {
+                (None, b'>') => break,
+                (None, q @ (b'"' | b'\'')) => quote = Some(q),
+                (Some(q), b) if b == q => quote = None,
+                _ => {}
+            }
+            end += 1;
+        }
+        if end >= bytes.len() {
+            self.at = bytes.len();
+            return Some(Err(ManifoldError::ImportError("SVG ends inside a tag".to_string())));
+        }
+        self.at = end + 1;
+
+        let mut inner = &self.text[start..end];
+        let closing = inner.starts_with('/');
+        let self_closing = inner.ends_with('/');
+        inner = inner.trim_start_matches('/').trim_end_matches('/');
+        let name_end = inner.find(|c: char| c.is_whitespace()).unwrap_or(inner.len());
+        let name = &inner[..name_end];
+        let name = name.rsplit(':').next().unwrap_or(name);
+        Some(Ok(Tag { name, attributes: parse_attributes(&inner[name_end..]), closing, self_closing }))
+    }
+}
+
+/// `name="value"` pairs of a tag, with either quote; namespace prefixes
+/// are dropped from names.
+fn parse_attributes(mut text: &str) -> Vec<(&str, &str)> {
+    let mut attributes = Vec::new();
+    while let Some(eq) = text.find('=') {
+        let name = text[..eq].trim();
+        let rest = text[eq + 1..].trim_start();
+        let Some(quote) = rest.chars().next().filter(|&c| c == '"' || c == '\'') else { break };
+        let Some(close) = rest[1..].find(quote) else { break };
+        let name = name.rsplit(':').next().unwrap_or(name);
+        attributes.push((name, &rest[1..1 + close]));
+        text = &rest[close + 2..];
+    }
+    attributes
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions(svg: &str) -> Vec<Polygon2D> {
+        svg_polygons(svg.as_bytes(), &SegmentParams::with_fn(16)).unwrap()
+    }
+
+    fn area(points: &[[f64; 2]]) -> f64 {
+        let n = points.len();
+        (0..n).map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a[0] * b[1] - b[0] * a[1]
+        }).sum::<f64>() / 2.0
+    }
+
+    /// Test the viewBox maps to millimeters with Y up from the page bottom.
+    #[test]
+    fn test_viewport() {
+        let svg = r#"<svg width="100mm" height="50mm" viewBox="0 0 200 100"><rect x="0" y="0" width="20" height="10"/></svg>"#;
+        let r = regions(svg);
+        assert_eq!(r.len(), 1);
+        // 20x10 user units at 0.5 mm each, at the top of a 50 mm page
+        let xs: Vec<f64> = r[0].outer.iter().map(|p| p[0]).collect();
+        let ys: Vec<f64> = r[0].outer.iter().map(|p| p[1]).collect();
+        assert!(xs.iter().all(|&x| x == 0.0 || (x - 10.0).abs() < 1e-9), "{:?}", xs);
+        assert!(ys.iter().all(|&y| (y - 50.0).abs() < 1e-9 || (y - 45.0).abs() < 1e-9), "{:?}", ys);
+        assert!(area(&r[0].outer) > 0.0);
+
+        // Without a viewBox, user units are pixels at 96 dpi
+        let r = regions(r#"<svg height="96"><rect width="96" height="96"/></svg>"#);
+        assert!((area(&r[0].outer) - 25.4 * 25.4).abs() < 1e-9);
+    }
+
+    /// Test path commands, relative forms and implicit repeats.
+    #[test]
+    fn test_path_commands() {
+        let square = |d: &str| {
+            let r = regions(&format!(r#"<svg viewBox="0 0 96 96" width="96" height="96"><path d="{}"/></svg>"#, d));
+            assert_eq!(r.len(), 1, "{}", d);
+            area(&r[0].outer) / (MM_PER_PX * MM_PER_PX)
+        };
+        assert!((square("M0,0 L10,0 10,10 0,10z") - 100.0).abs() < 1e-9);
+        assert!((square("m0 0h10v10h-10Z") - 100.0).abs() < 1e-9);
+        assert!((square("M0-0l10.0.0 0 10-10 0") - 100.0).abs() < 1e-9);
+        // A half disc from an arc, with compact flags
+        let half = square("M0 0a5 5 0 1010 0z");
+        assert!((half - PI * 25.0 / 2.0).abs() < 1.5, "{}", half);
+        // Curves stay between their chord and control polygon
+        let cubic = square("M0 0 C0 10 10 10 10 0 Z");
+        assert!(cubic > 0.0 && cubic < 100.0);
+        let smooth = square("M0 0 Q5 10 10 0 T20 0 V-10 H0 Z");
+        // The reflected control carves out what the first curve adds
+        assert!((smooth - 200.0).abs() < 1.0, "{}", smooth);
+    }
+
+    /// Test holes, unions, transforms and hidden content.
+    #[test]
+    fn test_document() {
+        let svg = r#"<?xml version="1.0"?>
+            <!-- logo -->
+            <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100" width="100" height="100">
+              <defs><rect id="unused" width="50" height="50"/></defs>
+              <g transform="translate(10 0) scale(2)">
+                <path d="M0 0 H20 V20 H0 Z M5 5 V15 H15 V5 Z"/>
+              </g>
+              <svg:circle cx="80" cy="80" r="5" transform="rotate(45, 80, 80)"/>
+              <ellipse cx="0" cy="0" rx="0" ry="5"/>
+            </svg>"#;
+        let r = regions(svg);
+        assert_eq!(r.len(), 2);
+        let framed = r.iter().find(|p| !p.holes.is_empty()).unwrap();
+        let outer = area(&framed.outer) / (MM_PER_PX * MM_PER_PX);
+        assert!((outer - 1600.0).abs() < 1e-6, "{}", outer);
+        assert_eq!(r.iter().map(|p| p.outer.len()).sum::<usize>(), 4 + 16);
+    }
+
+    /// Test rounded rectangles and polygons.
+    #[test]
+    fn test_shapes() {
+        let r = regions(r#"<svg><rect width="10" height="10" rx="2"/><polygon points="20,0 30,0 25,5"/></svg>"#);
+        assert_eq!(r.len(), 2);
+        let rounded = r.iter().map(|p| p.outer.len()).max().unwrap();
+        assert_eq!(rounded, 4 * 5);
+    }
+
+    /// Test malformed files are rejected.
+    #[test]
+    fn test_errors() {
+        let params = SegmentParams::default();
+        assert!(svg_polygons(b"<html></html>", &params).is_err());
+        assert!(svg_polygons(b"<svg><path d=\"M0 0 L1\"/></svg>", &params).is_err());
+        assert!(svg_polygons(b"<svg><path d=\"10 10\"/></svg>", &params).is_err());
+        assert!(svg_polygons(b"<svg><path d=\"M0 0", &params).is_err());
+        assert!(svg_polygons(&[0xff, 0xfe], &params).is_err());
+        assert!(svg_polygons(b"<svg/>", &params).unwrap().is_empty());
+    }
+}
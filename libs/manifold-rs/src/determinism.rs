@@ -0,0 +1,286 @@
+//! # Determinism Self-Check
+//!
+//! Renders a model twice from scratch and requires byte-identical meshes
+//! and identical evaluation output, so nondeterminism from hash iteration
+//! order or parallel reduction is caught on the first run rather than as a
+//! flaky diff later. [`verify_parallel_determinism`] instead renders once
+//! with serial and once with parallel boolean reduction (see
+//! [`Reduction`]) and requires the same.
+//!
+//! The `_with_eval_options` variants take full evaluation options, as the
+//! command line's `--verify-determinism` does; console output and
+//! warnings reach `options.log` from the first pass only.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::determinism::verify_determinism;
+//!
+//! let mesh = verify_determinism("difference() { cube(10); sphere(6); }", &[]).unwrap();
+//! assert!(!mesh.is_empty());
+//! ```
+
+use openscad_eval::{EvalOptions, LibraryBundle};
+
+use crate::error::{ManifoldError, ManifoldResult};
+use crate::manifold::boolean::{reduction, set_reduction, CsgScope, Reduction};
+use crate::manifold::simplify::simplify;
+use crate::mesh::Mesh;
+use crate::openscad::from_ir::geometry_to_mesh;
+
+// =============================================================================
+// RENDER PASS
+// =============================================================================
+
+/// Everything one render pass produces that must match across passes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderStats {
+    /// Number of mesh vertices.
+    pub vertex_count: usize,
+    /// Number of mesh triangles.
+    pub triangle_count: usize,
+    /// Evaluation warnings, in order.
+    pub warnings: Vec<String>,
+    /// Console output, in order.
+    pub echoes: Vec<String>,
+}
+
+/// Run the full pipeline once with no state shared with other passes.
+fn render_pass(source: &str, options: &EvalOptions) -> ManifoldResult<(Mesh, RenderStats)> {
+    let evaluated = openscad_eval::evaluate_with_options(source, options)
+        .map_err(ManifoldError::from)?;
+    let _csg = CsgScope::enter(&options.csg);
+    let mesh = simplify(geometry_to_mesh(&evaluated.geometry)?, &options.simplify);
+    let stats = RenderStats {
+        vertex_count: mesh.vertex_count(),
+        triangle_count: mesh.triangle_count(),
+        warnings: evaluated.warnings,
+        echoes: evaluated.echoes,
+    };
+    Ok((mesh, stats))
+}
+
+// =============================================================================
+// VERIFICATION
+// =============================================================================
+
+/// Render twice and check both passes agree exactly.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code
+/// - `libraries`: Bundles from `openscad_eval::compile_library`
+///
+/// ## Returns
+///
+/// The mesh of the first pass.
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::Nondeterministic` describing the first
+/// difference, or any error from the render itself.
+pub fn verify_determinism(source: &str, libraries: &[LibraryBundle]) -> ManifoldResult<Mesh> {
+    verify_determinism_with_eval_options(source, &library_options(libraries))
+}
+
+/// Render twice with full evaluation options and check both passes agree
+/// exactly.
+///
+/// ## Errors
+///
+/// Same as [`verify_determinism`].
+pub fn verify_determinism_with_eval_options(source: &str, options: &EvalOptions) -> ManifoldResult<Mesh> {
+    let (first, first_stats) = render_pass(source, options)?;
+    let (second, second_stats) = render_pass(source, &quiet(options))?;
+    compare_passes(first, first_stats, second, second_stats)
+}
+
+/// Render a 2D result to a drawing twice and check both files are
+/// byte-identical.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code
+/// - `format`: One of [`EXPORT_2D_FORMATS`](crate::openscad::drawing::EXPORT_2D_FORMATS)
+/// - `options`: Evaluation options
+///
+/// ## Returns
+///
+/// The file of the first pass.
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::Nondeterministic` naming the first differing
+/// byte, or any error from [`render_2d_with_eval_options`](crate::render_2d_with_eval_options).
+pub fn verify_drawing_determinism(source: &str, format: &str, options: &EvalOptions) -> ManifoldResult<Vec<u8>> {
+    let first = crate::render_2d_with_eval_options(source, format, options)?;
+    let second = crate::render_2d_with_eval_options(source, format, &quiet(options))?;
+    match first.iter().zip(&second).position(|(a, b)| a != b) {
+        None if first.len() == second.len() => Ok(first),
+        None => Err(ManifoldError::Nondeterministic(format!(
+            "{} length differs: {} vs {}", format, first.len(), second.len(),
+        ))),
+        Some(i) => Err(ManifoldError::Nondeterministic(format!("{} differs at byte {}", format, i))),
+    }
+}
+
+/// Render with serial and then parallel reduction and check both passes
+/// agree exactly.
+///
+/// The reduction setting is restored afterwards. Since it is
+/// process-wide, renders running concurrently on other threads may use
+/// either setting meanwhile; their results are the same either way.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code
+/// - `libraries`: Bundles from `openscad_eval::compile_library`
+///
+/// ## Returns
+///
+/// The mesh of the serial pass.
+///
+/// ## Errors
+///
+/// Same as [`verify_determinism`].
+pub fn verify_parallel_determinism(source: &str, libraries: &[LibraryBundle]) -> ManifoldResult<Mesh> {
+    let options = library_options(libraries);
+    let previous = reduction();
+    set_reduction(Reduction::Serial);
+    let serial = render_pass(source, &options);
+    set_reduction(Reduction::Parallel);
+    let parallel = render_pass(source, &options);
+    set_reduction(previous);
+
+    let ((first, first_stats), (second, second_stats)) = (serial?, parallel?);
+    compare_passes(first, first_stats, second, second_stats)
+}
+
+/// Options with only `libraries` set.
+fn library_options(libraries: &[LibraryBundle]) -> EvalOptions {
+    EvalOptions { libraries: libraries.to_vec(), ..EvalOptions::default() }
+}
+
+/// `options` for a repeat pass: without the log, so it is written once.
+fn quiet(options: &EvalOptions) -> EvalOptions {
+    EvalOptions { log: None, ..options.clone() }
+}
+
+/// Check two passes agree, returning the first mesh.
+fn compare_passes(first: Mesh, first_stats: RenderStats, second: Mesh, second_stats: RenderStats) -> ManifoldResult<Mesh> {
+    if first_stats != second_stats {
+        return Err(ManifoldError::Nondeterministic(format!(
+            "stats differ: {:?} vs {:?}",
+            first_stats, second_stats
+        )));
+    }
+    if let Some(difference) = first_difference(&first, &second) {
+        return Err(ManifoldError::Nondeterministic(difference));
+    }
+    Ok(first)
+}
+
+/// Describe the first byte-level difference between two meshes.
+///
+/// Floats are compared by bit pattern, so `0.0` vs `-0.0` counts.
+pub fn first_difference(a: &Mesh, b: &Mesh) -> Option<String> {
+    let bits = |v: &[f32]| v.iter().map(|f| u64::from(f.to_bits())).collect::<Vec<u64>>();
+    let bits64 = |v: &[f64]| v.iter().map(|f| f.to_bits()).collect::<Vec<u64>>();
+    let wide = |v: &[u32]| v.iter().map(|&i| u64::from(i)).collect::<Vec<u64>>();
+    let (no_colors, no_nodes) = (Vec::new(), Vec::new());
+    let buffers = [
+        ("vertices", bits64(&a.vertices), bits64(&b.vertices)),
+        ("indices", wide(&a.indices), wide(&b.indices)),
+        ("normals", bits(&a.normals), bits(&b.normals)),
+        (
+            "colors",
+            bits(a.colors.as_ref().unwrap_or(&no_colors)),
+            bits(b.colors.as_ref().unwrap_or(&no_colors)),
+        ),
+        ("nodes", wide(a.nodes.as_ref().unwrap_or(&no_nodes)), wide(b.nodes.as_ref().unwrap_or(&no_nodes))),
+    ];
+
+    for (name, x, y) in buffers {
+        if x.len() != y.len() {
+            return Some(format!("{} length differs: {} vs {}", name, x.len(), y.len()));
+        }
+        if let Some(i) = (0..x.len()).find(|&i| x[i] != y[i]) {
+            return Some(format!("{} differ at index {}: {:#x} vs {:#x}", name, i, x[i], y[i]));
+        }
+    }
+    if a.colors.is_some() != b.colors.is_some() {
+        return Some("colors present in only one mesh".to_string());
+    }
+    if a.nodes.is_some() != b.nodes.is_some() {
+        return Some("nodes present in only one mesh".to_string());
+    }
+    None
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the boolean-heavy acceptance model is deterministic.
+    #[test]
+    fn test_csg_is_deterministic() {
+        let source = r#"
+            translate([-24,0,0]) union() { cube(15, center=true); sphere(10); }
+            intersection() { cube(15, center=true); sphere(10); }
+            translate([24,0,0]) difference() { cube(15, center=true); sphere(10); }
+            color([1, 0, 0]) text("C4D");
+            echo_dim(size = 15);
+        "#;
+        let mesh = verify_determinism(source, &[]).unwrap();
+        assert!(mesh.triangle_count() > 50);
+    }
+
+    /// Test serial and parallel reduction give the same mesh for many operands.
+    #[test]
+    fn test_parallel_reduction_is_deterministic() {
+        let source = r#"
+            difference() {
+                union() for (i = [0:6]) rotate([0, 0, i * 25]) cube([20, 2, 2]);
+                for (i = [0:4]) translate([i * 3, 0, 0]) sphere(1.5, $fn = 12);
+            }
+            intersection() { for (i = [0:3]) rotate([0, 0, i * 20]) cube(8, center = true); }
+        "#;
+        let mesh = verify_parallel_determinism(source, &[]).unwrap();
+        assert!(mesh.triangle_count() > 50);
+    }
+
+    /// Test the options variants pass overrides to both passes and log
+    /// the first pass only.
+    #[test]
+    fn test_with_eval_options() {
+        let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&lines);
+        let mut options = EvalOptions {
+            log: Some(std::sync::Arc::new(move |entry: openscad_eval::LogEntry| sink.lock().unwrap().push(format!("{:?}", entry)))),
+            ..EvalOptions::default()
+        };
+        options.overrides.insert("size".to_string(), openscad_eval::Value::Number(4.0));
+        let mesh = verify_determinism_with_eval_options("size = 1; echo(size); cube(size);", &options).unwrap();
+        assert_eq!(mesh.bounding_box().unwrap().1, [4.0; 3]);
+        assert_eq!(lines.lock().unwrap().len(), 1);
+
+        let svg = verify_drawing_determinism("size = 1; square(size);", "svg", &options).unwrap();
+        assert!(String::from_utf8(svg).unwrap().contains(r#"width="4mm""#));
+    }
+
+    /// Test a single flipped bit is reported with its location.
+    #[test]
+    fn test_first_difference() {
+        let a = crate::render("cube(1);").unwrap();
+        let mut b = a.clone();
+        assert_eq!(first_difference(&a, &b), None);
+
+        b.normals[4] = -b.normals[4];
+        let message = first_difference(&a, &b).unwrap();
+        assert!(message.starts_with("normals differ at index 4"));
+    }
+}
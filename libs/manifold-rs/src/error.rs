@@ -70,11 +70,52 @@ pub enum ManifoldError {
         message: String,
     },
     
+    /// Font could not be loaded or used.
+    ///
+    /// Contains description of what went wrong.
+    #[error("Font error: {0}")]
+    FontError(String),
+
+    /// Imported file could not be found or parsed.
+    ///
+    /// Contains the file name and what went wrong.
+    #[error("Import error: {0}")]
+    ImportError(String),
+
+    /// Two renders of the same input produced different output.
+    ///
+    /// Contains the first difference found.
+    #[error("Nondeterministic render: {0}")]
+    Nondeterministic(String),
+
     /// Invalid segment parameters.
     ///
     /// Contains the invalid parameter values.
     #[error("Invalid segment parameters: {0}")]
     InvalidSegmentParams(String),
+
+    /// A configured limit aborted the render.
+    ///
+    /// Names the limit, its value, the value reached and, if the
+    /// evaluator tripped it, the statement responsible.
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(Box<openscad_eval::LimitExceeded>),
+
+    /// The render was cancelled through its `CancellationToken`.
+    #[error("Render cancelled")]
+    Cancelled,
+}
+
+/// Evaluator errors keep limit reports and cancellation structured; the
+/// rest become [`ManifoldError::EvalError`] messages.
+impl From<openscad_eval::EvalError> for ManifoldError {
+    fn from(error: openscad_eval::EvalError) -> Self {
+        match error {
+            openscad_eval::EvalError::LimitExceeded(report) => ManifoldError::LimitExceeded(report),
+            openscad_eval::EvalError::Cancelled => ManifoldError::Cancelled,
+            other => ManifoldError::EvalError(other.to_string()),
+        }
+    }
 }
 
 // =============================================================================
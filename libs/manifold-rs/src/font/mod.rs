@@ -0,0 +1,335 @@
+//! # Font Engine
+//!
+//! Browser-safe glyph outlining for `text()`, built on `ttf-parser` (pure
+//! Rust, no system font lookup).
+//!
+//! ## Fonts
+//!
+//! Fonts are registered from raw TrueType/OpenType bytes with
+//! [`register_font`] and looked up by family name, optionally with a
+//! `:style=` suffix as in OpenSCAD (`"Liberation Sans:style=Bold"`). Unknown
+//! names fall back to the default font: DejaVu Sans, bundled with the
+//! `embedded-font` feature.
+//!
+//! ## Layout
+//!
+//! Glyphs are placed along the baseline by their advance widths (times
+//! `spacing`) and scaled so the font's ascent equals `size`. `halign` uses
+//! the total advance; `valign` uses the bounding box of the outlines.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::font::{text_polygons, TextParams};
+//!
+//! let polygons = text_polygons("Hi", &TextParams::default()).unwrap();
+//! assert!(!polygons.is_empty());
+//! ```
+
+pub mod outline;
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+use openscad_eval::{HAlign, VAlign};
+use ttf_parser::{name_id, Face, GlyphId};
+
+use crate::error::{ManifoldError, ManifoldResult};
+use outline::{group_contours, ContourBuilder};
+
+/// Family name of the bundled default font.
+pub const DEFAULT_FAMILY: &str = "DejaVu Sans";
+
+/// Bundled default font data.
+#[cfg(feature = "embedded-font")]
+static EMBEDDED_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+/// A filled 2D region: one CCW outline with CW holes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Polygon2D {
+    /// Outline vertices, counter-clockwise.
+    pub outer: Vec<[f64; 2]>,
+    /// Hole vertices, clockwise.
+    pub holes: Vec<Vec<[f64; 2]>>,
+}
+
+/// Text layout parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextParams<'a> {
+    /// Ascent (height above the baseline).
+    pub size: f64,
+    /// Font name, `None` for the default font.
+    pub font: Option<&'a str>,
+    /// Horizontal alignment.
+    pub halign: HAlign,
+    /// Vertical alignment.
+    pub valign: VAlign,
+    /// Advance multiplier between characters.
+    pub spacing: f64,
+    /// Line segments per curve.
+    pub curve_segments: u32,
+}
+
+impl Default for TextParams<'_> {
+    fn default() -> Self {
+        Self {
+            size: 10.0,
+            font: None,
+            halign: HAlign::default(),
+            valign: VAlign::default(),
+            spacing: 1.0,
+            curve_segments: 4,
+        }
+    }
+}
+
+// =============================================================================
+// REGISTRY
+// =============================================================================
+
+/// Registered font data keyed by lowercase `family` and `family:style=sub`.
+fn registry() -> &'static RwLock<BTreeMap<String, Arc<[u8]>>> {
+    static REGISTRY: OnceLock<RwLock<BTreeMap<String, Arc<[u8]>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Register a TrueType/OpenType font for `text()`.
+///
+/// A later font with the same family replaces the earlier one for lookups
+/// without a style.
+///
+/// ## Parameters
+///
+/// - `data`: Raw font file bytes
+///
+/// ## Returns
+///
+/// The font's family name.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// let data = std::fs::read("LiberationSans-Regular.ttf").unwrap();
+/// let family = manifold_rs::font::register_font(data).unwrap();
+/// assert_eq!(family, "Liberation Sans");
+/// ```
+pub fn register_font(data: Vec<u8>) -> ManifoldResult<String> {
+    let face = Face::parse(&data, 0)
+        .map_err(|e| ManifoldError::FontError(format!("cannot parse font: {}", e)))?;
+    let family = face_name(&face, &[name_id::TYPOGRAPHIC_FAMILY, name_id::FAMILY])
+        .ok_or_else(|| ManifoldError::FontError("font has no family name".to_string()))?;
+    let style = face_name(&face, &[name_id::TYPOGRAPHIC_SUBFAMILY, name_id::SUBFAMILY])
+        .unwrap_or_else(|| "Regular".to_string());
+
+    let data: Arc<[u8]> = data.into();
+    let key = family.to_lowercase();
+    let mut fonts = registry().write().unwrap_or_else(PoisonError::into_inner);
+    fonts.insert(format!("{}:style={}", key, style.to_lowercase()), data.clone());
+    fonts.insert(key, data);
+    Ok(family)
+}
+
+/// Names under which registered fonts can be looked up, sorted.
+pub fn registered_fonts() -> Vec<String> {
+    registry().read().unwrap_or_else(PoisonError::into_inner).keys().cloned().collect()
+}
+
+/// Remove all registered fonts (the bundled default stays available).
+pub fn clear_fonts() {
+    registry().write().unwrap_or_else(PoisonError::into_inner).clear();
+}
+
+/// First Unicode name record among `ids`, in order of preference.
+fn face_name(face: &Face<'_>, ids: &[u16]) -> Option<String> {
+    ids.iter().find_map(|&id| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == id && name.is_unicode())
+            .find_map(|name| name.to_string())
+    })
+}
+
+/// Resolve a font name to font data.
+///
+/// Tries the full `family:style=...` name, then the family alone, then the
+/// bundled default.
+fn resolve_font(name: Option<&str>) -> ManifoldResult<Arc<[u8]>> {
+    if let Some(name) = name {
+        let name = name.trim().to_lowercase();
+        let family = name.split(':').next().unwrap_or_default().trim();
+        let fonts = registry().read().unwrap_or_else(PoisonError::into_inner);
+        if let Some(data) = fonts.get(&name).or_else(|| fonts.get(family)) {
+            return Ok(data.clone());
+        }
+    }
+    default_font()
+}
+
+/// The bundled default font.
+#[cfg(feature = "embedded-font")]
+fn default_font() -> ManifoldResult<Arc<[u8]>> {
+    static DEFAULT: OnceLock<Arc<[u8]>> = OnceLock::new();
+    Ok(DEFAULT.get_or_init(|| EMBEDDED_FONT.into()).clone())
+}
+
+/// Without a bundled font, fall back to the default family if registered.
+#[cfg(not(feature = "embedded-font"))]
+fn default_font() -> ManifoldResult<Arc<[u8]>> {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&DEFAULT_FAMILY.to_lowercase())
+        .cloned()
+        .ok_or_else(|| ManifoldError::FontError("no font available for text()".to_string()))
+}
+
+// =============================================================================
+// LAYOUT
+// =============================================================================
+
+/// Lay out `text` and return its glyph outlines as filled polygons.
+///
+/// Characters missing from the font render as the font's `.notdef` glyph.
+///
+/// ## Parameters
+///
+/// - `text`: Text to outline
+/// - `params`: Size, font, alignment and spacing
+///
+/// ## Returns
+///
+/// Polygons in layout order, positioned according to the alignment.
+pub fn text_polygons(text: &str, params: &TextParams<'_>) -> ManifoldResult<Vec<Polygon2D>> {
+    let data = resolve_font(params.font)?;
+    let face = Face::parse(&data, 0)
+        .map_err(|e| ManifoldError::FontError(format!("cannot parse font: {}", e)))?;
+
+    let ascent = match face.ascender() {
+        a if a > 0 => a as f64,
+        _ => face.units_per_em() as f64,
+    };
+    let scale = params.size / ascent;
+
+    let mut polygons = Vec::new();
+    let mut pen_x = 0.0;
+    for ch in text.chars() {
+        let glyph = face.glyph_index(ch).unwrap_or(GlyphId(0));
+        let mut builder = ContourBuilder::new(params.curve_segments);
+        face.outline_glyph(glyph, &mut builder);
+
+        let contours = builder.finish()
+            .into_iter()
+            .map(|c| c.into_iter().map(|p| [p[0] * scale + pen_x, p[1] * scale]).collect())
+            .collect();
+        polygons.extend(group_contours(contours));
+
+        let advance = face.glyph_hor_advance(glyph).unwrap_or(0) as f64;
+        pen_x += advance * scale * params.spacing;
+    }
+
+    let (min_y, max_y) = polygons.iter()
+        .flat_map(|p| p.outer.iter())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p[1]), hi.max(p[1])));
+    let dx = match params.halign {
+        HAlign::Left => 0.0,
+        HAlign::Center => -pen_x / 2.0,
+        HAlign::Right => -pen_x,
+    };
+    let dy = match params.valign {
+        _ if polygons.is_empty() => 0.0,
+        VAlign::Baseline => 0.0,
+        VAlign::Top => -max_y,
+        VAlign::Center => -(min_y + max_y) / 2.0,
+        VAlign::Bottom => -min_y,
+    };
+
+    if dx != 0.0 || dy != 0.0 {
+        for polygon in &mut polygons {
+            for p in polygon.outer.iter_mut().chain(polygon.holes.iter_mut().flatten()) {
+                p[0] += dx;
+                p[1] += dy;
+            }
+        }
+    }
+    Ok(polygons)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+// Layout tests need the bundled font
+#[cfg(all(test, feature = "embedded-font"))]
+mod tests {
+    use super::*;
+
+    /// Bounding box of all outline points.
+    fn bounds(polygons: &[Polygon2D]) -> [f64; 4] {
+        polygons.iter().flat_map(|p| p.outer.iter()).fold(
+            [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY],
+            |b, p| [b[0].min(p[0]), b[1].min(p[1]), b[2].max(p[0]), b[3].max(p[1])],
+        )
+    }
+
+    /// Test glyphs with counters get holes.
+    #[test]
+    fn test_holes() {
+        let polygons = text_polygons("o", &TextParams::default()).unwrap();
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].holes.len(), 1);
+    }
+
+    /// Test capitals are about `size` tall and sit on the baseline.
+    #[test]
+    fn test_size() {
+        let params = TextParams { size: 20.0, ..TextParams::default() };
+        let b = bounds(&text_polygons("H", &params).unwrap());
+        assert!(b[1].abs() < 1e-6);
+        assert!(b[3] > 14.0 && b[3] <= 20.0);
+    }
+
+    /// Test alignment moves the outlines around the origin.
+    #[test]
+    fn test_alignment() {
+        let params = TextParams {
+            halign: HAlign::Center,
+            valign: VAlign::Center,
+            ..TextParams::default()
+        };
+        let b = bounds(&text_polygons("HH", &params).unwrap());
+        assert!((b[1] + b[3]).abs() < 1e-6);
+        assert!(b[0] < 0.0 && b[2] > 0.0);
+
+        let right = TextParams { halign: HAlign::Right, ..TextParams::default() };
+        assert!(bounds(&text_polygons("HH", &right).unwrap())[2] <= 0.0);
+    }
+
+    /// Test spacing widens the layout.
+    #[test]
+    fn test_spacing() {
+        let narrow = bounds(&text_polygons("II", &TextParams::default()).unwrap());
+        let wide = TextParams { spacing: 2.0, ..TextParams::default() };
+        let wide = bounds(&text_polygons("II", &wide).unwrap());
+        assert!(wide[2] - wide[0] > narrow[2] - narrow[0]);
+    }
+
+    /// Test unknown fonts fall back and bad data is rejected.
+    #[test]
+    fn test_font_lookup() {
+        let params = TextParams { font: Some("No Such Font:style=Bold"), ..TextParams::default() };
+        assert!(!text_polygons("A", &params).unwrap().is_empty());
+        assert!(matches!(register_font(vec![0; 16]), Err(ManifoldError::FontError(_))));
+    }
+
+    /// Test a registered font is found by family and style.
+    #[test]
+    fn test_register_font() {
+        let family = register_font(EMBEDDED_FONT.to_vec()).unwrap();
+        assert_eq!(family, DEFAULT_FAMILY);
+        assert!(registered_fonts().contains(&"dejavu sans:style=book".to_string()));
+    }
+}
@@ -0,0 +1,235 @@
+//! # Glyph Outlines
+//!
+//! Flattens TrueType/CFF glyph outlines into closed polylines and groups
+//! them into outlines with holes.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::font::outline::{group_contours, ContourBuilder};
+//! use ttf_parser::OutlineBuilder;
+//!
+//! let mut builder = ContourBuilder::new(4);
+//! builder.move_to(0.0, 0.0);
+//! builder.line_to(10.0, 0.0);
+//! builder.quad_to(10.0, 10.0, 0.0, 10.0);
+//! builder.close();
+//! let polygons = group_contours(builder.finish());
+//! assert_eq!(polygons.len(), 1);
+//! ```
+
+use ttf_parser::OutlineBuilder;
+
+use super::Polygon2D;
+
+// =============================================================================
+// CONTOUR BUILDER
+// =============================================================================
+
+/// Outline sink that flattens curves into line segments.
+#[derive(Debug, Clone)]
+pub struct ContourBuilder {
+    /// Line segments per quadratic or cubic curve.
+    segments: u32,
+    /// Finished contours.
+    contours: Vec<Vec<[f64; 2]>>,
+    /// Contour under construction.
+    current: Vec<[f64; 2]>,
+}
+
+impl ContourBuilder {
+    /// Create a builder that splits each curve into `segments` lines.
+    pub fn new(segments: u32) -> Self {
+        Self {
+            segments: segments.max(1),
+            contours: Vec::new(),
+            current: Vec::new(),
+        }
+    }
+
+    /// Take the finished contours, closing any open one.
+    pub fn finish(mut self) -> Vec<Vec<[f64; 2]>> {
+        self.flush();
+        self.contours
+    }
+
+    /// Last point of the current contour.
+    fn last(&self) -> [f64; 2] {
+        self.current.last().copied().unwrap_or([0.0, 0.0])
+    }
+
+    /// Move the current contour to the finished list.
+    fn flush(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+        if contour.len() > 1 && contour.first() == contour.last() {
+            contour.pop();
+        }
+        if contour.len() >= 3 {
+            self.contours.push(contour);
+        }
+    }
+}
+
+impl OutlineBuilder for ContourBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.flush();
+        self.current.push([x as f64, y as f64]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.current.push([x as f64, y as f64]);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.last();
+        let (p1, p2) = ([x1 as f64, y1 as f64], [x as f64, y as f64]);
+        for i in 1..=self.segments {
+            let t = i as f64 / self.segments as f64;
+            let u = 1.0 - t;
+            self.current.push([
+                u * u * p0[0] + 2.0 * u * t * p1[0] + t * t * p2[0],
+                u * u * p0[1] + 2.0 * u * t * p1[1] + t * t * p2[1],
+            ]);
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.last();
+        let (p1, p2, p3) = ([x1 as f64, y1 as f64], [x2 as f64, y2 as f64], [x as f64, y as f64]);
+        for i in 1..=self.segments {
+            let t = i as f64 / self.segments as f64;
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            self.current.push([
+                a * p0[0] + b * p1[0] + c * p2[0] + d * p3[0],
+                a * p0[1] + b * p1[1] + c * p2[1] + d * p3[1],
+            ]);
+        }
+    }
+
+    fn close(&mut self) {
+        self.flush();
+    }
+}
+
+// =============================================================================
+// CONTOUR GROUPING
+// =============================================================================
+
+/// Group contours into outlines with holes by nesting depth.
+///
+/// A contour nested inside an even number of others is an outline; an odd
+/// one is a hole of the outline directly containing it. This works for both
+/// TrueType and CFF winding conventions.
+///
+/// ## Returns
+///
+/// Polygons with CCW outlines and CW holes.
+pub fn group_contours(contours: Vec<Vec<[f64; 2]>>) -> Vec<Polygon2D> {
+    let contours: Vec<Vec<[f64; 2]>> = contours.into_iter()
+        .filter(|c| c.len() >= 3 && signed_area(c).abs() > f64::EPSILON)
+        .collect();
+
+    // parents[i]: indices of contours containing contour i
+    let parents: Vec<Vec<usize>> = (0..contours.len())
+        .map(|i| {
+            (0..contours.len())
+                .filter(|&j| j != i && contains(&contours[j], contours[i][0]))
+                .collect()
+        })
+        .collect();
+
+    let mut polygons: Vec<Polygon2D> = Vec::new();
+    let mut outer_slot = vec![None; contours.len()];
+    for (i, contour) in contours.iter().enumerate() {
+        if parents[i].len().is_multiple_of(2) {
+            outer_slot[i] = Some(polygons.len());
+            polygons.push(Polygon2D { outer: oriented(contour, true), holes: Vec::new() });
+        }
+    }
+
+    for (i, contour) in contours.iter().enumerate() {
+        let depth = parents[i].len();
+        if !depth.is_multiple_of(2) {
+            let slot = parents[i].iter()
+                .find(|&&j| parents[j].len() == depth - 1)
+                .and_then(|&j| outer_slot[j]);
+            if let Some(slot) = slot {
+                polygons[slot].holes.push(oriented(contour, false));
+            }
+        }
+    }
+
+    polygons
+}
+
+/// Copy of `contour` wound CCW (`ccw`) or CW.
+fn oriented(contour: &[[f64; 2]], ccw: bool) -> Vec<[f64; 2]> {
+    let mut contour = contour.to_vec();
+    if (signed_area(&contour) > 0.0) != ccw {
+        contour.reverse();
+    }
+    contour
+}
+
+/// Twice the signed area (positive for CCW).
+fn signed_area(points: &[[f64; 2]]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (p, q) = (points[i], points[(i + 1) % n]);
+            p[0] * q[1] - q[0] * p[1]
+        })
+        .sum()
+}
+
+/// Even-odd point-in-polygon test.
+fn contains(polygon: &[[f64; 2]], point: [f64; 2]) -> bool {
+    let mut inside = false;
+    let n = polygon.len();
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + n - 1) % n]);
+        if (a[1] > point[1]) != (b[1] > point[1])
+            && point[0] < (b[0] - a[0]) * (point[1] - a[1]) / (b[1] - a[1]) + a[0]
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(min: f64, max: f64) -> Vec<[f64; 2]> {
+        vec![[min, min], [max, min], [max, max], [min, max]]
+    }
+
+    /// Test curves are split into the requested number of segments.
+    #[test]
+    fn test_curve_flattening() {
+        let mut builder = ContourBuilder::new(8);
+        builder.move_to(0.0, 0.0);
+        builder.quad_to(5.0, 10.0, 10.0, 0.0);
+        builder.close();
+        let contours = builder.finish();
+        assert_eq!(contours[0].len(), 9);
+        assert_eq!(contours[0][8], [10.0, 0.0]);
+    }
+
+    /// Test nested contours become outline, hole, and island.
+    #[test]
+    fn test_grouping() {
+        let polygons = group_contours(vec![square(0.0, 10.0), square(2.0, 8.0), square(4.0, 6.0)]);
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].holes.len(), 1);
+        assert!(signed_area(&polygons[0].outer) > 0.0);
+        assert!(signed_area(&polygons[0].holes[0]) < 0.0);
+        assert!(polygons[1].holes.is_empty());
+    }
+}
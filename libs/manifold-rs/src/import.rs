@@ -0,0 +1,330 @@
+//! # Import
+//!
+//! Resolves `import()` file names to meshes and `surface()` file names to
+//! heightmaps.
+//!
+//! ## Files
+//!
+//! The browser has no filesystem, so files are registered up front with
+//! [`register_file`] and looked up by exact name. Native builds fall back
+//! to reading the path from disk when no file is registered under it.
+//! Names reach this module already resolved by the evaluator's
+//! [`FileProvider`](openscad_eval::FileProvider): with a filesystem
+//! provider, as a path next to the file calling `import()`, so a model
+//! finds its sibling files from any working directory.
+//!
+//! ## Formats
+//!
+//! The format is chosen by extension (case-insensitive):
+//!
+//! | Extension | Reader |
+//! |-----------|--------|
+//! | `.stl` | [`Mesh::from_stl`] (binary or ASCII) |
+//! | `.obj` | [`Mesh::from_obj`] |
+//! | `.off` | [`Mesh::from_off`] |
+//! | `.svg` | [`import_polygons`], 2D (see [`crate::cross_section::svg`]) |
+//!
+//! `surface()` reads heightmaps with [`import_heightmap`]:
+//!
+//! | Extension | Reader |
+//! |-----------|--------|
+//! | `.dat` | Text matrix of numbers, one row per line, `#` comment lines |
+//! | `.png` | Image luminance scaled to 0–100, top row at the highest Y |
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::import::register_file;
+//!
+//! let part = manifold_rs::render("cube(5);").unwrap();
+//! register_file("part.stl", part.to_stl_binary());
+//!
+//! let mesh = manifold_rs::render(r#"import("part.stl");"#).unwrap();
+//! assert_eq!(mesh.triangle_count(), 12);
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+use crate::cross_section::svg::svg_polygons;
+use crate::error::{ManifoldError, ManifoldResult};
+use crate::font::Polygon2D;
+use crate::mesh::Mesh;
+use crate::openscad::SegmentParams;
+
+/// File extensions `import()` can read.
+pub const IMPORT_FORMATS: &[&str] = &["stl", "obj", "off", "svg"];
+
+/// File extensions `surface()` can read.
+pub const SURFACE_FORMATS: &[&str] = &["dat", "png"];
+
+// =============================================================================
+// REGISTRY
+// =============================================================================
+
+/// Registered file contents keyed by name.
+fn registry() -> &'static RwLock<BTreeMap<String, Arc<[u8]>>> {
+    static REGISTRY: OnceLock<RwLock<BTreeMap<String, Arc<[u8]>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Register a file for `import()`, replacing any file with the same name.
+///
+/// ## Parameters
+///
+/// - `name`: Name the source passes to `import()`, e.g. `"part.stl"`
+/// - `data`: Raw file bytes
+pub fn register_file(name: &str, data: Vec<u8>) {
+    registry().write().unwrap_or_else(PoisonError::into_inner).insert(name.to_string(), data.into());
+}
+
+/// Names of registered files, sorted.
+pub fn registered_files() -> Vec<String> {
+    registry().read().unwrap_or_else(PoisonError::into_inner).keys().cloned().collect()
+}
+
+/// Remove all registered files.
+pub fn clear_files() {
+    registry().write().unwrap_or_else(PoisonError::into_inner).clear();
+}
+
+/// Look up a file: registered files first, then the filesystem (native only).
+fn read_file(name: &str) -> ManifoldResult<Arc<[u8]>> {
+    if let Some(data) = registry().read().unwrap_or_else(PoisonError::into_inner).get(name) {
+        return Ok(data.clone());
+    }
+    read_from_disk(name)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_from_disk(name: &str) -> ManifoldResult<Arc<[u8]>> {
+    std::fs::read(name)
+        .map(Into::into)
+        .map_err(|e| ManifoldError::ImportError(format!("cannot read {}: {}", name, e)))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_from_disk(name: &str) -> ManifoldResult<Arc<[u8]>> {
+    Err(ManifoldError::ImportError(format!("{} is not registered", name)))
+}
+
+// =============================================================================
+// READERS
+// =============================================================================
+
+/// Load an imported file as a mesh.
+///
+/// ## Parameters
+///
+/// - `name`: File name as written in `import()`
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::ImportError` if the file is missing, has an
+/// unsupported extension, or does not parse.
+pub fn import_mesh(name: &str) -> ManifoldResult<Mesh> {
+    // Keep in sync with IMPORT_FORMATS
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).unwrap_or_default();
+    let read = match extension.as_str() {
+        "stl" => Mesh::from_stl,
+        "obj" => Mesh::from_obj,
+        "off" => Mesh::from_off,
+        "svg" => return Err(ManifoldError::ImportError(format!("{} is 2D and has no mesh", name))),
+        _ => return Err(ManifoldError::ImportError(format!("unsupported import format: {}", name))),
+    };
+    read(&read_file(name)?).map_err(|e| match e {
+        ManifoldError::ImportError(message) => ManifoldError::ImportError(format!("{}: {}", name, message)),
+        e => e,
+    })
+}
+
+/// Load an imported 2D file as regions.
+///
+/// ## Parameters
+///
+/// - `name`: File name as written in `import()`
+/// - `center`: Center the bounding box on the origin
+/// - `params`: Segment parameters for curves
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::ImportError` if the file is missing, is not a
+/// 2D format, or does not parse.
+pub fn import_polygons(name: &str, center: bool, params: &SegmentParams) -> ManifoldResult<Vec<Polygon2D>> {
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).unwrap_or_default();
+    if extension != "svg" {
+        return Err(ManifoldError::ImportError(format!("unsupported 2D import format: {}", name)));
+    }
+    let mut regions = svg_polygons(&read_file(name)?, params).map_err(|e| match e {
+        ManifoldError::ImportError(message) => ManifoldError::ImportError(format!("{}: {}", name, message)),
+        e => e,
+    })?;
+    if center {
+        let (min, max) = regions.iter()
+            .flat_map(|region| region.outer.iter())
+            .fold(([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]), |(min, max), p| {
+                ([min[0].min(p[0]), min[1].min(p[1])], [max[0].max(p[0]), max[1].max(p[1])])
+            });
+        let [dx, dy] = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+        for p in regions.iter_mut().flat_map(|region| region.outer.iter_mut().chain(region.holes.iter_mut().flatten())) {
+            *p = [p[0] - dx, p[1] - dy];
+        }
+    }
+    Ok(regions)
+}
+
+/// Load a `surface()` file as heights by row.
+///
+/// Row `r`, column `c` is the height at `x = c`, `y = r`. Short `.dat`
+/// rows are padded with zeros.
+///
+/// ## Parameters
+///
+/// - `name`: File name as written in `surface()`
+/// - `invert`: For images, make dark pixels high instead of light ones
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::ImportError` if the file is missing, has an
+/// unsupported extension, does not parse, or holds no values.
+pub fn import_heightmap(name: &str, invert: bool) -> ManifoldResult<Vec<Vec<f64>>> {
+    // Keep in sync with SURFACE_FORMATS
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).unwrap_or_default();
+    let rows = match extension.as_str() {
+        "dat" => parse_dat(&read_file(name)?),
+        "png" => decode_png(&read_file(name)?, invert),
+        _ => return Err(ManifoldError::ImportError(format!("unsupported surface format: {}", name))),
+    };
+    rows.and_then(|rows| if rows.is_empty() { Err("no height values".to_string()) } else { Ok(rows) })
+        .map_err(|message| ManifoldError::ImportError(format!("{}: {}", name, message)))
+}
+
+/// Parse a `.dat` heightmap: whitespace-separated numbers, one row per
+/// line, skipping blank lines and lines starting with `#`.
+fn parse_dat(data: &[u8]) -> Result<Vec<Vec<f64>>, String> {
+    let text = std::str::from_utf8(data).map_err(|_| "not a text file".to_string())?;
+    let mut rows = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let row = line.split_whitespace()
+            .map(|word| word.parse::<f64>().ok().filter(|v| v.is_finite()))
+            .collect::<Option<Vec<f64>>>()
+            .ok_or_else(|| format!("line {}: expected numbers", number + 1))?;
+        rows.push(row);
+    }
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(width, 0.0);
+    }
+    Ok(rows)
+}
+
+/// Decode a PNG heightmap: luminance scaled to 0–100, with the image's
+/// top row last so the picture reads upright seen from above.
+fn decode_png(data: &[u8], invert: bool) -> Result<Vec<Vec<f64>>, String> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer).map_err(|e| e.to_string())?;
+
+    let (color, _) = reader.output_color_type();
+    let samples = color.samples();
+    let luminance = |pixel: &[u8]| match color {
+        png::ColorType::Rgb | png::ColorType::Rgba => {
+            0.2126 * f64::from(pixel[0]) + 0.7152 * f64::from(pixel[1]) + 0.0722 * f64::from(pixel[2])
+        }
+        _ => f64::from(pixel[0]),
+    };
+    Ok(buffer[..frame.buffer_size()]
+        .chunks_exact(frame.line_size)
+        .rev()
+        .map(|line| {
+            line.chunks_exact(samples)
+                .take(frame.width as usize)
+                .map(|pixel| {
+                    let height = 100.0 * luminance(pixel) / 255.0;
+                    if invert { 100.0 - height } else { height }
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test registered files are found by exact name.
+    #[test]
+    fn test_registered_file() {
+        let cube = crate::render("cube(2);").unwrap();
+        register_file("test_registered_file.STL", cube.to_stl_ascii().into_bytes());
+        assert!(registered_files().contains(&"test_registered_file.STL".to_string()));
+
+        let mesh = import_mesh("test_registered_file.STL").unwrap();
+        assert_eq!(mesh.triangle_count(), 12);
+
+        register_file("test_registered_file.obj", cube.to_obj().into_bytes());
+        register_file("test_registered_file.off", cube.to_off().into_bytes());
+        assert_eq!(import_mesh("test_registered_file.obj").unwrap().triangle_count(), 12);
+        assert_eq!(import_mesh("test_registered_file.off").unwrap().triangle_count(), 12);
+    }
+
+    /// Test missing files and unknown formats are reported.
+    #[test]
+    fn test_import_errors() {
+        assert!(matches!(import_mesh("no/such/file.stl"), Err(ManifoldError::ImportError(_))));
+        assert!(matches!(import_mesh("part.step"), Err(ManifoldError::ImportError(_))));
+    }
+
+    /// Test SVG files import as regions, centered on request.
+    #[test]
+    fn test_import_polygons() {
+        register_file("test_import_polygons.svg", br#"<svg viewBox="0 0 10 10" width="10mm" height="10mm"><rect x="2" y="2" width="4" height="2"/></svg>"#.to_vec());
+        let params = SegmentParams::default();
+        let regions = import_polygons("test_import_polygons.svg", true, &params).unwrap();
+        assert_eq!(regions.len(), 1);
+        for p in &regions[0].outer {
+            assert!((p[0].abs() - 2.0).abs() < 1e-9 && (p[1].abs() - 1.0).abs() < 1e-9, "{:?}", p);
+        }
+        assert!(import_polygons("part.stl", false, &params).is_err());
+        assert!(import_mesh("test_import_polygons.svg").is_err());
+    }
+
+    /// Test .dat heightmaps skip comments and pad short rows.
+    #[test]
+    fn test_heightmap_dat() {
+        register_file("test_heightmap.dat", b"# heights\n1 2 3\n\n4 5\n".to_vec());
+        let rows = import_heightmap("test_heightmap.dat", true).unwrap();
+        assert_eq!(rows, vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 0.0]]);
+
+        register_file("test_heightmap_bad.dat", b"1 two 3\n".to_vec());
+        assert!(matches!(import_heightmap("test_heightmap_bad.dat", false), Err(ManifoldError::ImportError(_))));
+        assert!(matches!(import_heightmap("map.txt", false), Err(ManifoldError::ImportError(_))));
+    }
+
+    /// Test PNG heightmaps scale luminance to 0–100 with the top row last.
+    #[test]
+    fn test_heightmap_png() {
+        // 2x2 grayscale, top row first: black, white / white, dark grey
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, 2, 2);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header().unwrap().write_image_data(&[0, 255, 255, 51]).unwrap();
+        register_file("test_heightmap.png", data);
+
+        let rows = import_heightmap("test_heightmap.png", false).unwrap();
+        assert_eq!(rows, vec![vec![100.0, 20.0], vec![0.0, 100.0]]);
+        let inverted = import_heightmap("test_heightmap.png", true).unwrap();
+        assert_eq!(inverted, vec![vec![0.0, 80.0], vec![100.0, 0.0]]);
+    }
+}
@@ -19,9 +19,11 @@
 //!   ├─ OpenSCAD Wrapper ($fn/$fa/$fs → circularSegments)
 //!   ├─ Manifold (3D solid operations)
 //!   ├─ CrossSection (2D polygon operations)
+//!   ├─ Font (text() glyph outlines)
+//!   ├─ Import (import() and surface() file registry and readers)
 //!   └─ Mesh (output format)
 //!       ↓
-//! wasm (Float32Array/Uint32Array)
+//! wasm (Float32Array or Float64Array/Uint32Array)
 //! ```
 //!
 //! ## Example
@@ -34,6 +36,15 @@
 //! assert!(!mesh.vertices.is_empty());
 //! ```
 //!
+//! Rust applications can also build models directly with [`Solid`]:
+//!
+//! ```rust
+//! use manifold_rs::Solid;
+//!
+//! let mesh = Solid::cube(10).union(Solid::sphere(5)).mesh().unwrap();
+//! assert!(!mesh.is_empty());
+//! ```
+//!
 //! ## Browser Safety
 //!
 //! This crate is designed for WebAssembly:
@@ -61,15 +72,46 @@ pub mod cross_section;
 /// OpenSCAD compatibility wrapper for $fn/$fa/$fs.
 pub mod openscad;
 
+/// Font engine for text() glyph outlines.
+pub mod font;
+
+/// Render-twice determinism self-check.
+pub mod determinism;
+
+/// File registry and readers for import() and surface().
+pub mod import;
+
+/// Fluent builder for CSG models without OpenSCAD source.
+pub mod solid;
+
+/// Thread-pool helpers for independent subtrees and boolean pairs.
+pub(crate) mod parallel;
+
 // =============================================================================
 // RE-EXPORTS
 // =============================================================================
 
 pub use error::ManifoldError;
 pub use mesh::Mesh;
+pub use mesh::output::RenderOutput;
 pub use manifold::Manifold;
 pub use cross_section::CrossSection;
+pub use solid::Solid;
 pub use openscad::SegmentParams;
+pub use openscad::outlines::{OutlineLoop, Winding};
+
+/// Crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Boolean backends compiled in, as named by
+/// [`CsgBackend`](openscad_eval::CsgBackend): BSP trees over the triangle
+/// mesh, intersection splitting, or a choice between them per boolean.
+pub const BACKENDS: &[&str] = &["bsp", "intersect", "auto"];
+
+/// Whether [`Reduction::Parallel`](manifold::boolean::Reduction::Parallel)
+/// runs on threads in this build: the `parallel` feature on a native
+/// target, or with `wasm-threads` on wasm32.
+pub const THREADS: bool = cfg!(all(feature = "parallel", any(not(target_arch = "wasm32"), feature = "wasm-threads")));
 
 // =============================================================================
 // PUBLIC API
@@ -103,12 +145,463 @@ pub use openscad::SegmentParams;
 /// Returns `ManifoldError::EvalError` if source code evaluation fails.
 /// Returns `ManifoldError::GeometryError` if mesh generation fails.
 pub fn render(source: &str) -> Result<Mesh, ManifoldError> {
+    render_with_libraries(source, &[])
+}
+
+/// Render OpenSCAD source code to a mesh with precompiled libraries.
+///
+/// Same as [`render`], but registers the given library bundles with the
+/// evaluator first so the source can call their modules and functions.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `libraries`: Bundles from `openscad_eval::compile_library`
+///
+/// ## Errors
+///
+/// Same as [`render`].
+pub fn render_with_libraries(
+    source: &str,
+    libraries: &[openscad_eval::LibraryBundle],
+) -> Result<Mesh, ManifoldError> {
+    render_with_eval_options(source, &openscad_eval::EvalOptions::with_libraries(libraries))
+}
+
+/// Render OpenSCAD source code with top-level variable overrides.
+///
+/// The equivalent of `openscad -D name=value`: overrides replace top-level
+/// assignments of the same name, so sizes or serial numbers can be stamped
+/// into a model without editing its source.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `overrides`: Values by variable name
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::render_with_overrides;
+/// use openscad_eval::options::parse_override;
+///
+/// let overrides = [parse_override("size=20").unwrap()].into_iter().collect();
+/// let mesh = render_with_overrides("size = 10; cube(size);", &overrides).unwrap();
+/// assert!(mesh.vertices.contains(&20.0));
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`render`].
+pub fn render_with_overrides(
+    source: &str,
+    overrides: &openscad_eval::Overrides,
+) -> Result<Mesh, ManifoldError> {
+    let options = openscad_eval::EvalOptions {
+        overrides: overrides.clone(),
+        ..openscad_eval::EvalOptions::default()
+    };
+    render_with_eval_options(source, &options)
+}
+
+/// Render OpenSCAD source code with full evaluation options.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Libraries, shims, overrides and limits for the evaluator,
+///   and the CSG settings for meshing
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::{render_with_eval_options, ManifoldError};
+/// use openscad_eval::{EvalOptions, LimitKind, Limits};
+///
+/// let options = EvalOptions {
+///     limits: Limits { max_triangles: Some(100), ..Limits::default() },
+///     ..EvalOptions::default()
+/// };
+/// let Err(ManifoldError::LimitExceeded(report)) = render_with_eval_options("sphere(10, $fn = 64);", &options) else {
+///     panic!("expected the triangle limit to trip");
+/// };
+/// assert_eq!(report.kind, LimitKind::Triangles);
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`render`], and `ManifoldError::LimitExceeded` if a limit in
+/// `options.limits` trips. Triangle, memory and time limits are checked
+/// once the mesh is complete, after `options.simplify`.
+/// `ManifoldError::Cancelled` if `options.cancel` is cancelled.
+pub fn render_with_eval_options(
+    source: &str,
+    options: &openscad_eval::EvalOptions,
+) -> Result<Mesh, ManifoldError> {
+    let started = openscad_eval::limits::now_ms();
+
     // Step 1: Evaluate source to geometry using openscad-eval
-    let evaluated = openscad_eval::evaluate(source)
-        .map_err(|e| ManifoldError::EvalError(e.to_string()))?;
-    
-    // Step 2: Convert GeometryNode to Mesh using OpenSCAD wrapper
-    openscad::from_ir::geometry_to_mesh(&evaluated.geometry)
+    let evaluated = openscad_eval::evaluate_with_options(source, options)
+        .map_err(ManifoldError::from)?;
+
+    // Step 2: Mesh the geometry and check the mesh limits
+    let _csg = manifold::boolean::CsgScope::enter(&options.csg);
+    let _span = openscad_eval::timing::stage_span("mesh");
+    let progress = options.progress.as_deref();
+    let mesh = openscad::from_ir::geometry_to_mesh_with_progress(&evaluated.geometry, progress, options.cancel.as_ref())?;
+    let mesh = manifold::simplify::simplify(mesh, &options.simplify);
+    let report = |done| {
+        if let Some(sink) = progress {
+            sink.report(openscad_eval::Progress { stage: openscad_eval::Stage::Mesh, done, total: 1 });
+        }
+    };
+    report(0);
+    check_mesh_limits(&mesh, &options.limits, started)?;
+    report(1);
+    Ok(mesh)
+}
+
+/// Render OpenSCAD source code with a chosen CSG backend and tuning.
+///
+/// The options apply to this render only; the process-wide settings of
+/// [`manifold::boolean`] are restored afterwards. `cache_size` is for
+/// render sessions and has no effect here.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Backend, auto threshold, weld tolerance and sphere style
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::manifold::boolean::{backend, Backend};
+/// use manifold_rs::render_with_options;
+/// use openscad_eval::{CsgBackend, CsgOptions, SphereStyle};
+///
+/// let options = CsgOptions {
+///     backend: Some(CsgBackend::Intersect),
+///     sphere: Some(SphereStyle::Icosphere),
+///     ..CsgOptions::default()
+/// };
+/// let mesh = render_with_options("difference() { cube(8, center = true); sphere(5, $fn = 30); }", &options).unwrap();
+/// assert!(mesh.validate().is_valid());
+/// assert_eq!(backend(), Backend::Bsp);
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`render`].
+pub fn render_with_options(
+    source: &str,
+    options: &openscad_eval::CsgOptions,
+) -> Result<Mesh, ManifoldError> {
+    let options = openscad_eval::EvalOptions {
+        csg: *options,
+        ..openscad_eval::EvalOptions::default()
+    };
+    render_with_eval_options(source, &options)
+}
+
+/// Render OpenSCAD source code, reporting progress and stopping when
+/// cancelled.
+///
+/// `progress` is called as each stage advances (see
+/// [`openscad_eval::progress`]): top-level statements evaluated, then
+/// geometry nodes meshed, then the finished mesh. Cancel `cancel` from
+/// another thread to abort the render.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `progress`: Receives the reports
+/// - `cancel`: Token to abort the render with
+///
+/// ## Example
+///
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use manifold_rs::render_with_progress;
+/// use openscad_eval::{CancellationToken, Progress, Stage};
+///
+/// let stages = Arc::new(Mutex::new(Vec::new()));
+/// let seen = Arc::clone(&stages);
+/// let token = CancellationToken::new();
+/// let mesh = render_with_progress(
+///     "difference() { cube(10); sphere(6); }",
+///     move |p: Progress| seen.lock().unwrap().push(p.stage),
+///     &token,
+/// ).unwrap();
+/// assert!(!mesh.is_empty());
+/// assert_eq!(stages.lock().unwrap().last(), Some(&Stage::Mesh));
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`render`], and `ManifoldError::Cancelled` once `cancel` is
+/// cancelled.
+pub fn render_with_progress(
+    source: &str,
+    progress: impl openscad_eval::ProgressSink + 'static,
+    cancel: &openscad_eval::CancellationToken,
+) -> Result<Mesh, ManifoldError> {
+    let options = openscad_eval::EvalOptions {
+        progress: Some(std::sync::Arc::new(progress)),
+        cancel: Some(cancel.clone()),
+        ..openscad_eval::EvalOptions::default()
+    };
+    render_with_eval_options(source, &options)
+}
+
+/// Mesh an already evaluated script.
+///
+/// For callers that evaluate first to keep the warnings and console
+/// messages of [`openscad_eval::EvaluatedAst`], which the `render*`
+/// functions drop.
+///
+/// ## Parameters
+///
+/// - `evaluated`: Result of `openscad_eval::evaluate_with_options`
+/// - `options`: The options evaluation ran with, for the mesh limits,
+///   `simplify` and `csg`
+/// - `started_ms`: `openscad_eval::limits::now_ms()` before evaluation,
+///   for the time limit
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_eval::{evaluate, limits::now_ms, EvalOptions};
+///
+/// let started = now_ms();
+/// let evaluated = evaluate("echo(\"hi\"); cube(1);").unwrap();
+/// let mesh = manifold_rs::render_evaluated(&evaluated, &EvalOptions::default(), started).unwrap();
+/// assert_eq!(mesh.triangle_count(), 12);
+/// assert_eq!(evaluated.echoes, vec!["\"hi\"".to_string()]);
+/// ```
+///
+/// ## Errors
+///
+/// `ManifoldError::GeometryError` if mesh generation fails, and
+/// `ManifoldError::LimitExceeded` if a triangle, memory or time limit
+/// trips.
+pub fn render_evaluated(
+    evaluated: &openscad_eval::EvaluatedAst,
+    options: &openscad_eval::EvalOptions,
+    started_ms: f64,
+) -> Result<Mesh, ManifoldError> {
+    let _csg = manifold::boolean::CsgScope::enter(&options.csg);
+    let _span = openscad_eval::timing::stage_span("mesh");
+    let mesh = openscad::from_ir::geometry_to_mesh(&evaluated.geometry)?;
+    let mesh = manifold::simplify::simplify(mesh, &options.simplify);
+    check_mesh_limits(&mesh, &options.limits, started_ms)?;
+    Ok(mesh)
+}
+
+/// Render OpenSCAD source code to a mesh and its preview layers.
+///
+/// Like [`render`], with the geometry under the `#` and `%` modifiers
+/// meshed into separate layers for a viewer to style (see
+/// [`mesh::output`]).
+///
+/// ## Example
+///
+/// ```rust
+/// let output = manifold_rs::render_preview("cube(10); %sphere(4);").unwrap();
+/// assert_eq!(output.solid.triangle_count(), 12);
+/// assert!(!output.transparent.is_empty());
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`render`].
+pub fn render_preview(source: &str) -> Result<RenderOutput, ManifoldError> {
+    render_timed(source, &openscad_eval::EvalOptions::default())
+}
+
+/// Render OpenSCAD source code to a mesh, its preview layers and the time
+/// each stage took.
+///
+/// Like [`render_with_eval_options`], returning a [`RenderOutput`] whose
+/// `timings` split the render into parsing, lowering to the AST,
+/// evaluation, each CSG node and meshing (see
+/// [`openscad_eval::timing`]).
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_eval::EvalOptions;
+///
+/// let output = manifold_rs::render_timed("difference() { cube(10); sphere(6); }", &EvalOptions::default()).unwrap();
+/// let timings = &output.timings;
+/// assert_eq!(timings.csg.len(), 1);
+/// assert_eq!(timings.csg[0].kind, "difference");
+/// assert!(timings.mesh_ms >= timings.csg[0].ms);
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`render_with_eval_options`].
+pub fn render_timed(source: &str, options: &openscad_eval::EvalOptions) -> Result<RenderOutput, ManifoldError> {
+    let started = openscad_eval::limits::now_ms();
+    let evaluated = openscad_eval::evaluate_with_options(source, options)
+        .map_err(ManifoldError::from)?;
+    render_evaluated_preview(&evaluated, options, started)
+}
+
+/// Mesh an already evaluated script and its preview layers.
+///
+/// Like [`render_evaluated`]; the limits apply to the solid only. The
+/// output's `timings` are those of `evaluated` with the mesh stage added,
+/// and its `stats` those of `evaluated`.
+///
+/// ## Errors
+///
+/// Same as [`render_evaluated`].
+pub fn render_evaluated_preview(
+    evaluated: &openscad_eval::EvaluatedAst,
+    options: &openscad_eval::EvalOptions,
+    started_ms: f64,
+) -> Result<RenderOutput, ManifoldError> {
+    let meshing = openscad_eval::limits::now_ms();
+    let _csg = manifold::boolean::CsgScope::enter(&options.csg);
+    let _span = openscad_eval::timing::stage_span("mesh");
+    let (mesh, csg) = openscad::from_ir::geometry_to_mesh_timed(&evaluated.geometry, None, None, None)?;
+    let solid = manifold::simplify::simplify(mesh, &options.simplify);
+    check_mesh_limits(&solid, &options.limits, started_ms)?;
+    let mut output = openscad::preview::PreviewTrees::of(&evaluated.geometry)?.mesh(solid)?;
+    output.timings = openscad_eval::Timings { csg, mesh_ms: openscad_eval::limits::now_ms() - meshing, ..evaluated.timings.clone() };
+    output.stats = evaluated.stats();
+    Ok(output)
+}
+
+/// Check a finished mesh against the triangle, memory and time limits.
+fn check_mesh_limits(mesh: &Mesh, limits: &openscad_eval::Limits, started_ms: f64) -> Result<(), ManifoldError> {
+    use openscad_eval::LimitKind;
+
+    // Positions are f64, everything else four bytes wide
+    let narrow = mesh.normals.len() + mesh.colors.as_ref().map_or(0, Vec::len) + mesh.indices.len()
+        + mesh.nodes.as_ref().map_or(0, Vec::len);
+    let bytes = mesh.vertices.len() * 8 + narrow * 4;
+    let elapsed = (openscad_eval::limits::now_ms() - started_ms).max(0.0) as u64;
+    limits.check(LimitKind::Triangles, mesh.triangle_count() as u64)
+        .and_then(|_| limits.check(LimitKind::Memory, bytes as u64))
+        .and_then(|_| limits.check(LimitKind::Time, elapsed))
+        .map_err(ManifoldError::LimitExceeded)
+}
+
+/// Render OpenSCAD source code straight to binary STL.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+///
+/// ## Example
+///
+/// ```rust
+/// let stl = manifold_rs::render_to_stl("cube(10);").unwrap();
+/// assert_eq!(stl.len(), 84 + 50 * 12);
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`render`].
+pub fn render_to_stl(source: &str) -> Result<Vec<u8>, ManifoldError> {
+    Ok(render(source)?.to_stl_binary())
+}
+
+/// Render OpenSCAD source code to 2D outline loops.
+///
+/// For laser cutting and CNC: a 2D result is returned as closed polylines
+/// instead of triangles, each outer loop counter-clockwise and followed by
+/// its clockwise holes. See [`openscad::outlines`].
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::{render_outlines, Winding};
+///
+/// let loops = render_outlines("polygon([[0,0],[10,0],[10,10],[0,10],[3,3],[7,3],[7,7],[3,7]], [[0,1,2,3],[4,5,6,7]]);").unwrap();
+/// assert_eq!(loops.len(), 2);
+/// assert_eq!(loops[1].winding, Winding::Clockwise);
+/// assert_eq!(loops[1].parent, Some(0));
+/// ```
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::EvalError` if source code evaluation fails.
+/// Returns `ManifoldError::GeometryError` if the result is 3D.
+pub fn render_outlines(source: &str) -> Result<Vec<OutlineLoop>, ManifoldError> {
+    render_outlines_with_eval_options(source, &openscad_eval::EvalOptions::default())
+}
+
+/// Render OpenSCAD source code to 2D outline loops with full evaluation
+/// options.
+///
+/// ## Errors
+///
+/// Same as [`render_outlines`].
+pub fn render_outlines_with_eval_options(
+    source: &str,
+    options: &openscad_eval::EvalOptions,
+) -> Result<Vec<OutlineLoop>, ManifoldError> {
+    let evaluated = openscad_eval::evaluate_with_options(source, options)
+        .map_err(ManifoldError::from)?;
+    openscad::outlines::geometry_to_outlines(&evaluated.geometry)
+}
+
+/// Render a 2D result of OpenSCAD source code to an SVG or DXF file.
+///
+/// For laser cutting: `projection()` and raw 2D shapes are written as
+/// their outline loops. See [`openscad::drawing`].
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `format`: One of [`openscad::drawing::EXPORT_2D_FORMATS`],
+///   case-insensitive
+///
+/// ## Example
+///
+/// ```rust
+/// let svg = manifold_rs::render_2d("projection() cube([10, 4, 3]);", "svg").unwrap();
+/// let svg = String::from_utf8(svg).unwrap();
+/// assert!(svg.contains(r#"width="10mm" height="4mm""#));
+/// assert!(svg.contains(r#"d="M0,0 L10,0 L10,-4 L0,-4 Z""#));
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`render_outlines`], and `ManifoldError::GeometryError` for an
+/// unknown format.
+pub fn render_2d(source: &str, format: &str) -> Result<Vec<u8>, ManifoldError> {
+    render_2d_with_eval_options(source, format, &openscad_eval::EvalOptions::default())
+}
+
+/// Render a 2D result to an SVG or DXF file with full evaluation options.
+///
+/// ## Errors
+///
+/// Same as [`render_2d`].
+pub fn render_2d_with_eval_options(
+    source: &str,
+    format: &str,
+    options: &openscad_eval::EvalOptions,
+) -> Result<Vec<u8>, ManifoldError> {
+    // Check the format before rendering
+    let formats = openscad::drawing::EXPORT_2D_FORMATS;
+    if !formats.contains(&format.to_ascii_lowercase().as_str()) {
+        return Err(ManifoldError::GeometryError(format!(
+            "unsupported 2D export format: {} (expected one of {})", format, formats.join(", "),
+        )));
+    }
+    let loops = render_outlines_with_eval_options(source, options)?;
+    openscad::drawing::export_outlines(&loops, format).ok_or_else(|| {
+        ManifoldError::GeometryError(format!("unsupported 2D export format: {}", format))
+    })
 }
 
 // =============================================================================
@@ -187,4 +680,120 @@ mod tests {
         assert!(mesh.vertex_count() > 100);
         assert!(mesh.triangle_count() > 50);
     }
+
+    /// Test 2D outlines skip triangulation and keep exact coordinates.
+    #[test]
+    fn test_render_outlines() {
+        let loops = render_outlines("translate([10, 0]) rotate(90) square([2, 1]);").unwrap();
+        assert_eq!(loops.len(), 1);
+        let expected = [[10.0, 0.0], [10.0, 2.0], [9.0, 2.0], [9.0, 0.0]];
+        for (p, q) in loops[0].points.iter().zip(expected) {
+            assert!((p[0] - q[0]).abs() < 1e-12 && (p[1] - q[1]).abs() < 1e-12, "{:?}", loops[0].points);
+        }
+
+        // Traced from the mesh of an operation without an exact path
+        let loops = render_outlines("hull() { square(10); translate([20, 0]) square(1); }").unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].points.len(), 5);
+
+        assert!(matches!(render_outlines("cube(1);"), Err(ManifoldError::GeometryError(_))));
+    }
+
+    /// Test 2D results render to drawings and 3D results are refused.
+    #[test]
+    fn test_render_2d() {
+        let dxf = render_2d("difference() { square(10); translate([5, 5]) square(2, center = true); }", "DXF").unwrap();
+        assert_eq!(String::from_utf8(dxf).unwrap().matches("POLYLINE").count(), 2);
+        let svg = String::from_utf8(render_2d("projection() sphere(5, $fn = 16);", "svg").unwrap()).unwrap();
+        assert!(svg.contains(r#"viewBox="-4.9"#), "{}", svg);
+        assert_eq!(svg.matches(" L").count(), 15);
+        assert!(matches!(render_2d("cube(1);", "svg"), Err(ManifoldError::GeometryError(_))));
+        let err = render_2d("square(1);", "stl").unwrap_err();
+        assert!(err.to_string().contains("svg, dxf"), "{}", err);
+    }
+
+    /// Test limit reports name the limit and, from the evaluator, the
+    /// statement that tripped it.
+    #[test]
+    fn test_limit_reports() {
+        use openscad_eval::{EvalOptions, LimitKind, Limits};
+
+        let render_limited = |source: &str, limits: Limits| {
+            let options = EvalOptions { limits, ..EvalOptions::default() };
+            match render_with_eval_options(source, &options) {
+                Err(ManifoldError::LimitExceeded(report)) => *report,
+                other => panic!("expected a limit error, got {:?}", other.map(|m| m.triangle_count())),
+            }
+        };
+
+        let source = "module row() {\n  for (i = [0:9]) translate([i, 0, 0]) cube(1);\n}\nfor (j = [0:9]) translate([0, j, 0]) row();\n";
+        let report = render_limited(source, Limits { max_nodes: Some(50), ..Limits::default() });
+        assert_eq!(report.kind, LimitKind::Nodes);
+        assert_eq!((report.limit, report.observed), (50, 51));
+        assert_eq!(report.to_string(), "cube() call at 2:40 exceeded 50 geometry nodes (reached 51)");
+
+        let report = render_limited(source, Limits { max_iterations: Some(25), ..Limits::default() });
+        assert_eq!(report.to_string(), "for-loop at 2:3 exceeded 25 iterations (reached 30)");
+
+        let report = render_limited("x = [for (i = [0:1e12]) i];", Limits { max_iterations: Some(10), ..Limits::default() });
+        assert_eq!(report.construct.map(|c| c.description), Some("assignment to x".to_string()));
+
+        let report = render_limited("cube(1);", Limits { max_memory_bytes: Some(100), ..Limits::default() });
+        assert_eq!((report.kind, report.construct), (LimitKind::Memory, None));
+
+        let report = render_limited("for (i = [0:1e5]) if (i < 0) cube(1);", Limits { max_time_ms: Some(0), ..Limits::default() });
+        assert_eq!(report.kind, LimitKind::Time);
+        // Blamed on the loop, or on nothing if the clock ticked before it began
+        assert!(report.construct.is_none_or(|c| c.description == "for-loop"));
+    }
+
+    /// Test every stage reports from 0 to its total, in pipeline order,
+    /// and a cancelled render fails from the mesh stage too.
+    #[test]
+    fn test_progress_and_cancel() {
+        use std::sync::{Arc, Mutex};
+        use openscad_eval::{CancellationToken, Progress, Stage};
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let source = "union() { cube(2); translate([1, 0, 0]) sphere(1); }\ncylinder(1, 1, 1);";
+        render_with_progress(source, move |p: Progress| sink.lock().unwrap().push(p), &CancellationToken::new()).unwrap();
+
+        let reports = reports.lock().unwrap();
+        let ends: Vec<Progress> = reports.iter().filter(|p| p.done == p.total).copied().collect();
+        assert_eq!(reports[0], Progress { stage: Stage::Evaluate, done: 0, total: 2 });
+        assert_eq!(ends.last(), Some(&Progress { stage: Stage::Mesh, done: 1, total: 1 }));
+        assert!(ends.contains(&Progress { stage: Stage::Csg, done: 6, total: 6 }));
+        let stages: Vec<Stage> = reports.iter().map(|p| p.stage).collect();
+        assert!(stages.windows(2).all(|w| w[0] <= w[1]));
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        assert!(matches!(render_with_progress("cube(1);", |_: Progress| {}, &cancel), Err(ManifoldError::Cancelled)));
+        let evaluated = openscad_eval::evaluate("cube(1);").unwrap();
+        let meshed = openscad::from_ir::geometry_to_mesh_with_progress(&evaluated.geometry, None, Some(&cancel));
+        assert!(matches!(meshed, Err(ManifoldError::Cancelled)));
+    }
+
+    /// Test timed renders give the same mesh, time each stage, and place
+    /// each CSG node in the tree, inside its parent's time.
+    #[test]
+    fn test_render_timed() {
+        let source = "module ring() { difference() { cylinder(2, r = 5); cylinder(2, r = 4); } }\n\
+                      union() { ring(); hull() { cube(1); translate([3, 0, 0]) cube(1); } }";
+        let output = render_timed(source, &openscad_eval::EvalOptions::default()).unwrap();
+        assert_eq!(output.solid.vertices, render(source).unwrap().vertices);
+
+        let timings = output.timings;
+        let nodes: Vec<_> = timings.csg.iter().map(|t| (t.node, t.depth, t.kind.as_str())).collect();
+        assert_eq!(nodes, [(0, 0, "union"), (1, 1, "difference"), (4, 1, "hull")]);
+        assert!(timings.csg.iter().all(|t| t.ms <= timings.csg[0].ms && t.ms >= 0.0));
+        assert!(timings.mesh_ms >= timings.csg[0].ms);
+        assert!(timings.parse_ms > 0.0 && timings.ast_ms > 0.0 && timings.eval_ms > 0.0);
+        assert!(timings.total_ms() >= timings.mesh_ms + timings.eval_ms);
+
+        // Meshing alone keeps only the timings evaluation recorded
+        let evaluated = openscad_eval::evaluate(source).unwrap();
+        assert_eq!(render_evaluated_preview(&evaluated, &Default::default(), 0.0).unwrap().timings.eval_ms, evaluated.timings.eval_ms);
+    }
 }
@@ -17,6 +17,8 @@
 //! - **Difference**: Keep (A outside B) ∪ (B inside A, reversed)
 //! - **Intersection**: Keep (A inside B) ∪ (B inside A)
 //!
+//! Faces lying on the other mesh's surface are kept by orientation, see [`Keep`].
+//!
 //! ## Limitations vs Manifold
 //!
 //! BSP splits polygons along arbitrary planes, causing ~44% more triangles than
@@ -27,10 +29,54 @@
 //! - Naylor, B. (1990). "Binary Space Partitioning Trees"
 //! - Thibault, W. C., & Naylor, B. F. (1987). "Set operations on polyhedra using BSP trees"
 
-use crate::mesh::Mesh;
-use super::geometry::{dot, point_inside_mesh};
+use std::collections::HashSet;
+
+use crate::parallel;
+use super::geometry::{classify_face, dot, FaceClass, IndexedMesh};
 use super::polygon::{BspPolygon, Plane, PolygonClassification, split_polygon};
 
+// =============================================================================
+// KEEP RULES
+// =============================================================================
+
+/// Which polygons a robust clip keeps, by their [`FaceClass`] against the
+/// other mesh.
+///
+/// Faces lying on the other mesh's surface are kept or dropped by
+/// orientation, so each shared face appears exactly once in the result:
+///
+/// | Operation | Side | Inside | Same | Opposite |
+/// |-----------|------|--------|------|----------|
+/// | Union | A | ✗ | ✓ | ✗ |
+/// | Union | B | ✗ | ✗ | ✗ |
+/// | Difference | A | ✗ | ✗ | ✓ |
+/// | Difference | B | ✓ | ✗ | ✗ |
+/// | Intersection | A | ✓ | ✓ | ✗ |
+/// | Intersection | B | ✓ | ✗ | ✗ |
+///
+/// Outside is always the opposite of inside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keep {
+    /// Keep polygons inside the other mesh (else those outside)
+    pub inside: bool,
+    /// Keep polygons on its surface facing the same way
+    pub same: bool,
+    /// Keep polygons on its surface facing the other way
+    pub opposite: bool,
+}
+
+impl Keep {
+    /// Whether a polygon classified as `class` is kept.
+    pub fn keeps(self, class: FaceClass) -> bool {
+        match class {
+            FaceClass::Inside => self.inside,
+            FaceClass::Outside => !self.inside,
+            FaceClass::Same => self.same,
+            FaceClass::Opposite => self.opposite,
+        }
+    }
+}
+
 // =============================================================================
 // BSP NODE
 // =============================================================================
@@ -88,14 +134,18 @@ impl BspNode {
         }
 
         // Use first polygon's plane as splitting plane
-        if self.plane.is_none() {
-            self.plane = Some(Plane::from_polygon(&polygons[0]));
-        }
-
-        let plane = self.plane.unwrap();
+        let plane = *self
+            .plane
+            .get_or_insert_with(|| Plane::from_polygon(&polygons[0]));
         let mut front_polys = Vec::new();
         let mut back_polys = Vec::new();
 
+        let mut polygons = polygons.into_iter();
+        if self.polygons.is_empty() {
+            // The plane's own polygon stays here even when it is not quite
+            // planar, so every level takes one and the recursion ends
+            self.polygons.extend(polygons.next());
+        }
         for poly in polygons {
             let (classification, front_part, back_part) = split_polygon(&poly, &plane);
             
@@ -123,13 +173,15 @@ impl BspNode {
 
         // Build subtrees
         if !front_polys.is_empty() {
-            self.front = Some(Box::new(BspNode::new()));
-            self.front.as_mut().unwrap().build(front_polys);
+            let mut front = Box::new(BspNode::new());
+            front.build(front_polys);
+            self.front = Some(front);
         }
 
         if !back_polys.is_empty() {
-            self.back = Some(Box::new(BspNode::new()));
-            self.back.as_mut().unwrap().build(back_polys);
+            let mut back = Box::new(BspNode::new());
+            back.build(back_polys);
+            self.back = Some(back);
         }
     }
 
@@ -139,28 +191,25 @@ impl BspNode {
     ///
     /// - `polygons`: Polygons to clip
     /// - `mesh`: Original mesh for point-in-mesh tests at leaves
-    /// - `keep_inside`: If true, keep polygons inside mesh; if false, keep outside
+    /// - `keep`: Which polygons to keep, see [`Keep`]
     ///
     /// ## Why Robust Classification?
     ///
     /// Standard BSP clipping relies on implicit leaf classification which can fail
-    /// for complex geometry. This method uses explicit ray-casting at leaf nodes.
+    /// for complex geometry. This method uses explicit ray-casting at leaf nodes,
+    /// which also tells faces lying on the mesh's surface apart.
     pub fn clip_polygons_robust(
         &self,
         polygons: Vec<BspPolygon>,
-        mesh: &Mesh,
-        keep_inside: bool,
+        mesh: &IndexedMesh,
+        keep: Keep,
     ) -> Vec<BspPolygon> {
-        if self.plane.is_none() {
+        let Some(plane) = self.plane else {
             // Leaf node: verify each polygon against mesh
-            return polygons.into_iter().filter(|poly| {
-                let center = poly.centroid();
-                let is_inside = point_inside_mesh(&center, mesh);
-                if keep_inside { is_inside } else { !is_inside }
-            }).collect();
-        }
-
-        let plane = self.plane.unwrap();
+            // Ray casts are independent per polygon
+            let keep = parallel::map(&polygons, |poly| keep.keeps(classify_face(&poly.centroid(), &poly.normal, mesh)));
+            return polygons.into_iter().zip(keep).filter_map(|(poly, keep)| keep.then_some(poly)).collect();
+        };
         let mut front_polys = Vec::new();
         let mut back_polys = Vec::new();
 
@@ -170,12 +219,15 @@ impl BspNode {
             
             match classification {
                 PolygonClassification::Coplanar => {
-                    // Route based on normal direction relative to plane
+                    // Cut where this node's faces end, so each piece lies
+                    // wholly on or off them, then route based on normal
+                    // direction relative to plane
                     let facing_same = dot(&poly.normal, &plane.normal) > 0.0;
+                    let pieces = self.split_coplanar(poly);
                     if facing_same {
-                        front_polys.push(poly);
+                        front_polys.extend(pieces);
                     } else {
-                        back_polys.push(poly);
+                        back_polys.extend(pieces);
                     }
                 }
                 PolygonClassification::Front => front_polys.push(poly),
@@ -196,35 +248,68 @@ impl BspNode {
             &self.front,
             front_polys,
             mesh,
-            keep_inside,
+            keep,
         );
         
         result.extend(self.clip_subtree_robust(
             &self.back,
             back_polys,
             mesh,
-            keep_inside,
+            keep,
         ));
 
         result
     }
 
+    /// Split a polygon coplanar with this node along the boundary edges of
+    /// the node's polygons.
+    ///
+    /// The leaves classify each piece by one point, so a piece must not
+    /// straddle the edge of a face it lies on. Edges shared by two of the
+    /// node's polygons are interior and do not cut.
+    fn split_coplanar(&self, poly: BspPolygon) -> Vec<BspPolygon> {
+        let key = |v: &[f64; 3]| v.map(f64::to_bits);
+        let edges: Vec<_> = self
+            .polygons
+            .iter()
+            .flat_map(|p| {
+                let n = p.vertices.len();
+                (0..n).map(move |i| (p.vertices[i], p.vertices[(i + 1) % n], p.normal))
+            })
+            .collect();
+        let directed: HashSet<_> = edges.iter().map(|(a, b, _)| (key(a), key(b))).collect();
+
+        let mut pieces = vec![poly];
+        for (a, b, normal) in edges {
+            if directed.contains(&(key(&b), key(&a))) {
+                continue;
+            }
+            let plane = Plane::through_edge(a, b, normal);
+            pieces = pieces
+                .into_iter()
+                .flat_map(|piece| match split_polygon(&piece, &plane) {
+                    (PolygonClassification::Spanning, front, back) => front.into_iter().chain(back).collect(),
+                    _ => vec![piece],
+                })
+                .collect();
+        }
+        pieces
+    }
+
     /// Helper to clip in a subtree (or at leaf if subtree is None).
     fn clip_subtree_robust(
         &self,
         subtree: &Option<Box<BspNode>>,
         polygons: Vec<BspPolygon>,
-        mesh: &Mesh,
-        keep_inside: bool,
+        mesh: &IndexedMesh,
+        keep: Keep,
     ) -> Vec<BspPolygon> {
         if let Some(ref node) = subtree {
-            node.clip_polygons_robust(polygons, mesh, keep_inside)
+            node.clip_polygons_robust(polygons, mesh, keep)
         } else {
             // Missing child = implicit leaf, check against mesh
             polygons.into_iter().filter(|poly| {
-                let center = poly.centroid();
-                let is_inside = point_inside_mesh(&center, mesh);
-                if keep_inside { is_inside } else { !is_inside }
+                keep.keeps(classify_face(&poly.centroid(), &poly.normal, mesh))
             }).collect()
         }
     }
@@ -260,11 +345,9 @@ impl BspNode {
     /// Standard polygon clipping (may misclassify at leaves).
     #[allow(dead_code)]
     pub fn clip_polygons(&self, polygons: Vec<BspPolygon>) -> Vec<BspPolygon> {
-        if self.plane.is_none() {
+        let Some(plane) = self.plane else {
             return polygons;
-        }
-
-        let plane = self.plane.unwrap();
+        };
         let mut front_polys = Vec::new();
         let mut back_polys = Vec::new();
 
@@ -5,7 +5,8 @@
 //! ## Contents
 //!
 //! - **Vector math**: `dot`, `cross`, `normalize`
-//! - **Ray casting**: `ray_triangle_intersect`, `point_inside_mesh`
+//! - **Ray casting**: `classify_point`, `classify_face`, `point_inside_mesh` (exact)
+//! - **Indexing**: `IndexedMesh`, a mesh with a BVH over its triangles
 //! - **Distance**: `point_to_triangle_distance`, `polygon_centroid`
 //!
 //! ## Design Principles
@@ -14,7 +15,9 @@
 //! - **DRY**: Reusable primitives used by multiple modules
 //! - **SRP**: Only geometry calculations, no mesh/BSP logic
 
+use crate::mesh::bvh::{Aabb, Bvh};
 use crate::mesh::Mesh;
+use super::predicates::{orient2d, orient3d};
 
 // =============================================================================
 // CONSTANTS
@@ -22,11 +25,9 @@ use crate::mesh::Mesh;
 
 /// Tolerance for floating-point comparisons.
 ///
-/// Used in ray-triangle intersection to avoid numerical issues.
-pub const EPSILON: f32 = 1e-5;
-
-/// Stricter tolerance for ray intersection.
-const RAY_EPSILON: f32 = 1e-7;
+/// Vertices this close to a splitting plane count as on it, which keeps
+/// the vertices created by earlier splits from being split again.
+pub const EPSILON: f64 = 1e-5;
 
 // =============================================================================
 // VECTOR MATH
@@ -42,7 +43,7 @@ const RAY_EPSILON: f32 = 1e-7;
 /// assert_eq!(dot(&a, &b), 0.0); // Perpendicular
 /// ```
 #[inline]
-pub fn dot(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+pub fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
     a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
 }
 
@@ -59,7 +60,7 @@ pub fn dot(a: &[f32; 3], b: &[f32; 3]) -> f32 {
 /// assert_eq!(z, [0.0, 0.0, 1.0]);
 /// ```
 #[inline]
-pub fn cross(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
+pub fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
     [
         a[1] * b[2] - a[2] * b[1],
         a[2] * b[0] - a[0] * b[2],
@@ -69,7 +70,7 @@ pub fn cross(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
 
 /// Compute length of a 3D vector.
 #[inline]
-pub fn length(v: &[f32; 3]) -> f32 {
+pub fn length(v: &[f64; 3]) -> f64 {
     (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
 }
 
@@ -77,7 +78,7 @@ pub fn length(v: &[f32; 3]) -> f32 {
 ///
 /// Returns `[0, 0, 1]` for zero-length vectors (safe fallback).
 #[inline]
-pub fn normalize(v: &[f32; 3]) -> [f32; 3] {
+pub fn normalize(v: &[f64; 3]) -> [f64; 3] {
     let len = length(v);
     if len > 1e-9 {
         [v[0] / len, v[1] / len, v[2] / len]
@@ -87,133 +88,229 @@ pub fn normalize(v: &[f32; 3]) -> [f32; 3] {
 }
 
 // =============================================================================
-// RAY CASTING
+// INDEXING
 // =============================================================================
 
-/// Möller–Trumbore ray-triangle intersection algorithm.
-///
-/// Tests if a ray from `origin` in direction `dir` intersects the triangle
-/// defined by vertices `v0`, `v1`, `v2`.
-///
-/// ## Algorithm
-///
-/// Uses barycentric coordinates to efficiently test intersection without
-/// computing the intersection point explicitly.
-///
-/// ## Parameters
-///
-/// - `origin`: Ray starting point
-/// - `dir`: Ray direction (should be normalized for consistent results)
-/// - `v0`, `v1`, `v2`: Triangle vertices in counter-clockwise order
-///
-/// ## Returns
-///
-/// `true` if ray intersects triangle in positive direction
-///
-/// ## Reference
-///
-/// Möller, T., & Trumbore, B. (1997). Fast, minimum storage ray-triangle intersection.
-pub fn ray_triangle_intersect(
-    origin: &[f32; 3],
-    dir: &[f32; 3],
-    v0: &[f32; 3],
-    v1: &[f32; 3],
-    v2: &[f32; 3],
-) -> bool {
-    let edge1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
-    let edge2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
-    
-    let h = cross(dir, &edge2);
-    let a = dot(&edge1, &h);
-    
-    // Ray parallel to triangle
-    if a.abs() < RAY_EPSILON {
-        return false;
-    }
-    
-    let f = 1.0 / a;
-    let s = [origin[0] - v0[0], origin[1] - v0[1], origin[2] - v0[2]];
-    let u = f * dot(&s, &h);
-    
-    // Outside triangle (u coordinate)
-    if !(0.0..=1.0).contains(&u) {
-        return false;
-    }
-    
-    let q = cross(&s, &edge1);
-    let v = f * dot(dir, &q);
-    
-    // Outside triangle (v coordinate or u+v > 1)
-    if v < 0.0 || u + v > 1.0 {
-        return false;
+/// A mesh together with a BVH over its triangles.
+///
+/// Built once per boolean operand, so each ray cast only tests the
+/// triangles whose boxes it passes instead of the whole mesh.
+#[derive(Debug, Clone)]
+pub struct IndexedMesh<'a> {
+    /// The indexed mesh.
+    pub mesh: &'a Mesh,
+    bvh: Bvh,
+}
+
+impl<'a> IndexedMesh<'a> {
+    /// Index the triangles of `mesh`.
+    pub fn new(mesh: &'a Mesh) -> Self {
+        Self { mesh, bvh: Bvh::from_mesh(mesh) }
     }
-    
-    let t = f * dot(&edge2, &q);
-    
-    // Intersection in positive direction only
-    t > RAY_EPSILON
 }
 
-/// Test if point is inside closed mesh using robust ray casting.
+// =============================================================================
+// RAY CASTING
+// =============================================================================
+
+/// Position of a point relative to a closed mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointClass {
+    /// Strictly inside the volume.
+    Inside,
+    /// Strictly outside the volume.
+    Outside,
+    /// Exactly on one of the mesh's triangles.
+    Boundary,
+}
+
+/// Classify a point against a closed mesh with exact ray casting.
 ///
 /// ## Algorithm
 ///
-/// Casts rays in 6 cardinal directions (±X, ±Y, ±Z) and counts intersections.
-/// Uses voting to handle edge cases where a ray might graze an edge/vertex.
-///
-/// A point is inside if the majority of rays (≥3 of 6) indicate odd intersection count.
-///
-/// ## Parameters
-///
-/// - `point`: Point to test
-/// - `mesh`: Closed mesh to test against
+/// Casts rays in the 6 cardinal directions (±X, ±Y, ±Z) and counts the
+/// triangles each crosses, deciding every crossing with exact predicates
+/// (see [`super::predicates`]) instead of a tolerance:
 ///
-/// ## Returns
+/// 1. Project the triangle along the ray's axis; the ray crosses it if the
+///    point is inside the projection (three `orient2d` signs)
+/// 2. The crossing is ahead of the point if the `orient3d` sign of the point
+///    against the triangle's plane says so
 ///
-/// `true` if point is inside the mesh
+/// A point exactly on a triangle is `Boundary`. A ray through an edge or
+/// vertex is moved off it by an infinitesimal shift of the point, so each
+/// edge is counted by exactly one of its triangles. Triangles seen edge-on
+/// along a ray are skipped, as their neighbours count for them. The rays
+/// then vote, odd counts meaning inside, which tolerates small defects in
+/// meshes that are not quite closed.
 ///
 /// ## Example
 ///
 /// ```ignore
-/// let center = [0.0, 0.0, 0.0];
-/// let inside = point_inside_mesh(&center, &unit_cube);
+/// let class = classify_point(&[0.0, 0.0, 5.0], &unit_cube); // Boundary on the top face
 /// ```
-pub fn point_inside_mesh(point: &[f32; 3], mesh: &Mesh) -> bool {
-    // Cast rays in 6 cardinal directions for robustness
-    const DIRS: [[f32; 3]; 6] = [
-        [1.0, 0.0, 0.0], [-1.0, 0.0, 0.0],
-        [0.0, 1.0, 0.0], [0.0, -1.0, 0.0],
-        [0.0, 0.0, 1.0], [0.0, 0.0, -1.0],
-    ];
-    
+pub fn classify_point(point: &[f64; 3], mesh: &IndexedMesh) -> PointClass {
     let mut inside_votes = 0;
-    
-    for dir in &DIRS {
-        let count = count_ray_intersections(point, dir, mesh);
-        
-        // Odd count = inside
-        if count % 2 == 1 {
-            inside_votes += 1;
+    for axis in 0..3 {
+        for forward in [true, false] {
+            match count_crossings(point, axis, forward, mesh) {
+                Some(count) => inside_votes += count % 2,
+                None => return PointClass::Boundary,
+            }
         }
     }
-    
-    // Majority voting (3+ of 6)
-    inside_votes >= 3
+
+    // Majority of the 6 rays, ties counting as inside
+    if inside_votes >= 3 {
+        PointClass::Inside
+    } else {
+        PointClass::Outside
+    }
+}
+
+/// Test if point is inside a closed mesh or on its surface; see [`classify_point`].
+pub fn point_inside_mesh(point: &[f64; 3], mesh: &IndexedMesh) -> bool {
+    classify_point(point, mesh) != PointClass::Outside
+}
+
+/// Position of a polygon relative to a closed mesh, judged at a point of it.
+///
+/// A polygon lying on the mesh's surface is `Same` or `Opposite` by
+/// whether its normal agrees with the surface there, which is what
+/// decides whether a shared face survives a boolean.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaceClass {
+    /// Strictly inside the volume.
+    Inside,
+    /// Strictly outside the volume.
+    Outside,
+    /// On the surface, facing the same way.
+    Same,
+    /// On the surface, facing the other way.
+    Opposite,
+}
+
+/// Classify a polygon with normal `normal` through `point` against a closed mesh.
+///
+/// A point on the surface takes the orientation of the triangle it lies
+/// on. A polygon that only touches the surface, crossing it at an edge
+/// rather than lying in a face, counts as `Inside`, as
+/// [`point_inside_mesh`] does.
+pub fn classify_face(point: &[f64; 3], normal: &[f64; 3], mesh: &IndexedMesh) -> FaceClass {
+    match classify_point(point, mesh) {
+        PointClass::Inside => FaceClass::Inside,
+        PointClass::Outside => FaceClass::Outside,
+        PointClass::Boundary => surface_orientation(point, normal, mesh),
+    }
+}
+
+/// Orientation of the surface under a point known to lie on it.
+fn surface_orientation(point: &[f64; 3], normal: &[f64; 3], mesh: &IndexedMesh) -> FaceClass {
+    // Cosine between the normals above which the faces count as coplanar
+    const COPLANAR_COS: f64 = 0.99;
+    let mut best = 0.0f64;
+
+    for t in mesh.bvh.overlapping(&Aabb::point(*point)) {
+        let (v0, v1, v2) = get_triangle_vertices(mesh.mesh, t as usize * 3);
+        if orient3d(v0, v1, v2, *point) != 0.0 {
+            continue;
+        }
+        // Containment in the projection along the normal's largest axis
+        let n = compute_triangle_normal(&v0, &v1, &v2);
+        let axis = (0..3).max_by(|&a, &b| n[a].abs().total_cmp(&n[b].abs())).unwrap_or(2);
+        let (u, w) = ((axis + 1) % 3, (axis + 2) % 3);
+        let project = |v: &[f64; 3]| [v[u], v[w]];
+        let area = orient2d(project(&v0), project(&v1), project(&v2));
+        let p = project(point);
+        let contains = [(v0, v1), (v1, v2), (v2, v0)].iter().all(|(a, b)| {
+            let e = orient2d(project(a), project(b), p);
+            e == 0.0 || e.signum() == area.signum()
+        });
+        if area == 0.0 || !contains {
+            continue;
+        }
+        let d = dot(normal, &n);
+        if d.abs() > best.abs() {
+            best = d;
+        }
+    }
+
+    if best > COPLANAR_COS {
+        FaceClass::Same
+    } else if best < -COPLANAR_COS {
+        FaceClass::Opposite
+    } else {
+        FaceClass::Inside
+    }
 }
 
-/// Count ray-mesh intersections for a single ray direction.
-fn count_ray_intersections(origin: &[f32; 3], dir: &[f32; 3], mesh: &Mesh) -> usize {
+/// Count the triangles crossed by the ray from `origin` along `axis`,
+/// or `None` if `origin` lies on one of them.
+fn count_crossings(origin: &[f64; 3], axis: usize, forward: bool, mesh: &IndexedMesh) -> Option<usize> {
+    // Project onto the two other axes in cyclic order, so a projected
+    // triangle's orientation is the sign of its normal's `axis` component
+    let (u, w) = ((axis + 1) % 3, (axis + 2) % 3);
+    let project = |v: &[f64; 3]| [v[u], v[w]];
+    let p = project(origin);
     let mut count = 0;
-    
-    for i in (0..mesh.indices.len()).step_by(3) {
-        let (v0, v1, v2) = get_triangle_vertices(mesh, i);
-        
-        if ray_triangle_intersect(origin, dir, &v0, &v1, &v2) {
+    let mut direction = [0.0; 3];
+    direction[axis] = if forward { 1.0 } else { -1.0 };
+
+    for t in mesh.bvh.along_ray(*origin, direction) {
+        let (v0, v1, v2) = get_triangle_vertices(mesh.mesh, t as usize * 3);
+        let tri = [v0, v1, v2];
+
+        // Cheap rejections: projection misses the ray, or triangle behind
+        let outside = |k: usize, value: f64| tri.iter().all(|v| v[k] < value) || tri.iter().all(|v| v[k] > value);
+        if outside(u, origin[u]) || outside(w, origin[w]) {
+            continue;
+        }
+        let behind = if forward { tri.iter().all(|v| v[axis] < origin[axis]) } else { tri.iter().all(|v| v[axis] > origin[axis]) };
+        if behind {
+            continue;
+        }
+
+        let area = orient2d(project(&v0), project(&v1), project(&v2));
+        if area == 0.0 {
+            continue;
+        }
+        let q = tri.map(|v| project(&v));
+        let edges = [(q[1], q[2]), (q[2], q[0]), (q[0], q[1])].map(|(a, b)| (orient2d(a, b, p), a, b));
+        if edges.iter().any(|&(e, _, _)| e != 0.0 && e.signum() != area.signum()) {
+            continue;
+        }
+
+        let side = orient3d(v0, v1, v2, *origin);
+        if side == 0.0 {
+            return None;
+        }
+        // Through an edge or vertex: decide for the shifted point
+        if edges.iter().any(|&(e, a, b)| e == 0.0 && perturbed_sign(a, b) != area.signum()) {
+            continue;
+        }
+        // The plane is reached moving along the ray when the point is
+        // behind it relative to the ray's direction
+        if side * area.signum() * direction[axis] < 0.0 {
             count += 1;
         }
     }
-    
-    count
+    Some(count)
+}
+
+/// Sign of `orient2d(a, b, p)` for `p` on the line through `a` and `b`,
+/// shifted to `p + (ε, ε²)` for an infinitesimal `ε`.
+///
+/// The shift is the same for every triangle, so the two triangles sharing
+/// an edge see the point on opposite sides of it.
+fn perturbed_sign(a: [f64; 2], b: [f64; 2]) -> f64 {
+    // d/dε of the determinant is a[1] − b[1], then b[0] − a[0] for ε²
+    if a[1] != b[1] {
+        if a[1] > b[1] { 1.0 } else { -1.0 }
+    } else if b[0] > a[0] {
+        1.0
+    } else {
+        -1.0
+    }
 }
 
 // =============================================================================
@@ -231,7 +328,7 @@ fn count_ray_intersections(origin: &[f32; 3], dir: &[f32; 3], mesh: &Mesh) -> us
 ///
 /// Tuple of three vertex positions `(v0, v1, v2)`
 #[inline]
-pub fn get_triangle_vertices(mesh: &Mesh, idx_offset: usize) -> ([f32; 3], [f32; 3], [f32; 3]) {
+pub fn get_triangle_vertices(mesh: &Mesh, idx_offset: usize) -> ([f64; 3], [f64; 3], [f64; 3]) {
     let i0 = mesh.indices[idx_offset] as usize * 3;
     let i1 = mesh.indices[idx_offset + 1] as usize * 3;
     let i2 = mesh.indices[idx_offset + 2] as usize * 3;
@@ -246,7 +343,7 @@ pub fn get_triangle_vertices(mesh: &Mesh, idx_offset: usize) -> ([f32; 3], [f32;
 /// Compute triangle normal from three vertices.
 ///
 /// Returns normalized cross product of edges (v1-v0) × (v2-v0).
-pub fn compute_triangle_normal(v0: &[f32; 3], v1: &[f32; 3], v2: &[f32; 3]) -> [f32; 3] {
+pub fn compute_triangle_normal(v0: &[f64; 3], v1: &[f64; 3], v2: &[f64; 3]) -> [f64; 3] {
     let edge1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
     let edge2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
     normalize(&cross(&edge1, &edge2))
@@ -266,11 +363,11 @@ pub fn compute_triangle_normal(v0: &[f32; 3], v1: &[f32; 3], v2: &[f32; 3]) -> [
 /// Used for boundary tolerance checks, not exact distance computation.
 #[allow(dead_code)]
 pub fn point_to_triangle_distance(
-    point: &[f32; 3],
-    v0: &[f32; 3],
-    v1: &[f32; 3],
-    v2: &[f32; 3],
-) -> f32 {
+    point: &[f64; 3],
+    v0: &[f64; 3],
+    v1: &[f64; 3],
+    v2: &[f64; 3],
+) -> f64 {
     let normal = compute_triangle_normal(v0, v1, v2);
     let to_point = [point[0] - v0[0], point[1] - v0[1], point[2] - v0[2]];
     dot(&to_point, &normal).abs()
@@ -280,8 +377,8 @@ pub fn point_to_triangle_distance(
 ///
 /// Iterates all triangles and returns minimum plane distance.
 #[allow(dead_code)]
-pub fn point_to_mesh_distance(point: &[f32; 3], mesh: &Mesh) -> f32 {
-    let mut min_dist = f32::MAX;
+pub fn point_to_mesh_distance(point: &[f64; 3], mesh: &Mesh) -> f64 {
+    let mut min_dist = f64::MAX;
     
     for i in (0..mesh.indices.len()).step_by(3) {
         let (v0, v1, v2) = get_triangle_vertices(mesh, i);
@@ -317,6 +414,40 @@ mod tests {
         assert!((z[2] - 1.0).abs() < 1e-6);
     }
 
+    /// Test exact point classification, including points on faces and edges.
+    #[test]
+    fn test_classify_point() {
+        let mut cube = Mesh::new();
+        crate::manifold::constructors::build_cube(&mut cube, [10.0, 10.0, 10.0], true);
+        let cube = IndexedMesh::new(&cube);
+        assert_eq!(classify_point(&[0.0, 0.0, 0.0], &cube), PointClass::Inside);
+        assert_eq!(classify_point(&[6.0, 0.0, 0.0], &cube), PointClass::Outside);
+        // Rays from these pass through the cube's diagonal edges
+        assert_eq!(classify_point(&[1.0, 1.0, 1.0], &cube), PointClass::Inside);
+        assert_eq!(classify_point(&[0.0, 0.0, 5.0], &cube), PointClass::Boundary);
+        assert_eq!(classify_point(&[5.0, 5.0, 5.0], &cube), PointClass::Boundary);
+        // Just off the face, closer than any tolerance
+        let above = f64::from_bits(5.0f64.to_bits() + 1);
+        assert_eq!(classify_point(&[1.0, 2.0, above], &cube), PointClass::Outside);
+        assert!(point_inside_mesh(&[0.0, 0.0, 5.0], &cube));
+    }
+
+    /// Test faces on the surface take its orientation.
+    #[test]
+    fn test_classify_face() {
+        let mut cube = Mesh::new();
+        crate::manifold::constructors::build_cube(&mut cube, [10.0, 10.0, 10.0], true);
+        let cube = IndexedMesh::new(&cube);
+        let up = [0.0, 0.0, 1.0];
+        assert_eq!(classify_face(&[1.0, 2.0, 5.0], &up, &cube), FaceClass::Same);
+        assert_eq!(classify_face(&[1.0, 2.0, 5.0], &[0.0, 0.0, -1.0], &cube), FaceClass::Opposite);
+        // On the top face's diagonal, shared by its two triangles
+        assert_eq!(classify_face(&[0.0, 0.0, 5.0], &up, &cube), FaceClass::Same);
+        // Crossing the top edge at an angle
+        assert_eq!(classify_face(&[0.0, 5.0, 5.0], &[0.0, 0.6, 0.8], &cube), FaceClass::Inside);
+        assert_eq!(classify_face(&[0.0, 0.0, 9.0], &up, &cube), FaceClass::Outside);
+    }
+
     #[test]
     fn test_normalize() {
         let v = normalize(&[3.0, 4.0, 0.0]);
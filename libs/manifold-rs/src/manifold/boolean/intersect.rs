@@ -0,0 +1,377 @@
+//! # Intersection Splitting
+//!
+//! A second boolean backend that cuts triangles only where the other
+//! mesh's surface actually crosses them.
+//!
+//! ## Why
+//!
+//! The BSP backend splits polygons along the plane of every polygon above
+//! them in the tree, whether or not that polygon comes anywhere near, so
+//! triangles far from the seam get cut too. Here a triangle is only cut
+//! by the triangles of the other mesh that cross it, and only in the
+//! pieces the crossing passes through.
+//!
+//! ## Algorithm
+//!
+//! ```text
+//! 1. Index the other mesh's triangles by bounding box
+//! 2. For each triangle, find the triangles crossing it (exact orient3d
+//!    signs) and the segment where each crosses
+//! 3. Cut the pieces the segment passes through along the crossing
+//!    triangle's plane; coplanar triangles cut along their edges
+//! 4. Classify each piece against the other mesh, as the BSP leaves do
+//! ```
+//!
+//! The pieces stay convex, since each cut is a plane split of a convex
+//! polygon.
+//!
+//! ## Selecting
+//!
+//! The backend is chosen at runtime with [`set_backend`], process-wide
+//! like [`super::set_reduction`]. [`Backend::Auto`] picks per boolean,
+//! by the operands' size (see [`super::set_auto_threshold`]):
+//!
+//! ```rust
+//! use manifold_rs::manifold::boolean::{backend, set_backend, union_all, Backend};
+//! use manifold_rs::manifold::constructors::build_cube;
+//! use manifold_rs::mesh::Mesh;
+//!
+//! let mut a = Mesh::new();
+//! build_cube(&mut a, [10.0, 10.0, 10.0], true);
+//! let mut b = a.clone();
+//! b.translate(5.0, 5.0, 5.0);
+//!
+//! set_backend(Backend::Intersect);
+//! let union = union_all(&[a, b]).unwrap();
+//! set_backend(Backend::Bsp);
+//! assert!(!union.is_empty());
+//! assert_eq!(backend(), Backend::Bsp);
+//! ```
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use openscad_eval::CsgBackend;
+
+use crate::mesh::bvh::{Aabb, Bvh};
+use crate::mesh::Mesh;
+use crate::parallel;
+use super::bsp::Keep;
+use super::geometry::{classify_face, IndexedMesh};
+use super::polygon::{mesh_to_triangles, split_polygon, BspPolygon, Plane, PolygonClassification};
+use super::predicates::orient3d;
+use super::settings::auto_threshold;
+
+// =============================================================================
+// BACKEND SELECTION
+// =============================================================================
+
+/// The selected backend, as its index in [`Backend::ALL`].
+static BACKEND: AtomicU8 = AtomicU8::new(0);
+
+/// How the polygons of each boolean operand are cut before classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Split along the planes of a BSP tree of the other operand.
+    #[default]
+    Bsp,
+    /// Split only where the other operand's triangles cross.
+    Intersect,
+    /// `Bsp` for small booleans, `Intersect` once the two operands have
+    /// [`auto_threshold`](super::auto_threshold) triangles together.
+    Auto,
+}
+
+impl Backend {
+    /// Every backend, in the order stored.
+    const ALL: [Backend; 3] = [Backend::Bsp, Backend::Intersect, Backend::Auto];
+
+    /// The backend a boolean of `a` and `b` runs with: `Bsp` or `Intersect`.
+    fn for_operands(self, a: &Mesh, b: &Mesh) -> Backend {
+        match self {
+            Backend::Auto if a.triangle_count() + b.triangle_count() >= auto_threshold() => Backend::Intersect,
+            Backend::Auto => Backend::Bsp,
+            other => other,
+        }
+    }
+}
+
+impl From<CsgBackend> for Backend {
+    fn from(backend: CsgBackend) -> Self {
+        match backend {
+            CsgBackend::Bsp => Backend::Bsp,
+            CsgBackend::Intersect => Backend::Intersect,
+            CsgBackend::Auto => Backend::Auto,
+        }
+    }
+}
+
+/// Select the backend for all subsequent booleans.
+///
+/// The setting is process-wide.
+pub fn set_backend(backend: Backend) {
+    let index = Backend::ALL.iter().position(|b| *b == backend).unwrap_or(0);
+    BACKEND.store(index as u8, Ordering::Relaxed);
+}
+
+/// The current backend setting.
+pub fn backend() -> Backend {
+    Backend::ALL.get(usize::from(BACKEND.load(Ordering::Relaxed))).copied().unwrap_or_default()
+}
+
+/// The backend a boolean of `a` and `b` runs with under the current
+/// setting.
+pub(super) fn backend_for(a: &Mesh, b: &Mesh) -> Backend {
+    backend().for_operands(a, b)
+}
+
+// =============================================================================
+// CLIPPING
+// =============================================================================
+
+/// Cut `subject`'s triangles where `other` crosses them and keep the
+/// pieces `keep` selects.
+pub(super) fn clip(subject: &Mesh, other: &Mesh, keep: Keep) -> Vec<BspPolygon> {
+    let index = TriangleIndex::new(mesh_to_triangles(other));
+    let solid = IndexedMesh::new(other);
+    let pieces: Vec<BspPolygon> = parallel::map_owned(mesh_to_triangles(subject), |tri| split_triangle(tri, &index))
+        .into_iter()
+        .flatten()
+        .collect();
+    let kept = parallel::map(&pieces, |piece| keep.keeps(classify_face(&piece.centroid(), &piece.normal, &solid)));
+    pieces.into_iter().zip(kept).filter_map(|(piece, kept)| kept.then_some(piece)).collect()
+}
+
+/// Cut one triangle by every triangle of the index crossing it.
+fn split_triangle(tri: BspPolygon, index: &TriangleIndex) -> Vec<BspPolygon> {
+    let bounds = Aabb::of(&tri.vertices);
+    let mut pieces = vec![tri.clone()];
+
+    for cutter in index.overlapping(&bounds) {
+        match crossing(&tri, cutter) {
+            Crossing::None => {}
+            Crossing::Segment(segment) => {
+                let plane = Plane::from_polygon(cutter);
+                pieces = cut(pieces, &plane, |piece| passes_through(&segment, piece));
+            }
+            Crossing::Coplanar => {
+                let n = cutter.vertices.len();
+                for i in 0..n {
+                    let plane = Plane::through_edge(cutter.vertices[i], cutter.vertices[(i + 1) % n], cutter.normal);
+                    pieces = cut(pieces, &plane, |_| true);
+                }
+            }
+        }
+    }
+    pieces
+}
+
+/// Split the pieces `selected` accepts by `plane`.
+fn cut(pieces: Vec<BspPolygon>, plane: &Plane, selected: impl Fn(&BspPolygon) -> bool) -> Vec<BspPolygon> {
+    pieces
+        .into_iter()
+        .flat_map(|piece| {
+            if !selected(&piece) {
+                return vec![piece];
+            }
+            match split_polygon(&piece, plane) {
+                (PolygonClassification::Spanning, front, back) => front.into_iter().chain(back).collect(),
+                _ => vec![piece],
+            }
+        })
+        .collect()
+}
+
+// =============================================================================
+// CROSSINGS
+// =============================================================================
+
+/// How a triangle of the other mesh meets a subject triangle.
+enum Crossing {
+    /// They do not cross.
+    None,
+    /// It crosses the subject's plane along this segment.
+    Segment([[f64; 3]; 2]),
+    /// Both lie in the same plane.
+    Coplanar,
+}
+
+/// Where `cutter` crosses the plane of the triangle `tri`.
+///
+/// The sides are decided exactly; the segment's end points are rounded.
+fn crossing(tri: &BspPolygon, cutter: &BspPolygon) -> Crossing {
+    let [a, b, c] = [tri.vertices[0], tri.vertices[1], tri.vertices[2]];
+    let [p, q, r] = [cutter.vertices[0], cutter.vertices[1], cutter.vertices[2]];
+    let sides = [p, q, r].map(|v| orient3d(a, b, c, v));
+    if sides.iter().all(|&s| s == 0.0) {
+        return Crossing::Coplanar;
+    }
+    let strictly_one_side = |s: &[f64; 3]| s.iter().all(|&x| x > 0.0) || s.iter().all(|&x| x < 0.0);
+    if strictly_one_side(&sides) || strictly_one_side(&[a, b, c].map(|v| orient3d(p, q, r, v))) {
+        return Crossing::None;
+    }
+
+    // Points of the cutter's boundary on the subject's plane
+    let vertices = [p, q, r];
+    let mut points: Vec<[f64; 3]> = Vec::with_capacity(2);
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        if sides[i] == 0.0 {
+            points.push(vertices[i]);
+        } else if sides[i] * sides[j] < 0.0 {
+            let t = sides[i] / (sides[i] - sides[j]);
+            points.push(std::array::from_fn(|k| vertices[i][k] + t * (vertices[j][k] - vertices[i][k])));
+        }
+    }
+    match points[..] {
+        [start, end] if start != end => Crossing::Segment([start, end]),
+        // Touching at a single point cuts nothing
+        _ => Crossing::None,
+    }
+}
+
+/// Whether a segment in a convex piece's plane passes through its interior
+/// or along its boundary.
+///
+/// Cyrus–Beck clipping against the piece's edges.
+fn passes_through(segment: &[[f64; 3]; 2], piece: &BspPolygon) -> bool {
+    let [start, end] = *segment;
+    let direction: [f64; 3] = std::array::from_fn(|k| end[k] - start[k]);
+    let normal = piece.normal;
+    let (mut enter, mut exit) = (0.0f64, 1.0f64);
+
+    let n = piece.vertices.len();
+    for i in 0..n {
+        let [a, b] = [piece.vertices[i], piece.vertices[(i + 1) % n]];
+        let edge: [f64; 3] = std::array::from_fn(|k| b[k] - a[k]);
+        // Outward normal of the edge within the piece's plane
+        let outward = [
+            edge[1] * normal[2] - edge[2] * normal[1],
+            edge[2] * normal[0] - edge[0] * normal[2],
+            edge[0] * normal[1] - edge[1] * normal[0],
+        ];
+        let offset = (0..3).map(|k| outward[k] * (start[k] - a[k])).sum::<f64>();
+        let rate = (0..3).map(|k| outward[k] * direction[k]).sum::<f64>();
+        if rate == 0.0 {
+            if offset > 0.0 {
+                return false;
+            }
+        } else if rate > 0.0 {
+            exit = exit.min(-offset / rate);
+        } else {
+            enter = enter.max(-offset / rate);
+        }
+        if enter >= exit {
+            return false;
+        }
+    }
+    true
+}
+
+// =============================================================================
+// SPATIAL INDEX
+// =============================================================================
+
+/// The other mesh's triangles under a BVH, for overlap queries.
+struct TriangleIndex {
+    triangles: Vec<BspPolygon>,
+    bvh: Bvh,
+}
+
+impl TriangleIndex {
+    fn new(triangles: Vec<BspPolygon>) -> Self {
+        let bounds: Vec<Aabb> = triangles.iter().map(|tri| Aabb::of(&tri.vertices)).collect();
+        Self { bvh: Bvh::new(&bounds), triangles }
+    }
+
+    /// Triangles whose bounds overlap `bounds`.
+    fn overlapping<'a>(&'a self, bounds: &Aabb) -> impl Iterator<Item = &'a BspPolygon> + 'a {
+        self.bvh.overlapping(bounds).into_iter().map(|i| &self.triangles[i as usize])
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifold::boolean::geometry::{cross, dot};
+    use crate::manifold::constructors::build_cube;
+
+    fn cube(size: f64, offset: [f64; 3]) -> Mesh {
+        let mut mesh = Mesh::new();
+        build_cube(&mut mesh, [size, size, size], true);
+        mesh.translate(offset[0], offset[1], offset[2]);
+        mesh
+    }
+
+    /// Test only triangles the other mesh crosses are cut.
+    #[test]
+    fn test_cuts_only_crossed_triangles() {
+        let a = cube(10.0, [0.0; 3]);
+        // Pokes through the +X face only
+        let b = cube(2.0, [5.0, 0.0, 0.0]);
+        let index = TriangleIndex::new(mesh_to_triangles(&b));
+        let pieces: Vec<usize> = mesh_to_triangles(&a).into_iter().map(|tri| split_triangle(tri, &index).len()).collect();
+        // The two +X triangles are cut, the other ten are untouched
+        assert_eq!(pieces.iter().filter(|&&n| n > 1).count(), 2);
+        assert_eq!(pieces.iter().filter(|&&n| n == 1).count(), 10);
+    }
+
+    /// Test `Auto` picks by the operands' triangles and the rest are kept.
+    #[test]
+    fn test_auto_backend() {
+        let small = cube(1.0, [0.0; 3]);
+        let mut large = Mesh::new();
+        let count = auto_threshold().div_ceil(small.triangle_count());
+        for i in 0..count {
+            large.merge(&cube(1.0, [i as f64 * 2.0, 0.0, 0.0]));
+        }
+        assert_eq!(Backend::Auto.for_operands(&small, &small), Backend::Bsp);
+        assert_eq!(Backend::Auto.for_operands(&large, &small), Backend::Intersect);
+        assert_eq!(Backend::Bsp.for_operands(&large, &small), Backend::Bsp);
+        assert_eq!(Backend::from(CsgBackend::Intersect), Backend::Intersect);
+    }
+
+    /// Test crossings are found exactly, including touching and coplanar triangles.
+    #[test]
+    fn test_crossing_kinds() {
+        let tri = BspPolygon::new(vec![[0.0, 0.0, 0.0], [4.0, 0.0, 0.0], [0.0, 4.0, 0.0]]);
+        let through = BspPolygon::new(vec![[1.0, 1.0, -1.0], [1.0, 1.0, 1.0], [3.0, -2.0, 0.0]]);
+        assert!(matches!(crossing(&tri, &through), Crossing::Segment(_)));
+        let above = BspPolygon::new(vec![[1.0, 1.0, 1.0], [2.0, 1.0, 1.0], [1.0, 2.0, 3.0]]);
+        assert!(matches!(crossing(&tri, &above), Crossing::None));
+        let touching = BspPolygon::new(vec![[1.0, 1.0, 0.0], [2.0, 1.0, 1.0], [1.0, 2.0, 1.0]]);
+        assert!(matches!(crossing(&tri, &touching), Crossing::None));
+        let flat = BspPolygon::new(vec![[1.0, 1.0, 0.0], [5.0, 1.0, 0.0], [1.0, 5.0, 0.0]]);
+        assert!(matches!(crossing(&tri, &flat), Crossing::Coplanar));
+    }
+
+    /// Test the kept pieces cover exactly the expected surface.
+    #[test]
+    fn test_clip_areas() {
+        let a = cube(10.0, [0.0; 3]);
+        let b = cube(10.0, [5.0, 5.0, 5.0]);
+        let area = |polygons: &[BspPolygon]| -> f64 {
+            polygons
+                .iter()
+                .map(|p| {
+                    (1..p.vertices.len() - 1)
+                        .map(|i| {
+                            let [o, x, y] = [p.vertices[0], p.vertices[i], p.vertices[i + 1]];
+                            let e1 = [x[0] - o[0], x[1] - o[1], x[2] - o[2]];
+                            let e2 = [y[0] - o[0], y[1] - o[1], y[2] - o[2]];
+                            0.5 * dot(&cross(&e1, &e2), &p.normal)
+                        })
+                        .sum::<f64>()
+                })
+                .sum()
+        };
+        // A outside B: five full faces minus three quarters, plus three L-shapes
+        let outside = clip(&a, &b, Keep { inside: false, same: true, opposite: false });
+        assert!((area(&outside) - 525.0).abs() < 1e-3, "{}", area(&outside));
+        let inside = clip(&a, &b, Keep { inside: true, same: true, opposite: false });
+        assert!((area(&inside) - 75.0).abs() < 1e-3, "{}", area(&inside));
+    }
+}
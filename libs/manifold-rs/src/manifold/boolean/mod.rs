@@ -21,11 +21,15 @@
 //! ```text
 //! 1. Convert meshes to BSP polygons
 //! 2. Build BSP tree from each mesh
-//! 3. Clip polygons using robust point-in-mesh tests
+//! 3. Clip polygons using robust point-in-mesh tests, keeping faces
+//!    shared by both meshes once, by orientation (see `bsp::Keep`)
 //! 4. Merge coplanar polygons to reduce fragmentation
 //! 5. Convert back to mesh with vertex welding
 //! ```
 //!
+//! With [`Backend::Intersect`], steps 2–3 instead cut each triangle only
+//! where the other mesh crosses it, then classify the pieces the same way.
+//!
 //! ## Example
 //!
 //! ```rust
@@ -61,8 +65,14 @@
 //!
 //! - `mod.rs` - Public API (this file)
 //! - `bsp.rs` - BSP tree implementation
+//! - `intersect.rs` - Intersection-splitting backend (see [`Backend`])
 //! - `polygon.rs` - Polygon operations (split, merge, convert)
 //! - `geometry.rs` - Math utilities (ray casting, point-in-mesh)
+//! - `predicates.rs` - Exact orientation and in-sphere tests
+//! - `reduce.rs` - Deterministic pairwise reduction of n-ary operations,
+//!   skipping disjoint operands
+//! - `settings.rs` - Auto backend threshold, weld tolerance, and
+//!   applying a render's `CsgOptions`
 //! - `tests.rs` - Integration tests
 
 // =============================================================================
@@ -71,7 +81,11 @@
 
 mod bsp;
 mod geometry;
+mod intersect;
 mod polygon;
+pub mod predicates;
+mod reduce;
+mod settings;
 
 #[cfg(test)]
 mod tests;
@@ -80,20 +94,30 @@ mod tests;
 // RE-EXPORTS (internal use only)
 // =============================================================================
 
-use bsp::BspNode;
+use bsp::{BspNode, Keep};
 use polygon::{mesh_to_polygons, polygons_to_mesh};
+use reduce::{mesh_bounds, reduce_pairwise, reduce_union};
+pub(crate) use geometry::{point_inside_mesh, IndexedMesh};
+pub use intersect::{backend, set_backend, Backend};
+pub use reduce::{reduction, set_reduction, Reduction};
+pub use settings::{auto_threshold, set_auto_threshold, set_weld_tolerance, weld_tolerance, CsgScope};
+pub use settings::{DEFAULT_AUTO_THRESHOLD, DEFAULT_WELD_TOLERANCE};
 
 // =============================================================================
 // PUBLIC API
 // =============================================================================
 
 use crate::error::ManifoldResult;
+use crate::mesh::bvh::Aabb;
 use crate::mesh::Mesh;
+use crate::parallel;
 
 /// Compute union of multiple meshes.
 ///
 /// Returns the combined volume of all input meshes. Overlapping regions
-/// are merged into a single surface.
+/// are merged into a single surface. Meshes are combined in a fixed
+/// pairwise tree (see [`Reduction`]), and only where their bounding
+/// boxes overlap; separate parts are appended as they are.
 ///
 /// ## Parameters
 ///
@@ -124,19 +148,16 @@ pub fn union_all(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
     match meshes.len() {
         0 => Ok(Mesh::new()),
         1 => Ok(meshes[0].clone()),
-        _ => {
-            let mut result = meshes[0].clone();
-            for mesh in &meshes[1..] {
-                result = bsp_union(&result, mesh)?;
-            }
-            Ok(result)
-        }
+        _ => reduce_union(meshes, bsp_union),
     }
 }
 
 /// Compute difference of meshes (first minus rest).
 ///
 /// Returns the first mesh with all subsequent meshes subtracted (carved out).
+/// The subtracted meshes are unioned first, as by [`union_all`], and
+/// removed in one step; those whose bounding boxes miss the first mesh's
+/// are left out.
 ///
 /// ## Parameters
 ///
@@ -168,11 +189,15 @@ pub fn difference_all(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
         0 => Ok(Mesh::new()),
         1 => Ok(meshes[0].clone()),
         _ => {
-            let mut result = meshes[0].clone();
-            for mesh in &meshes[1..] {
-                result = bsp_difference(&result, mesh)?;
+            let base = mesh_bounds(&meshes[0]);
+            let cutters: Vec<Mesh> = meshes[1..].iter()
+                .filter(|mesh| mesh_bounds(mesh).overlaps(&base))
+                .cloned()
+                .collect();
+            if cutters.is_empty() {
+                return Ok(meshes[0].clone());
             }
-            Ok(result)
+            bsp_difference(&meshes[0], &union_all(&cutters)?)
         }
     }
 }
@@ -180,7 +205,9 @@ pub fn difference_all(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
 /// Compute intersection of all meshes.
 ///
 /// Returns the volume common to all input meshes. Only regions inside
-/// all meshes are retained.
+/// all meshes are retained. Meshes are combined in a fixed pairwise tree
+/// (see [`Reduction`]); if their bounding boxes share no point, the
+/// result is empty without running any boolean.
 ///
 /// ## Parameters
 ///
@@ -213,11 +240,15 @@ pub fn intersection_all(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
         0 => Ok(Mesh::new()),
         1 => Ok(meshes[0].clone()),
         _ => {
-            let mut result = meshes[0].clone();
-            for mesh in &meshes[1..] {
-                result = bsp_intersection(&result, mesh)?;
+            // Boxes overlap pairwise exactly when they all share a point
+            let common = meshes.iter().map(mesh_bounds).reduce(|a, b| Aabb {
+                min: std::array::from_fn(|k| a.min[k].max(b.min[k])),
+                max: std::array::from_fn(|k| a.max[k].min(b.max[k])),
+            });
+            if common.is_some_and(|bounds| bounds.is_empty()) {
+                return Ok(Mesh::new());
             }
-            Ok(result)
+            reduce_pairwise(meshes, bsp_intersection)
         }
     }
 }
@@ -228,20 +259,13 @@ pub fn intersection_all(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
 
 /// BSP-based union: A ∪ B = (A outside B) ∪ (B outside A)
 fn bsp_union(a: &Mesh, b: &Mesh) -> ManifoldResult<Mesh> {
-    let mut tree_a = BspNode::new();
-    tree_a.build(mesh_to_polygons(a));
-    
-    let mut tree_b = BspNode::new();
-    tree_b.build(mesh_to_polygons(b));
-    
-    let polys_a = mesh_to_polygons(a);
-    let polys_b = mesh_to_polygons(b);
-    
-    // Keep A outside B
-    let result_a = tree_b.clip_polygons_robust(polys_a, b, false);
-    
-    // Keep B outside A
-    let result_b = tree_a.clip_polygons_robust(polys_b, a, false);
+    // Keep A outside B, and B outside A; shared faces once, from A
+    let (result_a, result_b) = clip_both(
+        a,
+        b,
+        Keep { inside: false, same: true, opposite: false },
+        Keep { inside: false, same: false, opposite: false },
+    );
     
     // Merge results
     let mut final_polys = result_a;
@@ -259,20 +283,14 @@ fn bsp_difference(a: &Mesh, b: &Mesh) -> ManifoldResult<Mesh> {
         return Ok(a.clone());
     }
     
-    let mut tree_a = BspNode::new();
-    tree_a.build(mesh_to_polygons(a));
-    
-    let mut tree_b = BspNode::new();
-    tree_b.build(mesh_to_polygons(b));
-    
-    let polys_a = mesh_to_polygons(a);
-    let polys_b = mesh_to_polygons(b);
-    
-    // Keep A outside B
-    let result_a = tree_b.clip_polygons_robust(polys_a, b, false);
-    
-    // Keep B inside A (will be reversed to form hole walls)
-    let mut result_b = tree_a.clip_polygons_robust(polys_b, a, true);
+    // Keep A outside B, and B inside A (will be reversed to form hole walls);
+    // A's faces touching B from outside stay, faces B removes go
+    let (result_a, mut result_b) = clip_both(
+        a,
+        b,
+        Keep { inside: false, same: false, opposite: true },
+        Keep { inside: true, same: false, opposite: false },
+    );
     
     // Reverse B polygons (flip normals for inside-out surfaces)
     for poly in &mut result_b {
@@ -292,20 +310,13 @@ fn bsp_intersection(a: &Mesh, b: &Mesh) -> ManifoldResult<Mesh> {
         return Ok(Mesh::new());
     }
     
-    let mut tree_a = BspNode::new();
-    tree_a.build(mesh_to_polygons(a));
-    
-    let mut tree_b = BspNode::new();
-    tree_b.build(mesh_to_polygons(b));
-    
-    let polys_a = mesh_to_polygons(a);
-    let polys_b = mesh_to_polygons(b);
-    
-    // Keep A inside B
-    let result_a = tree_b.clip_polygons_robust(polys_a, b, true);
-    
-    // Keep B inside A
-    let result_b = tree_a.clip_polygons_robust(polys_b, a, true);
+    // Keep A inside B, and B inside A; shared faces once, from A
+    let (result_a, result_b) = clip_both(
+        a,
+        b,
+        Keep { inside: true, same: true, opposite: false },
+        Keep { inside: true, same: false, opposite: false },
+    );
     
     // Merge results
     let mut final_polys = result_a;
@@ -313,3 +324,21 @@ fn bsp_intersection(a: &Mesh, b: &Mesh) -> ManifoldResult<Mesh> {
     
     Ok(polygons_to_mesh(&final_polys))
 }
+
+/// Clip A's polygons by B's tree and B's by A's, keeping the parts
+/// `keep_a` and `keep_b` select.
+///
+/// The two sides are independent and run in parallel under
+/// [`Reduction::Parallel`].
+fn clip_both(a: &Mesh, b: &Mesh, keep_a: Keep, keep_b: Keep) -> (Vec<polygon::BspPolygon>, Vec<polygon::BspPolygon>) {
+    let chosen = intersect::backend_for(a, b);
+    let clip = |subject: &Mesh, other: &Mesh, keep: Keep| match chosen {
+        Backend::Intersect => intersect::clip(subject, other, keep),
+        Backend::Bsp | Backend::Auto => {
+            let mut tree = BspNode::new();
+            tree.build(mesh_to_polygons(other));
+            tree.clip_polygons_robust(mesh_to_polygons(subject), &geometry::IndexedMesh::new(other), keep)
+        }
+    };
+    parallel::join(|| clip(a, b, keep_a), || clip(b, a, keep_b))
+}
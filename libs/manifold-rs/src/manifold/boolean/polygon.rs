@@ -7,7 +7,7 @@
 //! - **Data structures**: `BspPolygon`, `Plane`, `PolygonClassification`
 //! - **Split operations**: `split_polygon` for BSP tree construction
 //! - **Merge operations**: `merge_coplanar_polygons` for optimization
-//! - **Conversion**: `mesh_to_polygons`, `polygons_to_mesh`
+//! - **Conversion**: `mesh_to_polygons`, `mesh_to_triangles`, `polygons_to_mesh`
 //!
 //! ## Design Principles
 //!
@@ -16,9 +16,11 @@
 //! - **Testable**: Pure functions with clear inputs/outputs
 
 use crate::mesh::Mesh;
+use crate::parallel;
 use super::geometry::{dot, cross, normalize, compute_triangle_normal, EPSILON};
+use super::predicates::{orient2d, orient3d};
+use super::settings::weld_tolerance;
 use std::collections::HashMap;
-use rayon::prelude::*;
 
 // =============================================================================
 // DATA STRUCTURES
@@ -29,12 +31,18 @@ use rayon::prelude::*;
 /// The plane equation is: `dot(normal, point) = w`
 ///
 /// Points with `dot(normal, point) > w` are on the front (positive) side.
+///
+/// When the plane comes from a polygon it also keeps three of its
+/// vertices, so vertices exactly on the plane are recognised with
+/// [`orient3d`] however far they are from the origin.
 #[derive(Debug, Clone, Copy)]
 pub struct Plane {
     /// Unit normal vector pointing to front side
-    pub normal: [f32; 3],
+    pub normal: [f64; 3],
     /// Signed distance from origin: `w = dot(normal, point_on_plane)`
-    pub w: f32,
+    pub w: f64,
+    /// Three non-collinear points spanning the plane, if known
+    pub points: Option<[[f64; 3]; 3]>,
 }
 
 impl Plane {
@@ -43,8 +51,49 @@ impl Plane {
         Self {
             normal: poly.normal,
             w: dot(&poly.normal, &poly.vertices[0]),
+            points: spanning_triple(&poly.vertices),
         }
     }
+
+    /// Create the plane through edge `a → b` of a polygon with normal
+    /// `normal`, perpendicular to the polygon and facing away from it
+    /// (for counter-clockwise winding).
+    pub fn through_edge(a: [f64; 3], b: [f64; 3], normal: [f64; 3]) -> Self {
+        let edge = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let outward = normalize(&cross(&edge, &normal));
+        let c = [a[0] + normal[0], a[1] + normal[1], a[2] + normal[2]];
+        Self {
+            normal: outward,
+            w: dot(&outward, &a),
+            points: spanning_triple(&[a, b, c]),
+        }
+    }
+}
+
+/// The fan triangle of `vertices` with the largest area, if not all collinear.
+///
+/// Collinearity is decided exactly: three points are collinear when all
+/// three of their axis-plane projections are, by [`orient2d`].
+fn spanning_triple(vertices: &[[f64; 3]]) -> Option<[[f64; 3]; 3]> {
+    let a = vertices[0];
+    let sub = |p: [f64; 3]| [p[0] - a[0], p[1] - a[1], p[2] - a[2]];
+    let collinear = |p: [f64; 3], q: [f64; 3]| {
+        (0..3).all(|k| {
+            let project = |v: [f64; 3]| [v[k], v[(k + 1) % 3]];
+            orient2d(project(a), project(p), project(q)) == 0.0
+        })
+    };
+    vertices
+        .windows(2)
+        .skip(1)
+        .filter(|pair| !collinear(pair[0], pair[1]))
+        .map(|pair| {
+            let (u, v) = (sub(pair[0]), sub(pair[1]));
+            let c = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+            (c[0] * c[0] + c[1] * c[1] + c[2] * c[2], [a, pair[0], pair[1]])
+        })
+        .max_by(|x, y| x.0.total_cmp(&y.0))
+        .map(|(_, triple)| triple)
 }
 
 /// Polygon in BSP tree with vertices and precomputed normal.
@@ -57,31 +106,45 @@ impl Plane {
 #[derive(Debug, Clone)]
 pub struct BspPolygon {
     /// Polygon vertices in counter-clockwise order
-    pub vertices: Vec<[f32; 3]>,
+    pub vertices: Vec<[f64; 3]>,
     /// Unit normal vector (precomputed for efficiency)
-    pub normal: [f32; 3],
+    pub normal: [f64; 3],
+    /// Color of the face it came from, if the mesh had colors
+    pub color: Option<[f32; 4]>,
+    /// Geometry node of the triangle it came from, if the mesh had node ids
+    pub node: Option<u32>,
 }
 
 impl BspPolygon {
     /// Create polygon from vertices (computes normal automatically).
     #[allow(dead_code)]
-    pub fn new(vertices: Vec<[f32; 3]>) -> Self {
+    pub fn new(vertices: Vec<[f64; 3]>) -> Self {
         let normal = if vertices.len() >= 3 {
             compute_triangle_normal(&vertices[0], &vertices[1], &vertices[2])
         } else {
             [0.0, 0.0, 1.0]
         };
-        Self { vertices, normal }
+        Self { vertices, normal, color: None, node: None }
     }
 
     /// Create polygon with explicit normal.
-    pub fn with_normal(vertices: Vec<[f32; 3]>, normal: [f32; 3]) -> Self {
-        Self { vertices, normal }
+    pub fn with_normal(vertices: Vec<[f64; 3]>, normal: [f64; 3]) -> Self {
+        Self { vertices, normal, color: None, node: None }
+    }
+
+    /// The same polygon with a face color.
+    pub fn with_color(self, color: Option<[f32; 4]>) -> Self {
+        Self { color, ..self }
+    }
+
+    /// The same polygon with a source node.
+    pub fn with_node(self, node: Option<u32>) -> Self {
+        Self { node, ..self }
     }
 
     /// Compute centroid (average of all vertices).
-    pub fn centroid(&self) -> [f32; 3] {
-        let n = self.vertices.len() as f32;
+    pub fn centroid(&self) -> [f64; 3] {
+        let n = self.vertices.len() as f64;
         let sum = self.vertices.iter().fold([0.0, 0.0, 0.0], |acc, v| {
             [acc[0] + v[0], acc[1] + v[1], acc[2] + v[2]]
         });
@@ -116,7 +179,8 @@ pub enum PolygonClassification {
 ///
 /// ## Algorithm
 ///
-/// 1. Classify each vertex as front (+1), back (-1), or on-plane (0)
+/// 1. Classify each vertex as front (+1), back (-1), or on-plane (0):
+///    exactly coplanar with the plane's points, or within EPSILON of it
 /// 2. If all same classification, return that classification
 /// 3. If spanning, compute intersection points and split
 ///
@@ -141,7 +205,10 @@ pub fn split_polygon(
     
     for v in &poly.vertices {
         let dist = dot(&plane.normal, v) - plane.w;
-        if dist < -EPSILON {
+        let exact = plane.points.is_some_and(|[a, b, c]| orient3d(a, b, c, *v) == 0.0);
+        if exact {
+            types.push(0);
+        } else if dist < -EPSILON {
             types.push(-1);
             back_count += 1;
         } else if dist > EPSILON {
@@ -167,13 +234,13 @@ pub fn split_polygon(
     let (front_verts, back_verts) = compute_split_vertices(poly, plane, &types);
     
     let front_poly = if front_verts.len() >= 3 {
-        Some(BspPolygon::with_normal(front_verts, poly.normal))
+        Some(BspPolygon::with_normal(front_verts, poly.normal).with_color(poly.color).with_node(poly.node))
     } else {
         None
     };
     
     let back_poly = if back_verts.len() >= 3 {
-        Some(BspPolygon::with_normal(back_verts, poly.normal))
+        Some(BspPolygon::with_normal(back_verts, poly.normal).with_color(poly.color).with_node(poly.node))
     } else {
         None
     };
@@ -186,7 +253,7 @@ fn compute_split_vertices(
     poly: &BspPolygon,
     plane: &Plane,
     types: &[i32],
-) -> (Vec<[f32; 3]>, Vec<[f32; 3]>) {
+) -> (Vec<[f64; 3]>, Vec<[f64; 3]>) {
     let mut front_verts = Vec::new();
     let mut back_verts = Vec::new();
     
@@ -217,7 +284,7 @@ fn compute_split_vertices(
 }
 
 /// Compute intersection point of edge with plane.
-fn compute_plane_intersection(v1: &[f32; 3], v2: &[f32; 3], plane: &Plane) -> [f32; 3] {
+fn compute_plane_intersection(v1: &[f64; 3], v2: &[f64; 3], plane: &Plane) -> [f64; 3] {
     let edge = [v2[0] - v1[0], v2[1] - v1[1], v2[2] - v1[2]];
     let denom = dot(&plane.normal, &edge);
     
@@ -270,14 +337,14 @@ pub fn merge_coplanar_polygons(polygons: Vec<BspPolygon>) -> Vec<BspPolygon> {
 
     // Sort groups by key for deterministic processing
     let mut sorted_groups: Vec<_> = groups.into_iter().collect();
-    sorted_groups.par_sort_by_key(|(key, _)| *key);
-
-    let result: Vec<_> = sorted_groups
-        .into_par_iter()
-        .flat_map(|(_key, group)| merge_polygon_group(group))
-        .collect();
-    
-    result
+    sorted_groups.sort_by_key(|(key, _)| *key);
+
+    parallel::map_owned(sorted_groups, |(_key, group)| merge_polygon_group(group))
+        .into_iter()
+        .flatten()
+        // Merging can collapse a group to nothing usable
+        .filter(|poly| poly.vertices.len() >= 3)
+        .collect()
 }
 
 /// Uses integer quantization to handle floating-point imprecision.
@@ -343,11 +410,18 @@ fn merge_polygon_group(mut group: Vec<BspPolygon>) -> Vec<BspPolygon> {
 ///
 /// Finds a shared edge (same vertices in reverse order) and merges
 /// the polygons by removing the shared edge and concatenating vertices.
+/// Polygons of different colors or nodes are never merged, nor polygons whose
+/// union would not be convex: BSP splitting and the fan triangulation
+/// of [`polygons_to_mesh`] both rely on convex polygons.
 ///
 /// ## Returns
 ///
-/// `Some(merged)` if polygons share an edge, `None` otherwise
+/// `Some(merged)` if polygons share an edge and merge into a convex
+/// polygon, `None` otherwise
 fn try_merge_polygons(p1: &BspPolygon, p2: &BspPolygon) -> Option<BspPolygon> {
+    if p1.color != p2.color || p1.node != p2.node {
+        return None;
+    }
     let n1 = p1.vertices.len();
     let n2 = p2.vertices.len();
     
@@ -362,7 +436,8 @@ fn try_merge_polygons(p1: &BspPolygon, p2: &BspPolygon) -> Option<BspPolygon> {
             
             // Shared edge: a1==b2 and b1==a2 (reversed)
             if vertices_equal(a1, b2) && vertices_equal(b1, a2) {
-                return Some(merge_at_edge(p1, p2, i, j));
+                let merged = merge_at_edge(p1, p2, i, j);
+                return is_convex(&merged.vertices, &p1.normal).then_some(merged);
             }
         }
     }
@@ -370,13 +445,13 @@ fn try_merge_polygons(p1: &BspPolygon, p2: &BspPolygon) -> Option<BspPolygon> {
     None
 }
 
-/// Check if two vertices are approximately equal.
-const VERTEX_EPSILON: f32 = 1e-4;
-
-fn vertices_equal(a: &[f32; 3], b: &[f32; 3]) -> bool {
-    (a[0] - b[0]).abs() < VERTEX_EPSILON &&
-    (a[1] - b[1]).abs() < VERTEX_EPSILON &&
-    (a[2] - b[2]).abs() < VERTEX_EPSILON
+/// Check if two vertices are approximately equal, within the weld
+/// tolerance.
+fn vertices_equal(a: &[f64; 3], b: &[f64; 3]) -> bool {
+    let tolerance = f64::from(weld_tolerance());
+    (a[0] - b[0]).abs() < tolerance &&
+    (a[1] - b[1]).abs() < tolerance &&
+    (a[2] - b[2]).abs() < tolerance
 }
 
 /// Merge two polygons at their shared edge.
@@ -395,21 +470,43 @@ fn merge_at_edge(p1: &BspPolygon, p2: &BspPolygon, edge1: usize, edge2: usize) -
         merged.push(p2.vertices[(edge2 + k) % n2]);
     }
     
-    // Add p1 vertices after shared edge end
-    for k in (edge1 + 2)..n1 {
+    // Add p1 vertices from the shared edge end on
+    for k in (edge1 + 1)..n1 {
         merged.push(p1.vertices[k]);
     }
     
     // Remove collinear vertices
     let cleaned = remove_collinear_vertices(&merged);
     
-    BspPolygon::with_normal(cleaned, p1.normal)
+    BspPolygon::with_normal(cleaned, p1.normal).with_color(p1.color).with_node(p1.node)
+}
+
+/// Check a planar polygon is convex and wound counter-clockwise about
+/// `normal`: every corner turns the same way as the normal, and no corner
+/// repeats.
+fn is_convex(vertices: &[[f64; 3]], normal: &[f64; 3]) -> bool {
+    let n = vertices.len();
+    if n < 3 {
+        return false;
+    }
+    for i in 0..n {
+        let (prev, curr, next) = (&vertices[(i + n - 1) % n], &vertices[i], &vertices[(i + 1) % n]);
+        let v1 = [curr[0] - prev[0], curr[1] - prev[1], curr[2] - prev[2]];
+        let v2 = [next[0] - curr[0], next[1] - curr[1], next[2] - curr[2]];
+        if dot(&cross(&v1, &v2), normal) <= 0.0 {
+            return false;
+        }
+        if vertices[i + 1..].iter().any(|other| vertices_equal(curr, other)) {
+            return false;
+        }
+    }
+    true
 }
 
 /// Remove collinear vertices from polygon boundary.
 ///
 /// Vertices are collinear if the cross product of adjacent edges is ~zero.
-fn remove_collinear_vertices(vertices: &[[f32; 3]]) -> Vec<[f32; 3]> {
+fn remove_collinear_vertices(vertices: &[[f64; 3]]) -> Vec<[f64; 3]> {
     if vertices.len() < 3 {
         return vertices.to_vec();
     }
@@ -448,6 +545,15 @@ fn remove_collinear_vertices(vertices: &[[f32; 3]]) -> Vec<[f32; 3]> {
 ///
 /// Also performs initial coplanar merge to reduce BSP tree depth.
 pub fn mesh_to_polygons(mesh: &Mesh) -> Vec<BspPolygon> {
+    // Pre-merge to reduce BSP fragmentation
+    merge_coplanar_polygons(mesh_to_triangles(mesh))
+}
+
+/// Convert mesh triangles to BSP polygons, one per triangle.
+///
+/// A colored mesh gives each polygon the color of its triangle's first
+/// vertex; a mesh with node ids gives it its triangle's node.
+pub fn mesh_to_triangles(mesh: &Mesh) -> Vec<BspPolygon> {
     let mut polygons = Vec::new();
     
     for i in (0..mesh.indices.len()).step_by(3) {
@@ -460,12 +566,17 @@ pub fn mesh_to_polygons(mesh: &Mesh) -> Vec<BspPolygon> {
         let v2 = [mesh.vertices[i2], mesh.vertices[i2 + 1], mesh.vertices[i2 + 2]];
         
         let normal = compute_triangle_normal(&v0, &v1, &v2);
+        let color = mesh.colors.as_ref().map(|c| {
+            let i = mesh.indices[i] as usize * 4;
+            [c[i], c[i + 1], c[i + 2], c[i + 3]]
+        });
         
-        polygons.push(BspPolygon::with_normal(vec![v0, v1, v2], normal));
+        let node = mesh.node_of(i / 3);
+
+        polygons.push(BspPolygon::with_normal(vec![v0, v1, v2], normal).with_color(color).with_node(node));
     }
     
-    // Pre-merge to reduce BSP fragmentation
-    merge_coplanar_polygons(polygons)
+    polygons
 }
 
 /// Convert BSP polygons back to mesh with vertex welding.
@@ -475,27 +586,44 @@ pub fn mesh_to_polygons(mesh: &Mesh) -> Vec<BspPolygon> {
 /// 1. Merge coplanar polygons
 /// 2. Fan-triangulate each polygon
 /// 3. Weld identical vertices
+/// 4. Canonicalize: split T-junctions, drop degenerate triangles
+///
+/// If any polygon has a color, the mesh gets per-vertex colors, with
+/// [`Mesh::DEFAULT_COLOR`] for the uncolored ones; likewise for node ids,
+/// with [`Mesh::NO_NODE`].
 pub fn polygons_to_mesh(polygons: &[BspPolygon]) -> Mesh {
     let merged = merge_coplanar_polygons(polygons.to_vec());
     
     let mut mesh = Mesh::new();
+    if merged.iter().any(|poly| poly.color.is_some()) {
+        mesh.colors = Some(Vec::new());
+    }
+    if merged.iter().any(|poly| poly.node.is_some()) {
+        mesh.nodes = Some(Vec::new());
+    }
     let mut welder = VertexWelder::new();
     
     for poly in &merged {
         if poly.vertices.len() < 3 {
             continue;
         }
+        let color = poly.color.unwrap_or(Mesh::DEFAULT_COLOR);
+        let normal = poly.normal.map(|c| c as f32);
         
         // Fan triangulation from first vertex
-        let idx0 = welder.add(&mut mesh, poly.vertices[0], poly.normal);
+        let idx0 = welder.add(&mut mesh, poly.vertices[0], normal, color);
         
         for i in 1..poly.vertices.len() - 1 {
-            let idx1 = welder.add(&mut mesh, poly.vertices[i], poly.normal);
-            let idx2 = welder.add(&mut mesh, poly.vertices[i + 1], poly.normal);
+            let idx1 = welder.add(&mut mesh, poly.vertices[i], normal, color);
+            let idx2 = welder.add(&mut mesh, poly.vertices[i + 1], normal, color);
             mesh.add_triangle(idx0, idx1, idx2);
+            if let (Some(last), Some(node)) = (mesh.nodes.as_mut().and_then(|n| n.last_mut()), poly.node) {
+                *last = node;
+            }
         }
     }
     
+    mesh.canonicalize(f64::from(weld_tolerance()));
     mesh
 }
 
@@ -510,26 +638,30 @@ pub fn polygons_to_mesh(polygons: &[BspPolygon]) -> Mesh {
 pub struct VertexWelder {
     /// Spatial hash: quantized position -> list of vertex indices
     cache: HashMap<[i32; 3], Vec<u32>>,
+    /// Weld distance, the current weld tolerance when created.
+    tolerance: f64,
 }
 
 impl VertexWelder {
     /// Create new vertex welder.
     pub fn new() -> Self {
-        Self { cache: HashMap::new() }
+        Self { cache: HashMap::new(), tolerance: f64::from(weld_tolerance()) }
     }
     
     /// Add vertex to mesh, returning index (may reuse existing vertex).
     ///
     /// ## Welding Criteria
     ///
-    /// - Position within 1e-4 units
+    /// - Position within the weld tolerance (see
+    ///   [`set_weld_tolerance`](super::set_weld_tolerance))
     /// - Normal dot product > 0.9 (within ~25°)
-    pub fn add(&mut self, mesh: &mut Mesh, pos: [f32; 3], normal: [f32; 3]) -> u32 {
+    /// - Same color, if the mesh has colors; `color` is ignored otherwise
+    pub fn add(&mut self, mesh: &mut Mesh, pos: [f64; 3], normal: [f32; 3], color: [f32; 4]) -> u32 {
         // Quantize position for spatial hash
         let key = [
-            (pos[0] * 10000.0) as i32,
-            (pos[1] * 10000.0) as i32,
-            (pos[2] * 10000.0) as i32,
+            (pos[0] / self.tolerance) as i32,
+            (pos[1] / self.tolerance) as i32,
+            (pos[2] / self.tolerance) as i32,
         ];
         
         // Check for existing vertex at this position
@@ -539,12 +671,14 @@ impl VertexWelder {
                 let v = [mesh.vertices[i], mesh.vertices[i+1], mesh.vertices[i+2]];
                 let dist_sq = (v[0]-pos[0]).powi(2) + (v[1]-pos[1]).powi(2) + (v[2]-pos[2]).powi(2);
                 
-                if dist_sq < 1e-8 {
+                if dist_sq < self.tolerance * self.tolerance {
                     let n = [mesh.normals[i], mesh.normals[i+1], mesh.normals[i+2]];
                     let dot_n = n[0]*normal[0] + n[1]*normal[1] + n[2]*normal[2];
                     
+                    let same_color = mesh.colors.as_ref().is_none_or(|c| c[idx as usize * 4..idx as usize * 4 + 4] == color);
+                    
                     // Weld if normals are similar (~25° threshold)
-                    if dot_n > 0.9 {
+                    if dot_n > 0.9 && same_color {
                         return idx;
                     }
                 }
@@ -553,6 +687,9 @@ impl VertexWelder {
         
         // Add new vertex
         let idx = mesh.add_vertex(pos[0], pos[1], pos[2], normal[0], normal[1], normal[2]);
+        if let Some(colors) = mesh.colors.as_mut() {
+            colors.extend_from_slice(&color);
+        }
         self.cache.entry(key).or_default().push(idx);
         idx
     }
@@ -578,7 +715,7 @@ mod tests {
             vec![[0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [0.5, 1.0, 1.0]],
             [0.0, 0.0, 1.0],
         );
-        let plane = Plane { normal: [0.0, 0.0, 1.0], w: 0.0 };
+        let plane = Plane { normal: [0.0, 0.0, 1.0], w: 0.0, points: None };
         
         let (class, _, _) = split_polygon(&poly, &plane);
         assert_eq!(class, PolygonClassification::Front);
@@ -590,7 +727,7 @@ mod tests {
             vec![[0.0, 0.0, -1.0], [1.0, 0.0, -1.0], [0.5, 1.0, -1.0]],
             [0.0, 0.0, -1.0],
         );
-        let plane = Plane { normal: [0.0, 0.0, 1.0], w: 0.0 };
+        let plane = Plane { normal: [0.0, 0.0, 1.0], w: 0.0, points: None };
         
         let (class, _, _) = split_polygon(&poly, &plane);
         assert_eq!(class, PolygonClassification::Back);
@@ -602,7 +739,7 @@ mod tests {
             vec![[0.0, 0.0, -1.0], [1.0, 0.0, -1.0], [0.5, 0.0, 1.0]],
             [0.0, 1.0, 0.0],
         );
-        let plane = Plane { normal: [0.0, 0.0, 1.0], w: 0.0 };
+        let plane = Plane { normal: [0.0, 0.0, 1.0], w: 0.0, points: None };
         
         let (class, front, back) = split_polygon(&poly, &plane);
         assert_eq!(class, PolygonClassification::Spanning);
@@ -610,9 +747,49 @@ mod tests {
         assert!(back.is_some());
     }
 
+    /// Test coplanar polygons far from the origin stay coplanar.
+    #[test]
+    fn test_split_polygon_far_coplanar() {
+        // Tilted plane through (100000, 100000, 100000), where the plane
+        // distance alone is rounded by more than EPSILON
+        let base = [100000.0, 100000.0, 100000.0];
+        let at = |x: f64, y: f64| [base[0] + x, base[1] + y, base[2] + 0.5 * x - 0.25 * y];
+        let poly = BspPolygon::new(vec![at(0.0, 0.0), at(4.0, 0.0), at(0.0, 4.0)]);
+        let plane = Plane::from_polygon(&poly);
+        let other = BspPolygon::new(vec![at(-8.0, 2.0), at(2.0, -6.0), at(6.0, 6.0)]);
+        let (class, _, _) = split_polygon(&other, &plane);
+        assert_eq!(class, PolygonClassification::Coplanar);
+        assert!(Plane::from_polygon(&BspPolygon::new(vec![[0.0; 3], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]])).points.is_none());
+    }
+
     #[test]
     fn test_vertices_equal() {
         assert!(vertices_equal(&[0.0, 0.0, 0.0], &[0.00001, 0.0, 0.0]));
         assert!(!vertices_equal(&[0.0, 0.0, 0.0], &[1.0, 0.0, 0.0]));
     }
+
+    /// Test merging keeps every corner whichever edge of the first polygon is shared.
+    #[test]
+    fn test_merge_keeps_corners() {
+        // The square's two halves, split along either diagonal
+        let (a, b, c, d) = ([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]);
+        for (first, second) in [(vec![a, b, c], vec![a, c, d]), (vec![b, d, a], vec![b, c, d])] {
+            let merged = try_merge_polygons(&BspPolygon::new(first), &BspPolygon::new(second)).unwrap();
+            assert_eq!(merged.vertices.len(), 4, "{:?}", merged.vertices);
+            for corner in [a, b, c, d] {
+                assert!(merged.vertices.contains(&corner));
+            }
+        }
+    }
+
+    /// Test polygons are not merged into a non-convex one.
+    #[test]
+    fn test_merge_stays_convex() {
+        // Two unit squares side by side merge; an L of them does not
+        let square = |x: f64, y: f64| BspPolygon::new(vec![[x, y, 0.0], [x + 1.0, y, 0.0], [x + 1.0, y + 1.0, 0.0], [x, y + 1.0, 0.0]]);
+        assert_eq!(try_merge_polygons(&square(0.0, 0.0), &square(1.0, 0.0)).unwrap().vertices.len(), 4);
+        let l_shape = try_merge_polygons(&square(0.0, 0.0), &square(1.0, 0.0)).unwrap();
+        assert!(try_merge_polygons(&l_shape, &square(0.0, 1.0)).is_none());
+
+    }
 }
@@ -0,0 +1,374 @@
+//! # Robust Geometric Predicates
+//!
+//! Orientation and in-sphere tests whose signs are always exact.
+//!
+//! ## Why
+//!
+//! The sign of a determinant decides which side of a plane a point lies
+//! on. Evaluated in plain floating point, the sign is wrong whenever the
+//! rounding error exceeds the value, which happens for points on or very
+//! near the plane, exactly the coplanar faces CSG models are full of. A
+//! wrong sign splits a polygon that should not be split, or classifies a
+//! face as inside, and the result gets cracks or flipped triangles.
+//!
+//! ## How
+//!
+//! Each predicate follows Shewchuk's adaptive scheme:
+//!
+//! ```text
+//! 1. Evaluate in f64 and bound the rounding error
+//! 2. |value| > bound → the sign is right, return it
+//! 3. Otherwise evaluate exactly with floating-point expansions
+//! ```
+//!
+//! Step 3 is rare, so the predicates cost about as much as the plain
+//! expressions. The expansions are exact for any finite `f64` inputs,
+//! model coordinates included.
+//!
+//! ## Reference
+//!
+//! Shewchuk, J. R. (1997). Adaptive precision floating-point arithmetic
+//! and fast robust geometric predicates.
+
+// =============================================================================
+// PREDICATES
+// =============================================================================
+
+/// Half an ulp of 1.0: the relative rounding error of one operation.
+const EPS: f64 = f64::EPSILON / 2.0;
+
+/// Error bound factors of the floating-point stage.
+const ORIENT2D_BOUND: f64 = (3.0 + 16.0 * EPS) * EPS;
+const ORIENT3D_BOUND: f64 = (7.0 + 56.0 * EPS) * EPS;
+const INSPHERE_BOUND: f64 = (16.0 + 224.0 * EPS) * EPS;
+
+/// Orientation of three 2D points.
+///
+/// Positive if `a`, `b`, `c` turn counter-clockwise, negative if
+/// clockwise, zero if they are collinear. The magnitude is twice the
+/// triangle's signed area, approximately; only the sign is exact.
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::manifold::boolean::predicates::orient2d;
+///
+/// assert!(orient2d([0.0, 0.0], [1.0, 0.0], [0.0, 1.0]) > 0.0);
+/// assert_eq!(orient2d([0.0, 0.0], [1.0, 1.0], [3.0, 3.0]), 0.0);
+/// ```
+pub fn orient2d(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    let left = (a[0] - c[0]) * (b[1] - c[1]);
+    let right = (a[1] - c[1]) * (b[0] - c[0]);
+    let det = left - right;
+    if det.abs() > ORIENT2D_BOUND * (left.abs() + right.abs()) {
+        return det;
+    }
+
+    let [acx, acy, bcx, bcy] = [diff(a[0], c[0]), diff(a[1], c[1]), diff(b[0], c[0]), diff(b[1], c[1])];
+    estimate(&sub(&mul(&acx, &bcy), &mul(&acy, &bcx)))
+}
+
+/// Orientation of a point against the plane through three 3D points.
+///
+/// Positive if `d` lies in front of the plane through `a`, `b`, `c`
+/// (on the side `(b − a) × (c − a)` points to), negative if behind, zero
+/// if the four points are coplanar. The magnitude is six times the signed
+/// volume of the tetrahedron, approximately; only the sign is exact.
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::manifold::boolean::predicates::orient3d;
+///
+/// let (a, b, c) = ([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+/// assert!(orient3d(a, b, c, [0.0, 0.0, 1.0]) > 0.0);
+/// assert_eq!(orient3d(a, b, c, [0.3, 0.7, 0.0]), 0.0);
+/// ```
+pub fn orient3d(a: [f64; 3], b: [f64; 3], c: [f64; 3], d: [f64; 3]) -> f64 {
+    -orient3d_below(a, b, c, d)
+}
+
+/// Shewchuk's orient3d: positive if `d` lies behind the plane.
+fn orient3d_below(a: [f64; 3], b: [f64; 3], c: [f64; 3], d: [f64; 3]) -> f64 {
+    let [adx, ady, adz] = [a[0] - d[0], a[1] - d[1], a[2] - d[2]];
+    let [bdx, bdy, bdz] = [b[0] - d[0], b[1] - d[1], b[2] - d[2]];
+    let [cdx, cdy, cdz] = [c[0] - d[0], c[1] - d[1], c[2] - d[2]];
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    let det = adz * (bdxcdy - cdxbdy) + bdz * (cdxady - adxcdy) + cdz * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * adz.abs()
+        + (cdxady.abs() + adxcdy.abs()) * bdz.abs()
+        + (adxbdy.abs() + bdxady.abs()) * cdz.abs();
+    if det.abs() > ORIENT3D_BOUND * permanent {
+        return det;
+    }
+
+    let [adx, ady, adz] = [diff(a[0], d[0]), diff(a[1], d[1]), diff(a[2], d[2])];
+    let [bdx, bdy, bdz] = [diff(b[0], d[0]), diff(b[1], d[1]), diff(b[2], d[2])];
+    let [cdx, cdy, cdz] = [diff(c[0], d[0]), diff(c[1], d[1]), diff(c[2], d[2])];
+    let bc = sub(&mul(&bdx, &cdy), &mul(&cdx, &bdy));
+    let ca = sub(&mul(&cdx, &ady), &mul(&adx, &cdy));
+    let ab = sub(&mul(&adx, &bdy), &mul(&bdx, &ady));
+    estimate(&add(&add(&mul(&adz, &bc), &mul(&bdz, &ca)), &mul(&cdz, &ab)))
+}
+
+/// Position of a point against the sphere through four 3D points.
+///
+/// Positive if `e` lies inside the sphere through `a`, `b`, `c`, `d`,
+/// negative if outside, zero if on it, whatever the order of the four;
+/// they must not be coplanar, or the result is zero.
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::manifold::boolean::predicates::insphere;
+///
+/// let (a, b, c, d) = ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 0.0, 1.0]);
+/// assert!(insphere(a, b, c, d, [0.0, 0.0, 0.0]) > 0.0);
+/// assert_eq!(insphere(a, b, c, d, [0.0, -1.0, 0.0]), 0.0);
+/// ```
+pub fn insphere(a: [f64; 3], b: [f64; 3], c: [f64; 3], d: [f64; 3], e: [f64; 3]) -> f64 {
+    let orientation = orient3d_below(a, b, c, d);
+    if orientation == 0.0 {
+        return 0.0;
+    }
+    insphere_positive(a, b, c, d, e) * orientation.signum()
+}
+
+/// Shewchuk's insphere: positive inside, for `a`, `b`, `c`, `d` with a
+/// positive [`orient3d_below`].
+fn insphere_positive(a: [f64; 3], b: [f64; 3], c: [f64; 3], d: [f64; 3], e: [f64; 3]) -> f64 {
+    let rel = |p: [f64; 3]| [p[0] - e[0], p[1] - e[1], p[2] - e[2]];
+    let [ae, be, ce, de] = [rel(a), rel(b), rel(c), rel(d)];
+    // 2x2 minors of the x and y columns, with their absolute terms summed
+    let minor = |p: [f64; 3], q: [f64; 3]| {
+        let (x, y) = (p[0] * q[1], q[0] * p[1]);
+        (x - y, x.abs() + y.abs())
+    };
+    let (ab, bc, cd, da, ac, bd) = (minor(ae, be), minor(be, ce), minor(ce, de), minor(de, ae), minor(ae, ce), minor(be, de));
+
+    let abc = ae[2] * bc.0 - be[2] * ac.0 + ce[2] * ab.0;
+    let bcd = be[2] * cd.0 - ce[2] * bd.0 + de[2] * bc.0;
+    let cda = ce[2] * da.0 + de[2] * ac.0 + ae[2] * cd.0;
+    let dab = de[2] * ab.0 + ae[2] * bd.0 + be[2] * da.0;
+    let lift = |p: [f64; 3]| p[0] * p[0] + p[1] * p[1] + p[2] * p[2];
+    let det = (lift(de) * abc - lift(ce) * dab) + (lift(be) * cda - lift(ae) * bcd);
+
+    let perm_abc = ae[2].abs() * bc.1 + be[2].abs() * ac.1 + ce[2].abs() * ab.1;
+    let perm_bcd = be[2].abs() * cd.1 + ce[2].abs() * bd.1 + de[2].abs() * bc.1;
+    let perm_cda = ce[2].abs() * da.1 + de[2].abs() * ac.1 + ae[2].abs() * cd.1;
+    let perm_dab = de[2].abs() * ab.1 + ae[2].abs() * bd.1 + be[2].abs() * da.1;
+    let permanent = lift(de) * perm_abc + lift(ce) * perm_dab + lift(be) * perm_cda + lift(ae) * perm_bcd;
+    if det.abs() > INSPHERE_BOUND * permanent {
+        return det;
+    }
+
+    let rel = |p: [f64; 3]| [diff(p[0], e[0]), diff(p[1], e[1]), diff(p[2], e[2])];
+    let [ae, be, ce, de] = [rel(a), rel(b), rel(c), rel(d)];
+    let cross = |p: &[Expansion; 3], q: &[Expansion; 3]| sub(&mul(&p[0], &q[1]), &mul(&q[0], &p[1]));
+    let (ab, bc, cd, da, ac, bd) = (cross(&ae, &be), cross(&be, &ce), cross(&ce, &de), cross(&de, &ae), cross(&ae, &ce), cross(&be, &de));
+    let abc = add(&sub(&mul(&ae[2], &bc), &mul(&be[2], &ac)), &mul(&ce[2], &ab));
+    let bcd = add(&sub(&mul(&be[2], &cd), &mul(&ce[2], &bd)), &mul(&de[2], &bc));
+    let cda = add(&add(&mul(&ce[2], &da), &mul(&de[2], &ac)), &mul(&ae[2], &cd));
+    let dab = add(&add(&mul(&de[2], &ab), &mul(&ae[2], &bd)), &mul(&be[2], &da));
+    let lift = |p: &[Expansion; 3]| add(&add(&mul(&p[0], &p[0]), &mul(&p[1], &p[1])), &mul(&p[2], &p[2]));
+    let det = add(
+        &sub(&mul(&lift(&de), &abc), &mul(&lift(&ce), &dab)),
+        &sub(&mul(&lift(&be), &cda), &mul(&lift(&ae), &bcd)),
+    );
+    estimate(&det)
+}
+
+// =============================================================================
+// EXPANSION ARITHMETIC
+// =============================================================================
+
+/// Exact value as a sum of non-overlapping `f64`s, smallest magnitude
+/// first, without zeros.
+type Expansion = Vec<f64>;
+
+/// `a + b` as `(sum, error)`, exactly.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    (x, (a - a_virtual) + (b - b_virtual))
+}
+
+/// Split `a` into two halves of 26 bits each (Dekker).
+fn split(a: f64) -> (f64, f64) {
+    const SPLITTER: f64 = 134_217_729.0; // 2^27 + 1
+    let c = SPLITTER * a;
+    let hi = c - (c - a);
+    (hi, a - hi)
+}
+
+/// `a * b` as `(product, error)`, exactly.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    let (ahi, alo) = split(a);
+    let (bhi, blo) = split(b);
+    let err = x - ahi * bhi - alo * bhi - ahi * blo;
+    (x, alo * blo - err)
+}
+
+/// `a - b` exactly.
+fn diff(a: f64, b: f64) -> Expansion {
+    let (x, y) = two_sum(a, -b);
+    [y, x].into_iter().filter(|&v| v != 0.0).collect()
+}
+
+/// `e + b` exactly.
+fn grow(e: &[f64], b: f64) -> Expansion {
+    let mut out = Vec::with_capacity(e.len() + 1);
+    let mut q = b;
+    for &component in e {
+        let (sum, err) = two_sum(q, component);
+        if err != 0.0 {
+            out.push(err);
+        }
+        q = sum;
+    }
+    if q != 0.0 {
+        out.push(q);
+    }
+    out
+}
+
+/// `e + f` exactly.
+fn add(e: &[f64], f: &[f64]) -> Expansion {
+    f.iter().fold(e.to_vec(), |acc, &component| grow(&acc, component))
+}
+
+/// `e - f` exactly.
+fn sub(e: &[f64], f: &[f64]) -> Expansion {
+    f.iter().fold(e.to_vec(), |acc, &component| grow(&acc, -component))
+}
+
+/// `e * b` exactly.
+fn scale(e: &[f64], b: f64) -> Expansion {
+    let mut out = Vec::with_capacity(2 * e.len());
+    let Some((&first, rest)) = e.split_first() else {
+        return out;
+    };
+    let (mut q, err) = two_product(first, b);
+    out.push(err);
+    for &component in rest {
+        let (product, product_err) = two_product(component, b);
+        let (sum, sum_err) = two_sum(q, product_err);
+        out.push(sum_err);
+        let (next, next_err) = two_sum(product, sum);
+        out.push(next_err);
+        q = next;
+    }
+    out.push(q);
+    out.retain(|&v| v != 0.0);
+    out
+}
+
+/// `e * f` exactly.
+fn mul(e: &[f64], f: &[f64]) -> Expansion {
+    f.iter().fold(Vec::new(), |acc, &component| add(&acc, &scale(e, component)))
+}
+
+/// Approximate value with the exact sign: the largest component.
+fn estimate(e: &[f64]) -> f64 {
+    e.last().copied().unwrap_or(0.0)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test expansions are exact where plain arithmetic rounds.
+    #[test]
+    fn test_expansions_exact() {
+        let big = 1e20;
+        let sum = add(&[1.0], &[big]);
+        assert_eq!(sum, vec![1.0, big]);
+        assert_eq!(estimate(&sub(&sum, &[big])), 1.0);
+        let product = mul(&diff(1.0 + f64::EPSILON, 0.0), &[1.0 - f64::EPSILON]);
+        assert_eq!(estimate(&sub(&product, &[1.0])), -f64::EPSILON * f64::EPSILON);
+    }
+
+    /// Test orient3d against exact integer arithmetic on points at and
+    /// near a tilted plane, where plain f64 loses the sign.
+    #[test]
+    fn test_orient3d_matches_exact() {
+        // Coordinates are multiples of 2^-10 below 2^14, so scaled by 2^10
+        // they are integers and the determinant fits an i128
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        let grid = |v: f64| (v * 1024.0).round() / 1024.0;
+        let int = |p: [f64; 3]| p.map(|v| (v * 1024.0) as i128);
+        let mut degenerate = 0;
+        for _ in 0..2000 {
+            let mut point = || [0, 1, 2].map(|_| grid((next() % 8_000_000) as f64 / 1000.0));
+            let (a, b, c) = (point(), point(), point());
+            // A point on the plane near `a`, snapped to the grid: on it or
+            // just off
+            let (s, t) = ((next() % 1000) as f64 / 997.0, (next() % 1000) as f64 / 991.0);
+            let near = [0, 1, 2].map(|i| grid(a[i] + s * (b[i] - a[i]) + t * (c[i] - a[i])));
+            // The fourth corner of the parallelogram: exactly on it
+            let corner = [0, 1, 2].map(|i| b[i] + c[i] - a[i]);
+            let mut nudged = corner;
+            nudged[(next() % 3) as usize] += 1.0 / 1024.0;
+            let d = [near, corner, nudged][(next() % 3) as usize];
+            let [a, b, c, d] = [a, b, c, d].map(int);
+            let e = |p: [i128; 3]| [p[0] - a[0], p[1] - a[1], p[2] - a[2]];
+            let (u, v, w) = (e(b), e(c), e(d));
+            let exact = (u[1] * v[2] - u[2] * v[1]) * w[0] + (u[2] * v[0] - u[0] * v[2]) * w[1] + (u[0] * v[1] - u[1] * v[0]) * w[2];
+            let back = |p: [i128; 3]| p.map(|v| v as f64 / 1024.0);
+            let got = orient3d(back(a), back(b), back(c), back(d));
+            assert_eq!(got.partial_cmp(&0.0), exact.partial_cmp(&0), "{:?}", (a, b, c, d));
+            degenerate += usize::from(exact == 0);
+        }
+        assert!(degenerate > 0);
+    }
+
+    /// Test points on a plane far from the origin are coplanar, and a
+    /// one-ulp step off it is seen.
+    #[test]
+    fn test_orient3d_far_from_origin() {
+        let (a, b, c) = ([1000.125, 2000.25, 7.0], [1003.75, 2001.875, 7.0], [999.25, 2004.0, 7.0]);
+        let above = f64::from_bits(7.0f64.to_bits() + 1);
+        assert_eq!(orient3d(a, b, c, [12345.6, 2002.2, 7.0]), 0.0);
+        assert!(orient3d(a, b, c, [1000.0, 2002.2, above]) > 0.0);
+        assert!(orient3d(b, a, c, [1000.0, 2002.2, above]) < 0.0);
+    }
+
+    /// Test nearly collinear points where the naive determinant is wrong.
+    #[test]
+    fn test_orient2d_near_collinear() {
+        // Points on the line y = x, offset by one ulp at a large coordinate
+        let x = 9_007_199_254_740_991.0f64;
+        let c = [x, f64::from_bits(x.to_bits() - 1)];
+        assert_eq!(orient2d([0.5, 0.5], [12.0, 12.0], [x, x]), 0.0);
+        assert!(orient2d([0.5, 0.5], [12.0, 12.0], c) < 0.0);
+        // Sign is consistent under cyclic permutation
+        let d = [f64::from_bits(0.3f64.to_bits() + 1), 0.3];
+        let s = orient2d([0.1, 0.1], [0.2, 0.2], d).signum();
+        assert_eq!(orient2d([0.2, 0.2], d, [0.1, 0.1]).signum(), s);
+    }
+
+    /// Test cospherical points and orientation independence.
+    #[test]
+    fn test_insphere() {
+        let (a, b, c, d) = ([3.0, 0.0, 0.0], [0.0, 3.0, 0.0], [0.0, 0.0, 3.0], [-3.0, 0.0, 0.0]);
+        assert_eq!(insphere(a, b, c, d, [0.0, 0.0, -3.0]), 0.0);
+        assert!(insphere(a, b, c, d, [0.0, 0.0, 2.9]) > 0.0);
+        assert!(insphere(b, a, c, d, [0.0, 0.0, 2.9]) > 0.0);
+        assert!(insphere(a, b, c, d, [0.0, 0.0, 3.1]) < 0.0);
+        assert_eq!(insphere(a, b, [1.0, 2.0, 0.0], [2.0, 1.0, 0.0], [0.0; 3]), 0.0);
+    }
+}
@@ -0,0 +1,247 @@
+//! # Reduction Order
+//!
+//! Combines the operands of an n-ary boolean in a fixed pairwise tree.
+//!
+//! Floating-point booleans are not associative: `(a ∪ b) ∪ c` and
+//! `a ∪ (b ∪ c)` can differ in the last bits of their vertices. The tree
+//! shape therefore depends only on the number of operands, never on
+//! thread scheduling, so a parallel render gives the same mesh as a
+//! serial one, bit for bit:
+//!
+//! ```text
+//! a  b  c  d  e
+//!  \/    \/   |
+//!  ab    cd   e
+//!    \  /     |
+//!    abcd     e
+//!        \   /
+//!        abcde
+//! ```
+//!
+//! Each level pairs neighbours left to right and carries an odd operand
+//! up unchanged. Whether the pairs of a level, and the operand subtrees
+//! of each CSG node before them, run on the rayon pool is chosen at
+//! runtime with [`set_reduction`]. Builds without threads (see
+//! [`crate::THREADS`]) always run serially.
+//!
+//! ## Disjoint Operands
+//!
+//! Operands whose bounding boxes do not touch cannot change each other,
+//! so a union only runs booleans within groups of operands whose boxes
+//! overlap, directly or through other operands, and appends the groups'
+//! meshes. A `for` loop of hundreds of separate parts then costs no
+//! boolean at all. The groups are found in one sweep along x and listed
+//! by their first operand, each keeping its operands in order, so the
+//! result is as deterministic as the tree.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::manifold::boolean::{set_reduction, reduction, Reduction};
+//!
+//! set_reduction(Reduction::Parallel);
+//! let parallel = manifold_rs::render("union() for (i = [0:4]) translate([i * 5, 0, 0]) cube(6);").unwrap();
+//! set_reduction(Reduction::Serial);
+//! let serial = manifold_rs::render("union() for (i = [0:4]) translate([i * 5, 0, 0]) cube(6);").unwrap();
+//! assert_eq!(parallel.vertices, serial.vertices);
+//! assert_eq!(reduction(), Reduction::Serial);
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::ManifoldResult;
+use crate::mesh::bvh::Aabb;
+use crate::mesh::Mesh;
+use crate::parallel;
+
+/// Whether reductions run their pairs in parallel.
+static PARALLEL: AtomicBool = AtomicBool::new(false);
+
+/// How the pairs of each reduction level and the operand subtrees of CSG
+/// nodes are evaluated.
+///
+/// Both give identical meshes; only speed differs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reduction {
+    /// One pair after another on the calling thread.
+    #[default]
+    Serial,
+    /// Pairs of a level and sibling subtrees spread over the rayon thread pool.
+    Parallel,
+}
+
+/// Select serial or parallel reduction for all subsequent booleans.
+///
+/// The setting is process-wide.
+pub fn set_reduction(reduction: Reduction) {
+    PARALLEL.store(reduction == Reduction::Parallel, Ordering::Relaxed);
+}
+
+/// The current reduction setting.
+pub fn reduction() -> Reduction {
+    if PARALLEL.load(Ordering::Relaxed) { Reduction::Parallel } else { Reduction::Serial }
+}
+
+/// Combine meshes with `op` in the fixed pairwise tree.
+///
+/// ## Parameters
+///
+/// - `meshes`: Operands; none gives an empty mesh
+/// - `op`: Binary boolean, applied as `op(left, right)`
+///
+/// ## Errors
+///
+/// The first error from `op`; in parallel mode, the first in operand
+/// order among the pairs of the failing level.
+pub(crate) fn reduce_pairwise<F>(meshes: &[Mesh], op: F) -> ManifoldResult<Mesh>
+where
+    F: Fn(&Mesh, &Mesh) -> ManifoldResult<Mesh> + Sync + Send,
+{
+    let combine = |pair: &[Mesh]| match pair {
+        [left, right] => op(left, right),
+        [single] => Ok(single.clone()),
+        _ => Ok(Mesh::new()),
+    };
+
+    let mut level = meshes.to_vec();
+    while level.len() > 1 {
+        level = parallel::map_chunks(&level, 2, combine)
+            .into_iter()
+            .collect::<ManifoldResult<_>>()?;
+    }
+    Ok(level.pop().unwrap_or_default())
+}
+
+/// Bounding box of a mesh's vertices; empty for an empty mesh.
+pub(crate) fn mesh_bounds(mesh: &Mesh) -> Aabb {
+    mesh.vertices
+        .chunks_exact(3)
+        .fold(Aabb::EMPTY, |bounds, p| bounds.union(&Aabb::point([p[0], p[1], p[2]])))
+}
+
+/// Group the boxes that overlap, directly or through other boxes.
+///
+/// ## Returns
+///
+/// Indices of each group in ascending order, groups ordered by their
+/// first index. Empty boxes form groups of their own.
+pub(crate) fn overlap_groups(bounds: &[Aabb]) -> Vec<Vec<usize>> {
+    // Union-find whose roots are the smallest index of their set
+    let mut parent: Vec<usize> = (0..bounds.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    // Sweep along x, testing each box against those still open
+    let mut order: Vec<usize> = (0..bounds.len()).filter(|&i| !bounds[i].is_empty()).collect();
+    order.sort_by(|&a, &b| bounds[a].min[0].total_cmp(&bounds[b].min[0]));
+    let mut open: Vec<usize> = Vec::new();
+    for i in order {
+        open.retain(|&j| bounds[j].max[0] >= bounds[i].min[0]);
+        for &j in &open {
+            if bounds[i].overlaps(&bounds[j]) {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+        open.push(i);
+    }
+
+    let mut slot = vec![usize::MAX; bounds.len()];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for i in 0..bounds.len() {
+        let r = root(&mut parent, i);
+        if r == i {
+            slot[i] = groups.len();
+            groups.push(Vec::new());
+        }
+        groups[slot[r]].push(i);
+    }
+    groups
+}
+
+/// Union meshes with `op`, running it only within groups of overlapping
+/// operands and appending the groups' results.
+pub(crate) fn reduce_union<F>(meshes: &[Mesh], op: F) -> ManifoldResult<Mesh>
+where
+    F: Fn(&Mesh, &Mesh) -> ManifoldResult<Mesh> + Sync + Send,
+{
+    let bounds: Vec<Aabb> = meshes.iter().map(mesh_bounds).collect();
+    let groups = overlap_groups(&bounds);
+    let combined = parallel::map(&groups, |group| match group.as_slice() {
+        [single] => Ok(meshes[*single].clone()),
+        _ => reduce_pairwise(&group.iter().map(|&i| meshes[i].clone()).collect::<Vec<_>>(), &op),
+    });
+
+    let mut result = Mesh::new();
+    for mesh in combined {
+        result.merge(&mesh?);
+    }
+    Ok(result)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test operands are combined in the documented tree shape.
+    #[test]
+    fn test_tree_shape() {
+        // Encode each operand as a mesh with one vertex whose x is its label
+        let leaf = |label: f64| {
+            let mut mesh = Mesh::new();
+            mesh.add_vertex(label, 0.0, 0.0, 0.0, 0.0, 1.0);
+            mesh
+        };
+        // Non-associative op: records nesting as 10 * left + right
+        let op = |a: &Mesh, b: &Mesh| Ok(leaf(10.0 * a.vertices[0] + b.vertices[0]));
+
+        let meshes: Vec<Mesh> = (1..=5).map(|i| leaf(i as f64)).collect();
+        // ((1 2) (3 4)) 5 = (12 * 10 + 34) * 10 + 5
+        assert_eq!(reduce_pairwise(&meshes, op).unwrap().vertices[0], 1545.0);
+        assert_eq!(reduce_pairwise(&meshes[..1], op).unwrap().vertices[0], 1.0);
+        assert!(reduce_pairwise(&[], op).unwrap().is_empty());
+    }
+
+    /// Test boxes are grouped by overlap, transitively and in order.
+    #[test]
+    fn test_overlap_groups() {
+        let cube = |x: f64| Aabb { min: [x, 0.0, 0.0], max: [x + 1.0, 1.0, 1.0] };
+        // 0 and 2 touch through 3; 1 stands alone; 4 is empty
+        let bounds = [cube(0.0), cube(10.0), cube(2.0), cube(1.0), Aabb::EMPTY, Aabb { min: [0.5, 5.0, 0.0], max: [1.5, 6.0, 1.0] }];
+        assert_eq!(overlap_groups(&bounds), vec![vec![0, 2, 3], vec![1], vec![4], vec![5]]);
+        assert!(overlap_groups(&[]).is_empty());
+    }
+
+    /// Test disjoint operands are appended without running the boolean.
+    #[test]
+    fn test_reduce_union_skips_disjoint() {
+        use crate::manifold::constructors::build_cube;
+        let cube = |x: f64| {
+            let mut mesh = Mesh::new();
+            build_cube(&mut mesh, [1.0, 1.0, 1.0], false);
+            mesh.translate(x, 0.0, 0.0);
+            mesh
+        };
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let op = |a: &Mesh, b: &Mesh| {
+            calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let mut merged = a.clone();
+            merged.merge(b);
+            Ok(merged)
+        };
+        let meshes: Vec<Mesh> = [0.0, 5.0, 10.0, 10.5].into_iter().map(cube).collect();
+        let result = reduce_union(&meshes, op).unwrap();
+        assert_eq!(calls.into_inner(), 1);
+        assert_eq!(result.triangle_count(), 48);
+        assert_eq!(result.vertices[..3], meshes[0].vertices[..3]);
+    }
+}
@@ -0,0 +1,126 @@
+//! # Boolean Settings
+//!
+//! Tuning of the boolean backends and primitives, process-wide like
+//! [`super::set_backend`]:
+//!
+//! | Setting | Default | Effect |
+//! |---------|---------|--------|
+//! | [`set_auto_threshold`] | [`DEFAULT_AUTO_THRESHOLD`] | Operand triangles from which [`Backend::Auto`] splits by intersection |
+//! | [`set_weld_tolerance`] | [`DEFAULT_WELD_TOLERANCE`] | Distance within which boolean results weld vertices |
+//! | [`set_sphere_style`] | `Uv` | How `sphere()` is tessellated |
+//!
+//! A render applies the [`CsgOptions`] it was given with a [`CsgScope`],
+//! which restores the previous settings when dropped, so one render's
+//! options do not leak into the next.
+//!
+//! [`Backend::Auto`]: super::Backend::Auto
+//! [`set_sphere_style`]: crate::manifold::constructors::set_sphere_style
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::manifold::boolean::{backend, weld_tolerance, Backend, CsgScope, DEFAULT_WELD_TOLERANCE};
+//! use openscad_eval::{CsgBackend, CsgOptions};
+//!
+//! let options = CsgOptions { backend: Some(CsgBackend::Auto), weld_tolerance: Some(1e-3), ..CsgOptions::default() };
+//! {
+//!     let _scope = CsgScope::enter(&options);
+//!     assert_eq!(backend(), Backend::Auto);
+//!     assert_eq!(weld_tolerance(), 1e-3);
+//! }
+//! assert_eq!(weld_tolerance(), DEFAULT_WELD_TOLERANCE);
+//! ```
+
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use openscad_eval::{CsgOptions, SphereStyle};
+
+use super::intersect::{backend, set_backend, Backend};
+use crate::manifold::constructors::{set_sphere_style, sphere_style};
+
+/// Default of [`auto_threshold`].
+pub const DEFAULT_AUTO_THRESHOLD: usize = 1000;
+
+/// Default of [`weld_tolerance`].
+pub const DEFAULT_WELD_TOLERANCE: f32 = 1e-4;
+
+/// Operand triangles from which `Auto` uses intersection splitting.
+static AUTO_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_AUTO_THRESHOLD);
+
+/// Bits of the weld tolerance.
+static WELD_TOLERANCE: AtomicU32 = AtomicU32::new(DEFAULT_WELD_TOLERANCE.to_bits());
+
+/// Set how many triangles the two operands of a boolean need together
+/// before [`Backend::Auto`] uses intersection splitting rather than BSP.
+///
+/// The setting is process-wide.
+pub fn set_auto_threshold(triangles: usize) {
+    AUTO_THRESHOLD.store(triangles, Ordering::Relaxed);
+}
+
+/// The current auto threshold.
+pub fn auto_threshold() -> usize {
+    AUTO_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Set the distance within which boolean results weld vertices together.
+///
+/// Values that are not positive and finite are ignored. The setting is
+/// process-wide.
+pub fn set_weld_tolerance(tolerance: f32) {
+    if tolerance.is_finite() && tolerance > 0.0 {
+        WELD_TOLERANCE.store(tolerance.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// The current weld tolerance.
+pub fn weld_tolerance() -> f32 {
+    f32::from_bits(WELD_TOLERANCE.load(Ordering::Relaxed))
+}
+
+/// The given options applied to the boolean settings until dropped.
+///
+/// Options left `None` keep the current setting; `cache_size` belongs to
+/// render sessions and is not applied here.
+#[derive(Debug)]
+#[must_use = "the settings are restored as soon as the scope is dropped"]
+pub struct CsgScope {
+    backend: Backend,
+    auto_threshold: usize,
+    weld_tolerance: f32,
+    sphere: SphereStyle,
+}
+
+impl CsgScope {
+    /// Apply `options`, remembering the settings they replace.
+    pub fn enter(options: &CsgOptions) -> Self {
+        let scope = Self {
+            backend: backend(),
+            auto_threshold: auto_threshold(),
+            weld_tolerance: weld_tolerance(),
+            sphere: sphere_style(),
+        };
+        if let Some(chosen) = options.backend {
+            set_backend(chosen.into());
+        }
+        if let Some(triangles) = options.auto_threshold {
+            set_auto_threshold(triangles);
+        }
+        if let Some(tolerance) = options.weld_tolerance {
+            set_weld_tolerance(tolerance);
+        }
+        if let Some(style) = options.sphere {
+            set_sphere_style(style);
+        }
+        scope
+    }
+}
+
+impl Drop for CsgScope {
+    fn drop(&mut self) {
+        set_backend(self.backend);
+        set_auto_threshold(self.auto_threshold);
+        set_weld_tolerance(self.weld_tolerance);
+        set_sphere_style(self.sphere);
+    }
+}
@@ -262,6 +262,9 @@ fn test_single_mesh() {
 ///
 /// - BSP: ~600-650 vertices, ~1400-1500 triangles (~44% overhead vs Manifold)
 /// - With polygon merging: reduced from 2000+ triangles
+/// - Canonicalization splits every T-junction on the seam, adding roughly
+///   one triangle per split vertex (~2200 triangles) in exchange for a
+///   watertight result
 ///
 /// ## Future Work
 ///
@@ -296,9 +299,10 @@ fn test_regression_union_cube_sphere_optimization() {
         vertex_count
     );
     
+    // T-junction splits during canonicalization add triangles along the seam
     assert!(
-        triangle_count < 1600,
-        "Triangle count {} exceeds BSP limit (OpenSCAD: 1008, BSP max: 1600)",
+        triangle_count < 2400,
+        "Triangle count {} exceeds BSP limit (OpenSCAD: 1008, BSP max: 2400)",
         triangle_count
     );
     
@@ -358,3 +362,91 @@ fn test_three_way_union() {
     assert!(!result.is_empty());
     assert!(result.triangle_count() >= 12);
 }
+
+/// Regression test for booleans far from the origin.
+///
+/// At large coordinates the plane distances used by the BSP round by
+/// more than `EPSILON`. With exact on-plane and inside tests, a cut
+/// sharing faces with the block matches the same cut at the origin.
+#[test]
+fn test_regression_far_from_origin() {
+    let render = |offset: f64| {
+        let mut outer = Mesh::new();
+        build_cube(&mut outer, [10.0, 10.0, 10.0], true);
+        outer.translate(offset, offset, offset);
+        let mut inner = Mesh::new();
+        build_cube(&mut inner, [4.0, 4.0, 20.0], true);
+        inner.translate(offset + 2.0, offset, offset);
+        difference_all(&[outer, inner]).unwrap()
+    };
+    let near = render(0.0);
+    let far = render(100000.0);
+    assert!(!far.is_empty());
+    assert_eq!(far.triangle_count(), near.triangle_count());
+}
+
+/// Regression test for unions of rotated bars overflowing the stack.
+///
+/// Faces merged from an earlier union are coplanar only within the merge
+/// tolerance, so one could span its own plane in the BSP and be split
+/// without end.
+#[test]
+fn test_regression_rotated_union() {
+    let bars: Vec<Mesh> = (0..6)
+        .map(|i| {
+            let mut bar = Mesh::new();
+            build_cube(&mut bar, [12.0, 4.0, 1.5], false);
+            bar.translate(8.0, -2.0, i as f64 * 0.5);
+            let (sin, cos) = (i as f64 * 9.0).to_radians().sin_cos();
+            bar.transform(&[[cos, sin, 0.0, 0.0], [-sin, cos, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]]);
+            bar
+        })
+        .collect();
+    let union = union_all(&bars).unwrap();
+    assert!(union.volume() > 72.0 && union.volume() < 6.0 * 72.0, "{}", union.volume());
+}
+
+// =============================================================================
+// COPLANAR FACE TESTS
+// =============================================================================
+
+/// Whether a boolean result is a closed, consistently wound surface.
+fn is_closed(mesh: &Mesh) -> bool {
+    crate::mesh::halfedge::HalfEdgeMesh::from_mesh(mesh).1.is_manifold()
+}
+
+/// Test booleans of cubes sharing faces give closed shells without
+/// duplicate or missing faces.
+///
+/// ```text
+///   +---+---+      A: [0,10]³, B shares its +X face
+///   | A | B |      C: A's top half, sharing 5 faces with A
+///   +---+---+
+/// ```
+#[test]
+fn test_coplanar_faces() {
+    let mut a = Mesh::new();
+    build_cube(&mut a, [10.0, 10.0, 10.0], false);
+    let mut b = a.clone();
+    b.translate(10.0, 0.0, 0.0);
+    let mut c = Mesh::new();
+    build_cube(&mut c, [10.0, 10.0, 5.0], false);
+    c.translate(0.0, 0.0, 5.0);
+
+    // Touching cubes: the shared wall disappears
+    let union = union_all(&[a.clone(), b.clone()]).unwrap();
+    assert!(is_closed(&union));
+    assert!(difference_all(&[a.clone(), b.clone()]).unwrap().triangle_count() == a.triangle_count());
+    assert!(intersection_all(&[a.clone(), b]).unwrap().is_empty());
+
+    // Identical cubes: each face kept once
+    assert_eq!(union_all(&[a.clone(), a.clone()]).unwrap().triangle_count(), a.triangle_count());
+    assert_eq!(intersection_all(&[a.clone(), a.clone()]).unwrap().triangle_count(), a.triangle_count());
+    assert!(difference_all(&[a.clone(), a.clone()]).unwrap().is_empty());
+
+    // Cutting off a half that shares faces leaves the bottom half
+    let bottom = difference_all(&[a.clone(), c.clone()]).unwrap();
+    assert!(is_closed(&bottom));
+    let top = intersection_all(&[a, c]).unwrap();
+    assert!(is_closed(&top));
+}
@@ -1,6 +1,7 @@
 //! # 3D Primitive Constructors
 //!
-//! Mesh builders for cube, sphere, cylinder, and polyhedron primitives.
+//! Mesh builders for cube, sphere, cylinder, polyhedron and surface
+//! primitives, and the torus and rounded cube extensions.
 //! Uses OpenSCAD-compatible algorithms with Manifold circularSegments.
 //!
 //! ## OpenSCAD Compatibility
@@ -9,9 +10,32 @@
 //! - Cube uses 24 vertices (4 per face for per-face normals)
 //! - Sphere uses offset phi (no pole vertices)
 //! - Cylinder uses separate vertices for caps and sides
+//!
+//! ## Sphere Styles
+//!
+//! OpenSCAD's latitude/longitude sphere crowds thin triangles at its
+//! caps, which booleans cut poorly. [`set_sphere_style`] selects, process
+//! wide, what `sphere()` is meshed as:
+//!
+//! | Style | Mesh | Triangles at `$fn = 30` |
+//! |-------|------|-------------------------|
+//! | [`SphereStyle::Uv`] | OpenSCAD's rings (the default) | 900 |
+//! | [`SphereStyle::Icosphere`] | Subdivided icosahedron, `ceil(n / 5)` per edge | 720 |
+//! | [`SphereStyle::CubeSphere`] | Cube faces in an `ceil(n / 4)` grid, projected | 768 |
+//!
+//! The other styles roughly keep the number of segments around the
+//! equator, so `$fn`, `$fa` and `$fs` still set the resolution.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use openscad_eval::SphereStyle;
 
+use crate::error::ManifoldResult;
+use crate::manifold::hull::compute_hull;
 use crate::mesh::Mesh;
-use std::f32::consts::PI;
+use crate::mesh::triangulate::{newell_normal, triangulate_face};
+use std::f64::consts::PI;
 
 // =============================================================================
 // CUBE
@@ -46,7 +70,7 @@ use std::f32::consts::PI;
 /// assert_eq!(mesh.triangle_count(), 12);
 /// ```
 pub fn build_cube(mesh: &mut Mesh, size: [f64; 3], center: bool) {
-    let [sx, sy, sz] = [size[0] as f32, size[1] as f32, size[2] as f32];
+    let [sx, sy, sz] = size;
     
     // Calculate bounds based on center parameter
     let (min_x, max_x) = if center { (-sx / 2.0, sx / 2.0) } else { (0.0, sx) };
@@ -125,16 +149,16 @@ pub fn build_cube(mesh: &mut Mesh, size: [f64; 3], center: bool) {
 /// - `radius`: Sphere radius
 /// - `circular_segments`: Number of segments around circumference
 pub fn build_sphere(mesh: &mut Mesh, radius: f64, circular_segments: u32) {
-    let r = radius as f32;
+    let r = radius;
     let num_fragments = circular_segments.max(3) as usize;
-    let num_rings = (num_fragments + 1) / 2;
+    let num_rings = num_fragments.div_ceil(2);
     
     // Generate ring vertices with OpenSCAD-compatible offset
     let mut rings: Vec<Vec<u32>> = Vec::with_capacity(num_rings);
     
     for ring_idx in 0..num_rings {
         // OpenSCAD offset formula: phi = 180 * (i + 0.5) / num_rings
-        let phi_deg = 180.0 * (ring_idx as f32 + 0.5) / num_rings as f32;
+        let phi_deg = 180.0 * (ring_idx as f64 + 0.5) / num_rings as f64;
         let phi_rad = phi_deg.to_radians();
         
         let ring_radius = r * phi_rad.sin();
@@ -142,14 +166,14 @@ pub fn build_sphere(mesh: &mut Mesh, radius: f64, circular_segments: u32) {
         
         let mut ring = Vec::with_capacity(num_fragments);
         for seg_idx in 0..num_fragments {
-            let theta = 2.0 * PI * seg_idx as f32 / num_fragments as f32;
+            let theta = 2.0 * PI * seg_idx as f64 / num_fragments as f64;
             let x = ring_radius * theta.cos();
             let y = ring_radius * theta.sin();
             
             // Normal is normalized position for unit sphere
             let len = (x * x + y * y + z * z).sqrt();
             let (nx, ny, nz) = if len > 0.0 {
-                (x / len, y / len, z / len)
+                ((x / len) as f32, (y / len) as f32, (z / len) as f32)
             } else {
                 (0.0, 0.0, 1.0)
             };
@@ -165,7 +189,7 @@ pub fn build_sphere(mesh: &mut Mesh, radius: f64, circular_segments: u32) {
         let ring = &rings[0];
         let n = ring.len();
         
-        if n % 2 == 0 {
+        if n.is_multiple_of(2) {
             // Split diagonal triangulation (OpenSCAD style for even N)
             // Splits along diagonal (N/2-1)-(N-1)
             let p1 = n - 1;
@@ -205,7 +229,7 @@ pub fn build_sphere(mesh: &mut Mesh, radius: f64, circular_segments: u32) {
         let ring = &rings[num_rings - 1];
         let n = ring.len();
         
-        if n % 2 == 0 {
+        if n.is_multiple_of(2) {
             // Split diagonal triangulation (Reversed)
             let p1 = n - 1;
             let p2 = n / 2 - 1;
@@ -228,6 +252,167 @@ pub fn build_sphere(mesh: &mut Mesh, radius: f64, circular_segments: u32) {
     }
 }
 
+/// The selected sphere style, as its index in [`SPHERE_STYLES`].
+static SPHERE_STYLE: AtomicU8 = AtomicU8::new(0);
+
+/// Every sphere style, in the order stored.
+const SPHERE_STYLES: [SphereStyle; 3] = [SphereStyle::Uv, SphereStyle::Icosphere, SphereStyle::CubeSphere];
+
+/// Select how all subsequent spheres are tessellated.
+///
+/// The setting is process-wide.
+pub fn set_sphere_style(style: SphereStyle) {
+    let index = SPHERE_STYLES.iter().position(|s| *s == style).unwrap_or(0);
+    SPHERE_STYLE.store(index as u8, Ordering::Relaxed);
+}
+
+/// The current sphere style.
+pub fn sphere_style() -> SphereStyle {
+    SPHERE_STYLES.get(usize::from(SPHERE_STYLE.load(Ordering::Relaxed))).copied().unwrap_or_default()
+}
+
+/// Build a sphere in the given style.
+///
+/// ## Parameters
+///
+/// - `mesh`: Output mesh to populate
+/// - `radius`: Sphere radius
+/// - `circular_segments`: Number of segments around circumference
+/// - `style`: Tessellation to use
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::mesh::Mesh;
+/// use manifold_rs::manifold::constructors::build_sphere_styled;
+/// use openscad_eval::SphereStyle;
+///
+/// let mut mesh = Mesh::new();
+/// build_sphere_styled(&mut mesh, 1.0, 30, SphereStyle::Icosphere);
+/// assert_eq!(mesh.triangle_count(), 720);
+/// assert!(mesh.validate().is_valid());
+/// ```
+pub fn build_sphere_styled(mesh: &mut Mesh, radius: f64, circular_segments: u32, style: SphereStyle) {
+    match style {
+        SphereStyle::Uv => build_sphere(mesh, radius, circular_segments),
+        SphereStyle::Icosphere => build_icosphere(mesh, radius, circular_segments.div_ceil(5).max(1)),
+        SphereStyle::CubeSphere => build_cube_sphere(mesh, radius, circular_segments.div_ceil(4).max(1)),
+    }
+}
+
+/// Build a geodesic sphere: an icosahedron with each edge split into
+/// `frequency` parts, projected onto the sphere.
+///
+/// Gives `20 * frequency²` triangles of nearly equal size and shape.
+pub fn build_icosphere(mesh: &mut Mesh, radius: f64, frequency: u32) {
+    let t = (1.0 + 5.0_f64.sqrt()) / 2.0;
+    let corners: [[f64; 3]; 12] = [
+        [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+        [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+        [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+    ];
+    let faces: [[usize; 3]; 20] = [
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+    let n = frequency.max(1) as usize;
+    // Points are keyed on their corner weights, so the faces sharing an
+    // edge or corner share its vertices exactly
+    let mut sphere = SphereVertices::new(mesh, radius);
+    for [a, b, c] in faces {
+        let mut point = |i: usize, j: usize| {
+            let mut weights = [(a, n - i - j), (b, i), (c, j)];
+            weights.sort_unstable();
+            let key = weights.map(|(corner, w)| if w == 0 { (0, 0) } else { (corner, w) });
+            let mut p = [0.0; 3];
+            for (corner, w) in weights {
+                for (k, value) in p.iter_mut().enumerate() {
+                    *value += corners[corner][k] * w as f64;
+                }
+            }
+            sphere.vertex(key, p)
+        };
+        let mut triangles = Vec::with_capacity(n * n);
+        for i in 0..n {
+            for j in 0..n - i {
+                triangles.push([point(i, j), point(i + 1, j), point(i, j + 1)]);
+                if i + j + 1 < n {
+                    triangles.push([point(i + 1, j), point(i + 1, j + 1), point(i, j + 1)]);
+                }
+            }
+        }
+        for [p, q, r] in triangles {
+            sphere.mesh.add_triangle(p, q, r);
+        }
+    }
+}
+
+/// Build a cube sphere: each face of a cube split into a
+/// `resolution × resolution` grid, projected onto the sphere.
+///
+/// The grid is spaced by equal angles rather than equal lengths, which
+/// keeps the quads near the cube's corners from shrinking. Gives
+/// `12 * resolution²` triangles.
+pub fn build_cube_sphere(mesh: &mut Mesh, radius: f64, resolution: u32) {
+    let n = resolution.max(1) as i64;
+    let warp = |c: i64| (c as f64 / n as f64 * std::f64::consts::FRAC_PI_4).tan();
+    let mut sphere = SphereVertices::new(mesh, radius);
+    for axis in 0..3 {
+        for sign in [1, -1] {
+            // Integer grid points on the cube of half-size n, in steps of 2
+            let mut point = |a: i64, b: i64| {
+                let mut key = [0i64; 3];
+                key[axis] = sign * n;
+                key[(axis + 1) % 3] = 2 * a - n;
+                key[(axis + 2) % 3] = 2 * b - n;
+                sphere.vertex(key, key.map(warp))
+            };
+            let mut triangles = Vec::with_capacity((n * n * 2) as usize);
+            for a in 0..n {
+                for b in 0..n {
+                    let quad = [point(a, b), point(a + 1, b), point(a + 1, b + 1), point(a, b + 1)];
+                    if sign > 0 {
+                        triangles.push([quad[0], quad[1], quad[2]]);
+                        triangles.push([quad[0], quad[2], quad[3]]);
+                    } else {
+                        triangles.push([quad[0], quad[2], quad[1]]);
+                        triangles.push([quad[0], quad[3], quad[2]]);
+                    }
+                }
+            }
+            for [p, q, r] in triangles {
+                sphere.mesh.add_triangle(p, q, r);
+            }
+        }
+    }
+}
+
+/// Sphere vertices added once per key, projected from a direction.
+struct SphereVertices<'a, K> {
+    mesh: &'a mut Mesh,
+    radius: f64,
+    added: HashMap<K, u32>,
+}
+
+impl<'a, K: std::hash::Hash + Eq> SphereVertices<'a, K> {
+    fn new(mesh: &'a mut Mesh, radius: f64) -> Self {
+        Self { mesh, radius, added: HashMap::new() }
+    }
+
+    /// The vertex for `key`, added at `direction` scaled to the radius.
+    fn vertex(&mut self, key: K, direction: [f64; 3]) -> u32 {
+        let Self { mesh, radius, added } = self;
+        *added.entry(key).or_insert_with(|| {
+            let len = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt();
+            let [nx, ny, nz] = direction.map(|d| d / len);
+            let r = *radius;
+            mesh.add_vertex(nx * r, ny * r, nz * r, nx as f32, ny as f32, nz as f32)
+        })
+    }
+}
+
 // =============================================================================
 // CYLINDER
 // =============================================================================
@@ -259,9 +444,7 @@ pub fn build_cylinder(
     circular_segments: u32,
     center: bool,
 ) {
-    let h = height as f32;
-    let r1 = radius1 as f32;
-    let r2 = radius2 as f32;
+    let (h, r1, r2) = (height, radius1, radius2);
     let segments = circular_segments.max(3) as usize;
     
     let z_bottom = if center { -h / 2.0 } else { 0.0 };
@@ -271,7 +454,7 @@ pub fn build_cylinder(
     let mut bottom_ring: Vec<u32> = Vec::new();
     if r1 > 0.0 {
         for i in 0..segments {
-            let theta = 2.0 * PI * i as f32 / segments as f32;
+            let theta = 2.0 * PI * i as f64 / segments as f64;
             let x = r1 * theta.cos();
             let y = r1 * theta.sin();
             let v = mesh.add_vertex(x, y, z_bottom, 0.0, 0.0, -1.0);
@@ -283,7 +466,7 @@ pub fn build_cylinder(
     let mut top_ring: Vec<u32> = Vec::new();
     if r2 > 0.0 {
         for i in 0..segments {
-            let theta = 2.0 * PI * i as f32 / segments as f32;
+            let theta = 2.0 * PI * i as f64 / segments as f64;
             let x = r2 * theta.cos();
             let y = r2 * theta.sin();
             let v = mesh.add_vertex(x, y, z_top, 0.0, 0.0, 1.0);
@@ -293,7 +476,7 @@ pub fn build_cylinder(
 
     // Side faces with proper outward normals
     let cone_slope = (r1 - r2) / h;
-    let normal_z = cone_slope / (1.0 + cone_slope * cone_slope).sqrt();
+    let normal_z = (cone_slope / (1.0 + cone_slope * cone_slope).sqrt()) as f32;
     let normal_xy_scale = 1.0 / (1.0 + cone_slope * cone_slope).sqrt();
 
     // Create separate side vertices with outward normals
@@ -301,9 +484,9 @@ pub fn build_cylinder(
     let mut side_top: Vec<u32> = Vec::new();
     
     for i in 0..segments {
-        let theta = 2.0 * PI * i as f32 / segments as f32;
-        let nx = theta.cos() * normal_xy_scale;
-        let ny = theta.sin() * normal_xy_scale;
+        let theta = 2.0 * PI * i as f64 / segments as f64;
+        let nx = (theta.cos() * normal_xy_scale) as f32;
+        let ny = (theta.sin() * normal_xy_scale) as f32;
         
         if r1 > 0.0 {
             let x = r1 * theta.cos();
@@ -359,14 +542,106 @@ pub fn build_cylinder(
     }
 }
 
+// =============================================================================
+// EXTENSIONS
+// =============================================================================
+
+/// Build a torus around the Z axis, centered at the origin.
+///
+/// ## Parameters
+///
+/// - `mesh`: Output mesh to populate
+/// - `major`: Distance from the axis to the middle of the tube
+/// - `minor`: Radius of the tube, less than `major`
+/// - `segments`: Number of segments around the axis
+/// - `tube_segments`: Number of segments around the tube
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::mesh::Mesh;
+/// use manifold_rs::manifold::constructors::build_torus;
+///
+/// let mut mesh = Mesh::new();
+/// build_torus(&mut mesh, 10.0, 2.0, 24, 12);
+/// assert_eq!(mesh.triangle_count(), 24 * 12 * 2);
+/// assert!(mesh.validate().is_valid());
+/// ```
+pub fn build_torus(mesh: &mut Mesh, major: f64, minor: f64, segments: u32, tube_segments: u32) {
+    let (big_r, r) = (major, minor);
+    let around = segments.max(3) as usize;
+    let tube = tube_segments.max(3) as usize;
+
+    let mut grid = Vec::with_capacity(around * tube);
+    for i in 0..around {
+        let theta = 2.0 * PI * i as f64 / around as f64;
+        for j in 0..tube {
+            let phi = 2.0 * PI * j as f64 / tube as f64;
+            let (nx, ny, nz) = ((phi.cos() * theta.cos()) as f32, (phi.cos() * theta.sin()) as f32, phi.sin() as f32);
+            let ring = big_r + r * phi.cos();
+            grid.push(mesh.add_vertex(ring * theta.cos(), ring * theta.sin(), r * phi.sin(), nx, ny, nz));
+        }
+    }
+
+    let at = |i: usize, j: usize| grid[(i % around) * tube + j % tube];
+    for i in 0..around {
+        for j in 0..tube {
+            mesh.add_triangle(at(i, j), at(i + 1, j), at(i + 1, j + 1));
+            mesh.add_triangle(at(i, j), at(i + 1, j + 1), at(i, j + 1));
+        }
+    }
+}
+
+/// Build a box with rounded edges and corners.
+///
+/// The mesh is the convex hull of a cube sphere of the rounding radius at
+/// each corner of the box shrunk by that radius. The cube sphere's grid
+/// is even, so it has vertices on the axes and the flat faces keep the
+/// full size.
+///
+/// ## Parameters
+///
+/// - `size`: [width, depth, height]
+/// - `radius`: Rounding radius, at most half the smallest size
+/// - `center`: If true, center at origin; otherwise in positive octant
+/// - `segments`: Number of segments of a full turn of the rounding
+///
+/// ## Errors
+///
+/// Returns `ManifoldError::GeometryError` if the hull fails.
+pub fn build_rounded_cube(size: [f64; 3], radius: f64, center: bool, segments: u32) -> ManifoldResult<Mesh> {
+    let radius = radius.min(size[0].min(size[1]).min(size[2]) / 2.0);
+    let mut corner = Mesh::new();
+    let resolution = segments.div_ceil(4).max(2);
+    build_cube_sphere(&mut corner, radius, resolution + resolution % 2);
+    let offset = if center { [0.0; 3] } else { size.map(|s| s / 2.0) };
+    let inner = size.map(|s| s / 2.0 - radius);
+
+    let mut corners = Vec::with_capacity(8);
+    for sx in [-1.0, 1.0] {
+        for sy in [-1.0, 1.0] {
+            for sz in [-1.0, 1.0] {
+                let mut sphere = corner.clone();
+                let at = [sx * inner[0] + offset[0], sy * inner[1] + offset[1], sz * inner[2] + offset[2]];
+                sphere.translate(at[0], at[1], at[2]);
+                corners.push(sphere);
+            }
+        }
+    }
+    compute_hull(&corners)
+}
+
 // =============================================================================
 // POLYHEDRON
 // =============================================================================
 
 /// Build polyhedron from points and faces.
 ///
-/// Creates a custom mesh with computed face normals. Face winding is reversed
-/// from OpenSCAD convention (CCW → CW for OpenGL).
+/// Faces may be arbitrary simple (possibly non-convex) planar polygons and
+/// are ear-clipped. Faces with out-of-range indices, fewer than 3 distinct
+/// vertices or zero area are skipped; the evaluator reports them (see
+/// `openscad_eval::polyhedron`). Face winding is reversed from OpenSCAD
+/// convention (CW seen from outside → CCW).
 ///
 /// ## OpenSCAD Equivalent
 ///
@@ -380,52 +655,136 @@ pub fn build_cylinder(
 /// - `points`: Vertex positions
 /// - `faces`: Face definitions (indices into points array)
 pub fn build_polyhedron(mesh: &mut Mesh, points: &[[f64; 3]], faces: &[Vec<usize>]) {
-    // Convert points to f32
-    let pts: Vec<[f32; 3]> = points
-        .iter()
-        .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
-        .collect();
-    
-    // Process each face
     for face in faces {
-        if face.len() < 3 {
+        if face.len() < 3 || face.iter().any(|&i| i >= points.len()) {
             continue;
         }
-        
-        // Compute face normal from first 3 vertices
-        let p0 = pts[face[0]];
-        let p1 = pts[face[1]];
-        let p2 = pts[face[2]];
-        
-        let u = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
-        let v = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
-        let n = [
-            u[1] * v[2] - u[2] * v[1],
-            u[2] * v[0] - u[0] * v[2],
-            u[0] * v[1] - u[1] * v[0],
-        ];
+
+        let n = newell_normal(points, face);
         let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
-        let n = if len > 0.0 {
-            [n[0] / len, n[1] / len, n[2] / len]
-        } else {
-            [0.0, 0.0, 1.0]
+        if len < 1e-12 {
+            continue;
+        }
+        // OpenSCAD faces are clockwise, so the outward normal is reversed
+        let n = [(-n[0] / len) as f32, (-n[1] / len) as f32, (-n[2] / len) as f32];
+
+        let base = mesh.vertex_count() as u32;
+        for &i in face.iter() {
+            let p = points[i];
+            mesh.add_vertex(p[0], p[1], p[2], n[0], n[1], n[2]);
+        }
+
+        // Map point indices back to this face's local vertices
+        let local = |point: usize| {
+            base + face.iter().position(|&i| i == point).unwrap_or(0) as u32
         };
-        
-        // First vertex (shared for fan triangulation)
-        let first_vertex = mesh.add_vertex(p0[0], p0[1], p0[2], n[0], n[1], n[2]);
-        
-        // Fan triangulation
-        for i in 1..face.len() - 1 {
-            let idx1 = face[i];
-            let idx2 = face[i + 1];
-            
-            let v1 = mesh.add_vertex(pts[idx1][0], pts[idx1][1], pts[idx1][2], n[0], n[1], n[2]);
-            let v2 = mesh.add_vertex(pts[idx2][0], pts[idx2][1], pts[idx2][2], n[0], n[1], n[2]);
-            
+        for [a, b, c] in triangulate_face(points, face) {
             // Reverse winding for OpenSCAD compatibility
-            mesh.add_triangle(first_vertex, v2, v1);
+            mesh.add_triangle(local(a), local(c), local(b));
+        }
+    }
+}
+
+// =============================================================================
+// SURFACE
+// =============================================================================
+
+/// Build a heightmap solid.
+///
+/// Grid point `(column, row)` sits at `x = column`, `y = row` with its
+/// value as height. Each grid cell is split into two triangles, and the
+/// solid is closed by walls down to a flat base at `z = 0`, or one unit
+/// below the lowest value if that is lower. Grids with fewer than two
+/// rows or columns have no area and produce nothing.
+///
+/// ## OpenSCAD Equivalent
+///
+/// ```text
+/// surface(file="map.dat", center);
+/// ```
+///
+/// ## Parameters
+///
+/// - `mesh`: Output mesh to populate
+/// - `rows`: Heights by row, all rows the same length
+/// - `center`: If true, center the grid on the origin in X and Y
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::mesh::Mesh;
+/// use manifold_rs::manifold::constructors::build_surface;
+///
+/// let mut mesh = Mesh::new();
+/// build_surface(&mut mesh, &[vec![1.0, 2.0], vec![3.0, 4.0]], false);
+/// // Top, four walls and a bottom fan
+/// assert_eq!(mesh.triangle_count(), 2 + 4 * 2 + 4);
+/// ```
+pub fn build_surface(mesh: &mut Mesh, rows: &[Vec<f64>], center: bool) {
+    let depth = rows.len();
+    let width = rows.first().map_or(0, Vec::len);
+    if width < 2 || depth < 2 || rows.iter().any(|row| row.len() != width) {
+        return;
+    }
+
+    let (ox, oy) = if center { (-(width as f64 - 1.0) / 2.0, -(depth as f64 - 1.0) / 2.0) } else { (0.0, 0.0) };
+    let base = rows.iter().flatten().fold(0.0f64, |m, &h| m.min(h - 1.0));
+    let point = |column: usize, row: usize, z: f64| [ox + column as f64, oy + row as f64, z];
+
+    // Top: one vertex per grid point, normals from the local slope
+    let top = mesh.vertex_count() as u32;
+    for (row, heights) in rows.iter().enumerate() {
+        for (column, &h) in heights.iter().enumerate() {
+            let slope = |a: f64, b: f64, span: usize| (b - a) / span as f64;
+            let dx = slope(heights[column.saturating_sub(1)], heights[(column + 1).min(width - 1)], (column + 1).min(width - 1) - column.saturating_sub(1));
+            let dy = slope(rows[row.saturating_sub(1)][column], rows[(row + 1).min(depth - 1)][column], (row + 1).min(depth - 1) - row.saturating_sub(1));
+            let len = (dx * dx + dy * dy + 1.0).sqrt();
+            let [x, y, z] = point(column, row, h);
+            mesh.add_vertex(x, y, z, (-dx / len) as f32, (-dy / len) as f32, (1.0 / len) as f32);
         }
     }
+    let index = |column: usize, row: usize| top + (row * width + column) as u32;
+    for row in 0..depth - 1 {
+        for column in 0..width - 1 {
+            let (a, b) = (index(column, row), index(column + 1, row));
+            let (c, d) = (index(column + 1, row + 1), index(column, row + 1));
+            mesh.add_triangle(a, b, c);
+            mesh.add_triangle(a, c, d);
+        }
+    }
+
+    // Boundary grid points, counter-clockwise seen from above
+    let boundary: Vec<(usize, usize)> = (0..width - 1).map(|c| (c, 0))
+        .chain((0..depth - 1).map(|r| (width - 1, r)))
+        .chain((1..width).rev().map(|c| (c, depth - 1)))
+        .chain((1..depth).rev().map(|r| (0, r)))
+        .collect();
+
+    // Walls: one flat quad per boundary segment, facing outward
+    for (i, &(c0, r0)) in boundary.iter().enumerate() {
+        let (c1, r1) = boundary[(i + 1) % boundary.len()];
+        let (dx, dy) = (c1 as f32 - c0 as f32, r1 as f32 - r0 as f32);
+        let (nx, ny) = (dy, -dx);
+        let v = mesh.vertex_count() as u32;
+        for p in [point(c0, r0, base), point(c1, r1, base), point(c1, r1, rows[r1][c1]), point(c0, r0, rows[r0][c0])] {
+            mesh.add_vertex(p[0], p[1], p[2], nx, ny, 0.0);
+        }
+        mesh.add_triangle(v, v + 1, v + 2);
+        mesh.add_triangle(v, v + 2, v + 3);
+    }
+
+    // Bottom: a fan around the base center, facing down
+    let middle = mesh.vertex_count() as u32;
+    let [x, y, z] = [ox + (width as f64 - 1.0) / 2.0, oy + (depth as f64 - 1.0) / 2.0, base];
+    mesh.add_vertex(x, y, z, 0.0, 0.0, -1.0);
+    for &(column, row) in &boundary {
+        let [x, y, z] = point(column, row, base);
+        mesh.add_vertex(x, y, z, 0.0, 0.0, -1.0);
+    }
+    let ring = boundary.len() as u32;
+    for i in 0..ring {
+        mesh.add_triangle(middle, middle + 1 + (i + 1) % ring, middle + 1 + i);
+    }
 }
 
 // =============================================================================
@@ -466,6 +825,34 @@ mod tests {
         assert!(mesh.triangle_count() > 0);
     }
 
+    /// Test every sphere style is closed, consistently wound and on the sphere.
+    #[test]
+    fn test_sphere_styles() {
+        for (style, triangles) in [(SphereStyle::Uv, 252), (SphereStyle::Icosphere, 320), (SphereStyle::CubeSphere, 192)] {
+            let mut mesh = Mesh::new();
+            build_sphere_styled(&mut mesh, 2.0, 16, style);
+            assert_eq!(mesh.triangle_count(), triangles, "{:?}", style);
+            assert!(mesh.validate().is_valid(), "{:?}", style);
+            assert!(mesh.volume() > 0.0, "{:?}", style);
+            for p in mesh.vertices.chunks_exact(3) {
+                let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+                assert!((r - 2.0).abs() < 1e-5, "{:?}", style);
+            }
+        }
+    }
+
+    /// Test a rounded cube keeps its size and is closed.
+    #[test]
+    fn test_build_rounded_cube() {
+        let mesh = build_rounded_cube([10.0, 6.0, 4.0], 1.0, false, 16).unwrap();
+        assert!(mesh.validate().is_valid());
+        let max = |k: usize| mesh.vertices.iter().skip(k).step_by(3).fold(f64::MIN, |m, &v| m.max(v));
+        let min = |k: usize| mesh.vertices.iter().skip(k).step_by(3).fold(f64::MAX, |m, &v| m.min(v));
+        for (k, size) in [10.0, 6.0, 4.0].into_iter().enumerate() {
+            assert!(min(k).abs() < 1e-5 && (max(k) - size).abs() < 1e-5);
+        }
+    }
+
     /// Test cylinder construction.
     #[test]
     fn test_build_cylinder() {
@@ -503,4 +890,67 @@ mod tests {
         build_polyhedron(&mut mesh, &points, &faces);
         assert_eq!(mesh.triangle_count(), 4);
     }
+
+    /// Test a non-convex face is ear-clipped and invalid faces are skipped.
+    #[test]
+    fn test_build_polyhedron_non_convex_and_invalid() {
+        let mut mesh = Mesh::new();
+        // L-shaped face in the XY plane, wound clockwise seen from +Z
+        let points = [
+            [0.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [1.0, 2.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [2.0, 1.0, 0.0],
+            [2.0, 0.0, 0.0],
+        ];
+        let faces = vec![
+            vec![0, 1, 2, 3, 4, 5],
+            vec![0, 1, 9],      // out of range
+            vec![0, 1, 1],      // too few distinct vertices
+        ];
+        build_polyhedron(&mut mesh, &points, &faces);
+        assert_eq!(mesh.triangle_count(), 4);
+        assert_eq!(mesh.vertex_count(), 6);
+
+        // Total area equals the L-shape's area (3), so no triangle overlaps
+        let v = |i: u32| {
+            let i = i as usize * 3;
+            [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+        };
+        let mut area = 0.0;
+        for t in mesh.indices.chunks(3) {
+            let (a, b, c) = (v(t[0]), v(t[1]), v(t[2]));
+            let z = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+            // Reversed to CCW, so every triangle faces +Z
+            assert!(z > 0.0);
+            area += z / 2.0;
+        }
+        assert!((area - 3.0).abs() < 1e-5);
+    }
+
+    /// Test a heightmap is a closed solid on its base.
+    #[test]
+    fn test_build_surface() {
+        let rows = vec![vec![1.0, 3.0, 2.0], vec![0.5, 4.0, 1.0], vec![2.0, 2.0, 2.0], vec![1.0, 0.0, 1.0]];
+        let mut mesh = Mesh::new();
+        build_surface(&mut mesh, &rows, false);
+        // 2 per cell, 2 per boundary segment, 1 per bottom fan segment
+        assert_eq!(mesh.triangle_count(), 2 * 6 + 2 * 10 + 10);
+        assert!(crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&mesh).1.is_manifold());
+
+        let z: Vec<f64> = mesh.vertices.chunks(3).map(|v| v[2]).collect();
+        assert_eq!(z.iter().cloned().fold(f64::MAX, f64::min), -1.0);
+        assert_eq!(z.iter().cloned().fold(f64::MIN, f64::max), 4.0);
+
+        let mut centered = Mesh::new();
+        build_surface(&mut centered, &rows, true);
+        let xs: Vec<f64> = centered.vertices.chunks(3).map(|v| v[0]).collect();
+        assert_eq!(xs.iter().cloned().fold(f64::MAX, f64::min), -1.0);
+        assert_eq!(xs.iter().cloned().fold(f64::MIN, f64::max), 1.0);
+
+        let mut empty = Mesh::new();
+        build_surface(&mut empty, &[vec![1.0, 2.0]], false);
+        assert_eq!(empty.triangle_count(), 0);
+    }
 }
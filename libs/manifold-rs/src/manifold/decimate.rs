@@ -0,0 +1,556 @@
+//! # Quadric Decimation
+//!
+//! Reduces a mesh to a target triangle count by collapsing edges, cheapest
+//! first.
+//!
+//! ## Algorithm
+//!
+//! Garland–Heckbert quadric error metrics: every vertex carries the sum of
+//! the squared distances to the planes of its triangles, as a 4x4 quadric.
+//! Collapsing an edge moves both ends to the point minimising their summed
+//! quadric, and the error there is the cost of the collapse. Collapses
+//! come off a priority queue, cheapest first (ties by vertex index, so the
+//! result is deterministic), until the mesh is small enough or no safe
+//! collapse is left.
+//!
+//! A collapse is skipped when it would:
+//!
+//! - glue two sheets together (the ends share a neighbour that is not
+//!   opposite the edge),
+//! - pinch an interior edge between two boundary vertices, or fold a
+//!   tetrahedron flat,
+//! - flip or flatten a neighbouring triangle,
+//! - move a vertex on a non-manifold edge.
+//!
+//! Boundary edges add a heavy quadric for the plane through them
+//! perpendicular to their triangle, so open outlines keep their shape.
+//! Normals are recomputed from the remaining triangles; colors follow the
+//! vertices that survive and node ids the triangles.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::manifold::decimate::decimate;
+//!
+//! let mesh = manifold_rs::render("sphere(10, $fn = 48);").unwrap();
+//! let coarse = decimate(&mesh, 400);
+//! assert!(coarse.triangle_count() <= 400);
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+
+use crate::mesh::Mesh;
+
+/// Weight of boundary quadrics, per unit of squared edge length.
+const BOUNDARY_WEIGHT: f64 = 1000.0;
+
+/// Smallest cosine between a triangle's normal before and after a
+/// collapse.
+const MIN_TURN_COS: f64 = 0.2;
+
+// =============================================================================
+// DECIMATION
+// =============================================================================
+
+/// Collapse edges of a mesh until it has at most `target_triangles`.
+///
+/// ## Parameters
+///
+/// - `mesh`: Mesh to decimate
+/// - `target_triangles`: Triangle count to stop at
+///
+/// ## Returns
+///
+/// The decimated mesh. It keeps more triangles than asked for when no
+/// further collapse is safe; a mesh already small enough is returned
+/// unchanged.
+pub fn decimate(mesh: &Mesh, target_triangles: usize) -> Mesh {
+    if mesh.triangle_count() <= target_triangles {
+        return mesh.clone();
+    }
+    let mut decimator = Decimator::new(mesh);
+    decimator.run(target_triangles);
+    decimator.into_mesh(mesh)
+}
+
+/// Symmetric 4x4 quadric, stored as its upper triangle:
+/// `aa ab ac ad bb bc bd cc cd dd`.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// Squared distance to the plane `n·p + d = 0`, times `weight`.
+    fn plane([a, b, c]: [f64; 3], d: f64, weight: f64) -> Self {
+        Self([a * a, a * b, a * c, a * d, b * b, b * c, b * d, c * c, c * d, d * d].map(|v| v * weight))
+    }
+
+    /// Sum of two quadrics.
+    fn add(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+
+    /// Error at point `p`.
+    fn error(&self, [x, y, z]: [f64; 3]) -> f64 {
+        let q = &self.0;
+        q[0] * x * x + 2.0 * q[1] * x * y + 2.0 * q[2] * x * z + 2.0 * q[3] * x
+            + q[4] * y * y + 2.0 * q[5] * y * z + 2.0 * q[6] * y
+            + q[7] * z * z + 2.0 * q[8] * z
+            + q[9]
+    }
+
+    /// Point of least error, `None` if the quadric is (nearly) singular.
+    fn minimum(&self) -> Option<[f64; 3]> {
+        let q = &self.0;
+        let m = [[q[0], q[1], q[2]], [q[1], q[4], q[5]], [q[2], q[5], q[7]]];
+        let rhs = [-q[3], -q[6], -q[8]];
+        let det = det3(m);
+        let trace = q[0] + q[4] + q[7];
+        if det.abs() <= 1e-9 * trace * trace * trace {
+            return None;
+        }
+        // Cramer's rule
+        Some(std::array::from_fn(|k| {
+            let mut mk = m;
+            for (row, r) in mk.iter_mut().zip(rhs) {
+                row[k] = r;
+            }
+            det3(mk) / det
+        }))
+    }
+}
+
+/// A queued edge collapse.
+#[derive(Debug, Clone, Copy)]
+struct Collapse {
+    cost: f64,
+    edge: (u32, u32),
+    /// Versions of both ends when queued; stale entries are skipped.
+    versions: (u32, u32),
+    position: [f64; 3],
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    // Reversed, so the max-heap pops the cheapest collapse first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| other.edge.cmp(&self.edge))
+    }
+}
+
+/// Working state of a decimation, over welded vertices.
+struct Decimator {
+    points: Vec<[f64; 3]>,
+    /// Original vertex → welded vertex.
+    weld: Vec<u32>,
+    /// Welded vertex → the vertex it collapsed into (itself while alive).
+    merged: Vec<u32>,
+    /// Triangles as original vertex indices; collapsed ones are `None`.
+    triangles: Vec<Option<[u32; 3]>>,
+    /// Node id of each triangle, if the mesh has them.
+    nodes: Option<Vec<u32>>,
+    /// Welded vertex → triangles using it (may include dead ones).
+    incident: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    /// Vertices on non-manifold edges, which never move.
+    locked: Vec<bool>,
+    versions: Vec<u32>,
+    queue: BinaryHeap<Collapse>,
+    live: usize,
+}
+
+impl Decimator {
+    /// Weld the mesh and queue every edge.
+    fn new(mesh: &Mesh) -> Self {
+        let (points, weld) = mesh.welded_positions();
+        let n = points.len();
+
+        let kept: Vec<(usize, [u32; 3])> = mesh.indices.chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .enumerate()
+            .filter(|(_, t)| {
+                let [a, b, c] = t.map(|i| weld[i as usize]);
+                a != b && b != c && a != c
+            })
+            .collect();
+        let nodes = mesh.nodes.as_ref().map(|nodes| kept.iter().map(|&(t, _)| nodes[t]).collect());
+        let triangles: Vec<Option<[u32; 3]>> = kept.into_iter().map(|(_, t)| Some(t)).collect();
+
+        let mut decimator = Self {
+            merged: (0..n as u32).collect(),
+            incident: vec![Vec::new(); n],
+            quadrics: vec![Quadric::default(); n],
+            locked: vec![false; n],
+            versions: vec![0; n],
+            queue: BinaryHeap::new(),
+            live: triangles.len(),
+            points,
+            weld,
+            triangles,
+            nodes,
+        };
+
+        // Face quadrics, weighted by area, and the faces of every edge
+        let mut edges: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+        for t in 0..decimator.triangles.len() {
+            let Some(face) = decimator.face(t) else {
+                continue;
+            };
+            let normal = decimator.normal(face);
+            let area = length(normal) / 2.0;
+            if area > 0.0 {
+                let unit = normal.map(|c| c / (2.0 * area));
+                let plane = Quadric::plane(unit, -dot(unit, decimator.points[face[0] as usize]), area);
+                for v in face {
+                    decimator.quadrics[v as usize] = decimator.quadrics[v as usize].add(&plane);
+                }
+            }
+            for (k, &v) in face.iter().enumerate() {
+                decimator.incident[v as usize].push(t);
+                let w = face[(k + 1) % 3];
+                edges.entry((v.min(w), v.max(w))).or_default().push(t);
+            }
+        }
+
+        // Boundary edges hold their ends to the outline; non-manifold ones lock them
+        let mut keys: Vec<(u32, u32)> = edges.keys().copied().collect();
+        keys.sort_unstable();
+        for &(a, b) in &keys {
+            let faces = &edges[&(a, b)];
+            match faces.len() {
+                1 => {
+                    let Some(face) = decimator.face(faces[0]) else {
+                        continue;
+                    };
+                    let (pa, pb) = (decimator.points[a as usize], decimator.points[b as usize]);
+                    let along = sub(pb, pa);
+                    let side = cross(along, decimator.normal(face));
+                    let len = length(side);
+                    if len > 0.0 {
+                        let unit = side.map(|c| c / len);
+                        let weight = BOUNDARY_WEIGHT * dot(along, along);
+                        let plane = Quadric::plane(unit, -dot(unit, pa), weight);
+                        for v in [a, b] {
+                            decimator.quadrics[v as usize] = decimator.quadrics[v as usize].add(&plane);
+                        }
+                    }
+                }
+                2 => {}
+                _ => {
+                    decimator.locked[a as usize] = true;
+                    decimator.locked[b as usize] = true;
+                }
+            }
+        }
+        for (a, b) in keys {
+            decimator.queue_edge(a, b);
+        }
+        decimator
+    }
+
+    /// Collapse the cheapest safe edges until `target` triangles remain.
+    fn run(&mut self, target: usize) {
+        while self.live > target {
+            let Some(collapse) = self.queue.pop() else {
+                break;
+            };
+            let (u, v) = collapse.edge;
+            let current = (self.versions[u as usize], self.versions[v as usize]);
+            if current != collapse.versions || self.merged[u as usize] != u || self.merged[v as usize] != v {
+                continue;
+            }
+            if self.collapse(u, v, collapse.position) {
+                for w in self.neighbours(v) {
+                    self.queue_edge(v, w);
+                }
+            }
+        }
+    }
+
+    /// Queue the collapse of edge `a`-`b` at its cheapest position.
+    fn queue_edge(&mut self, a: u32, b: u32) {
+        if self.locked[a as usize] || self.locked[b as usize] {
+            return;
+        }
+        let (a, b) = (a.min(b), a.max(b));
+        let quadric = self.quadrics[a as usize].add(&self.quadrics[b as usize]);
+        let (pa, pb) = (self.points[a as usize], self.points[b as usize]);
+        let mid = std::array::from_fn(|i| (pa[i] + pb[i]) / 2.0);
+
+        // The exact minimum, unless ill-conditioned enough to wander off
+        let reach = 4.0 * dot(sub(pb, pa), sub(pb, pa));
+        let optimum = quadric.minimum().filter(|&p| dot(sub(p, mid), sub(p, mid)) <= reach);
+        let (cost, position) = optimum.into_iter()
+            .chain([pa, pb])
+            .map(|p| (quadric.error(p).max(0.0), p))
+            .fold((quadric.error(mid).max(0.0), mid), |best, next| if next.0 < best.0 { next } else { best });
+
+        self.queue.push(Collapse {
+            cost,
+            edge: (a, b),
+            versions: (self.versions[a as usize], self.versions[b as usize]),
+            position,
+        });
+    }
+
+    /// Collapse `u` into `v` at `position`, if that is safe.
+    fn collapse(&mut self, u: u32, v: u32, position: [f64; 3]) -> bool {
+        let mut faces: Vec<usize> = self.incident[u as usize].iter()
+            .chain(&self.incident[v as usize])
+            .copied()
+            .filter(|&t| self.triangles[t].is_some())
+            .collect();
+        faces.sort_unstable();
+        faces.dedup();
+        let welded: Vec<(usize, [u32; 3])> = faces.iter()
+            .filter_map(|&t| Some((t, self.face(t)?)))
+            .collect();
+
+        let (shared, moved): (Vec<_>, Vec<_>) = welded.iter().partition(|(_, f)| f.contains(&u) && f.contains(&v));
+        if shared.is_empty() || shared.len() > 2 {
+            return false;
+        }
+
+        // Link condition: the only common neighbours are opposite the edge
+        let nu = self.neighbours(u);
+        let nv = self.neighbours(v);
+        let common = nu.iter().filter(|w| **w != v && nv.contains(w)).count();
+        if common != shared.len() {
+            return false;
+        }
+        // A closed piece that small would fold into a double-sided sheet
+        let ring = nu.iter().chain(&nv).filter(|w| **w != u && **w != v).collect::<BTreeSet<_>>();
+        if shared.len() == 2 && ring.len() <= 2 {
+            return false;
+        }
+        if shared.len() == 2 && self.on_boundary(u) && self.on_boundary(v) {
+            return false;
+        }
+
+        // No neighbouring triangle may flip or collapse
+        for (_, face) in &moved {
+            let before = self.normal(*face);
+            let after = self.normal_with(*face, |w| if w == u || w == v { position } else { self.points[w as usize] });
+            let (lb, la) = (length(before), length(after));
+            if la <= 1e-12 * lb.max(1e-30) || dot(before, after) < MIN_TURN_COS * lb * la {
+                return false;
+            }
+        }
+
+        for (t, _) in shared {
+            self.triangles[t] = None;
+            self.live -= 1;
+        }
+        self.merged[u as usize] = v;
+        self.points[v as usize] = position;
+        self.quadrics[v as usize] = self.quadrics[v as usize].add(&self.quadrics[u as usize]);
+        self.incident[v as usize] = faces.into_iter().filter(|&t| self.triangles[t].is_some()).collect();
+        self.incident[u as usize].clear();
+        self.versions[u as usize] += 1;
+        self.versions[v as usize] += 1;
+        true
+    }
+
+    /// Alive vertex an original vertex has collapsed into.
+    fn root(&self, original: u32) -> u32 {
+        let mut v = self.weld[original as usize];
+        while self.merged[v as usize] != v {
+            v = self.merged[v as usize];
+        }
+        v
+    }
+
+    /// Welded corners of a live triangle.
+    fn face(&self, t: usize) -> Option<[u32; 3]> {
+        self.triangles[t].map(|corners| corners.map(|c| self.root(c)))
+    }
+
+    /// Vertices sharing a live triangle with `v`, in ascending order.
+    fn neighbours(&self, v: u32) -> Vec<u32> {
+        let mut out: Vec<u32> = self.incident[v as usize].iter()
+            .filter_map(|&t| self.face(t))
+            .flatten()
+            .filter(|&w| w != v)
+            .collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+
+    /// Whether `v` has an edge used by a single live triangle.
+    fn on_boundary(&self, v: u32) -> bool {
+        let mut uses: HashMap<u32, usize> = HashMap::new();
+        for face in self.incident[v as usize].iter().filter_map(|&t| self.face(t)) {
+            for w in face.into_iter().filter(|&w| w != v) {
+                *uses.entry(w).or_default() += 1;
+            }
+        }
+        uses.values().any(|&n| n == 1)
+    }
+
+    /// Unnormalized normal (twice the area) of a welded triangle.
+    fn normal(&self, face: [u32; 3]) -> [f64; 3] {
+        self.normal_with(face, |w| self.points[w as usize])
+    }
+
+    /// Unnormalized normal with the corners placed by `at`.
+    fn normal_with(&self, face: [u32; 3], at: impl Fn(u32) -> [f64; 3]) -> [f64; 3] {
+        let [a, b, c] = face.map(at);
+        cross(sub(b, a), sub(c, a))
+    }
+
+    /// Build the output mesh, keeping the original vertices' colors and
+    /// the triangles' node ids.
+    fn into_mesh(self, mesh: &Mesh) -> Mesh {
+        let mut out = Mesh::new();
+        let mut colors = mesh.colors.as_ref().map(|_| Vec::new());
+        let mut nodes = self.nodes.as_ref().map(|_| Vec::new());
+        let mut map: HashMap<u32, u32> = HashMap::new();
+        let mut sums: Vec<[f64; 3]> = Vec::new();
+
+        for t in 0..self.triangles.len() {
+            let (Some(corners), Some(face)) = (self.triangles[t], self.face(t)) else {
+                continue;
+            };
+            let normal = self.normal(face);
+            let [a, b, c] = corners.map(|o| {
+                let index = *map.entry(o).or_insert_with(|| {
+                    let p = self.points[self.root(o) as usize];
+                    if let (Some(dst), Some(src)) = (colors.as_mut(), mesh.colors.as_ref()) {
+                        dst.extend_from_slice(&src[o as usize * 4..o as usize * 4 + 4]);
+                    }
+                    sums.push([0.0; 3]);
+                    out.add_vertex(p[0], p[1], p[2], 0.0, 0.0, 0.0)
+                });
+                let sum = &mut sums[index as usize];
+                *sum = std::array::from_fn(|i| sum[i] + normal[i]);
+                index
+            });
+            out.add_triangle(a, b, c);
+            if let (Some(dst), Some(src)) = (nodes.as_mut(), self.nodes.as_ref()) {
+                dst.push(src[t]);
+            }
+        }
+
+        for (i, sum) in sums.into_iter().enumerate() {
+            let len = length(sum);
+            if len > 0.0 {
+                for (n, c) in out.normals[i * 3..i * 3 + 3].iter_mut().zip(sum) {
+                    *n = (c / len) as f32;
+                }
+            }
+        }
+        out.colors = colors;
+        out.nodes = nodes;
+        out.weld(0.0);
+        out
+    }
+}
+
+/// Determinant of a 3x3 matrix.
+fn det3(m: [[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+/// Difference `a - b`.
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Cross product.
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Dot product.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Euclidean length.
+fn length(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::halfedge::HalfEdgeMesh;
+
+    /// Test a fine sphere reaches the target, stays closed and keeps its shape.
+    #[test]
+    fn test_sphere_decimated() {
+        let mesh = crate::render("sphere(10, $fn = 48);").unwrap();
+        let coarse = decimate(&mesh, 300);
+        assert!(coarse.triangle_count() <= 300, "{} triangles", coarse.triangle_count());
+        assert!(coarse.triangle_count() > 200);
+        assert!(HalfEdgeMesh::from_mesh(&coarse).1.is_manifold());
+        for p in coarse.vertices.chunks_exact(3) {
+            let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            assert!((9.0..=10.5).contains(&r), "radius {}", r);
+        }
+    }
+
+    /// Test an open flat grid keeps its outline and area.
+    #[test]
+    fn test_flat_grid_keeps_outline() {
+        let mut mesh = Mesh::new();
+        for y in 0..5 {
+            for x in 0..5 {
+                mesh.add_vertex(x as f64, y as f64, 0.0, 0.0, 0.0, 1.0);
+            }
+        }
+        for y in 0..4 {
+            for x in 0..4 {
+                let i = y * 5 + x;
+                mesh.add_triangle(i, i + 1, i + 6);
+                mesh.add_triangle(i, i + 6, i + 5);
+            }
+        }
+        let coarse = decimate(&mesh, 2);
+        assert!(coarse.triangle_count() < 8, "{} triangles", coarse.triangle_count());
+
+        let area: f64 = coarse.indices.chunks_exact(3)
+            .map(|t| {
+                let p = |i: u32| [coarse.vertices[i as usize * 3], coarse.vertices[i as usize * 3 + 1]];
+                let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+                ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.0
+            })
+            .sum();
+        assert!((area - 16.0).abs() < 1e-3, "area {}", area);
+        assert!(coarse.normals.chunks_exact(3).all(|n| n == [0.0, 0.0, 1.0]));
+    }
+
+    /// Test small meshes and unreachable targets leave the mesh closed.
+    #[test]
+    fn test_limits() {
+        let cube = crate::render("cube(10);").unwrap();
+        assert_eq!(decimate(&cube, 12).indices, cube.indices);
+        let squashed = decimate(&cube, 0);
+        assert!(squashed.triangle_count() >= 4);
+        assert!(HalfEdgeMesh::from_mesh(&squashed).1.is_manifold());
+    }
+}
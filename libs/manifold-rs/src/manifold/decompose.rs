@@ -0,0 +1,296 @@
+//! # Convex Decomposition
+//!
+//! Splits a closed mesh into convex cells that together fill its volume.
+//!
+//! ## Algorithm
+//!
+//! A solid-leaf BSP tree is built from the mesh's face planes. Each node
+//! splits space by the plane of one face; faces in that plane are
+//! consumed, the rest are sorted (and split) to the front or back. A back
+//! side without faces left is inside the solid, so the path to it is a
+//! convex cell: the intersection of the half-spaces on the way down,
+//! bounded by the mesh's bounding box. Cell corners are found as the
+//! intersections of three bounding planes that satisfy all the others.
+//!
+//! Cells touch along shared planes but do not overlap. Their number
+//! depends on face order and grows with the mesh's concavity; a convex
+//! mesh is one cell.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::manifold::decompose::convex_decomposition;
+//!
+//! let mesh = manifold_rs::render("difference() { cube(2); translate([1, 1, -1]) cube(3); }").unwrap();
+//! assert!(convex_decomposition(&mesh).len() >= 2);
+//! ```
+
+use crate::mesh::Mesh;
+
+/// Distance below which a point counts as on a plane, relative to the
+/// mesh's largest coordinate.
+const RELATIVE_EPSILON: f64 = 1e-6;
+
+/// Half-space `normal · x <= offset`.
+type HalfSpace = ([f64; 3], f64);
+
+/// Parts of a split polygon in front of and behind a plane.
+pub(crate) type SplitPolygon = (Option<Vec<[f64; 3]>>, Option<Vec<[f64; 3]>>);
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Decompose a closed mesh into convex cells.
+///
+/// ## Parameters
+///
+/// - `mesh`: Closed, outward-oriented mesh
+///
+/// ## Returns
+///
+/// Corner points of each cell. Empty if the mesh has no volume.
+pub fn convex_decomposition(mesh: &Mesh) -> Vec<Vec<[f64; 3]>> {
+    let polygons: Vec<Polygon> = mesh.indices.chunks_exact(3)
+        .filter_map(|t| {
+            let points: Vec<[f64; 3]> = t.iter()
+                .map(|&i| {
+                    let i = i as usize * 3;
+                    [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+                })
+                .collect();
+            Polygon::new(points)
+        })
+        .collect();
+    if polygons.is_empty() {
+        return Vec::new();
+    }
+
+    let corners = polygons.iter().flat_map(|p| p.points.iter());
+    let min = corners.clone().fold([f64::MAX; 3], |m, p| [m[0].min(p[0]), m[1].min(p[1]), m[2].min(p[2])]);
+    let max = corners.fold([f64::MIN; 3], |m, p| [m[0].max(p[0]), m[1].max(p[1]), m[2].max(p[2])]);
+    let extent = min.iter().chain(&max).fold(0.0f64, |m, c| m.max(c.abs()));
+    let epsilon = RELATIVE_EPSILON * extent.max(f64::MIN_POSITIVE);
+
+    // The bounding box closes cells at the mesh's outer faces
+    let mut bounds: Vec<HalfSpace> = Vec::new();
+    for k in 0..3 {
+        let mut n = [0.0; 3];
+        n[k] = 1.0;
+        bounds.push((n, max[k]));
+        n[k] = -1.0;
+        bounds.push((n, -min[k]));
+    }
+
+    let mut cells = Vec::new();
+    build(polygons, &mut bounds, epsilon, &mut cells);
+    cells
+}
+
+// =============================================================================
+// SOLID BSP
+// =============================================================================
+
+/// Convex polygon with its unit normal and plane offset.
+struct Polygon {
+    points: Vec<[f64; 3]>,
+    normal: [f64; 3],
+    offset: f64,
+}
+
+impl Polygon {
+    /// Polygon from counter-clockwise points, `None` if degenerate.
+    fn new(points: Vec<[f64; 3]>) -> Option<Self> {
+        let n = polygon_normal(&points);
+        let len = dot(n, n).sqrt();
+        if points.len() < 3 || len <= 0.0 {
+            return None;
+        }
+        let normal = n.map(|c| c / len);
+        let offset = dot(normal, points[0]);
+        Some(Self { points, normal, offset })
+    }
+}
+
+/// Recursively split `polygons`, collecting inside cells.
+///
+/// `path` holds the half-spaces bounding the current region; it is
+/// restored before returning.
+fn build(mut polygons: Vec<Polygon>, path: &mut Vec<HalfSpace>, epsilon: f64, cells: &mut Vec<Vec<[f64; 3]>>) {
+    let splitter = polygons.swap_remove(0);
+    let (normal, offset) = (splitter.normal, splitter.offset);
+
+    let (mut front, mut back) = (Vec::new(), Vec::new());
+    for polygon in polygons {
+        let distances: Vec<f64> = polygon.points.iter().map(|&p| dot(normal, p) - offset).collect();
+        if distances.iter().all(|d| d.abs() <= epsilon) {
+            continue;
+        }
+        let (f, b) = split_polygon(&polygon.points, &distances, epsilon);
+        front.extend(f.and_then(Polygon::new));
+        back.extend(b.and_then(Polygon::new));
+    }
+
+    // Front: outside this face; empty means outside the solid
+    if !front.is_empty() {
+        path.push((normal.map(|c| -c), -offset));
+        build(front, path, epsilon, cells);
+        path.pop();
+    }
+
+    // Back: inside this face; empty means inside the solid
+    path.push((normal, offset));
+    if back.is_empty() {
+        let corners = cell_corners(path, epsilon);
+        if corners.len() >= 4 {
+            cells.push(corners);
+        }
+    } else {
+        build(back, path, epsilon, cells);
+    }
+    path.pop();
+}
+
+/// Split a convex polygon by signed distances to a plane into the parts
+/// in front and behind; points within `epsilon` of the plane go to both.
+pub(crate) fn split_polygon(points: &[[f64; 3]], distances: &[f64], epsilon: f64) -> SplitPolygon {
+    if distances.iter().all(|&d| d <= epsilon) {
+        return (None, Some(points.to_vec()));
+    }
+    if distances.iter().all(|&d| d >= -epsilon) {
+        return (Some(points.to_vec()), None);
+    }
+    let (mut front, mut back) = (Vec::new(), Vec::new());
+    for i in 0..points.len() {
+        let j = (i + 1) % points.len();
+        let (p, q, dp, dq) = (points[i], points[j], distances[i], distances[j]);
+        if dp >= -epsilon {
+            front.push(p);
+        }
+        if dp <= epsilon {
+            back.push(p);
+        }
+        if (dp > epsilon && dq < -epsilon) || (dp < -epsilon && dq > epsilon) {
+            let t = dp / (dp - dq);
+            let x = [p[0] + (q[0] - p[0]) * t, p[1] + (q[1] - p[1]) * t, p[2] + (q[2] - p[2]) * t];
+            front.push(x);
+            back.push(x);
+        }
+    }
+    ((front.len() >= 3).then_some(front), (back.len() >= 3).then_some(back))
+}
+
+/// Newell normal of a polygon (not normalised).
+pub(crate) fn polygon_normal(points: &[[f64; 3]]) -> [f64; 3] {
+    let mut n = [0.0; 3];
+    for i in 0..points.len() {
+        let (p, q) = (points[i], points[(i + 1) % points.len()]);
+        n[0] += (p[1] - q[1]) * (p[2] + q[2]);
+        n[1] += (p[2] - q[2]) * (p[0] + q[0]);
+        n[2] += (p[0] - q[0]) * (p[1] + q[1]);
+    }
+    n
+}
+
+/// Corners of the region inside all half-spaces.
+///
+/// Every triple of planes is intersected and the points inside all
+/// half-spaces kept, without duplicates.
+fn cell_corners(halfspaces: &[HalfSpace], epsilon: f64) -> Vec<[f64; 3]> {
+    let mut corners: Vec<[f64; 3]> = Vec::new();
+    let n = halfspaces.len();
+    for i in 0..n {
+        for j in i + 1..n {
+            for k in j + 1..n {
+                let Some(p) = intersect([halfspaces[i], halfspaces[j], halfspaces[k]]) else {
+                    continue;
+                };
+                let inside = halfspaces.iter().all(|&(normal, offset)| dot(normal, p) - offset <= epsilon);
+                let duplicate = corners.iter().any(|c| (0..3).all(|a| (c[a] - p[a]).abs() <= epsilon));
+                if inside && !duplicate {
+                    corners.push(p);
+                }
+            }
+        }
+    }
+    corners
+}
+
+/// Common point of three planes, `None` if they don't meet in one point.
+fn intersect([(n1, d1), (n2, d2), (n3, d3)]: [HalfSpace; 3]) -> Option<[f64; 3]> {
+    let (c23, c31, c12) = (cross(n2, n3), cross(n3, n1), cross(n1, n2));
+    let det = dot(n1, c23);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    Some([0, 1, 2].map(|a| (d1 * c23[a] + d2 * c31[a] + d3 * c12[a]) / det))
+}
+
+/// Dot product.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Cross product.
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifold::hull::compute_hull;
+
+    /// Summed volume of the hulls of the cells.
+    fn cells_volume(cells: &[Vec<[f64; 3]>]) -> f64 {
+        cells.iter()
+            .map(|cell| {
+                let mut points = Mesh::new();
+                for p in cell {
+                    points.add_vertex(p[0], p[1], p[2], 0.0, 0.0, 1.0);
+                }
+                let hull = compute_hull(&[points]).unwrap();
+                hull.indices.chunks_exact(3)
+                    .map(|t| {
+                        let p = |i: u32| {
+                            let i = i as usize * 3;
+                            [hull.vertices[i], hull.vertices[i + 1], hull.vertices[i + 2]]
+                        };
+                        dot(p(t[0]), cross(p(t[1]), p(t[2]))) / 6.0
+                    })
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+
+    /// Test a convex mesh is a single cell.
+    #[test]
+    fn test_convex_single_cell() {
+        let cells = convex_decomposition(&crate::render("cube(2);").unwrap());
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].len(), 8);
+    }
+
+    /// Test the cells of a non-convex mesh fill it exactly.
+    #[test]
+    fn test_cells_fill_volume() {
+        for (source, volume) in [
+            ("difference() { cube(2); translate([1, 1, -1]) cube(3); }", 6.0),
+            ("difference() { cube(3, center = true); cube([1, 1, 4], center = true); }", 24.0),
+        ] {
+            let cells = convex_decomposition(&crate::render(source).unwrap());
+            assert!(cells.len() >= 2, "{}", source);
+            assert!((cells_volume(&cells) - volume).abs() < 1e-4, "{}: {}", source, cells_volume(&cells));
+        }
+    }
+
+    /// Test an empty mesh has no cells.
+    #[test]
+    fn test_empty() {
+        assert!(convex_decomposition(&Mesh::new()).is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 //! # Convex Hull
 //!
-//! QuickHull algorithm for computing convex hulls of point sets.
+//! QuickHull algorithm for computing convex hulls of point sets, plus a
+//! monotone chain hull for flat 2D geometry.
 //!
 //! ## Algorithm
 //!
@@ -27,8 +28,11 @@ use std::collections::HashSet;
 // CONSTANTS
 // =============================================================================
 
-/// Tolerance for coplanarity tests.
-const EPSILON: f32 = 1e-7;
+/// Tolerance for coplanarity tests, relative to the largest coordinate.
+///
+/// Input positions carry the rounding of the operations that made them,
+/// so anything closer to a plane than that counts as on it.
+const RELATIVE_EPSILON: f64 = 1e-6;
 
 // =============================================================================
 // PUBLIC API
@@ -63,7 +67,7 @@ const EPSILON: f32 = 1e-7;
 /// ```
 pub fn compute_hull(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
     // Collect all unique points
-    let mut points: Vec<[f32; 3]> = Vec::new();
+    let mut points: Vec<[f64; 3]> = Vec::new();
     let mut seen: HashSet<[i32; 3]> = HashSet::new();
     
     for mesh in meshes {
@@ -94,6 +98,85 @@ pub fn compute_hull(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
     quickhull(&points)
 }
 
+/// Compute the 2D convex hull of flat meshes.
+///
+/// Vertices are projected onto the XY plane, the way `hull()` treats 2D
+/// children. Points on the hull's edges are dropped.
+///
+/// ## Parameters
+///
+/// - `meshes`: Slice of 2D meshes to hull
+///
+/// ## Returns
+///
+/// Upward-facing polygon at z = 0, or an empty mesh if the points span
+/// no area
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::mesh::Mesh;
+/// use manifold_rs::manifold::hull::compute_hull_2d;
+/// use manifold_rs::cross_section::primitives::build_square_mesh;
+///
+/// let mut a = Mesh::new();
+/// build_square_mesh(&mut a, [1.0, 1.0], false);
+/// let mut b = a.clone();
+/// b.translate(3.0, 0.0, 0.0);
+///
+/// let hull = compute_hull_2d(&[a, b]).unwrap();
+/// assert_eq!(hull.vertex_count(), 4);
+/// ```
+pub fn compute_hull_2d(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
+    let points: Vec<[f64; 2]> = meshes.iter()
+        .flat_map(|mesh| mesh.vertices.chunks_exact(3).map(|p| [p[0], p[1]]))
+        .collect();
+    let extent = points.iter().fold(0.0f64, |m, p| m.max(p[0].abs()).max(p[1].abs()));
+    let tolerance = RELATIVE_EPSILON * extent * extent;
+
+    let mut mesh = Mesh::new();
+    let hull = convex_hull_2d(points, tolerance);
+    if hull.len() < 3 {
+        return Ok(mesh);
+    }
+    let indices: Vec<u32> = hull.iter()
+        .map(|p| mesh.add_vertex(p[0], p[1], 0.0, 0.0, 0.0, 1.0))
+        .collect();
+    for i in 1..indices.len() - 1 {
+        mesh.add_triangle(indices[0], indices[i], indices[i + 1]);
+    }
+    Ok(mesh)
+}
+
+/// Counter-clockwise convex hull of 2D points (Andrew's monotone chain).
+///
+/// Turns whose cross product is at most `tolerance` count as straight,
+/// so collinear points are left out.
+pub(crate) fn convex_hull_2d(mut points: Vec<[f64; 2]>, tolerance: f64) -> Vec<[f64; 2]> {
+    points.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let turn = |o: [f64; 2], a: [f64; 2], b: [f64; 2]| (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0]);
+    let mut hull: Vec<[f64; 2]> = Vec::new();
+    for pass in 0..2 {
+        let start = hull.len();
+        for &p in points.iter() {
+            while hull.len() >= start + 2 && turn(hull[hull.len() - 2], hull[hull.len() - 1], p) <= tolerance {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+    hull
+}
+
 // =============================================================================
 // QUICKHULL IMPLEMENTATION
 // =============================================================================
@@ -111,7 +194,7 @@ pub fn compute_hull(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
 ///    - Delete visible faces
 ///    - Create new faces from horizon edges + new point
 ///    - Reassign outside points from deleted faces to new faces
-fn quickhull(points: &[[f32; 3]]) -> ManifoldResult<Mesh> {
+fn quickhull(points: &[[f64; 3]]) -> ManifoldResult<Mesh> {
     // Find extreme points for initial tetrahedron
     let (min_x, max_x, min_y, max_y, min_z, max_z) = find_extreme_points(points);
     
@@ -130,7 +213,7 @@ fn quickhull(points: &[[f32; 3]]) -> ManifoldResult<Mesh> {
 }
 
 /// Find indices of extreme points (min/max in each dimension).
-fn find_extreme_points(points: &[[f32; 3]]) -> (usize, usize, usize, usize, usize, usize) {
+fn find_extreme_points(points: &[[f64; 3]]) -> (usize, usize, usize, usize, usize, usize) {
     let mut min_x = 0;
     let mut max_x = 0;
     let mut min_y = 0;
@@ -162,7 +245,9 @@ fn find_extreme_points(points: &[[f32; 3]]) -> (usize, usize, usize, usize, usiz
 /// - Set of point indices outside this face
 struct Hull<'a> {
     /// Reference to original points
-    points: &'a [[f32; 3]],
+    points: &'a [[f64; 3]],
+    /// Distance below which a point counts as on a plane
+    epsilon: f64,
     /// Active faces in the hull
     faces: Vec<HullFace>,
     /// Which points are already part of the hull vertices
@@ -174,9 +259,9 @@ struct HullFace {
     /// Vertex indices (into Hull.points)
     verts: [usize; 3],
     /// Face normal (outward pointing)
-    normal: [f32; 3],
+    normal: [f64; 3],
     /// Distance from origin along normal
-    d: f32,
+    d: f64,
     /// Point indices that are outside this face
     outside: Vec<usize>,
     /// Is this face still active?
@@ -185,9 +270,11 @@ struct HullFace {
 
 impl<'a> Hull<'a> {
     /// Create new hull builder with reference to points.
-    fn new(points: &'a [[f32; 3]]) -> Self {
+    fn new(points: &'a [[f64; 3]]) -> Self {
+        let extent = points.iter().flatten().fold(0.0f64, |m, c| m.max(c.abs()));
         Self {
             points,
+            epsilon: RELATIVE_EPSILON * extent.max(f64::MIN_POSITIVE),
             faces: Vec::new(),
             in_hull: vec![false; points.len()],
         }
@@ -207,7 +294,7 @@ impl<'a> Hull<'a> {
     ) -> bool {
         // Find two most distant points among extremes
         let extremes = [min_x, max_x, min_y, max_y, min_z, max_z];
-        let mut max_dist = 0.0f32;
+        let mut max_dist = 0.0f64;
         let mut p0 = 0;
         let mut p1 = 0;
         
@@ -222,12 +309,12 @@ impl<'a> Hull<'a> {
             }
         }
         
-        if max_dist < EPSILON {
+        if max_dist.sqrt() < self.epsilon {
             return false;
         }
         
         // Find point farthest from line p0-p1
-        let mut max_dist = 0.0f32;
+        let mut max_dist = 0.0f64;
         let mut p2 = 0;
         for (i, p) in self.points.iter().enumerate() {
             let dist = point_line_distance_sq(p, &self.points[p0], &self.points[p1]);
@@ -237,17 +324,17 @@ impl<'a> Hull<'a> {
             }
         }
         
-        if max_dist < EPSILON {
+        if max_dist.sqrt() < self.epsilon {
             return false;
         }
         
         // Find point farthest from plane p0-p1-p2
-        let plane_normal = cross(
+        let plane_normal = normalize(&cross(
             &sub(&self.points[p1], &self.points[p0]),
             &sub(&self.points[p2], &self.points[p0]),
-        );
+        ));
         
-        let mut max_dist = 0.0f32;
+        let mut max_dist = 0.0f64;
         let mut p3 = 0;
         for (i, p) in self.points.iter().enumerate() {
             let dist = dot(&sub(p, &self.points[p0]), &plane_normal).abs();
@@ -257,7 +344,7 @@ impl<'a> Hull<'a> {
             }
         }
         
-        if max_dist < EPSILON {
+        if max_dist < self.epsilon {
             return false;
         }
         
@@ -317,13 +404,14 @@ impl<'a> Hull<'a> {
     }
     
     /// Assign a single point to the first face it's outside of.
-    fn assign_point_to_face(&mut self, pt_idx: usize, p: &[f32; 3]) {
+    fn assign_point_to_face(&mut self, pt_idx: usize, p: &[f64; 3]) {
+        let epsilon = self.epsilon;
         for face in &mut self.faces {
             if !face.active {
                 continue;
             }
             let dist = dot(&face.normal, p) - face.d;
-            if dist > EPSILON {
+            if dist > epsilon {
                 face.outside.push(pt_idx);
                 return;
             }
@@ -337,7 +425,7 @@ impl<'a> Hull<'a> {
             // Find face with farthest outside point
             let mut best_face = None;
             let mut best_pt = 0;
-            let mut best_dist = 0.0f32;
+            let mut best_dist = self.epsilon;
             
             for (face_idx, face) in self.faces.iter().enumerate() {
                 if !face.active || face.outside.is_empty() {
@@ -365,7 +453,7 @@ impl<'a> Hull<'a> {
     }
     
     /// Add a point to the hull by updating visible faces.
-    fn add_point_to_hull(&mut self, start_face: usize, pt_idx: usize) {
+    fn add_point_to_hull(&mut self, _start_face: usize, pt_idx: usize) {
         let p = &self.points[pt_idx];
         
         // Find all visible faces (point is above the face plane)
@@ -375,12 +463,17 @@ impl<'a> Hull<'a> {
                 continue;
             }
             let dist = dot(&face.normal, p) - face.d;
-            if dist > EPSILON {
+            if dist > self.epsilon {
                 visible.push(i);
             }
         }
         
         if visible.is_empty() {
+            // Not outside any face after all: drop it from the outside sets
+            self.in_hull[pt_idx] = true;
+            for face in &mut self.faces {
+                face.outside.retain(|&i| i != pt_idx);
+            }
             return;
         }
         
@@ -413,8 +506,9 @@ impl<'a> Hull<'a> {
                 });
                 
                 if is_horizon {
-                    // Store edge with correct winding (v1, v0) for new face
-                    horizon.push((v1, v0));
+                    // The new face replaces this one on the same side of
+                    // the edge, so it keeps the edge's direction
+                    horizon.push((v0, v1));
                 }
             }
         }
@@ -460,18 +554,9 @@ impl<'a> Hull<'a> {
             let p1 = self.points[face.verts[1]];
             let p2 = self.points[face.verts[2]];
             
-            let v0 = mesh.add_vertex(
-                p0[0], p0[1], p0[2],
-                face.normal[0], face.normal[1], face.normal[2],
-            );
-            let v1 = mesh.add_vertex(
-                p1[0], p1[1], p1[2],
-                face.normal[0], face.normal[1], face.normal[2],
-            );
-            let v2 = mesh.add_vertex(
-                p2[0], p2[1], p2[2],
-                face.normal[0], face.normal[1], face.normal[2],
-            );
+            let [nx, ny, nz] = face.normal.map(|c| c as f32);
+            let [v0, v1, v2] = [p0, p1, p2]
+                .map(|p| mesh.add_vertex(p[0], p[1], p[2], nx, ny, nz));
             
             mesh.add_triangle(v0, v1, v2);
         }
@@ -484,15 +569,15 @@ impl<'a> Hull<'a> {
 // VECTOR MATH HELPERS
 // =============================================================================
 
-fn sub(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
+fn sub(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
     [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
 }
 
-fn dot(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
     a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
 }
 
-fn cross(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
     [
         a[1] * b[2] - a[2] * b[1],
         a[2] * b[0] - a[0] * b[2],
@@ -500,7 +585,7 @@ fn cross(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
     ]
 }
 
-fn normalize(v: &[f32; 3]) -> [f32; 3] {
+fn normalize(v: &[f64; 3]) -> [f64; 3] {
     let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
     if len > 0.0 {
         [v[0] / len, v[1] / len, v[2] / len]
@@ -509,12 +594,12 @@ fn normalize(v: &[f32; 3]) -> [f32; 3] {
     }
 }
 
-fn distance_sq(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+fn distance_sq(a: &[f64; 3], b: &[f64; 3]) -> f64 {
     let d = sub(a, b);
     dot(&d, &d)
 }
 
-fn point_line_distance_sq(p: &[f32; 3], a: &[f32; 3], b: &[f32; 3]) -> f32 {
+fn point_line_distance_sq(p: &[f64; 3], a: &[f64; 3], b: &[f64; 3]) -> f64 {
     let ab = sub(b, a);
     let ap = sub(p, a);
     let c = cross(&ab, &ap);
@@ -542,6 +627,24 @@ mod tests {
         assert!(hull.triangle_count() <= 12, "Cube hull should have at most 12 triangles");
     }
 
+    /// Test points on the faces and edges of a box are not hull vertices.
+    #[test]
+    fn test_hull_coplanar_points() {
+        let mut points = Mesh::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    points.add_vertex(x as f64, y as f64, z as f64, 0.0, 0.0, 1.0);
+                }
+            }
+        }
+        let hull = compute_hull(&[points]).unwrap();
+        assert_eq!(hull.welded_positions().0.len(), 8);
+        assert_eq!(hull.triangle_count(), 12);
+        let (_, report) = crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&hull);
+        assert!(report.is_manifold());
+    }
+
     /// Test hull of empty input.
     #[test]
     fn test_hull_empty() {
@@ -575,6 +678,40 @@ mod tests {
         assert!(hull.triangle_count() >= 4);
     }
     
+    /// Test the 2D hull of two circles is one flat polygon.
+    #[test]
+    fn test_hull_2d_circles() {
+        let mut a = Mesh::new();
+        crate::cross_section::primitives::build_circle_mesh(&mut a, 1.0, 8);
+        let mut b = a.clone();
+        b.translate(5.0, 0.0, 0.0);
+
+        let hull = compute_hull_2d(&[a, b]).unwrap();
+        // Five points of each octagon, those facing the other circle dropped
+        assert_eq!(hull.vertex_count(), 10);
+        assert_eq!(hull.triangle_count(), 8);
+        assert!(hull.vertices.chunks_exact(3).all(|p| p[2] == 0.0));
+        let area: f64 = hull.indices.chunks_exact(3)
+            .map(|t| {
+                let p = |i: u32| [hull.vertices[i as usize * 3], hull.vertices[i as usize * 3 + 1]];
+                let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+                ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.0
+            })
+            .sum();
+        // Octagon area 2√2 plus the 5 × 2 band between the circles
+        assert!((area - (2.0 * 2f64.sqrt() + 10.0)).abs() < 1e-4, "{}", area);
+    }
+
+    /// Test collinear 2D points have no hull.
+    #[test]
+    fn test_hull_2d_collinear() {
+        let mut line = Mesh::new();
+        for x in 0..4 {
+            line.add_vertex(x as f64, 2.0 * x as f64, 0.0, 0.0, 0.0, 1.0);
+        }
+        assert!(compute_hull_2d(&[line]).unwrap().is_empty());
+    }
+
     /// Test hull with insufficient points returns empty.
     #[test]
     fn test_hull_insufficient_points() {
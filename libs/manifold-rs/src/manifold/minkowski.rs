@@ -1,22 +1,40 @@
 //! # Minkowski Sum
 //!
-//! Computes the Minkowski sum of multiple meshes using vertex-sum + hull.
+//! Computes the Minkowski sum of multiple meshes.
 //!
 //! ## Algorithm
 //!
-//! For convex shapes A and B:
-//! `A ⊕ B = { a + b : a ∈ A, b ∈ B }`
+//! `A ⊕ B = { a + b : a ∈ A, b ∈ B }`, folded left over the children.
 //!
-//! Implementation:
-//! 1. Collect all vertices from both meshes
-//! 2. Compute pairwise sums
-//! 3. Take convex hull of result
+//! For convex A and B the sum is the convex hull of all vertex sums.
+//! Otherwise one operand, B, is split into convex cells `Cₖ` (B itself if
+//! convex, see [`decompose`](super::decompose)) and
 //!
-//! Note: This is exact for convex inputs, approximate for non-convex.
+//! `A ⊕ B = ⋃ₖ (A + cₖ) ∪ ⋃ₖ ⋃ₛ hull(s ⊕ Cₖ)`
+//!
+//! over the convex faces `s` of A, for any `cₖ` in `Cₖ`: sliding A
+//! across a cell from `cₖ` covers the interior, the swept faces the rest.
+//! Choosing `cₖ` inside the cell puts every `A + cₖ` strictly inside the
+//! swept pieces, so the surface of the sum is the part of the pieces'
+//! surfaces outside all other pieces and all `A + cₖ`. Because the pieces
+//! are convex this clipping is exact and needs no general booleans.
+//!
+//! Neighbouring pieces are clipped differently, so their surfaces meet
+//! with T-junctions; like boolean results, the output is then
+//! [canonicalized](crate::mesh::canonical) into a closed manifold. The
+//! number of pieces is the number of convex faces of A times the number
+//! of cells of B, so keep the non-convex operands coarse where possible.
+
+use std::collections::HashMap;
 
 use crate::error::ManifoldResult;
 use crate::mesh::Mesh;
-use super::hull::compute_hull;
+use super::boolean::{point_inside_mesh, union_all, weld_tolerance, IndexedMesh};
+use super::decompose::{convex_decomposition, polygon_normal, split_polygon};
+use super::hull::{compute_hull, convex_hull_2d};
+
+/// Relative tolerance for the convexity test.
+const CONVEX_TOLERANCE: f64 = 1e-5;
 
 // =============================================================================
 // PUBLIC API
@@ -24,15 +42,13 @@ use super::hull::compute_hull;
 
 /// Compute Minkowski sum of multiple meshes.
 ///
-/// Takes all vertices and computes pairwise sums, then convex hull.
-///
 /// ## Parameters
 ///
 /// - `meshes`: Slice of meshes (at least 2 for meaningful result)
 ///
 /// ## Returns
 ///
-/// Minkowski sum mesh
+/// Minkowski sum mesh. Empty meshes are skipped.
 ///
 /// ## Example
 ///
@@ -52,60 +68,349 @@ use super::hull::compute_hull;
 /// assert!(!result.is_empty());
 /// ```
 pub fn compute_minkowski(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
-    if meshes.is_empty() {
+    let mut operands = meshes.iter().filter(|m| !m.is_empty());
+    let Some(first) = operands.next() else {
         return Ok(Mesh::new());
+    };
+    let mut result = first.clone();
+    for mesh in operands {
+        result = minkowski_pair(&result, mesh)?;
+    }
+    Ok(result)
+}
+
+/// Whether a closed mesh is convex: every vertex lies on or behind the
+/// plane of every face.
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::mesh::Mesh;
+/// use manifold_rs::manifold::minkowski::is_convex;
+/// use manifold_rs::manifold::constructors::build_cube;
+///
+/// let mut cube = Mesh::new();
+/// build_cube(&mut cube, [1.0, 1.0, 1.0], false);
+/// assert!(is_convex(&cube));
+/// ```
+pub fn is_convex(mesh: &Mesh) -> bool {
+    let (points, remap) = mesh.welded_positions();
+    let extent = points.iter()
+        .flat_map(|p| p.iter())
+        .fold(0.0f64, |m, c| m.max(c.abs()));
+    let tolerance = CONVEX_TOLERANCE * extent.max(1.0);
+
+    mesh.indices.chunks_exact(3).all(|t| {
+        let corners = [t[0], t[1], t[2]].map(|i| points[remap[i as usize] as usize]);
+        unit_normal(corners).is_none_or(|n| points.iter().all(|&p| dot(n, sub(p, corners[0])) <= tolerance))
+    })
+}
+
+// =============================================================================
+// DECOMPOSITION
+// =============================================================================
+
+/// Minkowski sum of two non-empty meshes.
+fn minkowski_pair(a: &Mesh, b: &Mesh) -> ManifoldResult<Mesh> {
+    match (is_convex(a), is_convex(b)) {
+        (true, true) => hull_of_sums(&a.welded_positions().0, &b.welded_positions().0),
+        (false, true) => sweep(a, &[b.welded_positions().0]),
+        (true, false) => sweep(b, &[a.welded_positions().0]),
+        (false, false) => sweep(a, &convex_decomposition(b)),
+    }
+}
+
+/// Sum of a mesh and the union of convex cells.
+///
+/// With `cₖ` inside cell `Cₖ`, each `A + cₖ` lies strictly inside the
+/// sweep of A's boundary over `Cₖ`, so the result's surface is the outer
+/// surface of the sweep: the parts of each swept piece outside every
+/// other piece and outside every `A + cₖ`. Pieces are convex, so the
+/// clipping is exact; the fragments are then welded and split at
+/// T-junctions into a closed surface. Flat cells have no inside point;
+/// then the pieces fall back to boolean unions.
+fn sweep(solid: &Mesh, cells: &[Vec<[f64; 3]>]) -> ManifoldResult<Mesh> {
+    let faces = convex_faces(solid);
+    let mut pieces = Vec::with_capacity(faces.len() * cells.len());
+    for cell in cells {
+        for face in &faces {
+            pieces.push(hull_of_sums(face, cell)?);
+        }
+    }
+    let centers: Vec<[f64; 3]> = cells.iter()
+        .map(|cell| {
+            cell.iter()
+                .fold([0.0f64; 3], |c, p| [c[0] + p[0], c[1] + p[1], c[2] + p[2]])
+                .map(|c| c / cell.len() as f64)
+        })
+        .collect();
+    if cells.is_empty() || pieces.iter().any(Mesh::is_empty) {
+        pieces.retain(|m| !m.is_empty());
+        pieces.extend(centers.iter().map(|&c| translated(solid, c)));
+        return union_all(&pieces);
+    }
+    let index = IndexedMesh::new(solid);
+
+    let pieces: Vec<ConvexPiece> = pieces.iter().map(ConvexPiece::new).collect();
+    let mut mesh = Mesh::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        for triangle in &piece.triangles {
+            let mut fragments = vec![triangle.to_vec()];
+            for (j, other) in pieces.iter().enumerate() {
+                if j != i {
+                    fragments = fragments.into_iter()
+                        .flat_map(|f| other.clip_outside(f, j > i))
+                        .collect();
+                }
+            }
+            for fragment in fragments {
+                let n = fragment.len() as f64;
+                let centroid = fragment.iter()
+                    .fold([0.0; 3], |c, p| [c[0] + p[0], c[1] + p[1], c[2] + p[2]])
+                    .map(|c| c / n);
+                let in_core = centers.iter().any(|c| point_inside_mesh(&sub(centroid, *c), &index));
+                if !in_core {
+                    add_polygon(&mut mesh, &fragment);
+                }
+            }
+        }
     }
-    
-    if meshes.len() == 1 {
-        return Ok(meshes[0].clone());
-    }
-    
-    // Start with first mesh vertices
-    let mut current_points: Vec<[f32; 3]> = Vec::new();
-    for i in (0..meshes[0].vertices.len()).step_by(3) {
-        current_points.push([
-            meshes[0].vertices[i],
-            meshes[0].vertices[i + 1],
-            meshes[0].vertices[i + 2],
-        ]);
-    }
-    
-    // Add each subsequent mesh via pairwise sums
-    for mesh in &meshes[1..] {
-        let mut next_points: Vec<[f32; 3]> = Vec::new();
-        
-        // Collect vertices from current mesh
-        let mut mesh_points: Vec<[f32; 3]> = Vec::new();
-        for i in (0..mesh.vertices.len()).step_by(3) {
-            mesh_points.push([
-                mesh.vertices[i],
-                mesh.vertices[i + 1],
-                mesh.vertices[i + 2],
-            ]);
+    mesh.canonicalize(f64::from(weld_tolerance()));
+    Ok(mesh)
+}
+
+/// Closed convex mesh prepared for clipping.
+struct ConvexPiece {
+    /// Triangles, counter-clockwise from outside.
+    triangles: Vec<[[f64; 3]; 3]>,
+    /// Outward unit normal and offset of each face plane.
+    planes: Vec<([f64; 3], f64)>,
+    /// Bounding box.
+    min: [f64; 3],
+    max: [f64; 3],
+    /// Distance below which a point counts as on a plane.
+    epsilon: f64,
+}
+
+impl ConvexPiece {
+    fn new(mesh: &Mesh) -> Self {
+        let triangles: Vec<[[f64; 3]; 3]> = mesh.indices.chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]].map(|i| {
+                let i = i as usize * 3;
+                [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+            }))
+            .collect();
+        let planes = triangles.iter()
+            .filter_map(|&[a, b, c]| {
+                let n = cross(sub(b, a), sub(c, a));
+                let len = dot(n, n).sqrt();
+                (len > 0.0).then(|| {
+                    let n = n.map(|c| c / len);
+                    (n, dot(n, a))
+                })
+            })
+            .collect();
+        let corners = triangles.iter().flatten();
+        let min = corners.clone().fold([f64::MAX; 3], |m, p| [m[0].min(p[0]), m[1].min(p[1]), m[2].min(p[2])]);
+        let max = corners.fold([f64::MIN; 3], |m, p| [m[0].max(p[0]), m[1].max(p[1]), m[2].max(p[2])]);
+        let extent = min.iter().chain(&max).fold(0.0f64, |m, c| m.max(c.abs()));
+        Self { triangles, planes, min, max, epsilon: CONVEX_TOLERANCE * extent.max(1.0) }
+    }
+
+    /// Parts of a convex polygon outside this piece.
+    ///
+    /// Parts lying on a face of this piece are kept if they face the same
+    /// way and `keep_shared` is set, so exactly one of two coincident
+    /// faces survives; parts touching an opposite face are dropped.
+    fn clip_outside(&self, polygon: Vec<[f64; 3]>, keep_shared: bool) -> Vec<Vec<[f64; 3]>> {
+        let e = self.epsilon;
+        let disjoint = (0..3).any(|k| {
+            polygon.iter().all(|p| p[k] < self.min[k] - e) || polygon.iter().all(|p| p[k] > self.max[k] + e)
+        });
+        if disjoint {
+            return vec![polygon];
         }
-        
-        // Compute pairwise sums
-        for p1 in &current_points {
-            for p2 in &mesh_points {
-                next_points.push([
-                    p1[0] + p2[0],
-                    p1[1] + p2[1],
-                    p1[2] + p2[2],
-                ]);
+        let normal = polygon_normal(&polygon);
+
+        let mut outside = Vec::new();
+        let mut rest = polygon;
+        let mut shared = None;
+        for &(n, d) in &self.planes {
+            let distances: Vec<f64> = rest.iter().map(|&p| dot(n, p) - d).collect();
+            if distances.iter().all(|s| s.abs() <= e) {
+                shared = Some(dot(n, normal) > 0.0);
+                continue;
             }
+            let (front, back) = split_polygon(&rest, &distances, e);
+            outside.extend(front);
+            match back {
+                Some(back) => rest = back,
+                None => return outside,
+            }
+        }
+        // What is left is inside the piece, or on one of its faces
+        if shared == Some(true) && keep_shared {
+            outside.push(rest);
         }
-        
-        current_points = next_points;
+        outside
+    }
+}
+
+/// Append a convex polygon as a triangle fan with a flat normal.
+fn add_polygon(mesh: &mut Mesh, polygon: &[[f64; 3]]) {
+    let n = polygon_normal(polygon);
+    let len = dot(n, n).sqrt();
+    if len <= 0.0 {
+        return;
     }
-    
-    // Convert points to mesh for hull computation
-    let mut point_mesh = Mesh::new();
-    for p in &current_points {
-        point_mesh.add_vertex(p[0], p[1], p[2], 0.0, 0.0, 1.0);
+    let [nx, ny, nz] = n.map(|c| (c / len) as f32);
+    let ids: Vec<u32> = polygon.iter()
+        .map(|p| mesh.add_vertex(p[0], p[1], p[2], nx, ny, nz))
+        .collect();
+    for k in 1..ids.len() - 1 {
+        mesh.add_triangle(ids[0], ids[k], ids[k + 1]);
     }
-    
-    // Take convex hull
-    compute_hull(&[point_mesh])
+}
+
+/// Convex hull of all pairwise sums of two point sets.
+fn hull_of_sums(a: &[[f64; 3]], b: &[[f64; 3]]) -> ManifoldResult<Mesh> {
+    let mut points = Mesh::new();
+    for p in a {
+        for q in b {
+            points.add_vertex(p[0] + q[0], p[1] + q[1], p[2] + q[2], 0.0, 0.0, 1.0);
+        }
+    }
+    compute_hull(&[points])
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Corner positions of the convex planar faces of a mesh.
+///
+/// Edge-connected triangles in one plane are grouped; a group whose
+/// triangles exactly fill their convex hull is one face, otherwise each
+/// of its triangles is.
+fn convex_faces(mesh: &Mesh) -> Vec<Vec<[f64; 3]>> {
+    let (points, remap) = mesh.welded_positions();
+    let triangles: Vec<[u32; 3]> = mesh.indices.chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]].map(|i| remap[i as usize]))
+        .filter(|[a, b, c]| a != b && b != c && a != c)
+        .collect();
+    let normals: Vec<Option<[f64; 3]>> = triangles.iter()
+        .map(|t| unit_normal(t.map(|i| points[i as usize])))
+        .collect();
+
+    // Triangles by undirected edge, to find coplanar neighbours
+    let mut by_edge: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            by_edge.entry((a.min(b), a.max(b))).or_default().push(i);
+        }
+    }
+
+    let mut faces = Vec::new();
+    let mut done = vec![false; triangles.len()];
+    for start in 0..triangles.len() {
+        if done[start] {
+            continue;
+        }
+        done[start] = true;
+        let Some(normal) = normals[start] else {
+            continue;
+        };
+        let plane = dot(normal, points[triangles[start][0] as usize]);
+
+        // Flood fill through edges into triangles of the same plane
+        let mut group = vec![start];
+        let mut next = 0;
+        while next < group.len() {
+            let t = triangles[group[next]];
+            next += 1;
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                for &other in &by_edge[&(a.min(b), a.max(b))] {
+                    let coplanar = normals[other].is_some_and(|n| {
+                        dot(n, normal) > 1.0 - CONVEX_TOLERANCE
+                            && (dot(normal, points[triangles[other][0] as usize]) - plane).abs()
+                                <= CONVEX_TOLERANCE * plane.abs().max(1.0)
+                    });
+                    if !done[other] && coplanar {
+                        done[other] = true;
+                        group.push(other);
+                    }
+                }
+            }
+        }
+
+        let corners: Vec<[f64; 3]> = {
+            let mut ids: Vec<u32> = group.iter().flat_map(|&g| triangles[g]).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            ids.into_iter().map(|i| points[i as usize]).collect()
+        };
+        let area: f64 = group.iter().map(|&g| triangle_area(triangles[g].map(|i| points[i as usize]))).sum();
+        if group.len() > 1 && (planar_hull_area(&corners, normal) - area).abs() <= CONVEX_TOLERANCE * area.max(1.0) {
+            faces.push(corners);
+        } else {
+            faces.extend(group.iter().map(|&g| triangles[g].map(|i| points[i as usize]).to_vec()));
+        }
+    }
+    faces
+}
+
+/// Area of the convex hull of points in a plane with the given normal.
+fn planar_hull_area(points: &[[f64; 3]], normal: [f64; 3]) -> f64 {
+    // Project onto the two axes least aligned with the normal
+    let axis = (0..3).max_by(|&a, &b| normal[a].abs().total_cmp(&normal[b].abs())).unwrap_or(2);
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    let flat: Vec<[f64; 2]> = points.iter().map(|p| [p[u], p[v]]).collect();
+    let hull = convex_hull_2d(flat, 0.0);
+
+    let twice: f64 = (0..hull.len())
+        .map(|i| {
+            let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+            a[0] * b[1] - a[1] * b[0]
+        })
+        .sum();
+    (twice / 2.0).abs() / normal[axis].abs()
+}
+
+/// Area of a triangle.
+fn triangle_area([a, b, c]: [[f64; 3]; 3]) -> f64 {
+    let n = cross(sub(b, a), sub(c, a));
+    dot(n, n).sqrt() / 2.0
+}
+
+/// Unit normal of a triangle, `None` if degenerate.
+fn unit_normal([a, b, c]: [[f64; 3]; 3]) -> Option<[f64; 3]> {
+    let n = cross(sub(b, a), sub(c, a));
+    let len = dot(n, n).sqrt();
+    (len > f64::from(f32::EPSILON)).then(|| n.map(|c| c / len))
+}
+
+/// Copy of a mesh moved by `offset`.
+fn translated(mesh: &Mesh, offset: [f64; 3]) -> Mesh {
+    let mut moved = mesh.clone();
+    moved.translate(offset[0], offset[1], offset[2]);
+    moved
+}
+
+/// Vector difference.
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Dot product.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Cross product.
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
 }
 
 // =============================================================================
@@ -116,6 +421,22 @@ pub fn compute_minkowski(meshes: &[Mesh]) -> ManifoldResult<Mesh> {
 mod tests {
     use super::*;
     use crate::manifold::constructors::build_cube;
+    use crate::mesh::halfedge::HalfEdgeMesh;
+
+    /// Enclosed volume by the divergence theorem.
+    pub fn volume(mesh: &Mesh) -> f64 {
+        mesh.indices.chunks_exact(3)
+            .map(|t| {
+                let p = |i: u32| {
+                    let i = i as usize * 3;
+                    [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+                };
+                let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+                (a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+                    + a[2] * (b[0] * c[1] - b[1] * c[0])) / 6.0
+            })
+            .sum()
+    }
 
     /// Test Minkowski sum of two cubes.
     ///
@@ -125,14 +446,13 @@ mod tests {
     fn test_minkowski_cubes() {
         let mut cube1 = Mesh::new();
         build_cube(&mut cube1, [10.0, 10.0, 10.0], true);
-        
+
         let mut cube2 = Mesh::new();
         build_cube(&mut cube2, [2.0, 2.0, 2.0], true);
-        
+
         let result = compute_minkowski(&[cube1, cube2]).unwrap();
-        assert!(!result.is_empty(), "Minkowski result should not be empty");
-        // Result should be cube-like (8 vertices for convex hull of combined corners)
-        assert!(result.triangle_count() >= 4, "Should have at least 4 triangles");
+        assert!((volume(&result) - 1728.0).abs() < 1e-3);
+        assert!(HalfEdgeMesh::from_mesh(&result).1.is_manifold());
     }
 
     /// Test Minkowski with single mesh.
@@ -140,7 +460,7 @@ mod tests {
     fn test_minkowski_single() {
         let mut cube = Mesh::new();
         build_cube(&mut cube, [10.0, 10.0, 10.0], true);
-        
+
         let result = compute_minkowski(&[cube]).unwrap();
         assert!(!result.is_empty());
     }
@@ -151,4 +471,62 @@ mod tests {
         let result = compute_minkowski(&[]).unwrap();
         assert!(result.is_empty());
     }
+
+    /// Test the convexity check.
+    #[test]
+    fn test_is_convex() {
+        assert!(is_convex(&crate::render("sphere(5, $fn = 16);").unwrap()));
+        assert!(!is_convex(&crate::render("difference() { cube(4); translate([2, 2, -1]) cube(4); }").unwrap()));
+    }
+
+    /// Test the rounded-cube pattern is the hull of the summed corners.
+    #[test]
+    fn test_minkowski_rounded_cube() {
+        let mesh = crate::render("minkowski() { cube(10, center = true); sphere(1, $fn = 16); }").unwrap();
+        let sphere = crate::render("sphere(1, $fn = 16);").unwrap();
+        assert!(HalfEdgeMesh::from_mesh(&mesh).1.is_manifold());
+
+        let extent = |m: &Mesh| m.vertices.iter().fold(0.0f64, |e, &c| e.max(c.abs()));
+        assert!((extent(&mesh) - (5.0 + extent(&sphere))).abs() < 1e-4);
+        // Between the cube grown by the inscribed sphere and by its bounding cube
+        let v = volume(&mesh);
+        assert!(v > 1000.0 + 6.0 * 100.0 * 0.9 && v < 1728.0, "{}", v);
+    }
+
+    /// Test a non-convex operand keeps its notch instead of being hulled.
+    #[test]
+    fn test_minkowski_non_convex() {
+        // L-shape: 4×4×1 minus a 2×2 corner
+        let mesh = crate::render("minkowski() { \
+            difference() { cube([4, 4, 1]); translate([2, 2, -1]) cube([3, 3, 3]); } \
+            cube(0.5, center = true); }").unwrap();
+        // Grown L-shape: 4.5×4.5×1.5 minus a 2×2 notch
+        let expected = (4.5 * 4.5 - 2.0 * 2.0) * 1.5;
+        assert!((volume(&mesh) - expected).abs() < 1e-3, "{}", volume(&mesh));
+        assert!(mesh.vertices.chunks_exact(3).all(|p| !(p[0] > 2.25 + 1e-4 && p[1] > 2.25 + 1e-4)));
+        assert!(mesh.validate().is_valid(), "{:?}", mesh.validate());
+    }
+
+    /// Test a frame grown by a sphere is closed where the swept pieces
+    /// of its faces meet.
+    #[test]
+    fn test_minkowski_non_convex_watertight() {
+        let mesh = crate::render("minkowski() { \
+            difference() { cube(10); translate([2, 2, -1]) cube([6, 6, 12]); } \
+            sphere(1, $fn = 8); }").unwrap();
+        let report = mesh.validate();
+        assert!(report.is_valid(), "{:?}", report);
+        assert!(HalfEdgeMesh::from_mesh(&mesh).1.is_manifold());
+    }
+
+    /// Test two non-convex operands.
+    #[test]
+    fn test_minkowski_both_non_convex() {
+        let l_shape = crate::render("difference() { cube([2, 2, 1]); translate([1, 1, -1]) cube(3); }").unwrap();
+        assert!(!is_convex(&l_shape));
+        let result = compute_minkowski(&[l_shape.clone(), l_shape]).unwrap();
+        // 4×4 footprint with a stepped notch of three unit squares, 2 high
+        assert!((volume(&result) - 26.0).abs() < 1e-3, "{}", volume(&result));
+        assert!(result.validate().is_valid(), "{:?}", result.validate());
+    }
 }
@@ -8,6 +8,12 @@
 //! - `boolean`: Union, Difference, Intersection operations
 //! - `hull`: Convex hull computation
 //! - `minkowski`: Minkowski sum
+//! - `decompose`: Convex decomposition
+//! - `smooth`: Taubin mesh smoothing
+//! - `simplify`: Coplanar face merging and render-time clean-up
+//! - `decimate`: Quadric edge-collapse decimation
+//! - `refine`: Edge splitting and Loop subdivision
+//! - `warp`: Per-vertex deformation: twist, taper, bend
 //!
 //! ## Algorithm Reference
 //!
@@ -20,7 +26,17 @@ pub mod constructors;
 pub mod boolean;
 pub mod hull;
 pub mod minkowski;
+pub mod decompose;
+pub mod smooth;
+pub mod simplify;
+pub mod decimate;
+pub mod refine;
+pub mod warp;
 
+use glam::DVec3;
+
+use crate::font::Polygon2D;
+use crate::mesh::slice::Plane;
 use crate::mesh::Mesh;
 
 // =============================================================================
@@ -95,4 +111,45 @@ impl Manifold {
     pub fn triangle_count(&self) -> usize {
         self.mesh.triangle_count()
     }
+
+    /// Split every edge into `n` pieces, keeping the shape.
+    ///
+    /// See [`refine::refine_mesh`].
+    #[must_use]
+    pub fn refine(&self, n: u32) -> Self {
+        Self::from_mesh(refine::refine_mesh(&self.mesh, n))
+    }
+
+    /// Split edges until none is longer than `length`, keeping the shape.
+    ///
+    /// See [`refine::refine_to_length`].
+    #[must_use]
+    pub fn refine_to_length(&self, length: f64) -> Self {
+        Self::from_mesh(refine::refine_to_length(&self.mesh, length))
+    }
+
+    /// Round the shape with `levels` of Loop subdivision.
+    ///
+    /// See [`refine::loop_subdivide`].
+    #[must_use]
+    pub fn smooth(&self, levels: u32) -> Self {
+        Self::from_mesh(refine::loop_subdivide(&self.mesh, levels))
+    }
+
+    /// Move every vertex to `f` of its position.
+    ///
+    /// See [`warp::warp_mesh`]; refine first so there are vertices to
+    /// move.
+    #[must_use]
+    pub fn warp<F: Fn(DVec3) -> DVec3>(&self, f: F) -> Self {
+        Self::from_mesh(warp::warp_mesh(&self.mesh, f))
+    }
+
+    /// Cross-section at height `z`, as regions in x and y.
+    ///
+    /// See [`Mesh::slice`] for how contours are formed.
+    #[must_use]
+    pub fn slice(&self, z: f64) -> Vec<Polygon2D> {
+        self.mesh.slice(&Plane::z(z))
+    }
 }
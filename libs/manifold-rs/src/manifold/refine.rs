@@ -0,0 +1,475 @@
+//! # Refinement
+//!
+//! Subdivides a mesh into smaller triangles, after Manifold-3D's `Refine`
+//! and `RefineToLength`, with Loop subdivision for smooth results.
+//!
+//! | Function | Triangles | Shape |
+//! |----------|-----------|-------|
+//! | [`refine_mesh`] | × n² | Unchanged, every edge split into `n` |
+//! | [`refine_to_length`] | As needed | Unchanged, no edge longer than `length` |
+//! | [`loop_subdivide`] | × 4 per level | Rounded toward a smooth surface |
+//!
+//! Flat refinement leaves the surface where it is, so a later operation
+//! that moves vertices has enough of them to bend it smoothly. Loop
+//! subdivision moves every vertex toward a smooth limit surface: a cube
+//! turns into a rounded blob, a coarse sphere into a round one.
+//!
+//! Points along an edge are computed from the edge alone, so triangles
+//! sharing it agree exactly and a closed mesh stays closed. Vertices are
+//! welded by exact position. The result is flat-shaded and vertex colors
+//! are not kept; apply `color()` outside `refine()`.
+//!
+//! ## Loop Subdivision
+//!
+//! Each level splits every triangle into four through its edge points:
+//!
+//! ```text
+//! edge point   = 3/8 (a + b) + 1/8 (c + d)      a, b on the edge; c, d opposite
+//! vertex point = (1 − kβ) v + β Σ neighbours    k neighbours; β = 3/16 for k = 3, else 3/(8k)
+//! ```
+//!
+//! Edges without exactly two faces are creases: their edge points are
+//! midpoints, a vertex on two crease edges moves along them only
+//! (`3/4 v + 1/8 (a + b)`), and a vertex on any other number of crease
+//! edges stays put.
+//!
+//! ## References
+//!
+//! - Loop, "Smooth Subdivision Surfaces Based on Triangles", 1987
+//! - [Manifold-3D](https://github.com/elalish/manifold) `Manifold::Refine`
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::manifold::refine::{loop_subdivide, refine_mesh};
+//!
+//! let cube = manifold_rs::render("cube(10);").unwrap();
+//! let fine = refine_mesh(&cube, 3);
+//! assert_eq!(fine.triangle_count(), 12 * 9);
+//! assert!((fine.volume() - 1000.0).abs() < 1e-9);
+//! let blob = loop_subdivide(&cube, 2);
+//! assert_eq!(blob.triangle_count(), 12 * 16);
+//! assert!(blob.volume() < 1000.0);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::mesh::Mesh;
+
+/// Most bisection passes of [`refine_to_length`], each at most
+/// quadrupling the triangles.
+pub const MAX_LENGTH_PASSES: u32 = 12;
+
+/// Most Loop levels [`refine`] applies to reach a length.
+pub const MAX_LOOP_LEVELS: u32 = 6;
+
+// =============================================================================
+// PARAMETERS
+// =============================================================================
+
+/// Refinement parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefineParams {
+    /// Pieces every edge is split into; with `smooth`, rounded up to a
+    /// power of two, one Loop level per doubling.
+    pub pieces: u32,
+    /// Keep splitting until no edge is longer than this.
+    pub max_length: Option<f64>,
+    /// Loop subdivision instead of flat splitting.
+    pub smooth: bool,
+}
+
+impl Default for RefineParams {
+    fn default() -> Self {
+        Self {
+            pieces: 2,
+            max_length: None,
+            smooth: false,
+        }
+    }
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Refine a mesh as `params` asks.
+///
+/// Flat refinement splits edges into `pieces` first and then bisects
+/// those still longer than `max_length`. Smooth refinement runs Loop
+/// levels until both are met, at most [`MAX_LOOP_LEVELS`] for the length.
+pub fn refine(mesh: &Mesh, params: &RefineParams) -> Mesh {
+    let (mut positions, mut triangles) = welded(mesh);
+    if params.smooth {
+        let mut level = 0;
+        while (1u64 << level) < u64::from(params.pieces)
+            || params.max_length.is_some_and(|length| level < MAX_LOOP_LEVELS && longest_edge(&positions, &triangles) > length)
+        {
+            triangles = loop_level(&mut positions, &triangles);
+            level += 1;
+        }
+    } else {
+        triangles = split_edges(&mut positions, &triangles, params.pieces);
+        if let Some(length) = params.max_length {
+            triangles = bisect_to_length(&mut positions, triangles, length);
+        }
+    }
+    flat_mesh(&positions, &triangles)
+}
+
+/// Split every edge into `pieces` and every triangle into `pieces²`.
+///
+/// The surface does not move; `0` and `1` leave the triangles as they are.
+pub fn refine_mesh(mesh: &Mesh, pieces: u32) -> Mesh {
+    refine(mesh, &RefineParams { pieces, ..RefineParams::default() })
+}
+
+/// Bisect edges until none is longer than `length`.
+///
+/// Stops after [`MAX_LENGTH_PASSES`] passes even if edges are still too
+/// long; a length that is not positive leaves the triangles as they are.
+pub fn refine_to_length(mesh: &Mesh, length: f64) -> Mesh {
+    refine(mesh, &RefineParams { pieces: 1, max_length: Some(length), smooth: false })
+}
+
+/// Apply `levels` of Loop subdivision.
+pub fn loop_subdivide(mesh: &Mesh, levels: u32) -> Mesh {
+    let (mut positions, mut triangles) = welded(mesh);
+    for _ in 0..levels {
+        triangles = loop_level(&mut positions, &triangles);
+    }
+    flat_mesh(&positions, &triangles)
+}
+
+// =============================================================================
+// FLAT REFINEMENT
+// =============================================================================
+
+/// Split every triangle into a grid of `n²`, appending the new points.
+fn split_edges(positions: &mut Vec<[f64; 3]>, triangles: &[[u32; 3]], n: u32) -> Vec<[u32; 3]> {
+    if n <= 1 {
+        return triangles.to_vec();
+    }
+    let mut edges: HashMap<(u32, u32), Vec<u32>> = HashMap::new();
+    let mut out = Vec::with_capacity(triangles.len() * (n * n) as usize);
+    for &[a, b, c] in triangles {
+        // rows[i][j]: the point i/n along a→b and j/n along a→c
+        let rows: Vec<Vec<u32>> = (0..=n)
+            .map(|i| {
+                (0..=n - i)
+                    .map(|j| match (i, j) {
+                        (0, 0) => a,
+                        (i, 0) if i == n => b,
+                        (0, j) if j == n => c,
+                        (i, 0) => edge_point(positions, &mut edges, a, b, i, n),
+                        (0, j) => edge_point(positions, &mut edges, a, c, j, n),
+                        (i, j) if i + j == n => edge_point(positions, &mut edges, b, c, j, n),
+                        (i, j) => {
+                            let (pa, pb, pc) = (positions[a as usize], positions[b as usize], positions[c as usize]);
+                            let (u, v) = (f64::from(i) / f64::from(n), f64::from(j) / f64::from(n));
+                            push(positions, std::array::from_fn(|k| pa[k] + u * (pb[k] - pa[k]) + v * (pc[k] - pa[k])))
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        for i in 0..n as usize {
+            for j in 0..n as usize - i {
+                out.push([rows[i][j], rows[i + 1][j], rows[i][j + 1]]);
+                if i + j + 1 < n as usize {
+                    out.push([rows[i + 1][j], rows[i + 1][j + 1], rows[i][j + 1]]);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// The point `k/n` of the way from `p` to `q`, shared by both triangles
+/// on the edge.
+fn edge_point(positions: &mut Vec<[f64; 3]>, edges: &mut HashMap<(u32, u32), Vec<u32>>, p: u32, q: u32, k: u32, n: u32) -> u32 {
+    let (lo, hi) = (p.min(q), p.max(q));
+    let points = edges.entry((lo, hi)).or_insert_with(|| {
+        let (a, b) = (positions[lo as usize], positions[hi as usize]);
+        (1..n)
+            .map(|i| {
+                let t = f64::from(i) / f64::from(n);
+                push(positions, std::array::from_fn(|axis| a[axis] + t * (b[axis] - a[axis])))
+            })
+            .collect()
+    });
+    let k = if p == lo { k } else { n - k };
+    points[k as usize - 1]
+}
+
+/// Bisect edges longer than `length` until none are left.
+fn bisect_to_length(positions: &mut Vec<[f64; 3]>, mut triangles: Vec<[u32; 3]>, length: f64) -> Vec<[u32; 3]> {
+    if length <= 0.0 || length.is_nan() {
+        return triangles;
+    }
+    for _ in 0..MAX_LENGTH_PASSES {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        for t in &triangles {
+            for k in 0..3 {
+                let (p, q) = (t[k], t[(k + 1) % 3]);
+                if distance(positions[p as usize], positions[q as usize]) > length {
+                    let key = (p.min(q), p.max(q));
+                    midpoints.entry(key).or_insert_with(|| {
+                        let (a, b) = (positions[key.0 as usize], positions[key.1 as usize]);
+                        push(positions, std::array::from_fn(|axis| (a[axis] + b[axis]) / 2.0))
+                    });
+                }
+            }
+        }
+        if midpoints.is_empty() {
+            break;
+        }
+
+        let mut next = Vec::with_capacity(triangles.len() * 2);
+        for t in triangles {
+            let mids = [0, 1, 2].map(|k| {
+                let (p, q) = (t[k], t[(k + 1) % 3]);
+                midpoints.get(&(p.min(q), p.max(q))).copied()
+            });
+            // Rotate so the split edges come first: a→b, then b→c
+            let Some(r) = (0..3).find(|&r| match [mids[r], mids[(r + 1) % 3], mids[(r + 2) % 3]] {
+                [None, None, None] => r == 0,
+                [Some(_), None, None] | [Some(_), Some(_), None] | [Some(_), Some(_), Some(_)] => true,
+                _ => false,
+            }) else {
+                continue;
+            };
+            let [a, b, c] = [t[r], t[(r + 1) % 3], t[(r + 2) % 3]];
+            match [mids[r], mids[(r + 1) % 3], mids[(r + 2) % 3]] {
+                [Some(ab), Some(bc), Some(ca)] => next.extend([[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]),
+                [Some(ab), Some(bc), None] => next.extend([[ab, b, bc], [a, ab, bc], [a, bc, c]]),
+                [Some(ab), None, None] => next.extend([[a, ab, c], [ab, b, c]]),
+                _ => next.push([a, b, c]),
+            }
+        }
+        triangles = next;
+    }
+    triangles
+}
+
+// =============================================================================
+// LOOP SUBDIVISION
+// =============================================================================
+
+/// One level of Loop subdivision: moves the existing points, appends the
+/// edge points and returns the four triangles of each.
+fn loop_level(positions: &mut Vec<[f64; 3]>, triangles: &[[u32; 3]]) -> Vec<[u32; 3]> {
+    // Corners opposite each edge, in the order edges first appear
+    let mut order: Vec<(u32, u32)> = Vec::new();
+    let mut opposite: HashMap<(u32, u32), Vec<u32>> = HashMap::new();
+    for &[a, b, c] in triangles {
+        for (p, q, r) in [(a, b, c), (b, c, a), (c, a, b)] {
+            let key = (p.min(q), p.max(q));
+            opposite.entry(key).or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            }).push(r);
+        }
+    }
+
+    let old = positions.clone();
+    let mut neighbours: Vec<Vec<u32>> = vec![Vec::new(); old.len()];
+    let mut creases: Vec<Vec<u32>> = vec![Vec::new(); old.len()];
+    for key in &order {
+        let (p, q) = *key;
+        neighbours[p as usize].push(q);
+        neighbours[q as usize].push(p);
+        if opposite[key].len() != 2 {
+            creases[p as usize].push(q);
+            creases[q as usize].push(p);
+        }
+    }
+
+    // Vertex points
+    for (v, position) in positions.iter_mut().enumerate() {
+        let p = old[v];
+        *position = match (creases[v].len(), neighbours[v].len()) {
+            (_, 0) => p,
+            (0, k) => {
+                let beta = if k == 3 { 3.0 / 16.0 } else { 3.0 / (8.0 * k as f64) };
+                let sum = neighbours[v].iter().fold([0.0; 3], |s, &n| add(s, old[n as usize]));
+                std::array::from_fn(|i| (1.0 - k as f64 * beta) * p[i] + beta * sum[i])
+            }
+            (2, _) => {
+                let (a, b) = (old[creases[v][0] as usize], old[creases[v][1] as usize]);
+                std::array::from_fn(|i| 0.75 * p[i] + 0.125 * (a[i] + b[i]))
+            }
+            _ => p,
+        };
+    }
+
+    // Edge points
+    let mut midpoints: HashMap<(u32, u32), u32> = HashMap::with_capacity(order.len());
+    for key in order {
+        let (a, b) = (old[key.0 as usize], old[key.1 as usize]);
+        let point = match opposite[&key][..] {
+            [c, d] => {
+                let (c, d) = (old[c as usize], old[d as usize]);
+                std::array::from_fn(|i| 0.375 * (a[i] + b[i]) + 0.125 * (c[i] + d[i]))
+            }
+            _ => std::array::from_fn(|i| (a[i] + b[i]) / 2.0),
+        };
+        midpoints.insert(key, push(positions, point));
+    }
+
+    let mid = |p: u32, q: u32| midpoints[&(p.min(q), p.max(q))];
+    triangles.iter()
+        .flat_map(|&[a, b, c]| {
+            let (ab, bc, ca) = (mid(a, b), mid(b, c), mid(c, a));
+            [[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]
+        })
+        .collect()
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Welded positions and the non-degenerate triangles between them.
+fn welded(mesh: &Mesh) -> (Vec<[f64; 3]>, Vec<[u32; 3]>) {
+    let (positions, weld) = mesh.welded_positions();
+    let triangles = mesh.indices.chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]].map(|v| weld[v as usize]))
+        .filter(|[a, b, c]| a != b && b != c && c != a)
+        .collect();
+    (positions, triangles)
+}
+
+/// Flat-shaded mesh of indexed triangles.
+fn flat_mesh(positions: &[[f64; 3]], triangles: &[[u32; 3]]) -> Mesh {
+    let mut mesh = Mesh::with_capacity(triangles.len() * 3, triangles.len());
+    for t in triangles {
+        let [a, b, c] = t.map(|v| positions[v as usize]);
+        let n = cross(sub(b, a), sub(c, a));
+        let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        let n = if len > 0.0 { n.map(|c| (c / len) as f32) } else { [0.0; 3] };
+        let ids = [a, b, c].map(|p| mesh.add_vertex(p[0], p[1], p[2], n[0], n[1], n[2]));
+        mesh.add_triangle(ids[0], ids[1], ids[2]);
+    }
+    mesh
+}
+
+/// Length of the longest edge.
+fn longest_edge(positions: &[[f64; 3]], triangles: &[[u32; 3]]) -> f64 {
+    triangles.iter()
+        .flat_map(|t| [0, 1, 2].map(|k| distance(positions[t[k] as usize], positions[t[(k + 1) % 3] as usize])))
+        .fold(0.0, f64::max)
+}
+
+/// Append a point, returning its index.
+fn push(positions: &mut Vec<[f64; 3]>, p: [f64; 3]) -> u32 {
+    positions.push(p);
+    (positions.len() - 1) as u32
+}
+
+/// Vector sum.
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// Vector difference.
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Cross product.
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// Distance between two points.
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    let d = sub(a, b);
+    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Longest edge of a mesh.
+    fn longest(mesh: &Mesh) -> f64 {
+        let (positions, triangles) = welded(mesh);
+        longest_edge(&positions, &triangles)
+    }
+
+    /// Test flat refinement keeps a closed mesh closed and in place.
+    #[test]
+    fn test_refine_keeps_shape() {
+        let cube = crate::render("cube([4, 2, 1]);").unwrap();
+        for n in [1, 2, 5] {
+            let fine = refine_mesh(&cube, n);
+            assert_eq!(fine.triangle_count(), 12 * (n * n) as usize);
+            assert!(fine.validate().is_valid(), "{}", n);
+            assert!((fine.volume() - 8.0).abs() < 1e-9);
+            assert!((fine.surface_area() - cube.surface_area()).abs() < 1e-9);
+        }
+        assert!((longest(&refine_mesh(&cube, 4)) - 20f64.sqrt() / 4.0).abs() < 1e-9);
+    }
+
+    /// Test refining to a length splits only where needed.
+    #[test]
+    fn test_refine_to_length() {
+        let bar = crate::render("cube([10, 1, 1]);").unwrap();
+        let fine = refine_to_length(&bar, 1.5);
+        assert!(longest(&fine) <= 1.5);
+        assert!(fine.validate().is_valid());
+        assert!((fine.volume() - 10.0).abs() < 1e-9);
+        // Already short enough
+        assert_eq!(refine_to_length(&bar, 100.0).triangle_count(), 12);
+        assert_eq!(refine_to_length(&bar, 0.0).triangle_count(), 12);
+    }
+
+    /// Test Loop subdivision rounds a cube while keeping it closed.
+    #[test]
+    fn test_loop_rounds() {
+        let cube = crate::render("cube(2, center = true);").unwrap();
+        let round = loop_subdivide(&cube, 3);
+        assert_eq!(round.triangle_count(), 12 * 64);
+        assert!(round.validate().is_valid());
+        let radii: Vec<f64> = round.vertices.chunks_exact(3).map(|p| (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt()).collect();
+        let (min, max) = radii.iter().fold((f64::INFINITY, 0.0f64), |(lo, hi), &r| (lo.min(r), hi.max(r)));
+        // Corners pulled in far more than face centres
+        assert!(max < 3f64.sqrt() * 0.8 && min > 0.5, "{} {}", min, max);
+        assert!(round.volume() > 0.0 && round.volume() < 8.0);
+    }
+
+    /// Test the boundary of an open surface stays on its crease.
+    #[test]
+    fn test_loop_crease() {
+        let mut mesh = Mesh::new();
+        for [x, y] in [[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]] {
+            mesh.add_vertex(x, y, 0.0, 0.0, 0.0, 1.0);
+        }
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(0, 2, 3);
+        let fine = loop_subdivide(&mesh, 2);
+        assert_eq!(fine.triangle_count(), 32);
+        assert!(fine.vertices.chunks_exact(3).all(|p| p[2] == 0.0));
+        // Corners round off along the boundary, inside the square
+        assert!(fine.vertices.chunks_exact(3).all(|p| (0.0..=2.0).contains(&p[0]) && (0.0..=2.0).contains(&p[1])));
+        assert!(!fine.vertices.chunks_exact(3).any(|p| p[0] == 0.0 && p[1] == 0.0));
+    }
+
+    /// Test smooth refinement rounds the pieces up to a power of two and
+    /// levels up to reach a length.
+    #[test]
+    fn test_smooth_params() {
+        let cube = crate::render("cube(8);").unwrap();
+        let smooth = |pieces, max_length| refine(&cube, &RefineParams { pieces, max_length, smooth: true });
+        assert_eq!(smooth(3, None).triangle_count(), 12 * 16);
+        assert_eq!(smooth(1, None).triangle_count(), 12);
+        let fine = smooth(1, Some(2.0));
+        assert!(longest(&fine) <= 2.0);
+        assert_eq!(smooth(1, Some(1e-9)).triangle_count(), 12 * 4usize.pow(MAX_LOOP_LEVELS));
+    }
+}
@@ -0,0 +1,317 @@
+//! # Coplanar Simplification
+//!
+//! Coarsens the triangulation of flat regions without changing the surface.
+//!
+//! ## Algorithm
+//!
+//! A vertex whose surrounding triangles all lie in one plane, and which is
+//! surrounded by a single closed fan (not on a boundary or a non-manifold
+//! edge), adds nothing to the shape. It is removed and the ring of its
+//! neighbours re-triangulated by ear clipping. Passes repeat until no
+//! vertex can be removed; a vertex is only considered once per pass after
+//! its neighbourhood changed, which keeps the result deterministic.
+//!
+//! Vertices on straight edges between two flat regions are kept, so the
+//! outline of every face survives; only interior vertices disappear.
+//! Vertex normals and colors of the remaining vertices are kept. In a mesh
+//! with node ids, a vertex is only removed from among the triangles of one
+//! node, so each re-triangulated region keeps its source.
+//!
+//! ## Render-Time Clean-Up
+//!
+//! [`simplify`] applies the [`SimplifyOptions`] of a render to its
+//! finished mesh: this coplanar merge, then optionally
+//! [`decimate`](super::decimate::decimate) down to a triangle budget,
+//! then flat or smooth [normals](crate::mesh::normals).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::manifold::simplify::simplify_coplanar;
+//!
+//! // Pyramid whose base is split into four triangles around its centre
+//! let mesh = manifold_rs::render("polyhedron([[0,0,0],[2,0,0],[2,2,0],[0,2,0],[1,1,0],[1,1,1]], \
+//!     [[0,1,4],[1,2,4],[2,3,4],[3,0,4],[0,5,1],[1,5,2],[2,5,3],[3,5,0]]);").unwrap();
+//! assert_eq!(simplify_coplanar(&mesh).triangle_count(), 6);
+//! ```
+
+use openscad_eval::{NormalMode, SimplifyOptions};
+
+use super::decimate::decimate;
+use crate::mesh::normals::DEFAULT_CREASE_ANGLE;
+use crate::mesh::triangulate::triangulate_face;
+use crate::mesh::Mesh;
+
+/// Minimum cosine between face normals treated as coplanar.
+const COPLANAR_COS: f64 = 1.0 - 1e-9;
+
+// =============================================================================
+// SIMPLIFICATION
+// =============================================================================
+
+/// Merge coplanar faces of a mesh.
+///
+/// ## Parameters
+///
+/// - `mesh`: Mesh to simplify
+///
+/// ## Returns
+///
+/// Mesh with the same surface and no removable interior vertices. Unused
+/// vertices are dropped.
+pub fn simplify_coplanar(mesh: &Mesh) -> Mesh {
+    let (points, weld) = mesh.welded_positions();
+
+    // Triangles as original vertex indices and node ids; removed ones
+    // become None
+    let mut triangles: Vec<Option<([u32; 3], u32)>> = mesh.indices.chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .enumerate()
+        .filter(|(_, t)| {
+            let [a, b, c] = t.map(|i| weld[i as usize]);
+            a != b && b != c && a != c
+        })
+        .map(|(i, t)| Some((t, mesh.nodes.as_ref().map_or(Mesh::NO_NODE, |nodes| nodes[i]))))
+        .collect();
+
+    loop {
+        let mut incident: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            if let Some((t, _)) = t {
+                for &corner in t {
+                    incident[weld[corner as usize] as usize].push(i);
+                }
+            }
+        }
+
+        let mut touched = vec![false; points.len()];
+        let mut changed = false;
+        for vertex in 0..points.len() {
+            if touched[vertex] || incident[vertex].len() < 3 {
+                continue;
+            }
+            let faces: Vec<([u32; 3], u32)> = incident[vertex].iter().filter_map(|&i| triangles[i]).collect();
+            let node = faces[0].1;
+            if faces.iter().any(|&(_, other)| other != node) {
+                continue;
+            }
+            let faces: Vec<[u32; 3]> = faces.into_iter().map(|(t, _)| t).collect();
+            let Some(replacement) = remove_vertex(vertex as u32, &faces, &points, &weld) else {
+                continue;
+            };
+
+            for &i in &incident[vertex] {
+                if let Some((t, _)) = triangles[i].take() {
+                    for corner in t {
+                        touched[weld[corner as usize] as usize] = true;
+                    }
+                }
+            }
+            triangles.extend(replacement.into_iter().map(|t| Some((t, node))));
+            changed = true;
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    compact(mesh, triangles.iter().flatten())
+}
+
+/// Apply the clean-up chosen in `options` to a finished mesh.
+///
+/// ## Returns
+///
+/// The mesh, merged if `options.merge_coplanar`, decimated if it has
+/// more than `options.target_triangles`, and then shaded as
+/// `options.normals` asks; unchanged by the defaults.
+pub fn simplify(mesh: Mesh, options: &SimplifyOptions) -> Mesh {
+    let mesh = if options.merge_coplanar { simplify_coplanar(&mesh) } else { mesh };
+    let mut mesh = match options.target_triangles {
+        Some(target) if mesh.triangle_count() > target => decimate(&mesh, target),
+        _ => mesh,
+    };
+    match options.normals {
+        Some(NormalMode::Flat) => mesh.flat_normals(),
+        Some(NormalMode::Smooth) => mesh.smooth_normals(options.crease_angle.unwrap_or(DEFAULT_CREASE_ANGLE)),
+        None => {}
+    }
+    mesh
+}
+
+/// Triangles replacing the fan around a removable vertex.
+///
+/// ## Returns
+///
+/// `None` if the vertex is on a boundary, not surrounded by one closed
+/// fan, not flat, or its ring does not triangulate cleanly.
+fn remove_vertex(vertex: u32, faces: &[[u32; 3]], points: &[[f64; 3]], weld: &[u32]) -> Option<Vec<[u32; 3]>> {
+    let welded = |i: u32| weld[i as usize];
+
+    // Each face contributes the ring edge opposite the vertex
+    let mut edges: Vec<(u32, u32)> = Vec::with_capacity(faces.len());
+    let mut original = std::collections::HashMap::new();
+    for t in faces {
+        let k = t.iter().position(|&c| welded(c) == vertex)?;
+        let (a, b) = (t[(k + 1) % 3], t[(k + 2) % 3]);
+        original.insert(welded(a), a);
+        original.insert(welded(b), b);
+        edges.push((welded(a), welded(b)));
+    }
+
+    // The ring must be one cycle through every face
+    let mut ring = vec![edges[0].0];
+    while ring.len() < edges.len() {
+        let last = *ring.last()?;
+        let mut next = edges.iter().filter(|(a, _)| *a == last).map(|&(_, b)| b);
+        let step = next.next()?;
+        if next.next().is_some() || ring.contains(&step) {
+            return None;
+        }
+        ring.push(step);
+    }
+    let last = *ring.last()?;
+    if !edges.iter().any(|&(a, b)| a == last && b == ring[0]) {
+        return None;
+    }
+
+    // All faces in one plane
+    let normal = unit_normal(points, [vertex, edges[0].0, edges[0].1])?;
+    for &(a, b) in &edges[1..] {
+        let n = unit_normal(points, [vertex, a, b])?;
+        if dot(n, normal) < COPLANAR_COS {
+            return None;
+        }
+    }
+
+    // Re-triangulate the ring; every new face must face the same way
+    let face: Vec<usize> = ring.iter().map(|&v| v as usize).collect();
+    let replacement = triangulate_face(points, &face);
+    if replacement.len() != ring.len() - 2 {
+        return None;
+    }
+    replacement.into_iter()
+        .map(|[a, b, c]| {
+            let n = unit_normal(points, [a as u32, b as u32, c as u32])?;
+            (dot(n, normal) >= COPLANAR_COS)
+                .then(|| [original[&(a as u32)], original[&(b as u32)], original[&(c as u32)]])
+        })
+        .collect()
+}
+
+/// Copy the referenced vertices and their attributes into a new mesh,
+/// with the node ids of the triangles if `mesh` has them.
+fn compact<'a>(mesh: &Mesh, triangles: impl Iterator<Item = &'a ([u32; 3], u32)>) -> Mesh {
+    let mut out = Mesh::new();
+    let mut colors = mesh.colors.as_ref().map(|_| Vec::new());
+    let mut nodes = mesh.nodes.as_ref().map(|_| Vec::new());
+    let mut map: Vec<Option<u32>> = vec![None; mesh.vertex_count()];
+    for (t, node) in triangles {
+        let [a, b, c] = t.map(|i| {
+            *map[i as usize].get_or_insert_with(|| {
+                let i = i as usize;
+                let n = &mesh.normals[i * 3..i * 3 + 3];
+                if let (Some(dst), Some(src)) = (colors.as_mut(), mesh.colors.as_ref()) {
+                    dst.extend_from_slice(&src[i * 4..i * 4 + 4]);
+                }
+                out.add_vertex(mesh.vertices[i * 3], mesh.vertices[i * 3 + 1], mesh.vertices[i * 3 + 2], n[0], n[1], n[2])
+            })
+        });
+        out.add_triangle(a, b, c);
+        if let Some(nodes) = nodes.as_mut() {
+            nodes.push(*node);
+        }
+    }
+    out.colors = colors;
+    out.nodes = nodes;
+    out
+}
+
+/// Unit normal of a triangle of welded points, `None` if degenerate.
+fn unit_normal(points: &[[f64; 3]], [a, b, c]: [u32; 3]) -> Option<[f64; 3]> {
+    let (p0, p1, p2) = (points[a as usize], points[b as usize], points[c as usize]);
+    let e1 = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+    let e2 = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+    let n = [
+        e1[1] * e2[2] - e1[2] * e2[1],
+        e1[2] * e2[0] - e1[0] * e2[2],
+        e1[0] * e2[1] - e1[1] * e2[0],
+    ];
+    let len = dot(n, n).sqrt();
+    (len > 1e-12).then(|| n.map(|c| c / len))
+}
+
+/// Dot product.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::halfedge::HalfEdgeMesh;
+
+    /// Test an interior vertex of a flat face is removed and the mesh stays closed.
+    #[test]
+    fn test_interior_vertex_removed() {
+        let mesh = crate::render("polyhedron([[0,0,0],[2,0,0],[2,2,0],[0,2,0],[1,1,0],[1,1,1]], \
+            [[0,1,4],[1,2,4],[2,3,4],[3,0,4],[0,5,1],[1,5,2],[2,5,3],[3,5,0]]);").unwrap();
+        let simplified = simplify_coplanar(&mesh);
+        assert_eq!(simplified.triangle_count(), 6);
+        assert!(HalfEdgeMesh::from_mesh(&simplified).1.is_manifold());
+    }
+
+    /// Test curved and already minimal meshes are unchanged.
+    #[test]
+    fn test_no_coplanar_vertices() {
+        for source in ["cube(10);", "sphere(5, $fn = 12);"] {
+            let mesh = crate::render(source).unwrap();
+            let simplified = simplify_coplanar(&mesh);
+            assert_eq!(simplified.triangle_count(), mesh.triangle_count(), "{}", source);
+            assert_eq!(simplified.vertex_count(), mesh.vertex_count(), "{}", source);
+        }
+    }
+
+    /// Test render options merge a boolean's flat faces and cap its triangles.
+    #[test]
+    fn test_simplify_options() {
+        let mesh = crate::render("difference() { cube(20, center = true); sphere(12, $fn = 32); }").unwrap();
+        let defaults = simplify(mesh.clone(), &SimplifyOptions::default());
+        assert_eq!(defaults.indices, mesh.indices);
+
+        let merged = simplify(mesh.clone(), &SimplifyOptions { merge_coplanar: true, ..SimplifyOptions::default() });
+        assert!(merged.triangle_count() < mesh.triangle_count());
+
+        let options = SimplifyOptions { merge_coplanar: true, target_triangles: Some(500), ..SimplifyOptions::default() };
+        assert!(simplify(mesh, &options).triangle_count() <= 500);
+    }
+
+    /// Test render options pick flat or smooth normals, smooth ones
+    /// sharing vertices across the sphere.
+    #[test]
+    fn test_normal_options() {
+        let mesh = crate::render("sphere(5, $fn = 24);").unwrap();
+        let shaded = |normals, crease_angle| {
+            simplify(mesh.clone(), &SimplifyOptions { normals: Some(normals), crease_angle, ..SimplifyOptions::default() })
+        };
+        let flat = shaded(NormalMode::Flat, None);
+        let smooth = shaded(NormalMode::Smooth, None);
+        assert_eq!(flat.indices.len(), smooth.indices.len());
+        assert!(smooth.vertex_count() < flat.vertex_count());
+        assert_eq!(shaded(NormalMode::Smooth, Some(0.0)).vertex_count(), flat.vertex_count());
+    }
+
+    /// Test colors follow the kept vertices.
+    #[test]
+    fn test_keeps_colors() {
+        let mesh = crate::render("color([0, 1, 0]) cube(1);").unwrap();
+        let simplified = simplify_coplanar(&mesh);
+        let colors = simplified.colors.clone().unwrap();
+        assert_eq!(colors.len(), simplified.vertex_count() * 4);
+        assert_eq!(&colors[..4], &[0.0, 1.0, 0.0, 1.0]);
+    }
+}
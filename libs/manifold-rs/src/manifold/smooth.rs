@@ -0,0 +1,244 @@
+//! # Taubin Smoothing
+//!
+//! Smooths a triangle mesh by moving each vertex toward the average of its
+//! neighbours, alternating a shrinking pass (weight `lambda`) with an
+//! inflating pass (weight `mu < 0`) so the shape does not shrink the way
+//! plain Laplacian smoothing does.
+//!
+//! ## Algorithm
+//!
+//! 1. Weld the mesh into a half-edge mesh to find vertex neighbours
+//! 2. Fix vertices on open boundaries and sharp feature edges (optional)
+//! 3. For each iteration, apply `p += w * (avg(neighbours) - p)` with
+//!    `w = lambda`, then with `w = mu`
+//! 4. Convert back to a flat-shaded mesh
+//!
+//! Vertex colors are not kept; apply `color()` outside `smooth()`.
+//!
+//! ## References
+//!
+//! - Taubin, "A Signal Processing Approach to Fair Surface Design", SIGGRAPH 1995
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::manifold::smooth::{smooth_mesh, SmoothParams};
+//!
+//! let sphere = manifold_rs::render("sphere(10, $fn = 8);").unwrap();
+//! let smoothed = smooth_mesh(&sphere, &SmoothParams::default());
+//! assert_eq!(smoothed.triangle_count(), sphere.triangle_count());
+//! ```
+
+use crate::mesh::halfedge::{HalfEdgeMesh, INVALID_ID};
+use crate::mesh::Mesh;
+
+// =============================================================================
+// PARAMETERS
+// =============================================================================
+
+/// Taubin smoothing parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothParams {
+    /// Number of shrink/inflate pass pairs.
+    pub iterations: u32,
+    /// Shrinking weight, in (0, 1).
+    pub lambda: f64,
+    /// Inflating weight, negative.
+    pub mu: f64,
+    /// Keep vertices on open boundaries fixed.
+    pub preserve_boundary: bool,
+    /// Keep vertices on edges sharper than this angle (degrees) fixed.
+    pub feature_angle: Option<f64>,
+}
+
+impl Default for SmoothParams {
+    fn default() -> Self {
+        Self {
+            iterations: 10,
+            lambda: 0.5,
+            mu: -0.53,
+            preserve_boundary: true,
+            feature_angle: None,
+        }
+    }
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Smooth a triangle mesh.
+///
+/// ## Parameters
+///
+/// - `mesh`: Mesh to smooth (welded internally by exact position)
+/// - `params`: Iterations, weights and preservation options
+///
+/// ## Returns
+///
+/// Flat-shaded smoothed mesh.
+pub fn smooth_mesh(mesh: &Mesh, params: &SmoothParams) -> Mesh {
+    let (mut he_mesh, _) = HalfEdgeMesh::from_mesh(mesh);
+    taubin_smooth(&mut he_mesh, params);
+    he_mesh.to_mesh()
+}
+
+/// Smooth a half-edge mesh in place.
+///
+/// ## Parameters
+///
+/// - `mesh`: Half-edge mesh whose vertex positions are updated
+/// - `params`: Iterations, weights and preservation options
+pub fn taubin_smooth(mesh: &mut HalfEdgeMesh, params: &SmoothParams) {
+    let neighbors = vertex_neighbors(mesh);
+    let fixed = fixed_vertices(mesh, params);
+    let mut positions: Vec<[f64; 3]> = mesh.vertices.iter()
+        .map(|v| [v.x, v.y, v.z])
+        .collect();
+
+    for _ in 0..params.iterations {
+        for weight in [params.lambda, params.mu] {
+            positions = laplacian_step(&positions, &neighbors, &fixed, weight);
+        }
+    }
+
+    for (v, p) in mesh.vertices.iter_mut().zip(&positions) {
+        v.x = p[0];
+        v.y = p[1];
+        v.z = p[2];
+    }
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Distinct neighbours of each vertex, sorted.
+fn vertex_neighbors(mesh: &HalfEdgeMesh) -> Vec<Vec<u32>> {
+    let mut neighbors = vec![Vec::new(); mesh.vertex_count()];
+    for he in &mesh.halfedges {
+        neighbors[he.start_vert as usize].push(he.end_vert);
+        neighbors[he.end_vert as usize].push(he.start_vert);
+    }
+    for list in &mut neighbors {
+        list.sort_unstable();
+        list.dedup();
+    }
+    neighbors
+}
+
+/// Vertices that must not move.
+fn fixed_vertices(mesh: &HalfEdgeMesh, params: &SmoothParams) -> Vec<bool> {
+    let mut fixed = vec![false; mesh.vertex_count()];
+    let normals: Vec<[f64; 3]> = (0..mesh.face_count()).map(|f| face_normal(mesh, f as u32)).collect();
+    let cos_limit = params.feature_angle.map(|angle| angle.to_radians().cos());
+
+    for (id, he) in mesh.halfedges.iter().enumerate() {
+        let sharp = if he.pair == INVALID_ID {
+            params.preserve_boundary
+        } else if let (Some(cos_limit), true) = (cos_limit, (he.pair as usize) > id) {
+            let a = normals[he.face as usize];
+            let b = normals[mesh.halfedges[he.pair as usize].face as usize];
+            a[0] * b[0] + a[1] * b[1] + a[2] * b[2] < cos_limit
+        } else {
+            false
+        };
+        if sharp {
+            fixed[he.start_vert as usize] = true;
+            fixed[he.end_vert as usize] = true;
+        }
+    }
+    fixed
+}
+
+/// Unit normal of a face (zero if degenerate).
+fn face_normal(mesh: &HalfEdgeMesh, face: u32) -> [f64; 3] {
+    let corners: Vec<[f64; 3]> = mesh.face_halfedges(face)
+        .map(|he| {
+            let v = &mesh.vertices[mesh.halfedges[he as usize].start_vert as usize];
+            [v.x, v.y, v.z]
+        })
+        .collect();
+    let [a, b, c] = corners[..] else {
+        return [0.0; 3];
+    };
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let w = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [u[1] * w[2] - u[2] * w[1], u[2] * w[0] - u[0] * w[2], u[0] * w[1] - u[1] * w[0]];
+    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if len > 0.0 { [n[0] / len, n[1] / len, n[2] / len] } else { [0.0; 3] }
+}
+
+/// One umbrella-operator pass with the given weight.
+fn laplacian_step(positions: &[[f64; 3]], neighbors: &[Vec<u32>], fixed: &[bool], weight: f64) -> Vec<[f64; 3]> {
+    positions.iter().enumerate()
+        .map(|(i, &p)| {
+            if fixed[i] || neighbors[i].is_empty() {
+                return p;
+            }
+            let mut avg = [0.0; 3];
+            for &n in &neighbors[i] {
+                for axis in 0..3 {
+                    avg[axis] += positions[n as usize][axis];
+                }
+            }
+            let count = neighbors[i].len() as f64;
+            [0, 1, 2].map(|axis| p[axis] + weight * (avg[axis] / count - p[axis]))
+        })
+        .collect()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Largest distance of any vertex from the origin.
+    fn max_radius(mesh: &Mesh) -> f64 {
+        mesh.vertices.chunks_exact(3)
+            .map(|p| (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt())
+            .fold(0.0, f64::max)
+    }
+
+    /// Test Taubin smoothing shrinks far less than plain Laplacian.
+    #[test]
+    fn test_taubin_preserves_volume() {
+        let sphere = crate::render("sphere(10, $fn = 16);").unwrap();
+        let taubin = smooth_mesh(&sphere, &SmoothParams::default());
+        let laplacian = smooth_mesh(&sphere, &SmoothParams { mu: 0.0, ..SmoothParams::default() });
+
+        let original = max_radius(&sphere);
+        assert!(original - max_radius(&taubin) < 0.5);
+        assert!(original - max_radius(&laplacian) > original - max_radius(&taubin));
+    }
+
+    /// Test feature edges pin a cube's corners in place.
+    #[test]
+    fn test_feature_angle() {
+        let cube = crate::render("cube(10, center = true);").unwrap();
+        let pinned = smooth_mesh(&cube, &SmoothParams { feature_angle: Some(30.0), ..SmoothParams::default() });
+        assert!((max_radius(&pinned) - max_radius(&cube)).abs() < 1e-5);
+
+        let free = smooth_mesh(&cube, &SmoothParams::default());
+        assert!(max_radius(&free) < max_radius(&cube));
+    }
+
+    /// Test open boundaries stay put unless released.
+    #[test]
+    fn test_preserve_boundary() {
+        let mut mesh = Mesh::new();
+        let corners = [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [2.0, 2.0, 0.0], [1.0, 1.0, 1.0]];
+        let ids: Vec<u32> = corners.iter().map(|c| mesh.add_vertex(c[0], c[1], c[2], 0.0, 0.0, 1.0)).collect();
+        for [a, b] in [[0, 1], [1, 3], [3, 2], [2, 0]] {
+            mesh.add_triangle(ids[a], ids[b], ids[4]);
+        }
+
+        let (mut he_mesh, _) = HalfEdgeMesh::from_mesh(&mesh);
+        taubin_smooth(&mut he_mesh, &SmoothParams::default());
+        assert_eq!(he_mesh.vertices[0].x, 0.0);
+        assert!(he_mesh.vertices[4].z < 1.0);
+    }
+}
@@ -0,0 +1,205 @@
+//! # Warping
+//!
+//! Moves every vertex of a mesh through a function, after Manifold-3D's
+//! `Warp`, for deformations a transform matrix cannot express: twisted
+//! columns, tapered posts, bent brackets.
+//!
+//! Only vertices move, so straight edges stay straight: a cube has no
+//! vertices along its sides to bend. Refine the mesh first (see
+//! [`refine`](super::refine)) so the deformation has points to act on.
+//!
+//! The result is flat-shaded and keeps its colors. A function that turns
+//! the solid inside out, such as a mirror, has its triangles rewound so
+//! the mesh still faces outward.
+//!
+//! ## Deformations
+//!
+//! | Function | Along | Effect |
+//! |----------|-------|--------|
+//! | [`twist`] | z | Rotation about the Z axis, `0` at the bottom to `degrees` at the top |
+//! | [`taper`] | z | Scale in x and y about the Z axis, `1` at the bottom to `scale` at the top |
+//! | [`bend`] | x | Arc of `degrees` in the XZ plane, around an axis parallel to Y |
+//!
+//! Each spans the mesh's own extent along its axis, so the same call fits
+//! a model of any size.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use glam::DVec3;
+//! use manifold_rs::manifold::refine::refine_mesh;
+//! use manifold_rs::manifold::warp::{twist, warp_mesh};
+//!
+//! let column = refine_mesh(&manifold_rs::render("cube([2, 2, 10], center = true);").unwrap(), 8);
+//! let twisted = twist(&column, 90.0);
+//! assert!((twisted.volume() - column.volume()).abs() < 1.0);
+//! let doubled = warp_mesh(&column, |v| v * DVec3::new(2.0, 1.0, 1.0));
+//! assert!((doubled.volume() - 80.0).abs() < 1e-9);
+//! ```
+
+use glam::DVec3;
+
+use crate::mesh::Mesh;
+
+// =============================================================================
+// WARP
+// =============================================================================
+
+/// Move every vertex of `mesh` to `f` of its position.
+///
+/// ## Returns
+///
+/// The mesh with the same triangles and colors, new flat normals, and
+/// triangles rewound if `f` turned it inside out.
+#[must_use]
+pub fn warp_mesh<F: Fn(DVec3) -> DVec3>(mesh: &Mesh, f: F) -> Mesh {
+    let mut warped = mesh.clone();
+    for p in warped.vertices.chunks_exact_mut(3) {
+        let v = f(DVec3::new(p[0], p[1], p[2]));
+        p.copy_from_slice(&v.to_array());
+    }
+    if mesh.volume() * warped.volume() < 0.0 {
+        for triangle in warped.indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+    warped.flat_normals();
+    warped
+}
+
+// =============================================================================
+// DEFORMATIONS
+// =============================================================================
+
+/// Twist `mesh` about the Z axis, counter-clockwise seen from above by
+/// `degrees` from its bottom to its top.
+#[must_use]
+pub fn twist(mesh: &Mesh, degrees: f64) -> Mesh {
+    let Some((bottom, height)) = extent(mesh, 2) else {
+        return mesh.clone();
+    };
+    let total = degrees.to_radians();
+    warp_mesh(mesh, |v| {
+        let (sin, cos) = (total * (v.z - bottom) / height).sin_cos();
+        DVec3::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos, v.z)
+    })
+}
+
+/// Scale `mesh` in x and y about the Z axis, from `1` at its bottom to
+/// `scale` at its top.
+#[must_use]
+pub fn taper(mesh: &Mesh, scale: [f64; 2]) -> Mesh {
+    let Some((bottom, height)) = extent(mesh, 2) else {
+        return mesh.clone();
+    };
+    warp_mesh(mesh, |v| {
+        let t = (v.z - bottom) / height;
+        DVec3::new(v.x * (1.0 + t * (scale[0] - 1.0)), v.y * (1.0 + t * (scale[1] - 1.0)), v.z)
+    })
+}
+
+/// Bend `mesh` along x into an arc of `degrees`, curving toward +Z for
+/// positive angles.
+///
+/// The plane `z = 0` keeps its length; the left end stays where it is
+/// and the rest rolls around an axis parallel to Y at height
+/// `length / angle` above it, so material above the plane is compressed
+/// and below it stretched.
+#[must_use]
+pub fn bend(mesh: &Mesh, degrees: f64) -> Mesh {
+    let Some((left, length)) = extent(mesh, 0) else {
+        return mesh.clone();
+    };
+    if degrees == 0.0 {
+        return mesh.clone();
+    }
+    let radius = length / degrees.to_radians();
+    warp_mesh(mesh, |v| {
+        let (sin, cos) = ((v.x - left) / radius).sin_cos();
+        DVec3::new(left + (radius - v.z) * sin, v.y, radius - (radius - v.z) * cos)
+    })
+}
+
+/// Lowest coordinate along `axis` and the extent from it, `None` for an
+/// empty or flat mesh.
+fn extent(mesh: &Mesh, axis: usize) -> Option<(f64, f64)> {
+    let (min, max) = mesh.bounding_box()?;
+    let size = max[axis] - min[axis];
+    (size > 0.0).then_some((min[axis], size))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifold::refine::refine_mesh;
+
+    /// A refined box, `[x, y, z]` large.
+    fn block(x: f64, y: f64, z: f64) -> Mesh {
+        let mesh = crate::render(&format!("cube([{}, {}, {}]);", x, y, z)).unwrap();
+        refine_mesh(&mesh, 16)
+    }
+
+    /// Test a rigid motion keeps the volume and a mirror is rewound.
+    #[test]
+    fn test_warp_mesh() {
+        let cube = block(2.0, 2.0, 2.0);
+        let moved = warp_mesh(&cube, |v| v + DVec3::new(5.0, 0.0, 0.0));
+        assert!((moved.volume() - 8.0).abs() < 1e-9);
+        assert_eq!(moved.bounding_box().unwrap().0, [5.0, 0.0, 0.0]);
+        let mirrored = warp_mesh(&cube, |v| DVec3::new(-v.x, v.y, v.z));
+        assert!((mirrored.volume() - 8.0).abs() < 1e-9);
+        assert!(mirrored.validate().is_valid());
+    }
+
+    /// Test a twist turns the top and leaves the bottom.
+    #[test]
+    fn test_twist() {
+        let column = block(2.0, 2.0, 10.0);
+        let twisted = twist(&column, 90.0);
+        let (min, max) = twisted.bounding_box().unwrap();
+        // The far corner of the base stays, the top one swings to negative x
+        assert!((max[0] - 2.0).abs() < 1e-9 && (min[0] + 2.0).abs() < 1e-9, "{:?}", (min, max));
+        assert!(twisted.validate().is_valid());
+    }
+
+    /// Test a taper gives a frustum.
+    #[test]
+    fn test_taper() {
+        let post = block(2.0, 2.0, 4.0);
+        let tapered = taper(&post, [0.5, 0.5]);
+        // Frustum of squares 2 and 1, height 4
+        let expected = 4.0 / 3.0 * (4.0 + 1.0 + 2.0);
+        assert!((tapered.volume() - expected).abs() < 1e-9, "{}", tapered.volume());
+        let (_, max) = tapered.bounding_box().unwrap();
+        assert!((max[0] - 2.0).abs() < 1e-9);
+    }
+
+    /// Test a half-turn bend brings the ends together above the start.
+    #[test]
+    fn test_bend() {
+        let bar = block(10.0, 1.0, 0.5);
+        let bent = bend(&bar, 180.0);
+        let (min, max) = bent.bounding_box().unwrap();
+        let radius = 10.0 / std::f64::consts::PI;
+        assert!((min[0]).abs() < 1e-9 && (max[0] - radius).abs() < 0.05, "{:?}", (min, max));
+        assert!((max[2] - 2.0 * radius).abs() < 1e-9, "{:?}", (min, max));
+        assert!(bent.volume() > 0.0);
+        assert_eq!(bend(&bar, 0.0).vertex_count(), bar.vertex_count());
+    }
+
+    /// Test a flat or empty mesh is returned as it is.
+    #[test]
+    fn test_degenerate() {
+        assert!(twist(&Mesh::new(), 45.0).is_empty());
+        let mut flat = Mesh::new();
+        flat.add_vertex(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        flat.add_vertex(1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        flat.add_vertex(0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        flat.add_triangle(0, 1, 2);
+        assert_eq!(taper(&flat, [2.0, 2.0]).vertices, flat.vertices);
+    }
+}
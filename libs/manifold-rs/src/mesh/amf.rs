@@ -0,0 +1,174 @@
+//! # AMF Export
+//!
+//! Writes meshes as Additive Manufacturing File Format XML with one object
+//! per color, so slicers for multi-material printers can assign each
+//! `color()` of the model its own extruder.
+//!
+//! ## Layout
+//!
+//! ```text
+//! <amf unit="millimeter" version="1.1">
+//!   <material id="1">          ← one per distinct color
+//!     <color><r/><g/><b/><a/></color>
+//!   </material>
+//!   <object id="1">            ← one per color, its triangles only
+//!     <mesh>
+//!       <vertices>…</vertices>
+//!       <volume materialid="1">…</volume>
+//!     </mesh>
+//!   </object>
+//! </amf>
+//! ```
+//!
+//! Colors come from the per-vertex colors `color()` leaves on the mesh; a
+//! triangle belongs to the group of its first corner. A mesh without
+//! colors is a single object with no material. Vertices are welded by
+//! exact position within each object, and triangles that collapse in the
+//! process are dropped, as for 3MF.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mesh = manifold_rs::render("color([1, 0, 0]) cube(10); color([0, 0, 1]) translate([20, 0, 0]) cube(10);").unwrap();
+//! let amf = mesh.to_amf();
+//! assert_eq!(amf.matches("<object ").count(), 2);
+//! assert_eq!(amf.matches("<material ").count(), 2);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::threemf::color_hex;
+use super::Mesh;
+
+/// Triangles of one color, with the color's RGBA if the mesh has colors.
+struct ColorGroup {
+    rgba: Option<[f32; 4]>,
+    triangles: Vec<[usize; 3]>,
+}
+
+// =============================================================================
+// EXPORT
+// =============================================================================
+
+impl Mesh {
+    /// Encode the mesh as AMF XML, one object per color.
+    #[must_use]
+    pub fn to_amf(&self) -> String {
+        let (positions, remap) = self.welded_positions();
+        let groups = self.color_groups(&remap);
+
+        let mut xml = String::new();
+        // Writing to a String cannot fail
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(xml, r#"<amf unit="millimeter" version="1.1">"#);
+        // Material and object ids both count from 1, by group
+        for (id, group) in (1..).zip(&groups) {
+            let Some(rgba) = group.rgba else { continue };
+            let _ = writeln!(xml, r#"  <material id="{}">"#, id);
+            let _ = writeln!(xml, r#"    <metadata type="name">{}</metadata>"#, color_hex(&rgba));
+            let _ = writeln!(
+                xml,
+                "    <color><r>{}</r><g>{}</g><b>{}</b><a>{}</a></color>",
+                rgba[0].clamp(0.0, 1.0), rgba[1].clamp(0.0, 1.0), rgba[2].clamp(0.0, 1.0), rgba[3].clamp(0.0, 1.0),
+            );
+            let _ = writeln!(xml, "  </material>");
+        }
+        for (id, group) in (1..).zip(&groups) {
+            // Number this object's vertices in first-use order
+            let mut local: BTreeMap<usize, usize> = BTreeMap::new();
+            let mut order = Vec::new();
+            let triangles: Vec<[usize; 3]> = group.triangles.iter()
+                .map(|t| t.map(|v| *local.entry(v).or_insert_with(|| {
+                    order.push(v);
+                    order.len() - 1
+                })))
+                .collect();
+
+            let _ = writeln!(xml, r#"  <object id="{}">"#, id);
+            let _ = writeln!(xml, "    <mesh>");
+            let _ = writeln!(xml, "      <vertices>");
+            for &v in &order {
+                let p = positions[v];
+                let _ = writeln!(xml, "        <vertex><coordinates><x>{}</x><y>{}</y><z>{}</z></coordinates></vertex>", p[0], p[1], p[2]);
+            }
+            let _ = writeln!(xml, "      </vertices>");
+            match group.rgba {
+                Some(_) => {
+                    let _ = writeln!(xml, r#"      <volume materialid="{}">"#, id);
+                }
+                None => {
+                    let _ = writeln!(xml, "      <volume>");
+                }
+            }
+            for t in triangles {
+                let _ = writeln!(xml, "        <triangle><v1>{}</v1><v2>{}</v2><v3>{}</v3></triangle>", t[0], t[1], t[2]);
+            }
+            let _ = writeln!(xml, "      </volume>");
+            let _ = writeln!(xml, "    </mesh>");
+            let _ = writeln!(xml, "  </object>");
+        }
+        let _ = writeln!(xml, "</amf>");
+        xml
+    }
+
+    /// Welded triangles grouped by the color of their first corner, in
+    /// first-seen order of the colors.
+    fn color_groups(&self, remap: &[u32]) -> Vec<ColorGroup> {
+        let mut group_ids: BTreeMap<String, usize> = BTreeMap::new();
+        let mut groups: Vec<ColorGroup> = Vec::new();
+        for t in self.indices.chunks_exact(3) {
+            let Some(v) = t.iter().map(|&i| remap.get(i as usize).map(|&v| v as usize)).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            if v[0] == v[1] || v[1] == v[2] || v[0] == v[2] {
+                continue;
+            }
+            let rgba = self.colors.as_ref().map(|colors| {
+                let i = t[0] as usize * 4;
+                colors.get(i..i + 4).map_or(Self::DEFAULT_COLOR, |c| [c[0], c[1], c[2], c[3]])
+            });
+            let key = rgba.map(|c| color_hex(&c)).unwrap_or_default();
+            let id = *group_ids.entry(key).or_insert_with(|| {
+                groups.push(ColorGroup { rgba, triangles: Vec::new() });
+                groups.len() - 1
+            });
+            groups[id].triangles.push([v[0], v[1], v[2]]);
+        }
+        groups
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test each color becomes an object with its own welded vertices.
+    #[test]
+    fn test_color_objects() {
+        let mesh = crate::render("color([1, 0, 0]) cube(10); translate([20, 0, 0]) cube(10);").unwrap();
+        let amf = mesh.to_amf();
+        assert_eq!(amf.matches("<object ").count(), 2);
+        assert_eq!(amf.matches("<vertex>").count(), 16);
+        assert_eq!(amf.matches("<triangle>").count(), 24);
+        assert!(amf.contains("<metadata type=\"name\">#FF0000FF</metadata>"));
+        // The uncolored cube is white
+        assert!(amf.contains("<color><r>1</r><g>1</g><b>1</b><a>1</a></color>"));
+        assert!(amf.contains(r#"<volume materialid="2">"#));
+    }
+
+    /// Test a mesh without colors is one object without materials.
+    #[test]
+    fn test_uncolored() {
+        let amf = crate::render("cube(10);").unwrap().to_amf();
+        assert!(amf.starts_with("<?xml"));
+        assert_eq!(amf.matches("<object ").count(), 1);
+        assert!(!amf.contains("<material"));
+        assert!(amf.contains("      <volume>\n"));
+        assert!(Mesh::new().to_amf().ends_with("</amf>\n"));
+    }
+}
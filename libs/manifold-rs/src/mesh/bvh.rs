@@ -0,0 +1,445 @@
+//! # Bounding-Volume Hierarchy
+//!
+//! Binary tree of axis-aligned boxes over a set of items (usually the
+//! triangles of a mesh), for finding the items near a box, a ray or a
+//! triangle without visiting all of them.
+//!
+//! ## Construction
+//!
+//! Top-down with the surface area heuristic (SAH): at each node the item
+//! centres are binned along each axis, and the split minimising
+//!
+//! ```text
+//! cost = area(left) · count(left) + area(right) · count(right)
+//! ```
+//!
+//! is taken if it beats keeping the node as a leaf. Large and small items
+//! therefore end up in separate subtrees, so a few huge triangles do not
+//! slow down queries among many small ones, as they would in a uniform
+//! grid or a sweep sorted along one axis.
+//!
+//! ## Queries
+//!
+//! Every query returns item indices in ascending order, so callers visit
+//! candidates in the same order as a linear scan would. The tests against
+//! the boxes are conservative: an item is only left out if its box
+//! certainly misses the query. Exact decisions stay with the caller.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::mesh::bvh::{Aabb, Bvh};
+//!
+//! let mesh = manifold_rs::render("cube(10);").unwrap();
+//! let bvh = Bvh::from_mesh(&mesh);
+//! // Triangles hit by a ray up the z axis from below the cube
+//! let hits = bvh.along_ray([5.0, 5.0, -1.0], [0.0, 0.0, 1.0]);
+//! assert_eq!(hits.len(), 4);
+//! // Triangles whose boxes reach the corner at the origin
+//! assert!(!bvh.overlapping(&Aabb::point([0.0, 0.0, 0.0])).is_empty());
+//! ```
+
+use super::Mesh;
+
+/// Most items kept in a leaf.
+const MAX_LEAF: usize = 4;
+
+/// Bins per axis when searching for a split.
+const BINS: usize = 16;
+
+// =============================================================================
+// BOXES
+// =============================================================================
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    /// Lowest corner.
+    pub min: [f64; 3],
+    /// Highest corner.
+    pub max: [f64; 3],
+}
+
+impl Aabb {
+    /// The box containing nothing; the identity for [`Aabb::union`].
+    pub const EMPTY: Aabb = Aabb { min: [f64::INFINITY; 3], max: [f64::NEG_INFINITY; 3] };
+
+    /// Box around a single point.
+    pub fn point(p: [f64; 3]) -> Self {
+        Self { min: p, max: p }
+    }
+
+    /// Smallest box around the points.
+    pub fn of(points: &[[f64; 3]]) -> Self {
+        points.iter().fold(Self::EMPTY, |b, &p| b.union(&Self::point(p)))
+    }
+
+    /// Smallest box around both boxes.
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: std::array::from_fn(|k| self.min[k].min(other.min[k])),
+            max: std::array::from_fn(|k| self.max[k].max(other.max[k])),
+        }
+    }
+
+    /// Whether the boxes share a point, boundaries included.
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        (0..3).all(|k| self.min[k] <= other.max[k] && other.min[k] <= self.max[k])
+    }
+
+    /// Whether the box contains nothing.
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|k| self.min[k] > self.max[k])
+    }
+
+    /// Surface area, the SAH's measure of how likely a query hits the box.
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let [x, y, z] = std::array::from_fn(|k| self.max[k] - self.min[k]);
+        2.0 * (x * y + y * z + z * x)
+    }
+
+    /// Centre of the box.
+    fn center(&self) -> [f64; 3] {
+        std::array::from_fn(|k| (self.min[k] + self.max[k]) / 2.0)
+    }
+
+    /// Whether the ray `origin + t · direction`, `t ≥ 0`, touches the box.
+    fn hit_by(&self, origin: [f64; 3], direction: [f64; 3]) -> bool {
+        let (mut near, mut far) = (0.0f64, f64::INFINITY);
+        for k in 0..3 {
+            let (o, d) = (origin[k], direction[k]);
+            let (lo, hi) = (self.min[k], self.max[k]);
+            if d == 0.0 {
+                if o < lo || o > hi {
+                    return false;
+                }
+                continue;
+            }
+            let (t1, t2) = ((lo - o) / d, (hi - o) / d);
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+        }
+        near <= far
+    }
+
+    /// Whether the plane `normal · p = offset` may pass through the box,
+    /// allowing `slack` for rounding.
+    fn reaches_plane(&self, normal: [f64; 3], offset: f64, slack: f64) -> bool {
+        let (mut low, mut high) = (-offset, -offset);
+        for (k, n) in normal.iter().enumerate() {
+            let (a, b) = (n * self.min[k], n * self.max[k]);
+            low += a.min(b);
+            high += a.max(b);
+        }
+        low <= slack && high >= -slack
+    }
+}
+
+// =============================================================================
+// TREE
+// =============================================================================
+
+/// Tree node. Inner nodes keep their left child right after themselves.
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Aabb,
+    /// Leaf: first item in `items`. Inner: index of the right child.
+    start: u32,
+    /// Items in a leaf; 0 for inner nodes.
+    count: u32,
+}
+
+/// Bounding-volume hierarchy over item boxes.
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    /// Item indices, grouped by leaf.
+    items: Vec<u32>,
+    /// Box of each item, by item index.
+    bounds: Vec<Aabb>,
+}
+
+impl Bvh {
+    /// Build a tree over the boxes of items `0..bounds.len()`.
+    ///
+    /// Empty boxes are left out and never returned.
+    pub fn new(bounds: &[Aabb]) -> Self {
+        let mut items: Vec<u32> = (0..bounds.len() as u32).filter(|&i| !bounds[i as usize].is_empty()).collect();
+        let centers: Vec<[f64; 3]> = bounds.iter().map(Aabb::center).collect();
+        let mut bvh = Self { nodes: Vec::new(), items: Vec::new(), bounds: bounds.to_vec() };
+        if !items.is_empty() {
+            let len = items.len();
+            bvh.build(bounds, &centers, &mut items, 0, len);
+        }
+        bvh.items = items;
+        bvh
+    }
+
+    /// Build a tree over the triangles of a mesh, item `i` being the
+    /// triangle at `indices[3i..3i + 3]`.
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let bounds: Vec<Aabb> = mesh.indices.chunks_exact(3)
+            .map(|t| {
+                let corner = |i: u32| {
+                    let i = i as usize * 3;
+                    [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+                };
+                Aabb::of(&[corner(t[0]), corner(t[1]), corner(t[2])])
+            })
+            .collect();
+        Self::new(&bounds)
+    }
+
+    /// Number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the tree holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Items whose boxes overlap `bounds`.
+    pub fn overlapping(&self, bounds: &Aabb) -> Vec<u32> {
+        self.collect(|b| b.overlaps(bounds))
+    }
+
+    /// Items whose boxes the ray from `origin` along `direction` touches.
+    pub fn along_ray(&self, origin: [f64; 3], direction: [f64; 3]) -> Vec<u32> {
+        self.collect(|b| b.hit_by(origin, direction))
+    }
+
+    /// Items whose boxes overlap the triangle's box and reach its plane.
+    pub fn near_triangle(&self, triangle: [[f64; 3]; 3]) -> Vec<u32> {
+        let bounds = Aabb::of(&triangle);
+        let [a, b, c] = triangle;
+        let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+        let normal = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        let offset = normal[0] * a[0] + normal[1] * a[1] + normal[2] * a[2];
+        // Rounding of the plane test grows with the normal and coordinates
+        let scale = normal.iter().map(|n| n.abs()).sum::<f64>() * a.iter().chain(&b).chain(&c).fold(1.0f64, |m, x| m.max(x.abs()));
+        let slack = scale * 1e-12;
+        self.collect(|n| n.overlaps(&bounds) && n.reaches_plane(normal, offset, slack))
+    }
+
+    /// Items passing `test` whose ancestors all pass it too, sorted.
+    fn collect(&self, test: impl Fn(&Aabb) -> bool) -> Vec<u32> {
+        let mut found = Vec::new();
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0usize);
+        }
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if !test(&node.bounds) {
+                continue;
+            }
+            if node.count > 0 {
+                let start = node.start as usize;
+                let items = &self.items[start..start + node.count as usize];
+                found.extend(items.iter().filter(|&&item| test(&self.bounds[item as usize])));
+            } else {
+                stack.push(node.start as usize);
+                stack.push(i + 1);
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+
+    /// Build the subtree over `items[start..end]`, returning its index.
+    fn build(&mut self, bounds: &[Aabb], centers: &[[f64; 3]], items: &mut [u32], start: usize, end: usize) -> usize {
+        let index = self.nodes.len();
+        let node_bounds = items[start..end].iter().fold(Aabb::EMPTY, |b, &i| b.union(&bounds[i as usize]));
+        self.nodes.push(Node { bounds: node_bounds, start: start as u32, count: (end - start) as u32 });
+
+        let count = end - start;
+        if count <= MAX_LEAF {
+            return index;
+        }
+        let Some(split) = best_split(bounds, centers, &items[start..end], node_bounds.surface_area()) else {
+            return index;
+        };
+
+        // Partition by bin, as the costs were counted, keeping the order
+        // stable for determinism
+        let (left, right): (Vec<u32>, Vec<u32>) = items[start..end].iter()
+            .partition(|&&i| split.goes_left(centers[i as usize]));
+        if left.is_empty() || right.is_empty() {
+            return index;
+        }
+        let mid = start + left.len();
+        items[start..mid].copy_from_slice(&left);
+        items[mid..end].copy_from_slice(&right);
+
+        self.build(bounds, centers, items, start, mid);
+        let right = self.build(bounds, centers, items, mid, end);
+        self.nodes[index].start = right as u32;
+        self.nodes[index].count = 0;
+        index
+    }
+}
+
+/// A split plane between two bins along an axis.
+#[derive(Debug, Clone, Copy)]
+struct Split {
+    axis: usize,
+    /// Lowest centre along the axis and the span of the centres.
+    lo: f64,
+    width: f64,
+    /// Last bin on the left.
+    bin: usize,
+}
+
+impl Split {
+    /// Whether an item with centre `center` falls left of the split.
+    fn goes_left(&self, center: [f64; 3]) -> bool {
+        bin_of(center[self.axis], self.lo, self.width) <= self.bin
+    }
+}
+
+/// Bin of a centre at `c` along an axis whose centres start at `lo` and
+/// span `width`.
+fn bin_of(c: f64, lo: f64, width: f64) -> usize {
+    (((c - lo) / width * BINS as f64) as usize).min(BINS - 1)
+}
+
+/// Cheapest SAH split of `items`, or `None` if keeping them in one leaf
+/// is cheaper.
+fn best_split(bounds: &[Aabb], centers: &[[f64; 3]], items: &[u32], area: f64) -> Option<Split> {
+    let mut best: Option<(f64, Split)> = None;
+    for axis in 0..3 {
+        let (lo, hi) = items.iter()
+            .map(|&i| centers[i as usize][axis])
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| (lo.min(c), hi.max(c)));
+        if lo >= hi {
+            continue;
+        }
+
+        let width = hi - lo;
+        let mut bins = [(Aabb::EMPTY, 0usize); BINS];
+        for &i in items {
+            let bin = &mut bins[bin_of(centers[i as usize][axis], lo, width)];
+            bin.0 = bin.0.union(&bounds[i as usize]);
+            bin.1 += 1;
+        }
+
+        // Sweep from the right for the suffix costs, then from the left
+        let mut right = [(0.0f64, 0usize); BINS];
+        let (mut acc, mut n) = (Aabb::EMPTY, 0);
+        for b in (1..BINS).rev() {
+            acc = acc.union(&bins[b].0);
+            n += bins[b].1;
+            right[b] = (acc.surface_area(), n);
+        }
+        let (mut acc, mut n) = (Aabb::EMPTY, 0);
+        for b in 0..BINS - 1 {
+            acc = acc.union(&bins[b].0);
+            n += bins[b].1;
+            let (right_area, right_count) = right[b + 1];
+            if n == 0 || right_count == 0 {
+                continue;
+            }
+            let cost = acc.surface_area() * n as f64 + right_area * right_count as f64;
+            if best.is_none_or(|(c, _)| cost < c) {
+                best = Some((cost, Split { axis, lo, width, bin: b }));
+            }
+        }
+    }
+
+    // A leaf costs one test per item against the whole node
+    let (cost, split) = best?;
+    (cost < area * items.len() as f64).then_some(split)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Many small boxes along a line and a few huge ones across it.
+    fn mixed_boxes() -> Vec<Aabb> {
+        let mut seed = 12345u32;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 8) as f64 / (1 << 24) as f64
+        };
+        let mut boxes: Vec<Aabb> = (0..2000)
+            .map(|_| {
+                let p = [next() * 1000.0, next() * 10.0, next() * 10.0];
+                Aabb { min: p, max: [p[0] + 0.5, p[1] + 0.5, p[2] + 0.5] }
+            })
+            .collect();
+        boxes.push(Aabb { min: [0.0, 4.0, 4.0], max: [1000.0, 4.2, 4.2] });
+        boxes.push(Aabb { min: [-50.0, -1.0, -1.0], max: [1050.0, 0.1, 0.1] });
+        boxes.push(Aabb::EMPTY);
+        boxes
+    }
+
+    /// Test box and ray queries return exactly what a linear scan does.
+    #[test]
+    fn test_queries_match_linear_scan() {
+        let boxes = mixed_boxes();
+        let bvh = Bvh::new(&boxes);
+        assert_eq!(bvh.len(), boxes.len() - 1);
+
+        for query in [
+            Aabb { min: [100.0, 2.0, 2.0], max: [120.0, 5.0, 5.0] },
+            Aabb::point([500.0, 4.1, 4.1]),
+            Aabb { min: [-100.0, -100.0, -100.0], max: [-90.0, 100.0, 100.0] },
+        ] {
+            let expected: Vec<u32> = (0..boxes.len() as u32).filter(|&i| !boxes[i as usize].is_empty() && boxes[i as usize].overlaps(&query)).collect();
+            assert_eq!(bvh.overlapping(&query), expected);
+        }
+
+        for (origin, direction) in [([-10.0, 4.1, 4.1], [1.0, 0.0, 0.0]), ([300.0, 5.0, -20.0], [0.0, 0.0, 1.0]), ([300.0, 5.0, -20.0], [0.0, 0.0, -1.0])] {
+            let expected: Vec<u32> = (0..boxes.len() as u32).filter(|&i| !boxes[i as usize].is_empty() && boxes[i as usize].hit_by(origin, direction)).collect();
+            assert_eq!(bvh.along_ray(origin, direction), expected);
+        }
+    }
+
+    /// Test the SAH keeps the huge boxes from dragging small queries into every leaf.
+    #[test]
+    fn test_sah_separates_sizes() {
+        let bvh = Bvh::new(&mixed_boxes());
+        assert!(bvh.nodes.iter().filter(|n| n.count > 0).all(|n| n.count as usize <= MAX_LEAF));
+        let visited = bvh.nodes.iter().filter(|n| n.bounds.overlaps(&Aabb::point([700.0, 8.0, 8.0]))).count();
+        assert!(visited < 60, "{} nodes visited", visited);
+    }
+
+    /// Test centres one float step apart still split, without recursing forever.
+    #[test]
+    fn test_split_close_centers() {
+        // Points at x = 1 and wide plates at the next float, which the SAH
+        // wants apart
+        let next = f64::from_bits(1.0f64.to_bits() + 1);
+        let boxes: Vec<Aabb> = (0..10)
+            .map(|i| match i % 2 {
+                0 => Aabb::point([1.0, 0.0, 0.0]),
+                _ => Aabb { min: [next, -100.0, -100.0], max: [next, 100.0, 100.0] },
+            })
+            .collect();
+        let bvh = Bvh::new(&boxes);
+        assert_eq!(bvh.overlapping(&Aabb::point([1.0, 0.0, 0.0])), vec![0, 2, 4, 6, 8]);
+    }
+
+    /// Test triangle queries keep the triangles the plane passes and drop the rest.
+    #[test]
+    fn test_near_triangle() {
+        let mesh = crate::render("cube(10);").unwrap();
+        let bvh = Bvh::from_mesh(&mesh);
+        // A slanted triangle through the cube
+        let near = bvh.near_triangle([[-1.0, -1.0, 2.0], [11.0, -1.0, 2.0], [5.0, 11.0, 8.0]]);
+        assert!(!near.is_empty() && near.len() < 12);
+        // A triangle far away
+        assert!(bvh.near_triangle([[20.0, 0.0, 0.0], [21.0, 0.0, 0.0], [20.0, 1.0, 0.0]]).is_empty());
+        assert!(Bvh::new(&[]).overlapping(&Aabb::point([0.0; 3])).is_empty());
+    }
+}
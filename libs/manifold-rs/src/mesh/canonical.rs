@@ -0,0 +1,359 @@
+//! # Canonicalization
+//!
+//! Cleans up the meshes booleans leave behind: vertices a rounding error
+//! apart, triangles collapsed to a line, and T-junctions, where a vertex
+//! of one triangle lies on the edge of its neighbour. A T-junction leaves
+//! a crack in the surface: the neighbour's edge has no partner, so the
+//! mesh is not closed even though it looks closed.
+//!
+//! ## Passes
+//!
+//! ```text
+//! 1. weld: snap positions within tolerance together, then merge
+//!    vertices equal in position, normal and color
+//! 2. split each triangle at every vertex lying inside one of its edges
+//! 3. drop triangles with two corners at one position
+//! 4. weld again, merging the vertices the splits created
+//! ```
+//!
+//! Vertices at one position with different normals stay apart, so flat
+//! shading survives welding.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::Mesh;
+//!
+//! // A square whose right triangle has a vertex in the middle of the
+//! // left triangle's hypotenuse
+//! let mut mesh = Mesh::new();
+//! for [x, y] in [[0.0, 0.0], [2.0, 0.0], [0.0, 2.0], [2.0, 2.0], [1.0, 1.0]] {
+//!     mesh.add_vertex(x, y, 0.0, 0.0, 0.0, 1.0);
+//! }
+//! mesh.add_triangle(0, 1, 2);
+//! mesh.add_triangle(1, 3, 4);
+//! mesh.add_triangle(4, 3, 2);
+//! let report = mesh.canonicalize(1e-4);
+//! assert_eq!(report.t_junctions, 1);
+//! assert_eq!(mesh.triangle_count(), 4);
+//! ```
+
+use std::collections::HashMap;
+
+use super::Mesh;
+
+/// What [`Mesh::canonicalize`] changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CanonicalReport {
+    /// Vertices merged into another.
+    pub welded: usize,
+    /// Triangles dropped for having two corners at one position.
+    pub degenerate: usize,
+    /// Triangle splits made to remove T-junctions.
+    pub t_junctions: usize,
+}
+
+impl Mesh {
+    /// Merge vertices closer than `tolerance` that share normal and color.
+    ///
+    /// Positions within `tolerance` of an earlier vertex are first moved
+    /// onto it, whatever their normals; `0.0` snaps nothing. Vertices no
+    /// triangle uses are dropped.
+    ///
+    /// ## Returns
+    ///
+    /// Number of vertices removed.
+    pub fn weld(&mut self, tolerance: f64) -> usize {
+        let before = self.vertex_count();
+        if tolerance > 0.0 {
+            self.snap_positions(tolerance);
+        }
+        self.merge_equal_vertices();
+        before - self.vertex_count()
+    }
+
+    /// Weld, remove T-junctions and drop degenerate triangles.
+    ///
+    /// See the [module documentation](self) for the passes. Booleans run
+    /// this on their output.
+    pub fn canonicalize(&mut self, tolerance: f64) -> CanonicalReport {
+        let mut report = CanonicalReport { welded: self.weld(tolerance), ..Default::default() };
+        report.t_junctions = self.split_t_junctions(tolerance);
+        report.degenerate = self.drop_degenerate();
+        report.welded += self.weld(0.0);
+        report
+    }
+
+    // =========================================================================
+    // WELDING
+    // =========================================================================
+
+    /// Move every position within `tolerance` of an earlier one onto it.
+    fn snap_positions(&mut self, tolerance: f64) {
+        let cell = |v: f64| (v / tolerance).floor() as i64;
+        let mut grid: HashMap<[i64; 3], Vec<[f64; 3]>> = HashMap::new();
+        for p in self.vertices.chunks_exact_mut(3) {
+            let key = [cell(p[0]), cell(p[1]), cell(p[2])];
+            let near = neighbours(key)
+                .filter_map(|k| grid.get(&k))
+                .flatten()
+                .find(|q| distance_sq(p, &q[..]) <= tolerance * tolerance)
+                .copied();
+            match near {
+                Some(q) => p.copy_from_slice(&q),
+                None => grid.entry(key).or_default().push([p[0], p[1], p[2]]),
+            }
+        }
+    }
+
+    /// Merge vertices with bitwise equal position, normal and color, and
+    /// drop unused ones.
+    fn merge_equal_vertices(&mut self) {
+        let mut seen: HashMap<Vec<u64>, u32> = HashMap::new();
+        let mut remap = vec![u32::MAX; self.vertex_count()];
+        let mut kept = Vec::new();
+
+        let mut indices = std::mem::take(&mut self.indices);
+        for index in &mut indices {
+            let i = *index as usize;
+            if remap[i] == u32::MAX {
+                let key = self.vertex_attributes(i).collect();
+                remap[i] = *seen.entry(key).or_insert_with(|| {
+                    kept.push(i);
+                    (kept.len() - 1) as u32
+                });
+            }
+            *index = remap[i];
+        }
+        self.indices = indices;
+
+        fn gather<T: Copy>(values: &[T], kept: &[usize], width: usize) -> Vec<T> {
+            kept.iter().flat_map(|&i| values[i * width..(i + 1) * width].iter().copied()).collect()
+        }
+        self.vertices = gather(&self.vertices, &kept, 3);
+        self.normals = gather(&self.normals, &kept, 3);
+        self.colors = self.colors.as_deref().map(|colors| gather(colors, &kept, 4));
+    }
+
+    /// Bit patterns of the position, normal and color of vertex `i`,
+    /// flattened.
+    fn vertex_attributes(&self, i: usize) -> impl Iterator<Item = u64> + '_ {
+        // Adding 0.0 turns -0.0 into 0.0
+        let color = self.colors.as_ref().map(|c| &c[i * 4..i * 4 + 4]).unwrap_or(&[]);
+        let position = self.vertices[i * 3..i * 3 + 3].iter().map(|x| (x + 0.0).to_bits());
+        let rest = self.normals[i * 3..i * 3 + 3].iter().chain(color).map(|x| u64::from((x + 0.0).to_bits()));
+        position.chain(rest)
+    }
+
+    // =========================================================================
+    // T-JUNCTIONS
+    // =========================================================================
+
+    /// Split triangles at vertices lying inside their edges.
+    ///
+    /// The vertices on each edge are found once, on the input triangles,
+    /// and each split uses one of them up, so slivers whose corners lie
+    /// on each other's edges cannot make it loop.
+    ///
+    /// ## Returns
+    ///
+    /// Number of splits.
+    fn split_t_junctions(&mut self, tolerance: f64) -> usize {
+        let index = PositionIndex::new(self);
+        let triangles: Vec<[u32; 3]> = self.indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect();
+        let mut indices = Vec::with_capacity(self.indices.len());
+        let mut nodes = self.nodes.as_ref().map(|nodes| Vec::with_capacity(nodes.len()));
+        let mut splits = 0;
+
+        for (t, tri) in triangles.into_iter().enumerate() {
+            let corners = tri.map(|i| self.position(i));
+            let on_edges: [Vec<[f64; 3]>; 3] = std::array::from_fn(|k| {
+                let mut points = index.inside_edge(corners[k], corners[(k + 1) % 3], tolerance);
+                points.retain(|p| !corners.contains(p));
+                points
+            });
+
+            let mut pending = vec![(tri, on_edges)];
+            while let Some((tri, mut on_edges)) = pending.pop() {
+                let Some(k) = (0..3).find(|&k| !on_edges[k].is_empty()) else {
+                    indices.extend_from_slice(&tri);
+                    if let (Some(nodes), Some(old)) = (nodes.as_mut(), self.nodes.as_ref()) {
+                        nodes.push(old[t]);
+                    }
+                    continue;
+                };
+                // Replace edge a → b by a → v → b, fanning from the opposite
+                // corner; the new edges v → c and c → v have no points
+                let (a, b, c) = (tri[k], tri[(k + 1) % 3], tri[(k + 2) % 3]);
+                let mut before = std::mem::take(&mut on_edges[k]);
+                let mut after = before.split_off(before.len() / 2);
+                let p = after.remove(0);
+                let v = self.interpolated_vertex(a, b, p);
+                let [_, bc, ca] = [k, (k + 1) % 3, (k + 2) % 3].map(|e| std::mem::take(&mut on_edges[e]));
+                pending.push(([v, b, c], [after, bc, Vec::new()]));
+                pending.push(([a, v, c], [before, Vec::new(), ca]));
+                splits += 1;
+            }
+        }
+        self.indices = indices;
+        if nodes.is_some() {
+            self.nodes = nodes;
+        }
+        splits
+    }
+
+    /// Add a vertex at `p` on edge `a → b`, with normal and color blended
+    /// from the edge's ends.
+    fn interpolated_vertex(&mut self, a: u32, b: u32, p: [f64; 3]) -> u32 {
+        let (pa, pb) = (self.position(a), self.position(b));
+        let t = (distance_sq(&p, &pa) / distance_sq(&pb, &pa)).sqrt();
+        let t = t as f32;
+        let lerp = |x: &[f32], y: &[f32]| -> Vec<f32> { x.iter().zip(y).map(|(x, y)| x + t * (y - x)).collect() };
+        let (a, b) = (a as usize, b as usize);
+
+        let mut normal = lerp(&self.normals[a * 3..a * 3 + 3], &self.normals[b * 3..b * 3 + 3]);
+        let length = normal.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if length > 0.0 {
+            normal.iter_mut().for_each(|x| *x /= length);
+        }
+        let color = self.colors.as_ref().map(|c| lerp(&c[a * 4..a * 4 + 4], &c[b * 4..b * 4 + 4]));
+
+        self.vertices.extend_from_slice(&p);
+        self.normals.extend_from_slice(&normal);
+        if let (Some(colors), Some(color)) = (self.colors.as_mut(), color) {
+            colors.extend_from_slice(&color);
+        }
+        (self.vertex_count() - 1) as u32
+    }
+
+    /// Drop triangles with two corners at the same position.
+    ///
+    /// ## Returns
+    ///
+    /// Number of triangles dropped.
+    fn drop_degenerate(&mut self) -> usize {
+        let before = self.triangle_count();
+        let kept: Vec<bool> = self.indices
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|i| self.position(i));
+                a != b && b != c && c != a
+            })
+            .collect();
+        let indices = std::mem::take(&mut self.indices);
+        self.indices = indices.chunks_exact(3).zip(&kept).filter(|(_, &k)| k).flat_map(|(t, _)| t).copied().collect();
+        if let Some(nodes) = self.nodes.as_mut() {
+            *nodes = nodes.iter().zip(&kept).filter(|(_, &k)| k).map(|(&node, _)| node).collect();
+        }
+        before - self.triangle_count()
+    }
+
+    fn position(&self, i: u32) -> [f64; 3] {
+        let i = i as usize * 3;
+        [self.vertices[i], self.vertices[i + 1], self.vertices[i + 2]]
+    }
+}
+
+/// The 27 grid cells around and including `key`.
+fn neighbours(key: [i64; 3]) -> impl Iterator<Item = [i64; 3]> {
+    (-1..=1).flat_map(move |x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| [key[0] + x, key[1] + y, key[2] + z])))
+}
+
+fn distance_sq(p: &[f64], q: &[f64]) -> f64 {
+    (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2)
+}
+
+// =============================================================================
+// POSITION INDEX
+// =============================================================================
+
+/// Distinct positions of a mesh sorted by x, for edge queries.
+struct PositionIndex {
+    positions: Vec<[f64; 3]>,
+}
+
+impl PositionIndex {
+    fn new(mesh: &Mesh) -> Self {
+        let (mut positions, _) = mesh.welded_positions();
+        positions.sort_by(|p, q| p[0].total_cmp(&q[0]));
+        Self { positions }
+    }
+
+    /// Positions strictly inside segment `a → b`, within `tolerance` of
+    /// it and farther than `tolerance` from both ends, in order from `a`.
+    fn inside_edge(&self, a: [f64; 3], b: [f64; 3], tolerance: f64) -> Vec<[f64; 3]> {
+        let (lo, hi) = (a[0].min(b[0]) - tolerance, a[0].max(b[0]) + tolerance);
+        let first = self.positions.partition_point(|p| p[0] < lo);
+        let edge: [f64; 3] = std::array::from_fn(|k| b[k] - a[k]);
+        let length = edge.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if length <= 2.0 * tolerance {
+            return Vec::new();
+        }
+
+        let mut inside: Vec<(f64, [f64; 3])> = self.positions[first..]
+            .iter()
+            .take_while(|p| p[0] <= hi)
+            .filter_map(|p| {
+                let offset: [f64; 3] = std::array::from_fn(|k| p[k] - a[k]);
+                let along = (0..3).map(|k| offset[k] * edge[k]).sum::<f64>() / length;
+                let off_line_sq = offset.iter().map(|x| x * x).sum::<f64>() - along * along;
+                (along > tolerance && along < length - tolerance && off_line_sq <= tolerance * tolerance).then_some((along, *p))
+            })
+            .collect();
+        inside.sort_by(|x, y| x.0.total_cmp(&y.0));
+        inside.into_iter().map(|(_, p)| p).collect()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test welding snaps near positions but keeps distinct normals apart.
+    #[test]
+    fn test_weld() {
+        let mut mesh = Mesh::new();
+        mesh.add_vertex(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        mesh.add_vertex(1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        mesh.add_vertex(0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        mesh.add_vertex(1.00001, 0.0, 0.0, 0.0, 0.0, 1.0);
+        mesh.add_vertex(0.0, 1.0, 0.0, 1.0, 0.0, 0.0);
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(0, 3, 4);
+
+        assert_eq!(mesh.clone().weld(0.0), 0);
+        assert_eq!(mesh.weld(1e-4), 1);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 1, 3]);
+        assert_eq!(mesh.position(3), [0.0, 1.0, 0.0]);
+    }
+
+    /// Test T-junction splits close a cracked strip and blend colors.
+    #[test]
+    fn test_t_junctions_and_degenerates() {
+        // Two quads side by side; the right one is split at x = 2, y = 1
+        let mut mesh = Mesh::new();
+        let points = [[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0], [4.0, 0.0], [4.0, 2.0], [2.0, 1.0]];
+        for (i, [x, y]) in points.into_iter().enumerate() {
+            let gray = if i == 2 { 1.0 } else { 0.0 };
+            mesh.add_vertex_with_color(x, y, 0.0, 0.0, 0.0, 1.0, gray, gray, gray, 1.0);
+        }
+        mesh.indices = vec![0, 1, 2, 0, 2, 3, 1, 4, 6, 6, 4, 5, 6, 5, 2];
+        // A sliver along the bottom edge
+        mesh.indices.extend([0, 1, 1]);
+
+        let report = mesh.canonicalize(1e-4);
+        assert_eq!(report, CanonicalReport { welded: 0, degenerate: 1, t_junctions: 1 });
+        assert_eq!(mesh.triangle_count(), 6);
+        // Only the outline is open now
+        let (_, report) = crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&mesh);
+        assert_eq!(report.boundary_edges, 6);
+        // The new vertex at (2, 1) is halfway between black and white
+        let colors = mesh.colors.as_ref().unwrap();
+        assert!((0..mesh.vertex_count()).any(|i| mesh.position(i as u32) == [2.0, 1.0, 0.0] && colors[i * 4] == 0.5));
+    }
+}
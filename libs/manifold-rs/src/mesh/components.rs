@@ -0,0 +1,135 @@
+//! # Connected Components
+//!
+//! Splits a mesh into its separate bodies, the sets of triangles joined
+//! through shared vertices. A model meant as one part but made of two
+//! components has a gap somewhere, which a slicer would print as two
+//! loose pieces; exporters can also write each component as its own
+//! object.
+//!
+//! Vertices at the same position are treated as one, so the duplicated
+//! corners of flat shading do not split faces apart. Bodies touching at
+//! a single vertex or edge are one component. An enclosed cavity is a
+//! component of its own, wound inside out, with negative volume.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mesh = manifold_rs::render("cube(1); translate([3, 0, 0]) cube(2);").unwrap();
+//! let parts = mesh.split_components();
+//! assert_eq!(parts.len(), 2);
+//! assert!((parts[1].volume() - 8.0).abs() < 1e-9);
+//! let (min, max) = parts[1].bounding_box().unwrap();
+//! assert_eq!((min, max), ([3.0, 0.0, 0.0], [5.0, 2.0, 2.0]));
+//! ```
+
+use super::measure::{find, union};
+use super::Mesh;
+
+impl Mesh {
+    /// The connected components of the mesh, as separate meshes.
+    ///
+    /// ## Returns
+    ///
+    /// One mesh per component in the order of their first triangles,
+    /// each with its triangles in their original order and the normals
+    /// and colors of its vertices and node ids of its triangles; empty for
+    /// an empty mesh.
+    #[must_use]
+    pub fn split_components(&self) -> Vec<Mesh> {
+        let (positions, weld) = self.welded_positions();
+        let mut parent: Vec<u32> = (0..positions.len() as u32).collect();
+        for t in self.indices.chunks_exact(3) {
+            union(&mut parent, weld[t[0] as usize], weld[t[1] as usize]);
+            union(&mut parent, weld[t[1] as usize], weld[t[2] as usize]);
+        }
+
+        // Component of each welded root, numbered as first reached
+        let mut slot = vec![None; positions.len()];
+        let mut parts: Vec<Mesh> = Vec::new();
+        // Index of each input vertex in its component's mesh
+        let mut local = vec![None; self.vertex_count()];
+        for (f, t) in self.indices.chunks_exact(3).enumerate() {
+            let root = find(&mut parent, weld[t[0] as usize]) as usize;
+            let part = *slot[root].get_or_insert_with(|| {
+                parts.push(Mesh {
+                    colors: self.colors.as_ref().map(|_| Vec::new()),
+                    nodes: self.nodes.as_ref().map(|_| Vec::new()),
+                    ..Mesh::default()
+                });
+                parts.len() - 1
+            });
+            let mesh = &mut parts[part];
+            let ids = [t[0], t[1], t[2]].map(|v| {
+                let v = v as usize;
+                *local[v].get_or_insert_with(|| {
+                    let (p, n) = (&self.vertices[v * 3..v * 3 + 3], &self.normals[v * 3..v * 3 + 3]);
+                    let id = mesh.add_vertex(p[0], p[1], p[2], n[0], n[1], n[2]);
+                    if let (Some(colors), Some(source)) = (mesh.colors.as_mut(), self.colors.as_ref()) {
+                        colors.extend_from_slice(&source[v * 4..v * 4 + 4]);
+                    }
+                    id
+                })
+            });
+            mesh.add_triangle(ids[0], ids[1], ids[2]);
+            if let (Some(last), Some(nodes)) = (mesh.nodes.as_mut().and_then(|n| n.last_mut()), self.nodes.as_ref()) {
+                *last = nodes[f];
+            }
+        }
+        parts
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test one solid stays whole, with everything it had.
+    #[test]
+    fn test_single_component() {
+        let mesh = crate::render("color([1, 0, 0]) sphere(3, $fn = 16);").unwrap();
+        let parts = mesh.split_components();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].vertex_count(), mesh.vertex_count());
+        assert_eq!(parts[0].triangle_count(), mesh.triangle_count());
+        assert_eq!(parts[0].normals.len(), mesh.normals.len());
+        assert_eq!(parts[0].colors.as_ref().map(Vec::len), Some(mesh.vertex_count() * 4));
+        assert!((parts[0].volume() - mesh.volume()).abs() < 1e-9);
+    }
+
+    /// Test separate bodies split apart, each keeping its own colors.
+    #[test]
+    fn test_separate_bodies() {
+        let mesh = crate::render(
+            "color([1, 0, 0]) cube(1); translate([3, 0, 0]) cube(1); translate([6, 0, 0]) sphere(1, $fn = 12);",
+        ).unwrap();
+        let parts = mesh.split_components();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts.iter().map(Mesh::triangle_count).sum::<usize>(), mesh.triangle_count());
+        assert_eq!(parts[0].colors.as_ref().unwrap()[..4], [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(parts[1].colors.as_ref().unwrap()[..4], Mesh::DEFAULT_COLOR);
+        let (min, max) = parts[2].bounding_box().unwrap();
+        assert!(min[0] > 5.0 && max[0] < 7.0, "{:?}", (min, max));
+        assert!(parts.iter().all(|p| p.genus() == Some(0)));
+    }
+
+    /// Test a cavity is a component of its own with negative volume.
+    #[test]
+    fn test_cavity() {
+        let mesh = crate::render("difference() { cube(10, center = true); cube(4, center = true); }").unwrap();
+        let mut volumes: Vec<f64> = mesh.split_components().iter().map(Mesh::volume).collect();
+        volumes.sort_by(f64::total_cmp);
+        assert_eq!(volumes.len(), 2);
+        assert!((volumes[0] + 64.0).abs() < 1e-9 && (volumes[1] - 1000.0).abs() < 1e-9, "{:?}", volumes);
+    }
+
+    /// Test an empty mesh has no components.
+    #[test]
+    fn test_empty() {
+        assert!(Mesh::new().split_components().is_empty());
+        assert!(Mesh::new().bounding_box().is_none());
+    }
+}
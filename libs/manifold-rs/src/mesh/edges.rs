@@ -0,0 +1,134 @@
+//! # Feature Edges
+//!
+//! The edges a technical drawing outlines: creases where two faces meet
+//! at more than a threshold angle, and boundary edges, which only one
+//! face uses. Edges where more than two faces meet are included too;
+//! edges inside flat or gently curved regions are not.
+//!
+//! The result is a line list, ready for a `LineSegments` buffer:
+//!
+//! ```text
+//! [x0, y0, z0, x1, y1, z1,   ← first edge
+//!  x0, y0, z0, x1, y1, z1,   ← second edge
+//!  ...]
+//! ```
+//!
+//! Vertices at the same position are treated as one, so the duplicated
+//! corners of flat shading do not make every edge a boundary.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let cube = manifold_rs::render("cube(1);").unwrap();
+//! let lines = cube.feature_edges(30.0);
+//! assert_eq!(lines.len(), 12 * 6);
+//! ```
+
+use std::collections::HashMap;
+
+use super::Mesh;
+
+impl Mesh {
+    /// Sharp and boundary edges as a line list, two positions per edge.
+    ///
+    /// ## Parameters
+    ///
+    /// - `angle_threshold`: Angle in degrees between the normals of two
+    ///   faces above which their shared edge is sharp
+    ///
+    /// ## Returns
+    ///
+    /// Six coordinates per edge, in the order the edges first appear in
+    /// the triangles. Degenerate triangles contribute no edges.
+    #[must_use]
+    pub fn feature_edges(&self, angle_threshold: f64) -> Vec<f64> {
+        let (positions, weld) = self.welded_positions();
+        let min_cos = angle_threshold.clamp(0.0, 180.0).to_radians().cos();
+
+        // Normals of the faces on each edge, by welded endpoints
+        let mut order: Vec<(u32, u32)> = Vec::new();
+        let mut faces: HashMap<(u32, u32), Vec<[f64; 3]>> = HashMap::new();
+        for t in self.indices.chunks_exact(3) {
+            let corners = [t[0], t[1], t[2]].map(|v| weld[v as usize]);
+            let Some(normal) = face_normal(corners.map(|v| positions[v as usize])) else {
+                continue;
+            };
+            for k in 0..3 {
+                let (a, b) = (corners[k], corners[(k + 1) % 3]);
+                let key = (a.min(b), a.max(b));
+                faces.entry(key).or_insert_with(|| {
+                    order.push(key);
+                    Vec::new()
+                }).push(normal);
+            }
+        }
+
+        let mut lines = Vec::new();
+        for key in order {
+            let sharp = match faces[&key][..] {
+                [a, b] => a[0] * b[0] + a[1] * b[1] + a[2] * b[2] < min_cos,
+                _ => true,
+            };
+            if sharp {
+                lines.extend_from_slice(&positions[key.0 as usize]);
+                lines.extend_from_slice(&positions[key.1 as usize]);
+            }
+        }
+        lines
+    }
+}
+
+/// Unit normal of a triangle, `None` if it is degenerate.
+fn face_normal([a, b, c]: [[f64; 3]; 3]) -> Option<[f64; 3]> {
+    let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+    let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    (len > 0.0).then(|| n.map(|c| c / len))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of edges in a line list.
+    fn count(lines: &[f64]) -> usize {
+        lines.len() / 6
+    }
+
+    /// Test a cylinder outlines its two rims and, past the threshold,
+    /// every side edge too.
+    #[test]
+    fn test_cylinder_rims() {
+        let mesh = crate::render("cylinder(h = 4, r = 2, $fn = 32);").unwrap();
+        let rims = mesh.feature_edges(30.0);
+        assert_eq!(count(&rims), 64);
+        assert!(rims.chunks_exact(3).all(|p| p[2] == 0.0 || p[2] == 4.0));
+        // 360° / 32 = 11.25° between side faces
+        assert_eq!(count(&mesh.feature_edges(10.0)), 96);
+    }
+
+    /// Test an open surface outlines its boundary and not its interior.
+    #[test]
+    fn test_boundary() {
+        let mut mesh = Mesh::new();
+        for [x, y] in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]] {
+            mesh.add_vertex(x, y, 0.0, 0.0, 0.0, 1.0);
+        }
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(0, 2, 3);
+        assert_eq!(count(&mesh.feature_edges(30.0)), 4);
+    }
+
+    /// Test a flat-shaded sphere with duplicated corners has no
+    /// boundary edges and no outlines at a generous threshold.
+    #[test]
+    fn test_smooth_surface() {
+        let mut mesh = crate::render("sphere(5, $fn = 32);").unwrap();
+        mesh.flat_normals();
+        assert!(mesh.feature_edges(60.0).is_empty());
+    }
+}
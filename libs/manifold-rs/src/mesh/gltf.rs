@@ -0,0 +1,214 @@
+//! # glTF Binary Export
+//!
+//! Writes meshes as GLB, the single-file form of glTF 2.0 that Three.js,
+//! Babylon.js, `<model-viewer>` and Blender load directly.
+//!
+//! ## Layout
+//!
+//! ```text
+//! header   "glTF", version 2, total length
+//! JSON     scene → node → mesh → one triangle primitive
+//! BIN      positions | normals | colors (if any) | indices
+//! ```
+//!
+//! Positions, normals and colors are `f32`, indices `u32`; positions
+//! are narrowed from the mesh's `f64`, the rest written as the mesh
+//! holds them. Vertex colors from `color()` become `COLOR_0`.
+//! OpenSCAD models are Z-up and glTF is Y-up, so the node carries a
+//! rotation of -90° about X; units are left as model units (glTF readers
+//! take them as meters).
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mesh = manifold_rs::render("color([1, 0, 0]) cube(10);").unwrap();
+//! let glb = mesh.to_glb();
+//! assert!(glb.starts_with(b"glTF"));
+//! assert_eq!(glb.len() % 4, 0);
+//! ```
+
+use std::fmt::Write;
+
+use super::Mesh;
+
+/// `glTF` magic at the start of the header.
+const MAGIC: u32 = 0x4654_6C67;
+
+/// Chunk type of the JSON chunk.
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+
+/// Chunk type of the binary chunk.
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+/// Accessor component type of `f32`.
+const FLOAT: u32 = 5126;
+
+/// Accessor component type of `u32`.
+const UNSIGNED_INT: u32 = 5125;
+
+/// Buffer view target of vertex attributes.
+const ARRAY_BUFFER: u32 = 34962;
+
+/// Buffer view target of indices.
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Quaternion turning Z-up into Y-up: -90° about X.
+const Z_UP_TO_Y_UP: &str = "[-0.70710677,0,0,0.70710677]";
+
+// =============================================================================
+// EXPORT
+// =============================================================================
+
+impl Mesh {
+    /// Encode the mesh as a GLB file.
+    ///
+    /// An empty mesh gives a scene with one node and no mesh, since glTF
+    /// accessors cannot be empty.
+    #[must_use]
+    pub fn to_glb(&self) -> Vec<u8> {
+        let count = self.vertex_count();
+        let indices: Vec<u32> = self.indices.chunks_exact(3)
+            .filter(|t| t.iter().all(|&i| (i as usize) < count))
+            .flatten()
+            .copied()
+            .collect();
+        let colors = self.colors.as_deref().filter(|c| c.len() == count * 4);
+
+        let mut bin = Vec::new();
+        let mut json = String::new();
+        // Writing to a String cannot fail
+        let _ = write!(json, r#"{{"asset":{{"version":"2.0","generator":"manifold-rs {}"}},"#, crate::VERSION);
+        let _ = write!(json, r#""scene":0,"scenes":[{{"nodes":[0]}}],"#);
+        if indices.is_empty() {
+            let _ = write!(json, r#""nodes":[{{"rotation":{}}}]}}"#, Z_UP_TO_Y_UP);
+            return glb(json, bin);
+        }
+
+        let mut views = Vec::new();
+        let mut accessors = Vec::new();
+        let view = |bin: &mut Vec<u8>, bytes: Vec<u8>, target: u32| {
+            let entry = format!(r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}}"#, bin.len(), bytes.len(), target);
+            bin.extend_from_slice(&bytes);
+            entry
+        };
+        let floats = |values: &[f32]| values.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<u8>>();
+
+        // POSITION needs its bounds
+        let positions = self.vertices_f32();
+        let (min, max) = bounds(&positions);
+        views.push(view(&mut bin, floats(&positions), ARRAY_BUFFER));
+        accessors.push(format!(
+            r#"{{"bufferView":0,"componentType":{},"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}}"#,
+            FLOAT, count, min[0], min[1], min[2], max[0], max[1], max[2],
+        ));
+        views.push(view(&mut bin, floats(&self.normals), ARRAY_BUFFER));
+        accessors.push(format!(r#"{{"bufferView":1,"componentType":{},"count":{},"type":"VEC3"}}"#, FLOAT, count));
+        let mut attributes = String::from(r#""POSITION":0,"NORMAL":1"#);
+        if let Some(colors) = colors {
+            views.push(view(&mut bin, floats(colors), ARRAY_BUFFER));
+            accessors.push(format!(r#"{{"bufferView":2,"componentType":{},"count":{},"type":"VEC4"}}"#, FLOAT, count));
+            attributes.push_str(r#","COLOR_0":2"#);
+        }
+        let index_view = views.len();
+        let bytes = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        views.push(view(&mut bin, bytes, ELEMENT_ARRAY_BUFFER));
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"SCALAR"}}"#,
+            index_view, UNSIGNED_INT, indices.len(),
+        ));
+
+        let _ = write!(json, r#""nodes":[{{"mesh":0,"rotation":{}}}],"#, Z_UP_TO_Y_UP);
+        let _ = write!(json, r#""meshes":[{{"primitives":[{{"attributes":{{{}}},"indices":{},"mode":4}}]}}],"#, attributes, index_view);
+        let _ = write!(json, r#""buffers":[{{"byteLength":{}}}],"#, bin.len());
+        let _ = write!(json, r#""bufferViews":[{}],"#, views.join(","));
+        let _ = write!(json, r#""accessors":[{}]}}"#, accessors.join(","));
+        glb(json, bin)
+    }
+}
+
+/// Component-wise bounds of flat x, y, z positions.
+fn bounds(positions: &[f32]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for p in positions.chunks_exact(3) {
+        for k in 0..3 {
+            min[k] = min[k].min(p[k]);
+            max[k] = max[k].max(p[k]);
+        }
+    }
+    (min, max)
+}
+
+/// Assemble the header and the two chunks, each padded to 4 bytes: the
+/// JSON with spaces, the binary data with zeros.
+fn glb(json: String, mut bin: Vec<u8>) -> Vec<u8> {
+    let mut json = json.into_bytes();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+    let total = 12 + 8 + json.len() + if bin.is_empty() { 0 } else { 8 + bin.len() };
+
+    let mut out = Vec::with_capacity(total);
+    for word in [MAGIC, 2, total as u32, json.len() as u32, CHUNK_JSON] {
+        out.extend_from_slice(&word.to_le_bytes());
+    }
+    out.extend_from_slice(&json);
+    if !bin.is_empty() {
+        out.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        out.extend_from_slice(&CHUNK_BIN.to_le_bytes());
+        out.extend_from_slice(&bin);
+    }
+    out
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Split a GLB file into its JSON document and binary chunk.
+    fn chunks(glb: &[u8]) -> (serde_json::Value, &[u8]) {
+        let word = |i: usize| u32::from_le_bytes([glb[i], glb[i + 1], glb[i + 2], glb[i + 3]]) as usize;
+        assert_eq!(word(0), MAGIC as usize);
+        assert_eq!(word(8), glb.len());
+        let json_len = word(12);
+        let json = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+        let bin = glb.get(20 + json_len + 8..).unwrap_or_default();
+        (json, bin)
+    }
+
+    /// Test the document describes the mesh's arrays in the binary chunk.
+    #[test]
+    fn test_layout() {
+        let mesh = crate::render("color([1, 0, 0, 0.5]) cube(10);").unwrap();
+        let glb = mesh.to_glb();
+        let (json, bin) = chunks(&glb);
+        let attributes = &json["meshes"][0]["primitives"][0]["attributes"];
+        assert_eq!(attributes["COLOR_0"], 2);
+        assert_eq!(json["accessors"][0]["count"], mesh.vertex_count());
+        assert_eq!(json["accessors"][0]["max"], serde_json::json!([10, 10, 10]));
+        assert_eq!(json["accessors"][3]["count"], mesh.indices.len());
+        assert_eq!(json["buffers"][0]["byteLength"], bin.len());
+
+        // Indices are the last view, read back as written
+        let view = &json["bufferViews"][3];
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+        let first = u32::from_le_bytes([bin[offset], bin[offset + 1], bin[offset + 2], bin[offset + 3]]);
+        assert_eq!(first, mesh.indices[0]);
+    }
+
+    /// Test meshes without colors or triangles.
+    #[test]
+    fn test_plain_and_empty() {
+        let (json, _) = chunks(&crate::render("cube(1);").unwrap().to_glb());
+        assert!(json["meshes"][0]["primitives"][0]["attributes"].get("COLOR_0").is_none());
+        assert_eq!(json["accessors"].as_array().unwrap().len(), 3);
+
+        let empty = Mesh::new().to_glb();
+        let (json, bin) = chunks(&empty);
+        assert!(json.get("meshes").is_none());
+        assert!(bin.is_empty());
+    }
+}
@@ -16,16 +16,41 @@
 //! Vertex:   [x, y, z, halfedge]
 //! Face:     [halfedge]
 //! ```
+//!
+//! ## Conversion
+//!
+//! [`HalfEdgeMesh::from_mesh`] welds the flat `Mesh` by exact position,
+//! links paired half-edges and reports anything that keeps the result from
+//! being a closed 2-manifold. [`HalfEdgeMesh::to_mesh`] goes back to a
+//! flat-shaded `Mesh`.
+//!
+//! ```rust
+//! use manifold_rs::mesh::halfedge::HalfEdgeMesh;
+//!
+//! let mesh = manifold_rs::render("cube(10);").unwrap();
+//! let (he_mesh, report) = HalfEdgeMesh::from_mesh(&mesh);
+//! assert!(report.is_manifold());
+//! assert_eq!(he_mesh.vertex_count(), 8);
+//! assert_eq!(he_mesh.to_mesh().triangle_count(), 12);
+//! ```
+
+use std::collections::HashMap;
+
+use super::Mesh;
 
 // =============================================================================
 // HALFEDGE STRUCT
 // =============================================================================
 
-/// Index type for half-edges, vertices, and faces.
+/// Index type for half-edges.
 ///
 /// Uses u32 for compact memory layout (4 bytes vs 8 for usize).
 pub type HalfEdgeId = u32;
+
+/// Index type for vertices.
 pub type VertexId = u32;
+
+/// Index type for faces.
 pub type FaceId = u32;
 
 /// Invalid index sentinel value.
@@ -63,11 +88,11 @@ pub struct HalfEdge {
 #[derive(Debug, Clone, Copy, Default)]
 pub struct HalfEdgeVertex {
     /// Position x coordinate.
-    pub x: f32,
+    pub x: f64,
     /// Position y coordinate.
-    pub y: f32,
+    pub y: f64,
     /// Position z coordinate.
-    pub z: f32,
+    pub z: f64,
     
     /// One outgoing half-edge from this vertex.
     ///
@@ -161,7 +186,7 @@ impl HalfEdgeMesh {
     /// ## Returns
     ///
     /// Vertex ID for use in face definitions.
-    pub fn add_vertex(&mut self, x: f32, y: f32, z: f32) -> VertexId {
+    pub fn add_vertex(&mut self, x: f64, y: f64, z: f64) -> VertexId {
         let id = self.vertices.len() as VertexId;
         self.vertices.push(HalfEdgeVertex {
             x,
@@ -209,6 +234,146 @@ impl HalfEdgeMesh {
     }
 }
 
+// =============================================================================
+// CONVERSION
+// =============================================================================
+
+/// Topology problems found while converting a `Mesh` to half-edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ManifoldReport {
+    /// Input vertices merged into an earlier vertex at the same position.
+    pub welded_vertices: usize,
+    /// Triangles dropped because two corners welded together or an index
+    /// was out of range.
+    pub degenerate_faces: usize,
+    /// Half-edges with no opposite half-edge (holes in the surface).
+    pub boundary_edges: usize,
+    /// Half-edges whose directed edge already belongs to another face:
+    /// edges shared by more than two faces, or neighbours wound opposite
+    /// ways.
+    pub non_manifold_edges: usize,
+}
+
+impl ManifoldReport {
+    /// Check whether the mesh is a closed, consistently wound 2-manifold.
+    #[must_use]
+    pub fn is_manifold(&self) -> bool {
+        self.boundary_edges == 0 && self.non_manifold_edges == 0
+    }
+}
+
+impl HalfEdgeMesh {
+    /// Build a half-edge mesh from a flat triangle mesh.
+    ///
+    /// Vertices at exactly the same position are welded, triangles that
+    /// collapse are dropped, and half-edges are paired with their opposite
+    /// where it is unambiguous. Normals and colors are not carried over.
+    ///
+    /// ## Parameters
+    ///
+    /// - `mesh`: Triangle mesh to convert
+    ///
+    /// ## Returns
+    ///
+    /// The half-edge mesh and a report of welding and manifoldness.
+    #[must_use]
+    pub fn from_mesh(mesh: &Mesh) -> (Self, ManifoldReport) {
+        let (positions, remap) = mesh.welded_positions();
+        let mut report = ManifoldReport {
+            welded_vertices: mesh.vertex_count() - positions.len(),
+            ..ManifoldReport::default()
+        };
+
+        let mut result = Self::new();
+        for p in &positions {
+            result.add_vertex(p[0], p[1], p[2]);
+        }
+
+        // Faces, with half-edge k of face f at 3f + k
+        let mut directed: HashMap<(VertexId, VertexId), HalfEdgeId> = HashMap::new();
+        for t in mesh.indices.chunks_exact(3) {
+            let corners = [t[0], t[1], t[2]].map(|i| remap.get(i as usize).copied());
+            let [Some(a), Some(b), Some(c)] = corners else {
+                report.degenerate_faces += 1;
+                continue;
+            };
+            if a == b || b == c || a == c {
+                report.degenerate_faces += 1;
+                continue;
+            }
+
+            let face = result.faces.len() as FaceId;
+            let first = result.halfedges.len() as HalfEdgeId;
+            result.faces.push(HalfEdgeFace { halfedge: first });
+            for (k, (start, end)) in [(a, b), (b, c), (c, a)].into_iter().enumerate() {
+                let id = first + k as HalfEdgeId;
+                result.halfedges.push(HalfEdge {
+                    start_vert: start,
+                    end_vert: end,
+                    pair: INVALID_ID,
+                    face,
+                    next: first + ((k + 1) % 3) as HalfEdgeId,
+                });
+                if result.vertices[start as usize].halfedge == INVALID_ID {
+                    result.vertices[start as usize].halfedge = id;
+                }
+                if directed.insert((start, end), id).is_some() {
+                    report.non_manifold_edges += 1;
+                }
+            }
+        }
+
+        // Pair each half-edge with the opposite directed edge
+        for id in 0..result.halfedges.len() {
+            let he = result.halfedges[id];
+            if he.pair != INVALID_ID {
+                continue;
+            }
+            let Some(&opposite) = directed.get(&(he.end_vert, he.start_vert)) else {
+                continue;
+            };
+            let is_owner = directed.get(&(he.start_vert, he.end_vert)) == Some(&(id as HalfEdgeId));
+            if is_owner && result.halfedges[opposite as usize].pair == INVALID_ID {
+                result.halfedges[id].pair = opposite;
+                result.halfedges[opposite as usize].pair = id as HalfEdgeId;
+            }
+        }
+        report.boundary_edges = result.halfedges.iter().filter(|he| he.pair == INVALID_ID).count();
+
+        (result, report)
+    }
+
+    /// Convert back to a flat-shaded triangle mesh.
+    ///
+    /// Each face gets its own three vertices with the face normal, like the
+    /// built-in primitives.
+    #[must_use]
+    pub fn to_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::with_capacity(self.faces.len() * 3, self.faces.len());
+        for face in 0..self.faces.len() {
+            let corners: Vec<[f64; 3]> = self.face_halfedges(face as FaceId)
+                .map(|he| {
+                    let v = &self.vertices[self.halfedges[he as usize].start_vert as usize];
+                    [v.x, v.y, v.z]
+                })
+                .collect();
+            let [a, b, c] = corners[..] else {
+                continue;
+            };
+
+            let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let w = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let n = [u[1] * w[2] - u[2] * w[1], u[2] * w[0] - u[0] * w[2], u[0] * w[1] - u[1] * w[0]];
+            let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            let n = if len > 0.0 { n.map(|c| (c / len) as f32) } else { [0.0; 3] };
+
+            let ids = [a, b, c].map(|p| mesh.add_vertex(p[0], p[1], p[2], n[0], n[1], n[2]));
+            mesh.add_triangle(ids[0], ids[1], ids[2]);
+        }
+        mesh
+    }
+}
+
 // =============================================================================
 // ITERATORS
 // =============================================================================
@@ -302,6 +467,76 @@ mod tests {
         assert_eq!(mesh.vertex_count(), 1);
     }
 
+    /// Test a cube welds to 8 vertices with every half-edge paired.
+    #[test]
+    fn test_from_mesh_cube() {
+        let mesh = crate::render("cube(10);").unwrap();
+        let (he_mesh, report) = HalfEdgeMesh::from_mesh(&mesh);
+        assert!(report.is_manifold());
+        assert_eq!(report.welded_vertices, 24 - 8);
+        assert_eq!(he_mesh.vertex_count(), 8);
+        assert_eq!(he_mesh.face_count(), 12);
+        assert_eq!(he_mesh.halfedge_count(), 36);
+
+        for (id, he) in he_mesh.halfedges.iter().enumerate() {
+            let pair = he_mesh.halfedges[he.pair as usize];
+            assert_eq!(pair.pair as usize, id);
+            assert_eq!((pair.start_vert, pair.end_vert), (he.end_vert, he.start_vert));
+        }
+        // Each cube corner touches 3 to 6 triangles
+        for v in 0..8 {
+            let valence = he_mesh.vertex_halfedges(v).count();
+            assert!((3..=6).contains(&valence));
+        }
+    }
+
+    /// Test open surfaces and degenerate triangles are reported.
+    #[test]
+    fn test_from_mesh_report() {
+        let mut mesh = Mesh::new();
+        let v0 = mesh.add_vertex(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let v1 = mesh.add_vertex(1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let v2 = mesh.add_vertex(0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        let v3 = mesh.add_vertex(1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        mesh.add_triangle(v0, v1, v2);
+        mesh.add_triangle(v0, v1, v3);
+
+        let (he_mesh, report) = HalfEdgeMesh::from_mesh(&mesh);
+        assert_eq!(report.welded_vertices, 1);
+        assert_eq!(report.degenerate_faces, 1);
+        assert_eq!(report.boundary_edges, 3);
+        assert!(!report.is_manifold());
+        assert_eq!(he_mesh.face_count(), 1);
+    }
+
+    /// Test a face shared by two opposite-wound copies is non-manifold.
+    #[test]
+    fn test_from_mesh_non_manifold() {
+        let mut mesh = Mesh::new();
+        let v0 = mesh.add_vertex(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let v1 = mesh.add_vertex(1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let v2 = mesh.add_vertex(0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        let v3 = mesh.add_vertex(0.0, 0.0, 1.0, 0.0, 0.0, 1.0);
+        mesh.add_triangle(v0, v1, v2);
+        mesh.add_triangle(v0, v1, v3);
+
+        let (_, report) = HalfEdgeMesh::from_mesh(&mesh);
+        assert_eq!(report.non_manifold_edges, 1);
+    }
+
+    /// Test converting back keeps the geometry.
+    #[test]
+    fn test_round_trip() {
+        let mesh = crate::render("sphere(5);").unwrap();
+        let (he_mesh, report) = HalfEdgeMesh::from_mesh(&mesh);
+        assert!(report.is_manifold());
+
+        let back = he_mesh.to_mesh();
+        assert_eq!(back.triangle_count(), mesh.triangle_count());
+        let (again, _) = HalfEdgeMesh::from_mesh(&back);
+        assert_eq!(again.vertex_count(), he_mesh.vertex_count());
+    }
+
     /// Test invalid ID constant.
     #[test]
     fn test_invalid_id() {
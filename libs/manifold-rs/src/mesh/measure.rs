@@ -0,0 +1,246 @@
+//! # Measurements
+//!
+//! Mass properties of a closed mesh, for checking print volume and weight
+//! without an external tool.
+//!
+//! ## Formulas
+//!
+//! ```text
+//! volume   = Σ (a · (b × c)) / 6           signed tetrahedra to a reference point
+//! area     = Σ |(b − a) × (c − a)| / 2
+//! centroid = Σ volume_t · (r + a + b + c) / 4  /  volume
+//! genus    = components − (V − E + F) / 2   Euler characteristic per shell
+//! ```
+//!
+//! Volumes are taken relative to the centre of the bounding box rather
+//! than the origin, which keeps far-off models from cancelling away their
+//! precision. Sums run in `f64`. Volume and centroid are only meaningful
+//! for closed, consistently wound meshes; holes make them depend on the
+//! reference point.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mesh = manifold_rs::render("cube(10);").unwrap();
+//! assert!((mesh.volume() - 1000.0).abs() < 1e-9);
+//! assert!((mesh.surface_area() - 600.0).abs() < 1e-9);
+//! let [x, y, z] = mesh.centroid().unwrap();
+//! assert!((x - 5.0).abs() < 1e-9 && (y - 5.0).abs() < 1e-9 && (z - 5.0).abs() < 1e-9);
+//! assert_eq!(mesh.genus(), Some(0));
+//! ```
+
+use std::collections::HashMap;
+
+use super::Mesh;
+
+impl Mesh {
+    /// Enclosed volume, negative if the mesh is wound inside out.
+    #[must_use]
+    pub fn volume(&self) -> f64 {
+        let reference = self.reference_point();
+        self.triangle_points().map(|t| tetrahedron_volume(reference, t)).sum()
+    }
+
+    /// Total area of the triangles.
+    #[must_use]
+    pub fn surface_area(&self) -> f64 {
+        self.triangle_points()
+            .map(|[a, b, c]| length(cross(sub(b, a), sub(c, a))) / 2.0)
+            .sum()
+    }
+
+    /// Centre of mass of the enclosed solid, at uniform density.
+    ///
+    /// `None` if the mesh encloses no volume.
+    #[must_use]
+    pub fn centroid(&self) -> Option<[f64; 3]> {
+        let reference = self.reference_point();
+        let mut volume = 0.0;
+        let mut moment = [0.0; 3];
+        for t in self.triangle_points() {
+            let v = tetrahedron_volume(reference, t);
+            volume += v;
+            for (i, m) in moment.iter_mut().enumerate() {
+                *m += v * (reference[i] + t[0][i] + t[1][i] + t[2][i]) / 4.0;
+            }
+        }
+        (volume.abs() > f64::EPSILON * self.surface_area().powf(1.5)).then(|| moment.map(|m| m / volume))
+    }
+
+    /// Number of handles of the surface, summed over its shells: 0 for a
+    /// sphere, 1 for a torus.
+    ///
+    /// `None` unless every edge joins exactly two triangles. Vertices are
+    /// welded by exact position.
+    #[must_use]
+    pub fn genus(&self) -> Option<u32> {
+        let (positions, remap) = self.welded_positions();
+        let triangles: Vec<[u32; 3]> = self.indices.chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]].map(|i| remap[i as usize]))
+            .filter(|[a, b, c]| a != b && b != c && a != c)
+            .collect();
+
+        let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+        for &[a, b, c] in &triangles {
+            for (from, to) in [(a, b), (b, c), (c, a)] {
+                *edges.entry((from.min(to), from.max(to))).or_default() += 1;
+            }
+        }
+        if triangles.is_empty() || edges.values().any(|&uses| uses != 2) {
+            return None;
+        }
+
+        // Shells are the connected components over shared vertices
+        let mut parent: Vec<u32> = (0..positions.len() as u32).collect();
+        for &[a, b, c] in &triangles {
+            union(&mut parent, a, b);
+            union(&mut parent, b, c);
+        }
+        let mut used = vec![false; positions.len()];
+        for v in triangles.iter().flatten() {
+            used[*v as usize] = true;
+        }
+        let vertices = used.iter().filter(|&&u| u).count() as i64;
+        let shells = (0..positions.len() as u32).filter(|&v| used[v as usize] && find(&mut parent, v) == v).count() as i64;
+
+        let euler = vertices - edges.len() as i64 + triangles.len() as i64;
+        u32::try_from(shells - euler / 2).ok()
+    }
+
+    /// Lowest and highest corners of the axis-aligned bounding box.
+    ///
+    /// `None` if the mesh has no vertices.
+    #[must_use]
+    pub fn bounding_box(&self) -> Option<([f64; 3], [f64; 3])> {
+        if self.vertices.is_empty() {
+            return None;
+        }
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for p in self.vertices.chunks_exact(3) {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
+        }
+        Some((min, max))
+    }
+
+    /// Corners of every triangle.
+    fn triangle_points(&self) -> impl Iterator<Item = [[f64; 3]; 3]> + '_ {
+        self.indices.chunks_exact(3).map(|t| {
+            [t[0], t[1], t[2]].map(|i| {
+                let i = i as usize * 3;
+                [self.vertices[i], self.vertices[i + 1], self.vertices[i + 2]]
+            })
+        })
+    }
+
+    /// Centre of the bounding box, the apex of the volume tetrahedra.
+    fn reference_point(&self) -> [f64; 3] {
+        self.bounding_box().map_or([0.0; 3], |(min, max)| std::array::from_fn(|axis| (min[axis] + max[axis]) / 2.0))
+    }
+}
+
+/// Signed volume of the tetrahedron from `apex` to a triangle.
+fn tetrahedron_volume(apex: [f64; 3], [a, b, c]: [[f64; 3]; 3]) -> f64 {
+    let (a, b, c) = (sub(a, apex), sub(b, apex), sub(c, apex));
+    dot(a, cross(b, c)) / 6.0
+}
+
+/// Root of a vertex's set.
+pub(super) fn find(parent: &mut [u32], mut v: u32) -> u32 {
+    while parent[v as usize] != v {
+        parent[v as usize] = parent[parent[v as usize] as usize];
+        v = parent[v as usize];
+    }
+    v
+}
+
+/// Merge the sets of two vertices.
+pub(super) fn union(parent: &mut [u32], a: u32, b: u32) {
+    let (ra, rb) = (find(parent, a), find(parent, b));
+    if ra != rb {
+        parent[ra.max(rb) as usize] = ra.min(rb);
+    }
+}
+
+/// Difference `a - b`.
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Cross product.
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Dot product.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Euclidean length.
+fn length(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test volume, area and centroid of solids, including a boolean far from the origin.
+    #[test]
+    fn test_mass_properties() {
+        let mesh = crate::render("translate([1000, 0, 0]) difference() { cube(10); cube(5); }").unwrap();
+        assert!((mesh.volume() - 875.0).abs() < 1e-6, "{}", mesh.volume());
+        assert!((mesh.surface_area() - 600.0).abs() < 1e-6, "{}", mesh.surface_area());
+        // Seven unit octants of a 2x2x2 cube, the corner one missing
+        let expected = 1000.0 + (5.0 * 1000.0 - 2.5 * 125.0) / 875.0;
+        let centroid = mesh.centroid().unwrap();
+        assert!((centroid[0] - expected).abs() < 1e-6, "{:?}", centroid);
+
+        let sphere = crate::render("sphere(10, $fn = 64);").unwrap();
+        let ideal = 4.0 / 3.0 * std::f64::consts::PI * 1000.0;
+        assert!((sphere.volume() - ideal).abs() / ideal < 0.01);
+        assert!(sphere.centroid().unwrap().iter().all(|c| c.abs() < 1e-4));
+    }
+
+    /// Test inside-out and empty meshes.
+    #[test]
+    fn test_inside_out_and_empty() {
+        let mut mesh = crate::render("cube(2);").unwrap();
+        for t in mesh.indices.chunks_exact_mut(3) {
+            t.swap(1, 2);
+        }
+        assert!((mesh.volume() + 8.0).abs() < 1e-9);
+        assert!(mesh.centroid().unwrap().iter().all(|c| (c - 1.0).abs() < 1e-9));
+
+        let empty = Mesh::new();
+        assert_eq!(empty.volume(), 0.0);
+        assert_eq!(empty.centroid(), None);
+        assert_eq!(empty.genus(), None);
+    }
+
+    /// Test the genus counts handles over all shells and needs a closed mesh.
+    #[test]
+    fn test_genus() {
+        let ring = crate::render("difference() { cube(10, center = true); cylinder(h = 20, r = 2, center = true, $fn = 8); }").unwrap();
+        assert_eq!(ring.genus(), Some(1));
+
+        let two = crate::render("cube(1); translate([5, 0, 0]) difference() { cube(10, center = true); cylinder(h = 20, r = 2, center = true, $fn = 8); }").unwrap();
+        assert_eq!(two.genus(), Some(1));
+
+        let mut open = crate::render("cube(1);").unwrap();
+        open.indices.truncate(33);
+        assert_eq!(open.genus(), None);
+    }
+}
@@ -6,6 +6,23 @@
 //!
 //! - `Mesh` - Main triangle mesh with vertices, indices, normals
 //! - `halfedge` - HalfEdge mesh for topology operations
+//! - `bvh` - Bounding-volume hierarchy for spatial queries
+//! - `canonical` - Welding, T-junction removal and cleanup after booleans
+//! - `validate` - Watertightness and defect report
+//! - `measure` - Volume, surface area, centroid, genus and bounding box
+//! - `components` - Splitting into connected bodies
+//! - `normals` - Flat and smooth (creased) vertex normals
+//! - `edges` - Sharp and boundary edges as a line list
+//! - `slice` - Cross-sections by a plane as closed contours
+//! - `ray` - Ray casting for picking points on the surface
+//! - `output` - A rendered model with its `#` and `%` preview layers
+//! - `triangulate` - Ear-clipping polygon triangulation
+//! - `stl` - Binary and ASCII STL import and export
+//! - `threemf` - 3MF export with colors
+//! - `amf` - AMF export with one object per color
+//! - `gltf` - GLB (binary glTF) export for web viewers
+//! - `obj` - Wavefront OBJ import and export with normals
+//! - `off` - OFF import and export with normals
 //!
 //! ## Example
 //!
@@ -19,7 +36,29 @@
 //! mesh.add_triangle(v0, v1, v2);
 //! ```
 
+pub mod bvh;
+pub mod canonical;
+pub mod components;
+pub mod edges;
 pub mod halfedge;
+pub mod measure;
+pub mod normals;
+pub mod output;
+pub mod ray;
+pub mod slice;
+pub mod triangulate;
+pub mod stl;
+pub mod threemf;
+pub mod amf;
+pub mod gltf;
+pub mod obj;
+pub mod off;
+pub mod validate;
+
+use std::collections::HashMap;
+
+/// File formats meshes can be written as.
+pub const EXPORT_FORMATS: &[&str] = &["stl", "3mf", "amf", "glb", "obj", "off"];
 
 // =============================================================================
 // MESH STRUCT
@@ -36,6 +75,7 @@ pub mod halfedge;
 /// - `indices`: [i0, i1, i2, ...] - 3 indices per triangle
 /// - `normals`: [nx0, ny0, nz0, ...] - 3 floats per vertex
 /// - `colors`: Optional [r, g, b, a, ...] - 4 floats per vertex
+/// - `nodes`: Optional [n0, n1, ...] - 1 id per triangle
 ///
 /// ## Example
 ///
@@ -50,8 +90,10 @@ pub mod halfedge;
 pub struct Mesh {
     /// Vertex positions: [x0, y0, z0, x1, y1, z1, ...]
     ///
-    /// Each vertex has 3 components (x, y, z).
-    pub vertices: Vec<f32>,
+    /// Each vertex has 3 components (x, y, z), in double precision so
+    /// large models keep their detail; [`Mesh::vertices_f32`] narrows
+    /// them for GPU buffers.
+    pub vertices: Vec<f64>,
     
     /// Triangle indices: [i0, i1, i2, ...]
     ///
@@ -67,9 +109,25 @@ pub struct Mesh {
     ///
     /// Each vertex has 4 color components (r, g, b, a) in range [0.0, 1.0].
     pub colors: Option<Vec<f32>>,
+
+    /// Optional source of each triangle: [n0, n1, ...]
+    ///
+    /// Each triangle has the id of the geometry node that made it (see
+    /// [`provenance`](crate::openscad::provenance)), or [`Mesh::NO_NODE`].
+    /// Booleans and clean-ups carry the ids along; operations that build
+    /// new surfaces leave them out.
+    pub nodes: Option<Vec<u32>>,
 }
 
 impl Mesh {
+    /// Color of uncolored vertices in a mesh that has colors: white, which
+    /// leaves the material's own color unchanged under vertex coloring.
+    pub const DEFAULT_COLOR: [f32; 4] = [1.0; 4];
+
+    /// Node id of triangles whose source is unknown, in a mesh that has
+    /// node ids.
+    pub const NO_NODE: u32 = u32::MAX;
+
     // =========================================================================
     // CONSTRUCTORS
     // =========================================================================
@@ -111,6 +169,7 @@ impl Mesh {
             indices: Vec::with_capacity(triangle_capacity * 3),
             normals: Vec::with_capacity(vertex_capacity * 3),
             colors: None,
+            nodes: None,
         }
     }
 
@@ -140,7 +199,7 @@ impl Mesh {
     /// let idx = mesh.add_vertex(1.0, 2.0, 3.0, 0.0, 0.0, 1.0);
     /// assert_eq!(idx, 0);
     /// ```
-    pub fn add_vertex(&mut self, x: f32, y: f32, z: f32, nx: f32, ny: f32, nz: f32) -> u32 {
+    pub fn add_vertex(&mut self, x: f64, y: f64, z: f64, nx: f32, ny: f32, nz: f32) -> u32 {
         let index = (self.vertices.len() / 3) as u32;
         self.vertices.extend_from_slice(&[x, y, z]);
         self.normals.extend_from_slice(&[nx, ny, nz]);
@@ -158,22 +217,18 @@ impl Mesh {
     /// ## Returns
     ///
     /// Vertex index (u32)
+    #[allow(clippy::too_many_arguments)]
     pub fn add_vertex_with_color(
         &mut self,
-        x: f32, y: f32, z: f32,
+        x: f64, y: f64, z: f64,
         nx: f32, ny: f32, nz: f32,
         r: f32, g: f32, b: f32, a: f32,
     ) -> u32 {
         let index = self.add_vertex(x, y, z, nx, ny, nz);
         
-        // Initialize colors if needed
-        if self.colors.is_none() {
-            self.colors = Some(Vec::with_capacity(self.vertices.len() / 3 * 4));
-        }
-        
-        if let Some(ref mut colors) = self.colors {
-            colors.extend_from_slice(&[r, g, b, a]);
-        }
+        // Initialize colors if needed, earlier vertices taking the default
+        let colors = self.colors.get_or_insert_with(|| Self::DEFAULT_COLOR.repeat(index as usize));
+        colors.extend_from_slice(&[r, g, b, a]);
         
         index
     }
@@ -202,6 +257,9 @@ impl Mesh {
     /// ```
     pub fn add_triangle(&mut self, v0: u32, v1: u32, v2: u32) {
         self.indices.extend_from_slice(&[v0, v1, v2]);
+        if let Some(nodes) = self.nodes.as_mut() {
+            nodes.push(Self::NO_NODE);
+        }
     }
 
     // =========================================================================
@@ -238,6 +296,24 @@ impl Mesh {
         self.indices.len() / 3
     }
 
+    /// Vertex positions narrowed to `f32`, as GPU vertex buffers and
+    /// most mesh formats take them.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use manifold_rs::Mesh;
+    ///
+    /// let mut mesh = Mesh::new();
+    /// mesh.add_vertex(1e7 + 0.25, 0.0, 0.0, 0.0, 0.0, 1.0);
+    /// assert_eq!(mesh.vertices[0], 1e7 + 0.25);
+    /// assert_eq!(mesh.vertices_f32()[0], 1e7);
+    /// ```
+    #[must_use]
+    pub fn vertices_f32(&self) -> Vec<f32> {
+        self.vertices.iter().map(|&c| c as f32).collect()
+    }
+
     /// Check if mesh is empty.
     ///
     /// ## Example
@@ -262,7 +338,7 @@ impl Mesh {
     /// ## Parameters
     ///
     /// - `dx, dy, dz`: Translation offset
-    pub fn translate(&mut self, dx: f32, dy: f32, dz: f32) {
+    pub fn translate(&mut self, dx: f64, dy: f64, dz: f64) {
         for i in (0..self.vertices.len()).step_by(3) {
             self.vertices[i] += dx;
             self.vertices[i + 1] += dy;
@@ -272,23 +348,27 @@ impl Mesh {
 
     /// Apply scale to all vertices.
     ///
+    /// An odd number of negative factors reflects the mesh; see
+    /// [`Mesh::transform`].
+    ///
     /// ## Parameters
     ///
     /// - `sx, sy, sz`: Scale factors
-    pub fn scale(&mut self, sx: f32, sy: f32, sz: f32) {
-        for i in (0..self.vertices.len()).step_by(3) {
-            self.vertices[i] *= sx;
-            self.vertices[i + 1] *= sy;
-            self.vertices[i + 2] *= sz;
-        }
+    pub fn scale(&mut self, sx: f64, sy: f64, sz: f64) {
+        self.transform(&[[sx, 0.0, 0.0, 0.0], [0.0, sy, 0.0, 0.0], [0.0, 0.0, sz, 0.0], [0.0, 0.0, 0.0, 1.0]]);
     }
 
     /// Apply 4x4 transformation matrix to all vertices and normals.
     ///
+    /// Normals go through the inverse transpose, so they stay
+    /// perpendicular to their faces under shear and non-uniform scale.
+    /// A matrix with a negative determinant reflects the mesh; then the
+    /// triangles are rewound so the surface still faces outwards.
+    ///
     /// ## Parameters
     ///
     /// - `matrix`: 4x4 transformation matrix in column-major order
-    pub fn transform(&mut self, matrix: &[[f32; 4]; 4]) {
+    pub fn transform(&mut self, matrix: &[[f64; 4]; 4]) {
         // Transform vertices
         for i in (0..self.vertices.len()).step_by(3) {
             let x = self.vertices[i];
@@ -299,23 +379,32 @@ impl Mesh {
             self.vertices[i + 1] = matrix[0][1] * x + matrix[1][1] * y + matrix[2][1] * z + matrix[3][1];
             self.vertices[i + 2] = matrix[0][2] * x + matrix[1][2] * y + matrix[2][2] * z + matrix[3][2];
         }
-        
-        // Transform normals (without translation, only rotation)
+
+        // Cofactors of the linear part: the inverse transpose times the determinant
+        let linear = |row: usize, col: usize| matrix[col % 3][row % 3];
+        let cofactor: [[f64; 3]; 3] = std::array::from_fn(|r| {
+            std::array::from_fn(|c| linear(r + 1, c + 1) * linear(r + 2, c + 2) - linear(r + 1, c + 2) * linear(r + 2, c + 1))
+        });
+        let determinant: f64 = (0..3).map(|c| linear(0, c) * cofactor[0][c]).sum();
+        let sign = if determinant < 0.0 { -1.0 } else { 1.0 };
+
+        // Transform normals (without translation)
         for i in (0..self.normals.len()).step_by(3) {
-            let nx = self.normals[i];
-            let ny = self.normals[i + 1];
-            let nz = self.normals[i + 2];
-            
-            let rnx = matrix[0][0] * nx + matrix[1][0] * ny + matrix[2][0] * nz;
-            let rny = matrix[0][1] * nx + matrix[1][1] * ny + matrix[2][1] * nz;
-            let rnz = matrix[0][2] * nx + matrix[1][2] * ny + matrix[2][2] * nz;
+            let n = [self.normals[i], self.normals[i + 1], self.normals[i + 2]].map(f64::from);
+            let r: [f64; 3] = std::array::from_fn(|row| sign * (0..3).map(|c| cofactor[row][c] * n[c]).sum::<f64>());
             
             // Renormalize
-            let len = (rnx * rnx + rny * rny + rnz * rnz).sqrt();
+            let len = (r[0] * r[0] + r[1] * r[1] + r[2] * r[2]).sqrt();
             if len > 0.0 {
-                self.normals[i] = rnx / len;
-                self.normals[i + 1] = rny / len;
-                self.normals[i + 2] = rnz / len;
+                self.normals[i] = (r[0] / len) as f32;
+                self.normals[i + 1] = (r[1] / len) as f32;
+                self.normals[i + 2] = (r[2] / len) as f32;
+            }
+        }
+
+        if determinant < 0.0 {
+            for triangle in self.indices.chunks_exact_mut(3) {
+                triangle.swap(1, 2);
             }
         }
     }
@@ -326,7 +415,10 @@ impl Mesh {
 
     /// Merge another mesh into this one.
     ///
-    /// Indices are adjusted to account for existing vertices.
+    /// Indices are adjusted to account for existing vertices. If only one
+    /// of the meshes has colors, the other's vertices get
+    /// [`Mesh::DEFAULT_COLOR`]; if only one has node ids, the other's
+    /// triangles get [`Mesh::NO_NODE`].
     ///
     /// ## Parameters
     ///
@@ -343,12 +435,123 @@ impl Mesh {
             self.indices.push(idx + vertex_offset);
         }
         
-        // Merge colors if present
-        if let Some(ref other_colors) = other.colors {
-            let colors = self.colors.get_or_insert_with(Vec::new);
-            colors.extend_from_slice(other_colors);
+        // Merge colors if present on either side
+        if self.colors.is_some() || other.colors.is_some() {
+            let colors = self.colors.get_or_insert_with(|| Self::DEFAULT_COLOR.repeat(vertex_offset as usize));
+            match &other.colors {
+                Some(other_colors) => colors.extend_from_slice(other_colors),
+                None => colors.extend(Self::DEFAULT_COLOR.repeat(other.vertex_count())),
+            }
+        }
+
+        // Merge node ids likewise
+        if self.nodes.is_some() || other.nodes.is_some() {
+            let triangles = self.triangle_count() - other.triangle_count();
+            let nodes = self.nodes.get_or_insert_with(|| vec![Self::NO_NODE; triangles]);
+            match &other.nodes {
+                Some(other_nodes) => nodes.extend_from_slice(other_nodes),
+                None => nodes.resize(self.indices.len() / 3, Self::NO_NODE),
+            }
         }
     }
+
+    // =========================================================================
+    // NODE IDS
+    // =========================================================================
+
+    /// Id of the geometry node that made `triangle`, if known.
+    #[must_use]
+    pub fn node_of(&self, triangle: usize) -> Option<u32> {
+        self.nodes.as_ref()?.get(triangle).copied().filter(|&node| node != Self::NO_NODE)
+    }
+
+    /// Record `node` as the source of the triangles from `first` on whose
+    /// source is not known yet.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use manifold_rs::Mesh;
+    ///
+    /// let mut mesh = manifold_rs::render("cube(1);").unwrap();
+    /// mesh.nodes = None;
+    /// mesh.assign_nodes(6, 3);
+    /// assert_eq!((mesh.node_of(5), mesh.node_of(6)), (None, Some(3)));
+    /// ```
+    pub fn assign_nodes(&mut self, first: usize, node: u32) {
+        let count = self.triangle_count();
+        let nodes = self.nodes.get_or_insert_with(Vec::new);
+        nodes.resize(count, Self::NO_NODE);
+        for id in nodes.iter_mut().skip(first).filter(|id| **id == Self::NO_NODE) {
+            *id = node;
+        }
+    }
+
+    /// Add `offset` to every known node id, as when a mesh of a subtree
+    /// becomes part of a larger tree.
+    pub fn offset_nodes(&mut self, offset: i64) {
+        for id in self.nodes.iter_mut().flatten().filter(|id| **id != Self::NO_NODE) {
+            *id = (i64::from(*id) + offset) as u32;
+        }
+    }
+
+    // =========================================================================
+    // WELDING
+    // =========================================================================
+
+    /// Encode the mesh in one of the [`EXPORT_FORMATS`].
+    ///
+    /// STL is written as binary. The format name is case-insensitive.
+    ///
+    /// ## Returns
+    ///
+    /// The file bytes, or `None` for an unknown format.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// let mesh = manifold_rs::render("cube(1);").unwrap();
+    /// assert!(mesh.export("OBJ").unwrap().starts_with(b"o "));
+    /// assert!(mesh.export("dxf").is_none());
+    /// ```
+    #[must_use]
+    pub fn export(&self, format: &str) -> Option<Vec<u8>> {
+        // Keep in sync with EXPORT_FORMATS
+        match format.to_ascii_lowercase().as_str() {
+            "stl" => Some(self.to_stl_binary()),
+            "3mf" => Some(self.to_3mf()),
+            "amf" => Some(self.to_amf().into_bytes()),
+            "glb" => Some(self.to_glb()),
+            "obj" => Some(self.to_obj().into_bytes()),
+            "off" => Some(self.to_off().into_bytes()),
+            _ => None,
+        }
+    }
+
+    /// Distinct vertex positions, merging vertices at exactly the same point.
+    ///
+    /// `-0.0` and `0.0` count as the same coordinate.
+    ///
+    /// ## Returns
+    ///
+    /// Positions in first-seen order, and for each input vertex the index
+    /// of its welded position.
+    #[must_use]
+    pub fn welded_positions(&self) -> (Vec<[f64; 3]>, Vec<u32>) {
+        let mut seen: HashMap<[u64; 3], u32> = HashMap::new();
+        let mut positions = Vec::new();
+        let remap = self.vertices.chunks_exact(3)
+            .map(|p| {
+                // Adding 0.0 turns -0.0 into 0.0
+                let p = [p[0] + 0.0, p[1] + 0.0, p[2] + 0.0];
+                *seen.entry([p[0].to_bits(), p[1].to_bits(), p[2].to_bits()]).or_insert_with(|| {
+                    positions.push(p);
+                    (positions.len() - 1) as u32
+                })
+            })
+            .collect();
+        (positions, remap)
+    }
 }
 
 // =============================================================================
@@ -400,6 +603,31 @@ mod tests {
         assert!((mesh.vertices[2] - 30.0).abs() < 0.001);
     }
 
+    /// Test reflections and shears keep the surface facing outwards.
+    #[test]
+    fn test_transform_reflection_and_shear() {
+        let mut cube = Mesh::new();
+        crate::manifold::constructors::build_cube(&mut cube, [2.0, 2.0, 2.0], false);
+        // Column-major: x' = -x + y, y' = y, z' = z
+        let matrix = [[-1.0, 0.0, 0.0, 0.0], [1.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+        cube.transform(&matrix);
+        assert!((cube.volume() - 8.0).abs() < 1e-4, "{}", cube.volume());
+        // Each vertex normal agrees with its face's winding
+        for t in cube.indices.chunks_exact(3) {
+            let p = |i: u32| [0, 1, 2].map(|k| cube.vertices[i as usize * 3 + k]);
+            let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+            let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+            let face = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+            let n = [0, 1, 2].map(|k| f64::from(cube.normals[t[0] as usize * 3 + k]));
+            let len = (face[0] * face[0] + face[1] * face[1] + face[2] * face[2]).sqrt();
+            let cos = (face[0] * n[0] + face[1] * n[1] + face[2] * n[2]) / len;
+            assert!(cos > 0.999, "{}", cos);
+        }
+
+        cube.scale(1.0, -1.0, 1.0);
+        assert!((cube.volume() - 8.0).abs() < 1e-4);
+    }
+
     /// Test mesh merging.
     #[test]
     fn test_merge() {
@@ -412,4 +640,40 @@ mod tests {
         mesh1.merge(&mesh2);
         assert_eq!(mesh1.vertex_count(), 2);
     }
+
+    /// Test merging colored and uncolored meshes keeps one color per vertex.
+    #[test]
+    fn test_merge_colors() {
+        let mut plain = Mesh::new();
+        plain.add_vertex(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let mut red = Mesh::new();
+        red.add_vertex_with_color(1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0);
+
+        let mut mesh = plain.clone();
+        mesh.merge(&red);
+        mesh.merge(&plain);
+        assert_eq!(mesh.colors.unwrap(), [Mesh::DEFAULT_COLOR, [1.0, 0.0, 0.0, 1.0], Mesh::DEFAULT_COLOR].concat());
+    }
+
+    /// Test node ids are kept per triangle through merges and offsets.
+    #[test]
+    fn test_nodes() {
+        let mut triangle = Mesh::new();
+        for x in [0.0, 1.0, 0.0] {
+            triangle.add_vertex(x, 1.0 - x, 0.0, 0.0, 0.0, 1.0);
+        }
+        triangle.add_triangle(0, 1, 2);
+        assert_eq!(triangle.node_of(0), None);
+
+        let mut tagged = triangle.clone();
+        tagged.assign_nodes(0, 4);
+        let mut mesh = triangle.clone();
+        mesh.merge(&tagged);
+        mesh.merge(&triangle);
+        assert_eq!(mesh.nodes.as_deref(), Some(&[Mesh::NO_NODE, 4, Mesh::NO_NODE][..]));
+
+        mesh.assign_nodes(2, 1);
+        mesh.offset_nodes(10);
+        assert_eq!([0, 1, 2, 3].map(|t| mesh.node_of(t)), [None, Some(14), Some(11), None]);
+    }
 }
@@ -0,0 +1,232 @@
+//! # Normals
+//!
+//! Recomputes the vertex normals of a finished mesh for display: flat,
+//! one normal per face, or smooth, averaged over the faces around each
+//! vertex. Booleans leave a mix of both, with every triangle carrying its
+//! own corners wherever they cut.
+//!
+//! ## Smoothing
+//!
+//! ```text
+//! corner normal = Σ angle(f) · normal(f)   over the faces f at the corner's
+//!                                          position within the crease angle
+//!                                          of the corner's own face
+//! ```
+//!
+//! Weighting by the corner angle keeps a face split into many thin
+//! triangles from outweighing its neighbours. Faces meeting at more than
+//! the crease angle keep separate normals, so a cylinder's rim stays
+//! sharp while its side shades round.
+//!
+//! Corners with the same position, normal and color share one vertex,
+//! so either mode needs no more vertices than the surface has distinct
+//! shading: a smooth sphere has one vertex per position.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut mesh = manifold_rs::render("sphere(5, $fn = 24);").unwrap();
+//! mesh.smooth_normals(30.0);
+//! let (positions, _) = mesh.welded_positions();
+//! assert_eq!(mesh.vertex_count(), positions.len());
+//! ```
+
+use std::collections::HashMap;
+
+use super::Mesh;
+
+/// Crease angle in degrees of smooth normals when the options leave it
+/// out.
+pub const DEFAULT_CREASE_ANGLE: f64 = 30.0;
+
+impl Mesh {
+    /// Give every face its own normal, sharing vertices between adjacent
+    /// faces in one plane.
+    ///
+    /// Triangles, positions and colors are unchanged.
+    pub fn flat_normals(&mut self) {
+        self.shade(None);
+    }
+
+    /// Average normals over the faces around each vertex, keeping edges
+    /// sharp where faces meet at more than `crease_angle` degrees.
+    ///
+    /// Triangles, positions and colors are unchanged; `0.0` gives flat
+    /// normals and `180.0` smooths every edge.
+    pub fn smooth_normals(&mut self, crease_angle: f64) {
+        self.shade(Some(crease_angle.clamp(0.0, 180.0).to_radians().cos()));
+    }
+
+    /// Rebuild the vertices with corner normals averaged over the faces
+    /// whose normals are at least `min_cos` aligned with the corner's
+    /// own, or flat normals for `None`.
+    fn shade(&mut self, min_cos: Option<f64>) {
+        let (positions, weld) = self.welded_positions();
+        let triangles: Vec<[usize; 3]> = self.indices.chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect();
+        let faces: Vec<Face> = triangles.iter()
+            .map(|t| Face::of(t.map(|v| positions[weld[v] as usize])))
+            .collect();
+
+        // Faces around each welded position, with the corner there
+        let mut around: Vec<Vec<(usize, usize)>> = vec![Vec::new(); positions.len()];
+        if min_cos.is_some() {
+            for (f, t) in triangles.iter().enumerate() {
+                for (k, &v) in t.iter().enumerate() {
+                    around[weld[v] as usize].push((f, k));
+                }
+            }
+        }
+
+        let mut out = Mesh::with_capacity(positions.len(), triangles.len());
+        if self.colors.is_some() {
+            out.colors = Some(Vec::new());
+        }
+        let mut shared: HashMap<(u32, [u32; 3], [u32; 4]), u32> = HashMap::new();
+        for (f, t) in triangles.iter().enumerate() {
+            let own = faces[f].normal;
+            let ids = [0, 1, 2].map(|k| {
+                let v = t[k];
+                let normal = match min_cos {
+                    Some(min_cos) if own != [0.0; 3] => {
+                        let sum = around[weld[v] as usize].iter()
+                            .map(|&(g, corner)| (&faces[g], corner))
+                            .filter(|(face, _)| dot(face.normal, own) >= min_cos)
+                            .fold([0.0; 3], |s, (face, corner)| {
+                                std::array::from_fn(|i| s[i] + face.angles[corner] * face.normal[i])
+                            });
+                        normalized(sum).unwrap_or(own)
+                    }
+                    _ => own,
+                };
+                let normal = normal.map(|c| c as f32);
+                let color = self.colors.as_ref().map_or(Mesh::DEFAULT_COLOR, |c| [0, 1, 2, 3].map(|i| c[v * 4 + i]));
+                let key = (weld[v], normal.map(|c| (c + 0.0).to_bits()), color.map(|c| (c + 0.0).to_bits()));
+                *shared.entry(key).or_insert_with(|| {
+                    let p = positions[weld[v] as usize];
+                    let id = out.add_vertex(p[0], p[1], p[2], normal[0], normal[1], normal[2]);
+                    if let Some(colors) = out.colors.as_mut() {
+                        colors.extend_from_slice(&color);
+                    }
+                    id
+                })
+            });
+            out.add_triangle(ids[0], ids[1], ids[2]);
+        }
+        out.nodes = self.nodes.take();
+        *self = out;
+    }
+}
+
+/// Unit normal and corner angles of a triangle.
+struct Face {
+    /// Unit normal, zero if the triangle is degenerate.
+    normal: [f64; 3],
+    /// Interior angle at each corner, in radians.
+    angles: [f64; 3],
+}
+
+impl Face {
+    fn of(corners: [[f64; 3]; 3]) -> Self {
+        let [a, b, c] = corners;
+        let normal = normalized(cross(sub(b, a), sub(c, a))).unwrap_or([0.0; 3]);
+        let angle = |p: [f64; 3], q: [f64; 3], r: [f64; 3]| {
+            let (u, v) = (sub(q, p), sub(r, p));
+            let len = (dot(u, u) * dot(v, v)).sqrt();
+            if len > 0.0 { (dot(u, v) / len).clamp(-1.0, 1.0).acos() } else { 0.0 }
+        };
+        Self { normal, angles: [angle(a, b, c), angle(b, c, a), angle(c, a, b)] }
+    }
+}
+
+/// `v` scaled to unit length, `None` if it is zero.
+fn normalized(v: [f64; 3]) -> Option<[f64; 3]> {
+    let len = dot(v, v).sqrt();
+    (len > 0.0).then(|| v.map(|c| c / len))
+}
+
+/// Vector difference.
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Dot product.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Cross product.
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test flat normals share vertices within a face and split them at
+    /// edges.
+    #[test]
+    fn test_flat_cube() {
+        let mut mesh = crate::render("cube(2);").unwrap();
+        mesh.smooth_normals(180.0);
+        assert_eq!(mesh.vertex_count(), 8);
+        mesh.flat_normals();
+        assert_eq!(mesh.vertex_count(), 24);
+        assert_eq!(mesh.triangle_count(), 12);
+        assert!((mesh.volume() - 8.0).abs() < 1e-9);
+        assert!(mesh.normals.chunks_exact(3).all(|n| n.iter().filter(|c| c.abs() == 1.0).count() == 1));
+    }
+
+    /// Test a sphere shades round with one vertex per position, its
+    /// normals pointing away from the centre.
+    #[test]
+    fn test_smooth_sphere() {
+        let mut mesh = crate::render("sphere(5, $fn = 24);").unwrap();
+        mesh.flat_normals();
+        let flat = mesh.vertex_count();
+        mesh.smooth_normals(DEFAULT_CREASE_ANGLE);
+        let (positions, _) = mesh.welded_positions();
+        assert_eq!(mesh.vertex_count(), positions.len());
+        assert!(mesh.vertex_count() < flat);
+        for (p, n) in mesh.vertices.chunks_exact(3).zip(mesh.normals.chunks_exact(3)) {
+            let len = dot([p[0], p[1], p[2]], [p[0], p[1], p[2]]).sqrt();
+            let cos = (p[0] * f64::from(n[0]) + p[1] * f64::from(n[1]) + p[2] * f64::from(n[2])) / len;
+            assert!(cos > 0.95, "{}", cos);
+        }
+    }
+
+    /// Test a cylinder keeps its rim sharp: cap vertices point straight
+    /// up or down, side vertices sideways.
+    #[test]
+    fn test_crease_keeps_rim() {
+        let mut mesh = crate::render("cylinder(h = 4, r = 2, $fn = 32);").unwrap();
+        mesh.smooth_normals(DEFAULT_CREASE_ANGLE);
+        for n in mesh.normals.chunks_exact(3) {
+            assert!(n[2].abs() > 0.999 || n[2].abs() < 1e-6, "{:?}", n);
+        }
+        // Each rim position has one cap and one side vertex
+        let (positions, _) = mesh.welded_positions();
+        let side = mesh.normals.chunks_exact(3).filter(|n| n[2].abs() < 1e-6).count();
+        assert_eq!((positions.len(), side, mesh.vertex_count()), (64, 64, 128));
+    }
+
+    /// Test vertices of different colors are not merged.
+    #[test]
+    fn test_colors_kept() {
+        let mut mesh = crate::render("color([1, 0, 0]) cube(1); translate([1, 0, 0]) cube(1);").unwrap();
+        let colors = mesh.colors.clone().unwrap();
+        let red = colors.chunks_exact(4).filter(|c| *c == [1.0, 0.0, 0.0, 1.0]).count();
+        assert!(red > 0);
+        mesh.smooth_normals(DEFAULT_CREASE_ANGLE);
+        let colors = mesh.colors.as_ref().unwrap();
+        assert_eq!(colors.len(), mesh.vertex_count() * 4);
+        assert!(colors.chunks_exact(4).any(|c| c == [1.0, 0.0, 0.0, 1.0]));
+        assert!(colors.chunks_exact(4).any(|c| c == [1.0; 4]));
+    }
+}
@@ -0,0 +1,233 @@
+//! # OBJ Import and Export
+//!
+//! Writes meshes as Wavefront OBJ, the format Blender and MeshLab open
+//! without plugins, and reads OBJ files back for `import()`.
+//!
+//! Output shares vertex positions between triangles, welded by exact
+//! position, and refers to each corner's normal separately:
+//!
+//! ```text
+//! o OpenSCAD_Model
+//! v 0 0 0
+//! vn 0 0 -1
+//! f 1//1 3//1 2//1
+//! ```
+//!
+//! Input takes `v`, `vn` and `f` lines and ignores the rest (texture
+//! coordinates, groups, materials). Polygons are triangulated; faces
+//! whose corners all have normals keep them, other faces are flat-shaded
+//! like STL facets.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::Mesh;
+//!
+//! let mesh = manifold_rs::render("cube(10);").unwrap();
+//! let obj = mesh.to_obj();
+//! assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 8);
+//! let back = Mesh::from_obj(obj.as_bytes()).unwrap();
+//! assert_eq!(back.triangle_count(), 12);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::triangulate::triangulate_face;
+use super::Mesh;
+use crate::error::{ManifoldError, ManifoldResult};
+
+/// Object name written to the `o` line.
+const OBJECT_NAME: &str = "OpenSCAD_Model";
+
+/// A face corner: position index, and normal index if given.
+pub(super) type Corner = (usize, Option<usize>);
+
+// =============================================================================
+// EXPORT
+// =============================================================================
+
+impl Mesh {
+    /// Encode the mesh as OBJ text with vertex normals.
+    #[must_use]
+    pub fn to_obj(&self) -> String {
+        let (positions, remap) = self.welded_positions();
+        let mut normal_ids: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut normals = Vec::new();
+        let normal_of: Vec<usize> = self.normals.chunks_exact(3)
+            .map(|n| {
+                *normal_ids.entry([n[0].to_bits(), n[1].to_bits(), n[2].to_bits()]).or_insert_with(|| {
+                    normals.push([n[0], n[1], n[2]]);
+                    normals.len() - 1
+                })
+            })
+            .collect();
+
+        let mut out = String::new();
+        // Writing to a String cannot fail
+        let _ = writeln!(out, "o {}", OBJECT_NAME);
+        for p in &positions {
+            let _ = writeln!(out, "v {} {} {}", p[0], p[1], p[2]);
+        }
+        for n in &normals {
+            let _ = writeln!(out, "vn {} {} {}", n[0], n[1], n[2]);
+        }
+        for triangle in self.indices.chunks_exact(3) {
+            let corners: Option<Vec<String>> = triangle.iter()
+                .map(|&i| {
+                    let position = remap.get(i as usize)?;
+                    let normal = normal_of.get(i as usize)?;
+                    Some(format!("{}//{}", position + 1, normal + 1))
+                })
+                .collect();
+            // Skip triangles with bad indices, as the STL writer does
+            if let Some(corners) = corners {
+                let _ = writeln!(out, "f {}", corners.join(" "));
+            }
+        }
+        out
+    }
+}
+
+// =============================================================================
+// IMPORT
+// =============================================================================
+
+impl Mesh {
+    /// Decode an OBJ file.
+    ///
+    /// Indices may be negative, counting back from the latest vertex or
+    /// normal, as the format allows.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `ManifoldError::ImportError` if the data is not text, or a
+    /// vertex, normal or face does not parse or refers to a missing one.
+    pub fn from_obj(data: &[u8]) -> ManifoldResult<Mesh> {
+        let text = std::str::from_utf8(data)
+            .map_err(|_| ManifoldError::ImportError("OBJ is not text".to_string()))?;
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut faces = Vec::new();
+        for (line_number, line) in text.lines().enumerate() {
+            let bad = |what: &str| ManifoldError::ImportError(format!("bad {} on line {}", what, line_number + 1));
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("v") => positions.push(parse_xyz(words).ok_or_else(|| bad("vertex"))?),
+                Some("vn") => normals.push(parse_xyz(words).ok_or_else(|| bad("normal"))?.map(|c| c as f32)),
+                Some("f") => {
+                    let face: Vec<Corner> = words
+                        .map(|word| parse_corner(word, positions.len(), normals.len()))
+                        .collect::<Option<_>>()
+                        .filter(|face: &Vec<Corner>| face.len() >= 3)
+                        .ok_or_else(|| bad("face"))?;
+                    faces.push(face);
+                }
+                _ => {}
+            }
+        }
+        Ok(mesh_faces(&positions, &normals, &faces))
+    }
+}
+
+/// The first three numbers of a `v` or `vn` line; colors or a `w`
+/// after them are ignored.
+fn parse_xyz<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<[f64; 3]> {
+    let mut next = || words.next()?.parse::<f64>().ok();
+    Some([next()?, next()?, next()?])
+}
+
+/// One `v`, `v/t`, `v//n` or `v/t/n` corner of an `f` line, as 0-based
+/// indices.
+fn parse_corner(word: &str, positions: usize, normals: usize) -> Option<Corner> {
+    let mut parts = word.split('/');
+    let position = resolve(parts.next()?, positions)?;
+    let normal = match parts.nth(1) {
+        Some(normal) if !normal.is_empty() => Some(resolve(normal, normals)?),
+        _ => None,
+    };
+    Some((position, normal))
+}
+
+/// A 1-based or negative (relative) OBJ index into `count` items.
+fn resolve(index: &str, count: usize) -> Option<usize> {
+    let index: i64 = index.parse().ok()?;
+    let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+    (0..count as i64).contains(&resolved).then_some(resolved as usize)
+}
+
+/// Triangulate polygon faces over shared positions into a mesh.
+///
+/// Faces with a normal at every corner share one vertex per position and
+/// normal pair; the rest get a flat facet per triangle.
+pub(super) fn mesh_faces(positions: &[[f64; 3]], normals: &[[f32; 3]], faces: &[Vec<Corner>]) -> Mesh {
+    let mut mesh = Mesh::with_capacity(positions.len(), faces.len());
+    let mut shared: HashMap<(usize, usize), u32> = HashMap::new();
+    for face in faces {
+        let points: Vec<[f64; 3]> = face.iter().map(|&(p, _)| positions[p]).collect();
+        let local: Vec<usize> = (0..face.len()).collect();
+        let face_normals: Option<Vec<usize>> = face.iter().map(|&(_, n)| n).collect();
+        for triangle in triangulate_face(&points, &local) {
+            match &face_normals {
+                Some(face_normals) => {
+                    let ids = triangle.map(|k| {
+                        let (p, n) = (face[k].0, face_normals[k]);
+                        *shared.entry((p, n)).or_insert_with(|| {
+                            let ([x, y, z], [nx, ny, nz]) = (positions[p], normals[n]);
+                            mesh.add_vertex(x, y, z, nx, ny, nz)
+                        })
+                    });
+                    mesh.add_triangle(ids[0], ids[1], ids[2]);
+                }
+                None => mesh.push_facet(triangle.map(|k| positions[face[k].0])),
+            }
+        }
+    }
+    mesh
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test writing and reading back keeps triangles and normals.
+    #[test]
+    fn test_round_trip() {
+        let mesh = crate::render("sphere(5, $fn = 12);").unwrap();
+        let back = Mesh::from_obj(mesh.to_obj().as_bytes()).unwrap();
+        assert_eq!(back.triangle_count(), mesh.triangle_count());
+        let corners = |m: &Mesh| -> Vec<[f64; 6]> {
+            m.indices.iter().map(|&i| {
+                let (v, n) = (&m.vertices[i as usize * 3..], &m.normals[i as usize * 3..]);
+                [v[0], v[1], v[2], n[0].into(), n[1].into(), n[2].into()]
+            }).collect()
+        };
+        assert_eq!(corners(&back), corners(&mesh));
+        assert!(back.validate().is_valid());
+    }
+
+    /// Test quads, relative indices and faces without normals.
+    #[test]
+    fn test_polygons() {
+        let obj = "# square\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\nf 1/1/1 2/1/1 3/1/1 4/1/1\nf -4 -3 -2\n";
+        let mesh = Mesh::from_obj(obj.as_bytes()).unwrap();
+        assert_eq!(mesh.triangle_count(), 3);
+        // The quad shares its four corners; the bare triangle is flat
+        assert_eq!(mesh.vertex_count(), 4 + 3);
+        assert!(mesh.normals.chunks_exact(3).all(|n| n == [0.0, 0.0, 1.0]));
+    }
+
+    /// Test malformed files are rejected.
+    #[test]
+    fn test_import_errors() {
+        assert!(Mesh::from_obj(b"v 1 2\n").is_err());
+        assert!(Mesh::from_obj(b"v 0 0 0\nv 1 0 0\nf 1 2 3\n").is_err());
+        assert!(Mesh::from_obj(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2\n").is_err());
+        assert!(Mesh::from_obj(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1//1 2//1 3//1\n").is_err());
+        assert!(Mesh::from_obj(&[0xff, 0xfe]).is_err());
+    }
+}
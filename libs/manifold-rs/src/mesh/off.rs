@@ -0,0 +1,176 @@
+//! # OFF Import and Export
+//!
+//! Writes meshes as Object File Format, which MeshLab and Geomview read,
+//! and reads OFF files back for `import()`.
+//!
+//! Output is `NOFF`: each vertex line carries its normal after the
+//! position. Vertices are written as the mesh holds them, so flat faces
+//! keep their own copies of shared corners; MeshLab's "merge close
+//! vertices" joins them.
+//!
+//! ```text
+//! NOFF
+//! 24 12 0
+//! 0 0 0 0 0 -1
+//! 3 0 2 1
+//! ```
+//!
+//! Input accepts `OFF`, `NOFF`, `COFF` and `CNOFF`, with `#` comments and
+//! the counts on the header line or the next. Normals are kept for `N`
+//! files; plain `OFF` faces are flat-shaded like STL facets. Colors are
+//! ignored.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::Mesh;
+//!
+//! let mesh = manifold_rs::render("cube(10);").unwrap();
+//! let off = mesh.to_off();
+//! assert!(off.starts_with("NOFF\n"));
+//! let back = Mesh::from_off(off.as_bytes()).unwrap();
+//! assert_eq!(back.triangle_count(), 12);
+//! ```
+
+use std::fmt::Write;
+
+use super::obj::{mesh_faces, Corner};
+use super::Mesh;
+use crate::error::{ManifoldError, ManifoldResult};
+
+// =============================================================================
+// EXPORT
+// =============================================================================
+
+impl Mesh {
+    /// Encode the mesh as `NOFF` text, with a normal on each vertex line.
+    #[must_use]
+    pub fn to_off(&self) -> String {
+        let count = self.vertex_count();
+        let triangles: Vec<&[u32]> = self.indices.chunks_exact(3)
+            .filter(|t| t.iter().all(|&i| (i as usize) < count))
+            .collect();
+
+        let mut out = String::new();
+        // Writing to a String cannot fail
+        let _ = writeln!(out, "NOFF");
+        let _ = writeln!(out, "{} {} 0", count, triangles.len());
+        for (p, n) in self.vertices.chunks_exact(3).zip(self.normals.chunks_exact(3)) {
+            let _ = writeln!(out, "{} {} {} {} {} {}", p[0], p[1], p[2], n[0], n[1], n[2]);
+        }
+        for t in triangles {
+            let _ = writeln!(out, "3 {} {} {}", t[0], t[1], t[2]);
+        }
+        out
+    }
+}
+
+// =============================================================================
+// IMPORT
+// =============================================================================
+
+impl Mesh {
+    /// Decode an OFF file.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `ManifoldError::ImportError` if the data is not text, the
+    /// header is not a 3D OFF variant, or a count, vertex or face does
+    /// not parse or refers to a missing vertex.
+    pub fn from_off(data: &[u8]) -> ManifoldResult<Mesh> {
+        let text = std::str::from_utf8(data)
+            .map_err(|_| ManifoldError::ImportError("OFF is not text".to_string()))?;
+        // Non-empty lines without comments, with their 1-based numbers
+        let mut lines = text.lines().enumerate()
+            .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or_default().split_whitespace().collect::<Vec<_>>()))
+            .filter(|(_, words)| !words.is_empty());
+        let bad = |what: &str, line: usize| ManifoldError::ImportError(format!("bad {} on line {}", what, line));
+
+        let (header_line, mut header) = lines.next()
+            .ok_or_else(|| ManifoldError::ImportError("OFF is empty".to_string()))?;
+        let with_normals = match header[0] {
+            "OFF" | "COFF" => false,
+            "NOFF" | "CNOFF" => true,
+            _ => return Err(ManifoldError::ImportError(format!("unsupported OFF header: {}", header[0]))),
+        };
+        // Counts may follow the keyword on the header line
+        header.remove(0);
+        let (count_line, counts) = if header.is_empty() { lines.next().unwrap_or((header_line, header)) } else { (header_line, header) };
+        let count = |k: usize| counts.get(k).and_then(|c| c.parse::<usize>().ok()).ok_or_else(|| bad("counts", count_line));
+        let (vertex_count, face_count) = (count(0)?, count(1)?);
+
+        let mut positions = Vec::with_capacity(vertex_count);
+        let mut normals = Vec::new();
+        for _ in 0..vertex_count {
+            let (line, words) = lines.next().ok_or_else(|| ManifoldError::ImportError("OFF ends before its vertices".to_string()))?;
+            let numbers: Vec<f64> = words.iter().take(if with_normals { 6 } else { 3 })
+                .map(|w| w.parse::<f64>()).collect::<Result<_, _>>()
+                .map_err(|_| bad("vertex", line))?;
+            match numbers[..] {
+                [x, y, z] if !with_normals => positions.push([x, y, z]),
+                [x, y, z, nx, ny, nz] => {
+                    positions.push([x, y, z]);
+                    normals.push([nx, ny, nz].map(|c| c as f32));
+                }
+                _ => return Err(bad("vertex", line)),
+            }
+        }
+
+        let mut faces = Vec::with_capacity(face_count);
+        for _ in 0..face_count {
+            let (line, words) = lines.next().ok_or_else(|| ManifoldError::ImportError("OFF ends before its faces".to_string()))?;
+            let corners = words[0].parse::<usize>().ok().filter(|&n| n >= 3).ok_or_else(|| bad("face", line))?;
+            // Indices past the corner count are a face color
+            let face: Vec<Corner> = words.get(1..=corners)
+                .and_then(|ids| ids.iter().map(|w| w.parse::<usize>().ok().filter(|&i| i < vertex_count)).collect::<Option<Vec<_>>>())
+                .ok_or_else(|| bad("face", line))?
+                .into_iter()
+                .map(|i| (i, with_normals.then_some(i)))
+                .collect();
+            faces.push(face);
+        }
+        Ok(mesh_faces(&positions, &normals, &faces))
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test writing and reading back gives the same mesh.
+    #[test]
+    fn test_round_trip() {
+        let mesh = crate::render("difference() { cube(10); sphere(6, $fn = 12); }").unwrap();
+        let back = Mesh::from_off(mesh.to_off().as_bytes()).unwrap();
+        let corners = |m: &Mesh| -> Vec<[f64; 6]> {
+            m.indices.iter().map(|&i| {
+                let (v, n) = (&m.vertices[i as usize * 3..], &m.normals[i as usize * 3..]);
+                [v[0], v[1], v[2], n[0].into(), n[1].into(), n[2].into()]
+            }).collect()
+        };
+        assert_eq!(corners(&back), corners(&mesh));
+    }
+
+    /// Test plain OFF with comments, counts on the header and a quad.
+    #[test]
+    fn test_plain() {
+        let off = "OFF 4 1 0\n# square\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n4 0 1 2 3 255 0 0\n";
+        let mesh = Mesh::from_off(off.as_bytes()).unwrap();
+        assert_eq!(mesh.triangle_count(), 2);
+        assert!(mesh.normals.chunks_exact(3).all(|n| n == [0.0, 0.0, 1.0]));
+    }
+
+    /// Test malformed files are rejected.
+    #[test]
+    fn test_import_errors() {
+        assert!(Mesh::from_off(b"").is_err());
+        assert!(Mesh::from_off(b"4OFF\n0 0 0\n").is_err());
+        assert!(Mesh::from_off(b"OFF\n3 1 0\n0 0 0\n1 0 0\n").is_err());
+        assert!(Mesh::from_off(b"OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n").is_err());
+        assert!(Mesh::from_off(b"NOFF\n1 0 0\n0 0 0\n").is_err());
+    }
+}
@@ -0,0 +1,60 @@
+//! # Render Output
+//!
+//! A rendered model together with its preview layers.
+//!
+//! ## Layers
+//!
+//! - `solid` - The model itself, what gets exported
+//! - `highlighted` - Geometry under `#`, also part of `solid`
+//! - `transparent` - Geometry under `%`, not part of `solid`
+//!
+//! Renders that go through the whole pipeline also fill `timings` with the
+//! time of each stage (see [`openscad_eval::timing`]) and `stats` with the
+//! counts of the geometry tree (see [`openscad_eval::geometry::stats`]).
+//!
+//! A viewer draws `solid` as usual, `highlighted` on top of it in a
+//! translucent accent color and `transparent` as a ghost. The layers are
+//! shown as modelled, without the booleans around them, so a `#` operand
+//! of `difference()` shows the tool rather than the hole it cuts.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let output = manifold_rs::render_preview("difference() { cube(10); #sphere(4); }").unwrap();
+//! assert!(output.solid.volume() < 1000.0);
+//! assert!(!output.highlighted.is_empty());
+//! assert!(output.transparent.is_empty());
+//! ```
+
+use openscad_eval::{GeometryStats, Timings};
+
+use super::Mesh;
+
+/// A rendered model and its preview layers.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOutput {
+    /// The model.
+    pub solid: Mesh,
+    /// Geometry under the `#` modifier.
+    pub highlighted: Mesh,
+    /// Geometry under the `%` modifier.
+    pub transparent: Mesh,
+    /// Time spent in each stage; zero for stages that did not run here.
+    pub timings: Timings,
+    /// Counts and estimated size of the geometry tree that was meshed.
+    pub stats: GeometryStats,
+}
+
+impl RenderOutput {
+    /// Whether there is anything to show besides the solid.
+    pub fn has_preview(&self) -> bool {
+        !self.highlighted.is_empty() || !self.transparent.is_empty()
+    }
+}
+
+impl From<Mesh> for RenderOutput {
+    /// A solid without preview layers.
+    fn from(solid: Mesh) -> Self {
+        Self { solid, ..Self::default() }
+    }
+}
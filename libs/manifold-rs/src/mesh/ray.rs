@@ -0,0 +1,166 @@
+//! # Ray Casting
+//!
+//! Where a ray meets the surface of a mesh, for picking a point on a
+//! model in a viewer: the ray from the camera through the clicked pixel
+//! hits the triangle under the cursor.
+//!
+//! Candidates come from a [`Bvh`] over the triangles and are tested
+//! exactly with the Möller–Trumbore intersection:
+//!
+//! ```text
+//! origin + t · direction = (1 − u − v) · a + u · b + v · c
+//! hit if t ≥ 0, u ≥ 0, v ≥ 0, u + v ≤ 1
+//! ```
+//!
+//! Triangles are hit from either side, so a ray from inside a solid finds
+//! its wall. A ray through an edge or vertex hits every triangle there,
+//! at the same distance; the lowest triangle index comes first.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mesh = manifold_rs::render("cube(10);").unwrap();
+//! let hit = mesh.ray_cast([5.0, 5.0, 20.0], [0.0, 0.0, -1.0]).unwrap();
+//! assert_eq!(hit.point, [5.0, 5.0, 10.0]);
+//! assert_eq!(hit.distance, 10.0);
+//! ```
+
+use super::bvh::Bvh;
+use super::Mesh;
+
+/// Where a ray meets a triangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Index of the triangle hit.
+    pub triangle: u32,
+    /// Point hit.
+    pub point: [f64; 3],
+    /// Distance from the ray's origin to the point.
+    pub distance: f64,
+}
+
+impl Mesh {
+    /// The first triangle the ray from `origin` along `direction` hits.
+    ///
+    /// `direction` need not be unit length; `None` if the ray misses the
+    /// mesh or `direction` is zero.
+    #[must_use]
+    pub fn ray_cast(&self, origin: [f64; 3], direction: [f64; 3]) -> Option<RayHit> {
+        self.ray_hits(origin, direction).into_iter().next()
+    }
+
+    /// Every triangle the ray from `origin` along `direction` hits,
+    /// nearest first.
+    #[must_use]
+    pub fn ray_hits(&self, origin: [f64; 3], direction: [f64; 3]) -> Vec<RayHit> {
+        let length = dot(direction, direction).sqrt();
+        if length == 0.0 || !length.is_finite() {
+            return Vec::new();
+        }
+        let direction = direction.map(|c| c / length);
+
+        let mut hits: Vec<RayHit> = Bvh::from_mesh(self)
+            .along_ray(origin, direction)
+            .into_iter()
+            .filter_map(|t| {
+                let corners = [0, 1, 2].map(|k| {
+                    let v = self.indices[t as usize * 3 + k] as usize;
+                    [self.vertices[v * 3], self.vertices[v * 3 + 1], self.vertices[v * 3 + 2]]
+                });
+                let distance = intersect(origin, direction, corners)?;
+                let point = std::array::from_fn(|i| origin[i] + distance * direction[i]);
+                Some(RayHit { triangle: t, point, distance })
+            })
+            .collect();
+        // Stable, so equal distances keep triangle order
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+}
+
+/// Distance along the unit `direction` at which the ray from `origin`
+/// meets the triangle, if it does.
+fn intersect(origin: [f64; 3], direction: [f64; 3], [a, b, c]: [[f64; 3]; 3]) -> Option<f64> {
+    let (ab, ac) = (sub(b, a), sub(c, a));
+    let p = cross(direction, ac);
+    let det = dot(ab, p);
+    // Parallel to the triangle's plane, or a degenerate triangle
+    if det == 0.0 {
+        return None;
+    }
+    let s = sub(origin, a);
+    let u = dot(s, p) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = cross(s, ab);
+    let v = dot(direction, q) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = dot(ac, q) / det;
+    (t >= 0.0).then_some(t)
+}
+
+/// `a − b`.
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Dot product.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Cross product.
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test a ray through a cube hits its near and far faces, nearest
+    /// first, whatever the length of its direction.
+    #[test]
+    fn test_hits_in_order() {
+        let mesh = crate::render("cube(2, center = true);").unwrap();
+        let hits = mesh.ray_hits([0.3, 0.2, -5.0], [0.0, 0.0, 10.0]);
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].point[2], hits[0].distance), (-1.0, 4.0));
+        assert_eq!((hits[1].point[2], hits[1].distance), (1.0, 6.0));
+        // The triangle hit is on the bottom face
+        let t = hits[0].triangle as usize;
+        let zs = [0, 1, 2].map(|k| mesh.vertices[mesh.indices[t * 3 + k] as usize * 3 + 2]);
+        assert_eq!(zs, [-1.0; 3]);
+    }
+
+    /// Test rays from inside, past the mesh and without a direction.
+    #[test]
+    fn test_inside_and_misses() {
+        let mesh = crate::render("sphere(5, $fn = 24);").unwrap();
+        let hit = mesh.ray_cast([0.0; 3], [1.0, 1.0, 0.0]).unwrap();
+        assert!(hit.distance > 4.5 && hit.distance <= 5.0, "{}", hit.distance);
+        assert!(mesh.ray_cast([0.0, 0.0, 10.0], [1.0, 0.0, 0.0]).is_none());
+        assert!(mesh.ray_cast([0.0, 0.0, 10.0], [0.0, 0.0, 1.0]).is_none());
+        assert!(mesh.ray_cast([0.0, 0.0, 10.0], [0.0; 3]).is_none());
+    }
+
+    /// Test a ray through an edge hits both triangles there.
+    #[test]
+    fn test_edge_hit() {
+        let mut mesh = Mesh::new();
+        for [x, y] in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]] {
+            mesh.add_vertex(x, y, 0.0, 0.0, 0.0, 1.0);
+        }
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(0, 2, 3);
+        let hits = mesh.ray_hits([0.5, 0.5, 1.0], [0.0, 0.0, -1.0]);
+        assert_eq!(hits.iter().map(|h| h.triangle).collect::<Vec<_>>(), [0, 1]);
+    }
+}
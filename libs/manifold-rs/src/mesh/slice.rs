@@ -0,0 +1,289 @@
+//! # Slicing
+//!
+//! Cross-sections of a mesh by a plane, as closed 2D contours: the
+//! outline of the solid where the plane cuts it, with the holes where it
+//! cuts through cavities or tunnels. Points along straight runs, where a
+//! flat face was split into several triangles, are left out.
+//!
+//! ## Algorithm
+//!
+//! ```text
+//! 1. Signed distance of every welded position to the plane
+//! 2. Each triangle with corners on both sides crosses the plane along
+//!    one segment, between the points where two of its edges cross
+//! 3. Segments chain into loops through the edges they share
+//! 4. Loops map into plane coordinates and nest into outlines and holes
+//! ```
+//!
+//! Positions exactly on the plane count as above it, so a face lying in
+//! the plane belongs to the solid below it: slicing a cube at its top
+//! face gives the square, at its bottom face nothing. Chains that do not
+//! close, from the boundary of an open mesh, are dropped.
+//!
+//! ## Plane Coordinates
+//!
+//! For a plane `z = c` the 2D coordinates are `x` and `y`. For other
+//! planes they are along two perpendicular unit axes `u`, `v` in the
+//! plane with `u × v` along the normal, so outlines come out
+//! counter-clockwise seen from the side the normal points to.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::mesh::slice::Plane;
+//!
+//! let mesh = manifold_rs::render("difference() { cube(10); translate([5, 5, 0]) cylinder(h = 10, r = 2); }").unwrap();
+//! let section = mesh.slice(&Plane::z(5.0));
+//! assert_eq!(section.len(), 1);
+//! assert_eq!(section[0].holes.len(), 1);
+//! ```
+
+use std::collections::HashMap;
+
+use super::Mesh;
+use crate::font::outline::group_contours;
+use crate::font::Polygon2D;
+
+// =============================================================================
+// PLANE
+// =============================================================================
+
+/// A cutting plane: the points `p` with `normal · p = offset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    /// Unit normal.
+    pub normal: [f64; 3],
+    /// Signed distance of the plane from the origin along the normal.
+    pub offset: f64,
+}
+
+impl Plane {
+    /// The plane perpendicular to `normal` at `offset` along it.
+    ///
+    /// `normal` need not be unit length; a zero normal gives the plane
+    /// `z = offset`.
+    #[must_use]
+    pub fn new(normal: [f64; 3], offset: f64) -> Self {
+        let len = dot(normal, normal).sqrt();
+        let normal = if len > 0.0 { normal.map(|c| c / len) } else { [0.0, 0.0, 1.0] };
+        Self { normal, offset }
+    }
+
+    /// The horizontal plane at height `z`.
+    #[must_use]
+    pub fn z(z: f64) -> Self {
+        Self { normal: [0.0, 0.0, 1.0], offset: z }
+    }
+
+    /// Signed distance of `p` from the plane, positive on the side the
+    /// normal points to.
+    #[must_use]
+    pub fn distance(&self, p: [f64; 3]) -> f64 {
+        dot(self.normal, p) - self.offset
+    }
+
+    /// In-plane axes `u` and `v`: `x` and `y` for an upward normal.
+    fn axes(&self) -> ([f64; 3], [f64; 3]) {
+        let n = self.normal;
+        let helper = if n[1].abs() < 0.9 { [0.0, 1.0, 0.0] } else { [0.0, 0.0, 1.0] };
+        let u = cross(helper, n);
+        let len = dot(u, u).sqrt();
+        let u = u.map(|c| c / len);
+        (u, cross(n, u))
+    }
+}
+
+// =============================================================================
+// SLICING
+// =============================================================================
+
+impl Mesh {
+    /// Cross-section of the mesh by `plane`.
+    ///
+    /// ## Returns
+    ///
+    /// Regions in plane coordinates, each a CCW outline with CW holes;
+    /// empty if the plane misses the mesh.
+    #[must_use]
+    pub fn slice(&self, plane: &Plane) -> Vec<Polygon2D> {
+        let (positions, weld) = self.welded_positions();
+        let above: Vec<bool> = positions.iter().map(|&p| plane.distance(p) >= 0.0).collect();
+
+        // Crossing point of an edge, computed once per undirected edge so
+        // both triangles on it agree exactly
+        let mut crossings: HashMap<(u32, u32), [f64; 3]> = HashMap::new();
+        let mut crossing = |a: u32, b: u32| {
+            let key = (a.min(b), a.max(b));
+            crossings.entry(key).or_insert_with(|| {
+                let (p, q) = (positions[key.0 as usize], positions[key.1 as usize]);
+                let (dp, dq) = (plane.distance(p), plane.distance(q));
+                let t = dp / (dp - dq);
+                std::array::from_fn(|i| p[i] + t * (q[i] - p[i]))
+            });
+            key
+        };
+
+        // One segment per crossing triangle, from the edge it crosses
+        // downward to the edge it crosses back up, keyed by its start
+        let mut next: HashMap<(u32, u32), (u32, u32)> = HashMap::new();
+        for t in self.indices.chunks_exact(3) {
+            let corners = [t[0], t[1], t[2]].map(|v| weld[v as usize]);
+            let (mut down, mut up) = (None, None);
+            for k in 0..3 {
+                let (a, b) = (corners[k], corners[(k + 1) % 3]);
+                match (above[a as usize], above[b as usize]) {
+                    (true, false) => down = Some(crossing(a, b)),
+                    (false, true) => up = Some(crossing(a, b)),
+                    _ => {}
+                }
+            }
+            if let (Some(down), Some(up)) = (down, up) {
+                next.insert(down, up);
+            }
+        }
+
+        // Follow the segments around, in a fixed order for determinism
+        let mut starts: Vec<(u32, u32)> = next.keys().copied().collect();
+        starts.sort_unstable();
+        let (u, v) = plane.axes();
+        let mut contours = Vec::new();
+        for start in starts {
+            let mut contour: Vec<[f64; 2]> = Vec::new();
+            let mut edge = start;
+            let closed = loop {
+                let Some(following) = next.remove(&edge) else {
+                    break false;
+                };
+                let p = crossings[&edge];
+                let point = [dot(p, u), dot(p, v)];
+                if contour.last() != Some(&point) {
+                    contour.push(point);
+                }
+                edge = following;
+                if edge == start {
+                    break true;
+                }
+            };
+            if contour.len() > 1 && contour.first() == contour.last() {
+                contour.pop();
+            }
+            if closed {
+                contours.push(without_collinear(contour));
+            }
+        }
+        group_contours(contours)
+    }
+}
+
+/// `contour` without the points in the middle of straight runs, which
+/// triangles split along a flat face leave behind.
+fn without_collinear(mut contour: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    let mut i = 0;
+    while contour.len() > 3 && i < contour.len() {
+        let n = contour.len();
+        let (a, p, b) = (contour[(i + n - 1) % n], contour[i], contour[(i + 1) % n]);
+        let (u, v) = ([p[0] - a[0], p[1] - a[1]], [b[0] - p[0], b[1] - p[1]]);
+        let turn = u[0] * v[1] - u[1] * v[0];
+        let along = u[0] * v[0] + u[1] * v[1];
+        if along > 0.0 && turn.abs() <= COLLINEAR_TOLERANCE * along {
+            contour.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    contour
+}
+
+/// Tangent of the largest turn at a point still taken as straight.
+const COLLINEAR_TOLERANCE: f64 = 1e-9;
+
+/// Dot product.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Cross product.
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Area of a loop, positive if counter-clockwise.
+    fn signed_area(points: &[[f64; 2]]) -> f64 {
+        let n = points.len();
+        (0..n).map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a[0] * b[1] - b[0] * a[1]
+        }).sum::<f64>() / 2.0
+    }
+
+    /// Area of a region, holes subtracted.
+    fn area(region: &Polygon2D) -> f64 {
+        signed_area(&region.outer) + region.holes.iter().map(|h| signed_area(h)).sum::<f64>()
+    }
+
+    /// Test a cube slices to its square, in x and y, counter-clockwise.
+    #[test]
+    fn test_cube() {
+        let mesh = crate::render("cube([4, 3, 2]);").unwrap();
+        let section = mesh.slice(&Plane::z(1.0));
+        assert_eq!(section.len(), 1);
+        assert!((signed_area(&section[0].outer) - 12.0).abs() < 1e-9);
+        assert!(section[0].outer.iter().all(|p| (p[0] == 0.0 || p[0] == 4.0) || (p[1] == 0.0 || p[1] == 3.0)));
+    }
+
+    /// Test faces in the plane belong to the solid below.
+    #[test]
+    fn test_faces_in_plane() {
+        let mesh = crate::render("cube(2);").unwrap();
+        assert!((area(&mesh.slice(&Plane::z(2.0))[0]) - 4.0).abs() < 1e-9);
+        assert!(mesh.slice(&Plane::z(0.0)).is_empty());
+        assert!(mesh.slice(&Plane::z(3.0)).is_empty());
+    }
+
+    /// Test a tunnel slices to an outline with a hole, and separate
+    /// parts to separate regions.
+    #[test]
+    fn test_holes_and_parts() {
+        let mesh = crate::render(
+            "difference() { cube(10); translate([5, 5, -1]) cylinder(h = 12, r = 2, $fn = 16); }
+             translate([20, 0, 0]) cube(1);",
+        ).unwrap();
+        let mut section = mesh.slice(&Plane::z(0.5));
+        section.sort_by(|a, b| a.outer[0][0].total_cmp(&b.outer[0][0]));
+        assert_eq!(section.len(), 2);
+        assert_eq!(section[0].holes.len(), 1);
+        assert_eq!(section[0].holes[0].len(), 16);
+        let circle = 8.0 * (std::f64::consts::TAU / 16.0).sin() * 4.0;
+        assert!((area(&section[0]) - (100.0 - circle)).abs() < 1e-9);
+        assert!((area(&section[1]) - 1.0).abs() < 1e-9);
+    }
+
+    /// Test a tilted plane through a cube's diagonal.
+    #[test]
+    fn test_tilted_plane() {
+        let mesh = crate::render("cube(2, center = true);").unwrap();
+        let section = mesh.slice(&Plane::new([1.0, 0.0, 1.0], 0.0));
+        assert_eq!(section.len(), 1);
+        // A 2 by 2√2 rectangle
+        assert!((area(&section[0]) - 4.0 * 2.0f64.sqrt()).abs() < 1e-9);
+    }
+
+    /// Test an open mesh gives no contours.
+    #[test]
+    fn test_open_mesh() {
+        let mut mesh = Mesh::new();
+        mesh.add_vertex(0.0, 0.0, -1.0, 1.0, 0.0, 0.0);
+        mesh.add_vertex(0.0, 1.0, -1.0, 1.0, 0.0, 0.0);
+        mesh.add_vertex(0.0, 0.0, 1.0, 1.0, 0.0, 0.0);
+        mesh.add_triangle(0, 1, 2);
+        assert!(mesh.slice(&Plane::z(0.0)).is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+//! # STL Import and Export
+//!
+//! Writes meshes as binary or ASCII STL, in the same layout OpenSCAD uses,
+//! and reads either kind back for `import()`.
+//!
+//! Facet normals are recomputed from each triangle's winding in both
+//! directions, rather than trusting the (possibly smoothed) vertex normals
+//! or the normals stored in the file.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mesh = manifold_rs::render("cube(10);").unwrap();
+//! let bytes = mesh.to_stl_binary();
+//! assert_eq!(bytes.len(), 84 + 50 * mesh.triangle_count());
+//! assert!(mesh.to_stl_ascii().starts_with("solid OpenSCAD_Model"));
+//! ```
+
+use std::fmt::Write;
+
+use super::Mesh;
+use crate::error::{ManifoldError, ManifoldResult};
+
+/// Solid name written to ASCII output and the binary header.
+const SOLID_NAME: &str = "OpenSCAD_Model";
+
+/// Size of the binary STL header in bytes.
+const BINARY_HEADER_LEN: usize = 80;
+
+/// Size of one binary STL facet record in bytes.
+const BINARY_FACET_LEN: usize = 50;
+
+impl Mesh {
+    /// Encode the mesh as binary STL.
+    ///
+    /// ## Returns
+    ///
+    /// 80-byte header, little-endian triangle count, then one 50-byte
+    /// record (normal, three vertices, attribute) per triangle.
+    #[must_use]
+    pub fn to_stl_binary(&self) -> Vec<u8> {
+        let count = self.triangle_count();
+        let mut out = Vec::with_capacity(BINARY_HEADER_LEN + 4 + BINARY_FACET_LEN * count);
+
+        let mut header = [0u8; BINARY_HEADER_LEN];
+        header[..SOLID_NAME.len()].copy_from_slice(SOLID_NAME.as_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&(count as u32).to_le_bytes());
+
+        for [a, b, c] in self.facets() {
+            let corners = [a, b, c].map(|p| p.map(|x| x as f32));
+            for value in facet_normal(a, b, c).iter().chain(corners.iter().flatten()) {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            out.extend_from_slice(&0u16.to_le_bytes());
+        }
+        out
+    }
+
+    /// Encode the mesh as ASCII STL.
+    #[must_use]
+    pub fn to_stl_ascii(&self) -> String {
+        let mut out = String::new();
+        // Writing to a String cannot fail
+        let _ = writeln!(out, "solid {}", SOLID_NAME);
+        for [a, b, c] in self.facets() {
+            let n = facet_normal(a, b, c);
+            let _ = writeln!(out, "  facet normal {} {} {}", n[0], n[1], n[2]);
+            let _ = writeln!(out, "    outer loop");
+            for v in [a, b, c] {
+                let _ = writeln!(out, "      vertex {} {} {}", v[0] as f32, v[1] as f32, v[2] as f32);
+            }
+            let _ = writeln!(out, "    endloop");
+            let _ = writeln!(out, "  endfacet");
+        }
+        let _ = writeln!(out, "endsolid {}", SOLID_NAME);
+        out
+    }
+
+    /// Triangle corner positions, skipping triangles with bad indices.
+    fn facets(&self) -> impl Iterator<Item = [[f64; 3]; 3]> + '_ {
+        let position = |i: u32| -> Option<[f64; 3]> {
+            let i = i as usize * 3;
+            self.vertices.get(i..i + 3).map(|p| [p[0], p[1], p[2]])
+        };
+        self.indices.chunks_exact(3).filter_map(move |t| {
+            Some([position(t[0])?, position(t[1])?, position(t[2])?])
+        })
+    }
+}
+
+// =============================================================================
+// IMPORT
+// =============================================================================
+
+impl Mesh {
+    /// Decode a binary or ASCII STL file.
+    ///
+    /// A file whose size matches its binary triangle count is read as
+    /// binary, even if the header starts with `solid`.
+    ///
+    /// ## Returns
+    ///
+    /// Flat-shaded mesh with three vertices per facet.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `ManifoldError::ImportError` if the data is neither format.
+    pub fn from_stl(data: &[u8]) -> ManifoldResult<Mesh> {
+        let header_len = BINARY_HEADER_LEN + 4;
+        if let Some(count) = data.get(BINARY_HEADER_LEN..header_len) {
+            let count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize;
+            if data.len() == header_len + BINARY_FACET_LEN * count {
+                return Ok(from_stl_binary(&data[header_len..]));
+            }
+        }
+
+        let text = std::str::from_utf8(data)
+            .map_err(|_| ManifoldError::ImportError("STL is neither binary nor ASCII".to_string()))?;
+        if !text.trim_start().starts_with("solid") {
+            return Err(ManifoldError::ImportError("STL is neither binary nor ASCII".to_string()));
+        }
+        from_stl_ascii(text)
+    }
+
+    /// Append a facet with its own vertices and face normal.
+    pub(super) fn push_facet(&mut self, [a, b, c]: [[f64; 3]; 3]) {
+        let n = facet_normal(a, b, c);
+        let ids = [a, b, c].map(|p| self.add_vertex(p[0], p[1], p[2], n[0], n[1], n[2]));
+        self.add_triangle(ids[0], ids[1], ids[2]);
+    }
+}
+
+/// Read binary facet records (the data after the header and count).
+fn from_stl_binary(records: &[u8]) -> Mesh {
+    let mut mesh = Mesh::with_capacity(records.len() / BINARY_FACET_LEN * 3, records.len() / BINARY_FACET_LEN);
+    for record in records.chunks_exact(BINARY_FACET_LEN) {
+        let float = |i: usize| f32::from_le_bytes([record[i], record[i + 1], record[i + 2], record[i + 3]]);
+        // Skip the stored normal at bytes 0..12
+        let corner = |k: usize| [float(12 + 12 * k), float(16 + 12 * k), float(20 + 12 * k)].map(f64::from);
+        mesh.push_facet([corner(0), corner(1), corner(2)]);
+    }
+    mesh
+}
+
+/// Read ASCII STL, taking every three `vertex` lines as a facet.
+fn from_stl_ascii(text: &str) -> ManifoldResult<Mesh> {
+    let mut mesh = Mesh::new();
+    let mut corners = Vec::with_capacity(3);
+    for (line_number, line) in text.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("vertex") {
+            continue;
+        }
+        let coords: Vec<f64> = words.map(str::parse).collect::<Result<_, _>>()
+            .map_err(|_| ManifoldError::ImportError(format!("bad vertex on line {}", line_number + 1)))?;
+        let [x, y, z] = coords[..] else {
+            return Err(ManifoldError::ImportError(format!("bad vertex on line {}", line_number + 1)));
+        };
+        corners.push([x, y, z]);
+        if corners.len() == 3 {
+            mesh.push_facet([corners[0], corners[1], corners[2]]);
+            corners.clear();
+        }
+    }
+    if !corners.is_empty() {
+        return Err(ManifoldError::ImportError("STL ends inside a facet".to_string()));
+    }
+    Ok(mesh)
+}
+
+/// Unit normal of a CCW triangle; zero for degenerate triangles.
+fn facet_normal(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> [f32; 3] {
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if len > 0.0 {
+        [n[0] / len, n[1] / len, n[2] / len].map(|c| c as f32)
+    } else {
+        [0.0; 3]
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> Mesh {
+        let mut mesh = Mesh::new();
+        let v0 = mesh.add_vertex(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let v1 = mesh.add_vertex(1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let v2 = mesh.add_vertex(0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        mesh.add_triangle(v0, v1, v2);
+        mesh
+    }
+
+    /// Test the binary layout: header, count, normal and vertices.
+    #[test]
+    fn test_binary_layout() {
+        let bytes = triangle().to_stl_binary();
+        assert_eq!(bytes.len(), 84 + 50);
+        assert!(bytes.starts_with(SOLID_NAME.as_bytes()));
+        assert_eq!(u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]), 1);
+
+        let float = |i: usize| f32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        assert_eq!([float(84), float(88), float(92)], [0.0, 0.0, 1.0]);
+        assert_eq!([float(108), float(112), float(116)], [1.0, 0.0, 0.0]);
+    }
+
+    /// Test ASCII output matches OpenSCAD's formatting.
+    #[test]
+    fn test_ascii() {
+        let ascii = triangle().to_stl_ascii();
+        let expected = "solid OpenSCAD_Model\n  facet normal 0 0 1\n    outer loop\n      vertex 0 0 0\n      vertex 1 0 0\n      vertex 0 1 0\n    endloop\n  endfacet\nendsolid OpenSCAD_Model\n";
+        assert_eq!(ascii, expected);
+    }
+
+    /// Test both formats read back to the written triangles.
+    #[test]
+    fn test_round_trip() {
+        let mesh = crate::render("cube(10);").unwrap();
+        let expected: Vec<_> = mesh.facets().collect();
+
+        let binary = Mesh::from_stl(&mesh.to_stl_binary()).unwrap();
+        assert_eq!(binary.facets().collect::<Vec<_>>(), expected);
+        let ascii = Mesh::from_stl(mesh.to_stl_ascii().as_bytes()).unwrap();
+        assert_eq!(ascii.facets().collect::<Vec<_>>(), expected);
+        assert_eq!(ascii.normals, binary.normals);
+    }
+
+    /// Test a binary file whose header starts with `solid` is still binary.
+    #[test]
+    fn test_binary_with_solid_header() {
+        let mut bytes = triangle().to_stl_binary();
+        bytes[..6].copy_from_slice(b"solid ");
+        assert_eq!(Mesh::from_stl(&bytes).unwrap().triangle_count(), 1);
+    }
+
+    /// Test malformed files are rejected.
+    #[test]
+    fn test_import_errors() {
+        assert!(Mesh::from_stl(b"not an stl").is_err());
+        assert!(Mesh::from_stl(b"solid x\n vertex 1 2\nendsolid x").is_err());
+        assert!(Mesh::from_stl(b"solid x\n vertex 1 2 3\nendsolid x").is_err());
+    }
+
+    /// Test an empty mesh still produces valid files.
+    #[test]
+    fn test_empty() {
+        let mesh = Mesh::new();
+        assert_eq!(mesh.to_stl_binary().len(), 84);
+        assert_eq!(mesh.to_stl_ascii().lines().count(), 2);
+    }
+}
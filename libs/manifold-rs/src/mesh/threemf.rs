@@ -0,0 +1,275 @@
+//! # 3MF Export
+//!
+//! Writes meshes as 3MF packages that slicers can open, including
+//! per-vertex colors as a materials-extension color group.
+//!
+//! ## Package Layout
+//!
+//! ```text
+//! [Content_Types].xml
+//! _rels/.rels
+//! 3D/3dmodel.model      ← vertices, triangles, colors (millimeters)
+//! ```
+//!
+//! The package is a ZIP archive with stored (uncompressed) entries, which
+//! every 3MF reader accepts and which needs no compression dependency.
+//! Vertices are welded by exact position, since 3MF meshes share vertices
+//! between triangles; triangles that collapse in the process are dropped.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mesh = manifold_rs::render("color([1, 0, 0]) cube(10);").unwrap();
+//! let package = mesh.to_3mf();
+//! assert!(package.starts_with(b"PK"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::Mesh;
+
+/// 3MF core namespace.
+const CORE_NS: &str = "http://schemas.microsoft.com/3dmanufacturing/core/2015/02";
+
+/// 3MF materials extension namespace.
+const MATERIAL_NS: &str = "http://schemas.microsoft.com/3dmanufacturing/material/2015/02";
+
+/// Content types part.
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+/// Package relationships part.
+const RELS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+// =============================================================================
+// EXPORT
+// =============================================================================
+
+impl Mesh {
+    /// Encode the mesh as a 3MF package.
+    #[must_use]
+    pub fn to_3mf(&self) -> Vec<u8> {
+        let mut zip = ZipWriter::default();
+        zip.add("[Content_Types].xml", CONTENT_TYPES.as_bytes());
+        zip.add("_rels/.rels", RELS.as_bytes());
+        zip.add("3D/3dmodel.model", self.to_3mf_model().as_bytes());
+        zip.finish()
+    }
+
+    /// The `3D/3dmodel.model` XML part.
+    #[must_use]
+    pub fn to_3mf_model(&self) -> String {
+        let (positions, remap) = self.welded_positions();
+
+        // Distinct colors, numbered in first-seen order
+        let mut color_ids: BTreeMap<String, usize> = BTreeMap::new();
+        let mut colors: Vec<String> = Vec::new();
+        let vertex_colors: Option<Vec<usize>> = self.colors.as_ref().map(|c| {
+            c.chunks_exact(4)
+                .map(|rgba| {
+                    let hex = color_hex(rgba);
+                    *color_ids.entry(hex.clone()).or_insert_with(|| {
+                        colors.push(hex);
+                        colors.len() - 1
+                    })
+                })
+                .collect()
+        });
+
+        let mut xml = String::new();
+        // Writing to a String cannot fail
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(xml, r#"<model unit="millimeter" xml:lang="en-US" xmlns="{}" xmlns:m="{}">"#, CORE_NS, MATERIAL_NS);
+        let _ = writeln!(xml, "  <resources>");
+        if !colors.is_empty() {
+            let _ = writeln!(xml, r#"    <m:colorgroup id="1">"#);
+            for color in &colors {
+                let _ = writeln!(xml, r#"      <m:color color="{}"/>"#, color);
+            }
+            let _ = writeln!(xml, "    </m:colorgroup>");
+        }
+        let _ = writeln!(xml, r#"    <object id="2" type="model">"#);
+        let _ = writeln!(xml, "      <mesh>");
+        let _ = writeln!(xml, "        <vertices>");
+        for p in &positions {
+            let _ = writeln!(xml, r#"          <vertex x="{}" y="{}" z="{}"/>"#, p[0], p[1], p[2]);
+        }
+        let _ = writeln!(xml, "        </vertices>");
+        let _ = writeln!(xml, "        <triangles>");
+        for t in self.indices.chunks_exact(3) {
+            let corners = [t[0] as usize, t[1] as usize, t[2] as usize];
+            let Some(v) = corners.iter().map(|&i| remap.get(i).copied()).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            if v[0] == v[1] || v[1] == v[2] || v[0] == v[2] {
+                continue;
+            }
+            let _ = write!(xml, r#"          <triangle v1="{}" v2="{}" v3="{}""#, v[0], v[1], v[2]);
+            if let Some(vc) = &vertex_colors {
+                let p: Vec<usize> = corners.iter().map(|&i| vc.get(i).copied().unwrap_or(0)).collect();
+                let _ = write!(xml, r#" pid="1" p1="{}" p2="{}" p3="{}""#, p[0], p[1], p[2]);
+            }
+            let _ = writeln!(xml, "/>");
+        }
+        let _ = writeln!(xml, "        </triangles>");
+        let _ = writeln!(xml, "      </mesh>");
+        let _ = writeln!(xml, "    </object>");
+        let _ = writeln!(xml, "  </resources>");
+        let _ = writeln!(xml, "  <build>");
+        let _ = writeln!(xml, r#"    <item objectid="2"/>"#);
+        let _ = writeln!(xml, "  </build>");
+        let _ = writeln!(xml, "</model>");
+        xml
+    }
+}
+
+/// `#RRGGBBAA` for an RGBA color in [0, 1].
+pub(super) fn color_hex(rgba: &[f32]) -> String {
+    let byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02X}{:02X}{:02X}{:02X}", byte(rgba[0]), byte(rgba[1]), byte(rgba[2]), byte(rgba[3]))
+}
+
+// =============================================================================
+// ZIP WRITER
+// =============================================================================
+
+/// Minimal ZIP writer with stored entries.
+#[derive(Debug, Default)]
+struct ZipWriter {
+    /// Local headers and file data.
+    data: Vec<u8>,
+    /// Central directory records.
+    directory: Vec<u8>,
+    /// Number of entries.
+    entries: u16,
+}
+
+impl ZipWriter {
+    /// Append a stored file.
+    fn add(&mut self, name: &str, contents: &[u8]) {
+        let offset = self.data.len() as u32;
+        let crc = crc32(contents);
+        let size = contents.len() as u32;
+
+        // Local file header
+        self.data.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header_fields(&mut self.data, crc, size, name);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(contents);
+
+        // Central directory header
+        let dir = &mut self.directory;
+        dir.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        dir.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        header_fields(dir, crc, size, name);
+        dir.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        dir.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        dir.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        dir.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        dir.extend_from_slice(&offset.to_le_bytes());
+        dir.extend_from_slice(name.as_bytes());
+
+        self.entries += 1;
+    }
+
+    /// Append the central directory and return the archive.
+    fn finish(mut self) -> Vec<u8> {
+        let directory_offset = self.data.len() as u32;
+        let directory_size = self.directory.len() as u32;
+        self.data.extend_from_slice(&self.directory);
+
+        // End of central directory record
+        self.data.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // disk
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // directory disk
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&directory_size.to_le_bytes());
+        self.data.extend_from_slice(&directory_offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.data
+    }
+}
+
+/// Fields shared by local and central headers, up to the extra length.
+fn header_fields(out: &mut Vec<u8>, crc: u32, size: u32, name: &str) {
+    out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+    out.extend_from_slice(&0u16.to_le_bytes()); // flags
+    out.extend_from_slice(&0u16.to_le_bytes()); // method: stored
+    out.extend_from_slice(&0u16.to_le_bytes()); // mod time
+    out.extend_from_slice(&0x21u16.to_le_bytes()); // mod date: 1980-01-01
+    out.extend_from_slice(&crc.to_le_bytes());
+    out.extend_from_slice(&size.to_le_bytes()); // compressed
+    out.extend_from_slice(&size.to_le_bytes()); // uncompressed
+    out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+}
+
+/// CRC-32 (IEEE) as used by ZIP.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the CRC matches the standard check value.
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    /// Test vertices are welded so a cube has 8 shared vertices.
+    #[test]
+    fn test_model_welds_vertices() {
+        let mesh = crate::render("cube(10);").unwrap();
+        let model = mesh.to_3mf_model();
+        assert_eq!(model.matches("<vertex ").count(), 8);
+        assert_eq!(model.matches("<triangle ").count(), 12);
+        assert!(!model.contains("colorgroup"));
+    }
+
+    /// Test per-vertex colors become a color group referenced by triangles.
+    #[test]
+    fn test_model_colors() {
+        let mesh = crate::render("color([1, 0, 0]) cube(1);").unwrap();
+        let model = mesh.to_3mf_model();
+        assert!(model.contains(r##"<m:color color="#FF0000FF"/>"##));
+        assert_eq!(model.matches(r#"pid="1" p1="0" p2="0" p3="0""#).count(), 12);
+    }
+
+    /// Test the package contains the three parts and a valid end record.
+    #[test]
+    fn test_package() {
+        let package = crate::render("cube(1);").unwrap().to_3mf();
+        let text = String::from_utf8_lossy(&package);
+        assert!(text.contains("[Content_Types].xml"));
+        assert!(text.contains("_rels/.rels"));
+        assert!(text.contains("3D/3dmodel.model"));
+
+        let end = &package[package.len() - 22..];
+        assert_eq!(&end[..4], &0x0605_4b50u32.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 3);
+    }
+}
@@ -0,0 +1,356 @@
+//! # Polygon Triangulation
+//!
+//! Ear-clipping triangulation for simple polygons, including non-convex
+//! ones that a fan would triangulate incorrectly. Polygons with holes are
+//! first merged into a single ring by bridging each hole to the outline.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::mesh::triangulate::triangulate_polygon;
+//!
+//! // L-shaped (non-convex) hexagon
+//! let l_shape = [[0.0, 0.0], [2.0, 0.0], [2.0, 1.0], [1.0, 1.0], [1.0, 2.0], [0.0, 2.0]];
+//! let tris = triangulate_polygon(&l_shape);
+//! assert_eq!(tris.len(), 4);
+//! ```
+
+// =============================================================================
+// 2D TRIANGULATION
+// =============================================================================
+
+/// Triangulate a simple 2D polygon by ear clipping.
+///
+/// Works for either winding; the returned triangles keep the winding of
+/// the input. Falls back to a fan for the remainder if no ear can be found
+/// (self-intersecting input).
+///
+/// ## Parameters
+///
+/// - `points`: Polygon vertices in order
+///
+/// ## Returns
+///
+/// Triangles as indices into `points`.
+pub fn triangulate_polygon(points: &[[f64; 2]]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    // Orientation sign so convexity tests work for CW and CCW input
+    let orientation = if signed_area(points) >= 0.0 { 1.0 } else { -1.0 };
+
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut triangles = Vec::with_capacity(n - 2);
+
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m).find(|&k| {
+            let prev = remaining[(k + m - 1) % m];
+            let curr = remaining[k];
+            let next = remaining[(k + 1) % m];
+            is_ear(points, &remaining, prev, curr, next, orientation)
+        });
+
+        match ear {
+            Some(k) => {
+                let prev = remaining[(k + m - 1) % m];
+                let next = remaining[(k + 1) % m];
+                triangles.push([prev, remaining[k], next]);
+                remaining.remove(k);
+            }
+            None => break,
+        }
+    }
+
+    // Remaining polygon: a single triangle, or a fan fallback
+    for k in 1..remaining.len().saturating_sub(1) {
+        triangles.push([remaining[0], remaining[k], remaining[k + 1]]);
+    }
+    triangles
+}
+
+/// Check whether `curr` is a clippable ear.
+fn is_ear(
+    points: &[[f64; 2]],
+    remaining: &[usize],
+    prev: usize,
+    curr: usize,
+    next: usize,
+    orientation: f64,
+) -> bool {
+    let (a, b, c) = (points[prev], points[curr], points[next]);
+    if cross(a, b, c) * orientation <= 0.0 {
+        // Reflex or collinear corner
+        return false;
+    }
+    // Coincident points (hole bridges duplicate vertices) never block an ear
+    remaining.iter()
+        .map(|&i| points[i])
+        .filter(|&p| p != a && p != b && p != c)
+        .all(|p| !point_in_triangle(p, a, b, c, orientation))
+}
+
+/// Twice the signed area (positive for CCW).
+fn signed_area(points: &[[f64; 2]]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let p = points[i];
+            let q = points[(i + 1) % n];
+            p[0] * q[1] - q[0] * p[1]
+        })
+        .sum()
+}
+
+/// Z component of (b - a) × (c - a).
+fn cross(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Point-in-triangle test, inclusive of edges.
+fn point_in_triangle(p: [f64; 2], a: [f64; 2], b: [f64; 2], c: [f64; 2], orientation: f64) -> bool {
+    cross(a, b, p) * orientation >= 0.0
+        && cross(b, c, p) * orientation >= 0.0
+        && cross(c, a, p) * orientation >= 0.0
+}
+
+// =============================================================================
+// POLYGONS WITH HOLES
+// =============================================================================
+
+/// Triangulate a polygon with holes.
+///
+/// Each hole is spliced into the outline through a bridge edge to the
+/// nearest outline vertex that can see it, giving one weakly simple ring
+/// that is then ear-clipped. Holes are bridged right to left so earlier
+/// bridges never cross later holes.
+///
+/// ## Parameters
+///
+/// - `outer`: Outline vertices, either winding
+/// - `holes`: Hole outlines, either winding, inside `outer`
+///
+/// ## Returns
+///
+/// The merged points (bridge endpoints are duplicated) and CCW triangles
+/// indexing into them.
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::mesh::triangulate::triangulate_with_holes;
+///
+/// let outer = [[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]];
+/// let hole = vec![[1.0, 1.0], [3.0, 1.0], [3.0, 3.0], [1.0, 3.0]];
+/// let (_points, tris) = triangulate_with_holes(&outer, &[hole]);
+/// assert_eq!(tris.len(), 8);
+/// ```
+pub fn triangulate_with_holes(
+    outer: &[[f64; 2]],
+    holes: &[Vec<[f64; 2]>],
+) -> (Vec<[f64; 2]>, Vec<[usize; 3]>) {
+    let mut ring = outer.to_vec();
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+
+    // Holes wind clockwise; bridge the rightmost hole first
+    let mut holes: Vec<Vec<[f64; 2]>> = holes.iter()
+        .filter(|h| h.len() >= 3)
+        .map(|h| {
+            let mut h = h.clone();
+            if signed_area(&h) > 0.0 {
+                h.reverse();
+            }
+            h
+        })
+        .collect();
+    holes.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+
+    for (k, hole) in holes.iter().enumerate() {
+        let (m, _) = hole.iter()
+            .enumerate()
+            .fold((0, f64::NEG_INFINITY), |best, (i, p)| if p[0] > best.1 { (i, p[0]) } else { best });
+        let Some(v) = find_bridge(&ring, &holes[k + 1..], hole[m]) else {
+            continue;
+        };
+
+        // ring[..=v], hole from m around to m, back to ring[v]
+        let mut merged = Vec::with_capacity(ring.len() + hole.len() + 2);
+        merged.extend_from_slice(&ring[..=v]);
+        merged.extend((0..=hole.len()).map(|i| hole[(m + i) % hole.len()]));
+        merged.extend_from_slice(&ring[v..]);
+        ring = merged;
+    }
+
+    let tris = triangulate_polygon(&ring);
+    (ring, tris)
+}
+
+/// Find the closest ring vertex whose segment to `target` crosses no edge.
+fn find_bridge(ring: &[[f64; 2]], other_holes: &[Vec<[f64; 2]>], target: [f64; 2]) -> Option<usize> {
+    let mut candidates: Vec<usize> = (0..ring.len()).collect();
+    candidates.sort_by(|&a, &b| dist2(ring[a], target).total_cmp(&dist2(ring[b], target)));
+
+    candidates.into_iter().find(|&v| {
+        let p = ring[v];
+        let blocked_by_ring = (0..ring.len())
+            .any(|i| segments_cross(p, target, ring[i], ring[(i + 1) % ring.len()]));
+        let blocked_by_hole = other_holes.iter().any(|h| {
+            (0..h.len()).any(|i| segments_cross(p, target, h[i], h[(i + 1) % h.len()]))
+        });
+        !blocked_by_ring && !blocked_by_hole
+    })
+}
+
+/// Whether two segments properly cross (shared endpoints don't count).
+fn segments_cross(a: [f64; 2], b: [f64; 2], c: [f64; 2], d: [f64; 2]) -> bool {
+    if a == c || a == d || b == c || b == d {
+        return false;
+    }
+    let d1 = cross(a, b, c);
+    let d2 = cross(a, b, d);
+    let d3 = cross(c, d, a);
+    let d4 = cross(c, d, b);
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+/// Largest x coordinate of a polygon.
+fn max_x(points: &[[f64; 2]]) -> f64 {
+    points.iter().map(|p| p[0]).fold(f64::NEG_INFINITY, f64::max)
+}
+
+/// Squared distance between two points.
+fn dist2(a: [f64; 2], b: [f64; 2]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)
+}
+
+// =============================================================================
+// 3D FACE TRIANGULATION
+// =============================================================================
+
+/// Triangulate a planar 3D face.
+///
+/// The face is projected onto the axis plane most perpendicular to its
+/// Newell normal and ear-clipped there.
+///
+/// ## Parameters
+///
+/// - `points`: Point positions
+/// - `face`: Face as indices into `points`, all in range
+///
+/// ## Returns
+///
+/// Triangles as indices into `points`, with the face's winding.
+pub fn triangulate_face(points: &[[f64; 3]], face: &[usize]) -> Vec<[usize; 3]> {
+    if face.len() == 3 {
+        return vec![[face[0], face[1], face[2]]];
+    }
+
+    let normal = newell_normal(points, face);
+    let abs = [normal[0].abs(), normal[1].abs(), normal[2].abs()];
+    // Drop the dominant axis; keep the projection's orientation consistent
+    let (u, v) = if abs[2] >= abs[0] && abs[2] >= abs[1] {
+        (0, 1)
+    } else if abs[0] >= abs[1] {
+        (1, 2)
+    } else {
+        (2, 0)
+    };
+
+    let projected: Vec<[f64; 2]> = face.iter()
+        .map(|&i| [points[i][u], points[i][v]])
+        .collect();
+
+    triangulate_polygon(&projected)
+        .into_iter()
+        .map(|[a, b, c]| [face[a], face[b], face[c]])
+        .collect()
+}
+
+/// Face normal by Newell's method (not normalized).
+pub fn newell_normal(points: &[[f64; 3]], face: &[usize]) -> [f64; 3] {
+    let mut n = [0.0; 3];
+    for (k, &i) in face.iter().enumerate() {
+        let p = points[i];
+        let q = points[face[(k + 1) % face.len()]];
+        n[0] += (p[1] - q[1]) * (p[2] + q[2]);
+        n[1] += (p[2] - q[2]) * (p[0] + q[0]);
+        n[2] += (p[0] - q[0]) * (p[1] + q[1]);
+    }
+    n
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Total area of triangles over 2D points.
+    fn area(points: &[[f64; 2]], tris: &[[usize; 3]]) -> f64 {
+        tris.iter()
+            .map(|t| cross(points[t[0]], points[t[1]], points[t[2]]) / 2.0)
+            .sum()
+    }
+
+    /// Test a convex quad gives two triangles.
+    #[test]
+    fn test_square() {
+        let square = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        let tris = triangulate_polygon(&square);
+        assert_eq!(tris.len(), 2);
+        assert!((area(&square, &tris) - 1.0).abs() < 1e-12);
+    }
+
+    /// Test a non-convex polygon is covered exactly (a fan would overlap).
+    #[test]
+    fn test_non_convex() {
+        // Arrow shape whose first vertex is reflex
+        let arrow = [[1.0, 1.0], [0.0, 2.0], [0.0, 0.0], [2.0, 0.0], [2.0, 2.0]];
+        let tris = triangulate_polygon(&arrow);
+        assert_eq!(tris.len(), 3);
+        assert!((area(&arrow, &tris) - signed_area(&arrow) / 2.0).abs() < 1e-12);
+        assert!(tris.iter().all(|t| cross(arrow[t[0]], arrow[t[1]], arrow[t[2]]) > 0.0));
+    }
+
+    /// Test clockwise input keeps clockwise triangles.
+    #[test]
+    fn test_clockwise() {
+        let square = [[0.0, 0.0], [0.0, 1.0], [1.0, 1.0], [1.0, 0.0]];
+        let tris = triangulate_polygon(&square);
+        assert!(tris.iter().all(|t| cross(square[t[0]], square[t[1]], square[t[2]]) < 0.0));
+    }
+
+    /// Test a square with a square hole is covered exactly.
+    #[test]
+    fn test_with_hole() {
+        let outer = [[0.0, 0.0], [0.0, 4.0], [4.0, 4.0], [4.0, 0.0]];
+        let hole = vec![[1.0, 1.0], [3.0, 1.0], [3.0, 3.0], [1.0, 3.0]];
+        let (points, tris) = triangulate_with_holes(&outer, &[hole]);
+        assert!((area(&points, &tris) - 12.0).abs() < 1e-9);
+        assert!(tris.iter().all(|t| cross(points[t[0]], points[t[1]], points[t[2]]) >= 0.0));
+    }
+
+    /// Test two holes side by side are both cut out.
+    #[test]
+    fn test_two_holes() {
+        let outer = [[0.0, 0.0], [6.0, 0.0], [6.0, 3.0], [0.0, 3.0]];
+        let left = vec![[1.0, 1.0], [2.0, 1.0], [2.0, 2.0], [1.0, 2.0]];
+        let right = vec![[4.0, 1.0], [5.0, 1.0], [5.0, 2.0], [4.0, 2.0]];
+        let (points, tris) = triangulate_with_holes(&outer, &[left, right]);
+        assert!((area(&points, &tris) - 16.0).abs() < 1e-9);
+    }
+
+    /// Test a vertical 3D face is projected and triangulated.
+    #[test]
+    fn test_face_3d() {
+        let points = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 0.0, 1.0], [0.0, 0.0, 1.0]];
+        let tris = triangulate_face(&points, &[0, 1, 2, 3]);
+        assert_eq!(tris.len(), 2);
+    }
+}
@@ -0,0 +1,358 @@
+//! # Validation
+//!
+//! Checks a mesh for the defects that make it unsafe to export or to feed
+//! into further booleans, and says where they are.
+//!
+//! ## Checks
+//!
+//! | Issue | Found where |
+//! |-------|-------------|
+//! | `OpenEdge` | An edge used by a single triangle: a hole in the surface |
+//! | `NonManifoldEdge` | An edge used by more than two triangles |
+//! | `InvertedNormal` | Two triangles running along their shared edge the same way; a triangle wound against its vertex normals; a closed mesh wound inside out |
+//! | `Degenerate` | A triangle with no area |
+//! | `SelfIntersection` | An edge of one triangle passing through the interior of another |
+//!
+//! Vertices are welded by exact position first, as in
+//! [`HalfEdgeMesh::from_mesh`](super::halfedge::HalfEdgeMesh::from_mesh),
+//! and degenerate triangles are left out of the other checks. Degenerate
+//! triangles and self-intersections are decided with exact orientation
+//! predicates, the latter for triangle pairs whose bounding boxes overlap.
+//! Triangles that only touch, or overlap within a common plane, are not
+//! reported as intersecting.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::mesh::validate::Issue;
+//!
+//! let mesh = manifold_rs::render("cube(10);").unwrap();
+//! assert!(mesh.validate().is_valid());
+//!
+//! // Drop the last triangle, leaving a hole with three open edges
+//! let mut open = mesh.clone();
+//! open.indices.truncate(33);
+//! let report = open.validate();
+//! assert_eq!(report.open_edges, 3);
+//! assert!(report.findings.iter().all(|f| f.issue == Issue::OpenEdge));
+//! ```
+
+use std::collections::HashMap;
+
+use super::bvh::{Aabb, Bvh};
+use super::Mesh;
+use crate::manifold::boolean::predicates::{orient2d, orient3d};
+
+/// Most findings kept per issue; the counts include the rest.
+pub const MAX_FINDINGS: usize = 100;
+
+/// Kind of defect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Issue {
+    /// Edge used by a single triangle.
+    OpenEdge,
+    /// Edge used by more than two triangles.
+    NonManifoldEdge,
+    /// Triangle wound against a neighbour or its normals, or a closed
+    /// mesh inside out.
+    InvertedNormal,
+    /// Triangle with no area.
+    Degenerate,
+    /// Two triangles passing through each other.
+    SelfIntersection,
+}
+
+/// One defect and where it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// What is wrong.
+    pub issue: Issue,
+    /// Triangles involved, by index in `indices / 3`; empty when the whole
+    /// mesh is affected.
+    pub triangles: Vec<u32>,
+    /// Point to flag: the middle of the edge, the centre of the triangle,
+    /// or where the triangles cross.
+    pub position: [f64; 3],
+}
+
+/// Result of [`Mesh::validate`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshReport {
+    /// Edges used by a single triangle.
+    pub open_edges: usize,
+    /// Edges used by more than two triangles.
+    pub non_manifold_edges: usize,
+    /// Edges between triangles wound alike, triangles wound against their
+    /// normals, and one for a closed mesh wound inside out.
+    pub inverted_normals: usize,
+    /// Triangles with no area.
+    pub degenerate_triangles: usize,
+    /// Pairs of triangles passing through each other.
+    pub self_intersections: usize,
+    /// Up to [`MAX_FINDINGS`] findings of each issue, in mesh order.
+    pub findings: Vec<Finding>,
+}
+
+impl MeshReport {
+    /// Whether no check found anything.
+    pub fn is_valid(&self) -> bool {
+        self.open_edges == 0
+            && self.non_manifold_edges == 0
+            && self.inverted_normals == 0
+            && self.degenerate_triangles == 0
+            && self.self_intersections == 0
+    }
+
+    /// Whether every edge joins exactly two triangles.
+    pub fn is_watertight(&self) -> bool {
+        self.open_edges == 0 && self.non_manifold_edges == 0
+    }
+
+    /// Count a finding, keeping it if there is room.
+    fn add(&mut self, finding: Finding) {
+        let count = match finding.issue {
+            Issue::OpenEdge => &mut self.open_edges,
+            Issue::NonManifoldEdge => &mut self.non_manifold_edges,
+            Issue::InvertedNormal => &mut self.inverted_normals,
+            Issue::Degenerate => &mut self.degenerate_triangles,
+            Issue::SelfIntersection => &mut self.self_intersections,
+        };
+        *count += 1;
+        if *count <= MAX_FINDINGS {
+            self.findings.push(finding);
+        }
+    }
+}
+
+impl Mesh {
+    /// Check the mesh for holes, non-manifold edges, inverted normals,
+    /// degenerate triangles and self-intersections.
+    ///
+    /// See the [module documentation](self) for what each check looks at.
+    #[must_use]
+    pub fn validate(&self) -> MeshReport {
+        let (positions, remap) = self.welded_positions();
+        let mut report = MeshReport::default();
+
+        // Triangles as welded corners, without the degenerate ones
+        let mut triangles: Vec<(u32, [u32; 3])> = Vec::new();
+        for (t, corners) in self.indices.chunks_exact(3).enumerate() {
+            let welded = [corners[0], corners[1], corners[2]].map(|i| remap[i as usize]);
+            let points = welded.map(|v| positions[v as usize]);
+            if is_degenerate(points) {
+                report.add(Finding { issue: Issue::Degenerate, triangles: vec![t as u32], position: centroid(points) });
+            } else {
+                triangles.push((t as u32, welded));
+            }
+        }
+
+        check_edges(&triangles, &positions, &mut report);
+        let conflicts = report.inverted_normals;
+        check_normals(self, &triangles, &positions, &mut report);
+        if report.is_watertight() && conflicts == 0 && !triangles.is_empty() && signed_volume(&triangles, &positions) < 0.0 {
+            let center = positions.iter().fold([0.0; 3], |acc, p| std::array::from_fn(|i| acc[i] + p[i] / positions.len() as f64));
+            report.add(Finding { issue: Issue::InvertedNormal, triangles: Vec::new(), position: center });
+        }
+        check_intersections(&triangles, &positions, &mut report);
+        report
+    }
+}
+
+// =============================================================================
+// CHECKS
+// =============================================================================
+
+/// Open, non-manifold and inconsistently wound edges.
+fn check_edges(triangles: &[(u32, [u32; 3])], positions: &[[f64; 3]], report: &mut MeshReport) {
+    // Undirected edge → (triangle, whether it runs from low to high index)
+    let mut edges: HashMap<(u32, u32), Vec<(u32, bool)>> = HashMap::new();
+    for &(t, [a, b, c]) in triangles {
+        for (from, to) in [(a, b), (b, c), (c, a)] {
+            edges.entry((from.min(to), from.max(to))).or_default().push((t, from < to));
+        }
+    }
+    let mut keys: Vec<(u32, u32)> = edges.keys().copied().collect();
+    keys.sort_unstable_by_key(|key| (edges[key][0].0, *key));
+
+    for key in keys {
+        let uses = &edges[&key];
+        let (a, b) = (positions[key.0 as usize], positions[key.1 as usize]);
+        let position = std::array::from_fn(|i| (a[i] + b[i]) / 2.0);
+        let issue = match uses.len() {
+            1 => Issue::OpenEdge,
+            2 if uses[0].1 == uses[1].1 => Issue::InvertedNormal,
+            2 => continue,
+            _ => Issue::NonManifoldEdge,
+        };
+        report.add(Finding { issue, triangles: uses.iter().map(|&(t, _)| t).collect(), position });
+    }
+}
+
+/// Triangles whose winding faces away from all three vertex normals.
+fn check_normals(mesh: &Mesh, triangles: &[(u32, [u32; 3])], positions: &[[f64; 3]], report: &mut MeshReport) {
+    for &(t, welded) in triangles {
+        let points = welded.map(|v| positions[v as usize]);
+        let normal = winding_normal(points);
+        let corners = &mesh.indices[t as usize * 3..t as usize * 3 + 3];
+        let against = corners.iter().all(|&i| {
+            let n = &mesh.normals[i as usize * 3..i as usize * 3 + 3];
+            let d = normal[0] * f64::from(n[0]) + normal[1] * f64::from(n[1]) + normal[2] * f64::from(n[2]);
+            d < 0.0
+        });
+        if against {
+            report.add(Finding { issue: Issue::InvertedNormal, triangles: vec![t], position: centroid(points) });
+        }
+    }
+}
+
+/// Pairs of triangles where an edge of one passes through the other.
+fn check_intersections(triangles: &[(u32, [u32; 3])], positions: &[[f64; 3]], report: &mut MeshReport) {
+    let points: Vec<[[f64; 3]; 3]> = triangles.iter().map(|(_, welded)| welded.map(|v| positions[v as usize])).collect();
+    let bvh = Bvh::new(&points.iter().map(|p| Aabb::of(p)).collect::<Vec<_>>());
+
+    // Each triangle against the later ones its plane reaches
+    let mut pairs = Vec::new();
+    for (i, &a) in points.iter().enumerate() {
+        for j in bvh.near_triangle(a).into_iter().map(|j| j as usize).filter(|&j| j > i) {
+            let b = points[j];
+            if let Some(position) = crossing(a, b).or_else(|| crossing(b, a)) {
+                pairs.push((triangles[i].0.min(triangles[j].0), triangles[i].0.max(triangles[j].0), position));
+            }
+        }
+    }
+
+    pairs.sort_by_key(|&(a, b, _)| (a, b));
+    for (a, b, position) in pairs {
+        report.add(Finding { issue: Issue::SelfIntersection, triangles: vec![a, b], position });
+    }
+}
+
+/// Where an edge of `a` passes through the interior of `b`, if one does.
+fn crossing(a: [[f64; 3]; 3], [p, q, r]: [[f64; 3]; 3]) -> Option<[f64; 3]> {
+    (0..3).find_map(|k| {
+        let (s, e) = (a[k], a[(k + 1) % 3]);
+        let (ds, de) = (orient3d(p, q, r, s), orient3d(p, q, r, e));
+        if ds == 0.0 || de == 0.0 || (ds > 0.0) == (de > 0.0) {
+            return None;
+        }
+        let sides = [orient3d(s, e, p, q), orient3d(s, e, q, r), orient3d(s, e, r, p)];
+        let inside = sides.iter().all(|&o| o > 0.0) || sides.iter().all(|&o| o < 0.0);
+        inside.then(|| {
+            let t = ds / (ds - de);
+            std::array::from_fn(|i| s[i] + t * (e[i] - s[i]))
+        })
+    })
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Whether three points are collinear, decided exactly.
+fn is_degenerate([a, b, c]: [[f64; 3]; 3]) -> bool {
+    let project = |p: [f64; 3], i: usize, j: usize| [p[i], p[j]];
+    [(0, 1), (1, 2), (2, 0)].into_iter()
+        .all(|(i, j)| orient2d(project(a, i, j), project(b, i, j), project(c, i, j)) == 0.0)
+}
+
+/// Unnormalized normal of a triangle, from its winding.
+fn winding_normal(points: [[f64; 3]; 3]) -> [f64; 3] {
+    let [a, b, c] = points;
+    let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+    [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]]
+}
+
+/// Six times the signed volume enclosed by the triangles.
+fn signed_volume(triangles: &[(u32, [u32; 3])], positions: &[[f64; 3]]) -> f64 {
+    triangles.iter()
+        .map(|(_, welded)| {
+            let [a, b, c] = welded.map(|v| positions[v as usize]);
+            a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0]) + a[2] * (b[0] * c[1] - b[1] * c[0])
+        })
+        .sum()
+}
+
+/// Centre of a triangle.
+fn centroid([a, b, c]: [[f64; 3]; 3]) -> [f64; 3] {
+    std::array::from_fn(|i| (a[i] + b[i] + c[i]) / 3.0)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test rendered solids, including boolean results, are valid.
+    #[test]
+    fn test_valid_meshes() {
+        for source in ["cube(10);", "sphere(5, $fn = 16);", "difference() { cube(10); translate([5, 5, 5]) cube(10); }"] {
+            let report = crate::render(source).unwrap().validate();
+            assert!(report.is_valid(), "{}: {:?}", source, report);
+        }
+    }
+
+    /// Test a flipped triangle and an inside-out cube are reported.
+    #[test]
+    fn test_inverted_normals() {
+        let cube = crate::render("cube(10);").unwrap();
+
+        let mut flipped = cube.clone();
+        flipped.indices.swap(1, 2);
+        let report = flipped.validate();
+        assert!(report.is_watertight());
+        // Three edges wound like their neighbour's, and the normals
+        assert_eq!(report.inverted_normals, 4);
+        assert!(report.findings.iter().all(|f| f.triangles.contains(&0)));
+
+        let mut inside_out = cube.clone();
+        for t in inside_out.indices.chunks_exact_mut(3) {
+            t.swap(1, 2);
+        }
+        for n in &mut inside_out.normals {
+            *n = -*n;
+        }
+        let report = inside_out.validate();
+        assert_eq!(report.inverted_normals, 1);
+        assert!(report.findings[0].triangles.is_empty());
+    }
+
+    /// Test degenerate and non-manifold triangles are reported.
+    #[test]
+    fn test_degenerate_and_non_manifold() {
+        let mut mesh = crate::render("cube(10);").unwrap();
+        let [a, b] = [mesh.indices[0], mesh.indices[1]];
+        let apex = mesh.add_vertex(5.0, 5.0, -5.0, 0.0, 0.0, -1.0);
+        mesh.add_triangle(a, b, apex);
+        let mid = mesh.add_vertex(0.0, 0.0, 5.0, 1.0, 0.0, 0.0);
+        let top = mesh.add_vertex(0.0, 0.0, 10.0, 1.0, 0.0, 0.0);
+        let bottom = mesh.add_vertex(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        mesh.add_triangle(bottom, mid, top);
+
+        let report = mesh.validate();
+        assert_eq!(report.degenerate_triangles, 1);
+        assert_eq!(report.findings.iter().find(|f| f.issue == Issue::Degenerate).unwrap().triangles, vec![13]);
+        assert_eq!(report.non_manifold_edges, 1);
+        assert_eq!(report.open_edges, 2);
+    }
+
+    /// Test overlapping cubes merged without a boolean intersect.
+    #[test]
+    fn test_self_intersections() {
+        let mut mesh = crate::render("cube(10);").unwrap();
+        mesh.merge(&crate::render("translate([5, 3, 4]) cube(10);").unwrap());
+        let report = mesh.validate();
+        assert!(report.is_watertight());
+        assert!(report.self_intersections > 0);
+        let finding = report.findings.iter().find(|f| f.issue == Issue::SelfIntersection).unwrap();
+        assert_eq!(finding.triangles.len(), 2);
+        assert!(finding.position.iter().all(|c| (3.0..=10.0).contains(c)));
+
+        // Cubes sharing a face only touch
+        let mut touching = crate::render("cube(10);").unwrap();
+        touching.merge(&crate::render("translate([10, 0, 0]) cube(10);").unwrap());
+        assert_eq!(touching.validate().self_intersections, 0);
+    }
+}
@@ -0,0 +1,208 @@
+//! # Mesh Cache
+//!
+//! Keeps the meshes of geometry subtrees between renders, so a re-render
+//! after a small edit only meshes the subtrees that changed.
+//!
+//! ## Keys
+//!
+//! A subtree is keyed on its structural hash from
+//! [`openscad_eval::NodeHashes`]: two nodes share an entry exactly when
+//! every primitive, parameter and child is equal. Editing one child of a
+//! union changes that child's hash and the union's, but not the hashes of
+//! its siblings.
+//!
+//! ## What is cached
+//!
+//! [`geometry_to_mesh_cached`] stores the root it is given and every
+//! subtree that is costly to mesh (booleans, hulls, Minkowski sums,
+//! extrusions, offsets, projections, smoothing). Primitives and
+//! transforms are cheap to rebuild from a cached child and are not
+//! stored. Subtrees with `import()`, `surface()` or `text()` are never
+//! cached, since their meshes depend on files and fonts outside the tree.
+//!
+//! ## Eviction
+//!
+//! Entries live for one round. [`MeshCache::sweep`] ends a round and drops
+//! every entry that was neither used nor stored during it, so the cache
+//! holds at most the subtrees of the latest render.
+//!
+//! [`MeshCache::set_limit`] caps the number of entries. A full cache
+//! stores no new meshes until a sweep frees room; it keeps serving the
+//! ones it holds.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::openscad::cache::MeshCache;
+//! use manifold_rs::openscad::from_ir::geometry_to_mesh_cached;
+//! use openscad_eval::evaluate;
+//!
+//! let mut cache = MeshCache::new();
+//! let node = evaluate("difference() { cube(10); sphere(6); }").unwrap().geometry;
+//! geometry_to_mesh_cached(&node, &mut cache, None, None).unwrap();
+//! geometry_to_mesh_cached(&node, &mut cache, None, None).unwrap();
+//! let stats = cache.sweep();
+//! assert_eq!((stats.hits, stats.misses), (1, 1));
+//! ```
+//!
+//! [`geometry_to_mesh_cached`]: super::from_ir::geometry_to_mesh_cached
+
+use std::collections::HashMap;
+
+use crate::Mesh;
+
+/// Cache lookups during one round.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Meshes taken from the cache.
+    pub hits: usize,
+    /// Meshes built, stored unless the cache was full.
+    pub misses: usize,
+}
+
+/// A cached mesh and the round it was last used in.
+#[derive(Debug)]
+struct Entry {
+    mesh: Mesh,
+    round: u64,
+}
+
+/// Meshes of geometry subtrees, keyed on their structural hashes.
+#[derive(Debug, Default)]
+pub struct MeshCache {
+    entries: HashMap<u64, Entry>,
+    round: u64,
+    stats: CacheStats,
+    limit: Option<usize>,
+}
+
+impl MeshCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The mesh stored for `hash`, counted as a hit and kept this round.
+    pub fn get(&mut self, hash: u64) -> Option<Mesh> {
+        let entry = self.entries.get_mut(&hash)?;
+        entry.round = self.round;
+        self.stats.hits += 1;
+        Some(entry.mesh.clone())
+    }
+
+    /// Store the mesh built for `hash`, counted as a miss.
+    ///
+    /// Not stored if the cache is at its limit.
+    pub fn insert(&mut self, hash: u64, mesh: &Mesh) {
+        self.stats.misses += 1;
+        let full = self.limit.is_some_and(|limit| self.entries.len() >= limit);
+        if !full || self.entries.contains_key(&hash) {
+            self.entries.insert(hash, Entry { mesh: mesh.clone(), round: self.round });
+        }
+    }
+
+    /// Cap the number of cached meshes, or lift the cap with `None`.
+    ///
+    /// Entries over a lowered limit are dropped at the next sweep, in
+    /// order of their hashes.
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    /// The entry limit, if any.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Lookups so far this round.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// End the round: drop entries it did not touch and return its stats.
+    pub fn sweep(&mut self) -> CacheStats {
+        let round = self.round;
+        self.entries.retain(|_, entry| entry.round == round);
+        if let Some(limit) = self.limit.filter(|limit| self.entries.len() > *limit) {
+            let mut hashes: Vec<u64> = self.entries.keys().copied().collect();
+            hashes.sort_unstable();
+            for hash in hashes.into_iter().skip(limit) {
+                self.entries.remove(&hash);
+            }
+        }
+        self.round += 1;
+        std::mem::take(&mut self.stats)
+    }
+
+    /// Number of cached meshes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache holds no meshes.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop every cached mesh.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.stats = CacheStats::default();
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openscad::from_ir::{geometry_to_mesh, geometry_to_mesh_cached};
+    use openscad_eval::evaluate;
+
+    /// Test unchanged subtrees hit, changed ones miss, and stale ones are swept.
+    #[test]
+    fn test_rounds() {
+        let mut cache = MeshCache::new();
+        let render = |cache: &mut MeshCache, source| {
+            let node = evaluate(source).unwrap().geometry;
+            let mesh = geometry_to_mesh_cached(&node, cache, None, None).unwrap();
+            assert_eq!(mesh.vertices, geometry_to_mesh(&node).unwrap().vertices);
+        };
+
+        // Root group, the difference and the hull are stored
+        render(&mut cache, "difference() { cube(10); sphere(6); } hull() { cube(1); sphere(1); }");
+        assert_eq!(cache.sweep(), CacheStats { hits: 0, misses: 3 });
+
+        // The hull changed: the root misses, the difference hits
+        render(&mut cache, "difference() { cube(10); sphere(6); } hull() { cube(2); sphere(1); }");
+        assert_eq!(cache.sweep(), CacheStats { hits: 1, misses: 2 });
+        assert_eq!(cache.len(), 3);
+
+        // Subtrees reading fonts are never stored
+        render(&mut cache, "union() { text(\"A\"); square(1); }");
+        assert_eq!(cache.sweep(), CacheStats { hits: 0, misses: 0 });
+        assert!(cache.is_empty());
+    }
+
+    /// Test a full cache stores nothing new and a lowered limit trims at the sweep.
+    #[test]
+    fn test_limit() {
+        let mut cache = MeshCache::new();
+        cache.set_limit(Some(1));
+        cache.insert(1, &Mesh::new());
+        cache.insert(2, &Mesh::new());
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(2).is_none());
+        assert_eq!(cache.sweep(), CacheStats { hits: 0, misses: 2 });
+
+        cache.set_limit(None);
+        cache.insert(2, &Mesh::new());
+        cache.get(1);
+        cache.set_limit(Some(1));
+        cache.sweep();
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(1).is_some());
+    }
+}
@@ -0,0 +1,190 @@
+//! # 2D Drawing Export
+//!
+//! Writes outline loops as SVG or DXF, the formats laser cutters and CAM
+//! software take for 2D parts. A 3D part is cut with `projection()`
+//! first: its shadow, or with `cut = true` its section at `z = 0`.
+//!
+//! ## SVG
+//!
+//! One `<path>` holding every loop, filled with the even-odd rule so holes
+//! stay open. SVG's Y axis points down, so Y is negated; the `viewBox` is
+//! the bounds of the loops and `width`/`height` are in millimeters, so
+//! one model unit prints as one millimeter, as in OpenSCAD.
+//!
+//! ## DXF
+//!
+//! AutoCAD R12 ASCII with only an `ENTITIES` section: one closed
+//! `POLYLINE` per loop on layer `0`. R12 is the version every laser and
+//! CNC package still reads.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::openscad::drawing::{outlines_to_dxf, outlines_to_svg};
+//!
+//! let loops = manifold_rs::render_outlines("difference() { square(10); translate([5, 5]) circle(2); }").unwrap();
+//! assert!(outlines_to_svg(&loops).contains(r#"viewBox="0 -10 10 10""#));
+//! assert_eq!(outlines_to_dxf(&loops).matches("POLYLINE").count(), 2);
+//!
+//! let section = manifold_rs::render_outlines("projection(cut = true) sphere(5, $fn = 8);").unwrap();
+//! assert_eq!(outlines_to_dxf(&section).matches("VERTEX").count(), 8);
+//! ```
+
+use std::fmt::Write;
+
+use super::outlines::OutlineLoop;
+
+/// File formats 2D results can be written as.
+pub const EXPORT_2D_FORMATS: &[&str] = &["svg", "dxf"];
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Encode loops in one of the [`EXPORT_2D_FORMATS`].
+///
+/// The format name is case-insensitive.
+///
+/// ## Returns
+///
+/// The file bytes, or `None` for an unknown format.
+#[must_use]
+pub fn export_outlines(loops: &[OutlineLoop], format: &str) -> Option<Vec<u8>> {
+    // Keep in sync with EXPORT_2D_FORMATS
+    match format.to_ascii_lowercase().as_str() {
+        "svg" => Some(outlines_to_svg(loops).into_bytes()),
+        "dxf" => Some(outlines_to_dxf(loops).into_bytes()),
+        _ => None,
+    }
+}
+
+/// Encode loops as an SVG document with one even-odd filled path.
+#[must_use]
+pub fn outlines_to_svg(loops: &[OutlineLoop]) -> String {
+    let [min_x, min_y, max_x, max_y] = bounds(loops);
+    let (width, height) = (max_x - min_x, max_y - min_y);
+
+    let mut path = String::new();
+    for outline in loops.iter().filter(|l| !l.points.is_empty()) {
+        for (i, p) in outline.points.iter().enumerate() {
+            // Writing to a String cannot fail
+            let _ = write!(path, "{}{},{} ", if i == 0 { "M" } else { "L" }, p[0], flip(p[1]));
+        }
+        path.push_str("Z ");
+    }
+
+    let mut svg = String::new();
+    let _ = writeln!(svg, r#"<?xml version="1.0" standalone="no"?>"#);
+    let _ = writeln!(
+        svg,
+        r#"<svg width="{}mm" height="{}mm" viewBox="{} {} {} {}" xmlns="http://www.w3.org/2000/svg" version="1.1">"#,
+        width, height, min_x, flip(max_y), width, height,
+    );
+    let _ = writeln!(svg, "<title>OpenSCAD Model</title>");
+    if !path.is_empty() {
+        let _ = writeln!(svg, r#"<path d="{}" fill="lightgray" fill-rule="evenodd" stroke="black" stroke-width="0.1"/>"#, path.trim_end());
+    }
+    let _ = writeln!(svg, "</svg>");
+    svg
+}
+
+/// Encode loops as R12 DXF with one closed polyline per loop.
+#[must_use]
+pub fn outlines_to_dxf(loops: &[OutlineLoop]) -> String {
+    let mut dxf = String::new();
+    // Group code on one line, value on the next
+    let mut pair = |code: u32, value: &dyn std::fmt::Display| {
+        // Writing to a String cannot fail
+        let _ = write!(dxf, "{:>3}\n{}\n", code, value);
+    };
+    pair(0, &"SECTION");
+    pair(2, &"ENTITIES");
+    for outline in loops.iter().filter(|l| !l.points.is_empty()) {
+        pair(0, &"POLYLINE");
+        pair(8, &0);
+        // Vertices follow; the polyline is closed and its own point unused
+        pair(66, &1);
+        pair(10, &0);
+        pair(20, &0);
+        pair(30, &0);
+        pair(70, &1);
+        for p in &outline.points {
+            pair(0, &"VERTEX");
+            pair(8, &0);
+            pair(10, &p[0]);
+            pair(20, &p[1]);
+        }
+        pair(0, &"SEQEND");
+        pair(8, &0);
+    }
+    pair(0, &"ENDSEC");
+    pair(0, &"EOF");
+    dxf
+}
+
+/// SVG Y of a model Y, without writing `-0`.
+fn flip(y: f64) -> f64 {
+    0.0 - y
+}
+
+/// Bounds of all loop points as `[min_x, min_y, max_x, max_y]`; zero for
+/// no points.
+fn bounds(loops: &[OutlineLoop]) -> [f64; 4] {
+    let mut points = loops.iter().flat_map(|l| &l.points).peekable();
+    if points.peek().is_none() {
+        return [0.0; 4];
+    }
+    points.fold([f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY], |b, p| {
+        [b[0].min(p[0]), b[1].min(p[1]), b[2].max(p[0]), b[3].max(p[1])]
+    })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Winding;
+
+    fn square() -> Vec<OutlineLoop> {
+        vec![OutlineLoop {
+            points: vec![[0.0, 0.0], [2.0, 0.0], [2.0, 1.0], [0.0, 1.0]],
+            is_hole: false,
+            winding: Winding::CounterClockwise,
+            parent: None,
+        }]
+    }
+
+    /// Test the SVG flips Y and sizes the document to the loops.
+    #[test]
+    fn test_svg() {
+        let svg = outlines_to_svg(&square());
+        assert!(svg.contains(r#"width="2mm" height="1mm" viewBox="0 -1 2 1""#));
+        assert!(svg.contains(r#"d="M0,0 L2,0 L2,-1 L0,-1 Z""#));
+
+        let empty = outlines_to_svg(&[]);
+        assert!(empty.contains(r#"viewBox="0 0 0 0""#));
+        assert!(!empty.contains("<path"));
+    }
+
+    /// Test the DXF has one closed polyline with each vertex.
+    #[test]
+    fn test_dxf() {
+        let dxf = outlines_to_dxf(&square());
+        assert!(dxf.starts_with("  0\nSECTION\n  2\nENTITIES\n"));
+        assert!(dxf.contains("  0\nPOLYLINE\n  8\n0\n 66\n1\n 10\n0\n 20\n0\n 30\n0\n 70\n1\n"));
+        assert_eq!(dxf.matches("VERTEX").count(), 4);
+        assert!(dxf.contains(" 10\n2\n 20\n1\n"));
+        assert!(dxf.ends_with("  0\nENDSEC\n  0\nEOF\n"));
+    }
+
+    /// Test format names are matched case-insensitively.
+    #[test]
+    fn test_export_outlines() {
+        assert!(export_outlines(&square(), "SVG").unwrap().starts_with(b"<?xml"));
+        assert!(export_outlines(&square(), "dxf").is_some());
+        assert!(export_outlines(&square(), "stl").is_none());
+    }
+}
@@ -19,17 +19,46 @@
 //! ## Supported Geometry Types
 //!
 //! - **Primitives**: Cube, Sphere, Cylinder, Polyhedron
-//! - **2D Primitives**: Circle, Square, Polygon
-//! - **Transforms**: Translate, Rotate, Scale, Mirror, Multmatrix
+//! - **2D Primitives**: Circle, Square, Polygon, Text
+//! - **Import**: STL, OBJ and OFF meshes and SVG drawings via the import
+//!   registry
+//! - **Surface**: `.dat` and PNG heightmaps via the import registry
+//! - **Transforms**: Translate, Rotate, Scale, Resize, Mirror, Multmatrix
 //! - **Booleans**: Union, Difference, Intersection
 //! - **Extrusions**: LinearExtrude, RotateExtrude
 //! - **Operations**: Hull, Minkowski, Offset, Projection
+//! - **Extensions**: Smooth, Refine, Warp, Quality
+//! - **Modifiers**: Highlight (`#`) is meshed, Background (`%`) left out
+//!
+//! ## Node Ids
+//!
+//! Every triangle records the node that made it in [`Mesh::nodes`]: its
+//! pre-order position in the tree, counting nodes as
+//! [`GeometryNode::node_count`] does (see
+//! [`NodeTable`](super::provenance::NodeTable)). A node claims the
+//! triangles it adds that its children have not, so primitives own their
+//! faces, booleans pass each face on from the operand it came from, and
+//! hulls, extrusions and other operations that build new surfaces own
+//! those.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 
-use openscad_eval::GeometryNode;
-use crate::error::ManifoldResult;
+use openscad_eval::limits::now_ms;
+use openscad_eval::progress::{CancellationToken, Progress, ProgressSink, Stage};
+use openscad_eval::timing::{self, NodeTiming};
+use openscad_eval::{Deformation, GeometryNode, NodeHashes};
+use crate::error::{ManifoldError, ManifoldResult};
 use crate::mesh::Mesh;
 use crate::manifold;
 use crate::cross_section;
+use crate::cross_section::boolean::BooleanOp2D;
+use crate::cross_section::ops::{offset_polygons, OffsetJoin};
+use crate::font::TextParams;
+use crate::parallel;
+use super::cache::MeshCache;
+use super::outlines::{boolean_regions, geometry_regions, projection_regions};
 use super::SegmentParams;
 
 // =============================================================================
@@ -49,20 +78,331 @@ use super::SegmentParams;
 ///
 /// `ManifoldResult<Mesh>` - Triangle mesh on success
 pub fn geometry_to_mesh(node: &GeometryNode) -> ManifoldResult<Mesh> {
+    geometry_to_mesh_with_progress(node, None, None)
+}
+
+/// Convert GeometryNode to Mesh, reporting progress and stopping when
+/// cancelled.
+///
+/// Reports [`Stage::Csg`] progress as geometry nodes are meshed, from 0
+/// to the tree's [`GeometryNode::node_count`]. With parallel reduction,
+/// reports come from worker threads and may arrive slightly out of order.
+///
+/// ## Parameters
+///
+/// - `node`: Root GeometryNode from openscad-eval
+/// - `progress`: Receives the reports
+/// - `cancel`: Checked before each node is meshed
+///
+/// ## Errors
+///
+/// Same as [`geometry_to_mesh`], and `ManifoldError::Cancelled` once
+/// `cancel` is cancelled.
+pub fn geometry_to_mesh_with_progress(
+    node: &GeometryNode,
+    progress: Option<&dyn ProgressSink>,
+    cancel: Option<&CancellationToken>,
+) -> ManifoldResult<Mesh> {
+    convert(node, &Control::new(node, progress, cancel, None, false))
+}
+
+/// Convert GeometryNode to Mesh, reusing the meshes of unchanged subtrees.
+///
+/// Subtrees found in `cache` by structural hash are not meshed again;
+/// the root and costly subtrees meshed here are stored for the next call
+/// (see [`cache`](super::cache)). Gives the same mesh as
+/// [`geometry_to_mesh_with_progress`]. Cached subtrees count as meshed
+/// for progress.
+///
+/// ## Parameters
+///
+/// - `node`: Root GeometryNode from openscad-eval
+/// - `cache`: Meshes kept from earlier conversions
+/// - `progress`: Receives the reports
+/// - `cancel`: Checked before each node is meshed
+///
+/// ## Errors
+///
+/// Same as [`geometry_to_mesh_with_progress`]. Subtrees meshed before an
+/// error stay cached.
+pub fn geometry_to_mesh_cached(
+    node: &GeometryNode,
+    cache: &mut MeshCache,
+    progress: Option<&dyn ProgressSink>,
+    cancel: Option<&CancellationToken>,
+) -> ManifoldResult<Mesh> {
+    convert(node, &Control::new(node, progress, cancel, Some(CacheScope::new(node, cache)), false))
+}
+
+/// Convert GeometryNode to Mesh, timing each CSG node.
+///
+/// Gives the same mesh as [`geometry_to_mesh_with_progress`], or
+/// [`geometry_to_mesh_cached`] with a `cache`, and how long each boolean,
+/// hull, Minkowski sum, extrusion, offset, projection and smoothing took,
+/// ordered by position in the tree (see [`NodeTiming`]). Times include the
+/// children, so they add up to more than the whole when nested. A subtree
+/// taken from the cache is timed as its lookup.
+///
+/// ## Example
+///
+/// ```rust
+/// use manifold_rs::openscad::from_ir::geometry_to_mesh_timed;
+///
+/// let evaluated = openscad_eval::evaluate("difference() { cube(10); union() { sphere(6); cylinder(20, r = 2); } }").unwrap();
+/// let (mesh, timings) = geometry_to_mesh_timed(&evaluated.geometry, None, None, None).unwrap();
+/// assert!(!mesh.is_empty());
+/// let kinds: Vec<_> = timings.iter().map(|t| (t.node, t.depth, t.kind.as_str())).collect();
+/// assert_eq!(kinds, [(0, 0, "difference"), (2, 1, "union")]);
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`geometry_to_mesh_with_progress`].
+pub fn geometry_to_mesh_timed(
+    node: &GeometryNode,
+    cache: Option<&mut MeshCache>,
+    progress: Option<&dyn ProgressSink>,
+    cancel: Option<&CancellationToken>,
+) -> ManifoldResult<(Mesh, Vec<NodeTiming>)> {
+    let scope = cache.map(|cache| CacheScope::new(node, cache));
+    let control = Control::new(node, progress, cancel, scope, true);
+    let mesh = convert(node, &control)?;
+    let mut timings = control.timer.map(|timer| timer.timings.into_inner().unwrap_or_else(|e| e.into_inner())).unwrap_or_default();
+    timings.sort_by_key(|t| t.node);
+    Ok((mesh, timings))
+}
+
+/// Run a conversion under `control`.
+fn convert(node: &GeometryNode, control: &Control) -> ManifoldResult<Mesh> {
+    control.report(0);
     let mut mesh = Mesh::new();
     let params = SegmentParams::default();
-    process_node(node, &mut mesh, &params)?;
+    process_node(node, &mut mesh, &params, control)?;
+    control.report(control.total);
     Ok(mesh)
 }
 
+// =============================================================================
+// CACHING
+// =============================================================================
+
+/// The cache of one conversion and the hashes of the nodes it may hold.
+struct CacheScope<'a> {
+    /// Structural hash of each cacheable node, by address.
+    hashes: HashMap<usize, u64>,
+    cache: Mutex<&'a mut MeshCache>,
+}
+
+impl<'a> CacheScope<'a> {
+    /// The scope converting `node` through `cache`.
+    fn new(node: &GeometryNode, cache: &'a mut MeshCache) -> Self {
+        let mut hashes = HashMap::new();
+        index_hashes(node, &NodeHashes::new(node), true, &mut hashes);
+        Self { hashes, cache: Mutex::new(cache) }
+    }
+}
+
+/// Record the hashes of the cacheable nodes under `node`: the root and
+/// costly subtrees, as long as they are self-contained.
+fn index_hashes(node: &GeometryNode, hashes: &NodeHashes, root: bool, out: &mut HashMap<usize, u64>) {
+    if hashes.self_contained && (root || csg_kind(node).is_some()) {
+        out.insert(node as *const GeometryNode as usize, hashes.hash);
+    }
+    for (child, child_hashes) in node.children().iter().zip(&hashes.children) {
+        index_hashes(child, child_hashes, false, out);
+    }
+}
+
+/// The name of a CSG node: one whose meshing combines or reshapes its
+/// children, and costs more than copying its mesh. Only these are cached
+/// and timed.
+fn csg_kind(node: &GeometryNode) -> Option<&'static str> {
+    Some(match node {
+        GeometryNode::Union { .. } => "union",
+        GeometryNode::Difference { .. } => "difference",
+        GeometryNode::Intersection { .. } => "intersection",
+        GeometryNode::Hull { .. } => "hull",
+        GeometryNode::Minkowski { .. } => "minkowski",
+        GeometryNode::LinearExtrude { .. } => "linear_extrude",
+        GeometryNode::RotateExtrude { .. } => "rotate_extrude",
+        GeometryNode::Offset { .. } => "offset",
+        GeometryNode::Projection { .. } => "projection",
+        GeometryNode::Smooth { .. } => "smooth",
+        GeometryNode::Refine { .. } => "refine",
+        GeometryNode::Warp { .. } => "warp",
+        _ => return None,
+    })
+}
+
+// =============================================================================
+// TIMING
+// =============================================================================
+
+/// Per-node timings of one conversion.
+#[derive(Default)]
+struct NodeTimer {
+    /// Timings so far, across threads.
+    timings: Mutex<Vec<NodeTiming>>,
+}
+
+impl NodeTimer {
+    /// Record that meshing the node at `position` and `depth` took `ms`.
+    fn record(&self, (position, depth): (u32, u32), kind: &str, ms: f64) {
+        if let Ok(mut timings) = self.timings.lock() {
+            timings.push(NodeTiming { node: position, depth, kind: kind.to_string(), ms });
+        }
+    }
+}
+
+/// Record the pre-order position and depth of the nodes under `node`,
+/// counting every node from `next` as [`GeometryNode::node_count`] does.
+fn index_positions(node: &GeometryNode, depth: u32, next: &mut u32, out: &mut HashMap<usize, (u32, u32)>) {
+    if matches!(node, GeometryNode::Empty) {
+        return;
+    }
+    out.insert(node as *const GeometryNode as usize, (*next, depth));
+    *next += 1;
+    for child in node.children() {
+        index_positions(child, depth + 1, next, out);
+    }
+}
+
+// =============================================================================
+// PROGRESS
+// =============================================================================
+
+/// Progress reporting, cancellation and caching for one conversion.
+struct Control<'a> {
+    progress: Option<&'a dyn ProgressSink>,
+    cancel: Option<&'a CancellationToken>,
+    /// Nodes meshed so far, across threads.
+    done: AtomicU64,
+    /// Nodes in the tree.
+    total: u64,
+    /// Pre-order position and depth of each node, by address.
+    positions: HashMap<usize, (u32, u32)>,
+    cache: Option<CacheScope<'a>>,
+    timer: Option<NodeTimer>,
+}
+
+impl<'a> Control<'a> {
+    fn new(
+        node: &GeometryNode,
+        progress: Option<&'a dyn ProgressSink>,
+        cancel: Option<&'a CancellationToken>,
+        cache: Option<CacheScope<'a>>,
+        timed: bool,
+    ) -> Self {
+        let mut positions = HashMap::new();
+        index_positions(node, 0, &mut 0, &mut positions);
+        let timer = timed.then(NodeTimer::default);
+        Self { progress, cancel, done: AtomicU64::new(0), total: node.node_count() as u64, positions, cache, timer }
+    }
+
+    /// Pre-order position and depth of `node`, if it is in the tree
+    /// rather than a copy made while meshing.
+    fn position(&self, node: &GeometryNode) -> Option<(u32, u32)> {
+        self.positions.get(&(node as *const GeometryNode as usize)).copied()
+    }
+
+    /// Hash of `node` if the conversion caches it.
+    fn cache_key(&self, node: &GeometryNode) -> Option<u64> {
+        self.cache.as_ref()?.hashes.get(&(node as *const GeometryNode as usize)).copied()
+    }
+
+    /// The cached mesh for `hash`, if any.
+    fn lookup(&self, hash: u64) -> Option<Mesh> {
+        self.cache.as_ref()?.cache.lock().ok()?.get(hash)
+    }
+
+    /// Store the mesh built for `hash`.
+    fn store(&self, hash: u64, mesh: &Mesh) {
+        if let Some(mut cache) = self.cache.as_ref().and_then(|scope| scope.cache.lock().ok()) {
+            cache.insert(hash, mesh);
+        }
+    }
+    /// Fail if the render was cancelled.
+    fn check(&self) -> ManifoldResult<()> {
+        match self.cancel {
+            Some(token) if token.is_cancelled() => Err(ManifoldError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Count `nodes` finished nodes.
+    fn advance(&self, nodes: u64) {
+        let done = self.done.fetch_add(nodes, Ordering::Relaxed) + nodes;
+        // Nodes skipped by hull() or meshed as 2D regions are never
+        // counted, so the count can stay short of the total
+        self.report(done.min(self.total));
+    }
+
+    fn report(&self, done: u64) {
+        if let Some(sink) = self.progress {
+            sink.report(Progress { stage: Stage::Csg, done, total: self.total });
+        }
+    }
+}
+
 // =============================================================================
 // NODE PROCESSING
 // =============================================================================
 
-/// Process a single geometry node recursively.
+/// Process a single geometry node recursively, checking for cancellation
+/// first and counting it as done after.
+fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams, control: &Control) -> ManifoldResult<()> {
+    control.check()?;
+    let Some(kind) = csg_kind(node) else { return mesh_node(node, mesh, params, control) };
+    let _span = timing::csg_span(kind);
+    let started = control.timer.as_ref().map(|_| now_ms());
+    let result = mesh_node(node, mesh, params, control);
+    if let (Some(timer), Some(started), Some(position)) = (&control.timer, started, control.position(node)) {
+        timer.record(position, kind, now_ms() - started);
+    }
+    result
+}
+
+/// Mesh a node through the cache, if the conversion has one, claiming
+/// the triangles its children left unclaimed.
+///
+/// Cached meshes keep node ids relative to their root, so a subtree's
+/// mesh fits wherever the same subtree turns up.
+fn mesh_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams, control: &Control) -> ManifoldResult<()> {
+    let position = control.position(node).map(|(position, _)| position);
+    let Some(hash) = control.cache_key(node) else {
+        let first = mesh.triangle_count();
+        build_node(node, mesh, params, control)?;
+        if let Some(position) = position {
+            mesh.assign_nodes(first, position);
+        }
+        control.advance(1);
+        return Ok(());
+    };
+    let offset = i64::from(position.unwrap_or(0));
+    // A cached subtree counts as meshed in one go
+    if let Some(mut cached) = control.lookup(hash) {
+        cached.offset_nodes(offset);
+        mesh.merge(&cached);
+        control.advance(node.node_count() as u64);
+        return Ok(());
+    }
+    let mut built = Mesh::new();
+    build_node(node, &mut built, params, control)?;
+    if let Some(position) = position {
+        built.assign_nodes(0, position);
+    }
+    built.offset_nodes(-offset);
+    control.store(hash, &built);
+    built.offset_nodes(offset);
+    mesh.merge(&built);
+    control.advance(1);
+    Ok(())
+}
+
+/// Mesh a single geometry node.
 ///
 /// Dispatches to appropriate handler based on node type.
-fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) -> ManifoldResult<()> {
+fn build_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams, control: &Control) -> ManifoldResult<()> {
     match node {
         // =====================================================================
         // 3D PRIMITIVES
@@ -76,7 +416,8 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         GeometryNode::Sphere { radius, fn_ } => {
             // Use fn_ directly as segments, or calculate from default params
             let segments = if *fn_ > 0 { *fn_ } else { params.calculate_segments(*radius) };
-            manifold::constructors::build_sphere(mesh, *radius, segments);
+            let style = manifold::constructors::sphere_style();
+            manifold::constructors::build_sphere_styled(mesh, *radius, segments, style);
             Ok(())
         }
         
@@ -87,6 +428,19 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
             Ok(())
         }
         
+        GeometryNode::Torus { major, minor, fn_, fn_tube } => {
+            let segments = if *fn_ > 0 { *fn_ } else { params.calculate_segments(*major + *minor) };
+            let tube = if *fn_tube > 0 { *fn_tube } else { params.calculate_segments(*minor) };
+            manifold::constructors::build_torus(mesh, *major, *minor, segments, tube);
+            Ok(())
+        }
+
+        GeometryNode::RoundedCube { size, radius, center, fn_ } => {
+            let segments = if *fn_ > 0 { *fn_ } else { params.calculate_segments(*radius) };
+            mesh.merge(&manifold::constructors::build_rounded_cube(*size, *radius, *center, segments)?);
+            Ok(())
+        }
+
         GeometryNode::Polyhedron { points, faces } => {
             manifold::constructors::build_polyhedron(mesh, points, faces);
             Ok(())
@@ -99,16 +453,16 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         GeometryNode::Translate { offset, child } => {
             let [dx, dy, dz] = *offset;
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
-            child_mesh.translate(dx as f32, dy as f32, dz as f32);
+            process_node(child, &mut child_mesh, params, control)?;
+            child_mesh.translate(dx, dy, dz);
             mesh.merge(&child_mesh);
             Ok(())
         }
         
         GeometryNode::Rotate { angles, child } => {
-            let matrix = rotation_matrix(*angles);
+            let matrix = convert_matrix(&rotation_matrix(*angles));
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
+            process_node(child, &mut child_mesh, params, control)?;
             child_mesh.transform(&matrix);
             mesh.merge(&child_mesh);
             Ok(())
@@ -117,19 +471,29 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         GeometryNode::Scale { factors, child } => {
             let [sx, sy, sz] = *factors;
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
-            child_mesh.scale(sx as f32, sy as f32, sz as f32);
+            process_node(child, &mut child_mesh, params, control)?;
+            child_mesh.scale(sx, sy, sz);
             mesh.merge(&child_mesh);
             Ok(())
         }
         
+        GeometryNode::Resize { newsize, auto, child } => {
+            let mut child_mesh = Mesh::new();
+            process_node(child, &mut child_mesh, params, control)?;
+            let (min, max) = bounding_box(&child_mesh);
+            let size = [0, 1, 2].map(|axis| max[axis] - min[axis]);
+            let [sx, sy, sz] = resize_factors(size, *newsize, *auto);
+            child_mesh.scale(sx, sy, sz);
+            mesh.merge(&child_mesh);
+            Ok(())
+        }
+
         GeometryNode::Mirror { normal, child } => {
-            let matrix = mirror_matrix(*normal);
+            let matrix = convert_matrix(&mirror_matrix(*normal));
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
+            process_node(child, &mut child_mesh, params, control)?;
+            // Rewinds the triangles, as the matrix reflects
             child_mesh.transform(&matrix);
-            // Flip triangle winding for mirrored geometry
-            flip_triangle_winding(&mut child_mesh);
             mesh.merge(&child_mesh);
             Ok(())
         }
@@ -137,7 +501,7 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         GeometryNode::Multmatrix { matrix, child } => {
             let mat = convert_matrix(matrix);
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
+            process_node(child, &mut child_mesh, params, control)?;
             child_mesh.transform(&mat);
             mesh.merge(&child_mesh);
             Ok(())
@@ -146,9 +510,22 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         // =====================================================================
         // BOOLEAN OPERATIONS (use children: Vec<GeometryNode>)
         // =====================================================================
-        
+
+        // 2D booleans clip outlines rather than flat meshes
+        GeometryNode::Union { children } if node.dimension() == Some(2) => {
+            build_boolean_2d(mesh, children, BooleanOp2D::Union, params)
+        }
+
+        GeometryNode::Difference { children } if node.dimension() == Some(2) => {
+            build_boolean_2d(mesh, children, BooleanOp2D::Difference, params)
+        }
+
+        GeometryNode::Intersection { children } if node.dimension() == Some(2) => {
+            build_boolean_2d(mesh, children, BooleanOp2D::Intersection, params)
+        }
+
         GeometryNode::Union { children } => {
-            let meshes = process_children(children, params)?;
+            let meshes = process_operands(children, params, control)?;
             let result = manifold::boolean::union_all(&meshes)?;
             mesh.merge(&result);
             Ok(())
@@ -158,7 +535,7 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
             if children.is_empty() {
                 return Ok(());
             }
-            let meshes = process_children(children, params)?;
+            let meshes = process_operands(children, params, control)?;
             let result = manifold::boolean::difference_all(&meshes)?;
             mesh.merge(&result);
             Ok(())
@@ -168,15 +545,26 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
             if children.is_empty() {
                 return Ok(());
             }
-            let meshes = process_children(children, params)?;
+            let meshes = process_operands(children, params, control)?;
             let result = manifold::boolean::intersection_all(&meshes)?;
             mesh.merge(&result);
             Ok(())
         }
         
         GeometryNode::Hull { children } => {
-            let meshes = process_children(children, params)?;
-            let result = manifold::hull::compute_hull(&meshes)?;
+            // Like OpenSCAD, the first child sets the hull's dimension and
+            // children of the other one are skipped
+            let dimension = node.dimension();
+            let matching: Vec<GeometryNode> = children.iter()
+                .filter(|child| child.dimension() == dimension)
+                .cloned()
+                .collect();
+            let meshes = process_children(&matching, params, control)?;
+            let result = if dimension == Some(2) {
+                manifold::hull::compute_hull_2d(&meshes)?
+            } else {
+                manifold::hull::compute_hull(&meshes)?
+            };
             mesh.merge(&result);
             Ok(())
         }
@@ -184,13 +572,13 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         GeometryNode::Minkowski { children } => {
             if children.len() < 2 {
                 // Single child: just return it
-                let meshes = process_children(children, params)?;
+                let meshes = process_operands(children, params, control)?;
                 if let Some(m) = meshes.first() {
                     mesh.merge(m);
                 }
                 return Ok(());
             }
-            let meshes = process_children(children, params)?;
+            let meshes = process_operands(children, params, control)?;
             let result = manifold::minkowski::compute_minkowski(&meshes)?;
             mesh.merge(&result);
             Ok(())
@@ -216,6 +604,41 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
             Ok(())
         }
 
+        GeometryNode::Text { text, size, font, halign, valign, spacing, fn_ } => {
+            // A glyph curve spans roughly a quarter turn of a circle
+            let params = TextParams {
+                size: *size,
+                font: font.as_deref(),
+                halign: *halign,
+                valign: *valign,
+                spacing: *spacing,
+                curve_segments: (*fn_ / 4).max(1),
+            };
+            cross_section::primitives::build_text_mesh(mesh, text, &params)
+        }
+
+        GeometryNode::Import { file, center, fn_ } if node.dimension() == Some(2) => {
+            let regions = crate::import::import_polygons(file, *center, &params.with_node_fn(*fn_))?;
+            cross_section::primitives::build_regions_mesh(mesh, &regions);
+            Ok(())
+        }
+
+        GeometryNode::Import { file, center, .. } => {
+            let mut imported = crate::import::import_mesh(file)?;
+            if *center {
+                let [dx, dy, dz] = bounding_box_center(&imported);
+                imported.translate(-dx, -dy, -dz);
+            }
+            mesh.merge(&imported);
+            Ok(())
+        }
+
+        GeometryNode::Surface { file, center, invert } => {
+            let rows = crate::import::import_heightmap(file, *invert)?;
+            manifold::constructors::build_surface(mesh, &rows, *center);
+            Ok(())
+        }
+
         // =====================================================================
         // EXTRUSIONS (use single child: Box<GeometryNode>)
         // =====================================================================
@@ -223,20 +646,18 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         GeometryNode::LinearExtrude { height, center, twist, scale, slices, child } => {
             // Build 2D child mesh first
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
-            // For now, just do simple extrusion
-            extrude_mesh(&mut child_mesh, *height, *center, *twist, scale, *slices);
-            mesh.merge(&child_mesh);
+            process_node(child, &mut child_mesh, params, control)?;
+            let extruded = cross_section::extrude::extrude_profile(&child_mesh, *height, *center, *twist, *scale, *slices);
+            mesh.merge(&extruded);
             Ok(())
         }
         
         GeometryNode::RotateExtrude { angle, fn_, child } => {
             // Build 2D child mesh first
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
-            let segments = if *fn_ > 0 { *fn_ } else { 32 };
-            revolve_mesh(&mut child_mesh, *angle, segments);
-            mesh.merge(&child_mesh);
+            process_node(child, &mut child_mesh, params, control)?;
+            let revolved = cross_section::extrude::revolve_profile(&child_mesh, *angle, *fn_, params)?;
+            mesh.merge(&revolved);
             Ok(())
         }
 
@@ -244,18 +665,69 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         // 2D OPERATIONS (use single child: Box<GeometryNode>)
         // =====================================================================
         
-        GeometryNode::Offset { delta, chamfer, child } => {
-            let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
-            offset_mesh(&mut child_mesh, *delta, *chamfer);
-            mesh.merge(&child_mesh);
+        GeometryNode::Offset { delta, round, chamfer, fn_, child } => {
+            let regions = geometry_regions(child, params)?;
+            let join = OffsetJoin::from_params(*round, *chamfer, *fn_);
+            cross_section::primitives::build_regions_mesh(mesh, &offset_polygons(&regions, *delta, join));
             Ok(())
         }
         
         GeometryNode::Projection { cut, child } => {
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
-            project_mesh(&mut child_mesh, *cut);
+            process_node(child, &mut child_mesh, params, control)?;
+            cross_section::primitives::build_regions_mesh(mesh, &projection_regions(&child_mesh, *cut));
+            Ok(())
+        }
+
+        // =====================================================================
+        // EXTENSIONS
+        // =====================================================================
+
+        GeometryNode::Smooth { iterations, lambda, mu, preserve_boundary, feature_angle, child } => {
+            let mut child_mesh = Mesh::new();
+            process_node(child, &mut child_mesh, params, control)?;
+            let smooth_params = manifold::smooth::SmoothParams {
+                iterations: *iterations,
+                lambda: *lambda,
+                mu: *mu,
+                preserve_boundary: *preserve_boundary,
+                feature_angle: *feature_angle,
+            };
+            mesh.merge(&manifold::smooth::smooth_mesh(&child_mesh, &smooth_params));
+            Ok(())
+        }
+
+        GeometryNode::Refine { pieces, max_length, smooth, child } => {
+            let mut child_mesh = Mesh::new();
+            process_node(child, &mut child_mesh, params, control)?;
+            let refine_params = manifold::refine::RefineParams {
+                pieces: *pieces,
+                max_length: *max_length,
+                smooth: *smooth,
+            };
+            mesh.merge(&manifold::refine::refine(&child_mesh, &refine_params));
+            Ok(())
+        }
+
+        GeometryNode::Warp { deformation, child } => {
+            let mut child_mesh = Mesh::new();
+            process_node(child, &mut child_mesh, params, control)?;
+            mesh.merge(&match *deformation {
+                Deformation::Twist { degrees } => manifold::warp::twist(&child_mesh, degrees),
+                Deformation::Taper { scale } => manifold::warp::taper(&child_mesh, scale),
+                Deformation::Bend { degrees } => manifold::warp::bend(&child_mesh, degrees),
+            });
+            Ok(())
+        }
+
+        GeometryNode::Quality { level, simplify, child } => {
+            // Segment counts from the evaluator are already scaled; the
+            // level covers nodes built without one
+            let mut child_mesh = Mesh::new();
+            process_node(child, &mut child_mesh, &params.with_quality(*level), control)?;
+            if *simplify {
+                child_mesh = manifold::simplify::simplify_coplanar(&child_mesh);
+            }
             mesh.merge(&child_mesh);
             Ok(())
         }
@@ -266,7 +738,7 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         
         GeometryNode::Color { rgba, child } => {
             let mut child_mesh = Mesh::new();
-            process_node(child, &mut child_mesh, params)?;
+            process_node(child, &mut child_mesh, params, control)?;
             apply_color(&mut child_mesh, rgba);
             mesh.merge(&child_mesh);
             Ok(())
@@ -274,12 +746,16 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
         
         GeometryNode::Group { children } => {
             for child in children {
-                process_node(child, mesh, params)?;
+                process_node(child, mesh, params, control)?;
             }
             Ok(())
         }
-        
-        GeometryNode::Empty => Ok(()),
+
+        // Highlighted geometry stays in the model; the preview layers
+        // (see `preview`) show both kinds separately
+        GeometryNode::Source { child, .. } | GeometryNode::Highlight { child } => process_node(child, mesh, params, control),
+
+        GeometryNode::Background { .. } | GeometryNode::Empty => Ok(()),
     }
 }
 
@@ -288,11 +764,47 @@ fn process_node(node: &GeometryNode, mesh: &mut Mesh, params: &SegmentParams) ->
 // =============================================================================
 
 /// Process multiple children and return their meshes.
-fn process_children(children: &[GeometryNode], params: &SegmentParams) -> ManifoldResult<Vec<Mesh>> {
-    let mut meshes = Vec::with_capacity(children.len());
-    for child in children {
+///
+/// Siblings are independent, so with [`Reduction::Parallel`] they are
+/// meshed on the thread pool; results keep child order either way.
+///
+/// ## Errors
+///
+/// The first error in child order.
+///
+/// [`Reduction::Parallel`]: crate::manifold::boolean::Reduction::Parallel
+fn process_children(children: &[GeometryNode], params: &SegmentParams, control: &Control) -> ManifoldResult<Vec<Mesh>> {
+    let meshes = parallel::map(children, |child| {
         let mut child_mesh = Mesh::new();
-        process_node(child, &mut child_mesh, params)?;
+        process_node(child, &mut child_mesh, params, control)?;
+        Ok(child_mesh)
+    });
+    non_empty(meshes)
+}
+
+/// Process the operands of a CSG operation.
+///
+/// Unlike [`process_children`], a `Group` operand is merged into one
+/// solid by union first, since concatenating overlapping meshes would
+/// give the boolean a self-intersecting input. Operands are evaluated in
+/// parallel like [`process_children`].
+fn process_operands(children: &[GeometryNode], params: &SegmentParams, control: &Control) -> ManifoldResult<Vec<Mesh>> {
+    let meshes = parallel::map(children, |child| match child {
+        GeometryNode::Group { children } => manifold::boolean::union_all(&process_operands(children, params, control)?),
+        other => {
+            let mut child_mesh = Mesh::new();
+            process_node(other, &mut child_mesh, params, control)?;
+            Ok(child_mesh)
+        }
+    });
+    non_empty(meshes)
+}
+
+/// Collect child results in order, dropping empty meshes.
+fn non_empty(results: Vec<ManifoldResult<Mesh>>) -> ManifoldResult<Vec<Mesh>> {
+    let mut meshes = Vec::with_capacity(results.len());
+    for result in results {
+        let child_mesh = result?;
         if !child_mesh.is_empty() {
             meshes.push(child_mesh);
         }
@@ -300,12 +812,49 @@ fn process_children(children: &[GeometryNode], params: &SegmentParams) -> Manifo
     Ok(meshes)
 }
 
+/// Mesh a 2D boolean of `children` as flat regions.
+fn build_boolean_2d(mesh: &mut Mesh, children: &[GeometryNode], op: BooleanOp2D, params: &SegmentParams) -> ManifoldResult<()> {
+    let regions = boolean_regions(children, op, params)?;
+    cross_section::primitives::build_regions_mesh(mesh, &regions);
+    Ok(())
+}
+
+/// Center of a mesh's axis-aligned bounding box (origin if empty).
+fn bounding_box_center(mesh: &Mesh) -> [f64; 3] {
+    let (min, max) = bounding_box(mesh);
+    [0, 1, 2].map(|axis| (min[axis] + max[axis]) / 2.0)
+}
+
+/// Corners of a mesh's axis-aligned bounding box (both at the origin if empty).
+pub(super) fn bounding_box(mesh: &Mesh) -> ([f64; 3], [f64; 3]) {
+    mesh.bounding_box().unwrap_or(([0.0; 3], [0.0; 3]))
+}
+
+/// Per-axis scale taking a bounding box of `size` to `newsize`, as
+/// `resize()` does.
+///
+/// Axes sized to 0, or with no extent to scale, keep their size unless
+/// `auto` is set for them; then they take the largest scale of the sized
+/// axes, preserving the aspect ratio.
+pub(super) fn resize_factors(size: [f64; 3], newsize: [f64; 3], auto: [bool; 3]) -> [f64; 3] {
+    let explicit = [0, 1, 2].map(|axis| (newsize[axis] > 0.0 && size[axis] > 0.0).then(|| newsize[axis] / size[axis]));
+    let largest = explicit.iter().flatten().copied().reduce(f64::max);
+    [0, 1, 2].map(|axis| match (explicit[axis], largest) {
+        (Some(factor), _) => factor,
+        (None, Some(factor)) if auto[axis] => factor,
+        _ => 1.0,
+    })
+}
+
 /// Create rotation matrix from Euler angles (degrees).
-fn rotation_matrix(angles: [f64; 3]) -> [[f32; 4]; 4] {
+///
+/// Row-major like OpenSCAD's `multmatrix()`; pass it through
+/// [`convert_matrix`] before [`Mesh::transform`].
+pub(super) fn rotation_matrix(angles: [f64; 3]) -> [[f64; 4]; 4] {
     let [ax, ay, az] = angles;
-    let (sx, cx) = (ax.to_radians().sin() as f32, ax.to_radians().cos() as f32);
-    let (sy, cy) = (ay.to_radians().sin() as f32, ay.to_radians().cos() as f32);
-    let (sz, cz) = (az.to_radians().sin() as f32, az.to_radians().cos() as f32);
+    let (sx, cx) = ax.to_radians().sin_cos();
+    let (sy, cy) = ay.to_radians().sin_cos();
+    let (sz, cz) = az.to_radians().sin_cos();
     
     // Combined rotation: Rz * Ry * Rx
     [
@@ -316,9 +865,9 @@ fn rotation_matrix(angles: [f64; 3]) -> [[f32; 4]; 4] {
     ]
 }
 
-/// Create mirror matrix for a plane defined by normal.
-fn mirror_matrix(normal: [f64; 3]) -> [[f32; 4]; 4] {
-    let [nx, ny, nz] = [normal[0] as f32, normal[1] as f32, normal[2] as f32];
+/// Create mirror matrix for a plane defined by normal (row-major).
+pub(super) fn mirror_matrix(normal: [f64; 3]) -> [[f64; 4]; 4] {
+    let [nx, ny, nz] = normal;
     let len = (nx * nx + ny * ny + nz * nz).sqrt();
     if len < 0.0001 {
         return [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
@@ -334,25 +883,10 @@ fn mirror_matrix(normal: [f64; 3]) -> [[f32; 4]; 4] {
     ]
 }
 
-/// Convert 4x4 f64 matrix to f32.
-fn convert_matrix(matrix: &[[f64; 4]; 4]) -> [[f32; 4]; 4] {
-    [
-        [matrix[0][0] as f32, matrix[0][1] as f32, matrix[0][2] as f32, matrix[0][3] as f32],
-        [matrix[1][0] as f32, matrix[1][1] as f32, matrix[1][2] as f32, matrix[1][3] as f32],
-        [matrix[2][0] as f32, matrix[2][1] as f32, matrix[2][2] as f32, matrix[2][3] as f32],
-        [matrix[3][0] as f32, matrix[3][1] as f32, matrix[3][2] as f32, matrix[3][3] as f32],
-    ]
-}
-
-/// Flip triangle winding order (for mirrored geometry).
-fn flip_triangle_winding(mesh: &mut Mesh) {
-    for i in (0..mesh.indices.len()).step_by(3) {
-        mesh.indices.swap(i + 1, i + 2);
-    }
-    // Also flip normals
-    for i in 0..mesh.normals.len() {
-        mesh.normals[i] = -mesh.normals[i];
-    }
+/// Convert a row-major 4x4 matrix to the column-major layout
+/// [`Mesh::transform`] expects.
+fn convert_matrix(matrix: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    [0, 1, 2, 3].map(|col| [0, 1, 2, 3].map(|row| matrix[row][col]))
 }
 
 /// Apply color to mesh vertices.
@@ -367,59 +901,6 @@ fn apply_color(mesh: &mut Mesh, color: &[f64; 4]) {
     }
 }
 
-/// Simple linear extrusion of a 2D mesh.
-///
-/// For now, just duplicates vertices at height and connects with quads.
-fn extrude_mesh(mesh: &mut Mesh, height: f64, center: bool, _twist: f64, _scale: &[f64; 2], _slices: u32) {
-    if mesh.is_empty() {
-        return;
-    }
-    
-    let h = height as f32;
-    let z_offset = if center { -h / 2.0 } else { 0.0 };
-    
-    // Offset existing vertices to bottom
-    for i in (0..mesh.vertices.len()).step_by(3) {
-        mesh.vertices[i + 2] += z_offset;
-    }
-    
-    // Simple: just add top vertices and side faces
-    // This is a placeholder - full implementation would handle twist/scale/slices
-    let bottom_count = mesh.vertex_count();
-    
-    // Duplicate vertices at top
-    for i in 0..bottom_count {
-        let idx = i * 3;
-        mesh.add_vertex(
-            mesh.vertices[idx],
-            mesh.vertices[idx + 1],
-            mesh.vertices[idx + 2] + h,
-            0.0, 0.0, 1.0,
-        );
-    }
-}
-
-/// Simple revolve of a 2D mesh around Z axis.
-///
-/// Placeholder implementation.
-fn revolve_mesh(_mesh: &mut Mesh, _angle: f64, _segments: u32) {
-    // Placeholder - full implementation would revolve the 2D profile
-}
-
-/// Offset a 2D mesh by delta.
-///
-/// Placeholder implementation.
-fn offset_mesh(_mesh: &mut Mesh, _delta: f64, _chamfer: bool) {
-    // Placeholder - full implementation would offset polygon edges
-}
-
-/// Project 3D mesh to 2D.
-///
-/// Placeholder implementation.
-fn project_mesh(_mesh: &mut Mesh, _cut: bool) {
-    // Placeholder - full implementation would project to XY plane
-}
-
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -482,6 +963,79 @@ mod tests {
         assert!((matrix[2][2] - 1.0).abs() < 0.001);
     }
 
+    /// Test rotations turn counter-clockwise about their axis.
+    #[test]
+    fn test_rotate_direction() {
+        let mesh = crate::render("rotate([0, 0, 90]) translate([5, 0, 0]) cube(1);").unwrap();
+        let ys: Vec<f64> = mesh.vertices.chunks_exact(3).map(|p| p[1]).collect();
+        assert!(ys.iter().all(|&y| (4.999..=6.001).contains(&y)), "{:?}", ys);
+        let mesh = crate::render("rotate([90, 0, 0]) translate([0, 5, 0]) cube(1);").unwrap();
+        assert!(mesh.vertices.chunks_exact(3).all(|p| p[2] > 4.999));
+    }
+
+    /// Test resize scales to the target size, with auto axes keeping the aspect ratio.
+    #[test]
+    fn test_resize() {
+        let size = |source: &str| {
+            let (min, max) = bounding_box(&crate::render(source).unwrap());
+            [0, 1, 2].map(|axis| max[axis] - min[axis])
+        };
+        let close = |a: [f64; 3], b: [f64; 3]| (0..3).all(|i| (a[i] - b[i]).abs() < 1e-4);
+        assert!(close(size("resize([20, 5, 0]) cube([2, 4, 6]);"), [20.0, 5.0, 6.0]));
+        assert!(close(size("resize([20, 0, 0], auto = true) cube([2, 4, 6]);"), [20.0, 40.0, 60.0]));
+        assert!(close(size("resize([20, 0, 0], auto = [false, true]) cube([2, 4, 6]);"), [20.0, 40.0, 6.0]));
+        // The largest explicit scale drives the auto axes
+        assert!(close(size("resize([4, 2, 0], auto = true) cube([2, 4, 6]);"), [4.0, 2.0, 12.0]));
+        assert_eq!(resize_factors([0.0, 1.0, 1.0], [5.0, 0.0, 0.0], [true; 3]), [1.0; 3]);
+    }
+
+    /// Test multmatrix() with a shear and a reflection gives a closed, outward-facing solid.
+    #[test]
+    fn test_multmatrix_reflection() {
+        let mesh = crate::render("multmatrix([[-1, 0.5, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0]]) cube(10);").unwrap();
+        assert!((mesh.volume() - 1000.0).abs() < 1e-3, "{}", mesh.volume());
+        assert!(mesh.validate().is_valid());
+        let union = crate::render("union() { cube(10); multmatrix([[-1, 0, 0, 5], [0, 1, 0, 0], [0, 0, 1, 0]]) cube(10); }").unwrap();
+        assert!((union.volume() - 1500.0).abs() < 1e-2, "{}", union.volume());
+    }
+
+    /// Test colors follow their faces through booleans.
+    #[test]
+    fn test_colors_through_booleans() {
+        let color_at = |mesh: &Mesh, x: f64| -> Vec<[f32; 4]> {
+            let colors = mesh.colors.as_ref().unwrap();
+            (0..mesh.vertex_count())
+                .filter(|&i| mesh.vertices[i * 3] == x)
+                .map(|i| [0, 1, 2, 3].map(|k| colors[i * 4 + k]))
+                .collect()
+        };
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let blue = [0.0, 0.0, 1.0, 1.0];
+
+        let union = crate::render("union() { color([1, 0, 0]) cube(10); color([0, 0, 1]) translate([5, 2, 2]) cube(10); }").unwrap();
+        assert_eq!(union.colors.as_ref().unwrap().len(), union.vertex_count() * 4);
+        assert!(color_at(&union, 0.0).iter().all(|&c| c == red));
+        assert!(color_at(&union, 15.0).iter().all(|&c| c == blue));
+
+        // The hole's walls take the subtracted part's color
+        let difference = crate::render("difference() { color([1, 0, 0]) cube(10); color([0, 0, 1]) translate([5, 2, 2]) cube(10); }").unwrap();
+        assert!(color_at(&difference, 0.0).iter().all(|&c| c == red));
+        assert!(color_at(&difference, 5.0).contains(&blue));
+        assert!(difference.validate().is_valid());
+    }
+
+    /// Test named colors reach the vertex colors of a render.
+    #[test]
+    fn test_named_colors() {
+        let mesh = crate::render("union() { color(\"red\") cube(1); color(\"blue\") translate([2, 0, 0]) cube(1); }").unwrap();
+        let colors = mesh.colors.as_ref().unwrap();
+        assert_eq!(colors.len(), mesh.vertex_count() * 4);
+        for i in 0..mesh.vertex_count() {
+            let expected = if mesh.vertices[i * 3] < 1.5 { [1.0, 0.0, 0.0, 1.0] } else { [0.0, 0.0, 1.0, 1.0] };
+            assert_eq!(colors[i * 4..i * 4 + 4], expected);
+        }
+    }
+
     /// Test mirror matrix.
     #[test]
     fn test_mirror_matrix() {
@@ -491,4 +1045,168 @@ mod tests {
         assert!((matrix[1][1] - 1.0).abs() < 0.001);
         assert!((matrix[2][2] - 1.0).abs() < 0.001);
     }
+
+    /// Test centered import moves the bounding box to the origin.
+    #[test]
+    fn test_import_center() {
+        let part = crate::render("translate([10, 10, 10]) cube(4);").unwrap();
+        crate::import::register_file("test_import_center.stl", part.to_stl_binary());
+
+        let node = GeometryNode::Import { file: "test_import_center.stl".to_string(), center: true, fn_: 0 };
+        let mesh = geometry_to_mesh(&node).unwrap();
+        assert_eq!(mesh.triangle_count(), 12);
+        assert_eq!(bounding_box_center(&mesh), [0.0, 0.0, 0.0]);
+    }
+
+    /// Test an imported SVG is 2D: it extrudes, with $fn setting its
+    /// curve resolution.
+    #[test]
+    fn test_import_svg() {
+        let svg = br#"<svg viewBox="0 0 96 96" width="96" height="96"><circle cx="48" cy="48" r="48"/><rect x="40" y="40" width="10" height="10"/></svg>"#;
+        crate::import::register_file("test_import_svg.svg", svg.to_vec());
+        let mesh = crate::render(r#"linear_extrude(2) import("test_import_svg.svg", center = true, $fn = 12);"#).unwrap();
+        assert!(crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&mesh).1.is_manifold());
+        let (min, max) = bounding_box(&mesh);
+        assert!((max[0] - min[0] - 25.4).abs() < 1e-4 && (max[2] - 2.0).abs() < 1e-6, "{:?} {:?}", min, max);
+
+        let loops = crate::render_outlines(r#"import("test_import_svg.svg", $fn = 12);"#).unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].points.len(), 12);
+    }
+
+    /// Test surface() meshes a registered heightmap into a closed solid.
+    #[test]
+    fn test_surface() {
+        crate::import::register_file("test_surface.dat", b"1 2 3\n4 5 6\n7 8 9\n".to_vec());
+        let mesh = crate::render(r#"surface(file = "test_surface.dat", center = true);"#).unwrap();
+        assert!(crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&mesh).1.is_manifold());
+        assert_eq!(bounding_box_center(&mesh), [0.0, 0.0, 4.5]);
+
+        assert!(matches!(crate::render(r#"surface("missing.dat");"#), Err(crate::ManifoldError::ImportError(_))));
+    }
+
+    /// Test quality scopes coarsen curves and simplify flat faces.
+    #[test]
+    fn test_quality_scope() {
+        let coarse = crate::render("quality(0.5) sphere(5, $fn = 20);").unwrap();
+        let expected = crate::render("sphere(5, $fn = 10);").unwrap();
+        assert_eq!(coarse.triangle_count(), expected.triangle_count());
+
+        let pyramid = "polyhedron([[0,0,0],[2,0,0],[2,2,0],[0,2,0],[1,1,0],[1,1,1]], \
+            [[0,1,4],[1,2,4],[2,3,4],[3,0,4],[0,5,1],[1,5,2],[2,5,3],[3,5,0]]);";
+        let kept = crate::render(&format!("quality(1) {}", pyramid)).unwrap();
+        let simplified = crate::render(&format!("quality(1, simplify = true) {}", pyramid)).unwrap();
+        assert_eq!(kept.triangle_count(), 8);
+        assert_eq!(simplified.triangle_count(), 6);
+    }
+
+    /// Test hull() of 2D children is a flat polygon, including transformed
+    /// and mixed-in 3D children.
+    #[test]
+    fn test_hull_2d() {
+        let mesh = crate::render("hull() { circle(1, $fn = 8); translate([5, 0]) circle(1, $fn = 8); }").unwrap();
+        assert_eq!(mesh.triangle_count(), 8);
+        assert!(mesh.vertices.chunks_exact(3).all(|p| p[2] == 0.0));
+
+        let node = GeometryNode::Hull {
+            children: vec![
+                GeometryNode::Square { size: [1.0, 1.0], center: false },
+                GeometryNode::Cube { size: [9.0; 3], center: false },
+                GeometryNode::Rotate { angles: [0.0, 0.0, 45.0], child: Box::new(GeometryNode::Square { size: [1.0, 1.0], center: false }) },
+            ],
+        };
+        let mesh = geometry_to_mesh(&node).unwrap();
+        assert!(mesh.vertices.chunks_exact(3).all(|p| p[2] == 0.0 && p[0] < 1.5 && p[1] < 1.5));
+        assert_eq!(mesh.vertex_count(), 5);
+    }
+
+    /// Test hull() of transformed 3D children encloses them all.
+    #[test]
+    fn test_hull_3d_transformed() {
+        let mesh = crate::render("hull() { rotate([0, 0, 45]) cube(2, center = true); translate([0, 0, 5]) sphere(1, $fn = 8); }").unwrap();
+        let (_, report) = crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&mesh);
+        assert!(report.is_manifold());
+        let top = mesh.vertices.chunks_exact(3).fold(f64::MIN, |m, p| m.max(p[2]));
+        let corner = mesh.vertices.chunks_exact(3).fold(f64::MIN, |m, p| m.max(p[1]));
+        assert!(top > 5.9 && top <= 6.0, "{}", top);
+        assert!((corner - 2f64.sqrt()).abs() < 1e-5, "{}", corner);
+    }
+
+    /// Test offset() then linear_extrude() gives a closed part with round
+    /// outer corners and a shrunk, sharp-cornered hole.
+    #[test]
+    fn test_offset_extruded() {
+        let mesh = crate::render("linear_extrude(2) offset(r = 1, $fn = 16) \
+            polygon([[0, 0], [10, 0], [10, 10], [0, 10], [3, 3], [7, 3], [7, 7], [3, 7]], \
+            [[0, 1, 2, 3], [4, 5, 6, 7]]);").unwrap();
+        let (_, report) = crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&mesh);
+        assert!(report.is_manifold());
+        let points: Vec<&[f64]> = mesh.vertices.chunks_exact(3).collect();
+        let max_x = points.iter().fold(f64::MIN, |m, p| m.max(p[0]));
+        assert!((max_x - 11.0).abs() < 1e-5, "{}", max_x);
+        // Rounded outer corner, sharp hole corner
+        assert!(!points.iter().any(|p| p[0] > 10.8 && p[1] > 10.8));
+        assert!(points.iter().any(|p| p[0] == 4.0 && p[1] == 4.0));
+    }
+
+    /// Test 2D booleans mesh flat and extrude to a closed part with a hole.
+    #[test]
+    fn test_boolean_2d() {
+        let plate = "difference() { square(10); translate([3, 3]) square(4); }";
+        let flat = crate::render(plate).unwrap();
+        assert!(flat.vertices.chunks_exact(3).all(|p| p[2] == 0.0));
+        assert_eq!(flat.triangle_count(), 8);
+
+        let solid = crate::render(&format!("linear_extrude(2) {}", plate)).unwrap();
+        let (_, report) = crate::mesh::halfedge::HalfEdgeMesh::from_mesh(&solid);
+        assert!(report.is_manifold());
+        // Outer, hole and cap walls: 4 + 4 quads, 8 triangles per cap
+        assert_eq!(solid.triangle_count(), 32);
+    }
+
+    /// Test projection meshes the flat shadow of its child, which extrudes
+    /// like any other 2D shape.
+    #[test]
+    fn test_projection() {
+        let flat = crate::render("projection() rotate([0, 0, 45]) cylinder(h = 5, r1 = 3, r2 = 1, $fn = 4);").unwrap();
+        assert!(flat.triangle_count() > 0);
+        assert!(flat.vertices.chunks_exact(3).all(|p| p[2] == 0.0));
+
+        let solid = crate::render("linear_extrude(2) projection() cube([3, 4, 5]);").unwrap();
+        assert!((solid.volume() - 24.0).abs() < 1e-9);
+    }
+
+    /// Test a group operand of a boolean is merged like a union.
+    #[test]
+    fn test_group_operand_is_unioned() {
+        let bar = |size: [f64; 3]| GeometryNode::Cube { size, center: true };
+        let cross = vec![bar([6.0, 1.0, 1.0]), bar([1.0, 6.0, 1.0])];
+        let with = |operand: GeometryNode| GeometryNode::Difference { children: vec![bar([4.0; 3]), operand] };
+
+        let grouped = geometry_to_mesh(&with(GeometryNode::Group { children: cross.clone() })).unwrap();
+        let unioned = geometry_to_mesh(&with(GeometryNode::Union { children: cross })).unwrap();
+        assert!(!grouped.is_empty());
+        assert_eq!(grouped.triangle_count(), unioned.triangle_count());
+        assert_eq!(grouped.vertex_count(), unioned.vertex_count());
+    }
+
+    /// Test sibling subtrees meshed in parallel give the serial mesh.
+    #[test]
+    fn test_parallel_subtrees() {
+        use crate::manifold::boolean::{reduction, set_reduction, Reduction};
+
+        let source = "union() { \
+            hull() { cube(2); translate([4, 0, 0]) sphere(1, $fn = 10); } \
+            difference() { translate([0, 4, 0]) cube(3); translate([1, 5, 1]) cube(1); } \
+            minkowski() { translate([0, 0, 4]) cube(2); sphere(0.5, $fn = 8); } }";
+        let previous = reduction();
+        set_reduction(Reduction::Serial);
+        let serial = crate::render(source).unwrap();
+        set_reduction(Reduction::Parallel);
+        let parallel = crate::render(source).unwrap();
+        set_reduction(previous);
+
+        assert!(!serial.is_empty());
+        assert_eq!(crate::determinism::first_difference(&serial, &parallel), None);
+    }
 }
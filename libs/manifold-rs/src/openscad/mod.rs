@@ -7,6 +7,12 @@
 //!
 //! - `segments`: $fn/$fa/$fs → circularSegments conversion
 //! - `from_ir`: GeometryNode → Mesh conversion
+//! - `outlines`: GeometryNode → 2D outline loops
+//! - `drawing`: Outline loops → SVG and DXF
+//! - `cache`: Meshes of subtrees kept between renders
+//! - `preview`: `#` and `%` geometry split off for the preview layers
+//! - `pick`: The module call behind a point on the model
+//! - `provenance`: The geometry node behind each triangle
 //!
 //! ## OpenSCAD Segment Calculation
 //!
@@ -19,6 +25,12 @@
 
 pub mod segments;
 pub mod from_ir;
+pub mod outlines;
+pub mod drawing;
+pub mod cache;
+pub mod preview;
+pub mod pick;
+pub mod provenance;
 
 // Re-export main types
 pub use segments::SegmentParams;
@@ -0,0 +1,564 @@
+//! # 2D Outlines
+//!
+//! Converts 2D geometry to closed polyline loops instead of triangles, for
+//! CAM and laser workflows that need toolpaths rather than a mesh.
+//!
+//! ## Output
+//!
+//! Each region is an outer loop wound counter-clockwise, followed by its
+//! holes wound clockwise. Holes point back at their outer loop, so a
+//! toolpath generator can cut holes before the part that contains them.
+//!
+//! ## Exact and Traced Loops
+//!
+//! Primitives, text, SVG imports, transforms, `color()`, `quality()` and
+//! groups map straight to loops with the evaluated vertices; `offset()`
+//! offsets those loops and booleans clip them, and `projection()` takes
+//! them from its child's mesh. Operations without a 2D kernel yet
+//! (`hull()`, `minkowski()`) are meshed, and their loops
+//! traced from the boundary edges of the triangulation; points left on
+//! straight edges by the triangulation are dropped.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::openscad::outlines::{geometry_to_outlines, Winding};
+//! use openscad_eval::GeometryNode;
+//!
+//! let node = GeometryNode::Square { size: [2.0, 1.0], center: false };
+//! let loops = geometry_to_outlines(&node).unwrap();
+//! assert_eq!(loops.len(), 1);
+//! assert_eq!(loops[0].winding, Winding::CounterClockwise);
+//! assert_eq!(loops[0].points, vec![[0.0, 0.0], [2.0, 0.0], [2.0, 1.0], [0.0, 1.0]]);
+//! ```
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use openscad_eval::GeometryNode;
+
+use super::from_ir::{geometry_to_mesh, mirror_matrix, resize_factors, rotation_matrix};
+use super::SegmentParams;
+use crate::cross_section::boolean::{boolean_contours, boolean_polygons, BooleanOp2D};
+use crate::cross_section::ops::{offset_polygons, OffsetJoin};
+use crate::error::{ManifoldError, ManifoldResult};
+use crate::font::outline::group_contours;
+use crate::font::{text_polygons, Polygon2D, TextParams};
+use crate::mesh::slice::Plane;
+use crate::mesh::Mesh;
+
+/// Relative tolerance below which a turn in a traced loop counts as straight.
+const COLLINEAR_EPSILON: f64 = 1e-9;
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+/// Direction a loop runs in, seen from +Z.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Winding {
+    /// Counter-clockwise (positive area), used for outer loops.
+    CounterClockwise,
+    /// Clockwise (negative area), used for holes.
+    Clockwise,
+}
+
+/// A closed polyline loop of a 2D result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineLoop {
+    /// Loop vertices; the last one connects back to the first.
+    pub points: Vec<[f64; 2]>,
+    /// Whether this loop bounds a hole.
+    pub is_hole: bool,
+    /// Direction of travel.
+    pub winding: Winding,
+    /// For holes, index of the outer loop containing them.
+    pub parent: Option<usize>,
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Convert 2D geometry to outline loops.
+///
+/// ## Parameters
+///
+/// - `node`: Evaluated 2D geometry
+///
+/// ## Returns
+///
+/// Loops in region order: each outer loop followed by its holes. Empty
+/// for empty geometry.
+///
+/// ## Errors
+///
+/// `ManifoldError::GeometryError` if the geometry is 3D.
+pub fn geometry_to_outlines(node: &GeometryNode) -> ManifoldResult<Vec<OutlineLoop>> {
+    if node.dimension() == Some(3) {
+        return Err(ManifoldError::GeometryError(
+            "outlines need a 2D result, but the geometry is 3D".to_string(),
+        ));
+    }
+
+    let mut regions = Vec::new();
+    collect_regions(node, &SegmentParams::default(), &mut regions)?;
+
+    let mut loops = Vec::new();
+    for region in regions {
+        let parent = loops.len();
+        loops.push(OutlineLoop {
+            points: region.outer,
+            is_hole: false,
+            winding: Winding::CounterClockwise,
+            parent: None,
+        });
+        loops.extend(region.holes.into_iter().map(|points| OutlineLoop {
+            points,
+            is_hole: true,
+            winding: Winding::Clockwise,
+            parent: Some(parent),
+        }));
+    }
+    Ok(loops)
+}
+
+// =============================================================================
+// REGION COLLECTION
+// =============================================================================
+
+/// Filled regions of 2D geometry, CCW outer loops with CW holes.
+///
+/// Used by operations that work on loops rather than triangles, such as
+/// `offset()`.
+pub(crate) fn geometry_regions(node: &GeometryNode, params: &SegmentParams) -> ManifoldResult<Vec<Polygon2D>> {
+    let mut regions = Vec::new();
+    collect_regions(node, params, &mut regions)?;
+    Ok(regions)
+}
+
+/// Regions of a 2D boolean, one operand per child.
+///
+/// As in OpenSCAD, 3D children are skipped, and so are empty ones, as the
+/// 3D path does.
+pub(crate) fn boolean_regions(
+    children: &[GeometryNode],
+    op: BooleanOp2D,
+    params: &SegmentParams,
+) -> ManifoldResult<Vec<Polygon2D>> {
+    let mut operands = Vec::with_capacity(children.len());
+    for child in children.iter().filter(|child| child.dimension() == Some(2)) {
+        let regions = geometry_regions(child, params)?;
+        if !regions.is_empty() {
+            operands.push(regions);
+        }
+    }
+    Ok(boolean_polygons(&operands, op))
+}
+
+/// Append the regions of a node, CCW outer loops with CW holes.
+fn collect_regions(node: &GeometryNode, params: &SegmentParams, out: &mut Vec<Polygon2D>) -> ManifoldResult<()> {
+    match node {
+        GeometryNode::Circle { radius, fn_ } => {
+            let segments = if *fn_ > 0 { *fn_ } else { params.calculate_segments(*radius) };
+            let n = segments.max(3);
+            let outer = (0..n)
+                .map(|i| {
+                    let theta = 2.0 * PI * f64::from(i) / f64::from(n);
+                    [radius * theta.cos(), radius * theta.sin()]
+                })
+                .collect();
+            out.extend(group_contours(vec![outer]));
+        }
+
+        GeometryNode::Square { size, center } => {
+            let [w, h] = *size;
+            let [x, y] = if *center { [-w / 2.0, -h / 2.0] } else { [0.0, 0.0] };
+            out.extend(group_contours(vec![vec![[x, y], [x + w, y], [x + w, y + h], [x, y + h]]]));
+        }
+
+        GeometryNode::Polygon { points, paths } => {
+            let contours = match paths.as_deref() {
+                Some(paths) if !paths.is_empty() => paths.iter()
+                    .map(|path| path.iter().filter_map(|&i| points.get(i).copied()).collect())
+                    .collect(),
+                _ => vec![points.clone()],
+            };
+            out.extend(group_contours(contours));
+        }
+
+        GeometryNode::Text { text, size, font, halign, valign, spacing, fn_ } => {
+            // Same curve resolution as the mesh path
+            let params = TextParams {
+                size: *size,
+                font: font.as_deref(),
+                halign: *halign,
+                valign: *valign,
+                spacing: *spacing,
+                curve_segments: (*fn_ / 4).max(1),
+            };
+            out.extend(text_polygons(text, &params)?);
+        }
+
+        GeometryNode::Import { file, center, fn_ } if node.dimension() == Some(2) => {
+            out.extend(crate::import::import_polygons(file, *center, &params.with_node_fn(*fn_))?);
+        }
+
+        GeometryNode::Translate { offset, child } => {
+            let [dx, dy, _] = *offset;
+            let matrix = [[1.0, 0.0, 0.0, dx], [0.0, 1.0, 0.0, dy], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+            collect_transformed(child, &matrix, params, out)?;
+        }
+
+        GeometryNode::Rotate { angles, child } => {
+            collect_transformed(child, &rotation_matrix(*angles), params, out)?;
+        }
+
+        GeometryNode::Scale { factors, child } => {
+            let [sx, sy, sz] = *factors;
+            let matrix = [[sx, 0.0, 0.0, 0.0], [0.0, sy, 0.0, 0.0], [0.0, 0.0, sz, 0.0], [0.0, 0.0, 0.0, 1.0]];
+            collect_transformed(child, &matrix, params, out)?;
+        }
+
+        GeometryNode::Resize { newsize, auto, child } => {
+            let mut regions = Vec::new();
+            collect_regions(child, params, &mut regions)?;
+            let (min, max) = regions.iter()
+                .flat_map(|region| region.outer.iter().chain(region.holes.iter().flatten()))
+                .fold(([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]), |(min, max), p| {
+                    ([min[0].min(p[0]), min[1].min(p[1])], [max[0].max(p[0]), max[1].max(p[1])])
+                });
+            let [sx, sy, _] = resize_factors([max[0] - min[0], max[1] - min[1], 0.0], *newsize, *auto);
+            for region in &mut regions {
+                for p in region.outer.iter_mut().chain(region.holes.iter_mut().flatten()) {
+                    *p = [p[0] * sx, p[1] * sy];
+                }
+            }
+            out.extend(regions);
+        }
+
+        GeometryNode::Mirror { normal, child } => {
+            collect_transformed(child, &mirror_matrix(*normal), params, out)?;
+        }
+
+        GeometryNode::Multmatrix { matrix, child } => {
+            collect_transformed(child, matrix, params, out)?;
+        }
+
+        GeometryNode::Color { child, .. }
+        | GeometryNode::Quality { child, .. }
+        | GeometryNode::Source { child, .. }
+        | GeometryNode::Highlight { child } => {
+            collect_regions(child, params, out)?;
+        }
+
+        GeometryNode::Offset { delta, round, chamfer, fn_, child } => {
+            let regions = geometry_regions(child, params)?;
+            out.extend(offset_polygons(&regions, *delta, OffsetJoin::from_params(*round, *chamfer, *fn_)));
+        }
+
+        GeometryNode::Union { children } if node.dimension() == Some(2) => {
+            out.extend(boolean_regions(children, BooleanOp2D::Union, params)?);
+        }
+
+        GeometryNode::Difference { children } if node.dimension() == Some(2) => {
+            out.extend(boolean_regions(children, BooleanOp2D::Difference, params)?);
+        }
+
+        GeometryNode::Intersection { children } if node.dimension() == Some(2) => {
+            out.extend(boolean_regions(children, BooleanOp2D::Intersection, params)?);
+        }
+
+        GeometryNode::Group { children } => {
+            for child in children {
+                collect_regions(child, params, out)?;
+            }
+        }
+
+        GeometryNode::Projection { cut, child } => {
+            out.extend(projection_regions(&geometry_to_mesh(child)?, *cut));
+        }
+
+        // Background geometry is not part of the result
+        GeometryNode::Empty | GeometryNode::Background { .. } => {}
+
+        // No exact 2D path yet: trace the meshed result
+        other => {
+            out.extend(group_contours(boundary_contours(&geometry_to_mesh(other)?)));
+        }
+    }
+    Ok(())
+}
+
+/// Append the regions of `child` mapped through a row-major 4x4 matrix.
+///
+/// Only the XY part applies to 2D geometry. Loops are rewound if the
+/// matrix mirrors, and dropped if it flattens them to nothing.
+fn collect_transformed(
+    child: &GeometryNode,
+    matrix: &[[f64; 4]; 4],
+    params: &SegmentParams,
+    out: &mut Vec<Polygon2D>,
+) -> ManifoldResult<()> {
+    let mut regions = Vec::new();
+    collect_regions(child, params, &mut regions)?;
+
+    let apply = |points: Vec<[f64; 2]>| -> Vec<[f64; 2]> {
+        points.into_iter()
+            .map(|[x, y]| [
+                matrix[0][0] * x + matrix[0][1] * y + matrix[0][3],
+                matrix[1][0] * x + matrix[1][1] * y + matrix[1][3],
+            ])
+            .collect()
+    };
+    let contours = regions.into_iter()
+        .flat_map(|region| std::iter::once(region.outer).chain(region.holes))
+        .map(apply)
+        .collect();
+    out.extend(group_contours(contours));
+    Ok(())
+}
+
+// =============================================================================
+// PROJECTION
+// =============================================================================
+
+/// Regions of `projection()` of a 3D mesh onto the XY plane.
+///
+/// With `cut`, the section of the mesh by the plane `z = 0`. Otherwise the
+/// shadow of the mesh: the union of its upward-facing triangles, flattened.
+/// For a closed mesh those cover every point of the shadow, and the
+/// boundary loops of each patch of them wind around a point as often as
+/// the patch's triangles do, so only the patch boundaries need merging.
+pub(crate) fn projection_regions(mesh: &Mesh, cut: bool) -> Vec<Polygon2D> {
+    if cut {
+        return mesh.slice(&Plane::z(0.0));
+    }
+
+    let vertex = |i: u32| {
+        let i = i as usize * 3;
+        [mesh.vertices[i], mesh.vertices[i + 1]]
+    };
+    let mut upward = mesh.clone();
+    upward.indices = mesh.indices.chunks_exact(3)
+        .filter(|t| {
+            let [a, b, c] = [0, 1, 2].map(|k| vertex(t[k]));
+            (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]) > 0.0
+        })
+        .flatten()
+        .copied()
+        .collect();
+    group_contours(boolean_contours(&[boundary_contours(&upward)], BooleanOp2D::Union))
+}
+
+// =============================================================================
+// BOUNDARY TRACING
+// =============================================================================
+
+/// Trace the boundary loops of a flat triangle mesh in the XY plane.
+///
+/// Edges shared by two triangles with opposite directions are interior;
+/// the rest are chained into closed loops. Chains that don't close (from
+/// T-junctions) are dropped.
+fn boundary_contours(mesh: &Mesh) -> Vec<Vec<[f64; 2]>> {
+    let (positions, remap) = mesh.welded_positions();
+
+    // Directed boundary edges, cancelling each edge against its reverse
+    let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+    for t in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| remap[t[k] as usize]);
+        if a == b || b == c || c == a {
+            continue;
+        }
+        for (from, to) in [(a, b), (b, c), (c, a)] {
+            match edges.get_mut(&(to, from)) {
+                Some(count) if *count > 1 => *count -= 1,
+                Some(_) => {
+                    edges.remove(&(to, from));
+                }
+                None => *edges.entry((from, to)).or_default() += 1,
+            }
+        }
+    }
+
+    let mut next: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut starts: Vec<(u32, u32)> = edges.into_iter()
+        .flat_map(|(edge, count)| std::iter::repeat_n(edge, count))
+        .collect();
+    // Deterministic loop order regardless of hashing
+    starts.sort_unstable();
+    for &(from, to) in &starts {
+        next.entry(from).or_default().push(to);
+    }
+
+    let point = |i: u32| {
+        let p = positions[i as usize];
+        [p[0], p[1]]
+    };
+    let mut contours = Vec::new();
+    for (start, _) in starts {
+        let mut chain = vec![start];
+        let mut current = start;
+        let closed = loop {
+            let Some(to) = next.get_mut(&current).and_then(Vec::pop) else {
+                break false;
+            };
+            if to == start {
+                break true;
+            }
+            chain.push(to);
+            current = to;
+        };
+        if closed {
+            contours.push(drop_collinear(chain.into_iter().map(point).collect()));
+        }
+    }
+    contours
+}
+
+/// Remove loop points lying on the straight line between their neighbours.
+fn drop_collinear(mut points: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    let extent = points.iter().fold(0.0f64, |m, p| m.max(p[0].abs()).max(p[1].abs()));
+    let tolerance = COLLINEAR_EPSILON * extent.max(1.0).powi(2);
+    let mut i = 0;
+    while points.len() > 3 && i < points.len() {
+        let n = points.len();
+        let (a, b, c) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+        let turn = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+        if turn.abs() <= tolerance {
+            points.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    points
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Twice the signed area of a loop.
+    fn area(points: &[[f64; 2]]) -> f64 {
+        (0..points.len())
+            .map(|i| {
+                let (p, q) = (points[i], points[(i + 1) % points.len()]);
+                p[0] * q[1] - q[0] * p[1]
+            })
+            .sum::<f64>()
+            / 2.0
+    }
+
+    /// Test a polygon with a hole keeps its exact points, classified.
+    #[test]
+    fn test_polygon_with_hole() {
+        let node = GeometryNode::Polygon {
+            points: vec![[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0], [2.0, 2.0], [8.0, 2.0], [8.0, 8.0], [2.0, 8.0]],
+            paths: Some(vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]),
+        };
+        let loops = geometry_to_outlines(&node).unwrap();
+        assert_eq!(loops.len(), 2);
+        assert!(!loops[0].is_hole && loops[0].winding == Winding::CounterClockwise);
+        assert!(loops[1].is_hole && loops[1].winding == Winding::Clockwise);
+        assert_eq!(loops[1].parent, Some(0));
+        assert_eq!(area(&loops[0].points), 100.0);
+        assert_eq!(area(&loops[1].points), -36.0);
+    }
+
+    /// Test mirroring rewinds loops so outers stay counter-clockwise.
+    #[test]
+    fn test_mirror_keeps_winding() {
+        let node = GeometryNode::Mirror {
+            normal: [1.0, 0.0, 0.0],
+            child: Box::new(GeometryNode::Circle { radius: 2.0, fn_: 6 }),
+        };
+        let loops = geometry_to_outlines(&node).unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].points.len(), 6);
+        assert!(area(&loops[0].points) > 0.0);
+    }
+
+    /// Test resize scales outlines exactly, ignoring the z target.
+    #[test]
+    fn test_resize() {
+        let node = GeometryNode::Resize {
+            newsize: [10.0, 0.0, 5.0],
+            auto: [true; 3],
+            child: Box::new(GeometryNode::Square { size: [2.0, 4.0], center: false }),
+        };
+        let loops = geometry_to_outlines(&node).unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(area(&loops[0].points), 200.0);
+    }
+
+    /// Test traced loops of a meshed operation lose triangulation points.
+    #[test]
+    fn test_boundary_tracing() {
+        let mut mesh = Mesh::new();
+        crate::cross_section::primitives::build_square_mesh(&mut mesh, [4.0, 2.0], false);
+        let mut split = Mesh::new();
+        // Square as four triangles around an interior point and an edge midpoint
+        let p = [[0.0, 0.0], [2.0, 0.0], [4.0, 0.0], [4.0, 2.0], [0.0, 2.0], [2.0, 1.0]];
+        let v: Vec<u32> = p.iter().map(|q| split.add_vertex(q[0], q[1], 0.0, 0.0, 0.0, 1.0)).collect();
+        for [a, b, c] in [[0, 1, 5], [1, 2, 5], [2, 3, 5], [3, 4, 5], [4, 0, 5]] {
+            split.add_triangle(v[a], v[b], v[c]);
+        }
+
+        for mesh in [mesh, split] {
+            let contours = boundary_contours(&mesh);
+            assert_eq!(contours.len(), 1);
+            assert_eq!(contours[0].len(), 4);
+            assert_eq!(area(&contours[0]), 8.0);
+        }
+    }
+
+    /// Test projection merges the shadows of all faces, and a cut keeps
+    /// the holes of the section.
+    #[test]
+    fn test_projection() {
+        let project = |source: &str| -> Vec<OutlineLoop> {
+            geometry_to_outlines(&openscad_eval::evaluate(source).unwrap().geometry).unwrap()
+        };
+        let loops = project("projection() { cube(4); translate([2, 2, 5]) cube(4); }");
+        assert_eq!(loops.len(), 1);
+        assert_eq!(area(&loops[0].points), 28.0);
+
+        let loops = project("projection() rotate([90, 0, 0]) linear_extrude(1) difference() { square(10); translate([2, 2]) square(6); }");
+        assert_eq!(loops.len(), 1);
+        assert!((area(&loops[0].points) - 10.0).abs() < 1e-9);
+
+        let loops = project("projection(cut = true) difference() { cube(10, center = true); cylinder(h = 20, r = 2, center = true, $fn = 6); }");
+        assert_eq!(loops.len(), 2);
+        assert!(loops[1].is_hole);
+        assert_eq!(area(&loops[0].points), 100.0);
+        assert_eq!(project("projection(cut = true) translate([0, 0, 1]) cube(1);"), []);
+    }
+
+    /// Test 2D booleans give exact loops, skipping 3D children.
+    #[test]
+    fn test_boolean_loops() {
+        let square = |x: f64| GeometryNode::Translate {
+            offset: [x, 0.0, 0.0],
+            child: Box::new(GeometryNode::Square { size: [2.0, 2.0], center: false }),
+        };
+        let node = GeometryNode::Difference {
+            children: vec![square(0.0), GeometryNode::Cube { size: [9.0; 3], center: false }, square(1.0)],
+        };
+        let loops = geometry_to_outlines(&node).unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].points.len(), 4);
+        assert_eq!(area(&loops[0].points), 2.0);
+    }
+
+    /// Test 3D geometry is rejected.
+    #[test]
+    fn test_rejects_3d() {
+        let node = GeometryNode::Cube { size: [1.0; 3], center: false };
+        assert!(geometry_to_outlines(&node).is_err());
+        assert!(geometry_to_outlines(&GeometryNode::Empty).unwrap().is_empty());
+    }
+}
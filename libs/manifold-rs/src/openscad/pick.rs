@@ -0,0 +1,131 @@
+//! # Picking
+//!
+//! Finds the module call behind a point on a rendered model, so clicking
+//! a shape in a viewer can jump the editor to the code that made it.
+//!
+//! The ray is cast against the finished mesh for the triangle and point
+//! hit, and the triangle's node id (see [`provenance`](super::provenance))
+//! names the node that made it:
+//!
+//! ```text
+//! ray → triangle hit → node id → NodeTable → kind, span
+//! ```
+//!
+//! The answer is the innermost module call whose geometry holds the hit.
+//! Surfaces a difference cuts belong to the child that was subtracted, so
+//! clicking the inside of a hole finds the call that made the hole.
+//! Hulls, extrusions and other operations build new surfaces; their own
+//! call is the answer.
+//!
+//! Spans exist only in trees evaluated with
+//! [`EvalOptions::sources`](openscad_eval::EvalOptions::sources); in other
+//! trees the hit has none.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::openscad::from_ir::geometry_to_mesh;
+//! use manifold_rs::openscad::pick::pick;
+//! use manifold_rs::openscad::provenance::NodeTable;
+//! use openscad_eval::EvalOptions;
+//!
+//! let source = "cube(10);\ntranslate([20, 0, 0]) sphere(5);";
+//! let options = EvalOptions { sources: true, ..EvalOptions::default() };
+//! let geometry = openscad_eval::evaluate_with_options(source, &options).unwrap().geometry;
+//! let mesh = geometry_to_mesh(&geometry).unwrap();
+//!
+//! let picked = pick(&NodeTable::new(&geometry), &mesh, [20.0, 0.0, 50.0], [0.0, 0.0, -1.0]).unwrap();
+//! assert_eq!(picked.span.unwrap().start.line, 1);
+//! assert!(picked.hit.point[2] > 4.5 && picked.hit.point[2] <= 5.0);
+//! ```
+
+use openscad_eval::Span;
+
+use crate::mesh::ray::RayHit;
+use crate::mesh::Mesh;
+use super::provenance::NodeTable;
+
+/// A point picked on a model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pick {
+    /// The triangle of the model's mesh hit, and where.
+    pub hit: RayHit,
+    /// Id of the geometry node that made the triangle, if known.
+    pub node: Option<u32>,
+    /// Span of the innermost module call whose geometry holds the hit.
+    pub span: Option<Span>,
+}
+
+/// Pick the point of `mesh` under the ray from `origin` along
+/// `direction`, with its source looked up in `table`, the table of the
+/// tree `mesh` was made from.
+///
+/// ## Returns
+///
+/// `None` if the ray misses the mesh.
+#[must_use]
+pub fn pick(table: &NodeTable, mesh: &Mesh, origin: [f64; 3], direction: [f64; 3]) -> Option<Pick> {
+    let hit = mesh.ray_cast(origin, direction)?;
+    let node = mesh.node_of(hit.triangle as usize);
+    let span = node.and_then(|node| table.span(node));
+    Some(Pick { hit, node, span })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openscad::from_ir::geometry_to_mesh;
+    use openscad_eval::EvalOptions;
+
+    /// The picks of a ray from `origin` along `direction`.
+    fn picked(source: &str, origin: [f64; 3], direction: [f64; 3]) -> Option<Pick> {
+        let options = EvalOptions { sources: true, ..EvalOptions::default() };
+        let geometry = openscad_eval::evaluate_with_options(source, &options).unwrap().geometry;
+        let mesh = geometry_to_mesh(&geometry).unwrap();
+        pick(&NodeTable::new(&geometry), &mesh, origin, direction)
+    }
+
+    /// Line of the call picked by a ray down the z axis at `[x, y]`.
+    fn picked_line(source: &str, x: f64, y: f64) -> Option<usize> {
+        picked(source, [x, y, 100.0], [0.0, 0.0, -1.0])?.span.map(|span| span.start.line)
+    }
+
+    /// Test the innermost call under transforms and in unions is found.
+    #[test]
+    fn test_nested_calls() {
+        let source = "union() {\n  cube(10);\n  translate([0, 0, 10])\n    rotate([0, 0, 45]) cylinder(h = 5, r = 2);\n}";
+        assert_eq!(picked_line(source, 8.0, 8.0), Some(1));
+        assert_eq!(picked_line(source, 0.5, 0.5), Some(3));
+        assert_eq!(picked_line(source, 50.0, 50.0), None);
+    }
+
+    /// Test a hole's wall belongs to the subtracted child, and calls of
+    /// user modules lead into the module body.
+    #[test]
+    fn test_difference_and_modules() {
+        let source = "module peg() {\n  cylinder(h = 20, r = 2);\n}\ndifference() {\n  cube(10);\n  translate([5, 5, -5]) peg();\n}";
+        // Straight down the through hole the ray meets nothing
+        assert_eq!(picked_line(source, 5.0, 5.0), None);
+        assert_eq!(picked_line(source, 1.0, 1.0), Some(4));
+
+        // Sideways into the hole, onto its far wall
+        let picked = picked(source, [5.0, 5.0, 5.0], [1.0, 0.0, 0.0]).unwrap();
+        assert!((picked.hit.point[0] - 7.0).abs() < 0.1, "{:?}", picked.hit);
+        assert_eq!(picked.span.map(|span| span.start.line), Some(1));
+    }
+
+    /// Test trees without sources still give the hit and its node.
+    #[test]
+    fn test_without_sources() {
+        let geometry = openscad_eval::evaluate("cube(2);").unwrap().geometry;
+        let mesh = geometry_to_mesh(&geometry).unwrap();
+        let table = NodeTable::new(&geometry);
+        let picked = pick(&table, &mesh, [1.0, 1.0, 5.0], [0.0, 0.0, -1.0]).unwrap();
+        assert_eq!((picked.hit.point, picked.span), ([1.0, 1.0, 2.0], None));
+        assert_eq!(picked.node.and_then(|node| table.get(node)).map(|node| node.kind), Some("cube"));
+    }
+}
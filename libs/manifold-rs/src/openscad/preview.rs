@@ -0,0 +1,188 @@
+//! # Preview Layers
+//!
+//! Splits the geometry under the `#` and `%` modifiers off an evaluated
+//! tree so it can be meshed into the layers of a [`RenderOutput`].
+//!
+//! ## Layer Trees
+//!
+//! A layer's tree keeps the modified subtrees under the transforms above
+//! them, so they show where they sit in the model. Booleans, hulls and
+//! Minkowski sums above them become plain groups, and extrusions, offsets,
+//! projections and smoothing are left out, as OpenSCAD's preview does: a
+//! `#` operand of `difference()` shows the whole tool, and a `#` profile
+//! under `linear_extrude()` shows the flat profile. `resize()` becomes
+//! the scale it applies to the whole of its child.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::openscad::preview::PreviewTrees;
+//! use openscad_eval::{evaluate, GeometryNode};
+//!
+//! let node = evaluate("translate([5, 0, 0]) union() { cube(1); %sphere(2); }").unwrap().geometry;
+//! let trees = PreviewTrees::of(&node).unwrap();
+//! assert!(trees.highlighted.is_none());
+//! assert!(matches!(trees.transparent, Some(GeometryNode::Translate { .. })));
+//! ```
+
+use openscad_eval::GeometryNode;
+
+use crate::error::ManifoldResult;
+use crate::mesh::output::RenderOutput;
+use crate::mesh::Mesh;
+use super::from_ir::{bounding_box, geometry_to_mesh, resize_factors};
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// The geometry of each preview layer, split off an evaluated tree.
+#[derive(Debug, Clone, Default)]
+pub struct PreviewTrees {
+    /// Geometry under `#`, or `None` if there is none.
+    pub highlighted: Option<GeometryNode>,
+    /// Geometry under `%`, or `None` if there is none.
+    pub transparent: Option<GeometryNode>,
+}
+
+impl PreviewTrees {
+    /// Split the preview layers off `node`.
+    ///
+    /// ## Errors
+    ///
+    /// Errors of meshing the child of a `resize()` that has modified
+    /// geometry under it, to find its scale.
+    pub fn of(node: &GeometryNode) -> ManifoldResult<Self> {
+        Ok(Self {
+            highlighted: layer_tree(node, Layer::Highlighted)?,
+            transparent: layer_tree(node, Layer::Transparent)?,
+        })
+    }
+
+    /// Mesh the layers and put them next to `solid`.
+    ///
+    /// ## Errors
+    ///
+    /// Errors of meshing either layer.
+    pub fn mesh(&self, solid: Mesh) -> ManifoldResult<RenderOutput> {
+        let mesh = |tree: &Option<GeometryNode>| tree.as_ref().map_or_else(|| Ok(Mesh::new()), geometry_to_mesh);
+        Ok(RenderOutput {
+            solid,
+            highlighted: mesh(&self.highlighted)?,
+            transparent: mesh(&self.transparent)?,
+            ..RenderOutput::default()
+        })
+    }
+}
+
+/// Mesh a geometry tree with its preview layers.
+///
+/// ## Errors
+///
+/// Same as [`geometry_to_mesh`].
+pub fn geometry_to_output(node: &GeometryNode) -> ManifoldResult<RenderOutput> {
+    PreviewTrees::of(node)?.mesh(geometry_to_mesh(node)?)
+}
+
+// =============================================================================
+// LAYER TREES
+// =============================================================================
+
+/// Which modifier a layer shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    Highlighted,
+    Transparent,
+}
+
+/// The part of `node` shown in `layer`, or `None` if nothing is.
+fn layer_tree(node: &GeometryNode, layer: Layer) -> ManifoldResult<Option<GeometryNode>> {
+    let wrap = |child: &GeometryNode, rebuild: &dyn Fn(Box<GeometryNode>) -> GeometryNode| {
+        Ok(layer_tree(child, layer)?.map(|part| rebuild(Box::new(part))))
+    };
+    match node {
+        GeometryNode::Highlight { child } if layer == Layer::Highlighted => Ok(Some(child.as_ref().clone())),
+        GeometryNode::Background { child } if layer == Layer::Transparent => Ok(Some(child.as_ref().clone())),
+
+        GeometryNode::Translate { offset, child } => wrap(child, &|child| GeometryNode::Translate { offset: *offset, child }),
+        GeometryNode::Rotate { angles, child } => wrap(child, &|child| GeometryNode::Rotate { angles: *angles, child }),
+        GeometryNode::Scale { factors, child } => wrap(child, &|child| GeometryNode::Scale { factors: *factors, child }),
+        GeometryNode::Mirror { normal, child } => wrap(child, &|child| GeometryNode::Mirror { normal: *normal, child }),
+        GeometryNode::Multmatrix { matrix, child } => wrap(child, &|child| GeometryNode::Multmatrix { matrix: *matrix, child }),
+        GeometryNode::Color { rgba, child } => wrap(child, &|child| GeometryNode::Color { rgba: *rgba, child }),
+        GeometryNode::Quality { level, simplify, child } => {
+            wrap(child, &|child| GeometryNode::Quality { level: *level, simplify: *simplify, child })
+        }
+
+        GeometryNode::Resize { newsize, auto, child } => {
+            let Some(part) = layer_tree(child, layer)? else {
+                return Ok(None);
+            };
+            // The scale depends on the whole child, not just the part shown
+            let (min, max) = bounding_box(&geometry_to_mesh(child)?);
+            let size = [0, 1, 2].map(|axis| max[axis] - min[axis]);
+            let factors = resize_factors(size, *newsize, *auto);
+            Ok(Some(GeometryNode::Scale { factors, child: Box::new(part) }))
+        }
+
+        // Operations and the other modifier show their parts as they are
+        other => {
+            let mut parts = Vec::new();
+            for child in other.children() {
+                parts.extend(layer_tree(child, layer)?);
+            }
+            Ok(match parts.len() {
+                0 => None,
+                1 => parts.pop(),
+                _ => Some(GeometryNode::Group { children: parts }),
+            })
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openscad_eval::evaluate;
+
+    fn render(source: &str) -> RenderOutput {
+        geometry_to_output(&evaluate(source).unwrap().geometry).unwrap()
+    }
+
+    /// Test `#` stays in the solid and `%` is left out of it.
+    #[test]
+    fn test_layers() {
+        let output = render("#cube(10); %translate([20, 0, 0]) cube(5);");
+        assert!((output.solid.volume() - 1000.0).abs() < 1e-6);
+        assert!((output.highlighted.volume() - 1000.0).abs() < 1e-6);
+        assert!((output.transparent.volume() - 125.0).abs() < 1e-6);
+        assert!(output.transparent.vertices.chunks_exact(3).all(|p| p[0] >= 20.0));
+    }
+
+    /// Test layers keep the transforms above them but not the booleans.
+    #[test]
+    fn test_layers_under_operations() {
+        let output = render("translate([0, 0, 10]) difference() { cube(10); #translate([5, 5, 5]) cube(10); }");
+        assert!((output.solid.volume() - 875.0).abs() < 1e-6);
+        assert!((output.highlighted.volume() - 1000.0).abs() < 1e-6);
+        assert!(output.highlighted.vertices.chunks_exact(3).all(|p| p[2] >= 15.0));
+
+        // A background first operand leaves the next one as the base
+        let output = render("difference() { %cube(10); cube(4); sphere(1); }");
+        assert!(output.solid.volume() > 55.0 && output.solid.volume() < 64.0);
+        assert!(!output.transparent.is_empty());
+        assert!(output.highlighted.is_empty());
+    }
+
+    /// Test a layer under resize() takes the scale of the whole child.
+    #[test]
+    fn test_layers_under_resize() {
+        let output = render("resize([20, 0, 0]) { cube(10); #cube(5); }");
+        let max_x = output.highlighted.vertices.chunks_exact(3).map(|p| p[0]).fold(f64::MIN, f64::max);
+        assert!((max_x - 10.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,198 @@
+//! # Provenance
+//!
+//! Which geometry node, and which line of source, made each triangle of
+//! a rendered model: for picking, coloring a model by part, and pointing
+//! validation findings at the code to blame.
+//!
+//! Meshing records a compact node id per triangle in
+//! [`Mesh::nodes`](crate::Mesh::nodes) (see
+//! [`from_ir`](super::from_ir)); a [`NodeTable`] built from the same
+//! tree turns the ids back into nodes:
+//!
+//! ```text
+//! union()                  id 0  union
+//! ├─ cube(10)              id 1  cube
+//! └─ translate([20, 0, 0]) id 2  translate
+//!    └─ sphere(5)          id 3  sphere
+//! ```
+//!
+//! Ids are pre-order positions, counting nodes as
+//! [`GeometryNode::node_count`] does. Spans come from the innermost
+//! enclosing `Source` node, so they exist only in trees evaluated with
+//! [`EvalOptions::sources`](openscad_eval::EvalOptions::sources); there
+//! each `Source` node takes an id of its own too.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::openscad::from_ir::geometry_to_mesh;
+//! use manifold_rs::openscad::provenance::NodeTable;
+//! use openscad_eval::EvalOptions;
+//!
+//! let options = EvalOptions { sources: true, ..EvalOptions::default() };
+//! let source = "cube(10);\ntranslate([20, 0, 0]) sphere(5);";
+//! let geometry = openscad_eval::evaluate_with_options(source, &options).unwrap().geometry;
+//! let mesh = geometry_to_mesh(&geometry).unwrap();
+//! let table = NodeTable::new(&geometry);
+//!
+//! let last = table.triangle(&mesh, mesh.triangle_count() - 1).unwrap();
+//! assert_eq!((last.kind, last.span.unwrap().start.line), ("sphere", 1));
+//! ```
+
+use openscad_eval::{GeometryNode, Span};
+
+use crate::mesh::Mesh;
+
+/// A geometry node, as a triangle's node id refers to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// Kind of node (see [`GeometryNode::kind`]).
+    pub kind: &'static str,
+    /// Span of the innermost module call holding the node.
+    pub span: Option<Span>,
+    /// Id of the parent node; `None` for the root.
+    pub parent: Option<u32>,
+    /// Depth in the tree; `0` for the root.
+    pub depth: u32,
+}
+
+/// The nodes of a geometry tree by id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeTable {
+    nodes: Vec<NodeInfo>,
+}
+
+impl NodeTable {
+    /// The table of the tree under `root`.
+    #[must_use]
+    pub fn new(root: &GeometryNode) -> Self {
+        let mut nodes = Vec::with_capacity(root.node_count());
+        index(root, None, None, 0, &mut nodes);
+        Self { nodes }
+    }
+
+    /// The node with id `id`.
+    #[must_use]
+    pub fn get(&self, id: u32) -> Option<&NodeInfo> {
+        self.nodes.get(id as usize)
+    }
+
+    /// Number of nodes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the tree has no nodes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Span of the innermost module call holding node `id`.
+    #[must_use]
+    pub fn span(&self, id: u32) -> Option<Span> {
+        self.get(id)?.span
+    }
+
+    /// The node that made triangle `triangle` of `mesh`, a mesh of this
+    /// table's tree.
+    #[must_use]
+    pub fn triangle(&self, mesh: &Mesh, triangle: usize) -> Option<&NodeInfo> {
+        self.get(mesh.node_of(triangle)?)
+    }
+}
+
+/// Append `node` and the nodes under it in pre-order, skipping empty ones.
+fn index(node: &GeometryNode, span: Option<Span>, parent: Option<u32>, depth: u32, out: &mut Vec<NodeInfo>) {
+    if matches!(node, GeometryNode::Empty) {
+        return;
+    }
+    let span = match node {
+        GeometryNode::Source { span, .. } => Some(*span),
+        _ => span,
+    };
+    let id = out.len() as u32;
+    out.push(NodeInfo { kind: node.kind(), span, parent, depth });
+    for child in node.children() {
+        index(child, span, Some(id), depth + 1, out);
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openscad::cache::MeshCache;
+    use crate::openscad::from_ir::{geometry_to_mesh, geometry_to_mesh_cached};
+    use openscad_eval::EvalOptions;
+
+    fn evaluate(source: &str) -> GeometryNode {
+        let options = EvalOptions { sources: true, ..EvalOptions::default() };
+        openscad_eval::evaluate_with_options(source, &options).unwrap().geometry
+    }
+
+    /// Lines of the calls behind the triangles of `mesh`, in order, once
+    /// each.
+    fn lines(table: &NodeTable, mesh: &Mesh) -> Vec<Option<usize>> {
+        let mut lines: Vec<Option<usize>> = (0..mesh.triangle_count())
+            .map(|t| table.triangle(mesh, t).and_then(|node| node.span).map(|span| span.start.line))
+            .collect();
+        lines.dedup();
+        lines
+    }
+
+    /// Test the table follows the tree in pre-order like `node_count`.
+    #[test]
+    fn test_table() {
+        let geometry = evaluate("union() {\n  cube(1);\n  translate([2, 0, 0]) sphere(1);\n}");
+        let table = NodeTable::new(&geometry);
+        assert_eq!(table.len(), geometry.node_count());
+        let sphere = (0..table.len() as u32).find(|&id| table.get(id).unwrap().kind == "sphere").unwrap();
+        // The call's Source node sits between the sphere and its translate
+        let ancestors: Vec<_> = std::iter::successors(table.get(sphere).unwrap().parent, |&id| table.get(id).unwrap().parent)
+            .map(|id| table.get(id).unwrap().kind)
+            .collect();
+        assert_eq!(ancestors[..2], ["source", "translate"]);
+        assert_eq!(table.span(sphere).unwrap().start.line, 2);
+        assert_eq!(table.get(0).map(|root| (root.parent, root.depth)), Some((None, 0)));
+        assert!(table.get(table.len() as u32).is_none());
+    }
+
+    /// Test triangles of a difference keep their operands, so the wall of
+    /// a hole belongs to the call that cut it.
+    #[test]
+    fn test_difference() {
+        let geometry = evaluate("difference() {\n  cube(10);\n  translate([5, 5, -1]) cylinder(h = 12, r = 2);\n}");
+        let mesh = geometry_to_mesh(&geometry).unwrap();
+        let table = NodeTable::new(&geometry);
+        let mut lines = lines(&table, &mesh);
+        lines.sort();
+        lines.dedup();
+        assert_eq!(lines, [Some(1), Some(2)]);
+    }
+
+    /// Test operations that build new surfaces claim them, and ids stay
+    /// right for subtrees taken from the cache at another place.
+    #[test]
+    fn test_operations_and_cache() {
+        let geometry = evaluate("hull() {\n  cube(1);\n  sphere(1);\n}");
+        let mesh = geometry_to_mesh(&geometry).unwrap();
+        let table = NodeTable::new(&geometry);
+        assert!((0..mesh.triangle_count()).all(|t| table.triangle(&mesh, t).unwrap().kind == "hull"));
+
+        let mut cache = MeshCache::new();
+        let part = "union() { cube(1); sphere(1); }";
+        geometry_to_mesh_cached(&evaluate(part), &mut cache, None, None).unwrap();
+        let moved = evaluate(&format!("translate([5, 0, 0]) color([1, 0, 0]) {}", part));
+        let cached = geometry_to_mesh_cached(&moved, &mut cache, None, None).unwrap();
+        let fresh = geometry_to_mesh(&moved).unwrap();
+        assert_eq!(cached.nodes, fresh.nodes);
+        let table = NodeTable::new(&moved);
+        let kinds: Vec<_> = (0..cached.triangle_count()).map(|t| table.triangle(&cached, t).unwrap().kind).collect();
+        assert!(kinds.contains(&"cube") && kinds.contains(&"sphere"), "{:?}", kinds);
+    }
+}
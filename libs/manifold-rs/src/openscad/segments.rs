@@ -21,6 +21,15 @@
 //! - `$fa`: 12° (30 segments for full circle)
 //! - `$fs`: 2mm (depends on radius)
 //!
+//! ## Quality
+//!
+//! [`SegmentParams::quality`] multiplies the result, like the evaluator's
+//! `quality()` scopes and render quality (see
+//! [`openscad_eval::QualityOptions`]). The evaluator resolves the segment
+//! count of every node it produces; the mesher only computes counts for
+//! nodes built without one (`fn_: 0`), and scales them by the level of the
+//! enclosing `Quality` node.
+//!
 //! ## Reference
 //!
 //! <https://en.wikibooks.org/wiki/OpenSCAD_User_Manual/Other_Language_Features#$fa,_$fs_and_$fn>
@@ -92,6 +101,11 @@ pub struct SegmentParams {
     ///
     /// Default: 2mm
     pub fs: f64,
+
+    /// Segment multiplier, applied last.
+    ///
+    /// Default: 1
+    pub quality: f64,
 }
 
 impl Default for SegmentParams {
@@ -100,11 +114,13 @@ impl Default for SegmentParams {
     /// - $fn: None (use $fa/$fs)
     /// - $fa: 12°
     /// - $fs: 2mm
+    /// - quality: 1
     fn default() -> Self {
         Self {
             fn_: None,
             fa: DEFAULT_FA,
             fs: DEFAULT_FS,
+            quality: 1.0,
         }
     }
 }
@@ -128,8 +144,7 @@ impl SegmentParams {
     pub fn with_fn(fn_: u32) -> Self {
         Self {
             fn_: Some(fn_.max(MIN_SEGMENTS)),
-            fa: DEFAULT_FA,
-            fs: DEFAULT_FS,
+            ..Self::default()
         }
     }
 
@@ -154,12 +169,61 @@ impl SegmentParams {
             fn_: None,
             fa: fa.clamp(0.01, 360.0),
             fs: fs.clamp(0.01, 1000.0),
+            quality: 1.0,
+        }
+    }
+
+    /// The same parameters with a segment multiplier.
+    ///
+    /// ## Parameters
+    ///
+    /// - `quality`: Segment multiplier; values that are not positive and
+    ///   finite keep the current one
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use manifold_rs::openscad::SegmentParams;
+    ///
+    /// let params = SegmentParams::with_fn(16).with_quality(0.5);
+    /// assert_eq!(params.calculate_segments(5.0), 8);
+    /// ```
+    #[must_use]
+    pub fn with_quality(self, quality: f64) -> Self {
+        if quality > 0.0 && quality.is_finite() {
+            Self { quality, ..self }
+        } else {
+            self
+        }
+    }
+
+    /// Use a node's `$fn` if it is set, keeping `$fa`, `$fs` and quality.
+    ///
+    /// ## Parameters
+    ///
+    /// - `fn_`: `$fn` from the evaluator; 0 keeps the current one
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use manifold_rs::openscad::SegmentParams;
+    ///
+    /// assert_eq!(SegmentParams::default().with_node_fn(12).calculate_segments(5.0), 12);
+    /// assert_eq!(SegmentParams::with_fn(16).with_node_fn(0).calculate_segments(5.0), 16);
+    /// ```
+    #[must_use]
+    pub fn with_node_fn(self, fn_: u32) -> Self {
+        if fn_ > 0 {
+            Self { fn_: Some(fn_.max(MIN_SEGMENTS)), ..self }
+        } else {
+            self
         }
     }
 
     /// Calculate number of segments for a given radius.
     ///
-    /// Implements OpenSCAD's exact segment calculation algorithm:
+    /// Implements OpenSCAD's exact segment calculation algorithm, then
+    /// scales by [`quality`](Self::quality):
     ///
     /// ```text
     /// if $fn > 0:
@@ -196,21 +260,22 @@ impl SegmentParams {
     #[must_use]
     pub fn calculate_segments(&self, radius: f64) -> u32 {
         // If $fn is set and > 0, use it directly
-        if let Some(fn_) = self.fn_ {
-            if fn_ > 0 {
-                return fn_.clamp(MIN_SEGMENTS, MAX_SEGMENTS);
+        let segments = match self.fn_ {
+            Some(fn_) if fn_ > 0 => fn_,
+            _ => {
+                // Calculate from $fa: segments = ceil(360 / $fa)
+                let from_fa = (360.0 / self.fa).ceil() as u32;
+
+                // Calculate from $fs: segments = ceil(circumference / $fs)
+                let circumference = 2.0 * PI * radius.abs();
+                let from_fs = (circumference / self.fs).ceil() as u32;
+
+                from_fa.max(from_fs)
             }
-        }
-        
-        // Calculate from $fa: segments = ceil(360 / $fa)
-        let from_fa = (360.0 / self.fa).ceil() as u32;
-        
-        // Calculate from $fs: segments = ceil(circumference / $fs)
-        let circumference = 2.0 * PI * radius.abs();
-        let from_fs = (circumference / self.fs).ceil() as u32;
-        
-        // Return maximum, clamped to valid range
-        from_fa.max(from_fs).max(MIN_SEGMENTS).min(MAX_SEGMENTS)
+        };
+
+        // Scale by quality, clamped to valid range
+        ((segments as f64 * self.quality).round() as u32).clamp(MIN_SEGMENTS, MAX_SEGMENTS)
     }
 
     /// Calculate segments for a sphere.
@@ -228,7 +293,7 @@ impl SegmentParams {
     #[must_use]
     pub fn calculate_sphere_segments(&self, radius: f64) -> (u32, u32) {
         let segments = self.calculate_segments(radius);
-        let rings = (segments + 1) / 2;
+        let rings = segments.div_ceil(2);
         (segments, rings)
     }
 
@@ -333,12 +398,21 @@ mod tests {
     fn test_fn_zero() {
         let params = SegmentParams {
             fn_: Some(0),
-            fa: DEFAULT_FA,
-            fs: DEFAULT_FS,
+            ..SegmentParams::default()
         };
         
         // Should fallback to $fa/$fs calculation, not return 0
         let segments = params.calculate_segments(10.0);
         assert!(segments >= MIN_SEGMENTS);
     }
+
+    /// Test quality scales the count within the valid range.
+    #[test]
+    fn test_quality() {
+        let params = SegmentParams::default().with_quality(2.0);
+        assert_eq!(params.calculate_segments(10.0), 2 * SegmentParams::default().calculate_segments(10.0));
+        assert_eq!(SegmentParams::with_fn(4).with_quality(0.25).calculate_segments(1.0), MIN_SEGMENTS);
+        assert_eq!(SegmentParams::with_fn(200).with_quality(4.0).calculate_segments(1.0), MAX_SEGMENTS);
+        assert_eq!(SegmentParams::with_fn(16).with_quality(-1.0).quality, 1.0);
+    }
 }
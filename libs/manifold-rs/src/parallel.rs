@@ -0,0 +1,116 @@
+//! # Parallel Evaluation
+//!
+//! Runs independent work on the rayon thread pool: the operand subtrees of
+//! CSG operations, the pairs of each boolean reduction level, the two
+//! sides of each boolean with their triangle classification, and the
+//! coplanar groups merged after a boolean.
+//!
+//! ## Determinism
+//!
+//! Results are always returned in item order, so callers combine them the
+//! same way whether or not threads ran, and a parallel render gives the
+//! same mesh as a serial one, bit for bit.
+//!
+//! ## Availability
+//!
+//! Threads need the `parallel` feature (on by default) and either a native
+//! target or, on wasm32, the `wasm-threads` feature, where the pool runs in
+//! web workers over shared memory once the host has started it (see
+//! `init_thread_pool` in the wasm crate). Without them
+//! [`Reduction::Parallel`] can still be selected, but everything runs on
+//! the calling thread.
+//!
+//! [`Reduction::Parallel`]: crate::manifold::boolean::Reduction::Parallel
+
+/// Whether work runs on the thread pool right now.
+#[cfg(all(feature = "parallel", any(not(target_arch = "wasm32"), feature = "wasm-threads")))]
+fn threaded() -> bool {
+    use crate::manifold::boolean::{reduction, Reduction};
+    reduction() == Reduction::Parallel
+}
+
+/// Apply `f` to each item, results in item order.
+pub(crate) fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(all(feature = "parallel", any(not(target_arch = "wasm32"), feature = "wasm-threads")))]
+    if threaded() {
+        use rayon::prelude::*;
+        return items.par_iter().map(f).collect();
+    }
+    items.iter().map(f).collect()
+}
+
+/// Apply `f` to each chunk of `size` items, results in chunk order.
+pub(crate) fn map_chunks<T, R, F>(items: &[T], size: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&[T]) -> R + Sync + Send,
+{
+    #[cfg(all(feature = "parallel", any(not(target_arch = "wasm32"), feature = "wasm-threads")))]
+    if threaded() {
+        use rayon::prelude::*;
+        return items.par_chunks(size).map(f).collect();
+    }
+    items.chunks(size).map(f).collect()
+}
+
+/// Run `a` and `b`, potentially in parallel, returning both results.
+pub(crate) fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(all(feature = "parallel", any(not(target_arch = "wasm32"), feature = "wasm-threads")))]
+    if threaded() {
+        return rayon::join(a, b);
+    }
+    (a(), b())
+}
+
+/// Apply `f` to each item, taking ownership, results in item order.
+pub(crate) fn map_owned<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    #[cfg(all(feature = "parallel", any(not(target_arch = "wasm32"), feature = "wasm-threads")))]
+    if threaded() {
+        use rayon::prelude::*;
+        return items.into_par_iter().map(f).collect();
+    }
+    items.into_iter().map(f).collect()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifold::boolean::{reduction, set_reduction, Reduction};
+
+    /// Test results keep item order in both modes.
+    #[test]
+    fn test_map_keeps_order() {
+        let items: Vec<u64> = (0..1000).collect();
+        let previous = reduction();
+        for mode in [Reduction::Serial, Reduction::Parallel] {
+            set_reduction(mode);
+            let doubled = map(&items, |i| i * 2);
+            assert_eq!(doubled, map_owned(items.clone(), |i| i * 2));
+            assert_eq!(doubled[999], 1998);
+            assert_eq!(map_chunks(&items, 2, |pair| pair[0]), (0..1000).step_by(2).collect::<Vec<_>>());
+            assert_eq!(join(|| 1, || 2), (1, 2));
+        }
+        set_reduction(previous);
+    }
+}
@@ -0,0 +1,314 @@
+//! # Solid Builder
+//!
+//! Builds CSG models in Rust without writing OpenSCAD source. A [`Solid`]
+//! is a [`GeometryNode`] tree assembled by chained calls, meshed by the
+//! same code as evaluated scripts: it honours the boolean backend and
+//! settings of [`manifold::boolean`](crate::manifold::boolean), and
+//! [`Solid::mesh_cached`] shares a [`MeshCache`] with script renders.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use manifold_rs::Solid;
+//!
+//! let mesh = Solid::cube(10)
+//!     .translate([-5.0, -5.0, -5.0])
+//!     .difference(Solid::sphere(6))
+//!     .mesh()
+//!     .unwrap();
+//! assert!(mesh.validate().is_valid());
+//! ```
+//!
+//! ## Resolution
+//!
+//! Round primitives get as many segments as the same call in a script
+//! with OpenSCAD's defaults (`$fa = 12`, `$fs = 2`), or exactly the count
+//! given to the `_segments` variants, like `$fn`.
+//!
+//! ```rust
+//! use manifold_rs::Solid;
+//!
+//! let coarse = Solid::sphere_segments(5.0, 8);
+//! assert_eq!(coarse.mesh().unwrap().triangle_count(), 60);
+//! ```
+
+use openscad_eval::{CsgOptions, GeometryNode, Scope};
+
+use crate::error::ManifoldResult;
+use crate::manifold::boolean::CsgScope;
+use crate::mesh::Mesh;
+use crate::openscad::cache::MeshCache;
+use crate::openscad::from_ir::{geometry_to_mesh, geometry_to_mesh_cached};
+
+// =============================================================================
+// SOLID
+// =============================================================================
+
+/// A CSG model under construction.
+///
+/// Every method takes the solid by value and returns the combined one, so
+/// models read as one chain. Nothing is meshed until [`mesh`](Self::mesh).
+#[derive(Debug, Clone)]
+pub struct Solid {
+    node: GeometryNode,
+}
+
+impl Solid {
+    // -------------------------------------------------------------------------
+    // Primitives
+    // -------------------------------------------------------------------------
+
+    /// Cube with sides of `size`, one corner at the origin.
+    pub fn cube(size: impl Into<f64>) -> Self {
+        let size = size.into();
+        Self::cuboid([size; 3], false)
+    }
+
+    /// Box of `size` [x, y, z], centered on the origin or with one corner
+    /// at it.
+    pub fn cuboid(size: [f64; 3], center: bool) -> Self {
+        GeometryNode::Cube { size, center }.into()
+    }
+
+    /// Sphere of `radius` at the origin, with OpenSCAD's default resolution.
+    pub fn sphere(radius: impl Into<f64>) -> Self {
+        let radius = radius.into();
+        Self::sphere_segments(radius, default_segments(radius))
+    }
+
+    /// Sphere of `radius` at the origin with `segments` around, at least 3.
+    pub fn sphere_segments(radius: f64, segments: u32) -> Self {
+        GeometryNode::Sphere { radius, fn_: segments.max(3) }.into()
+    }
+
+    /// Cylinder of `height` and `radius` standing on the origin, with
+    /// OpenSCAD's default resolution.
+    pub fn cylinder(height: impl Into<f64>, radius: impl Into<f64>) -> Self {
+        let radius = radius.into();
+        Self::cone(height, radius, radius)
+    }
+
+    /// Cone from `radius1` at the origin to `radius2` at `height`; either
+    /// radius may be 0.
+    pub fn cone(height: impl Into<f64>, radius1: impl Into<f64>, radius2: impl Into<f64>) -> Self {
+        let (radius1, radius2) = (radius1.into(), radius2.into());
+        Self::cone_segments(height.into(), radius1, radius2, default_segments(radius1.max(radius2)))
+    }
+
+    /// Cone or cylinder with `segments` around, at least 3.
+    pub fn cone_segments(height: f64, radius1: f64, radius2: f64, segments: u32) -> Self {
+        GeometryNode::Cylinder { height, radius1, radius2, center: false, fn_: segments.max(3) }.into()
+    }
+
+    /// Polyhedron of `points` and `faces`, each face listing point indices
+    /// clockwise seen from outside, as in OpenSCAD.
+    pub fn polyhedron(points: Vec<[f64; 3]>, faces: Vec<Vec<usize>>) -> Self {
+        GeometryNode::Polyhedron { points, faces }.into()
+    }
+
+    /// Nothing; the identity of [`union`](Self::union).
+    pub fn empty() -> Self {
+        GeometryNode::Empty.into()
+    }
+
+    // -------------------------------------------------------------------------
+    // Transforms
+    // -------------------------------------------------------------------------
+
+    /// Move by `offset`.
+    #[must_use]
+    pub fn translate(self, offset: [f64; 3]) -> Self {
+        GeometryNode::Translate { offset, child: Box::new(self.node) }.into()
+    }
+
+    /// Rotate by `angles` in degrees about X, then Y, then Z.
+    #[must_use]
+    pub fn rotate(self, angles: [f64; 3]) -> Self {
+        GeometryNode::Rotate { angles, child: Box::new(self.node) }.into()
+    }
+
+    /// Scale by `factors` about the origin.
+    #[must_use]
+    pub fn scale(self, factors: [f64; 3]) -> Self {
+        GeometryNode::Scale { factors, child: Box::new(self.node) }.into()
+    }
+
+    /// Mirror in the plane through the origin with `normal`.
+    #[must_use]
+    pub fn mirror(self, normal: [f64; 3]) -> Self {
+        GeometryNode::Mirror { normal, child: Box::new(self.node) }.into()
+    }
+
+    /// Apply a 4x4 affine `matrix`, rows first.
+    #[must_use]
+    pub fn transform(self, matrix: [[f64; 4]; 4]) -> Self {
+        GeometryNode::Multmatrix { matrix, child: Box::new(self.node) }.into()
+    }
+
+    /// Paint with `rgba`, each channel from 0 to 1.
+    #[must_use]
+    pub fn color(self, rgba: [f64; 4]) -> Self {
+        GeometryNode::Color { rgba, child: Box::new(self.node) }.into()
+    }
+
+    // -------------------------------------------------------------------------
+    // Booleans
+    // -------------------------------------------------------------------------
+
+    /// Merge with `other`.
+    ///
+    /// Chained unions collect into one node, so `a.union(b).union(c)`
+    /// meshes as a single n-ary union.
+    #[must_use]
+    pub fn union(self, other: Solid) -> Self {
+        self.combine(other, |children| GeometryNode::Union { children })
+    }
+
+    /// Cut `other` away. Chained differences all cut the first solid.
+    #[must_use]
+    pub fn difference(self, other: Solid) -> Self {
+        self.combine(other, |children| GeometryNode::Difference { children })
+    }
+
+    /// Keep what is inside both this and `other`.
+    #[must_use]
+    pub fn intersection(self, other: Solid) -> Self {
+        self.combine(other, |children| GeometryNode::Intersection { children })
+    }
+
+    /// Convex hull of this and `other`.
+    #[must_use]
+    pub fn hull(self, other: Solid) -> Self {
+        self.combine(other, |children| GeometryNode::Hull { children })
+    }
+
+    /// Minkowski sum of this and `other`.
+    #[must_use]
+    pub fn minkowski(self, other: Solid) -> Self {
+        GeometryNode::Minkowski { children: vec![self.node, other.node] }.into()
+    }
+
+    /// Union of all `solids`; [`empty`](Self::empty) if there are none.
+    pub fn union_all(solids: impl IntoIterator<Item = Solid>) -> Self {
+        GeometryNode::union(solids.into_iter().map(|solid| solid.node).collect()).into()
+    }
+
+    /// Append `other` to this node if it is already the operation `make`
+    /// builds, otherwise start a new node with both.
+    fn combine(self, other: Solid, make: fn(Vec<GeometryNode>) -> GeometryNode) -> Self {
+        let node = match (make(Vec::new()), self.node) {
+            (GeometryNode::Union { .. }, GeometryNode::Union { mut children })
+            | (GeometryNode::Difference { .. }, GeometryNode::Difference { mut children })
+            | (GeometryNode::Intersection { .. }, GeometryNode::Intersection { mut children })
+            | (GeometryNode::Hull { .. }, GeometryNode::Hull { mut children }) => {
+                children.push(other.node);
+                make(children)
+            }
+            (_, node) => make(vec![node, other.node]),
+        };
+        node.into()
+    }
+
+    // -------------------------------------------------------------------------
+    // Output
+    // -------------------------------------------------------------------------
+
+    /// Mesh the solid with the current boolean settings.
+    ///
+    /// ## Errors
+    ///
+    /// `ManifoldError::GeometryError` or `BooleanError` if meshing fails,
+    /// as for the same tree evaluated from a script.
+    pub fn mesh(&self) -> ManifoldResult<Mesh> {
+        geometry_to_mesh(&self.node)
+    }
+
+    /// Mesh the solid with `options` applied for this call only.
+    ///
+    /// ## Errors
+    ///
+    /// Same as [`mesh`](Self::mesh).
+    pub fn mesh_with(&self, options: &CsgOptions) -> ManifoldResult<Mesh> {
+        let _csg = CsgScope::enter(options);
+        self.mesh()
+    }
+
+    /// Mesh the solid, reusing and filling `cache` (see
+    /// [`geometry_to_mesh_cached`]).
+    ///
+    /// ## Errors
+    ///
+    /// Same as [`mesh`](Self::mesh).
+    pub fn mesh_cached(&self, cache: &mut MeshCache) -> ManifoldResult<Mesh> {
+        geometry_to_mesh_cached(&self.node, cache, None, None)
+    }
+
+    /// The geometry tree.
+    pub fn node(&self) -> &GeometryNode {
+        &self.node
+    }
+
+    /// Take the geometry tree, e.g. to serialize it.
+    pub fn into_node(self) -> GeometryNode {
+        self.node
+    }
+}
+
+/// Segments a script gives a circle of `radius` with the default `$fa`
+/// and `$fs`.
+fn default_segments(radius: f64) -> u32 {
+    Scope::new().calculate_fragments(radius)
+}
+
+impl From<GeometryNode> for Solid {
+    /// Wrap a tree, e.g. one from `openscad_eval::evaluate`, to extend it.
+    fn from(node: GeometryNode) -> Self {
+        Self { node }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test a built solid meshes like the same script.
+    #[test]
+    fn test_matches_script() {
+        let built = Solid::cube(10)
+            .difference(Solid::cylinder(12, 3).translate([5.0, 5.0, -1.0]))
+            .union(Solid::sphere(2).translate([5.0, 5.0, 10.0]))
+            .mesh()
+            .unwrap();
+        let script = crate::render(
+            "union() { difference() { cube(10); translate([5, 5, -1]) cylinder(h = 12, r = 3); } translate([5, 5, 10]) sphere(2); }",
+        )
+        .unwrap();
+        assert_eq!(built.vertices, script.vertices);
+        assert_eq!(built.indices, script.indices);
+    }
+
+    /// Test chained booleans of one kind collect into one node.
+    #[test]
+    fn test_chains_flatten() {
+        let solid = Solid::cube(1).union(Solid::sphere(1)).union(Solid::cube(2));
+        assert!(matches!(solid.node(), GeometryNode::Union { children } if children.len() == 3));
+        let solid = Solid::cube(4).difference(Solid::cube(1)).intersection(Solid::cube(2));
+        assert!(matches!(solid.node(), GeometryNode::Intersection { children } if children.len() == 2));
+        assert!(Solid::union_all([]).node().is_empty());
+    }
+
+    /// Test meshing through a cache reuses the root mesh.
+    #[test]
+    fn test_cached() {
+        let solid = Solid::cube(10).difference(Solid::sphere(6));
+        let mut cache = MeshCache::new();
+        let first = solid.mesh_cached(&mut cache).unwrap();
+        let second = solid.mesh_cached(&mut cache).unwrap();
+        assert_eq!(first.vertices, second.vertices);
+        assert_eq!(cache.sweep().hits, 1);
+    }
+}
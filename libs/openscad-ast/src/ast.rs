@@ -63,8 +63,11 @@ impl Default for Ast {
 /// - `ModuleDeclaration` - Module definition
 /// - `FunctionDeclaration` - Function definition
 /// - `ForLoop` - For loop
+/// - `IntersectionFor` - Loop intersecting its iterations
 /// - `IfElse` - If/else statement
+/// - `Let` - Let block
 /// - `Block` - Block of statements
+/// - `Modified` - Statement under a modifier like `#` or `%`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Statement {
     /// Module call like `cube(10);` or `translate([1,2,3]) cube(5);`
@@ -123,6 +126,19 @@ pub enum Statement {
         span: Span,
     },
 
+    /// Intersection loop like `intersection_for (i = [0:2]) { ... }`.
+    ///
+    /// Like [`Statement::ForLoop`], but the iterations are intersected
+    /// instead of grouped.
+    IntersectionFor {
+        /// Loop variable assignments.
+        assignments: Vec<(String, Expression)>,
+        /// Body statements.
+        body: Vec<Statement>,
+        /// Source span.
+        span: Span,
+    },
+
     /// If/else statement.
     IfElse {
         /// Condition expression.
@@ -135,6 +151,16 @@ pub enum Statement {
         span: Span,
     },
 
+    /// Let block like `let (x = 10) cube(x);`
+    Let {
+        /// Bindings, evaluated in order.
+        assignments: Vec<(String, Expression)>,
+        /// Body statements.
+        body: Vec<Statement>,
+        /// Source span.
+        span: Span,
+    },
+
     /// Block of statements.
     Block {
         /// Statements in block.
@@ -142,6 +168,61 @@ pub enum Statement {
         /// Source span.
         span: Span,
     },
+
+    /// File inclusion like `include <lib.scad>`; the file's statements run
+    /// in place.
+    Include {
+        /// Path as written between the angle brackets.
+        path: String,
+        /// Source span.
+        span: Span,
+    },
+
+    /// Library import like `use <lib.scad>`; only the file's modules and
+    /// functions become available.
+    Use {
+        /// Path as written between the angle brackets.
+        path: String,
+        /// Source span.
+        span: Span,
+    },
+
+    /// Statement under a modifier like `#cube(10);` or `*sphere(5);`.
+    Modified {
+        /// The modifier character's meaning.
+        modifier: Modifier,
+        /// Modified statement.
+        statement: Box<Statement>,
+        /// Source span, modifier included.
+        span: Span,
+    },
+}
+
+/// Modifier characters that change how a statement is previewed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Modifier {
+    /// Disable `*`: the statement is ignored.
+    Disable,
+    /// Show only `!`: the statement's geometry becomes the whole result.
+    ShowOnly,
+    /// Highlight `#`: part of the model, also shown highlighted.
+    Highlight,
+    /// Background `%`: not part of the model, shown transparent.
+    Background,
+}
+
+impl Modifier {
+    /// Parse modifier from string.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "*" => Some(Self::Disable),
+            "!" => Some(Self::ShowOnly),
+            "#" => Some(Self::Highlight),
+            "%" => Some(Self::Background),
+            _ => None,
+        }
+    }
 }
 
 // =============================================================================
@@ -172,6 +253,11 @@ pub enum Expression {
     /// List literal like `[1, 2, 3]`.
     List(Vec<Expression>),
 
+    /// List comprehension like `[for (i = [0:10]) i * i]`.
+    ///
+    /// Elements may mix plain expressions and generator clauses.
+    ListComprehension(Vec<ComprehensionElement>),
+
     /// Range like `[0:10]` or `[0:1:10]`.
     Range {
         /// Start value.
@@ -210,6 +296,14 @@ pub enum Expression {
         else_expr: Box<Expression>,
     },
 
+    /// Let expression like `let (x = 1) x + 1`.
+    Let {
+        /// Bindings, evaluated in order.
+        assignments: Vec<(String, Expression)>,
+        /// Body expression.
+        body: Box<Expression>,
+    },
+
     /// Function call like `sin(x)`.
     FunctionCall {
         /// Function name.
@@ -235,6 +329,44 @@ pub enum Expression {
     },
 }
 
+/// An element of a list comprehension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ComprehensionElement {
+    /// Plain expression, contributes one value.
+    Expr(Expression),
+
+    /// Generator like `for (x = [0:2], y = [0:2]) [x, y]`.
+    ///
+    /// Multiple assignments iterate as nested loops, first one outermost.
+    For {
+        /// Loop variable assignments.
+        assignments: Vec<(String, Expression)>,
+        /// Element produced per iteration.
+        body: Box<ComprehensionElement>,
+    },
+
+    /// Filter like `if (i > 0) i` or `if (i > 0) i else -i`.
+    If {
+        /// Condition.
+        condition: Expression,
+        /// Element when true.
+        then_elem: Box<ComprehensionElement>,
+        /// Optional element when false.
+        else_elem: Option<Box<ComprehensionElement>>,
+    },
+
+    /// Flatten one level like `each [1, 2, 3]`.
+    Each(Box<ComprehensionElement>),
+
+    /// Local bindings like `let (j = i * 2) j`.
+    Let {
+        /// Bindings, evaluated in order.
+        assignments: Vec<(String, Expression)>,
+        /// Element evaluated with the bindings in scope.
+        body: Box<ComprehensionElement>,
+    },
+}
+
 // =============================================================================
 // ARGUMENT
 // =============================================================================
@@ -306,6 +438,7 @@ pub enum BinaryOp {
 
 impl BinaryOp {
     /// Parse operator from string.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "+" => Some(Self::Add),
@@ -340,6 +473,7 @@ pub enum UnaryOp {
 
 impl UnaryOp {
     /// Parse operator from string.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "-" => Some(Self::Neg),
@@ -364,6 +498,13 @@ mod tests {
         assert!(ast.statements.is_empty());
     }
 
+    #[test]
+    fn test_modifier_from_str() {
+        assert_eq!(Modifier::from_str("#"), Some(Modifier::Highlight));
+        assert_eq!(Modifier::from_str("%"), Some(Modifier::Background));
+        assert_eq!(Modifier::from_str("+"), None);
+    }
+
     #[test]
     fn test_binary_op_from_str() {
         assert_eq!(BinaryOp::from_str("+"), Some(BinaryOp::Add));
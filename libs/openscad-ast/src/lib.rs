@@ -22,9 +22,12 @@ pub mod error;
 pub mod visitor;
 
 // Re-export public API
-pub use ast::{Ast, Statement, Expression, Argument, BinaryOp, UnaryOp};
+pub use ast::{Ast, Statement, Expression, ComprehensionElement, Argument, BinaryOp, UnaryOp, Modifier};
 pub use error::AstError;
-pub use openscad_parser::{Span, Position};
+pub use openscad_parser::{Cst, Span, Position};
+
+/// Crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // =============================================================================
 // PUBLIC API
@@ -51,7 +54,16 @@ pub use openscad_parser::{Span, Position};
 /// assert_eq!(ast.statements.len(), 1);
 /// ```
 pub fn parse(source: &str) -> Result<Ast, AstError> {
-    // Parse to CST using openscad-parser
+    lower(&parse_cst(source)?)
+}
+
+/// Parse OpenSCAD source code into a Concrete Syntax Tree, the first half
+/// of [`parse`].
+///
+/// ## Errors
+///
+/// `AstError::ParseError` with every syntax error, joined by `; `.
+pub fn parse_cst(source: &str) -> Result<Cst, AstError> {
     let cst = openscad_parser::parse(source);
     
     // Check for parse errors
@@ -63,9 +75,17 @@ pub fn parse(source: &str) -> Result<Ast, AstError> {
                 .join("; ")
         ));
     }
-    
-    // Transform CST to AST using visitor
-    visitor::cst_to_ast::transform(&cst)
+    Ok(cst)
+}
+
+/// Transform a CST from [`parse_cst`] into an AST, the second half of
+/// [`parse`].
+///
+/// ## Errors
+///
+/// `AstError` for constructs the AST cannot represent.
+pub fn lower(cst: &Cst) -> Result<Ast, AstError> {
+    visitor::cst_to_ast::transform(cst)
 }
 
 // =============================================================================
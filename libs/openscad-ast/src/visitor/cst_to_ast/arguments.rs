@@ -125,7 +125,7 @@ fn transform_named(node: &CstNode) -> Result<Option<Argument>, AstError> {
     // Name can be Identifier or SpecialVariable
     let name = find_argument_name(node)?;
     
-    // Value is the non-identifier/non-special-variable child
+    // Value is the child after the name
     let value = find_argument_value(node)?;
     
     Ok(Some(Argument::Named { name, value }))
@@ -140,10 +140,12 @@ fn find_argument_name(node: &CstNode) -> Result<String, AstError> {
 }
 
 /// Find the value of a named argument.
+///
+/// The value is always the second child; it may itself be an identifier
+/// (`h=h`), so it cannot be located by node kind.
 fn find_argument_value(node: &CstNode) -> Result<Expression, AstError> {
-    node.children.iter()
-        .find(|c| c.kind != NodeKind::Identifier && c.kind != NodeKind::SpecialVariable)
-        .map(|c| transform_expression(c))
+    node.children.get(1)
+        .map(transform_expression)
         .transpose()?
         .ok_or_else(|| AstError::InvalidCst("Named argument missing value".to_string()))
 }
@@ -191,6 +193,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_transform_named_identifier_value() {
+        let args = get_args("cylinder(h=h, r=1);");
+        assert_eq!(args.len(), 2);
+        match &args[0] {
+            Argument::Named { name, value } => {
+                assert_eq!(name, "h");
+                assert!(matches!(value, Expression::Identifier(n) if n == "h"));
+            }
+            _ => panic!("Expected Named argument"),
+        }
+    }
+
     #[test]
     fn test_transform_multiple_positional() {
         let args = get_args("cylinder(10, 5, 3);");
@@ -0,0 +1,188 @@
+//! # List Comprehension Transformation
+//!
+//! Transforms CST list comprehension nodes to AST expressions.
+//!
+//! ## Supported Clauses
+//!
+//! - Generators: `for (i = [0:10]) ...`
+//! - Filters: `if (cond) ... else ...`
+//! - Flattening: `each ...`
+//! - Bindings: `let (a = 1) ...`
+//!
+//! ## Example
+//!
+//! ```text
+//! [for (i = [0:10]) if (i % 2 == 0) i * i]
+//! ```
+
+use crate::ast::{ComprehensionElement, Expression};
+use crate::error::AstError;
+use openscad_parser::{CstNode, NodeKind};
+
+use super::control_flow::transform_assignment_list;
+use super::expressions::transform_expression;
+
+/// Ordered variable bindings of a `for` or `let` clause.
+type Bindings = Vec<(String, Expression)>;
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Transform list comprehension node.
+///
+/// ## CST Structure
+///
+/// ```text
+/// ListComprehension
+/// ├── Expression | ComprehensionFor | ComprehensionIf | ...
+/// └── ...
+/// ```
+pub fn transform_list_comprehension(node: &CstNode) -> Result<Expression, AstError> {
+    let elements: Result<Vec<_>, _> = node.children.iter()
+        .map(transform_element)
+        .collect();
+    Ok(Expression::ListComprehension(elements?))
+}
+
+// =============================================================================
+// ELEMENTS
+// =============================================================================
+
+/// Transform a single comprehension element.
+fn transform_element(node: &CstNode) -> Result<ComprehensionElement, AstError> {
+    match node.kind {
+        NodeKind::ComprehensionFor => {
+            let (assignments, body) = transform_bound_clause(node, "for")?;
+            Ok(ComprehensionElement::For { assignments, body })
+        }
+        NodeKind::ComprehensionLet => {
+            let (assignments, body) = transform_bound_clause(node, "let")?;
+            Ok(ComprehensionElement::Let { assignments, body })
+        }
+        NodeKind::ComprehensionIf => transform_if(node),
+        NodeKind::ComprehensionEach => {
+            let body = node.children.first()
+                .map(transform_element)
+                .transpose()?
+                .ok_or_else(|| AstError::InvalidExpression("each missing element".to_string()))?;
+            Ok(ComprehensionElement::Each(Box::new(body)))
+        }
+        _ => Ok(ComprehensionElement::Expr(transform_expression(node)?)),
+    }
+}
+
+/// Transform a clause with an assignment list and a body element.
+///
+/// ## CST Structure
+///
+/// ```text
+/// ComprehensionFor | ComprehensionLet
+/// ├── ForAssignments
+/// └── Element (body)
+/// ```
+fn transform_bound_clause(
+    node: &CstNode,
+    clause: &str,
+) -> Result<(Bindings, Box<ComprehensionElement>), AstError> {
+    let assignments = node.find_child(NodeKind::ForAssignments)
+        .map(transform_assignment_list)
+        .transpose()?
+        .unwrap_or_default();
+
+    let body = node.children.iter()
+        .find(|c| c.kind != NodeKind::ForAssignments)
+        .map(transform_element)
+        .transpose()?
+        .ok_or_else(|| AstError::InvalidExpression(format!("{} missing element", clause)))?;
+
+    Ok((assignments, Box::new(body)))
+}
+
+/// Transform comprehension filter.
+///
+/// ## CST Structure
+///
+/// ```text
+/// ComprehensionIf
+/// ├── Expression (condition)
+/// ├── Element (then)
+/// └── Element (optional else)
+/// ```
+fn transform_if(node: &CstNode) -> Result<ComprehensionElement, AstError> {
+    let mut children = node.children.iter();
+
+    let condition = children.next()
+        .map(transform_expression)
+        .transpose()?
+        .ok_or_else(|| AstError::InvalidExpression("if missing condition".to_string()))?;
+
+    let then_elem = children.next()
+        .map(transform_element)
+        .transpose()?
+        .ok_or_else(|| AstError::InvalidExpression("if missing element".to_string()))?;
+
+    let else_elem = children.next()
+        .map(transform_element)
+        .transpose()?
+        .map(Box::new);
+
+    Ok(ComprehensionElement::If {
+        condition,
+        then_elem: Box::new(then_elem),
+        else_elem,
+    })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openscad_parser::parse as parse_cst;
+
+    fn parse_expr(source: &str) -> Expression {
+        let cst = parse_cst(&format!("x = {};", source));
+        transform_expression(&cst.root.children[0].children[1]).unwrap()
+    }
+
+    #[test]
+    fn test_transform_for() {
+        match parse_expr("[for (i = [0:3]) i * i]") {
+            Expression::ListComprehension(elements) => {
+                assert_eq!(elements.len(), 1);
+                match &elements[0] {
+                    ComprehensionElement::For { assignments, body } => {
+                        assert_eq!(assignments[0].0, "i");
+                        assert!(matches!(**body, ComprehensionElement::Expr(_)));
+                    }
+                    _ => panic!("Expected For"),
+                }
+            }
+            _ => panic!("Expected ListComprehension"),
+        }
+    }
+
+    #[test]
+    fn test_transform_mixed_elements() {
+        match parse_expr("[0, each v, for (i = v) if (i > 0) i else -i]") {
+            Expression::ListComprehension(elements) => {
+                assert_eq!(elements.len(), 3);
+                assert!(matches!(elements[0], ComprehensionElement::Expr(_)));
+                assert!(matches!(elements[1], ComprehensionElement::Each(_)));
+                match &elements[2] {
+                    ComprehensionElement::For { body, .. } => {
+                        assert!(matches!(
+                            **body,
+                            ComprehensionElement::If { else_elem: Some(_), .. }
+                        ));
+                    }
+                    _ => panic!("Expected For"),
+                }
+            }
+            _ => panic!("Expected ListComprehension"),
+        }
+    }
+}
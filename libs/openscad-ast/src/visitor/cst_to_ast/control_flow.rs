@@ -7,6 +7,7 @@
 //! - Blocks: `{ ... }`
 //! - For loops: `for (i = [0:10]) { ... }`
 //! - If/else: `if (condition) { ... } else { ... }`
+//! - Let blocks: `let (x = 10) { ... }`
 //!
 //! ## Example
 //!
@@ -58,12 +59,14 @@ pub fn transform_block(node: &CstNode) -> Result<Statement, AstError> {
 
 /// Transform for loop node.
 ///
-/// For loops have assignments and a body statement.
+/// For loops have assignments and a body statement. An
+/// `IntersectionForBlock` has the same structure and becomes
+/// [`Statement::IntersectionFor`].
 ///
 /// ## CST Structure
 ///
 /// ```text
-/// ForBlock
+/// ForBlock | IntersectionForBlock
 /// ├── ForAssignments
 /// │   └── ForAssignment
 /// │       ├── Identifier (variable name)
@@ -76,6 +79,7 @@ pub fn transform_block(node: &CstNode) -> Result<Statement, AstError> {
 /// ```text
 /// for (i = [0:10]) cube(i);
 /// for (i = [0:10], j = [0:5]) translate([i, j, 0]) cube(1);
+/// intersection_for (a = [0, 60]) rotate(a) cube([10, 2, 2], center = true);
 /// ```
 pub fn transform_for_block(node: &CstNode) -> Result<Statement, AstError> {
     let mut assignments = Vec::new();
@@ -102,10 +106,10 @@ pub fn transform_for_block(node: &CstNode) -> Result<Statement, AstError> {
         }
     }
     
-    Ok(Statement::ForLoop {
-        assignments,
-        body,
-        span: node.span,
+    let span = node.span;
+    Ok(match node.kind {
+        NodeKind::IntersectionForBlock => Statement::IntersectionFor { assignments, body, span },
+        _ => Statement::ForLoop { assignments, body, span },
     })
 }
 
@@ -125,10 +129,9 @@ fn transform_for_assignment(node: &CstNode) -> Result<Option<(String, Expression
             "For assignment missing variable name".to_string()
         ))?;
     
-    // Value is the non-identifier child
-    let value = node.children.iter()
-        .find(|c| c.kind != NodeKind::Identifier)
-        .map(|c| transform_expression(c))
+    // Value is the second child; it may itself be an identifier (`i = pts`)
+    let value = node.children.get(1)
+        .map(transform_expression)
         .transpose()?
         .ok_or_else(|| AstError::InvalidCst(
             "For assignment missing value".to_string()
@@ -137,6 +140,70 @@ fn transform_for_assignment(node: &CstNode) -> Result<Option<(String, Expression
     Ok(Some((name, value)))
 }
 
+// =============================================================================
+// LET
+// =============================================================================
+
+/// Transform let block node.
+///
+/// ## CST Structure
+///
+/// ```text
+/// LetBlock
+/// ├── ForAssignments
+/// │   └── ForAssignment
+/// │       ├── Identifier (variable name)
+/// │       └── Expression (value)
+/// └── Statement (body)
+/// ```
+///
+/// ## Example
+///
+/// ```text
+/// let (x = 10) cube(x);
+/// let (x = 10, y = x * 2) translate([x, y, 0]) cube(5);
+/// ```
+pub fn transform_let_block(node: &CstNode) -> Result<Statement, AstError> {
+    let mut assignments = Vec::new();
+    let mut body = Vec::new();
+
+    for child in &node.children {
+        match child.kind {
+            NodeKind::ForAssignments => {
+                assignments = transform_assignment_list(child)?;
+            }
+            _ => {
+                if let Some(stmt) = transform_statement(child)? {
+                    body.push(stmt);
+                }
+            }
+        }
+    }
+
+    Ok(Statement::Let {
+        assignments,
+        body,
+        span: node.span,
+    })
+}
+
+/// Transform a `ForAssignments` list (let blocks, let expressions and
+/// comprehension generators).
+///
+/// Bindings keep their source order, since later bindings may refer to
+/// earlier ones.
+pub(super) fn transform_assignment_list(node: &CstNode) -> Result<Vec<(String, Expression)>, AstError> {
+    let mut assignments = Vec::new();
+    for assign in &node.children {
+        if assign.kind == NodeKind::ForAssignment {
+            if let Some(assignment) = transform_for_assignment(assign)? {
+                assignments.push(assignment);
+            }
+        }
+    }
+    Ok(assignments)
+}
+
 // =============================================================================
 // IF/ELSE
 // =============================================================================
@@ -165,13 +232,13 @@ pub fn transform_if_block(node: &CstNode) -> Result<Statement, AstError> {
     
     // First child is condition
     let condition = children.next()
-        .map(|c| transform_expression(c))
+        .map(transform_expression)
         .transpose()?
         .unwrap_or(Expression::Boolean(true));
     
     // Second child is then body
     let then_body = children.next()
-        .map(|c| transform_statement(c))
+        .map(transform_statement)
         .transpose()?
         .flatten()
         .map(|s| vec![s])
@@ -179,7 +246,7 @@ pub fn transform_if_block(node: &CstNode) -> Result<Statement, AstError> {
     
     // Optional else body
     let else_body = children.next()
-        .map(|c| transform_statement(c))
+        .map(transform_statement)
         .transpose()?
         .flatten()
         .map(|s| vec![s]);
@@ -231,6 +298,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_transform_intersection_for() {
+        let cst = parse_cst("intersection_for (a = [0, 60]) rotate(a) cube(1);");
+        let block = &cst.root.children[0];
+        let stmt = transform_for_block(block).unwrap();
+
+        match stmt {
+            Statement::IntersectionFor { assignments, body, .. } => {
+                assert_eq!(assignments[0].0, "a");
+                assert_eq!(body.len(), 1);
+            }
+            _ => panic!("Expected IntersectionFor"),
+        }
+    }
+
     #[test]
     fn test_transform_if() {
         let cst = parse_cst("if (true) cube(10);");
@@ -261,4 +343,21 @@ mod tests {
             _ => panic!("Expected IfElse"),
         }
     }
+
+    #[test]
+    fn test_transform_let_block() {
+        let cst = parse_cst("let (x = 10, y = x) cube(y);");
+        let let_block = &cst.root.children[0];
+        let stmt = transform_let_block(let_block).unwrap();
+        
+        match stmt {
+            Statement::Let { assignments, body, .. } => {
+                assert_eq!(assignments.len(), 2);
+                assert_eq!(assignments[0].0, "x");
+                assert!(matches!(&assignments[1].1, Expression::Identifier(n) if n == "x"));
+                assert_eq!(body.len(), 1);
+            }
+            _ => panic!("Expected Let"),
+        }
+    }
 }
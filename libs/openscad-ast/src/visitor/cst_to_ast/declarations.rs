@@ -43,23 +43,22 @@ use super::expressions::transform_expression;
 /// $fn = 32;
 /// ```
 pub fn transform_assignment(node: &CstNode) -> Result<Statement, AstError> {
-    // Name can be Identifier or SpecialVariable
-    let name = node.find_child(NodeKind::Identifier)
-        .or_else(|| node.find_child(NodeKind::SpecialVariable))
+    // Name is the first child, Identifier or SpecialVariable
+    let name = node.children.first()
+        .filter(|n| n.kind == NodeKind::Identifier || n.kind == NodeKind::SpecialVariable)
         .map(|n| n.text_or_empty().to_string())
         .ok_or_else(|| AstError::InvalidCst(
             "Assignment missing name".to_string()
         ))?;
-    
-    // Value is the non-identifier/non-special-variable child
-    let value = node.children.iter()
-        .find(|c| c.kind != NodeKind::Identifier && c.kind != NodeKind::SpecialVariable)
-        .map(|c| transform_expression(c))
+
+    // Value is the expression after it, which may itself be a variable
+    let value = node.children.get(1)
+        .map(transform_expression)
         .transpose()?
         .ok_or_else(|| AstError::InvalidCst(
             "Assignment missing value".to_string()
         ))?;
-    
+
     Ok(Statement::Assignment {
         name,
         value,
@@ -98,7 +97,7 @@ pub fn transform_module_declaration(node: &CstNode) -> Result<Statement, AstErro
     
     // Parse parameters (reuse function parameter parsing)
     let params = node.find_child(NodeKind::Parameters)
-        .map(|p| transform_parameters(p))
+        .map(transform_parameters)
         .transpose()?
         .unwrap_or_default();
     
@@ -146,15 +145,14 @@ pub fn transform_function_declaration(node: &CstNode) -> Result<Statement, AstEr
     
     // Parse parameters
     let params = node.find_child(NodeKind::Parameters)
-        .map(|p| transform_parameters(p))
+        .map(transform_parameters)
         .transpose()?
         .unwrap_or_default();
     
     // Body is the expression child (not identifier, not parameters)
     let body = node.children.iter()
-        .filter(|c| c.kind != NodeKind::Identifier && c.kind != NodeKind::Parameters)
-        .last()
-        .map(|c| transform_expression(c))
+        .rfind(|c| c.kind != NodeKind::Identifier && c.kind != NodeKind::Parameters)
+        .map(transform_expression)
         .transpose()?
         .unwrap_or(Expression::Undef);
     
@@ -177,7 +175,7 @@ pub fn transform_function_declaration(node: &CstNode) -> Result<Statement, AstEr
 /// ```
 fn transform_parameters(node: &CstNode) -> Result<Vec<Parameter>, AstError> {
     node.children.iter()
-        .map(|p| transform_parameter(p))
+        .map(transform_parameter)
         .collect()
 }
 
@@ -190,10 +188,8 @@ fn transform_parameter(node: &CstNode) -> Result<Parameter, AstError> {
         ))?;
     
     // Check for default value (second child after identifier)
-    let default = node.children.iter()
-        .skip(1)
-        .next()
-        .map(|c| transform_expression(c))
+    let default = node.children.get(1)
+        .map(transform_expression)
         .transpose()?;
     
     Ok(Parameter { name, default })
@@ -237,6 +233,21 @@ mod tests {
         }
     }
 
+    /// Test a variable on the right-hand side is the value, not a name.
+    #[test]
+    fn test_transform_assignment_variable() {
+        for (source, expected) in [("y = x;", "x"), ("$fn = $fa;", "$fa")] {
+            let cst = parse_cst(source);
+            let stmt = transform_assignment(&cst.root.children[0]).unwrap();
+            match stmt {
+                Statement::Assignment { value: Expression::Identifier(v) | Expression::SpecialVariable(v), .. } => {
+                    assert_eq!(v, expected);
+                }
+                other => panic!("Expected Assignment of a variable, got {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn test_transform_module_declaration() {
         let cst = parse_cst("module foo() { cube(10); }");
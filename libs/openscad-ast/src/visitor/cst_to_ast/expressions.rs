@@ -21,6 +21,8 @@ use openscad_parser::{CstNode, NodeKind};
 use super::literals::{transform_number, transform_string, transform_boolean, transform_undef};
 use super::operators::{transform_binary, transform_unary, transform_ternary};
 use super::arguments::transform_arguments;
+use super::control_flow::transform_assignment_list;
+use super::comprehensions::transform_list_comprehension;
 
 // =============================================================================
 // PUBLIC API
@@ -51,10 +53,12 @@ pub fn transform_expression(node: &CstNode) -> Result<Expression, AstError> {
         
         // Compound expressions
         NodeKind::List => transform_list(node),
+        NodeKind::ListComprehension => transform_list_comprehension(node),
         NodeKind::Range => transform_range(node),
         NodeKind::BinaryExpression => transform_binary(node),
         NodeKind::UnaryExpression => transform_unary(node),
         NodeKind::TernaryExpression => transform_ternary(node),
+        NodeKind::LetExpression => transform_let(node),
         NodeKind::FunctionCall => transform_function_call(node),
         NodeKind::IndexExpression => transform_index(node),
         NodeKind::DotExpression => transform_member(node),
@@ -125,6 +129,33 @@ fn transform_range(node: &CstNode) -> Result<Expression, AstError> {
     }
 }
 
+/// Transform let expression.
+///
+/// ## CST Structure
+///
+/// ```text
+/// LetExpression
+/// ├── ForAssignments
+/// └── Expression (body)
+/// ```
+fn transform_let(node: &CstNode) -> Result<Expression, AstError> {
+    let assignments = node.find_child(NodeKind::ForAssignments)
+        .map(transform_assignment_list)
+        .transpose()?
+        .unwrap_or_default();
+
+    let body = node.children.iter()
+        .find(|c| c.kind != NodeKind::ForAssignments)
+        .map(transform_expression)
+        .transpose()?
+        .ok_or_else(|| AstError::InvalidExpression("Let expression missing body".to_string()))?;
+
+    Ok(Expression::Let {
+        assignments,
+        body: Box::new(body),
+    })
+}
+
 /// Transform function call.
 ///
 /// ## CST Structure
@@ -215,6 +246,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_transform_float() {
         let expr = parse_expr("3.14");
         match expr {
@@ -262,4 +294,17 @@ mod tests {
             _ => panic!("Expected BinaryOp"),
         }
     }
+
+    #[test]
+    fn test_transform_let() {
+        let expr = parse_expr("let (a = 1, b = a) a + b");
+        match expr {
+            Expression::Let { assignments, body } => {
+                assert_eq!(assignments.len(), 2);
+                assert_eq!(assignments[1].0, "b");
+                assert!(matches!(*body, Expression::BinaryOp { op: BinaryOp::Add, .. }));
+            }
+            _ => panic!("Expected Let"),
+        }
+    }
 }
@@ -156,6 +156,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_transform_float() {
         let expr = parse_literal("3.14");
         match expr {
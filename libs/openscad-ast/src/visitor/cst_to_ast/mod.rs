@@ -9,7 +9,8 @@
 //! - `arguments` - Shared argument transformation
 //! - `literals` - Literal transformations (number, string, boolean)
 //! - `operators` - Operator transformations (binary, unary, ternary)
-//! - `control_flow` - Control flow (for, if/else, blocks)
+//! - `control_flow` - Control flow (for, if/else, let, blocks)
+//! - `comprehensions` - List comprehension clauses (for, if, each, let)
 //! - `declarations` - Declarations (assignment, module, function)
 //!
 //! ## Example
@@ -31,6 +32,7 @@ mod arguments;
 mod literals;
 mod operators;
 mod control_flow;
+mod comprehensions;
 mod declarations;
 
 use crate::ast::Ast;
@@ -14,12 +14,12 @@
 //! let statements = transform_statements(&cst.root.children)?;
 //! ```
 
-use crate::ast::Statement;
+use crate::ast::{Modifier, Statement};
 use crate::error::AstError;
 use openscad_parser::{CstNode, NodeKind};
 
 use super::arguments::transform_arguments;
-use super::control_flow::{transform_block, transform_for_block, transform_if_block};
+use super::control_flow::{transform_block, transform_for_block, transform_if_block, transform_let_block};
 use super::declarations::{transform_assignment, transform_module_declaration, transform_function_declaration};
 
 // =============================================================================
@@ -76,23 +76,39 @@ pub fn transform_statement(node: &CstNode) -> Result<Option<Statement>, AstError
         NodeKind::Block => {
             Ok(Some(transform_block(node)?))
         }
-        NodeKind::ForBlock => {
+        NodeKind::ForBlock | NodeKind::IntersectionForBlock => {
             Ok(Some(transform_for_block(node)?))
         }
         NodeKind::IfBlock => {
             Ok(Some(transform_if_block(node)?))
         }
+        NodeKind::LetBlock => {
+            Ok(Some(transform_let_block(node)?))
+        }
         
+        // File statements
+        NodeKind::IncludeStatement => {
+            Ok(Some(Statement::Include { path: file_path(node)?, span: node.span }))
+        }
+        NodeKind::UseStatement => {
+            Ok(Some(Statement::Use { path: file_path(node)?, span: node.span }))
+        }
+
         // Skip non-statement nodes
         NodeKind::Semicolon | NodeKind::Comment => Ok(None),
         
         // Modifier wraps another statement
         NodeKind::Modifier => {
-            if let Some(child) = node.children.last() {
-                transform_statement(child)
-            } else {
-                Ok(None)
-            }
+            let [mark, child] = node.children.as_slice() else {
+                return Err(AstError::InvalidCst("modifier missing statement".to_string()));
+            };
+            let modifier = Modifier::from_str(mark.text_or_empty())
+                .ok_or_else(|| AstError::InvalidCst(format!("unknown modifier '{}'", mark.text_or_empty())))?;
+            Ok(transform_statement(child)?.map(|statement| Statement::Modified {
+                modifier,
+                statement: Box::new(statement),
+                span: node.span,
+            }))
         }
         
         _ => {
@@ -103,6 +119,13 @@ pub fn transform_statement(node: &CstNode) -> Result<Option<Statement>, AstError
     }
 }
 
+/// Path of an include/use statement.
+fn file_path(node: &CstNode) -> Result<String, AstError> {
+    node.find_child(NodeKind::FilePath)
+        .map(|n| n.text_or_empty().to_string())
+        .ok_or_else(|| AstError::InvalidCst("include/use missing file path".to_string()))
+}
+
 // =============================================================================
 // MODULE CALL
 // =============================================================================
@@ -214,4 +237,30 @@ mod tests {
             _ => panic!("Expected ModuleCall"),
         }
     }
+
+    #[test]
+    fn test_transform_include_and_use() {
+        let cst = parse_cst("include <parts/bolt.scad>\nuse <lib.scad>\n");
+        let stmts = transform_statements(&cst.root.children).unwrap();
+
+        assert_eq!(stmts.len(), 2);
+        assert!(matches!(&stmts[0], Statement::Include { path, .. } if path == "parts/bolt.scad"));
+        assert!(matches!(&stmts[1], Statement::Use { path, .. } if path == "lib.scad"));
+    }
+
+    #[test]
+    fn test_transform_modifier() {
+        let cst = parse_cst("#translate([1,2,3]) cube(10); *sphere(5);");
+        let stmts = transform_statements(&cst.root.children).unwrap();
+
+        assert_eq!(stmts.len(), 2);
+        match &stmts[0] {
+            Statement::Modified { modifier, statement, .. } => {
+                assert_eq!(*modifier, Modifier::Highlight);
+                assert!(matches!(statement.as_ref(), Statement::ModuleCall { name, .. } if name == "translate"));
+            }
+            _ => panic!("Expected Modified"),
+        }
+        assert!(matches!(&stmts[1], Statement::Modified { modifier: Modifier::Disable, .. }));
+    }
 }
@@ -0,0 +1,111 @@
+//! # Capabilities
+//!
+//! The modules and functions this evaluator understands, for front-ends
+//! that feature-detect instead of assuming full OpenSCAD support.
+//!
+//! Every name listed here is dispatched by the evaluator; the tests below
+//! keep the lists and the dispatch tables in sync.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::capabilities::{BUILTIN_MODULES, EXTENSION_MODULES};
+//!
+//! assert!(BUILTIN_MODULES.contains(&"cube"));
+//! assert!(EXTENSION_MODULES.contains(&"smooth"));
+//! ```
+
+/// Built-in OpenSCAD modules.
+pub const BUILTIN_MODULES: &[&str] = &[
+    // 3D primitives
+    "cube", "sphere", "cylinder", "polyhedron", "import", "surface",
+    // 2D primitives
+    "circle", "square", "polygon", "text",
+    // Booleans
+    "union", "difference", "intersection", "hull", "minkowski",
+    // Transforms
+    "translate", "rotate", "scale", "resize", "mirror", "multmatrix", "color",
+    // Extrusions and 2D operations
+    "linear_extrude", "rotate_extrude", "offset", "projection",
+    // Structure
+    "children",
+    // Console output and checks
+    "echo", "assert",
+];
+
+/// Modules this engine adds on top of OpenSCAD.
+pub const EXTENSION_MODULES: &[&str] = &["smooth", "quality", "echo_dim"];
+
+/// Primitives this engine adds, available with
+/// [`EvalOptions::extensions`](crate::EvalOptions::extensions).
+pub const EXTENSION_PRIMITIVES: &[&str] = &["torus", "prism", "rounded_cube"];
+
+/// Operations this engine adds, available with
+/// [`EvalOptions::extensions`](crate::EvalOptions::extensions).
+pub const EXTENSION_OPERATIONS: &[&str] = &["refine", "warp_twist", "warp_taper", "warp_bend"];
+
+/// Built-in functions.
+pub const BUILTIN_FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "asin", "acos", "atan", "atan2",
+    "abs", "sign", "floor", "ceil", "round",
+    "sqrt", "pow", "exp", "ln", "log",
+    "min", "max", "norm", "len", "cross", "rands",
+    "is_undef", "is_num", "is_bool", "is_string", "is_list",
+    "concat", "lookup", "search",
+    "str", "chr", "ord",
+    "path_length", "resample_path", "offset_path",
+];
+
+/// Statements beyond assignments and module calls.
+pub const STATEMENTS: &[&str] = &[
+    "module", "function", "for", "intersection_for", "if", "let", "include", "use",
+];
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{evaluate, EvalError};
+
+    /// Check a call is dispatched rather than reported as unknown.
+    fn is_known(source: &str, unknown: &str) -> bool {
+        match evaluate(source) {
+            Ok(result) => !result.warnings.iter().any(|w| w.starts_with(unknown)),
+            Err(e) => !matches!(e, EvalError::UnknownIdentifier(_)),
+        }
+    }
+
+    /// Test every listed module is dispatched.
+    #[test]
+    fn test_modules_known() {
+        for name in BUILTIN_MODULES.iter().chain(EXTENSION_MODULES) {
+            assert!(is_known(&format!("{}();", name), "Unknown module"), "{}", name);
+        }
+        assert!(!is_known("no_such_module();", "Unknown module"));
+    }
+
+    /// Test the extension primitives and operations are only dispatched
+    /// with the option.
+    #[test]
+    fn test_primitives_gated() {
+        let options = crate::EvalOptions { extensions: true, ..crate::EvalOptions::default() };
+        for name in EXTENSION_PRIMITIVES.iter().chain(EXTENSION_OPERATIONS) {
+            let source = format!("{}();", name);
+            assert!(!is_known(&source, "Unknown module"), "{}", name);
+            let result = crate::evaluate_with_options(&source, &options).unwrap();
+            assert!(!result.warnings.iter().any(|w| w.starts_with("Unknown module")), "{}", name);
+        }
+    }
+
+    /// Test every listed function is dispatched.
+    #[test]
+    fn test_functions_known() {
+        for name in BUILTIN_FUNCTIONS {
+            assert!(is_known(&format!("x = [{}(1)];", name), "Unknown function"), "{}", name);
+        }
+        assert!(!is_known("x = [no_such_function(1)];", "Unknown function"));
+    }
+}
@@ -0,0 +1,145 @@
+//! # Constant Folding
+//!
+//! Values of a program's top-level variables, computed with the evaluator
+//! but without running any module call, for editors that show values
+//! while the user types.
+//!
+//! ## Rules
+//!
+//! - Function declarations are registered first, so constants may call
+//!   them wherever they are declared
+//! - Assignments are folded in order; a later assignment of the same name
+//!   replaces the value
+//! - An assignment that reads an undefined variable, warns or fails is not
+//!   a constant, and neither is anything that reads it
+//! - Module calls, `include` and `use` are skipped
+//!
+//! Each expression runs under small time and iteration limits, so a runaway
+//! list comprehension gives no value rather than a stalled editor.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::constants::ConstantFolder;
+//! use openscad_eval::Value;
+//!
+//! let ast = openscad_ast::parse("function twice(x) = 2 * x; w = 5; x = twice(w) + 5; cube(x);").unwrap();
+//! let folder = ConstantFolder::new(&ast);
+//! assert_eq!(folder.value("x"), Some(&Value::Number(15.0)));
+//! ```
+
+use std::collections::BTreeMap;
+
+use openscad_ast::{Ast, Expression, Statement};
+
+use crate::limits::{now_ms, Limits};
+use crate::scope::Scope;
+use crate::value::Value;
+use crate::visitor::expressions::eval_expr;
+use crate::visitor::context::Usage;
+use crate::visitor::EvalContext;
+
+/// Time budget of each folded expression, in milliseconds.
+const MAX_TIME_MS: u64 = 50;
+
+/// Loop iterations allowed in each folded expression.
+const MAX_ITERATIONS: u64 = 100_000;
+
+/// Top-level constants of a program.
+pub struct ConstantFolder {
+    /// Context holding the program's functions.
+    ctx: EvalContext,
+    /// Folded variables by name.
+    values: BTreeMap<String, Value>,
+}
+
+impl ConstantFolder {
+    /// Fold the top-level assignments of a program.
+    pub fn new(ast: &Ast) -> Self {
+        let mut ctx = EvalContext::new();
+        ctx.limits = Limits { max_time_ms: Some(MAX_TIME_MS), max_iterations: Some(MAX_ITERATIONS), ..Limits::default() };
+        let mut folder = Self { ctx, values: BTreeMap::new() };
+
+        for statement in &ast.statements {
+            if let Statement::FunctionDeclaration { name, params, body, .. } = statement {
+                folder.ctx.define_function(name.clone(), params.clone(), body.clone());
+            }
+        }
+        for statement in &ast.statements {
+            let Statement::Assignment { name, value, .. } = statement else { continue };
+            // Readers of a name that is not constant must not see an
+            // earlier value either
+            match folder.fold(value) {
+                Some(folded) => folder.values.insert(name.clone(), folded),
+                None => folder.values.remove(name),
+            };
+        }
+        folder
+    }
+
+    /// Value of a top-level variable, if it is a constant.
+    #[must_use]
+    pub fn value(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    /// All constants by name.
+    #[must_use]
+    pub fn values(&self) -> &BTreeMap<String, Value> {
+        &self.values
+    }
+
+    /// Fold an expression in the top-level scope.
+    ///
+    /// ## Returns
+    ///
+    /// The value, or `None` if evaluating it warns, fails or reads a
+    /// variable that is not a constant.
+    pub fn fold(&mut self, expression: &Expression) -> Option<Value> {
+        // Only constants are defined, so reading anything else warns
+        let mut scope = Scope::new();
+        for (name, value) in &self.values {
+            scope.define(name, value.clone());
+        }
+        self.ctx.scope = scope;
+        self.ctx.usage = Usage { started_ms: now_ms(), ..Usage::default() };
+        let warnings = self.ctx.warnings.len();
+        let value = eval_expr(&mut self.ctx, expression).ok()?;
+        (self.ctx.warnings.len() == warnings).then_some(value)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fold(source: &str) -> ConstantFolder {
+        ConstantFolder::new(&openscad_ast::parse(source).unwrap())
+    }
+
+    /// Test constants fold through functions declared later and
+    /// reassignment.
+    #[test]
+    fn test_fold() {
+        let folder = fold("a = half(b); b = 10; function half(x) = x / 2; c = [b, \"s\"]; b = 4; $fn = 32;");
+        assert_eq!(folder.value("b"), Some(&Value::Number(4.0)));
+        assert_eq!(folder.value("c").map(Value::to_echo_string).as_deref(), Some("[10, \"s\"]"));
+        assert_eq!(folder.value("$fn"), Some(&Value::Number(32.0)));
+        // b was undefined when a was folded
+        assert!(folder.value("a").is_none());
+    }
+
+    /// Test names that are not constants spread to their readers.
+    #[test]
+    fn test_not_constant() {
+        let folder = fold("include <lib.scad>\nx = lib_size; y = x + 1; z = [for (i = [0:1e9]) i]; w = y;");
+        assert!(folder.value("x").is_none());
+        assert!(folder.value("y").is_none());
+        assert!(folder.value("w").is_none());
+        assert!(folder.value("z").is_none());
+    }
+}
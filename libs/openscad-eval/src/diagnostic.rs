@@ -0,0 +1,58 @@
+//! # Diagnostics
+//!
+//! Non-fatal problems found while evaluating a script. The script still
+//! produces geometry; each diagnostic says what was ignored or adjusted
+//! and where.
+//!
+//! Every diagnostic's text is also in [`EvaluatedAst::warnings`], which
+//! keeps the plain strings for callers that only print them.
+//!
+//! [`EvaluatedAst::warnings`]: crate::EvaluatedAst::warnings
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::{evaluate, DiagnosticKind};
+//!
+//! let result = evaluate("cube(10, rounding = 2);").unwrap();
+//! let diagnostic = &result.diagnostics[0];
+//! assert_eq!(diagnostic.kind, DiagnosticKind::IgnoredArgument);
+//! assert_eq!(diagnostic.text, "cube(): variable rounding not specified as parameter");
+//! assert_eq!(diagnostic.span.unwrap().start.column, 0);
+//! ```
+
+use openscad_ast::Span;
+use serde::{Deserialize, Serialize};
+
+/// What a diagnostic is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum DiagnosticKind {
+    /// An argument that no parameter takes, or one given twice.
+    IgnoredArgument,
+    /// A primitive with a negative or zero size, clamped or left out.
+    Degenerate,
+    /// An operand of a boolean operation with no geometry.
+    EmptyGeometry,
+    /// An operand that cannot change the result, such as a subtrahend of
+    /// `difference()` clear of the first child.
+    NoEffect,
+    /// Syntax OpenSCAD still accepts but has deprecated.
+    Deprecated,
+    /// Anything else: unknown modules, bad function arguments, missing
+    /// files.
+    General,
+}
+
+/// One non-fatal problem.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct Diagnostic {
+    /// What the diagnostic is about.
+    pub kind: DiagnosticKind,
+    /// The warning, as in [`EvaluatedAst::warnings`](crate::EvaluatedAst::warnings).
+    pub text: String,
+    /// The statement being evaluated, if known.
+    pub span: Option<Span>,
+}
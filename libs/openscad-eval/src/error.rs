@@ -2,8 +2,11 @@
 //!
 //! Error types for AST evaluation.
 
+use openscad_ast::Span;
 use thiserror::Error;
 
+use crate::limits::{LimitExceeded, RecursionLimit};
+
 /// Errors that can occur during evaluation.
 #[derive(Debug, Clone, Error)]
 pub enum EvalError {
@@ -34,6 +37,61 @@ pub enum EvalError {
     /// Invalid range.
     #[error("Invalid range: {0}")]
     InvalidRange(String),
+
+    /// Compiled library bundle could not be read.
+    #[error("Invalid library bundle: {0}")]
+    InvalidLibrary(String),
+
+    /// Serialized evaluation result could not be read or written.
+    #[error("Invalid geometry JSON: {0}")]
+    InvalidGeometry(String),
+
+    /// File included or used from within itself.
+    #[error("Recursive include: {0}")]
+    RecursiveInclude(String),
+
+    /// A configured limit aborted the evaluation.
+    #[error("{0}")]
+    LimitExceeded(Box<LimitExceeded>),
+
+    /// User functions or modules nested deeper than allowed.
+    #[error("{0}")]
+    RecursionLimit(Box<RecursionLimit>),
+
+    /// The render was cancelled through its `CancellationToken`.
+    #[error("Render cancelled")]
+    Cancelled,
+
+    /// An `assert()` condition was false.
+    #[error(
+        "Assertion failed at {}:{}{}",
+        span.start.line + 1,
+        span.start.column + 1,
+        message.as_ref().map(|m| format!(": {}", m)).unwrap_or_default()
+    )]
+    AssertionFailed {
+        /// The assert's message argument, formatted, if given.
+        message: Option<String>,
+        /// Source span of the assert.
+        span: Span,
+    },
+}
+
+impl EvalError {
+    /// Whether the error ends the whole evaluation.
+    ///
+    /// Most errors only skip the statement or argument that caused them;
+    /// limits, runaway recursion, cancellation and failed assertions must
+    /// not be swallowed that way.
+    pub fn aborts_evaluation(&self) -> bool {
+        matches!(
+            self,
+            EvalError::LimitExceeded(_)
+                | EvalError::RecursionLimit(_)
+                | EvalError::Cancelled
+                | EvalError::AssertionFailed { .. }
+        )
+    }
 }
 
 // =============================================================================
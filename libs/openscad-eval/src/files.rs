@@ -0,0 +1,251 @@
+//! # File Providers
+//!
+//! Where `include <file>` and `use <file>` read their sources from, and
+//! where the data files of `import()` and `surface()` are found.
+//!
+//! ## Providers
+//!
+//! - [`MemoryFileProvider`]: an in-memory map of names to sources, for
+//!   WASM and tests
+//! - [`FsFileProvider`]: the native filesystem, with a root directory and
+//!   library search paths (not available on wasm32)
+//!
+//! ## Resolution
+//!
+//! Paths are resolved relative to the directory of the file containing the
+//! `include`/`use` first, then as given (for the memory provider) or
+//! against the library paths (for the filesystem provider). The resolved
+//! name is what later relative paths in that file resolve against.
+//!
+//! `import()` and `surface()` files are binary and read when meshing, so
+//! providers only [resolve](FileProvider::resolve) their names: the
+//! filesystem provider to the path next to the file naming them, like
+//! `include`. The memory provider leaves names as written, for the
+//! renderer's registered files.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::files::{FileProvider, MemoryFileProvider};
+//!
+//! let mut files = MemoryFileProvider::new();
+//! files.insert("lib/shapes.scad", "module box() { cube(1); }");
+//!
+//! let file = files.load("shapes.scad", Some("lib/main.scad")).unwrap();
+//! assert_eq!(file.name, "lib/shapes.scad");
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+
+// =============================================================================
+// PROVIDER TRAIT
+// =============================================================================
+
+/// A source file loaded for `include` or `use`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    /// Resolved name, used for nested relative paths and cycle detection.
+    pub name: String,
+    /// File contents.
+    pub source: String,
+}
+
+/// Loads files named by `include <path>` and `use <path>`.
+pub trait FileProvider: fmt::Debug + Send + Sync {
+    /// Load `path` as written in the source.
+    ///
+    /// ## Parameters
+    ///
+    /// - `path`: Path between the angle brackets
+    /// - `from`: Resolved name of the including file, `None` for the main
+    ///   source
+    ///
+    /// ## Returns
+    ///
+    /// The file, or `None` if it cannot be found or read.
+    fn load(&self, path: &str, from: Option<&str>) -> Option<SourceFile>;
+
+    /// Resolve the name of an `import()` or `surface()` file, which the
+    /// renderer reads.
+    ///
+    /// ## Parameters
+    ///
+    /// - `path`: File name as written in the source
+    /// - `from`: Resolved name of the file naming it, `None` for the main
+    ///   source
+    ///
+    /// ## Returns
+    ///
+    /// The name to read, or `None` to read `path` as written.
+    fn resolve(&self, path: &str, from: Option<&str>) -> Option<String> {
+        let _ = (path, from);
+        None
+    }
+}
+
+// =============================================================================
+// MEMORY PROVIDER
+// =============================================================================
+
+/// In-memory files by `/`-separated name.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileProvider {
+    files: BTreeMap<String, String>,
+}
+
+impl MemoryFileProvider {
+    /// Create an empty provider.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a file. The name is normalised (`./` and `..`
+    /// segments resolved).
+    pub fn insert(&mut self, name: &str, source: impl Into<String>) {
+        self.files.insert(normalize(name), source.into());
+    }
+
+    /// Remove all files.
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
+
+    /// Names of the stored files, sorted.
+    pub fn names(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+}
+
+impl FileProvider for MemoryFileProvider {
+    fn load(&self, path: &str, from: Option<&str>) -> Option<SourceFile> {
+        let relative = from
+            .and_then(|f| f.rsplit_once('/'))
+            .map(|(dir, _)| normalize(&format!("{}/{}", dir, path)));
+        relative.into_iter()
+            .chain(std::iter::once(normalize(path)))
+            .find_map(|name| {
+                self.files.get(&name).map(|source| SourceFile { name, source: source.clone() })
+            })
+    }
+}
+
+/// Resolve `.` and `..` segments and drop empty ones.
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+// =============================================================================
+// FILESYSTEM PROVIDER
+// =============================================================================
+
+/// Files on the native filesystem.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Default)]
+pub struct FsFileProvider {
+    /// Directory the main source's paths resolve against.
+    pub root: PathBuf,
+    /// Library directories searched after the including file's directory
+    /// (like `OPENSCADPATH`).
+    pub library_paths: Vec<PathBuf>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FsFileProvider {
+    /// Create a provider resolving the main source's paths against `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), library_paths: Vec::new() }
+    }
+
+    /// Add a library search directory.
+    pub fn with_library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_paths.push(path.into());
+        self
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileProvider for FsFileProvider {
+    fn load(&self, path: &str, from: Option<&str>) -> Option<SourceFile> {
+        let base = match from {
+            Some(file) => Path::new(file).parent().unwrap_or(Path::new("")).to_path_buf(),
+            None => self.root.clone(),
+        };
+        std::iter::once(base)
+            .chain(self.library_paths.iter().cloned())
+            .map(|dir| dir.join(path))
+            .find_map(|candidate| {
+                let source = std::fs::read_to_string(&candidate).ok()?;
+                Some(SourceFile { name: candidate.display().to_string(), source })
+            })
+    }
+
+    fn resolve(&self, path: &str, from: Option<&str>) -> Option<String> {
+        let base = match from {
+            Some(file) => Path::new(file).parent().unwrap_or(Path::new("")).to_path_buf(),
+            None => self.root.clone(),
+        };
+        let candidate = base.join(path);
+        candidate.is_file().then(|| candidate.display().to_string())
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test paths resolve next to the including file before the root.
+    #[test]
+    fn test_memory_resolution() {
+        let mut files = MemoryFileProvider::new();
+        files.insert("a.scad", "x = 1;");
+        files.insert("lib/a.scad", "x = 2;");
+        files.insert("./lib/b.scad", "x = 3;");
+
+        assert_eq!(files.load("a.scad", None).unwrap().name, "a.scad");
+        assert_eq!(files.load("a.scad", Some("lib/main.scad")).unwrap().name, "lib/a.scad");
+        assert_eq!(files.load("../a.scad", Some("lib/main.scad")).unwrap().name, "a.scad");
+        assert_eq!(files.load("lib/b.scad", Some("main.scad")).unwrap().source, "x = 3;");
+        assert!(files.load("missing.scad", None).is_none());
+    }
+
+    /// Test the filesystem provider searches the root then library paths.
+    #[test]
+    fn test_fs_resolution() {
+        let dir = std::env::temp_dir().join(format!("c4d-files-{}", std::process::id()));
+        let libs = dir.join("libs");
+        std::fs::create_dir_all(&libs).unwrap();
+        std::fs::write(dir.join("main.scad"), "include <shapes.scad>").unwrap();
+        std::fs::write(libs.join("shapes.scad"), "cube(1);").unwrap();
+
+        let provider = FsFileProvider::new(&dir).with_library_path(&libs);
+        let main = provider.load("main.scad", None).unwrap();
+        let shapes = provider.load("shapes.scad", Some(&main.name)).unwrap();
+        assert_eq!(shapes.source, "cube(1);");
+        assert!(provider.load("nope.scad", None).is_none());
+
+        // Data files resolve next to the file naming them, not in libraries
+        std::fs::write(dir.join("part.stl"), "solid").unwrap();
+        assert_eq!(provider.resolve("part.stl", Some(&main.name)), Some(dir.join("part.stl").display().to_string()));
+        assert_eq!(provider.resolve("shapes.scad", None), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
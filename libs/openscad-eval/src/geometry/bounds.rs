@@ -0,0 +1,328 @@
+//! # Bounds
+//!
+//! Axis-aligned boxes around evaluated geometry, worked out from the tree
+//! without meshing it. A box always holds its geometry but may be larger:
+//! a rotated cube gets the box of its rotated corners, a difference the box
+//! of its first child. Nodes whose extent is only known once meshed
+//! (`import()`, `surface()`, `text()`, `warp_bend()` and `resize()` of
+//! those) have no box.
+//!
+//! 2D geometry has a box flat in z.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::evaluate;
+//!
+//! let geometry = evaluate("translate([10, 0, 0]) cube(2, center = true);").unwrap().geometry;
+//! let bounds = geometry.bounds().unwrap();
+//! assert_eq!(bounds.min, [9.0, -1.0, -1.0]);
+//! assert_eq!(bounds.max, [11.0, 1.0, 1.0]);
+//! ```
+
+use super::optimize::{transform_matrix, transform_point, Matrix};
+use super::{Deformation, GeometryNode};
+
+/// An axis-aligned box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    /// Lowest corner.
+    pub min: [f64; 3],
+    /// Highest corner.
+    pub max: [f64; 3],
+}
+
+impl Bounds {
+    /// The box of no geometry, inside out so that it unites with any box
+    /// to that box.
+    pub const EMPTY: Bounds = Bounds { min: [f64::INFINITY; 3], max: [f64::NEG_INFINITY; 3] };
+
+    /// The smallest box holding `points`.
+    pub fn of_points<'a>(points: impl IntoIterator<Item = &'a [f64; 3]>) -> Bounds {
+        points.into_iter().fold(Bounds::EMPTY, |bounds, p| Bounds {
+            min: std::array::from_fn(|i| bounds.min[i].min(p[i])),
+            max: std::array::from_fn(|i| bounds.max[i].max(p[i])),
+        })
+    }
+
+    /// Whether the box holds nothing.
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.min[i] > self.max[i])
+    }
+
+    /// The smallest box holding both boxes.
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        Bounds {
+            min: std::array::from_fn(|i| self.min[i].min(other.min[i])),
+            max: std::array::from_fn(|i| self.max[i].max(other.max[i])),
+        }
+    }
+
+    /// The box both boxes hold.
+    pub fn intersection(&self, other: &Bounds) -> Bounds {
+        let bounds = Bounds {
+            min: std::array::from_fn(|i| self.min[i].max(other.min[i])),
+            max: std::array::from_fn(|i| self.max[i].min(other.max[i])),
+        };
+        if bounds.is_empty() { Bounds::EMPTY } else { bounds }
+    }
+
+    /// Whether the boxes share volume, or area for flat boxes. Boxes that
+    /// only touch do not overlap.
+    pub fn overlaps(&self, other: &Bounds) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && (0..3).all(|i| {
+                let (low, high) = (self.min[i].max(other.min[i]), self.max[i].min(other.max[i]));
+                let flat = self.min[i] == self.max[i] || other.min[i] == other.max[i];
+                low < high || flat && low == high
+            })
+    }
+
+    /// This box moved by `offset`.
+    fn shifted(&self, offset: [f64; 3]) -> Bounds {
+        Bounds {
+            min: std::array::from_fn(|i| self.min[i] + offset[i]),
+            max: std::array::from_fn(|i| self.max[i] + offset[i]),
+        }
+    }
+
+    /// The box of this box transformed by an affine `matrix`.
+    fn transformed(&self, matrix: &Matrix) -> Bounds {
+        if self.is_empty() {
+            return Bounds::EMPTY;
+        }
+        let corners: Vec<[f64; 3]> = (0..8)
+            .map(|c| std::array::from_fn(|i| if c & (1 << i) == 0 { self.min[i] } else { self.max[i] }))
+            .map(|corner| transform_point(matrix, &corner))
+            .collect();
+        Bounds::of_points(&corners)
+    }
+}
+
+impl GeometryNode {
+    /// The box around this geometry, `None` when it is not known without
+    /// meshing; see the [module docs](self).
+    pub fn bounds(&self) -> Option<Bounds> {
+        if let Some(matrix) = transform_matrix(self) {
+            return self.only_child()?.bounds().map(|b| b.transformed(&matrix));
+        }
+        Some(match self {
+            Self::Cube { size, center } | Self::RoundedCube { size, center, .. } => cube(*size, *center),
+            Self::Sphere { radius, .. } => cube([2.0 * radius; 3], true),
+            Self::Cylinder { height, radius1, radius2, center, .. } => {
+                let r = radius1.max(*radius2);
+                cube([2.0 * r, 2.0 * r, *height], false).shifted([-r, -r, if *center { -height / 2.0 } else { 0.0 }])
+            }
+            Self::Torus { major, minor, .. } => {
+                let r = major + minor;
+                cube([2.0 * r, 2.0 * r, 2.0 * minor], true)
+            }
+            Self::Polyhedron { points, .. } => Bounds::of_points(points),
+            Self::Circle { radius, .. } => cube([2.0 * radius, 2.0 * radius, 0.0], true),
+            Self::Square { size: [x, y], center } => cube([*x, *y, 0.0], *center),
+            Self::Polygon { points, .. } => Bounds::of_points(&points.iter().map(|[x, y]| [*x, *y, 0.0]).collect::<Vec<_>>()),
+            Self::Text { .. } | Self::Import { .. } | Self::Surface { .. } | Self::Resize { .. } => return None,
+            Self::Color { child, .. }
+            | Self::Quality { child, .. }
+            | Self::Smooth { child, .. }
+            | Self::Refine { child, .. }
+            | Self::Source { child, .. }
+            | Self::Highlight { child } => return child.bounds(),
+            // Background geometry is shown but not part of the model
+            Self::Background { .. } | Self::Empty => Bounds::EMPTY,
+            Self::Union { children } | Self::Hull { children } | Self::Group { children } => {
+                let mut bounds = Bounds::EMPTY;
+                for child in children {
+                    bounds = bounds.union(&child.bounds()?);
+                }
+                bounds
+            }
+            Self::Difference { children } => match children.first() {
+                Some(first) => first.bounds()?,
+                None => Bounds::EMPTY,
+            },
+            Self::Intersection { children } => {
+                let mut children = children.iter();
+                let Some(first) = children.next() else { return Some(Bounds::EMPTY) };
+                let mut bounds = first.bounds()?;
+                for child in children {
+                    bounds = bounds.intersection(&child.bounds()?);
+                }
+                bounds
+            }
+            Self::Minkowski { children } => {
+                let mut sum: Option<Bounds> = None;
+                for child in children {
+                    let child = child.bounds()?;
+                    if child.is_empty() {
+                        continue;
+                    }
+                    sum = Some(match sum {
+                        Some(b) => Bounds {
+                            min: std::array::from_fn(|i| b.min[i] + child.min[i]),
+                            max: std::array::from_fn(|i| b.max[i] + child.max[i]),
+                        },
+                        None => child,
+                    });
+                }
+                sum.unwrap_or(Bounds::EMPTY)
+            }
+            Self::LinearExtrude { height, twist, scale, center, child, .. } => {
+                let base = child.bounds()?;
+                if base.is_empty() {
+                    return Some(Bounds::EMPTY);
+                }
+                let top = Bounds {
+                    min: [base.min[0] * scale[0], base.min[1] * scale[1], 0.0],
+                    max: [base.max[0] * scale[0], base.max[1] * scale[1], 0.0],
+                };
+                let mut flat = base.union(&Bounds::of_points(&[top.min, top.max]));
+                if *twist != 0.0 {
+                    let r = radius(&flat);
+                    flat = Bounds { min: [-r, -r, 0.0], max: [r, r, 0.0] };
+                }
+                let z = if *center { -height / 2.0 } else { 0.0 };
+                Bounds { min: [flat.min[0], flat.min[1], z], max: [flat.max[0], flat.max[1], z + height] }
+            }
+            Self::Warp { deformation, child } => {
+                let inner = child.bounds()?;
+                if inner.is_empty() {
+                    return Some(Bounds::EMPTY);
+                }
+                match deformation {
+                    Deformation::Twist { .. } => {
+                        let r = radius(&inner);
+                        Bounds { min: [-r, -r, inner.min[2]], max: [r, r, inner.max[2]] }
+                    }
+                    // Each point scales by a factor between 1 and the top's
+                    Deformation::Taper { scale } => {
+                        let top = [0, 1, 2].map(|i| if i < 2 { scale[i] } else { 1.0 });
+                        let scaled = Bounds::of_points(&[
+                            std::array::from_fn(|i| inner.min[i] * top[i]),
+                            std::array::from_fn(|i| inner.max[i] * top[i]),
+                        ]);
+                        inner.union(&scaled)
+                    }
+                    Deformation::Bend { .. } => return None,
+                }
+            }
+            Self::RotateExtrude { child, .. } => {
+                let profile = child.bounds()?;
+                if profile.is_empty() {
+                    return Some(Bounds::EMPTY);
+                }
+                let r = profile.min[0].abs().max(profile.max[0].abs());
+                Bounds { min: [-r, -r, profile.min[1]], max: [r, r, profile.max[1]] }
+            }
+            Self::Offset { delta, child, .. } => {
+                let inner = child.bounds()?;
+                if inner.is_empty() {
+                    return Some(Bounds::EMPTY);
+                }
+                // An inset only shrinks the outline, so the box still holds it
+                let grow = delta.max(0.0);
+                Bounds {
+                    min: [inner.min[0] - grow, inner.min[1] - grow, inner.min[2]],
+                    max: [inner.max[0] + grow, inner.max[1] + grow, inner.max[2]],
+                }
+            }
+            Self::Projection { child, .. } => {
+                let solid = child.bounds()?;
+                if solid.is_empty() {
+                    return Some(Bounds::EMPTY);
+                }
+                Bounds { min: [solid.min[0], solid.min[1], 0.0], max: [solid.max[0], solid.max[1], 0.0] }
+            }
+            Self::Translate { .. } | Self::Rotate { .. } | Self::Scale { .. } | Self::Mirror { .. } | Self::Multmatrix { .. } => {
+                unreachable!("transforms are handled above")
+            }
+        })
+    }
+
+    /// The child of a single-child node.
+    fn only_child(&self) -> Option<&GeometryNode> {
+        match self {
+            Self::Translate { child, .. }
+            | Self::Rotate { child, .. }
+            | Self::Scale { child, .. }
+            | Self::Mirror { child, .. }
+            | Self::Multmatrix { child, .. } => Some(child),
+            _ => None,
+        }
+    }
+}
+
+/// The box of a cube of `size`, at the origin or centered on it. Negative
+/// sizes are taken as zero, like the primitives.
+fn cube(size: [f64; 3], center: bool) -> Bounds {
+    let size = size.map(|s| s.max(0.0));
+    let min = if center { size.map(|s| -s / 2.0) } else { [0.0; 3] };
+    Bounds { min, max: std::array::from_fn(|i| min[i] + size[i]) }
+}
+
+/// Distance from the z axis to the farthest corner of a flat box.
+fn radius(bounds: &Bounds) -> f64 {
+    let x = bounds.min[0].abs().max(bounds.max[0].abs());
+    let y = bounds.min[1].abs().max(bounds.max[1].abs());
+    x.hypot(y)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate;
+
+    /// Bounds of the geometry of `source`.
+    fn bounds(source: &str) -> Option<Bounds> {
+        evaluate(source).unwrap().geometry.bounds()
+    }
+
+    /// Test primitives, transforms and booleans get the expected boxes.
+    #[test]
+    fn test_bounds() {
+        let b = bounds("cylinder(h = 4, r1 = 1, r2 = 2, center = true);").unwrap();
+        assert_eq!((b.min, b.max), ([-2.0, -2.0, -2.0], [2.0, 2.0, 2.0]));
+
+        let b = bounds("rotate([0, 0, 90]) cube([2, 1, 1]);").unwrap();
+        assert!((b.min[0] + 1.0).abs() < 1e-9 && (b.max[1] - 2.0).abs() < 1e-9);
+
+        let b = bounds("difference() { cube(10); translate([20, 0, 0]) cube(1); }").unwrap();
+        assert_eq!(b.max, [10.0; 3]);
+
+        let b = bounds("linear_extrude(5) square(2, center = true);").unwrap();
+        assert_eq!((b.min, b.max), ([-1.0, -1.0, 0.0], [1.0, 1.0, 5.0]));
+
+        assert!(bounds("intersection() { cube(1); translate([5, 0, 0]) cube(1); }").unwrap().is_empty());
+        assert!(bounds("import(\"part.stl\");").is_none());
+    }
+
+    /// Test warps widen the child's box, and a bend has none.
+    #[test]
+    fn test_warp_bounds() {
+        let options = crate::EvalOptions { extensions: true, ..crate::EvalOptions::default() };
+        let bounds = |source: &str| crate::evaluate_with_options(source, &options).unwrap().geometry.bounds();
+        let b = bounds("warp_twist(45) cube([3, 4, 5]);").unwrap();
+        assert_eq!((b.min, b.max), ([-5.0, -5.0, 0.0], [5.0, 5.0, 5.0]));
+        let b = bounds("warp_taper([2, 0.5]) cube(2, center = true);").unwrap();
+        assert_eq!((b.min, b.max), ([-2.0, -1.0, -1.0], [2.0, 1.0, 1.0]));
+        assert!(bounds("warp_bend(90) cube(2);").is_none());
+    }
+
+    /// Test touching boxes do not overlap, but flat ones on one plane do.
+    #[test]
+    fn test_overlaps() {
+        let a = cube([1.0; 3], false);
+        assert!(a.overlaps(&cube([1.0; 3], true)));
+        assert!(!a.overlaps(&a.shifted([1.0, 0.0, 0.0])));
+        assert!(!a.overlaps(&Bounds::EMPTY));
+
+        let square = cube([1.0, 1.0, 0.0], false);
+        assert!(square.overlaps(&square.shifted([0.5, 0.5, 0.0])));
+        assert!(!square.overlaps(&square.shifted([2.0, 0.0, 0.0])));
+    }
+}
@@ -0,0 +1,1141 @@
+//! # Geometry Types
+//!
+//! Evaluated geometry node types representing resolved OpenSCAD geometry.
+//!
+//! These types have all expressions evaluated - sizes are concrete numbers,
+//! transforms are resolved matrices, etc.
+//!
+//! ## Serialization
+//!
+//! [`EvaluatedAst`] round-trips through JSON, so tools can inspect the
+//! evaluated tree, store it, and mesh it later without evaluating the
+//! source again:
+//!
+//! ```rust
+//! use openscad_eval::{evaluate, EvaluatedAst, GeometryNode};
+//!
+//! let json = evaluate("translate([1, 0, 0]) cube(2);").unwrap().to_json().unwrap();
+//! let restored = EvaluatedAst::from_json(&json).unwrap();
+//! assert!(matches!(restored.geometry, GeometryNode::Translate { offset: [1.0, 0.0, 0.0], .. }));
+//! ```
+//!
+//! ## Traversal
+//!
+//! [`GeometryVisitor`] and [`GeometryRewriter`] walk a tree with enter
+//! and exit hooks, so a pass only handles the nodes it cares about (see
+//! [`walk`]). [`EvaluatedAst::optimize`] flattens the transforms of a tree
+//! before meshing (see [`optimize`]).
+//!
+//! [`GeometryNode::bounds`] boxes a tree without meshing it (see
+//! [`bounds`]), and [`EvaluatedAst::stats`] counts its nodes and estimates
+//! its triangles (see [`stats`]).
+
+pub mod bounds;
+pub mod optimize;
+pub mod stats;
+pub mod walk;
+
+use openscad_ast::Span;
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostic::Diagnostic;
+use crate::error::EvalError;
+use crate::message::Message;
+use crate::timing::Timings;
+
+pub use bounds::Bounds;
+pub use stats::GeometryStats;
+pub use walk::{GeometryRewriter, GeometryVisitor, Visit};
+
+// =============================================================================
+// EVALUATED AST
+// =============================================================================
+
+/// Result of AST evaluation.
+///
+/// Contains the root geometry node, any warnings and console output, and
+/// the library shims that stood in for missing definitions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluatedAst {
+    /// Root geometry node.
+    pub geometry: GeometryNode,
+    /// Evaluation warnings.
+    pub warnings: Vec<String>,
+    /// Evaluation warnings with their kind and location, in order.
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+    /// Text of the echo messages, in evaluation order.
+    #[serde(default)]
+    pub echoes: Vec<String>,
+    /// Console output with where it came from, in evaluation order.
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    /// Shimmed modules that were called, as `LIBRARY::name`, sorted.
+    #[serde(default)]
+    pub shimmed: Vec<String>,
+    /// Time spent parsing and evaluating; see [`crate::timing`].
+    #[serde(default)]
+    pub timings: Timings,
+}
+
+impl EvaluatedAst {
+    /// Create new evaluated AST.
+    pub fn new(geometry: GeometryNode) -> Self {
+        Self {
+            geometry,
+            warnings: Vec::new(),
+            diagnostics: Vec::new(),
+            echoes: Vec::new(),
+            messages: Vec::new(),
+            shimmed: Vec::new(),
+            timings: Timings::default(),
+        }
+    }
+
+    /// Create with warnings.
+    pub fn with_warnings(geometry: GeometryNode, warnings: Vec<String>) -> Self {
+        Self { geometry, warnings, ..Self::new(GeometryNode::Empty) }
+    }
+
+    /// Encode the result as JSON.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `EvalError::InvalidGeometry` if the result cannot be encoded.
+    pub fn to_json(&self) -> Result<String, EvalError> {
+        serde_json::to_string(self).map_err(|e| EvalError::InvalidGeometry(e.to_string()))
+    }
+
+    /// Count the nodes of the geometry and estimate its triangles, before
+    /// meshing it; see [`GeometryStats`].
+    pub fn stats(&self) -> GeometryStats {
+        self.geometry.stats()
+    }
+
+    /// Decode a result produced by [`to_json`](Self::to_json).
+    ///
+    /// ## Errors
+    ///
+    /// Returns `EvalError::InvalidGeometry` if the JSON is malformed or
+    /// names unknown node types. Non-finite numbers are written as `null`
+    /// and so cannot be read back.
+    pub fn from_json(json: &str) -> Result<Self, EvalError> {
+        serde_json::from_str(json).map_err(|e| EvalError::InvalidGeometry(e.to_string()))
+    }
+}
+
+// =============================================================================
+// GEOMETRY NODE
+// =============================================================================
+
+/// A node in the evaluated geometry tree.
+///
+/// All values are fully resolved (no variables, expressions evaluated).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GeometryNode {
+    // =========================================================================
+    // 3D PRIMITIVES
+    // =========================================================================
+
+    /// Cube primitive.
+    ///
+    /// ## OpenSCAD Equivalent
+    /// 
+    /// ```text
+    /// cube(size);
+    /// cube([x, y, z], center=true);
+    /// ```
+    Cube {
+        /// Size as [x, y, z].
+        size: [f64; 3],
+        /// Whether centered at origin.
+        center: bool,
+    },
+
+    /// Sphere primitive.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// sphere(r=5);
+    /// sphere(d=10, $fn=32);
+    /// ```
+    Sphere {
+        /// Radius.
+        radius: f64,
+        /// Number of fragments ($fn).
+        fn_: u32,
+    },
+
+    /// Cylinder primitive.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// cylinder(h=10, r=5);
+    /// cylinder(h=10, r1=5, r2=3, center=true);
+    /// ```
+    Cylinder {
+        /// Height.
+        height: f64,
+        /// Bottom radius.
+        radius1: f64,
+        /// Top radius.
+        radius2: f64,
+        /// Whether centered.
+        center: bool,
+        /// Number of fragments.
+        fn_: u32,
+    },
+
+    /// Polyhedron primitive.
+    Polyhedron {
+        /// Vertex positions.
+        points: Vec<[f64; 3]>,
+        /// Face indices.
+        faces: Vec<Vec<usize>>,
+    },
+
+    // =========================================================================
+    // 2D PRIMITIVES
+    // =========================================================================
+
+    /// Circle primitive.
+    Circle {
+        /// Radius.
+        radius: f64,
+        /// Number of fragments.
+        fn_: u32,
+    },
+
+    /// Square/rectangle primitive.
+    Square {
+        /// Size as [x, y].
+        size: [f64; 2],
+        /// Whether centered.
+        center: bool,
+    },
+
+    /// Polygon primitive.
+    Polygon {
+        /// Vertex positions.
+        points: Vec<[f64; 2]>,
+        /// Optional paths.
+        paths: Option<Vec<Vec<usize>>>,
+    },
+
+    /// Text primitive, outlined by the font engine at mesh time.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// text("Hello", size=10, font="Liberation Sans");
+    /// text("42", halign="center", valign="center", spacing=1.2);
+    /// ```
+    Text {
+        /// Text to render.
+        text: String,
+        /// Ascent (height above the baseline) in units.
+        size: f64,
+        /// Font name, optionally with a `:style=` suffix.
+        font: Option<String>,
+        /// Horizontal alignment.
+        halign: HAlign,
+        /// Vertical alignment.
+        valign: VAlign,
+        /// Advance multiplier between characters.
+        spacing: f64,
+        /// Number of fragments for curve flattening.
+        fn_: u32,
+    },
+
+    /// Imported geometry, loaded from the file at mesh time.
+    ///
+    /// Whether it is 2D or 3D depends on the file format: SVG files are
+    /// 2D, meshes 3D.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// import("part.stl");
+    /// import(file="logo.svg", center=true, $fn=32);
+    /// ```
+    Import {
+        /// File name, resolved by the mesh stage.
+        file: String,
+        /// Whether to center the bounding box on the origin.
+        center: bool,
+        /// `$fn` for flattening curves of 2D files; 0 to use `$fa`/`$fs`.
+        #[serde(default)]
+        fn_: u32,
+    },
+
+    /// Heightmap solid, loaded from the file at mesh time.
+    ///
+    /// Each data value becomes the height of one grid point, one unit
+    /// apart in X and Y, above a base one unit below the lowest value.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// surface(file="map.dat", center=true);
+    /// surface(file="logo.png", invert=true);
+    /// ```
+    Surface {
+        /// File name (`.dat` text or `.png` image), resolved by the mesh stage.
+        file: String,
+        /// Whether to center the grid on the origin in X and Y.
+        center: bool,
+        /// Whether image heights are inverted, dark being high.
+        invert: bool,
+    },
+
+    // =========================================================================
+    // TRANSFORMS
+    // =========================================================================
+
+    /// Translation transform.
+    Translate {
+        /// Translation vector [x, y, z].
+        offset: [f64; 3],
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Rotation transform.
+    Rotate {
+        /// Rotation angles [x, y, z] in degrees.
+        angles: [f64; 3],
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Scale transform.
+    Scale {
+        /// Scale factors [x, y, z].
+        factors: [f64; 3],
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Mirror transform.
+    Mirror {
+        /// Mirror plane normal.
+        normal: [f64; 3],
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Resize to absolute dimensions, scaling about the origin.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// resize([20, 0, 0], auto = true) sphere(5);
+    /// ```
+    ///
+    /// Axes with a size of 0 keep their size, or with `auto` set take the
+    /// largest scale of the sized axes, preserving the aspect ratio.
+    Resize {
+        /// Target size [x, y, z]; 0 leaves an axis to `auto`.
+        newsize: [f64; 3],
+        /// Per-axis auto scaling of unsized axes.
+        auto: [bool; 3],
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// General matrix transform.
+    Multmatrix {
+        /// 4x4 transformation matrix.
+        matrix: [[f64; 4]; 4],
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Color modifier.
+    Color {
+        /// RGBA color.
+        rgba: [f64; 4],
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    // =========================================================================
+    // BOOLEAN OPERATIONS
+    // =========================================================================
+
+    /// CSG union of children: one solid with overlaps merged.
+    ///
+    /// Produced by an explicit `union()`, and wherever a [`Group`](Self::Group)
+    /// is used as a boolean operand (see [`GeometryNode::into_union`]).
+    Union {
+        /// Child geometries.
+        children: Vec<GeometryNode>,
+    },
+
+    /// Difference (first child minus rest).
+    Difference {
+        /// Child geometries.
+        children: Vec<GeometryNode>,
+    },
+
+    /// Intersection of children.
+    Intersection {
+        /// Child geometries.
+        children: Vec<GeometryNode>,
+    },
+
+    /// Convex hull of children.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// hull() {
+    ///   sphere(5);
+    ///   translate([20, 0, 0]) sphere(5);
+    /// }
+    /// ```
+    ///
+    /// Creates the convex hull (smallest convex shape) containing all children.
+    Hull {
+        /// Child geometries to hull.
+        children: Vec<GeometryNode>,
+    },
+
+    /// Minkowski sum of children.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// minkowski() {
+    ///   cube(10);
+    ///   sphere(2);
+    /// }
+    /// ```
+    ///
+    /// Creates the Minkowski sum - effectively "inflates" the first child
+    /// by the shape of the second child.
+    Minkowski {
+        /// Child geometries (typically 2).
+        children: Vec<GeometryNode>,
+    },
+
+    // =========================================================================
+    // EXTRUSIONS
+    // =========================================================================
+
+    /// Linear extrusion.
+    LinearExtrude {
+        /// Extrusion height.
+        height: f64,
+        /// Twist angle in degrees.
+        twist: f64,
+        /// Scale at top.
+        scale: [f64; 2],
+        /// Number of slices.
+        slices: u32,
+        /// Whether centered.
+        center: bool,
+        /// Child 2D geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Rotational extrusion.
+    RotateExtrude {
+        /// Sweep angle in degrees.
+        angle: f64,
+        /// Number of fragments.
+        fn_: u32,
+        /// Child 2D geometry.
+        child: Box<GeometryNode>,
+    },
+
+    // =========================================================================
+    // 2D OPERATIONS
+    // =========================================================================
+
+    /// 2D Offset (expand/shrink polygon).
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// offset(r = 5) circle(10);
+    /// offset(delta = 2, chamfer = true) square(10);
+    /// ```
+    Offset {
+        /// Offset amount (positive = expand, negative = shrink).
+        delta: f64,
+        /// Whether corners are rounded (`r`) rather than mitered (`delta`).
+        round: bool,
+        /// Whether `delta` corners are cut off instead of mitered.
+        chamfer: bool,
+        /// Segments of a full circle of radius `delta`, for round corners.
+        fn_: u32,
+        /// Child 2D geometry to offset.
+        child: Box<GeometryNode>,
+    },
+
+    /// 3D to 2D Projection.
+    ///
+    /// ## OpenSCAD Equivalent
+    ///
+    /// ```text
+    /// projection() sphere(10);
+    /// projection(cut = true) cube(10);
+    /// ```
+    Projection {
+        /// If true, only project the XY cross-section at Z=0.
+        cut: bool,
+        /// Child 3D geometry to project.
+        child: Box<GeometryNode>,
+    },
+
+    // =========================================================================
+    // EXTENSIONS (not in OpenSCAD)
+    // =========================================================================
+
+    /// Taubin smoothing of the child mesh.
+    ///
+    /// ## Syntax
+    ///
+    /// ```text
+    /// smooth(iterations = 10, lambda = 0.5) sphere(10, $fn = 12);
+    /// smooth(5, feature_angle = 30) cube(10);
+    /// ```
+    Smooth {
+        /// Number of shrink/inflate pass pairs.
+        iterations: u32,
+        /// Shrinking weight (0 < lambda < 1).
+        lambda: f64,
+        /// Inflating weight (negative, |mu| slightly above lambda).
+        mu: f64,
+        /// Keep vertices on open boundaries fixed.
+        preserve_boundary: bool,
+        /// Keep vertices on edges sharper than this angle (degrees) fixed.
+        feature_angle: Option<f64>,
+        /// Child 3D geometry to smooth.
+        child: Box<GeometryNode>,
+    },
+
+    /// Subdivision of the child mesh into smaller triangles, flat or by
+    /// Loop subdivision (only with the extensions option).
+    ///
+    /// ## Syntax
+    ///
+    /// ```text
+    /// refine(4) cube(10);                 // every edge in 4 pieces
+    /// refine(length = 1) cylinder(10, 2); // no edge longer than 1
+    /// refine(8, smooth = true) cube(10);  // three Loop levels
+    /// ```
+    Refine {
+        /// Pieces every edge is split into.
+        pieces: u32,
+        /// Keep splitting until no edge is longer than this.
+        max_length: Option<f64>,
+        /// Loop subdivision, rounding the shape.
+        smooth: bool,
+        /// Child 3D geometry to refine.
+        child: Box<GeometryNode>,
+    },
+
+    /// Deformation of the child mesh by moving its vertices (only with
+    /// the extensions option).
+    ///
+    /// ## Syntax
+    ///
+    /// ```text
+    /// warp_twist(90) refine(length = 1) cube([4, 4, 20]);
+    /// warp_taper(0.5) refine(length = 1) cylinder(10, 3);
+    /// warp_bend(90) refine(length = 1) cube([30, 5, 2]);
+    /// ```
+    Warp {
+        /// How vertices move, over the child's own extent.
+        deformation: Deformation,
+        /// Child 3D geometry to deform.
+        child: Box<GeometryNode>,
+    },
+
+    /// Tessellation quality scope.
+    ///
+    /// Segment counts inside were already multiplied by `level` during
+    /// evaluation; the node tells the mesher whether to merge coplanar
+    /// faces of the child.
+    ///
+    /// ## Syntax
+    ///
+    /// ```text
+    /// quality(0.25) body();              // coarse, simplified
+    /// quality(4, simplify = false) thread();
+    /// ```
+    Quality {
+        /// Effective segment multiplier, including enclosing scopes and
+        /// the render's [`QualityOptions`](crate::QualityOptions).
+        level: f64,
+        /// Merge coplanar faces of the child mesh.
+        simplify: bool,
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Torus around the Z axis, centered at the origin.
+    ///
+    /// Only produced with [`EvalOptions::extensions`](crate::EvalOptions::extensions).
+    ///
+    /// ## Syntax
+    ///
+    /// ```text
+    /// torus(r1 = 10, r2 = 2);
+    /// ```
+    Torus {
+        /// Distance from the axis to the middle of the tube.
+        major: f64,
+        /// Radius of the tube, less than `major`.
+        minor: f64,
+        /// Number of fragments around the axis.
+        fn_: u32,
+        /// Number of fragments around the tube.
+        fn_tube: u32,
+    },
+
+    /// Box with its edges and corners rounded off.
+    ///
+    /// Only produced with [`EvalOptions::extensions`](crate::EvalOptions::extensions).
+    ///
+    /// ## Syntax
+    ///
+    /// ```text
+    /// rounded_cube([20, 10, 5], r = 1, center = true);
+    /// ```
+    RoundedCube {
+        /// Size as [x, y, z].
+        size: [f64; 3],
+        /// Rounding radius, at most half the smallest size.
+        radius: f64,
+        /// Whether centered.
+        center: bool,
+        /// Number of fragments of a full turn of the rounding.
+        fn_: u32,
+    },
+
+    // =========================================================================
+    // META
+    // =========================================================================
+
+    /// Group of geometries rendered independently, without CSG merging.
+    ///
+    /// Produced for statement lists: the top level, module bodies,
+    /// `children()`, `for` loops and blocks under transforms. Meshers may
+    /// concatenate the children's meshes. Where a group becomes a single
+    /// operand of `union()`, `difference()`, `intersection()` or
+    /// `minkowski()` it must be merged first, which
+    /// [`GeometryNode::into_union`] makes explicit.
+    Group {
+        /// Child geometries.
+        children: Vec<GeometryNode>,
+    },
+
+    /// Geometry of one module call, with where the call is in the source.
+    ///
+    /// Only produced with [`EvalOptions::sources`](crate::EvalOptions::sources),
+    /// around the result of every module call that has geometry, so a
+    /// point on the mesh can be traced back to the code that made it.
+    /// Meshes and bounds are those of the child; hashes ignore the span.
+    Source {
+        /// Span of the call statement.
+        span: Span,
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Geometry under the `#` modifier: part of the model, and also shown
+    /// highlighted in previews.
+    Highlight {
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Geometry under the `%` modifier: left out of the model and only
+    /// shown, transparent, in previews.
+    Background {
+        /// Child geometry.
+        child: Box<GeometryNode>,
+    },
+
+    /// Empty geometry (for conditionals that produce nothing).
+    Empty,
+}
+
+// =============================================================================
+// DEFORMATIONS
+// =============================================================================
+
+/// How [`GeometryNode::Warp`] moves vertices. Each deformation spans the
+/// child's extent along its axis.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Deformation {
+    /// Rotation about the Z axis, counter-clockwise seen from above, from
+    /// none at the bottom to `degrees` at the top.
+    Twist {
+        /// Rotation at the top, in degrees.
+        degrees: f64,
+    },
+    /// Scale in x and y about the Z axis, from `1` at the bottom to
+    /// `scale` at the top.
+    Taper {
+        /// Scale at the top in x and y.
+        scale: [f64; 2],
+    },
+    /// Arc along x in the XZ plane, toward +Z for positive angles, with
+    /// `z = 0` keeping its length.
+    Bend {
+        /// Angle the whole length turns through, in degrees.
+        degrees: f64,
+    },
+}
+
+// =============================================================================
+// TEXT ALIGNMENT
+// =============================================================================
+
+/// Horizontal text alignment (`halign`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum HAlign {
+    /// Text starts at the origin.
+    #[default]
+    Left,
+    /// Text is centered on the origin.
+    Center,
+    /// Text ends at the origin.
+    Right,
+}
+
+impl HAlign {
+    /// Parse an OpenSCAD `halign` value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "left" => Some(Self::Left),
+            "center" => Some(Self::Center),
+            "right" => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
+/// Vertical text alignment (`valign`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum VAlign {
+    /// Top of the outlines at the origin.
+    Top,
+    /// Outlines centered on the origin.
+    Center,
+    /// Baseline at the origin.
+    #[default]
+    Baseline,
+    /// Bottom of the outlines at the origin.
+    Bottom,
+}
+
+impl VAlign {
+    /// Parse an OpenSCAD `valign` value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "top" => Some(Self::Top),
+            "center" => Some(Self::Center),
+            "baseline" => Some(Self::Baseline),
+            "bottom" => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+}
+
+impl GeometryNode {
+    /// Group of `children`, collapsing to `Empty` or the only child.
+    pub fn group(mut children: Vec<GeometryNode>) -> Self {
+        match children.len() {
+            0 => Self::Empty,
+            1 => children.remove(0),
+            _ => Self::Group { children },
+        }
+    }
+
+    /// Union of `children`, collapsing to `Empty` or the only child.
+    pub fn union(mut children: Vec<GeometryNode>) -> Self {
+        match children.len() {
+            0 => Self::Empty,
+            1 => children.remove(0),
+            _ => Self::Union { children },
+        }
+    }
+
+    /// Turn a group into the union of its members, for use as one CSG
+    /// operand.
+    ///
+    /// Nested groups are flattened into the same union. Other nodes are
+    /// returned unchanged.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use openscad_eval::GeometryNode;
+    ///
+    /// let cube = GeometryNode::Cube { size: [1.0; 3], center: false };
+    /// let group = GeometryNode::group(vec![cube.clone(), GeometryNode::group(vec![cube.clone(), cube])]);
+    /// match group.into_union() {
+    ///     GeometryNode::Union { children } => assert_eq!(children.len(), 3),
+    ///     other => panic!("expected union, got {:?}", other),
+    /// }
+    /// ```
+    pub fn into_union(self) -> Self {
+        match self {
+            Self::Group { children } => {
+                let mut members = Vec::with_capacity(children.len());
+                flatten_group(children, &mut members);
+                Self::union(members)
+            }
+            Self::Source { span, child } => Self::Source { span, child: Box::new(child.into_union()) },
+            other => other,
+        }
+    }
+
+    /// Check if this is an empty node.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    /// Check if this is a 2D node.
+    pub fn is_2d(&self) -> bool {
+        matches!(
+            self,
+            Self::Circle { .. }
+                | Self::Square { .. }
+                | Self::Polygon { .. }
+                | Self::Text { .. }
+                | Self::Offset { .. }
+                | Self::Projection { .. }
+        )
+    }
+
+    /// Dimension of the geometry this node produces.
+    ///
+    /// Unlike [`is_2d`](Self::is_2d) and [`is_3d`](Self::is_3d), this looks
+    /// through transforms and operations: a translated circle is 2D, a
+    /// linear extrusion 3D. Operations take the dimension of their first
+    /// child that has one, as OpenSCAD does.
+    ///
+    /// ## Returns
+    ///
+    /// `Some(2)` or `Some(3)`, `None` for empty geometry
+    pub fn dimension(&self) -> Option<u8> {
+        match self {
+            Self::Empty | Self::Background { .. } => None,
+            Self::Circle { .. }
+            | Self::Square { .. }
+            | Self::Polygon { .. }
+            | Self::Text { .. }
+            | Self::Offset { .. }
+            | Self::Projection { .. } => Some(2),
+            Self::Import { file, .. } => Some(if is_2d_file(file) { 2 } else { 3 }),
+            Self::Cube { .. }
+            | Self::Sphere { .. }
+            | Self::Cylinder { .. }
+            | Self::Polyhedron { .. }
+            | Self::Surface { .. }
+            | Self::LinearExtrude { .. }
+            | Self::RotateExtrude { .. }
+            | Self::Smooth { .. }
+            | Self::Refine { .. }
+            | Self::Warp { .. }
+            | Self::Torus { .. }
+            | Self::RoundedCube { .. } => Some(3),
+            Self::Translate { child, .. }
+            | Self::Rotate { child, .. }
+            | Self::Scale { child, .. }
+            | Self::Mirror { child, .. }
+            | Self::Resize { child, .. }
+            | Self::Multmatrix { child, .. }
+            | Self::Color { child, .. }
+            | Self::Quality { child, .. }
+            | Self::Source { child, .. }
+            | Self::Highlight { child } => child.dimension(),
+            Self::Union { children }
+            | Self::Difference { children }
+            | Self::Intersection { children }
+            | Self::Hull { children }
+            | Self::Minkowski { children }
+            | Self::Group { children } => children.iter().find_map(Self::dimension),
+        }
+    }
+
+    /// Check if this is a 3D node.
+    pub fn is_3d(&self) -> bool {
+        matches!(
+            self,
+            Self::Cube { .. }
+                | Self::Sphere { .. }
+                | Self::Cylinder { .. }
+                | Self::Polyhedron { .. }
+                | Self::Torus { .. }
+                | Self::RoundedCube { .. }
+        )
+    }
+
+    /// Number of nodes in this tree, this one included; `Empty` counts
+    /// as none.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use openscad_eval::GeometryNode;
+    ///
+    /// let cube = GeometryNode::Cube { size: [1.0; 3], center: false };
+    /// let moved = GeometryNode::Translate { offset: [1.0, 0.0, 0.0], child: Box::new(cube.clone()) };
+    /// assert_eq!(GeometryNode::Union { children: vec![cube, moved] }.node_count(), 4);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        match self {
+            Self::Empty => 0,
+            Self::Translate { child, .. }
+            | Self::Rotate { child, .. }
+            | Self::Scale { child, .. }
+            | Self::Mirror { child, .. }
+            | Self::Resize { child, .. }
+            | Self::Multmatrix { child, .. }
+            | Self::Color { child, .. }
+            | Self::LinearExtrude { child, .. }
+            | Self::RotateExtrude { child, .. }
+            | Self::Offset { child, .. }
+            | Self::Projection { child, .. }
+            | Self::Smooth { child, .. }
+            | Self::Refine { child, .. }
+            | Self::Warp { child, .. }
+            | Self::Quality { child, .. }
+            | Self::Source { child, .. }
+            | Self::Highlight { child }
+            | Self::Background { child } => 1 + child.node_count(),
+            Self::Union { children }
+            | Self::Difference { children }
+            | Self::Intersection { children }
+            | Self::Hull { children }
+            | Self::Minkowski { children }
+            | Self::Group { children } => 1 + children.iter().map(Self::node_count).sum::<usize>(),
+            _ => 1,
+        }
+    }
+
+    /// Name of this kind of node: the OpenSCAD module making it where
+    /// there is one, such as `"cube"` or `"linear_extrude"`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use openscad_eval::GeometryNode;
+    ///
+    /// let cube = GeometryNode::Cube { size: [1.0; 3], center: false };
+    /// assert_eq!(cube.kind(), "cube");
+    /// assert_eq!(GeometryNode::Group { children: vec![cube] }.kind(), "group");
+    /// ```
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Cube { .. } => "cube",
+            Self::Sphere { .. } => "sphere",
+            Self::Cylinder { .. } => "cylinder",
+            Self::Polyhedron { .. } => "polyhedron",
+            Self::Circle { .. } => "circle",
+            Self::Square { .. } => "square",
+            Self::Polygon { .. } => "polygon",
+            Self::Text { .. } => "text",
+            Self::Import { .. } => "import",
+            Self::Surface { .. } => "surface",
+            Self::Translate { .. } => "translate",
+            Self::Rotate { .. } => "rotate",
+            Self::Scale { .. } => "scale",
+            Self::Mirror { .. } => "mirror",
+            Self::Resize { .. } => "resize",
+            Self::Multmatrix { .. } => "multmatrix",
+            Self::Color { .. } => "color",
+            Self::Union { .. } => "union",
+            Self::Difference { .. } => "difference",
+            Self::Intersection { .. } => "intersection",
+            Self::Hull { .. } => "hull",
+            Self::Minkowski { .. } => "minkowski",
+            Self::LinearExtrude { .. } => "linear_extrude",
+            Self::RotateExtrude { .. } => "rotate_extrude",
+            Self::Offset { .. } => "offset",
+            Self::Projection { .. } => "projection",
+            Self::Smooth { .. } => "smooth",
+            Self::Refine { .. } => "refine",
+            Self::Warp { .. } => "warp",
+            Self::Quality { .. } => "quality",
+            Self::Torus { .. } => "torus",
+            Self::RoundedCube { .. } => "rounded_cube",
+            Self::Group { .. } => "group",
+            Self::Source { .. } => "source",
+            Self::Highlight { .. } => "highlight",
+            Self::Background { .. } => "background",
+            Self::Empty => "empty",
+        }
+    }
+
+    /// Direct children of this node, in order; none for primitives.
+    pub fn children(&self) -> &[GeometryNode] {
+        match self {
+            Self::Translate { child, .. }
+            | Self::Rotate { child, .. }
+            | Self::Scale { child, .. }
+            | Self::Mirror { child, .. }
+            | Self::Resize { child, .. }
+            | Self::Multmatrix { child, .. }
+            | Self::Color { child, .. }
+            | Self::LinearExtrude { child, .. }
+            | Self::RotateExtrude { child, .. }
+            | Self::Offset { child, .. }
+            | Self::Projection { child, .. }
+            | Self::Smooth { child, .. }
+            | Self::Refine { child, .. }
+            | Self::Warp { child, .. }
+            | Self::Quality { child, .. }
+            | Self::Source { child, .. }
+            | Self::Highlight { child }
+            | Self::Background { child } => std::slice::from_ref(child.as_ref()),
+            Self::Union { children }
+            | Self::Difference { children }
+            | Self::Intersection { children }
+            | Self::Hull { children }
+            | Self::Minkowski { children }
+            | Self::Group { children } => children,
+            _ => &[],
+        }
+    }
+
+    /// Direct children of this node, mutably; see [`children`](Self::children).
+    pub fn children_mut(&mut self) -> &mut [GeometryNode] {
+        match self {
+            Self::Translate { child, .. }
+            | Self::Rotate { child, .. }
+            | Self::Scale { child, .. }
+            | Self::Mirror { child, .. }
+            | Self::Resize { child, .. }
+            | Self::Multmatrix { child, .. }
+            | Self::Color { child, .. }
+            | Self::LinearExtrude { child, .. }
+            | Self::RotateExtrude { child, .. }
+            | Self::Offset { child, .. }
+            | Self::Projection { child, .. }
+            | Self::Smooth { child, .. }
+            | Self::Refine { child, .. }
+            | Self::Warp { child, .. }
+            | Self::Quality { child, .. }
+            | Self::Source { child, .. }
+            | Self::Highlight { child }
+            | Self::Background { child } => std::slice::from_mut(child.as_mut()),
+            Self::Union { children }
+            | Self::Difference { children }
+            | Self::Intersection { children }
+            | Self::Hull { children }
+            | Self::Minkowski { children }
+            | Self::Group { children } => children,
+            _ => &mut [],
+        }
+    }
+}
+
+/// Whether `import()` of this file gives 2D geometry, by extension.
+fn is_2d_file(file: &str) -> bool {
+    file.rsplit_once('.').is_some_and(|(_, ext)| ext.eq_ignore_ascii_case("svg"))
+}
+
+/// Append the members of a group, descending into nested groups.
+fn flatten_group(children: Vec<GeometryNode>, out: &mut Vec<GeometryNode>) {
+    for child in children {
+        match child {
+            GeometryNode::Group { children } => flatten_group(children, out),
+            other => out.push(other),
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cube_node() {
+        let cube = GeometryNode::Cube {
+            size: [10.0, 10.0, 10.0],
+            center: false,
+        };
+        assert!(cube.is_3d());
+        assert!(!cube.is_2d());
+    }
+
+    #[test]
+    fn test_circle_node() {
+        let circle = GeometryNode::Circle {
+            radius: 5.0,
+            fn_: 32,
+        };
+        assert!(circle.is_2d());
+        assert!(!circle.is_3d());
+    }
+
+    #[test]
+    fn test_empty_node() {
+        let empty = GeometryNode::Empty;
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_group_constructors_collapse() {
+        let cube = GeometryNode::Cube { size: [1.0; 3], center: false };
+        assert!(GeometryNode::group(Vec::new()).is_empty());
+        assert!(GeometryNode::union(Vec::new()).is_empty());
+        assert!(matches!(GeometryNode::group(vec![cube.clone()]), GeometryNode::Cube { .. }));
+        assert!(matches!(GeometryNode::union(vec![cube.clone(), cube]), GeometryNode::Union { .. }));
+    }
+
+    #[test]
+    fn test_dimension_looks_through_operations() {
+        let circle = GeometryNode::Circle { radius: 1.0, fn_: 8 };
+        let moved = GeometryNode::Translate { offset: [1.0, 0.0, 0.0], child: Box::new(circle.clone()) };
+        assert_eq!(moved.dimension(), Some(2));
+        let group = GeometryNode::Group { children: vec![GeometryNode::Empty, moved, GeometryNode::Sphere { radius: 1.0, fn_: 8 }] };
+        assert_eq!(group.dimension(), Some(2));
+        assert_eq!(GeometryNode::Empty.dimension(), None);
+
+        let import = |file: &str| GeometryNode::Import { file: file.to_string(), center: false, fn_: 0 };
+        assert_eq!(import("logo.SVG").dimension(), Some(2));
+        assert_eq!(import("part.stl").dimension(), Some(3));
+    }
+
+    #[test]
+    fn test_into_union_leaves_other_nodes() {
+        let cube = GeometryNode::Cube { size: [1.0; 3], center: false };
+        assert!(matches!(cube.into_union(), GeometryNode::Cube { .. }));
+        let union = GeometryNode::Union { children: vec![GeometryNode::Empty, GeometryNode::Empty] };
+        assert!(matches!(union.into_union(), GeometryNode::Union { children } if children.len() == 2));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let source = r#"
+            echo("parts");
+            color([1, 0, 0]) difference() { cube(10, center = true); sphere(6, $fn = 24); }
+            translate([20, 0, 0]) linear_extrude(height = 5, twist = 30) square([4, 2]);
+            rotate_extrude(angle = 90) translate([5, 0]) circle(1);
+            multmatrix([[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]) text("A");
+            polyhedron([[0, 0, 0], [1, 0, 0], [0, 1, 0], [0, 0, 1]], [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]]);
+            cube(1, rounding = 1);
+        "#;
+        let evaluated = crate::evaluate(source).unwrap();
+        let restored = EvaluatedAst::from_json(&evaluated.to_json().unwrap()).unwrap();
+        assert_eq!(restored.geometry.structural_hash(), evaluated.geometry.structural_hash());
+        assert_eq!(restored.echoes, evaluated.echoes);
+        assert_eq!(restored.diagnostics, evaluated.diagnostics);
+        assert!(matches!(EvaluatedAst::from_json("{}"), Err(EvalError::InvalidGeometry(_))));
+    }
+}
@@ -0,0 +1,289 @@
+//! # Transform Flattening
+//!
+//! A pass that leaves fewer transforms for the mesh builder to apply.
+//!
+//! | Step | Before | After |
+//! |------|--------|-------|
+//! | Compose | `translate(a) rotate(b) scale(c) x` | `multmatrix(T·R·S) x` |
+//! | Push down | `translate(a) union() { x; y; }` | `union() { translate(a) x; translate(a) y; }` |
+//! | Bake | `translate(a) polyhedron(points, faces)` | `polyhedron(points + a, faces)` |
+//! | Drop identities | `rotate(360) scale(1) x` | `x` |
+//!
+//! Transforms are pushed through `color()`, quality scopes, source spans,
+//! groups, unions, and — when the matrix is invertible — differences,
+//! intersections and hulls, all of which commute with it. They stop at
+//! everything else: `resize()` and `minkowski()` depend on the child's
+//! shape, extrusions and 2D operations on its plane, and the `#` and `%`
+//! modifiers keep their subtree whole for the preview layers. What is
+//! left is written as the simplest node for its matrix: a translation,
+//! a scale, or a `multmatrix`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::{evaluate, GeometryNode};
+//!
+//! let mut result = evaluate("translate([1, 0, 0]) translate([0, 2, 0]) rotate(360) cube(1);").unwrap();
+//! result.optimize();
+//! assert!(matches!(result.geometry, GeometryNode::Translate { offset: [1.0, 2.0, 0.0], .. }));
+//! ```
+
+use crate::visitor::math::{cos_degrees, sin_degrees};
+
+use super::{EvaluatedAst, GeometryNode, GeometryRewriter};
+
+/// Row-major affine matrix, as in `multmatrix()`.
+pub(super) type Matrix = [[f64; 4]; 4];
+
+const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+impl EvaluatedAst {
+    /// Flatten the transforms of the geometry; see [`GeometryNode::optimize`].
+    pub fn optimize(&mut self) {
+        let geometry = std::mem::replace(&mut self.geometry, GeometryNode::Empty);
+        self.geometry = geometry.optimize();
+    }
+}
+
+impl GeometryNode {
+    /// Compose, push down, bake and drop the transforms of this tree,
+    /// as described in the [module docs](self).
+    ///
+    /// The result meshes to the same solid, up to rounding.
+    pub fn optimize(self) -> GeometryNode {
+        self.rewrite(&mut Flatten)
+    }
+}
+
+/// The pass, bottom-up, so each transform meets already flattened children.
+struct Flatten;
+
+impl GeometryRewriter for Flatten {
+    fn exit(&mut self, node: GeometryNode) -> GeometryNode {
+        match transform_matrix(&node) {
+            Some(matrix) => apply(&matrix, only_child(node)),
+            None => node,
+        }
+    }
+}
+
+// =============================================================================
+// PASS
+// =============================================================================
+
+/// Apply `matrix` to `node`, as deep into the tree as it goes.
+fn apply(matrix: &Matrix, node: GeometryNode) -> GeometryNode {
+    if let Some(inner) = transform_matrix(&node) {
+        return apply(&multiply(matrix, &inner), only_child(node));
+    }
+    if *matrix == IDENTITY {
+        return node;
+    }
+    let det = determinant(matrix);
+    match node {
+        GeometryNode::Empty => GeometryNode::Empty,
+        GeometryNode::Color { .. }
+        | GeometryNode::Quality { .. }
+        | GeometryNode::Source { .. }
+        | GeometryNode::Group { .. }
+        | GeometryNode::Union { .. } => {
+            apply_to_children(matrix, node)
+        }
+        GeometryNode::Difference { .. } | GeometryNode::Intersection { .. } | GeometryNode::Hull { .. } if det != 0.0 => {
+            apply_to_children(matrix, node)
+        }
+        GeometryNode::Polyhedron { points, mut faces } if det != 0.0 => {
+            // A reflection turns the faces inside out unless they are rewound
+            if det < 0.0 {
+                faces.iter_mut().for_each(|face| face.reverse());
+            }
+            GeometryNode::Polyhedron { points: points.iter().map(|p| transform_point(matrix, p)).collect(), faces }
+        }
+        other => transform_node(matrix, other),
+    }
+}
+
+/// `node` with `matrix` applied to each of its children.
+fn apply_to_children(matrix: &Matrix, mut node: GeometryNode) -> GeometryNode {
+    for child in node.children_mut() {
+        let taken = std::mem::replace(child, GeometryNode::Empty);
+        *child = apply(matrix, taken);
+    }
+    node
+}
+
+/// Matrix of a transform node, `None` for other nodes.
+pub(super) fn transform_matrix(node: &GeometryNode) -> Option<Matrix> {
+    match *node {
+        GeometryNode::Translate { offset: [x, y, z], .. } => {
+            Some([[1.0, 0.0, 0.0, x], [0.0, 1.0, 0.0, y], [0.0, 0.0, 1.0, z], [0.0, 0.0, 0.0, 1.0]])
+        }
+        GeometryNode::Scale { factors: [x, y, z], .. } => {
+            Some([[x, 0.0, 0.0, 0.0], [0.0, y, 0.0, 0.0], [0.0, 0.0, z, 0.0], [0.0, 0.0, 0.0, 1.0]])
+        }
+        GeometryNode::Rotate { angles, .. } => Some(rotation(angles)),
+        GeometryNode::Mirror { normal, .. } => Some(mirror(normal)),
+        GeometryNode::Multmatrix { matrix, .. } => Some(matrix),
+        _ => None,
+    }
+}
+
+/// The child of a single-child node.
+fn only_child(mut node: GeometryNode) -> GeometryNode {
+    node.children_mut()
+        .first_mut()
+        .map_or(GeometryNode::Empty, |child| std::mem::replace(child, GeometryNode::Empty))
+}
+
+/// The simplest transform node applying `matrix` to `child`.
+fn transform_node(matrix: &Matrix, child: GeometryNode) -> GeometryNode {
+    let child = Box::new(child);
+    let linear_identity = (0..3).all(|r| (0..3).all(|c| matrix[r][c] == IDENTITY[r][c]));
+    let diagonal = (0..3).all(|r| (0..3).all(|c| r == c || matrix[r][c] == 0.0));
+    let translation = [matrix[0][3], matrix[1][3], matrix[2][3]];
+    let affine = matrix[3] == IDENTITY[3];
+    if affine && linear_identity {
+        GeometryNode::Translate { offset: translation, child }
+    } else if affine && diagonal && translation == [0.0; 3] {
+        GeometryNode::Scale { factors: [matrix[0][0], matrix[1][1], matrix[2][2]], child }
+    } else {
+        GeometryNode::Multmatrix { matrix: *matrix, child }
+    }
+}
+
+// =============================================================================
+// MATRICES
+// =============================================================================
+
+/// Rotation by Euler angles in degrees, about x, then y, then z.
+fn rotation(angles: [f64; 3]) -> Matrix {
+    let [(sx, cx), (sy, cy), (sz, cz)] = angles.map(|a| (sin_degrees(a), cos_degrees(a)));
+    [
+        [cy * cz, sx * sy * cz - cx * sz, cx * sy * cz + sx * sz, 0.0],
+        [cy * sz, sx * sy * sz + cx * cz, cx * sy * sz - sx * cz, 0.0],
+        [-sy, sx * cy, cx * cy, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+/// Reflection in the plane through the origin with `normal`; a zero
+/// normal reflects nothing, as in the mesh builder.
+fn mirror(normal: [f64; 3]) -> Matrix {
+    let length = normal.iter().map(|n| n * n).sum::<f64>().sqrt();
+    if length < 0.0001 {
+        return IDENTITY;
+    }
+    let n = normal.map(|c| c / length);
+    let mut matrix = IDENTITY;
+    for (r, row) in matrix.iter_mut().take(3).enumerate() {
+        for (c, cell) in row.iter_mut().take(3).enumerate() {
+            *cell -= 2.0 * n[r] * n[c];
+        }
+    }
+    matrix
+}
+
+/// `a · b`: `b` applied first.
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    std::array::from_fn(|r| std::array::from_fn(|c| (0..4).map(|k| a[r][k] * b[k][c]).sum()))
+}
+
+/// Determinant of the linear part.
+fn determinant(m: &Matrix) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+/// `p` transformed by an affine `matrix`.
+pub(super) fn transform_point(matrix: &Matrix, p: &[f64; 3]) -> [f64; 3] {
+    std::array::from_fn(|r| matrix[r][0] * p[0] + matrix[r][1] * p[1] + matrix[r][2] * p[2] + matrix[r][3])
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate;
+
+    /// Optimized geometry of `source`.
+    fn optimized(source: &str) -> GeometryNode {
+        let mut result = evaluate(source).unwrap();
+        result.optimize();
+        result.geometry
+    }
+
+    /// Test nested transforms compose into one matrix.
+    #[test]
+    fn test_compose() {
+        match optimized("translate([10, 0, 0]) rotate([0, 0, 90]) scale(2) cube(1);") {
+            GeometryNode::Multmatrix { matrix, child } => {
+                assert!(matches!(*child, GeometryNode::Cube { .. }));
+                assert_eq!(transform_point(&matrix, &[1.0, 0.0, 0.0]), [10.0, 2.0, 0.0]);
+            }
+            other => panic!("expected a multmatrix, got {:?}", other),
+        }
+        assert!(matches!(
+            optimized("scale([1, 2, 3]) scale(2) sphere(1);"),
+            GeometryNode::Scale { factors: [2.0, 4.0, 6.0], .. }
+        ));
+    }
+
+    /// Test identity transforms are removed.
+    #[test]
+    fn test_identities_removed() {
+        assert!(matches!(optimized("translate([0, 0, 0]) scale(1) rotate(360) cube(1);"), GeometryNode::Cube { .. }));
+        assert!(matches!(optimized("translate([1, 0, 0]) translate([-1, 0, 0]) cube(1);"), GeometryNode::Cube { .. }));
+        assert!(matches!(optimized("mirror([1, 0, 0]) mirror([1, 0, 0]) cube(1);"), GeometryNode::Cube { .. }));
+    }
+
+    /// Test transforms are pushed through unions and colors to compose
+    /// with the transforms below.
+    #[test]
+    fn test_push_down() {
+        let GeometryNode::Union { children } = optimized("translate([1, 0, 0]) union() { translate([0, 1, 0]) cube(1); color([1, 0, 0]) sphere(1); }") else {
+            panic!("expected a union on top");
+        };
+        assert!(matches!(children[0], GeometryNode::Translate { offset: [1.0, 1.0, 0.0], .. }));
+        let GeometryNode::Color { child, .. } = &children[1] else { panic!("expected a color") };
+        assert!(matches!(**child, GeometryNode::Translate { offset: [1.0, 0.0, 0.0], .. }));
+    }
+
+    /// Test transforms stop at nodes they do not commute with.
+    #[test]
+    fn test_push_down_stops() {
+        assert!(matches!(optimized("translate([1, 0, 0]) resize([2, 2, 2]) cube(1);"), GeometryNode::Translate { .. }));
+        assert!(matches!(optimized("translate([1, 0, 0]) minkowski() { cube(1); sphere(1); }"), GeometryNode::Translate { .. }));
+        assert!(matches!(optimized("translate([1, 0, 0]) #cube(1);"), GeometryNode::Translate { .. }));
+        // A flattening scale loses the difference's subtracted volume
+        assert!(matches!(optimized("scale([1, 1, 0]) difference() { cube(2); cube(1); }"), GeometryNode::Scale { .. }));
+        assert!(matches!(optimized("scale([1, 1, 2]) difference() { cube(2); cube(1); }"), GeometryNode::Difference { .. }));
+    }
+
+    /// Test transforms are baked into polyhedron points, rewinding the
+    /// faces of a reflection.
+    #[test]
+    fn test_bake_polyhedron() {
+        let source = "mirror([1, 0, 0]) translate([1, 0, 0]) polyhedron([[0, 0, 0], [1, 0, 0], [0, 1, 0], [0, 0, 1]], [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]]);";
+        let GeometryNode::Polyhedron { points, faces } = optimized(source) else { panic!("expected a polyhedron") };
+        assert_eq!(points[1], [-2.0, 0.0, 0.0]);
+        assert_eq!(faces[0], [2, 1, 0]);
+    }
+
+    /// Test the pass leaves the node count no higher for plain chains.
+    #[test]
+    fn test_node_count() {
+        let source = "translate([1, 0, 0]) rotate([0, 0, 45]) translate([0, 1, 0]) scale(2) cube(1);";
+        let before = evaluate(source).unwrap().geometry.node_count();
+        assert_eq!(before, 5);
+        assert_eq!(optimized(source).node_count(), 2);
+    }
+}
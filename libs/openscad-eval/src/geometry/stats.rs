@@ -0,0 +1,313 @@
+//! # Statistics
+//!
+//! What a geometry tree is made of, and roughly how many triangles its
+//! mesh will have, worked out without meshing it. A UI can warn about a
+//! model that is too heavy before starting a long render.
+//!
+//! ## Triangle Estimate
+//!
+//! | Node | Estimate |
+//! |------|----------|
+//! | `cube()`, `sphere()`, `cylinder()`, `polyhedron()`, ... | Triangles of the primitive at its `$fn` |
+//! | 2D shapes | Triangles of the filled outline |
+//! | `linear_extrude()` | Outline walls per slice, plus both caps |
+//! | `rotate_extrude()` | Outline walls per fragment |
+//! | Booleans, `hull()`, `minkowski()`, groups | Sum of the children |
+//! | `refine(n)` | Child times `n²`; `length` is not estimated |
+//! | Transforms, `color()`, modifiers, warps | Same as the child |
+//! | `import()`, `surface()`, `text()` | Counted in `unmeasured`, not estimated |
+//!
+//! Booleans usually cut about as many triangles as they remove, so the
+//! estimate is of the right order rather than exact.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::evaluate;
+//!
+//! let stats = evaluate("difference() { cube(10); translate([5, 5, 5]) sphere(3, $fn = 16); }").unwrap().stats();
+//! assert_eq!(stats.primitives["cube"], 1);
+//! assert_eq!((stats.booleans, stats.transforms, stats.depth), (1, 1, 3));
+//! assert_eq!(stats.estimated_triangles, 12 + 252);
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::walk::{GeometryVisitor, Visit};
+use super::GeometryNode;
+use crate::scope::Scope;
+
+/// Counts and size of a geometry tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "typescript", ts(rename_all = "camelCase"))]
+pub struct GeometryStats {
+    /// Nodes in the tree, as [`GeometryNode::node_count`].
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub nodes: usize,
+    /// Nodes on the longest path from the root to a leaf; 0 when empty.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub depth: usize,
+    /// Primitives by OpenSCAD name, e.g. `"cube"` or `"circle"`.
+    #[cfg_attr(feature = "typescript", ts(type = "Record<string, number>"))]
+    pub primitives: BTreeMap<String, usize>,
+    /// `union()`, `difference()` and `intersection()` nodes.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub booleans: usize,
+    /// `translate()`, `rotate()`, `scale()`, `mirror()`, `resize()` and
+    /// `multmatrix()` nodes.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub transforms: usize,
+    /// `hull()`, `minkowski()`, extrusions, `offset()`, `projection()` and
+    /// `smooth()` nodes.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub operations: usize,
+    /// Imports, surfaces and text, whose size is only known once meshed
+    /// and which the estimate leaves out.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub unmeasured: usize,
+    /// Rough number of triangles of the mesh; see the module docs.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub estimated_triangles: u64,
+}
+
+impl GeometryNode {
+    /// Count the nodes of this tree and estimate its triangles.
+    pub fn stats(&self) -> GeometryStats {
+        let mut counter = Counter::default();
+        self.walk(&mut counter);
+        GeometryStats { estimated_triangles: counter.root.triangles, ..counter.stats }
+    }
+}
+
+// =============================================================================
+// COUNTING
+// =============================================================================
+
+/// Estimated size of a subtree.
+#[derive(Debug, Clone, Copy, Default)]
+struct Size {
+    /// Triangles of the mesh, or of the filled outline for 2D.
+    triangles: u64,
+    /// Outline vertices, for 2D.
+    outline: u64,
+}
+
+impl Size {
+    /// A 2D shape with `outline` vertices.
+    fn flat(outline: u64) -> Self {
+        Self { triangles: outline.saturating_sub(2), outline }
+    }
+
+    /// A solid of `triangles`.
+    fn solid(triangles: u64) -> Self {
+        Self { triangles, outline: 0 }
+    }
+
+    /// The children's sizes added up.
+    fn sum(children: &[Size]) -> Self {
+        children.iter().fold(Size::default(), |acc, s| Size {
+            triangles: acc.triangles.saturating_add(s.triangles),
+            outline: acc.outline.saturating_add(s.outline),
+        })
+    }
+}
+
+/// Walks a tree once, counting on the way down and sizing on the way up.
+#[derive(Debug, Default)]
+struct Counter {
+    stats: GeometryStats,
+    /// Sizes of the children exited under each node entered and not yet
+    /// exited, innermost last.
+    children: Vec<Vec<Size>>,
+    /// Size of the root once exited.
+    root: Size,
+}
+
+impl GeometryVisitor for Counter {
+    fn enter(&mut self, node: &GeometryNode) -> Visit {
+        if matches!(node, GeometryNode::Empty) {
+            return Visit::SkipChildren;
+        }
+        let stats = &mut self.stats;
+        stats.nodes += 1;
+        stats.depth = stats.depth.max(self.children.len() + 1);
+        match node {
+            GeometryNode::Union { .. } | GeometryNode::Difference { .. } | GeometryNode::Intersection { .. } => {
+                stats.booleans += 1
+            }
+            GeometryNode::Translate { .. }
+            | GeometryNode::Rotate { .. }
+            | GeometryNode::Scale { .. }
+            | GeometryNode::Mirror { .. }
+            | GeometryNode::Resize { .. }
+            | GeometryNode::Multmatrix { .. } => stats.transforms += 1,
+            GeometryNode::Hull { .. }
+            | GeometryNode::Minkowski { .. }
+            | GeometryNode::LinearExtrude { .. }
+            | GeometryNode::RotateExtrude { .. }
+            | GeometryNode::Offset { .. }
+            | GeometryNode::Projection { .. }
+            | GeometryNode::Smooth { .. }
+            | GeometryNode::Refine { .. }
+            | GeometryNode::Warp { .. } => stats.operations += 1,
+            _ => {}
+        }
+        if let Some(name) = primitive_name(node) {
+            *stats.primitives.entry(name.to_string()).or_default() += 1;
+        }
+        if matches!(node, GeometryNode::Import { .. } | GeometryNode::Surface { .. } | GeometryNode::Text { .. }) {
+            stats.unmeasured += 1;
+        }
+        self.children.push(Vec::new());
+        Visit::Children
+    }
+
+    fn exit(&mut self, node: &GeometryNode) {
+        if matches!(node, GeometryNode::Empty) {
+            return;
+        }
+        let children = self.children.pop().unwrap_or_default();
+        let size = size_of(node, &children);
+        match self.children.last_mut() {
+            Some(siblings) => siblings.push(size),
+            None => self.root = size,
+        }
+    }
+}
+
+/// OpenSCAD name of a primitive node, or `None` for other nodes.
+fn primitive_name(node: &GeometryNode) -> Option<&'static str> {
+    Some(match node {
+        GeometryNode::Cube { .. } => "cube",
+        GeometryNode::Sphere { .. } => "sphere",
+        GeometryNode::Cylinder { .. } => "cylinder",
+        GeometryNode::Polyhedron { .. } => "polyhedron",
+        GeometryNode::Torus { .. } => "torus",
+        GeometryNode::RoundedCube { .. } => "rounded_cube",
+        GeometryNode::Circle { .. } => "circle",
+        GeometryNode::Square { .. } => "square",
+        GeometryNode::Polygon { .. } => "polygon",
+        GeometryNode::Text { .. } => "text",
+        GeometryNode::Import { .. } => "import",
+        GeometryNode::Surface { .. } => "surface",
+        _ => return None,
+    })
+}
+
+/// Estimated size of `node` from the sizes of its children.
+fn size_of(node: &GeometryNode, children: &[Size]) -> Size {
+    let child = Size::sum(children);
+    match node {
+        GeometryNode::Cube { .. } => Size::solid(12),
+        GeometryNode::Sphere { radius, fn_ } => Size::solid(sphere_triangles(fragments(*fn_, *radius))),
+        GeometryNode::Cylinder { radius1, radius2, fn_, .. } => {
+            Size::solid(4 * fragments(*fn_, radius1.max(*radius2)) - 4)
+        }
+        GeometryNode::Torus { major, minor, fn_, fn_tube } => {
+            Size::solid(2 * fragments(*fn_, major + minor) * fragments(*fn_tube, *minor))
+        }
+        GeometryNode::RoundedCube { radius, fn_, .. } => {
+            Size::solid(sphere_triangles(fragments(*fn_, *radius)) + 12)
+        }
+        GeometryNode::Polyhedron { faces, .. } => {
+            Size::solid(faces.iter().map(|face| face.len().saturating_sub(2) as u64).sum())
+        }
+        GeometryNode::Circle { radius, fn_ } => Size::flat(fragments(*fn_, *radius)),
+        GeometryNode::Square { .. } => Size::flat(4),
+        GeometryNode::Polygon { points, .. } => Size::flat(points.len() as u64),
+        GeometryNode::Import { .. } | GeometryNode::Surface { .. } | GeometryNode::Text { .. } => Size::default(),
+        GeometryNode::LinearExtrude { slices, .. } => {
+            let walls = 2 * child.outline * u64::from((*slices).max(1));
+            Size::solid(walls + 2 * child.triangles)
+        }
+        GeometryNode::RotateExtrude { fn_, .. } => {
+            Size::solid(2 * child.outline * u64::from((*fn_).max(3)))
+        }
+        // An offset or projection yields an outline of about the same size
+        GeometryNode::Offset { .. } | GeometryNode::Projection { .. } => {
+            let outline = if child.outline > 0 { child.outline } else { child.triangles };
+            Size::flat(outline)
+        }
+        // Loop levels split edges in two, rounding the pieces up
+        GeometryNode::Refine { pieces, smooth, .. } => {
+            let pieces = u64::from((*pieces).max(1));
+            let pieces = if *smooth { pieces.next_power_of_two() } else { pieces };
+            Size::solid(child.triangles.saturating_mul(pieces.saturating_mul(pieces)))
+        }
+        _ => child,
+    }
+}
+
+/// Triangles of a UV sphere of `fragments` around: half as many rings,
+/// bands between them and a polygon cap at each pole.
+fn sphere_triangles(fragments: u64) -> u64 {
+    let rings = fragments.div_ceil(2).max(2);
+    2 * fragments * (rings - 1) + 2 * (fragments - 2)
+}
+
+/// Fragments of a full turn: `fn_`, or the default for `radius` when the
+/// node leaves it at 0.
+fn fragments(fn_: u32, radius: f64) -> u64 {
+    let fn_ = if fn_ > 0 { fn_ } else { Scope::new().calculate_fragments(radius) };
+    u64::from(fn_.max(3))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::evaluate;
+
+    /// Test counts and depth of a mixed tree; `%` and `#` geometry counts.
+    #[test]
+    fn test_counts() {
+        let source = "
+            union() {
+                translate([0, 0, 5]) rotate([0, 0, 45]) cube(2);
+                %sphere(1);
+                hull() { circle(1); square(2); }
+            }
+            if (false) cube(1);
+        ";
+        let stats = evaluate(source).unwrap().stats();
+        assert_eq!(stats.primitives.iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(), vec![("circle", 1), ("cube", 1), ("sphere", 1), ("square", 1)]);
+        assert_eq!((stats.booleans, stats.transforms, stats.operations), (1, 2, 1));
+        assert_eq!(stats.nodes, evaluate(source).unwrap().geometry.node_count());
+        assert_eq!(stats.depth, 4);
+    }
+
+    /// Test estimates of extrusions grow with the outline and slices.
+    #[test]
+    fn test_extrusion_estimate() {
+        let extruded = evaluate("linear_extrude(10, slices = 4) square(1);").unwrap().stats();
+        assert_eq!(extruded.estimated_triangles, 2 * 4 * 4 + 2 * 2);
+        let revolved = evaluate("rotate_extrude($fn = 8) translate([5, 0]) square(1);").unwrap().stats();
+        assert_eq!(revolved.estimated_triangles, 2 * 4 * 8);
+    }
+
+    /// Test many fine spheres give an estimate in the millions, and
+    /// imports are counted as unmeasured.
+    #[test]
+    fn test_large_estimate() {
+        let stats = evaluate("for (i = [0:99]) translate([i, 0, 0]) sphere(1, $fn = 200);").unwrap().stats();
+        assert_eq!(stats.primitives["sphere"], 100);
+        assert!(stats.estimated_triangles > 3_000_000);
+
+        let stats = evaluate("import(\"part.stl\"); cube(1);").unwrap().stats();
+        assert_eq!((stats.unmeasured, stats.estimated_triangles), (1, 12));
+        assert_eq!(stats, evaluate("cube(1); import(\"part.stl\");").unwrap().stats());
+    }
+
+    /// Test an empty tree has no nodes and no depth.
+    #[test]
+    fn test_empty() {
+        let stats = evaluate("").unwrap().stats();
+        assert_eq!((stats.nodes, stats.depth, stats.estimated_triangles), (0, 0, 0));
+    }
+}
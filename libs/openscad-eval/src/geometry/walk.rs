@@ -0,0 +1,234 @@
+//! # Geometry Traversal
+//!
+//! Depth-first walks over [`GeometryNode`] trees for custom passes, such
+//! as counting primitives, collecting bounds or folding transforms.
+//!
+//! | Trait | Walk with | Hooks |
+//! |-------|-----------|-------|
+//! | [`GeometryVisitor`] | [`GeometryNode::walk`] | `enter(&node)`, `exit(&node)` |
+//! | [`GeometryRewriter`] | [`GeometryNode::rewrite`] | `enter(node) -> node`, `exit(node) -> node` |
+//!
+//! Every hook has a default, so a pass implements only the ones it needs.
+//! Nodes are entered parent first and exited children first; children
+//! are visited in order.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::{evaluate, GeometryNode, GeometryVisitor, Visit};
+//!
+//! /// Counts cubes, ignoring `%` background geometry.
+//! struct Cubes(usize);
+//!
+//! impl GeometryVisitor for Cubes {
+//!     fn enter(&mut self, node: &GeometryNode) -> Visit {
+//!         match node {
+//!             GeometryNode::Cube { .. } => self.0 += 1,
+//!             GeometryNode::Background { .. } => return Visit::SkipChildren,
+//!             _ => {}
+//!         }
+//!         Visit::Children
+//!     }
+//! }
+//!
+//! let result = evaluate("cube(1); translate([2, 0, 0]) cube(1); %cube(3);").unwrap();
+//! let mut cubes = Cubes(0);
+//! result.geometry.walk(&mut cubes);
+//! assert_eq!(cubes.0, 2);
+//! ```
+
+use super::GeometryNode;
+
+/// What a walk does after entering a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    /// Visit the node's children, then exit it.
+    Children,
+    /// Leave the children out; the node is still exited.
+    SkipChildren,
+}
+
+/// Read-only pass over a geometry tree.
+pub trait GeometryVisitor {
+    /// Called before the node's children.
+    fn enter(&mut self, _node: &GeometryNode) -> Visit {
+        Visit::Children
+    }
+
+    /// Called after the node's children.
+    fn exit(&mut self, _node: &GeometryNode) {}
+}
+
+/// Pass that replaces nodes of a geometry tree.
+pub trait GeometryRewriter {
+    /// Replace the node before its children are rewritten; the children
+    /// of the returned node are walked next.
+    fn enter(&mut self, node: GeometryNode) -> GeometryNode {
+        node
+    }
+
+    /// Replace the node after its children were rewritten.
+    fn exit(&mut self, node: GeometryNode) -> GeometryNode {
+        node
+    }
+}
+
+impl GeometryNode {
+    /// Walk this tree depth-first with `visitor`.
+    pub fn walk<V: GeometryVisitor + ?Sized>(&self, visitor: &mut V) {
+        if visitor.enter(self) == Visit::Children {
+            for child in self.children() {
+                child.walk(visitor);
+            }
+        }
+        visitor.exit(self);
+    }
+
+    /// Rebuild this tree depth-first with `rewriter`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use openscad_eval::{GeometryNode, GeometryRewriter};
+    ///
+    /// /// Replaces spheres with cubes of the same radius.
+    /// struct Boxes;
+    ///
+    /// impl GeometryRewriter for Boxes {
+    ///     fn exit(&mut self, node: GeometryNode) -> GeometryNode {
+    ///         match node {
+    ///             GeometryNode::Sphere { radius, .. } => GeometryNode::Cube { size: [radius * 2.0; 3], center: true },
+    ///             other => other,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let sphere = GeometryNode::Sphere { radius: 2.0, fn_: 0 };
+    /// let moved = GeometryNode::Translate { offset: [1.0, 0.0, 0.0], child: Box::new(sphere) };
+    /// let rewritten = moved.rewrite(&mut Boxes);
+    /// assert!(matches!(rewritten.children()[0], GeometryNode::Cube { size: [4.0, 4.0, 4.0], .. }));
+    /// ```
+    pub fn rewrite<R: GeometryRewriter + ?Sized>(self, rewriter: &mut R) -> GeometryNode {
+        let mut node = rewriter.enter(self);
+        for child in node.children_mut() {
+            let taken = std::mem::replace(child, GeometryNode::Empty);
+            *child = taken.rewrite(rewriter);
+        }
+        rewriter.exit(node)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names of the nodes as they are entered and exited.
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl GeometryVisitor for Trace {
+        fn enter(&mut self, node: &GeometryNode) -> Visit {
+            self.0.push(format!("+{}", name(node)));
+            Visit::Children
+        }
+
+        fn exit(&mut self, node: &GeometryNode) {
+            self.0.push(format!("-{}", name(node)));
+        }
+    }
+
+    /// Variant name of a node.
+    fn name(node: &GeometryNode) -> String {
+        format!("{:?}", node).split([' ', '(', '{']).next().unwrap_or_default().to_string()
+    }
+
+    /// Folds nested translations into one.
+    struct FoldTranslations;
+
+    impl GeometryRewriter for FoldTranslations {
+        fn exit(&mut self, node: GeometryNode) -> GeometryNode {
+            match node {
+                GeometryNode::Translate { offset: [x, y, z], child } => match *child {
+                    GeometryNode::Translate { offset: [a, b, c], child } => {
+                        GeometryNode::Translate { offset: [x + a, y + b, z + c], child }
+                    }
+                    child => GeometryNode::Translate { offset: [x, y, z], child: Box::new(child) },
+                },
+                other => other,
+            }
+        }
+    }
+
+    fn cube() -> GeometryNode {
+        GeometryNode::Cube { size: [1.0; 3], center: false }
+    }
+
+    fn translate(x: f64, child: GeometryNode) -> GeometryNode {
+        GeometryNode::Translate { offset: [x, 0.0, 0.0], child: Box::new(child) }
+    }
+
+    /// Test nodes are entered parent first and exited children first.
+    #[test]
+    fn test_walk_order() {
+        let tree = GeometryNode::Union { children: vec![translate(1.0, cube()), cube()] };
+        let mut trace = Trace::default();
+        tree.walk(&mut trace);
+        assert_eq!(trace.0, ["+Union", "+Translate", "+Cube", "-Cube", "-Translate", "+Cube", "-Cube", "-Union"]);
+    }
+
+    /// Test skipped children are neither entered nor exited.
+    #[test]
+    fn test_skip_children() {
+        struct Shallow(Vec<String>);
+
+        impl GeometryVisitor for Shallow {
+            fn enter(&mut self, node: &GeometryNode) -> Visit {
+                self.0.push(name(node));
+                Visit::SkipChildren
+            }
+        }
+
+        let mut shallow = Shallow(Vec::new());
+        translate(1.0, cube()).walk(&mut shallow);
+        assert_eq!(shallow.0, ["Translate"]);
+    }
+
+    /// Test rewriting sees rewritten children on exit.
+    #[test]
+    fn test_rewrite_folds_bottom_up() {
+        let tree = GeometryNode::Group { children: vec![translate(1.0, translate(2.0, translate(3.0, cube()))), cube()] };
+        let folded = tree.rewrite(&mut FoldTranslations);
+        let GeometryNode::Group { children } = &folded else { panic!("expected a group") };
+        assert!(matches!(&children[0], GeometryNode::Translate { offset: [6.0, 0.0, 0.0], child } if matches!(**child, GeometryNode::Cube { .. })));
+        assert_eq!(folded.node_count(), 4);
+    }
+
+    /// Test nodes returned by `enter` have their own children walked.
+    #[test]
+    fn test_rewrite_enter_replaces() {
+        struct Wrap;
+
+        impl GeometryRewriter for Wrap {
+            fn enter(&mut self, node: GeometryNode) -> GeometryNode {
+                match node {
+                    GeometryNode::Sphere { .. } => translate(1.0, cube()),
+                    other => other,
+                }
+            }
+
+            fn exit(&mut self, node: GeometryNode) -> GeometryNode {
+                match node {
+                    GeometryNode::Cube { .. } => GeometryNode::Empty,
+                    other => other,
+                }
+            }
+        }
+
+        let rewritten = GeometryNode::Sphere { radius: 1.0, fn_: 0 }.rewrite(&mut Wrap);
+        assert!(matches!(rewritten, GeometryNode::Translate { child, .. } if child.is_empty()));
+    }
+}
@@ -0,0 +1,319 @@
+//! # Structural Hashing
+//!
+//! Hashes geometry trees so the mesh stage can tell which subtrees are
+//! unchanged between two evaluations and reuse their meshes.
+//!
+//! ## Hashes
+//!
+//! Every node gets two hashes:
+//!
+//! | Hash | Covers |
+//! |------|--------|
+//! | `own` | The node's kind and parameters, not its children |
+//! | `hash` | `own` and the `hash` of every child, in order |
+//!
+//! Equal `hash` values mean equal subtrees (up to 64-bit collisions).
+//! `-0.0` hashes like `0.0`, as both give the same mesh. Hashes are stable
+//! within a process, not across builds, so they must not be persisted.
+//!
+//! ## Diffing
+//!
+//! [`diff`] walks two hashed trees together and returns the smallest
+//! subtrees of the new tree that differ from the old one:
+//!
+//! ```text
+//! old: union(cube(10), translate([0,0,5]) sphere(4))
+//! new: union(cube(10), translate([0,0,5]) sphere(5))
+//!                                          ^^^^^^^^^ path [1, 0]
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::{evaluate, hash::diff, NodeHashes};
+//!
+//! let old = evaluate("union() { cube(10); translate([0, 0, 5]) sphere(4); }").unwrap().geometry;
+//! let new = evaluate("union() { cube(10); translate([0, 0, 5]) sphere(5); }").unwrap().geometry;
+//! let (old, new) = (NodeHashes::new(&old), NodeHashes::new(&new));
+//! assert_eq!(old.children[0].hash, new.children[0].hash);
+//! assert_eq!(diff(&old, &new), vec![vec![1, 0]]);
+//! ```
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::geometry::{Deformation, GeometryNode};
+
+// =============================================================================
+// NODE HASHES
+// =============================================================================
+
+/// Hashes of a geometry tree, shaped like the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeHashes {
+    /// Hash of the whole subtree.
+    pub hash: u64,
+    /// Hash of the node without its children.
+    pub own: u64,
+    /// Whether the subtree's mesh depends on the tree alone, not on files
+    /// or fonts loaded at mesh time (`import`, `surface`, `text`).
+    pub self_contained: bool,
+    /// Hashes of the children, in order.
+    pub children: Vec<NodeHashes>,
+}
+
+impl NodeHashes {
+    /// Hash every node of `node`'s tree, bottom-up in one pass.
+    pub fn new(node: &GeometryNode) -> Self {
+        let children: Vec<NodeHashes> = node.children().iter().map(NodeHashes::new).collect();
+        let mut hasher = DefaultHasher::new();
+        hash_fields(node, &mut hasher);
+        let own = hasher.finish();
+
+        let mut hasher = DefaultHasher::new();
+        own.hash(&mut hasher);
+        for child in &children {
+            child.hash.hash(&mut hasher);
+        }
+        let self_contained = !matches!(node, GeometryNode::Import { .. } | GeometryNode::Surface { .. } | GeometryNode::Text { .. })
+            && children.iter().all(|child| child.self_contained);
+        Self { hash: hasher.finish(), own, self_contained, children }
+    }
+}
+
+impl GeometryNode {
+    /// Hash of this node's whole subtree; see [`NodeHashes`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use openscad_eval::GeometryNode;
+    ///
+    /// let cube = |x| GeometryNode::Cube { size: [x, 1.0, 1.0], center: false };
+    /// assert_eq!(cube(0.0).structural_hash(), cube(-0.0).structural_hash());
+    /// assert_ne!(cube(1.0).structural_hash(), cube(2.0).structural_hash());
+    /// ```
+    pub fn structural_hash(&self) -> u64 {
+        NodeHashes::new(self).hash
+    }
+}
+
+// =============================================================================
+// DIFF
+// =============================================================================
+
+/// Child indices leading from the root to a subtree.
+pub type NodePath = Vec<usize>;
+
+/// The smallest subtrees of `new` that differ from `old`, in tree order.
+///
+/// A node is reported whole when its own parameters or its number of
+/// children changed; otherwise only its differing children are. Equal
+/// trees give no paths, and a changed root gives `[[]]`.
+pub fn diff(old: &NodeHashes, new: &NodeHashes) -> Vec<NodePath> {
+    let mut paths = Vec::new();
+    diff_into(old, new, &mut Vec::new(), &mut paths);
+    paths
+}
+
+fn diff_into(old: &NodeHashes, new: &NodeHashes, path: &mut NodePath, out: &mut Vec<NodePath>) {
+    if old.hash == new.hash {
+        return;
+    }
+    if old.own != new.own || old.children.len() != new.children.len() {
+        out.push(path.clone());
+        return;
+    }
+    for (i, (old, new)) in old.children.iter().zip(&new.children).enumerate() {
+        path.push(i);
+        diff_into(old, new, path, out);
+        path.pop();
+    }
+}
+
+// =============================================================================
+// FIELDS
+// =============================================================================
+
+/// Feed a node's kind and parameters, not its children, to `h`.
+fn hash_fields(node: &GeometryNode, h: &mut DefaultHasher) {
+    std::mem::discriminant(node).hash(h);
+    match node {
+        GeometryNode::Cube { size, center } => {
+            floats(size, h);
+            center.hash(h);
+        }
+        GeometryNode::Sphere { radius, fn_ } | GeometryNode::Circle { radius, fn_ } => {
+            float(*radius, h);
+            fn_.hash(h);
+        }
+        GeometryNode::Cylinder { height, radius1, radius2, center, fn_ } => {
+            floats(&[*height, *radius1, *radius2], h);
+            center.hash(h);
+            fn_.hash(h);
+        }
+        GeometryNode::Polyhedron { points, faces } => {
+            points.len().hash(h);
+            points.iter().for_each(|p| floats(p, h));
+            faces.hash(h);
+        }
+        GeometryNode::Square { size, center } => {
+            floats(size, h);
+            center.hash(h);
+        }
+        GeometryNode::Polygon { points, paths } => {
+            points.len().hash(h);
+            points.iter().for_each(|p| floats(p, h));
+            paths.hash(h);
+        }
+        GeometryNode::Text { text, size, font, halign, valign, spacing, fn_ } => {
+            text.hash(h);
+            floats(&[*size, *spacing], h);
+            font.hash(h);
+            halign.hash(h);
+            valign.hash(h);
+            fn_.hash(h);
+        }
+        GeometryNode::Import { file, center, fn_ } => {
+            file.hash(h);
+            center.hash(h);
+            fn_.hash(h);
+        }
+        GeometryNode::Surface { file, center, invert } => {
+            file.hash(h);
+            center.hash(h);
+            invert.hash(h);
+        }
+        GeometryNode::Translate { offset: v, .. }
+        | GeometryNode::Rotate { angles: v, .. }
+        | GeometryNode::Scale { factors: v, .. }
+        | GeometryNode::Mirror { normal: v, .. } => floats(v, h),
+        GeometryNode::Resize { newsize, auto, .. } => {
+            floats(newsize, h);
+            auto.hash(h);
+        }
+        GeometryNode::Multmatrix { matrix, .. } => matrix.iter().for_each(|row| floats(row, h)),
+        GeometryNode::Color { rgba, .. } => floats(rgba, h),
+        GeometryNode::LinearExtrude { height, twist, scale, slices, center, .. } => {
+            floats(&[*height, *twist], h);
+            floats(scale, h);
+            slices.hash(h);
+            center.hash(h);
+        }
+        GeometryNode::RotateExtrude { angle, fn_, .. } => {
+            float(*angle, h);
+            fn_.hash(h);
+        }
+        GeometryNode::Offset { delta, round, chamfer, fn_, .. } => {
+            float(*delta, h);
+            round.hash(h);
+            chamfer.hash(h);
+            fn_.hash(h);
+        }
+        GeometryNode::Projection { cut, .. } => cut.hash(h),
+        GeometryNode::Smooth { iterations, lambda, mu, preserve_boundary, feature_angle, .. } => {
+            iterations.hash(h);
+            floats(&[*lambda, *mu], h);
+            preserve_boundary.hash(h);
+            feature_angle.map(f64::to_bits).hash(h);
+        }
+        GeometryNode::Refine { pieces, max_length, smooth, .. } => {
+            pieces.hash(h);
+            max_length.map(f64::to_bits).hash(h);
+            smooth.hash(h);
+        }
+        GeometryNode::Warp { deformation, .. } => match deformation {
+            Deformation::Twist { degrees } => {
+                0u8.hash(h);
+                float(*degrees, h);
+            }
+            Deformation::Taper { scale } => {
+                1u8.hash(h);
+                floats(scale, h);
+            }
+            Deformation::Bend { degrees } => {
+                2u8.hash(h);
+                float(*degrees, h);
+            }
+        },
+        GeometryNode::Quality { level, simplify, .. } => {
+            float(*level, h);
+            simplify.hash(h);
+        }
+        GeometryNode::Torus { major, minor, fn_, fn_tube } => {
+            floats(&[*major, *minor], h);
+            fn_.hash(h);
+            fn_tube.hash(h);
+        }
+        GeometryNode::RoundedCube { size, radius, center, fn_ } => {
+            floats(size, h);
+            float(*radius, h);
+            center.hash(h);
+            fn_.hash(h);
+        }
+        GeometryNode::Union { children }
+        | GeometryNode::Difference { children }
+        | GeometryNode::Intersection { children }
+        | GeometryNode::Hull { children }
+        | GeometryNode::Minkowski { children }
+        | GeometryNode::Group { children } => children.len().hash(h),
+        // A span only says where the call is: moving it changes no mesh
+        GeometryNode::Source { .. } | GeometryNode::Highlight { .. } | GeometryNode::Background { .. } | GeometryNode::Empty => {}
+    }
+}
+
+/// Feed a float to `h`, with `-0.0` as `0.0`.
+fn float(x: f64, h: &mut DefaultHasher) {
+    let x = if x == 0.0 { 0.0 } else { x };
+    x.to_bits().hash(h);
+}
+
+fn floats(xs: &[f64], h: &mut DefaultHasher) {
+    xs.iter().for_each(|&x| float(x, h));
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate;
+
+    fn hashes(source: &str) -> NodeHashes {
+        NodeHashes::new(&evaluate(source).unwrap().geometry)
+    }
+
+    /// Test equal sources hash equal and any parameter change shows.
+    #[test]
+    fn test_hash_equality() {
+        let a = hashes("difference() { cube(10); translate([5, 5, 5]) sphere(3); }");
+        assert_eq!(a, hashes("difference() { cube(10); translate([5, 5, 5]) sphere(3); }"));
+        assert_ne!(a.hash, hashes("difference() { cube(10); translate([5, 5, 6]) sphere(3); }").hash);
+        assert_ne!(a.hash, hashes("union() { cube(10); translate([5, 5, 5]) sphere(3); }").hash);
+        // Children order matters
+        assert_ne!(a.hash, hashes("difference() { translate([5, 5, 5]) sphere(3); cube(10); }").hash);
+    }
+
+    /// Test diffs report the smallest changed subtrees.
+    #[test]
+    fn test_diff_paths() {
+        let old = hashes("cube(1); union() { sphere(1); cylinder(h = 2, r = 1); }");
+        assert!(diff(&old, &old).is_empty());
+        let new = hashes("cube(1); union() { sphere(1); cylinder(h = 3, r = 1); }");
+        assert_eq!(diff(&old, &new), vec![vec![1, 1]]);
+        let new = hashes("cube(2); union() { sphere(1); }");
+        assert_eq!(diff(&old, &new), vec![vec![0], vec![1]]);
+        let new = hashes("sphere(1);");
+        assert_eq!(diff(&old, &new), vec![Vec::<usize>::new()]);
+    }
+
+    /// Test subtrees reading files or fonts are not self-contained.
+    #[test]
+    fn test_self_contained() {
+        let tree = hashes("cube(1); translate([1, 0, 0]) text(\"A\");");
+        assert!(!tree.self_contained);
+        assert!(tree.children[0].self_contained);
+        assert!(!tree.children[1].self_contained);
+    }
+}
@@ -22,12 +22,44 @@ pub mod error;
 pub mod scope;
 pub mod visitor;
 pub mod value;
+pub mod library;
+pub mod polyhedron;
+pub mod units;
+pub mod colors;
+pub mod options;
+pub mod files;
+pub mod capabilities;
+pub mod constants;
+pub mod limits;
+pub mod message;
+pub mod diagnostic;
+pub mod progress;
+pub mod hash;
+pub mod random;
+pub mod timing;
+
+#[cfg(test)]
+mod snapshots;
 
 // Re-export public API
-pub use geometry::{GeometryNode, EvaluatedAst};
+pub use geometry::{Bounds, Deformation, GeometryNode, GeometryStats, EvaluatedAst, GeometryRewriter, GeometryVisitor, HAlign, VAlign, Visit};
 pub use error::EvalError;
+pub use library::{compile_library, LibraryBundle};
 pub use scope::Scope;
 pub use value::Value;
+pub use visitor::ShimLibrary;
+pub use options::{CsgBackend, CsgOptions, EvalOptions, EvalParams, NormalMode, OutputOptions, Overrides, Precision, QualityOptions, SimplifyOptions, SphereStyle};
+pub use files::{FileProvider, MemoryFileProvider, SourceFile};
+pub use limits::{LimitExceeded, LimitKind, Limits, RecursionLimit};
+pub use message::{LogEntry, LogSink, Message, MessageKind};
+pub use diagnostic::{Diagnostic, DiagnosticKind};
+pub use progress::{CancellationToken, Progress, ProgressSink, Stage};
+pub use hash::NodeHashes;
+pub use timing::{NodeTiming, Timings};
+pub use openscad_ast::Span;
+
+/// Crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // =============================================================================
 // PUBLIC API
@@ -53,12 +85,134 @@ pub use value::Value;
 /// let result = evaluate("cube(10);").unwrap();
 /// ```
 pub fn evaluate(source: &str) -> Result<EvaluatedAst, EvalError> {
+    evaluate_with_libraries(source, &[])
+}
+
+/// Evaluate OpenSCAD source code with precompiled libraries preloaded.
+///
+/// Library definitions are registered in order before the source is
+/// evaluated, so the source can call their functions and modules as if
+/// they had been `use`d.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `libraries`: Bundles produced by [`compile_library`]
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_eval::{compile_library, evaluate_with_libraries, LibraryBundle};
+///
+/// let bytes = compile_library("module peg(h) { cylinder(h=h, r=1); }").unwrap();
+/// let lib = LibraryBundle::from_bytes(&bytes).unwrap();
+/// let result = evaluate_with_libraries("peg(5);", &[lib]).unwrap();
+/// ```
+pub fn evaluate_with_libraries(
+    source: &str,
+    libraries: &[LibraryBundle],
+) -> Result<EvaluatedAst, EvalError> {
     // Parse to AST using openscad-ast
     let ast = openscad_ast::parse(source)
         .map_err(|e| EvalError::ParseError(e.to_string()))?;
     
     // Evaluate AST to geometry
-    visitor::evaluate_ast(&ast)
+    visitor::evaluate_ast_with_libraries(&ast, libraries)
+}
+
+/// Evaluate OpenSCAD source code with native library shims enabled.
+///
+/// Shims stand in for common MCAD/BOSL2 helpers when the real libraries
+/// are not available. Definitions from `libraries` or from the source
+/// itself always take precedence. The shims that were actually used are
+/// reported in [`EvaluatedAst::shimmed`].
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `libraries`: Bundles produced by [`compile_library`]
+/// - `shims`: Libraries to shim
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_eval::{evaluate_with_shims, ShimLibrary};
+///
+/// let result = evaluate_with_shims("cuboid([10, 5, 2]);", &[], &[ShimLibrary::Bosl2]).unwrap();
+/// assert_eq!(result.shimmed, vec!["BOSL2::cuboid".to_string()]);
+/// ```
+pub fn evaluate_with_shims(
+    source: &str,
+    libraries: &[LibraryBundle],
+    shims: &[ShimLibrary],
+) -> Result<EvaluatedAst, EvalError> {
+    let ast = openscad_ast::parse(source)
+        .map_err(|e| EvalError::ParseError(e.to_string()))?;
+    visitor::evaluate_ast_with_shims(&ast, libraries, shims)
+}
+
+/// Evaluate OpenSCAD source code with libraries, shims and overrides.
+///
+/// Overrides follow OpenSCAD's `-D name=value`: they replace top-level
+/// assignments (and library globals) of the same name, but not locals
+/// inside modules, functions or `let`.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Libraries, shims and overrides
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_eval::{evaluate_with_options, EvalOptions, GeometryNode, Value};
+///
+/// let mut options = EvalOptions::default();
+/// options.overrides.insert("h".to_string(), Value::Number(5.0));
+/// let result = evaluate_with_options("h = 1; cube([1, 1, h]);", &options).unwrap();
+/// assert!(matches!(result.geometry, GeometryNode::Cube { size: [1.0, 1.0, 5.0], .. }));
+/// ```
+pub fn evaluate_with_options(
+    source: &str,
+    options: &EvalOptions,
+) -> Result<EvaluatedAst, EvalError> {
+    let parse_error = |e: openscad_ast::AstError| EvalError::ParseError(e.to_string());
+    let started = limits::now_ms();
+    let cst = {
+        let _span = timing::stage_span("parse");
+        openscad_ast::parse_cst(source).map_err(parse_error)?
+    };
+    let parsed = limits::now_ms();
+    let ast = {
+        let _span = timing::stage_span("ast");
+        openscad_ast::lower(&cst).map_err(parse_error)?
+    };
+    let lowered = limits::now_ms();
+    let mut result = visitor::evaluate_ast_with_options(&ast, options)?;
+    result.timings.parse_ms = parsed - started;
+    result.timings.ast_ms = lowered - parsed;
+    Ok(result)
+}
+
+/// Evaluate OpenSCAD source code with the given animation time, preview
+/// mode and viewport.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `params`: Values for `$t`, `$preview`, `$vpr`, `$vpt`, `$vpd`, `$vpf`
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_eval::{evaluate_with_params, EvalParams, GeometryNode};
+///
+/// let source = "if ($preview) sphere(1); else cube(1);";
+/// let result = evaluate_with_params(source, EvalParams { preview: false, ..EvalParams::default() }).unwrap();
+/// assert!(matches!(result.geometry, GeometryNode::Cube { .. }));
+/// ```
+pub fn evaluate_with_params(source: &str, params: EvalParams) -> Result<EvaluatedAst, EvalError> {
+    evaluate_with_options(source, &EvalOptions { params, ..EvalOptions::default() })
 }
 
 // =============================================================================
@@ -607,4 +761,125 @@ mod tests {
             _ => panic!("Expected Hull with 4 children"),
         }
     }
+
+    /// Test let expression bindings are sequential and scoped to the body.
+    #[test]
+    fn test_evaluate_let_expression() {
+        let code = r#"
+            function f(x) = let (a = x * 2, b = a + 1) b;
+            cube(f(2));
+        "#;
+        let result = evaluate(code).unwrap();
+        match result.geometry {
+            GeometryNode::Cube { size, .. } => assert_eq!(size, [5.0, 5.0, 5.0]),
+            _ => panic!("Expected Cube"),
+        }
+    }
+
+    /// Test let block bindings do not leak into the enclosing scope.
+    #[test]
+    fn test_evaluate_let_block() {
+        let code = r#"
+            w = 1;
+            let (w = 10, h = w / 2) cube([w, w, h]);
+            sphere(w);
+        "#;
+        let result = evaluate(code).unwrap();
+        match result.geometry {
+            GeometryNode::Group { children } => {
+                assert_eq!(children.len(), 2);
+                match &children[0] {
+                    GeometryNode::Cube { size, .. } => assert_eq!(*size, [10.0, 10.0, 5.0]),
+                    _ => panic!("First child should be Cube"),
+                }
+                match &children[1] {
+                    GeometryNode::Sphere { radius, .. } => assert_eq!(*radius, 1.0),
+                    _ => panic!("Second child should be Sphere"),
+                }
+            }
+            _ => panic!("Expected Group"),
+        }
+    }
+
+    /// Test list comprehensions generate geometry inputs end to end.
+    #[test]
+    fn test_evaluate_list_comprehension() {
+        let code = r#"
+            n = 6;
+            pts = [for (i = [0:n-1]) let (a = i * 360 / n) [10 * cos(a), 10 * sin(a)]];
+            polygon(pts);
+        "#;
+        let result = evaluate(code).unwrap();
+        match result.geometry {
+            GeometryNode::Polygon { points, .. } => {
+                assert_eq!(points.len(), 6);
+                assert!((points[0][0] - 10.0).abs() < 1e-9);
+            }
+            _ => panic!("Expected Polygon"),
+        }
+    }
+
+    /// Test polyhedron problems are warned with the call's location.
+    #[test]
+    fn test_evaluate_polyhedron_validation() {
+        let code = "cube(1);\npolyhedron(points=[[0,0,0],[1,0,0],[0,1,0]], faces=[[0,1,2],[0,1,5]]);";
+        let result = evaluate(code).unwrap();
+        assert!(result.warnings.iter().any(|w| {
+            w.starts_with("polyhedron at line 2, column 1") && w.contains("face 1 references missing point 5")
+        }));
+        assert!(result.warnings.iter().any(|w| w.contains("is open")));
+    }
+
+    /// Test overrides replace top-level assignments but not locals.
+    #[test]
+    fn test_evaluate_with_overrides() {
+        let mut options = EvalOptions::default();
+        options.overrides.insert("w".to_string(), Value::Number(7.0));
+        let source = "w = 1; module m() { w = 2; cube(w); } m(); translate([w, 0, 0]) cube(1);";
+        let result = evaluate_with_options(source, &options).unwrap();
+        match result.geometry {
+            GeometryNode::Group { children } => {
+                assert!(matches!(children[0], GeometryNode::Cube { size: [2.0, 2.0, 2.0], .. }));
+                assert!(matches!(children[1], GeometryNode::Translate { offset: [7.0, 0.0, 0.0], .. }));
+            }
+            other => panic!("Expected Group, got {:?}", other),
+        }
+    }
+
+    /// Test overrides define variables the source never assigns.
+    #[test]
+    fn test_evaluate_with_new_override() {
+        let mut options = EvalOptions::default();
+        options.overrides.insert("serial".to_string(), Value::Number(3.0));
+        let result = evaluate_with_options("cube(serial);", &options).unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Cube { size: [3.0, 3.0, 3.0], .. }));
+    }
+
+    /// Test every range expansion is checked against the iteration limit
+    /// before any values are made.
+    #[test]
+    fn test_iteration_limit_expansions() {
+        let options = EvalOptions {
+            limits: Limits { max_iterations: Some(1000), ..Limits::default() },
+            ..EvalOptions::default()
+        };
+        for source in ["x = [each [0:1e12]];", "x = concat([0:1e12]);", "x = search(1, [0:1e12]);", "x = chr([1:1e12]);"] {
+            match evaluate_with_options(source, &options) {
+                Err(EvalError::LimitExceeded(report)) => assert_eq!(report.kind, LimitKind::Iterations, "{}", source),
+                other => panic!("{}: expected the iteration limit, got {:?}", source, other),
+            }
+        }
+        assert!(evaluate_with_options("x = [each [0:999]];", &options).is_ok());
+    }
+
+    /// Test a loop over a huge range takes its values lazily, so only the
+    /// node limit stops it.
+    #[test]
+    fn test_huge_loop_is_lazy() {
+        let options = EvalOptions { limits: Limits { max_nodes: Some(10), ..Limits::default() }, ..EvalOptions::default() };
+        match evaluate_with_options("for (i = [0:1e12]) cube(1);", &options) {
+            Err(EvalError::LimitExceeded(report)) => assert_eq!(report.kind, LimitKind::Nodes),
+            other => panic!("expected the node limit, got {:?}", other),
+        }
+    }
 }
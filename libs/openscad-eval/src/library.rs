@@ -0,0 +1,165 @@
+//! # Precompiled Libraries
+//!
+//! Compile OpenSCAD library files into serialized definition bundles that
+//! can be registered with the evaluator without reparsing.
+//!
+//! ## Bundle Contents
+//!
+//! Only definitions are kept: function and module declarations, plus
+//! top-level assignments (library constants). Geometry statements are
+//! dropped, matching the semantics of `use <lib.scad>`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::{compile_library, LibraryBundle};
+//!
+//! let bytes = compile_library("function sq(x) = x * x;").unwrap();
+//! let bundle = LibraryBundle::from_bytes(&bytes).unwrap();
+//! assert_eq!(bundle.statements.len(), 1);
+//! ```
+
+use crate::error::EvalError;
+use openscad_ast::Statement;
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// CONSTANTS
+// =============================================================================
+
+/// Bundle format version.
+///
+/// Bumped whenever the serialized AST layout changes; bundles with a
+/// different version are rejected rather than misread.
+pub const LIBRARY_FORMAT_VERSION: u32 = 1;
+
+// =============================================================================
+// LIBRARY BUNDLE
+// =============================================================================
+
+/// Precompiled library definitions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryBundle {
+    /// Format version the bundle was written with.
+    pub version: u32,
+    /// Definition statements (functions, modules, assignments).
+    pub statements: Vec<Statement>,
+}
+
+impl LibraryBundle {
+    /// Decode a bundle produced by [`compile_library`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns `EvalError::InvalidLibrary` if the bytes are malformed or were
+    /// written with a different format version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EvalError> {
+        let bundle: Self = serde_json::from_slice(bytes)
+            .map_err(|e| EvalError::InvalidLibrary(e.to_string()))?;
+        if bundle.version != LIBRARY_FORMAT_VERSION {
+            return Err(EvalError::InvalidLibrary(format!(
+                "unsupported format version {} (expected {})",
+                bundle.version, LIBRARY_FORMAT_VERSION
+            )));
+        }
+        Ok(bundle)
+    }
+
+    /// Encode the bundle to bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EvalError> {
+        serde_json::to_vec(self).map_err(|e| EvalError::InvalidLibrary(e.to_string()))
+    }
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Compile library source into a serialized definition bundle.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD library source code
+///
+/// ## Returns
+///
+/// Bundle bytes, loadable with [`LibraryBundle::from_bytes`].
+///
+/// ## Errors
+///
+/// Returns `EvalError::ParseError` if the source does not parse.
+pub fn compile_library(source: &str) -> Result<Vec<u8>, EvalError> {
+    let ast = openscad_ast::parse(source)
+        .map_err(|e| EvalError::ParseError(e.to_string()))?;
+
+    let statements = ast
+        .statements
+        .into_iter()
+        .filter(is_definition)
+        .collect();
+
+    LibraryBundle {
+        version: LIBRARY_FORMAT_VERSION,
+        statements,
+    }
+    .to_bytes()
+}
+
+/// Check whether a top-level statement belongs in a library bundle.
+fn is_definition(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::FunctionDeclaration { .. }
+            | Statement::ModuleDeclaration { .. }
+            | Statement::Assignment { .. }
+    )
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{evaluate_with_libraries, GeometryNode};
+
+    /// Test geometry statements are stripped from bundles.
+    #[test]
+    fn test_compile_keeps_definitions_only() {
+        let bytes = compile_library("size = 3; module box() { cube(size); } cube(1);").unwrap();
+        let bundle = LibraryBundle::from_bytes(&bytes).unwrap();
+        assert_eq!(bundle.statements.len(), 2);
+    }
+
+    /// Test registered modules, functions and constants are usable.
+    #[test]
+    fn test_registered_library_is_callable() {
+        let bytes = compile_library(
+            "unit = 2; function twice(x) = x * 2; module box() { cube(twice(unit)); }",
+        )
+        .unwrap();
+        let lib = LibraryBundle::from_bytes(&bytes).unwrap();
+        let result = evaluate_with_libraries("box();", &[lib]).unwrap();
+        match result.geometry {
+            GeometryNode::Cube { size, .. } => assert_eq!(size, [4.0, 4.0, 4.0]),
+            other => panic!("Expected Cube, got {:?}", other),
+        }
+    }
+
+    /// Test malformed and mismatched bundles are rejected.
+    #[test]
+    fn test_invalid_bundle() {
+        assert!(matches!(
+            LibraryBundle::from_bytes(b"not a bundle"),
+            Err(EvalError::InvalidLibrary(_))
+        ));
+
+        let bundle = LibraryBundle { version: LIBRARY_FORMAT_VERSION + 1, statements: vec![] };
+        let bytes = bundle.to_bytes().unwrap();
+        assert!(matches!(
+            LibraryBundle::from_bytes(&bytes),
+            Err(EvalError::InvalidLibrary(_))
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+//! # Limits
+//!
+//! Budgets that abort a runaway render, and the report of which one
+//! tripped.
+//!
+//! ## Limits
+//!
+//! | Limit | Counted | Checked by |
+//! |-------|---------|------------|
+//! | Time | Milliseconds since evaluation started | evaluator, mesh stage |
+//! | Iterations | `for` loop and comprehension iterations, values spliced by `each`, ranges expanded by `concat()`, `search()` and `chr()`, and `rands()` values, in total | evaluator |
+//! | Nodes | Geometry nodes produced by module calls | evaluator |
+//! | Triangles | Triangles of the final mesh | mesh stage |
+//! | Memory | Bytes of the final mesh buffers | mesh stage |
+//!
+//! All limits are off by default. A loop's iterations are counted before
+//! it starts, so `for (i = [0:1e9])` fails at once instead of building a
+//! billion values first; ranges are expanded only after their count is
+//! checked. Without a limit a loop takes its values one at a time, so it
+//! runs in constant memory however long the range.
+//!
+//! Recursion is always bounded: nested calls of user functions and modules
+//! stop at `EvalOptions::max_recursion_depth` (default
+//! [`DEFAULT_MAX_RECURSION_DEPTH`]) with a [`RecursionLimit`] naming the
+//! call chain. Tail calls do not nest, so accumulator-style recursion runs
+//! in constant depth; like OpenSCAD, one call stops after
+//! [`MAX_TAIL_CALLS`] of them, so `function f(n) = f(n + 1);` fails
+//! instead of looping forever.
+//!
+//! ## Reports
+//!
+//! [`LimitExceeded`] names the limit, its configured value, the value
+//! reached and, for evaluator limits, the innermost statement being
+//! evaluated when it tripped:
+//!
+//! ```text
+//! for-loop at 10:1 exceeded 1,000,000 iterations (reached 1,000,000,001)
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::{evaluate_with_options, EvalError, EvalOptions};
+//! use openscad_eval::limits::{LimitKind, Limits};
+//!
+//! let options = EvalOptions {
+//!     limits: Limits { max_iterations: Some(1000), ..Limits::default() },
+//!     ..EvalOptions::default()
+//! };
+//! let Err(EvalError::LimitExceeded(report)) = evaluate_with_options("for (i = [0:1e9]) cube(i);", &options) else {
+//!     panic!("expected the iteration limit to trip");
+//! };
+//! assert_eq!(report.kind, LimitKind::Iterations);
+//! assert_eq!(report.to_string(), "for-loop at 1:1 exceeded 1,000 iterations (reached 1,000,000,001)");
+//! ```
+
+use std::fmt;
+
+use openscad_ast::Span;
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// CONFIGURATION
+// =============================================================================
+
+/// Which budget was exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum LimitKind {
+    /// Wall-clock time, in milliseconds.
+    Time,
+    /// Loop iterations.
+    Iterations,
+    /// Geometry nodes.
+    Nodes,
+    /// Mesh triangles.
+    Triangles,
+    /// Mesh memory, in bytes.
+    Memory,
+}
+
+impl LimitKind {
+    /// Unit the limit is counted in, as written in reports.
+    pub fn unit(self) -> &'static str {
+        match self {
+            LimitKind::Time => "ms",
+            LimitKind::Iterations => "iterations",
+            LimitKind::Nodes => "geometry nodes",
+            LimitKind::Triangles => "triangles",
+            LimitKind::Memory => "bytes",
+        }
+    }
+}
+
+/// Budgets for one render; `None` means unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    /// Maximum wall-clock time in milliseconds.
+    pub max_time_ms: Option<u64>,
+    /// Maximum loop iterations, summed over all loops.
+    pub max_iterations: Option<u64>,
+    /// Maximum geometry nodes.
+    pub max_nodes: Option<u64>,
+    /// Maximum triangles in the final mesh.
+    pub max_triangles: Option<u64>,
+    /// Maximum bytes of the final mesh buffers.
+    pub max_memory_bytes: Option<u64>,
+}
+
+impl Limits {
+    /// The configured value of a limit.
+    pub fn get(&self, kind: LimitKind) -> Option<u64> {
+        match kind {
+            LimitKind::Time => self.max_time_ms,
+            LimitKind::Iterations => self.max_iterations,
+            LimitKind::Nodes => self.max_nodes,
+            LimitKind::Triangles => self.max_triangles,
+            LimitKind::Memory => self.max_memory_bytes,
+        }
+    }
+
+    /// Check an observed value against a limit.
+    ///
+    /// ## Errors
+    ///
+    /// A report without a construct if `observed` is over the limit.
+    pub fn check(&self, kind: LimitKind, observed: u64) -> Result<(), Box<LimitExceeded>> {
+        match self.get(kind) {
+            Some(limit) if observed > limit => {
+                Err(Box::new(LimitExceeded { kind, limit, observed, construct: None }))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Nested user function and module calls allowed when the options do not
+/// say otherwise.
+pub const DEFAULT_MAX_RECURSION_DEPTH: usize = 10_000;
+
+/// Tail calls one user function call may make in a row, as in OpenSCAD.
+pub const MAX_TAIL_CALLS: usize = 1_000_000;
+
+// =============================================================================
+// REPORTS
+// =============================================================================
+
+/// A statement being evaluated, as named in reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct Construct {
+    /// What it is, e.g. `for-loop` or `cube() call`.
+    pub description: String,
+    /// Where it is in the source.
+    pub span: Span,
+}
+
+/// A limit that aborted a render.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct LimitExceeded {
+    /// Which limit tripped.
+    pub kind: LimitKind,
+    /// Its configured value.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub limit: u64,
+    /// The value reached.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub observed: u64,
+    /// Innermost statement being evaluated, if the evaluator tripped it.
+    pub construct: Option<Construct>,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.construct {
+            // Lines and columns are 1-based in messages
+            Some(c) => write!(f, "{} at {}:{}", c.description, c.span.start.line + 1, c.span.start.column + 1)?,
+            None => f.write_str("render")?,
+        }
+        write!(
+            f,
+            " exceeded {} {} (reached {})",
+            group_digits(self.limit),
+            self.kind.unit(),
+            group_digits(self.observed)
+        )
+    }
+}
+
+/// Recursion that went deeper than the configured depth, or made more
+/// than [`MAX_TAIL_CALLS`] tail calls.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecursionLimit {
+    /// The configured depth, or the tail call limit.
+    pub limit: usize,
+    /// Calls in progress, outermost first, e.g. `["tree", "branch", ...]`.
+    pub chain: Vec<String>,
+    /// Innermost statement being evaluated.
+    pub construct: Option<Construct>,
+    /// Whether the limit is the one on tail calls.
+    #[serde(default)]
+    pub tail_calls: bool,
+}
+
+impl fmt::Display for RecursionLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.construct {
+            Some(c) => write!(f, "{} at {}:{}", c.description, c.span.start.line + 1, c.span.start.column + 1)?,
+            None => f.write_str("render")?,
+        }
+        let calls = if self.tail_calls { "tail calls" } else { "nested calls" };
+        write!(f, " exceeded {} {}: {}", group_digits(self.limit as u64), calls, format_chain(&self.chain))
+    }
+}
+
+/// Write a call chain with runs of the same call collapsed and the middle
+/// of long chains elided: `a() → b() ×9,998 → a()`.
+fn format_chain(chain: &[String]) -> String {
+    // Runs of consecutive identical calls
+    let mut runs: Vec<(&str, u64)> = Vec::new();
+    for name in chain {
+        match runs.last_mut() {
+            Some((last, count)) if last == name => *count += 1,
+            _ => runs.push((name, 1)),
+        }
+    }
+    let run = |(name, count): &(&str, u64)| match count {
+        1 => format!("{}()", name),
+        n => format!("{}() ×{}", name, group_digits(*n)),
+    };
+
+    const SHOWN: usize = 4;
+    if runs.len() <= 2 * SHOWN + 1 {
+        return runs.iter().map(run).collect::<Vec<_>>().join(" → ");
+    }
+    let head: Vec<String> = runs[..SHOWN].iter().map(run).collect();
+    let tail: Vec<String> = runs[runs.len() - SHOWN..].iter().map(run).collect();
+    let hidden: u64 = runs[SHOWN..runs.len() - SHOWN].iter().map(|(_, n)| n).sum();
+    format!("{} → … {} more … → {}", head.join(" → "), group_digits(hidden), tail.join(" → "))
+}
+
+/// Write a count with thousands separators: `1000000` → `1,000,000`.
+fn group_digits(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// =============================================================================
+// CLOCK
+// =============================================================================
+
+/// Milliseconds on a monotonic clock, for measuring elapsed time.
+///
+/// Uses `Date.now()` in the browser, where `std::time::Instant` is not
+/// available.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+/// Milliseconds on a monotonic clock, for measuring elapsed time.
+///
+/// Uses `Date.now()` in the browser, where `std::time::Instant` is not
+/// available.
+#[cfg(target_arch = "wasm32")]
+pub fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openscad_ast::Position;
+
+    /// Test reports name the construct with 1-based positions.
+    #[test]
+    fn test_report_message() {
+        let report = LimitExceeded {
+            kind: LimitKind::Iterations,
+            limit: 1_000_000,
+            observed: 1_000_001,
+            construct: Some(Construct {
+                description: "for-loop".to_string(),
+                span: Span::new(Position::new(90, 9, 0), Position::new(120, 9, 30)),
+            }),
+        };
+        assert_eq!(report.to_string(), "for-loop at 10:1 exceeded 1,000,000 iterations (reached 1,000,001)");
+
+        let report = Limits { max_triangles: Some(500), ..Limits::default() }.check(LimitKind::Triangles, 1234).unwrap_err();
+        assert_eq!(report.to_string(), "render exceeded 500 triangles (reached 1,234)");
+        assert!(Limits::default().check(LimitKind::Memory, u64::MAX).is_ok());
+    }
+
+    /// Test call chains collapse repeats and elide the middle.
+    #[test]
+    fn test_recursion_message() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let report = RecursionLimit { limit: 10_000, chain: names(&["fact"; 10_000]), construct: None, tail_calls: false };
+        assert_eq!(report.to_string(), "render exceeded 10,000 nested calls: fact() ×10,000");
+        let report = RecursionLimit { limit: MAX_TAIL_CALLS, chain: names(&["main", "g"]), construct: None, tail_calls: true };
+        assert_eq!(report.to_string(), "render exceeded 1,000,000 tail calls: main() → g()");
+
+        let chain = names(&["main", "even", "odd", "even", "odd", "even", "odd", "even", "odd", "even", "odd"]);
+        assert_eq!(
+            format_chain(&chain),
+            "main() → even() → odd() → even() → … 3 more … → even() → odd() → even() → odd()"
+        );
+        assert_eq!(format_chain(&names(&["tree", "branch", "branch"])), "tree() → branch() ×2");
+    }
+
+    /// Test digit grouping.
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1000), "1,000");
+        assert_eq!(group_digits(1234567), "1,234,567");
+    }
+}
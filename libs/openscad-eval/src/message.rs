@@ -0,0 +1,105 @@
+//! # Console Messages
+//!
+//! Output a script prints while it is evaluated, as opposed to warnings
+//! about the script itself.
+//!
+//! Both end up in the [`EvaluatedAst`](crate::EvaluatedAst) once evaluation
+//! finishes. A [`LogSink`] in the options also receives each one as it
+//! happens, so a host can show them live, and still sees them if the
+//! evaluation fails later on.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::{evaluate, MessageKind};
+//!
+//! let result = evaluate("w = 20;\necho(\"width\", w = w);").unwrap();
+//! let message = &result.messages[0];
+//! assert_eq!(message.kind, MessageKind::Echo);
+//! assert_eq!(message.text, "\"width\", w = 20");
+//! assert_eq!(message.span.unwrap().start.line, 1);
+//! ```
+
+use std::fmt;
+
+use openscad_ast::Span;
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostic::Diagnostic;
+
+/// What produced a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum MessageKind {
+    /// `echo()` and `echo_dim()`.
+    Echo,
+}
+
+/// One line of console output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct Message {
+    /// What produced the message.
+    pub kind: MessageKind,
+    /// The text, without OpenSCAD's `ECHO: ` prefix.
+    pub text: String,
+    /// The statement that printed it, if known.
+    pub span: Option<Span>,
+}
+
+// =============================================================================
+// LOG SINKS
+// =============================================================================
+
+/// A line of console output or a warning, as it happens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogEntry<'a> {
+    /// Console output, also kept in `EvaluatedAst::messages`.
+    Message(&'a Message),
+    /// A warning, also kept in `EvaluatedAst::diagnostics`.
+    Diagnostic(&'a Diagnostic),
+}
+
+/// Receives console output and warnings while a script is evaluated.
+///
+/// Entries come in evaluation order from the evaluating thread. Any
+/// `Fn(LogEntry) + Send + Sync` closure is a sink.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use openscad_eval::{evaluate_with_options, EvalOptions, LogEntry};
+///
+/// let lines = Arc::new(Mutex::new(Vec::new()));
+/// let sink = Arc::clone(&lines);
+/// let options = EvalOptions {
+///     log: Some(Arc::new(move |entry: LogEntry| {
+///         let text = match entry {
+///             LogEntry::Message(m) => format!("ECHO: {}", m.text),
+///             LogEntry::Diagnostic(d) => format!("WARNING: {}", d.text),
+///         };
+///         sink.lock().unwrap().push(text);
+///     })),
+///     ..EvalOptions::default()
+/// };
+/// evaluate_with_options("echo(1); cube(1, r = 2);", &options).unwrap();
+/// assert_eq!(*lines.lock().unwrap(), ["ECHO: 1", "WARNING: cube(): variable r not specified as parameter"]);
+/// ```
+pub trait LogSink: Send + Sync {
+    /// Called with each entry, as it is produced.
+    fn log(&self, entry: LogEntry<'_>);
+}
+
+impl<F: Fn(LogEntry<'_>) + Send + Sync> LogSink for F {
+    fn log(&self, entry: LogEntry<'_>) {
+        self(entry)
+    }
+}
+
+impl fmt::Debug for dyn LogSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LogSink")
+    }
+}
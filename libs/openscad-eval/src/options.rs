@@ -0,0 +1,442 @@
+//! # Evaluation Options
+//!
+//! Settings applied to an evaluation from outside the source: precompiled
+//! libraries, compatibility shims, `-D` style parameter overrides, the
+//! special variables a viewer sets, the provider `include`/`use` read
+//! files from, resource limits, progress reporting and cancellation, live
+//! console output, tessellation quality, the mesher's boolean backend,
+//! the clean-up it applies to the finished mesh, and what is handed out
+//! with it.
+//!
+//! ## Overrides
+//!
+//! Like OpenSCAD's `-D name=value`, an override replaces the value of every
+//! top-level assignment to `name` and defines `name` if the source never
+//! assigns it. Assignments inside modules, functions, `let` and blocks are
+//! locals and are left alone.
+//!
+//! ## Special Variables
+//!
+//! [`EvalParams`] sets what OpenSCAD's GUI would: the animation time `$t`,
+//! `$preview`, and the viewport `$vpr`, `$vpt`, `$vpd` and `$vpf`. A
+//! viewer drives an animation by evaluating once per frame with a new
+//! `t`. Overrides of the same names win. Its `seed` makes unseeded
+//! `rands()` calls repeatable (see [`random`](crate::random)).
+//!
+//! ## Quality
+//!
+//! [`QualityOptions`] scales every segment count, as if the whole script
+//! were inside `quality(level)`: one level for previews and one for final
+//! renders, picked by [`EvalParams::preview`]. A viewer can keep dragging responsive on
+//! heavy models with a coarse preview and still export finely:
+//!
+//! ```rust
+//! use openscad_eval::{evaluate_with_options, EvalOptions, EvalParams, GeometryNode, QualityOptions};
+//!
+//! let quality = QualityOptions { preview: 0.5, render: 2.0 };
+//! let sphere = |preview| {
+//!     let options = EvalOptions { quality, params: EvalParams { preview, ..EvalParams::default() }, ..EvalOptions::default() };
+//!     match evaluate_with_options("sphere(5, $fn = 20);", &options).unwrap().geometry {
+//!         GeometryNode::Sphere { fn_, .. } => fn_,
+//!         other => panic!("expected a sphere, got {:?}", other),
+//!     }
+//! };
+//! assert_eq!((sphere(true), sphere(false)), (10, 40));
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::{evaluate_with_options, EvalOptions, GeometryNode};
+//! use openscad_eval::options::parse_override;
+//!
+//! let mut options = EvalOptions::default();
+//! let (name, value) = parse_override("size=20").unwrap();
+//! options.overrides.insert(name, value);
+//!
+//! let result = evaluate_with_options("size = 10; cube(size);", &options).unwrap();
+//! assert!(matches!(result.geometry, GeometryNode::Cube { size: [20.0, 20.0, 20.0], .. }));
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::EvalError;
+use crate::files::FileProvider;
+use crate::library::LibraryBundle;
+use crate::limits::Limits;
+use crate::message::LogSink;
+use crate::progress::{CancellationToken, ProgressSink};
+use crate::scope::{Scope, DEFAULT_VPD, DEFAULT_VPF, DEFAULT_VPR, DEFAULT_VPT};
+use crate::value::Value;
+use crate::visitor::{evaluate_statements, EvalContext, ShimLibrary};
+
+/// Top-level variable overrides by name.
+pub type Overrides = BTreeMap<String, Value>;
+
+// =============================================================================
+// OPTIONS
+// =============================================================================
+
+/// Everything an evaluation takes besides the source itself.
+#[derive(Debug, Clone, Default)]
+pub struct EvalOptions {
+    /// Precompiled libraries, registered in order.
+    pub libraries: Vec<LibraryBundle>,
+    /// Libraries to shim natively.
+    pub shims: Vec<ShimLibrary>,
+    /// Top-level variable overrides.
+    pub overrides: Overrides,
+    /// Animation time, preview mode and viewport.
+    pub params: EvalParams,
+    /// Source of `include`/`use` files; without one they are skipped with
+    /// a warning.
+    pub file_provider: Option<Arc<dyn FileProvider>>,
+    /// Budgets that abort a runaway evaluation.
+    pub limits: Limits,
+    /// Deepest nesting of user function and module calls; `None` for
+    /// [`DEFAULT_MAX_RECURSION_DEPTH`](crate::limits::DEFAULT_MAX_RECURSION_DEPTH).
+    pub max_recursion_depth: Option<usize>,
+    /// Receives a report as each stage advances.
+    pub progress: Option<Arc<dyn ProgressSink>>,
+    /// Receives console output and warnings as they happen.
+    pub log: Option<Arc<dyn LogSink>>,
+    /// Aborts the render with `EvalError::Cancelled` once cancelled.
+    pub cancel: Option<CancellationToken>,
+    /// Segment multipliers for previews and final renders.
+    pub quality: QualityOptions,
+    /// Enable the primitives OpenSCAD does not have, `torus`, `prism` and
+    /// `rounded_cube`, and the operations `refine` and `warp_*` (see
+    /// [`visitor::extensions`](crate::visitor::extensions)).
+    /// Off for strict OpenSCAD, where they are unknown modules.
+    pub extensions: bool,
+    /// Wrap the geometry of every module call in a
+    /// [`GeometryNode::Source`](crate::GeometryNode::Source) with the call's
+    /// span, for picking. Off by default, leaving the tree as OpenSCAD
+    /// would build it; meshes are the same either way.
+    pub sources: bool,
+    /// Clean-up of the finished mesh, after all booleans.
+    pub simplify: SimplifyOptions,
+    /// Backend and tuning of the mesher's booleans, and its sphere style.
+    pub csg: CsgOptions,
+    /// Precision and extras of what is handed to the caller.
+    pub output: OutputOptions,
+}
+
+impl EvalOptions {
+    /// Options with the given libraries preloaded.
+    pub fn with_libraries(libraries: &[LibraryBundle]) -> Self {
+        Self { libraries: libraries.to_vec(), ..Self::default() }
+    }
+}
+
+/// Tessellation quality of previews and final renders; the default
+/// changes nothing.
+///
+/// Each level multiplies every segment count, `$fn` included, and
+/// applies before any `quality()` in the script.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityOptions {
+    /// Segment multiplier while `$preview` is true.
+    pub preview: f64,
+    /// Segment multiplier while `$preview` is false.
+    pub render: f64,
+}
+
+impl Default for QualityOptions {
+    fn default() -> Self {
+        Self { preview: 1.0, render: 1.0 }
+    }
+}
+
+impl QualityOptions {
+    /// The multiplier for a preview or a final render.
+    pub fn level(&self, preview: bool) -> f64 {
+        if preview { self.preview } else { self.render }
+    }
+}
+
+/// Post-processing of the finished mesh; the default changes nothing.
+///
+/// The evaluator ignores these; the mesher applies them once the whole
+/// model is meshed, merging first, decimating second and recomputing
+/// normals last.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimplifyOptions {
+    /// Merge adjacent coplanar triangles into larger faces and
+    /// re-triangulate them, without changing the surface.
+    pub merge_coplanar: bool,
+    /// Decimate by quadric error down to at most this many triangles,
+    /// where that is possible without tearing the mesh.
+    pub target_triangles: Option<usize>,
+    /// Recompute the vertex normals flat or smooth; `None` keeps the
+    /// normals meshing produced.
+    pub normals: Option<NormalMode>,
+    /// Angle in degrees between faces above which smooth normals keep
+    /// the edge sharp; `None` for 30°.
+    pub crease_angle: Option<f64>,
+}
+
+/// Shading of the finished mesh's vertex normals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum NormalMode {
+    /// One normal per face.
+    Flat,
+    /// Normals averaged over the faces around each vertex, except across
+    /// edges sharper than [`SimplifyOptions::crease_angle`].
+    Smooth,
+}
+
+/// Algorithm the mesher's booleans cut the operands with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum CsgBackend {
+    /// Split along the planes of a BSP tree of the other operand.
+    #[default]
+    Bsp,
+    /// Split only where the other operand's triangles cross.
+    Intersect,
+    /// BSP for small operands, intersection splitting from
+    /// [`CsgOptions::auto_threshold`] triangles on.
+    Auto,
+}
+
+/// What a render hands the caller besides the mesh, and in which
+/// precision; the default is what a WebGL viewer needs.
+///
+/// The evaluator and the mesher ignore these; the caller applies them to
+/// the finished mesh.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutputOptions {
+    /// Precision of the vertex positions.
+    pub precision: Precision,
+    /// Also hand out the edges where faces meet at more than this angle
+    /// in degrees, and the boundary edges; `None` for no edges.
+    pub feature_angle: Option<f64>,
+}
+
+/// Precision of the vertex positions a render hands out.
+///
+/// The mesher works in double precision either way; this only picks what
+/// the positions are narrowed to at the end, as GPU buffers take `f32`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum Precision {
+    /// `f32`, as a `Float32Array` for WebGL.
+    #[default]
+    Single,
+    /// `f64`, for models whose detail is small next to their distance
+    /// from the origin.
+    Double,
+}
+
+/// How the mesher tessellates `sphere()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum SphereStyle {
+    /// Rings of latitude, as OpenSCAD does.
+    #[default]
+    Uv,
+    /// A subdivided icosahedron: triangles of nearly equal size.
+    Icosphere,
+    /// A cube with gridded faces, projected onto the sphere.
+    CubeSphere,
+}
+
+/// Boolean and tessellation settings of the mesher; `None` keeps its
+/// current setting.
+///
+/// The evaluator ignores these; the mesher applies them for the duration
+/// of the render.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CsgOptions {
+    /// Algorithm of each boolean.
+    pub backend: Option<CsgBackend>,
+    /// Triangles of the two operands together from which
+    /// [`CsgBackend::Auto`] switches to intersection splitting.
+    pub auto_threshold: Option<usize>,
+    /// Distance within which boolean results weld vertices together.
+    pub weld_tolerance: Option<f32>,
+    /// Most subtree meshes a render session keeps between updates.
+    pub cache_size: Option<usize>,
+    /// Tessellation of spheres.
+    pub sphere: Option<SphereStyle>,
+}
+
+// =============================================================================
+// SPECIAL VARIABLES
+// =============================================================================
+
+/// Values of the special variables a viewer sets rather than the script.
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_eval::{evaluate_with_params, EvalParams, GeometryNode};
+///
+/// let source = "translate([$t * 100, 0, 0]) cube(1);";
+/// let result = evaluate_with_params(source, EvalParams { t: 0.25, ..EvalParams::default() }).unwrap();
+/// assert!(matches!(result.geometry, GeometryNode::Translate { offset: [25.0, 0.0, 0.0], .. }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvalParams {
+    /// `$t`: animation time, from 0 to 1.
+    pub t: f64,
+    /// `$preview`: whether this is a quick preview rather than a final
+    /// render.
+    pub preview: bool,
+    /// `$vpr`: viewport rotation in degrees.
+    pub vpr: [f64; 3],
+    /// `$vpt`: viewport translation.
+    pub vpt: [f64; 3],
+    /// `$vpd`: viewport camera distance.
+    pub vpd: f64,
+    /// `$vpf`: viewport field of view in degrees.
+    pub vpf: f64,
+    /// Seed of the numbers unseeded `rands()` calls draw, for output that
+    /// is the same on every render; `None` seeds from the clock.
+    pub seed: Option<u64>,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        Self {
+            t: 0.0,
+            preview: true,
+            vpr: DEFAULT_VPR,
+            vpt: DEFAULT_VPT,
+            vpd: DEFAULT_VPD,
+            vpf: DEFAULT_VPF,
+            seed: None,
+        }
+    }
+}
+
+impl EvalParams {
+    /// Define the special variables in `scope`.
+    pub fn define(&self, scope: &mut Scope) {
+        scope.define("$t", Value::Number(self.t));
+        scope.define("$preview", Value::Boolean(self.preview));
+        scope.define("$vpr", Value::from(self.vpr));
+        scope.define("$vpt", Value::from(self.vpt));
+        scope.define("$vpd", Value::Number(self.vpd));
+        scope.define("$vpf", Value::Number(self.vpf));
+    }
+}
+
+// =============================================================================
+// PARSING
+// =============================================================================
+
+/// Parse a `name=value` override, as given to `-D`.
+///
+/// The value is any constant OpenSCAD expression: `size=20`,
+/// `label="A-01"`, `dims=[10, 20, 5]`.
+///
+/// ## Errors
+///
+/// Returns `EvalError::ParseError` if the text is not an assignment or the
+/// value does not parse, and any error from evaluating the value.
+pub fn parse_override(text: &str) -> Result<(String, Value), EvalError> {
+    let (name, _) = text.split_once('=')
+        .ok_or_else(|| EvalError::ParseError(format!("Override must be name=value: {}", text)))?;
+    let name = name.trim();
+
+    let ast = openscad_ast::parse(&format!("{};", text))
+        .map_err(|e| EvalError::ParseError(format!("Invalid override {}: {}", text, e)))?;
+    let mut ctx = EvalContext::new();
+    evaluate_statements(&mut ctx, &ast.statements)?;
+
+    let value = ctx.scope.get(name)
+        .cloned()
+        .ok_or_else(|| EvalError::ParseError(format!("Invalid override name: {}", name)))?;
+    Ok((name.to_string(), value))
+}
+
+/// Convert a JSON value (e.g. from a JavaScript options object) to an
+/// override value. `null` becomes `undef`; objects are not supported.
+pub fn value_from_json(json: &serde_json::Value) -> Result<Value, EvalError> {
+    match json {
+        serde_json::Value::Null => Ok(Value::Undef),
+        serde_json::Value::Bool(b) => Ok(Value::Boolean(*b)),
+        serde_json::Value::Number(n) => n.as_f64()
+            .map(Value::Number)
+            .ok_or_else(|| EvalError::TypeError(format!("Unsupported number: {}", n))),
+        serde_json::Value::String(s) => Ok(Value::String(s.clone())),
+        serde_json::Value::Array(items) => {
+            items.iter().map(value_from_json).collect::<Result<_, _>>().map(Value::List)
+        }
+        serde_json::Value::Object(_) => {
+            Err(EvalError::TypeError("Objects cannot be used as override values".to_string()))
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test numbers, strings and lists parse as OpenSCAD values.
+    #[test]
+    fn test_parse_override() {
+        assert_eq!(parse_override("size=20").unwrap(), ("size".to_string(), Value::Number(20.0)));
+        assert_eq!(
+            parse_override("label = \"A-01\"").unwrap(),
+            ("label".to_string(), Value::String("A-01".to_string()))
+        );
+        let (_, dims) = parse_override("dims=[1, 2 * 3]").unwrap();
+        assert_eq!(dims, Value::List(vec![Value::Number(1.0), Value::Number(6.0)]));
+    }
+
+    /// Test malformed overrides are rejected.
+    #[test]
+    fn test_parse_override_errors() {
+        assert!(parse_override("size").is_err());
+        assert!(parse_override("size=").is_err());
+    }
+
+    /// Test the defaults match a fresh scope and params replace them.
+    #[test]
+    fn test_params() {
+        let mut scope = Scope::new();
+        let names = ["$t", "$preview", "$vpr", "$vpt", "$vpd", "$vpf"];
+        let defaults: Vec<Value> = names.iter().map(|n| scope.get(n).cloned().unwrap()).collect();
+        EvalParams::default().define(&mut scope);
+        for (name, value) in names.iter().zip(&defaults) {
+            assert_eq!(scope.get(name), Some(value), "{}", name);
+        }
+
+        let params = EvalParams { t: 0.5, preview: false, vpr: [0.0, 0.0, 90.0], ..EvalParams::default() };
+        params.define(&mut scope);
+        assert_eq!(scope.get("$t"), Some(&Value::Number(0.5)));
+        assert_eq!(scope.get("$preview"), Some(&Value::Boolean(false)));
+        assert_eq!(scope.get("$vpr"), Some(&Value::from([0.0, 0.0, 90.0])));
+    }
+
+    /// Test JSON values map onto OpenSCAD values.
+    #[test]
+    fn test_value_from_json() {
+        let json: serde_json::Value = serde_json::from_str(r#"[1, true, "x", null]"#).unwrap();
+        assert_eq!(
+            value_from_json(&json).unwrap(),
+            Value::List(vec![
+                Value::Number(1.0),
+                Value::Boolean(true),
+                Value::String("x".to_string()),
+                Value::Undef,
+            ])
+        );
+        assert!(value_from_json(&serde_json::json!({"a": 1})).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! # Polyhedron Validation
+//!
+//! Checks `polyhedron()` point/face data before it reaches the mesher.
+//!
+//! ## Checks
+//!
+//! - Faces with fewer than 3 distinct vertices
+//! - Face indices outside the point list
+//! - Degenerate (zero-area) faces
+//! - Open edges (used by only one face)
+//! - Inconsistent winding (two faces traverse an edge in the same direction)
+//! - Non-manifold edges (shared by more than two faces)
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::polyhedron::validate_polyhedron;
+//!
+//! let points = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+//! let faces = vec![vec![0, 1, 2], vec![0, 3, 1], vec![0, 2, 3], vec![1, 3, 2]];
+//! assert!(validate_polyhedron(&points, &faces).is_empty());
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Twice-area threshold below which a face counts as degenerate.
+const DEGENERATE_AREA: f64 = 1e-12;
+
+// =============================================================================
+// ISSUES
+// =============================================================================
+
+/// A problem found in polyhedron data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolyhedronIssue {
+    /// Face has fewer than 3 distinct vertices.
+    TooFewVertices {
+        /// Face index.
+        face: usize,
+    },
+    /// Face references a point that does not exist.
+    IndexOutOfRange {
+        /// Face index.
+        face: usize,
+        /// Offending point index.
+        index: usize,
+    },
+    /// Face has (near) zero area.
+    DegenerateFace {
+        /// Face index.
+        face: usize,
+    },
+    /// Edge is used by only one face, so the surface is not closed.
+    OpenEdge {
+        /// Edge endpoints (point indices, ascending).
+        edge: (usize, usize),
+    },
+    /// Two faces traverse the edge in the same direction.
+    InconsistentWinding {
+        /// Edge endpoints (point indices, ascending).
+        edge: (usize, usize),
+    },
+    /// Edge is shared by more than two faces.
+    NonManifoldEdge {
+        /// Edge endpoints (point indices, ascending).
+        edge: (usize, usize),
+        /// Number of faces using the edge.
+        faces: usize,
+    },
+}
+
+impl PolyhedronIssue {
+    /// Face the issue belongs to, if it is a per-face issue.
+    pub fn face(&self) -> Option<usize> {
+        match self {
+            Self::TooFewVertices { face }
+            | Self::IndexOutOfRange { face, .. }
+            | Self::DegenerateFace { face } => Some(*face),
+            _ => None,
+        }
+    }
+
+    /// Whether the face cannot be meshed at all.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::TooFewVertices { .. } | Self::IndexOutOfRange { .. })
+    }
+}
+
+impl fmt::Display for PolyhedronIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewVertices { face } => {
+                write!(f, "face {} has fewer than 3 distinct vertices", face)
+            }
+            Self::IndexOutOfRange { face, index } => {
+                write!(f, "face {} references missing point {}", face, index)
+            }
+            Self::DegenerateFace { face } => write!(f, "face {} is degenerate (zero area)", face),
+            Self::OpenEdge { edge } => {
+                write!(f, "edge {}-{} is open (used by one face)", edge.0, edge.1)
+            }
+            Self::InconsistentWinding { edge } => {
+                write!(f, "edge {}-{} has inconsistent face winding", edge.0, edge.1)
+            }
+            Self::NonManifoldEdge { edge, faces } => {
+                write!(f, "edge {}-{} is shared by {} faces", edge.0, edge.1, faces)
+            }
+        }
+    }
+}
+
+// =============================================================================
+// VALIDATION
+// =============================================================================
+
+/// Validate polyhedron points and faces.
+///
+/// Per-face issues come first in face order, followed by edge issues in
+/// edge order, so the output is deterministic. Faces with fatal issues are
+/// left out of the edge checks.
+///
+/// ## Parameters
+///
+/// - `points`: Vertex positions
+/// - `faces`: Faces as point indices
+///
+/// ## Returns
+///
+/// All issues found; empty for a closed, consistently wound 2-manifold.
+pub fn validate_polyhedron(points: &[[f64; 3]], faces: &[Vec<usize>]) -> Vec<PolyhedronIssue> {
+    let mut issues = Vec::new();
+    // Directed edge counts keyed by the undirected edge: (forward, backward)
+    let mut edges: BTreeMap<(usize, usize), (usize, usize)> = BTreeMap::new();
+
+    for (face_index, face) in faces.iter().enumerate() {
+        if let Some(&index) = face.iter().find(|&&i| i >= points.len()) {
+            issues.push(PolyhedronIssue::IndexOutOfRange { face: face_index, index });
+            continue;
+        }
+
+        let mut distinct = face.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() < 3 {
+            issues.push(PolyhedronIssue::TooFewVertices { face: face_index });
+            continue;
+        }
+
+        if face_area2(points, face) < DEGENERATE_AREA {
+            issues.push(PolyhedronIssue::DegenerateFace { face: face_index });
+        }
+
+        for (k, &a) in face.iter().enumerate() {
+            let b = face[(k + 1) % face.len()];
+            if a == b {
+                continue;
+            }
+            let entry = edges.entry((a.min(b), a.max(b))).or_default();
+            if a < b {
+                entry.0 += 1;
+            } else {
+                entry.1 += 1;
+            }
+        }
+    }
+
+    for (edge, (forward, backward)) in edges {
+        let total = forward + backward;
+        if total == 1 {
+            issues.push(PolyhedronIssue::OpenEdge { edge });
+        } else if total > 2 {
+            issues.push(PolyhedronIssue::NonManifoldEdge { edge, faces: total });
+        } else if forward != backward {
+            issues.push(PolyhedronIssue::InconsistentWinding { edge });
+        }
+    }
+
+    issues
+}
+
+/// Twice the area of a (possibly non-planar) polygon via Newell's method.
+fn face_area2(points: &[[f64; 3]], face: &[usize]) -> f64 {
+    let mut n = [0.0; 3];
+    for (k, &i) in face.iter().enumerate() {
+        let p = points[i];
+        let q = points[face[(k + 1) % face.len()]];
+        n[0] += (p[1] - q[1]) * (p[2] + q[2]);
+        n[1] += (p[2] - q[2]) * (p[0] + q[0]);
+        n[2] += (p[0] - q[0]) * (p[1] + q[1]);
+    }
+    (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tetra_points() -> Vec<[f64; 3]> {
+        vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+    }
+
+    /// Test a closed tetrahedron passes.
+    #[test]
+    fn test_valid_tetrahedron() {
+        let faces = vec![vec![0, 1, 2], vec![0, 3, 1], vec![0, 2, 3], vec![1, 3, 2]];
+        assert!(validate_polyhedron(&tetra_points(), &faces).is_empty());
+    }
+
+    /// Test a missing face leaves open edges.
+    #[test]
+    fn test_open_edges() {
+        let faces = vec![vec![0, 1, 2], vec![0, 3, 1], vec![0, 2, 3]];
+        let issues = validate_polyhedron(&tetra_points(), &faces);
+        assert_eq!(issues.len(), 3);
+        assert!(issues.iter().all(|i| matches!(i, PolyhedronIssue::OpenEdge { .. })));
+    }
+
+    /// Test a flipped face is reported as inconsistent winding.
+    #[test]
+    fn test_flipped_face() {
+        let faces = vec![vec![0, 1, 2], vec![0, 3, 1], vec![0, 2, 3], vec![1, 2, 3]];
+        let issues = validate_polyhedron(&tetra_points(), &faces);
+        assert!(!issues.is_empty());
+        assert!(issues.iter().all(|i| matches!(i, PolyhedronIssue::InconsistentWinding { .. })));
+    }
+
+    /// Test per-face problems are reported with their face index.
+    #[test]
+    fn test_face_issues() {
+        let mut points = tetra_points();
+        points.push([2.0, 0.0, 0.0]);
+        let faces = vec![vec![0, 1, 9], vec![0, 1, 1], vec![0, 1, 4]];
+        let issues = validate_polyhedron(&points, &faces);
+        assert_eq!(issues[0], PolyhedronIssue::IndexOutOfRange { face: 0, index: 9 });
+        assert_eq!(issues[1], PolyhedronIssue::TooFewVertices { face: 1 });
+        assert_eq!(issues[2], PolyhedronIssue::DegenerateFace { face: 2 });
+        assert!(issues[0].is_fatal());
+        assert_eq!(issues[2].to_string(), "face 2 is degenerate (zero area)");
+    }
+}
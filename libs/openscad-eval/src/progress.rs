@@ -0,0 +1,171 @@
+//! # Progress and Cancellation
+//!
+//! Lets a caller watch a render and stop it early.
+//!
+//! ## Stages
+//!
+//! | Stage | Counted | Reported by |
+//! |-------|---------|-------------|
+//! | Evaluate | Top-level statements | evaluator |
+//! | Csg | Geometry nodes meshed and combined | mesh stage |
+//! | Mesh | The finished mesh checked against the limits | mesh stage |
+//!
+//! Each stage starts with `done == 0` and ends with `done == total`, so a
+//! progress bar can give each stage its own share.
+//!
+//! ## Cancellation
+//!
+//! A [`CancellationToken`] is checked wherever the time limit is: before
+//! each statement, on each user function call and before each geometry
+//! node is meshed. Cancelling from another thread, or from an event
+//! handler between the steps of an async render, aborts with
+//! `EvalError::Cancelled` instead of finishing the render.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use std::sync::{Arc, Mutex};
+//! use openscad_eval::{evaluate_with_options, EvalError, EvalOptions};
+//! use openscad_eval::progress::{CancellationToken, Progress, Stage};
+//!
+//! let reports = Arc::new(Mutex::new(Vec::new()));
+//! let sink = Arc::clone(&reports);
+//! let options = EvalOptions {
+//!     progress: Some(Arc::new(move |p: Progress| sink.lock().unwrap().push(p))),
+//!     ..EvalOptions::default()
+//! };
+//! evaluate_with_options("cube(1); sphere(1);", &options).unwrap();
+//! let last = *reports.lock().unwrap().last().unwrap();
+//! assert_eq!(last, Progress { stage: Stage::Evaluate, done: 2, total: 2 });
+//!
+//! let cancel = CancellationToken::new();
+//! cancel.cancel();
+//! let options = EvalOptions { cancel: Some(cancel), ..EvalOptions::default() };
+//! assert!(matches!(evaluate_with_options("cube(1);", &options), Err(EvalError::Cancelled)));
+//! ```
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// PROGRESS
+// =============================================================================
+
+/// Part of the pipeline a report is about, ordered as they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum Stage {
+    /// Evaluating the script to a geometry tree.
+    Evaluate,
+    /// Meshing primitives and combining them with booleans.
+    Csg,
+    /// Checking and handing over the finished mesh.
+    Mesh,
+}
+
+/// How far a stage has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct Progress {
+    /// The stage running.
+    pub stage: Stage,
+    /// Units of work finished.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub done: u64,
+    /// Units of work in the stage.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub total: u64,
+}
+
+impl Progress {
+    /// Share of the stage finished, from 0 to 1; an empty stage is done.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.done as f64 / self.total as f64).min(1.0)
+        }
+    }
+}
+
+/// Receives progress reports.
+///
+/// Reports can come from rayon worker threads when booleans run in
+/// parallel, hence `Send + Sync`; keep `report` cheap. Any
+/// `Fn(Progress) + Send + Sync` closure is a sink.
+pub trait ProgressSink: Send + Sync {
+    /// Called as a stage advances.
+    fn report(&self, progress: Progress);
+}
+
+impl<F: Fn(Progress) + Send + Sync> ProgressSink for F {
+    fn report(&self, progress: Progress) {
+        self(progress)
+    }
+}
+
+impl fmt::Debug for dyn ProgressSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressSink")
+    }
+}
+
+// =============================================================================
+// CANCELLATION
+// =============================================================================
+
+/// Shared flag that aborts a render.
+///
+/// Clones share the flag: keep one, hand another to the render, and call
+/// [`CancellationToken::cancel`] on yours to stop it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// A token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every render holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`CancellationToken::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test clones share the flag.
+    #[test]
+    fn test_token_is_shared() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+
+    /// Test fractions are clamped and empty stages count as done.
+    #[test]
+    fn test_fraction() {
+        let progress = |done, total| Progress { stage: Stage::Csg, done, total };
+        assert_eq!(progress(1, 4).fraction(), 0.25);
+        assert_eq!(progress(0, 0).fraction(), 1.0);
+        assert_eq!(progress(5, 4).fraction(), 1.0);
+    }
+}
@@ -0,0 +1,90 @@
+//! # Random Numbers
+//!
+//! The generator behind `rands()`: SplitMix64, in plain integer
+//! arithmetic, so a seed gives the same numbers on every platform, the
+//! browser included.
+//!
+//! ## Seeds
+//!
+//! | Call | Numbers from |
+//! |------|--------------|
+//! | `rands(min, max, n, seed)` | A generator of its own, seeded with `seed` |
+//! | `rands(min, max, n)` | The evaluation's generator, seeded with [`EvalParams::seed`] |
+//!
+//! Without [`EvalParams::seed`], the evaluation's generator is seeded from
+//! the clock, so unseeded calls differ from render to render, as in
+//! OpenSCAD.
+//!
+//! [`EvalParams::seed`]: crate::EvalParams::seed
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::random::Rng;
+//!
+//! let mut a = Rng::new(42);
+//! let mut b = Rng::new(42);
+//! assert_eq!(a.next_f64(), b.next_f64());
+//! assert!((0.0..1.0).contains(&a.next_f64()));
+//! ```
+
+use crate::limits::now_ms;
+
+/// Added to the state on every draw (the golden ratio in 64 bits).
+const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// A SplitMix64 pseudo-random generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// A generator seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// A generator seeded from the clock.
+    pub fn from_clock() -> Self {
+        Self::new(now_ms().to_bits())
+    }
+
+    /// The next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `[0, 1)`, from the top 53 bits of the next draw.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::from_clock()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the generator matches the SplitMix64 reference sequence.
+    #[test]
+    fn test_reference_sequence() {
+        let mut rng = Rng::new(1234567);
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+        assert_eq!(rng.next_u64(), 9817491932198370423);
+    }
+}
@@ -7,7 +7,7 @@
 //! - Variables are lexically scoped
 //! - Inner scopes can shadow outer scope variables
 //! - Variables cannot be reassigned in the same scope
-//! - Special variables ($fn, $fa, $fs) have default values
+//! - Special variables ($fn, $fa, $fs, $t, $preview, $vp*) have default values
 //!
 //! ## Example
 //!
@@ -33,6 +33,14 @@ pub const DEFAULT_FN: f64 = 0.0;
 pub const DEFAULT_FA: f64 = 12.0;
 /// Default $fs value (mm).
 pub const DEFAULT_FS: f64 = 2.0;
+/// Default $vpr value: viewport rotation in degrees.
+pub const DEFAULT_VPR: [f64; 3] = [55.0, 0.0, 25.0];
+/// Default $vpt value: viewport translation.
+pub const DEFAULT_VPT: [f64; 3] = [0.0, 0.0, 0.0];
+/// Default $vpd value: viewport camera distance.
+pub const DEFAULT_VPD: f64 = 140.0;
+/// Default $vpf value: viewport field of view in degrees.
+pub const DEFAULT_VPF: f64 = 22.5;
 
 // =============================================================================
 // SCOPE
@@ -64,6 +72,8 @@ impl ScopeLevel {
 /// ## Example
 ///
 /// ```rust
+/// use openscad_eval::{Scope, Value};
+///
 /// let mut scope = Scope::new();
 /// scope.define("x", Value::Number(10.0));
 ///
@@ -85,7 +95,8 @@ impl Scope {
     ///
     /// ## Returns
     ///
-    /// A scope with $fn, $fa, $fs set to defaults.
+    /// A scope with $fn, $fa, $fs, $t, $preview and the viewport
+    /// variables set to defaults.
     pub fn new() -> Self {
         let mut scope = Self {
             levels: vec![ScopeLevel::new()],
@@ -97,6 +108,10 @@ impl Scope {
         scope.define("$fs", Value::Number(DEFAULT_FS));
         scope.define("$t", Value::Number(0.0)); // Animation time
         scope.define("$preview", Value::Boolean(true)); // Preview mode
+        scope.define("$vpr", Value::from(DEFAULT_VPR));
+        scope.define("$vpt", Value::from(DEFAULT_VPT));
+        scope.define("$vpd", Value::Number(DEFAULT_VPD));
+        scope.define("$vpf", Value::Number(DEFAULT_VPF));
         
         scope
     }
@@ -118,6 +133,11 @@ impl Scope {
         }
     }
 
+    /// Whether the global (file-level) scope is the current one.
+    pub fn is_global(&self) -> bool {
+        self.levels.len() == 1
+    }
+
     /// Define a variable in the current scope.
     ///
     /// ## Parameters
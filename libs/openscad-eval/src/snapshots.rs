@@ -0,0 +1,195 @@
+//! # IR Snapshots
+//!
+//! Golden snapshots of the evaluated geometry tree for representative
+//! scripts, so evaluator changes that alter semantics show up as a diff.
+//!
+//! ## Layout
+//!
+//! Each case in [`CASES`] has a file `snapshots/<name>.json` in this crate,
+//! holding the pretty-printed JSON of its [`EvaluatedAst`](crate::EvaluatedAst)
+//! (geometry, warnings and echoes). Floats are rounded to 9 decimals so
+//! last-bit differences in `sin`/`cos` between platforms don't matter.
+//!
+//! ## Updating
+//!
+//! A mismatch or missing snapshot fails the test, prints a line diff and
+//! writes the new output next to it as `<name>.json.new`. After reviewing
+//! the change, accept all new outputs with:
+//!
+//! ```text
+//! UPDATE_SNAPSHOTS=1 cargo test -p openscad-eval snapshots
+//! ```
+
+use std::path::PathBuf;
+
+use serde_json::Value as Json;
+
+use crate::evaluate;
+
+/// Decimal places kept for floats in snapshots.
+const FLOAT_DECIMALS: i32 = 9;
+
+/// Snapshot cases by name.
+const CASES: &[(&str, &str)] = &[
+    ("primitives", "cube(10); cube([1, 2, 3], center = true); sphere(r = 5); sphere(d = 4); \
+        cylinder(h = 10, r1 = 2, r2 = 1, center = true); circle(3); square([4, 2]);"),
+    ("transforms", "translate([1, 2, 3]) rotate([0, 0, 90]) scale(2) mirror([1, 0, 0]) cube(1); \
+        color([1, 0, 0]) sphere(1); color([0, 0, 1, 0.5]) cube(2);"),
+    ("booleans", "difference() { cube(10, center = true); sphere(6); } \
+        intersection() { cube(4); translate([2, 2, 2]) cube(4); } hull() { circle(1); translate([5, 0]) circle(1); }"),
+    ("special_variables", "$fn = 12; sphere(1); cylinder(h = 2, r = 1, $fn = 6); \
+        module ring() { circle(2); } ring($fn = 8);"),
+    ("modules", "module box(size = 2, center = false) { cube(size, center = center); } \
+        module wrap() { translate([0, 0, 1]) children(); } \
+        box(); box(4, center = true); box(center = true, size = [1, 2, 3]); wrap() { box(); sphere(1); }"),
+    ("functions", "function double(x) = 2 * x; \
+        function fact(n) = n <= 1 ? 1 : n * fact(n - 1); \
+        cube([double(1), fact(4), len([1, 2, 3])]);"),
+    ("scoping", "a = 1; module m() { a = 2; cube(a); } m(); cube(a); \
+        translate([a, 0, 0]) { b = a + 1; sphere(b); }"),
+    ("control_flow", "for (i = [0 : 2]) translate([i * 2, 0, 0]) cube(1); \
+        for (p = [[1, 0], [0, 1]]) translate(p) circle(0.5); \
+        if (1 > 2) cube(1); else sphere(1); \
+        let (w = 3, h = w * 2) square([w, h]);"),
+    ("expressions", "v = [for (i = [1 : 3]) i * i]; r = [0 : 0.5 : 1]; \
+        cube([v[2], 1 + 2 * 3 - 4 / 2, -(2 ^ 3) + 10]); cube([sin(30), cos(60), abs(-2)]);"),
+    ("extrusions", "linear_extrude(height = 5, center = true, twist = 90, scale = 0.5, $fn = 8) square(2); \
+        rotate_extrude(angle = 180, $fn = 16) translate([3, 0]) circle(1); offset(r = 1) square(2);"),
+    ("warnings", "no_such_module(); v = [no_such_function(1)]; cube(1);"),
+];
+
+// =============================================================================
+// HARNESS
+// =============================================================================
+
+/// Directory holding the snapshot files.
+fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots")
+}
+
+/// Evaluate a script into its snapshot text.
+///
+/// Evaluation errors are snapshotted too, as `{"error": "..."}`.
+fn render_snapshot(source: &str) -> String {
+    let mut json = match evaluate(source) {
+        Ok(result) => serde_json::json!({
+            "geometry": result.geometry,
+            "warnings": result.warnings,
+            "echoes": result.echoes,
+        }),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    };
+    round_floats(&mut json);
+    let mut text = serde_json::to_string_pretty(&json).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+/// Compare a case against its snapshot file.
+///
+/// ## Returns
+///
+/// `None` if it matches (or was updated), else a report with the diff.
+fn check_snapshot(name: &str, source: &str) -> Option<String> {
+    let path = snapshot_dir().join(format!("{}.json", name));
+    let new_path = path.with_extension("json.new");
+    let actual = render_snapshot(source);
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some_and(|v| v != "0") {
+        std::fs::create_dir_all(snapshot_dir()).ok();
+        std::fs::write(&path, &actual).ok();
+        std::fs::remove_file(&new_path).ok();
+        return None;
+    }
+
+    let expected = std::fs::read_to_string(&path).ok();
+    if expected.as_deref() == Some(actual.as_str()) {
+        std::fs::remove_file(&new_path).ok();
+        return None;
+    }
+    std::fs::write(&new_path, &actual).ok();
+    Some(match expected {
+        Some(expected) => format!("snapshot '{}' changed:\n{}", name, line_diff(&expected, &actual)),
+        None => format!("snapshot '{}' is missing; new output in {}", name, new_path.display()),
+    })
+}
+
+/// Round every non-integer number to [`FLOAT_DECIMALS`] places.
+fn round_floats(json: &mut Json) {
+    match json {
+        Json::Number(n) if n.is_f64() => {
+            let scale = 10f64.powi(FLOAT_DECIMALS);
+            let rounded = (n.as_f64().unwrap_or_default() * scale).round() / scale;
+            // -0.0 and 0.0 snapshot the same
+            let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+            if let Some(number) = serde_json::Number::from_f64(rounded) {
+                *n = number;
+            }
+        }
+        Json::Array(items) => items.iter_mut().for_each(round_floats),
+        Json::Object(fields) => fields.values_mut().for_each(round_floats),
+        _ => {}
+    }
+}
+
+/// Unified-style line diff, `-` for expected and `+` for actual lines.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let (old, new): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+
+    // Longest common subsequence table, from the end
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("{:>5} - {}\n", i + 1, old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("{:>5} + {}\n", j + 1, new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test every case matches its golden snapshot.
+    #[test]
+    fn test_snapshots() {
+        let failures: Vec<String> = CASES.iter()
+            .filter_map(|(name, source)| check_snapshot(name, source))
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    /// Test the diff marks only changed lines.
+    #[test]
+    fn test_line_diff() {
+        let diff = line_diff("a\nb\nc\n", "a\nx\nc\nd\n");
+        assert_eq!(diff, "    2 - b\n    2 + x\n    4 + d\n");
+    }
+
+    /// Test float rounding hides last-bit noise.
+    #[test]
+    fn test_round_floats() {
+        let mut json = serde_json::json!([0.1 + 0.2, -0.0000000000001, 2]);
+        round_floats(&mut json);
+        assert_eq!(json.to_string(), "[0.3,0.0,2]");
+    }
+}
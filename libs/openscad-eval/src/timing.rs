@@ -0,0 +1,185 @@
+//! # Timings
+//!
+//! Where the time of a render went, stage by stage, and optional
+//! [`tracing`](https://docs.rs/tracing) spans for profilers.
+//!
+//! ## Stages
+//!
+//! | Field | Measures |
+//! |-------|----------|
+//! | `parse_ms` | Source to concrete syntax tree |
+//! | `ast_ms` | Syntax tree to AST |
+//! | `eval_ms` | AST to geometry tree |
+//! | `csg` | Each CSG node meshed, children included |
+//! | `mesh_ms` | Geometry tree to finished mesh, `csg` included |
+//!
+//! The evaluator fills the first three in [`EvaluatedAst::timings`]; the
+//! mesh stage copies them into its result and adds the rest. A stage that
+//! was skipped, such as parsing when a cached AST is reused, stays at 0.
+//!
+//! ## Tracing
+//!
+//! With the `tracing` feature the pipeline also opens spans, so a
+//! subscriber such as `tracing-chrome` can draw a flame chart of a render:
+//!
+//! | Span | Fields | Opened for |
+//! |------|--------|------------|
+//! | `parse`, `ast`, `eval`, `mesh` | | Each stage |
+//! | `statement` | `construct`, `line`, `column` | Each module call, loop, `if`, `let` and assignment evaluated |
+//! | `csg` | `kind` | Each CSG node meshed |
+//!
+//! `statement` spans nest as the calls do, so time attributes to the
+//! source lines that spent it. Without the feature the spans compile to
+//! nothing.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::evaluate;
+//!
+//! let result = evaluate("for (i = [0:99]) translate([i, 0, 0]) cube(1);").unwrap();
+//! let timings = result.timings;
+//! assert!(timings.parse_ms >= 0.0 && timings.eval_ms >= 0.0);
+//! assert!(timings.csg.is_empty());
+//! ```
+//!
+//! [`EvaluatedAst::timings`]: crate::EvaluatedAst::timings
+
+use serde::{Deserialize, Serialize};
+
+use crate::limits::Construct;
+
+/// Milliseconds spent in each stage of a render.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "typescript", ts(rename_all = "camelCase"))]
+pub struct Timings {
+    /// Parsing the source to a syntax tree.
+    pub parse_ms: f64,
+    /// Lowering the syntax tree to an AST.
+    pub ast_ms: f64,
+    /// Evaluating the AST to a geometry tree.
+    pub eval_ms: f64,
+    /// CSG nodes meshed, by position in the geometry tree.
+    #[serde(default)]
+    pub csg: Vec<NodeTiming>,
+    /// Meshing the geometry tree, CSG nodes included.
+    #[serde(default)]
+    pub mesh_ms: f64,
+}
+
+/// Time spent meshing one CSG node: a boolean, hull, Minkowski sum,
+/// extrusion, offset, projection or smoothing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct NodeTiming {
+    /// Position of the node in a pre-order walk of the geometry tree, the
+    /// root being 0 and empty nodes not counted.
+    pub node: u32,
+    /// Depth of the node, the root being 0.
+    pub depth: u32,
+    /// What the node is, e.g. `difference` or `linear_extrude`.
+    pub kind: String,
+    /// Milliseconds meshing the node and its children.
+    pub ms: f64,
+}
+
+impl Timings {
+    /// Total of the stages: parsing, lowering, evaluation and meshing.
+    pub fn total_ms(&self) -> f64 {
+        self.parse_ms + self.ast_ms + self.eval_ms + self.mesh_ms
+    }
+}
+
+// =============================================================================
+// TRACING
+// =============================================================================
+
+/// An entered `tracing` span, left when dropped; empty without the
+/// `tracing` feature.
+#[must_use = "the span is left as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct SpanGuard {
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+/// Enter the span of a pipeline stage: `parse`, `ast`, `eval` or `mesh`.
+pub fn stage_span(stage: &'static str) -> SpanGuard {
+    #[cfg(feature = "tracing")]
+    {
+        let span = match stage {
+            "parse" => tracing::info_span!("parse"),
+            "ast" => tracing::info_span!("ast"),
+            "eval" => tracing::info_span!("eval"),
+            _ => tracing::info_span!("mesh"),
+        };
+        SpanGuard { _span: span.entered() }
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = stage;
+        SpanGuard {}
+    }
+}
+
+/// Enter the span of a statement being evaluated, at its 1-based source
+/// position.
+pub fn statement_span(construct: &Construct) -> SpanGuard {
+    #[cfg(feature = "tracing")]
+    {
+        let start = construct.span.start;
+        let span = tracing::trace_span!(
+            "statement",
+            construct = %construct.description,
+            line = start.line + 1,
+            column = start.column + 1,
+        );
+        SpanGuard { _span: span.entered() }
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = construct;
+        SpanGuard {}
+    }
+}
+
+/// Enter the span of a CSG node being meshed.
+pub fn csg_span(kind: &'static str) -> SpanGuard {
+    #[cfg(feature = "tracing")]
+    {
+        SpanGuard { _span: tracing::trace_span!("csg", kind).entered() }
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = kind;
+        SpanGuard {}
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the JSON names, as JavaScript sees them.
+    #[test]
+    fn test_json() {
+        let timings = Timings {
+            parse_ms: 1.0,
+            ast_ms: 2.0,
+            eval_ms: 3.0,
+            csg: vec![NodeTiming { node: 0, depth: 0, kind: "union".to_string(), ms: 4.0 }],
+            mesh_ms: 5.0,
+        };
+        let json = serde_json::to_value(&timings).unwrap();
+        assert_eq!(json["parseMs"], 1.0);
+        assert_eq!(json["csg"][0]["kind"], "union");
+        assert_eq!(timings.total_ms(), 11.0);
+        assert_eq!(serde_json::from_value::<Timings>(json).unwrap(), timings);
+    }
+}
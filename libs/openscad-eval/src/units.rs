@@ -0,0 +1,105 @@
+//! # Units
+//!
+//! Unit conversion and dimension formatting for debugging output.
+//!
+//! Model units are millimetres; dimensions are echoed in both millimetres
+//! and inches so designs built around imperial hardware can be checked at
+//! a glance.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::units::format_dim;
+//! use openscad_eval::Value;
+//!
+//! let text = format_dim(&Value::Number(25.4), 2).unwrap();
+//! assert_eq!(text, "25.40 mm (1.00 in)");
+//! ```
+
+use crate::error::EvalError;
+use crate::value::Value;
+
+// =============================================================================
+// CONSTANTS
+// =============================================================================
+
+/// Millimetres per inch.
+pub const MM_PER_INCH: f64 = 25.4;
+
+/// Default number of decimal places in formatted dimensions.
+pub const DEFAULT_DIM_PRECISION: usize = 2;
+
+/// Largest accepted number of decimal places.
+pub const MAX_DIM_PRECISION: usize = 10;
+
+// =============================================================================
+// FORMATTING
+// =============================================================================
+
+/// Format a dimension in millimetres and inches.
+///
+/// Numbers and (nested) lists of numbers are accepted; lists are
+/// converted element-wise.
+///
+/// ## Parameters
+///
+/// - `value`: Dimension in millimetres
+/// - `precision`: Decimal places, clamped to [`MAX_DIM_PRECISION`]
+///
+/// ## Example
+///
+/// ```text
+/// 25.4        -> "25.40 mm (1.00 in)"
+/// [10, 20]    -> "[10.00, 20.00] mm ([0.39, 0.79] in)"
+/// ```
+pub fn format_dim(value: &Value, precision: usize) -> Result<String, EvalError> {
+    let precision = precision.min(MAX_DIM_PRECISION);
+    let mm = format_scaled(value, 1.0, precision)?;
+    let inch = format_scaled(value, 1.0 / MM_PER_INCH, precision)?;
+    Ok(format!("{} mm ({} in)", mm, inch))
+}
+
+/// Format a number or list of numbers multiplied by `factor`.
+fn format_scaled(value: &Value, factor: f64, precision: usize) -> Result<String, EvalError> {
+    match value {
+        Value::Number(n) => Ok(format!("{:.*}", precision, n * factor)),
+        Value::List(items) => {
+            let parts: Result<Vec<_>, _> = items.iter()
+                .map(|item| format_scaled(item, factor, precision))
+                .collect();
+            Ok(format!("[{}]", parts?.join(", ")))
+        }
+        _ => Err(EvalError::TypeError(format!(
+            "Expected number or list of numbers for dimension, got {:?}",
+            value
+        ))),
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_dim(&Value::Number(50.8), 1).unwrap(), "50.8 mm (2.0 in)");
+    }
+
+    #[test]
+    fn test_format_list() {
+        let value = Value::List(vec![Value::Number(25.4), Value::Number(12.7)]);
+        assert_eq!(
+            format_dim(&value, 3).unwrap(),
+            "[25.400, 12.700] mm ([1.000, 0.500] in)"
+        );
+    }
+
+    #[test]
+    fn test_format_rejects_non_numeric() {
+        assert!(format_dim(&Value::String("x".to_string()), 2).is_err());
+    }
+}
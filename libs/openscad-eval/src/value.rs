@@ -2,6 +2,8 @@
 //!
 //! Value types used during evaluation.
 
+use std::fmt;
+
 use crate::error::EvalError;
 use serde::{Deserialize, Serialize};
 
@@ -10,9 +12,10 @@ use serde::{Deserialize, Serialize};
 // =============================================================================
 
 /// A runtime value in OpenSCAD.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum Value {
     /// Undefined value.
+    #[default]
     Undef,
     /// Boolean.
     Boolean(bool),
@@ -96,11 +99,208 @@ impl Value {
     pub fn is_undef(&self) -> bool {
         matches!(self, Value::Undef)
     }
+
+    /// Number of values [`Value::into_iteration_values`] yields, without
+    /// building them; saturates for unbounded ranges.
+    pub fn iteration_count(&self) -> u64 {
+        match self {
+            Value::List(items) => items.len() as u64,
+            Value::Range { start, end, step } => {
+                let step = step.unwrap_or(1.0);
+                let steps = (end - start) / step;
+                if step == 0.0 || steps.is_nan() || steps < 0.0 {
+                    0
+                } else {
+                    // Float to int casts saturate
+                    (steps.floor() as u64).saturating_add(1)
+                }
+            }
+            Value::String(s) => s.chars().count() as u64,
+            _ => 1,
+        }
+    }
+
+    /// Expand into the values a `for` loop iterates over.
+    ///
+    /// Lists yield their items, ranges their numbers and strings their
+    /// characters; any other value is iterated once as itself. Range
+    /// values are `start + i * step` rather than a running sum, so
+    /// `[0:0.1:1]` ends exactly at `1` and descending ranges like
+    /// `[10:-1:0]` count down.
+    ///
+    /// Values are made as they are taken, so a loop over `[0:1e9]` never
+    /// holds more than one of them; callers check
+    /// [`Value::iteration_count`] against the iteration limit first.
+    pub fn into_iteration_values(self) -> IterationValues {
+        let count = self.iteration_count();
+        match self {
+            Value::List(items) => IterationValues::List(items.into_iter()),
+            Value::Range { start, step, .. } => IterationValues::Range { start, step: step.unwrap_or(1.0), next: 0, count },
+            Value::String(s) => IterationValues::Chars(s.chars().collect::<Vec<char>>().into_iter()),
+            other => IterationValues::Single(Some(other)),
+        }
+    }
+}
+
+/// The values a `for` loop iterates over, from
+/// [`Value::into_iteration_values`].
+#[derive(Debug, Clone)]
+pub enum IterationValues {
+    /// Items of a list.
+    List(std::vec::IntoIter<Value>),
+    /// Numbers of a range, made on demand.
+    Range {
+        /// First value.
+        start: f64,
+        /// Distance between values.
+        step: f64,
+        /// Index of the next value.
+        next: u64,
+        /// Number of values.
+        count: u64,
+    },
+    /// Characters of a string.
+    Chars(std::vec::IntoIter<char>),
+    /// Any other value, once.
+    Single(Option<Value>),
+}
+
+impl Iterator for IterationValues {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        match self {
+            Self::List(items) => items.next(),
+            Self::Range { start, step, next, count } => {
+                if next >= count {
+                    return None;
+                }
+                let value = *start + *next as f64 * *step;
+                *next += 1;
+                Some(Value::Number(value))
+            }
+            Self::Chars(chars) => chars.next().map(|c| Value::String(c.to_string())),
+            Self::Single(value) => value.take(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self {
+            Self::List(items) => items.len(),
+            Self::Range { next, count, .. } => usize::try_from(count - next).unwrap_or(usize::MAX),
+            Self::Chars(chars) => chars.len(),
+            Self::Single(value) => usize::from(value.is_some()),
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const N: usize> From<[f64; N]> for Value {
+    /// A vector of numbers, like `[1, 2, 3]`.
+    fn from(vector: [f64; N]) -> Self {
+        Value::List(vector.into_iter().map(Value::Number).collect())
+    }
+}
+
+// =============================================================================
+// FORMATTING
+// =============================================================================
+
+/// Formats a value as OpenSCAD's `str()` does.
+///
+/// Strings are written as-is at the top level and quoted inside lists;
+/// numbers use [`format_number`].
+///
+/// ```text
+/// 5                 -> 5
+/// 1 / 3             -> 0.333333
+/// ["a", 1, true]    -> ["a", 1, true]
+/// [0 : 2 : 10]      -> [0 : 2 : 10]
+/// ```
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(s) => f.write_str(s),
+            other => write_nested(other, f),
+        }
+    }
 }
 
-impl Default for Value {
-    fn default() -> Self {
-        Value::Undef
+impl Value {
+    /// Format the value as `echo()` prints it: like `str()`, but strings
+    /// are quoted at the top level too.
+    ///
+    /// ```text
+    /// "a"        -> "a"
+    /// ["a", 1]   -> ["a", 1]
+    /// ```
+    pub fn to_echo_string(&self) -> String {
+        struct Echo<'a>(&'a Value);
+        impl fmt::Display for Echo<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write_nested(self.0, f)
+            }
+        }
+        Echo(self).to_string()
+    }
+}
+
+/// Write a value as it appears inside a list, with strings quoted.
+fn write_nested(value: &Value, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match value {
+        Value::Undef => f.write_str("undef"),
+        Value::Boolean(b) => write!(f, "{}", b),
+        Value::Number(n) => f.write_str(&format_number(*n)),
+        Value::String(s) => write!(f, "{:?}", s),
+        Value::List(items) => {
+            f.write_str("[")?;
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write_nested(item, f)?;
+            }
+            f.write_str("]")
+        }
+        Value::Range { start, end, step } => write!(
+            f,
+            "[{} : {} : {}]",
+            format_number(*start),
+            format_number(step.unwrap_or(1.0)),
+            format_number(*end)
+        ),
+    }
+}
+
+/// Format a number like C's `%g`: six significant digits, trailing zeros
+/// dropped, and an exponent below 1e-4 or from 1e6 on.
+///
+/// ```text
+/// 5 -> 5    0.1 + 0.2 -> 0.3    1e6 -> 1e+06    -1/0 -> -inf
+/// ```
+pub fn format_number(n: f64) -> String {
+    if n.is_nan() {
+        return "nan".to_string();
+    }
+    if n.is_infinite() {
+        return if n > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    if n == 0.0 {
+        return "0".to_string();
+    }
+
+    // Round to six significant digits first, so 999999.5 becomes 1e+06
+    let scientific = format!("{:.5e}", n);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let trim = |s: &str| if s.contains('.') { s.trim_end_matches('0').trim_end_matches('.').to_string() } else { s.to_string() };
+
+    if !(-4..6).contains(&exponent) {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", trim(mantissa), sign, exponent.abs())
+    } else {
+        let decimals = (5 - exponent) as usize;
+        trim(&format!("{:.*}", decimals, n))
     }
 }
 
@@ -141,4 +341,78 @@ mod tests {
         assert!(Value::Number(1.0).as_boolean());
         assert!(!Value::Number(0.0).as_boolean());
     }
+
+    #[test]
+    fn test_iteration_values() {
+        let range = Value::Range { start: 0.0, end: 4.0, step: Some(2.0) };
+        assert_eq!(
+            range.into_iteration_values().collect::<Vec<_>>(),
+            vec![Value::Number(0.0), Value::Number(2.0), Value::Number(4.0)]
+        );
+        assert_eq!(
+            Value::String("ab".to_string()).into_iteration_values().collect::<Vec<_>>(),
+            vec![Value::String("a".to_string()), Value::String("b".to_string())]
+        );
+        assert_eq!(Value::Number(3.0).into_iteration_values().collect::<Vec<_>>(), vec![Value::Number(3.0)]);
+    }
+
+    /// Test descending ranges count down and fractional steps do not drift.
+    #[test]
+    fn test_range_values() {
+        let numbers = |range: Value| -> Vec<f64> {
+            range.into_iteration_values().map(|v| v.as_number().unwrap()).collect()
+        };
+        assert_eq!(numbers(Value::Range { start: 10.0, end: 0.0, step: Some(-3.0) }), vec![10.0, 7.0, 4.0, 1.0]);
+        assert_eq!(numbers(Value::Range { start: 0.0, end: 5.0, step: Some(-1.0) }), Vec::<f64>::new());
+        assert_eq!(numbers(Value::Range { start: 0.0, end: 1.0, step: Some(0.1) }).last(), Some(&1.0));
+    }
+
+    #[test]
+    fn test_iteration_count() {
+        for value in [
+            Value::Range { start: 0.0, end: 4.0, step: Some(2.0) },
+            Value::Range { start: 5.0, end: 0.0, step: Some(-1.5) },
+            Value::Range { start: 1.0, end: 0.0, step: None },
+            Value::String("héllo".to_string()),
+            Value::Number(3.0),
+        ] {
+            assert_eq!(value.iteration_count(), value.clone().into_iteration_values().count() as u64, "{:?}", value);
+        }
+        let huge = Value::Range { start: 0.0, end: f64::INFINITY, step: None };
+        assert_eq!(huge.iteration_count(), u64::MAX);
+        // Lazy, so taking a few values of a huge range is cheap
+        let first: Vec<Value> = Value::Range { start: 0.0, end: 1e12, step: None }.into_iteration_values().take(2).collect();
+        assert_eq!(first, vec![Value::Number(0.0), Value::Number(1.0)]);
+    }
+
+    /// Test numbers format like `%g` with six significant digits.
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(5.0), "5");
+        assert_eq!(format_number(-2.5), "-2.5");
+        assert_eq!(format_number(1.0 / 3.0), "0.333333");
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(123456.0), "123456");
+        assert_eq!(format_number(1234567.0), "1.23457e+06");
+        assert_eq!(format_number(999999.5), "1e+06");
+        assert_eq!(format_number(0.0001), "0.0001");
+        assert_eq!(format_number(0.00001), "1e-05");
+        assert_eq!(format_number(f64::NEG_INFINITY), "-inf");
+    }
+
+    /// Test values display as str() does, quoting only nested strings.
+    #[test]
+    fn test_display() {
+        assert_eq!(Value::String("a\"b".to_string()).to_string(), "a\"b");
+        let list = Value::List(vec![
+            Value::String("a".to_string()),
+            Value::Number(1.0),
+            Value::Boolean(true),
+            Value::Undef,
+            Value::List(vec![]),
+        ]);
+        assert_eq!(list.to_string(), "[\"a\", 1, true, undef, []]");
+        let range = Value::Range { start: 0.0, end: 10.0, step: Some(2.0) };
+        assert_eq!(range.to_string(), "[0 : 2 : 10]");
+    }
 }
@@ -0,0 +1,338 @@
+//! # Argument Resolution
+//!
+//! Binds the arguments of a call to the callee's parameters, following
+//! OpenSCAD's rules for user functions, user modules and builtins alike.
+//!
+//! ## Rules
+//!
+//! - Positional arguments fill the positional parameters in order; extra
+//!   ones are dropped with a warning.
+//! - Named arguments then override, wherever they appear in the call.
+//! - A named argument that matches no parameter is ignored with a
+//!   warning, unless it is a special variable (`$fn`), which is set for
+//!   the duration of the call.
+//! - A parameter given no argument is missing: the callee uses its
+//!   default, or `undef`. An explicit `undef` argument is not missing.
+//!
+//! ## Example
+//!
+//! ```text
+//! module plate(w, h = 2) { cube([w, w, h]); }
+//! plate(10);               // w = 10, h = 2
+//! plate(h = 1, 10);        // w = 10, h = 1
+//! plate(10, 1, 5);         // warning: too many unnamed arguments
+//! plate(10, depth = 3);    // warning: depth is not a parameter
+//! plate(10, $fn = 64);     // $fn = 64 inside plate()
+//! ```
+
+use crate::error::EvalError;
+use crate::diagnostic::DiagnosticKind;
+use crate::value::Value;
+use openscad_ast::ast::Parameter;
+use openscad_ast::Argument;
+use std::collections::HashMap;
+
+use super::context::EvalContext;
+use super::expressions::eval_expr;
+
+// =============================================================================
+// CALL ARGUMENTS
+// =============================================================================
+
+/// Arguments of a call, evaluated in the caller's scope.
+#[derive(Debug, Clone, Default)]
+pub struct CallArguments {
+    /// Positional arguments, in order.
+    pub positional: Vec<Value>,
+    /// Named arguments, in order.
+    pub named: Vec<(String, Value)>,
+}
+
+impl CallArguments {
+    /// Evaluate the arguments of a call in the current scope, left to
+    /// right.
+    ///
+    /// ## Errors
+    ///
+    /// The first error from an argument expression
+    pub fn evaluate(ctx: &mut EvalContext, args: &[Argument]) -> Result<Self, EvalError> {
+        let mut call_args = Self::default();
+        for arg in args {
+            match arg {
+                Argument::Positional(e) => call_args.positional.push(eval_expr(ctx, e)?),
+                Argument::Named { name, value } => {
+                    let value = eval_expr(ctx, value)?;
+                    call_args.named.push((name.clone(), value));
+                }
+            }
+        }
+        Ok(call_args)
+    }
+}
+
+// =============================================================================
+// RESOLVER
+// =============================================================================
+
+/// Binds call arguments to the parameters of one callee.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// let args = CallArguments::evaluate(ctx, args)?;
+/// let resolved = ArgumentResolver::new("cylinder", &["h", "r1", "r2", "center"])
+///     .named(&["r", "d", "d1", "d2"])
+///     .resolve(ctx, args);
+/// let height = resolved.get("h");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArgumentResolver<'a> {
+    /// Callee name, for warnings.
+    callee: &'a str,
+    /// Parameters that take positional arguments, in order.
+    positional: Vec<&'a str>,
+    /// Parameters that can only be given by name.
+    named: Vec<&'a str>,
+}
+
+impl<'a> ArgumentResolver<'a> {
+    /// Resolver for `callee` with the given positional parameters.
+    pub fn new(callee: &'a str, params: &[&'a str]) -> Self {
+        Self { callee, positional: params.to_vec(), named: Vec::new() }
+    }
+
+    /// Add parameters that can only be given by name.
+    pub fn named(mut self, params: &[&'a str]) -> Self {
+        self.named.extend_from_slice(params);
+        self
+    }
+
+    /// Bind `args` to the parameters, warning about arguments that are
+    /// dropped.
+    ///
+    /// ## Parameters
+    ///
+    /// - `ctx`: Evaluation context, for warnings
+    /// - `args`: Evaluated call arguments
+    ///
+    /// ## Returns
+    ///
+    /// The supplied parameters and special variables
+    pub fn resolve(&self, ctx: &mut EvalContext, args: CallArguments) -> ResolvedArguments {
+        let mut resolved = ResolvedArguments::default();
+
+        let supplied = args.positional.len();
+        for (name, value) in self.positional.iter().zip(args.positional) {
+            resolved.values.insert(name.to_string(), value);
+        }
+        if supplied > self.positional.len() {
+            ctx.diagnose(DiagnosticKind::IgnoredArgument, format!(
+                "{}(): too many unnamed arguments supplied ({} given, {} expected)",
+                self.callee,
+                supplied,
+                self.positional.len()
+            ));
+        }
+
+        let mut seen = Vec::with_capacity(args.named.len());
+        for (name, value) in args.named {
+            if seen.contains(&name) {
+                ctx.diagnose(DiagnosticKind::IgnoredArgument, format!("{}(): argument {} supplied more than once", self.callee, name));
+            }
+            seen.push(name.clone());
+            if self.accepts(&name) {
+                resolved.values.insert(name, value);
+            } else if name.starts_with('$') {
+                resolved.specials.retain(|(n, _)| *n != name);
+                resolved.specials.push((name, value));
+            } else {
+                ctx.diagnose(DiagnosticKind::IgnoredArgument, format!("{}(): variable {} not specified as parameter", self.callee, name));
+            }
+        }
+        resolved
+    }
+
+    /// Evaluate the arguments of a call in the current scope and bind
+    /// them, as [`CallArguments::evaluate`] then [`Self::resolve`].
+    ///
+    /// ## Errors
+    ///
+    /// The first error from an argument expression
+    pub fn evaluate(&self, ctx: &mut EvalContext, args: &[Argument]) -> Result<ResolvedArguments, EvalError> {
+        let call_args = CallArguments::evaluate(ctx, args)?;
+        Ok(self.resolve(ctx, call_args))
+    }
+
+    /// Check `name` is one of the parameters.
+    fn accepts(&self, name: &str) -> bool {
+        self.positional.contains(&name) || self.named.contains(&name)
+    }
+}
+
+// =============================================================================
+// USER PARAMETERS
+// =============================================================================
+
+/// Bind the arguments of a user function or module call in the current
+/// scope.
+///
+/// Each parameter gets its argument, else its default (evaluated in the
+/// current scope, so it can use earlier parameters), else `undef`. Special
+/// variables passed by name are defined after the parameters.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context, with the callee's scope pushed
+/// - `callee`: Function or module name, for warnings
+/// - `params`: Declared parameters
+/// - `args`: Evaluated call arguments
+///
+/// ## Errors
+///
+/// The first error from a default value
+pub fn bind_parameters(
+    ctx: &mut EvalContext,
+    callee: &str,
+    params: &[Parameter],
+    args: CallArguments,
+) -> Result<(), EvalError> {
+    let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+    let mut resolved = ArgumentResolver::new(callee, &names).resolve(ctx, args);
+    for param in params {
+        let value = match (resolved.take(&param.name), &param.default) {
+            (Some(value), _) => value,
+            (None, Some(default)) => eval_expr(ctx, default)?,
+            (None, None) => Value::Undef,
+        };
+        ctx.scope.define(&param.name, value);
+    }
+    resolved.define_specials(ctx);
+    Ok(())
+}
+
+// =============================================================================
+// RESOLVED ARGUMENTS
+// =============================================================================
+
+/// Arguments bound to parameters by an [`ArgumentResolver`].
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedArguments {
+    /// Supplied parameters by name; missing ones are absent.
+    values: HashMap<String, Value>,
+    /// Special variables passed by name, in call order.
+    specials: Vec<(String, Value)>,
+}
+
+impl ResolvedArguments {
+    /// Value supplied for a parameter, treating `undef` as missing, as
+    /// builtins do.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name).filter(|v| !matches!(v, Value::Undef))
+    }
+
+    /// Value supplied for a parameter, `undef` included, for binding to
+    /// a user parameter.
+    pub fn take(&mut self, name: &str) -> Option<Value> {
+        self.values.remove(name)
+    }
+
+    /// Special variables passed to the call.
+    pub fn specials(&self) -> &[(String, Value)] {
+        &self.specials
+    }
+
+    /// Define the special variables in the current scope.
+    pub fn define_specials(&self, ctx: &mut EvalContext) {
+        for (name, value) in &self.specials {
+            ctx.scope.define(name, value.clone());
+        }
+    }
+
+    /// Run `f` with the special variables defined in a scope of their own,
+    /// so they do not outlive the call.
+    pub fn with_specials<T>(&self, ctx: &mut EvalContext, f: impl FnOnce(&mut EvalContext) -> T) -> T {
+        if self.specials.is_empty() {
+            return f(ctx);
+        }
+        ctx.scope.push();
+        self.define_specials(ctx);
+        let result = f(ctx);
+        ctx.scope.pop();
+        result
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Resolve `positional` and `named` values against `f(a, b)`.
+    fn resolve(positional: &[f64], named: &[(&str, Value)]) -> (ResolvedArguments, Vec<String>) {
+        let mut ctx = EvalContext::new();
+        let args = CallArguments {
+            positional: positional.iter().map(|n| Value::Number(*n)).collect(),
+            named: named.iter().map(|(n, v)| (n.to_string(), v.clone())).collect(),
+        };
+        let resolved = ArgumentResolver::new("f", &["a", "b"]).named(&["c"]).resolve(&mut ctx, args);
+        (resolved, ctx.warnings)
+    }
+
+    /// Test positional arguments fill in order and named ones override.
+    #[test]
+    fn test_positional_then_named() {
+        let (resolved, warnings) = resolve(&[1.0], &[("a", Value::Number(5.0)), ("c", Value::Number(3.0))]);
+        assert_eq!(resolved.get("a"), Some(&Value::Number(5.0)));
+        assert_eq!(resolved.get("b"), None);
+        assert_eq!(resolved.get("c"), Some(&Value::Number(3.0)));
+        assert!(warnings.is_empty());
+    }
+
+    /// Test dropped arguments warn and special variables are kept.
+    #[test]
+    fn test_dropped_arguments() {
+        let (resolved, warnings) = resolve(&[1.0, 2.0, 3.0], &[("d", Value::Number(4.0)), ("$fn", Value::Number(8.0))]);
+        assert_eq!(resolved.get("b"), Some(&Value::Number(2.0)));
+        assert_eq!(resolved.specials(), &[("$fn".to_string(), Value::Number(8.0))]);
+        assert_eq!(warnings, [
+            "f(): too many unnamed arguments supplied (3 given, 2 expected)",
+            "f(): variable d not specified as parameter",
+        ]);
+
+        let (resolved, warnings) = resolve(&[], &[("a", Value::Number(1.0)), ("a", Value::Number(2.0))]);
+        assert_eq!(resolved.get("a"), Some(&Value::Number(2.0)));
+        assert_eq!(warnings, ["f(): argument a supplied more than once"]);
+    }
+
+    /// Test user modules and functions bind by the same rules and special
+    /// variables stay inside the call.
+    #[test]
+    fn test_user_parameters() {
+        use crate::geometry::GeometryNode;
+
+        let source = "function f(a, b = 1) = a + b; \
+            module m(a, b = a * 2) { cube([a, b, $fn]); } \
+            m(b = 5, 1, c = 3, $fn = 4); sphere(f(1, 2, 3), $fn = 5);";
+        let result = crate::evaluate(source).unwrap();
+        let GeometryNode::Group { children } = &result.geometry else { panic!("expected group") };
+        assert!(matches!(children[0], GeometryNode::Cube { size: [1.0, 5.0, 4.0], .. }));
+        assert!(matches!(children[1], GeometryNode::Sphere { radius: 3.0, fn_: 5 }));
+        assert_eq!(result.warnings, [
+            "m(): variable c not specified as parameter",
+            "f(): too many unnamed arguments supplied (3 given, 2 expected)",
+        ]);
+    }
+
+    /// Test an explicit undef is supplied for user parameters but missing
+    /// for builtins.
+    #[test]
+    fn test_explicit_undef() {
+        let (mut resolved, _) = resolve(&[], &[("a", Value::Undef)]);
+        assert_eq!(resolved.get("a"), None);
+        assert_eq!(resolved.take("a"), Some(Value::Undef));
+        assert_eq!(resolved.take("b"), None);
+    }
+}
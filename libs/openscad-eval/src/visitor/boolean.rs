@@ -10,6 +10,14 @@
 //! - `hull()` - Convex hull of all children
 //! - `minkowski()` - Minkowski sum of children
 //!
+//! ## Groups as Operands
+//!
+//! A child that evaluates to a [`GeometryNode::Group`] (a `for` loop or a
+//! module call with several top-level statements) is one operand, so it
+//! is turned into a union with [`GeometryNode::into_union`]. Under
+//! `union()` its members are spliced in directly. `hull()` only needs the
+//! points, so its children stay groups.
+//!
 //! ## Example
 //!
 //! ```rust,ignore
@@ -17,6 +25,7 @@
 //! let hull = eval_hull(&mut ctx, &children)?;
 //! ```
 
+use crate::diagnostic::DiagnosticKind;
 use crate::error::EvalError;
 use crate::geometry::GeometryNode;
 use openscad_ast::Statement;
@@ -48,18 +57,25 @@ pub fn eval_union(
     ctx: &mut EvalContext,
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let child_nodes = flatten_children(ctx, children)?;
+    let child_nodes = flatten_children(ctx, children, None)?
+        .into_iter()
+        .flat_map(|node| match node {
+            group @ GeometryNode::Group { .. } => match group.into_union() {
+                GeometryNode::Union { children } => children,
+                other => vec![other],
+            },
+            other => vec![other],
+        })
+        .collect();
 
-    match child_nodes.len() {
-        0 => Ok(GeometryNode::Empty),
-        1 => Ok(child_nodes.into_iter().next().unwrap()),
-        _ => Ok(GeometryNode::Union { children: child_nodes }),
-    }
+    Ok(GeometryNode::union(child_nodes))
 }
 
 /// Evaluate difference() call.
 ///
-/// Subtracts all subsequent children from the first child.
+/// Subtracts all subsequent children from the first child. A subtrahend
+/// whose bounds are clear of the first child's is kept but warned about
+/// as [`DiagnosticKind::NoEffect`].
 ///
 /// ## Parameters
 ///
@@ -78,7 +94,8 @@ pub fn eval_difference(
     ctx: &mut EvalContext,
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let child_nodes = flatten_children(ctx, children)?;
+    let child_nodes = operands(ctx, "difference", children)?;
+    warn_clear_subtrahends(ctx, &child_nodes);
 
     if child_nodes.is_empty() {
         Ok(GeometryNode::Empty)
@@ -108,7 +125,7 @@ pub fn eval_intersection(
     ctx: &mut EvalContext,
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let child_nodes = flatten_children(ctx, children)?;
+    let child_nodes = operands(ctx, "intersection", children)?;
 
     if child_nodes.is_empty() {
         Ok(GeometryNode::Empty)
@@ -124,6 +141,8 @@ pub fn eval_intersection(
 /// Evaluate hull() call.
 ///
 /// Creates the convex hull (smallest convex shape) containing all children.
+/// The hull is 2D or 3D after its first child; children of the other
+/// dimension are dropped with a warning, as in OpenSCAD.
 ///
 /// ## Parameters
 ///
@@ -143,7 +162,22 @@ pub fn eval_hull(
     ctx: &mut EvalContext,
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let child_nodes = flatten_children(ctx, children)?;
+    let mut child_nodes = flatten_children(ctx, children, None)?;
+
+    // The first child decides between a 2D and a 3D hull
+    if let Some(dimension) = child_nodes.iter().find_map(GeometryNode::dimension) {
+        let before = child_nodes.len();
+        child_nodes.retain(|node| node.dimension().is_none_or(|d| d == dimension));
+        let ignored = before - child_nodes.len();
+        if ignored > 0 {
+            let other = if dimension == 2 { 3 } else { 2 };
+            ctx.warn(format!(
+                "Mixing 2D and 3D objects is not supported in hull(); ignoring {} {}D object(s)",
+                ignored,
+                other,
+            ));
+        }
+    }
 
     match child_nodes.len() {
         0 => Ok(GeometryNode::Empty),
@@ -175,7 +209,7 @@ pub fn eval_minkowski(
     ctx: &mut EvalContext,
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let child_nodes = flatten_children(ctx, children)?;
+    let child_nodes = operands(ctx, "minkowski", children)?;
 
     match child_nodes.len() {
         0 => Ok(GeometryNode::Empty),
@@ -197,6 +231,8 @@ pub fn eval_minkowski(
 ///
 /// - `ctx`: Evaluation context
 /// - `children`: Child statements to flatten
+/// - `operation`: Operation name to warn about empty children with, for
+///   operations where leaving one out changes the result
 ///
 /// ## Returns
 ///
@@ -204,35 +240,60 @@ pub fn eval_minkowski(
 fn flatten_children(
     ctx: &mut EvalContext,
     children: &[Statement],
+    operation: Option<&str>,
 ) -> Result<Vec<GeometryNode>, EvalError> {
     let mut result = Vec::new();
 
-    for stmt in children {
-        match stmt {
-            // Extract statements from blocks
-            Statement::Block { statements, .. } => {
-                for inner in statements {
-                    if let Some(node) = evaluate_statement(ctx, inner)? {
-                        if !node.is_empty() {
-                            result.push(node);
-                        }
-                    }
-                }
-            }
-            // Regular statements
-            _ => {
-                if let Some(node) = evaluate_statement(ctx, stmt)? {
-                    if !node.is_empty() {
-                        result.push(node);
-                    }
+    // Extract statements from blocks
+    let statements = children.iter().flat_map(|stmt| match stmt {
+        Statement::Block { statements, .. } => statements.as_slice(),
+        other => std::slice::from_ref(other),
+    });
+    for (index, stmt) in statements.enumerate() {
+        match evaluate_statement(ctx, stmt)? {
+            Some(GeometryNode::Empty) => {
+                if let Some(operation) = operation {
+                    ctx.diagnose(
+                        DiagnosticKind::EmptyGeometry,
+                        format!("{}(): child {} is empty and was left out", operation, index + 1),
+                    );
                 }
             }
+            Some(node) => result.push(node),
+            None => {}
         }
     }
 
     Ok(result)
 }
 
+/// Warn about subtrahends whose bounds are clear of the first operand's,
+/// as they remove nothing. Operands without known bounds are not checked.
+fn warn_clear_subtrahends(ctx: &mut EvalContext, operands: &[GeometryNode]) {
+    let Some(base) = operands.first().and_then(GeometryNode::bounds).filter(|b| !b.is_empty()) else { return };
+    for (index, operand) in operands.iter().enumerate().skip(1) {
+        let clear = operand.bounds().is_some_and(|b| !b.is_empty() && !b.overlaps(&base));
+        if clear {
+            ctx.diagnose(
+                DiagnosticKind::NoEffect,
+                format!("difference(): child {} does not overlap child 1 and removes nothing", index + 1),
+            );
+        }
+    }
+}
+
+/// Flatten children into CSG operands, merging groups into unions.
+fn operands(
+    ctx: &mut EvalContext,
+    operation: &str,
+    children: &[Statement],
+) -> Result<Vec<GeometryNode>, EvalError> {
+    Ok(flatten_children(ctx, children, Some(operation))?
+        .into_iter()
+        .map(GeometryNode::into_union)
+        .collect())
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -279,4 +340,87 @@ mod tests {
         let node = eval_minkowski(&mut ctx, &[]).unwrap();
         assert!(matches!(node, GeometryNode::Empty));
     }
+
+    /// Test loop-generated operands of difference() become unions.
+    #[test]
+    fn test_difference_loop_operands_are_unions() {
+        let result = crate::evaluate(
+            "difference() { for (i = [0, 1]) translate([i, 0, 0]) cube(2); for (i = [0 : 2]) sphere(i + 1); }",
+        ).unwrap();
+        let GeometryNode::Difference { children } = result.geometry else {
+            panic!("Expected Difference, got {:?}", result.geometry);
+        };
+        assert_eq!(children.len(), 2);
+        assert!(matches!(&children[0], GeometryNode::Union { children } if children.len() == 2));
+        assert!(matches!(&children[1], GeometryNode::Union { children } if children.len() == 3));
+    }
+
+    /// Test groups under union() are spliced into one flat union.
+    #[test]
+    fn test_union_splices_groups() {
+        let result = crate::evaluate(
+            "module pair() { cube(1); sphere(1); } union() { pair(); for (i = [0 : 2]) cube(i + 1); }",
+        ).unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Union { children } if children.len() == 5));
+    }
+
+    /// Test hull() drops children whose dimension differs from the first.
+    #[test]
+    fn test_hull_mixed_dimensions() {
+        let result = crate::evaluate("hull() { translate([2, 0]) circle(1); cube(1); square(1); }").unwrap();
+        let GeometryNode::Hull { children } = &result.geometry else {
+            panic!("Expected Hull, got {:?}", result.geometry);
+        };
+        assert_eq!(children.len(), 2);
+        assert!(children.iter().all(|c| c.dimension() == Some(2)));
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("ignoring 1 3D"), "{}", result.warnings[0]);
+
+        let result = crate::evaluate("hull() { cube(1); square(1); circle(1); }").unwrap();
+        assert!(result.warnings[0].contains("ignoring 2 2D"), "{}", result.warnings[0]);
+    }
+
+    /// Test the top level and hull() children stay groups.
+    #[test]
+    fn test_groups_kept_outside_csg() {
+        let result = crate::evaluate("cube(1); sphere(1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Group { .. }));
+        let result = crate::evaluate("hull() { for (i = [0, 1]) cube(i + 1); sphere(1); }").unwrap();
+        let GeometryNode::Hull { children } = result.geometry else {
+            panic!("Expected Hull, got {:?}", result.geometry);
+        };
+        assert!(matches!(&children[0], GeometryNode::Group { .. }));
+    }
+
+    /// Test empty operands of difference() warn, and of union() do not.
+    #[test]
+    fn test_empty_operands_warn() {
+        let result = crate::evaluate("difference() { cube(2); union() {} cube(1); }").unwrap();
+        let GeometryNode::Difference { children } = &result.geometry else {
+            panic!("Expected Difference, got {:?}", result.geometry);
+        };
+        assert_eq!(children.len(), 2);
+        assert_eq!(result.warnings, ["difference(): child 2 is empty and was left out"]);
+        assert_eq!(result.diagnostics[0].kind, DiagnosticKind::EmptyGeometry);
+        assert_eq!(result.diagnostics[0].span.unwrap().start.column, 0);
+
+        let result = crate::evaluate("union() { cube(2); union() {} }").unwrap();
+        assert!(result.diagnostics.is_empty());
+    }
+
+    /// Test subtrahends clear of the first child of difference() warn.
+    #[test]
+    fn test_clear_subtrahend_warns() {
+        let result = crate::evaluate("difference() { cube(10); translate([20, 0, 0]) sphere(2); }").unwrap();
+        assert_eq!(result.warnings, ["difference(): child 2 does not overlap child 1 and removes nothing"]);
+        assert_eq!(result.diagnostics[0].kind, DiagnosticKind::NoEffect);
+
+        // Overlapping, merely touching faces, and unknown bounds
+        let result = crate::evaluate("difference() { cube(10); translate([5, 5, 5]) sphere(2); }").unwrap();
+        assert!(result.diagnostics.is_empty());
+        let result = crate::evaluate("difference() { cube(10); translate([10, 0, 0]) cube(1); }").unwrap();
+        assert_eq!(result.diagnostics.len(), 1);
+        let result = crate::evaluate("difference() { import(\"a.stl\"); translate([99, 0, 0]) cube(1); }").unwrap();
+        assert!(result.diagnostics.iter().all(|d| d.kind != DiagnosticKind::NoEffect));
+    }
 }
@@ -0,0 +1,584 @@
+//! # Library Compatibility Shims
+//!
+//! Native stand-ins for the most used helpers of popular OpenSCAD
+//! libraries, for designs whose real libraries cannot be loaded.
+//!
+//! ## Shimmed Symbols
+//!
+//! - **BOSL2**: `cuboid`, `cyl`, `xcyl`, `ycyl`, `zcyl`, `up`, `down`,
+//!   `left`, `right`, `fwd`, `back`, `move`, `xrot`, `yrot`, `zrot`,
+//!   `position`, `attach` and the anchor constants (`CENTER`, `TOP`, ...)
+//! - **MCAD**: `roundedBox`, `regular_polygon` and the unit constants
+//!   (`mm`, `cm`, `inch`)
+//!
+//! Shims only apply when the name is not already defined, so real library
+//! definitions (registered bundles or user code) always win. Every shim
+//! module that is actually called is recorded in the context and reported
+//! through `EvaluatedAst::shimmed`.
+//!
+//! ## Attachments
+//!
+//! `cuboid` and `cyl` expose their bounding size to their children as
+//! `$parent_size`; `position(anchor)` moves children to that anchor and
+//! `attach(anchor)` additionally tilts them so their +Z points away from
+//! the parent face.
+//!
+//! ## Example
+//!
+//! ```text
+//! cuboid([20, 10, 5], anchor=BOTTOM)
+//!     attach(TOP) cyl(l=10, r=2, anchor=BOTTOM);
+//! ```
+
+use crate::error::EvalError;
+use crate::geometry::GeometryNode;
+use crate::value::Value;
+use openscad_ast::{Argument, Statement};
+use serde::{Deserialize, Serialize};
+
+use super::context::{EvalContext, evaluate_statements};
+use super::arguments::{ArgumentResolver, ResolvedArguments};
+
+/// Special variable carrying the parent's bounding size to attached children.
+const PARENT_SIZE: &str = "$parent_size";
+
+// =============================================================================
+// SHIM LIBRARY
+// =============================================================================
+
+/// A library whose common helpers can be shimmed natively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ShimLibrary {
+    /// MCAD (`MCAD/boxes.scad`, `MCAD/regular_shapes.scad`, `MCAD/units.scad`).
+    Mcad,
+    /// BOSL2 (`BOSL2/std.scad`).
+    Bosl2,
+}
+
+impl ShimLibrary {
+    /// All shimmable libraries.
+    pub const ALL: [ShimLibrary; 2] = [ShimLibrary::Mcad, ShimLibrary::Bosl2];
+
+    /// Library name as used in `include`/`use` paths.
+    pub fn name(self) -> &'static str {
+        match self {
+            ShimLibrary::Mcad => "MCAD",
+            ShimLibrary::Bosl2 => "BOSL2",
+        }
+    }
+
+    /// Modules provided by this shim.
+    pub fn modules(self) -> &'static [&'static str] {
+        match self {
+            ShimLibrary::Mcad => &["roundedBox", "regular_polygon"],
+            ShimLibrary::Bosl2 => &[
+                "cuboid", "cyl", "xcyl", "ycyl", "zcyl",
+                "up", "down", "left", "right", "fwd", "back", "move",
+                "xrot", "yrot", "zrot",
+                "position", "attach",
+            ],
+        }
+    }
+
+    /// Constants provided by this shim.
+    pub fn constants(self) -> Vec<(&'static str, Value)> {
+        let vec3 = |x: f64, y: f64, z: f64| {
+            Value::List(vec![Value::Number(x), Value::Number(y), Value::Number(z)])
+        };
+        match self {
+            ShimLibrary::Mcad => vec![
+                ("mm", Value::Number(1.0)),
+                ("cm", Value::Number(10.0)),
+                ("inch", Value::Number(25.4)),
+            ],
+            ShimLibrary::Bosl2 => vec![
+                ("CENTER", vec3(0.0, 0.0, 0.0)),
+                ("TOP", vec3(0.0, 0.0, 1.0)),
+                ("UP", vec3(0.0, 0.0, 1.0)),
+                ("BOTTOM", vec3(0.0, 0.0, -1.0)),
+                ("BOT", vec3(0.0, 0.0, -1.0)),
+                ("DOWN", vec3(0.0, 0.0, -1.0)),
+                ("LEFT", vec3(-1.0, 0.0, 0.0)),
+                ("RIGHT", vec3(1.0, 0.0, 0.0)),
+                ("FRONT", vec3(0.0, -1.0, 0.0)),
+                ("FWD", vec3(0.0, -1.0, 0.0)),
+                ("BACK", vec3(0.0, 1.0, 0.0)),
+            ],
+        }
+    }
+
+    /// Check whether this shim provides the named module.
+    pub fn provides_module(self, name: &str) -> bool {
+        self.modules().contains(&name)
+    }
+}
+
+// =============================================================================
+// INSTALLATION
+// =============================================================================
+
+/// Enable shims on a context.
+///
+/// Constants are defined only where the name is still unbound, so values
+/// from real libraries registered earlier are kept.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `shims`: Libraries to shim
+pub fn install_shims(ctx: &mut EvalContext, shims: &[ShimLibrary]) {
+    for &shim in shims {
+        for (name, value) in shim.constants() {
+            if ctx.scope.get(name).is_none() {
+                ctx.scope.define(name, value);
+            }
+        }
+        if !ctx.shims.contains(&shim) {
+            ctx.shims.push(shim);
+        }
+    }
+}
+
+/// Evaluate a module call through the enabled shims.
+///
+/// Called after user-defined modules have been checked, so a real
+/// definition always takes precedence.
+///
+/// ## Returns
+///
+/// `Ok(None)` if no enabled shim provides `name`.
+pub fn eval_shim_module(
+    ctx: &mut EvalContext,
+    name: &str,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<Option<GeometryNode>, EvalError> {
+    let Some(shim) = ctx.shims.iter().copied().find(|s| s.provides_module(name)) else {
+        return Ok(None);
+    };
+    ctx.shimmed.insert(format!("{}::{}", shim.name(), name));
+
+    let (positional, named) = shim_parameters(name);
+    let args = ArgumentResolver::new(name, positional).named(named).evaluate(ctx, args)?;
+    args.with_specials(ctx, |ctx| eval_shim(ctx, name, &args, children))
+}
+
+/// Build the node for one shim module call.
+fn eval_shim(
+    ctx: &mut EvalContext,
+    name: &str,
+    args: &ResolvedArguments,
+    children: &[Statement],
+) -> Result<Option<GeometryNode>, EvalError> {
+    let node = match name {
+        // BOSL2 shapes
+        "cuboid" => eval_cuboid(ctx, args, children)?,
+        "cyl" | "zcyl" => eval_cyl(ctx, args, children, [0.0, 0.0, 0.0])?,
+        "xcyl" => eval_cyl(ctx, args, children, [0.0, 90.0, 0.0])?,
+        "ycyl" => eval_cyl(ctx, args, children, [90.0, 0.0, 0.0])?,
+
+        // BOSL2 movement
+        "up" => translate(ctx, [0.0, 0.0, number(args, "z", 0.0)?], children)?,
+        "down" => translate(ctx, [0.0, 0.0, -number(args, "z", 0.0)?], children)?,
+        "right" => translate(ctx, [number(args, "x", 0.0)?, 0.0, 0.0], children)?,
+        "left" => translate(ctx, [-number(args, "x", 0.0)?, 0.0, 0.0], children)?,
+        "back" => translate(ctx, [0.0, number(args, "y", 0.0)?, 0.0], children)?,
+        "fwd" => translate(ctx, [0.0, -number(args, "y", 0.0)?, 0.0], children)?,
+        "move" => translate(ctx, vec3(args, "v", [0.0; 3])?, children)?,
+        "xrot" => rotate(ctx, [number(args, "a", 0.0)?, 0.0, 0.0], children)?,
+        "yrot" => rotate(ctx, [0.0, number(args, "a", 0.0)?, 0.0], children)?,
+        "zrot" => rotate(ctx, [0.0, 0.0, number(args, "a", 0.0)?], children)?,
+
+        // BOSL2 attachments
+        "position" => eval_position(ctx, args, children, false)?,
+        "attach" => eval_position(ctx, args, children, true)?,
+
+        // MCAD
+        "roundedBox" => eval_rounded_box(ctx, args)?,
+        "regular_polygon" => GeometryNode::Circle {
+            radius: number(args, "radius", 1.0)?,
+            fn_: number(args, "sides", 3.0)?.max(3.0) as u32,
+        },
+
+        _ => return Ok(None),
+    };
+    Ok(Some(node))
+}
+
+// =============================================================================
+// ARGUMENTS
+// =============================================================================
+
+/// Positional and named-only parameters of a shim module.
+fn shim_parameters(name: &str) -> (&'static [&'static str], &'static [&'static str]) {
+    match name {
+        "cuboid" => (&["size"], &["anchor", "rounding", "chamfer"]),
+        "cyl" | "xcyl" | "ycyl" | "zcyl" => (
+            &["l", "r"],
+            &["h", "height", "length", "d", "r1", "r2", "d1", "d2", "anchor"],
+        ),
+        "up" | "down" => (&["z"], &[]),
+        "right" | "left" => (&["x"], &[]),
+        "back" | "fwd" => (&["y"], &[]),
+        "move" => (&["v"], &[]),
+        "xrot" | "yrot" | "zrot" => (&["a"], &[]),
+        "position" | "attach" => (&["at"], &[]),
+        "roundedBox" => (&["size", "radius", "sidesonly"], &[]),
+        "regular_polygon" => (&["sides", "radius"], &[]),
+        _ => (&[], &[]),
+    }
+}
+
+/// Numeric argument with a default.
+fn number(args: &ResolvedArguments, name: &str, default: f64) -> Result<f64, EvalError> {
+    args.get(name).map(Value::as_number).transpose().map(|v| v.unwrap_or(default))
+}
+
+/// Vector argument with a default.
+fn vec3(args: &ResolvedArguments, name: &str, default: [f64; 3]) -> Result<[f64; 3], EvalError> {
+    args.get(name).map(Value::as_vec3).transpose().map(|v| v.unwrap_or(default))
+}
+
+/// BOSL2 `anchor` argument, defaulting to `CENTER`.
+fn anchor(args: &ResolvedArguments) -> Result<[f64; 3], EvalError> {
+    vec3(args, "anchor", [0.0; 3])
+}
+
+// =============================================================================
+// BOSL2 SHAPES
+// =============================================================================
+
+/// Evaluate BOSL2 `cuboid(size, anchor=CENTER)`.
+///
+/// `rounding` and `chamfer` are accepted but ignored with a warning.
+fn eval_cuboid(
+    ctx: &mut EvalContext,
+    args: &ResolvedArguments,
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let size = vec3(args, "size", [1.0; 3])?;
+    if args.get("rounding").is_some() || args.get("chamfer").is_some() {
+        ctx.warn("cuboid shim ignores rounding/chamfer".to_string());
+    }
+    let body = GeometryNode::Cube { size, center: true };
+    anchored(ctx, body, size, anchor(args)?, children)
+}
+
+/// Evaluate BOSL2 `cyl(l, r|d, r1|d1, r2|d2, anchor=CENTER)` and its
+/// axis-aligned variants.
+fn eval_cyl(
+    ctx: &mut EvalContext,
+    args: &ResolvedArguments,
+    children: &[Statement],
+    orient: [f64; 3],
+) -> Result<GeometryNode, EvalError> {
+    let height = ["h", "height", "length", "l"].iter()
+        .find_map(|n| args.get(n))
+        .map(Value::as_number)
+        .transpose()?
+        .unwrap_or(1.0);
+
+    let radius = match (args.get("r"), args.get("d")) {
+        (Some(r), _) => r.as_number()?,
+        (None, Some(d)) => d.as_number()? / 2.0,
+        (None, None) => 1.0,
+    };
+    let end_radius = |r: &str, d: &str| -> Result<f64, EvalError> {
+        match (args.get(r), args.get(d)) {
+            (Some(r), _) => r.as_number(),
+            (None, Some(d)) => Ok(d.as_number()? / 2.0),
+            (None, None) => Ok(radius),
+        }
+    };
+    let radius1 = end_radius("r1", "d1")?;
+    let radius2 = end_radius("r2", "d2")?;
+
+    let fn_ = ctx.calculate_fragments(radius1.max(radius2));
+    let body = GeometryNode::Cylinder { height, radius1, radius2, center: true, fn_ };
+    let diameter = 2.0 * radius1.max(radius2);
+    let node = anchored(ctx, body, [diameter, diameter, height], anchor(args)?, children)?;
+
+    Ok(if orient == [0.0; 3] {
+        node
+    } else {
+        GeometryNode::Rotate { angles: orient, child: Box::new(node) }
+    })
+}
+
+/// Place a centered shape by its anchor and evaluate attached children.
+///
+/// Children see the shape's bounding size as `$parent_size`.
+fn anchored(
+    ctx: &mut EvalContext,
+    body: GeometryNode,
+    size: [f64; 3],
+    anchor: [f64; 3],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let node = if children.is_empty() {
+        body
+    } else {
+        ctx.scope.push();
+        ctx.scope.define(PARENT_SIZE, Value::List(size.iter().map(|&s| Value::Number(s)).collect()));
+        let attached = evaluate_statements(ctx, children);
+        ctx.scope.pop();
+        GeometryNode::Group { children: vec![body, attached?] }
+    };
+
+    let offset = [
+        -anchor[0] * size[0] / 2.0,
+        -anchor[1] * size[1] / 2.0,
+        -anchor[2] * size[2] / 2.0,
+    ];
+    Ok(if offset == [0.0; 3] {
+        node
+    } else {
+        GeometryNode::Translate { offset, child: Box::new(node) }
+    })
+}
+
+// =============================================================================
+// BOSL2 TRANSFORMS AND ATTACHMENTS
+// =============================================================================
+
+/// Translate evaluated children.
+fn translate(
+    ctx: &mut EvalContext,
+    offset: [f64; 3],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let child = evaluate_statements(ctx, children)?;
+    Ok(GeometryNode::Translate { offset, child: Box::new(child) })
+}
+
+/// Rotate evaluated children by Euler angles.
+fn rotate(
+    ctx: &mut EvalContext,
+    angles: [f64; 3],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let child = evaluate_statements(ctx, children)?;
+    Ok(GeometryNode::Rotate { angles, child: Box::new(child) })
+}
+
+/// Evaluate `position(anchor)` or, with `orient`, `attach(anchor)`.
+fn eval_position(
+    ctx: &mut EvalContext,
+    args: &ResolvedArguments,
+    children: &[Statement],
+    orient: bool,
+) -> Result<GeometryNode, EvalError> {
+    let anchor = vec3(args, "at", [0.0; 3])?;
+    let size = match ctx.scope.get(PARENT_SIZE).cloned() {
+        Some(size) => size.as_vec3()?,
+        None => {
+            ctx.warn("position/attach used outside of a shimmed parent".to_string());
+            [0.0; 3]
+        }
+    };
+    let offset = [
+        anchor[0] * size[0] / 2.0,
+        anchor[1] * size[1] / 2.0,
+        anchor[2] * size[2] / 2.0,
+    ];
+
+    let mut child = evaluate_statements(ctx, children)?;
+    if orient {
+        let angles = direction_angles(anchor);
+        if angles != [0.0; 3] {
+            child = GeometryNode::Rotate { angles, child: Box::new(child) };
+        }
+    }
+    Ok(GeometryNode::Translate { offset, child: Box::new(child) })
+}
+
+/// Euler angles (degrees) that rotate +Z onto `dir`.
+///
+/// `rotate([0, b, c])` maps +Z to
+/// `[sin(b)cos(c), sin(b)sin(c), cos(b)]`.
+fn direction_angles(dir: [f64; 3]) -> [f64; 3] {
+    let len = (dir[0] * dir[0] + dir[1] * dir[1] + dir[2] * dir[2]).sqrt();
+    if len == 0.0 {
+        return [0.0; 3];
+    }
+    let tilt = (dir[2] / len).clamp(-1.0, 1.0).acos().to_degrees();
+    let spin = if dir[0] == 0.0 && dir[1] == 0.0 {
+        0.0
+    } else {
+        dir[1].atan2(dir[0]).to_degrees()
+    };
+    [0.0, tilt, spin]
+}
+
+// =============================================================================
+// MCAD
+// =============================================================================
+
+/// Evaluate MCAD `roundedBox(size, radius, sidesonly)`.
+///
+/// Built as the hull of corner spheres, or of corner cylinders when only
+/// the vertical edges are rounded.
+fn eval_rounded_box(ctx: &mut EvalContext, args: &ResolvedArguments) -> Result<GeometryNode, EvalError> {
+    let size = vec3(args, "size", [1.0; 3])?;
+    let radius = number(args, "radius", 1.0)?;
+    let sides_only = args.get("sidesonly").is_some_and(Value::as_boolean);
+
+    let fn_ = ctx.calculate_fragments(radius);
+    let dx = size[0] / 2.0 - radius;
+    let dy = size[1] / 2.0 - radius;
+    let dz = size[2] / 2.0 - radius;
+
+    let mut corners = Vec::new();
+    for x in [-dx, dx] {
+        for y in [-dy, dy] {
+            if sides_only {
+                let post = GeometryNode::Cylinder {
+                    height: size[2],
+                    radius1: radius,
+                    radius2: radius,
+                    center: true,
+                    fn_,
+                };
+                corners.push(GeometryNode::Translate { offset: [x, y, 0.0], child: Box::new(post) });
+            } else {
+                for z in [-dz, dz] {
+                    let ball = GeometryNode::Sphere { radius, fn_ };
+                    corners.push(GeometryNode::Translate { offset: [x, y, z], child: Box::new(ball) });
+                }
+            }
+        }
+    }
+    Ok(GeometryNode::Hull { children: corners })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::evaluate_ast_with_shims;
+
+    fn eval(source: &str, shims: &[ShimLibrary]) -> crate::EvaluatedAst {
+        let ast = openscad_ast::parse(source).unwrap();
+        evaluate_ast_with_shims(&ast, &[], shims).unwrap()
+    }
+
+    /// Test shims are inactive unless enabled.
+    #[test]
+    fn test_disabled_by_default() {
+        let result = eval("cuboid(10);", &[]);
+        assert!(result.geometry.is_empty());
+        assert!(result.shimmed.is_empty());
+    }
+
+    /// Test cuboid is centered and shifted by its anchor.
+    #[test]
+    fn test_cuboid_anchor() {
+        let result = eval("cuboid([2, 4, 6], anchor=BOTTOM);", &[ShimLibrary::Bosl2]);
+        match result.geometry {
+            GeometryNode::Translate { offset, child } => {
+                assert_eq!(offset, [0.0, 0.0, 3.0]);
+                assert!(matches!(*child, GeometryNode::Cube { center: true, .. }));
+            }
+            other => panic!("Expected Translate, got {:?}", other),
+        }
+        assert_eq!(result.shimmed, vec!["BOSL2::cuboid".to_string()]);
+    }
+
+    /// Test cyl accepts BOSL2 length/diameter names.
+    #[test]
+    fn test_cyl() {
+        let result = eval("cyl(l=10, d=4);", &[ShimLibrary::Bosl2]);
+        match result.geometry {
+            GeometryNode::Cylinder { height, radius1, radius2, center, .. } => {
+                assert_eq!(height, 10.0);
+                assert_eq!((radius1, radius2), (2.0, 2.0));
+                assert!(center);
+            }
+            other => panic!("Expected Cylinder, got {:?}", other),
+        }
+    }
+
+    /// Test shim arguments bind by name or position, and unknown ones warn.
+    #[test]
+    fn test_arguments() {
+        let result = eval("cyl(r=2, 10, anchor=TOP, $fn=6, twist=1);", &[ShimLibrary::Bosl2]);
+        match result.geometry {
+            GeometryNode::Translate { offset, child } => {
+                assert_eq!(offset, [0.0, 0.0, -5.0]);
+                assert!(matches!(*child, GeometryNode::Cylinder { height: 10.0, radius1: 2.0, fn_: 6, .. }));
+            }
+            other => panic!("Expected Translate, got {:?}", other),
+        }
+        assert_eq!(result.warnings, ["cyl(): variable twist not specified as parameter"]);
+
+        let result = eval("regular_polygon(radius=4, 6);", &[ShimLibrary::Mcad]);
+        assert!(matches!(result.geometry, GeometryNode::Circle { radius: 4.0, fn_: 6 }));
+    }
+
+    /// Test attach moves children to the parent face and orients them.
+    #[test]
+    fn test_attach() {
+        let result = eval("cuboid(10) attach(RIGHT) cyl(l=2, r=1);", &[ShimLibrary::Bosl2]);
+        let GeometryNode::Group { children } = result.geometry else {
+            panic!("Expected Group");
+        };
+        match &children[1] {
+            GeometryNode::Translate { offset, child } => {
+                assert_eq!(*offset, [5.0, 0.0, 0.0]);
+                match child.as_ref() {
+                    GeometryNode::Rotate { angles, .. } => assert_eq!(*angles, [0.0, 90.0, 0.0]),
+                    other => panic!("Expected Rotate, got {:?}", other),
+                }
+            }
+            other => panic!("Expected Translate, got {:?}", other),
+        }
+        assert_eq!(
+            result.shimmed,
+            vec!["BOSL2::attach".to_string(), "BOSL2::cuboid".to_string(), "BOSL2::cyl".to_string()]
+        );
+    }
+
+    /// Test a real definition takes precedence over the shim.
+    #[test]
+    fn test_user_definition_wins() {
+        let result = eval("module cuboid(s) { sphere(s); } cuboid(3);", &[ShimLibrary::Bosl2]);
+        assert!(matches!(result.geometry, GeometryNode::Sphere { .. }));
+        assert!(result.shimmed.is_empty());
+    }
+
+    /// Test MCAD roundedBox builds a hull of corner spheres.
+    #[test]
+    fn test_rounded_box() {
+        let result = eval("roundedBox([10, 10, 10], 1, false);", &[ShimLibrary::Mcad]);
+        match result.geometry {
+            GeometryNode::Hull { children } => assert_eq!(children.len(), 8),
+            other => panic!("Expected Hull, got {:?}", other),
+        }
+        let result = eval("roundedBox(size=[10, 10, 2], radius=1, sidesonly=true);", &[ShimLibrary::Mcad]);
+        match result.geometry {
+            GeometryNode::Hull { children } => assert_eq!(children.len(), 4),
+            other => panic!("Expected Hull, got {:?}", other),
+        }
+    }
+
+    /// Test shim constants do not override existing bindings.
+    #[test]
+    fn test_constants() {
+        let mut ctx = EvalContext::new();
+        ctx.scope.define("inch", Value::Number(1.0));
+        install_shims(&mut ctx, &[ShimLibrary::Mcad]);
+        assert_eq!(ctx.scope.get("inch"), Some(&Value::Number(1.0)));
+        assert_eq!(ctx.scope.get("cm"), Some(&Value::Number(10.0)));
+    }
+
+    /// Test the direction angles for axis anchors.
+    #[test]
+    fn test_direction_angles() {
+        assert_eq!(direction_angles([0.0, 0.0, 1.0]), [0.0, 0.0, 0.0]);
+        assert_eq!(direction_angles([0.0, 0.0, -1.0]), [0.0, 180.0, 0.0]);
+        assert_eq!(direction_angles([0.0, 1.0, 0.0]), [0.0, 90.0, 90.0]);
+    }
+}
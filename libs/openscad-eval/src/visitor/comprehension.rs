@@ -0,0 +1,192 @@
+//! # List Comprehension Evaluation
+//!
+//! Evaluates list comprehensions to list values.
+//!
+//! ## Semantics
+//!
+//! - Plain expressions contribute one value
+//! - `for` contributes one body result per iteration; multiple assignments
+//!   nest, the first one outermost
+//! - `if` contributes its branch, or nothing when false without `else`
+//! - `each` splices a list, range or string into the result
+//! - `let` binds variables for its body only
+//!
+//! ## Example
+//!
+//! ```text
+//! [for (i = [0:4]) if (i % 2 == 0) i * i]   // [0, 4, 16]
+//! [for (x = [0:1], y = [0:1]) [x, y]]       // [[0,0], [0,1], [1,0], [1,1]]
+//! [0, each [1, 2], 3]                       // [0, 1, 2, 3]
+//! ```
+
+use crate::error::EvalError;
+use crate::value::Value;
+use openscad_ast::{ComprehensionElement, Expression};
+
+use super::context::EvalContext;
+use super::expressions::{bind_assignments, eval_expr};
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Evaluate a list comprehension.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `elements`: Comprehension elements, in source order
+///
+/// ## Returns
+///
+/// `Value::List` with the generated values
+pub fn eval_list_comprehension(
+    ctx: &mut EvalContext,
+    elements: &[ComprehensionElement],
+) -> Result<Value, EvalError> {
+    let mut out = Vec::new();
+    for element in elements {
+        eval_element(ctx, element, &mut out)?;
+    }
+    Ok(Value::List(out))
+}
+
+// =============================================================================
+// ELEMENTS
+// =============================================================================
+
+/// Evaluate one element, appending its values to `out`.
+fn eval_element(
+    ctx: &mut EvalContext,
+    element: &ComprehensionElement,
+    out: &mut Vec<Value>,
+) -> Result<(), EvalError> {
+    match element {
+        ComprehensionElement::Expr(expr) => {
+            out.push(eval_expr(ctx, expr)?);
+            Ok(())
+        }
+        ComprehensionElement::For { assignments, body } => {
+            eval_for(ctx, assignments, body, out)
+        }
+        ComprehensionElement::If { condition, then_elem, else_elem } => {
+            if eval_expr(ctx, condition)?.as_boolean() {
+                eval_element(ctx, then_elem, out)
+            } else if let Some(else_elem) = else_elem {
+                eval_element(ctx, else_elem, out)
+            } else {
+                Ok(())
+            }
+        }
+        ComprehensionElement::Each(body) => {
+            let mut inner = Vec::new();
+            eval_element(ctx, body, &mut inner)?;
+            for value in inner {
+                ctx.count_iterations(value.iteration_count())?;
+                out.extend(value.into_iteration_values());
+            }
+            Ok(())
+        }
+        ComprehensionElement::Let { assignments, body } => {
+            ctx.scope.push();
+            let result = bind_assignments(ctx, assignments)
+                .and_then(|_| eval_element(ctx, body, out));
+            ctx.scope.pop();
+            result
+        }
+    }
+}
+
+/// Evaluate a `for` generator.
+///
+/// Each assignment opens a nested loop, so
+/// `for (x = a, y = b)` behaves like `for (x = a) for (y = b)`.
+fn eval_for(
+    ctx: &mut EvalContext,
+    assignments: &[(String, Expression)],
+    body: &ComprehensionElement,
+    out: &mut Vec<Value>,
+) -> Result<(), EvalError> {
+    let Some(((name, iterable), rest)) = assignments.split_first() else {
+        return eval_element(ctx, body, out);
+    };
+
+    let iterable = eval_expr(ctx, iterable)?;
+    ctx.count_iterations(iterable.iteration_count())?;
+    for value in iterable.into_iteration_values() {
+        ctx.scope.push();
+        ctx.scope.define(name, value);
+        let result = eval_for(ctx, rest, body, out);
+        ctx.scope.pop();
+        result?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Evaluate `source` as the value of `x` and return it.
+    fn eval_value(source: &str) -> Value {
+        let ast = openscad_ast::parse(&format!("x = {};", source)).unwrap();
+        let mut ctx = EvalContext::new();
+        crate::visitor::evaluate_statements(&mut ctx, &ast.statements).unwrap();
+        ctx.scope.get("x").cloned().unwrap()
+    }
+
+    fn nums(values: &[f64]) -> Value {
+        Value::List(values.iter().map(|n| Value::Number(*n)).collect())
+    }
+
+    /// Test a simple generator.
+    #[test]
+    fn test_for() {
+        assert_eq!(eval_value("[for (i = [0:3]) i * i]"), nums(&[0.0, 1.0, 4.0, 9.0]));
+    }
+
+    /// Test filters with and without else.
+    #[test]
+    fn test_if_filter() {
+        assert_eq!(eval_value("[for (i = [0:4]) if (i % 2 == 0) i]"), nums(&[0.0, 2.0, 4.0]));
+        assert_eq!(
+            eval_value("[for (i = [-1, 2]) if (i > 0) i else -i]"),
+            nums(&[1.0, 2.0])
+        );
+    }
+
+    /// Test multiple assignments iterate as nested loops.
+    #[test]
+    fn test_nested_assignments() {
+        let value = eval_value("[for (x = [0:1], y = [5, 6]) [x, y]]");
+        assert_eq!(
+            value,
+            Value::List(vec![nums(&[0.0, 5.0]), nums(&[0.0, 6.0]), nums(&[1.0, 5.0]), nums(&[1.0, 6.0])])
+        );
+    }
+
+    /// Test each flattens one level and mixes with plain elements.
+    #[test]
+    fn test_each() {
+        assert_eq!(eval_value("[0, each [1, 2], each [3:4]]"), nums(&[0.0, 1.0, 2.0, 3.0, 4.0]));
+        assert_eq!(
+            eval_value("[for (p = [[1, 2], [3]]) each p]"),
+            nums(&[1.0, 2.0, 3.0])
+        );
+    }
+
+    /// Test let bindings inside a comprehension do not leak.
+    #[test]
+    fn test_let() {
+        let ast = openscad_ast::parse("x = [for (i = [1:2]) let (j = i * 10) j];").unwrap();
+        let mut ctx = EvalContext::new();
+        crate::visitor::evaluate_statements(&mut ctx, &ast.statements).unwrap();
+        assert_eq!(ctx.scope.get("x"), Some(&nums(&[10.0, 20.0])));
+        assert!(ctx.scope.get("j").is_none());
+        assert!(ctx.scope.get("i").is_none());
+    }
+}
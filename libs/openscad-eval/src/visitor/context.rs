@@ -18,19 +18,34 @@
 //! ```
 
 use crate::error::EvalError;
+use crate::diagnostic::{Diagnostic, DiagnosticKind};
+use crate::files::FileProvider;
 use crate::geometry::GeometryNode;
+use crate::library::LibraryBundle;
+use crate::limits::{now_ms, Construct, LimitKind, Limits, RecursionLimit, DEFAULT_MAX_RECURSION_DEPTH, MAX_TAIL_CALLS};
+use crate::progress::CancellationToken;
+use crate::random::Rng;
+use crate::message::{LogEntry, LogSink, Message, MessageKind};
+use crate::options::Overrides;
 use crate::scope::Scope;
-use crate::value::Value;
-use openscad_ast::{Statement, Expression, Argument};
+use openscad_ast::{Statement, Expression, Argument, Span};
 use openscad_ast::ast::Parameter;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
 
+use super::arguments::{bind_parameters, CallArguments};
 use super::expressions::eval_expr;
-use super::primitives::{eval_cube, eval_sphere, eval_cylinder, eval_polyhedron, eval_circle, eval_square, eval_polygon};
+use super::primitives::{eval_cube, eval_sphere, eval_cylinder, eval_polyhedron, eval_circle, eval_square, eval_polygon, eval_text, eval_import, eval_surface};
 use super::boolean::{eval_union, eval_difference, eval_intersection, eval_hull, eval_minkowski};
-use super::transforms::{eval_translate, eval_rotate, eval_scale, eval_mirror, eval_color};
+use super::transforms::{eval_translate, eval_rotate, eval_scale, eval_resize, eval_mirror, eval_multmatrix, eval_color};
 use super::extrusions::{eval_linear_extrude, eval_rotate_extrude};
 use super::ops_2d::{eval_offset, eval_projection};
+use super::compat::{ShimLibrary, eval_shim_module};
+use super::control_flow::{eval_for, eval_if, eval_intersection_for, eval_let};
+use super::debug::{eval_assert, eval_echo, eval_echo_dim};
+use super::extensions::{eval_prism, eval_quality, eval_refine, eval_rounded_cube, eval_smooth, eval_torus, eval_warp_bend, eval_warp_taper, eval_warp_twist};
+use super::includes::{eval_include, eval_use};
+use super::modifiers::eval_modified;
 
 // =============================================================================
 // USER-DEFINED FUNCTIONS
@@ -90,13 +105,27 @@ pub struct ModuleDef {
 /// ## Fields
 ///
 /// - `warnings`: Collected warnings during evaluation
+/// - `diagnostics`: The same warnings with their kind and location
+/// - `messages`: Console output from echo modules
 /// - `scope`: Variable scope for lexical scoping
 /// - `functions`: User-defined functions
 /// - `modules`: User-defined modules
 /// - `children_stack`: Stack of children for nested module calls
+/// - `shims`: Enabled library compatibility shims
+/// - `shimmed`: Shim modules actually called
+/// - `file_provider`: Source of `include`/`use` files
+/// - `file_stack`: Files currently being included or used
+/// - `quality`: Segment multiplier from enclosing `quality()` scopes
+/// - `root`: Geometry of the first `!` statement
+/// - `log`: Receives console output and warnings as they happen
+/// - `rng`: Generator of unseeded `rands()` calls
 pub struct EvalContext {
     /// Collected warnings (undefined variables, unknown modules, etc.).
     pub warnings: Vec<String>,
+    /// Collected warnings with their kind and location.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Console output from echo modules.
+    pub messages: Vec<Message>,
     /// Variable scope for lexical scoping.
     pub scope: Scope,
     /// User-defined functions.
@@ -106,6 +135,51 @@ pub struct EvalContext {
     /// Stack of children statements for nested module calls.
     /// Each level represents the children passed to the current module.
     pub children_stack: Vec<Vec<Statement>>,
+    /// Enabled library compatibility shims.
+    pub shims: Vec<ShimLibrary>,
+    /// Shim modules called during evaluation, as `LIBRARY::name`.
+    pub shimmed: BTreeSet<String>,
+    /// Values that replace top-level assignments (`-D name=value`).
+    pub overrides: Overrides,
+    /// Source of `include`/`use` files.
+    pub file_provider: Option<Arc<dyn FileProvider>>,
+    /// Resolved names of the files being evaluated, innermost last.
+    pub file_stack: Vec<String>,
+    /// Segment multiplier from enclosing `quality()` scopes.
+    pub quality: f64,
+    /// Budgets that abort a runaway evaluation.
+    pub limits: Limits,
+    /// Counts checked against the limits.
+    pub usage: Usage,
+    /// Statements being evaluated, innermost last, for limit reports.
+    pub constructs: Vec<Construct>,
+    /// User functions and modules being called, innermost last.
+    pub call_stack: Vec<String>,
+    /// Deepest allowed nesting of `call_stack`.
+    pub max_recursion_depth: usize,
+    /// Aborts evaluation once cancelled.
+    pub cancel: Option<CancellationToken>,
+    /// Geometry of the first `!` statement, which replaces the result.
+    pub root: Option<GeometryNode>,
+    /// Receives console output and warnings as they happen.
+    pub log: Option<Arc<dyn LogSink>>,
+    /// Generator of unseeded `rands()` calls.
+    pub rng: Rng,
+    /// Whether the extension primitives are available.
+    pub extensions: bool,
+    /// Whether module calls wrap their geometry in `Source` nodes.
+    pub sources: bool,
+}
+
+/// Running totals checked against [`Limits`].
+#[derive(Debug, Clone, Default)]
+pub struct Usage {
+    /// Loop iterations so far.
+    pub iterations: u64,
+    /// Geometry nodes produced by module calls so far.
+    pub nodes: u64,
+    /// [`now_ms`] when evaluation started.
+    pub started_ms: f64,
 }
 
 impl EvalContext {
@@ -120,10 +194,29 @@ impl EvalContext {
     pub fn new() -> Self {
         Self {
             warnings: Vec::new(),
+            diagnostics: Vec::new(),
+            messages: Vec::new(),
             scope: Scope::new(),
             functions: HashMap::new(),
             modules: HashMap::new(),
             children_stack: Vec::new(),
+            shims: Vec::new(),
+            shimmed: BTreeSet::new(),
+            overrides: Overrides::new(),
+            file_provider: None,
+            file_stack: Vec::new(),
+            quality: 1.0,
+            limits: Limits::default(),
+            usage: Usage { started_ms: now_ms(), ..Usage::default() },
+            constructs: Vec::new(),
+            call_stack: Vec::new(),
+            max_recursion_depth: DEFAULT_MAX_RECURSION_DEPTH,
+            cancel: None,
+            root: None,
+            log: None,
+            rng: Rng::from_clock(),
+            extensions: false,
+            sources: false,
         }
     }
 
@@ -207,7 +300,20 @@ impl EvalContext {
     ///
     /// Number of segments to use (minimum 3)
     pub fn calculate_fragments(&self, radius: f64) -> u32 {
-        self.scope.calculate_fragments(radius)
+        self.scale_fragments(self.scope.calculate_fragments(radius))
+    }
+
+    /// Apply the `quality()` multiplier to a segment count.
+    ///
+    /// ## Returns
+    ///
+    /// The scaled count, rounded, and at least 3; an unset count (0)
+    /// stays 0, so meshing still picks one from `$fa` and `$fs`
+    pub fn scale_fragments(&self, fragments: u32) -> u32 {
+        if fragments == 0 {
+            return 0;
+        }
+        ((fragments as f64 * self.quality).round() as u32).max(3)
     }
 
     /// Add a warning message.
@@ -216,8 +322,161 @@ impl EvalContext {
     ///
     /// - `msg`: Warning message to add
     pub fn warn(&mut self, msg: String) {
+        self.diagnose(DiagnosticKind::General, msg);
+    }
+
+    /// Add a warning of a known kind, at the statement being evaluated.
+    ///
+    /// ## Parameters
+    ///
+    /// - `kind`: What the warning is about
+    /// - `msg`: Warning message to add
+    pub fn diagnose(&mut self, kind: DiagnosticKind, msg: String) {
+        let span = self.constructs.last().map(|c| c.span);
+        let diagnostic = Diagnostic { kind, text: msg.clone(), span };
+        if let Some(log) = &self.log {
+            log.log(LogEntry::Diagnostic(&diagnostic));
+        }
+        self.diagnostics.push(diagnostic);
         self.warnings.push(msg);
     }
+
+    /// Add a line of console output.
+    ///
+    /// ## Parameters
+    ///
+    /// - `msg`: Echoed text, without the `ECHO:` prefix
+    pub fn echo(&mut self, msg: String) {
+        let span = self.constructs.last().map(|c| c.span);
+        let message = Message { kind: MessageKind::Echo, text: msg, span };
+        if let Some(log) = &self.log {
+            log.log(LogEntry::Message(&message));
+        }
+        self.messages.push(message);
+    }
+
+    /// Install top-level variable overrides.
+    ///
+    /// Each override is defined globally right away, so it is visible even
+    /// if the source never assigns it, and replaces later top-level
+    /// assignments to the same name.
+    ///
+    /// ## Parameters
+    ///
+    /// - `overrides`: Values by variable name
+    pub fn set_overrides(&mut self, overrides: Overrides) {
+        for (name, value) in &overrides {
+            self.scope.define(name, value.clone());
+        }
+        self.overrides = overrides;
+    }
+
+    /// Register a precompiled library's definitions.
+    ///
+    /// Functions and modules become callable and top-level assignments
+    /// become global variables. Libraries carry no geometry.
+    ///
+    /// ## Parameters
+    ///
+    /// - `library`: Bundle produced by `compile_library`
+    pub fn register_library(&mut self, library: &LibraryBundle) -> Result<(), EvalError> {
+        evaluate_statements(self, &library.statements)?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// LIMITS
+// =============================================================================
+
+impl EvalContext {
+    /// Count loop iterations before running them.
+    ///
+    /// ## Errors
+    ///
+    /// `EvalError::LimitExceeded` if the total goes over the iteration limit.
+    pub fn count_iterations(&mut self, count: u64) -> Result<(), EvalError> {
+        self.usage.iterations = self.usage.iterations.saturating_add(count);
+        self.check_limit(LimitKind::Iterations, self.usage.iterations)
+    }
+
+    /// Count a geometry node.
+    ///
+    /// ## Errors
+    ///
+    /// `EvalError::LimitExceeded` if the total goes over the node limit.
+    pub fn count_node(&mut self) -> Result<(), EvalError> {
+        self.usage.nodes += 1;
+        self.check_limit(LimitKind::Nodes, self.usage.nodes)
+    }
+
+    /// Check for cancellation and the time limit; the clock is only read
+    /// when a time limit is set.
+    ///
+    /// ## Errors
+    ///
+    /// `EvalError::Cancelled` if the token was cancelled, and
+    /// `EvalError::LimitExceeded` if evaluation has run too long.
+    pub fn check_running(&self) -> Result<(), EvalError> {
+        if self.cancel.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(EvalError::Cancelled);
+        }
+        if self.limits.max_time_ms.is_none() {
+            return Ok(());
+        }
+        let elapsed = (now_ms() - self.usage.started_ms).max(0.0) as u64;
+        self.check_limit(LimitKind::Time, elapsed)
+    }
+
+    /// Enter a user function or module call; pair with [`Self::exit_call`].
+    ///
+    /// ## Errors
+    ///
+    /// `EvalError::RecursionLimit` with the call chain if the calls in
+    /// progress are already at the maximum depth.
+    pub fn enter_call(&mut self, name: &str) -> Result<(), EvalError> {
+        if self.call_stack.len() >= self.max_recursion_depth {
+            return Err(self.recursion_limit(self.max_recursion_depth, false));
+        }
+        self.call_stack.push(name.to_string());
+        Ok(())
+    }
+
+    /// Count the tail calls the innermost call has made in a row.
+    ///
+    /// ## Errors
+    ///
+    /// `EvalError::RecursionLimit` with the call chain once `count` goes
+    /// over [`MAX_TAIL_CALLS`].
+    pub fn check_tail_calls(&self, count: usize) -> Result<(), EvalError> {
+        if count > MAX_TAIL_CALLS {
+            return Err(self.recursion_limit(MAX_TAIL_CALLS, true));
+        }
+        Ok(())
+    }
+
+    /// The recursion error for the calls in progress.
+    fn recursion_limit(&self, limit: usize, tail_calls: bool) -> EvalError {
+        EvalError::RecursionLimit(Box::new(RecursionLimit {
+            limit,
+            chain: self.call_stack.clone(),
+            construct: self.constructs.last().cloned(),
+            tail_calls,
+        }))
+    }
+
+    /// Leave the innermost user function or module call.
+    pub fn exit_call(&mut self) {
+        self.call_stack.pop();
+    }
+
+    /// Check a count against its limit, blaming the innermost statement.
+    fn check_limit(&self, kind: LimitKind, observed: u64) -> Result<(), EvalError> {
+        self.limits.check(kind, observed).map_err(|mut report| {
+            report.construct = self.constructs.last().cloned();
+            EvalError::LimitExceeded(report)
+        })
+    }
 }
 
 impl Default for EvalContext {
@@ -254,11 +513,7 @@ pub fn evaluate_statements(
         }
     }
 
-    match children.len() {
-        0 => Ok(GeometryNode::Empty),
-        1 => Ok(children.remove(0)),
-        _ => Ok(GeometryNode::Group { children }),
-    }
+    Ok(GeometryNode::group(children))
 }
 
 /// Evaluate a single statement.
@@ -274,30 +529,76 @@ pub fn evaluate_statements(
 pub fn evaluate_statement(
     ctx: &mut EvalContext,
     stmt: &Statement,
+) -> Result<Option<GeometryNode>, EvalError> {
+    ctx.check_running()?;
+    let construct = describe_statement(stmt);
+    let _span = construct.as_ref().map(crate::timing::statement_span);
+    let tracked = construct.is_some();
+    ctx.constructs.extend(construct);
+    let result = evaluate_statement_inner(ctx, stmt);
+    if tracked {
+        ctx.constructs.pop();
+    }
+    result
+}
+
+/// Name a statement for limit reports; `None` for declarations and
+/// blocks, which never run long themselves.
+fn describe_statement(stmt: &Statement) -> Option<Construct> {
+    let (description, span) = match stmt {
+        Statement::ModuleCall { name, span, .. } => (format!("{}() call", name), span),
+        Statement::Assignment { name, span, .. } => (format!("assignment to {}", name), span),
+        Statement::ForLoop { span, .. } => ("for-loop".to_string(), span),
+        Statement::IntersectionFor { span, .. } => ("intersection_for loop".to_string(), span),
+        Statement::IfElse { span, .. } => ("if statement".to_string(), span),
+        Statement::Let { span, .. } => ("let block".to_string(), span),
+        _ => return None,
+    };
+    Some(Construct { description, span: *span })
+}
+
+/// Evaluate a single statement, without limit bookkeeping.
+fn evaluate_statement_inner(
+    ctx: &mut EvalContext,
+    stmt: &Statement,
 ) -> Result<Option<GeometryNode>, EvalError> {
     match stmt {
-        Statement::ModuleCall { name, args, children, .. } => {
-            evaluate_module_call(ctx, name, args, children)
+        Statement::ModuleCall { name, args, children, span } => {
+            let node = evaluate_module_call(ctx, name, args, children, *span)?;
+            if node.is_some() {
+                ctx.count_node()?;
+            }
+            Ok(match node {
+                Some(node) if ctx.sources && !node.is_empty() => Some(GeometryNode::Source { span: *span, child: Box::new(node) }),
+                node => node,
+            })
         }
         Statement::Block { statements, .. } => {
-            // Block creates a new scope
+            // Block creates a new scope, closed even if the block fails
             ctx.scope.push();
-            let result = evaluate_statements(ctx, statements)?;
+            let result = evaluate_statements(ctx, statements);
             ctx.scope.pop();
-            Ok(Some(result))
+            result.map(Some)
         }
         Statement::Assignment { name, value, .. } => {
+            // Top-level assignments yield to overrides; locals never do
+            if ctx.scope.is_global() {
+                if let Some(val) = ctx.overrides.get(name).cloned() {
+                    ctx.scope.define(name, val);
+                    return Ok(None);
+                }
+            }
             // Evaluate the value and store in scope
             let val = eval_expr(ctx, value)?;
             ctx.scope.define(name, val);
             Ok(None)
         }
-        Statement::ForLoop { assignments, body, .. } => {
-            evaluate_for_loop(ctx, assignments, body)
-        }
+        Statement::ForLoop { assignments, body, .. } => eval_for(ctx, assignments, body),
+        Statement::IntersectionFor { assignments, body, .. } => eval_intersection_for(ctx, assignments, body),
         Statement::IfElse { condition, then_body, else_body, .. } => {
-            evaluate_if_else(ctx, condition, then_body, else_body.as_deref())
+            eval_if(ctx, condition, then_body, else_body.as_deref())
         }
+        Statement::Let { assignments, body, .. } => eval_let(ctx, assignments, body),
         Statement::FunctionDeclaration { name, params, body, .. } => {
             // Register the function for later evaluation
             ctx.define_function(name.clone(), params.clone(), body.clone());
@@ -308,6 +609,9 @@ pub fn evaluate_statement(
             ctx.define_module(name.clone(), params.clone(), body.clone());
             Ok(None)
         }
+        Statement::Include { path, .. } => eval_include(ctx, path),
+        Statement::Use { path, .. } => eval_use(ctx, path),
+        Statement::Modified { modifier, statement, span } => eval_modified(ctx, *modifier, statement, *span),
     }
 }
 
@@ -317,7 +621,8 @@ pub fn evaluate_statement(
 ///
 /// 1. Special modules (children)
 /// 2. User-defined modules
-/// 3. Built-in primitives, transforms, booleans, extrusions
+/// 3. Enabled library shims
+/// 4. Built-in primitives, transforms, booleans, extrusions
 ///
 /// ## Parameters
 ///
@@ -325,11 +630,13 @@ pub fn evaluate_statement(
 /// - `name`: Module name (e.g., "cube", "translate", or user-defined)
 /// - `args`: Module arguments
 /// - `children`: Child statements (for transforms/booleans/user modules)
+/// - `span`: Source span of the call, used in diagnostics
 fn evaluate_module_call(
     ctx: &mut EvalContext,
     name: &str,
     args: &[Argument],
     children: &[Statement],
+    span: Span,
 ) -> Result<Option<GeometryNode>, EvalError> {
     // Special module: children() - evaluates the children passed to current module
     if name == "children" {
@@ -338,7 +645,15 @@ fn evaluate_module_call(
 
     // Check for user-defined module first
     if let Some(module) = ctx.get_module(name).cloned() {
-        return eval_user_module(ctx, &module, args, children);
+        ctx.enter_call(name)?;
+        let result = grow_stack(|| eval_user_module(ctx, name, &module, args, children));
+        ctx.exit_call();
+        return result;
+    }
+
+    // Library shims stand in for modules no real definition provided
+    if let Some(node) = eval_shim_module(ctx, name, args, children)? {
+        return Ok(Some(node));
     }
 
     // Built-in modules
@@ -347,12 +662,15 @@ fn evaluate_module_call(
         "cube" => Ok(Some(eval_cube(ctx, args)?)),
         "sphere" => Ok(Some(eval_sphere(ctx, args)?)),
         "cylinder" => Ok(Some(eval_cylinder(ctx, args)?)),
-        "polyhedron" => Ok(Some(eval_polyhedron(ctx, args)?)),
+        "polyhedron" => Ok(Some(eval_polyhedron(ctx, args, span)?)),
 
         // 2D Primitives
         "circle" => Ok(Some(eval_circle(ctx, args)?)),
         "square" => Ok(Some(eval_square(ctx, args)?)),
         "polygon" => Ok(Some(eval_polygon(ctx, args)?)),
+        "text" => Ok(Some(eval_text(ctx, args)?)),
+        "import" => Ok(Some(eval_import(ctx, args)?)),
+        "surface" => Ok(Some(eval_surface(ctx, args)?)),
 
         // Boolean operations
         "union" => Ok(Some(eval_union(ctx, children)?)),
@@ -367,7 +685,9 @@ fn evaluate_module_call(
         "translate" => Ok(Some(eval_translate(ctx, args, children)?)),
         "rotate" => Ok(Some(eval_rotate(ctx, args, children)?)),
         "scale" => Ok(Some(eval_scale(ctx, args, children)?)),
+        "resize" => Ok(Some(eval_resize(ctx, args, children)?)),
         "mirror" => Ok(Some(eval_mirror(ctx, args, children)?)),
+        "multmatrix" => Ok(Some(eval_multmatrix(ctx, args, children)?)),
         "color" => Ok(Some(eval_color(ctx, args, children)?)),
 
         // Extrusions
@@ -378,6 +698,31 @@ fn evaluate_module_call(
         "offset" => Ok(Some(eval_offset(ctx, args, children)?)),
         "projection" => Ok(Some(eval_projection(ctx, args, children)?)),
 
+        // Extensions
+        "smooth" => Ok(Some(eval_smooth(ctx, args, children)?)),
+        "quality" => Ok(Some(eval_quality(ctx, args, children)?)),
+        "torus" if ctx.extensions => Ok(Some(eval_torus(ctx, args)?)),
+        "prism" if ctx.extensions => Ok(Some(eval_prism(ctx, args)?)),
+        "rounded_cube" if ctx.extensions => Ok(Some(eval_rounded_cube(ctx, args)?)),
+        "refine" if ctx.extensions => Ok(Some(eval_refine(ctx, args, children)?)),
+        "warp_twist" if ctx.extensions => Ok(Some(eval_warp_twist(ctx, args, children)?)),
+        "warp_taper" if ctx.extensions => Ok(Some(eval_warp_taper(ctx, args, children)?)),
+        "warp_bend" if ctx.extensions => Ok(Some(eval_warp_bend(ctx, args, children)?)),
+
+        // Debugging output
+        "echo" => {
+            eval_echo(ctx, args)?;
+            evaluate_statements(ctx, children).map(Some)
+        }
+        "assert" => {
+            eval_assert(ctx, args, span)?;
+            evaluate_statements(ctx, children).map(Some)
+        }
+        "echo_dim" => {
+            eval_echo_dim(ctx, args)?;
+            Ok(None)
+        }
+
         // Unknown module - warn and skip
         _ => {
             ctx.warn(format!("Unknown module: {}", name));
@@ -398,6 +743,7 @@ fn evaluate_module_call(
 /// ## Parameters
 ///
 /// - `ctx`: Evaluation context
+/// - `name`: Module name, for argument warnings
 /// - `module`: The module definition
 /// - `args`: Arguments passed to the module call
 /// - `children`: Child statements passed to the module
@@ -410,24 +756,13 @@ fn evaluate_module_call(
 /// ```
 fn eval_user_module(
     ctx: &mut EvalContext,
+    name: &str,
     module: &ModuleDef,
     args: &[Argument],
     children: &[Statement],
 ) -> Result<Option<GeometryNode>, EvalError> {
     // Evaluate all arguments first
-    let mut arg_values: Vec<crate::value::Value> = Vec::new();
-    let mut named_args: std::collections::HashMap<String, crate::value::Value> = std::collections::HashMap::new();
-
-    for arg in args {
-        match arg {
-            Argument::Positional(e) => {
-                arg_values.push(eval_expr(ctx, e)?);
-            }
-            Argument::Named { name, value } => {
-                named_args.insert(name.clone(), eval_expr(ctx, value)?);
-            }
-        }
-    }
+    let call_args = CallArguments::evaluate(ctx, args)?;
 
     // Create new scope for module evaluation
     ctx.scope.push();
@@ -438,27 +773,10 @@ fn eval_user_module(
     // Set $children special variable
     ctx.scope.define("$children", crate::value::Value::Number(children.len() as f64));
 
-    // Bind parameters to arguments
-    for (i, param) in module.params.iter().enumerate() {
-        // Check for named argument first
-        let value = if let Some(v) = named_args.get(&param.name) {
-            v.clone()
-        } else if i < arg_values.len() {
-            // Use positional argument
-            arg_values[i].clone()
-        } else if let Some(default) = &param.default {
-            // Use default value
-            eval_expr(ctx, default)?
-        } else {
-            // No value provided
-            crate::value::Value::Undef
-        };
-
-        ctx.scope.define(&param.name, value);
-    }
-
-    // Evaluate module body
-    let result = evaluate_statements(ctx, &module.body);
+    // Bind parameters, then evaluate the body; the scope and children
+    // are released below even if either fails
+    let result = bind_parameters(ctx, name, &module.params, call_args)
+        .and_then(|_| evaluate_statements(ctx, &module.body));
 
     // Pop children stack
     ctx.pop_children();
@@ -515,109 +833,25 @@ fn eval_children_call(
 }
 
 // =============================================================================
-// CONTROL FLOW
+// STACK
 // =============================================================================
 
-/// Evaluate a for loop.
-///
-/// Each iteration creates a new scope with the loop variable.
+/// Run a nested call, first moving to a fresh stack segment if the current
+/// one is nearly used up, so deep recursion reaches the recursion limit
+/// instead of overflowing the native stack.
 ///
-/// ## Parameters
-///
-/// - `ctx`: Evaluation context
-/// - `assignments`: Loop variable assignments
-/// - `body`: Loop body statements
-///
-/// ## Example
-///
-/// ```text
-/// for (i = [0:2]) cube(i);  // Creates 3 cubes
-/// ```
-fn evaluate_for_loop(
-    ctx: &mut EvalContext,
-    assignments: &[(String, Expression)],
-    body: &[Statement],
-) -> Result<Option<GeometryNode>, EvalError> {
-    let mut children = Vec::new();
-
-    // Handle single assignment (most common case)
-    if let Some((var_name, range_expr)) = assignments.first() {
-        let range_val = eval_expr(ctx, range_expr)?;
-        
-        // Get iteration values
-        let values = match range_val {
-            Value::List(items) => items,
-            Value::Range { start, end, step } => {
-                let mut vals = Vec::new();
-                let mut current = start;
-                let step_val = step.unwrap_or(1.0);
-                if step_val > 0.0 {
-                    while current <= end {
-                        vals.push(Value::Number(current));
-                        current += step_val;
-                    }
-                } else if step_val < 0.0 {
-                    while current >= end {
-                        vals.push(Value::Number(current));
-                        current += step_val;
-                    }
-                }
-                vals
-            }
-            _ => vec![range_val],
-        };
-
-        // Iterate
-        for val in values {
-            ctx.scope.push();
-            ctx.scope.define(var_name, val);
-            
-            if let Ok(node) = evaluate_statements(ctx, body) {
-                if !node.is_empty() {
-                    children.push(node);
-                }
-            }
-            
-            ctx.scope.pop();
-        }
-    }
-
-    match children.len() {
-        0 => Ok(None),
-        1 => Ok(Some(children.remove(0))),
-        _ => Ok(Some(GeometryNode::Group { children })),
-    }
+/// In the browser the stack cannot grow; there the recursion limit alone
+/// bounds the depth.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn grow_stack<R>(call: impl FnOnce() -> R) -> R {
+    // Grow by 4 MiB whenever less than 256 KiB is left
+    stacker::maybe_grow(256 * 1024, 4 * 1024 * 1024, call)
 }
 
-/// Evaluate an if/else statement.
-///
-/// ## Parameters
-///
-/// - `ctx`: Evaluation context
-/// - `condition`: Condition expression
-/// - `then_body`: Statements if condition is true
-/// - `else_body`: Optional statements if condition is false
-fn evaluate_if_else(
-    ctx: &mut EvalContext,
-    condition: &Expression,
-    then_body: &[Statement],
-    else_body: Option<&[Statement]>,
-) -> Result<Option<GeometryNode>, EvalError> {
-    let cond_val = eval_expr(ctx, condition)?;
-    
-    if cond_val.as_boolean() {
-        ctx.scope.push();
-        let result = evaluate_statements(ctx, then_body)?;
-        ctx.scope.pop();
-        Ok(Some(result))
-    } else if let Some(else_stmts) = else_body {
-        ctx.scope.push();
-        let result = evaluate_statements(ctx, else_stmts)?;
-        ctx.scope.pop();
-        Ok(Some(result))
-    } else {
-        Ok(None)
-    }
+/// Run a nested call; the browser stack cannot grow.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn grow_stack<R>(call: impl FnOnce() -> R) -> R {
+    call()
 }
 
 // =============================================================================
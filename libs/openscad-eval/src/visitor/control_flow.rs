@@ -0,0 +1,295 @@
+//! # Control Flow
+//!
+//! Evaluators for `for`, `intersection_for`, `if`/`else if`/`else` and
+//! `let` statements.
+//!
+//! ## Scoping
+//!
+//! Every loop iteration, `if` branch and `let` body runs in its own scope:
+//! assignments inside it are not visible after it, and the scope is closed
+//! even when the body fails, so an error skipped by an enclosing loop
+//! cannot leave variables behind.
+//!
+//! ## Geometry
+//!
+//! As in OpenSCAD, the statements have no node of their own: a single
+//! child is returned as is, several are grouped, and nothing (a false
+//! `if` without `else`, an empty range) yields no node at all. The one
+//! exception is `intersection_for`, which intersects its iterations, each
+//! taken as the union of its statements.
+//!
+//! ## Example
+//!
+//! ```text
+//! for (i = [0:2]) translate([i * 2, 0, 0]) cube(1);
+//! intersection_for (a = [0, 60, 120]) rotate(a) cube([10, 2, 2], center = true);
+//! if (n == 1) cube(1); else if (n == 2) sphere(1); else cylinder(1);
+//! let (w = 10, h = w / 2) cube([w, w, h]);
+//! ```
+
+use crate::error::EvalError;
+use crate::geometry::GeometryNode;
+use openscad_ast::{Expression, Statement};
+
+use super::context::{evaluate_statements, EvalContext};
+use super::expressions::{bind_assignments, eval_expr};
+
+// =============================================================================
+// FOR
+// =============================================================================
+
+/// Evaluate a for loop.
+///
+/// Each iteration creates a new scope with the loop variable.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `assignments`: Loop variable assignments
+/// - `body`: Loop body statements
+///
+/// ## Example
+///
+/// ```text
+/// for (i = [0:2]) cube(i);  // Creates 3 cubes
+/// ```
+pub fn eval_for(
+    ctx: &mut EvalContext,
+    assignments: &[(String, Expression)],
+    body: &[Statement],
+) -> Result<Option<GeometryNode>, EvalError> {
+    let children = iterate(ctx, assignments, body)?;
+    Ok(non_empty(GeometryNode::group(children)))
+}
+
+/// Evaluate an `intersection_for` loop.
+///
+/// Iterations run as in [`eval_for`]; the statements of each iteration
+/// are unioned and the iterations intersected. Iterations that produce
+/// nothing are left out rather than emptying the result.
+///
+/// ## Example
+///
+/// ```text
+/// // Hexagonal prism from three rotated bars
+/// intersection_for (a = [0, 60, 120]) rotate(a) cube([20, 10 * sqrt(3), 5], center = true);
+/// ```
+pub fn eval_intersection_for(
+    ctx: &mut EvalContext,
+    assignments: &[(String, Expression)],
+    body: &[Statement],
+) -> Result<Option<GeometryNode>, EvalError> {
+    let mut children: Vec<GeometryNode> =
+        iterate(ctx, assignments, body)?.into_iter().map(GeometryNode::into_union).collect();
+    Ok(match children.len() {
+        0 => None,
+        1 => children.pop(),
+        _ => Some(GeometryNode::Intersection { children }),
+    })
+}
+
+/// Run `body` once per value of the first loop assignment, each time in a
+/// new scope, and return the non-empty results in order.
+fn iterate(
+    ctx: &mut EvalContext,
+    assignments: &[(String, Expression)],
+    body: &[Statement],
+) -> Result<Vec<GeometryNode>, EvalError> {
+    let mut children = Vec::new();
+
+    // Handle single assignment (most common case)
+    if let Some((var_name, range_expr)) = assignments.first() {
+        let iterable = eval_expr(ctx, range_expr)?;
+        ctx.count_iterations(iterable.iteration_count())?;
+
+        // Iterate
+        for val in iterable.into_iteration_values() {
+            ctx.scope.push();
+            ctx.scope.define(var_name, val);
+            let result = evaluate_statements(ctx, body);
+            ctx.scope.pop();
+
+            match result {
+                Ok(node) if !node.is_empty() => children.push(node),
+                // Limits abort the whole evaluation; other errors skip the iteration
+                Err(e) if e.aborts_evaluation() => return Err(e),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(children)
+}
+
+// =============================================================================
+// IF
+// =============================================================================
+
+/// Evaluate an if/else statement, following `else if` chains.
+///
+/// The parser nests `else if` as an `if` that is the only statement of
+/// the `else` branch. The chain is walked in a loop rather than by
+/// recursion, so conditions are tested in order and only the first true
+/// branch (or the final `else`) runs.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `condition`: Condition expression
+/// - `then_body`: Statements if condition is true
+/// - `else_body`: Optional statements if condition is false
+///
+/// ## Example
+///
+/// ```text
+/// module part(kind) {
+///     if (kind == "box") cube(1);
+///     else if (kind == "ball") sphere(1);
+///     else cylinder(1);
+/// }
+/// ```
+pub fn eval_if(
+    ctx: &mut EvalContext,
+    condition: &Expression,
+    then_body: &[Statement],
+    else_body: Option<&[Statement]>,
+) -> Result<Option<GeometryNode>, EvalError> {
+    let (mut condition, mut then_body, mut else_body) = (condition, then_body, else_body);
+    loop {
+        if eval_expr(ctx, condition)?.as_boolean() {
+            return eval_branch(ctx, then_body);
+        }
+        match else_body {
+            Some([Statement::IfElse { condition: next, then_body: next_then, else_body: next_else, .. }]) => {
+                condition = next;
+                then_body = next_then;
+                else_body = next_else.as_deref();
+            }
+            Some(body) => return eval_branch(ctx, body),
+            None => return Ok(None),
+        }
+    }
+}
+
+/// Evaluate one branch of an `if` in its own scope.
+fn eval_branch(ctx: &mut EvalContext, body: &[Statement]) -> Result<Option<GeometryNode>, EvalError> {
+    ctx.scope.push();
+    let result = evaluate_statements(ctx, body);
+    ctx.scope.pop();
+    Ok(non_empty(result?))
+}
+
+// =============================================================================
+// LET
+// =============================================================================
+
+/// Evaluate a let block.
+///
+/// Bindings are visible only to the body statements.
+///
+/// ## Example
+///
+/// ```text
+/// let (w = 10, h = w / 2) cube([w, w, h]);
+/// ```
+pub fn eval_let(
+    ctx: &mut EvalContext,
+    assignments: &[(String, Expression)],
+    body: &[Statement],
+) -> Result<Option<GeometryNode>, EvalError> {
+    ctx.scope.push();
+    let result = bind_assignments(ctx, assignments).and_then(|_| evaluate_statements(ctx, body));
+    ctx.scope.pop();
+    Ok(non_empty(result?))
+}
+
+/// `None` for an empty node, so the statement adds nothing to its parent.
+fn non_empty(node: GeometryNode) -> Option<GeometryNode> {
+    (!node.is_empty()).then_some(node)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate;
+
+    /// Names of the primitives in the result, in order.
+    fn shapes(source: &str) -> Vec<&'static str> {
+        fn collect(node: &GeometryNode, out: &mut Vec<&'static str>) {
+            match node {
+                GeometryNode::Group { children } => children.iter().for_each(|c| collect(c, out)),
+                GeometryNode::Empty => {}
+                GeometryNode::Cube { .. } => out.push("cube"),
+                GeometryNode::Sphere { .. } => out.push("sphere"),
+                GeometryNode::Cylinder { .. } => out.push("cylinder"),
+                _ => out.push("other"),
+            }
+        }
+        let mut out = Vec::new();
+        collect(&evaluate(source).unwrap().geometry, &mut out);
+        out
+    }
+
+    /// Test each link of an else-if chain, inside a module.
+    #[test]
+    fn test_else_if_chain() {
+        let module = "module part(n) { if (n == 1) cube(1); else if (n == 2) sphere(1); \
+            else if (n == 3) { h = 2; cylinder(h); } else { } }";
+        assert_eq!(shapes(&format!("{} part(1); part(2); part(3); part(4);", module)), ["cube", "sphere", "cylinder"]);
+        assert_eq!(shapes("if (false) cube(1);"), Vec::<&str>::new());
+    }
+
+    /// Test intersection_for intersects its iterations, unioning the
+    /// statements of each one.
+    #[test]
+    fn test_intersection_for() {
+        let result = evaluate("intersection_for (i = [0 : 2]) { cube(i + 1); sphere(i + 1); }").unwrap();
+        match result.geometry {
+            GeometryNode::Intersection { children } => {
+                assert_eq!(children.len(), 3);
+                assert!(children.iter().all(|c| matches!(c, GeometryNode::Union { children } if children.len() == 2)));
+            }
+            other => panic!("expected intersection, got {:?}", other),
+        }
+
+        // One iteration is the child itself, none is nothing
+        let result = evaluate("intersection_for (i = [5]) cube(i);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Cube { size: [5.0, 5.0, 5.0], .. }));
+        assert_eq!(shapes("intersection_for (i = []) cube(i);"), Vec::<&str>::new());
+
+        // Empty iterations are left out and the loop variable does not leak
+        let result = evaluate("intersection_for (i = [0 : 2]) if (i != 1) cube(i + 1); cube(is_undef(i) ? 1 : 9);").unwrap();
+        match result.geometry {
+            GeometryNode::Group { children } => {
+                assert!(matches!(&children[0], GeometryNode::Intersection { children } if children.len() == 2));
+                assert!(matches!(children[1], GeometryNode::Cube { size: [1.0, 1.0, 1.0], .. }));
+            }
+            other => panic!("expected group, got {:?}", other),
+        }
+    }
+
+    /// Test a branch's variables stay in it and the scope is closed when
+    /// the branch fails inside a loop.
+    #[test]
+    fn test_branch_scopes() {
+        let result = evaluate("x = 1; if (true) { x = 2; y = 3; } cube([x, is_undef(y) ? 1 : 9, 1]);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Cube { size: [1.0, 1.0, 1.0], .. }));
+
+        let ast = openscad_ast::parse("for (i = [0 : 2]) if (i > 0) { y = i; cube(\"bad\"); }").unwrap();
+        let mut ctx = EvalContext::new();
+        evaluate_statements(&mut ctx, &ast.statements).unwrap();
+        assert!(ctx.scope.is_global());
+        assert_eq!(ctx.scope.get("y"), None);
+
+        // Same for a module whose default argument fails
+        let ast = openscad_ast::parse("module m(a = [1] + \"x\") { cube(a); } for (i = [0 : 1]) m();").unwrap();
+        let mut ctx = EvalContext::new();
+        evaluate_statements(&mut ctx, &ast.statements).unwrap();
+        assert!(ctx.scope.is_global());
+        assert!(ctx.current_children().is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+//! # Debugging Evaluators
+//!
+//! Evaluators for modules that only produce console output or check the
+//! script. Both `echo()` and `assert()` pass their children through.
+//!
+//! ## Modules
+//!
+//! - `echo(...)` - Print values, named ones as `name = value`
+//! - `assert(condition, message)` - Stop with an error if the condition is false
+//! - `echo_dim(value, precision=2)` - Echo a dimension in mm and inches
+//!
+//! `assert()` and the `precision` option of `echo_dim()` are bound by
+//! OpenSCAD's rules (see [`arguments`](super::arguments)); the values of
+//! `echo()` and `echo_dim()` print any named argument, so they take every
+//! other name.
+//!
+//! ## Example
+//!
+//! ```text
+//! echo("size", w = 10);            // "size", w = 10
+//! assert(w > 0, "w must be positive");
+//! echo_dim(25.4);                  // 25.40 mm (1.00 in)
+//! echo_dim(bolt=6.35, precision=3); // bolt = 6.350 mm (0.250 in)
+//! ```
+
+use crate::error::EvalError;
+use crate::units::{format_dim, DEFAULT_DIM_PRECISION};
+use crate::value::Value;
+use openscad_ast::{Argument, Span};
+
+use super::arguments::ArgumentResolver;
+use super::context::EvalContext;
+use super::expressions::eval_expr;
+
+// =============================================================================
+// ECHO
+// =============================================================================
+
+/// Evaluate echo() call.
+///
+/// Arguments are printed in order, separated by commas; strings are
+/// quoted and named arguments are written as `name = value`.
+///
+/// ## OpenSCAD Signature
+///
+/// ```text
+/// echo(value, ...);
+/// echo(name=value, ...);
+/// ```
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `args`: Arguments from the module call
+pub fn eval_echo(ctx: &mut EvalContext, args: &[Argument]) -> Result<(), EvalError> {
+    let mut parts = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            Argument::Positional(expr) => parts.push(eval_expr(ctx, expr)?.to_echo_string()),
+            Argument::Named { name, value } => {
+                parts.push(format!("{} = {}", name, eval_expr(ctx, value)?.to_echo_string()));
+            }
+        }
+    }
+    ctx.echo(parts.join(", "));
+    Ok(())
+}
+
+// =============================================================================
+// ASSERT
+// =============================================================================
+
+/// Evaluate assert() call.
+///
+/// A missing condition counts as false, as in OpenSCAD.
+///
+/// ## OpenSCAD Signature
+///
+/// ```text
+/// assert(condition);
+/// assert(condition, message);
+/// ```
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `args`: Arguments from the module call
+/// - `span`: Source span of the call, reported on failure
+///
+/// ## Errors
+///
+/// `EvalError::AssertionFailed` with the formatted message if the
+/// condition is false.
+pub fn eval_assert(ctx: &mut EvalContext, args: &[Argument], span: Span) -> Result<(), EvalError> {
+    let args = ArgumentResolver::new("assert", &["condition", "message"]).evaluate(ctx, args)?;
+    if args.get("condition").is_some_and(Value::as_boolean) {
+        return Ok(());
+    }
+    Err(EvalError::AssertionFailed { message: args.get("message").map(Value::to_string), span })
+}
+
+// =============================================================================
+// ECHO_DIM
+// =============================================================================
+
+/// Evaluate echo_dim() call.
+///
+/// Every value argument is echoed on its own line; named arguments are
+/// prefixed with their name like `echo()` does. `precision` sets the
+/// number of decimal places.
+///
+/// ## OpenSCAD Signature
+///
+/// ```text
+/// echo_dim(value);
+/// echo_dim(name=value, precision=3);
+/// ```
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `args`: Arguments from the module call
+pub fn eval_echo_dim(ctx: &mut EvalContext, args: &[Argument]) -> Result<(), EvalError> {
+    let (options, dims): (Vec<Argument>, Vec<Argument>) = args.iter().cloned()
+        .partition(|arg| matches!(arg, Argument::Named { name, .. } if name == "precision"));
+    let options = ArgumentResolver::new("echo_dim", &[]).named(&["precision"]).evaluate(ctx, &options)?;
+    let precision = options.get("precision")
+        .map(Value::as_number)
+        .transpose()?
+        .map_or(DEFAULT_DIM_PRECISION, |p| p.max(0.0) as usize);
+
+    let mut values = Vec::with_capacity(dims.len());
+    for arg in &dims {
+        match arg {
+            Argument::Named { name, value } => values.push((Some(name.as_str()), eval_expr(ctx, value)?)),
+            Argument::Positional(expr) => values.push((None, eval_expr(ctx, expr)?)),
+        }
+    }
+
+    for (label, value) in values {
+        match format_dim(&value, precision) {
+            Ok(text) => match label {
+                Some(label) => ctx.echo(format!("{} = {}", label, text)),
+                None => ctx.echo(text),
+            },
+            Err(e) => ctx.warn(format!("echo_dim: {}", e)),
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::{evaluate, EvalError, MessageKind};
+
+    /// Test echo() formats values and records where it was called.
+    #[test]
+    fn test_echo() {
+        let result = evaluate("v = [1, \"a\"];\necho(\"v\", v, n = 1 / 4);\necho() cube(1);").unwrap();
+        assert_eq!(result.echoes, vec!["\"v\", [1, \"a\"], n = 0.25".to_string(), String::new()]);
+        assert_eq!(result.messages[0].kind, MessageKind::Echo);
+        assert_eq!(result.messages[0].span.unwrap().start.line, 1);
+        assert_eq!(result.messages[1].span.unwrap().start.line, 2);
+        assert!(!result.geometry.is_empty());
+    }
+
+    /// Test assert() passes children through and fails with its message.
+    #[test]
+    fn test_assert() {
+        let result = evaluate("w = 2; assert(w > 0, \"w must be positive\") cube(w);").unwrap();
+        assert!(!result.geometry.is_empty());
+
+        let Err(error) = evaluate("w = -2;\nfor (i = [0 : 1]) assert(w > 0, str(\"w = \", w)) cube(1);") else {
+            panic!("expected the assertion to fail");
+        };
+        assert!(matches!(error, EvalError::AssertionFailed { ref span, .. } if span.start.line == 1));
+        assert_eq!(error.to_string(), "Assertion failed at 2:19: w = -2");
+
+        let error = evaluate("assert();").unwrap_err();
+        assert_eq!(error.to_string(), "Assertion failed at 1:1");
+
+        // Named in any order, and unknown names warn
+        let error = evaluate("assert(message = \"too small\", condition = 1 > 2);").unwrap_err();
+        assert_eq!(error.to_string(), "Assertion failed at 1:1: too small");
+        let result = evaluate("assert(true, \"ok\", cond = false);").unwrap();
+        assert_eq!(result.warnings, ["assert(): variable cond not specified as parameter"]);
+    }
+
+    /// Test the log sink gets output and warnings as they happen, even
+    /// when evaluation fails afterwards.
+    #[test]
+    fn test_log_sink() {
+        use crate::{evaluate_with_options, DiagnosticKind, EvalOptions, LogEntry};
+        use std::sync::{Arc, Mutex};
+
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&lines);
+        let options = EvalOptions {
+            log: Some(Arc::new(move |entry: LogEntry| {
+                let line = match entry {
+                    LogEntry::Message(m) => (None, m.text.clone(), m.span.map(|s| s.start.line)),
+                    LogEntry::Diagnostic(d) => (Some(d.kind), d.text.clone(), d.span.map(|s| s.start.line)),
+                };
+                sink.lock().unwrap().push(line);
+            })),
+            ..EvalOptions::default()
+        };
+        let source = "echo(\"start\");\nfor (i = [3 : 1]) echo(i);\nassert(false, \"stop\");";
+        assert!(evaluate_with_options(source, &options).is_err());
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines[0], (None, "\"start\"".to_string(), Some(0)));
+        assert_eq!(lines[1].0, Some(DiagnosticKind::Deprecated));
+        assert_eq!(lines[2..], [(None, "1".to_string(), Some(1)), (None, "2".to_string(), Some(1)), (None, "3".to_string(), Some(1))]);
+    }
+
+    /// Test positional and named dimensions are echoed.
+    #[test]
+    fn test_echo_dim() {
+        let result = evaluate("w = 50.8; echo_dim(w); echo_dim(bolt=6.35, precision=3);").unwrap();
+        assert_eq!(
+            result.echoes,
+            vec![
+                "50.80 mm (2.00 in)".to_string(),
+                "bolt = 6.350 mm (0.250 in)".to_string(),
+            ]
+        );
+        assert!(result.geometry.is_empty());
+
+        let result = evaluate("echo_dim(w=1, precision=1, precision=0);").unwrap();
+        assert_eq!(result.echoes, ["w = 1 mm (0 in)"]);
+        assert_eq!(result.warnings, ["echo_dim(): argument precision supplied more than once"]);
+    }
+
+    /// Test non-numeric values warn instead of failing the render.
+    #[test]
+    fn test_echo_dim_non_numeric() {
+        let result = evaluate("echo_dim(\"abc\"); cube(1);").unwrap();
+        assert!(result.echoes.is_empty());
+        assert!(result.warnings.iter().any(|w| w.starts_with("echo_dim")));
+    }
+}
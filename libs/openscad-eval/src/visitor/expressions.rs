@@ -8,7 +8,8 @@
 //! - Variable and special variable lookup
 //! - Binary and unary operations
 //! - Function calls (built-in functions)
-//! - List and range expressions
+//! - List, range and list comprehension expressions
+//! - Let expressions
 //!
 //! ## Example
 //!
@@ -20,10 +21,17 @@
 //! ```
 
 use crate::error::EvalError;
-use crate::value::Value;
+use crate::diagnostic::DiagnosticKind;
+use crate::value::{format_number, Value};
 use openscad_ast::{Expression, Argument, BinaryOp, UnaryOp};
 
-use super::context::EvalContext;
+use super::comprehension::eval_list_comprehension;
+use super::lists::{eval_list_function, LIST_FUNCTIONS};
+use super::math::{eval_math_function, eval_rands, MATH_FUNCTIONS};
+use super::paths::{eval_path_function, PATH_FUNCTIONS};
+use super::strings::{eval_string_function, STRING_FUNCTIONS};
+use super::arguments::{bind_parameters, CallArguments};
+use super::context::{grow_stack, EvalContext, FunctionDef};
 
 // =============================================================================
 // EXPRESSION EVALUATION
@@ -55,12 +63,14 @@ pub fn eval_expr(ctx: &mut EvalContext, expr: &Expression) -> Result<Value, Eval
         Expression::Identifier(name) => eval_identifier(ctx, name),
         Expression::SpecialVariable(name) => eval_special_var(ctx, name),
         Expression::List(items) => eval_list(ctx, items),
+        Expression::ListComprehension(elements) => eval_list_comprehension(ctx, elements),
         Expression::Range { start, end, step } => eval_range(ctx, start, end, step.as_deref()),
         Expression::BinaryOp { op, left, right } => eval_binary_op(ctx, *op, left, right),
         Expression::UnaryOp { op, operand } => eval_unary_op(ctx, *op, operand),
         Expression::Ternary { condition, then_expr, else_expr } => {
             eval_ternary(ctx, condition, then_expr, else_expr)
         }
+        Expression::Let { assignments, body } => eval_let(ctx, assignments, body),
         Expression::FunctionCall { name, args } => eval_function_call(ctx, name, args),
         Expression::Index { object, index } => eval_index(ctx, object, index),
         Expression::Member { object, member } => eval_member(ctx, object, member),
@@ -129,6 +139,9 @@ fn eval_list(ctx: &mut EvalContext, items: &[Expression]) -> Result<Value, EvalE
 
 /// Evaluate a range expression.
 ///
+/// Descending ranges need a negative step: `[10:-1:0]`. As in OpenSCAD,
+/// `[10:0]` without a step is read as `[0:10]` with a deprecation warning.
+///
 /// ## Parameters
 ///
 /// - `ctx`: Evaluation context
@@ -148,6 +161,14 @@ fn eval_range(
         .transpose()?
         .map(|v| v.as_number())
         .transpose()?;
+    if st.is_none() && s > e {
+        ctx.diagnose(DiagnosticKind::Deprecated, format!(
+            "DEPRECATED: Using ranges of the form [begin:end] with begin value greater than the end value is deprecated; use [{}:-1:{}] to count down",
+            format_number(s),
+            format_number(e)
+        ));
+        return Ok(Value::Range { start: e, end: s, step: None });
+    }
     Ok(Value::Range { start: s, end: e, step: st })
 }
 
@@ -172,7 +193,48 @@ fn eval_ternary(
     }
 }
 
-/// Evaluate index access (e.g., arr[0]).
+/// Evaluate a let expression.
+///
+/// Bindings live in a fresh scope that is dropped once the body has
+/// been evaluated.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `assignments`: Bindings, in source order
+/// - `body`: Expression evaluated with the bindings in scope
+fn eval_let(
+    ctx: &mut EvalContext,
+    assignments: &[(String, Expression)],
+    body: &Expression,
+) -> Result<Value, EvalError> {
+    ctx.scope.push();
+    let result = bind_assignments(ctx, assignments).and_then(|_| eval_expr(ctx, body));
+    ctx.scope.pop();
+    result
+}
+
+/// Define `let` bindings in the current scope.
+///
+/// Bindings are evaluated in order, so each one can refer to those
+/// before it: `let (a = 1, b = a + 1)`.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `assignments`: Bindings, in source order
+pub fn bind_assignments(
+    ctx: &mut EvalContext,
+    assignments: &[(String, Expression)],
+) -> Result<(), EvalError> {
+    for (name, value) in assignments {
+        let val = eval_expr(ctx, value)?;
+        ctx.scope.define(name, val);
+    }
+    Ok(())
+}
+
+/// Evaluate index access (e.g., arr[0], name[0]).
 ///
 /// ## Parameters
 ///
@@ -185,14 +247,18 @@ fn eval_index(
     index: &Expression,
 ) -> Result<Value, EvalError> {
     let obj = eval_expr(ctx, object)?;
-    let idx = eval_expr(ctx, index)?.as_number()? as usize;
+    let number = eval_expr(ctx, index)?.as_number()?;
+    // An index outside the list or string is undef; the sign is checked
+    // first, since the cast saturates at 0
+    let idx = (number >= 0.0).then_some(number as usize);
     match obj {
-        Value::List(items) => {
-            items.get(idx).cloned().ok_or_else(|| {
-                EvalError::InvalidArgument(format!("Index {} out of bounds", idx))
-            })
+        Value::List(items) => Ok(idx.and_then(|i| items.get(i).cloned()).unwrap_or(Value::Undef)),
+        // Strings index by character
+        Value::String(s) => {
+            let c = idx.and_then(|i| s.chars().nth(i));
+            Ok(c.map_or(Value::Undef, |c| Value::String(c.to_string())))
         }
-        _ => Err(EvalError::TypeError("Cannot index non-list".to_string())),
+        _ => Err(EvalError::TypeError("Cannot index non-list, non-string".to_string())),
     }
 }
 
@@ -298,9 +364,11 @@ fn eval_unary_op(
 ///
 /// ## Supported Built-in Functions
 ///
-/// - Trigonometric: sin, cos, tan
-/// - Math: abs, sqrt, floor, ceil, round
-/// - List: len
+/// - Math: trigonometry in degrees, rounding, powers, min/max, norm,
+///   cross, len and type tests (see [`super::math`])
+/// - Lists: concat, lookup, search (see [`super::lists`])
+/// - Strings: str, chr, ord (see [`super::strings`])
+/// - Paths: path_length, resample_path, offset_path
 fn eval_function_call(
     ctx: &mut EvalContext,
     name: &str,
@@ -308,79 +376,43 @@ fn eval_function_call(
 ) -> Result<Value, EvalError> {
     // First, check for user-defined functions
     if let Some(func) = ctx.get_function(name).cloned() {
-        return eval_user_function(ctx, &func, args);
+        ctx.check_running()?;
+        let call_args = CallArguments::evaluate(ctx, args)?;
+        ctx.enter_call(name)?;
+        let result = grow_stack(|| eval_user_function(ctx, name.to_string(), func, call_args));
+        ctx.exit_call();
+        return result;
     }
 
-    // Evaluate arguments for built-in functions
-    let arg_values: Vec<_> = args.iter()
-        .filter_map(|a| match a {
-            Argument::Positional(e) => eval_expr(ctx, e).ok(),
-            Argument::Named { value, .. } => eval_expr(ctx, value).ok(),
-        })
-        .collect();
+    // Evaluate arguments for built-in functions; a failed argument is
+    // dropped unless it aborts the evaluation
+    let mut named_args: Vec<(Option<String>, Value)> = Vec::new();
+    for arg in args {
+        let (arg_name, expr) = match arg {
+            Argument::Positional(e) => (None, e),
+            Argument::Named { name, value } => (Some(name.clone()), value),
+        };
+        match eval_expr(ctx, expr) {
+            Ok(value) => named_args.push((arg_name, value)),
+            Err(e) if e.aborts_evaluation() => return Err(e),
+            Err(_) => {}
+        }
+    }
 
     match name {
-        // Trigonometric (angles in degrees)
-        "sin" => {
-            let angle = arg_values.first()
-                .map(|v| v.as_number().unwrap_or(0.0).to_radians().sin())
-                .unwrap_or(0.0);
-            Ok(Value::Number(angle))
-        }
-        "cos" => {
-            let angle = arg_values.first()
-                .map(|v| v.as_number().unwrap_or(0.0).to_radians().cos())
-                .unwrap_or(0.0);
-            Ok(Value::Number(angle))
-        }
-        "tan" => {
-            let angle = arg_values.first()
-                .map(|v| v.as_number().unwrap_or(0.0).to_radians().tan())
-                .unwrap_or(0.0);
-            Ok(Value::Number(angle))
-        }
-        
         // Math functions
-        "abs" => {
-            let val = arg_values.first()
-                .map(|v| v.as_number().unwrap_or(0.0).abs())
-                .unwrap_or(0.0);
-            Ok(Value::Number(val))
-        }
-        "sqrt" => {
-            let val = arg_values.first()
-                .map(|v| v.as_number().unwrap_or(0.0).sqrt())
-                .unwrap_or(0.0);
-            Ok(Value::Number(val))
-        }
-        "floor" => {
-            let val = arg_values.first()
-                .map(|v| v.as_number().unwrap_or(0.0).floor())
-                .unwrap_or(0.0);
-            Ok(Value::Number(val))
-        }
-        "ceil" => {
-            let val = arg_values.first()
-                .map(|v| v.as_number().unwrap_or(0.0).ceil())
-                .unwrap_or(0.0);
-            Ok(Value::Number(val))
-        }
-        "round" => {
-            let val = arg_values.first()
-                .map(|v| v.as_number().unwrap_or(0.0).round())
-                .unwrap_or(0.0);
-            Ok(Value::Number(val))
-        }
-        
+        "rands" => eval_rands(ctx, &named_args),
+        _ if MATH_FUNCTIONS.contains(&name) => Ok(eval_math_function(ctx, name, &named_args)),
+
         // List functions
-        "len" => {
-            match arg_values.first() {
-                Some(Value::List(l)) => Ok(Value::Number(l.len() as f64)),
-                Some(Value::String(s)) => Ok(Value::Number(s.len() as f64)),
-                _ => Ok(Value::Undef),
-            }
-        }
-        
+        _ if LIST_FUNCTIONS.contains(&name) => eval_list_function(ctx, name, &named_args),
+
+        // String functions
+        _ if STRING_FUNCTIONS.contains(&name) => eval_string_function(ctx, name, &named_args),
+
+        // Path functions
+        _ if PATH_FUNCTIONS.contains(&name) => Ok(eval_path_function(ctx, name, &named_args)),
+
         // Unknown function
         _ => {
             ctx.warn(format!("Unknown function: {}", name));
@@ -393,72 +425,93 @@ fn eval_function_call(
 // USER-DEFINED FUNCTIONS
 // =============================================================================
 
+/// Where a function body ends up: a value, or a call in tail position.
+enum Tail {
+    /// The body's value.
+    Value(Value),
+    /// A user function call whose result is the body's result.
+    Call(String, FunctionDef, CallArguments),
+}
+
 /// Evaluate a user-defined function call.
 ///
-/// Creates a new scope with the function parameters bound to argument values,
-/// then evaluates the function body expression.
+/// Binds the parameters in a new scope, then evaluates the body. A call
+/// in tail position (a whole branch of `?:` or the body of a `let`)
+/// replaces the current call instead of nesting inside it, so
+/// accumulator-style recursion runs in constant depth:
 ///
-/// ## Parameters
+/// ```text
+/// function sum(n, acc = 0) = n == 0 ? acc : sum(n - 1, acc + n);
+/// sum(100000);  // 5000050000, one level deep
+/// ```
 ///
-/// - `ctx`: Evaluation context
-/// - `func`: The user-defined function definition
-/// - `args`: Arguments passed to the function
+/// Tail calls count as loop iterations towards the iteration limit, and
+/// after [`MAX_TAIL_CALLS`](crate::limits::MAX_TAIL_CALLS) in a row the
+/// call fails with the recursion limit, so endless tail recursion stops
+/// even with no limits set.
 ///
-/// ## Example
+/// ## Parameters
 ///
-/// ```text
-/// function double(x) = x * 2;
-/// double(5);  // Returns 10
-/// ```
+/// - `ctx`: Evaluation context; the caller has entered the call
+/// - `name`: Function name, for argument warnings
+/// - `func`: The user-defined function definition
+/// - `args`: Arguments, already evaluated
 fn eval_user_function(
     ctx: &mut EvalContext,
-    func: &super::context::FunctionDef,
-    args: &[Argument],
+    mut name: String,
+    mut func: FunctionDef,
+    mut args: CallArguments,
 ) -> Result<Value, EvalError> {
-    // Evaluate all arguments first
-    let mut arg_values: Vec<Value> = Vec::new();
-    let mut named_args: std::collections::HashMap<String, Value> = std::collections::HashMap::new();
+    let mut tail_calls = 0;
+    loop {
+        ctx.scope.push();
+        let mut scopes = 1;
+        let outcome = bind_parameters(ctx, &name, &func.params, std::mem::take(&mut args))
+            .and_then(|_| eval_tail(ctx, &func.body, &mut scopes));
+        for _ in 0..scopes {
+            ctx.scope.pop();
+        }
 
-    for arg in args {
-        match arg {
-            Argument::Positional(e) => {
-                arg_values.push(eval_expr(ctx, e)?);
-            }
-            Argument::Named { name, value } => {
-                named_args.insert(name.clone(), eval_expr(ctx, value)?);
+        match outcome? {
+            Tail::Value(value) => return Ok(value),
+            Tail::Call(next_name, next, next_args) => {
+                tail_calls += 1;
+                ctx.check_tail_calls(tail_calls)?;
+                ctx.count_iterations(1)?;
+                ctx.check_running()?;
+                if let Some(frame) = ctx.call_stack.last_mut() {
+                    frame.clone_from(&next_name);
+                }
+                name = next_name;
+                func = next;
+                args = next_args;
             }
         }
     }
+}
 
-    // Create a new scope for function evaluation
-    ctx.scope.push();
-
-    // Bind parameters to arguments
-    for (i, param) in func.params.iter().enumerate() {
-        // Check for named argument first
-        let value = if let Some(v) = named_args.get(&param.name) {
-            v.clone()
-        } else if i < arg_values.len() {
-            // Use positional argument
-            arg_values[i].clone()
-        } else if let Some(default) = &param.default {
-            // Use default value
-            eval_expr(ctx, default)?
-        } else {
-            // No value provided
-            Value::Undef
-        };
-
-        ctx.scope.define(&param.name, value);
+/// Evaluate a function body up to a user function call in tail position.
+///
+/// `let` scopes opened on the way are counted in `scopes` for the caller
+/// to close.
+fn eval_tail(ctx: &mut EvalContext, expr: &Expression, scopes: &mut usize) -> Result<Tail, EvalError> {
+    match expr {
+        Expression::Ternary { condition, then_expr, else_expr } => {
+            let branch = if eval_expr(ctx, condition)?.as_boolean() { then_expr } else { else_expr };
+            eval_tail(ctx, branch, scopes)
+        }
+        Expression::Let { assignments, body } => {
+            ctx.scope.push();
+            *scopes += 1;
+            bind_assignments(ctx, assignments)?;
+            eval_tail(ctx, body, scopes)
+        }
+        Expression::FunctionCall { name, args } => match ctx.get_function(name).cloned() {
+            Some(func) => Ok(Tail::Call(name.clone(), func, CallArguments::evaluate(ctx, args)?)),
+            None => eval_expr(ctx, expr).map(Tail::Value),
+        },
+        _ => eval_expr(ctx, expr).map(Tail::Value),
     }
-
-    // Evaluate function body
-    let result = eval_expr(ctx, &func.body);
-
-    // Pop function scope
-    ctx.scope.pop();
-
-    result
 }
 
 // =============================================================================
@@ -532,4 +585,98 @@ mod tests {
         let result = eval_expr(&mut ctx, &Expression::Identifier("x".to_string())).unwrap();
         assert_eq!(result, Value::Number(10.0));
     }
+
+    #[test]
+    fn test_eval_let_scoped() {
+        let mut ctx = ctx();
+        let expr = Expression::Let {
+            assignments: vec![("a".to_string(), Expression::Number(3.0))],
+            body: Box::new(Expression::Identifier("a".to_string())),
+        };
+        assert_eq!(eval_expr(&mut ctx, &expr).unwrap(), Value::Number(3.0));
+        assert!(ctx.scope.get("a").is_none());
+    }
+
+    /// Test descending ranges need a step; `[b:e]` with `b > e` is swapped.
+    #[test]
+    fn test_eval_range_direction() {
+        let mut ctx = ctx();
+        let range = |start: f64, end: f64, step: Option<f64>| Expression::Range {
+            start: Box::new(Expression::Number(start)),
+            end: Box::new(Expression::Number(end)),
+            step: step.map(|s| Box::new(Expression::Number(s))),
+        };
+
+        let down = eval_expr(&mut ctx, &range(10.0, 0.0, Some(-1.0))).unwrap();
+        assert_eq!(down.iteration_count(), 11);
+        assert!(ctx.warnings.is_empty());
+
+        let swapped = eval_expr(&mut ctx, &range(10.0, 0.0, None)).unwrap();
+        assert_eq!(swapped, Value::Range { start: 0.0, end: 10.0, step: None });
+        assert_eq!(ctx.warnings.len(), 1);
+        assert!(ctx.warnings[0].contains("[10:-1:0]"));
+        assert_eq!(ctx.diagnostics[0].kind, DiagnosticKind::Deprecated);
+    }
+
+    /// Evaluate `source` and return `x`, or the error.
+    fn run(source: &str, max_depth: usize) -> Result<Value, EvalError> {
+        let ast = openscad_ast::parse(source).unwrap();
+        let mut ctx = ctx();
+        ctx.max_recursion_depth = max_depth;
+        super::super::evaluate_statements(&mut ctx, &ast.statements)?;
+        assert!(ctx.call_stack.is_empty());
+        Ok(ctx.scope.get("x").cloned().unwrap_or(Value::Undef))
+    }
+
+    /// Test plain recursion, including mutual recursion.
+    #[test]
+    fn test_recursive_functions() {
+        let fact = "function fact(n) = n <= 1 ? 1 : n * fact(n - 1);";
+        assert_eq!(run(&format!("{} x = fact(10);", fact), 100).unwrap(), Value::Number(3628800.0));
+
+        let parity = "function even(n) = n == 0 ? true : !odd(n - 1); function odd(n) = n == 0 ? false : !even(n - 1);";
+        assert_eq!(run(&format!("{} x = even(40);", parity), 100).unwrap(), Value::Boolean(true));
+    }
+
+    /// Test tail calls through `?:` and `let` do not nest.
+    #[test]
+    fn test_tail_calls() {
+        let sum = "function sum(n, acc = 0) = n == 0 ? acc : let (m = n - 1) sum(m, acc + n);";
+        assert_eq!(run(&format!("{} x = sum(100000);", sum), 10).unwrap(), Value::Number(5000050000.0));
+    }
+
+    /// Test deep recursion stops at the limit and reports the chain.
+    #[test]
+    fn test_recursion_limit() {
+        let source = "function down(n) = n == 0 ? 0 : 1 + down(n - 1);\nx = down(20000);";
+        assert_eq!(run(source, 20001).unwrap(), Value::Number(20000.0));
+
+        let Err(EvalError::RecursionLimit(report)) = run(source, 5000) else {
+            panic!("expected the recursion limit");
+        };
+        assert_eq!(report.chain.len(), 5000);
+        assert_eq!(report.to_string(), "assignment to x at 2:1 exceeded 5,000 nested calls: down() ×5,000");
+
+        // Arguments of builtins do not swallow it
+        let nested = "function down(n) = n == 0 ? 0 : 1 + down(n - 1);\nx = str(down(100));";
+        assert!(matches!(run(nested, 10), Err(EvalError::RecursionLimit(_))));
+
+        // Modules recurse too
+        let Err(EvalError::RecursionLimit(report)) = run("module tree(n) { if (n > 0) tree(n - 1); }\ntree(100);", 50) else {
+            panic!("expected the recursion limit");
+        };
+        assert_eq!(report.construct.unwrap().description, "tree() call");
+    }
+
+    /// Test endless tail recursion stops at the tail call limit without
+    /// any limits set.
+    #[test]
+    fn test_tail_call_limit() {
+        let source = "function g(n) = g(n + 1);\nfunction f() = 1 + g(0);\nx = f();";
+        let Err(EvalError::RecursionLimit(report)) = run(source, 100) else {
+            panic!("expected the tail call limit");
+        };
+        assert!(report.tail_calls);
+        assert_eq!(report.to_string(), "assignment to x at 3:1 exceeded 1,000,000 tail calls: f() → g()");
+    }
 }
@@ -0,0 +1,570 @@
+//! # Extension Evaluators
+//!
+//! Evaluators for modules that OpenSCAD does not have. A user-defined
+//! module with the same name still takes precedence, so existing files
+//! that define their own `smooth()` keep working.
+//!
+//! ## Modules
+//!
+//! - `smooth(iterations, lambda)` - Taubin smoothing of the child mesh
+//! - `quality(level, simplify)` - Tessellation scope for the children
+//!
+//! ## Primitives and Operations
+//!
+//! Only with [`EvalOptions::extensions`](crate::EvalOptions::extensions),
+//! for scripts written against this engine rather than OpenSCAD; without
+//! it they are unknown modules, as in OpenSCAD:
+//!
+//! - `torus(r1, r2)` - Ring around the Z axis
+//! - `prism(n, r, h, center)` - Regular `n`-sided prism
+//! - `rounded_cube(size, r, center)` - Box with rounded edges
+//! - `refine(n, length, smooth)` - Subdivision of the child mesh
+//! - `warp_twist(angle)`, `warp_taper(scale)`, `warp_bend(angle)` -
+//!   Deformation of the child mesh
+//!
+//! ## Example
+//!
+//! ```text
+//! smooth(10) sphere(10, $fn = 12);
+//! smooth(iterations = 5, lambda = 0.6, feature_angle = 40) cube(10);
+//! quality(0.25) { body(); quality(8) thread(); }
+//! torus(10, 2);
+//! refine(3, smooth = true) cube(10, center = true);
+//! warp_twist(90) refine(length = 1) cube([4, 4, 20]);
+//! ```
+
+use crate::diagnostic::DiagnosticKind;
+use crate::error::EvalError;
+use crate::geometry::{Deformation, GeometryNode};
+use crate::value::Value;
+use openscad_ast::{Argument, Statement};
+
+use super::arguments::ArgumentResolver;
+use super::context::{EvalContext, evaluate_statements};
+use super::primitives::{clamp_sizes, degenerate, radius_of};
+
+/// Taubin pass-band frequency used to derive `mu` from `lambda`.
+const PASS_BAND: f64 = 0.1;
+
+// =============================================================================
+// SMOOTH
+// =============================================================================
+
+/// Evaluate smooth() call.
+///
+/// Each iteration is a shrinking Laplacian pass with weight `lambda`
+/// followed by an inflating pass with weight `mu`, which smooths without
+/// the shrinkage of plain Laplacian smoothing. If `mu` is not given it is
+/// derived from `lambda` with a pass-band of 0.1 (`1/lambda + 1/mu = 0.1`).
+///
+/// ## Signature
+///
+/// ```text
+/// smooth(iterations = 10, lambda = 0.5, mu, preserve_boundary = true, feature_angle) child;
+/// ```
+///
+/// ## Parameters
+///
+/// - `iterations`: Number of shrink/inflate pass pairs
+/// - `lambda`: Shrinking weight, in (0, 1)
+/// - `mu`: Inflating weight, negative
+/// - `preserve_boundary`: Keep vertices on open edges fixed
+/// - `feature_angle`: Keep vertices on edges whose faces meet at more
+///   than this many degrees fixed; `undef` smooths everything
+pub fn eval_smooth(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("smooth", &["iterations", "lambda"])
+        .named(&["mu", "preserve_boundary", "feature_angle"])
+        .evaluate(ctx, args)?;
+    let iterations = args.get("iterations").map(Value::as_number).transpose()?.unwrap_or(10.0);
+    let lambda = args.get("lambda").map(Value::as_number).transpose()?.unwrap_or(0.5);
+    let mu = args.get("mu").map(Value::as_number).transpose()?;
+    let preserve_boundary = args.get("preserve_boundary").is_none_or(Value::as_boolean);
+    let feature_angle = args.get("feature_angle").map(Value::as_number).transpose()?;
+
+    if !(lambda > 0.0 && lambda < 1.0) {
+        return Err(EvalError::InvalidArgument(format!("smooth: lambda must be between 0 and 1, got {}", lambda)));
+    }
+    let mu = mu.unwrap_or(1.0 / (PASS_BAND - 1.0 / lambda));
+    if mu >= 0.0 {
+        return Err(EvalError::InvalidArgument(format!("smooth: mu must be negative, got {}", mu)));
+    }
+    if iterations < 0.0 {
+        ctx.warn(format!("smooth: negative iterations {}, using 0", iterations));
+    }
+
+    let child = args.with_specials(ctx, |ctx| evaluate_statements(ctx, children))?;
+    Ok(GeometryNode::Smooth {
+        iterations: iterations.max(0.0) as u32,
+        lambda,
+        mu,
+        preserve_boundary,
+        feature_angle,
+        child: Box::new(child),
+    })
+}
+
+// =============================================================================
+// QUALITY
+// =============================================================================
+
+/// Evaluate quality() call.
+///
+/// Multiplies the segment count of every curved primitive in the children
+/// by `level`, so one detailed feature can stay crisp while the rest of a
+/// model renders coarse. Nested scopes multiply. With `simplify`, the
+/// mesher merges coplanar faces of the children; by default coarse scopes
+/// (effective level below 1) simplify and detailed ones do not.
+///
+/// ## Signature
+///
+/// ```text
+/// quality(level = 1, simplify) children;
+/// ```
+///
+/// ## Parameters
+///
+/// - `level`: Segment multiplier, positive
+/// - `simplify`: Merge coplanar faces of the children
+pub fn eval_quality(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("quality", &["level", "simplify"]).evaluate(ctx, args)?;
+    let level = args.get("level").map(Value::as_number).transpose()?.unwrap_or(1.0);
+    let simplify = args.get("simplify").map(Value::as_boolean);
+
+    if !(level > 0.0 && level.is_finite()) {
+        return Err(EvalError::InvalidArgument(format!("quality: level must be positive, got {}", level)));
+    }
+
+    let outer = ctx.quality;
+    ctx.quality = outer * level;
+    let child = args.with_specials(ctx, |ctx| evaluate_statements(ctx, children));
+    ctx.quality = outer;
+
+    let level = outer * level;
+    Ok(GeometryNode::Quality {
+        level,
+        simplify: simplify.unwrap_or(level < 1.0),
+        child: Box::new(child?),
+    })
+}
+
+// =============================================================================
+// REFINE
+// =============================================================================
+
+/// Evaluate refine() call.
+///
+/// Flat refinement splits every edge into `n` pieces and then bisects
+/// edges longer than `length`, leaving the shape as it is. With `smooth`,
+/// Loop subdivision rounds the shape instead, halving edges once per
+/// level: `n` rounds up to a power of two and levels continue, up to a
+/// limit, until edges are shorter than `length`.
+///
+/// ## Signature
+///
+/// ```text
+/// refine(n = 2, length, smooth = false) child;
+/// ```
+///
+/// ## Parameters
+///
+/// - `n`: Pieces per edge, at least 1; 1 when only `length` is given
+/// - `length`: Longest edge wanted, positive
+/// - `smooth`: Loop subdivision instead of flat splitting
+pub fn eval_refine(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("refine", &["n", "length", "smooth"]).evaluate(ctx, args)?;
+    let max_length = args.get("length").map(Value::as_number).transpose()?;
+    let pieces = args.get("n").map(Value::as_number).transpose()?
+        .unwrap_or(if max_length.is_some() { 1.0 } else { 2.0 });
+    let smooth = args.get("smooth").is_some_and(Value::as_boolean);
+    if pieces < 1.0 || !pieces.is_finite() {
+        return Err(EvalError::InvalidArgument(format!("refine: n must be at least 1, got {}", pieces)));
+    }
+    if let Some(length) = max_length.filter(|length| *length <= 0.0 || length.is_nan()) {
+        return Err(EvalError::InvalidArgument(format!("refine: length must be positive, got {}", length)));
+    }
+
+    let child = evaluate_statements(ctx, children)?;
+    Ok(GeometryNode::Refine {
+        pieces: pieces as u32,
+        max_length,
+        smooth,
+        child: Box::new(child),
+    })
+}
+
+// =============================================================================
+// WARP
+// =============================================================================
+
+/// Evaluate warp_twist() call.
+///
+/// Rotates the child about the Z axis, counter-clockwise seen from above,
+/// by nothing at its bottom up to `angle` at its top. Only vertices move,
+/// so refine the child first for smooth results.
+///
+/// ## Signature
+///
+/// ```text
+/// warp_twist(angle = 0) child;
+/// ```
+pub fn eval_warp_twist(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let degrees = warp_angle(ctx, "warp_twist", args)?;
+    warp(ctx, Deformation::Twist { degrees }, children)
+}
+
+/// Evaluate warp_taper() call.
+///
+/// Scales the child in x and y about the Z axis, by 1 at its bottom up to
+/// `scale` at its top, as `linear_extrude()` scales its outline.
+///
+/// ## Signature
+///
+/// ```text
+/// warp_taper(scale = 1) child;
+/// ```
+///
+/// ## Parameters
+///
+/// - `scale`: Scale at the top, a number or `[x, y]`, not negative
+pub fn eval_warp_taper(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("warp_taper", &["scale"]).evaluate(ctx, args)?;
+    let scale = args.get("scale").map(Value::as_vec2).transpose()?.unwrap_or([1.0, 1.0]);
+    if scale.iter().any(|s| *s < 0.0 || !s.is_finite()) {
+        return Err(EvalError::InvalidArgument(format!("warp_taper: scale must not be negative, got {:?}", scale)));
+    }
+    warp(ctx, Deformation::Taper { scale }, children)
+}
+
+/// Evaluate warp_bend() call.
+///
+/// Bends the child along x into an arc turning through `angle`, toward +Z
+/// for positive angles. The plane `z = 0` keeps its length and the end at
+/// the lowest x stays in place.
+///
+/// ## Signature
+///
+/// ```text
+/// warp_bend(angle = 0) child;
+/// ```
+pub fn eval_warp_bend(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let degrees = warp_angle(ctx, "warp_bend", args)?;
+    warp(ctx, Deformation::Bend { degrees }, children)
+}
+
+/// The `angle` argument of `module`, in degrees.
+fn warp_angle(ctx: &mut EvalContext, module: &str, args: &[Argument]) -> Result<f64, EvalError> {
+    let args = ArgumentResolver::new(module, &["angle"]).evaluate(ctx, args)?;
+    let degrees = args.get("angle").map(Value::as_number).transpose()?.unwrap_or(0.0);
+    if !degrees.is_finite() {
+        return Err(EvalError::InvalidArgument(format!("{}: angle must be finite, got {}", module, degrees)));
+    }
+    Ok(degrees)
+}
+
+/// A warp node around the evaluated `children`.
+fn warp(ctx: &mut EvalContext, deformation: Deformation, children: &[Statement]) -> Result<GeometryNode, EvalError> {
+    let child = evaluate_statements(ctx, children)?;
+    Ok(GeometryNode::Warp { deformation, child: Box::new(child) })
+}
+
+// =============================================================================
+// PRIMITIVES
+// =============================================================================
+
+/// Evaluate torus() call.
+///
+/// ## Signature
+///
+/// ```text
+/// torus(r1, r2);
+/// torus(d1 = 20, d2 = 4);
+/// ```
+///
+/// ## Parameters
+///
+/// - `r1`: Distance from the Z axis to the middle of the tube
+/// - `r2`: Radius of the tube, less than `r1`
+pub fn eval_torus(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("torus", &["r1", "r2"]).named(&["d1", "d2"]).evaluate(ctx, args)?;
+    let major = radius_of(&args, "r1", "d1")?.unwrap_or(2.0);
+    let minor = radius_of(&args, "r2", "d2")?.unwrap_or(1.0);
+    let [major, minor] = clamp_sizes(ctx, "torus", [major, minor]);
+    if minor == 0.0 {
+        return Ok(degenerate(ctx, "torus"));
+    }
+    if minor >= major {
+        return Err(EvalError::InvalidArgument(format!("torus: r2 must be less than r1, got r1 = {}, r2 = {}", major, minor)));
+    }
+
+    let (fn_, fn_tube) = args.with_specials(ctx, |ctx| (ctx.calculate_fragments(major + minor), ctx.calculate_fragments(minor)));
+    Ok(GeometryNode::Torus { major, minor, fn_, fn_tube })
+}
+
+/// Evaluate prism() call.
+///
+/// A cylinder with exactly `n` sides: `$fn`, `$fa`, `$fs` and `quality()`
+/// do not change it.
+///
+/// ## Signature
+///
+/// ```text
+/// prism(n, r, h, center);
+/// prism(6, d = 10, h = 3);
+/// ```
+///
+/// ## Parameters
+///
+/// - `n`: Number of sides, at least 3
+/// - `r`: Radius of the corners
+/// - `h`: Height
+/// - `center`: Center along Z
+pub fn eval_prism(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("prism", &["n", "r", "h", "center"]).named(&["d"]).evaluate(ctx, args)?;
+    let sides = args.get("n").map(Value::as_number).transpose()?.unwrap_or(6.0);
+    if !(sides >= 3.0 && sides.is_finite()) {
+        return Err(EvalError::InvalidArgument(format!("prism: n must be at least 3, got {}", sides)));
+    }
+    let radius = radius_of(&args, "r", "d")?.unwrap_or(1.0);
+    let height = args.get("h").map(Value::as_number).transpose()?.unwrap_or(1.0);
+    let center = args.get("center").is_some_and(Value::as_boolean);
+    let [radius, height] = clamp_sizes(ctx, "prism", [radius, height]);
+    if radius == 0.0 || height == 0.0 {
+        return Ok(degenerate(ctx, "prism"));
+    }
+
+    Ok(GeometryNode::Cylinder { height, radius1: radius, radius2: radius, center, fn_: sides as u32 })
+}
+
+/// Evaluate rounded_cube() call.
+///
+/// ## Signature
+///
+/// ```text
+/// rounded_cube(size, r, center);
+/// rounded_cube([20, 10, 5], r = 1, center = true);
+/// ```
+///
+/// ## Parameters
+///
+/// - `size`: Number or [x, y, z], as for `cube()`
+/// - `r`: Rounding radius, clamped to half the smallest size
+/// - `center`: Center at the origin
+pub fn eval_rounded_cube(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("rounded_cube", &["size", "r", "center"]).evaluate(ctx, args)?;
+    let size = args.get("size").map(Value::as_vec3).transpose()?.unwrap_or([1.0, 1.0, 1.0]);
+    let radius = args.get("r").map(Value::as_number).transpose()?.unwrap_or(0.1);
+    let center = args.get("center").is_some_and(Value::as_boolean);
+    let size = clamp_sizes(ctx, "rounded_cube", size);
+    if size.contains(&0.0) {
+        return Ok(degenerate(ctx, "rounded_cube"));
+    }
+    let [radius] = clamp_sizes(ctx, "rounded_cube", [radius]);
+    let limit = size[0].min(size[1]).min(size[2]) / 2.0;
+    if radius > limit {
+        ctx.diagnose(DiagnosticKind::Degenerate, format!("rounded_cube(): r = {} clamped to {}", radius, limit));
+    }
+    let radius = radius.min(limit);
+    if radius == 0.0 {
+        return Ok(GeometryNode::Cube { size, center });
+    }
+
+    let fn_ = args.with_specials(ctx, |ctx| ctx.calculate_fragments(radius));
+    Ok(GeometryNode::RoundedCube { size, radius, center, fn_ })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::{evaluate, Deformation, GeometryNode};
+
+    /// Test defaults and the derived mu.
+    #[test]
+    fn test_smooth_defaults() {
+        let result = evaluate("smooth() cube(10);").unwrap();
+        match result.geometry {
+            GeometryNode::Smooth { iterations, lambda, mu, preserve_boundary, feature_angle, .. } => {
+                assert_eq!(iterations, 10);
+                assert_eq!(lambda, 0.5);
+                assert!((mu - 1.0 / (0.1 - 2.0)).abs() < 1e-12);
+                assert!(preserve_boundary);
+                assert_eq!(feature_angle, None);
+            }
+            other => panic!("Expected Smooth, got {:?}", other),
+        }
+    }
+
+    /// Test positional and named arguments.
+    #[test]
+    fn test_smooth_arguments() {
+        let result = evaluate("smooth(3, 0.3, feature_angle = 45, preserve_boundary = false) cube(10);").unwrap();
+        match result.geometry {
+            GeometryNode::Smooth { iterations, lambda, preserve_boundary, feature_angle, .. } => {
+                assert_eq!(iterations, 3);
+                assert_eq!(lambda, 0.3);
+                assert!(!preserve_boundary);
+                assert_eq!(feature_angle, Some(45.0));
+            }
+            other => panic!("Expected Smooth, got {:?}", other),
+        }
+    }
+
+    /// Test named arguments do not shift the positional ones.
+    #[test]
+    fn test_smooth_reordered_arguments() {
+        let result = evaluate("smooth(mu = -0.6, 4, lambda = 0.2) cube(10);").unwrap();
+        match result.geometry {
+            GeometryNode::Smooth { iterations, lambda, mu, .. } => {
+                assert_eq!(iterations, 4);
+                assert_eq!(lambda, 0.2);
+                assert_eq!(mu, -0.6);
+            }
+            other => panic!("Expected Smooth, got {:?}", other),
+        }
+        assert!(result.warnings.is_empty());
+    }
+
+    /// Test quality scales segment counts inside the scope only.
+    #[test]
+    fn test_quality_scales_segments() {
+        let result = evaluate("quality(0.5) { sphere(5, $fn = 20); quality(3) circle(5, $fn = 20); } sphere(5, $fn = 20);").unwrap();
+        let GeometryNode::Group { children } = result.geometry else {
+            panic!("Expected Group, got {:?}", result.geometry);
+        };
+        match &children[0] {
+            GeometryNode::Quality { level, simplify, child } => {
+                assert_eq!(*level, 0.5);
+                assert!(*simplify);
+                let GeometryNode::Group { children: inner } = child.as_ref() else {
+                    panic!("Expected Group, got {:?}", child);
+                };
+                assert!(matches!(inner[0], GeometryNode::Sphere { fn_: 10, .. }));
+                assert!(matches!(&inner[1], GeometryNode::Quality { level, simplify: false, child }
+                    if *level == 1.5 && matches!(child.as_ref(), GeometryNode::Circle { fn_: 30, .. })));
+            }
+            other => panic!("Expected Quality, got {:?}", other),
+        }
+        assert!(matches!(children[1], GeometryNode::Sphere { fn_: 20, .. }));
+    }
+
+    /// Test simplify can be set explicitly and level must be positive.
+    #[test]
+    fn test_quality_arguments() {
+        let result = evaluate("quality(4, simplify = true) cube(1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Quality { simplify: true, .. }));
+        assert!(evaluate("quality(0) cube(1);").is_err());
+
+        let result = evaluate("quality(simplify = true, 2) cube(1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Quality { level: 2.0, simplify: true, .. }));
+        let result = evaluate("quality(2, detail = 3) cube(1);").unwrap();
+        assert_eq!(result.warnings, ["quality(): variable detail not specified as parameter"]);
+    }
+
+    /// Test the render quality applies under `quality()` scopes and must be positive.
+    #[test]
+    fn test_render_quality() {
+        use crate::{evaluate_with_options, EvalOptions, EvalParams, QualityOptions};
+
+        let final_render = |render| EvalOptions {
+            quality: QualityOptions { preview: 1.0, render },
+            params: EvalParams { preview: false, ..EvalParams::default() },
+            ..EvalOptions::default()
+        };
+        let result = evaluate_with_options("quality(0.5) circle(5, $fn = 20);", &final_render(4.0)).unwrap();
+        assert!(matches!(&result.geometry, GeometryNode::Quality { level, child, .. }
+            if *level == 2.0 && matches!(child.as_ref(), GeometryNode::Circle { fn_: 40, .. })));
+        assert!(evaluate_with_options("cube(1);", &final_render(0.0)).is_err());
+        assert!(evaluate_with_options("cube(1);", &final_render(f64::NAN)).is_err());
+    }
+
+    /// Evaluate with the extension primitives enabled.
+    fn extended(source: &str) -> Result<crate::EvaluatedAst, crate::EvalError> {
+        let options = crate::EvalOptions { extensions: true, ..crate::EvalOptions::default() };
+        crate::evaluate_with_options(source, &options)
+    }
+
+    /// Test the primitives need the flag and take their arguments.
+    #[test]
+    fn test_primitives() {
+        assert!(evaluate("torus(10, 2);").unwrap().geometry.is_empty());
+        assert!(matches!(extended("torus(d1 = 20, r2 = 2, $fn = 12);").unwrap().geometry,
+            GeometryNode::Torus { major: 10.0, minor: 2.0, fn_: 12, fn_tube: 12 }));
+        assert!(extended("torus(2, 3);").is_err());
+        assert!(matches!(extended("quality(4) prism(5, 2, 3);").unwrap().geometry,
+            GeometryNode::Quality { ref child, .. } if matches!(**child, GeometryNode::Cylinder { fn_: 5, radius1: 2.0, height: 3.0, .. })));
+        assert!(extended("prism(2);").is_err());
+        assert!(matches!(extended("rounded_cube([4, 2, 6], r = 5);").unwrap().geometry,
+            GeometryNode::RoundedCube { size: [4.0, 2.0, 6.0], radius: 1.0, .. }));
+        assert!(matches!(extended("rounded_cube(3, r = 0);").unwrap().geometry, GeometryNode::Cube { .. }));
+    }
+
+    /// Test a user module of the same name wins over the extension.
+    #[test]
+    fn test_user_module_wins() {
+        let result = extended("module torus(a, b) { cube(a); } torus(3, 1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Cube { size: [3.0, 3.0, 3.0], .. }));
+    }
+
+    /// Test refine needs the flag, takes its arguments and checks them.
+    #[test]
+    fn test_refine() {
+        assert!(evaluate("refine(3) cube(1);").unwrap().geometry.is_empty());
+        assert!(matches!(extended("refine() cube(1);").unwrap().geometry,
+            GeometryNode::Refine { pieces: 2, max_length: None, smooth: false, .. }));
+        assert!(matches!(extended("refine(length = 0.5) cube(1);").unwrap().geometry,
+            GeometryNode::Refine { pieces: 1, max_length: Some(0.5), .. }));
+        assert!(matches!(extended("refine(4, smooth = true) cube(1);").unwrap().geometry,
+            GeometryNode::Refine { pieces: 4, smooth: true, ref child, .. } if matches!(**child, GeometryNode::Cube { .. })));
+        assert!(extended("refine(0) cube(1);").is_err());
+        assert!(extended("refine(length = -1) cube(1);").is_err());
+    }
+
+    /// Test the warp modules need the flag and build their deformations.
+    #[test]
+    fn test_warp() {
+        assert!(evaluate("warp_twist(90) cube(1);").unwrap().geometry.is_empty());
+        assert!(matches!(extended("warp_twist(90) cube(1);").unwrap().geometry,
+            GeometryNode::Warp { deformation: Deformation::Twist { degrees }, .. } if degrees == 90.0));
+        assert!(matches!(extended("warp_taper([0.5, 2]) cube(1);").unwrap().geometry,
+            GeometryNode::Warp { deformation: Deformation::Taper { scale: [0.5, 2.0] }, .. }));
+        assert!(matches!(extended("warp_taper(0) cube(1);").unwrap().geometry,
+            GeometryNode::Warp { deformation: Deformation::Taper { scale: [0.0, 0.0] }, .. }));
+        assert!(matches!(extended("warp_bend(angle = -45) cube(1);").unwrap().geometry,
+            GeometryNode::Warp { deformation: Deformation::Bend { degrees }, .. } if degrees == -45.0));
+        assert!(extended("warp_taper(-1) cube(1);").is_err());
+        assert!(extended("warp_bend(1 / 0) cube(1);").is_err());
+    }
+
+    /// Test invalid weights are rejected.
+    #[test]
+    fn test_smooth_invalid() {
+        assert!(evaluate("smooth(lambda = 1.5) cube(10);").is_err());
+        assert!(evaluate("smooth(mu = 0.5) cube(10);").is_err());
+    }
+}
@@ -4,8 +4,11 @@
 //!
 //! ## Operations
 //!
-//! - `linear_extrude(height, twist, scale, slices, center)` - Extrude 2D shape along Z
-//! - `rotate_extrude(angle, $fn)` - Rotate 2D shape around Z axis
+//! - `linear_extrude(height, center, convexity, twist, slices, scale)` - Extrude 2D shape along Z
+//! - `rotate_extrude(angle, convexity)` - Rotate 2D shape around Z axis
+//!
+//! Arguments bind by OpenSCAD's rules (see [`arguments`](super::arguments)),
+//! and special variables passed to an extrusion apply to its children.
 //!
 //! ## Example
 //!
@@ -18,8 +21,8 @@ use crate::geometry::GeometryNode;
 use crate::value::Value;
 use openscad_ast::{Argument, Statement};
 
+use super::arguments::ArgumentResolver;
 use super::context::{EvalContext, evaluate_statements};
-use super::expressions::eval_expr;
 
 // =============================================================================
 // EXTRUSIONS
@@ -29,6 +32,10 @@ use super::expressions::eval_expr;
 ///
 /// Extrudes a 2D shape along the Z axis.
 ///
+/// Without `slices`, a twisted extrusion gets one slice per fragment of
+/// the twist angle (from `$fn`, or `$fa` if that is 0), so twisted walls
+/// are as smooth as a circle at the same settings.
+///
 /// ## OpenSCAD Signature
 ///
 /// ```text
@@ -52,29 +59,26 @@ pub fn eval_linear_extrude(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let mut height = 1.0;
-    let mut twist = 0.0;
-    let mut scale = [1.0, 1.0];
-    let mut slices = 1;
-    let mut center = false;
-
-    for arg in args {
-        match arg {
-            Argument::Positional(expr) => {
-                height = eval_expr(ctx, expr)?.as_number()?;
+    let args = ArgumentResolver::new("linear_extrude", &["height", "center", "convexity", "twist", "slices", "scale"])
+        .evaluate(ctx, args)?;
+    let height = args.get("height").map(Value::as_number).transpose()?.unwrap_or(1.0);
+    let center = args.get("center").is_some_and(Value::as_boolean);
+    let twist = args.get("twist").map(Value::as_number).transpose()?.unwrap_or(0.0);
+    let slices = args.get("slices").map(Value::as_number).transpose()?.map(|n| n.max(1.0) as u32);
+    let scale = args.get("scale").map(Value::as_vec2).transpose()?.unwrap_or([1.0, 1.0]);
+
+    let (slices, child) = args.with_specials(ctx, |ctx| {
+        let slices = slices.unwrap_or_else(|| {
+            if twist == 0.0 {
+                return 1;
             }
-            Argument::Named { name, value } => match name.as_str() {
-                "height" => height = eval_expr(ctx, value)?.as_number()?,
-                "twist" => twist = eval_expr(ctx, value)?.as_number()?,
-                "scale" => scale = eval_expr(ctx, value)?.as_vec2()?,
-                "slices" => slices = eval_expr(ctx, value)?.as_number()? as u32,
-                "center" => center = eval_expr(ctx, value)?.as_boolean(),
-                _ => {}
-            },
-        }
-    }
-
-    let child = evaluate_statements(ctx, children)?;
+            let fn_ = ctx.scope.fn_value();
+            let fragments = if fn_ > 0 { fn_ } else { (360.0 / ctx.scope.fa_value()).ceil() as u32 };
+            let fragments = ctx.scale_fragments(fragments);
+            ((fragments as f64 * twist.abs() / 360.0).ceil() as u32).max(1)
+        });
+        evaluate_statements(ctx, children).map(|child| (slices, child))
+    })?;
     Ok(GeometryNode::LinearExtrude {
         height,
         twist,
@@ -112,23 +116,14 @@ pub fn eval_rotate_extrude(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let mut angle = 360.0;
-
-    for arg in args {
-        if let Argument::Named { name, value } = arg {
-            match name.as_str() {
-                "angle" => angle = eval_expr(ctx, value)?.as_number()?,
-                "$fn" => {
-                    let fn_val = eval_expr(ctx, value)?.as_number()?;
-                    ctx.scope.define("$fn", Value::Number(fn_val));
-                }
-                _ => {}
-            }
-        }
-    }
-
-    let fn_ = ctx.scope.fn_value().max(3);
-    let child = evaluate_statements(ctx, children)?;
+    let args = ArgumentResolver::new("rotate_extrude", &["angle", "convexity"]).evaluate(ctx, args)?;
+    let angle = args.get("angle").map(Value::as_number).transpose()?.unwrap_or(360.0);
+
+    // `$fn` and the like apply to the children too, and only to them
+    let (fn_, child) = args.with_specials(ctx, |ctx| {
+        let fn_ = ctx.scale_fragments(ctx.scope.fn_value());
+        evaluate_statements(ctx, children).map(|child| (fn_, child))
+    })?;
     Ok(GeometryNode::RotateExtrude {
         angle,
         fn_,
@@ -181,6 +176,16 @@ mod tests {
         }
     }
 
+    /// Test twist picks slices from $fn unless slices is given.
+    #[test]
+    fn test_eval_linear_extrude_twist_slices() {
+        let result = crate::evaluate("$fn = 16; linear_extrude(10, twist = 90) square(1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::LinearExtrude { slices: 4, .. }));
+
+        let result = crate::evaluate("linear_extrude(10, twist = 90, slices = 7) square(1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::LinearExtrude { slices: 7, .. }));
+    }
+
     #[test]
     fn test_eval_rotate_extrude_default() {
         let mut ctx = ctx();
@@ -188,7 +193,8 @@ mod tests {
         match node {
             GeometryNode::RotateExtrude { angle, fn_, .. } => {
                 assert_eq!(angle, 360.0);
-                assert!(fn_ >= 3);
+                // Unset, so meshing falls back to $fa and $fs
+                assert_eq!(fn_, 0);
             }
             _ => panic!("Expected RotateExtrude"),
         }
@@ -209,4 +215,26 @@ mod tests {
             _ => panic!("Expected RotateExtrude"),
         }
     }
+
+    /// Test arguments bind by position in OpenSCAD's order or by name in
+    /// any order, and special variables stay inside the call.
+    #[test]
+    fn test_extrude_arguments() {
+        let result = crate::evaluate("linear_extrude(5, true, 10, 90, 3, 2) square(1);").unwrap();
+        assert!(matches!(
+            result.geometry,
+            GeometryNode::LinearExtrude { height: 5.0, center: true, twist: 90.0, slices: 3, scale: [2.0, 2.0], .. }
+        ));
+        let result = crate::evaluate("linear_extrude(scale = [1, 2], center = true, height = 4) square(1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::LinearExtrude { height: 4.0, center: true, scale: [1.0, 2.0], .. }));
+
+        let result = crate::evaluate("rotate_extrude(90, $fn = 12) translate([5, 0]) circle(1);\nsphere(1);").unwrap();
+        let GeometryNode::Group { children } = &result.geometry else { panic!("expected group") };
+        let GeometryNode::RotateExtrude { angle: 90.0, fn_: 12, child } = &children[0] else { panic!("expected rotate_extrude") };
+        assert!(matches!(&**child, GeometryNode::Translate { child, .. } if matches!(**child, GeometryNode::Circle { fn_: 12, .. })));
+        assert!(!matches!(children[1], GeometryNode::Sphere { fn_: 12, .. }));
+
+        let result = crate::evaluate("rotate_extrude(angel = 90) translate([5, 0]) circle(1);").unwrap();
+        assert_eq!(result.warnings, ["rotate_extrude(): variable angel not specified as parameter"]);
+    }
 }
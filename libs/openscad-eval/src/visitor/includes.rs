@@ -0,0 +1,183 @@
+//! # Include and Use
+//!
+//! Evaluation of `include <file>` and `use <file>`.
+//!
+//! ## Semantics
+//!
+//! - `include` evaluates the whole file in the caller's scope, as if its
+//!   text were pasted in: assignments, declarations and geometry
+//! - `use` only registers the file's functions and modules (and follows
+//!   its own `use` statements); its geometry and variables are ignored
+//!
+//! Files come from the context's [`FileProvider`](crate::files::FileProvider).
+//! A missing provider or file is a warning, as in OpenSCAD. A file that
+//! includes itself, directly or through other files, is an error; `use`
+//! cycles are allowed and the inner `use` is skipped.
+//!
+//! ## Example
+//!
+//! ```text
+//! include <params.scad>   // width, height and their geometry
+//! use <shapes.scad>       // rounded_box() only
+//! rounded_box(width, height);
+//! ```
+
+use crate::error::EvalError;
+use crate::files::SourceFile;
+use crate::geometry::GeometryNode;
+use openscad_ast::Statement;
+
+use super::context::{EvalContext, evaluate_statement, evaluate_statements};
+
+// =============================================================================
+// INCLUDE
+// =============================================================================
+
+/// Evaluate `include <path>`.
+///
+/// ## Returns
+///
+/// The included file's geometry, or `None` if the file could not be loaded.
+///
+/// ## Errors
+///
+/// `EvalError::RecursiveInclude` if the file is already being included,
+/// `EvalError::ParseError` if it does not parse, and any evaluation error
+/// from its statements.
+pub fn eval_include(ctx: &mut EvalContext, path: &str) -> Result<Option<GeometryNode>, EvalError> {
+    let Some(file) = load_file(ctx, path, "include") else {
+        return Ok(None);
+    };
+    if ctx.file_stack.contains(&file.name) {
+        return Err(EvalError::RecursiveInclude(file.name));
+    }
+    let statements = parse_file(&file)?;
+
+    ctx.file_stack.push(file.name);
+    let result = evaluate_statements(ctx, &statements);
+    ctx.file_stack.pop();
+    result.map(Some)
+}
+
+// =============================================================================
+// USE
+// =============================================================================
+
+/// Evaluate `use <path>`.
+///
+/// ## Errors
+///
+/// `EvalError::ParseError` if the file does not parse.
+pub fn eval_use(ctx: &mut EvalContext, path: &str) -> Result<Option<GeometryNode>, EvalError> {
+    let Some(file) = load_file(ctx, path, "use") else {
+        return Ok(None);
+    };
+    if ctx.file_stack.contains(&file.name) {
+        return Ok(None);
+    }
+    let statements = parse_file(&file)?;
+
+    ctx.file_stack.push(file.name);
+    let mut result = Ok(None);
+    for stmt in &statements {
+        if matches!(stmt, Statement::FunctionDeclaration { .. }
+            | Statement::ModuleDeclaration { .. }
+            | Statement::Use { .. })
+        {
+            result = evaluate_statement(ctx, stmt);
+            if result.is_err() {
+                break;
+            }
+        }
+    }
+    ctx.file_stack.pop();
+    result.map(|_| None)
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Load a file relative to the file currently being evaluated, warning if
+/// it cannot be found.
+fn load_file(ctx: &mut EvalContext, path: &str, kind: &str) -> Option<SourceFile> {
+    let from = ctx.file_stack.last().map(String::as_str);
+    let file = ctx.file_provider.as_ref().and_then(|provider| provider.load(path, from));
+    if file.is_none() {
+        ctx.warn(format!("Can't open {} file '{}'", kind, path));
+    }
+    file
+}
+
+/// Parse a loaded file into statements.
+fn parse_file(file: &SourceFile) -> Result<Vec<Statement>, EvalError> {
+    openscad_ast::parse(&file.source)
+        .map(|ast| ast.statements)
+        .map_err(|e| EvalError::ParseError(format!("{}: {}", file.name, e)))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::files::MemoryFileProvider;
+    use crate::{evaluate_with_options, EvalError, EvalOptions, GeometryNode};
+
+    /// Options with the given in-memory files.
+    fn options(files: &[(&str, &str)]) -> EvalOptions {
+        let mut provider = MemoryFileProvider::new();
+        for (name, source) in files {
+            provider.insert(name, *source);
+        }
+        EvalOptions { file_provider: Some(Arc::new(provider)), ..EvalOptions::default() }
+    }
+
+    /// Test include brings in variables, modules and geometry.
+    #[test]
+    fn test_include() {
+        let options = options(&[("params.scad", "size = 4; module box() { cube(size); } sphere(1);")]);
+        let result = evaluate_with_options("include <params.scad>\nbox();", &options).unwrap();
+        match result.geometry {
+            GeometryNode::Group { children } => {
+                assert!(matches!(children[0], GeometryNode::Sphere { .. }));
+                assert!(matches!(children[1], GeometryNode::Cube { size: [4.0, 4.0, 4.0], .. }));
+            }
+            other => panic!("expected group, got {:?}", other),
+        }
+    }
+
+    /// Test use registers modules and functions only.
+    #[test]
+    fn test_use() {
+        let options = options(&[(
+            "lib/shapes.scad",
+            "use <util.scad>\nsize = 4; sphere(1); module box() { cube(twice(1)); }",
+        ), (
+            "lib/util.scad",
+            "function twice(x) = 2 * x;",
+        )]);
+        let result = evaluate_with_options("use <lib/shapes.scad>\nbox();", &options).unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Cube { size: [2.0, 2.0, 2.0], .. }));
+
+        let result = evaluate_with_options("use <lib/shapes.scad>\nx = [size];", &options).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("size")));
+    }
+
+    /// Test missing files warn and recursive includes fail.
+    #[test]
+    fn test_missing_and_recursive() {
+        let result = evaluate_with_options("include <nope.scad>\ncube(1);", &EvalOptions::default()).unwrap();
+        assert!(result.warnings.iter().any(|w| w.contains("nope.scad")));
+
+        let options = options(&[("a.scad", "include <b.scad>"), ("b.scad", "include <a.scad>")]);
+        let err = evaluate_with_options("include <a.scad>", &options).unwrap_err();
+        assert!(matches!(err, EvalError::RecursiveInclude(name) if name == "a.scad"));
+
+        let options = self::options(&[("a.scad", "use <b.scad> module a() {}"), ("b.scad", "use <a.scad>")]);
+        assert!(evaluate_with_options("use <a.scad> a();", &options).is_ok());
+    }
+}
@@ -0,0 +1,281 @@
+//! # List Functions
+//!
+//! Built-in functions that build and query lists.
+//!
+//! ## Functions
+//!
+//! - `concat(...)` - Lists joined end to end; other values become elements
+//! - `lookup(key, table)` - Linear interpolation in a `[key, value]` table
+//! - `search(match, in, num_returns = 1, index_col = 0)` - Indices of matches
+//!
+//! ## Example
+//!
+//! ```text
+//! echo(concat([1, 2], 3, [[4]]));               // [1, 2, 3, [4]]
+//! echo(lookup(1.5, [[1, 10], [2, 20]]));        // 15
+//! echo(search("b", "abcb", 0));                 // [[1, 3]]
+//! echo(search([2], [["a", 1], ["b", 2]], 1, 1)); // [1]
+//! ```
+
+use crate::error::EvalError;
+use crate::value::Value;
+
+use super::context::EvalContext;
+
+/// Names of the list functions, for dispatch.
+pub const LIST_FUNCTIONS: &[&str] = &["concat", "lookup", "search"];
+
+// =============================================================================
+// DISPATCH
+// =============================================================================
+
+/// Evaluate a list function.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context, for warnings and the iteration limit
+/// - `name`: One of [`LIST_FUNCTIONS`]
+/// - `args`: Evaluated arguments; names are ignored, as in OpenSCAD
+///
+/// ## Returns
+///
+/// The result, or `undef` if the arguments are invalid
+///
+/// ## Errors
+///
+/// `EvalError::LimitExceeded` if expanding a range goes over the
+/// iteration limit.
+pub fn eval_list_function(ctx: &mut EvalContext, name: &str, args: &[(Option<String>, Value)]) -> Result<Value, EvalError> {
+    let values: Vec<&Value> = args.iter().map(|(_, v)| v).collect();
+    Ok(match name {
+        "concat" => concat(ctx, &values)?,
+        "lookup" => match (values.first(), values.get(1)) {
+            (Some(Value::Number(key)), Some(Value::List(table))) => lookup(*key, table).unwrap_or_else(|| {
+                ctx.warn("lookup: table must be a list of [key, value] number pairs".to_string());
+                Value::Undef
+            }),
+            _ => {
+                ctx.warn("lookup: expected a number and a table".to_string());
+                Value::Undef
+            }
+        },
+        "search" => {
+            let count = |i: usize, default: usize| match values.get(i) {
+                Some(Value::Number(n)) if *n >= 0.0 => *n as usize,
+                _ => default,
+            };
+            if let Some(range @ Value::Range { .. }) = values.get(1) {
+                ctx.count_iterations(range.iteration_count())?;
+            }
+            match (values.first(), values.get(1)) {
+                (Some(needle), Some(haystack)) => search(ctx, needle, haystack, count(2, 1), count(3, 0)),
+                _ => {
+                    ctx.warn("search: expected a match value and a string or list to search".to_string());
+                    Value::Undef
+                }
+            }
+        }
+        _ => Value::Undef,
+    })
+}
+
+// =============================================================================
+// CONCAT
+// =============================================================================
+
+/// Join lists end to end; ranges are expanded and any other value is
+/// added as a single element.
+fn concat(ctx: &mut EvalContext, values: &[&Value]) -> Result<Value, EvalError> {
+    let mut out = Vec::new();
+    for value in values {
+        match value {
+            Value::List(items) => out.extend(items.iter().cloned()),
+            Value::Range { .. } => {
+                ctx.count_iterations(value.iteration_count())?;
+                out.extend((*value).clone().into_iteration_values());
+            }
+            other => out.push((*other).clone()),
+        }
+    }
+    Ok(Value::List(out))
+}
+
+// =============================================================================
+// LOOKUP
+// =============================================================================
+
+/// Interpolate `key` in a table of `[key, value]` pairs.
+///
+/// The table need not be sorted: the nearest keys at or below and at or
+/// above `key` are found by scanning. Keys outside the table clamp to the
+/// value of the lowest or highest key. `None` if the table is empty or
+/// any entry is not a pair of numbers.
+fn lookup(key: f64, table: &[Value]) -> Option<Value> {
+    let entries = table
+        .iter()
+        .map(|entry| match entry {
+            Value::List(pair) => match pair.as_slice() {
+                [Value::Number(k), Value::Number(v), ..] => Some((*k, *v)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<Vec<(f64, f64)>>>()?;
+
+    // Nearest entries at or below and at or above the key
+    let below = entries.iter().filter(|e| e.0 <= key).max_by(|a, b| a.0.total_cmp(&b.0));
+    let above = entries.iter().filter(|e| e.0 >= key).min_by(|a, b| a.0.total_cmp(&b.0));
+    let value = match (below, above) {
+        (Some(low), Some(high)) if high.0 > low.0 => low.1 + (high.1 - low.1) * (key - low.0) / (high.0 - low.0),
+        (Some(low), _) => low.1,
+        // Below the lowest key
+        (None, Some(high)) => high.1,
+        (None, None) => return None,
+    };
+    Some(Value::Number(value))
+}
+
+// =============================================================================
+// SEARCH
+// =============================================================================
+
+/// Find the indices of `needle` in `haystack`, as OpenSCAD's `search()`.
+///
+/// - A number or boolean is matched against every element (or its
+///   `index_col` column) and gives a flat list of indices.
+/// - A string is searched character by character; each character gives
+///   its first index when `num_returns` is 1 (nothing if absent), or a
+///   list of up to `num_returns` indices (all when 0).
+/// - A list is searched element by element, like a string, except that an
+///   element not found with `num_returns` 1 gives `[]`.
+fn search(ctx: &mut EvalContext, needle: &Value, haystack: &Value, num_returns: usize, index_col: usize) -> Value {
+    let table: Vec<Value> = match haystack {
+        Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
+        Value::List(_) | Value::Range { .. } => haystack.clone().into_iteration_values().collect(),
+        _ => {
+            ctx.warn("search: can only search a string or a list".to_string());
+            return Value::Undef;
+        }
+    };
+    // Elements of a list are compared by their index_col column
+    let key = |element: &Value| -> Option<Value> {
+        match element {
+            Value::List(columns) => columns.get(index_col).cloned(),
+            other if index_col == 0 => Some(other.clone()),
+            _ => None,
+        }
+    };
+    let find = |target: &Value, limit: usize| -> Vec<Value> {
+        table
+            .iter()
+            .enumerate()
+            .filter(|(_, element)| key(element).as_ref() == Some(target))
+            .map(|(i, _)| Value::Number(i as f64))
+            .take(if limit == 0 { usize::MAX } else { limit })
+            .collect()
+    };
+
+    match needle {
+        Value::Number(_) | Value::Boolean(_) => Value::List(find(needle, num_returns)),
+        Value::String(s) => {
+            let mut results = Vec::new();
+            for c in s.chars() {
+                let found = find(&Value::String(c.to_string()), num_returns);
+                if found.is_empty() {
+                    ctx.warn(format!("search: term not found: \"{}\"", c));
+                }
+                if num_returns == 1 {
+                    results.extend(found);
+                } else {
+                    results.push(Value::List(found));
+                }
+            }
+            Value::List(results)
+        }
+        Value::List(targets) => Value::List(
+            targets
+                .iter()
+                .map(|target| match find(target, num_returns) {
+                    found if num_returns == 1 && found.len() == 1 => found[0].clone(),
+                    found => Value::List(found),
+                })
+                .collect(),
+        ),
+        _ => {
+            ctx.warn("search: invalid match value".to_string());
+            Value::Undef
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::evaluate_statements;
+
+    /// Evaluate `x = <expr>;`, returning `x` and the warnings.
+    fn eval(expr: &str) -> (Value, Vec<String>) {
+        let ast = openscad_ast::parse(&format!("x = {};", expr)).unwrap();
+        let mut ctx = EvalContext::new();
+        evaluate_statements(&mut ctx, &ast.statements).unwrap();
+        (ctx.scope.get("x").cloned().unwrap_or(Value::Undef), ctx.warnings)
+    }
+
+    /// Printed form of `expr`'s value.
+    fn show(expr: &str) -> String {
+        eval(expr).0.to_string()
+    }
+
+    /// Test concat() joins lists and wraps other values.
+    #[test]
+    fn test_concat() {
+        assert_eq!(show("concat([1, 2], 3, [[4]])"), "[1, 2, 3, [4]]");
+        assert_eq!(show("concat(\"ab\", [\"c\"])"), "[\"ab\", \"c\"]");
+        assert_eq!(show("concat([1:3], [])"), "[1, 2, 3]");
+        assert_eq!(show("concat()"), "[]");
+    }
+
+    /// Test lookup() interpolates and clamps, in any table order.
+    #[test]
+    fn test_lookup() {
+        let table = "[[-1, 2], [0, 4], [2, 8], [1, 5]]";
+        assert_eq!(show(&format!("lookup(0.5, {})", table)), "4.5");
+        assert_eq!(show(&format!("lookup(1.5, {})", table)), "6.5");
+        assert_eq!(show(&format!("lookup(2, {})", table)), "8");
+        assert_eq!(show(&format!("lookup(-5, {})", table)), "2");
+        assert_eq!(show(&format!("lookup(10, {})", table)), "8");
+
+        let (value, warnings) = eval("lookup(1, [[0, \"a\"]])");
+        assert_eq!(value, Value::Undef);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    /// Test search() in strings, character by character.
+    #[test]
+    fn test_search_string() {
+        assert_eq!(show("search(\"a\", \"abcdabcd\")"), "[0]");
+        assert_eq!(show("search(\"a\", \"abcdabcd\", 0)"), "[[0, 4]]");
+        assert_eq!(show("search(\"aab\", \"abcdabcd\")"), "[0, 0, 1]");
+        assert_eq!(show("search(\"ad\", \"abcdabcd\", 2)"), "[[0, 4], [3, 7]]");
+
+        let (value, warnings) = eval("search(\"e\", \"abcd\")");
+        assert_eq!(value.to_string(), "[]");
+        assert_eq!(warnings.len(), 1);
+    }
+
+    /// Test search() in lists, by value and by column.
+    #[test]
+    fn test_search_list() {
+        assert_eq!(show("search(3, [1, 3, 5, 3])"), "[1]");
+        assert_eq!(show("search(3, [1, 3, 5, 3], 0)"), "[1, 3]");
+        let table = "[[\"a\", 1], [\"b\", 2], [\"c\", 3], [\"a\", 4]]";
+        assert_eq!(show(&format!("search(\"a\", {})", table)), "[0]");
+        assert_eq!(show(&format!("search(\"a\", {}, 0)", table)), "[[0, 3]]");
+        assert_eq!(show(&format!("search([2, 9], {}, 1, 1)", table)), "[1, []]");
+        assert_eq!(show("search([\"abc\"], [\"x\", \"abc\"])"), "[1]");
+    }
+}
@@ -0,0 +1,425 @@
+//! # Math Functions
+//!
+//! Built-in numeric, vector and type-test functions.
+//!
+//! ## Functions
+//!
+//! - Trigonometric (degrees): `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `atan2`
+//! - Rounding: `abs`, `sign`, `floor`, `ceil`, `round`
+//! - Powers and logarithms: `sqrt`, `pow`, `exp`, `ln`, `log`
+//! - Reductions: `min`, `max`, `norm`, `len`
+//! - Vectors: `cross`
+//! - Random: `rands` (see [`random`](crate::random))
+//! - Type tests: `is_undef`, `is_num`, `is_bool`, `is_string`, `is_list`
+//!
+//! As in OpenSCAD, angles are in degrees and `sin`/`cos` are exact at
+//! multiples of 30°, `tan` at multiples of 45°, so `cos(90)` is `0`
+//! rather than `6.1e-17`. Arguments of the wrong type give `undef` and a
+//! warning.
+//!
+//! ## Example
+//!
+//! ```text
+//! echo(sin(30));                 // 0.5
+//! echo(atan2(1, 1));             // 45
+//! echo(max([3, 9, 4]));          // 9
+//! echo(cross([1, 0, 0], [0, 1, 0]));  // [0, 0, 1]
+//! ```
+
+use crate::error::EvalError;
+use crate::random::Rng;
+use crate::value::Value;
+
+use super::context::EvalContext;
+
+/// Names of the math functions, for dispatch.
+pub const MATH_FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "asin", "acos", "atan", "atan2",
+    "abs", "sign", "floor", "ceil", "round",
+    "sqrt", "pow", "exp", "ln", "log",
+    "min", "max", "norm", "len", "cross",
+    "is_undef", "is_num", "is_bool", "is_string", "is_list",
+];
+
+// =============================================================================
+// DISPATCH
+// =============================================================================
+
+/// Evaluate a math function.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context, for warnings
+/// - `name`: One of [`MATH_FUNCTIONS`]
+/// - `args`: Evaluated arguments; names are ignored, as in OpenSCAD
+///
+/// ## Returns
+///
+/// The result, or `undef` if the arguments are invalid
+pub fn eval_math_function(ctx: &mut EvalContext, name: &str, args: &[(Option<String>, Value)]) -> Value {
+    let values: Vec<&Value> = args.iter().map(|(_, v)| v).collect();
+    let number = |i: usize| match values.get(i) {
+        Some(Value::Number(n)) => Some(*n),
+        _ => None,
+    };
+
+    let result = match name {
+        // Type tests accept anything
+        "is_undef" => return Value::Boolean(values.first().is_none_or(|v| v.is_undef())),
+        "is_num" => return Value::Boolean(number(0).is_some_and(|n| !n.is_nan())),
+        "is_bool" => return Value::Boolean(matches!(values.first(), Some(Value::Boolean(_)))),
+        "is_string" => return Value::Boolean(matches!(values.first(), Some(Value::String(_)))),
+        "is_list" => return Value::Boolean(matches!(values.first(), Some(Value::List(_)))),
+
+        "len" => {
+            return match values.first() {
+                Some(Value::List(items)) => Value::Number(items.len() as f64),
+                Some(Value::String(s)) => Value::Number(s.chars().count() as f64),
+                _ => Value::Undef,
+            };
+        }
+
+        "sin" => number(0).map(sin_degrees),
+        "cos" => number(0).map(cos_degrees),
+        "tan" => number(0).map(tan_degrees),
+        "asin" => number(0).map(|x| x.asin().to_degrees()),
+        "acos" => number(0).map(|x| x.acos().to_degrees()),
+        "atan" => number(0).map(|x| x.atan().to_degrees()),
+        "atan2" => number(0).zip(number(1)).map(|(y, x)| y.atan2(x).to_degrees()),
+
+        "abs" => number(0).map(f64::abs),
+        "sign" => number(0).map(|x| if x > 0.0 { 1.0 } else if x < 0.0 { -1.0 } else { 0.0 }),
+        "floor" => number(0).map(f64::floor),
+        "ceil" => number(0).map(f64::ceil),
+        // Halves round away from zero, like OpenSCAD
+        "round" => number(0).map(f64::round),
+
+        "sqrt" => number(0).map(f64::sqrt),
+        "pow" => number(0).zip(number(1)).map(|(base, exponent)| base.powf(exponent)),
+        "exp" => number(0).map(f64::exp),
+        "ln" => number(0).map(f64::ln),
+        // log(x) is base 10; log(b, x) is base b
+        "log" => match values.len() {
+            1 => number(0).map(f64::log10),
+            _ => number(0).zip(number(1)).map(|(base, x)| x.ln() / base.ln()),
+        },
+
+        "min" | "max" => {
+            // Either one list argument or several numbers
+            let candidates = match values.as_slice() {
+                [Value::List(items)] => items.iter().collect(),
+                _ => values.clone(),
+            };
+            let numbers: Option<Vec<f64>> = candidates.iter()
+                .map(|v| match v {
+                    Value::Number(n) => Some(*n),
+                    _ => None,
+                })
+                .collect();
+            let pick = if name == "min" { f64::min } else { f64::max };
+            numbers.and_then(|n| n.into_iter().reduce(pick))
+        }
+        "norm" => vector(values.first()).map(|v| v.iter().map(|c| c * c).sum::<f64>().sqrt()),
+        "cross" => {
+            return match (vector(values.first()), vector(values.get(1))) {
+                (Some(a), Some(b)) if a.len() == 3 && b.len() == 3 => Value::List(
+                    cross(&a, &b).into_iter().map(Value::Number).collect(),
+                ),
+                // 2D vectors give the z component only
+                (Some(a), Some(b)) if a.len() == 2 && b.len() == 2 => Value::Number(a[0] * b[1] - a[1] * b[0]),
+                _ => {
+                    ctx.warn("cross: expected two vectors of 2 or 3 numbers".to_string());
+                    Value::Undef
+                }
+            };
+        }
+        _ => return Value::Undef,
+    };
+
+    result.map(Value::Number).unwrap_or_else(|| {
+        ctx.warn(format!("{}: invalid arguments", name));
+        Value::Undef
+    })
+}
+
+/// Evaluate `rands(min, max, count, seed)`.
+///
+/// Gives `count` numbers in `[min, max)`. With a `seed` the numbers are
+/// the same on every call; without one they continue the evaluation's
+/// sequence.
+///
+/// ## Errors
+///
+/// `EvalError::LimitExceeded` if the numbers go over the iteration limit.
+pub fn eval_rands(ctx: &mut EvalContext, args: &[(Option<String>, Value)]) -> Result<Value, EvalError> {
+    let number = |i: usize| match args.get(i) {
+        Some((_, Value::Number(n))) if !n.is_nan() => Some(*n),
+        _ => None,
+    };
+    let (Some(min), Some(max), Some(count)) = (number(0), number(1), number(2)) else {
+        ctx.warn("rands: invalid arguments".to_string());
+        return Ok(Value::Undef);
+    };
+    if count < 0.0 || !count.is_finite() {
+        ctx.warn("rands: invalid arguments".to_string());
+        return Ok(Value::Undef);
+    }
+
+    let count = count.floor() as u64;
+    ctx.count_iterations(count)?;
+    let mut seeded = number(3).map(|seed| Rng::new(seed.to_bits()));
+    let rng = seeded.as_mut().unwrap_or(&mut ctx.rng);
+    let values = (0..count)
+        .map(|_| Value::Number(min + (max - min) * rng.next_f64()))
+        .collect();
+    Ok(Value::List(values))
+}
+
+/// Numbers of a list value, `None` for anything else.
+fn vector(value: Option<&&Value>) -> Option<Vec<f64>> {
+    match value {
+        Some(Value::List(items)) => items.iter()
+            .map(|v| match v {
+                Value::Number(n) => Some(*n),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Cross product of two 3D vectors.
+fn cross(a: &[f64], b: &[f64]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+// =============================================================================
+// DEGREE TRIGONOMETRY
+// =============================================================================
+
+/// Sine of an angle in degrees, exact at multiples of 30°.
+pub fn sin_degrees(degrees: f64) -> f64 {
+    if !degrees.is_finite() {
+        return f64::NAN;
+    }
+    // Reduce to [0, 90] and track the sign
+    let mut x = degrees.rem_euclid(360.0);
+    let negate = x >= 180.0;
+    if negate {
+        x -= 180.0;
+    }
+    if x > 90.0 {
+        x = 180.0 - x;
+    }
+    let value = match x {
+        0.0 => 0.0,
+        30.0 => 0.5,
+        90.0 => 1.0,
+        // Cosine of the complement is more accurate near 90°
+        _ if x > 45.0 => (90.0 - x).to_radians().cos(),
+        _ => x.to_radians().sin(),
+    };
+    if negate { -value } else { value }
+}
+
+/// Cosine of an angle in degrees, exact at multiples of 30°.
+pub fn cos_degrees(degrees: f64) -> f64 {
+    sin_degrees(degrees.rem_euclid(360.0) + 90.0)
+}
+
+/// Tangent of an angle in degrees, exact at multiples of 45°.
+///
+/// Odd multiples of 90° give infinity.
+pub fn tan_degrees(degrees: f64) -> f64 {
+    if !degrees.is_finite() {
+        return f64::NAN;
+    }
+    match degrees.rem_euclid(180.0) {
+        0.0 => 0.0,
+        45.0 => 1.0,
+        90.0 => f64::INFINITY,
+        135.0 => -1.0,
+        x => x.to_radians().tan(),
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::evaluate_statements;
+
+    /// Evaluate `x = <expr>;`, returning `x` and the warnings.
+    fn eval(expr: &str) -> (Value, Vec<String>) {
+        let ast = openscad_ast::parse(&format!("x = {};", expr)).unwrap();
+        let mut ctx = EvalContext::new();
+        evaluate_statements(&mut ctx, &ast.statements).unwrap();
+        (ctx.scope.get("x").cloned().unwrap_or(Value::Undef), ctx.warnings)
+    }
+
+    /// Number result of `expr`.
+    fn number(expr: &str) -> f64 {
+        match eval(expr).0 {
+            Value::Number(n) => n,
+            other => panic!("{}: expected a number, got {:?}", expr, other),
+        }
+    }
+
+    /// Test sine and cosine are exact at multiples of 30° in every quadrant.
+    #[test]
+    fn test_sin_cos_exact() {
+        assert_eq!(number("sin(30)"), 0.5);
+        assert_eq!(number("sin(150)"), 0.5);
+        assert_eq!(number("sin(-30)"), -0.5);
+        assert_eq!(number("sin(180)"), 0.0);
+        assert_eq!(number("sin(270)"), -1.0);
+        assert_eq!(number("cos(60)"), 0.5);
+        assert_eq!(number("cos(90)"), 0.0);
+        assert_eq!(number("cos(180)"), -1.0);
+        assert_eq!(number("cos(720)"), 1.0);
+        assert!((number("sin(45)") - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-15);
+        assert!((number("cos(10)") - 10f64.to_radians().cos()).abs() < 1e-15);
+    }
+
+    /// Test tangent is exact at multiples of 45° and infinite at 90°.
+    #[test]
+    fn test_tan() {
+        assert_eq!(number("tan(45)"), 1.0);
+        assert_eq!(number("tan(-45)"), -1.0);
+        assert_eq!(number("tan(180)"), 0.0);
+        assert_eq!(number("tan(90)"), f64::INFINITY);
+        assert!((number("tan(30)") - 30f64.to_radians().tan()).abs() < 1e-15);
+    }
+
+    /// Test inverse trigonometry returns degrees.
+    #[test]
+    fn test_inverse_trig() {
+        assert!((number("asin(0.5)") - 30.0).abs() < 1e-12);
+        assert!((number("acos(0.5)") - 60.0).abs() < 1e-12);
+        assert_eq!(number("atan(1)"), 45.0);
+        assert_eq!(number("atan2(1, 1)"), 45.0);
+        assert_eq!(number("atan2(1, -1)"), 135.0);
+        assert_eq!(number("atan2(-1, 0)"), -90.0);
+    }
+
+    /// Test rounding functions, with halves rounded away from zero.
+    #[test]
+    fn test_rounding() {
+        assert_eq!(number("abs(-2.5)"), 2.5);
+        assert_eq!(number("sign(-3)"), -1.0);
+        assert_eq!(number("sign(0)"), 0.0);
+        assert_eq!(number("sign(0.1)"), 1.0);
+        assert_eq!(number("floor(-1.5)"), -2.0);
+        assert_eq!(number("ceil(-1.5)"), -1.0);
+        assert_eq!(number("round(2.5)"), 3.0);
+        assert_eq!(number("round(-2.5)"), -3.0);
+    }
+
+    /// Test powers, roots and logarithms.
+    #[test]
+    fn test_powers_and_logs() {
+        assert_eq!(number("sqrt(16)"), 4.0);
+        assert_eq!(number("pow(2, 10)"), 1024.0);
+        assert_eq!(number("exp(0)"), 1.0);
+        assert_eq!(number("ln(1)"), 0.0);
+        assert_eq!(number("log(1000)"), 3.0);
+        assert!((number("log(2, 8)") - 3.0).abs() < 1e-12);
+    }
+
+    /// Test min and max over arguments or a single list.
+    #[test]
+    fn test_min_max() {
+        assert_eq!(number("min(3, 1, 2)"), 1.0);
+        assert_eq!(number("max(3, 1, 2)"), 3.0);
+        assert_eq!(number("min([4, -2, 7])"), -2.0);
+        assert_eq!(number("max([4, -2, 7])"), 7.0);
+        assert_eq!(number("max(5)"), 5.0);
+        assert_eq!(eval("min([])"), (Value::Undef, vec!["min: invalid arguments".to_string()]));
+    }
+
+    /// Test norm, len and cross products in 2D and 3D.
+    #[test]
+    fn test_vectors() {
+        assert_eq!(number("norm([3, 4])"), 5.0);
+        assert_eq!(number("norm([1, 2, 2])"), 3.0);
+        assert_eq!(number("len([1, 2, 3])"), 3.0);
+        assert_eq!(number("len(\"héllo\")"), 5.0);
+        assert_eq!(eval("len(5)").0, Value::Undef);
+
+        let z = Value::List(vec![Value::Number(0.0), Value::Number(0.0), Value::Number(1.0)]);
+        assert_eq!(eval("cross([1, 0, 0], [0, 1, 0])").0, z);
+        assert_eq!(number("cross([2, 0], [0, 3])"), 6.0);
+        let (value, warnings) = eval("cross([1, 0, 0], [0, 1])");
+        assert_eq!(value, Value::Undef);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    /// Test type tests accept any value.
+    #[test]
+    fn test_type_tests() {
+        assert_eq!(eval("is_undef(undef)").0, Value::Boolean(true));
+        assert_eq!(eval("is_undef(0)").0, Value::Boolean(false));
+        assert_eq!(eval("is_num(1)").0, Value::Boolean(true));
+        assert_eq!(eval("is_num(\"1\")").0, Value::Boolean(false));
+        assert_eq!(eval("is_bool(false)").0, Value::Boolean(true));
+        assert_eq!(eval("is_string(\"a\")").0, Value::Boolean(true));
+        assert_eq!(eval("is_list([])").0, Value::Boolean(true));
+        assert_eq!(eval("is_list(\"ab\")").0, Value::Boolean(false));
+    }
+
+    /// Test non-numeric arguments give undef with a warning.
+    #[test]
+    fn test_invalid_arguments() {
+        for expr in ["sin(\"a\")", "pow(2)", "sqrt([4])", "norm(3)", "max(1, \"b\")"] {
+            let (value, warnings) = eval(expr);
+            assert_eq!(value, Value::Undef, "{}", expr);
+            assert_eq!(warnings.len(), 1, "{}", expr);
+        }
+    }
+
+    /// Test seeded `rands()` calls repeat and stay in range.
+    #[test]
+    fn test_rands_seeded() {
+        let (first, _) = eval("rands(5, 10, 20, 42)");
+        assert_eq!(eval("rands(5, 10, 20, 42)").0, first);
+        assert_ne!(eval("rands(5, 10, 20, 43)").0, first);
+        let Value::List(values) = first else { panic!("expected a list") };
+        assert_eq!(values.len(), 20);
+        assert!(values.iter().all(|v| matches!(v, Value::Number(n) if (5.0..10.0).contains(n))));
+        // Pinned so the sequence cannot drift between platforms
+        let mut rng = Rng::new(42f64.to_bits());
+        assert_eq!(number("rands(0, 1, 1, 42)[0]"), rng.next_f64());
+    }
+
+    /// Test the global seed makes unseeded `rands()` calls repeatable.
+    #[test]
+    fn test_rands_global_seed() {
+        use crate::{evaluate_with_options, EvalOptions, EvalParams};
+        let render = || {
+            let options = EvalOptions {
+                params: EvalParams { seed: Some(7), ..EvalParams::default() },
+                ..EvalOptions::default()
+            };
+            evaluate_with_options("echo(rands(0, 1, 3), rands(0, 1, 3));", &options).unwrap().echoes
+        };
+        let echoes = render();
+        assert_eq!(render(), echoes);
+        // Later calls continue the sequence
+        let (first, second) = echoes[0].split_once("], [").unwrap();
+        assert_ne!(first.trim_start_matches("ECHO: ["), second.trim_end_matches(']'));
+    }
+
+    /// Test invalid `rands()` arguments give undef with a warning.
+    #[test]
+    fn test_rands_invalid() {
+        for expr in ["rands(0, 1)", "rands(0, 1, -1)", "rands(\"a\", 1, 2)"] {
+            let (value, warnings) = eval(expr);
+            assert_eq!(value, Value::Undef, "{}", expr);
+            assert_eq!(warnings.len(), 1, "{}", expr);
+        }
+        assert_eq!(eval("rands(0, 1, 0)").0, Value::List(vec![]));
+    }
+}
@@ -5,12 +5,24 @@
 //! ## Module Structure (SRP)
 //!
 //! - `context` - Evaluator state and statement evaluation
+//! - `arguments` - Binding call arguments to parameters
+//! - `control_flow` - for, intersection_for, if/else if/else and let statements
 //! - `expressions` - Expression evaluation
+//! - `comprehension` - List comprehension evaluation
+//! - `math` - Numeric, vector and type-test functions (sin, max, cross, is_num)
+//! - `lists` - List functions (concat, lookup, search)
+//! - `strings` - String functions (str, chr, ord)
+//! - `paths` - Point list functions (path_length, resample_path, offset_path)
 //! - `primitives` - 3D and 2D primitive evaluators
 //! - `boolean` - Boolean operation evaluators
 //! - `transforms` - Transform evaluators
 //! - `extrusions` - Extrusion evaluators
 //! - `ops_2d` - 2D operations (offset, projection)
+//! - `compat` - Native MCAD/BOSL2 compatibility shims
+//! - `debug` - Console output modules (echo_dim)
+//! - `extensions` - Modules OpenSCAD does not have (smooth)
+//! - `includes` - `include <file>` and `use <file>`
+//! - `modifiers` - The `*`, `!`, `#` and `%` modifiers
 //!
 //! ## Example
 //!
@@ -23,19 +35,38 @@
 //! ```
 
 pub mod context;
+pub mod arguments;
+pub mod control_flow;
 pub mod expressions;
+pub mod comprehension;
+pub mod math;
+pub mod lists;
+pub mod strings;
+pub mod paths;
 pub mod primitives;
 pub mod boolean;
 pub mod transforms;
 pub mod extrusions;
 pub mod ops_2d;
+pub mod compat;
+pub mod debug;
+pub mod extensions;
+pub mod includes;
+pub mod modifiers;
 
 // Re-export public API
-pub use context::{EvalContext, evaluate_statements};
+pub use context::{EvalContext, evaluate_statement, evaluate_statements};
+pub use compat::ShimLibrary;
 
 use crate::error::EvalError;
-use crate::geometry::EvaluatedAst;
-use openscad_ast::Ast;
+use crate::geometry::{EvaluatedAst, GeometryNode};
+use crate::library::LibraryBundle;
+use crate::limits::DEFAULT_MAX_RECURSION_DEPTH;
+use crate::message::MessageKind;
+use crate::options::EvalOptions;
+use crate::progress::{Progress, ProgressSink, Stage};
+use crate::random::Rng;
+use openscad_ast::{Ast, Statement};
 
 // =============================================================================
 // PUBLIC API
@@ -63,9 +94,125 @@ use openscad_ast::Ast;
 /// let result = evaluate_ast(&ast).unwrap();
 /// ```
 pub fn evaluate_ast(ast: &Ast) -> Result<EvaluatedAst, EvalError> {
+    evaluate_ast_with_libraries(ast, &[])
+}
+
+/// Evaluate AST to geometry after registering precompiled libraries.
+///
+/// ## Parameters
+///
+/// - `ast`: Abstract Syntax Tree from openscad-ast
+/// - `libraries`: Library bundles to register, in order
+pub fn evaluate_ast_with_libraries(
+    ast: &Ast,
+    libraries: &[LibraryBundle],
+) -> Result<EvaluatedAst, EvalError> {
+    evaluate_ast_with_shims(ast, libraries, &[])
+}
+
+/// Evaluate AST to geometry with libraries and compatibility shims.
+///
+/// Libraries are registered first, so their definitions take precedence
+/// over the shims. Shim modules that were called are listed in
+/// `EvaluatedAst::shimmed`.
+///
+/// ## Parameters
+///
+/// - `ast`: Abstract Syntax Tree from openscad-ast
+/// - `libraries`: Library bundles to register, in order
+/// - `shims`: Libraries to shim natively
+pub fn evaluate_ast_with_shims(
+    ast: &Ast,
+    libraries: &[LibraryBundle],
+    shims: &[ShimLibrary],
+) -> Result<EvaluatedAst, EvalError> {
+    let options = EvalOptions {
+        shims: shims.to_vec(),
+        ..EvalOptions::with_libraries(libraries)
+    };
+    evaluate_ast_with_options(ast, &options)
+}
+
+/// Evaluate AST to geometry with all evaluation options.
+///
+/// Overrides are installed after the libraries, so they also replace
+/// library globals of the same name. The special variables from
+/// `options.params` are defined first, so overrides of them win.
+///
+/// ## Parameters
+///
+/// - `ast`: Abstract Syntax Tree from openscad-ast
+/// - `options`: Libraries, shims, overrides and limits
+pub fn evaluate_ast_with_options(
+    ast: &Ast,
+    options: &EvalOptions,
+) -> Result<EvaluatedAst, EvalError> {
     let mut ctx = EvalContext::new();
-    let geometry = evaluate_statements(&mut ctx, &ast.statements)?;
-    Ok(EvaluatedAst::with_warnings(geometry, ctx.warnings))
+    options.params.define(&mut ctx.scope);
+    ctx.rng = options.params.seed.map_or_else(Rng::from_clock, Rng::new);
+    ctx.set_overrides(options.overrides.clone());
+    ctx.file_provider = options.file_provider.clone();
+    ctx.limits = options.limits.clone();
+    ctx.max_recursion_depth = options.max_recursion_depth.unwrap_or(DEFAULT_MAX_RECURSION_DEPTH);
+    ctx.cancel = options.cancel.clone();
+    ctx.log = options.log.clone();
+    let quality = options.quality.level(options.params.preview);
+    if !(quality > 0.0 && quality.is_finite()) {
+        return Err(EvalError::InvalidArgument(format!("quality: level must be positive, got {}", quality)));
+    }
+    ctx.quality = quality;
+    ctx.extensions = options.extensions;
+    ctx.sources = options.sources;
+    for library in &options.libraries {
+        ctx.register_library(library)?;
+    }
+    compat::install_shims(&mut ctx, &options.shims);
+    let started = crate::limits::now_ms();
+    let geometry = {
+        let _span = crate::timing::stage_span("eval");
+        evaluate_top_level(&mut ctx, &ast.statements, options.progress.as_deref())?
+    };
+    let mut result = EvaluatedAst::with_warnings(geometry, ctx.warnings);
+    result.timings.eval_ms = crate::limits::now_ms() - started;
+    result.diagnostics = ctx.diagnostics;
+    result.echoes = ctx.messages.iter()
+        .filter(|m| m.kind == MessageKind::Echo)
+        .map(|m| m.text.clone())
+        .collect();
+    result.messages = ctx.messages;
+    result.shimmed = ctx.shimmed.into_iter().collect();
+    Ok(result)
+}
+
+/// Evaluate the top-level statements like [`evaluate_statements`],
+/// reporting [`Stage::Evaluate`] progress after each one.
+fn evaluate_top_level(
+    ctx: &mut EvalContext,
+    statements: &[Statement],
+    progress: Option<&dyn ProgressSink>,
+) -> Result<GeometryNode, EvalError> {
+    let total = statements.len() as u64;
+    let report = |done| {
+        if let Some(sink) = progress {
+            sink.report(Progress { stage: Stage::Evaluate, done, total });
+        }
+    };
+
+    report(0);
+    let mut children = Vec::new();
+    for (done, stmt) in (1..).zip(statements) {
+        if let Some(node) = evaluate_statement(ctx, stmt)? {
+            if !node.is_empty() {
+                children.push(node);
+            }
+        }
+        report(done);
+    }
+    // A `!` statement replaces everything else
+    if let Some(root) = ctx.root.take() {
+        return Ok(root);
+    }
+    Ok(GeometryNode::group(children))
 }
 
 // =============================================================================
@@ -146,4 +293,26 @@ mod tests {
             _ => panic!("Expected Translate"),
         }
     }
+
+    #[test]
+    fn test_eval_sources() {
+        let source = "translate([1, 0, 0])\n  cube(10);";
+        let ast = openscad_ast::parse(source).unwrap();
+        let options = EvalOptions { sources: true, ..EvalOptions::default() };
+        let result = evaluate_ast_with_options(&ast, &options).unwrap();
+        match &result.geometry {
+            GeometryNode::Source { span, child } => {
+                assert_eq!((span.start.line, span.end.line), (0, 1));
+                match &child.children()[0] {
+                    GeometryNode::Source { span, .. } => assert_eq!(span.start.line, 1),
+                    other => panic!("Expected Source, got {:?}", other),
+                }
+            }
+            other => panic!("Expected Source, got {:?}", other),
+        }
+        // Moving the calls keeps the hash, and so the cached meshes
+        let moved = openscad_ast::parse(&format!("\n\n{}", source)).unwrap();
+        let moved = evaluate_ast_with_options(&moved, &options).unwrap();
+        assert_eq!(result.geometry.structural_hash(), moved.geometry.structural_hash());
+    }
 }
@@ -0,0 +1,99 @@
+//! # Modifier Evaluators
+//!
+//! Evaluators for the modifier characters in front of a statement.
+//!
+//! ## Modifiers
+//!
+//! - `*` - Disable: the statement produces nothing
+//! - `!` - Show only: the statement's geometry becomes the whole result
+//! - `#` - Highlight: kept in the model, also shown highlighted
+//! - `%` - Background: left out of the model, shown transparent
+//!
+//! ## Example
+//!
+//! ```text
+//! difference() {
+//!     cube(10, center = true);
+//!     #cylinder(h = 12, r = 3, center = true);  // see the hole's tool
+//! }
+//! %sphere(8);                                   // reference only
+//! ```
+
+use crate::error::EvalError;
+use crate::geometry::GeometryNode;
+use openscad_ast::{Modifier, Span, Statement};
+
+use super::context::{evaluate_statement, EvalContext};
+
+// =============================================================================
+// MODIFIED STATEMENTS
+// =============================================================================
+
+/// Evaluate a statement under a modifier.
+///
+/// The first `!` statement is stored in `ctx.root` and replaces the
+/// top-level result; later ones warn and render normally.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `modifier`: The modifier
+/// - `statement`: Modified statement
+/// - `span`: Source span, modifier included
+pub fn eval_modified(
+    ctx: &mut EvalContext,
+    modifier: Modifier,
+    statement: &Statement,
+    span: Span,
+) -> Result<Option<GeometryNode>, EvalError> {
+    if modifier == Modifier::Disable {
+        return Ok(None);
+    }
+    let Some(node) = evaluate_statement(ctx, statement)? else {
+        return Ok(None);
+    };
+    match modifier {
+        Modifier::Highlight => Ok(Some(GeometryNode::Highlight { child: Box::new(node) })),
+        Modifier::Background => Ok(Some(GeometryNode::Background { child: Box::new(node) })),
+        _ => {
+            if ctx.root.is_some() {
+                ctx.warn(format!(
+                    "root modifier (!) at line {} ignored: already set",
+                    span.start.line + 1
+                ));
+            } else {
+                ctx.root = Some(node.clone());
+            }
+            Ok(Some(node))
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::{evaluate, GeometryNode};
+
+    /// Test `*` drops the statement and `#`/`%` wrap its geometry.
+    #[test]
+    fn test_disable_highlight_background() {
+        let result = evaluate("*cube(1); #sphere(1); %translate([2, 0, 0]) cube(1);").unwrap();
+        let GeometryNode::Group { children } = result.geometry else {
+            panic!("expected group, got {:?}", result.geometry);
+        };
+        assert_eq!(children.len(), 2);
+        assert!(matches!(&children[0], GeometryNode::Highlight { child } if matches!(**child, GeometryNode::Sphere { .. })));
+        assert!(matches!(&children[1], GeometryNode::Background { child } if matches!(**child, GeometryNode::Translate { .. })));
+    }
+
+    /// Test `!` makes its subtree the root, dropping enclosing transforms.
+    #[test]
+    fn test_show_only() {
+        let result = evaluate("cube(1);\ntranslate([5, 0, 0]) !sphere(2);\n!cylinder(1, 1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Sphere { radius, .. } if radius == 2.0));
+        assert_eq!(result.warnings, vec!["root modifier (!) at line 3 ignored: already set".to_string()]);
+    }
+}
@@ -9,10 +9,11 @@
 
 use crate::error::EvalError;
 use crate::geometry::GeometryNode;
+use crate::value::Value;
 use openscad_ast::{Argument, Statement};
 
+use super::arguments::ArgumentResolver;
 use super::context::{EvalContext, evaluate_statements};
-use super::expressions::eval_expr;
 
 // =============================================================================
 // OFFSET
@@ -29,7 +30,8 @@ use super::expressions::eval_expr;
 ///
 /// ## Parameters
 ///
-/// - `r`: Round offset (uses circular arcs at corners)
+/// - `r`: Round offset (uses circular arcs at corners, segmented by
+///   `$fn`/`$fa`/`$fs` for a circle of radius `r`)
 /// - `delta`: Straight offset (uses mitered or chamfered corners)
 /// - `chamfer`: If true with delta, use beveled corners
 ///
@@ -44,43 +46,24 @@ pub fn eval_offset(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let mut delta = 1.0;
-    let mut chamfer = false;
-    let mut use_radius = false;
-
-    for arg in args {
-        match arg {
-            Argument::Positional(expr) => {
-                // First positional arg is r (radius)
-                delta = eval_expr(ctx, expr)?.as_number()?;
-                use_radius = true;
-            }
-            Argument::Named { name, value } => match name.as_str() {
-                "r" => {
-                    delta = eval_expr(ctx, value)?.as_number()?;
-                    use_radius = true;
-                }
-                "delta" => {
-                    delta = eval_expr(ctx, value)?.as_number()?;
-                    use_radius = false;
-                }
-                "chamfer" => {
-                    chamfer = eval_expr(ctx, value)?.as_boolean();
-                }
-                _ => {}
-            },
-        }
-    }
-
-    // If using radius mode, chamfer is always false
-    if use_radius {
-        chamfer = false;
-    }
+    let args = ArgumentResolver::new("offset", &["r"]).named(&["delta", "chamfer"]).evaluate(ctx, args)?;
+
+    // r wins over delta; with neither, offset by delta = 1
+    let (delta, round) = match (args.get("r"), args.get("delta")) {
+        (Some(r), _) => (r.as_number()?, true),
+        (None, Some(delta)) => (delta.as_number()?, false),
+        (None, None) => (1.0, false),
+    };
+    // Chamfer only applies to delta offsets
+    let chamfer = !round && args.get("chamfer").is_some_and(Value::as_boolean);
+    let fn_ = if round { args.with_specials(ctx, |ctx| ctx.calculate_fragments(delta.abs())) } else { 0 };
 
     let child = evaluate_statements(ctx, children)?;
     Ok(GeometryNode::Offset {
         delta,
+        round,
         chamfer,
+        fn_,
         child: Box::new(child),
     })
 }
@@ -94,7 +77,7 @@ pub fn eval_offset(
 /// ## OpenSCAD Signature
 ///
 /// ```text
-/// projection(cut = false) child;
+/// projection(cut = false, convexity) child;
 /// ```
 ///
 /// ## Parameters
@@ -112,17 +95,10 @@ pub fn eval_projection(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let mut cut = false;
-
-    for arg in args {
-        if let Argument::Named { name, value } = arg {
-            if name == "cut" {
-                cut = eval_expr(ctx, value)?.as_boolean();
-            }
-        }
-    }
+    let args = ArgumentResolver::new("projection", &["cut", "convexity"]).evaluate(ctx, args)?;
+    let cut = args.get("cut").is_some_and(Value::as_boolean);
 
-    let child = evaluate_statements(ctx, children)?;
+    let child = args.with_specials(ctx, |ctx| evaluate_statements(ctx, children))?;
     Ok(GeometryNode::Projection {
         cut,
         child: Box::new(child),
@@ -164,9 +140,11 @@ mod tests {
         }];
         let node = eval_offset(&mut ctx, &args, &[]).unwrap();
         match node {
-            GeometryNode::Offset { delta, chamfer, .. } => {
+            GeometryNode::Offset { delta, round, chamfer, fn_, .. } => {
                 assert_eq!(delta, 5.0);
+                assert!(round);
                 assert!(!chamfer);
+                assert!(fn_ >= 3);
             }
             _ => panic!("Expected Offset"),
         }
@@ -187,8 +165,9 @@ mod tests {
         ];
         let node = eval_offset(&mut ctx, &args, &[]).unwrap();
         match node {
-            GeometryNode::Offset { delta, chamfer, .. } => {
+            GeometryNode::Offset { delta, round, chamfer, .. } => {
                 assert_eq!(delta, 3.0);
+                assert!(!round);
                 assert!(chamfer);
             }
             _ => panic!("Expected Offset"),
@@ -222,4 +201,12 @@ mod tests {
             _ => panic!("Expected Projection"),
         }
     }
+
+    /// Test cut can be given positionally and unknown arguments warn.
+    #[test]
+    fn test_eval_projection_positional_cut() {
+        let result = crate::evaluate("projection(true, slice = 1) cube(1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Projection { cut: true, .. }));
+        assert_eq!(result.warnings, ["projection(): variable slice not specified as parameter"]);
+    }
 }
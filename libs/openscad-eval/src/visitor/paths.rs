@@ -0,0 +1,301 @@
+//! # Path Functions
+//!
+//! Built-in functions over point lists, for generative 2D patterns that
+//! would otherwise need slow hand-written recursion in user code.
+//!
+//! ## Functions
+//!
+//! - `path_length(points, closed=false)` - Total length of a polyline
+//! - `resample_path(points, n, closed=false)` - `n` points evenly spaced by arc length
+//! - `offset_path(points, d, closed=false)` - 2D polyline shifted sideways by `d`
+//!
+//! Points may have any dimension for `path_length` and `resample_path`,
+//! as long as all have the same one. Invalid arguments give `undef` and a
+//! warning.
+//!
+//! ## Example
+//!
+//! ```text
+//! square_path = [[0, 0], [10, 0], [10, 10], [0, 10]];
+//! echo(path_length(square_path, closed = true));  // 40
+//! dots = resample_path(square_path, 8, closed = true);
+//! inset = offset_path(square_path, -1, closed = true);
+//! ```
+
+use crate::value::Value;
+
+use super::context::EvalContext;
+
+/// Names of the path functions, for dispatch.
+pub const PATH_FUNCTIONS: &[&str] = &["path_length", "resample_path", "offset_path"];
+
+// =============================================================================
+// DISPATCH
+// =============================================================================
+
+/// Evaluate a path function.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context, for warnings
+/// - `name`: One of [`PATH_FUNCTIONS`]
+/// - `args`: Evaluated arguments with their names, if given by name
+///
+/// ## Returns
+///
+/// The result, or `undef` if the arguments are invalid
+pub fn eval_path_function(ctx: &mut EvalContext, name: &str, args: &[(Option<String>, Value)]) -> Value {
+    let points = argument(args, 0, "points").and_then(point_list);
+    let closed = argument(args, 2, "closed").or_else(|| {
+        // path_length has no second parameter, so closed may come second
+        (name == "path_length").then(|| argument(args, 1, "closed")).flatten()
+    });
+    let closed = closed.is_some_and(Value::as_boolean);
+
+    let Some(points) = points else {
+        ctx.warn(format!("{}: expected a list of at least 2 points of equal dimension", name));
+        return Value::Undef;
+    };
+
+    match name {
+        "path_length" => Value::Number(path_length(&points, closed)),
+        "resample_path" => {
+            let n = argument(args, 1, "n").and_then(|v| v.as_number().ok());
+            match n {
+                Some(n) if n >= 2.0 && n.fract() == 0.0 => to_value(resample_path(&points, n as usize, closed)),
+                _ => {
+                    ctx.warn("resample_path: n must be an integer of at least 2".to_string());
+                    Value::Undef
+                }
+            }
+        }
+        "offset_path" => {
+            let d = argument(args, 1, "d").and_then(|v| v.as_number().ok());
+            match d {
+                Some(d) if points[0].len() == 2 => {
+                    let flat: Vec<[f64; 2]> = points.iter().map(|p| [p[0], p[1]]).collect();
+                    to_value(offset_path(&flat, d, closed).into_iter().map(Vec::from).collect())
+                }
+                Some(_) => {
+                    ctx.warn("offset_path: points must be 2D".to_string());
+                    Value::Undef
+                }
+                None => {
+                    ctx.warn("offset_path: d must be a number".to_string());
+                    Value::Undef
+                }
+            }
+        }
+        _ => Value::Undef,
+    }
+}
+
+/// Argument by name, else by position among the positional ones.
+fn argument<'a>(args: &'a [(Option<String>, Value)], index: usize, name: &str) -> Option<&'a Value> {
+    args.iter()
+        .find(|(n, _)| n.as_deref() == Some(name))
+        .or_else(|| args.iter().filter(|(n, _)| n.is_none()).nth(index))
+        .map(|(_, v)| v)
+}
+
+/// Points of a list value, `None` unless there are at least two numeric
+/// vectors of the same dimension (2 or more).
+fn point_list(value: &Value) -> Option<Vec<Vec<f64>>> {
+    let Value::List(items) = value else {
+        return None;
+    };
+    let points: Vec<Vec<f64>> = items.iter()
+        .map(|item| match item {
+            Value::List(_) => item.as_number_list().ok(),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    let dimension = points.first()?.len();
+    (points.len() >= 2 && dimension >= 2 && points.iter().all(|p| p.len() == dimension)).then_some(points)
+}
+
+/// List value of points.
+fn to_value(points: Vec<Vec<f64>>) -> Value {
+    Value::List(points.into_iter()
+        .map(|p| Value::List(p.into_iter().map(Value::Number).collect()))
+        .collect())
+}
+
+// =============================================================================
+// PATH OPERATIONS
+// =============================================================================
+
+/// Total length of a polyline, including the closing segment if `closed`.
+pub fn path_length(points: &[Vec<f64>], closed: bool) -> f64 {
+    segments(points, closed).map(|(a, b)| distance(a, b)).sum()
+}
+
+/// `n` points spaced evenly by arc length along a polyline.
+///
+/// An open path keeps both end points; a closed one starts at the first
+/// point and leaves the closing gap the same as the others.
+pub fn resample_path(points: &[Vec<f64>], n: usize, closed: bool) -> Vec<Vec<f64>> {
+    let total = path_length(points, closed);
+    let steps = if closed { n } else { n - 1 };
+
+    let mut result = Vec::with_capacity(n);
+    let mut segments = segments(points, closed).peekable();
+    // Arc length at the start of the current segment
+    let mut walked = 0.0;
+    for k in 0..n {
+        let target = total * k as f64 / steps as f64;
+        // Advance to the segment containing the target
+        while let Some(&(a, b)) = segments.peek() {
+            let length = distance(a, b);
+            if walked + length >= target && length > 0.0 {
+                break;
+            }
+            walked += length;
+            segments.next();
+        }
+        let point = match segments.peek() {
+            Some(&(a, b)) => {
+                let t = ((target - walked) / distance(a, b)).clamp(0.0, 1.0);
+                a.iter().zip(b).map(|(a, b)| a + (b - a) * t).collect()
+            }
+            // Rounding carried the target past the end
+            None => points[if closed { 0 } else { points.len() - 1 }].clone(),
+        };
+        result.push(point);
+    }
+    result
+}
+
+/// A 2D polyline shifted sideways by `d`, with mitred corners.
+///
+/// Positive `d` moves to the right of the direction of travel, which is
+/// outward for a counter-clockwise polygon, as with `offset(delta = d)`.
+/// Repeated points are merged first; corners where the path turns back
+/// on itself are shifted along the outgoing segment only.
+pub fn offset_path(points: &[[f64; 2]], d: f64, closed: bool) -> Vec<[f64; 2]> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 2 {
+        return points;
+    }
+
+    // Right-hand unit normal of the segment starting at each point
+    let n = points.len();
+    let normals: Vec<[f64; 2]> = (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+            let length = dx.hypot(dy);
+            [dy / length, -dx / length]
+        })
+        .collect();
+
+    (0..n)
+        .map(|i| {
+            let p = points[i];
+            let incoming = if i > 0 { Some(normals[i - 1]) } else { closed.then(|| normals[n - 1]) };
+            let outgoing = if i + 1 < n || closed { Some(normals[i]) } else { None };
+            let shift = match (incoming, outgoing) {
+                (Some(a), Some(b)) => {
+                    // Where the two shifted segment lines meet
+                    let cos = a[0] * b[0] + a[1] * b[1];
+                    if 1.0 + cos > 1e-9 {
+                        [(a[0] + b[0]) / (1.0 + cos), (a[1] + b[1]) / (1.0 + cos)]
+                    } else {
+                        b
+                    }
+                }
+                (Some(a), None) => a,
+                (None, Some(b)) => b,
+                (None, None) => [0.0, 0.0],
+            };
+            [p[0] + d * shift[0], p[1] + d * shift[1]]
+        })
+        .collect()
+}
+
+/// Consecutive point pairs, wrapping around if `closed`.
+fn segments(points: &[Vec<f64>], closed: bool) -> impl Iterator<Item = (&[f64], &[f64])> {
+    let wrap = if closed && points.len() > 2 { points.len() } else { points.len() - 1 };
+    (0..wrap).map(move |i| (points[i].as_slice(), points[(i + 1) % points.len()].as_slice()))
+}
+
+/// Euclidean distance.
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum::<f64>().sqrt()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::evaluate_statements;
+
+    /// Evaluate `x = <expr>;`, returning `x` and the warnings.
+    fn eval(expr: &str) -> (Value, Vec<String>) {
+        let ast = openscad_ast::parse(&format!("x = {};", expr)).unwrap();
+        let mut ctx = EvalContext::new();
+        evaluate_statements(&mut ctx, &ast.statements).unwrap();
+        (ctx.scope.get("x").cloned().unwrap_or(Value::Undef), ctx.warnings)
+    }
+
+    /// Test lengths of open and closed paths in 2D and 3D.
+    #[test]
+    fn test_path_length() {
+        let square = vec![vec![0.0, 0.0], vec![10.0, 0.0], vec![10.0, 10.0], vec![0.0, 10.0]];
+        assert_eq!(path_length(&square, false), 30.0);
+        assert_eq!(path_length(&square, true), 40.0);
+        assert_eq!(path_length(&[vec![0.0, 0.0, 0.0], vec![1.0, 2.0, 2.0]], false), 3.0);
+        assert_eq!(eval("path_length([[0, 0], [3, 4]])").0, Value::Number(5.0));
+        assert_eq!(eval("path_length([[0, 0], [3, 0], [3, 4]], closed = true)").0, Value::Number(12.0));
+        assert_eq!(eval("path_length([[0, 0], [3, 0], [3, 4]], true)").0, Value::Number(12.0));
+    }
+
+    /// Test resampling spaces points evenly by arc length.
+    #[test]
+    fn test_resample_path() {
+        let path = vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![4.0, 0.0]];
+        let points = resample_path(&path, 5, false);
+        let xs: Vec<f64> = points.iter().map(|p| p[0]).collect();
+        assert_eq!(xs, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        let square = vec![vec![0.0, 0.0], vec![2.0, 0.0], vec![2.0, 2.0], vec![0.0, 2.0]];
+        let points = resample_path(&square, 8, true);
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[1], vec![1.0, 0.0]);
+        assert_eq!(points[7], vec![0.0, 1.0]);
+
+        let (value, _) = eval("resample_path([[0, 0], [0, 3]], n = 4)");
+        let Value::List(points) = value else {
+            panic!("expected a list, got {:?}", value);
+        };
+        assert_eq!(points[2], Value::List(vec![Value::Number(0.0), Value::Number(2.0)]));
+    }
+
+    /// Test offsets mitre corners and keep end points square.
+    #[test]
+    fn test_offset_path() {
+        let square = [[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]];
+        assert_eq!(offset_path(&square, 1.0, true), vec![[-1.0, -1.0], [3.0, -1.0], [3.0, 3.0], [-1.0, 3.0]]);
+        assert_eq!(offset_path(&square, -0.5, true)[0], [0.5, 0.5]);
+
+        let open = offset_path(&[[0.0, 0.0], [2.0, 0.0], [2.0, 2.0]], 1.0, false);
+        assert_eq!(open, vec![[0.0, -1.0], [3.0, -1.0], [3.0, 2.0]]);
+    }
+
+    /// Test invalid arguments give undef with a warning.
+    #[test]
+    fn test_invalid_arguments() {
+        for expr in ["path_length(5)", "path_length([[0, 0]])", "resample_path([[0, 0], [1, 0]], 1.5)", "offset_path([[0, 0, 0], [1, 0, 0]], 1)"] {
+            let (value, warnings) = eval(expr);
+            assert_eq!(value, Value::Undef, "{}", expr);
+            assert_eq!(warnings.len(), 1, "{}", expr);
+        }
+    }
+}
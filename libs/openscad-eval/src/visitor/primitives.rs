@@ -12,6 +12,13 @@
 //!
 //! - `circle(r)` - Circle primitive
 //! - `square(size, center)` - Rectangle primitive
+//! - `text(text, size, font, halign, valign, spacing)` - Text outlines
+//!
+//! ## Degenerate Sizes
+//!
+//! Negative sizes and radii are clamped to zero, and a primitive with no
+//! volume or area is [`GeometryNode::Empty`]; both are reported as
+//! [`DiagnosticKind::Degenerate`] warnings.
 //!
 //! ## Example
 //!
@@ -19,13 +26,15 @@
 //! let node = eval_cube(&mut ctx, &args)?;
 //! ```
 
+use crate::diagnostic::DiagnosticKind;
 use crate::error::EvalError;
-use crate::geometry::GeometryNode;
+use crate::geometry::{GeometryNode, HAlign, VAlign};
 use crate::value::Value;
-use openscad_ast::Argument;
+use crate::polyhedron::validate_polyhedron;
+use openscad_ast::{Argument, Span};
 
+use super::arguments::{ArgumentResolver, ResolvedArguments};
 use super::context::EvalContext;
-use super::expressions::eval_expr;
 
 // =============================================================================
 // 3D PRIMITIVES
@@ -54,28 +63,14 @@ use super::expressions::eval_expr;
 /// cube(10, center=true); // Centered cube
 /// ```
 pub fn eval_cube(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
-    let mut size = [1.0, 1.0, 1.0];
-    let mut center = false;
-
-    // Process arguments
-    for (i, arg) in args.iter().enumerate() {
-        match arg {
-            Argument::Positional(expr) => {
-                if i == 0 {
-                    let value = eval_expr(ctx, expr)?;
-                    size = value.as_vec3()?;
-                } else if i == 1 {
-                    center = eval_expr(ctx, expr)?.as_boolean();
-                }
-            }
-            Argument::Named { name, value } => match name.as_str() {
-                "size" => size = eval_expr(ctx, value)?.as_vec3()?,
-                "center" => center = eval_expr(ctx, value)?.as_boolean(),
-                _ => ctx.warn(format!("Unknown argument for cube: {}", name)),
-            },
-        }
-    }
+    let args = ArgumentResolver::new("cube", &["size", "center"]).evaluate(ctx, args)?;
+    let size = args.get("size").map(Value::as_vec3).transpose()?.unwrap_or([1.0, 1.0, 1.0]);
+    let center = args.get("center").is_some_and(Value::as_boolean);
 
+    let size = clamp_sizes(ctx, "cube", size);
+    if size.contains(&0.0) {
+        return Ok(degenerate(ctx, "cube"));
+    }
     Ok(GeometryNode::Cube { size, center })
 }
 
@@ -94,28 +89,13 @@ pub fn eval_cube(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNod
 /// - `ctx`: Evaluation context
 /// - `args`: Arguments from the module call
 pub fn eval_sphere(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
-    let mut radius = 1.0;
-
-    for (i, arg) in args.iter().enumerate() {
-        match arg {
-            Argument::Positional(expr) => {
-                if i == 0 {
-                    radius = eval_expr(ctx, expr)?.as_number()?;
-                }
-            }
-            Argument::Named { name, value } => match name.as_str() {
-                "r" | "radius" => radius = eval_expr(ctx, value)?.as_number()?,
-                "d" | "diameter" => radius = eval_expr(ctx, value)?.as_number()? / 2.0,
-                "$fn" => {
-                    let fn_val = eval_expr(ctx, value)?.as_number()?;
-                    ctx.scope.define("$fn", Value::Number(fn_val));
-                }
-                _ => {}
-            },
-        }
+    let args = ArgumentResolver::new("sphere", &["r"]).named(&["d", "radius", "diameter"]).evaluate(ctx, args)?;
+    let [radius] = clamp_sizes(ctx, "sphere", [radius(&args, "r", "d")?.unwrap_or(1.0)]);
+    if radius == 0.0 {
+        return Ok(degenerate(ctx, "sphere"));
     }
 
-    let fn_ = ctx.calculate_fragments(radius);
+    let fn_ = args.with_specials(ctx, |ctx| ctx.calculate_fragments(radius));
     Ok(GeometryNode::Sphere { radius, fn_ })
 }
 
@@ -124,59 +104,38 @@ pub fn eval_sphere(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryN
 /// ## OpenSCAD Signature
 ///
 /// ```text
-/// cylinder(h, r, center);
 /// cylinder(h, r1, r2, center);
-/// cylinder(h, d, center);
+/// cylinder(h, r=radius, center=true);
+/// cylinder(h, d1=bottom, d2=top);
 /// ```
 ///
+/// As in OpenSCAD, a second positional argument is `r1`, not `r`:
+/// `cylinder(10, 5)` is a cone from radius 5 to the default 1.
+///
 /// ## Parameters
 ///
 /// - `ctx`: Evaluation context
 /// - `args`: Arguments from the module call
 pub fn eval_cylinder(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
-    let mut height = 1.0;
-    let mut radius1 = 1.0;
-    let mut radius2 = 1.0;
-    let mut center = false;
-
-    for (i, arg) in args.iter().enumerate() {
-        match arg {
-            Argument::Positional(expr) => {
-                if i == 0 {
-                    height = eval_expr(ctx, expr)?.as_number()?;
-                } else if i == 1 {
-                    let r = eval_expr(ctx, expr)?.as_number()?;
-                    radius1 = r;
-                    radius2 = r;
-                }
-            }
-            Argument::Named { name, value } => match name.as_str() {
-                "h" | "height" => height = eval_expr(ctx, value)?.as_number()?,
-                "r" | "radius" => {
-                    let r = eval_expr(ctx, value)?.as_number()?;
-                    radius1 = r;
-                    radius2 = r;
-                }
-                "r1" => radius1 = eval_expr(ctx, value)?.as_number()?,
-                "r2" => radius2 = eval_expr(ctx, value)?.as_number()?,
-                "d" | "diameter" => {
-                    let r = eval_expr(ctx, value)?.as_number()? / 2.0;
-                    radius1 = r;
-                    radius2 = r;
-                }
-                "d1" => radius1 = eval_expr(ctx, value)?.as_number()? / 2.0,
-                "d2" => radius2 = eval_expr(ctx, value)?.as_number()? / 2.0,
-                "center" => center = eval_expr(ctx, value)?.as_boolean(),
-                "$fn" => {
-                    let fn_val = eval_expr(ctx, value)?.as_number()?;
-                    ctx.scope.define("$fn", Value::Number(fn_val));
-                }
-                _ => {}
-            },
-        }
+    let args = ArgumentResolver::new("cylinder", &["h", "r1", "r2", "center"])
+        .named(&["r", "d", "d1", "d2", "height", "radius", "diameter"])
+        .evaluate(ctx, args)?;
+    let height = match args.get("h").or_else(|| args.get("height")) {
+        Some(h) => h.as_number()?,
+        None => 1.0,
+    };
+    let radius = radius(&args, "r", "d")?.unwrap_or(1.0);
+    let radius1 = radius_of(&args, "r1", "d1")?.unwrap_or(radius);
+    let radius2 = radius_of(&args, "r2", "d2")?.unwrap_or(radius);
+    let center = args.get("center").is_some_and(Value::as_boolean);
+
+    // A cone may come to a point, but not at both ends
+    let [height, radius1, radius2] = clamp_sizes(ctx, "cylinder", [height, radius1, radius2]);
+    if height == 0.0 || radius1.max(radius2) == 0.0 {
+        return Ok(degenerate(ctx, "cylinder"));
     }
 
-    let fn_ = ctx.calculate_fragments(radius1.max(radius2));
+    let fn_ = args.with_specials(ctx, |ctx| ctx.calculate_fragments(radius1.max(radius2)));
     Ok(GeometryNode::Cylinder {
         height,
         radius1,
@@ -186,6 +145,38 @@ pub fn eval_cylinder(ctx: &mut EvalContext, args: &[Argument]) -> Result<Geometr
     })
 }
 
+/// Radius given as `r`/`radius` or as diameter `d`/`diameter`; the
+/// diameter wins when both are given, as in OpenSCAD.
+fn radius(args: &ResolvedArguments, r: &str, d: &str) -> Result<Option<f64>, EvalError> {
+    match radius_of(args, r, d)? {
+        Some(radius) => Ok(Some(radius)),
+        None => radius_of(args, "radius", "diameter"),
+    }
+}
+
+/// Radius given as `r` or as diameter `d`, the diameter winning.
+pub(super) fn radius_of(args: &ResolvedArguments, r: &str, d: &str) -> Result<Option<f64>, EvalError> {
+    if let Some(d) = args.get(d) {
+        return Ok(Some(d.as_number()? / 2.0));
+    }
+    args.get(r).map(Value::as_number).transpose()
+}
+
+/// Clamp negative sizes of a primitive to zero, warning if any were.
+pub(super) fn clamp_sizes<const N: usize>(ctx: &mut EvalContext, callee: &str, sizes: [f64; N]) -> [f64; N] {
+    if sizes.iter().any(|&s| s < 0.0) {
+        ctx.diagnose(DiagnosticKind::Degenerate, format!("{}(): negative size clamped to 0", callee));
+    }
+    sizes.map(|s| s.max(0.0))
+}
+
+/// The empty geometry of a primitive with no volume or area, with a
+/// warning.
+pub(super) fn degenerate(ctx: &mut EvalContext, callee: &str) -> GeometryNode {
+    ctx.diagnose(DiagnosticKind::Degenerate, format!("{}(): size is zero, no geometry produced", callee));
+    GeometryNode::Empty
+}
+
 /// Evaluate polyhedron() call.
 ///
 /// ## OpenSCAD Signature
@@ -199,6 +190,7 @@ pub fn eval_cylinder(ctx: &mut EvalContext, args: &[Argument]) -> Result<Geometr
 ///
 /// - `ctx`: Evaluation context
 /// - `args`: Arguments from the module call
+/// - `span`: Source span of the call, reported with validation warnings
 ///
 /// ## Example
 ///
@@ -208,33 +200,27 @@ pub fn eval_cylinder(ctx: &mut EvalContext, args: &[Argument]) -> Result<Geometr
 ///     faces = [[0,1,2], [0,3,1], [0,2,3], [1,3,2]]
 /// );
 /// ```
-pub fn eval_polyhedron(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
-    let mut points: Vec<[f64; 3]> = Vec::new();
-    let mut faces: Vec<Vec<usize>> = Vec::new();
-    let mut convexity = 1;
-
-    for (i, arg) in args.iter().enumerate() {
-        match arg {
-            Argument::Positional(expr) => {
-                let val = eval_expr(ctx, expr)?;
-                match i {
-                    0 => points = parse_points(&val)?,
-                    1 => faces = parse_faces(&val)?,
-                    2 => convexity = val.as_number()? as i32,
-                    _ => {}
-                }
-            }
-            Argument::Named { name, value } => match name.as_str() {
-                "points" => points = parse_points(&eval_expr(ctx, value)?)?,
-                "faces" | "triangles" => faces = parse_faces(&eval_expr(ctx, value)?)?,
-                "convexity" => convexity = eval_expr(ctx, value)?.as_number()? as i32,
-                _ => {}
-            },
-        }
-    }
-
+pub fn eval_polyhedron(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    span: Span,
+) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("polyhedron", &["points", "faces", "convexity"])
+        .named(&["triangles"])
+        .evaluate(ctx, args)?;
+    let points = args.get("points").map(parse_points).transpose()?.unwrap_or_default();
+    let faces = args.get("faces").or_else(|| args.get("triangles")).map(parse_faces).transpose()?.unwrap_or_default();
     // Note: convexity is not stored in GeometryNode::Polyhedron
-    let _ = convexity;
+
+    // Report bad faces and topology; the mesher skips unusable faces
+    for issue in validate_polyhedron(&points, &faces) {
+        ctx.warn(format!(
+            "polyhedron at line {}, column {}: {}",
+            span.start.line + 1,
+            span.start.column + 1,
+            issue
+        ));
+    }
     
     Ok(GeometryNode::Polyhedron {
         points,
@@ -266,16 +252,13 @@ fn parse_faces(val: &Value) -> Result<Vec<Vec<usize>>, EvalError> {
             let mut faces = Vec::with_capacity(items.len());
             
             for item in items {
-                match item {
-                    Value::List(indices) => {
-                        let face: Vec<usize> = indices.iter()
-                            .filter_map(|v| v.as_number().ok().map(|n| n as usize))
-                            .collect();
-                        if face.len() >= 3 {
-                            faces.push(face);
-                        }
+                if let Value::List(indices) = item {
+                    let face: Vec<usize> = indices.iter()
+                        .filter_map(|v| v.as_number().ok().map(|n| n as usize))
+                        .collect();
+                    if face.len() >= 3 {
+                        faces.push(face);
                     }
-                    _ => {}
                 }
             }
             
@@ -304,28 +287,13 @@ fn parse_faces(val: &Value) -> Result<Vec<Vec<usize>>, EvalError> {
 /// - `ctx`: Evaluation context
 /// - `args`: Arguments from the module call
 pub fn eval_circle(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
-    let mut radius = 1.0;
-
-    for (i, arg) in args.iter().enumerate() {
-        match arg {
-            Argument::Positional(expr) => {
-                if i == 0 {
-                    radius = eval_expr(ctx, expr)?.as_number()?;
-                }
-            }
-            Argument::Named { name, value } => match name.as_str() {
-                "r" | "radius" => radius = eval_expr(ctx, value)?.as_number()?,
-                "d" | "diameter" => radius = eval_expr(ctx, value)?.as_number()? / 2.0,
-                "$fn" => {
-                    let fn_val = eval_expr(ctx, value)?.as_number()?;
-                    ctx.scope.define("$fn", Value::Number(fn_val));
-                }
-                _ => {}
-            },
-        }
+    let args = ArgumentResolver::new("circle", &["r"]).named(&["d", "radius", "diameter"]).evaluate(ctx, args)?;
+    let [radius] = clamp_sizes(ctx, "circle", [radius(&args, "r", "d")?.unwrap_or(1.0)]);
+    if radius == 0.0 {
+        return Ok(degenerate(ctx, "circle"));
     }
 
-    let fn_ = ctx.calculate_fragments(radius);
+    let fn_ = args.with_specials(ctx, |ctx| ctx.calculate_fragments(radius));
     Ok(GeometryNode::Circle { radius, fn_ })
 }
 
@@ -344,26 +312,14 @@ pub fn eval_circle(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryN
 /// - `ctx`: Evaluation context
 /// - `args`: Arguments from the module call
 pub fn eval_square(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
-    let mut size = [1.0, 1.0];
-    let mut center = false;
-
-    for (i, arg) in args.iter().enumerate() {
-        match arg {
-            Argument::Positional(expr) => {
-                if i == 0 {
-                    size = eval_expr(ctx, expr)?.as_vec2()?;
-                } else if i == 1 {
-                    center = eval_expr(ctx, expr)?.as_boolean();
-                }
-            }
-            Argument::Named { name, value } => match name.as_str() {
-                "size" => size = eval_expr(ctx, value)?.as_vec2()?,
-                "center" => center = eval_expr(ctx, value)?.as_boolean(),
-                _ => {}
-            },
-        }
-    }
+    let args = ArgumentResolver::new("square", &["size", "center"]).evaluate(ctx, args)?;
+    let size = args.get("size").map(Value::as_vec2).transpose()?.unwrap_or([1.0, 1.0]);
+    let center = args.get("center").is_some_and(Value::as_boolean);
 
+    let size = clamp_sizes(ctx, "square", size);
+    if size.contains(&0.0) {
+        return Ok(degenerate(ctx, "square"));
+    }
     Ok(GeometryNode::Square { size, center })
 }
 
@@ -388,33 +344,136 @@ pub fn eval_square(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryN
 /// polygon(points = [[0,0], [10,0], [10,10], [0,10]]);
 /// ```
 pub fn eval_polygon(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
-    let mut points: Vec<[f64; 2]> = Vec::new();
-    let mut paths: Option<Vec<Vec<usize>>> = None;
-    let mut _convexity = 1;
-
-    for (i, arg) in args.iter().enumerate() {
-        match arg {
-            Argument::Positional(expr) => {
-                let val = eval_expr(ctx, expr)?;
-                match i {
-                    0 => points = parse_points_2d(&val)?,
-                    1 => paths = Some(parse_paths(&val)?),
-                    2 => _convexity = val.as_number()? as i32,
-                    _ => {}
-                }
-            }
-            Argument::Named { name, value } => match name.as_str() {
-                "points" => points = parse_points_2d(&eval_expr(ctx, value)?)?,
-                "paths" => paths = Some(parse_paths(&eval_expr(ctx, value)?)?),
-                "convexity" => _convexity = eval_expr(ctx, value)?.as_number()? as i32,
-                _ => {}
-            },
-        }
-    }
+    let args = ArgumentResolver::new("polygon", &["points", "paths", "convexity"]).evaluate(ctx, args)?;
+    let points = args.get("points").map(parse_points_2d).transpose()?.unwrap_or_default();
+    let paths = args.get("paths").map(parse_paths).transpose()?;
 
     Ok(GeometryNode::Polygon { points, paths })
 }
 
+/// Evaluate text() call.
+///
+/// Only resolves the parameters; glyph outlines are produced by the font
+/// engine when the node is meshed. Unknown alignments fall back to the
+/// default with a warning.
+///
+/// ## OpenSCAD Signature
+///
+/// ```text
+/// text(text, size, font, halign = ..., valign = ..., spacing = ...);
+/// ```
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `args`: Arguments from the module call
+///
+/// ## Example
+///
+/// ```text
+/// text("Hello", size=10, font="Liberation Sans");
+/// text("42", halign="center", valign="center");
+/// ```
+pub fn eval_text(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("text", &["text", "size", "font"])
+        .named(&["halign", "valign", "spacing", "direction", "language", "script"])
+        .evaluate(ctx, args)?;
+    let text = args.get("text").map(text_value).transpose()?.unwrap_or_default();
+    let size = args.get("size").map(Value::as_number).transpose()?.unwrap_or(10.0);
+    let font = args.get("font").map(text_value).transpose()?;
+    let halign = args.get("halign").map(text_value).transpose()?.unwrap_or_else(|| "left".to_string());
+    let valign = args.get("valign").map(text_value).transpose()?.unwrap_or_else(|| "baseline".to_string());
+    let spacing = args.get("spacing").map(Value::as_number).transpose()?.unwrap_or(1.0);
+
+    let halign = HAlign::parse(&halign).unwrap_or_else(|| {
+        ctx.warn(format!("text: unknown halign \"{}\", using \"left\"", halign));
+        HAlign::default()
+    });
+    let valign = VAlign::parse(&valign).unwrap_or_else(|| {
+        ctx.warn(format!("text: unknown valign \"{}\", using \"baseline\"", valign));
+        VAlign::default()
+    });
+
+    let fn_ = args.with_specials(ctx, |ctx| ctx.calculate_fragments(size));
+    Ok(GeometryNode::Text { text, size, font, halign, valign, spacing, fn_ })
+}
+
+/// Evaluate import() call.
+///
+/// The file is only named here, resolved by the file provider next to the
+/// file naming it like `include` (see [`FileProvider::resolve`](crate::files::FileProvider::resolve));
+/// reading and parsing it happens when the geometry is meshed. `$fn` is
+/// kept for the curves of 2D files.
+///
+/// ## OpenSCAD
+///
+/// ```text
+/// import("part.stl");
+/// import(file="part.stl", center=true, convexity=4);
+/// import("logo.svg", $fn=64);
+/// ```
+pub fn eval_import(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
+    // Only file and center matter here; the rest are for 2D formats or
+    // the preview renderer
+    let args = ArgumentResolver::new("import", &["file", "layer", "convexity", "origin", "scale"])
+        .named(&["center", "dpi", "id"])
+        .evaluate(ctx, args)?;
+    let file = args.get("file").map(file_name).transpose()?;
+    let center = args.get("center").is_some_and(Value::as_boolean);
+
+    let file = file.ok_or_else(|| EvalError::InvalidArgument("import requires a file name".to_string()))?;
+    let file = resolve_file(ctx, file);
+    let fn_ = args.with_specials(ctx, |ctx| ctx.scope.fn_value());
+    Ok(GeometryNode::Import { file, center, fn_ })
+}
+
+/// Evaluate surface() call.
+///
+/// Like import(), the file is only named here and read at mesh time.
+/// `invert` only affects image files.
+///
+/// ## OpenSCAD
+///
+/// ```text
+/// surface("map.dat");
+/// surface(file="logo.png", center=true, invert=true, convexity=5);
+/// ```
+pub fn eval_surface(ctx: &mut EvalContext, args: &[Argument]) -> Result<GeometryNode, EvalError> {
+    // convexity only matters to the preview renderer
+    let args = ArgumentResolver::new("surface", &["file", "center", "convexity"]).named(&["invert"]).evaluate(ctx, args)?;
+    let file = args.get("file").map(file_name).transpose()?;
+    let center = args.get("center").is_some_and(Value::as_boolean);
+    let invert = args.get("invert").is_some_and(Value::as_boolean);
+
+    let file = file.ok_or_else(|| EvalError::InvalidArgument("surface requires a file name".to_string()))?;
+    let file = resolve_file(ctx, file);
+    Ok(GeometryNode::Surface { file, center, invert })
+}
+
+/// Convert an import() or surface() file argument.
+fn file_name(val: &Value) -> Result<String, EvalError> {
+    match val {
+        Value::String(s) => Ok(s.clone()),
+        _ => Err(EvalError::TypeError(format!("Expected string for file, got {:?}", val))),
+    }
+}
+
+/// Resolve an import() or surface() file name through the file provider,
+/// relative to the file being evaluated, as `include` does.
+fn resolve_file(ctx: &EvalContext, file: String) -> String {
+    let from = ctx.file_stack.last().map(String::as_str);
+    ctx.file_provider.as_ref().and_then(|provider| provider.resolve(&file, from)).unwrap_or(file)
+}
+
+/// Convert a text() string argument; numbers are printed as text.
+fn text_value(val: &Value) -> Result<String, EvalError> {
+    match val {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(_) => Ok(val.to_string()),
+        _ => Err(EvalError::TypeError(format!("Expected string for text, got {:?}", val))),
+    }
+}
+
 /// Parse 2D points array for polygon.
 fn parse_points_2d(val: &Value) -> Result<Vec<[f64; 2]>, EvalError> {
     match val {
@@ -439,16 +498,13 @@ fn parse_paths(val: &Value) -> Result<Vec<Vec<usize>>, EvalError> {
             let mut paths = Vec::with_capacity(items.len());
             
             for item in items {
-                match item {
-                    Value::List(indices) => {
-                        let path: Vec<usize> = indices.iter()
-                            .filter_map(|v| v.as_number().ok().map(|n| n as usize))
-                            .collect();
-                        if !path.is_empty() {
-                            paths.push(path);
-                        }
+                if let Value::List(indices) = item {
+                    let path: Vec<usize> = indices.iter()
+                        .filter_map(|v| v.as_number().ok().map(|n| n as usize))
+                        .collect();
+                    if !path.is_empty() {
+                        paths.push(path);
                     }
-                    _ => {}
                 }
             }
             
@@ -525,6 +581,18 @@ mod tests {
         }
     }
 
+    /// Test cylinder() takes h, r1, r2, center by position and d over r.
+    #[test]
+    fn test_eval_cylinder_arguments() {
+        let result = crate::evaluate("cylinder(10, 5); cylinder(2, r = 4, d2 = 2, $fn = 7); sphere(1);").unwrap();
+        let GeometryNode::Group { children } = &result.geometry else { panic!("expected group") };
+        assert!(matches!(children[0], GeometryNode::Cylinder { height: 10.0, radius1: 5.0, radius2: 1.0, .. }));
+        assert!(matches!(children[1], GeometryNode::Cylinder { radius1: 4.0, radius2: 1.0, fn_: 7, .. }));
+        // $fn only applied to its own call
+        assert!(!matches!(children[2], GeometryNode::Sphere { fn_: 7, .. }));
+        assert!(result.warnings.is_empty());
+    }
+
     #[test]
     fn test_eval_circle_default() {
         let mut ctx = ctx();
@@ -550,4 +618,92 @@ mod tests {
             _ => panic!("Expected Square"),
         }
     }
+
+    /// Test text() resolves arguments and falls back on bad alignment.
+    #[test]
+    fn test_eval_text() {
+        let mut ctx = ctx();
+        let args = vec![
+            Argument::Positional(Expression::String("Hi".to_string())),
+            Argument::Named { name: "size".to_string(), value: Expression::Number(5.0) },
+            Argument::Named { name: "halign".to_string(), value: Expression::String("center".to_string()) },
+            Argument::Named { name: "valign".to_string(), value: Expression::String("middle".to_string()) },
+        ];
+        match eval_text(&mut ctx, &args).unwrap() {
+            GeometryNode::Text { text, size, halign, valign, spacing, .. } => {
+                assert_eq!(text, "Hi");
+                assert_eq!(size, 5.0);
+                assert_eq!(halign, HAlign::Center);
+                assert_eq!(valign, VAlign::Baseline);
+                assert_eq!(spacing, 1.0);
+            }
+            _ => panic!("Expected Text"),
+        }
+        assert_eq!(ctx.warnings.len(), 1);
+    }
+
+    /// Test import names the file and requires one.
+    #[test]
+    fn test_eval_import() {
+        let mut ctx = ctx();
+        let args = vec![
+            Argument::Positional(Expression::String("part.stl".to_string())),
+            Argument::Named { name: "center".to_string(), value: Expression::Boolean(true) },
+            Argument::Named { name: "convexity".to_string(), value: Expression::Number(4.0) },
+        ];
+        match eval_import(&mut ctx, &args).unwrap() {
+            GeometryNode::Import { file, center, fn_ } => {
+                assert_eq!(file, "part.stl");
+                assert!(center);
+                assert_eq!(fn_, 0);
+            }
+            _ => panic!("Expected Import"),
+        }
+        assert!(ctx.warnings.is_empty());
+
+        assert!(eval_import(&mut ctx, &[]).is_err());
+    }
+
+    /// Test surface names the file and takes center and invert.
+    #[test]
+    fn test_eval_surface() {
+        let mut ctx = ctx();
+        let args = vec![
+            Argument::Named { name: "file".to_string(), value: Expression::String("map.png".to_string()) },
+            Argument::Named { name: "invert".to_string(), value: Expression::Boolean(true) },
+            Argument::Named { name: "convexity".to_string(), value: Expression::Number(5.0) },
+        ];
+        match eval_surface(&mut ctx, &args).unwrap() {
+            GeometryNode::Surface { file, center, invert } => {
+                assert_eq!(file, "map.png");
+                assert!(!center);
+                assert!(invert);
+            }
+            _ => panic!("Expected Surface"),
+        }
+        assert!(ctx.warnings.is_empty());
+
+        assert!(eval_surface(&mut ctx, &[]).is_err());
+    }
+
+    /// Test negative sizes are clamped and zero-sized primitives are empty.
+    #[test]
+    fn test_degenerate_sizes() {
+        let result = crate::evaluate("cube([2, -1, 3]);").unwrap();
+        assert!(result.geometry.is_empty());
+        let kinds: Vec<_> = result.diagnostics.iter().map(|d| d.kind).collect();
+        assert_eq!(kinds, [DiagnosticKind::Degenerate; 2]);
+        assert_eq!(result.warnings, ["cube(): negative size clamped to 0", "cube(): size is zero, no geometry produced"]);
+
+        // A cone may come to a point
+        let result = crate::evaluate("cylinder(h = 2, r1 = -1, r2 = 1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Cylinder { radius1, .. } if radius1 == 0.0));
+        assert_eq!(result.diagnostics.len(), 1);
+
+        for source in ["sphere(0);", "circle(d = -2);", "square([0, 1]);", "cylinder(h = 0);"] {
+            let result = crate::evaluate(source).unwrap();
+            assert!(result.geometry.is_empty(), "{}", source);
+            assert!(result.diagnostics.iter().all(|d| d.kind == DiagnosticKind::Degenerate), "{}", source);
+        }
+    }
 }
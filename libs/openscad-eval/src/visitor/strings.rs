@@ -0,0 +1,188 @@
+//! # String Functions
+//!
+//! Built-in functions that build and take apart strings.
+//!
+//! ## Functions
+//!
+//! - `str(...)` - Concatenation of the arguments' printed forms
+//! - `chr(code, ...)` - Characters from Unicode code points, or lists of them
+//! - `ord(char)` - Code point of a one-character string
+//!
+//! `len(s)` counts characters and `s[i]` is the `i`-th character (see
+//! [`super::math`] and [`super::expressions`]).
+//!
+//! ## Example
+//!
+//! ```text
+//! label = str("Part #", 7, " v", 1.5);  // "Part #7 v1.5"
+//! text(label);
+//! echo(chr([72, 105]));                 // "Hi"
+//! echo(ord("A"));                       // 65
+//! ```
+
+use crate::error::EvalError;
+use crate::value::Value;
+
+use super::context::EvalContext;
+
+/// Names of the string functions, for dispatch.
+pub const STRING_FUNCTIONS: &[&str] = &["str", "chr", "ord"];
+
+// =============================================================================
+// DISPATCH
+// =============================================================================
+
+/// Evaluate a string function.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context, for warnings and the iteration limit
+/// - `name`: One of [`STRING_FUNCTIONS`]
+/// - `args`: Evaluated arguments; names are ignored, as in OpenSCAD
+///
+/// ## Returns
+///
+/// The result, or `undef` if the arguments are invalid
+///
+/// ## Errors
+///
+/// `EvalError::LimitExceeded` if expanding a range of code points goes
+/// over the iteration limit.
+pub fn eval_string_function(ctx: &mut EvalContext, name: &str, args: &[(Option<String>, Value)]) -> Result<Value, EvalError> {
+    Ok(match name {
+        "str" => Value::String(args.iter().map(|(_, v)| v.to_string()).collect()),
+        "chr" => {
+            let mut text = String::new();
+            for (_, value) in args {
+                if !push_chars(ctx, &mut text, value)? {
+                    ctx.warn("chr: expected Unicode code points".to_string());
+                    return Ok(Value::Undef);
+                }
+            }
+            Value::String(text)
+        }
+        "ord" => {
+            let mut chars = match args.first() {
+                Some((_, Value::String(s))) => s.chars(),
+                _ => "".chars(),
+            };
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Value::Number(f64::from(u32::from(c))),
+                _ => {
+                    ctx.warn("ord: expected a string of one character".to_string());
+                    Value::Undef
+                }
+            }
+        }
+        _ => Value::Undef,
+    })
+}
+
+/// Append the characters of a code point or (nested) list of them;
+/// `false` if any is not a valid code point.
+fn push_chars(ctx: &mut EvalContext, text: &mut String, value: &Value) -> Result<bool, EvalError> {
+    match value {
+        Value::Number(n) if n.fract() == 0.0 && *n >= 1.0 && *n <= f64::from(u32::MAX) => {
+            Ok(char::from_u32(*n as u32).map(|c| text.push(c)).is_some())
+        }
+        Value::List(items) => {
+            for item in items {
+                if !push_chars(ctx, text, item)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        Value::Range { .. } => {
+            ctx.count_iterations(value.iteration_count())?;
+            for item in value.clone().into_iteration_values() {
+                if !push_chars(ctx, text, &item)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::evaluate_statements;
+
+    /// Evaluate `x = <expr>;`, returning `x` and the warnings.
+    fn eval(expr: &str) -> (Value, Vec<String>) {
+        let ast = openscad_ast::parse(&format!("x = {};", expr)).unwrap();
+        let mut ctx = EvalContext::new();
+        evaluate_statements(&mut ctx, &ast.statements).unwrap();
+        (ctx.scope.get("x").cloned().unwrap_or(Value::Undef), ctx.warnings)
+    }
+
+    /// String result of `expr`.
+    fn string(expr: &str) -> String {
+        match eval(expr).0 {
+            Value::String(s) => s,
+            other => panic!("{}: expected a string, got {:?}", expr, other),
+        }
+    }
+
+    /// Test str() concatenates printed forms of any values.
+    #[test]
+    fn test_str() {
+        assert_eq!(string("str(\"Part #\", 7)"), "Part #7");
+        assert_eq!(string("str(1.5, \" \", true, \" \", undef)"), "1.5 true undef");
+        assert_eq!(string("str([1, \"a\"])"), "[1, \"a\"]");
+        assert_eq!(string("str()"), "");
+    }
+
+    /// Test chr() accepts code points, lists and ranges.
+    #[test]
+    fn test_chr() {
+        assert_eq!(string("chr(65)"), "A");
+        assert_eq!(string("chr([72, 105])"), "Hi");
+        assert_eq!(string("chr(97, [98, 99])"), "abc");
+        assert_eq!(string("chr([65:67])"), "ABC");
+        assert_eq!(string("chr(8364)"), "€");
+
+        let (value, warnings) = eval("chr(-1)");
+        assert_eq!(value, Value::Undef);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    /// Test ord() inverts chr() and rejects anything but one character.
+    #[test]
+    fn test_ord() {
+        assert_eq!(eval("ord(\"A\")").0, Value::Number(65.0));
+        assert_eq!(eval("ord(chr(8364))").0, Value::Number(8364.0));
+        for expr in ["ord(\"ab\")", "ord(\"\")", "ord(65)"] {
+            let (value, warnings) = eval(expr);
+            assert_eq!(value, Value::Undef, "{}", expr);
+            assert_eq!(warnings.len(), 1, "{}", expr);
+        }
+    }
+
+    /// Test string indexing and len count characters, not bytes.
+    #[test]
+    fn test_indexing() {
+        assert_eq!(string("\"héllo\"[1]"), "é");
+        assert_eq!(string("str(\"ab\", 1)[2]"), "1");
+        assert_eq!(eval("len(\"héllo\")").0, Value::Number(5.0));
+    }
+
+    /// Test indices outside a string or list give undef rather than
+    /// wrapping to the first element or failing.
+    #[test]
+    fn test_indexing_out_of_range() {
+        for expr in [
+            "\"ab\"[-1]", "\"ab\"[-0.5]", "\"ab\"[2]", "\"\"[0]", "\"ab\"[1e30]",
+            "[1, 2, 3][-1]", "[1, 2, 3][-0.5]", "[1, 2, 3][5]", "[][0]", "[1, 2, 3][1e30]",
+        ] {
+            assert_eq!(eval(expr), (Value::Undef, Vec::new()), "{}", expr);
+        }
+    }
+}
@@ -5,10 +5,17 @@
 //! ## Transforms
 //!
 //! - `translate([x, y, z])` - Move geometry
-//! - `rotate([x, y, z])` - Rotate geometry
+//! - `rotate([x, y, z])`, `rotate(a, v)` - Rotate geometry
 //! - `scale([x, y, z])` - Scale geometry
+//! - `resize([x, y, z], auto)` - Scale geometry to a size
 //! - `mirror([x, y, z])` - Mirror geometry
-//! - `color([r, g, b, a])` - Color geometry
+//! - `multmatrix(m)` - Apply an affine matrix
+//! - `color([r, g, b, a] | "name" | "#hex", alpha)` - Color geometry
+//!
+//! Arguments bind by OpenSCAD's rules (see
+//! [`arguments`](super::arguments)): `rotate(v = [1, 0, 0], a = 90)` is
+//! `rotate(90, [1, 0, 0])`, and unknown names are ignored with a warning.
+//! Special variables passed to a transform apply to its children.
 //!
 //! ## Example
 //!
@@ -16,12 +23,14 @@
 //! let node = eval_translate(&mut ctx, &args, &children)?;
 //! ```
 
+use crate::colors::parse_color;
 use crate::error::EvalError;
 use crate::geometry::GeometryNode;
+use crate::value::Value;
 use openscad_ast::{Argument, Statement};
 
+use super::arguments::{ArgumentResolver, ResolvedArguments};
 use super::context::{EvalContext, evaluate_statements};
-use super::expressions::eval_expr;
 
 // =============================================================================
 // TRANSFORMS
@@ -46,20 +55,12 @@ pub fn eval_translate(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let offset = args.first()
-        .map(|a| match a {
-            Argument::Positional(e) => eval_expr(ctx, e),
-            Argument::Named { value, .. } => eval_expr(ctx, value),
-        })
-        .transpose()?
-        .map(|v| v.as_vec3())
-        .transpose()?
-        .unwrap_or([0.0, 0.0, 0.0]);
-
-    let child = evaluate_statements(ctx, children)?;
+    let args = ArgumentResolver::new("translate", &["v"]).evaluate(ctx, args)?;
+    let offset = args.get("v").map(Value::as_vec3).transpose()?.unwrap_or([0.0, 0.0, 0.0]);
+
     Ok(GeometryNode::Translate {
         offset,
-        child: Box::new(child),
+        child: evaluate_children(ctx, &args, children)?,
     })
 }
 
@@ -69,9 +70,13 @@ pub fn eval_translate(
 ///
 /// ```text
 /// rotate([x, y, z]) child;       // Euler angles in degrees
-/// rotate(a, v=[x, y, z]) child;  // Angle around axis (not yet supported)
+/// rotate(a) child;               // Angle around Z
+/// rotate(a, v=[x, y, z]) child;  // Angle around axis
 /// ```
 ///
+/// `v` only counts with a single angle; that rotation is a
+/// [`GeometryNode::Multmatrix`], about Z if `v` is the zero vector.
+///
 /// ## Parameters
 ///
 /// - `ctx`: Evaluation context
@@ -82,20 +87,21 @@ pub fn eval_rotate(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let angles = args.first()
-        .map(|a| match a {
-            Argument::Positional(e) => eval_expr(ctx, e),
-            Argument::Named { value, .. } => eval_expr(ctx, value),
-        })
-        .transpose()?
-        .map(|v| v.as_vec3())
-        .transpose()?
-        .unwrap_or([0.0, 0.0, 0.0]);
-
-    let child = evaluate_statements(ctx, children)?;
+    let args = ArgumentResolver::new("rotate", &["a", "v"]).evaluate(ctx, args)?;
+    let angles = match (args.get("a"), args.get("v")) {
+        (Some(&Value::Number(a)), Some(v)) => {
+            let matrix = axis_rotation(a, v.as_vec3()?);
+            return Ok(GeometryNode::Multmatrix { matrix, child: evaluate_children(ctx, &args, children)? });
+        }
+        // A single angle turns about Z, as in 2D
+        (Some(&Value::Number(a)), None) => [0.0, 0.0, a],
+        (Some(angles), _) => angles.as_vec3()?,
+        (None, _) => [0.0, 0.0, 0.0],
+    };
+
     Ok(GeometryNode::Rotate {
         angles,
-        child: Box::new(child),
+        child: evaluate_children(ctx, &args, children)?,
     })
 }
 
@@ -118,20 +124,51 @@ pub fn eval_scale(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let factors = args.first()
-        .map(|a| match a {
-            Argument::Positional(e) => eval_expr(ctx, e),
-            Argument::Named { value, .. } => eval_expr(ctx, value),
-        })
-        .transpose()?
-        .map(|v| v.as_vec3())
-        .transpose()?
-        .unwrap_or([1.0, 1.0, 1.0]);
-
-    let child = evaluate_statements(ctx, children)?;
+    let args = ArgumentResolver::new("scale", &["v"]).evaluate(ctx, args)?;
+    let factors = args.get("v").map(Value::as_vec3).transpose()?.unwrap_or([1.0, 1.0, 1.0]);
+
     Ok(GeometryNode::Scale {
         factors,
-        child: Box::new(child),
+        child: evaluate_children(ctx, &args, children)?,
+    })
+}
+
+/// Evaluate resize() call.
+///
+/// ## OpenSCAD Signature
+///
+/// ```text
+/// resize([x, y, z]) child;
+/// resize(newsize=[x, y, z], auto=false) child;
+/// resize([x, y, z], auto=[true, false, true]) child;
+/// ```
+///
+/// `auto` is a single flag for all axes or one per axis; missing flags
+/// are false. The child's size is only known once meshed, so the node
+/// keeps the target and the mesh builder derives the scale.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `args`: Target size and auto flags
+/// - `children`: Child statements to resize
+pub fn eval_resize(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("resize", &["newsize", "auto"]).evaluate(ctx, args)?;
+    let newsize = args.get("newsize").map(Value::as_vec3).transpose()?.unwrap_or([0.0; 3]);
+    let auto = match args.get("auto") {
+        Some(Value::List(flags)) => std::array::from_fn(|i| flags.get(i).is_some_and(Value::as_boolean)),
+        Some(flag) => [flag.as_boolean(); 3],
+        None => [false; 3],
+    };
+
+    Ok(GeometryNode::Resize {
+        newsize,
+        auto,
+        child: evaluate_children(ctx, &args, children)?,
     })
 }
 
@@ -156,20 +193,58 @@ pub fn eval_mirror(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
-    let normal = args.first()
-        .map(|a| match a {
-            Argument::Positional(e) => eval_expr(ctx, e),
-            Argument::Named { value, .. } => eval_expr(ctx, value),
-        })
-        .transpose()?
-        .map(|v| v.as_vec3())
-        .transpose()?
-        .unwrap_or([1.0, 0.0, 0.0]);
-
-    let child = evaluate_statements(ctx, children)?;
+    let args = ArgumentResolver::new("mirror", &["v"]).evaluate(ctx, args)?;
+    let normal = args.get("v").map(Value::as_vec3).transpose()?.unwrap_or([1.0, 0.0, 0.0]);
+
     Ok(GeometryNode::Mirror {
         normal,
-        child: Box::new(child),
+        child: evaluate_children(ctx, &args, children)?,
+    })
+}
+
+/// Evaluate multmatrix() call.
+///
+/// ## OpenSCAD Signature
+///
+/// ```text
+/// multmatrix(m = [[1, 0, 0, tx], [0, 1, 0, ty], [0, 0, 1, tz]]) child;
+/// multmatrix([[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]) child;
+/// ```
+///
+/// The matrix is given by rows, with the translation in the last column.
+/// Entries missing from short rows, or a missing last row, keep their
+/// identity values, so a 3x4 matrix is an affine 4x4.
+///
+/// ## Parameters
+///
+/// - `ctx`: Evaluation context
+/// - `args`: Matrix argument
+/// - `children`: Child statements to transform
+pub fn eval_multmatrix(
+    ctx: &mut EvalContext,
+    args: &[Argument],
+    children: &[Statement],
+) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("multmatrix", &["m"]).evaluate(ctx, args)?;
+    let mut matrix = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+    match args.get("m") {
+        Some(Value::List(rows)) => {
+            for (row, value) in matrix.iter_mut().zip(rows) {
+                let Value::List(_) = value else {
+                    return Err(EvalError::TypeError(format!("multmatrix() expects rows of numbers, got {:?}", value)));
+                };
+                for (entry, n) in row.iter_mut().zip(value.as_number_list()?) {
+                    *entry = n;
+                }
+            }
+        }
+        Some(other) => return Err(EvalError::TypeError(format!("multmatrix() expects a matrix, got {:?}", other))),
+        None => {}
+    }
+
+    Ok(GeometryNode::Multmatrix {
+        matrix,
+        child: evaluate_children(ctx, &args, children)?,
     })
 }
 
@@ -180,9 +255,15 @@ pub fn eval_mirror(
 /// ```text
 /// color([r, g, b]) child;
 /// color([r, g, b, a]) child;
-/// color("colorname") child;  // Not yet supported
+/// color(c=[r, g, b], alpha=a) child;
+/// color("colorname", alpha) child;
+/// color("#rrggbb") child;
 /// ```
 ///
+/// Strings are SVG color names or `#` hex (see [`crate::colors`]).
+/// `alpha` overrides the alpha of `c`. An unknown color name leaves the
+/// children uncolored with a warning, as in OpenSCAD.
+///
 /// ## Parameters
 ///
 /// - `ctx`: Evaluation context
@@ -193,25 +274,58 @@ pub fn eval_color(
     args: &[Argument],
     children: &[Statement],
 ) -> Result<GeometryNode, EvalError> {
+    let args = ArgumentResolver::new("color", &["c", "alpha"]).evaluate(ctx, args)?;
     let mut rgba = [1.0, 1.0, 1.0, 1.0];
-
-    if let Some(arg) = args.first() {
-        if let Argument::Positional(expr) = arg {
-            let value = eval_expr(ctx, expr)?;
-            let nums = value.as_number_list()?;
-            for (i, n) in nums.iter().take(4).enumerate() {
-                rgba[i] = *n;
+    match args.get("c") {
+        Some(Value::String(name)) => match parse_color(name) {
+            Some(color) => rgba = color,
+            None => {
+                ctx.warn(format!("color(): unable to parse color \"{}\"", name));
+                return Ok(*evaluate_children(ctx, &args, children)?);
+            }
+        },
+        Some(c) => {
+            for (channel, n) in rgba.iter_mut().zip(c.as_number_list()?) {
+                *channel = n;
             }
         }
+        None => {}
+    }
+    if let Some(alpha) = args.get("alpha") {
+        rgba[3] = alpha.as_number()?;
     }
 
-    let child = evaluate_statements(ctx, children)?;
     Ok(GeometryNode::Color {
         rgba,
-        child: Box::new(child),
+        child: evaluate_children(ctx, &args, children)?,
     })
 }
 
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Evaluate the children of a transform, with the special variables
+/// passed to it defined.
+fn evaluate_children(ctx: &mut EvalContext, args: &ResolvedArguments, children: &[Statement]) -> Result<Box<GeometryNode>, EvalError> {
+    args.with_specials(ctx, |ctx| evaluate_statements(ctx, children)).map(Box::new)
+}
+
+/// Matrix of a rotation by `degrees` about `axis`, counter-clockwise
+/// looking down the axis; about Z for the zero vector.
+fn axis_rotation(degrees: f64, axis: [f64; 3]) -> [[f64; 4]; 4] {
+    let length = axis.iter().map(|c| c * c).sum::<f64>().sqrt();
+    let [x, y, z] = if length > 0.0 { axis.map(|c| c / length) } else { [0.0, 0.0, 1.0] };
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let t = 1.0 - cos;
+    [
+        [t * x * x + cos, t * x * y - sin * z, t * x * z + sin * y, 0.0],
+        [t * x * y + sin * z, t * y * y + cos, t * y * z - sin * x, 0.0],
+        [t * x * z - sin * y, t * y * z + sin * x, t * z * z + cos, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -266,6 +380,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_eval_rotate_scalar_about_z() {
+        let mut ctx = ctx();
+        let args = vec![Argument::Positional(Expression::Number(90.0))];
+        let node = eval_rotate(&mut ctx, &args, &[]).unwrap();
+        match node {
+            GeometryNode::Rotate { angles, .. } => {
+                assert_eq!(angles, [0.0, 0.0, 90.0]);
+            }
+            _ => panic!("Expected Rotate"),
+        }
+    }
+
     #[test]
     fn test_eval_scale_default() {
         let mut ctx = ctx();
@@ -278,6 +405,18 @@ mod tests {
         }
     }
 
+    /// Test resize() reads the target size and both forms of `auto`.
+    #[test]
+    fn test_eval_resize() {
+        let resize = |source: &str| match crate::evaluate(source).unwrap().geometry {
+            GeometryNode::Resize { newsize, auto, .. } => (newsize, auto),
+            other => panic!("Expected Resize, got {:?}", other),
+        };
+        assert_eq!(resize("resize([20, 0, 5]) cube(1);"), ([20.0, 0.0, 5.0], [false; 3]));
+        assert_eq!(resize("resize([20, 0, 0], auto = true) cube(1);"), ([20.0, 0.0, 0.0], [true; 3]));
+        assert_eq!(resize("resize(newsize = [20, 10], auto = [false, true]) square(1);"), ([20.0, 10.0, 0.0], [false, true, false]));
+    }
+
     #[test]
     fn test_eval_mirror_default() {
         let mut ctx = ctx();
@@ -290,6 +429,75 @@ mod tests {
         }
     }
 
+    /// Test multmatrix() fills 3x4 and short rows from the identity.
+    #[test]
+    fn test_eval_multmatrix() {
+        let matrix = |source: &str| match crate::evaluate(source).unwrap().geometry {
+            GeometryNode::Multmatrix { matrix, .. } => matrix,
+            other => panic!("Expected Multmatrix, got {:?}", other),
+        };
+        assert_eq!(
+            matrix("multmatrix([[1, 0.5, 0, 10], [0, 1, 0, 20], [0, 0, -1, 30]]) cube(1);"),
+            [[1.0, 0.5, 0.0, 10.0], [0.0, 1.0, 0.0, 20.0], [0.0, 0.0, -1.0, 30.0], [0.0, 0.0, 0.0, 1.0]],
+        );
+        assert_eq!(matrix("multmatrix(m = [[2], [0, 3]]) cube(1);")[1], [0.0, 3.0, 0.0, 0.0]);
+        assert!(crate::evaluate("multmatrix(5) cube(1);").is_err());
+        assert!(crate::evaluate("multmatrix([1, 2, 3]) cube(1);").is_err());
+    }
+
+    /// Test arguments bind by name in any order, as in OpenSCAD.
+    #[test]
+    fn test_named_arguments() {
+        let node = |source: &str| crate::evaluate(source).unwrap().geometry;
+        assert!(matches!(node("translate(v = [1, 2, 3]) cube(1);"), GeometryNode::Translate { offset: [1.0, 2.0, 3.0], .. }));
+        assert!(matches!(node("scale(v = [2, 3, 4]) cube(1);"), GeometryNode::Scale { factors: [2.0, 3.0, 4.0], .. }));
+        assert!(matches!(node("mirror(v = [0, 1, 0]) cube(1);"), GeometryNode::Mirror { normal: [0.0, 1.0, 0.0], .. }));
+        assert!(matches!(node("rotate(a = [0, 90, 0]) cube(1);"), GeometryNode::Rotate { angles: [0.0, 90.0, 0.0], .. }));
+        assert!(matches!(node("color(alpha = 0.5, c = [1, 0, 0]) cube(1);"), GeometryNode::Color { rgba: [1.0, 0.0, 0.0, 0.5], .. }));
+        assert!(matches!(node("color([0, 0, 1, 0.2], 0.5) cube(1);"), GeometryNode::Color { rgba: [0.0, 0.0, 1.0, 0.5], .. }));
+    }
+
+    /// Test color() takes names and hex strings, and skips unknown names.
+    #[test]
+    fn test_color_strings() {
+        let node = |source: &str| crate::evaluate(source).unwrap().geometry;
+        assert!(matches!(node("color(\"red\") cube(1);"), GeometryNode::Color { rgba: [1.0, 0.0, 0.0, 1.0], .. }));
+        assert!(matches!(node("color(\"Blue\", 0.5) cube(1);"), GeometryNode::Color { rgba: [0.0, 0.0, 1.0, 0.5], .. }));
+        assert!(matches!(node("color(\"#ff0000\") cube(1);"), GeometryNode::Color { rgba: [1.0, 0.0, 0.0, 1.0], .. }));
+        assert!(matches!(node("color(c = \"#0f0\", alpha = 0.25) cube(1);"), GeometryNode::Color { rgba: [0.0, 1.0, 0.0, 0.25], .. }));
+
+        let result = crate::evaluate("color(\"reddish\") cube(1);").unwrap();
+        assert!(matches!(result.geometry, GeometryNode::Cube { .. }));
+        assert_eq!(result.warnings, ["color(): unable to parse color \"reddish\""]);
+    }
+
+    /// Test an angle with an axis turns about that axis, named or not.
+    #[test]
+    fn test_eval_rotate_axis() {
+        let matrix = |source: &str| match crate::evaluate(source).unwrap().geometry {
+            GeometryNode::Multmatrix { matrix, .. } => matrix,
+            other => panic!("Expected Multmatrix, got {:?}", other),
+        };
+        // Y goes to Z turning about X
+        let about_x = matrix("rotate(v = [1, 0, 0], a = 90) cube(1);");
+        assert_eq!(about_x, matrix("rotate(90, [2, 0, 0]) cube(1);"));
+        let y = [about_x[0][1], about_x[1][1], about_x[2][1]];
+        assert!((y[0]).abs() < 1e-12 && (y[1]).abs() < 1e-12 && (y[2] - 1.0).abs() < 1e-12, "{:?}", y);
+        // The zero axis falls back to Z
+        let about_z = matrix("rotate(90, [0, 0, 0]) cube(1);");
+        assert!((about_z[1][0] - 1.0).abs() < 1e-12);
+    }
+
+    /// Test unknown arguments warn, and special variables reach the
+    /// children.
+    #[test]
+    fn test_unknown_and_special_arguments() {
+        let result = crate::evaluate("translate([1, 0, 0], w = 2) cube(1);\nrotate(90, $fn = 6) sphere(1);").unwrap();
+        assert_eq!(result.warnings, ["translate(): variable w not specified as parameter"]);
+        let GeometryNode::Group { children } = &result.geometry else { panic!("expected group") };
+        assert!(matches!(&children[1], GeometryNode::Rotate { child, .. } if matches!(**child, GeometryNode::Sphere { fn_: 6, .. })));
+    }
+
     #[test]
     fn test_eval_color_default() {
         let mut ctx = ctx();
@@ -0,0 +1,225 @@
+//! # Builtins
+//!
+//! Signatures and one-line descriptions of the modules, functions and
+//! special variables the evaluator provides, for completion and hover.
+//!
+//! Parameters are listed in positional order, followed by those that are
+//! only taken by name. Variadic builtins such as `echo()` and `concat()`
+//! list none.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_lsp::builtins::{lookup, BuiltinKind};
+//!
+//! let cube = lookup("cube").unwrap();
+//! assert_eq!(cube.kind, BuiltinKind::Module);
+//! assert_eq!(cube.signature(), "cube(size, center)");
+//! ```
+
+/// What a builtin name refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinKind {
+    /// Module, called as a statement
+    Module,
+    /// Function, called in expressions
+    Function,
+    /// Special variable like `$fn`
+    Variable,
+}
+
+/// A builtin module, function or special variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Builtin {
+    /// Name as written in source.
+    pub name: &'static str,
+    /// What the name refers to.
+    pub kind: BuiltinKind,
+    /// Parameter names; empty for variables.
+    pub params: &'static [&'static str],
+    /// One-line description.
+    pub doc: &'static str,
+}
+
+impl Builtin {
+    /// Signature as shown to the user: `cube(size, center)` for calls,
+    /// the bare name for variables.
+    #[must_use]
+    pub fn signature(&self) -> String {
+        match self.kind {
+            BuiltinKind::Variable => self.name.to_string(),
+            _ => format!("{}({})", self.name, self.params.join(", ")),
+        }
+    }
+}
+
+const fn module(name: &'static str, params: &'static [&'static str], doc: &'static str) -> Builtin {
+    Builtin { name, kind: BuiltinKind::Module, params, doc }
+}
+
+const fn function(name: &'static str, params: &'static [&'static str], doc: &'static str) -> Builtin {
+    Builtin { name, kind: BuiltinKind::Function, params, doc }
+}
+
+const fn variable(name: &'static str, doc: &'static str) -> Builtin {
+    Builtin { name, kind: BuiltinKind::Variable, params: &[], doc }
+}
+
+/// Every builtin, modules first.
+pub const BUILTINS: &[Builtin] = &[
+    // 3D primitives
+    module("cube", &["size", "center"], "Box with one corner at the origin, or centered."),
+    module("sphere", &["r", "d"], "Sphere centered at the origin."),
+    module("cylinder", &["h", "r1", "r2", "center", "r", "d", "d1", "d2"], "Cylinder or cone along the Z axis."),
+    module("polyhedron", &["points", "faces", "convexity"], "Solid from points and faces listing point indices."),
+    module("import", &["file", "center", "convexity", "layer", "origin", "scale", "dpi", "id"], "Geometry read from an STL, OBJ, OFF or SVG file."),
+    module("surface", &["file", "center", "invert", "convexity"], "Height map read from a data or image file."),
+    // 2D primitives
+    module("circle", &["r", "d"], "Circle centered at the origin."),
+    module("square", &["size", "center"], "Rectangle with one corner at the origin, or centered."),
+    module("polygon", &["points", "paths", "convexity"], "Polygon from points, with optional paths for holes."),
+    module("text", &["text", "size", "font", "halign", "valign", "spacing", "direction", "language", "script"], "Outline of a string."),
+    // Booleans
+    module("union", &[], "Combines the children."),
+    module("difference", &[], "Subtracts the other children from the first."),
+    module("intersection", &[], "Keeps what all children have in common."),
+    module("hull", &[], "Convex hull of the children."),
+    module("minkowski", &["convexity"], "Minkowski sum of the children."),
+    // Transforms
+    module("translate", &["v"], "Moves the children by a vector."),
+    module("rotate", &["a", "v"], "Rotates the children by angles in degrees, or about an axis."),
+    module("scale", &["v"], "Scales the children by a factor or vector."),
+    module("resize", &["newsize", "auto"], "Scales the children to a given size."),
+    module("mirror", &["v"], "Mirrors the children across the plane with normal `v`."),
+    module("multmatrix", &["m"], "Transforms the children by a 4x4 or 4x3 matrix."),
+    module("color", &["c", "alpha"], "Colors the children by name, hex string or RGBA vector."),
+    // Extrusions and 2D operations
+    module("linear_extrude", &["height", "center", "convexity", "twist", "slices", "scale"], "Extrudes 2D children along Z."),
+    module("rotate_extrude", &["angle", "convexity"], "Sweeps 2D children around the Z axis."),
+    module("offset", &["r", "delta", "chamfer"], "Grows or shrinks 2D children."),
+    module("projection", &["cut"], "Projects 3D children onto the XY plane."),
+    // Structure
+    module("children", &["index"], "The children passed to the current module."),
+    // Console output and checks
+    module("echo", &[], "Prints its arguments to the console."),
+    module("assert", &["condition", "message"], "Stops with an error if the condition is false."),
+    // Extensions
+    module("smooth", &["iterations", "lambda", "mu", "preserve_boundary", "feature_angle"], "Taubin smoothing of the child mesh."),
+    module("quality", &["level", "simplify"], "Scales tessellation of the children."),
+    module("echo_dim", &[], "Prints the dimension of the children."),
+    module("torus", &["r1", "r2", "d1", "d2"], "Ring around the Z axis (extension)."),
+    module("prism", &["n", "r", "h", "center", "d"], "Regular n-sided prism (extension)."),
+    module("rounded_cube", &["size", "r", "center"], "Box with rounded edges (extension)."),
+    module("refine", &["n", "length", "smooth"], "Splits the child's triangles, or rounds it with smooth (extension)."),
+    module("warp_twist", &["angle"], "Twists the child about the Z axis, up to angle at its top (extension)."),
+    module("warp_taper", &["scale"], "Scales the child in x and y, up to scale at its top (extension)."),
+    module("warp_bend", &["angle"], "Bends the child along x into an arc of angle (extension)."),
+    // Trigonometry
+    function("sin", &["x"], "Sine of an angle in degrees."),
+    function("cos", &["x"], "Cosine of an angle in degrees."),
+    function("tan", &["x"], "Tangent of an angle in degrees."),
+    function("asin", &["x"], "Arc sine, in degrees."),
+    function("acos", &["x"], "Arc cosine, in degrees."),
+    function("atan", &["x"], "Arc tangent, in degrees."),
+    function("atan2", &["y", "x"], "Angle of the point (x, y), in degrees."),
+    // Arithmetic
+    function("abs", &["x"], "Absolute value."),
+    function("sign", &["x"], "-1, 0 or 1 by the sign of x."),
+    function("floor", &["x"], "Largest integer not above x."),
+    function("ceil", &["x"], "Smallest integer not below x."),
+    function("round", &["x"], "Nearest integer, halves away from zero."),
+    function("sqrt", &["x"], "Square root."),
+    function("pow", &["base", "exponent"], "Base raised to a power."),
+    function("exp", &["x"], "e raised to x."),
+    function("ln", &["x"], "Natural logarithm."),
+    function("log", &["x"], "Base 10 logarithm."),
+    function("min", &[], "Smallest of the arguments, or of a list."),
+    function("max", &[], "Largest of the arguments, or of a list."),
+    function("norm", &["v"], "Euclidean length of a vector."),
+    function("len", &["v"], "Number of elements of a list or characters of a string."),
+    function("cross", &["a", "b"], "Cross product of two vectors."),
+    function("rands", &["min_value", "max_value", "value_count", "seed_value"], "List of random numbers."),
+    // Type tests
+    function("is_undef", &["x"], "True if x is undef."),
+    function("is_num", &["x"], "True if x is a number."),
+    function("is_bool", &["x"], "True if x is a boolean."),
+    function("is_string", &["x"], "True if x is a string."),
+    function("is_list", &["x"], "True if x is a list."),
+    // Lists
+    function("concat", &[], "Lists joined end to end."),
+    function("lookup", &["key", "table"], "Value interpolated from a table of [key, value] pairs."),
+    function("search", &["match_value", "string_or_vector", "num_returns_per_match", "index_col_num"], "Indices of matching elements."),
+    // Strings
+    function("str", &[], "Arguments converted to strings and joined."),
+    function("chr", &["code"], "String of the characters with the given code points."),
+    function("ord", &["c"], "Code point of a one-character string."),
+    // Paths
+    function("path_length", &["points", "closed"], "Total length of a polyline."),
+    function("resample_path", &["points", "n", "closed"], "n points evenly spaced along a polyline."),
+    function("offset_path", &["points", "d", "closed"], "2D polyline shifted sideways by d."),
+    // Special variables
+    variable("$fn", "Fixed number of segments for circles; 0 uses $fa and $fs."),
+    variable("$fa", "Minimum angle of a circle segment, in degrees."),
+    variable("$fs", "Minimum length of a circle segment."),
+    variable("$t", "Animation time, from 0 to 1."),
+    variable("$preview", "True in preview, false in full renders."),
+    variable("$children", "Number of children passed to the current module."),
+    variable("$vpr", "Viewport rotation."),
+    variable("$vpt", "Viewport translation."),
+    variable("$vpd", "Viewport camera distance."),
+    variable("$vpf", "Viewport field of view."),
+];
+
+/// Statement keywords, offered where a statement may start.
+pub const STATEMENT_KEYWORDS: &[&str] = &[
+    "module", "function", "for", "intersection_for", "if", "else", "let", "include", "use",
+];
+
+/// Expression keywords and literals, offered where an expression may start.
+pub const EXPRESSION_KEYWORDS: &[&str] = &["true", "false", "undef", "let", "for", "each", "if"];
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Find a builtin by name.
+#[must_use]
+pub fn lookup(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|b| b.name == name)
+}
+
+/// Builtins of one kind.
+pub fn of_kind(kind: BuiltinKind) -> impl Iterator<Item = &'static Builtin> {
+    BUILTINS.iter().filter(move |b| b.kind == kind)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openscad_eval::capabilities::{BUILTIN_FUNCTIONS, BUILTIN_MODULES, EXTENSION_MODULES, EXTENSION_OPERATIONS, EXTENSION_PRIMITIVES};
+
+    /// Test every module and function the evaluator knows has an entry.
+    #[test]
+    fn test_covers_capabilities() {
+        for name in BUILTIN_MODULES.iter().chain(EXTENSION_MODULES).chain(EXTENSION_PRIMITIVES).chain(EXTENSION_OPERATIONS) {
+            assert_eq!(lookup(name).map(|b| b.kind), Some(BuiltinKind::Module), "{}", name);
+        }
+        for name in BUILTIN_FUNCTIONS {
+            assert_eq!(lookup(name).map(|b| b.kind), Some(BuiltinKind::Function), "{}", name);
+        }
+        assert_eq!(of_kind(BuiltinKind::Function).count(), BUILTIN_FUNCTIONS.len());
+    }
+
+    /// Test signatures of calls and variables.
+    #[test]
+    fn test_signature() {
+        assert_eq!(lookup("atan2").unwrap().signature(), "atan2(y, x)");
+        assert_eq!(lookup("union").unwrap().signature(), "union()");
+        assert_eq!(lookup("$fn").unwrap().signature(), "$fn");
+        assert!(lookup("no_such_builtin").is_none());
+    }
+}
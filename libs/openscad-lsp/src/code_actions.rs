@@ -0,0 +1,152 @@
+//! # Code Actions
+//!
+//! Quick fixes for the diagnostics at a range, from the fixes
+//! [`findings`] attaches to them, and one rewrite: wrapping the top-level
+//! shapes of a file in `union()`, so that they form a single object for
+//! transforms or export.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lsp_types::{Position, Range, Uri};
+//! use openscad_lsp::code_actions::code_actions;
+//! use openscad_lsp::document::Document;
+//!
+//! let uri: Uri = "file:///model.scad".parse().unwrap();
+//! let doc = Document::new("x == 1;".to_string(), 1);
+//! let actions = code_actions(&uri, &doc, Range::new(Position::new(0, 3), Position::new(0, 3)));
+//! assert_eq!(actions[0].title, "Replace `==` with `=`");
+//! ```
+
+use std::collections::HashMap;
+
+use lsp_types::{CodeAction, CodeActionKind, Range, TextEdit, Uri, WorkspaceEdit};
+use openscad_parser::{CstNode, NodeKind};
+
+use crate::diagnostics::findings;
+use crate::document::Document;
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Code actions for a range of a document: quick fixes first, then
+/// rewrites.
+#[must_use]
+pub fn code_actions(uri: &Uri, document: &Document, range: Range) -> Vec<CodeAction> {
+    let mut actions = Vec::new();
+    for finding in findings(document) {
+        let diagnostic = finding.diagnostic;
+        let overlaps = diagnostic.range.start <= range.end && range.start <= diagnostic.range.end;
+        let Some(fix) = finding.fix.filter(|_| overlaps) else { continue };
+        actions.push(CodeAction {
+            title: fix.title,
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic]),
+            edit: Some(workspace_edit(uri, fix.edits)),
+            is_preferred: Some(true),
+            ..CodeAction::default()
+        });
+    }
+    actions.extend(wrap_in_union(uri, document, range));
+    actions
+}
+
+// =============================================================================
+// REWRITES
+// =============================================================================
+
+/// Wrap the top-level shapes in `union()`, when the range touches one of
+/// several.
+///
+/// Only offered when the shapes are adjacent: moving an assignment or
+/// declaration into the block would hide it from the rest of the file.
+fn wrap_in_union(uri: &Uri, document: &Document, range: Range) -> Option<CodeAction> {
+    let cst = openscad_parser::parse(&document.text);
+    if !cst.errors.is_empty() {
+        return None;
+    }
+    let statements = &cst.root.children;
+    let shapes: Vec<usize> = (0..statements.len()).filter(|&i| is_shape(&statements[i])).collect();
+    let (&first, &last) = (shapes.first()?, shapes.last()?);
+    if shapes.len() < 2 || last - first + 1 != shapes.len() {
+        return None;
+    }
+    let bytes = document.offset_at(range.start)..document.offset_at(range.end);
+    let touched = shapes.iter().any(|&i| statements[i].span.start.byte <= bytes.end && bytes.start <= statements[i].span.end.byte);
+    if !touched {
+        return None;
+    }
+
+    let span = statements[first].span.start.byte..statements[last].span.end.byte;
+    let body: Vec<String> = document.text[span.clone()].lines()
+        .map(|line| if line.trim().is_empty() { String::new() } else { format!("    {}", line) })
+        .collect();
+    let range = Range::new(document.position_at(span.start), document.position_at(span.end));
+    let edit = TextEdit::new(range, format!("union() {{\n{}\n}}", body.join("\n")));
+    Some(CodeAction {
+        title: "Wrap top-level shapes in `union()`".to_string(),
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        edit: Some(workspace_edit(uri, vec![edit])),
+        ..CodeAction::default()
+    })
+}
+
+/// Whether a top-level statement produces geometry.
+pub(crate) fn is_shape(statement: &CstNode) -> bool {
+    matches!(
+        statement.kind,
+        NodeKind::ModuleCall | NodeKind::Modifier | NodeKind::ForBlock | NodeKind::IntersectionForBlock
+            | NodeKind::IfBlock | NodeKind::LetBlock
+    )
+}
+
+/// Edits of one document.
+fn workspace_edit(uri: &Uri, edits: Vec<TextEdit>) -> WorkspaceEdit {
+    WorkspaceEdit { changes: Some(HashMap::from([(uri.clone(), edits)])), ..WorkspaceEdit::default() }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Position;
+
+    fn actions(source: &str, line: u32) -> Vec<CodeAction> {
+        let uri: Uri = "file:///model.scad".parse().unwrap();
+        let document = Document::new(source.to_string(), 1);
+        code_actions(&uri, &document, Range::new(Position::new(line, 0), Position::new(line, 99)))
+    }
+
+    /// Test quick fixes are offered only for diagnostics in the range.
+    #[test]
+    fn test_quick_fixes() {
+        let source = "module peg(h) {}\npeg(1, r = 2);\nh = 1;";
+        let fixes = actions(source, 1);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].title, "Add parameter `r = 2` to `peg`");
+        assert_eq!(fixes[0].kind, Some(CodeActionKind::QUICKFIX));
+        assert_eq!(fixes[0].diagnostics.as_ref().unwrap()[0].message, "peg(): variable r not specified as parameter");
+        assert!(actions(source, 2).is_empty());
+        assert_eq!(actions("x = 1\ny = 2;", 1)[0].title, "Insert missing `;`");
+    }
+
+    /// Test adjacent top-level shapes can be wrapped in a union.
+    #[test]
+    fn test_wrap_in_union() {
+        let source = "size = 2;\ncube(size);\n\ntranslate([3, 0, 0])\n  sphere(1);";
+        let action = actions(source, 1).pop().unwrap();
+        assert_eq!(action.kind, Some(CodeActionKind::REFACTOR_REWRITE));
+        let edit = &action.edit.unwrap().changes.unwrap()[&"file:///model.scad".parse::<Uri>().unwrap()][0];
+        assert_eq!(edit.range, Range::new(Position::new(1, 0), Position::new(4, 12)));
+        assert_eq!(edit.new_text, "union() {\n    cube(size);\n\n    translate([3, 0, 0])\n      sphere(1);\n}");
+
+        // One shape, shapes split by an assignment, or a range off the shapes
+        assert!(actions("cube(1);", 0).is_empty());
+        assert!(actions("cube(1);\nx = 1;\nsphere(x);", 0).is_empty());
+        assert!(actions(source, 0).is_empty());
+    }
+}
@@ -0,0 +1,515 @@
+//! # Completion
+//!
+//! Completion items for a cursor position, chosen by what may be written
+//! there:
+//!
+//! | Position                        | Offered                                        |
+//! |---------------------------------|------------------------------------------------|
+//! | Start of a statement            | Modules and statement keywords                 |
+//! | Start of an operand             | Functions, variables in scope and literals     |
+//! | Start of an argument in `f(…)`  | Parameters of `f` not yet named, then operands |
+//! | After `$`                       | Special variables                              |
+//!
+//! Nothing is offered inside strings, comments and numbers, right after an
+//! operand, or where a new name is being declared.
+//!
+//! ## Context
+//!
+//! The text before the cursor is tokenized, and the statement the cursor
+//! is in - the tokens since the last `;`, `{` or `}` - is read to find
+//! the innermost open bracket and what precedes it.
+//!
+//! ## Scope
+//!
+//! Code being typed rarely parses, so names come from three sources:
+//!
+//! - Top-level modules, functions and variables of the whole document,
+//!   which OpenSCAD makes visible everywhere, parsed without the
+//!   statement being typed
+//! - The text up to the current statement with its open braces closed,
+//!   whose syntax tree gives the declarations, parameters and loop
+//!   variables of the blocks around the cursor
+//! - `for`, `let` and declaration parentheses within the current statement
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_lsp::completion::complete;
+//!
+//! let source = "module peg(h, r = 1) { cylinder(h, r); }\npeg(";
+//! let labels: Vec<String> = complete(source, source.len()).into_iter().map(|i| i.label).collect();
+//! assert_eq!(labels[..2], ["h", "r"]);
+//! ```
+
+use lsp_types::{CompletionItem, CompletionItemKind, Documentation};
+use openscad_parser::lexer::{Lexer, Token, TokenKind};
+
+use crate::builtins::{self, BuiltinKind, EXPRESSION_KEYWORDS, STATEMENT_KEYWORDS};
+use crate::scope::{collect_declarations, collect_scope, Declaration, Symbols};
+
+/// What may be written at the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Context {
+    /// Nothing we can complete.
+    None,
+    /// A module call or statement keyword.
+    Statement,
+    /// An operand of an expression.
+    Expression,
+    /// An argument of a call, positional or named.
+    Argument {
+        /// Name of the module or function called.
+        callee: String,
+        /// Parameters already passed by name.
+        named: Vec<String>,
+    },
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Completion items at a byte offset of the source.
+///
+/// Items are not filtered by the word under the cursor; clients match it
+/// themselves.
+#[must_use]
+pub fn complete(source: &str, offset: usize) -> Vec<CompletionItem> {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source[..offset];
+    let mut tokens = Lexer::new(before).tokenize();
+    tokens.retain(|t| t.kind != TokenKind::Eof);
+
+    let last_end = tokens.last().map_or(0, |t| t.span.end.byte);
+    if in_comment(&before[last_end..]) {
+        return Vec::new();
+    }
+    // The word being typed is not part of the context
+    let mut special = false;
+    if let Some(last) = tokens.last().filter(|t| t.span.end.byte == offset) {
+        match last.kind {
+            k if is_word(k) => {
+                special = k == TokenKind::SpecialVariable;
+                tokens.pop();
+            }
+            TokenKind::String | TokenKind::Number | TokenKind::Dot | TokenKind::FilePath | TokenKind::Error => return Vec::new(),
+            _ => {}
+        }
+    }
+
+    let start = statement_start(&tokens);
+    let context = context(&tokens[start..]);
+    if context == Context::None {
+        return Vec::new();
+    }
+    if special {
+        return builtins::of_kind(BuiltinKind::Variable).map(builtin_item).collect();
+    }
+
+    let symbols = symbols(source, offset, &tokens, start);
+    match context {
+        Context::None => Vec::new(),
+        Context::Statement => statement_items(&symbols),
+        Context::Expression => expression_items(&symbols),
+        Context::Argument { callee, named } => {
+            let mut items = parameter_items(&symbols, &callee, &named);
+            items.extend(expression_items(&symbols));
+            items
+        }
+    }
+}
+
+// =============================================================================
+// CONTEXT
+// =============================================================================
+
+/// Whether a token is a word the user may be in the middle of typing.
+fn is_word(kind: TokenKind) -> bool {
+    matches!(kind, TokenKind::Identifier | TokenKind::SpecialVariable | TokenKind::True | TokenKind::False | TokenKind::Undef)
+        || kind.is_keyword()
+}
+
+/// Whether text after the last token ends inside a comment.
+fn in_comment(gap: &str) -> bool {
+    let line = gap.rsplit('\n').next().unwrap_or_default();
+    let block = match (gap.rfind("/*"), gap.rfind("*/")) {
+        (Some(open), Some(close)) => open > close,
+        (open, _) => open.is_some(),
+    };
+    block || line.contains("//")
+}
+
+/// Whether a statement may start after the tokens, so that a call there
+/// is a module call rather than a function call.
+pub(crate) fn at_statement_start(tokens: &[Token]) -> bool {
+    context(&tokens[statement_start(tokens)..]) == Context::Statement
+}
+
+/// Index of the first token of the statement the tokens end in.
+fn statement_start(tokens: &[Token]) -> usize {
+    tokens.iter()
+        .rposition(|t| matches!(t.kind, TokenKind::Semicolon | TokenKind::LBrace | TokenKind::RBrace))
+        .map_or(0, |i| i + 1)
+}
+
+/// What may be written after the tokens of the current statement.
+fn context(statement: &[Token]) -> Context {
+    use TokenKind as T;
+    let kind = |i: usize| statement.get(i).map(|t| t.kind);
+
+    // Match brackets, keeping the openers left open at the cursor
+    let mut open: Vec<usize> = Vec::new();
+    let mut opener_of = vec![None; statement.len()];
+    for (i, token) in statement.iter().enumerate() {
+        match token.kind {
+            T::LParen | T::LBracket => open.push(i),
+            T::RParen | T::RBracket => opener_of[i] = open.pop(),
+            _ => {}
+        }
+    }
+
+    let previous = statement.len().checked_sub(1);
+    let after_operand = previous.is_some_and(|p| match statement[p].kind {
+        T::Identifier | T::SpecialVariable | T::Number | T::String | T::True | T::False | T::Undef | T::RBracket => true,
+        // `for (…)` and friends are followed by their body
+        T::RParen => !opener_of[p]
+            .and_then(|o| o.checked_sub(1))
+            .is_some_and(|o| matches!(kind(o), Some(T::For | T::IntersectionFor | T::Let | T::If))),
+        _ => false,
+    });
+    let expression = if after_operand { Context::None } else { Context::Expression };
+
+    let Some(&inner) = open.last() else {
+        // Top level of the statement
+        let assigned = top_level(statement, 0).any(|i| statement[i].kind == T::Eq);
+        if assigned {
+            return expression;
+        }
+        return match (kind(0), previous.map(|p| statement[p].kind)) {
+            (Some(T::Module), Some(T::RParen)) => Context::Statement,
+            (Some(T::Module | T::Function | T::Include | T::Use), _) => Context::None,
+            (_, None | Some(T::Else | T::Hash | T::Bang | T::Percent | T::Star | T::RParen)) => Context::Statement,
+            _ => Context::None,
+        };
+    };
+    if statement[inner].kind == T::LBracket {
+        return expression;
+    }
+
+    // Inside parentheses: the current argument runs from the last comma
+    let argument_start = top_level(statement, inner + 1)
+        .filter(|&i| statement[i].kind == T::Comma)
+        .last()
+        .map_or(inner + 1, |i| i + 1);
+    let has_value = top_level(statement, argument_start).any(|i| statement[i].kind == T::Eq);
+    let declares = match inner.checked_sub(1).and_then(kind) {
+        Some(T::For | T::IntersectionFor | T::Let) => true,
+        Some(T::Identifier) => matches!(inner.checked_sub(2).and_then(kind), Some(T::Module | T::Function)),
+        _ => false,
+    };
+    if declares {
+        return if has_value { expression } else { Context::None };
+    }
+    match inner.checked_sub(1).map(|c| &statement[c]) {
+        Some(callee) if callee.kind == T::Identifier && argument_start == statement.len() => Context::Argument {
+            callee: callee.text.clone(),
+            named: top_level(statement, inner + 1)
+                .filter(|&i| statement[i].kind == T::Identifier && kind(i + 1) == Some(T::Eq))
+                .map(|i| statement[i].text.clone())
+                .collect(),
+        },
+        _ => expression,
+    }
+}
+
+/// Indices of the tokens from `from` on that are not nested in brackets
+/// opened after `from`.
+fn top_level(statement: &[Token], from: usize) -> impl Iterator<Item = usize> + '_ {
+    let mut depth = 0usize;
+    (from..statement.len()).filter(move |&i| match statement[i].kind {
+        TokenKind::LParen | TokenKind::LBracket => {
+            depth += 1;
+            depth == 1
+        }
+        TokenKind::RParen | TokenKind::RBracket => {
+            depth = depth.saturating_sub(1);
+            depth == 0
+        }
+        _ => depth == 0,
+    })
+}
+
+// =============================================================================
+// SCOPE
+// =============================================================================
+
+/// Names visible at `offset`, after `tokens`; the current statement
+/// starts at token `start`.
+fn symbols(source: &str, offset: usize, tokens: &[Token], start: usize) -> Symbols {
+    let mut symbols = Symbols::default();
+    let cut = start.checked_sub(1).map_or(0, |i| tokens[i].span.end.byte);
+
+    // Top level of the whole document without the statement being typed,
+    // and from the next unindented line on in case the rest still fails
+    let without = format!("{}{}", &source[..cut], &source[offset..]);
+    collect_declarations(&openscad_parser::parse(&without).root, &without, &mut symbols);
+    let rest = &source[offset..];
+    let next_line = rest.match_indices('\n')
+        .map(|(i, _)| i + 1)
+        .find(|&i| rest[i..].starts_with(|c: char| !c.is_whitespace() && c != '}'));
+    if let Some(tail) = next_line.map(|i| &rest[i..]) {
+        collect_declarations(&openscad_parser::parse(tail).root, tail, &mut symbols);
+    }
+
+    // Blocks around the cursor, up to the current statement
+    let depth = tokens[..start].iter().fold(0usize, |depth, t| match t.kind {
+        TokenKind::LBrace => depth + 1,
+        TokenKind::RBrace => depth.saturating_sub(1),
+        _ => depth,
+    });
+    let head = format!("{}{}", &source[..cut], "}".repeat(depth));
+    collect_scope(&openscad_parser::parse(&head).root, &head, cut, &mut symbols);
+
+    // Declarations and binders of the current statement
+    let statement = &tokens[start..];
+    for (i, token) in statement.iter().enumerate() {
+        let open = match token.kind {
+            TokenKind::For | TokenKind::IntersectionFor | TokenKind::Let => i + 1,
+            TokenKind::Module | TokenKind::Function => i + 2,
+            _ => continue,
+        };
+        if statement.get(open).is_none_or(|t| t.kind != TokenKind::LParen) {
+            continue;
+        }
+        let names = binders(statement, open);
+        // A module or function is visible in its own body
+        if matches!(token.kind, TokenKind::Module | TokenKind::Function) {
+            let params = names.iter().map(|n| (n.clone(), None)).collect();
+            let params_end = closing_paren(statement, open).map_or(offset, |t| t.span.start.byte);
+            let declaration = Declaration { name: statement[i + 1].text.clone(), span: statement[i + 1].span, params, params_end };
+            match token.kind {
+                TokenKind::Module => symbols.modules.insert(0, declaration),
+                _ => symbols.functions.insert(0, declaration),
+            }
+        }
+        for name in names {
+            symbols.variables.insert(0, name);
+        }
+    }
+    symbols
+}
+
+/// Names bound in the parentheses opened at `open`: the identifiers
+/// right after the `(` and each top-level `,`.
+fn binders(statement: &[Token], open: usize) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut depth = 0usize;
+    for pair in statement[open..].windows(2) {
+        match pair[0].kind {
+            TokenKind::LParen | TokenKind::LBracket => depth += 1,
+            TokenKind::RParen | TokenKind::RBracket => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            break;
+        }
+        let separator = matches!(pair[0].kind, TokenKind::LParen | TokenKind::Comma);
+        if depth == 1 && separator && pair[1].kind == TokenKind::Identifier {
+            names.push(pair[1].text.clone());
+        }
+    }
+    names
+}
+
+/// The `)` closing the parentheses opened at `open`, if typed yet.
+fn closing_paren(statement: &[Token], open: usize) -> Option<&Token> {
+    let mut depth = 0usize;
+    statement[open..].iter().find(|token| {
+        match token.kind {
+            TokenKind::LParen | TokenKind::LBracket => depth += 1,
+            TokenKind::RParen | TokenKind::RBracket => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        depth == 0
+    })
+}
+
+// =============================================================================
+// ITEMS
+// =============================================================================
+
+/// Modules and statement keywords.
+fn statement_items(symbols: &Symbols) -> Vec<CompletionItem> {
+    let mut items = Vec::new();
+    for module in &symbols.modules {
+        items.push(declaration_item(module, CompletionItemKind::MODULE, "module"));
+    }
+    items.extend(builtins::of_kind(BuiltinKind::Module).map(builtin_item));
+    items.extend(STATEMENT_KEYWORDS.iter().map(|k| keyword_item(k)));
+    dedup(items)
+}
+
+/// Functions, variables and literals.
+fn expression_items(symbols: &Symbols) -> Vec<CompletionItem> {
+    let mut items = Vec::new();
+    for variable in &symbols.variables {
+        items.push(CompletionItem {
+            label: variable.clone(),
+            kind: Some(CompletionItemKind::VARIABLE),
+            ..CompletionItem::default()
+        });
+    }
+    for function in &symbols.functions {
+        items.push(declaration_item(function, CompletionItemKind::FUNCTION, "function"));
+    }
+    items.extend(builtins::of_kind(BuiltinKind::Function).map(builtin_item));
+    items.extend(builtins::of_kind(BuiltinKind::Variable).map(builtin_item));
+    items.extend(EXPRESSION_KEYWORDS.iter().map(|k| keyword_item(k)));
+    dedup(items)
+}
+
+/// Parameters of the module or function called, as `name = `.
+fn parameter_items(symbols: &Symbols, callee: &str, named: &[String]) -> Vec<CompletionItem> {
+    let user = symbols.modules.iter().chain(&symbols.functions).find(|d| d.name == callee);
+    let (params, signature): (Vec<&str>, String) = match (user, builtins::lookup(callee)) {
+        (Some(declaration), _) => (declaration.params.iter().map(|(n, _)| n.as_str()).collect(), declaration.signature()),
+        (None, Some(builtin)) => (builtin.params.to_vec(), builtin.signature()),
+        (None, None) => return Vec::new(),
+    };
+    params.into_iter()
+        .filter(|p| !named.iter().any(|n| n == p))
+        .enumerate()
+        .map(|(i, param)| CompletionItem {
+            label: param.to_string(),
+            kind: Some(CompletionItemKind::PROPERTY),
+            detail: Some(signature.clone()),
+            insert_text: Some(format!("{} = ", param)),
+            // Parameters first, in declaration order
+            sort_text: Some(format!("0{:03}", i)),
+            ..CompletionItem::default()
+        })
+        .collect()
+}
+
+/// Item for a user-defined module or function.
+fn declaration_item(declaration: &Declaration, kind: CompletionItemKind, keyword: &str) -> CompletionItem {
+    CompletionItem {
+        label: declaration.name.clone(),
+        kind: Some(kind),
+        detail: Some(format!("{} {}", keyword, declaration.signature())),
+        ..CompletionItem::default()
+    }
+}
+
+/// Item for a builtin, with its signature and description.
+fn builtin_item(builtin: &builtins::Builtin) -> CompletionItem {
+    let kind = match builtin.kind {
+        BuiltinKind::Module => CompletionItemKind::MODULE,
+        BuiltinKind::Function => CompletionItemKind::FUNCTION,
+        BuiltinKind::Variable => CompletionItemKind::VARIABLE,
+    };
+    CompletionItem {
+        label: builtin.name.to_string(),
+        kind: Some(kind),
+        detail: Some(builtin.signature()),
+        documentation: Some(Documentation::String(builtin.doc.to_string())),
+        ..CompletionItem::default()
+    }
+}
+
+/// Item for a keyword or literal.
+fn keyword_item(keyword: &str) -> CompletionItem {
+    CompletionItem {
+        label: keyword.to_string(),
+        kind: Some(CompletionItemKind::KEYWORD),
+        ..CompletionItem::default()
+    }
+}
+
+/// Drop items whose label an earlier item has, so user definitions and
+/// inner variables shadow the rest.
+fn dedup(items: Vec<CompletionItem>) -> Vec<CompletionItem> {
+    let mut seen = std::collections::HashSet::new();
+    items.into_iter().filter(|item| seen.insert(item.label.clone())).collect()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Labels offered where `|` is in the source.
+    fn labels(source: &str) -> Vec<String> {
+        let offset = source.find('|').unwrap();
+        let source = source.replace('|', "");
+        complete(&source, offset).into_iter().map(|i| i.label).collect()
+    }
+
+    fn has(labels: &[String], label: &str) -> bool {
+        labels.iter().any(|l| l == label)
+    }
+
+    /// Test statements offer modules and expressions offer functions.
+    #[test]
+    fn test_statement_and_expression() {
+        let statement = labels("module peg() {}\ntranslate([1, 0, 0]) pe|");
+        assert!(has(&statement, "peg") && has(&statement, "cube") && has(&statement, "for"));
+        assert!(!has(&statement, "sin"));
+
+        let expression = labels("function twice(x) = 2 * x;\nsize = 3;\ncube(size * tw|");
+        assert!(has(&expression, "twice") && has(&expression, "sin") && has(&expression, "size"));
+        assert!(!has(&expression, "cube"));
+        assert!(has(&labels("x = [1, |"), "true"));
+    }
+
+    /// Test named parameters of builtin and user calls.
+    #[test]
+    fn test_parameters() {
+        let items = complete("cylinder(h = 2, ", 16);
+        assert_eq!(items[0].label, "r1");
+        assert_eq!(items[0].insert_text.as_deref(), Some("r1 = "));
+        assert_eq!(items[0].detail.as_deref(), Some("cylinder(h, r1, r2, center, r, d, d1, d2)"));
+        assert!(!items.iter().any(|i| i.label == "h"));
+
+        // Declared after the call, and shadowing a builtin
+        let user = labels("cube(|);\nmodule cube(w, depth = 2) {}");
+        assert_eq!(user[..2], ["w", "depth"]);
+        assert!(!has(&user, "size"));
+        // Only at the start of an argument
+        assert!(!has(&labels("cube(size = |"), "center"));
+        assert!(labels("cube(10 |").is_empty());
+    }
+
+    /// Test variables of the enclosing blocks and statement are in scope.
+    #[test]
+    fn test_scope() {
+        let nested = labels("top = 1;\nmodule m(a, b = 2) {\n  inner = 3;\n  for (i = [0:3]) {\n    cube(|\n  }\n}\nafter = 4;");
+        for name in ["top", "a", "b", "inner", "i", "after"] {
+            assert!(has(&nested, name), "{}", name);
+        }
+        assert!(!has(&labels("module m() { hidden = 1; }\nx = |"), "hidden"));
+
+        let statement = labels("function f(p) = let (q = p) [for (k = [0:q]) |");
+        for name in ["p", "q", "k", "f"] {
+            assert!(has(&statement, name), "{}", name);
+        }
+    }
+
+    /// Test special variables, and positions with nothing to offer.
+    #[test]
+    fn test_special_and_none() {
+        assert_eq!(labels("sphere(5, $f|")[..3], ["$fn", "$fa", "$fs"]);
+        assert!(labels("// cu|").is_empty());
+        assert!(labels("/* a */ c|").contains(&"cube".to_string()));
+        assert!(labels("x = \"cu|").is_empty());
+        assert!(labels("x = 1|").is_empty());
+        assert!(labels("module na|").is_empty());
+        assert!(labels("for (i|").is_empty());
+    }
+}
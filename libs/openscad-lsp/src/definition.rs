@@ -0,0 +1,152 @@
+//! # Go to Definition
+//!
+//! Where the name at a position is declared: in the document, innermost
+//! scope first, then in the files it includes or uses. The path of an
+//! `include` or `use` statement leads to the file itself.
+//!
+//! Calls look for a module or function of their name, other names for a
+//! variable, parameter or loop variable.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lsp_types::{Position, Uri};
+//! use openscad_lsp::definition::definition;
+//! use openscad_lsp::document::Document;
+//! use openscad_lsp::workspace::Workspace;
+//!
+//! let lib: Uri = "file:///project/lib.scad".parse().unwrap();
+//! let main: Uri = "file:///project/main.scad".parse().unwrap();
+//! let mut workspace = Workspace::new();
+//! workspace.open(lib.clone(), Document::new("module peg() {}".to_string(), 1));
+//! workspace.open(main.clone(), Document::new("use <lib.scad>\npeg();".to_string(), 1));
+//!
+//! let location = definition(&workspace, &main, Position::new(1, 1)).unwrap();
+//! assert_eq!(location.uri, lib);
+//! assert_eq!(location.range.start, Position::new(0, 7));
+//! ```
+
+use lsp_types::{Location, Position, Range, Uri};
+use openscad_parser::{CstNode, NodeKind};
+
+use crate::scope::{declared_in, find_declaration, Namespace};
+use crate::workspace::{LinkKind, Workspace};
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// The declaration of the name at a position of an open document, or the
+/// file an `include`/`use` path names.
+#[must_use]
+pub fn definition(workspace: &Workspace, uri: &Uri, position: Position) -> Option<Location> {
+    let document = workspace.document(uri)?;
+    let offset = document.offset_at(position);
+    let root = openscad_parser::parse(&document.text).root;
+    let (parent, index) = name_at(&root, offset)?;
+    let node = &parent.children[index];
+    let name = node.text.as_deref()?;
+
+    if node.kind == NodeKind::FilePath {
+        let link = workspace.links(uri).iter().find(|l| l.span == node.span)?;
+        return Some(Location::new(link.target.clone()?, Range::default()));
+    }
+    let namespace = match (parent.kind, index) {
+        (NodeKind::ModuleCall | NodeKind::ModuleDeclaration, 0) => Namespace::Module,
+        (NodeKind::FunctionCall | NodeKind::FunctionDeclaration, 0) => Namespace::Function,
+        // Parameter names of a call, and member names
+        (NodeKind::NamedArgument, 0) | (NodeKind::DotExpression, 1) => return None,
+        _ => Namespace::Variable,
+    };
+    if let Some(declaration) = find_declaration(&root, offset, name, namespace) {
+        return Some(Location::new(uri.clone(), document.range_of(declaration.span)));
+    }
+    for (target, kind) in workspace.reachable(uri) {
+        // Variables of used files stay in them
+        if kind == LinkKind::Use && namespace == Namespace::Variable {
+            continue;
+        }
+        let Some(file) = workspace.file(&target) else { continue };
+        let root = openscad_parser::parse(&file.text).root;
+        if let Some(declaration) = declared_in(&root, name, namespace) {
+            return Some(Location::new(target.clone(), file.range_of(declaration.span)));
+        }
+    }
+    None
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// The parent and index of the name or path at an offset.
+fn name_at(node: &CstNode, offset: usize) -> Option<(&CstNode, usize)> {
+    node.children.iter().enumerate()
+        .filter(|(_, c)| c.span.start.byte <= offset && offset <= c.span.end.byte)
+        .find_map(|(i, child)| match child.kind {
+            NodeKind::Identifier | NodeKind::FilePath if child.children.is_empty() => Some((node, i)),
+            _ => name_at(child, offset),
+        })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::Document;
+
+    fn uri(path: &str) -> Uri {
+        format!("file:///project/{}", path).parse().unwrap()
+    }
+
+    /// A workspace with files open, keyed by path.
+    fn workspace(files: &[(&str, &str)]) -> Workspace {
+        let mut workspace = Workspace::new();
+        for (path, text) in files {
+            workspace.open(uri(path), Document::new(text.to_string(), 1));
+        }
+        workspace
+    }
+
+    /// The file and start of the definition of the name at `at` in
+    /// main.scad.
+    fn find(workspace: &Workspace, at: &str) -> Option<(String, Position)> {
+        let text = &workspace.document(&uri("main.scad")).unwrap().text;
+        let document = Document::new(text.clone(), 1);
+        let position = document.position_at(text.find(at).unwrap());
+        definition(workspace, &uri("main.scad"), position).map(|l| (l.uri.as_str().to_string(), l.range.start))
+    }
+
+    /// Test names resolve in the document, innermost scope first.
+    #[test]
+    fn test_local_definitions() {
+        let source = "w = 2;\nmodule peg(w) { cube(w); }\npeg(w);\nfunction f(x) = x;\ny = f(1);";
+        let workspace = workspace(&[("main.scad", source)]);
+        let main = uri("main.scad").as_str().to_string();
+        assert_eq!(find(&workspace, "w); }"), Some((main.clone(), Position::new(1, 11))));
+        assert_eq!(find(&workspace, "peg(w)"), Some((main.clone(), Position::new(1, 7))));
+        assert_eq!(find(&workspace, "w);\nf"), Some((main.clone(), Position::new(0, 0))));
+        assert_eq!(find(&workspace, "f(1)"), Some((main, Position::new(3, 9))));
+        assert_eq!(find(&workspace, "cube"), None);
+    }
+
+    /// Test names resolve through includes and uses, and paths to files.
+    #[test]
+    fn test_linked_definitions() {
+        let workspace = workspace(&[
+            ("config.scad", "size = 2;\nmodule base() {}"),
+            ("lib/shapes.scad", "hidden = 1;\nmodule peg() {}"),
+            ("main.scad", "include <config.scad>\nuse <lib/shapes.scad>\npeg();\nbase();\ncube(size + hidden);"),
+        ]);
+        let config = uri("config.scad").as_str().to_string();
+        let shapes = uri("lib/shapes.scad").as_str().to_string();
+        assert_eq!(find(&workspace, "peg"), Some((shapes.clone(), Position::new(1, 7))));
+        assert_eq!(find(&workspace, "base"), Some((config.clone(), Position::new(1, 7))));
+        assert_eq!(find(&workspace, "size"), Some((config.clone(), Position::new(0, 0))));
+        assert_eq!(find(&workspace, "hidden"), None);
+        assert_eq!(find(&workspace, "shapes.scad"), Some((shapes, Position::new(0, 0))));
+    }
+}
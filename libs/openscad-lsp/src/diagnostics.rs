@@ -0,0 +1,594 @@
+//! # Diagnostics
+//!
+//! Problems published for a document, each with the fix offered for it
+//! when there is an obvious one:
+//!
+//! | Code                     | Problem                                   | Fix                          |
+//! |--------------------------|-------------------------------------------|------------------------------|
+//! | `missing-semicolon`      | Statement not ended by `;`                | Insert `;`                   |
+//! | `equality-as-assignment` | `x == 1;` where `x = 1;` was meant        | Replace `==` with `=`        |
+//! | `unknown-parameter`      | Named argument a user module lacks        | Add the parameter, defaulting to the argument |
+//!
+//! Other parse errors are published without a code or fix.
+//!
+//! A document that parses is also evaluated, under tight limits, and the
+//! evaluator's warnings are published with their statements:
+//!
+//! | Code               | Problem                                                    |
+//! |--------------------|------------------------------------------------------------|
+//! | `ignored-argument` | Argument a builtin does not take, or one given twice      |
+//! | `degenerate`       | Primitive with a zero or negative size                     |
+//! | `empty-geometry`   | Boolean operand with no geometry                           |
+//! | `no-effect`        | `difference()` subtrahend clear of the first child         |
+//! | `deprecated`       | Syntax OpenSCAD has deprecated                             |
+//!
+//! Unknown modules and missing files are left out: the evaluator sees the
+//! document alone, without the files it includes.
+//!
+//! With the [`Workspace`] of the document, the files it includes and uses
+//! are checked as well:
+//!
+//! | Code               | Problem                                                    |
+//! |--------------------|------------------------------------------------------------|
+//! | `missing-file`     | `include`/`use` path that names no file                    |
+//! | `unknown-module`   | Call of a module declared nowhere, once every link resolves |
+//! | `unknown-function` | Call of a function declared nowhere, likewise              |
+//!
+//! and named arguments are checked against modules from linked files too,
+//! without a fix, as it would edit the other file.
+//!
+//! Modules, functions and variables declared but never referred to are
+//! hints (`unused-declaration`), shown faded. Top-level ones are only
+//! flagged in a file that draws something itself, since the declarations
+//! of a library are for the files using it, and top-level variables not at
+//! all in a file that includes others, which may read them.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lsp_types::NumberOrString;
+//! use openscad_lsp::diagnostics::findings;
+//! use openscad_lsp::document::Document;
+//!
+//! let doc = Document::new("x = 1\ny = 2;".to_string(), 1);
+//! let finding = &findings(&doc)[0];
+//! assert_eq!(finding.diagnostic.code, Some(NumberOrString::String("missing-semicolon".to_string())));
+//! assert_eq!(finding.fix.as_ref().unwrap().edits[0].new_text, ";");
+//! ```
+
+use std::collections::HashSet;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString, Range, TextEdit, Uri};
+use openscad_eval::limits::Limits;
+use openscad_eval::{DiagnosticKind, EvalOptions};
+use openscad_parser::lexer::{Lexer, TokenKind};
+use openscad_parser::{CstNode, NodeKind, ParseError, ParseErrorKind};
+
+use crate::builtins::{self, BuiltinKind};
+use crate::code_actions::is_shape;
+use crate::document::Document;
+use crate::scope::{collect_scope, Declaration, Symbols};
+use crate::workspace::{LinkKind, Linked, Workspace};
+
+/// Code of a statement missing its `;`.
+pub const MISSING_SEMICOLON: &str = "missing-semicolon";
+
+/// Code of `==` written for `=`.
+pub const EQUALITY_AS_ASSIGNMENT: &str = "equality-as-assignment";
+
+/// Code of a named argument the callee does not declare.
+pub const UNKNOWN_PARAMETER: &str = "unknown-parameter";
+
+/// Code of an `include`/`use` path that names no file.
+pub const MISSING_FILE: &str = "missing-file";
+
+/// Code of a call of an undeclared module.
+pub const UNKNOWN_MODULE: &str = "unknown-module";
+
+/// Code of a call of an undeclared function.
+pub const UNKNOWN_FUNCTION: &str = "unknown-function";
+
+/// Code of an argument the evaluator ignored.
+pub const IGNORED_ARGUMENT: &str = "ignored-argument";
+
+/// Code of a primitive with a zero or negative size.
+pub const DEGENERATE: &str = "degenerate";
+
+/// Code of a boolean operand with no geometry.
+pub const EMPTY_GEOMETRY: &str = "empty-geometry";
+
+/// Code of an operand that cannot change the result.
+pub const NO_EFFECT: &str = "no-effect";
+
+/// Code of deprecated syntax.
+pub const DEPRECATED: &str = "deprecated";
+
+/// Code of a module, function or variable never referred to.
+pub const UNUSED_DECLARATION: &str = "unused-declaration";
+
+/// Wall-clock budget of the evaluation behind the warnings, short enough
+/// to run on every change.
+const EVAL_TIME_MS: u64 = 250;
+
+/// A problem with a document.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// The problem as published.
+    pub diagnostic: Diagnostic,
+    /// The fix offered for it, if any.
+    pub fix: Option<Fix>,
+}
+
+/// Edits that fix a problem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    /// What the fix does, as shown in the editor.
+    pub title: String,
+    /// Edits to the document.
+    pub edits: Vec<TextEdit>,
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Problems of a document: parse errors, then argument, evaluation and
+/// unused declaration findings.
+#[must_use]
+pub fn findings(document: &Document) -> Vec<Finding> {
+    let cst = openscad_parser::parse(&document.text);
+    let mut findings: Vec<Finding> = cst.errors.iter().map(|error| parse_finding(document, error)).collect();
+    parameter_findings(&cst.root, &cst.root, document, &mut findings);
+    if cst.errors.is_empty() {
+        eval_findings(document, &mut findings);
+    }
+    unused_findings(&cst.root, document, &mut findings);
+    findings
+}
+
+/// Problems of an open document of a workspace: those of the document
+/// alone, then those of its links.
+#[must_use]
+pub fn workspace_findings(workspace: &Workspace, uri: &Uri) -> Vec<Finding> {
+    let Some(document) = workspace.document(uri) else { return Vec::new() };
+    let mut findings = findings(document);
+    link_findings(workspace, uri, document, &mut findings);
+    findings
+}
+
+/// Diagnostics of an open document of a workspace, as published.
+#[must_use]
+pub fn diagnostics(workspace: &Workspace, uri: &Uri) -> Vec<Diagnostic> {
+    workspace_findings(workspace, uri).into_iter().map(|f| f.diagnostic).collect()
+}
+
+// =============================================================================
+// PARSE ERRORS
+// =============================================================================
+
+/// A parse error, with a fix for the typos it can tell apart.
+fn parse_finding(document: &Document, error: &ParseError) -> Finding {
+    let range = document.range_of(error.span);
+    let fix = match &error.kind {
+        ParseErrorKind::UnexpectedToken { found, .. } if found == "==" => Some((EQUALITY_AS_ASSIGNMENT, Fix {
+            title: "Replace `==` with `=`".to_string(),
+            edits: vec![TextEdit::new(range, "=".to_string())],
+        })),
+        ParseErrorKind::UnexpectedToken { found, expected } if expected == ";" || found.is_empty() && expected == "statement" => {
+            missing_semicolon(document, error.span.start.byte).map(|fix| (MISSING_SEMICOLON, fix))
+        }
+        _ => None,
+    };
+    Finding {
+        diagnostic: Diagnostic {
+            range,
+            severity: Some(DiagnosticSeverity::ERROR),
+            code: fix.as_ref().map(|(code, _)| NumberOrString::String(code.to_string())),
+            source: Some("openscad".to_string()),
+            message: error.kind.to_string(),
+            ..Diagnostic::default()
+        },
+        fix: fix.map(|(_, fix)| fix),
+    }
+}
+
+/// Insert `;` after the token before an error, if that token can end a
+/// statement.
+///
+/// The error sits on whatever follows the unfinished statement, often the
+/// next line, so the `;` goes right after the statement instead.
+fn missing_semicolon(document: &Document, error: usize) -> Option<Fix> {
+    let tokens = Lexer::new(&document.text).tokenize();
+    let last = tokens.iter().take_while(|t| t.span.end.byte <= error && t.kind != TokenKind::Eof).last()?;
+    let ends_value = matches!(
+        last.kind,
+        TokenKind::RParen | TokenKind::RBracket | TokenKind::Identifier | TokenKind::Number
+            | TokenKind::String | TokenKind::True | TokenKind::False | TokenKind::Undef | TokenKind::SpecialVariable
+    );
+    if !ends_value {
+        return None;
+    }
+    let position = document.position_at(last.span.end.byte);
+    Some(Fix {
+        title: "Insert missing `;`".to_string(),
+        edits: vec![TextEdit::new(Range::new(position, position), ";".to_string())],
+    })
+}
+
+// =============================================================================
+// ARGUMENTS
+// =============================================================================
+
+/// Add findings for named arguments that user modules and functions do
+/// not declare, in a node and below it.
+fn parameter_findings(node: &CstNode, root: &CstNode, document: &Document, findings: &mut Vec<Finding>) {
+    if matches!(node.kind, NodeKind::ModuleCall | NodeKind::FunctionCall) {
+        call_findings(node, root, document, findings);
+    }
+    for child in &node.children {
+        parameter_findings(child, root, document, findings);
+    }
+}
+
+/// Add findings for the named arguments of one call.
+fn call_findings(call: &CstNode, root: &CstNode, document: &Document, findings: &mut Vec<Finding>) {
+    let source = document.text.as_str();
+    let Some(callee) = callee(call) else { return };
+    let mut symbols = Symbols::default();
+    collect_scope(root, source, call.span.start.byte, &mut symbols);
+    let declarations = if call.kind == NodeKind::ModuleCall { &symbols.modules } else { &symbols.functions };
+    // Builtins are checked by the evaluator
+    let Some(declaration) = declarations.iter().find(|d| d.name == callee) else { return };
+
+    for (name, argument) in unknown_arguments(call, declaration) {
+        let parameter = &source[argument.span.start.byte..argument.span.end.byte];
+        let separator = if declaration.params.is_empty() { "" } else { ", " };
+        let at = document.position_at(declaration.params_end);
+        findings.push(Finding {
+            diagnostic: unknown_parameter(document, callee, name),
+            fix: Some(Fix {
+                title: format!("Add parameter `{}` to `{}`", parameter, callee),
+                edits: vec![TextEdit::new(Range::new(at, at), format!("{}{}", separator, parameter))],
+            }),
+        });
+    }
+}
+
+/// Name of the callee of a call.
+fn callee(call: &CstNode) -> Option<&str> {
+    call.children.first().filter(|c| c.kind == NodeKind::Identifier).and_then(|c| c.text.as_deref())
+}
+
+/// Named arguments of a call that a declaration has no parameter for,
+/// with their name nodes. Special variables are always accepted.
+fn unknown_arguments<'a>(call: &'a CstNode, declaration: &Declaration) -> Vec<(&'a CstNode, &'a CstNode)> {
+    call.children.iter()
+        .filter(|c| c.kind == NodeKind::Arguments)
+        .flat_map(|a| &a.children)
+        .filter(|a| a.kind == NodeKind::NamedArgument)
+        .filter_map(|argument| {
+            let name = argument.children.first()?;
+            let text = name.text.as_deref()?;
+            let known = text.starts_with('$') || declaration.params.iter().any(|(param, _)| param == text);
+            (!known).then_some((name, argument))
+        })
+        .collect()
+}
+
+/// Warning about a named argument the callee lacks.
+fn unknown_parameter(document: &Document, callee: &str, name: &CstNode) -> Diagnostic {
+    Diagnostic {
+        range: document.range_of(name.span),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(UNKNOWN_PARAMETER.to_string())),
+        source: Some("openscad".to_string()),
+        message: format!("{}(): variable {} not specified as parameter", callee, name.text.as_deref().unwrap_or_default()),
+        ..Diagnostic::default()
+    }
+}
+
+// =============================================================================
+// LINKS
+// =============================================================================
+
+/// Add findings for unresolved links, and for calls only linked files
+/// can answer.
+fn link_findings(workspace: &Workspace, uri: &Uri, document: &Document, findings: &mut Vec<Finding>) {
+    for link in workspace.links(uri).iter().filter(|l| l.target.is_none()) {
+        let kind = if link.kind == LinkKind::Include { "include" } else { "use" };
+        findings.push(Finding {
+            diagnostic: Diagnostic {
+                range: document.range_of(link.span),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String(MISSING_FILE.to_string())),
+                source: Some("openscad".to_string()),
+                message: format!("Can't open {} file '{}'", kind, link.path),
+                ..Diagnostic::default()
+            },
+            fix: None,
+        });
+    }
+    let root = openscad_parser::parse(&document.text).root;
+    linked_call_findings(&root, &root, document, &workspace.linked(uri), findings);
+}
+
+/// Add findings for the calls in a node and below it that are neither
+/// builtins nor declared in the document.
+fn linked_call_findings(node: &CstNode, root: &CstNode, document: &Document, linked: &Linked, findings: &mut Vec<Finding>) {
+    if matches!(node.kind, NodeKind::ModuleCall | NodeKind::FunctionCall) {
+        linked_call(node, root, document, linked, findings);
+    }
+    for child in &node.children {
+        linked_call_findings(child, root, document, linked, findings);
+    }
+}
+
+/// Add findings for one call of a name from a linked file, or of one
+/// declared nowhere.
+fn linked_call(call: &CstNode, root: &CstNode, document: &Document, linked: &Linked, findings: &mut Vec<Finding>) {
+    let Some(callee) = callee(call) else { return };
+    let module = call.kind == NodeKind::ModuleCall;
+    let mut symbols = Symbols::default();
+    collect_scope(root, &document.text, call.span.start.byte, &mut symbols);
+    let declarations = if module { &symbols.modules } else { &symbols.functions };
+    let kind = if module { BuiltinKind::Module } else { BuiltinKind::Function };
+    if declarations.iter().any(|d| d.name == callee) || builtins::lookup(callee).is_some_and(|b| b.kind == kind) {
+        return;
+    }
+
+    match linked.declaration(callee, module) {
+        Some((_, declaration)) => {
+            for (name, _) in unknown_arguments(call, declaration) {
+                findings.push(Finding { diagnostic: unknown_parameter(document, callee, name), fix: None });
+            }
+        }
+        // Names from a file that cannot be read may be anything
+        None if linked.complete => {
+            let (code, what) = if module { (UNKNOWN_MODULE, "module") } else { (UNKNOWN_FUNCTION, "function") };
+            let name = call.children.first().map_or(call.span, |n| n.span);
+            findings.push(Finding {
+                diagnostic: Diagnostic {
+                    range: document.range_of(name),
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    code: Some(NumberOrString::String(code.to_string())),
+                    source: Some("openscad".to_string()),
+                    message: format!("Unknown {}: {}", what, callee),
+                    ..Diagnostic::default()
+                },
+                fix: None,
+            });
+        }
+        None => {}
+    }
+}
+
+// =============================================================================
+// EVALUATION
+// =============================================================================
+
+/// Add findings for the evaluator's warnings, except those already found
+/// statically and those needing other files.
+fn eval_findings(document: &Document, findings: &mut Vec<Finding>) {
+    let limits = Limits { max_time_ms: Some(EVAL_TIME_MS), max_iterations: Some(100_000), max_nodes: Some(100_000), ..Limits::default() };
+    let options = EvalOptions { limits, ..EvalOptions::default() };
+    // Errors such as a failed assert() stop the evaluation; the warnings
+    // before them are lost with it
+    let Ok(result) = openscad_eval::evaluate_with_options(&document.text, &options) else { return };
+
+    let mut seen = HashSet::new();
+    for diagnostic in result.diagnostics {
+        let code = match diagnostic.kind {
+            DiagnosticKind::IgnoredArgument => IGNORED_ARGUMENT,
+            DiagnosticKind::Degenerate => DEGENERATE,
+            DiagnosticKind::EmptyGeometry => EMPTY_GEOMETRY,
+            DiagnosticKind::NoEffect => NO_EFFECT,
+            DiagnosticKind::Deprecated => DEPRECATED,
+            DiagnosticKind::General => continue,
+        };
+        let Some(span) = diagnostic.span else { continue };
+        let known = findings.iter().any(|f| f.diagnostic.message == diagnostic.text);
+        // A statement in a loop warns once per iteration
+        if known || !seen.insert((span.start.byte, diagnostic.text.clone())) {
+            continue;
+        }
+        let tags = match diagnostic.kind {
+            DiagnosticKind::NoEffect => Some(vec![DiagnosticTag::UNNECESSARY]),
+            DiagnosticKind::Deprecated => Some(vec![DiagnosticTag::DEPRECATED]),
+            _ => None,
+        };
+        findings.push(Finding {
+            diagnostic: Diagnostic {
+                range: document.range_of(span),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String(code.to_string())),
+                source: Some("openscad".to_string()),
+                message: diagnostic.text,
+                tags,
+                ..Diagnostic::default()
+            },
+            fix: None,
+        });
+    }
+}
+
+// =============================================================================
+// UNUSED DECLARATIONS
+// =============================================================================
+
+/// Add hints for modules, functions and variables never referred to.
+///
+/// Names are matched across the whole file, whatever the scope or
+/// namespace, so a name used anywhere counts as used everywhere.
+fn unused_findings(root: &CstNode, document: &Document, findings: &mut Vec<Finding>) {
+    let mut used = HashSet::new();
+    collect_uses(root, &mut used);
+    let draws = root.children.iter().any(is_shape);
+    let includes = root.children.iter().any(|c| c.kind == NodeKind::IncludeStatement);
+    for statement in &root.children {
+        let checked = match statement.kind {
+            NodeKind::ModuleDeclaration | NodeKind::FunctionDeclaration => draws,
+            NodeKind::Assignment => draws && !includes,
+            _ => true,
+        };
+        if checked {
+            unused_declarations(statement, &used, document, findings);
+        }
+    }
+}
+
+/// Names referred to in a node and below it: every identifier but the
+/// names being declared or bound.
+fn collect_uses<'a>(node: &'a CstNode, used: &mut HashSet<&'a str>) {
+    let binder = matches!(
+        node.kind,
+        NodeKind::ModuleDeclaration | NodeKind::FunctionDeclaration | NodeKind::Assignment
+            | NodeKind::Parameter | NodeKind::ForAssignment | NodeKind::NamedArgument
+    );
+    for (index, child) in node.children.iter().enumerate() {
+        if binder && index == 0 {
+            continue;
+        }
+        if let Some(text) = child.text.as_deref().filter(|_| child.kind == NodeKind::Identifier) {
+            used.insert(text);
+        }
+        collect_uses(child, used);
+    }
+}
+
+/// Add hints for the declarations in a node and below it whose names are
+/// not used.
+fn unused_declarations(node: &CstNode, used: &HashSet<&str>, document: &Document, findings: &mut Vec<Finding>) {
+    let what = match node.kind {
+        NodeKind::ModuleDeclaration => Some("module"),
+        NodeKind::FunctionDeclaration => Some("function"),
+        NodeKind::Assignment => Some("variable"),
+        _ => None,
+    };
+    let name = node.children.first().filter(|n| n.kind == NodeKind::Identifier);
+    if let (Some(what), Some(name)) = (what, name) {
+        let text = name.text.as_deref().unwrap_or_default();
+        if !text.starts_with('$') && !used.contains(text) {
+            findings.push(Finding {
+                diagnostic: Diagnostic {
+                    range: document.range_of(name.span),
+                    severity: Some(DiagnosticSeverity::HINT),
+                    code: Some(NumberOrString::String(UNUSED_DECLARATION.to_string())),
+                    source: Some("openscad".to_string()),
+                    message: format!("{} `{}` is never used", what, text),
+                    tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+                    ..Diagnostic::default()
+                },
+                fix: None,
+            });
+        }
+    }
+    for child in &node.children {
+        unused_declarations(child, used, document, findings);
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The document after applying the fix of its first finding.
+    fn fixed(source: &str) -> String {
+        let document = Document::new(source.to_string(), 1);
+        let fix = findings(&document).into_iter().find_map(|f| f.fix).unwrap();
+        let mut text = source.to_string();
+        for edit in fix.edits.iter().rev() {
+            let range = document.offset_at(edit.range.start)..document.offset_at(edit.range.end);
+            text.replace_range(range, &edit.new_text);
+        }
+        text
+    }
+
+    /// Test parse errors from typos come with fixes.
+    #[test]
+    fn test_parse_fixes() {
+        assert_eq!(fixed("x = 1\ny = 2;"), "x = 1;\ny = 2;");
+        assert_eq!(fixed("cube(1)\n"), "cube(1);\n");
+        assert_eq!(fixed("module m() { x == 2; cube(x); }"), "module m() { x = 2; cube(x); }");
+
+        let document = Document::new("cube(;".to_string(), 1);
+        let findings = findings(&document);
+        assert!(findings[0].fix.is_none());
+        assert!(findings[0].diagnostic.code.is_none());
+    }
+
+    /// Test named arguments a user module lacks are flagged and added.
+    #[test]
+    fn test_unknown_parameter() {
+        assert_eq!(fixed("module peg(h) {}\npeg(1, r = 2);"), "module peg(h, r = 2) {}\npeg(1, r = 2);");
+        assert_eq!(fixed("function f() = 1;\nx = f(k = [1, 2]);"), "function f(k = [1, 2]) = 1;\nx = f(k = [1, 2]);");
+
+        // Builtins are left to the evaluator
+        let document = Document::new("module peg(h) {}\npeg(h = 1, $fn = 8);\ncube(size = 1, rounding = 2);".to_string(), 1);
+        let codes: Vec<_> = findings(&document).into_iter().map(|f| f.diagnostic.code).collect();
+        assert_eq!(codes, [Some(NumberOrString::String(IGNORED_ARGUMENT.to_string()))]);
+    }
+
+    /// Test evaluator warnings are published on their statements, once.
+    #[test]
+    fn test_eval_warnings() {
+        let source = "difference() {\n  cube(10);\n  translate([20, 0, 0]) cube(2);\n}\nfor (i = [0:2]) cube(0);\nmodule m(r) { sphere(r); }\nm(1, s = 2);";
+        let document = Document::new(source.to_string(), 1);
+        let found: Vec<_> = findings(&document).into_iter().map(|f| (f.diagnostic.message, f.diagnostic.range.start.line)).collect();
+        assert_eq!(found, [
+            ("m(): variable s not specified as parameter".to_string(), 6),
+            ("difference(): child 2 does not overlap child 1 and removes nothing".to_string(), 0),
+            ("cube(): size is zero, no geometry produced".to_string(), 4),
+        ]);
+
+        // Nothing is evaluated with parse errors, nor unknown modules flagged
+        let document = Document::new("cube(0);\ncube(;".to_string(), 1);
+        assert_eq!(findings(&document).len(), 1);
+        let document = Document::new("widget(1);".to_string(), 1);
+        assert!(findings(&document).is_empty());
+    }
+
+    /// Test links are checked against the files of the workspace.
+    #[test]
+    fn test_links() {
+        let uri = |path: &str| -> Uri { format!("file:///project/{}", path).parse().unwrap() };
+        let mut workspace = Workspace::new();
+        workspace.open(uri("lib.scad"), Document::new("module peg(h) {}".to_string(), 1));
+        let main = "use <lib.scad>\npeg(1, r = 2);\npge();\ncube(lenght([1]));";
+        workspace.open(uri("main.scad"), Document::new(main.to_string(), 1));
+        let found: Vec<_> = workspace_findings(&workspace, &uri("main.scad")).into_iter()
+            .map(|f| (f.diagnostic.message, f.fix.is_some()))
+            .collect();
+        assert_eq!(found, [
+            ("peg(): variable r not specified as parameter".to_string(), false),
+            ("Unknown module: pge".to_string(), false),
+            ("Unknown function: lenght".to_string(), false),
+        ]);
+
+        // With a link missing, unknown names may come from it
+        let main = "include <gone.scad>\nwidget();";
+        workspace.open(uri("main.scad"), Document::new(main.to_string(), 2));
+        let found: Vec<_> = workspace_findings(&workspace, &uri("main.scad")).into_iter().map(|f| f.diagnostic.message).collect();
+        assert_eq!(found, ["Can't open include file 'gone.scad'"]);
+    }
+
+    /// Test unused declarations are hints, except at the top of libraries.
+    #[test]
+    fn test_unused_declarations() {
+        let unused = |source: &str| -> Vec<String> {
+            let document = Document::new(source.to_string(), 1);
+            findings(&document).into_iter()
+                .filter(|f| f.diagnostic.code == Some(NumberOrString::String(UNUSED_DECLARATION.to_string())))
+                .map(|f| f.diagnostic.message)
+                .collect()
+        };
+        let source = "w = 2;\nd = 3;\n$fn = 32;\nfunction area(x) = x * x;\nmodule peg() { spare = 1; cube(w); }\nmodule old() {}\npeg();";
+        assert_eq!(unused(source), ["variable `d` is never used", "function `area` is never used", "variable `spare` is never used", "module `old` is never used"]);
+
+        assert!(unused("size = 2;\nmodule peg() { cube(size); }").is_empty());
+        assert_eq!(unused("include <config.scad>\nwall = 2;\ncube(1);"), Vec::<String>::new());
+    }
+}
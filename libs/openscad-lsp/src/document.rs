@@ -0,0 +1,100 @@
+//! # Documents
+//!
+//! Text of an open file and conversion between byte offsets, which the
+//! parser uses, and LSP positions, which count UTF-16 code units within
+//! a line.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lsp_types::Position;
+//! use openscad_lsp::document::Document;
+//!
+//! let doc = Document::new("cube(1);\nsphere(2);".to_string(), 1);
+//! assert_eq!(doc.offset_at(Position::new(1, 6)), 15);
+//! assert_eq!(doc.position_at(15), Position::new(1, 6));
+//! ```
+
+use lsp_types::{Position, Range};
+use openscad_parser::Span;
+
+/// An open text document.
+#[derive(Debug, Clone)]
+pub struct Document {
+    /// Full text.
+    pub text: String,
+    /// Version from the client, increasing with each change.
+    pub version: i32,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl Document {
+    /// Create a document from its text.
+    pub fn new(text: String, version: i32) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { text, version, line_starts }
+    }
+
+    /// Byte offset of an LSP position.
+    ///
+    /// Positions past the end of a line clamp to the line end, and lines
+    /// past the end of the text to the text end.
+    #[must_use]
+    pub fn offset_at(&self, position: Position) -> usize {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return self.text.len();
+        };
+        let line = self.text[start..].split('\n').next().unwrap_or_default();
+        let mut units = 0;
+        for (i, c) in line.char_indices() {
+            if units >= position.character as usize {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        start + line.len()
+    }
+
+    /// LSP position of a byte offset.
+    #[must_use]
+    pub fn position_at(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        let start = self.line_starts[line];
+        let character = self.text.get(start..offset)
+            .map_or(0, |s| s.chars().map(char::len_utf16).sum::<usize>());
+        Position::new(line as u32, character as u32)
+    }
+
+    /// LSP range of a parser span.
+    #[must_use]
+    pub fn range_of(&self, span: Span) -> Range {
+        Range::new(self.position_at(span.start.byte), self.position_at(span.end.byte))
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test positions count UTF-16 units and clamp past line ends.
+    #[test]
+    fn test_positions() {
+        // 'é' is two bytes and one unit, '😀' four bytes and two units
+        let doc = Document::new("a = \"é😀\";\nb".to_string(), 1);
+        assert_eq!(doc.offset_at(Position::new(0, 6)), 7);
+        assert_eq!(doc.offset_at(Position::new(0, 8)), 11);
+        assert_eq!(doc.position_at(11), Position::new(0, 8));
+        assert_eq!(doc.offset_at(Position::new(0, 99)), 13);
+        assert_eq!(doc.offset_at(Position::new(1, 0)), 14);
+        assert_eq!(doc.offset_at(Position::new(5, 0)), 15);
+        assert_eq!(doc.position_at(99), Position::new(1, 1));
+    }
+}
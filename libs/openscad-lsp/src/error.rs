@@ -0,0 +1,24 @@
+//! # Server Errors
+//!
+//! Error types for the language server loop.
+
+use thiserror::Error;
+
+/// Errors that stop the language server.
+#[derive(Debug, Error)]
+pub enum LspError {
+    /// The client broke the protocol or disconnected mid-handshake.
+    #[error("Protocol error: {0}")]
+    Protocol(#[from] lsp_server::ProtocolError),
+
+    /// Initialization parameters or capabilities could not be encoded.
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The connection to the client closed.
+    #[error("Connection closed: {0}")]
+    Disconnected(String),
+}
+
+/// Result type for server operations.
+pub type LspResult<T> = Result<T, LspError>;
@@ -0,0 +1,136 @@
+//! # Formatting
+//!
+//! Whole-document and range formatting with the parser's formatter. The
+//! client's tab size and tabs-or-spaces choice override the defaults; a
+//! document that does not parse is not formatted, so half-typed code is
+//! never rearranged.
+//!
+//! The formatter only rewrites whitespace and comments, so a
+//! [`SourceMap`] between the old and new text pairs every token up, and
+//! the edits replace just the runs between tokens that changed. Cursors,
+//! selections and diagnostics on the tokens stay where they are.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lsp_types::{FormattingOptions, Position, Range};
+//! use openscad_lsp::document::Document;
+//! use openscad_lsp::formatting::format_document;
+//!
+//! let doc = Document::new("cube( 10 ) ;".to_string(), 1);
+//! let options = FormattingOptions { tab_size: 2, insert_spaces: true, ..FormattingOptions::default() };
+//! let edits = format_document(&doc, &options).unwrap();
+//! // The space after `(` goes, `10` itself is left alone
+//! assert_eq!(edits[0].range, Range::new(Position::new(0, 5), Position::new(0, 6)));
+//! assert_eq!(edits[0].new_text, "");
+//! assert_eq!(edits.last().unwrap().new_text, "\n");
+//! ```
+
+use lsp_types::{FormattingOptions, Range, TextEdit};
+use openscad_parser::format::{self, FormatOptions};
+use openscad_parser::SourceMap;
+
+use crate::document::Document;
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Edits formatting a whole document.
+///
+/// ## Returns
+///
+/// One edit per rewritten run between tokens, none if the document is
+/// already formatted, or `None` if it does not parse.
+#[must_use]
+pub fn format_document(document: &Document, options: &FormattingOptions) -> Option<Vec<TextEdit>> {
+    let text = format::format_with_options(&document.text, &format_options(options)).ok()?;
+    Some(edits(document, 0..document.text.len(), &text))
+}
+
+/// Edits formatting the top-level statements that overlap a range.
+///
+/// ## Returns
+///
+/// Edits as for [`format_document`] within those statements, or `None`
+/// if the document does not parse.
+#[must_use]
+pub fn format_range(document: &Document, range: Range, options: &FormattingOptions) -> Option<Vec<TextEdit>> {
+    let bytes = document.offset_at(range.start)..document.offset_at(range.end);
+    let (bytes, text) = format::format_range(&document.text, bytes, &format_options(options)).ok()?;
+    Some(edits(document, bytes, &text))
+}
+
+/// Edits turning the text at `bytes` into its formatted `text`: one per
+/// run between tokens the [`SourceMap`] finds changed, or a single edit
+/// over all of it if the tokens do not pair up.
+fn edits(document: &Document, bytes: std::ops::Range<usize>, text: &str) -> Vec<TextEdit> {
+    let range_of = |start: usize, end: usize| Range::new(document.position_at(bytes.start + start), document.position_at(bytes.start + end));
+    let Some(map) = SourceMap::between(&document.text[bytes.clone()], text) else {
+        return vec![TextEdit::new(range_of(0, bytes.len()), text.to_string())];
+    };
+    map.changes()
+        .iter()
+        .map(|change| TextEdit::new(range_of(change.old.start.byte, change.old.end.byte), text[change.new.start.byte..change.new.end.byte].to_string()))
+        .collect()
+}
+
+/// Formatter options from the client's.
+fn format_options(options: &FormattingOptions) -> FormatOptions {
+    FormatOptions {
+        indent_width: options.tab_size as usize,
+        use_tabs: !options.insert_spaces,
+        ..FormatOptions::default()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Position;
+
+    fn options(insert_spaces: bool) -> FormattingOptions {
+        FormattingOptions { tab_size: 2, insert_spaces, ..FormattingOptions::default() }
+    }
+
+    /// The text of `document` after `edits`, applied last to first as
+    /// a client does.
+    fn apply(document: &Document, edits: &[TextEdit]) -> String {
+        let mut text = document.text.clone();
+        for edit in edits.iter().rev() {
+            let bytes = document.offset_at(edit.range.start)..document.offset_at(edit.range.end);
+            text.replace_range(bytes, &edit.new_text);
+        }
+        text
+    }
+
+    /// Test documents are formatted with the client's indentation, by
+    /// edits that leave the tokens alone.
+    #[test]
+    fn test_format_document() {
+        let document = Document::new("module m(){\ncube(1);}".to_string(), 1);
+        let edits = format_document(&document, &options(true)).unwrap();
+        assert_eq!(apply(&document, &edits), "module m() {\n  cube(1);\n}\n");
+        assert_eq!(edits[0], TextEdit::new(Range::new(Position::new(0, 10), Position::new(0, 10)), " ".to_string()));
+        assert_eq!(edits[1], TextEdit::new(Range::new(Position::new(0, 11), Position::new(1, 0)), "\n  ".to_string()));
+        assert_eq!(apply(&document, &format_document(&document, &options(false)).unwrap()), "module m() {\n\tcube(1);\n}\n");
+
+        let formatted = Document::new(apply(&document, &edits), 2);
+        assert!(format_document(&formatted, &options(true)).unwrap().is_empty());
+        assert!(format_document(&Document::new("cube(".to_string(), 1), &options(true)).is_none());
+    }
+
+    /// Test range formatting edits only the statements in the range.
+    #[test]
+    fn test_format_range() {
+        let document = Document::new("a=1;\nb  =  2;\nc=3;".to_string(), 1);
+        let range = Range::new(Position::new(1, 1), Position::new(1, 2));
+        let edits = format_range(&document, range, &options(true)).unwrap();
+        assert_eq!(apply(&document, &edits), "a=1;\nb = 2;\nc=3;");
+        assert!(edits.iter().all(|edit| edit.range.start.line == 1 && edit.range.end.line == 1));
+    }
+}
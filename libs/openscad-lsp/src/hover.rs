@@ -0,0 +1,182 @@
+//! # Hover
+//!
+//! What the name under the cursor refers to:
+//!
+//! | Name                          | Shown                                   |
+//! |-------------------------------|-----------------------------------------|
+//! | Builtin module or function    | Signature and description               |
+//! | User module or function       | Declaration with parameter defaults     |
+//! | Top-level variable            | Its value, if it is a constant          |
+//! | Special variable              | Description, and value if assigned      |
+//!
+//! Values come from [`ConstantFolder`], which evaluates top-level
+//! assignments without running module calls. Parameters, loop variables
+//! and block locals depend on how their block is called, so hovering
+//! them shows nothing; neither does a variable whose value cannot be
+//! known without rendering, or any variable while the document does not
+//! parse.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lsp_types::HoverContents;
+//! use openscad_lsp::document::Document;
+//! use openscad_lsp::hover::hover;
+//!
+//! let doc = Document::new("w = 5;\nx = w * 3;\ncube(x);".to_string(), 1);
+//! let HoverContents::Markup(markup) = hover(&doc, 23).unwrap().contents else { panic!() };
+//! assert_eq!(markup.value, "```openscad\nx = 15\n```");
+//! ```
+
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind};
+use openscad_eval::constants::ConstantFolder;
+use openscad_parser::lexer::{Lexer, TokenKind};
+use openscad_parser::CstNode;
+
+use crate::builtins::{self, Builtin, BuiltinKind};
+use crate::completion::at_statement_start;
+use crate::document::Document;
+use crate::scope::{collect_scope, locals, Symbols};
+
+/// Longest value shown before it is cut short.
+const MAX_VALUE_LEN: usize = 200;
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Hover for the name at a byte offset of a document.
+#[must_use]
+pub fn hover(document: &Document, offset: usize) -> Option<Hover> {
+    let source = document.text.as_str();
+    let tokens = Lexer::new(source).tokenize();
+    let index = tokens.iter().position(|t| t.span.start.byte <= offset && offset < t.span.end.byte)?;
+    let token = &tokens[index];
+    if !matches!(token.kind, TokenKind::Identifier | TokenKind::SpecialVariable) {
+        return None;
+    }
+    let name = token.text.as_str();
+    let previous = index.checked_sub(1).map(|i| tokens[i].kind);
+    let called = tokens.get(index + 1).is_some_and(|t| t.kind == TokenKind::LParen);
+
+    let root = openscad_parser::parse(source).root;
+    let mut symbols = Symbols::default();
+    collect_scope(&root, source, offset, &mut symbols);
+
+    let markdown = match previous {
+        Some(TokenKind::Module) => module_markdown(&symbols, name),
+        Some(TokenKind::Function) => function_markdown(&symbols, name),
+        _ if called && at_statement_start(&tokens[..index]) => module_markdown(&symbols, name),
+        _ if called => function_markdown(&symbols, name),
+        _ => variable_markdown(&root, source, offset, name),
+    }?;
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value: markdown }),
+        range: Some(document.range_of(token.span)),
+    })
+}
+
+// =============================================================================
+// CONTENTS
+// =============================================================================
+
+/// A user module's declaration, or a builtin module's documentation.
+fn module_markdown(symbols: &Symbols, name: &str) -> Option<String> {
+    match symbols.modules.iter().find(|m| m.name == name) {
+        Some(module) => Some(code(&format!("module {}", module.signature()))),
+        None => builtins::lookup(name).filter(|b| b.kind == BuiltinKind::Module).map(builtin_markdown),
+    }
+}
+
+/// A user function's declaration, or a builtin function's documentation.
+fn function_markdown(symbols: &Symbols, name: &str) -> Option<String> {
+    match symbols.functions.iter().find(|f| f.name == name) {
+        Some(function) => Some(code(&format!("function {}", function.signature()))),
+        None => builtins::lookup(name).filter(|b| b.kind == BuiltinKind::Function).map(builtin_markdown),
+    }
+}
+
+/// A top-level variable's value, with the description of a special one.
+fn variable_markdown(root: &CstNode, source: &str, offset: usize, name: &str) -> Option<String> {
+    let shadowed = locals(root, source, offset).variables.iter().any(|v| v == name);
+    let value = openscad_ast::parse(source).ok()
+        .filter(|_| !shadowed)
+        .and_then(|ast| ConstantFolder::new(&ast).value(name).map(|v| v.to_echo_string()))
+        .map(|value| code(&format!("{} = {}", name, shorten(value))));
+    let builtin = builtins::lookup(name).filter(|b| b.kind == BuiltinKind::Variable);
+    match (value, builtin) {
+        (Some(value), Some(builtin)) => Some(format!("{}\n{}", value, builtin.doc)),
+        (None, Some(builtin)) => Some(builtin_markdown(builtin)),
+        (value, None) => value,
+    }
+}
+
+/// Signature and description of a builtin.
+fn builtin_markdown(builtin: &Builtin) -> String {
+    format!("{}\n{}", code(&builtin.signature()), builtin.doc)
+}
+
+/// An OpenSCAD code block.
+fn code(text: &str) -> String {
+    format!("```openscad\n{}\n```", text)
+}
+
+/// A value cut to [`MAX_VALUE_LEN`] characters.
+fn shorten(value: String) -> String {
+    match value.char_indices().nth(MAX_VALUE_LEN) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value,
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Markdown of the hover at the first `|` in the source.
+    fn markdown(source: &str) -> Option<String> {
+        let offset = source.find('|').unwrap();
+        let document = Document::new(source.replacen('|', "", 1), 1);
+        hover(&document, offset).map(|h| match h.contents {
+            HoverContents::Markup(markup) => markup.value,
+            other => panic!("{:?}", other),
+        })
+    }
+
+    /// Test builtins show their signature and description.
+    #[test]
+    fn test_builtins() {
+        let cube = markdown("translate([1, 0, 0]) cu|be(5);").unwrap();
+        assert!(cube.starts_with("```openscad\ncube(size, center)\n```\nBox"));
+        assert!(markdown("tr|anslate([1, 0, 0]) cube(5);").unwrap().contains("translate(v)"));
+        assert!(markdown("x = s|in(30);").unwrap().contains("sin(x)"));
+        assert!(markdown("$f|n = 12;").unwrap().starts_with("```openscad\n$fn = 12\n```\nFixed"));
+    }
+
+    /// Test user modules and functions show their declarations.
+    #[test]
+    fn test_declarations() {
+        let source = "module peg(h, r = 1) { cylinder(h, r); }\nfunction area(r) = PI * r * r;\npe|g(2);";
+        assert_eq!(markdown(source).unwrap(), "```openscad\nmodule peg(h, r = 1)\n```");
+        assert!(markdown("function ar|ea(r) = r * r;").unwrap().contains("function area(r)"));
+        // A user module shadows the builtin of the same name
+        assert!(markdown("module cube(s) {}\ncu|be(1);").unwrap().contains("module cube(s)"));
+    }
+
+    /// Test variables show constant values and nothing otherwise.
+    #[test]
+    fn test_values() {
+        assert_eq!(markdown("x = 15;\ncube(|x);").unwrap(), "```openscad\nx = 15\n```");
+        assert!(markdown("name = \"peg\";\necho(na|me);").unwrap().contains("name = \"peg\""));
+        assert!(markdown("x = 1;\nmodule m(x) { cube(|x); }").is_none());
+        assert!(markdown("include <lib.scad>\ny = size;\ncube(|y);").is_none());
+        assert!(markdown("x = 1;\ncube(|x").is_none());
+
+        let long = markdown("v = [for (i = [0:999]) i];\necho(|v);").unwrap();
+        assert!(long.ends_with("…\n```"));
+    }
+}
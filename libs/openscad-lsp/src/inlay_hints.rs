@@ -0,0 +1,150 @@
+//! # Inlay Hints
+//!
+//! Parameter names before positional arguments, so `cylinder(10, 5)`
+//! reads as `cylinder(h: 10, r1: 5)`. Arguments bind to parameters the way
+//! the evaluator binds them: the nth positional argument to the nth
+//! parameter, whatever named arguments sit between them.
+//!
+//! No hint is shown for an argument that is a variable named like its
+//! parameter, nor past the last parameter of the callee.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lsp_types::{InlayHintLabel, Position, Range};
+//! use openscad_lsp::document::Document;
+//! use openscad_lsp::inlay_hints::inlay_hints;
+//!
+//! let doc = Document::new("cylinder(10, 5);".to_string(), 1);
+//! let hints = inlay_hints(&doc, Range::new(Position::new(0, 0), Position::new(1, 0)));
+//! let labels: Vec<_> = hints.iter().map(|h| match &h.label {
+//!     InlayHintLabel::String(label) => label.clone(),
+//!     _ => unreachable!(),
+//! }).collect();
+//! assert_eq!(labels, ["h:", "r1:"]);
+//! ```
+
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, Range};
+use openscad_parser::{CstNode, NodeKind};
+
+use crate::builtins::{self, BuiltinKind};
+use crate::document::Document;
+use crate::scope::{collect_scope, Symbols};
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Parameter name hints for the arguments within a range of a document.
+#[must_use]
+pub fn inlay_hints(document: &Document, range: Range) -> Vec<InlayHint> {
+    let source = document.text.as_str();
+    let root = openscad_parser::parse(source).root;
+    let bytes = document.offset_at(range.start)..document.offset_at(range.end);
+    let mut hints = Vec::new();
+    collect_hints(&root, &root, document, &bytes, &mut hints);
+    // A call's own hints come before those of calls in its arguments
+    hints.sort_by_key(|h| (h.position.line, h.position.character));
+    hints
+}
+
+// =============================================================================
+// COLLECTION
+// =============================================================================
+
+/// Add hints for the calls in a node and below it.
+fn collect_hints(node: &CstNode, root: &CstNode, document: &Document, bytes: &std::ops::Range<usize>, hints: &mut Vec<InlayHint>) {
+    if node.span.end.byte < bytes.start || node.span.start.byte > bytes.end {
+        return;
+    }
+    if matches!(node.kind, NodeKind::ModuleCall | NodeKind::FunctionCall) {
+        call_hints(node, root, document, bytes, hints);
+    }
+    for child in &node.children {
+        collect_hints(child, root, document, bytes, hints);
+    }
+}
+
+/// Add hints for the positional arguments of one call.
+fn call_hints(call: &CstNode, root: &CstNode, document: &Document, bytes: &std::ops::Range<usize>, hints: &mut Vec<InlayHint>) {
+    let Some(name) = call.children.first().filter(|c| c.kind == NodeKind::Identifier).and_then(|c| c.text.as_deref()) else { return };
+    let Some(arguments) = call.children.iter().find(|c| c.kind == NodeKind::Arguments) else { return };
+    let params = parameters(call, root, &document.text, name);
+
+    let positional = arguments.children.iter().filter(|a| a.kind == NodeKind::Argument);
+    for (argument, param) in positional.zip(params) {
+        let start = argument.span.start.byte;
+        let named_alike = argument.children.first().is_some_and(|v| v.kind == NodeKind::Identifier && v.text.as_deref() == Some(param.as_str()));
+        if named_alike || !bytes.contains(&start) {
+            continue;
+        }
+        hints.push(InlayHint {
+            position: document.position_at(start),
+            label: InlayHintLabel::String(format!("{}:", param)),
+            kind: Some(InlayHintKind::PARAMETER),
+            text_edits: None,
+            tooltip: None,
+            padding_left: None,
+            padding_right: Some(true),
+            data: None,
+        });
+    }
+}
+
+/// Parameter names of the callee of a call: the user declaration in
+/// scope, else the builtin.
+fn parameters(call: &CstNode, root: &CstNode, source: &str, name: &str) -> Vec<String> {
+    let mut symbols = Symbols::default();
+    collect_scope(root, source, call.span.start.byte, &mut symbols);
+    let (declarations, kind) = match call.kind {
+        NodeKind::ModuleCall => (&symbols.modules, BuiltinKind::Module),
+        _ => (&symbols.functions, BuiltinKind::Function),
+    };
+    match declarations.iter().find(|d| d.name == name) {
+        Some(declaration) => declaration.params.iter().map(|(param, _)| param.clone()).collect(),
+        None => builtins::lookup(name)
+            .filter(|b| b.kind == kind)
+            .map(|b| b.params.iter().map(|p| p.to_string()).collect())
+            .unwrap_or_default(),
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Position;
+
+    /// Hint labels with their positions, over the whole document.
+    fn hints(source: &str) -> Vec<(String, Position)> {
+        let document = Document::new(source.to_string(), 1);
+        inlay_hints(&document, Range::new(Position::new(0, 0), Position::new(99, 0))).into_iter()
+            .map(|h| match h.label {
+                InlayHintLabel::String(label) => (label, h.position),
+                other => panic!("{:?}", other),
+            })
+            .collect()
+    }
+
+    /// Test builtin and user callees name their positional arguments.
+    #[test]
+    fn test_hints() {
+        assert_eq!(hints("cylinder(10, 5);"), [("h:".to_string(), Position::new(0, 9)), ("r1:".to_string(), Position::new(0, 13))]);
+        let source = "function area(w, d) = w * d;\nmodule plate(w, t = 2) { cube([w, w, t]); }\nplate(area(3, 4), t = 1, 5);";
+        let labels: Vec<String> = hints(source).into_iter().map(|(l, _)| l).collect();
+        // cube takes [w, w, t] as size; the second positional of plate is t
+        assert_eq!(labels, ["size:", "w:", "w:", "d:", "t:"]);
+    }
+
+    /// Test arguments named like their parameter, extra arguments and
+    /// unknown callees get no hint.
+    #[test]
+    fn test_no_hints() {
+        assert!(hints("h = 10;\ncylinder(h);").is_empty());
+        assert_eq!(hints("sphere(1, 2, 3);").len(), 2);
+        assert!(hints("include <lib.scad>\nwidget(1);").is_empty());
+    }
+}
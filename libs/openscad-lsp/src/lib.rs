@@ -1,14 +1,68 @@
 //! # OpenSCAD LSP
 //!
 //! Language Server Protocol implementation for OpenSCAD.
+//!
+//! ## Architecture
+//!
+//! ```text
+//! Client ⇄ server (message loop)
+//!              ├── workspace  (open documents, include/use graph)
+//!              ├── document   (text, UTF-16 positions)
+//!              ├── diagnostics (problems and their fixes)
+//!              ├── code_actions (quick fixes, rewrites)
+//!              ├── completion (cursor context)
+//!              ├── hover      (declarations, constant values)
+//!              ├── definition (declarations across files)
+//!              ├── formatting (parser's formatter)
+//!              ├── semantic_tokens (names by what they refer to)
+//!              ├── inlay_hints (parameter names of positional arguments)
+//!              ├── scope      (names visible at a position)
+//!              └── builtins   (signatures and descriptions)
+//! ```
+//!
+//! ## Features
+//!
+//! - Parse errors and unknown named arguments as diagnostics, with quick
+//!   fixes for common typos
+//! - Evaluator warnings (degenerate primitives, empty operands,
+//!   subtractions that remove nothing) and unused declarations
+//! - Multi-file projects: missing files and unknown modules checked
+//!   against the include/use graph, dependents re-checked on change, and
+//!   go to definition across files
+//! - Completion of modules, functions, variables in scope and named
+//!   parameters
+//! - Hover with builtin documentation and constant values
+//! - Document and range formatting
+//! - Semantic highlighting of modules, functions, parameters and special
+//!   variables
+//! - Parameter name hints in calls with positional arguments
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! let (connection, io_threads) = lsp_server::Connection::stdio();
+//! openscad_lsp::server::run(connection).unwrap();
+//! io_threads.join().unwrap();
+//! ```
+
+pub mod builtins;
+pub mod code_actions;
+pub mod completion;
+pub mod definition;
+pub mod diagnostics;
+pub mod document;
+pub mod error;
+pub mod formatting;
+pub mod hover;
+pub mod inlay_hints;
+mod scope;
+pub mod semantic_tokens;
+pub mod server;
+pub mod workspace;
 
-/// Placeholder - LSP implementation will go here.
-pub fn placeholder() {}
+// Re-export public API
+pub use error::{LspError, LspResult};
+pub use server::Server;
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn test_placeholder() {
-        assert!(true);
-    }
-}
+/// Crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
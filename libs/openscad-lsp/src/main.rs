@@ -0,0 +1,12 @@
+//! # OpenSCAD Language Server
+//!
+//! Serves LSP over stdin and stdout, for editors to launch.
+
+use openscad_lsp::server;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (connection, io_threads) = lsp_server::Connection::stdio();
+    server::run(connection)?;
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! # Scope
+//!
+//! Names declared in a syntax tree and visible at a position, shared by
+//! completion and hover.
+//!
+//! OpenSCAD makes the modules, functions and variables of a block visible
+//! throughout it, so the names at a position are those declared directly
+//! in each node from the root down to the one containing the position,
+//! plus the parameters and loop variables bound by those nodes.
+//!
+//! Modules, functions and variables are separate namespaces: `peg` can
+//! name all three at once.
+
+use openscad_parser::{CstNode, NodeKind, Span};
+
+/// A user-defined module or function.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Declaration {
+    /// Name as declared.
+    pub name: String,
+    /// Span of the name.
+    pub span: Span,
+    /// Parameter names with the source text of their defaults.
+    pub params: Vec<(String, Option<String>)>,
+    /// Byte offset where another parameter would go.
+    pub params_end: usize,
+}
+
+impl Declaration {
+    /// Signature like `peg(h, r = 1)`.
+    pub fn signature(&self) -> String {
+        let params: Vec<String> = self.params.iter()
+            .map(|(name, default)| match default {
+                Some(default) => format!("{} = {}", name, default),
+                None => name.clone(),
+            })
+            .collect();
+        format!("{}({})", self.name, params.join(", "))
+    }
+}
+
+/// Names visible at a position, innermost first.
+#[derive(Debug, Default)]
+pub(crate) struct Symbols {
+    /// User-defined modules.
+    pub modules: Vec<Declaration>,
+    /// User-defined functions.
+    pub functions: Vec<Declaration>,
+    /// Variables, parameters and loop variables.
+    pub variables: Vec<String>,
+}
+
+// =============================================================================
+// COLLECTION
+// =============================================================================
+
+/// Collect the names declared in each node from the root down to the
+/// node containing `position`.
+pub(crate) fn collect_scope(node: &CstNode, source: &str, position: usize, symbols: &mut Symbols) {
+    collect_declarations(node, source, symbols);
+    for child in &node.children {
+        let bound = match child.kind {
+            NodeKind::Parameters | NodeKind::ForAssignments => &child.children,
+            _ => continue,
+        };
+        for binder in bound {
+            if let Some(name) = binder.children.first().and_then(|n| n.text.clone()) {
+                symbols.variables.insert(0, name);
+            }
+        }
+    }
+    let inner = node.children.iter().find(|c| c.span.start.byte < position && position < c.span.end.byte);
+    if let Some(inner) = inner {
+        collect_scope(inner, source, position, symbols);
+    }
+}
+
+/// Collect the modules, functions and variables declared directly in a
+/// node.
+pub(crate) fn collect_declarations(node: &CstNode, source: &str, symbols: &mut Symbols) {
+    for child in &node.children {
+        let Some(name) = child.children.first().and_then(|n| n.text.clone()) else { continue };
+        match child.kind {
+            NodeKind::ModuleDeclaration => symbols.modules.insert(0, declaration(name, child, source)),
+            NodeKind::FunctionDeclaration => symbols.functions.insert(0, declaration(name, child, source)),
+            NodeKind::Assignment => symbols.variables.insert(0, name),
+            _ => {}
+        }
+    }
+}
+
+/// Which kind of declaration a name refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Namespace {
+    /// Modules, called as statements
+    Module,
+    /// Functions, called in expressions
+    Function,
+    /// Variables, parameters and loop variables
+    Variable,
+}
+
+/// The name node of the declaration of `name` visible at a position,
+/// innermost first.
+pub(crate) fn find_declaration<'a>(root: &'a CstNode, position: usize, name: &str, namespace: Namespace) -> Option<&'a CstNode> {
+    let mut path = vec![root];
+    while let Some(inner) = path.last().unwrap().children.iter().find(|c| c.span.start.byte < position && position < c.span.end.byte) {
+        path.push(inner);
+    }
+    path.into_iter().rev().find_map(|node| declared_in(node, name, namespace))
+}
+
+/// The name node of a declaration of `name` made directly in a node,
+/// including the parameters and loop variables it binds.
+pub(crate) fn declared_in<'a>(node: &'a CstNode, name: &str, namespace: Namespace) -> Option<&'a CstNode> {
+    node.children.iter()
+        .flat_map(|child| match child.kind {
+            NodeKind::Parameters | NodeKind::ForAssignments if namespace == Namespace::Variable => child.children.iter().collect(),
+            _ => vec![child],
+        })
+        .filter(|child| match child.kind {
+            NodeKind::ModuleDeclaration => namespace == Namespace::Module,
+            NodeKind::FunctionDeclaration => namespace == Namespace::Function,
+            NodeKind::Assignment | NodeKind::Parameter | NodeKind::ForAssignment => namespace == Namespace::Variable,
+            _ => false,
+        })
+        .filter_map(|child| child.children.first())
+        .find(|n| n.text.as_deref() == Some(name))
+}
+
+/// Parameters of a module or function declaration node.
+fn declaration(name: String, node: &CstNode, source: &str) -> Declaration {
+    let parameters = node.children.iter().find(|c| c.kind == NodeKind::Parameters);
+    let params = parameters.into_iter()
+        .flat_map(|p| &p.children)
+        .filter_map(|param| {
+            let name = param.children.first()?.text.clone()?;
+            let default = param.children.get(1).and_then(|d| source.get(d.span.start.byte..d.span.end.byte));
+            Some((name, default.map(str::to_string)))
+        })
+        .collect();
+    let params_end = parameters.map_or(node.span.end.byte, |p| p.span.end.byte);
+    let span = node.children.first().map_or(node.span, |n| n.span);
+    Declaration { name, span, params, params_end }
+}
+
+/// Names bound below the top level at a position: parameters, loop
+/// variables and block-local declarations, which shadow top-level names.
+pub(crate) fn locals(root: &CstNode, source: &str, position: usize) -> Symbols {
+    let mut symbols = Symbols::default();
+    let top = root.children.iter().find(|c| c.span.start.byte < position && position < c.span.end.byte);
+    if let Some(top) = top {
+        collect_scope(top, source, position, &mut symbols);
+    }
+    symbols
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test only the blocks around the position contribute locals.
+    #[test]
+    fn test_locals() {
+        let source = "x = 1;\nmodule m(a) { y = 2; for (i = [0:1]) cube(i); }\nmodule n(b) { z = 3; }";
+        let root = openscad_parser::parse(source).root;
+        let at = |text: &str| locals(&root, source, source.find(text).unwrap()).variables;
+        let mut inside = at("cube");
+        inside.sort();
+        assert_eq!(inside, ["a", "i", "y"]);
+        assert_eq!(at("z ="), ["z", "b"]);
+        assert!(at("x =").is_empty());
+    }
+
+    /// Test declarations are found innermost first, by namespace.
+    #[test]
+    fn test_find_declaration() {
+        let source = "x = 1;\nfunction x() = 2;\nmodule m(x) { cube(x); }\ncube(x);";
+        let root = openscad_parser::parse(source).root;
+        let find = |at: &str, namespace| find_declaration(&root, source.rfind(at).unwrap(), "x", namespace).map(|n| n.span.start.byte);
+        assert_eq!(find("cube(x); }", Namespace::Variable), Some(source.find("m(x").unwrap() + 2));
+        assert_eq!(find("cube(x);", Namespace::Variable), Some(0));
+        assert_eq!(find("cube(x);", Namespace::Function), Some(source.find("x()").unwrap()));
+        assert_eq!(find("cube(x);", Namespace::Module), None);
+    }
+}
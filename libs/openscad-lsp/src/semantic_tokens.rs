@@ -0,0 +1,321 @@
+//! # Semantic Tokens
+//!
+//! Highlighting by what a name refers to, which a grammar cannot tell
+//! from the text alone:
+//!
+//! | Name                      | Token type  | Modifiers                      |
+//! |---------------------------|-------------|--------------------------------|
+//! | Module                    | `class`     | `declaration`, `defaultLibrary` |
+//! | Function                  | `function`  | `declaration`, `defaultLibrary` |
+//! | Parameter, named argument | `parameter` | `declaration`                  |
+//! | Variable                  | `variable`  | `declaration`                  |
+//! | Special variable          | `macro`     | `defaultLibrary`               |
+//! | Member like `.x`          | `property`  |                                |
+//!
+//! Modules are shapes and transforms rather than routines, so they take
+//! the type that themes color apart from functions. `defaultLibrary` marks
+//! builtins that no declaration in the file replaces.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_lsp::document::Document;
+//! use openscad_lsp::semantic_tokens::semantic_tokens;
+//!
+//! let doc = Document::new("module peg(h) { cylinder(h); }".to_string(), 1);
+//! // peg, h, cylinder, h
+//! assert_eq!(semantic_tokens(&doc).len(), 4);
+//! ```
+
+use std::collections::HashSet;
+
+use lsp_types::{SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend};
+use openscad_parser::{CstNode, NodeKind};
+
+use crate::builtins::{self, BuiltinKind};
+use crate::document::Document;
+
+/// Token types, indexed by [`Kind`].
+const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::CLASS,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::MACRO,
+    SemanticTokenType::PROPERTY,
+];
+
+/// Token modifiers, one bit each.
+const TOKEN_MODIFIERS: &[SemanticTokenModifier] = &[
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+];
+
+/// Bit of `declaration`.
+const DECLARATION: u32 = 1;
+
+/// Bit of `defaultLibrary`.
+const DEFAULT_LIBRARY: u32 = 1 << 1;
+
+/// What a name refers to, in [`TOKEN_TYPES`] order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Module,
+    Function,
+    Parameter,
+    Variable,
+    SpecialVariable,
+    Property,
+}
+
+/// A classified name.
+struct Classified {
+    start: usize,
+    end: usize,
+    kind: Kind,
+    modifiers: u32,
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Token types and modifiers announced to the client.
+#[must_use]
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend { token_types: TOKEN_TYPES.to_vec(), token_modifiers: TOKEN_MODIFIERS.to_vec() }
+}
+
+/// Semantic tokens of a document, encoded relative to each other as the
+/// protocol expects.
+#[must_use]
+pub fn semantic_tokens(document: &Document) -> Vec<SemanticToken> {
+    let root = openscad_parser::parse(&document.text).root;
+    let mut declared = HashSet::new();
+    collect_declared(&root, &mut declared);
+    let mut classifier = Classifier { declared, bindings: Vec::new(), tokens: Vec::new() };
+    classifier.walk(&root);
+
+    let mut tokens = classifier.tokens;
+    tokens.sort_by_key(|t| t.start);
+    let mut encoded = Vec::with_capacity(tokens.len());
+    let mut previous = lsp_types::Position::new(0, 0);
+    for token in tokens {
+        let position = document.position_at(token.start);
+        let delta_line = position.line - previous.line;
+        let delta_start = if delta_line == 0 { position.character - previous.character } else { position.character };
+        let length = document.text[token.start..token.end].encode_utf16().count() as u32;
+        encoded.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: token.kind as u32,
+            token_modifiers_bitset: token.modifiers,
+        });
+        previous = position;
+    }
+    encoded
+}
+
+// =============================================================================
+// CLASSIFIER
+// =============================================================================
+
+/// Walks a syntax tree, tracking which names are parameters.
+struct Classifier {
+    /// Names of user modules and functions anywhere in the file.
+    declared: HashSet<String>,
+    /// Names bound around the current node, innermost last.
+    bindings: Vec<(String, Kind)>,
+    tokens: Vec<Classified>,
+}
+
+impl Classifier {
+    fn walk(&mut self, node: &CstNode) {
+        let depth = self.bindings.len();
+        match node.kind {
+            NodeKind::SourceFile | NodeKind::Block => {
+                // Block variables are visible throughout the block
+                for child in node.children.iter().filter(|c| c.kind == NodeKind::Assignment) {
+                    if let Some(name) = child.children.first().and_then(|n| n.text.clone()) {
+                        self.bindings.push((name, Kind::Variable));
+                    }
+                }
+                node.children.iter().for_each(|c| self.walk(c));
+            }
+            NodeKind::ModuleDeclaration | NodeKind::FunctionDeclaration => {
+                let kind = if node.kind == NodeKind::ModuleDeclaration { Kind::Module } else { Kind::Function };
+                for child in &node.children {
+                    match child.kind {
+                        NodeKind::Identifier => self.push(child, kind, DECLARATION),
+                        NodeKind::Parameters => {
+                            for param in &child.children {
+                                param.children.iter().skip(1).for_each(|d| self.walk(d));
+                                if let Some(name) = param.children.first() {
+                                    self.push(name, Kind::Parameter, DECLARATION);
+                                }
+                            }
+                            for param in &child.children {
+                                if let Some(name) = param.children.first().and_then(|n| n.text.clone()) {
+                                    self.bindings.push((name, Kind::Parameter));
+                                }
+                            }
+                        }
+                        _ => self.walk(child),
+                    }
+                }
+            }
+            NodeKind::ModuleCall | NodeKind::FunctionCall => {
+                let (kind, builtin) = match node.kind {
+                    NodeKind::ModuleCall => (Kind::Module, BuiltinKind::Module),
+                    _ => (Kind::Function, BuiltinKind::Function),
+                };
+                let mut children = node.children.iter();
+                // Modules have a namespace of their own, but a variable
+                // holding a function literal is called like a function
+                let variable = |callee: &CstNode| node.kind == NodeKind::FunctionCall && self.binding(callee).is_some();
+                match children.next() {
+                    Some(callee) if callee.kind == NodeKind::Identifier && !variable(callee) => {
+                        let name = callee.text.as_deref().unwrap_or_default();
+                        let library = !self.declared.contains(name) && builtins::lookup(name).is_some_and(|b| b.kind == builtin);
+                        self.push(callee, kind, if library { DEFAULT_LIBRARY } else { 0 });
+                    }
+                    Some(callee) => self.walk(callee),
+                    None => {}
+                }
+                children.for_each(|c| self.walk(c));
+            }
+            NodeKind::Assignment | NodeKind::NamedArgument => {
+                let kind = if node.kind == NodeKind::Assignment { Kind::Variable } else { Kind::Parameter };
+                let mut children = node.children.iter();
+                if let Some(name) = children.next() {
+                    self.push(name, kind, if kind == Kind::Variable { DECLARATION } else { 0 });
+                }
+                children.for_each(|c| self.walk(c));
+            }
+            NodeKind::DotExpression => {
+                if let Some((member, object)) = node.children.split_last() {
+                    object.iter().for_each(|c| self.walk(c));
+                    self.push(member, Kind::Property, 0);
+                }
+            }
+            NodeKind::Identifier => {
+                let kind = self.binding(node).unwrap_or(Kind::Variable);
+                self.push(node, kind, 0);
+            }
+            NodeKind::SpecialVariable => {
+                let name = node.text.as_deref().unwrap_or_default();
+                let library = builtins::lookup(name).is_some();
+                self.push(node, Kind::SpecialVariable, if library { DEFAULT_LIBRARY } else { 0 });
+            }
+            _ => {
+                for child in &node.children {
+                    if child.kind != NodeKind::ForAssignments {
+                        self.walk(child);
+                        continue;
+                    }
+                    // Each binding sees the ones before it
+                    for assignment in &child.children {
+                        assignment.children.iter().skip(1).for_each(|v| self.walk(v));
+                        if let Some(name) = assignment.children.first() {
+                            self.push(name, Kind::Variable, DECLARATION);
+                            if let Some(text) = name.text.clone() {
+                                self.bindings.push((text, Kind::Variable));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        self.bindings.truncate(depth);
+    }
+
+    /// Kind of the innermost binding of a name.
+    fn binding(&self, node: &CstNode) -> Option<Kind> {
+        let name = node.text.as_deref()?;
+        self.bindings.iter().rev().find(|(n, _)| n == name).map(|(_, kind)| *kind)
+    }
+
+    /// Record a name. Operators, which the tree stores as identifiers,
+    /// are skipped.
+    fn push(&mut self, node: &CstNode, kind: Kind, modifiers: u32) {
+        let is_name = node.text.as_deref()
+            .and_then(|t| t.chars().next())
+            .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$');
+        if is_name && node.span.end.byte > node.span.start.byte {
+            self.tokens.push(Classified { start: node.span.start.byte, end: node.span.end.byte, kind, modifiers });
+        }
+    }
+}
+
+/// Names of every user module and function.
+fn collect_declared(node: &CstNode, declared: &mut HashSet<String>) {
+    for child in &node.children {
+        if matches!(child.kind, NodeKind::ModuleDeclaration | NodeKind::FunctionDeclaration) {
+            if let Some(name) = child.children.first().and_then(|n| n.text.clone()) {
+                declared.insert(name);
+            }
+        }
+        collect_declared(child, declared);
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decoded tokens as (text, type, modifiers).
+    fn decode(source: &str) -> Vec<(String, SemanticTokenType, u32)> {
+        let document = Document::new(source.to_string(), 1);
+        let lines: Vec<&str> = source.lines().collect();
+        let (mut line, mut character) = (0, 0);
+        semantic_tokens(&document).iter()
+            .map(|t| {
+                if t.delta_line > 0 {
+                    character = 0;
+                }
+                line += t.delta_line as usize;
+                character += t.delta_start as usize;
+                let text = lines[line][character..character + t.length as usize].to_string();
+                (text, TOKEN_TYPES[t.token_type as usize].clone(), t.token_modifiers_bitset)
+            })
+            .collect()
+    }
+
+    /// Test modules, functions, parameters and variables are told apart.
+    #[test]
+    fn test_classification() {
+        let source = "size = 2;\nmodule peg(h, r = size) {\n  cylinder(h, r = r);\n}\nfunction area(r) = PI * r * r;\npeg(area(1) + $fn);";
+        let tokens = decode(source);
+        let of = |text: &str| tokens.iter().filter(|t| t.0 == text).map(|t| (t.1.clone(), t.2)).collect::<Vec<_>>();
+        assert_eq!(of("size"), [(SemanticTokenType::VARIABLE, DECLARATION), (SemanticTokenType::VARIABLE, 0)]);
+        assert_eq!(of("peg"), [(SemanticTokenType::CLASS, DECLARATION), (SemanticTokenType::CLASS, 0)]);
+        assert_eq!(of("cylinder"), [(SemanticTokenType::CLASS, DEFAULT_LIBRARY)]);
+        assert_eq!(of("h"), [(SemanticTokenType::PARAMETER, DECLARATION), (SemanticTokenType::PARAMETER, 0)]);
+        assert_eq!(of("area"), [(SemanticTokenType::FUNCTION, DECLARATION), (SemanticTokenType::FUNCTION, 0)]);
+        assert_eq!(of("$fn"), [(SemanticTokenType::MACRO, DEFAULT_LIBRARY)]);
+        // Named argument, then the parameter it is given
+        assert_eq!(of("r")[1..3], [(SemanticTokenType::PARAMETER, 0), (SemanticTokenType::PARAMETER, 0)]);
+        assert!(tokens.iter().all(|t| t.0 != "*"));
+    }
+
+    /// Test loop variables, members and redeclared builtins.
+    #[test]
+    fn test_scopes() {
+        let tokens = decode("module cube(s) {}\nfor (i = [0:2]) cube(p.x + i);");
+        let kinds: Vec<_> = tokens.iter().map(|t| (t.0.as_str(), t.1.as_str(), t.2)).collect();
+        assert_eq!(kinds, [
+            ("cube", "class", DECLARATION),
+            ("s", "parameter", DECLARATION),
+            ("i", "variable", DECLARATION),
+            ("cube", "class", 0),
+            ("p", "variable", 0),
+            ("x", "property", 0),
+            ("i", "variable", 0),
+        ]);
+    }
+}
@@ -0,0 +1,411 @@
+//! # Server
+//!
+//! The message loop: keeps the open documents in a [`Workspace`],
+//! publishes their diagnostics whenever they or the files they depend on
+//! change, and answers completion, hover, definition, formatting, semantic
+//! token, inlay hint and code action requests.
+//!
+//! Documents are synced in full on each change; OpenSCAD files are small
+//! enough that reparsing them is cheaper than tracking edits. Changes to
+//! files on disk arrive as `workspace/didChangeWatchedFiles`, for clients
+//! set up to watch `*.scad`. Library directories come from `OPENSCADPATH`.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! let (connection, io_threads) = lsp_server::Connection::stdio();
+//! openscad_lsp::server::run(connection).unwrap();
+//! io_threads.join().unwrap();
+//! ```
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{
+    CodeActionRequest, Completion, Formatting, GotoDefinition, HoverRequest, InlayHintRequest, RangeFormatting,
+    Request as _, SemanticTokensFullRequest,
+};
+use lsp_types::{
+    CodeActionKind, CodeActionOptions, CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability,
+    CodeActionResponse, CompletionOptions, CompletionParams, CompletionResponse, DidChangeTextDocumentParams,
+    DidChangeWatchedFilesParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams, DocumentFormattingParams,
+    GotoDefinitionParams, GotoDefinitionResponse,
+    DocumentRangeFormattingParams, Hover, HoverParams, HoverProviderCapability, InlayHint, InlayHintParams, OneOf,
+    PublishDiagnosticsParams, SemanticTokens, SemanticTokensFullOptions, SemanticTokensOptions, SemanticTokensParams,
+    SemanticTokensResult, SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Uri,
+};
+
+use crate::code_actions::code_actions;
+use crate::completion::complete;
+use crate::definition::definition;
+use crate::diagnostics::diagnostics;
+use crate::document::Document;
+use crate::formatting::{format_document, format_range};
+use crate::hover::hover;
+use crate::inlay_hints::inlay_hints;
+use crate::semantic_tokens::{legend, semantic_tokens};
+use crate::workspace::Workspace;
+use crate::error::{LspError, LspResult};
+
+/// Characters that open a completion list without typing a word.
+const TRIGGER_CHARACTERS: &[&str] = &["(", ",", "$"];
+
+/// State of a language server session.
+#[derive(Debug, Default)]
+pub struct Server {
+    /// Open documents and the files they link to.
+    workspace: Workspace,
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Run a session on a connection until the client shuts it down.
+///
+/// ## Errors
+///
+/// Returns `LspError` if the handshake fails or the connection closes
+/// before shutdown.
+pub fn run(connection: Connection) -> LspResult<()> {
+    let capabilities = serde_json::to_value(Server::capabilities())?;
+    connection.initialize(capabilities)?;
+
+    let library_paths = std::env::var_os("OPENSCADPATH").map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>());
+    let workspace = library_paths.into_iter().flatten().fold(Workspace::new(), Workspace::with_library_path);
+    let mut server = Server::with_workspace(workspace);
+    let send = |message: Message| connection.sender.send(message).map_err(|e| LspError::Disconnected(e.to_string()));
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                send(server.handle_request(request).into())?;
+            }
+            Message::Notification(notification) => {
+                for reply in server.handle_notification(notification) {
+                    send(reply.into())?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+impl Server {
+    /// Create a session with no open documents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a session on a workspace, such as one with library paths.
+    pub fn with_workspace(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+
+    /// Capabilities announced to the client.
+    pub fn capabilities() -> ServerCapabilities {
+        ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            completion_provider: Some(CompletionOptions {
+                trigger_characters: Some(TRIGGER_CHARACTERS.iter().map(|c| c.to_string()).collect()),
+                ..CompletionOptions::default()
+            }),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            definition_provider: Some(OneOf::Left(true)),
+            document_formatting_provider: Some(OneOf::Left(true)),
+            document_range_formatting_provider: Some(OneOf::Left(true)),
+            semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
+                legend: legend(),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..SemanticTokensOptions::default()
+            })),
+            inlay_hint_provider: Some(OneOf::Left(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+                code_action_kinds: Some(vec![CodeActionKind::QUICKFIX, CodeActionKind::REFACTOR_REWRITE]),
+                ..CodeActionOptions::default()
+            })),
+            ..ServerCapabilities::default()
+        }
+    }
+
+    /// Text of an open document.
+    pub fn document(&self, uri: &Uri) -> Option<&Document> {
+        self.workspace.document(uri)
+    }
+
+    /// Answer a request.
+    ///
+    /// Unknown methods get a `MethodNotFound` error, malformed parameters
+    /// an `InvalidParams` error.
+    pub fn handle_request(&mut self, request: Request) -> Response {
+        let id = request.id.clone();
+        match request.method.as_str() {
+            Completion::METHOD => match serde_json::from_value::<CompletionParams>(request.params) {
+                Ok(params) => Response::new_ok(id, self.completion(params)),
+                Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+            },
+            HoverRequest::METHOD => match serde_json::from_value::<HoverParams>(request.params) {
+                Ok(params) => Response::new_ok(id, self.hover(params)),
+                Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+            },
+            GotoDefinition::METHOD => match serde_json::from_value::<GotoDefinitionParams>(request.params) {
+                Ok(params) => Response::new_ok(id, self.definition(params)),
+                Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+            },
+            Formatting::METHOD => match serde_json::from_value::<DocumentFormattingParams>(request.params) {
+                Ok(params) => Response::new_ok(id, self.formatting(params)),
+                Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+            },
+            RangeFormatting::METHOD => match serde_json::from_value::<DocumentRangeFormattingParams>(request.params) {
+                Ok(params) => Response::new_ok(id, self.range_formatting(params)),
+                Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+            },
+            SemanticTokensFullRequest::METHOD => match serde_json::from_value::<SemanticTokensParams>(request.params) {
+                Ok(params) => Response::new_ok(id, self.semantic_tokens(params)),
+                Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+            },
+            InlayHintRequest::METHOD => match serde_json::from_value::<InlayHintParams>(request.params) {
+                Ok(params) => Response::new_ok(id, self.inlay_hints(params)),
+                Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+            },
+            CodeActionRequest::METHOD => match serde_json::from_value::<CodeActionParams>(request.params) {
+                Ok(params) => Response::new_ok(id, self.code_actions(params)),
+                Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+            },
+            _ => Response::new_err(id, ErrorCode::MethodNotFound as i32, format!("Unknown method: {}", request.method)),
+        }
+    }
+
+    /// Apply a notification.
+    ///
+    /// ## Returns
+    ///
+    /// Notifications to send back: the diagnostics of a document that
+    /// opened, changed or closed, then those of the open documents that
+    /// depend on it.
+    pub fn handle_notification(&mut self, notification: Notification) -> Vec<Notification> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Ok(params) = serde_json::from_value::<DidOpenTextDocumentParams>(notification.params) else { return Vec::new() };
+                let document = params.text_document;
+                self.workspace.open(document.uri.clone(), Document::new(document.text, document.version));
+                self.revalidate(&document.uri)
+            }
+            DidChangeTextDocument::METHOD => {
+                let Ok(params) = serde_json::from_value::<DidChangeTextDocumentParams>(notification.params) else { return Vec::new() };
+                // Full sync: the last change holds the whole text
+                let Some(change) = params.content_changes.into_iter().last() else { return Vec::new() };
+                let uri = params.text_document.uri;
+                self.workspace.open(uri.clone(), Document::new(change.text, params.text_document.version));
+                self.revalidate(&uri)
+            }
+            DidCloseTextDocument::METHOD => {
+                let Ok(params) = serde_json::from_value::<DidCloseTextDocumentParams>(notification.params) else { return Vec::new() };
+                let uri = params.text_document.uri;
+                self.workspace.close(&uri);
+                // Clear what the closed document showed
+                self.revalidate(&uri)
+            }
+            DidChangeWatchedFiles::METHOD => {
+                let Ok(params) = serde_json::from_value::<DidChangeWatchedFilesParams>(notification.params) else { return Vec::new() };
+                let mut published = Vec::new();
+                for change in params.changes {
+                    let dependents = self.workspace.dependents(&change.uri);
+                    self.workspace.reload(&change.uri);
+                    // Their links may resolve differently once a file comes or goes
+                    for dependent in &dependents {
+                        self.workspace.reload(dependent);
+                    }
+                    published.extend(dependents.iter().map(|uri| self.diagnostics(uri)));
+                }
+                published
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // =========================================================================
+    // HANDLERS
+    // =========================================================================
+
+    /// Completion items at a position of an open document.
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let document = self.workspace.document(&position.text_document.uri)?;
+        let offset = document.offset_at(position.position);
+        Some(CompletionResponse::Array(complete(&document.text, offset)))
+    }
+
+    /// Hover for the name at a position of an open document.
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let document = self.workspace.document(&position.text_document.uri)?;
+        hover(document, document.offset_at(position.position))
+    }
+
+    /// Declaration of the name at a position of an open document.
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        definition(&self.workspace, &position.text_document.uri, position.position).map(GotoDefinitionResponse::Scalar)
+    }
+
+    /// Edits formatting an open document.
+    fn formatting(&self, params: DocumentFormattingParams) -> Option<Vec<TextEdit>> {
+        let document = self.workspace.document(&params.text_document.uri)?;
+        format_document(document, &params.options)
+    }
+
+    /// Edits formatting part of an open document.
+    fn range_formatting(&self, params: DocumentRangeFormattingParams) -> Option<Vec<TextEdit>> {
+        let document = self.workspace.document(&params.text_document.uri)?;
+        format_range(document, params.range, &params.options)
+    }
+
+    /// Semantic tokens of an open document.
+    fn semantic_tokens(&self, params: SemanticTokensParams) -> Option<SemanticTokensResult> {
+        let document = self.workspace.document(&params.text_document.uri)?;
+        let data = semantic_tokens(document);
+        Some(SemanticTokensResult::Tokens(SemanticTokens { result_id: None, data }))
+    }
+
+    /// Parameter name hints within a range of an open document.
+    fn inlay_hints(&self, params: InlayHintParams) -> Option<Vec<InlayHint>> {
+        let document = self.workspace.document(&params.text_document.uri)?;
+        Some(inlay_hints(document, params.range))
+    }
+
+    /// Code actions for a range of an open document, limited to the kinds
+    /// the client asks for.
+    fn code_actions(&self, params: CodeActionParams) -> Option<CodeActionResponse> {
+        let uri = params.text_document.uri;
+        let document = self.workspace.document(&uri)?;
+        let only = params.context.only;
+        let actions = code_actions(&uri, document, params.range).into_iter()
+            .filter(|a| only.as_ref().is_none_or(|only| a.kind.as_ref().is_some_and(|k| only.iter().any(|o| k.as_str().starts_with(o.as_str())))))
+            .map(CodeActionOrCommand::CodeAction)
+            .collect();
+        Some(actions)
+    }
+
+    /// Diagnostics of a document and of the open documents depending on
+    /// it, as `publishDiagnostics` notifications.
+    fn revalidate(&self, uri: &Uri) -> Vec<Notification> {
+        std::iter::once(uri).chain(&self.workspace.dependents(uri)).map(|uri| self.diagnostics(uri)).collect()
+    }
+
+    /// Problems of a document as a `publishDiagnostics` notification;
+    /// none for a closed document.
+    fn diagnostics(&self, uri: &Uri) -> Notification {
+        let (diagnostics, version) = match self.workspace.document(uri) {
+            Some(document) => (diagnostics(&self.workspace, uri), Some(document.version)),
+            None => (Vec::new(), None),
+        };
+        let params = PublishDiagnosticsParams::new(uri.clone(), diagnostics, version);
+        Notification::new(PublishDiagnostics::METHOD.to_string(), params)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_server::RequestId;
+    use serde_json::json;
+
+    const URI: &str = "file:///model.scad";
+
+    fn open(server: &mut Server, text: &str) -> Vec<Notification> {
+        let params = json!({ "textDocument": { "uri": URI, "languageId": "openscad", "version": 1, "text": text } });
+        server.handle_notification(Notification::new(DidOpenTextDocument::METHOD.to_string(), params))
+    }
+
+    /// Test opening and changing a document publishes its parse errors.
+    #[test]
+    fn test_diagnostics() {
+        let mut server = Server::new();
+        let published = open(&mut server, "cube(10);\nsphere(;");
+        let params: PublishDiagnosticsParams = serde_json::from_value(published[0].params.clone()).unwrap();
+        assert_eq!(params.version, Some(1));
+        assert!(!params.diagnostics.is_empty());
+        assert_eq!(params.diagnostics[0].range.start.line, 1);
+
+        let change = json!({ "textDocument": { "uri": URI, "version": 2 }, "contentChanges": [{ "text": "cube(10);" }] });
+        let published = server.handle_notification(Notification::new(DidChangeTextDocument::METHOD.to_string(), change));
+        assert_eq!(published[0].params["diagnostics"], json!([]));
+        assert_eq!(server.document(&URI.parse().unwrap()).unwrap().version, 2);
+    }
+
+    /// Test a change to a file re-checks the open files depending on it,
+    /// and definitions are found across files.
+    #[test]
+    fn test_workspace() {
+        let mut server = Server::new();
+        let lib = "file:///lib.scad";
+        let params = json!({ "textDocument": { "uri": URI, "languageId": "openscad", "version": 1, "text": "use <lib.scad>\npeg(h = 1);" } });
+        let published = server.handle_notification(Notification::new(DidOpenTextDocument::METHOD.to_string(), params));
+        assert_eq!(published[0].params["diagnostics"][0]["message"], "Can't open use file 'lib.scad'");
+
+        let params = json!({ "textDocument": { "uri": lib, "languageId": "openscad", "version": 1, "text": "module peg() {}" } });
+        let published = server.handle_notification(Notification::new(DidOpenTextDocument::METHOD.to_string(), params));
+        assert_eq!(published[1].params["uri"], URI);
+        assert_eq!(published[1].params["diagnostics"][0]["message"], "peg(): variable h not specified as parameter");
+
+        let change = json!({ "textDocument": { "uri": lib, "version": 2 }, "contentChanges": [{ "text": "module peg(h) {}" }] });
+        let published = server.handle_notification(Notification::new(DidChangeTextDocument::METHOD.to_string(), change));
+        assert_eq!(published.len(), 2);
+        assert_eq!(published[1].params["diagnostics"], json!([]));
+
+        let params = json!({ "textDocument": { "uri": URI }, "position": { "line": 1, "character": 1 } });
+        let response = server.handle_request(Request::new(RequestId::from(1), GotoDefinition::METHOD.to_string(), params));
+        let location = response.result.unwrap();
+        assert_eq!(location["uri"], lib);
+        assert_eq!(location["range"]["start"], json!({ "line": 0, "character": 7 }));
+    }
+
+    /// Test completion, hover, formatting, semantic token, inlay hint and
+    /// code action requests are answered from the open document.
+    #[test]
+    fn test_requests() {
+        let mut server = Server::new();
+        open(&mut server, "module peg(h) {}\npeg(");
+        let params = json!({ "textDocument": { "uri": URI }, "position": { "line": 1, "character": 4 } });
+        let response = server.handle_request(Request::new(RequestId::from(1), Completion::METHOD.to_string(), params));
+        assert_eq!(response.result.unwrap()[0]["label"], "h");
+
+        let params = json!({ "textDocument": { "uri": URI }, "position": { "line": 1, "character": 1 } });
+        let response = server.handle_request(Request::new(RequestId::from(2), HoverRequest::METHOD.to_string(), params));
+        assert_eq!(response.result.unwrap()["contents"]["value"], "```openscad\nmodule peg(h)\n```");
+
+        let params = json!({ "textDocument": { "uri": URI }, "options": { "tabSize": 4, "insertSpaces": true } });
+        let response = server.handle_request(Request::new(RequestId::from(3), Formatting::METHOD.to_string(), params));
+        assert_eq!(response.result.unwrap(), json!(null));
+
+        let params = json!({ "textDocument": { "uri": URI } });
+        let response = server.handle_request(Request::new(RequestId::from(4), SemanticTokensFullRequest::METHOD.to_string(), params));
+        // peg and its parameter; the unfinished call is not in the tree
+        assert_eq!(response.result.unwrap()["data"].as_array().unwrap().len(), 2 * 5);
+
+        let range = json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 2, "character": 0 } });
+        let params = json!({ "textDocument": { "uri": URI }, "range": range });
+        let response = server.handle_request(Request::new(RequestId::from(5), InlayHintRequest::METHOD.to_string(), params));
+        assert_eq!(response.result.unwrap(), json!([]));
+
+        open(&mut server, "x = 1\ny = 2;");
+        let range = json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 1, "character": 0 } });
+        let params = json!({ "textDocument": { "uri": URI }, "range": range, "context": { "diagnostics": [], "only": ["quickfix"] } });
+        let response = server.handle_request(Request::new(RequestId::from(6), CodeActionRequest::METHOD.to_string(), params));
+        assert_eq!(response.result.unwrap()[0]["title"], "Insert missing `;`");
+
+        let unknown = server.handle_request(Request::new(RequestId::from(2), "no/such".to_string(), json!(null)));
+        assert_eq!(unknown.error.unwrap().code, ErrorCode::MethodNotFound as i32);
+    }
+}
@@ -0,0 +1,441 @@
+//! # Workspace
+//!
+//! The files of a project and the `include`/`use` graph between them, so
+//! that a main file sees the modules of its helpers and a change to a
+//! helper re-checks the files that depend on it.
+//!
+//! Open documents are read from the editor; the files they link to, and
+//! those files' links in turn, are read from disk once and kept until they
+//! change there.
+//!
+//! ## Resolution
+//!
+//! As in the evaluator, a path resolves next to the file containing the
+//! statement first, then against each library path (like `OPENSCADPATH`).
+//!
+//! ## Visibility
+//!
+//! `include` makes everything in the file visible: its declarations,
+//! variables and its own links. `use` makes only modules and functions
+//! visible, those of the files it links to included.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lsp_types::Uri;
+//! use openscad_lsp::document::Document;
+//! use openscad_lsp::workspace::Workspace;
+//!
+//! let main: Uri = "file:///project/main.scad".parse().unwrap();
+//! let lib: Uri = "file:///project/lib/shapes.scad".parse().unwrap();
+//! let mut workspace = Workspace::new();
+//! workspace.open(lib.clone(), Document::new("module peg() {}".to_string(), 1));
+//! workspace.open(main.clone(), Document::new("use <lib/shapes.scad>\npeg();".to_string(), 1));
+//!
+//! assert_eq!(workspace.links(&main)[0].target, Some(lib.clone()));
+//! assert_eq!(workspace.dependents(&lib), [main]);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use lsp_types::Uri;
+use openscad_parser::{CstNode, NodeKind, Span};
+
+use crate::document::Document;
+use crate::scope::{collect_declarations, Declaration, Symbols};
+
+/// How a file links to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// `include <path>`
+    Include,
+    /// `use <path>`
+    Use,
+}
+
+/// An `include` or `use` statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    /// Statement kind.
+    pub kind: LinkKind,
+    /// Path as written, without the angle brackets.
+    pub path: String,
+    /// Span of the path.
+    pub span: Span,
+    /// File the path resolves to, `None` if there is none.
+    pub target: Option<Uri>,
+}
+
+/// Files of a project with the links between them.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    /// Open documents, which take precedence over the files on disk.
+    documents: HashMap<Uri, Document>,
+    /// Linked files read from disk.
+    files: HashMap<Uri, Document>,
+    /// Links of every open or linked file.
+    graph: HashMap<Uri, Vec<Link>>,
+    /// Directories searched after the linking file's own.
+    library_paths: Vec<PathBuf>,
+}
+
+/// Modules, functions and variables that other files make visible to a
+/// document.
+#[derive(Debug, Default)]
+pub(crate) struct Linked {
+    /// Modules, functions and variables with the file declaring them.
+    pub symbols: Vec<(Uri, Symbols)>,
+    /// Whether every link followed resolved, so that a name not found is
+    /// not declared anywhere.
+    pub complete: bool,
+}
+
+impl Linked {
+    /// A module or function declared in a linked file.
+    pub fn declaration(&self, name: &str, module: bool) -> Option<(&Uri, &Declaration)> {
+        self.symbols.iter().find_map(|(uri, symbols)| {
+            let declarations = if module { &symbols.modules } else { &symbols.functions };
+            declarations.iter().find(|d| d.name == name).map(|d| (uri, d))
+        })
+    }
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+impl Workspace {
+    /// Create an empty workspace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a library search directory.
+    pub fn with_library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_paths.push(path.into());
+        self
+    }
+
+    /// Open or replace a document, and follow its links.
+    pub fn open(&mut self, uri: Uri, document: Document) {
+        let known = self.graph.contains_key(&uri);
+        self.documents.insert(uri.clone(), document);
+        self.update(&uri);
+        if !known {
+            // A new file may be what an unresolved link was missing
+            let unresolved: Vec<Uri> = self.graph.iter()
+                .filter(|(_, links)| links.iter().any(|l| l.target.is_none()))
+                .map(|(uri, _)| uri.clone())
+                .collect();
+            for uri in unresolved {
+                self.update(&uri);
+            }
+        }
+    }
+
+    /// Close a document; it is read from disk again if another file links
+    /// to it.
+    pub fn close(&mut self, uri: &Uri) {
+        self.documents.remove(uri);
+        self.reload(uri);
+    }
+
+    /// Forget what was read of a file on disk, after it changed there.
+    pub fn reload(&mut self, uri: &Uri) {
+        self.files.remove(uri);
+        if self.documents.contains_key(uri) || self.graph.values().flatten().any(|l| l.target.as_ref() == Some(uri)) {
+            self.update(uri);
+        } else {
+            self.graph.remove(uri);
+        }
+    }
+
+    /// An open document.
+    pub fn document(&self, uri: &Uri) -> Option<&Document> {
+        self.documents.get(uri)
+    }
+
+    /// An open document, or a linked file as read from disk.
+    pub fn file(&self, uri: &Uri) -> Option<&Document> {
+        self.documents.get(uri).or_else(|| self.files.get(uri))
+    }
+
+    /// The `include` and `use` statements of a file.
+    pub fn links(&self, uri: &Uri) -> &[Link] {
+        self.graph.get(uri).map_or(&[], Vec::as_slice)
+    }
+
+    /// Open documents that link to a file, directly or through other
+    /// files, sorted by URI.
+    pub fn dependents(&self, uri: &Uri) -> Vec<Uri> {
+        let mut dependents: Vec<Uri> = self.documents.keys()
+            .filter(|open| *open != uri && self.reachable(open).iter().any(|(target, _)| target == uri))
+            .cloned()
+            .collect();
+        dependents.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        dependents
+    }
+
+    // =========================================================================
+    // GRAPH
+    // =========================================================================
+
+    /// Re-read the links of a file, then read and follow the linked files
+    /// not seen yet.
+    fn update(&mut self, uri: &Uri) {
+        let mut pending = vec![uri.clone()];
+        let mut updated = HashSet::new();
+        while let Some(uri) = pending.pop() {
+            if !updated.insert(uri.as_str().to_string()) {
+                continue;
+            }
+            if !self.documents.contains_key(&uri) && !self.files.contains_key(&uri) {
+                let Some(text) = to_path(&uri).and_then(|path| std::fs::read_to_string(path).ok()) else {
+                    self.graph.remove(&uri);
+                    continue;
+                };
+                self.files.insert(uri.clone(), Document::new(text, 0));
+            }
+            let links = self.read_links(&uri);
+            for target in links.iter().filter_map(|l| l.target.clone()) {
+                if !self.graph.contains_key(&target) {
+                    pending.push(target);
+                }
+            }
+            self.graph.insert(uri, links);
+        }
+    }
+
+    /// Links of a known file, resolved.
+    fn read_links(&self, uri: &Uri) -> Vec<Link> {
+        let Some(file) = self.file(uri) else { return Vec::new() };
+        let root = openscad_parser::parse(&file.text).root;
+        let mut links = Vec::new();
+        collect_links(&root, &mut |kind, path: &CstNode| {
+            let text = path.text.clone().unwrap_or_default();
+            let target = self.resolve(&text, uri);
+            links.push(Link { kind, path: text, span: path.span, target });
+        });
+        links
+    }
+
+    /// The file a path in a file resolves to.
+    fn resolve(&self, path: &str, from: &Uri) -> Option<Uri> {
+        let base = to_path(from)?.parent()?.to_path_buf();
+        std::iter::once(base)
+            .chain(self.library_paths.iter().cloned())
+            .filter_map(|dir| to_uri(&normalize(&dir.join(path))))
+            .find(|candidate| {
+                self.documents.contains_key(candidate)
+                    || self.files.contains_key(candidate)
+                    || to_path(candidate).is_some_and(|p| p.is_file())
+            })
+    }
+
+    // =========================================================================
+    // VISIBILITY
+    // =========================================================================
+
+    /// Files linked from a file, directly or not, each once, with whether
+    /// everything in them is visible (`include`) or only declarations
+    /// (`use`).
+    pub(crate) fn reachable(&self, uri: &Uri) -> Vec<(Uri, LinkKind)> {
+        self.walk(uri).0
+    }
+
+    /// Names other files make visible to a file.
+    pub(crate) fn linked(&self, uri: &Uri) -> Linked {
+        let (files, complete) = self.walk(uri);
+        let symbols = files.into_iter()
+            .filter_map(|(target, kind)| {
+                let file = self.file(&target)?;
+                let root = openscad_parser::parse(&file.text).root;
+                let mut symbols = Symbols::default();
+                collect_declarations(&root, &file.text, &mut symbols);
+                if kind == LinkKind::Use {
+                    symbols.variables.clear();
+                }
+                Some((target, symbols))
+            })
+            .collect();
+        Linked { symbols, complete }
+    }
+
+    /// Reachable files, and whether every link followed resolved.
+    fn walk(&self, uri: &Uri) -> (Vec<(Uri, LinkKind)>, bool) {
+        let mut files = Vec::new();
+        // By text: a Uri caches its parts, which rules it out as a set key
+        let mut seen = HashSet::from([uri.as_str().to_string()]);
+        let mut complete = true;
+        let mut pending = vec![(uri.clone(), LinkKind::Include)];
+        while let Some((file, visibility)) = pending.pop() {
+            // Links in reverse, so that the first is followed first
+            for link in self.links(&file).iter().rev() {
+                let Some(target) = &link.target else {
+                    complete = false;
+                    continue;
+                };
+                if seen.insert(target.as_str().to_string()) {
+                    let kind = if visibility == LinkKind::Use { LinkKind::Use } else { link.kind };
+                    files.push((target.clone(), kind));
+                    pending.push((target.clone(), kind));
+                }
+            }
+        }
+        (files, complete)
+    }
+}
+
+/// Call `f` with the kind and path node of each `include` and `use`
+/// statement in a node and below it.
+fn collect_links(node: &CstNode, f: &mut impl FnMut(LinkKind, &CstNode)) {
+    for child in &node.children {
+        let kind = match child.kind {
+            NodeKind::IncludeStatement => LinkKind::Include,
+            NodeKind::UseStatement => LinkKind::Use,
+            _ => {
+                collect_links(child, f);
+                continue;
+            }
+        };
+        if let Some(path) = child.children.iter().find(|c| c.kind == NodeKind::FilePath) {
+            f(kind, path);
+        }
+    }
+}
+
+// =============================================================================
+// PATHS
+// =============================================================================
+
+/// Filesystem path of a `file:` URI.
+pub fn to_path(uri: &Uri) -> Option<PathBuf> {
+    let rest = uri.as_str().strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%').then(|| path.get(i + 1..i + 3)).flatten().and_then(|h| u8::from_str_radix(h, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+/// `file:` URI of an absolute filesystem path.
+pub fn to_uri(path: &Path) -> Option<Uri> {
+    let mut uri = String::from("file://");
+    for byte in path.to_str()?.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri.parse().ok()
+}
+
+/// Resolve `.` and `..` components without touching the filesystem, so
+/// that a path names the same URI however it was reached.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri(path: &str) -> Uri {
+        format!("file:///project/{}", path).parse().unwrap()
+    }
+
+    fn open(workspace: &mut Workspace, path: &str, text: &str) {
+        workspace.open(uri(path), Document::new(text.to_string(), 1));
+    }
+
+    /// Test links resolve next to their file, and missing ones do not.
+    #[test]
+    fn test_links() {
+        let mut workspace = Workspace::new();
+        open(&mut workspace, "lib/a.scad", "include <../b.scad>\nuse <missing.scad>");
+        // b is opened after a links to it
+        open(&mut workspace, "b.scad", "");
+        let links = workspace.links(&uri("lib/a.scad"));
+        assert_eq!(links[0].kind, LinkKind::Include);
+        assert_eq!(links[0].target, Some(uri("b.scad")));
+        assert_eq!(links[1].path, "missing.scad");
+        assert_eq!(links[1].target, None);
+    }
+
+    /// Test dependents are found through chains of links, in any order of
+    /// opening.
+    #[test]
+    fn test_dependents() {
+        let mut workspace = Workspace::new();
+        open(&mut workspace, "shapes.scad", "include <config.scad>\nmodule peg() {}");
+        open(&mut workspace, "config.scad", "size = 2;");
+        open(&mut workspace, "lib.scad", "include <shapes.scad>");
+        open(&mut workspace, "main.scad", "use <lib.scad>\npeg();");
+        assert_eq!(workspace.dependents(&uri("shapes.scad")), [uri("lib.scad"), uri("main.scad")]);
+        assert_eq!(workspace.dependents(&uri("config.scad")), [uri("lib.scad"), uri("main.scad"), uri("shapes.scad")]);
+
+        let linked = workspace.linked(&uri("lib.scad"));
+        assert!(linked.complete);
+        assert!(linked.declaration("peg", true).is_some());
+        let has_size = |linked: &Linked| linked.symbols.iter().any(|(_, s)| s.variables.iter().any(|v| v == "size"));
+        assert!(has_size(&linked));
+        let linked = workspace.linked(&uri("main.scad"));
+        assert!(linked.declaration("peg", true).is_some());
+        assert!(!has_size(&linked));
+    }
+
+    /// Test linked files are read from disk, from library paths too.
+    #[test]
+    fn test_disk_files() {
+        let dir = std::env::temp_dir().join(format!("c4d-workspace-{}", std::process::id()));
+        let libs = dir.join("libs");
+        std::fs::create_dir_all(&libs).unwrap();
+        std::fs::write(libs.join("gears.scad"), "module gear(teeth) {}").unwrap();
+
+        let main = to_uri(&dir.join("main.scad")).unwrap();
+        let mut workspace = Workspace::new().with_library_path(&libs);
+        workspace.open(main.clone(), Document::new("use <gears.scad>\ngear(12);".to_string(), 1));
+        let gears = workspace.links(&main)[0].target.clone().unwrap();
+        assert_eq!(to_path(&gears).unwrap(), libs.join("gears.scad"));
+        assert_eq!(workspace.file(&gears).unwrap().text, "module gear(teeth) {}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Test paths with spaces round-trip through URIs.
+    #[test]
+    fn test_uris() {
+        let path = Path::new("/my models/part #2.scad");
+        let uri = to_uri(path).unwrap();
+        assert_eq!(uri.as_str(), "file:///my%20models/part%20%232.scad");
+        assert_eq!(to_path(&uri).unwrap(), path);
+    }
+}
@@ -27,6 +27,7 @@ use serde::{Deserialize, Serialize};
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::{CstNode, NodeKind, Span};
 /// let cst = openscad_parser::parse("cube(10);");
 /// if cst.errors.is_empty() {
 ///     println!("Parsed successfully!");
@@ -61,6 +62,7 @@ impl Cst {
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::{CstNode, NodeKind, Span};
 /// let node = CstNode::new(NodeKind::Number, Span::from_bytes(0, 2));
 /// assert_eq!(node.kind, NodeKind::Number);
 /// ```
@@ -176,7 +178,9 @@ pub enum NodeKind {
     FunctionDeclaration,
     /// For loop like `for (i = [0:10]) { ... }`
     ForBlock,
-    /// For loop assignments like `i = [0:10], j = [0:5]`
+    /// Intersection loop like `intersection_for (i = [0:2]) { ... }`
+    IntersectionForBlock,
+    /// For loop or let assignments like `i = [0:10], j = [0:5]`
     ForAssignments,
     /// Single for assignment like `i = [0:10]`
     ForAssignment,
@@ -188,6 +192,8 @@ pub enum NodeKind {
     IncludeStatement,
     /// Use statement
     UseStatement,
+    /// File path of an include/use statement, without the angle brackets
+    FilePath,
 
     // Expressions
     /// Binary operation like `a + b`
@@ -196,6 +202,8 @@ pub enum NodeKind {
     UnaryExpression,
     /// Ternary operation like `a ? b : c`
     TernaryExpression,
+    /// Let expression like `let (x = 1) x + 1`
+    LetExpression,
     /// Function call like `sin(x)`
     FunctionCall,
     /// Index access like `arr[0]`
@@ -204,6 +212,14 @@ pub enum NodeKind {
     DotExpression,
     /// List comprehension like `[for (i = [0:10]) i]`
     ListComprehension,
+    /// Comprehension generator like `for (i = [0:10]) i`
+    ComprehensionFor,
+    /// Comprehension filter like `if (i > 0) i else -i`
+    ComprehensionIf,
+    /// Comprehension flattening like `each [1, 2]`
+    ComprehensionEach,
+    /// Comprehension binding like `let (j = i * 2) j`
+    ComprehensionLet,
     /// Range like `[0:10]` or `[0:1:10]`
     Range,
     /// List literal like `[1, 2, 3]`
@@ -258,6 +274,7 @@ impl NodeKind {
             Self::BinaryExpression
                 | Self::UnaryExpression
                 | Self::TernaryExpression
+                | Self::LetExpression
                 | Self::FunctionCall
                 | Self::IndexExpression
                 | Self::DotExpression
@@ -282,11 +299,13 @@ impl NodeKind {
                 | Self::ModuleDeclaration
                 | Self::FunctionDeclaration
                 | Self::ForBlock
+                | Self::IntersectionForBlock
                 | Self::IfBlock
                 | Self::LetBlock
                 | Self::IncludeStatement
                 | Self::UseStatement
                 | Self::Block
+                | Self::Modifier
         )
     }
 
@@ -343,6 +362,7 @@ mod tests {
     fn test_node_kind_is_statement() {
         assert!(NodeKind::ModuleCall.is_statement());
         assert!(NodeKind::Assignment.is_statement());
+        assert!(NodeKind::Modifier.is_statement());
         assert!(!NodeKind::Number.is_statement());
     }
 }
@@ -23,6 +23,7 @@ use std::fmt;
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::{ParseError, ParseErrorKind, Span};
 /// let error = ParseError::new(
 ///     ParseErrorKind::UnexpectedToken {
 ///         found: ")".to_string(),
@@ -0,0 +1,600 @@
+//! # Formatter
+//!
+//! An opinionated formatter: one statement per line, blocks indented,
+//! single spaces around binary operators and after commas, and argument
+//! lists that do not fit the line broken one argument per line.
+//!
+//! ## Approach
+//!
+//! The lexer drops comments, but token spans cover every byte that is not
+//! whitespace or a comment, so the gaps between tokens hold exactly the
+//! comments and line breaks of the source. The formatter walks the tokens
+//! with those gaps, reprinting the tokens and keeping every comment. The
+//! syntax tree decides whether there is anything to format - a file that
+//! does not parse is left alone - and where statements start and end for
+//! range formatting.
+//!
+//! ## Rules
+//!
+//! - Line comments stay at the end of their line, or on their own line
+//! - One blank line between statements is kept; more are collapsed
+//! - `} else {` stays on one line; empty blocks print as `{}`
+//! - Range colons are tight, `[0:2:10]`; ternary colons are spaced
+//! - A chain of statements too long for a line continues its child on
+//!   the next line, indented
+//! - `*`, `!`, `#` and `%` at the start of a statement are modifiers and
+//!   touch what they modify
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_parser::format::format;
+//!
+//! let source = "module peg(h,r=1){cylinder(h=h,r=r); // body\n}";
+//! assert_eq!(format(source).unwrap(), "module peg(h, r = 1) {\n    cylinder(h = h, r = r); // body\n}\n");
+//! ```
+
+use std::ops::Range;
+
+use crate::error::ParseError;
+use crate::lexer::{Lexer, TokenKind};
+
+/// How formatted code is laid out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Columns per indentation level.
+    pub indent_width: usize,
+    /// Indent with tabs instead of spaces.
+    pub use_tabs: bool,
+    /// Column beyond which argument lists are broken.
+    pub max_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self { indent_width: 4, use_tabs: false, max_width: 100 }
+    }
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Format a file with the default options.
+///
+/// ## Errors
+///
+/// Returns the first parse error if the source does not parse.
+pub fn format(source: &str) -> Result<String, ParseError> {
+    format_with_options(source, &FormatOptions::default())
+}
+
+/// Format a file.
+///
+/// ## Returns
+///
+/// The formatted text, ending in a newline unless it is empty.
+///
+/// ## Errors
+///
+/// Returns the first parse error if the source does not parse.
+pub fn format_with_options(source: &str, options: &FormatOptions) -> Result<String, ParseError> {
+    let cst = crate::parse(source);
+    if let Some(error) = cst.errors.into_iter().next() {
+        return Err(error);
+    }
+    let mut text = Printer::new(source, options).print();
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Format the top-level statements that overlap a byte range.
+///
+/// The range grows to whole statements, since a statement is formatted as
+/// a unit. Text between statements outside the range is not touched.
+///
+/// ## Returns
+///
+/// The byte range that was formatted and its replacement, without a
+/// trailing newline. A range without statements is returned unchanged.
+///
+/// ## Errors
+///
+/// Returns the first parse error if the source does not parse.
+pub fn format_range(source: &str, range: Range<usize>, options: &FormatOptions) -> Result<(Range<usize>, String), ParseError> {
+    let cst = crate::parse(source);
+    if let Some(error) = cst.errors.into_iter().next() {
+        return Err(error);
+    }
+    let overlapping: Vec<_> = cst.root.children.iter()
+        .filter(|s| s.span.start.byte < range.end.max(range.start + 1) && range.start < s.span.end.byte)
+        .collect();
+    let (Some(first), Some(last)) = (overlapping.first(), overlapping.last()) else {
+        let text = source.get(range.clone()).unwrap_or_default().to_string();
+        return Ok((range, text));
+    };
+    let statements = first.span.start.byte..last.span.end.byte;
+    let text = Printer::new(&source[statements.clone()], options).print();
+    Ok((statements, text))
+}
+
+// =============================================================================
+// ITEMS
+// =============================================================================
+
+/// What a printed item is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    Token(TokenKind),
+    LineComment,
+    BlockComment,
+}
+
+/// A token or comment with the line breaks before it in the source.
+#[derive(Debug, Clone, Copy)]
+struct Item<'a> {
+    kind: ItemKind,
+    text: &'a str,
+    /// Line breaks between the previous item and this one.
+    newlines: usize,
+}
+
+impl Item<'_> {
+    fn is(&self, kind: TokenKind) -> bool {
+        self.kind == ItemKind::Token(kind)
+    }
+
+    fn is_comment(&self) -> bool {
+        !matches!(self.kind, ItemKind::Token(_))
+    }
+}
+
+/// Split the source into tokens and the comments between them.
+fn items(source: &str) -> Vec<Item<'_>> {
+    let mut items = Vec::new();
+    let mut end = 0;
+    for token in Lexer::new(source).tokenize() {
+        let newlines = gap_items(&source[end..token.span.start.byte], &mut items);
+        if token.kind == TokenKind::Eof {
+            break;
+        }
+        let text = &source[token.span.start.byte..token.span.end.byte];
+        items.push(Item { kind: ItemKind::Token(token.kind), text, newlines });
+        end = token.span.end.byte;
+    }
+    items
+}
+
+/// Add the comments of a gap between tokens.
+///
+/// ## Returns
+///
+/// Line breaks after the last comment, which belong to the next token.
+fn gap_items<'a>(gap: &'a str, items: &mut Vec<Item<'a>>) -> usize {
+    let mut rest = gap;
+    let mut newlines = 0;
+    loop {
+        let trimmed = rest.trim_start();
+        newlines += rest[..rest.len() - trimmed.len()].matches('\n').count();
+        rest = trimmed;
+        let (kind, len) = if rest.starts_with("//") {
+            (ItemKind::LineComment, rest.find('\n').unwrap_or(rest.len()))
+        } else if rest.starts_with("/*") {
+            (ItemKind::BlockComment, rest.find("*/").map_or(rest.len(), |i| i + 2))
+        } else {
+            return newlines;
+        };
+        items.push(Item { kind, text: rest[..len].trim_end(), newlines });
+        newlines = 0;
+        rest = &rest[len..];
+    }
+}
+
+// =============================================================================
+// ANALYSIS
+// =============================================================================
+
+/// What a token does, as far as spacing is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    /// Ends a value: a name, a literal, `]`, or `)` of a call
+    Operand,
+    /// Prefix operator or modifier, touching what follows
+    Unary,
+    /// Infix operator, spaced on both sides
+    Binary,
+    /// `:` of a range, touching both sides
+    RangeColon,
+    Other,
+}
+
+/// An open bracket during analysis.
+struct Frame {
+    kind: TokenKind,
+    /// Its `)` ends a statement call or a `for`/`let`/`if` header rather
+    /// than a value.
+    header: bool,
+    /// Opened by a call or header at the start of a statement, so a
+    /// statement follows its `)`.
+    statement: bool,
+    /// `?` waiting for their `:`.
+    questions: usize,
+}
+
+/// Spacing facts about each item, from one pass over the tokens.
+struct Analysis {
+    /// Index of the bracket closing each opening bracket.
+    closers: Vec<usize>,
+    /// Whether a space goes before each item when printed on one line.
+    spaced: Vec<bool>,
+    /// Items ending the head of a statement whose child follows, such as
+    /// the `)` of `translate(v)`.
+    heads: Vec<bool>,
+}
+
+/// Work out the role of each token and the spaces between them.
+fn analyze(items: &[Item]) -> Analysis {
+    use TokenKind::*;
+
+    let mut closers = vec![0; items.len()];
+    let mut spaced = vec![false; items.len()];
+    let mut heads = vec![false; items.len()];
+    let mut roles = vec![Role::Other; items.len()];
+    let mut frames = vec![Frame { kind: Eof, header: false, statement: false, questions: 0 }];
+    let mut opens = Vec::new();
+    // Start of a statement, and whether the previous token was at one
+    let mut start = true;
+    let mut previous: Option<(usize, bool)> = None;
+    let mut last_item: Option<usize> = None;
+
+    for (i, item) in items.iter().enumerate() {
+        let ItemKind::Token(kind) = item.kind else {
+            spaced[i] = last_item.is_some_and(|p| !items[p].is(LParen) && !items[p].is(LBracket));
+            last_item = Some(i);
+            continue;
+        };
+        let prev = previous.map(|(p, _)| p);
+        let prev_kind = prev.and_then(|p| match items[p].kind { ItemKind::Token(k) => Some(k), _ => None });
+        let prev_role = prev.map_or(Role::Other, |p| roles[p]);
+        let at_start = start;
+
+        let role = match kind {
+            Number | String | True | False | Undef | Identifier | SpecialVariable | FilePath | RBracket => Role::Operand,
+            RParen => if frames.last().is_some_and(|f| f.header) { Role::Other } else { Role::Operand },
+            Plus | Minus => if prev_role == Role::Operand { Role::Binary } else { Role::Unary },
+            Bang | Hash => Role::Unary,
+            Star | Percent => if at_start { Role::Unary } else { Role::Binary },
+            Colon => {
+                let frame = frames.last_mut().expect("root frame");
+                if frame.kind == LBracket && frame.questions == 0 {
+                    Role::RangeColon
+                } else {
+                    frame.questions = frame.questions.saturating_sub(1);
+                    Role::Binary
+                }
+            }
+            Question => {
+                frames.last_mut().expect("root frame").questions += 1;
+                Role::Binary
+            }
+            Slash | Caret | Eq | EqEq | BangEq | Lt | Gt | LtEq | GtEq | AmpAmp | PipePipe => Role::Binary,
+            _ => Role::Other,
+        };
+        roles[i] = role;
+
+        spaced[i] = match prev_kind {
+            _ if last_item.is_some_and(|p| items[p].is_comment()) => !matches!(kind, RParen | RBracket | Comma | Semicolon),
+            None => false,
+            Some(p) => match kind {
+                RParen | RBracket | Comma | Semicolon => false,
+                _ if matches!(p, LParen | LBracket) || prev_role == Role::Unary || p == Dot => false,
+                RBrace => p != LBrace,
+                Dot | LBracket => prev_role != Role::Operand,
+                LParen => !(prev_role == Role::Operand && matches!(p, Identifier | RParen | RBracket)),
+                _ => role != Role::RangeColon && prev_role != Role::RangeColon,
+            },
+        };
+
+        match kind {
+            LParen | LBracket | LBrace => {
+                let callee = previous.filter(|_| kind == LParen);
+                let header = callee.is_some_and(|(p, was_start)| match items[p].kind {
+                    ItemKind::Token(For | If | Let | IntersectionFor) => true,
+                    ItemKind::Token(Identifier) => was_start,
+                    _ => false,
+                });
+                let statement = header && callee.is_some_and(|(_, was_start)| was_start);
+                frames.push(Frame { kind, header, statement, questions: 0 });
+                opens.push(i);
+            }
+            RParen | RBracket | RBrace => {
+                if let Some(open) = opens.pop() {
+                    closers[open] = i;
+                }
+            }
+            _ => {}
+        }
+        let closed = match kind {
+            RParen | RBracket | RBrace if frames.len() > 1 => frames.pop(),
+            _ => None,
+        };
+
+        start = match kind {
+            Semicolon | LBrace | RBrace | Else | FilePath => true,
+            RParen => {
+                heads[i] = closed.is_some_and(|f| f.statement);
+                heads[i]
+            }
+            _ => at_start && role == Role::Unary,
+        };
+        previous = Some((i, at_start));
+        last_item = Some(i);
+    }
+    Analysis { closers, spaced, heads }
+}
+
+// =============================================================================
+// PRINTER
+// =============================================================================
+
+/// Lays out the items of a source.
+struct Printer<'a> {
+    items: Vec<Item<'a>>,
+    analysis: Analysis,
+    options: &'a FormatOptions,
+    out: String,
+    /// Indentation level of the current line.
+    indent: usize,
+    /// Nothing has been written on the current line yet.
+    line_start: bool,
+    /// Width of the current line.
+    column: usize,
+    /// Extra indentation of a child statement moved to its own line.
+    continuation: usize,
+}
+
+impl<'a> Printer<'a> {
+    fn new(source: &'a str, options: &'a FormatOptions) -> Self {
+        let items = items(source);
+        let analysis = analyze(&items);
+        Self { items, analysis, options, out: String::new(), indent: 0, line_start: true, column: 0, continuation: 0 }
+    }
+
+    /// Print every item, without a trailing newline.
+    fn print(mut self) -> String {
+        use TokenKind::*;
+
+        // Whether each open bracket was broken over several lines
+        let mut wrapped: Vec<bool> = Vec::new();
+        // Continuations of the statements enclosing each open block
+        let mut continuations: Vec<usize> = Vec::new();
+        let mut pending = false;
+        for i in 0..self.items.len() {
+            let item = self.items[i];
+            if item.is(RBrace) {
+                self.indent = self.indent.saturating_sub(1);
+                self.continuation = continuations.pop().unwrap_or_default();
+                pending |= i > 0 && !self.items[i - 1].is(LBrace);
+            }
+            if (item.is(RParen) || item.is(RBracket)) && wrapped.pop() == Some(true) {
+                self.indent = self.indent.saturating_sub(1);
+                pending = true;
+            }
+
+            // A comment on the same line stays at its end; anything on a
+            // line of its own after a comment stays there
+            let trailing = item.is_comment() && item.newlines == 0;
+            let after_comment = i > 0 && self.items[i - 1].is_comment() && item.newlines > 0;
+            if i > 0 && (pending || after_comment || item.is_comment() && item.newlines > 0) && !trailing {
+                let after_open = matches!(self.items[i - 1].kind, ItemKind::Token(LBrace | LParen | LBracket));
+                let closing = matches!(item.kind, ItemKind::Token(RBrace | RParen | RBracket));
+                self.newline(item.newlines > 1 && !after_open && !closing);
+                pending = false;
+            } else if self.analysis.spaced[i] {
+                self.write(" ");
+            }
+            self.write(item.text);
+
+            let next = self.items.get(i + 1).copied();
+            match item.kind {
+                // `include <...>` ends without a semicolon
+                ItemKind::Token(Semicolon | FilePath) => {
+                    self.end_statement();
+                    pending = true;
+                }
+                ItemKind::LineComment => pending = true,
+                ItemKind::Token(LBrace) => {
+                    continuations.push(std::mem::take(&mut self.continuation));
+                    self.indent += 1;
+                    pending = !next.is_some_and(|n| n.is(RBrace));
+                }
+                ItemKind::Token(RBrace) => {
+                    // `} else` goes on with the same statement
+                    if !next.is_some_and(|n| n.is(Else)) {
+                        self.end_statement();
+                    }
+                    pending = !next.is_some_and(|n| matches!(n.kind, ItemKind::Token(Else | Semicolon | Comma | RParen | RBracket)));
+                }
+                ItemKind::Token(RParen) if self.analysis.heads[i] && !self.child_fits(i) => {
+                    self.continuation += 1;
+                    self.indent += 1;
+                    pending = true;
+                }
+                ItemKind::Token(LParen | LBracket) => {
+                    let wrap = self.wraps(i);
+                    wrapped.push(wrap);
+                    if wrap {
+                        self.indent += 1;
+                        pending = true;
+                    }
+                }
+                ItemKind::Token(Comma) if wrapped.last() == Some(&true) => pending = true,
+                _ => {}
+            }
+        }
+        let trimmed = self.out.trim_end().len();
+        self.out.truncate(trimmed);
+        self.out
+    }
+
+    /// Whether the bracket group opened at an item is broken one element
+    /// per line: when a comment of its own forces it, or when a list does
+    /// not fit.
+    fn wraps(&self, open: usize) -> bool {
+        let close = self.analysis.closers[open];
+        let mut width = self.column;
+        let mut list = false;
+        let mut depth = 0usize;
+        for (i, item) in self.items.iter().enumerate().take(close + 1).skip(open + 1) {
+            match item.kind {
+                ItemKind::LineComment if depth == 0 => return true,
+                ItemKind::BlockComment if depth == 0 && item.text.contains('\n') => return true,
+                ItemKind::Token(TokenKind::LParen | TokenKind::LBracket) => depth += 1,
+                ItemKind::Token(TokenKind::RParen | TokenKind::RBracket) => depth = depth.saturating_sub(1),
+                ItemKind::Token(TokenKind::Comma) => list |= depth == 0,
+                _ => {}
+            }
+            width += usize::from(self.analysis.spaced[i]) + item.text.chars().count();
+        }
+        if self.items.get(close + 1).is_some_and(|n| n.is(TokenKind::Semicolon)) {
+            width += 1;
+        }
+        list && width > self.options.max_width
+    }
+
+    /// Whether the child of the statement head ending at an item fits on
+    /// the rest of the line, up to its `;` or `{`.
+    fn child_fits(&self, head: usize) -> bool {
+        let mut width = self.column;
+        let mut depth = 0usize;
+        for (i, item) in self.items.iter().enumerate().skip(head + 1) {
+            match item.kind {
+                ItemKind::LineComment if i == head + 1 => return true,
+                ItemKind::LineComment => return false,
+                ItemKind::Token(TokenKind::LParen | TokenKind::LBracket) => depth += 1,
+                ItemKind::Token(TokenKind::RParen | TokenKind::RBracket) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            width += usize::from(self.analysis.spaced[i]) + item.text.chars().count();
+            let end = depth == 0 && matches!(item.kind, ItemKind::Token(TokenKind::Semicolon | TokenKind::LBrace));
+            if end || width > self.options.max_width {
+                break;
+            }
+        }
+        width <= self.options.max_width
+    }
+
+    /// Drop the continuation indentation of a finished statement.
+    fn end_statement(&mut self) {
+        self.indent -= self.continuation;
+        self.continuation = 0;
+    }
+
+    /// End the line, with a blank line after it if asked.
+    fn newline(&mut self, blank: bool) {
+        self.out.push('\n');
+        if blank {
+            self.out.push('\n');
+        }
+        self.line_start = true;
+        self.column = 0;
+    }
+
+    /// Write text, indenting it if it starts a line.
+    fn write(&mut self, text: &str) {
+        if self.line_start {
+            if self.options.use_tabs {
+                self.out.extend(std::iter::repeat_n('\t', self.indent));
+            } else {
+                self.out.extend(std::iter::repeat_n(' ', self.indent * self.options.indent_width));
+            }
+            self.column = self.indent * self.options.indent_width;
+            self.line_start = false;
+        }
+        self.out.push_str(text);
+        match text.rfind('\n') {
+            Some(i) => self.column = text[i + 1..].chars().count(),
+            None => self.column += text.chars().count(),
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(source: &str) -> String {
+        format(source).unwrap()
+    }
+
+    /// Test spacing around operators, brackets and modifiers.
+    #[test]
+    fn test_spacing() {
+        assert_eq!(fmt("x=-1+a*b[0]-f(2) ;"), "x = -1 + a * b[0] - f(2);\n");
+        assert_eq!(fmt("y = a?-b : [0 : 2 : 10];"), "y = a ? -b : [0:2:10];\n");
+        assert_eq!(fmt("v=[for(i=[0:n])if(i%2==0)[i,0.5]];"), "v = [for (i = [0:n]) if (i % 2 == 0) [i, 0.5]];\n");
+        assert_eq!(fmt("z = let(a=1)!a&&p.x;"), "z = let (a = 1) !a && p.x;\n");
+        assert_eq!(fmt("*  translate([1,0,0])% cube(1);\n# sphere($fn=8);"), "*translate([1, 0, 0]) %cube(1);\n#sphere($fn = 8);\n");
+        assert_eq!(fmt("include<lib.scad>\nuse <a.scad>"), "include <lib.scad>\nuse <a.scad>\n");
+    }
+
+    /// Test blocks are indented, one statement per line.
+    #[test]
+    fn test_blocks() {
+        let source = "module m(){if(a){cube(1);}else{sphere(1);} for(i=[0:2]) translate([i,0,0]) cube(1);}\n\n\n\nm();{}";
+        let expected = "module m() {\n    if (a) {\n        cube(1);\n    } else {\n        sphere(1);\n    }\n    for (i = [0:2]) translate([i, 0, 0]) cube(1);\n}\n\nm();\n{}\n";
+        assert_eq!(fmt(source), expected);
+        assert_eq!(fmt(""), "");
+    }
+
+    /// Test comments are kept where they were.
+    #[test]
+    fn test_comments() {
+        let source = "// header\n\n/* block\n   comment */\nx = 1;   // trailing\nmodule m() { // opens\n// inside\ncube(x); /* after */\n}";
+        let expected = "// header\n\n/* block\n   comment */\nx = 1; // trailing\nmodule m() { // opens\n    // inside\n    cube(x); /* after */\n}\n";
+        assert_eq!(fmt(source), expected);
+        assert_eq!(fmt("cube([1, // width\n2]);"), "cube([\n    1, // width\n    2\n]);\n");
+    }
+
+    /// Test long lists break one element per line, long statement chains
+    /// break before a child, and the result is stable.
+    #[test]
+    fn test_wrapping() {
+        let options = FormatOptions { max_width: 30, ..FormatOptions::default() };
+        let source = "polygon(points=[[0,0],[10,0],[10,10]],paths=undef);";
+        let expected = "polygon(\n    points = [\n        [0, 0],\n        [10, 0],\n        [10, 10]\n    ],\n    paths = undef\n);\n";
+        let formatted = format_with_options(source, &options).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format_with_options(&formatted, &options).unwrap(), expected);
+
+        let chain = "translate([10, 20, 30]) rotate([0, 0, 45]) { cube(1); }";
+        let expected = "translate([10, 20, 30])\n    rotate([0, 0, 45]) {\n        cube(1);\n    }\n";
+        assert_eq!(format_with_options(chain, &options).unwrap(), expected);
+
+        let tabs = FormatOptions { use_tabs: true, ..FormatOptions::default() };
+        assert_eq!(format_with_options("module m() { cube(1); }", &tabs).unwrap(), "module m() {\n\tcube(1);\n}\n");
+    }
+
+    /// Test range formatting covers whole statements and nothing else.
+    #[test]
+    fn test_range() {
+        let source = "a=1;\nmodule m(){cube(a);}\nb=2;";
+        let offset = source.find("cube").unwrap();
+        let (range, text) = format_range(source, offset..offset, &FormatOptions::default()).unwrap();
+        assert_eq!(&source[range], "module m(){cube(a);}");
+        assert_eq!(text, "module m() {\n    cube(a);\n}");
+    }
+
+    /// Test sources that do not parse are refused.
+    #[test]
+    fn test_errors() {
+        assert!(format("cube(;").is_err());
+        assert!(format_range("a = 1;\ncube(", 0..1, &FormatOptions::default()).is_err());
+    }
+}
@@ -29,6 +29,7 @@ use std::str::Chars;
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::lexer::Cursor;
 /// let mut cursor = Cursor::new("cube");
 /// assert_eq!(cursor.advance(), Some('c'));
 /// assert_eq!(cursor.position().byte, 1);
@@ -56,6 +57,7 @@ impl<'a> Cursor<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Cursor;
     /// let cursor = Cursor::new("cube(10);");
     /// assert!(!cursor.is_eof());
     /// ```
@@ -78,6 +80,7 @@ impl<'a> Cursor<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Cursor;
     /// let cursor = Cursor::new("hello");
     /// let pos = cursor.position();
     /// assert_eq!(pos.byte, 0);
@@ -91,6 +94,7 @@ impl<'a> Cursor<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Cursor;
     /// let cursor = Cursor::new("");
     /// assert!(cursor.is_eof());
     /// ```
@@ -107,6 +111,7 @@ impl<'a> Cursor<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Cursor;
     /// let cursor = Cursor::new("abc");
     /// assert_eq!(cursor.peek(), Some('a'));
     /// assert_eq!(cursor.peek(), Some('a')); // Still 'a'
@@ -124,6 +129,7 @@ impl<'a> Cursor<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Cursor;
     /// let cursor = Cursor::new("ab");
     /// assert_eq!(cursor.peek_next(), Some('b'));
     /// ```
@@ -142,6 +148,7 @@ impl<'a> Cursor<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Cursor;
     /// let mut cursor = Cursor::new("ab");
     /// assert_eq!(cursor.advance(), Some('a'));
     /// assert_eq!(cursor.advance(), Some('b'));
@@ -175,6 +182,7 @@ impl<'a> Cursor<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Cursor;
     /// let mut cursor = Cursor::new("abc123");
     /// cursor.advance_while(|c| c.is_alphabetic());
     /// assert_eq!(cursor.peek(), Some('1'));
@@ -6,7 +6,7 @@
 //! ## Example
 //!
 //! ```rust
-//! use openscad_parser::lexer::Lexer;
+//! use openscad_parser::lexer::{Lexer, TokenKind};
 //!
 //! let tokens = Lexer::new("cube(10);").tokenize();
 //! assert_eq!(tokens[0].kind, TokenKind::Identifier);
@@ -31,6 +31,7 @@ use crate::span::{Position, Span};
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::lexer::{Lexer, TokenKind};
 /// let mut lexer = Lexer::new("cube(10);");
 /// let tokens = lexer.tokenize();
 /// ```
@@ -53,6 +54,7 @@ impl<'a> Lexer<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::{Lexer, TokenKind};
     /// let lexer = Lexer::new("cube(10);");
     /// ```
     pub fn new(source: &'a str) -> Self {
@@ -72,6 +74,7 @@ impl<'a> Lexer<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::{Lexer, TokenKind};
     /// let tokens = Lexer::new("cube(10);").tokenize();
     /// assert!(tokens.last().map(|t| t.kind == TokenKind::Eof).unwrap_or(false));
     /// ```
@@ -99,7 +102,7 @@ impl<'a> Lexer<'a> {
     fn skip_whitespace_and_comments(&mut self) {
         loop {
             // Skip whitespace
-            while self.cursor.peek().map_or(false, |c| c.is_whitespace()) {
+            while self.cursor.peek().is_some_and(|c| c.is_whitespace()) {
                 self.cursor.advance();
             }
 
@@ -107,7 +110,7 @@ impl<'a> Lexer<'a> {
             if self.cursor.peek() == Some('/') && self.cursor.peek_next() == Some('/') {
                 self.cursor.advance(); // /
                 self.cursor.advance(); // /
-                while self.cursor.peek().map_or(false, |c| c != '\n') {
+                while self.cursor.peek().is_some_and(|c| c != '\n') {
                     self.cursor.advance();
                 }
                 continue;
@@ -252,7 +255,7 @@ impl<'a> Lexer<'a> {
     }
 
     /// Scan a number literal.
-    fn scan_number(&mut self, start: Position, first_char: char) {
+    fn scan_number(&mut self, start: Position, _first_char: char) {
         let mut has_dot = false;
         let mut has_exponent = false;
 
@@ -311,6 +314,7 @@ impl<'a> Lexer<'a> {
             "if" => TokenKind::If,
             "else" => TokenKind::Else,
             "for" => TokenKind::For,
+            "intersection_for" => TokenKind::IntersectionFor,
             "let" => TokenKind::Let,
             "each" => TokenKind::Each,
             "include" => TokenKind::Include,
@@ -319,6 +323,41 @@ impl<'a> Lexer<'a> {
         };
 
         self.tokens.push(Token::new(kind, Span::new(start, end), text.to_string()));
+
+        if matches!(kind, TokenKind::Include | TokenKind::Use) {
+            self.scan_file_path();
+        }
+    }
+
+    /// Scan the `<path>` after `include`/`use`.
+    ///
+    /// Paths may contain characters that are not tokens (`.`, `-`, `/`), so
+    /// they are read as one raw token up to the closing `>` on the same line.
+    fn scan_file_path(&mut self) {
+        while self.cursor.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.cursor.advance();
+        }
+        if self.cursor.peek() != Some('<') {
+            return;
+        }
+
+        let start = self.cursor.position();
+        self.cursor.advance(); // <
+        let mut kind = TokenKind::Error;
+        while let Some(c) = self.cursor.peek() {
+            if c == '\n' {
+                break;
+            }
+            self.cursor.advance();
+            if c == '>' {
+                kind = TokenKind::FilePath;
+                break;
+            }
+        }
+
+        let end = self.cursor.position();
+        let text = &self.source[start.byte..end.byte];
+        self.tokens.push(Token::new(kind, Span::new(start, end), text.to_string()));
     }
 
     /// Scan a special variable ($fn, $fa, etc.).
@@ -401,6 +440,20 @@ mod tests {
         assert_eq!(tokens[0].text, "3.14");
     }
 
+    #[test]
+    fn test_tokenize_file_path() {
+        let tokens = Lexer::new("include <MCAD/nuts-and-bolts.scad>\nuse<lib.scad>").tokenize();
+        assert_eq!(tokens[0].kind, TokenKind::Include);
+        assert_eq!(tokens[1].kind, TokenKind::FilePath);
+        assert_eq!(tokens[1].text, "<MCAD/nuts-and-bolts.scad>");
+        assert_eq!(tokens[2].kind, TokenKind::Use);
+        assert_eq!(tokens[3].text, "<lib.scad>");
+
+        let unclosed = Lexer::new("include <lib.scad\ncube(1);").tokenize();
+        assert_eq!(unclosed[1].kind, TokenKind::Error);
+        assert_eq!(unclosed[2].text, "cube");
+    }
+
     #[test]
     fn test_tokenize_named_argument() {
         let tokens = Lexer::new("center=true").tokenize();
@@ -6,6 +6,7 @@
 //!
 //! ```rust
 //! use openscad_parser::lexer::{Token, TokenKind};
+//! use openscad_parser::Span;
 //!
 //! let token = Token::new(TokenKind::Number, Span::from_bytes(0, 2), "10".to_string());
 //! assert_eq!(token.kind, TokenKind::Number);
@@ -22,6 +23,8 @@ use crate::span::{Span, Spanned};
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::lexer::{Token, TokenKind};
+/// # use openscad_parser::Span;
 /// let token = Token::new(TokenKind::Identifier, Span::from_bytes(0, 4), "cube".to_string());
 /// assert_eq!(token.text, "cube");
 /// ```
@@ -102,6 +105,8 @@ pub enum TokenKind {
     Else,
     /// `for` keyword
     For,
+    /// `intersection_for` keyword
+    IntersectionFor,
     /// `let` keyword
     Let,
     /// `each` keyword
@@ -110,6 +115,8 @@ pub enum TokenKind {
     Include,
     /// `use` keyword
     Use,
+    /// `<path>` after `include`/`use`
+    FilePath,
 
     // Operators
     /// `+`
@@ -195,6 +202,7 @@ impl TokenKind {
                 | Self::If
                 | Self::Else
                 | Self::For
+                | Self::IntersectionFor
                 | Self::Let
                 | Self::Each
                 | Self::Include
@@ -245,10 +253,12 @@ impl TokenKind {
             Self::If => "if",
             Self::Else => "else",
             Self::For => "for",
+            Self::IntersectionFor => "intersection_for",
             Self::Let => "let",
             Self::Each => "each",
             Self::Include => "include",
             Self::Use => "use",
+            Self::FilePath => "file path",
             Self::Plus => "+",
             Self::Minus => "-",
             Self::Star => "*",
@@ -31,11 +31,17 @@ pub mod parser;
 pub mod cst;
 pub mod error;
 pub mod span;
+pub mod source_map;
+pub mod format;
 
 // Re-export public API
 pub use cst::{Cst, CstNode, NodeKind};
 pub use error::{ParseError, ParseErrorKind};
 pub use span::{Position, Span, Spanned};
+pub use source_map::SourceMap;
+
+/// Crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // =============================================================================
 // PUBLIC API
@@ -69,6 +75,7 @@ pub use span::{Position, Span, Spanned};
 /// Errors are collected in `cst.errors`. Check `cst.is_ok()` for success.
 ///
 /// ```rust
+/// # use openscad_parser::parse;
 /// let cst = parse("cube(;"); // Syntax error
 /// assert!(!cst.is_ok());
 /// println!("Errors: {:?}", cst.errors);
@@ -1,11 +1,12 @@
 //! # Collection Parsing
 //!
-//! Parses list and range expressions.
+//! Parses list, range and list comprehension expressions.
 //!
 //! ## Responsibilities
 //!
 //! - List literals: `[1, 2, 3]`
 //! - Range expressions: `[0:10]`, `[0:2:10]`
+//! - List comprehensions: `[for (i = [0:10]) if (i % 2 == 0) i * i]`
 //!
 //! ## Example
 //!
@@ -24,10 +25,13 @@ impl<'a> Parser<'a> {
     /// ## Grammar
     ///
     /// ```text
-    /// list = "[" (expression ("," expression)*)? "]"
+    /// list = "[" (element ("," element)*)? "]"
     /// range = "[" expression ":" expression (":" expression)? "]"
     /// ```
     ///
+    /// A list containing at least one generator element (`for`, `if`,
+    /// `each`, `let`) is produced as a `ListComprehension` node.
+    ///
     /// ## Example
     ///
     /// ```text
@@ -36,6 +40,7 @@ impl<'a> Parser<'a> {
     /// [1, 2, 3,]          // list with trailing comma
     /// [0:10]              // range (start:end)
     /// [0:2:10]            // range (start:step:end)
+    /// [for (i = [0:3]) i] // list comprehension
     /// ```
     pub(super) fn parse_list_or_range(&mut self) -> Result<CstNode, ParseError> {
         let start = self.current_position();
@@ -48,10 +53,10 @@ impl<'a> Parser<'a> {
         }
 
         // First element
-        let first = self.parse_expression()?;
+        let first = self.parse_list_element()?;
 
         // Check for range syntax
-        if self.check(TokenKind::Colon) {
+        if self.check(TokenKind::Colon) && !is_comprehension_clause(first.kind) {
             return self.parse_range(start, first);
         }
 
@@ -64,7 +69,7 @@ impl<'a> Parser<'a> {
     /// ## Grammar
     ///
     /// ```text
-    /// list = "[" expression ("," expression)* ","? "]"
+    /// list = "[" element ("," element)* ","? "]"
     /// ```
     fn parse_list(&mut self, start: crate::span::Position, first: CstNode) -> Result<CstNode, ParseError> {
         let mut elements = vec![first];
@@ -74,11 +79,85 @@ impl<'a> Parser<'a> {
             if self.check(TokenKind::RBracket) {
                 break;
             }
-            elements.push(self.parse_expression()?);
+            elements.push(self.parse_list_element()?);
         }
 
         self.expect(TokenKind::RBracket)?;
-        Ok(CstNode::with_children(NodeKind::List, self.span_from(start), elements))
+
+        let kind = if elements.iter().any(|e| is_comprehension_clause(e.kind)) {
+            NodeKind::ListComprehension
+        } else {
+            NodeKind::List
+        };
+        Ok(CstNode::with_children(kind, self.span_from(start), elements))
+    }
+
+    /// Parse a single list element, which may be a comprehension clause.
+    ///
+    /// ## Grammar
+    ///
+    /// ```text
+    /// element = "for" "(" for_assignments ")" element
+    ///         | "if" "(" expression ")" element ("else" element)?
+    ///         | "each" element
+    ///         | "let" "(" let_assignments ")" element
+    ///         | expression
+    /// ```
+    ///
+    /// ## Example
+    ///
+    /// ```text
+    /// for (x = [0:2], y = [0:2]) [x, y]
+    /// if (i > 0) i else -i
+    /// each [1, 2, 3]
+    /// let (j = i * 2) j
+    /// ```
+    fn parse_list_element(&mut self) -> Result<CstNode, ParseError> {
+        let start = self.current_position();
+
+        match self.peek().kind {
+            TokenKind::For => {
+                self.advance();
+                self.expect(TokenKind::LParen)?;
+                let assignments = self.parse_for_assignments()?;
+                self.expect(TokenKind::RParen)?;
+                let body = self.parse_list_element()?;
+                Ok(CstNode::with_children(
+                    NodeKind::ComprehensionFor,
+                    self.span_from(start),
+                    vec![assignments, body],
+                ))
+            }
+            TokenKind::If => {
+                self.advance();
+                self.expect(TokenKind::LParen)?;
+                let condition = self.parse_expression()?;
+                self.expect(TokenKind::RParen)?;
+                let mut children = vec![condition, self.parse_list_element()?];
+                if self.match_token(TokenKind::Else) {
+                    children.push(self.parse_list_element()?);
+                }
+                Ok(CstNode::with_children(NodeKind::ComprehensionIf, self.span_from(start), children))
+            }
+            TokenKind::Each => {
+                self.advance();
+                let body = self.parse_list_element()?;
+                Ok(CstNode::with_children(NodeKind::ComprehensionEach, self.span_from(start), vec![body]))
+            }
+            TokenKind::Let => {
+                self.advance();
+                self.expect(TokenKind::LParen)?;
+                let assignments = self.parse_let_assignments()?;
+                self.expect(TokenKind::RParen)?;
+                let body = self.parse_list_element()?;
+                Ok(CstNode::with_children(
+                    NodeKind::ComprehensionLet,
+                    self.span_from(start),
+                    vec![assignments, body],
+                ))
+            }
+            _ => self.parse_expression(),
+        }
     }
 
     /// Parse range.
@@ -115,6 +194,17 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// Check whether a list element is a comprehension clause.
+fn is_comprehension_clause(kind: NodeKind) -> bool {
+    matches!(
+        kind,
+        NodeKind::ComprehensionFor
+            | NodeKind::ComprehensionIf
+            | NodeKind::ComprehensionEach
+            | NodeKind::ComprehensionLet
+    )
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -197,4 +287,38 @@ mod tests {
             assert_eq!(child.kind, NodeKind::BinaryExpression);
         }
     }
+
+    #[test]
+    fn test_parse_list_comprehension() {
+        let expr = parse_expr("[for (i = [0:10]) i * i]");
+        assert_eq!(expr.kind, NodeKind::ListComprehension);
+        assert_eq!(expr.children.len(), 1);
+
+        let clause = &expr.children[0];
+        assert_eq!(clause.kind, NodeKind::ComprehensionFor);
+        assert_eq!(clause.children[0].kind, NodeKind::ForAssignments);
+        assert_eq!(clause.children[1].kind, NodeKind::BinaryExpression);
+    }
+
+    #[test]
+    fn test_parse_comprehension_if_else() {
+        let expr = parse_expr("[for (i = v) if (i > 0) i else -i]");
+        let clause = &expr.children[0].children[1];
+        assert_eq!(clause.kind, NodeKind::ComprehensionIf);
+        // condition + then + else
+        assert_eq!(clause.children.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_comprehension_each_and_let() {
+        let expr = parse_expr("[0, each [1, 2], for (x = [0:1], y = [0:1]) let (s = x + y) s]");
+        assert_eq!(expr.kind, NodeKind::ListComprehension);
+        assert_eq!(expr.children.len(), 3);
+        assert_eq!(expr.children[0].kind, NodeKind::Number);
+        assert_eq!(expr.children[1].kind, NodeKind::ComprehensionEach);
+
+        let for_clause = &expr.children[2];
+        assert_eq!(for_clause.children[0].children.len(), 2);
+        assert_eq!(for_clause.children[1].kind, NodeKind::ComprehensionLet);
+    }
 }
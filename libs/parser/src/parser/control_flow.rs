@@ -5,6 +5,7 @@
 //! ## Responsibilities
 //!
 //! - For loops: `for (i = [0:10]) { ... }`
+//! - Intersection loops: `intersection_for (i = [0:2]) { ... }`
 //! - If/else: `if (x > 0) { ... } else { ... }`
 //! - Let blocks: `let (x = 10) { ... }`
 //! - Blocks: `{ ... }`
@@ -53,12 +54,13 @@ impl<'a> Parser<'a> {
         Ok(CstNode::with_children(NodeKind::Block, self.span_from(start), children))
     }
 
-    /// Parse for loop block.
+    /// Parse for loop block, or an `intersection_for` loop with the same
+    /// shape.
     ///
     /// ## Grammar
     ///
     /// ```text
-    /// for_block = "for" "(" for_assignments ")" statement
+    /// for_block = ("for" | "intersection_for") "(" for_assignments ")" statement
     /// for_assignments = for_assignment ("," for_assignment)*
     /// for_assignment = identifier "=" expression
     /// ```
@@ -68,10 +70,12 @@ impl<'a> Parser<'a> {
     /// ```text
     /// for (i = [0:10]) cube(i);
     /// for (i = [0:10], j = [0:5]) translate([i, j, 0]) cube(1);
+    /// intersection_for (a = [0, 60]) rotate(a) cube([10, 2, 2], center = true);
     /// ```
     pub(super) fn parse_for_block(&mut self) -> Result<CstNode, ParseError> {
         let start = self.current_position();
-        self.advance(); // for
+        let kind = if self.check(TokenKind::IntersectionFor) { NodeKind::IntersectionForBlock } else { NodeKind::ForBlock };
+        self.advance(); // for / intersection_for
         self.expect(TokenKind::LParen)?;
         
         // Parse for assignments
@@ -80,7 +84,7 @@ impl<'a> Parser<'a> {
         self.expect(TokenKind::RParen)?;
         let body = self.parse_statement()?;
         
-        Ok(CstNode::with_children(NodeKind::ForBlock, self.span_from(start), vec![assignments, body]))
+        Ok(CstNode::with_children(kind, self.span_from(start), vec![assignments, body]))
     }
 
     /// Parse for loop assignments.
@@ -90,7 +94,7 @@ impl<'a> Parser<'a> {
     /// ```text
     /// for_assignments = for_assignment ("," for_assignment)*
     /// ```
-    fn parse_for_assignments(&mut self) -> Result<CstNode, ParseError> {
+    pub(super) fn parse_for_assignments(&mut self) -> Result<CstNode, ParseError> {
         let start = self.current_position();
         let mut children = Vec::new();
 
@@ -105,6 +109,30 @@ impl<'a> Parser<'a> {
         Ok(CstNode::with_children(NodeKind::ForAssignments, self.span_from(start), children))
     }
 
+    /// Parse let assignments.
+    ///
+    /// Unlike for assignments, the list may be empty and may end with a
+    /// trailing comma.
+    ///
+    /// ## Grammar
+    ///
+    /// ```text
+    /// let_assignments = (for_assignment ("," for_assignment)* ","?)?
+    /// ```
+    pub(super) fn parse_let_assignments(&mut self) -> Result<CstNode, ParseError> {
+        let start = self.current_position();
+        let mut children = Vec::new();
+
+        while !self.check(TokenKind::RParen) && !self.is_at_end() {
+            children.push(self.parse_for_assignment()?);
+            if !self.match_token(TokenKind::Comma) {
+                break;
+            }
+        }
+
+        Ok(CstNode::with_children(NodeKind::ForAssignments, self.span_from(start), children))
+    }
+
     /// Parse single for assignment: identifier = expression
     ///
     /// ## Grammar
@@ -169,7 +197,7 @@ impl<'a> Parser<'a> {
     /// ## Grammar
     ///
     /// ```text
-    /// let_block = "let" "(" assignments ")" statement
+    /// let_block = "let" "(" let_assignments ")" statement
     /// ```
     ///
     /// ## Example
@@ -183,15 +211,12 @@ impl<'a> Parser<'a> {
         self.advance(); // let
         self.expect(TokenKind::LParen)?;
         
-        // TODO: Parse let assignments properly
-        while !self.check(TokenKind::RParen) && !self.is_at_end() {
-            self.advance();
-        }
+        let assignments = self.parse_let_assignments()?;
         
         self.expect(TokenKind::RParen)?;
         let body = self.parse_statement()?;
         
-        Ok(CstNode::with_children(NodeKind::LetBlock, self.span_from(start), vec![body]))
+        Ok(CstNode::with_children(NodeKind::LetBlock, self.span_from(start), vec![assignments, body]))
     }
 }
 
@@ -244,6 +269,17 @@ mod tests {
         assert_eq!(assignments.children.len(), 2);
     }
 
+    #[test]
+    fn test_parse_intersection_for() {
+        let cst = parse("intersection_for (a = [0, 60]) { rotate(a) cube(1); }");
+        assert!(cst.errors.is_empty(), "Errors: {:?}", cst.errors);
+
+        let block = &cst.root.children[0];
+        assert_eq!(block.kind, NodeKind::IntersectionForBlock);
+        assert_eq!(block.children[0].kind, NodeKind::ForAssignments);
+        assert_eq!(block.children[1].kind, NodeKind::Block);
+    }
+
     #[test]
     fn test_parse_if() {
         let cst = parse("if (true) cube(10);");
@@ -280,4 +316,27 @@ mod tests {
         let inner_if = &outer_if.children[1];
         assert_eq!(inner_if.kind, NodeKind::IfBlock);
     }
+
+    #[test]
+    fn test_parse_let_block() {
+        let cst = parse("let (x = 10, y = x * 2) cube(y);");
+        assert!(cst.errors.is_empty(), "Errors: {:?}", cst.errors);
+        
+        let let_block = &cst.root.children[0];
+        assert_eq!(let_block.kind, NodeKind::LetBlock);
+        
+        // ForAssignments + body
+        assert_eq!(let_block.children.len(), 2);
+        assert_eq!(let_block.children[0].kind, NodeKind::ForAssignments);
+        assert_eq!(let_block.children[0].children.len(), 2);
+        assert_eq!(let_block.children[1].kind, NodeKind::ModuleCall);
+    }
+
+    #[test]
+    fn test_parse_let_block_empty_and_trailing_comma() {
+        let cst = parse("let () cube(1); let (a = 1,) cube(a);");
+        assert!(cst.errors.is_empty(), "Errors: {:?}", cst.errors);
+        assert_eq!(cst.root.children[0].children[0].children.len(), 0);
+        assert_eq!(cst.root.children[1].children[0].children.len(), 1);
+    }
 }
@@ -228,19 +228,7 @@ impl<'a> Parser<'a> {
     /// include <MCAD/boxes.scad>
     /// ```
     pub(super) fn parse_include_statement(&mut self) -> Result<CstNode, ParseError> {
-        let start = self.current_position();
-        self.advance(); // include
-        
-        // TODO: Parse path properly
-        while !self.check(TokenKind::Semicolon) && !self.is_at_end() {
-            self.advance();
-        }
-        
-        if self.check(TokenKind::Semicolon) {
-            self.advance();
-        }
-        
-        Ok(CstNode::new(NodeKind::IncludeStatement, self.span_from(start)))
+        self.parse_file_statement(NodeKind::IncludeStatement)
     }
 
     /// Parse use statement.
@@ -257,19 +245,24 @@ impl<'a> Parser<'a> {
     /// use <MCAD/boxes.scad>
     /// ```
     pub(super) fn parse_use_statement(&mut self) -> Result<CstNode, ParseError> {
+        self.parse_file_statement(NodeKind::UseStatement)
+    }
+
+    /// Parse `include`/`use` followed by its `<path>` token.
+    ///
+    /// No semicolon is required; a trailing one parses as an empty statement.
+    fn parse_file_statement(&mut self, kind: NodeKind) -> Result<CstNode, ParseError> {
         let start = self.current_position();
-        self.advance(); // use
-        
-        // TODO: Parse path properly
-        while !self.check(TokenKind::Semicolon) && !self.is_at_end() {
-            self.advance();
-        }
-        
-        if self.check(TokenKind::Semicolon) {
-            self.advance();
-        }
-        
-        Ok(CstNode::new(NodeKind::UseStatement, self.span_from(start)))
+        self.advance(); // include / use
+
+        let path = self.expect(TokenKind::FilePath)?.clone();
+        let text = path.text.trim_start_matches('<').trim_end_matches('>').trim();
+
+        Ok(CstNode::with_children(
+            kind,
+            self.span_from(start),
+            vec![CstNode::with_text(NodeKind::FilePath, path.span, text)],
+        ))
     }
 }
 
@@ -427,4 +420,27 @@ mod tests {
         let body = outer.find_child(NodeKind::Block).expect("Should have body");
         assert_eq!(body.children.len(), 2, "Should have inner module and call");
     }
+
+    #[test]
+    fn test_parse_include_and_use() {
+        let cst = parse("include <MCAD/boxes.scad>\nuse <lib.scad>;\ncube(1);");
+        assert!(cst.errors.is_empty(), "Errors: {:?}", cst.errors);
+
+        let include = &cst.root.children[0];
+        assert_eq!(include.kind, NodeKind::IncludeStatement);
+        assert_eq!(include.find_child(NodeKind::FilePath).unwrap().text_or_empty(), "MCAD/boxes.scad");
+
+        let use_stmt = &cst.root.children[1];
+        assert_eq!(use_stmt.kind, NodeKind::UseStatement);
+        assert_eq!(use_stmt.find_child(NodeKind::FilePath).unwrap().text_or_empty(), "lib.scad");
+
+        // The statement after the path is not swallowed
+        assert_eq!(cst.root.children.last().unwrap().kind, NodeKind::ModuleCall);
+    }
+
+    #[test]
+    fn test_parse_include_missing_path() {
+        let cst = parse("include cube(1);");
+        assert!(!cst.errors.is_empty());
+    }
 }
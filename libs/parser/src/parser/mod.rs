@@ -53,6 +53,8 @@ use crate::span::{Position, Span};
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::lexer::Lexer;
+/// # use openscad_parser::parser::Parser;
 /// let tokens = Lexer::new("cube(10);").tokenize();
 /// let mut parser = Parser::new("cube(10);", tokens);
 /// let cst = parser.parse();
@@ -60,6 +62,7 @@ use crate::span::{Position, Span};
 /// ```
 pub struct Parser<'a> {
     /// Source text (for error messages).
+    #[allow(dead_code)]
     source: &'a str,
     /// Token stream.
     tokens: Vec<Token>,
@@ -80,6 +83,8 @@ impl<'a> Parser<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Lexer;
+    /// # use openscad_parser::parser::Parser;
     /// let tokens = Lexer::new("cube(10);").tokenize();
     /// let parser = Parser::new("cube(10);", tokens);
     /// ```
@@ -101,6 +106,9 @@ impl<'a> Parser<'a> {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::lexer::Lexer;
+    /// # use openscad_parser::parser::Parser;
+    /// # let mut parser = Parser::new("cube(10);", Lexer::new("cube(10);").tokenize());
     /// let cst = parser.parse();
     /// if cst.errors.is_empty() {
     ///     println!("Parsed successfully!");
@@ -236,6 +244,7 @@ impl<'a> Parser<'a> {
                 TokenKind::Module
                 | TokenKind::Function
                 | TokenKind::For
+                | TokenKind::IntersectionFor
                 | TokenKind::If
                 | TokenKind::Let
                 | TokenKind::Include
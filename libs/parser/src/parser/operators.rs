@@ -37,6 +37,7 @@ use crate::lexer::TokenKind;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub(super) enum Precedence {
     /// No precedence (sentinel value)
+    #[allow(dead_code)]
     None = 0,
     /// Ternary: `?:`
     Ternary = 1,
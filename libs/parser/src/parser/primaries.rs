@@ -11,6 +11,7 @@
 //! - Identifiers: `x`, `myVar`
 //! - Special variables: `$fn`, `$fa`, `$fs`
 //! - Parenthesized expressions: `(1 + 2)`
+//! - Let expressions: `let (x = 1) x + 1`
 //!
 //! ## Example
 //!
@@ -31,6 +32,7 @@ impl<'a> Parser<'a> {
     /// ```text
     /// primary = number | string | boolean | undef | identifier
     ///         | special_variable | list | range | "(" expression ")"
+    ///         | "let" "(" let_assignments ")" expression
     /// ```
     ///
     /// ## Example
@@ -44,6 +46,7 @@ impl<'a> Parser<'a> {
     /// $fn
     /// [1, 2, 3]
     /// (1 + 2)
+    /// let (r = 5) r * 2
     /// ```
     pub(super) fn parse_primary(&mut self) -> Result<CstNode, ParseError> {
         let token = self.peek().clone();
@@ -103,6 +106,20 @@ impl<'a> Parser<'a> {
                 Ok(expr)
             }
 
+            // Let expression: let (a = 1, ...) body
+            TokenKind::Let => {
+                self.advance();
+                self.expect(TokenKind::LParen)?;
+                let assignments = self.parse_let_assignments()?;
+                self.expect(TokenKind::RParen)?;
+                let body = self.parse_expression()?;
+                Ok(CstNode::with_children(
+                    NodeKind::LetExpression,
+                    self.span_from(start),
+                    vec![assignments, body],
+                ))
+            }
+
             _ => Err(ParseError::unexpected_token(
                 &token.text,
                 "expression",
@@ -190,4 +207,14 @@ mod tests {
         // Should return the inner expression, not a wrapper
         assert_eq!(expr.kind, NodeKind::BinaryExpression);
     }
+
+    #[test]
+    fn test_parse_let_expression() {
+        let expr = parse_expr("let (a = 1, b = a + 1) a * b");
+        assert_eq!(expr.kind, NodeKind::LetExpression);
+        assert_eq!(expr.children[0].kind, NodeKind::ForAssignments);
+        assert_eq!(expr.children[0].children.len(), 2);
+        // Body extends over the whole trailing expression
+        assert_eq!(expr.children[1].kind, NodeKind::BinaryExpression);
+    }
 }
@@ -56,7 +56,7 @@ impl<'a> Parser<'a> {
             TokenKind::Function => self.parse_function_declaration(),
 
             // Control flow
-            TokenKind::For => self.parse_for_block(),
+            TokenKind::For | TokenKind::IntersectionFor => self.parse_for_block(),
             TokenKind::If => self.parse_if_block(),
             TokenKind::Let => self.parse_let_block(),
 
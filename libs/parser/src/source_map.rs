@@ -0,0 +1,217 @@
+//! # Source Map
+//!
+//! Maps locations in a source file to the same locations after the file
+//! has been reformatted, so diagnostics and cursors survive formatting.
+//!
+//! ## How It Works
+//!
+//! Formatting only rewrites the trivia between tokens (whitespace and
+//! comments), so the old and new texts lex to the same token sequence.
+//! Pairing the tokens up gives exact anchors; an offset inside a token
+//! keeps its distance from the token start, and an offset in the trivia
+//! after a token keeps its distance from the token end, clamped to the new
+//! trivia.
+//!
+//! Trivia runs whose text changed are reported by [`SourceMap::changes`],
+//! which is what a `--check` mode prints.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_parser::source_map::SourceMap;
+//!
+//! let map = SourceMap::between("cube( 10 ) ;", "cube(10);").unwrap();
+//! assert_eq!(map.map_offset(6), 5); // `10` moves one byte left
+//! assert_eq!(map.changes().len(), 3);
+//! ```
+
+use std::ops::Range;
+
+use crate::lexer::Lexer;
+use crate::span::{Position, Span};
+
+// =============================================================================
+// SOURCE MAP
+// =============================================================================
+
+/// Old→new location mapping between a file and its formatted text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMap {
+    /// Paired token byte ranges (old, new), in source order. Starts with an
+    /// empty anchor at offset 0 and ends with the EOF token.
+    anchors: Vec<(Range<usize>, Range<usize>)>,
+    /// Byte offsets of line starts in the old text.
+    old_lines: Vec<usize>,
+    /// Byte offsets of line starts in the new text.
+    new_lines: Vec<usize>,
+    /// Trivia runs whose text differs.
+    changes: Vec<Change>,
+}
+
+/// One rewritten trivia run between two tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    /// Location in the old text.
+    pub old: Span,
+    /// Location in the new text.
+    pub new: Span,
+}
+
+impl SourceMap {
+    /// Build the map between `old` and its reformatted text `new`.
+    ///
+    /// ## Returns
+    ///
+    /// `None` if the two texts do not lex to the same tokens, i.e. the
+    /// rewrite changed more than trivia.
+    pub fn between(old: &str, new: &str) -> Option<Self> {
+        let old_tokens = Lexer::new(old).tokenize();
+        let new_tokens = Lexer::new(new).tokenize();
+        if old_tokens.len() != new_tokens.len() {
+            return None;
+        }
+
+        let mut anchors = vec![(0..0, 0..0)];
+        for (a, b) in old_tokens.iter().zip(&new_tokens) {
+            if a.kind != b.kind || a.text != b.text {
+                return None;
+            }
+            anchors.push((a.span.start.byte..a.span.end.byte, b.span.start.byte..b.span.end.byte));
+        }
+
+        let old_lines = line_starts(old);
+        let new_lines = line_starts(new);
+        let changes = anchors.windows(2)
+            .filter_map(|pair| {
+                let old_gap = pair[0].0.end..pair[1].0.start;
+                let new_gap = pair[0].1.end..pair[1].1.start;
+                (old[old_gap.clone()] != new[new_gap.clone()]).then(|| Change {
+                    old: span_at(&old_lines, old_gap),
+                    new: span_at(&new_lines, new_gap),
+                })
+            })
+            .collect();
+
+        Some(Self { anchors, old_lines, new_lines, changes })
+    }
+
+    /// Map a byte offset in the old text to the new text.
+    pub fn map_offset(&self, offset: usize) -> usize {
+        // Last anchor starting at or before the offset (the first always does)
+        let index = self.anchors.partition_point(|(old, _)| old.start <= offset).saturating_sub(1);
+        let (old, new) = &self.anchors[index];
+
+        if offset < old.end {
+            return new.start + (offset - old.start);
+        }
+        let new_gap_end = self.anchors.get(index + 1).map_or(new.end, |(_, next)| next.start);
+        (new.end + (offset - old.end)).min(new_gap_end)
+    }
+
+    /// Map a position in the old text to the new text.
+    pub fn map_position(&self, position: Position) -> Position {
+        position_at(&self.new_lines, self.map_offset(position.byte))
+    }
+
+    /// Map a span in the old text to the new text.
+    pub fn map_span(&self, span: Span) -> Span {
+        Span::new(self.map_position(span.start), self.map_position(span.end))
+    }
+
+    /// Trivia runs the rewrite changed, in source order.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Check whether the rewrite changed nothing.
+    pub fn is_identity(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Position of a byte offset in the old text.
+    pub fn old_position(&self, offset: usize) -> Position {
+        position_at(&self.old_lines, offset)
+    }
+}
+
+// =============================================================================
+// LINE HELPERS
+// =============================================================================
+
+/// Byte offsets where each line starts.
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Position of a byte offset given the line starts.
+fn position_at(lines: &[usize], byte: usize) -> Position {
+    let line = lines.partition_point(|&start| start <= byte).saturating_sub(1);
+    Position::new(byte, line, byte - lines[line])
+}
+
+/// Span of a byte range given the line starts.
+fn span_at(lines: &[usize], range: Range<usize>) -> Span {
+    Span::new(position_at(lines, range.start), position_at(lines, range.end))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test offsets inside tokens and trivia map to the same spot.
+    #[test]
+    fn test_map_offset() {
+        let old = "cube( 10 ) ;";
+        let new = "cube(10);";
+        let map = SourceMap::between(old, new).unwrap();
+        assert_eq!(map.map_offset(0), 0);
+        assert_eq!(map.map_offset(2), 2);
+        assert_eq!(map.map_offset(7), 6); // second digit of `10`
+        assert_eq!(map.map_offset(9), 7); // `)`
+        assert_eq!(map.map_offset(8), 7); // removed space clamps to `)`
+        assert_eq!(map.map_offset(old.len()), new.len());
+    }
+
+    /// Test positions get lines and columns in the new text.
+    #[test]
+    fn test_map_span_across_lines() {
+        let old = "union(){cube(1);sphere(2);}";
+        let new = "union() {\n    cube(1);\n    sphere(2);\n}\n";
+        let map = SourceMap::between(old, new).unwrap();
+
+        let sphere = old.find("sphere").unwrap();
+        let span = map.map_span(Span::from_bytes(sphere, sphere + 6));
+        assert_eq!(span.start.line, 2);
+        assert_eq!(span.start.column, 4);
+        assert_eq!(&new[span.start.byte..span.end.byte], "sphere");
+    }
+
+    /// Test changes cover exactly the rewritten trivia.
+    #[test]
+    fn test_changes() {
+        let old = "a = 1;\n// keep\nb=2;\n";
+        let new = "a = 1;\n// keep\nb = 2;\n";
+        let map = SourceMap::between(old, new).unwrap();
+        let changes = map.changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].old.start.line, 2);
+        assert_eq!(changes[0].old.start.column, 1);
+        assert!(changes[0].old.is_empty());
+        assert_eq!(changes[0].new.len(), 1);
+
+        assert!(SourceMap::between(old, old).unwrap().is_identity());
+    }
+
+    /// Test rewrites that change tokens are rejected.
+    #[test]
+    fn test_token_change_rejected() {
+        assert!(SourceMap::between("cube(10);", "cube(11);").is_none());
+        assert!(SourceMap::between("cube(10);", "cube(10);;").is_none());
+    }
+}
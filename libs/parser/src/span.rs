@@ -28,10 +28,12 @@ use serde::{Deserialize, Serialize};
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::span::{Position, Span};
 /// let pos = Position::new(0, 0, 0); // Start of file
 /// let pos2 = Position::new(10, 0, 10); // Byte 10, line 0, column 10
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct Position {
     /// Byte offset in source (0-indexed).
     pub byte: usize,
@@ -53,6 +55,7 @@ impl Position {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::span::{Position, Span};
     /// let pos = Position::new(5, 0, 5);
     /// assert_eq!(pos.byte, 5);
     /// ```
@@ -65,6 +68,7 @@ impl Position {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::span::{Position, Span};
     /// let pos = Position::zero();
     /// assert_eq!(pos.byte, 0);
     /// assert_eq!(pos.line, 0);
@@ -84,6 +88,7 @@ impl Position {
 /// ## Example
 ///
 /// ```rust
+/// # use openscad_parser::span::{Position, Span};
 /// let span = Span::new(
 ///     Position::new(0, 0, 0),
 ///     Position::new(10, 0, 10),
@@ -91,6 +96,7 @@ impl Position {
 /// assert_eq!(span.len(), 10);
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct Span {
     /// Start position (inclusive).
     pub start: Position,
@@ -109,6 +115,7 @@ impl Span {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::span::{Position, Span};
     /// let span = Span::new(Position::zero(), Position::new(5, 0, 5));
     /// ```
     pub const fn new(start: Position, end: Position) -> Self {
@@ -125,6 +132,7 @@ impl Span {
     /// ## Example
     ///
     /// ```rust
+    /// # use openscad_parser::span::{Position, Span};
     /// let span = Span::new(Position::new(0, 0, 0), Position::new(10, 0, 10));
     /// assert_eq!(span.len(), 10);
     /// ```
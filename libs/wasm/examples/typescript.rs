@@ -0,0 +1,9 @@
+//! Print the TypeScript declarations of the types the WASM API exchanges.
+//!
+//! ```bash
+//! cargo run -p openscad-wasm --example typescript > types.d.ts
+//! ```
+
+fn main() {
+    print!("{}", openscad_wasm::typescript::declarations());
+}
@@ -0,0 +1,203 @@
+#!/usr/bin/env node
+/**
+ * # npm Package Build
+ *
+ * Builds `libs/wasm` into a package web users can `npm install` instead of
+ * wiring up wasm-bindgen output by hand.
+ *
+ * ## Output
+ *
+ * ```text
+ * libs/wasm/npm/dist/
+ *   package.json    ← npm/package.json with the crate version
+ *   index.mjs       ← ESM entry: default export init(), every binding
+ *   index.cjs       ← CommonJS entry: load() resolves to the ESM module
+ *   index.d.ts      ← types for the ESM entry
+ *   index.d.cts     ← types for the CommonJS entry
+ *   pkg/            ← wasm-pack output (--target web)
+ * ```
+ *
+ * wasm-bindgen types options and results as `any`; the exported functions
+ * name the types generated from Rust by `ts-rs` instead, and those
+ * declarations are appended to `pkg/openscad_wasm.d.ts` (see
+ * `src/typescript.rs`).
+ *
+ * With `--inline` the `.wasm` is embedded in the glue as base64, so the
+ * package is plain JavaScript: no asset to serve, no bundler plugin, and
+ * it loads in Node without a `fetch` of a `file:` URL.
+ *
+ * ## Usage
+ *
+ * ```bash
+ * node libs/wasm/npm/build.mjs                 # wasm fetched next to the glue
+ * node libs/wasm/npm/build.mjs --inline        # wasm inlined as base64
+ * node libs/wasm/npm/build.mjs --pkg <dir>     # reuse a wasm-pack --target web build
+ * cd libs/wasm/npm/dist && npm pack
+ * ```
+ */
+
+import { execFileSync } from 'node:child_process';
+import { copyFileSync, cpSync, existsSync, mkdirSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
+import { dirname, join, resolve } from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+// =============================================================================
+// PATHS
+// =============================================================================
+
+const NPM_DIR = dirname(fileURLToPath(import.meta.url));
+const CRATE_DIR = resolve(NPM_DIR, '..');
+const ROOT_DIR = resolve(CRATE_DIR, '../..');
+const DIST_DIR = join(NPM_DIR, 'dist');
+const PKG_DIR = join(DIST_DIR, 'pkg');
+
+/** Base name wasm-pack gives the glue, its types and the binary. */
+const OUT_NAME = 'openscad_wasm';
+
+/** Default wasm location in the wasm-bindgen glue, replaced by `--inline`. */
+const WASM_URL = `new URL('${OUT_NAME}_bg.wasm', import.meta.url)`;
+
+// =============================================================================
+// STEPS
+// =============================================================================
+
+/** Parse `--inline` and `--pkg <dir>`. */
+function parseArgs(argv) {
+  const args = { inline: false, pkg: null };
+  for (let i = 0; i < argv.length; i++) {
+    if (argv[i] === '--inline') {
+      args.inline = true;
+    } else if (argv[i] === '--pkg' && i + 1 < argv.length) {
+      args.pkg = resolve(argv[++i]);
+    } else {
+      throw new Error(`Unknown argument: ${argv[i]}`);
+    }
+  }
+  return args;
+}
+
+/** Build the wasm with threads (see .agent/workflows/build-wasm-rayon.md). */
+function buildWasm() {
+  execFileSync(
+    'wasm-pack',
+    ['build', CRATE_DIR, '--release', '--target', 'web', '--out-dir', PKG_DIR, '--out-name', OUT_NAME,
+      '--features', 'wasm-threads', '--', '-Z', 'build-std=std,panic_abort'],
+    {
+      stdio: 'inherit',
+      env: { ...process.env, RUSTFLAGS: '-C target-feature=+atomics,+bulk-memory,+mutable-globals' },
+    },
+  );
+  // wasm-pack writes its own package metadata; ours is the one at the root
+  for (const file of ['package.json', 'README.md', '.gitignore']) {
+    rmSync(join(PKG_DIR, file), { force: true });
+  }
+}
+
+/** TypeScript declarations generated from the Rust types. */
+function generateTypes() {
+  return execFileSync('cargo', ['run', '--quiet', '-p', 'openscad-wasm', '--example', 'typescript'], {
+    cwd: ROOT_DIR,
+    encoding: 'utf8',
+    stdio: ['ignore', 'pipe', 'inherit'],
+  });
+}
+
+/** Embed the wasm binary in the glue and drop the separate file. */
+function inlineWasm() {
+  const gluePath = join(PKG_DIR, `${OUT_NAME}.js`);
+  const wasmPath = join(PKG_DIR, `${OUT_NAME}_bg.wasm`);
+  const glue = readFileSync(gluePath, 'utf8');
+  if (!glue.includes(WASM_URL)) {
+    throw new Error(`${gluePath} does not load the wasm from ${WASM_URL}; wasm-bindgen output changed`);
+  }
+
+  const base64 = readFileSync(wasmPath).toString('base64');
+  const decoder = `
+/** The wasm binary, inlined by libs/wasm/npm/build.mjs --inline. */
+function __inlineWasm() {
+    const base64 = '${base64}';
+    if (typeof Buffer === 'function') {
+        return Buffer.from(base64, 'base64');
+    }
+    const binary = atob(base64);
+    const bytes = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) {
+        bytes[i] = binary.charCodeAt(i);
+    }
+    return bytes;
+}
+`;
+  writeFileSync(gluePath, glue.replace(WASM_URL, '__inlineWasm()') + decoder);
+  rmSync(wasmPath);
+}
+
+/** Write the entry points and package.json. */
+function writeEntries(types) {
+  const dts = join(PKG_DIR, `${OUT_NAME}.d.ts`);
+  writeFileSync(dts, `${readFileSync(dts, 'utf8')}\n${types}`);
+
+  writeFileSync(join(DIST_DIR, 'index.mjs'), `export * from './pkg/${OUT_NAME}.js';
+export { default } from './pkg/${OUT_NAME}.js';
+`);
+  writeFileSync(join(DIST_DIR, 'index.d.ts'), `export * from './pkg/${OUT_NAME}.js';
+export { default } from './pkg/${OUT_NAME}.js';
+`);
+
+  // wasm-bindgen only emits ES modules, so CommonJS loads them with import()
+  writeFileSync(join(DIST_DIR, 'index.cjs'), `'use strict';
+
+let loaded;
+
+/**
+ * Load and initialize the module once.
+ *
+ * @param {unknown} [options] Passed to the ESM default export \`init\`.
+ * @returns {Promise<typeof import('./index.mjs')>} The initialized module.
+ */
+exports.load = function load(options) {
+  loaded ??= import('./index.mjs').then(async (wasm) => {
+    await wasm.default(options);
+    return wasm;
+  });
+  return loaded;
+};
+`);
+  writeFileSync(join(DIST_DIR, 'index.d.cts'), `import type * as OpenScad from './index.js';
+
+/** Load and initialize the module once; resolves to the ESM module. */
+export declare function load(options?: Parameters<typeof OpenScad.default>[0]): Promise<typeof OpenScad>;
+`);
+
+  const cargo = readFileSync(join(CRATE_DIR, 'Cargo.toml'), 'utf8');
+  const version = cargo.match(/^version = "([^"]+)"/m)[1];
+  const manifest = JSON.parse(readFileSync(join(NPM_DIR, 'package.json'), 'utf8'));
+  writeFileSync(join(DIST_DIR, 'package.json'), `${JSON.stringify({ ...manifest, version }, null, 2)}\n`);
+}
+
+// =============================================================================
+// MAIN
+// =============================================================================
+
+const args = parseArgs(process.argv.slice(2));
+
+rmSync(DIST_DIR, { recursive: true, force: true });
+mkdirSync(DIST_DIR, { recursive: true });
+if (args.pkg) {
+  cpSync(args.pkg, PKG_DIR, { recursive: true });
+  for (const file of ['package.json', 'README.md', '.gitignore']) {
+    rmSync(join(PKG_DIR, file), { force: true });
+  }
+} else {
+  buildWasm();
+}
+
+const types = generateTypes();
+if (args.inline) {
+  inlineWasm();
+}
+writeEntries(types);
+if (existsSync(join(NPM_DIR, 'README.md'))) {
+  copyFileSync(join(NPM_DIR, 'README.md'), join(DIST_DIR, 'README.md'));
+}
+
+console.log(`Package written to ${DIST_DIR}${args.inline ? ' (wasm inlined)' : ''}`);
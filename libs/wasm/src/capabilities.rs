@@ -0,0 +1,128 @@
+//! # Capabilities
+//!
+//! What this build of the engine supports, returned by
+//! `get_capabilities()` so front-ends can feature-detect.
+//!
+//! ## Shape (JavaScript)
+//!
+//! ```javascript
+//! {
+//!     version: "0.1.0",
+//!     gitCommit: "3f2c…" | null,
+//!     crates: { "openscad-parser": "0.1.0", "manifold-rs": "0.1.0", … },
+//!     modules: ["cube", "sphere", …],
+//!     functions: ["sin", "cos", …],
+//!     statements: ["module", "function", "for", …],
+//!     extensions: ["smooth", "quality", …],
+//!     extensionPrimitives: ["torus", "prism", "rounded_cube"],
+//!     extensionOperations: ["refine", "warp_twist", …],
+//!     importFormats: ["stl", "obj", "off", "svg"],
+//!     exportFormats: ["stl", "3mf", "amf", "glb", "obj", "off"],
+//!     export2dFormats: ["svg", "dxf"],
+//!     backends: ["bsp", "intersect", "auto"],
+//!     threads: false,
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use openscad_eval::capabilities::{BUILTIN_FUNCTIONS, BUILTIN_MODULES, EXTENSION_MODULES, EXTENSION_OPERATIONS, EXTENSION_PRIMITIVES, STATEMENTS};
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Supported features and component versions.
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    /// Version of the WASM package.
+    pub version: &'static str,
+    /// Commit the package was built from, if `C4D_GIT_COMMIT` was set at
+    /// build time.
+    pub git_commit: Option<&'static str>,
+    /// Version of each pipeline crate by name.
+    pub crates: BTreeMap<&'static str, &'static str>,
+    /// Built-in OpenSCAD modules.
+    pub modules: Vec<&'static str>,
+    /// Built-in functions.
+    pub functions: Vec<&'static str>,
+    /// Statements beyond assignments and module calls.
+    pub statements: Vec<&'static str>,
+    /// Modules this engine adds on top of OpenSCAD.
+    pub extensions: Vec<&'static str>,
+    /// Primitives this engine adds, available with the `extensions`
+    /// render option.
+    pub extension_primitives: Vec<&'static str>,
+    /// Operations this engine adds, available with the `extensions`
+    /// render option.
+    pub extension_operations: Vec<&'static str>,
+    /// File extensions `import()` reads.
+    pub import_formats: Vec<&'static str>,
+    /// Formats `render_to_*` writes.
+    pub export_formats: Vec<&'static str>,
+    /// Formats `render_2d` writes 2D results as.
+    pub export_2d_formats: Vec<&'static str>,
+    /// Boolean backends compiled in, as accepted by `render()`'s
+    /// `backend` option.
+    pub backends: Vec<&'static str>,
+    /// Whether `init_thread_pool` is available for parallel rendering
+    /// (the `wasm-threads` feature).
+    pub threads: bool,
+}
+
+impl Capabilities {
+    /// Capabilities of this build.
+    pub fn current() -> Self {
+        let crates = BTreeMap::from([
+            ("openscad-wasm", crate::VERSION),
+            ("openscad-parser", openscad_parser::VERSION),
+            ("openscad-ast", openscad_ast::VERSION),
+            ("openscad-eval", openscad_eval::VERSION),
+            ("manifold-rs", manifold_rs::VERSION),
+        ]);
+        Self {
+            version: crate::VERSION,
+            git_commit: option_env!("C4D_GIT_COMMIT"),
+            crates,
+            modules: BUILTIN_MODULES.to_vec(),
+            functions: BUILTIN_FUNCTIONS.to_vec(),
+            statements: STATEMENTS.to_vec(),
+            extensions: EXTENSION_MODULES.to_vec(),
+            extension_primitives: EXTENSION_PRIMITIVES.to_vec(),
+            extension_operations: EXTENSION_OPERATIONS.to_vec(),
+            import_formats: manifold_rs::import::IMPORT_FORMATS.to_vec(),
+            export_formats: manifold_rs::mesh::EXPORT_FORMATS.to_vec(),
+            export_2d_formats: manifold_rs::openscad::drawing::EXPORT_2D_FORMATS.to_vec(),
+            backends: manifold_rs::BACKENDS.to_vec(),
+            threads: cfg!(feature = "wasm-threads"),
+        }
+    }
+
+    /// The capabilities as JSON text.
+    pub fn to_json(&self) -> String {
+        // Only strings, lists and maps of strings: serialization cannot fail
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the JSON has camelCase keys and the expected entries.
+    #[test]
+    fn test_capabilities_json() {
+        let json: serde_json::Value = serde_json::from_str(&Capabilities::current().to_json()).unwrap();
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert!(json["modules"].as_array().unwrap().iter().any(|m| m == "cube"));
+        assert!(json["extensions"].as_array().unwrap().iter().any(|m| m == "smooth"));
+        assert!(json["extensionPrimitives"].as_array().unwrap().iter().any(|m| m == "torus"));
+        assert!(json["extensionOperations"].as_array().unwrap().iter().any(|m| m == "refine"));
+        assert!(json["exportFormats"].as_array().unwrap().iter().any(|f| f == "3mf"));
+        assert!(json["export2dFormats"].as_array().unwrap().iter().any(|f| f == "dxf"));
+        assert_eq!(json["crates"].as_object().unwrap().len(), 5);
+    }
+}
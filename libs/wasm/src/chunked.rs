@@ -0,0 +1,528 @@
+//! # Chunked Rendering
+//!
+//! Splits the render pipeline into small resumable steps so the async entry
+//! point can hand control back to the browser event loop between them.
+//!
+//! ## Steps
+//!
+//! ```text
+//! Step 1: parse + evaluate → GeometryNode
+//! Step 2..n: mesh one leaf of the CSG tree, or finish one boolean,
+//!            and fold the mesh into its parent's
+//! Last step: finish the root, simplify, then mesh the `#` and `%`
+//!            preview layers
+//! ```
+//!
+//! Groups and 3D booleans are walked at every depth, keeping a stack of
+//! the nodes whose children are still being meshed; any other node is a
+//! leaf, meshed whole in one step. Each step is still a synchronous call,
+//! but no single step covers more than one leaf or one boolean.
+//!
+//! ## Progress and Cancellation
+//!
+//! The `progress` sink and `cancel` token of the options are honoured:
+//! evaluation reports its own progress, then each step reports the
+//! geometry nodes meshed so far and the last step the finished mesh. A
+//! cancelled token fails the next step, or the running one at its next
+//! statement or node, with `ManifoldError::Cancelled`.
+//!
+//! ## Timings
+//!
+//! The finished output carries the same [`Timings`] as a synchronous
+//! render: CSG nodes keep their positions in the whole geometry tree, a
+//! boolean's time adds up the steps of its children and its own, and
+//! `mesh_ms` adds up all steps, not the time between them. The node ids
+//! of the mesh's triangles are positions in the whole tree too.
+//!
+//! The `csg` settings apply during each step only, so other renders
+//! interleaved between steps keep their own.
+
+use std::collections::VecDeque;
+
+use manifold_rs::error::ManifoldResult;
+use manifold_rs::manifold::boolean::{difference_all, intersection_all, union_all, CsgScope};
+use manifold_rs::manifold::simplify::simplify;
+use std::sync::Arc;
+
+use manifold_rs::openscad::from_ir::geometry_to_mesh_timed;
+use manifold_rs::openscad::preview::PreviewTrees;
+use manifold_rs::{ManifoldError, Mesh, RenderOutput};
+use openscad_eval::limits::now_ms;
+use openscad_eval::{CancellationToken, CsgOptions, Diagnostic, EvalOptions, GeometryNode, GeometryStats, LibraryBundle, Message, NodeTiming, Progress, ProgressSink, SimplifyOptions, Stage, Timings};
+
+// =============================================================================
+// COMBINE OPERATION
+// =============================================================================
+
+/// How the meshes of a node's children are folded into its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Combine {
+    /// Concatenate meshes (implicit group).
+    Merge,
+    /// Boolean union.
+    Union,
+    /// Boolean difference (first child minus the rest).
+    Difference,
+    /// Boolean intersection.
+    Intersection,
+}
+
+impl Combine {
+    /// Fold `next` into `acc`.
+    pub(crate) fn apply(self, acc: Mesh, next: &Mesh) -> ManifoldResult<Mesh> {
+        match self {
+            Combine::Merge => {
+                let mut acc = acc;
+                acc.merge(next);
+                Ok(acc)
+            }
+            Combine::Union => union_all(&[acc, next.clone()]),
+            Combine::Difference => difference_all(&[acc, next.clone()]),
+            Combine::Intersection => intersection_all(&[acc, next.clone()]),
+        }
+    }
+}
+
+// =============================================================================
+// CHUNKED RENDER
+// =============================================================================
+
+/// Internal state of a chunked render.
+#[derive(Debug)]
+enum State {
+    /// Source not yet evaluated.
+    Source(String, EvalOptions),
+    /// Nodes being meshed; the innermost last.
+    Nodes {
+        stack: Vec<Frame>,
+        /// Geometry nodes meshed so far and in total.
+        done: u64,
+        total: u64,
+    },
+    /// Render finished.
+    Done(RenderOutput),
+}
+
+/// A geometry node with its pre-order position and depth in the whole
+/// tree.
+#[derive(Debug)]
+struct Operand {
+    node: GeometryNode,
+    position: u32,
+    depth: u32,
+    /// Whether the node is an operand of a boolean, so that a group
+    /// unions its children rather than concatenating them.
+    boolean: bool,
+}
+
+/// A node whose children are meshed in steps of their own, each folded
+/// into the node's mesh as it finishes.
+#[derive(Debug)]
+struct Frame {
+    combine: Combine,
+    pending: VecDeque<Operand>,
+    acc: Option<Mesh>,
+    position: u32,
+    depth: u32,
+    /// Kind of the node if it is timed as a CSG node.
+    kind: Option<&'static str>,
+    /// Time spent on the node's steps so far.
+    ms: f64,
+}
+
+impl Frame {
+    /// The frame meshing `operand` child by child, or the operand back
+    /// if it is meshed in a single step.
+    ///
+    /// Groups, `Source` nodes and 3D booleans are split, like the
+    /// synchronous path walks them: a group among boolean operands is
+    /// unioned, 2D booleans clip their outlines in one go.
+    fn split(operand: Operand) -> Result<Self, Box<Operand>> {
+        let Operand { node, position, depth, boolean } = operand;
+        let flat = node.dimension() == Some(2);
+        let (combine, kind, children, operands) = match node {
+            GeometryNode::Source { child, .. } => (Combine::Merge, None, vec![*child], false),
+            GeometryNode::Group { children } if boolean => (Combine::Union, None, children, true),
+            GeometryNode::Group { children } => (Combine::Merge, None, children, false),
+            GeometryNode::Union { children } if !flat => (Combine::Union, Some("union"), children, true),
+            GeometryNode::Difference { children } if !flat => (Combine::Difference, Some("difference"), children, true),
+            GeometryNode::Intersection { children } if !flat => (Combine::Intersection, Some("intersection"), children, true),
+            node => return Err(Box::new(Operand { node, position, depth, boolean })),
+        };
+        let mut next = position + 1;
+        let pending = children
+            .into_iter()
+            .filter(|child| !matches!(child, GeometryNode::Empty))
+            .map(|node| {
+                let position = next;
+                next += node.node_count() as u32;
+                Operand { node, position, depth: depth + 1, boolean: operands }
+            })
+            .collect();
+        Ok(Self { combine, pending, acc: None, position, depth, kind, ms: 0.0 })
+    }
+
+    /// The frame meshing the root of the tree, and the nodes it counts
+    /// as meshed already.
+    fn root(root: GeometryNode) -> (Self, u64) {
+        match Self::split(Operand { node: root, position: 0, depth: 0, boolean: false }) {
+            Ok(frame) => (frame, 1),
+            // An unsplit root is a frame's only child; the frame claims
+            // nothing its child does not
+            Err(leaf) => {
+                let frame = Self { combine: Combine::Merge, pending: VecDeque::from([*leaf]), acc: None, position: 0, depth: 0, kind: None, ms: 0.0 };
+                (frame, 0)
+            }
+        }
+    }
+
+    /// Fold the mesh of a finished child into the node's mesh.
+    fn fold(&mut self, mesh: Mesh) -> ManifoldResult<()> {
+        // Empty children are skipped, matching the synchronous path
+        if mesh.is_empty() {
+            return Ok(());
+        }
+        self.acc = Some(match self.acc.take() {
+            None => mesh,
+            Some(acc) => self.combine.apply(acc, &mesh)?,
+        });
+        Ok(())
+    }
+}
+
+/// Resumable render job.
+///
+/// Call [`ChunkedRender::step`] until it returns `true`, yielding to the
+/// event loop between calls, then take the mesh with
+/// [`ChunkedRender::finish`].
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_wasm::chunked::ChunkedRender;
+///
+/// let mut job = ChunkedRender::new("cube(1); sphere(1);");
+/// while !job.step().unwrap() {}
+/// assert!(!job.finish().solid.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct ChunkedRender {
+    state: State,
+    messages: Vec<Message>,
+    diagnostics: Vec<Diagnostic>,
+    /// Geometry of the preview layers, split off once evaluated.
+    preview: PreviewTrees,
+    /// Timings so far.
+    timings: Timings,
+    /// Counts of the geometry tree, once evaluated.
+    stats: GeometryStats,
+    progress: Option<Arc<dyn ProgressSink>>,
+    cancel: Option<CancellationToken>,
+    simplify: SimplifyOptions,
+    csg: CsgOptions,
+}
+
+impl ChunkedRender {
+    /// Create a render job for the given source.
+    pub fn new(source: &str) -> Self {
+        Self::with_libraries(source, Vec::new())
+    }
+
+    /// Create a render job with precompiled libraries preloaded.
+    pub fn with_libraries(source: &str, libraries: Vec<LibraryBundle>) -> Self {
+        Self::with_options(source, EvalOptions { libraries, ..EvalOptions::default() })
+    }
+
+    /// Create a render job with full evaluation options.
+    pub fn with_options(source: &str, options: EvalOptions) -> Self {
+        Self {
+            progress: options.progress.clone(),
+            cancel: options.cancel.clone(),
+            simplify: options.simplify,
+            csg: options.csg,
+            state: State::Source(source.to_string(), options),
+            messages: Vec::new(),
+            diagnostics: Vec::new(),
+            preview: PreviewTrees::default(),
+            timings: Timings::default(),
+            stats: GeometryStats::default(),
+        }
+    }
+
+    /// Run the next step of the pipeline.
+    ///
+    /// ## Returns
+    ///
+    /// `Ok(true)` once the render is complete, `Ok(false)` if more steps remain.
+    ///
+    /// ## Errors
+    ///
+    /// Any error of the pipeline, and `ManifoldError::Cancelled` once the
+    /// options' token is cancelled.
+    pub fn step(&mut self) -> ManifoldResult<bool> {
+        if self.cancel.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(ManifoldError::Cancelled);
+        }
+        let _csg = CsgScope::enter(&self.csg);
+        let state = std::mem::replace(&mut self.state, State::Done(RenderOutput::default()));
+        self.state = match state {
+            State::Source(source, options) => {
+                let evaluated = openscad_eval::evaluate_with_options(&source, &options)
+                    .map_err(ManifoldError::from)?;
+                self.messages = evaluated.messages;
+                self.diagnostics = evaluated.diagnostics;
+                self.preview = PreviewTrees::of(&evaluated.geometry)?;
+                self.timings = evaluated.timings;
+                self.stats = evaluated.geometry.stats();
+                let total = evaluated.geometry.node_count() as u64;
+                let (root, done) = Frame::root(evaluated.geometry);
+                self.report(Stage::Csg, 0, total);
+                State::Nodes { stack: vec![root], done, total }
+            }
+            State::Nodes { mut stack, mut done, total } => {
+                let start = now_ms();
+                let finished = self.advance(&mut stack, &mut done, start)?;
+                let ms = now_ms() - start;
+                for frame in &mut stack {
+                    frame.ms += ms;
+                }
+                self.timings.mesh_ms += ms;
+                match finished {
+                    None => {
+                        self.report(Stage::Csg, done, total);
+                        State::Nodes { stack, done, total }
+                    }
+                    Some(mesh) => {
+                        let start = now_ms();
+                        let mesh = simplify(mesh, &self.simplify);
+                        let mut output = self.preview.mesh(mesh)?;
+                        self.timings.mesh_ms += now_ms() - start;
+                        self.timings.csg.sort_by_key(|t| t.node);
+                        output.timings = std::mem::take(&mut self.timings);
+                        output.stats = std::mem::take(&mut self.stats);
+                        self.report(Stage::Mesh, 1, 1);
+                        State::Done(output)
+                    }
+                }
+            }
+            done @ State::Done(_) => done,
+        };
+        Ok(matches!(self.state, State::Done(_)))
+    }
+
+    /// Mesh the next leaf of the innermost frame, splitting the nodes on
+    /// the way down into frames of their own, or finish that frame once
+    /// it has no children left.
+    ///
+    /// ## Returns
+    ///
+    /// The mesh of the whole tree once the root's frame is finished.
+    fn advance(&mut self, stack: &mut Vec<Frame>, done: &mut u64, start: f64) -> ManifoldResult<Option<Mesh>> {
+        while let Some(frame) = stack.last_mut() {
+            let Some(operand) = frame.pending.pop_front() else { break };
+            match Frame::split(operand) {
+                Ok(child) => {
+                    *done += 1;
+                    stack.push(child);
+                }
+                Err(leaf) => {
+                    let (mut mesh, csg) = geometry_to_mesh_timed(&leaf.node, None, None, self.cancel.as_ref())?;
+                    // Positions in the leaf become positions in the whole tree
+                    mesh.offset_nodes(i64::from(leaf.position));
+                    self.timings.csg.extend(csg.into_iter().map(|mut t| {
+                        t.node += leaf.position;
+                        t.depth += leaf.depth;
+                        t
+                    }));
+                    *done += leaf.node.node_count() as u64;
+                    frame.fold(mesh)?;
+                    return Ok(None);
+                }
+            }
+        }
+        let Some(mut frame) = stack.pop() else { return Ok(Some(Mesh::new())) };
+        frame.ms += now_ms() - start;
+        let mut mesh = frame.acc.take().unwrap_or_default();
+        mesh.assign_nodes(0, frame.position);
+        if let Some(kind) = frame.kind {
+            self.timings.csg.push(NodeTiming { node: frame.position, depth: frame.depth, kind: kind.to_string(), ms: frame.ms });
+        }
+        match stack.last_mut() {
+            Some(parent) => parent.fold(mesh).map(|()| None),
+            None => Ok(Some(mesh)),
+        }
+    }
+
+    /// Send a report to the options' progress sink, if any.
+    fn report(&self, stage: Stage, done: u64, total: u64) {
+        if let Some(sink) = &self.progress {
+            sink.report(Progress { stage, done, total });
+        }
+    }
+
+    /// Console output of the script, once the first step has evaluated it.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Warnings of the script, once the first step has evaluated it.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Take the finished mesh and its preview layers.
+    ///
+    /// Returns empty meshes if called before the render completed.
+    pub fn finish(self) -> RenderOutput {
+        match self.state {
+            State::Done(output) => output,
+            _ => RenderOutput::default(),
+        }
+    }
+}
+
+/// Split the root geometry node into independently meshable children and
+/// the way their meshes combine.
+///
+/// A root [`GeometryNode::Source`] is looked through: it meshes as its
+/// child, so a script of one call still splits.
+pub(crate) fn split_top_level(root: GeometryNode) -> (Combine, Vec<GeometryNode>) {
+    let (combine, children) = match root {
+        GeometryNode::Source { child, .. } => return split_top_level(*child),
+        GeometryNode::Group { children } => (Combine::Merge, children),
+        GeometryNode::Union { children } => (Combine::Union, children),
+        GeometryNode::Difference { children } => (Combine::Difference, children),
+        GeometryNode::Intersection { children } => (Combine::Intersection, children),
+        other => (Combine::Merge, vec![other]),
+    };
+    // Boolean operands are single solids, so groups among them are merged
+    let children = match combine {
+        Combine::Merge => children,
+        _ => children.into_iter().map(GeometryNode::into_union).collect(),
+    };
+    (combine, children)
+}
+
+/// Depth in the geometry tree of the children [`split_top_level`] gives
+/// for `root`: 1 if the root is split, 0 if it is the only child, plus
+/// the `Source` nodes looked through above it.
+pub(crate) fn child_depth(root: &GeometryNode) -> u32 {
+    if let GeometryNode::Source { child, .. } = root {
+        return 1 + child_depth(child);
+    }
+    let split = matches!(
+        root,
+        GeometryNode::Group { .. } | GeometryNode::Union { .. } | GeometryNode::Difference { .. } | GeometryNode::Intersection { .. }
+    );
+    u32::from(split)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a job to completion, counting steps.
+    fn run(source: &str) -> (Mesh, usize) {
+        let mut job = ChunkedRender::new(source);
+        let mut steps = 1;
+        while !job.step().unwrap() {
+            steps += 1;
+        }
+        (job.finish().solid, steps)
+    }
+
+    /// Test chunked output matches the synchronous pipeline.
+    #[test]
+    fn test_matches_sync_render() {
+        let source = "difference() { cube(10, center=true); sphere(6); }";
+        let (mesh, _) = run(source);
+        let expected = manifold_rs::render(source).unwrap();
+        assert_eq!(mesh.vertex_count(), expected.vertex_count());
+        assert_eq!(mesh.triangle_count(), expected.triangle_count());
+    }
+
+    /// Test each top-level child gets its own step.
+    #[test]
+    fn test_one_step_per_child() {
+        // evaluate + 3 children + final
+        let (mesh, steps) = run("cube(1); translate([5,0,0]) cube(1); sphere(1);");
+        assert_eq!(steps, 5);
+        assert!(!mesh.is_empty());
+    }
+
+    /// Test nested booleans are split too, one step per leaf and per
+    /// finished boolean, and give the synchronous mesh and node ids.
+    #[test]
+    fn test_nested_steps() {
+        let source = "difference() { cube(10); union() { sphere(3); translate([10, 10, 10]) sphere(3); } }";
+        let mut job = ChunkedRender::new(source);
+        let mut steps = 1;
+        while !job.step().unwrap() {
+            steps += 1;
+        }
+        let output = job.finish();
+        // evaluate + cube + 2 spheres + union + difference, which is last
+        assert_eq!(steps, 6);
+        let expected = manifold_rs::render_timed(source, &EvalOptions::default()).unwrap();
+        assert_eq!(output.solid.triangle_count(), expected.solid.triangle_count());
+        assert_eq!(output.solid.nodes, expected.solid.nodes);
+        let nodes = |t: &Timings| t.csg.iter().map(|n| (n.node, n.depth, n.kind.clone())).collect::<Vec<_>>();
+        assert_eq!(nodes(&output.timings), nodes(&expected.timings));
+    }
+
+    /// Test CSG nodes are timed at their positions in the whole tree, as
+    /// in a synchronous render.
+    #[test]
+    fn test_timings() {
+        let source = "difference() { cube(10); union() { sphere(6); cylinder(20, r = 2); } } hull() { cube(1); sphere(1); }";
+        let mut job = ChunkedRender::new(source);
+        while !job.step().unwrap() {}
+        let timings = job.finish().timings;
+        let expected = manifold_rs::render_timed(source, &EvalOptions::default()).unwrap().timings;
+        let nodes = |t: &Timings| t.csg.iter().map(|n| (n.node, n.depth, n.kind.clone())).collect::<Vec<_>>();
+        assert_eq!(nodes(&timings), nodes(&expected));
+        assert_eq!(timings.csg.len(), 3);
+        assert!(timings.mesh_ms >= timings.csg.iter().filter(|n| n.depth == 1).map(|n| n.ms).sum::<f64>());
+    }
+
+    /// Test evaluation errors surface from the first step.
+    #[test]
+    fn test_eval_error() {
+        let mut job = ChunkedRender::new("cube(");
+        assert!(matches!(job.step(), Err(ManifoldError::EvalError(_))));
+    }
+
+    /// Test steps report growing progress and a cancelled token stops the
+    /// next step.
+    #[test]
+    fn test_progress_and_cancel() {
+        use std::sync::Mutex;
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let cancel = CancellationToken::new();
+        let options = EvalOptions {
+            progress: Some(Arc::new(move |p: Progress| sink.lock().unwrap().push(p))),
+            cancel: Some(cancel.clone()),
+            ..EvalOptions::default()
+        };
+        let mut job = ChunkedRender::with_options("cube(1); translate([5,0,0]) cube(1);", options);
+        while !job.step().unwrap() {}
+
+        let reports = reports.lock().unwrap();
+        let csg: Vec<u64> = reports.iter().filter(|p| p.stage == Stage::Csg).map(|p| p.done).collect();
+        // The root group counts as meshed once split, as in the total
+        assert_eq!(csg, vec![0, 2, 4]);
+        assert_eq!(reports.last(), Some(&Progress { stage: Stage::Mesh, done: 1, total: 1 }));
+
+        let options = EvalOptions { cancel: Some(cancel.clone()), ..EvalOptions::default() };
+        let mut job = ChunkedRender::with_options("cube(1); sphere(1);", options);
+        assert!(!job.step().unwrap());
+        cancel.cancel();
+        assert!(matches!(job.step(), Err(ManifoldError::Cancelled)));
+    }
+}
@@ -27,11 +27,58 @@
 //!
 //! const result = render('cube(10);');
 //! // result.vertices, result.indices, result.normals are typed arrays
+//!
+//! // Or, without blocking the main thread for the whole render:
+//! const asyncResult = await render_async('cube(10);');
+//!
+//! // Or with a progress bar and a stop button:
+//! const cancel = new CancellationToken();
+//! const tracked = await render_with_progress('cube(10);', (p) => console.log(p.stage, p.done, p.total), cancel);
+//!
+//! // Or with the mesh handed over in batches as it is transferred:
+//! const summary = await render_streaming('cube(10);', (chunk) => scene.addChunk(chunk));
+//!
+//! // Or evaluate now, keep the geometry tree as JSON, and mesh it later:
+//! const mesh = render_evaluated(eval_to_json('cube(10);'));
+//!
+//! // Or, from customizer sliders, without parsing the source again:
+//! const resized = render_with_overrides(source, { size: 20 });
+//!
+//! // Or, while editing, redoing only what each edit changed:
+//! const session = new RenderSession();
+//! const updated = session.update('cube(10); sphere(6);');
+//! const picked = session.pick(cameraPosition, rayDirection); // { point, kind, span } of the call under the cursor
+//!
+//! // echo() output and warnings of every render, as they happen:
+//! set_log_handler((line) => console.log(line.level, line.text));
 //! ```
 
+pub mod capabilities;
+pub mod chunked;
+pub mod options;
+pub mod result;
+pub mod session;
+pub mod streaming;
+pub mod typescript;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use manifold_rs::openscad::provenance::NodeTable;
+use openscad_ast::Ast;
+use openscad_eval::{EvalError, EvalOptions, EvaluatedAst, LibraryBundle, MemoryFileProvider, OutputOptions, Progress};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+#[cfg(feature = "wasm-threads")]
 pub use wasm_bindgen_rayon::init_thread_pool;
 
+use capabilities::Capabilities;
+use chunked::ChunkedRender;
+use options::RenderOptions;
+use result::{ExportedFile, LogLine, Measurement, Outline, PickResult, RenderResult, Validation};
+use streaming::StreamSuccess;
+
 // =============================================================================
 // CONSTANTS
 // =============================================================================
@@ -39,14 +86,67 @@ pub use wasm_bindgen_rayon::init_thread_pool;
 /// Current version of the WASM module.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// =============================================================================
+// LIBRARY REGISTRY
+// =============================================================================
+
+thread_local! {
+    /// Precompiled libraries preloaded into every render.
+    static LIBRARIES: RefCell<Vec<LibraryBundle>> = const { RefCell::new(Vec::new()) };
+    /// Text files for `include` and `use`.
+    static SOURCES: RefCell<MemoryFileProvider> = RefCell::new(MemoryFileProvider::new());
+    /// Receives log lines as renders evaluate, set by `set_log_handler`.
+    static LOG_HANDLER: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+    /// Last source parsed by `render_with_overrides`, with its AST.
+    static PARSED: RefCell<Option<(String, Rc<Ast>)>> = const { RefCell::new(None) };
+}
+
+/// Snapshot of the registered libraries.
+fn registered_libraries() -> Vec<LibraryBundle> {
+    LIBRARIES.with(|libs| libs.borrow().clone())
+}
+
+/// AST of `source`, parsed again only if it differs from the last call.
+fn cached_ast(source: &str) -> Result<Rc<Ast>, EvalError> {
+    PARSED.with(|parsed| {
+        let mut parsed = parsed.borrow_mut();
+        match parsed.as_ref() {
+            Some((cached, ast)) if cached == source => Ok(Rc::clone(ast)),
+            _ => {
+                let ast = Rc::new(
+                    openscad_ast::parse(source).map_err(|e| EvalError::ParseError(e.to_string()))?,
+                );
+                *parsed = Some((source.to_string(), Rc::clone(&ast)));
+                Ok(ast)
+            }
+        }
+    })
+}
+
+/// Evaluator options from a JavaScript options object (may be undefined).
+fn eval_options(options: &JsValue) -> Result<EvalOptions, String> {
+    let options = if options.is_undefined() || options.is_null() {
+        RenderOptions::default()
+    } else {
+        let json = js_sys::JSON::stringify(options)
+            .map_err(|_| "Render options must be a plain object".to_string())?;
+        RenderOptions::from_json(&String::from(json))?
+    };
+    let mut eval = options.to_eval_options(registered_libraries())?;
+    eval.file_provider = Some(Arc::new(SOURCES.with(|files| files.borrow().clone())));
+    eval.log = Some(Arc::new(forward_log));
+    Ok(eval)
+}
+
 // =============================================================================
 // INITIALIZATION
 // =============================================================================
 
 /// Initialize the WASM module.
 ///
-/// Sets up panic hook for better error messages in browser console.
-/// Call this once before using any other functions.
+/// Sets up panic hook for better error messages in browser console, and
+/// with the `tracing` feature reports render spans to the performance
+/// panel. Call this once before using any other functions.
 ///
 /// ## Example (JavaScript)
 ///
@@ -58,6 +158,34 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub fn wasm_init() {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
+    // Spans become performance marks, attributed to source lines
+    #[cfg(feature = "tracing")]
+    tracing_wasm::set_as_global_default();
+}
+
+/// Run independent CSG work on the worker thread pool.
+///
+/// With `enabled`, sibling subtrees, boolean pairs and triangle
+/// classification run in parallel; the mesh is the same either way. Only
+/// builds with the `wasm-threads` feature (`threads` in
+/// [`get_capabilities`]) have a pool: start it with `initThreadPool`
+/// first, and render from a worker, since the main browser thread cannot
+/// block while the pool works. Elsewhere this has no effect.
+///
+/// ## Example (JavaScript, inside a worker)
+///
+/// ```javascript
+/// import init, { initThreadPool, set_parallel, render } from './openscad_wasm.js';
+///
+/// await init();
+/// await initThreadPool(navigator.hardwareConcurrency);
+/// set_parallel(true);
+/// const result = render('union() for (i = [0:99]) translate([i, 0, 0]) sphere(1);');
+/// ```
+#[wasm_bindgen]
+pub fn set_parallel(enabled: bool) {
+    use manifold_rs::manifold::boolean::{set_reduction, Reduction};
+    set_reduction(if enabled { Reduction::Parallel } else { Reduction::Serial });
 }
 
 // =============================================================================
@@ -81,6 +209,27 @@ pub fn get_version() -> String {
     VERSION.to_string()
 }
 
+/// Get what this build supports.
+///
+/// ## Returns
+///
+/// Object listing supported modules, functions, statements, extensions,
+/// import/export formats and backends, plus the version of each crate.
+/// See [`capabilities`] for the exact shape.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const caps = get_capabilities();
+/// if (caps.exportFormats.includes('3mf')) {
+///     showButton('Download 3MF');
+/// }
+/// ```
+#[wasm_bindgen(unchecked_return_type = "Capabilities")]
+pub fn get_capabilities() -> JsValue {
+    js_sys::JSON::parse(&Capabilities::current().to_json()).unwrap_or(JsValue::NULL)
+}
+
 /// Render OpenSCAD source code to mesh (main entry point).
 ///
 /// Full pipeline: parser → AST → evaluator → mesh generator.
@@ -89,6 +238,8 @@ pub fn get_version() -> String {
 /// ## Parameters
 ///
 /// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, e.g. `{ overrides: { size: 20 } }`
+///   or `{ t: 0.25 }` for one animation frame (see `RenderOptions`)
 ///
 /// ## Returns
 ///
@@ -97,74 +248,996 @@ pub fn get_version() -> String {
 /// - `vertices`: Float32Array (x, y, z positions)
 /// - `indices`: Uint32Array (triangle indices)
 /// - `normals`: Float32Array (x, y, z normals)
+/// - `colors`: Float32Array (r, g, b, a per vertex) from `color()`, or null
 /// - `vertexCount`: number
 /// - `triangleCount`: number
 /// - `renderTimeMs`: number
+/// - `messages`: `echo()` output as `{ kind, text, span }` objects
+/// - `diagnostics`: warnings of the evaluation as `{ kind, text, span }`
+///   objects, such as ignored arguments and degenerate primitives
+/// - `highlighted`: `{ vertices, indices, normals }` of the geometry under
+///   `#`, or null; it is also part of the mesh
+/// - `transparent`: the same for the geometry under `%`, which is not
+///   part of the mesh
 /// - `error`: string (only if success is false)
+/// - `limit`: which limit aborted the render, or null (only if success
+///   is false; see `LimitExceeded`)
 ///
 /// ## Example (JavaScript)
 ///
 /// ```javascript
-/// const result = render('cube(10);');
+/// const result = render('size = 10; cube(size);', { overrides: { size: 20 } });
 /// if (result.success) {
 ///     scene.updateMesh(result.vertices, result.indices, result.normals);
 /// } else {
 ///     console.error(result.error);
 /// }
 /// ```
+#[wasm_bindgen(unchecked_return_type = "RenderResult")]
+pub fn render(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> JsValue {
+    let start = js_sys::Date::now();
+    let options = match eval_options(&options) {
+        Ok(options) => options,
+        Err(e) => return RenderResult::failure(&e).into_js(),
+    };
+
+    // Full pipeline: source → mesh, evaluating first to keep the messages
+    let started = openscad_eval::limits::now_ms();
+    let evaluated = openscad_eval::evaluate_with_options(source, &options);
+    mesh_evaluated(evaluated, &options, start, started)
+}
+
+/// Render with values for top-level variables, reusing the last parse.
+///
+/// Like [`render`] with `overrides` in its options, but the source is
+/// parsed only when it differs from the previous call's, so a
+/// customizer can re-render on every slider move without editing the
+/// source text or parsing it again. The values replace top-level
+/// assignments of the same name (`-D name=value`) and win over
+/// `options.overrides`.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `overrides`: Values by variable name: numbers, booleans, strings,
+///   `null` or arrays of them
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// The same object as [`render`].
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const source = 'width = 10; height = 5; cube([width, width, height]);';
+/// slider.oninput = () => {
+///     const result = render_with_overrides(source, { height: Number(slider.value) });
+///     if (result.success) scene.updateMesh(result.vertices, result.indices, result.normals);
+/// };
+/// ```
+#[wasm_bindgen(unchecked_return_type = "RenderResult")]
+pub fn render_with_overrides(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "{ [name: string]: JsonValue }")] overrides: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> JsValue {
+    let start = js_sys::Date::now();
+    let options = eval_options(&options).and_then(|mut options| {
+        let json = js_sys::JSON::stringify(&overrides)
+            .map_err(|_| "Overrides must be a plain object".to_string())?;
+        options.overrides.extend(options::overrides_from_json(&String::from(json))?);
+        Ok(options)
+    });
+    let options = match options {
+        Ok(options) => options,
+        Err(e) => return RenderResult::failure(&e).into_js(),
+    };
+
+    let started = openscad_eval::limits::now_ms();
+    let evaluated = cached_ast(source)
+        .and_then(|ast| openscad_eval::visitor::evaluate_ast_with_options(&ast, &options));
+    mesh_evaluated(evaluated, &options, start, started)
+}
+
+/// Evaluate OpenSCAD source code to its geometry tree as JSON.
+///
+/// The JSON is the evaluator's `EvaluatedAst`: the resolved geometry
+/// nodes with the warnings, diagnostics and console output. Tools can
+/// inspect or store it, and [`render_evaluated`] meshes it later without
+/// evaluating the source again.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// The JSON text, or throws an error string.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const tree = JSON.parse(eval_to_json('translate([5, 0, 0]) cube(10);'));
+/// console.log(tree.geometry);  // { Translate: { offset: [5, 0, 0], child: { Cube: ... } } }
+/// ```
 #[wasm_bindgen]
-pub fn render(source: &str) -> JsValue {
+pub fn eval_to_json(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<String, JsValue> {
+    let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    openscad_eval::evaluate_with_options(source, &options)
+        .and_then(|evaluated| evaluated.to_json())
+        .map_err(|e| JsValue::from_str(&format!("Evaluation error: {}", e)))
+}
+
+/// Mesh a geometry tree produced by [`eval_to_json`].
+///
+/// Runs only the mesh stage of [`render`]; the messages and diagnostics
+/// in the result are the ones stored in the JSON. Only the mesh options
+/// (`mergeCoplanar`, `targetTriangles` and the mesh limits) apply.
+///
+/// ## Parameters
+///
+/// - `json`: JSON text from [`eval_to_json`]
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// The same object as [`render`].
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// localStorage.setItem('model', eval_to_json(source));
+/// const result = render_evaluated(localStorage.getItem('model'));
+/// ```
+#[wasm_bindgen(unchecked_return_type = "RenderResult")]
+pub fn render_evaluated(
+    json: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> JsValue {
     let start = js_sys::Date::now();
+    let options = match eval_options(&options) {
+        Ok(options) => options,
+        Err(e) => return RenderResult::failure(&e).into_js(),
+    };
+    let started = openscad_eval::limits::now_ms();
+    mesh_evaluated(EvaluatedAst::from_json(json), &options, start, started)
+}
 
-    // Full pipeline: source → mesh
-    match manifold_rs::render(source) {
-        Ok(mesh) => {
-            let render_time_ms = js_sys::Date::now() - start;
-            create_success_result(mesh.vertices, mesh.indices, mesh.normals, render_time_ms)
+/// Mesh an evaluation for [`render`], [`render_with_overrides`] and
+/// [`render_evaluated`].
+///
+/// `start` is when the call began, for the render time; `started` is when
+/// evaluation began, for the time limit.
+fn mesh_evaluated(
+    evaluated: Result<EvaluatedAst, EvalError>,
+    options: &EvalOptions,
+    start: f64,
+    started: f64,
+) -> JsValue {
+    let evaluated = match evaluated {
+        Ok(evaluated) => evaluated,
+        Err(e) => return RenderResult::render_error(e.into(), Vec::new(), Vec::new()).into_js(),
+    };
+    match manifold_rs::render_evaluated_preview(&evaluated, options, started) {
+        Ok(output) => {
+            RenderResult::success(output, evaluated.messages, evaluated.diagnostics, js_sys::Date::now() - start, options.output)
+                .into_js()
         }
-        Err(e) => create_error_result(&format!("Render error: {}", e)),
+        Err(e) => RenderResult::render_error(e, evaluated.messages, evaluated.diagnostics).into_js(),
+    }
+}
+
+/// Render OpenSCAD source code to mesh without stalling the event loop.
+///
+/// Same pipeline and result shape as [`render`], but split into steps
+/// (evaluation, then one step per top-level CSG child). Control returns to
+/// the event loop between steps via `setTimeout(0)`, so the page stays
+/// responsive when a Web Worker is not an option.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// Promise resolving to the same object as [`render`].
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const result = await render_async('union() { cube(10); sphere(6); }');
+/// if (result.success) {
+///     scene.updateMesh(result.vertices, result.indices, result.normals);
+/// }
+/// ```
+#[wasm_bindgen(unchecked_return_type = "RenderResult")]
+pub async fn render_async(
+    source: String,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> JsValue {
+    let start = js_sys::Date::now();
+    let options = match eval_options(&options) {
+        Ok(options) => options,
+        Err(e) => return RenderResult::failure(&e).into_js(),
+    };
+    let output = options.output;
+    run_chunked(ChunkedRender::with_options(&source, options), output, start, || {}).await
+}
+
+/// Render OpenSCAD source code with progress reports and cancellation.
+///
+/// Runs like [`render_async`], calling `on_progress` with
+/// `{ stage, done, total }` (see `Progress`) as evaluation, meshing and
+/// the finished mesh advance, so a UI can draw a progress bar. Calling
+/// `cancel.cancel()` from an event handler stops the render at its next
+/// step and resolves with `cancelled: true`; the module stays usable for
+/// the next render.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `on_progress`: Called with each report, in order
+/// - `cancel`: Token to abort the render with
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// Promise resolving to the same object as [`render`].
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const cancel = new CancellationToken();
+/// stopButton.onclick = () => cancel.cancel();
+/// const result = await render_with_progress(source, (p) => {
+///     bar.value = p.total ? p.done / p.total : 1;
+///     label.textContent = p.stage;
+/// }, cancel);
+/// if (!result.success && result.cancelled) {
+///     showStatus('Render stopped');
+/// }
+/// ```
+#[wasm_bindgen(unchecked_return_type = "Promise<RenderResult>")]
+pub fn render_with_progress(
+    source: String,
+    #[wasm_bindgen(unchecked_param_type = "(progress: Progress) => void")] on_progress: js_sys::Function,
+    cancel: &RenderCancellation,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> js_sys::Promise {
+    let start = js_sys::Date::now();
+    let options = eval_options(&options);
+    let token = cancel.0.clone();
+    wasm_bindgen_futures::future_to_promise(async move {
+        let mut options = match options {
+            Ok(options) => options,
+            Err(e) => return Ok(RenderResult::failure(&e).into_js()),
+        };
+        // Reports are queued while a step runs and handed to JavaScript
+        // between steps
+        let queue: Arc<Mutex<Vec<Progress>>> = Arc::default();
+        let sink = Arc::clone(&queue);
+        options.progress = Some(Arc::new(move |p: Progress| {
+            if let Ok(mut queue) = sink.lock() {
+                queue.push(p);
+            }
+        }));
+        options.cancel = Some(token);
+
+        let flush = || {
+            let reports = queue.lock().map(|mut queue| std::mem::take(&mut *queue)).unwrap_or_default();
+            for report in reports {
+                let json = serde_json::to_string(&report).unwrap_or_default();
+                let value = js_sys::JSON::parse(&json).unwrap_or(JsValue::NULL);
+                // A throwing callback must not abort the render
+                let _ = on_progress.call1(&JsValue::NULL, &value);
+            }
+        };
+        let output = options.output;
+        Ok(run_chunked(ChunkedRender::with_options(&source, options), output, start, flush).await)
+    })
+}
+
+/// Run a chunked render to completion, yielding to the event loop between
+/// steps and calling `after_step` after each one.
+async fn run_chunked(mut job: ChunkedRender, output: OutputOptions, start: f64, after_step: impl Fn()) -> JsValue {
+    if let Err(e) = run_steps(&mut job, after_step).await {
+        return RenderResult::render_error(e, job.messages().to_vec(), job.diagnostics().to_vec()).into_js();
+    }
+
+    let messages = job.messages().to_vec();
+    let diagnostics = job.diagnostics().to_vec();
+    RenderResult::success(job.finish(), messages, diagnostics, js_sys::Date::now() - start, output).into_js()
+}
+
+/// Step a chunked render until its mesh is done, yielding to the event
+/// loop between steps.
+async fn run_steps(job: &mut ChunkedRender, after_step: impl Fn()) -> manifold_rs::error::ManifoldResult<()> {
+    loop {
+        let step = job.step();
+        after_step();
+        match step? {
+            true => return Ok(()),
+            false => yield_to_event_loop().await,
+        }
+    }
+}
+
+/// Render OpenSCAD source code and hand the mesh over in chunks.
+///
+/// Renders like [`render_async`], then calls `on_chunk` with batches of
+/// at most `max_triangles` triangles (65536 by default), yielding to the
+/// event loop between batches. Each chunk carries its own vertex, normal
+/// and color arrays with indices into them (see `MeshChunk` and
+/// [`streaming`]), so a viewer can turn each one into a
+/// `THREE.BufferGeometry` as it arrives instead of waiting for one large
+/// result.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `on_chunk`: Called with each chunk, in triangle order
+/// - `options`: Optional options object, as for [`render`]
+/// - `max_triangles`: Optional triangles per chunk
+///
+/// ## Returns
+///
+/// Promise resolving to `{ success: true, vertexCount, triangleCount,
+/// chunkCount, renderTimeMs, messages, diagnostics }`, or to the failure object of
+/// [`render`], in which case no chunk was sent.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const group = new THREE.Group();
+/// const summary = await render_streaming(source, (chunk) => {
+///     const geometry = new THREE.BufferGeometry();
+///     geometry.setAttribute('position', new THREE.BufferAttribute(chunk.vertices, 3));
+///     geometry.setAttribute('normal', new THREE.BufferAttribute(chunk.normals, 3));
+///     geometry.setIndex(new THREE.BufferAttribute(chunk.indices, 1));
+///     group.add(new THREE.Mesh(geometry, material));
+/// });
+/// ```
+#[wasm_bindgen(unchecked_return_type = "Promise<StreamResult>")]
+pub fn render_streaming(
+    source: String,
+    #[wasm_bindgen(unchecked_param_type = "(chunk: MeshChunk) => void")] on_chunk: js_sys::Function,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+    max_triangles: Option<u32>,
+) -> js_sys::Promise {
+    let start = js_sys::Date::now();
+    let options = eval_options(&options);
+    wasm_bindgen_futures::future_to_promise(async move {
+        let options = match options {
+            Ok(options) => options,
+            Err(e) => return Ok(RenderResult::failure(&e).into_js()),
+        };
+        let mut job = ChunkedRender::with_options(&source, options);
+        if let Err(e) = run_steps(&mut job, || {}).await {
+            return Ok(RenderResult::render_error(e, job.messages().to_vec(), job.diagnostics().to_vec()).into_js());
+        }
+
+        let messages = job.messages().to_vec();
+        let diagnostics = job.diagnostics().to_vec();
+        let mesh = job.finish().solid;
+        let max_triangles = max_triangles.map_or(streaming::DEFAULT_CHUNK_TRIANGLES, |n| n as usize);
+        let mut chunk_count = 0;
+        for chunk in streaming::mesh_chunks(&mesh, max_triangles) {
+            if chunk_count > 0 {
+                yield_to_event_loop().await;
+            }
+            // A throwing callback must not abort the transfer
+            let _ = on_chunk.call1(&JsValue::NULL, &chunk.into_js());
+            chunk_count += 1;
+        }
+
+        Ok(StreamSuccess {
+            success: true,
+            vertex_count: mesh.vertex_count() as u32,
+            triangle_count: mesh.triangle_count() as u32,
+            chunk_count,
+            render_time_ms: js_sys::Date::now() - start,
+            messages,
+            diagnostics,
+        }
+        .into_js())
+    })
+}
+
+/// Render OpenSCAD source code to a binary STL file.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// `Uint8Array` with the STL bytes, or throws an error string.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const stl = render_to_stl('cube(10);');
+/// const url = URL.createObjectURL(new Blob([stl], { type: 'model/stl' }));
+/// ```
+#[wasm_bindgen]
+pub fn render_to_stl(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<Vec<u8>, JsValue> {
+    let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    manifold_rs::render_with_eval_options(source, &options)
+        .map(|mesh| mesh.to_stl_binary())
+        .map_err(|e| JsValue::from_str(&format!("Render error: {}", e)))
+}
+
+/// Render OpenSCAD source code to a 3MF package, keeping `color()`.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// `Uint8Array` with the 3MF bytes, or throws an error string.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const model = render_to_3mf('color([1, 0, 0]) cube(10);');
+/// const blob = new Blob([model], { type: 'model/3mf' });
+/// ```
+#[wasm_bindgen]
+pub fn render_to_3mf(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<Vec<u8>, JsValue> {
+    let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    manifold_rs::render_with_eval_options(source, &options)
+        .map(|mesh| mesh.to_3mf())
+        .map_err(|e| JsValue::from_str(&format!("Render error: {}", e)))
+}
+
+/// Render OpenSCAD source code to a file in any export format.
+///
+/// Named `export_model` because `export` is reserved in JavaScript.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `format`: `"stl"` (binary), `"3mf"`, `"amf"` (one object per
+///   color), `"glb"` (for Three.js and other web viewers), `"obj"` or
+///   `"off"`, as listed in `get_capabilities().exportFormats`
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// `{ data: Uint8Array, filename }` with a suggested file name such as
+/// `model.obj`, or throws an error string, also for unknown formats.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const { data, filename } = export_model('cube(10);', 'obj');
+/// const link = Object.assign(document.createElement('a'), {
+///     href: URL.createObjectURL(new Blob([data])),
+///     download: filename,
+/// });
+/// link.click();
+/// ```
+#[wasm_bindgen(unchecked_return_type = "ExportedFile")]
+pub fn export_model(
+    source: &str,
+    format: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    let mesh = manifold_rs::render_with_eval_options(source, &options)
+        .map_err(|e| JsValue::from_str(&format!("Render error: {}", e)))?;
+    ExportedFile::of(&mesh, format)
+        .map(ExportedFile::into_js)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Render a 2D design to an SVG or DXF file for laser cutting.
+///
+/// Takes `projection()` results and raw 2D shapes; 3D results are an
+/// error, as for [`render_outlines`].
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `format`: `"svg"` or `"dxf"` (R12), as listed in
+///   `get_capabilities().export2dFormats`
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// `{ data: Uint8Array, filename }` with a suggested file name such as
+/// `model.svg`, or throws an error string.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const { data, filename } = render_2d('projection(cut = true) sphere(10);', 'dxf');
+/// ```
+#[wasm_bindgen(unchecked_return_type = "ExportedFile")]
+pub fn render_2d(
+    source: &str,
+    format: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    let loops = manifold_rs::render_outlines_with_eval_options(source, &options)
+        .map_err(|e| JsValue::from_str(&format!("Render error: {}", e)))?;
+    ExportedFile::of_outlines(&loops, format)
+        .map(ExportedFile::into_js)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Render a 2D design to outline loops for laser cutting and CNC.
+///
+/// No triangulation: each loop is a closed polyline, outer loops
+/// counter-clockwise and holes clockwise.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// Array of loops, or throws an error string (also for 3D results):
+/// - `points`: Float64Array (x, y pairs)
+/// - `hole`: boolean
+/// - `winding`: `"ccw"` or `"cw"`
+/// - `parent`: index of the enclosing outer loop, or `null`
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// for (const loop of render_outlines('difference() { square(20); circle(5); }')) {
+///     path.moveTo(loop.points[0], loop.points[1]);
+/// }
+/// ```
+#[wasm_bindgen(unchecked_return_type = "Outline[]")]
+pub fn render_outlines(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    let loops = manifold_rs::render_outlines_with_eval_options(source, &options)
+        .map_err(|e| JsValue::from_str(&format!("Render error: {}", e)))?;
+
+    let result = js_sys::Array::new();
+    for outline in loops {
+        result.push(&Outline::from(outline).into_js());
     }
+    Ok(result.into())
+}
+
+/// Render OpenSCAD source code and check the mesh for defects.
+///
+/// Flags what would make an export unusable: holes, non-manifold edges,
+/// inverted normals, degenerate triangles and self-intersections, each
+/// with the triangles involved, a point to mark in the viewer and the
+/// module calls that made those triangles.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// `Validation` object, or throws an error string if the render fails:
+/// - `valid`, `watertight`: booleans
+/// - `openEdges`, `nonManifoldEdges`, `invertedNormals`,
+///   `degenerateTriangles`, `selfIntersections`: counts
+/// - `findings`: `{ issue, triangles, position, spans }` objects, with
+///   `spans` the `{ start, end }` of the calls behind the triangles
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const report = validate(source);
+/// if (!report.valid) {
+///     for (const finding of report.findings) {
+///         viewer.addMarker(finding.position, finding.issue);
+///         finding.spans.forEach((span) => editor.underline(span.start.byte, span.end.byte));
+///     }
+/// }
+/// ```
+#[wasm_bindgen(unchecked_return_type = "Validation")]
+pub fn validate(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = EvalOptions { sources: true, ..eval_options(&options).map_err(|e| JsValue::from_str(&e))? };
+    let started = openscad_eval::limits::now_ms();
+    let render_error = |e: manifold_rs::ManifoldError| JsValue::from_str(&format!("Render error: {}", e));
+    let evaluated = openscad_eval::evaluate_with_options(source, &options).map_err(|e| render_error(e.into()))?;
+    let nodes = NodeTable::new(&evaluated.geometry);
+    let mesh = manifold_rs::render_evaluated(&evaluated, &options, started).map_err(render_error)?;
+    Ok(Validation::of(&mesh, &nodes).into_js())
+}
+
+/// Render OpenSCAD source code and measure the mesh.
+///
+/// Checks print volume and mass properties without exporting to an
+/// external tool. Multiply `volume` by the material density for the mass.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// `Measurement` object, or throws an error string if the render fails:
+/// - `volume`: enclosed volume (cubic units)
+/// - `surfaceArea`: total area (square units)
+/// - `centroid`: `[x, y, z]` centre of mass, or `null`
+/// - `genus`: number of handles, or `null` if the mesh is not closed
+/// - `components`: `{ volume, min, max, triangles }` per separate body
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const { volume, centroid } = measure('difference() { cube(20); sphere(12); }');
+/// const grams = volume / 1000 * 1.24;  // PLA, mm³ → cm³
+/// ```
+#[wasm_bindgen(unchecked_return_type = "Measurement")]
+pub fn measure(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    let mesh = manifold_rs::render_with_eval_options(source, &options)
+        .map_err(|e| JsValue::from_str(&format!("Render error: {}", e)))?;
+    Ok(Measurement::of(&mesh).into_js())
+}
+
+/// Evaluate OpenSCAD source code and count its geometry, without meshing.
+///
+/// Cheap next to a render, so a UI can warn about a heavy model before
+/// starting one. Renders report the same counts in their `stats`.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD source code string
+/// - `options`: Optional options object, as for [`render`]
+///
+/// ## Returns
+///
+/// `GeometryStats` object, or throws an error string if evaluation fails:
+/// - `nodes`, `depth`: size and depth of the geometry tree
+/// - `primitives`: primitives by name, e.g. `{ cube: 2, sphere: 1 }`
+/// - `booleans`, `transforms`, `operations`: node counts by category
+/// - `unmeasured`: imports, surfaces and text left out of the estimate
+/// - `estimatedTriangles`: rough triangle count of the mesh
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const { estimatedTriangles } = geometry_stats(source);
+/// if (estimatedTriangles > 1e6) warn(`This model will generate ~${Math.round(estimatedTriangles / 1e6)}M triangles`);
+/// ```
+#[wasm_bindgen(unchecked_return_type = "GeometryStats")]
+pub fn geometry_stats(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    let stats = openscad_eval::evaluate_with_options(source, &options)
+        .map(|evaluated| evaluated.stats())
+        .map_err(|e| JsValue::from_str(&format!("Evaluation error: {}", e)))?;
+    let json = serde_json::to_string(&stats).unwrap_or_default();
+    Ok(js_sys::JSON::parse(&json).unwrap_or(JsValue::NULL))
+}
+
+/// Compile a library file into a precompiled bundle.
+///
+/// Keeps only module/function definitions and top-level assignments. Ship
+/// the returned bytes with the app and load them with `register_library`
+/// to skip parsing large libraries at startup.
+///
+/// ## Parameters
+///
+/// - `source`: OpenSCAD library source code
+///
+/// ## Returns
+///
+/// `Uint8Array` bundle, or throws an error string if parsing fails.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const bundle = compile_library(mcadSource);
+/// register_library(bundle);
+/// const result = render('roundedBox([10,10,5], 1);');
+/// ```
+#[wasm_bindgen]
+pub fn compile_library(source: &str) -> Result<Vec<u8>, JsValue> {
+    openscad_eval::compile_library(source).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Register a precompiled library for all subsequent renders.
+///
+/// ## Parameters
+///
+/// - `bundle`: Bytes returned by `compile_library`
+#[wasm_bindgen]
+pub fn register_library(bundle: &[u8]) -> Result<(), JsValue> {
+    let library =
+        LibraryBundle::from_bytes(bundle).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    LIBRARIES.with(|libs| libs.borrow_mut().push(library));
+    Ok(())
+}
+
+/// Remove all registered libraries.
+#[wasm_bindgen]
+pub fn clear_libraries() {
+    LIBRARIES.with(|libs| libs.borrow_mut().clear());
 }
 
 // =============================================================================
-// RESULT HELPERS
+// FONTS
 // =============================================================================
 
-/// Create a success result with mesh data.
-fn create_success_result(
-    vertices: Vec<f32>,
-    indices: Vec<u32>,
-    normals: Vec<f32>,
-    render_time_ms: f64,
-) -> JsValue {
-    let result = js_sys::Object::new();
+/// Register a TrueType/OpenType font for `text()` in all subsequent renders.
+///
+/// ## Parameters
+///
+/// - `data`: Raw font file bytes
+///
+/// ## Returns
+///
+/// The font family name to pass as `font=`, or throws if the data is not a font.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const bytes = new Uint8Array(await (await fetch('LiberationSans.ttf')).arrayBuffer());
+/// const family = register_font(bytes);
+/// render(`text("Hello", font="${family}");`);
+/// ```
+#[wasm_bindgen]
+pub fn register_font(data: &[u8]) -> Result<String, JsValue> {
+    manifold_rs::font::register_font(data.to_vec()).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// =============================================================================
+// FILES
+// =============================================================================
+
+/// Register a file for `import()`, `surface()`, `include` and `use` in all
+/// subsequent renders.
+///
+/// The browser has no filesystem, so `import("name")`, `surface("name")`,
+/// `include <name>` and `use <name>` resolve against files registered
+/// here. Registering the same name again replaces it. `import()` reads
+/// STL, OBJ and OFF meshes and SVG drawings (2D, for extrusion);
+/// `surface()` reads `.dat` height tables and PNG images.
+///
+/// ## Parameters
+///
+/// - `name`: File name as written in the source
+/// - `data`: Raw file bytes
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const bytes = new Uint8Array(await file.arrayBuffer());
+/// register_file('part.stl', bytes);
+/// render('import("part.stl");');
+///
+/// register_file('logo.svg', new TextEncoder().encode(svgText));
+/// render('linear_extrude(2) import("logo.svg", center = true, $fn = 32);');
+///
+/// register_file('terrain.png', new Uint8Array(await heightmap.arrayBuffer()));
+/// render('scale([1, 1, 0.2]) surface("terrain.png", center = true);');
+/// ```
+#[wasm_bindgen]
+pub fn register_file(name: &str, data: &[u8]) {
+    if let Ok(text) = std::str::from_utf8(data) {
+        SOURCES.with(|files| files.borrow_mut().insert(name, text));
+    }
+    manifold_rs::import::register_file(name, data.to_vec());
+}
+
+/// Remove all files registered with [`register_file`].
+#[wasm_bindgen]
+pub fn clear_files() {
+    SOURCES.with(|files| files.borrow_mut().clear());
+    manifold_rs::import::clear_files();
+}
+
+// =============================================================================
+// CANCELLATION
+// =============================================================================
 
-    // Create typed arrays (zero-copy transfer)
-    let vertices_array = js_sys::Float32Array::from(vertices.as_slice());
-    let indices_array = js_sys::Uint32Array::from(indices.as_slice());
-    let normals_array = js_sys::Float32Array::from(normals.as_slice());
+/// Token that stops a [`render_with_progress`] call.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const cancel = new CancellationToken();
+/// const pending = render_with_progress(source, () => {}, cancel);
+/// cancel.cancel();
+/// console.log(cancel.isCancelled, (await pending).cancelled); // true true
+/// ```
+#[wasm_bindgen(js_name = CancellationToken)]
+#[derive(Debug, Clone, Default)]
+pub struct RenderCancellation(openscad_eval::CancellationToken);
 
-    let vertex_count = (vertices.len() / 3) as u32;
-    let triangle_count = (indices.len() / 3) as u32;
+#[wasm_bindgen(js_class = CancellationToken)]
+impl RenderCancellation {
+    /// A token that has not been cancelled.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    // Set result properties
-    let _ = js_sys::Reflect::set(&result, &"success".into(), &true.into());
-    let _ = js_sys::Reflect::set(&result, &"vertices".into(), &vertices_array);
-    let _ = js_sys::Reflect::set(&result, &"indices".into(), &indices_array);
-    let _ = js_sys::Reflect::set(&result, &"normals".into(), &normals_array);
-    let _ = js_sys::Reflect::set(&result, &"vertexCount".into(), &vertex_count.into());
-    let _ = js_sys::Reflect::set(&result, &"triangleCount".into(), &triangle_count.into());
-    let _ = js_sys::Reflect::set(&result, &"renderTimeMs".into(), &render_time_ms.into());
+    /// Stop every render this token was passed to.
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
 
-    result.into()
+    /// Whether [`RenderCancellation::cancel`] has been called.
+    #[wasm_bindgen(getter, js_name = isCancelled)]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
 }
 
-/// Create an error result.
-fn create_error_result(error: &str) -> JsValue {
-    let result = js_sys::Object::new();
-    let _ = js_sys::Reflect::set(&result, &"success".into(), &false.into());
-    let _ = js_sys::Reflect::set(&result, &"error".into(), &error.into());
-    result.into()
+// =============================================================================
+// SESSIONS
+// =============================================================================
+
+/// Renderer that keeps its work between calls, for live editing.
+///
+/// Each [`RenderSession::update`] returns the same object as [`render`],
+/// but reuses the parsed source and the meshes of top-level children that
+/// did not change since the previous update (see [`session`]). Registered
+/// libraries and files are read when the options are set.
+///
+/// [`RenderSession::pick`] maps a click on the model back to the module
+/// call that made it, so sessions evaluate with source spans.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// const session = new RenderSession({ preview: true });
+/// editor.onChange((source) => {
+///     const result = session.update(source);
+///     if (result.success) {
+///         scene.updateMesh(result.vertices, result.indices, result.normals);
+///     }
+///     console.log(session.stats()); // { parsed, evaluated, reused, meshed }
+/// });
+/// canvas.onClick((origin, direction) => {
+///     const picked = session.pick(origin, direction);
+///     if (picked?.span) {
+///         editor.select(picked.span.start.byte, picked.span.end.byte);
+///     }
+/// });
+/// ```
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct RenderSession(session::Session);
+
+#[wasm_bindgen]
+impl RenderSession {
+    /// A session rendering with `options`, as for [`render`].
+    ///
+    /// Throws an error string if the options are invalid.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+    ) -> Result<RenderSession, JsValue> {
+        let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self(session::Session::new(EvalOptions { sources: true, ..options })))
+    }
+
+    /// Render `source`, redoing only what changed since the last update.
+    #[wasm_bindgen(unchecked_return_type = "RenderResult")]
+    pub fn update(&mut self, source: &str) -> JsValue {
+        let start = js_sys::Date::now();
+        match self.0.update(source) {
+            Ok(output) => {
+                let (messages, diagnostics) = (self.0.messages().to_vec(), self.0.diagnostics().to_vec());
+                RenderResult::success(output, messages, diagnostics, js_sys::Date::now() - start, self.0.output_options()).into_js()
+            }
+            Err(e) => RenderResult::render_error(e, self.0.messages().to_vec(), self.0.diagnostics().to_vec()).into_js(),
+        }
+    }
+
+    /// Render with new options from the next update on.
+    ///
+    /// Throws an error string if the options are invalid.
+    #[wasm_bindgen(js_name = setOptions)]
+    pub fn set_options(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+    ) -> Result<(), JsValue> {
+        let options = eval_options(&options).map_err(|e| JsValue::from_str(&e))?;
+        self.0.set_options(EvalOptions { sources: true, ..options });
+        Ok(())
+    }
+
+    /// The point of the last rendered model under a ray, and the module
+    /// call that made it.
+    ///
+    /// ## Parameters
+    ///
+    /// - `origin`: `[x, y, z]` start of the ray, e.g. the camera position
+    /// - `direction`: `[x, y, z]` direction of the ray, of any length
+    ///
+    /// ## Returns
+    ///
+    /// `PickResult` object, or `null` if the ray misses the model or the
+    /// last update failed:
+    /// - `triangle`: index of the triangle hit in the result's `indices`
+    /// - `point`: `[x, y, z]` point hit
+    /// - `distance`: from `origin` to `point`
+    /// - `node`: id of the geometry node that made the triangle, or `null`
+    /// - `kind`: kind of that node, such as `"cube"`, or `null`
+    /// - `span`: `{ start, end }` of the innermost call holding the point,
+    ///   or `null`
+    ///
+    /// Throws an error string if `origin` or `direction` is not three
+    /// numbers.
+    #[wasm_bindgen(unchecked_return_type = "PickResult | null")]
+    pub fn pick(&self, origin: &[f64], direction: &[f64]) -> Result<JsValue, JsValue> {
+        let point = |v: &[f64], name: &str| {
+            <[f64; 3]>::try_from(v).map_err(|_| JsValue::from_str(&format!("{} must be [x, y, z]", name)))
+        };
+        let picked = self.0.pick(point(origin, "origin")?, point(direction, "direction")?);
+        Ok(picked.map_or(JsValue::NULL, |picked| PickResult::new(picked, self.0.nodes()).into_js()))
+    }
+
+    /// What the last update did.
+    #[wasm_bindgen(unchecked_return_type = "SessionStats")]
+    pub fn stats(&self) -> JsValue {
+        let json = serde_json::to_string(&self.0.stats()).unwrap_or_default();
+        js_sys::JSON::parse(&json).unwrap_or(JsValue::NULL)
+    }
+
+    /// Forget the kept source and meshes.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+// =============================================================================
+// SCHEDULING
+// =============================================================================
+
+#[wasm_bindgen]
+extern "C" {
+    /// Global `setTimeout` (available on window and in workers).
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout: i32) -> JsValue;
+}
+
+/// Suspend until the next macrotask so pending events and paints can run.
+async fn yield_to_event_loop() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, 0);
+    });
+    // setTimeout never rejects
+    let _ = JsFuture::from(promise).await;
 }
 
 // =============================================================================
@@ -178,6 +1251,44 @@ extern "C" {
     fn log(s: &str);
 }
 
+/// Stream `echo()` output, warnings and deprecation notices as renders
+/// evaluate.
+///
+/// The handler is called with `{ level, text, span }` (see `LogLine`)
+/// for each line, in order, while the script is still evaluating, so
+/// output shows up even if the render later fails or is cancelled. The
+/// same lines are in the result's `messages` and `diagnostics` once it
+/// completes. The handler applies to every render from then on,
+/// sessions included; pass `null` to remove it. Lines from a render on
+/// another worker go to that worker's handler.
+///
+/// ## Example (JavaScript)
+///
+/// ```javascript
+/// set_log_handler((line) => {
+///     const where = line.span ? ` (line ${line.span.start.line + 1})` : '';
+///     output.append(`${line.level.toUpperCase()}: ${line.text}${where}\n`);
+/// });
+/// const result = await render_async('echo("hello"); cube(10);');
+/// ```
+#[wasm_bindgen]
+pub fn set_log_handler(
+    #[wasm_bindgen(unchecked_param_type = "((line: LogLine) => void) | null")] handler: JsValue,
+) {
+    let handler = handler.dyn_into::<js_sys::Function>().ok();
+    LOG_HANDLER.with(|slot| *slot.borrow_mut() = handler);
+}
+
+/// Hand a log entry to the handler of `set_log_handler`, if any.
+fn forward_log(entry: openscad_eval::LogEntry<'_>) {
+    // Cloned out so the handler may replace itself
+    let Some(handler) = LOG_HANDLER.with(|slot| slot.borrow().clone()) else {
+        return;
+    };
+    // A throwing handler must not abort the render
+    let _ = handler.call1(&JsValue::NULL, &LogLine::from(entry).into_js());
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -208,6 +1319,45 @@ mod tests {
         assert!(!mesh.indices.is_empty());
     }
 
+    /// Test the AST is reused while the source stays the same.
+    #[test]
+    fn test_cached_ast() {
+        let source = "size = 1; cube(size);";
+        let first = cached_ast(source).unwrap();
+        assert!(Rc::ptr_eq(&first, &cached_ast(source).unwrap()));
+        assert!(!Rc::ptr_eq(&first, &cached_ast("cube(2);").unwrap()));
+        assert!(cached_ast("cube(").is_err());
+
+        let options = EvalOptions {
+            overrides: options::overrides_from_json(r#"{"size": 4}"#).unwrap(),
+            ..EvalOptions::default()
+        };
+        let evaluated = openscad_eval::visitor::evaluate_ast_with_options(&cached_ast(source).unwrap(), &options).unwrap();
+        assert!(matches!(evaluated.geometry, openscad_eval::GeometryNode::Cube { size: [4.0, 4.0, 4.0], .. }));
+    }
+
+    /// Test a geometry tree meshes the same after a trip through JSON.
+    #[test]
+    fn test_evaluated_json_meshes() {
+        let evaluated = openscad_eval::evaluate("difference() { cube(10); sphere(6, $fn = 16); }").unwrap();
+        let restored = EvaluatedAst::from_json(&evaluated.to_json().unwrap()).unwrap();
+        let options = EvalOptions::default();
+        let mesh = |ast: &EvaluatedAst| manifold_rs::render_evaluated(ast, &options, 0.0).unwrap();
+        assert_eq!(mesh(&restored).indices, mesh(&evaluated).indices);
+    }
+
+    /// Test registered libraries are visible to renders.
+    #[test]
+    fn test_registered_library() {
+        let bundle = openscad_eval::compile_library("module box() { cube(2); }").unwrap();
+        register_library(&bundle).unwrap();
+        let mesh = manifold_rs::render_with_libraries("box();", &registered_libraries()).unwrap();
+        clear_libraries();
+
+        assert_eq!(mesh.triangle_count(), 12);
+        assert!(registered_libraries().is_empty());
+    }
+
     /// Test version is not empty.
     #[test]
     fn test_version() {
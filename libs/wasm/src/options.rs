@@ -0,0 +1,447 @@
+//! # Render Options
+//!
+//! The options object accepted by `render()` and `render_async()`.
+//!
+//! ## Shape (JavaScript)
+//!
+//! ```javascript
+//! render(source, {
+//!     overrides: { size: 20, label: "A-01", dims: [10, 20, 5] },
+//!     t: 0.25,                    // $t, for animations
+//!     preview: false,             // $preview
+//!     previewQuality: 0.5,        // half the segments while previewing
+//!     renderQuality: 2,           // twice the segments for the final render
+//!     vpr: [55, 0, 25], vpt: [0, 0, 0], vpd: 140, vpf: 22.5,  // viewport
+//!     seed: 42,                   // same rands() output on every render
+//!     mergeCoplanar: true,        // merge flat faces after booleans
+//!     targetTriangles: 5000,      // decimate down to this many triangles
+//!     normals: "smooth",          // "flat" or "smooth" shading
+//!     creaseAngle: 40,            // keep edges sharper than 40° sharp
+//!     maxTimeMs: 10000,           // give up after 10 seconds
+//!     maxIterations: 1000000,     // loop iterations, over all loops
+//!     backend: "auto",            // "bsp", "intersect" or "auto"
+//!     precision: "double",        // vertices as a Float64Array
+//!     featureAngle: 30,           // outline edges sharper than 30°
+//!     extensions: true,           // torus(), refine(), warp_twist(), …
+//! });
+//! ```
+//!
+//! ## Quality
+//!
+//! `previewQuality` and `renderQuality` multiply every segment count,
+//! `$fn` included, while `preview` is true or false respectively; both
+//! default to 1. Render with `preview: true` while a slider is dragged
+//! and `preview: false` for export.
+//!
+//! ## Normals
+//!
+//! `normals` recomputes the vertex normals of the finished mesh:
+//! `"flat"` gives each face its own normal, `"smooth"` averages them over
+//! the faces around each vertex, so spheres and cylinders shade round,
+//! except across edges where faces meet at more than `creaseAngle`
+//! degrees (30 by default). Either way corners that shade alike share a
+//! vertex. Left out, the normals are those meshing produced.
+//!
+//! ## Booleans
+//!
+//! `backend` picks the boolean algorithm: BSP trees (the default),
+//! intersection splitting, or `"auto"`, which splits by intersection once
+//! two operands have `autoThreshold` triangles together (1000 by
+//! default). `weldTolerance` is the distance within which boolean results
+//! weld vertices, 1e-4 by default. `cacheSize` caps the subtree meshes a
+//! render session keeps. `sphereStyle` meshes spheres as OpenSCAD's rings
+//! (`"uv"`, the default), an `"icosphere"` or a `"cube_sphere"`; the
+//! latter two have no thin triangles at the poles and cut more cleanly.
+//!
+//! ## Precision
+//!
+//! Meshing runs in double precision. `precision` picks how the vertex
+//! positions of the result are handed over: `"single"` (the default) as a
+//! `Float32Array`, ready for a WebGL buffer, or `"double"` as a
+//! `Float64Array`, for models whose small detail sits far from the
+//! origin. Normals, colors and streamed chunks are always single.
+//!
+//! ## Feature Edges
+//!
+//! `featureAngle` adds `edges` to the result: a line list of the edges
+//! where faces meet at more than that many degrees, plus any boundary
+//! edges, in the precision of the vertices. Draw it as line segments over
+//! the shaded mesh for an outlined look. Left out, `edges` is `null`.
+//!
+//! ## Extensions
+//!
+//! `extensions: true` adds the primitives `torus()`, `prism()` and
+//! `rounded_cube()` and the operations `refine()`, `warp_twist()`,
+//! `warp_taper()` and `warp_bend()`, which OpenSCAD does not have. Off by default, so scripts render as they would in OpenSCAD.
+//!
+//! ## Limits
+//!
+//! `maxTimeMs`, `maxIterations`, `maxNodes`, `maxTriangles` and
+//! `maxMemoryBytes` abort a runaway render with a failure whose `limit`
+//! names the limit and the statement that tripped it (see
+//! `LimitExceeded`); they are off when left out. `maxRecursionDepth`
+//! bounds nested user function and module calls, 10,000 by default.
+//!
+//! To animate, render once per frame with a new `t`:
+//!
+//! ```javascript
+//! for (let frame = 0; frame < 24; frame++) {
+//!     const result = render(source, { t: frame / 24 });
+//! }
+//! ```
+//!
+//! All fields are optional; `undefined` or `null` options mean defaults.
+
+use std::collections::BTreeMap;
+
+use openscad_eval::options::value_from_json;
+use openscad_eval::{CsgBackend, CsgOptions, SphereStyle, EvalOptions, EvalParams, LibraryBundle, Limits, NormalMode, OutputOptions, Overrides, Precision, QualityOptions, SimplifyOptions};
+use serde::Deserialize;
+use ts_rs::TS;
+
+/// Options object passed from JavaScript.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderOptions {
+    /// Top-level variable overrides (`-D name=value`): numbers, booleans,
+    /// strings, `null` or arrays of them.
+    #[serde(default)]
+    #[ts(as = "Option<BTreeMap<String, serde_json::Value>>", optional)]
+    pub overrides: BTreeMap<String, serde_json::Value>,
+    /// `$t`: animation time, from 0 to 1.
+    #[serde(default)]
+    #[ts(optional)]
+    pub t: Option<f64>,
+    /// `$preview`: `true` (the default) for a quick preview, `false` for
+    /// a final render.
+    #[serde(default)]
+    #[ts(optional)]
+    pub preview: Option<bool>,
+    /// Segment multiplier while `preview` is true.
+    #[serde(default)]
+    #[ts(optional)]
+    pub preview_quality: Option<f64>,
+    /// Segment multiplier while `preview` is false.
+    #[serde(default)]
+    #[ts(optional)]
+    pub render_quality: Option<f64>,
+    /// `$vpr`: viewport rotation in degrees.
+    #[serde(default)]
+    #[ts(optional)]
+    pub vpr: Option<[f64; 3]>,
+    /// `$vpt`: viewport translation.
+    #[serde(default)]
+    #[ts(optional)]
+    pub vpt: Option<[f64; 3]>,
+    /// `$vpd`: viewport camera distance.
+    #[serde(default)]
+    #[ts(optional)]
+    pub vpd: Option<f64>,
+    /// `$vpf`: viewport field of view in degrees.
+    #[serde(default)]
+    #[ts(optional)]
+    pub vpf: Option<f64>,
+    /// Seed of unseeded `rands()` calls; left out, they differ per render.
+    #[serde(default)]
+    #[ts(optional, as = "Option<f64>")]
+    pub seed: Option<u64>,
+    /// Merge adjacent coplanar triangles of the finished mesh into larger
+    /// faces. Off by default.
+    #[serde(default)]
+    #[ts(optional)]
+    pub merge_coplanar: Option<bool>,
+    /// Decimate the finished mesh down to at most this many triangles.
+    #[serde(default)]
+    #[ts(optional)]
+    pub target_triangles: Option<u32>,
+    /// Flat or smooth vertex normals.
+    #[serde(default)]
+    #[ts(optional)]
+    pub normals: Option<NormalMode>,
+    /// Angle in degrees above which smooth normals keep an edge sharp.
+    #[serde(default)]
+    #[ts(optional)]
+    pub crease_angle: Option<f64>,
+    /// Maximum wall-clock time of the render in milliseconds.
+    #[serde(default)]
+    #[ts(optional, as = "Option<f64>")]
+    pub max_time_ms: Option<u64>,
+    /// Maximum loop iterations, summed over all loops.
+    #[serde(default)]
+    #[ts(optional, as = "Option<f64>")]
+    pub max_iterations: Option<u64>,
+    /// Maximum geometry nodes produced by module calls.
+    #[serde(default)]
+    #[ts(optional, as = "Option<f64>")]
+    pub max_nodes: Option<u64>,
+    /// Maximum triangles in the final mesh.
+    #[serde(default)]
+    #[ts(optional, as = "Option<f64>")]
+    pub max_triangles: Option<u64>,
+    /// Maximum bytes of the final mesh buffers.
+    #[serde(default)]
+    #[ts(optional, as = "Option<f64>")]
+    pub max_memory_bytes: Option<u64>,
+    /// Deepest nesting of user function and module calls.
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_recursion_depth: Option<u32>,
+    /// Algorithm of each boolean.
+    #[serde(default)]
+    #[ts(optional)]
+    pub backend: Option<CsgBackend>,
+    /// Operand triangles from which the `auto` backend splits by
+    /// intersection.
+    #[serde(default)]
+    #[ts(optional)]
+    pub auto_threshold: Option<u32>,
+    /// Distance within which boolean results weld vertices.
+    #[serde(default)]
+    #[ts(optional)]
+    pub weld_tolerance: Option<f64>,
+    /// Most subtree meshes a render session keeps between updates.
+    #[serde(default)]
+    #[ts(optional)]
+    pub cache_size: Option<u32>,
+    /// Tessellation of spheres.
+    #[serde(default)]
+    #[ts(optional)]
+    pub sphere_style: Option<SphereStyle>,
+    /// Enable the extension primitives.
+    #[serde(default)]
+    #[ts(optional)]
+    pub extensions: Option<bool>,
+    /// Precision of the result's vertex positions.
+    #[serde(default)]
+    #[ts(optional)]
+    pub precision: Option<Precision>,
+    /// Angle in degrees above which edges are handed out as outlines.
+    #[serde(default)]
+    #[ts(optional)]
+    pub feature_angle: Option<f64>,
+}
+
+impl RenderOptions {
+    /// Parse options from their JSON text.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid render options: {}", e))
+    }
+
+    /// Build evaluator options on top of the registered libraries.
+    pub fn to_eval_options(&self, libraries: Vec<LibraryBundle>) -> Result<EvalOptions, String> {
+        let defaults = EvalParams::default();
+        let params = EvalParams {
+            t: self.t.unwrap_or(defaults.t),
+            preview: self.preview.unwrap_or(defaults.preview),
+            vpr: self.vpr.unwrap_or(defaults.vpr),
+            vpt: self.vpt.unwrap_or(defaults.vpt),
+            vpd: self.vpd.unwrap_or(defaults.vpd),
+            vpf: self.vpf.unwrap_or(defaults.vpf),
+            seed: self.seed,
+        };
+        let quality_defaults = QualityOptions::default();
+        let quality = QualityOptions {
+            preview: self.preview_quality.unwrap_or(quality_defaults.preview),
+            render: self.render_quality.unwrap_or(quality_defaults.render),
+        };
+        let simplify = SimplifyOptions {
+            merge_coplanar: self.merge_coplanar.unwrap_or(false),
+            target_triangles: self.target_triangles.map(|n| n as usize),
+            normals: self.normals,
+            crease_angle: self.crease_angle,
+        };
+        let limits = Limits {
+            max_time_ms: self.max_time_ms,
+            max_iterations: self.max_iterations,
+            max_nodes: self.max_nodes,
+            max_triangles: self.max_triangles,
+            max_memory_bytes: self.max_memory_bytes,
+        };
+        let csg = CsgOptions {
+            backend: self.backend,
+            auto_threshold: self.auto_threshold.map(|n| n as usize),
+            weld_tolerance: self.weld_tolerance.map(|d| d as f32),
+            cache_size: self.cache_size.map(|n| n as usize),
+            sphere: self.sphere_style,
+        };
+        Ok(EvalOptions {
+            libraries,
+            params,
+            quality,
+            simplify,
+            limits,
+            csg,
+            extensions: self.extensions.unwrap_or(false),
+            max_recursion_depth: self.max_recursion_depth.map(|n| n as usize),
+            overrides: convert_overrides(&self.overrides)?,
+            output: OutputOptions {
+                precision: self.precision.unwrap_or_default(),
+                feature_angle: self.feature_angle,
+            },
+            ..EvalOptions::default()
+        })
+    }
+}
+
+/// Parse a `{ name: value }` object of overrides from its JSON text, as
+/// passed to `render_with_overrides()`.
+pub fn overrides_from_json(json: &str) -> Result<Overrides, String> {
+    let overrides: BTreeMap<String, serde_json::Value> = serde_json::from_str(json)
+        .map_err(|e| format!("Invalid overrides: {}", e))?;
+    convert_overrides(&overrides)
+}
+
+/// Convert JSON override values to evaluator values.
+fn convert_overrides(overrides: &BTreeMap<String, serde_json::Value>) -> Result<Overrides, String> {
+    overrides.iter()
+        .map(|(name, json)| {
+            let value = value_from_json(json)
+                .map_err(|e| format!("Invalid override {}: {}", name, e))?;
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openscad_eval::Value;
+
+    /// Test overrides are converted to evaluator values.
+    #[test]
+    fn test_overrides() {
+        let options = RenderOptions::from_json(r#"{"overrides": {"size": 20, "label": "A"}}"#).unwrap();
+        let eval = options.to_eval_options(Vec::new()).unwrap();
+        assert_eq!(eval.overrides["size"], Value::Number(20.0));
+        assert_eq!(eval.overrides["label"], Value::String("A".to_string()));
+    }
+
+    /// Test a bare overrides object is parsed like the `overrides` option.
+    #[test]
+    fn test_overrides_from_json() {
+        let overrides = overrides_from_json(r#"{"size": 20, "dims": [1, 2]}"#).unwrap();
+        assert_eq!(overrides["size"], Value::Number(20.0));
+        assert_eq!(overrides["dims"], Value::List(vec![Value::Number(1.0), Value::Number(2.0)]));
+        assert!(overrides_from_json(r#"{"size": {"x": 1}}"#).is_err());
+        assert!(overrides_from_json("[1]").is_err());
+    }
+
+    /// Test special variables are passed through and default otherwise.
+    #[test]
+    fn test_params() {
+        let options = RenderOptions::from_json(r#"{"t": 0.25, "preview": false, "vpr": [0, 0, 90]}"#).unwrap();
+        let params = options.to_eval_options(Vec::new()).unwrap().params;
+        assert_eq!(params.t, 0.25);
+        assert!(!params.preview);
+        assert_eq!(params.vpr, [0.0, 0.0, 90.0]);
+        assert_eq!(params.vpd, EvalParams::default().vpd);
+        assert_eq!(params.seed, None);
+        let seeded = RenderOptions::from_json(r#"{"seed": 42}"#).unwrap();
+        assert_eq!(seeded.to_eval_options(Vec::new()).unwrap().params.seed, Some(42));
+
+        let result = openscad_eval::evaluate_with_options(
+            "translate([$t * 100, 0, 0]) cube(1);",
+            &RenderOptions::from_json(r#"{"t": 0.5}"#).unwrap().to_eval_options(Vec::new()).unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(result.geometry, openscad_eval::GeometryNode::Translate { offset: [50.0, 0.0, 0.0], .. }));
+    }
+
+    /// Test quality levels reach the evaluator and follow `preview`.
+    #[test]
+    fn test_quality() {
+        let options = RenderOptions::from_json(r#"{"previewQuality": 0.5, "renderQuality": 2}"#).unwrap();
+        let eval = options.to_eval_options(Vec::new()).unwrap();
+        assert_eq!(eval.quality, QualityOptions { preview: 0.5, render: 2.0 });
+        let mesh = manifold_rs::render_with_eval_options("sphere(5, $fn = 20);", &eval).unwrap();
+        let fine = RenderOptions::from_json(r#"{"preview": false, "previewQuality": 0.5, "renderQuality": 2}"#).unwrap();
+        let fine = manifold_rs::render_with_eval_options("sphere(5, $fn = 20);", &fine.to_eval_options(Vec::new()).unwrap()).unwrap();
+        assert!(fine.triangle_count() > mesh.triangle_count());
+        let defaults = RenderOptions::from_json("{}").unwrap().to_eval_options(Vec::new()).unwrap();
+        assert_eq!(defaults.quality, QualityOptions::default());
+    }
+
+    /// Test mesh clean-up options reach the evaluator options.
+    #[test]
+    fn test_simplify() {
+        let options = RenderOptions::from_json(r#"{"mergeCoplanar": true, "targetTriangles": 500}"#).unwrap();
+        let simplify = options.to_eval_options(Vec::new()).unwrap().simplify;
+        assert_eq!(simplify, SimplifyOptions { merge_coplanar: true, target_triangles: Some(500), ..SimplifyOptions::default() });
+        let options = RenderOptions::from_json(r#"{"normals": "smooth", "creaseAngle": 45}"#).unwrap();
+        let simplify = options.to_eval_options(Vec::new()).unwrap().simplify;
+        assert_eq!((simplify.normals, simplify.crease_angle), (Some(NormalMode::Smooth), Some(45.0)));
+        assert!(RenderOptions::from_json(r#"{"normals": "gouraud"}"#).is_err());
+        let defaults = RenderOptions::from_json("{}").unwrap().to_eval_options(Vec::new()).unwrap();
+        assert_eq!(defaults.simplify, SimplifyOptions::default());
+    }
+
+    /// Test limits reach the evaluator and trip with the offending loop.
+    #[test]
+    fn test_limits() {
+        let options = RenderOptions::from_json(r#"{"maxTimeMs": 5000, "maxIterations": 1000, "maxRecursionDepth": 50}"#).unwrap();
+        let eval = options.to_eval_options(Vec::new()).unwrap();
+        assert_eq!(eval.limits, Limits { max_time_ms: Some(5000), max_iterations: Some(1000), ..Limits::default() });
+        assert_eq!(eval.max_recursion_depth, Some(50));
+
+        let error = manifold_rs::render_with_eval_options("cube(1);\nfor (i = [0:1e9]) cube(i);", &eval).unwrap_err();
+        let manifold_rs::ManifoldError::LimitExceeded(report) = error else {
+            panic!("expected a limit report, got {:?}", error);
+        };
+        assert_eq!(report.kind, openscad_eval::LimitKind::Iterations);
+        assert_eq!(report.construct.unwrap().span.start.line, 1);
+
+        let defaults = RenderOptions::from_json("{}").unwrap().to_eval_options(Vec::new()).unwrap();
+        assert_eq!(defaults.limits, Limits::default());
+        assert_eq!(defaults.max_recursion_depth, None);
+    }
+
+    /// Test boolean settings reach the evaluator options.
+    #[test]
+    fn test_csg() {
+        let options = RenderOptions::from_json(r#"{"backend": "auto", "autoThreshold": 200, "weldTolerance": 0.001, "cacheSize": 64}"#).unwrap();
+        let csg = options.to_eval_options(Vec::new()).unwrap().csg;
+        assert_eq!(csg, CsgOptions { backend: Some(CsgBackend::Auto), auto_threshold: Some(200), weld_tolerance: Some(0.001), cache_size: Some(64), sphere: None });
+        let defaults = RenderOptions::from_json("{}").unwrap().to_eval_options(Vec::new()).unwrap();
+        assert_eq!(defaults.csg, CsgOptions::default());
+        assert!(RenderOptions::from_json(r#"{"backend": "cgal"}"#).is_err());
+        let styled = RenderOptions::from_json(r#"{"sphereStyle": "cube_sphere"}"#).unwrap();
+        assert_eq!(styled.to_eval_options(Vec::new()).unwrap().csg.sphere, Some(SphereStyle::CubeSphere));
+    }
+
+    /// Test the extensions flag reaches the evaluator and is off by default.
+    #[test]
+    fn test_extensions() {
+        let options = RenderOptions::from_json(r#"{"extensions": true}"#).unwrap();
+        assert!(options.to_eval_options(Vec::new()).unwrap().extensions);
+        assert!(!RenderOptions::from_json("{}").unwrap().to_eval_options(Vec::new()).unwrap().extensions);
+    }
+
+    /// Test the precision reaches the evaluator options and is single by default.
+    #[test]
+    fn test_precision() {
+        let options = RenderOptions::from_json(r#"{"precision": "double"}"#).unwrap();
+        assert_eq!(options.to_eval_options(Vec::new()).unwrap().output.precision, Precision::Double);
+        assert_eq!(RenderOptions::from_json("{}").unwrap().to_eval_options(Vec::new()).unwrap().output.precision, Precision::Single);
+        assert!(RenderOptions::from_json(r#"{"precision": "half"}"#).is_err());
+    }
+
+    /// Test the feature angle reaches the evaluator options and is off by default.
+    #[test]
+    fn test_feature_angle() {
+        let options = RenderOptions::from_json(r#"{"featureAngle": 45}"#).unwrap();
+        assert_eq!(options.to_eval_options(Vec::new()).unwrap().output.feature_angle, Some(45.0));
+        assert_eq!(RenderOptions::from_json("{}").unwrap().to_eval_options(Vec::new()).unwrap().output.feature_angle, None);
+    }
+
+    /// Test an empty object means defaults and bad values are rejected.
+    #[test]
+    fn test_defaults_and_errors() {
+        assert!(RenderOptions::from_json("{}").unwrap().overrides.is_empty());
+        let bad = RenderOptions::from_json(r#"{"overrides": {"x": {"y": 1}}}"#).unwrap();
+        assert!(bad.to_eval_options(Vec::new()).is_err());
+    }
+}
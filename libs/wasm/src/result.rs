@@ -0,0 +1,911 @@
+//! # Render Results
+//!
+//! The objects the render functions hand back to JavaScript.
+//!
+//! ## Shape (JavaScript)
+//!
+//! ```javascript
+//! // render() / render_async(); vertices and edges are Float64Arrays with `precision: "double"`
+//! { success: true, vertices, indices, normals, colors | null, edges | null, vertexCount, triangleCount, renderTimeMs, timings,
+//!   stats, messages, diagnostics, highlighted: { vertices, indices, normals } | null,
+//!   transparent: { vertices, indices, normals } | null }
+//! { success: false, error: "Render error: …", limit: { kind, limit, observed, construct } | null, cancelled, messages,
+//!   diagnostics }
+//!
+//! // edges, with `featureAngle` set: line list of sharp and boundary edges, two points each
+//! [x0, y0, z0, x1, y1, z1, ...]
+//!
+//! // timings: milliseconds per stage, and per CSG node by pre-order position in the geometry tree
+//! { parseMs, astMs, evalMs, csg: [{ node: 2, depth: 1, kind: "difference", ms }], meshMs }
+//!
+//! // stats: counts of the geometry tree, and geometry_stats() before meshing
+//! { nodes, depth, primitives: { cube: 2, sphere: 1 }, booleans, transforms, operations, unmeasured,
+//!   estimatedTriangles }
+//!
+//! // messages: echo() output, in order
+//! [{ kind: "echo", text: "\"size\", 10", span: { start, end } | null }]
+//!
+//! // diagnostics: warnings of the evaluation, in order
+//! [{ kind: "ignored_argument", text: "cube(): variable r not specified as parameter", span: { start, end } | null }]
+//!
+//! // set_log_handler() handler, as evaluation runs
+//! { level: "echo" | "warning" | "deprecated", text, span: { start, end } | null }
+//!
+//! // render_outlines(), one per loop
+//! { points, hole, winding: "ccw" | "cw", parent }
+//!
+//! // validate()
+//! { valid, watertight, openEdges, nonManifoldEdges, invertedNormals, degenerateTriangles,
+//!   selfIntersections, findings: [{ issue: "open-edge", triangles: [12], position: [x, y, z],
+//!   spans: [{ start, end }] }] }
+//!
+//! // measure()
+//! { volume, surfaceArea, centroid: [x, y, z] | null, genus: number | null,
+//!   components: [{ volume, min: [x, y, z], max: [x, y, z], triangles }] }
+//!
+//! // RenderSession.pick(), or null on a miss
+//! { triangle, point: [x, y, z], distance, node: number | null, kind: "cube" | null,
+//!   span: { start, end } | null }
+//!
+//! // export_model(), render_2d()
+//! { data: Uint8Array, filename: "model.obj" }
+//! ```
+//!
+//! Each type is converted to its JavaScript object here and also derives
+//! its TypeScript declaration (see [`crate::typescript`]), so the two
+//! cannot drift apart.
+
+use manifold_rs::mesh::validate::{Finding, Issue};
+use manifold_rs::openscad::drawing::{export_outlines, EXPORT_2D_FORMATS};
+use manifold_rs::openscad::pick::Pick;
+use manifold_rs::openscad::provenance::NodeTable;
+use manifold_rs::{ManifoldError, Mesh, OutlineLoop, RenderOutput, Winding};
+use openscad_ast::Span;
+use openscad_eval::{Diagnostic, DiagnosticKind, GeometryStats, LimitExceeded, LogEntry, Message, OutputOptions, Precision, Timings};
+use serde::Serialize;
+use ts_rs::TS;
+use wasm_bindgen::JsValue;
+
+// =============================================================================
+// MESH RESULTS
+// =============================================================================
+
+/// Mesh from a successful render, as typed arrays.
+#[derive(Debug, Clone, TS)]
+#[ts(rename_all = "camelCase")]
+pub struct RenderSuccess {
+    /// Always `true`.
+    #[ts(type = "true")]
+    pub success: bool,
+    /// Vertex positions (x, y, z), in the precision of the options.
+    #[ts(type = "Float32Array | Float64Array")]
+    pub vertices: Positions,
+    /// Triangle indices.
+    #[ts(type = "Uint32Array")]
+    pub indices: Vec<u32>,
+    /// Vertex normals (x, y, z).
+    #[ts(type = "Float32Array")]
+    pub normals: Vec<f32>,
+    /// Vertex colors (r, g, b, a) from `color()`, or `null` if nothing is
+    /// colored; uncolored parts of a colored mesh are white.
+    #[ts(type = "Float32Array | null")]
+    pub colors: Option<Vec<f32>>,
+    /// Sharp and boundary edges as a line list, two positions per edge,
+    /// or `null` unless the options ask for them.
+    #[ts(type = "Float32Array | Float64Array | null")]
+    pub edges: Option<Positions>,
+    /// Number of vertices.
+    pub vertex_count: u32,
+    /// Number of triangles.
+    pub triangle_count: u32,
+    /// Wall-clock time of the render in milliseconds.
+    pub render_time_ms: f64,
+    /// Where that time went: parsing, evaluation, each CSG node and
+    /// meshing.
+    pub timings: Timings,
+    /// Counts and estimated triangles of the geometry tree.
+    pub stats: GeometryStats,
+    /// Console output of the script.
+    pub messages: Vec<Message>,
+    /// Warnings of the evaluation.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Geometry under `#`, part of the mesh above, or `null` if none.
+    pub highlighted: Option<PreviewMesh>,
+    /// Geometry under `%`, not part of the mesh above, or `null` if none.
+    pub transparent: Option<PreviewMesh>,
+}
+
+/// A preview layer of a successful render, as typed arrays.
+///
+/// Layers are for display only: a viewer draws `highlighted` in an
+/// accent color over the model and `transparent` as a ghost.
+#[derive(Debug, Clone, TS)]
+pub struct PreviewMesh {
+    /// Vertex positions (x, y, z), in the precision of the options.
+    #[ts(type = "Float32Array | Float64Array")]
+    pub vertices: Positions,
+    /// Triangle indices.
+    #[ts(type = "Uint32Array")]
+    pub indices: Vec<u32>,
+    /// Vertex normals (x, y, z).
+    #[ts(type = "Float32Array")]
+    pub normals: Vec<f32>,
+}
+
+impl PreviewMesh {
+    /// The layer for `mesh`, or `None` if it is empty.
+    fn of(mesh: Mesh, precision: Precision) -> Option<Self> {
+        (!mesh.is_empty()).then(|| Self {
+            vertices: Positions::of(mesh.vertices, precision),
+            indices: mesh.indices,
+            normals: mesh.normals,
+        })
+    }
+
+    /// Convert to the JavaScript object.
+    fn into_js(self) -> JsValue {
+        let result = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&result, &"vertices".into(), &self.vertices.to_js());
+        let _ = js_sys::Reflect::set(&result, &"indices".into(), &js_sys::Uint32Array::from(self.indices.as_slice()));
+        let _ = js_sys::Reflect::set(&result, &"normals".into(), &js_sys::Float32Array::from(self.normals.as_slice()));
+        result.into()
+    }
+}
+
+/// Vertex positions (x, y, z), narrowed to `f32` unless the options ask
+/// for double precision.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Positions {
+    /// Positions for a `Float32Array`.
+    Single(Vec<f32>),
+    /// Positions for a `Float64Array`, as meshed.
+    Double(Vec<f64>),
+}
+
+impl Positions {
+    /// The mesh positions `vertices` in `precision`.
+    pub fn of(vertices: Vec<f64>, precision: Precision) -> Self {
+        match precision {
+            Precision::Single => Positions::Single(vertices.iter().map(|&c| c as f32).collect()),
+            Precision::Double => Positions::Double(vertices),
+        }
+    }
+
+    /// Number of coordinates, three per vertex.
+    pub fn len(&self) -> usize {
+        match self {
+            Positions::Single(v) => v.len(),
+            Positions::Double(v) => v.len(),
+        }
+    }
+
+    /// Whether there are no positions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy into a JavaScript typed array.
+    fn to_js(&self) -> JsValue {
+        match self {
+            Positions::Single(v) => js_sys::Float32Array::from(v.as_slice()).into(),
+            Positions::Double(v) => js_sys::Float64Array::from(v.as_slice()).into(),
+        }
+    }
+}
+
+/// Why a render failed.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RenderFailure {
+    /// Always `false`.
+    #[ts(type = "false")]
+    pub success: bool,
+    /// Human-readable message.
+    pub error: String,
+    /// The limit that aborted the render, or `null`.
+    pub limit: Option<LimitExceeded>,
+    /// Whether the render was stopped through its `CancellationToken`.
+    pub cancelled: bool,
+    /// Console output of the script, if evaluation got far enough to
+    /// finish; empty when evaluation itself failed.
+    pub messages: Vec<Message>,
+    /// Warnings of the evaluation, like `messages`.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Result of `render()` and `render_async()`; check `success` first.
+#[derive(Debug, Clone, TS)]
+#[ts(untagged)]
+pub enum RenderResult {
+    /// The mesh.
+    Success(Box<RenderSuccess>),
+    /// The error.
+    Failure(RenderFailure),
+}
+
+impl RenderResult {
+    /// Successful result for a render and the script's console output
+    /// and warnings, handed out as `options` asks.
+    pub fn success(
+        output: RenderOutput,
+        messages: Vec<Message>,
+        diagnostics: Vec<Diagnostic>,
+        render_time_ms: f64,
+        options: OutputOptions,
+    ) -> Self {
+        let mesh = output.solid;
+        let precision = options.precision;
+        let edges = options.feature_angle.map(|angle| Positions::of(mesh.feature_edges(angle), precision));
+        RenderResult::Success(Box::new(RenderSuccess {
+            success: true,
+            vertex_count: (mesh.vertices.len() / 3) as u32,
+            triangle_count: (mesh.indices.len() / 3) as u32,
+            vertices: Positions::of(mesh.vertices, precision),
+            indices: mesh.indices,
+            normals: mesh.normals,
+            colors: mesh.colors,
+            edges,
+            render_time_ms,
+            timings: output.timings,
+            stats: output.stats,
+            messages,
+            diagnostics,
+            highlighted: PreviewMesh::of(output.highlighted, precision),
+            transparent: PreviewMesh::of(output.transparent, precision),
+        }))
+    }
+
+    /// Failed result with a plain message.
+    pub fn failure(error: &str) -> Self {
+        RenderResult::Failure(RenderFailure {
+            success: false,
+            error: error.to_string(),
+            limit: None,
+            cancelled: false,
+            messages: Vec::new(),
+            diagnostics: Vec::new(),
+        })
+    }
+
+    /// Failed result for a pipeline error, keeping limit reports and
+    /// cancellation structured.
+    ///
+    /// `messages` and `diagnostics` are the console output and warnings of
+    /// the evaluation, if it finished.
+    pub fn render_error(error: ManifoldError, messages: Vec<Message>, diagnostics: Vec<Diagnostic>) -> Self {
+        let limit = match &error {
+            ManifoldError::LimitExceeded(report) => Some(LimitExceeded::clone(report)),
+            _ => None,
+        };
+        RenderResult::Failure(RenderFailure {
+            success: false,
+            error: format!("Render error: {}", error),
+            limit,
+            cancelled: matches!(error, ManifoldError::Cancelled),
+            messages,
+            diagnostics,
+        })
+    }
+
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        match self {
+            RenderResult::Success(mesh) => {
+                let result = js_sys::Object::new();
+                // Typed arrays are copied once out of WASM memory
+                let _ = js_sys::Reflect::set(&result, &"success".into(), &true.into());
+                let _ = js_sys::Reflect::set(&result, &"vertices".into(), &mesh.vertices.to_js());
+                let _ = js_sys::Reflect::set(&result, &"indices".into(), &js_sys::Uint32Array::from(mesh.indices.as_slice()));
+                let _ = js_sys::Reflect::set(&result, &"normals".into(), &js_sys::Float32Array::from(mesh.normals.as_slice()));
+                let colors = mesh.colors.map_or(JsValue::NULL, |c| js_sys::Float32Array::from(c.as_slice()).into());
+                let _ = js_sys::Reflect::set(&result, &"colors".into(), &colors);
+                let edges = mesh.edges.map_or(JsValue::NULL, |e| e.to_js());
+                let _ = js_sys::Reflect::set(&result, &"edges".into(), &edges);
+                let _ = js_sys::Reflect::set(&result, &"vertexCount".into(), &mesh.vertex_count.into());
+                let _ = js_sys::Reflect::set(&result, &"triangleCount".into(), &mesh.triangle_count.into());
+                let _ = js_sys::Reflect::set(&result, &"renderTimeMs".into(), &mesh.render_time_ms.into());
+                let timings = serde_json::to_string(&mesh.timings).unwrap_or_default();
+                let _ = js_sys::Reflect::set(&result, &"timings".into(), &js_sys::JSON::parse(&timings).unwrap_or(JsValue::NULL));
+                let stats = serde_json::to_string(&mesh.stats).unwrap_or_default();
+                let _ = js_sys::Reflect::set(&result, &"stats".into(), &js_sys::JSON::parse(&stats).unwrap_or(JsValue::NULL));
+                let messages = serde_json::to_string(&mesh.messages).unwrap_or_default();
+                let _ = js_sys::Reflect::set(&result, &"messages".into(), &js_sys::JSON::parse(&messages).unwrap_or(JsValue::NULL));
+                let diagnostics = serde_json::to_string(&mesh.diagnostics).unwrap_or_default();
+                let _ = js_sys::Reflect::set(&result, &"diagnostics".into(), &js_sys::JSON::parse(&diagnostics).unwrap_or(JsValue::NULL));
+                for (name, layer) in [("highlighted", mesh.highlighted), ("transparent", mesh.transparent)] {
+                    let _ = js_sys::Reflect::set(&result, &name.into(), &layer.map_or(JsValue::NULL, PreviewMesh::into_js));
+                }
+                result.into()
+            }
+            RenderResult::Failure(failure) => {
+                let json = serde_json::to_string(&failure).unwrap_or_default();
+                js_sys::JSON::parse(&json).unwrap_or(JsValue::NULL)
+            }
+        }
+    }
+}
+
+// =============================================================================
+// EXPORTS
+// =============================================================================
+
+/// A rendered model encoded as a file, from `export_model()` or
+/// `render_2d()`.
+#[derive(Debug, Clone, TS)]
+pub struct ExportedFile {
+    /// File contents.
+    #[ts(type = "Uint8Array")]
+    pub data: Vec<u8>,
+    /// Suggested file name, e.g. `model.obj`.
+    pub filename: String,
+}
+
+impl ExportedFile {
+    /// Encode `mesh` in `format`, one of the formats in
+    /// `manifold_rs::mesh::EXPORT_FORMATS`.
+    pub fn of(mesh: &Mesh, format: &str) -> Result<Self, String> {
+        let format = format.to_ascii_lowercase();
+        let data = mesh.export(&format).ok_or_else(|| {
+            format!("Unsupported export format: {} (expected one of {})", format, manifold_rs::mesh::EXPORT_FORMATS.join(", "))
+        })?;
+        Ok(Self { data, filename: format!("model.{}", format) })
+    }
+
+    /// Encode 2D outline loops in `format`, one of the formats in
+    /// `manifold_rs::openscad::drawing::EXPORT_2D_FORMATS`.
+    pub fn of_outlines(loops: &[OutlineLoop], format: &str) -> Result<Self, String> {
+        let format = format.to_ascii_lowercase();
+        let data = export_outlines(loops, &format).ok_or_else(|| {
+            format!("Unsupported 2D export format: {} (expected one of {})", format, EXPORT_2D_FORMATS.join(", "))
+        })?;
+        Ok(Self { data, filename: format!("model.{}", format) })
+    }
+
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        let result = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&result, &"data".into(), &js_sys::Uint8Array::from(self.data.as_slice()));
+        let _ = js_sys::Reflect::set(&result, &"filename".into(), &self.filename.into());
+        result.into()
+    }
+}
+
+// =============================================================================
+// OUTLINES
+// =============================================================================
+
+/// One closed loop from `render_outlines()`.
+#[derive(Debug, Clone, TS)]
+pub struct Outline {
+    /// Points as x, y pairs.
+    #[ts(type = "Float64Array")]
+    pub points: Vec<f64>,
+    /// Whether the loop is a hole.
+    pub hole: bool,
+    /// Counter-clockwise for outer loops, clockwise for holes.
+    #[ts(type = "\"ccw\" | \"cw\"")]
+    pub winding: &'static str,
+    /// Index of the enclosing outer loop, or `null`.
+    pub parent: Option<u32>,
+}
+
+impl From<OutlineLoop> for Outline {
+    fn from(outline: OutlineLoop) -> Self {
+        Self {
+            points: outline.points.iter().flatten().copied().collect(),
+            hole: outline.is_hole,
+            winding: match outline.winding {
+                Winding::CounterClockwise => "ccw",
+                Winding::Clockwise => "cw",
+            },
+            parent: outline.parent.map(|i| i as u32),
+        }
+    }
+}
+
+impl Outline {
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        let parent = self.parent.map_or(JsValue::NULL, JsValue::from);
+        let item = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&item, &"points".into(), &js_sys::Float64Array::from(self.points.as_slice()));
+        let _ = js_sys::Reflect::set(&item, &"hole".into(), &self.hole.into());
+        let _ = js_sys::Reflect::set(&item, &"winding".into(), &self.winding.into());
+        let _ = js_sys::Reflect::set(&item, &"parent".into(), &parent);
+        item.into()
+    }
+}
+
+// =============================================================================
+// VALIDATION
+// =============================================================================
+
+/// One defect found by `validate()`.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ValidationFinding {
+    /// What is wrong.
+    #[ts(type = "\"open-edge\" | \"non-manifold-edge\" | \"inverted-normal\" | \"degenerate\" | \"self-intersection\"")]
+    pub issue: &'static str,
+    /// Triangles involved, by index; empty when the whole mesh is
+    /// affected.
+    pub triangles: Vec<u32>,
+    /// Point to flag in the viewer.
+    pub position: [f64; 3],
+    /// Module calls that made the triangles, each once, in triangle order.
+    pub spans: Vec<Span>,
+}
+
+impl ValidationFinding {
+    /// The finding for `finding` in `mesh`, with the calls behind its
+    /// triangles looked up in `nodes`.
+    fn new(finding: Finding, mesh: &Mesh, nodes: &NodeTable) -> Self {
+        let mut spans: Vec<Span> = Vec::new();
+        for &t in &finding.triangles {
+            let span = nodes.triangle(mesh, t as usize).and_then(|node| node.span);
+            if let Some(span) = span.filter(|span| !spans.contains(span)) {
+                spans.push(span);
+            }
+        }
+        Self {
+            issue: match finding.issue {
+                Issue::OpenEdge => "open-edge",
+                Issue::NonManifoldEdge => "non-manifold-edge",
+                Issue::InvertedNormal => "inverted-normal",
+                Issue::Degenerate => "degenerate",
+                Issue::SelfIntersection => "self-intersection",
+            },
+            triangles: finding.triangles,
+            position: finding.position,
+            spans,
+        }
+    }
+}
+
+/// Result of `validate()`: defect counts and where the first ones are.
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct Validation {
+    /// Whether no check found anything.
+    pub valid: bool,
+    /// Whether every edge joins exactly two triangles.
+    pub watertight: bool,
+    /// Edges used by a single triangle.
+    pub open_edges: u32,
+    /// Edges used by more than two triangles.
+    pub non_manifold_edges: u32,
+    /// Triangles wound against a neighbour or their normals, and one for
+    /// a closed mesh inside out.
+    pub inverted_normals: u32,
+    /// Triangles with no area.
+    pub degenerate_triangles: u32,
+    /// Pairs of triangles passing through each other.
+    pub self_intersections: u32,
+    /// Up to 100 findings of each issue.
+    pub findings: Vec<ValidationFinding>,
+}
+
+impl Validation {
+    /// Validate `mesh`, a mesh of the tree `nodes` was built from.
+    pub fn of(mesh: &Mesh, nodes: &NodeTable) -> Self {
+        let report = mesh.validate();
+        Self {
+            valid: report.is_valid(),
+            watertight: report.is_watertight(),
+            open_edges: report.open_edges as u32,
+            non_manifold_edges: report.non_manifold_edges as u32,
+            inverted_normals: report.inverted_normals as u32,
+            degenerate_triangles: report.degenerate_triangles as u32,
+            self_intersections: report.self_intersections as u32,
+            findings: report.findings.into_iter().map(|finding| ValidationFinding::new(finding, mesh, nodes)).collect(),
+        }
+    }
+
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        let json = serde_json::to_string(&self).unwrap_or_default();
+        js_sys::JSON::parse(&json).unwrap_or(JsValue::NULL)
+    }
+}
+
+// =============================================================================
+// MEASUREMENTS
+// =============================================================================
+
+/// Result of `measure()`: mass properties of the rendered mesh.
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct Measurement {
+    /// Enclosed volume, in cubic model units; negative if inside out.
+    pub volume: f64,
+    /// Total triangle area, in square model units.
+    pub surface_area: f64,
+    /// Centre of mass at uniform density, or `null` without volume.
+    pub centroid: Option<[f64; 3]>,
+    /// Handles summed over the shells, or `null` if the mesh is not
+    /// closed.
+    pub genus: Option<u32>,
+    /// The separate bodies of the mesh; more than one for a model meant
+    /// as a single part means something is not attached.
+    pub components: Vec<MeshComponent>,
+}
+
+/// One connected body of a measured mesh.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct MeshComponent {
+    /// Enclosed volume; negative for a cavity inside another body.
+    pub volume: f64,
+    /// Lowest corner of the bounding box.
+    pub min: [f64; 3],
+    /// Highest corner of the bounding box.
+    pub max: [f64; 3],
+    /// Number of triangles.
+    pub triangles: u32,
+}
+
+impl Measurement {
+    /// Measure a mesh.
+    pub fn of(mesh: &Mesh) -> Self {
+        Self {
+            volume: mesh.volume(),
+            surface_area: mesh.surface_area(),
+            centroid: mesh.centroid(),
+            genus: mesh.genus(),
+            components: mesh.split_components().iter()
+                .filter_map(|part| {
+                    let (min, max) = part.bounding_box()?;
+                    Some(MeshComponent { volume: part.volume(), min, max, triangles: part.triangle_count() as u32 })
+                })
+                .collect(),
+        }
+    }
+
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        let json = serde_json::to_string(&self).unwrap_or_default();
+        js_sys::JSON::parse(&json).unwrap_or(JsValue::NULL)
+    }
+}
+
+// =============================================================================
+// PICKING
+// =============================================================================
+
+/// Result of `RenderSession.pick()`: the point of the model under a ray.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PickResult {
+    /// Index of the triangle hit: its corners are `indices[3 * triangle]`
+    /// to `indices[3 * triangle + 2]` of the render result.
+    pub triangle: u32,
+    /// Point hit.
+    pub point: [f64; 3],
+    /// Distance from the ray's origin to the point, in model units.
+    pub distance: f64,
+    /// Id of the geometry node that made the triangle: its pre-order
+    /// position in the geometry tree.
+    pub node: Option<u32>,
+    /// Kind of that node, such as `"cube"` or `"hull"`.
+    pub kind: Option<String>,
+    /// The innermost module call whose geometry holds the point.
+    pub span: Option<Span>,
+}
+
+impl PickResult {
+    /// The result for `pick`, with its node looked up in `nodes`.
+    pub fn new(pick: Pick, nodes: Option<&NodeTable>) -> Self {
+        let kind = pick.node.zip(nodes).and_then(|(node, nodes)| nodes.get(node)).map(|info| info.kind.to_string());
+        Self {
+            triangle: pick.hit.triangle,
+            point: pick.hit.point,
+            distance: pick.hit.distance,
+            node: pick.node,
+            kind,
+            span: pick.span,
+        }
+    }
+
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        let json = serde_json::to_string(&self).unwrap_or_default();
+        js_sys::JSON::parse(&json).unwrap_or(JsValue::NULL)
+    }
+}
+
+// =============================================================================
+// LOG LINES
+// =============================================================================
+
+/// How a log line should be shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    /// `echo()` output.
+    Echo,
+    /// A warning about the script.
+    Warning,
+    /// Syntax OpenSCAD has deprecated.
+    Deprecated,
+}
+
+/// One line handed to the `set_log_handler()` handler during evaluation.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LogLine {
+    /// How to show the line.
+    pub level: LogLevel,
+    /// The text, without an `ECHO:` or `WARNING:` prefix.
+    pub text: String,
+    /// The statement that produced it, if known.
+    pub span: Option<Span>,
+}
+
+impl From<LogEntry<'_>> for LogLine {
+    fn from(entry: LogEntry<'_>) -> Self {
+        match entry {
+            LogEntry::Message(message) => Self { level: LogLevel::Echo, text: message.text.clone(), span: message.span },
+            LogEntry::Diagnostic(diagnostic) => Self {
+                level: match diagnostic.kind {
+                    DiagnosticKind::Deprecated => LogLevel::Deprecated,
+                    _ => LogLevel::Warning,
+                },
+                text: diagnostic.text.clone(),
+                span: diagnostic.span,
+            },
+        }
+    }
+}
+
+impl LogLine {
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        let json = serde_json::to_string(&self).unwrap_or_default();
+        js_sys::JSON::parse(&json).unwrap_or(JsValue::NULL)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test counts are derived from the mesh buffers.
+    #[test]
+    fn test_success_counts() {
+        let mesh = manifold_rs::render("cube(10);").unwrap();
+        let RenderResult::Success(result) = RenderResult::success(mesh.into(), Vec::new(), Vec::new(), 1.5, OutputOptions::default()) else {
+            panic!("expected success");
+        };
+        assert_eq!(result.vertex_count, 24);
+        assert_eq!(result.triangle_count, 12);
+        assert!(result.colors.is_none() && result.edges.is_none());
+        assert!(result.highlighted.is_none() && result.transparent.is_none());
+    }
+
+    /// Test feature edges come with the mesh when asked for.
+    #[test]
+    fn test_success_edges() {
+        let mesh = manifold_rs::render("cube(10);").unwrap();
+        let options = OutputOptions { feature_angle: Some(30.0), ..OutputOptions::default() };
+        let RenderResult::Success(result) = RenderResult::success(mesh.into(), Vec::new(), Vec::new(), 1.0, options) else {
+            panic!("expected success");
+        };
+        assert_eq!(result.edges.map(|e| e.len()), Some(12 * 6));
+    }
+
+    /// Test double precision keeps detail far from the origin that single
+    /// precision rounds away.
+    #[test]
+    fn test_success_precision() {
+        let source = "translate([1e7, 0, 0]) cube(0.25);";
+        let max_x = |precision| {
+            let output = manifold_rs::render_preview(source).unwrap();
+            let options = OutputOptions { precision, ..OutputOptions::default() };
+            let RenderResult::Success(result) = RenderResult::success(output, Vec::new(), Vec::new(), 1.0, options) else {
+                panic!("expected success");
+            };
+            match result.vertices {
+                Positions::Single(v) => v.chunks_exact(3).map(|p| f64::from(p[0])).fold(f64::MIN, f64::max),
+                Positions::Double(v) => v.chunks_exact(3).map(|p| p[0]).fold(f64::MIN, f64::max),
+            }
+        };
+        assert_eq!(max_x(Precision::Double), 1e7 + 0.25);
+        assert_eq!(max_x(Precision::Single), 1e7);
+    }
+
+    /// Test preview layers are passed on, and empty ones left out.
+    #[test]
+    fn test_success_preview() {
+        let output = manifold_rs::render_preview("cube(10); %sphere(4);").unwrap();
+        let RenderResult::Success(result) = RenderResult::success(output, Vec::new(), Vec::new(), 1.0, OutputOptions::default()) else {
+            panic!("expected success");
+        };
+        assert_eq!(result.triangle_count, 12);
+        assert!(result.highlighted.is_none());
+        let transparent = result.transparent.unwrap();
+        assert_eq!(transparent.vertices.len(), transparent.normals.len());
+        assert!(!transparent.indices.is_empty());
+    }
+
+    /// Test the stats of the geometry come with the mesh, `%` geometry
+    /// included.
+    #[test]
+    fn test_success_stats() {
+        let output = manifold_rs::render_preview("cube(10); %sphere(4, $fn = 8);").unwrap();
+        let RenderResult::Success(result) = RenderResult::success(output, Vec::new(), Vec::new(), 1.0, OutputOptions::default()) else {
+            panic!("expected success");
+        };
+        assert_eq!(result.stats.primitives.len(), 2);
+        assert_eq!(result.stats.estimated_triangles, 12 + 60);
+    }
+
+    /// Test colors survive a union and are passed on per vertex.
+    #[test]
+    fn test_success_colors() {
+        let mesh = manifold_rs::render("color([1, 0, 0]) cube(10); translate([5, 5, 5]) cube(10);").unwrap();
+        let RenderResult::Success(result) = RenderResult::success(mesh.into(), Vec::new(), Vec::new(), 1.0, OutputOptions::default()) else {
+            panic!("expected success");
+        };
+        let colors = result.colors.unwrap();
+        assert_eq!(colors.len(), result.vertex_count as usize * 4);
+        assert!(colors.chunks_exact(4).any(|c| c == [1.0, 0.0, 0.0, 1.0]));
+        assert!(colors.chunks_exact(4).any(|c| c == [1.0; 4]));
+    }
+
+    /// Test console output is kept on success and after evaluation.
+    #[test]
+    fn test_messages() {
+        let evaluated = openscad_eval::evaluate("echo(\"size\", 10); cube(1);").unwrap();
+        let mesh = manifold_rs::render_evaluated(&evaluated, &Default::default(), 0.0).unwrap();
+        let RenderResult::Success(result) = RenderResult::success(mesh.into(), evaluated.messages.clone(), Vec::new(), 1.0, OutputOptions::default()) else {
+            panic!("expected success");
+        };
+        assert_eq!(result.messages[0].text, "\"size\", 10");
+
+        let error = ManifoldError::GeometryError("bad".to_string());
+        let RenderResult::Failure(failure) = RenderResult::render_error(error, evaluated.messages, Vec::new()) else {
+            panic!("expected failure");
+        };
+        let json = serde_json::to_value(&failure).unwrap();
+        assert_eq!(json["messages"][0]["kind"], "echo");
+        assert_eq!(json["messages"][0]["span"]["start"]["column"], 0);
+    }
+
+    /// Test warnings are passed on with their kind and location.
+    #[test]
+    fn test_diagnostics() {
+        let evaluated = openscad_eval::evaluate("cube(1);\nsphere(-1, h = 2);").unwrap();
+        let mesh = manifold_rs::render_evaluated(&evaluated, &Default::default(), 0.0).unwrap();
+        let RenderResult::Success(result) = RenderResult::success(mesh.into(), Vec::new(), evaluated.diagnostics.clone(), 1.0, OutputOptions::default()) else {
+            panic!("expected success");
+        };
+        assert_eq!(result.diagnostics.len(), 3);
+
+        let error = ManifoldError::GeometryError("bad".to_string());
+        let RenderResult::Failure(failure) = RenderResult::render_error(error, Vec::new(), evaluated.diagnostics) else {
+            panic!("expected failure");
+        };
+        let json = serde_json::to_value(&failure).unwrap();
+        assert_eq!(json["diagnostics"][0]["kind"], "ignored_argument");
+        assert_eq!(json["diagnostics"][0]["text"], "sphere(): variable h not specified as parameter");
+        assert_eq!(json["diagnostics"][1]["kind"], "degenerate");
+        assert_eq!(json["diagnostics"][2]["span"]["start"]["line"], 1);
+    }
+
+    /// Test limit reports survive into the failure object.
+    #[test]
+    fn test_limit_failure() {
+        let options = openscad_eval::EvalOptions {
+            limits: openscad_eval::Limits { max_nodes: Some(1), ..Default::default() },
+            ..Default::default()
+        };
+        let error = manifold_rs::render_with_eval_options("cube(1); cube(2);", &options).unwrap_err();
+        let RenderResult::Failure(failure) = RenderResult::render_error(error, Vec::new(), Vec::new()) else {
+            panic!("expected failure");
+        };
+        let json = serde_json::to_value(&failure).unwrap();
+        assert_eq!(json["success"], false);
+        assert_eq!(json["limit"]["kind"], "nodes");
+        assert_eq!(json["limit"]["observed"], 2);
+        assert!(failure.error.ends_with("cube() call at 1:10 exceeded 1 geometry nodes (reached 2)"));
+
+        let RenderResult::Failure(plain) = RenderResult::failure("bad") else {
+            panic!("expected failure");
+        };
+        assert!(serde_json::to_value(&plain).unwrap()["limit"].is_null());
+    }
+
+    /// Test cancellation is flagged apart from other errors.
+    #[test]
+    fn test_cancelled_failure() {
+        let RenderResult::Failure(failure) = RenderResult::render_error(ManifoldError::Cancelled, Vec::new(), Vec::new()) else {
+            panic!("expected failure");
+        };
+        let json = serde_json::to_value(&failure).unwrap();
+        assert_eq!(json["cancelled"], true);
+        assert_eq!(json["limit"], serde_json::Value::Null);
+        assert_eq!(json["error"], "Render error: Render cancelled");
+    }
+
+    /// Test log lines tell echoes, warnings and deprecations apart.
+    #[test]
+    fn test_log_lines() {
+        let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&lines);
+        let options = openscad_eval::EvalOptions {
+            log: Some(std::sync::Arc::new(move |entry: LogEntry| sink.lock().unwrap().push(LogLine::from(entry)))),
+            ..Default::default()
+        };
+        openscad_eval::evaluate_with_options("echo(\"a\");\nfor (i = [1 : 0]) cube(-1);", &options).unwrap();
+        let json = serde_json::to_value(&*lines.lock().unwrap()).unwrap();
+        assert_eq!(json[0]["level"], "echo");
+        assert_eq!(json[0]["text"], "\"a\"");
+        assert_eq!(json[1]["level"], "deprecated");
+        assert_eq!(json[1]["span"]["start"]["line"], 1);
+        assert_eq!(json[2]["level"], "warning");
+    }
+
+    /// Test validation reports use the JavaScript names and point at the
+    /// calls behind the triangles.
+    #[test]
+    fn test_validation() {
+        let options = openscad_eval::EvalOptions { sources: true, ..Default::default() };
+        let geometry = openscad_eval::evaluate_with_options("\ncube(10);", &options).unwrap().geometry;
+        let nodes = NodeTable::new(&geometry);
+        let mut mesh = manifold_rs::openscad::from_ir::geometry_to_mesh(&geometry).unwrap();
+        let json = serde_json::to_value(Validation::of(&mesh, &nodes)).unwrap();
+        assert_eq!(json["valid"], true);
+        assert_eq!(json["findings"].as_array().unwrap().len(), 0);
+
+        mesh.indices.truncate(33);
+        mesh.nodes.as_mut().unwrap().truncate(11);
+        let json = serde_json::to_value(Validation::of(&mesh, &nodes)).unwrap();
+        assert_eq!(json["watertight"], false);
+        assert_eq!(json["openEdges"], 3);
+        assert_eq!(json["findings"][0]["issue"], "open-edge");
+        assert_eq!(json["findings"][0]["triangles"].as_array().unwrap().len(), 1);
+        assert_eq!(json["findings"][0]["spans"][0]["start"]["line"], 1);
+    }
+
+    /// Test measurements use the JavaScript names and null for open meshes.
+    #[test]
+    fn test_measurement() {
+        let mut mesh = manifold_rs::render("cube(10);").unwrap();
+        let json = serde_json::to_value(Measurement::of(&mesh)).unwrap();
+        assert!((json["volume"].as_f64().unwrap() - 1000.0).abs() < 1e-9);
+        assert!((json["surfaceArea"].as_f64().unwrap() - 600.0).abs() < 1e-9);
+        assert_eq!(json["genus"], 0);
+        assert_eq!(json["components"][0]["max"], serde_json::json!([10.0, 10.0, 10.0]));
+
+        let parts = manifold_rs::render("cube(1); translate([5, 0, 0]) cube(2);").unwrap();
+        let json = serde_json::to_value(Measurement::of(&parts)).unwrap();
+        let components = json["components"].as_array().unwrap();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[1]["min"], serde_json::json!([5.0, 0.0, 0.0]));
+        assert!((components[1]["volume"].as_f64().unwrap() - 8.0).abs() < 1e-9);
+        assert_eq!(components[1]["triangles"], 12);
+
+        mesh.indices.truncate(33);
+        let json = serde_json::to_value(Measurement::of(&mesh)).unwrap();
+        assert!(json["genus"].is_null());
+    }
+
+    /// Test exports pick the encoder and file name by format.
+    #[test]
+    fn test_exported_file() {
+        let mesh = manifold_rs::render("cube(10);").unwrap();
+        let file = ExportedFile::of(&mesh, "OFF").unwrap();
+        assert_eq!(file.filename, "model.off");
+        assert!(file.data.starts_with(b"NOFF"));
+        assert_eq!(ExportedFile::of(&mesh, "stl").unwrap().data, mesh.to_stl_binary());
+        assert!(ExportedFile::of(&mesh, "dxf").unwrap_err().contains("stl, 3mf, amf, glb, obj, off"));
+        assert_eq!(ExportedFile::of(&mesh, "amf").unwrap().filename, "model.amf");
+
+        let loops = manifold_rs::render_outlines("square(10);").unwrap();
+        let file = ExportedFile::of_outlines(&loops, "SVG").unwrap();
+        assert_eq!(file.filename, "model.svg");
+        assert!(file.data.starts_with(b"<?xml"));
+        assert!(ExportedFile::of_outlines(&loops, "stl").unwrap_err().contains("svg, dxf"));
+    }
+}
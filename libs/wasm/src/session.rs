@@ -0,0 +1,457 @@
+//! # Render Sessions
+//!
+//! Keeps the work of one render alive for the next, so an editor that
+//! re-renders on every keystroke only redoes what the edit changed.
+//!
+//! ## What is kept
+//!
+//! | Kept | Reused when |
+//! |------|-------------|
+//! | Last mesh and messages | The source and options are unchanged |
+//! | Parsed AST | The source is unchanged (options changed) |
+//! | Mesh of each top-level child | The child evaluates to the same geometry |
+//! | Meshes of costly subtrees | The subtree evaluates to the same geometry |
+//! | Node table of the last geometry tree | Picking, until the next update |
+//!
+//! Evaluation itself always runs over the whole script: a changed
+//! variable can reach any statement, and evaluating is cheap next to
+//! meshing. The children are then meshed through a [`MeshCache`] keyed on
+//! structural hashes, so only the subtrees whose geometry changed are
+//! meshed again, along with the operations above them; the final
+//! combination of the children is redone.
+//!
+//! The cache holds at most `options.csg.cache_size` meshes, if set, and
+//! is emptied when the backend, auto threshold or weld tolerance change,
+//! since the same geometry then meshes differently.
+//!
+//! The output's [`Timings`] cover this update only: parsing stays at 0
+//! when the AST was reused, and a child taken whole from the cache is
+//! timed as its lookup.
+//!
+//! ```text
+//! update(source)
+//!     ↓ same source and options? → last mesh
+//!     ↓ parse (skipped if the source is unchanged)
+//!     ↓ evaluate → top-level children
+//!     ↓ each child: mesh the subtrees missing from the MeshCache
+//!     ↓ combine → mesh
+//!     ↓ mesh the `#` and `%` preview layers (not cached)
+//! ```
+//!
+//! Each child's mesh keeps the node id of every triangle (see
+//! [`provenance`](manifold_rs::openscad::provenance)), shifted to its
+//! position in the whole tree before the children are combined.
+//! [`Session::pick`] finds the point of the last mesh under a ray and the
+//! module call that made it, when the options ask for
+//! [`sources`](EvalOptions::sources).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use openscad_eval::EvalOptions;
+//! use openscad_wasm::session::Session;
+//!
+//! let mut session = Session::new(EvalOptions::default());
+//! session.update("cube(10); sphere(5);").unwrap();
+//! session.update("cube(10); sphere(6);").unwrap();
+//! assert_eq!((session.stats().reused, session.stats().meshed), (1, 1));
+//! ```
+
+use manifold_rs::error::ManifoldResult;
+use manifold_rs::manifold::boolean::CsgScope;
+use manifold_rs::manifold::simplify::simplify;
+use manifold_rs::openscad::cache::MeshCache;
+use manifold_rs::openscad::from_ir::geometry_to_mesh_timed;
+use manifold_rs::openscad::pick::{pick, Pick};
+use manifold_rs::openscad::provenance::NodeTable;
+use manifold_rs::openscad::preview::PreviewTrees;
+use manifold_rs::{ManifoldError, Mesh, RenderOutput};
+use openscad_ast::Ast;
+use openscad_eval::limits::now_ms;
+use openscad_eval::timing::stage_span;
+use openscad_eval::{CancellationToken, CsgOptions, Diagnostic, EvalError, EvalOptions, Message, OutputOptions, Progress, Stage, Timings};
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::chunked::{child_depth, split_top_level};
+
+// =============================================================================
+// STATS
+// =============================================================================
+
+/// What the last update of a session did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct SessionStats {
+    /// Whether the source was parsed again.
+    pub parsed: bool,
+    /// Whether the script was evaluated again.
+    pub evaluated: bool,
+    /// Subtree meshes taken from the cache.
+    #[ts(type = "number")]
+    pub reused: usize,
+    /// Subtree meshes built and cached.
+    #[ts(type = "number")]
+    pub meshed: usize,
+}
+
+// =============================================================================
+// SESSION
+// =============================================================================
+
+/// Result of the last successful update.
+#[derive(Debug)]
+struct Rendered {
+    source: String,
+    output: RenderOutput,
+    nodes: NodeTable,
+}
+
+/// Incremental renderer for a script that is edited and rendered again.
+#[derive(Debug)]
+pub struct Session {
+    options: EvalOptions,
+    parsed: Option<(String, Ast)>,
+    last: Option<Rendered>,
+    cache: MeshCache,
+    messages: Vec<Message>,
+    diagnostics: Vec<Diagnostic>,
+    stats: SessionStats,
+}
+
+impl Session {
+    /// A session rendering with `options`.
+    pub fn new(options: EvalOptions) -> Self {
+        let mut cache = MeshCache::new();
+        cache.set_limit(options.csg.cache_size);
+        Self {
+            options,
+            parsed: None,
+            last: None,
+            cache,
+            messages: Vec::new(),
+            diagnostics: Vec::new(),
+            stats: SessionStats::default(),
+        }
+    }
+
+    /// Render with new options from the next update on.
+    ///
+    /// The parsed source and cached meshes stay: children that evaluate
+    /// to the same geometry under the new options are still reused. New
+    /// CSG settings other than `cache_size` empty the cache.
+    pub fn set_options(&mut self, options: EvalOptions) {
+        let meshing = |csg: CsgOptions| CsgOptions { cache_size: None, ..csg };
+        if meshing(options.csg) != meshing(self.options.csg) {
+            self.cache.clear();
+        }
+        self.cache.set_limit(options.csg.cache_size);
+        self.options = options;
+        self.last = None;
+    }
+
+    /// Render `source`, reusing what the previous updates left.
+    ///
+    /// Gives the same mesh as rendering `source` from scratch with the
+    /// session's options.
+    ///
+    /// ## Errors
+    ///
+    /// Any error of the pipeline, and `ManifoldError::Cancelled` if the
+    /// options' token is cancelled. Cached meshes are kept, along with
+    /// the children meshed before the error.
+    pub fn update(&mut self, source: &str) -> ManifoldResult<RenderOutput> {
+        self.stats = SessionStats::default();
+        if let Some(last) = self.last.as_ref().filter(|last| last.source == source) {
+            return Ok(last.output.clone());
+        }
+        self.last = None;
+
+        // A failed update leaves the cache as it was, so fixing a typo
+        // finds the meshes from before it
+        let (output, nodes) = self.render(source)?;
+        self.cache.sweep();
+        self.last = Some(Rendered { source: source.to_string(), output: output.clone(), nodes });
+        Ok(output)
+    }
+
+    /// The point of the last update's mesh under the ray from `origin`
+    /// along `direction`, and the module call that made it.
+    ///
+    /// The call is only known if the options set
+    /// [`sources`](EvalOptions::sources).
+    ///
+    /// ## Returns
+    ///
+    /// `None` if the ray misses the mesh, or there was no successful
+    /// update since the options were last set.
+    pub fn pick(&self, origin: [f64; 3], direction: [f64; 3]) -> Option<Pick> {
+        let last = self.last.as_ref()?;
+        pick(&last.nodes, &last.output.solid, origin, direction)
+    }
+
+    /// The nodes of the last update's geometry tree, by the ids its mesh's
+    /// triangles carry.
+    pub fn nodes(&self) -> Option<&NodeTable> {
+        self.last.as_ref().map(|last| &last.nodes)
+    }
+
+    /// Parse, evaluate and mesh, going through the cache.
+    fn render(&mut self, source: &str) -> ManifoldResult<(RenderOutput, NodeTable)> {
+        let mut timings = Timings::default();
+        if self.parsed.as_ref().is_none_or(|(parsed, _)| parsed != source) {
+            self.parsed = None;
+            let parse_error = |e: openscad_ast::AstError| ManifoldError::from(EvalError::ParseError(e.to_string()));
+            let start = now_ms();
+            let cst = {
+                let _span = stage_span("parse");
+                openscad_ast::parse_cst(source).map_err(parse_error)?
+            };
+            let lowered = now_ms();
+            let ast = {
+                let _span = stage_span("ast");
+                openscad_ast::lower(&cst).map_err(parse_error)?
+            };
+            timings.parse_ms = lowered - start;
+            timings.ast_ms = now_ms() - lowered;
+            self.parsed = Some((source.to_string(), ast));
+            self.stats.parsed = true;
+        }
+        let Some((_, ast)) = &self.parsed else {
+            unreachable!("the source was parsed above");
+        };
+
+        self.messages.clear();
+        self.diagnostics.clear();
+        let evaluated = openscad_eval::visitor::evaluate_ast_with_options(ast, &self.options)
+            .map_err(ManifoldError::from)?;
+        self.stats.evaluated = true;
+        self.messages = evaluated.messages;
+        self.diagnostics = evaluated.diagnostics;
+        timings.eval_ms = evaluated.timings.eval_ms;
+
+        let _mesh = stage_span("mesh");
+        let start = now_ms();
+        let _csg = CsgScope::enter(&self.options.csg);
+        let preview = PreviewTrees::of(&evaluated.geometry)?;
+        let stats = evaluated.geometry.stats();
+        let depth = child_depth(&evaluated.geometry);
+        let nodes = NodeTable::new(&evaluated.geometry);
+        let (combine, children) = split_top_level(evaluated.geometry);
+        let total = children.iter().map(|node| node.node_count() as u64).sum();
+        let cancel = self.options.cancel.as_ref();
+        self.report(Stage::Csg, 0, total);
+        let mut acc: Option<Mesh> = None;
+        let mut done = 0;
+        for child in &children {
+            if cancel.is_some_and(CancellationToken::is_cancelled) {
+                return Err(ManifoldError::Cancelled);
+            }
+            let before = self.cache.stats();
+            let result = geometry_to_mesh_timed(child, Some(&mut self.cache), None, cancel);
+            let after = self.cache.stats();
+            self.stats.reused += after.hits - before.hits;
+            self.stats.meshed += after.misses - before.misses;
+            let (mut mesh, csg) = result?;
+            // Positions in the child become positions in the whole tree
+            let offset = depth + done as u32;
+            mesh.offset_nodes(i64::from(offset));
+            timings.csg.extend(csg.into_iter().map(|mut t| {
+                t.node += offset;
+                t.depth += depth;
+                t
+            }));
+            // Empty children are skipped, matching the synchronous path
+            acc = match acc {
+                _ if mesh.is_empty() => acc,
+                None => Some(mesh),
+                Some(acc) => Some(combine.apply(acc, &mesh)?),
+            };
+            done += child.node_count() as u64;
+            self.report(Stage::Csg, done, total);
+        }
+        let mesh = simplify(acc.unwrap_or_default(), &self.options.simplify);
+        let mut output = preview.mesh(mesh)?;
+        timings.mesh_ms = now_ms() - start;
+        output.timings = timings;
+        output.stats = stats;
+        self.report(Stage::Mesh, 1, 1);
+        Ok((output, nodes))
+    }
+
+    /// Send a report to the options' progress sink, if any.
+    fn report(&self, stage: Stage, done: u64, total: u64) {
+        if let Some(sink) = &self.options.progress {
+            sink.report(Progress { stage, done, total });
+        }
+    }
+
+    /// Console output of the last evaluation.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Warnings of the last evaluation.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// How the session's results are handed out.
+    pub fn output_options(&self) -> OutputOptions {
+        self.options.output
+    }
+
+    /// What the last update did.
+    pub fn stats(&self) -> SessionStats {
+        self.stats
+    }
+
+    /// Number of subtree meshes held for the next update.
+    pub fn cached_meshes(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Forget everything kept, as if the session were new.
+    pub fn clear(&mut self) {
+        self.parsed = None;
+        self.last = None;
+        self.cache.clear();
+        self.messages.clear();
+        self.diagnostics.clear();
+        self.stats = SessionStats::default();
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test each update only meshes the children that changed.
+    #[test]
+    fn test_incremental_updates() {
+        let mut session = Session::new(EvalOptions::default());
+        let first = "union() { cube(10); translate([5, 5, 5]) sphere(4, $fn = 16); }";
+        let mesh = session.update(first).unwrap().solid;
+        assert_eq!(session.stats(), SessionStats { parsed: true, evaluated: true, reused: 0, meshed: 2 });
+        assert_eq!(mesh.triangle_count(), manifold_rs::render(first).unwrap().triangle_count());
+
+        // Unchanged source: nothing is redone
+        session.update(first).unwrap();
+        assert_eq!(session.stats(), SessionStats::default());
+
+        // One child edited: the other is reused, the result matches a fresh render
+        let second = "union() { cube(10); translate([5, 5, 6]) sphere(4, $fn = 16); }";
+        let mesh = session.update(second).unwrap().solid;
+        assert_eq!(session.stats(), SessionStats { parsed: true, evaluated: true, reused: 1, meshed: 1 });
+        let fresh = manifold_rs::render(second).unwrap();
+        assert_eq!((mesh.vertex_count(), mesh.triangle_count()), (fresh.vertex_count(), fresh.triangle_count()));
+        assert_eq!(session.cached_meshes(), 2);
+    }
+
+    /// Test new options keep the parsed source and reuse unaffected children.
+    #[test]
+    fn test_options_reuse_ast() {
+        let source = "h = 1; cube(2); cylinder(h = h, r = 1);";
+        let mut session = Session::new(EvalOptions::default());
+        session.update(source).unwrap();
+
+        let mut options = EvalOptions::default();
+        options.overrides.insert("h".to_string(), openscad_eval::Value::Number(3.0));
+        session.set_options(options);
+        session.update(source).unwrap();
+        assert_eq!(session.stats(), SessionStats { parsed: false, evaluated: true, reused: 1, meshed: 1 });
+    }
+
+    /// Test new CSG settings empty the cache and the cache size caps it.
+    #[test]
+    fn test_csg_options() {
+        let source = "difference() { cube(4); sphere(2); } hull() { cube(1); sphere(1); }";
+        let mut session = Session::new(EvalOptions::default());
+        session.update(source).unwrap();
+        assert_eq!(session.cached_meshes(), 2);
+
+        // The default backend, named: tests share the process-wide setting
+        let csg = CsgOptions { backend: Some(openscad_eval::CsgBackend::Bsp), cache_size: Some(1), ..CsgOptions::default() };
+        session.set_options(EvalOptions { csg, ..EvalOptions::default() });
+        assert_eq!(session.cached_meshes(), 0);
+        session.update(source).unwrap();
+        assert_eq!(session.stats().meshed, 2);
+        assert_eq!(session.cached_meshes(), 1);
+    }
+
+    /// Test errors surface, keep no result, and the session recovers.
+    #[test]
+    fn test_errors_and_clear() {
+        let mut session = Session::new(EvalOptions::default());
+        session.update("cube(1);").unwrap();
+        assert!(session.update("cube(1").is_err());
+        session.update("cube(1);").unwrap();
+        assert_eq!(session.stats().reused, 1);
+
+        session.clear();
+        assert_eq!(session.cached_meshes(), 0);
+        session.update("cube(1);").unwrap();
+        assert_eq!(session.stats().meshed, 1);
+    }
+
+    /// Test preview layers come with each update, also when nothing changed.
+    #[test]
+    fn test_preview_layers() {
+        let mut session = Session::new(EvalOptions::default());
+        let source = "cube(10); #translate([20, 0, 0]) cube(2);";
+        let output = session.update(source).unwrap();
+        assert!(!output.highlighted.is_empty() && output.transparent.is_empty());
+        assert_eq!(session.update(source).unwrap().highlighted.triangle_count(), 12);
+    }
+
+    /// Test timings cover the update: parsing only when the source
+    /// changed, CSG nodes at their positions in the whole tree.
+    #[test]
+    fn test_timings() {
+        let mut session = Session::new(EvalOptions::default());
+        let source = "cube(1); difference() { cube(10); sphere(6); }";
+        let timings = session.update(source).unwrap().timings;
+        let nodes: Vec<_> = timings.csg.iter().map(|n| (n.node, n.depth, n.kind.as_str())).collect();
+        assert_eq!(nodes, vec![(2, 1, "difference")]);
+        assert!(timings.mesh_ms >= timings.csg[0].ms);
+
+        session.set_options(EvalOptions::default());
+        let timings = session.update(source).unwrap().timings;
+        assert_eq!((timings.parse_ms, timings.ast_ms), (0.0, 0.0));
+        assert_eq!(timings.csg.len(), 1);
+    }
+
+    /// Test picking finds the call under the ray, with the tree split
+    /// and its children cached as without sources.
+    #[test]
+    fn test_pick() {
+        let mut session = Session::new(EvalOptions { sources: true, ..EvalOptions::default() });
+        assert_eq!(session.pick([0.0, 0.0, 50.0], [0.0, 0.0, -1.0]), None);
+
+        let source = "difference() {\n  cube(10);\n  translate([0, 0, 5]) cylinder(h = 10, r = 3);\n}";
+        session.update(source).unwrap();
+        assert_eq!(session.stats().meshed, 2);
+
+        let line = |session: &Session, x: f64| {
+            let picked = session.pick([x, 1.0, 50.0], [0.0, 0.0, -1.0])?;
+            picked.span.map(|span| span.start.line)
+        };
+        assert_eq!(line(&session, 8.0), Some(1));
+        assert_eq!(line(&session, 1.0), Some(2));
+        assert_eq!(line(&session, 20.0), None);
+
+        // Ids of the second child are shifted past the first
+        let picked = session.pick([1.0, 1.0, 50.0], [0.0, 0.0, -1.0]).unwrap();
+        let node = session.nodes().unwrap().get(picked.node.unwrap()).unwrap();
+        assert_eq!(node.kind, "cylinder");
+
+        session.update("cube(1);").unwrap();
+        let picked = session.pick([0.5, 0.5, 5.0], [0.0, 0.0, -1.0]).unwrap();
+        assert_eq!((picked.hit.distance, picked.span.map(|span| span.start.line)), (4.0, Some(0)));
+    }
+}
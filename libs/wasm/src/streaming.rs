@@ -0,0 +1,227 @@
+//! # Streaming Mesh Transfer
+//!
+//! Hands a finished mesh to JavaScript in batches of triangles, so a large
+//! model never exists as one giant result object and a viewer can add each
+//! batch to the scene as it arrives.
+//!
+//! ## Chunks
+//!
+//! Each chunk is a self-contained indexed mesh: its indices refer to its
+//! own vertex arrays, ready for one `THREE.BufferGeometry`. Vertices shared
+//! by triangles in different chunks are copied into each of them.
+//!
+//! ```text
+//! mesh triangles: [0 ........ 65535][65536 ..... 131071][131072 .. n)
+//!                      chunk 0            chunk 1           chunk 2
+//! ```
+//!
+//! ## Shape (JavaScript)
+//!
+//! ```javascript
+//! // each call of onChunk
+//! { index, firstTriangle, triangleCount, vertices, normals, colors | null, indices }
+//!
+//! // resolved value of render_streaming()
+//! { success: true, vertexCount, triangleCount, chunkCount, renderTimeMs, messages }
+//! ```
+
+use std::collections::HashMap;
+
+use manifold_rs::Mesh;
+use openscad_eval::{Diagnostic, Message};
+use ts_rs::TS;
+use wasm_bindgen::JsValue;
+
+use crate::result::RenderFailure;
+
+/// Triangles per chunk when the caller does not choose.
+pub const DEFAULT_CHUNK_TRIANGLES: usize = 65_536;
+
+// =============================================================================
+// CHUNKS
+// =============================================================================
+
+/// One batch of triangles with its own vertices.
+#[derive(Debug, Clone, PartialEq, TS)]
+#[ts(rename_all = "camelCase")]
+pub struct MeshChunk {
+    /// Position of the chunk in the stream, from 0.
+    pub index: u32,
+    /// Index of the chunk's first triangle in the whole mesh.
+    pub first_triangle: u32,
+    /// Number of triangles in the chunk.
+    pub triangle_count: u32,
+    /// Vertex positions (x, y, z), narrowed to `f32`.
+    #[ts(type = "Float32Array")]
+    pub vertices: Vec<f32>,
+    /// Vertex normals (x, y, z).
+    #[ts(type = "Float32Array")]
+    pub normals: Vec<f32>,
+    /// Vertex colors (r, g, b, a), if the mesh has any.
+    #[ts(type = "Float32Array | null")]
+    pub colors: Option<Vec<f32>>,
+    /// Triangle indices into this chunk's vertices.
+    #[ts(type = "Uint32Array")]
+    pub indices: Vec<u32>,
+}
+
+impl MeshChunk {
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        let colors = self.colors.map_or(JsValue::NULL, |c| js_sys::Float32Array::from(c.as_slice()).into());
+        let chunk = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&chunk, &"index".into(), &self.index.into());
+        let _ = js_sys::Reflect::set(&chunk, &"firstTriangle".into(), &self.first_triangle.into());
+        let _ = js_sys::Reflect::set(&chunk, &"triangleCount".into(), &self.triangle_count.into());
+        let _ = js_sys::Reflect::set(&chunk, &"vertices".into(), &js_sys::Float32Array::from(self.vertices.as_slice()));
+        let _ = js_sys::Reflect::set(&chunk, &"normals".into(), &js_sys::Float32Array::from(self.normals.as_slice()));
+        let _ = js_sys::Reflect::set(&chunk, &"colors".into(), &colors);
+        let _ = js_sys::Reflect::set(&chunk, &"indices".into(), &js_sys::Uint32Array::from(self.indices.as_slice()));
+        chunk.into()
+    }
+}
+
+/// Split a mesh into chunks of at most `max_triangles` triangles, in
+/// triangle order.
+///
+/// Chunks are built one at a time as the iterator advances.
+///
+/// ## Example
+///
+/// ```rust
+/// use openscad_wasm::streaming::mesh_chunks;
+///
+/// let mesh = manifold_rs::render("cube(1);").unwrap();
+/// let chunks: Vec<_> = mesh_chunks(&mesh, 5).collect();
+/// assert_eq!(chunks.iter().map(|c| c.triangle_count).collect::<Vec<_>>(), vec![5, 5, 2]);
+/// ```
+pub fn mesh_chunks(mesh: &Mesh, max_triangles: usize) -> impl Iterator<Item = MeshChunk> + '_ {
+    mesh.indices
+        .chunks(max_triangles.max(1) * 3)
+        .enumerate()
+        .map(move |(index, triangles)| build_chunk(mesh, index, max_triangles.max(1), triangles))
+}
+
+/// Copy the vertices `triangles` use and renumber them from 0.
+fn build_chunk(mesh: &Mesh, index: usize, max_triangles: usize, triangles: &[u32]) -> MeshChunk {
+    let mut local: HashMap<u32, u32> = HashMap::new();
+    let mut chunk = MeshChunk {
+        index: index as u32,
+        first_triangle: (index * max_triangles) as u32,
+        triangle_count: (triangles.len() / 3) as u32,
+        vertices: Vec::new(),
+        normals: Vec::new(),
+        colors: mesh.colors.as_ref().map(|_| Vec::new()),
+        indices: Vec::with_capacity(triangles.len()),
+    };
+    for &vertex in triangles {
+        let next = local.len() as u32;
+        let renumbered = *local.entry(vertex).or_insert_with(|| {
+            let v = vertex as usize;
+            chunk.vertices.extend(mesh.vertices[3 * v..3 * v + 3].iter().map(|&c| c as f32));
+            chunk.normals.extend_from_slice(&mesh.normals[3 * v..3 * v + 3]);
+            if let (Some(out), Some(colors)) = (chunk.colors.as_mut(), mesh.colors.as_ref()) {
+                out.extend_from_slice(&colors[4 * v..4 * v + 4]);
+            }
+            next
+        });
+        chunk.indices.push(renumbered);
+    }
+    chunk
+}
+
+// =============================================================================
+// RESULT
+// =============================================================================
+
+/// What a successful streaming render sent.
+#[derive(Debug, Clone, TS)]
+#[ts(rename_all = "camelCase")]
+pub struct StreamSuccess {
+    /// Always `true`.
+    #[ts(type = "true")]
+    pub success: bool,
+    /// Vertices of the whole mesh, before chunks copied shared ones.
+    pub vertex_count: u32,
+    /// Triangles over all chunks.
+    pub triangle_count: u32,
+    /// Number of chunks sent.
+    pub chunk_count: u32,
+    /// Wall-clock time of the render and transfer in milliseconds.
+    pub render_time_ms: f64,
+    /// Console output of the script.
+    pub messages: Vec<Message>,
+    /// Warnings of the evaluation.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Result of `render_streaming()`; check `success` first.
+#[derive(Debug, Clone, TS)]
+#[ts(untagged)]
+pub enum StreamResult {
+    /// The totals; the mesh itself went to the callback.
+    Success(StreamSuccess),
+    /// The error; no chunks were sent.
+    Failure(RenderFailure),
+}
+
+impl StreamSuccess {
+    /// Convert to the JavaScript object.
+    pub fn into_js(self) -> JsValue {
+        let messages = serde_json::to_string(&self.messages).unwrap_or_default();
+        let diagnostics = serde_json::to_string(&self.diagnostics).unwrap_or_default();
+        let result = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&result, &"success".into(), &true.into());
+        let _ = js_sys::Reflect::set(&result, &"vertexCount".into(), &self.vertex_count.into());
+        let _ = js_sys::Reflect::set(&result, &"triangleCount".into(), &self.triangle_count.into());
+        let _ = js_sys::Reflect::set(&result, &"chunkCount".into(), &self.chunk_count.into());
+        let _ = js_sys::Reflect::set(&result, &"renderTimeMs".into(), &self.render_time_ms.into());
+        let _ = js_sys::Reflect::set(&result, &"messages".into(), &js_sys::JSON::parse(&messages).unwrap_or(JsValue::NULL));
+        let _ = js_sys::Reflect::set(&result, &"diagnostics".into(), &js_sys::JSON::parse(&diagnostics).unwrap_or(JsValue::NULL));
+        result.into()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test chunks renumber their vertices and reassemble the mesh.
+    #[test]
+    fn test_chunks_reassemble() {
+        let mesh = manifold_rs::render("color([1, 0, 0]) difference() { cube(4, center = true); sphere(2.5, $fn = 12); }").unwrap();
+        let chunks: Vec<MeshChunk> = mesh_chunks(&mesh, 7).collect();
+        assert_eq!(chunks.len(), mesh.triangle_count().div_ceil(7));
+        let positions = mesh.vertices_f32();
+
+        let mut first_triangle = 0;
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!((chunk.index, chunk.first_triangle), (i as u32, first_triangle));
+            assert_eq!(chunk.indices.len(), 3 * chunk.triangle_count as usize);
+            assert!(chunk.indices.iter().all(|&v| (v as usize) < chunk.vertices.len() / 3));
+            assert_eq!(chunk.colors.as_ref().map(Vec::len), Some(chunk.vertices.len() / 3 * 4));
+
+            // Every corner resolves to the same position as in the mesh
+            let start = 3 * first_triangle as usize;
+            for (k, &v) in chunk.indices.iter().enumerate() {
+                let global = mesh.indices[start + k] as usize;
+                assert_eq!(chunk.vertices[3 * v as usize..3 * v as usize + 3], positions[3 * global..3 * global + 3]);
+            }
+            first_triangle += chunk.triangle_count;
+        }
+        assert_eq!(first_triangle as usize, mesh.triangle_count());
+    }
+
+    /// Test empty meshes give no chunks and a zero size is treated as one.
+    #[test]
+    fn test_chunk_edge_cases() {
+        assert_eq!(mesh_chunks(&Mesh::new(), 10).count(), 0);
+        let cube = manifold_rs::render("cube(1);").unwrap();
+        assert_eq!(mesh_chunks(&cube, 0).count(), 12);
+        assert!(mesh_chunks(&cube, 0).all(|c| c.colors.is_none()));
+    }
+}
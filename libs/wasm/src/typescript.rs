@@ -0,0 +1,148 @@
+//! # TypeScript Definitions
+//!
+//! Declarations for the objects passed to and returned from the exported
+//! functions, generated from the Rust types with `ts-rs`.
+//!
+//! wasm-bindgen types every `JsValue` as `any`. The exported functions
+//! name these types instead (`unchecked_param_type` /
+//! `unchecked_return_type`), and the npm package build appends
+//! [`declarations`] to wasm-bindgen's `.d.ts` so the names resolve:
+//!
+//! ```bash
+//! cargo run -p openscad-wasm --example typescript > types.d.ts
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! let dts = openscad_wasm::typescript::declarations();
+//! assert!(dts.contains("export type RenderResult = RenderSuccess | RenderFailure;"));
+//! assert!(dts.contains("export type LimitExceeded = {"));
+//! ```
+
+use openscad_eval::{GeometryStats, Progress};
+use ts_rs::{TypeVisitor, TS};
+
+use crate::capabilities::Capabilities;
+use crate::options::RenderOptions;
+use crate::result::{ExportedFile, LogLine, Measurement, Outline, PickResult, RenderResult, Validation};
+use crate::session::SessionStats;
+use crate::streaming::{MeshChunk, StreamResult};
+
+/// Banner at the top of the generated declarations.
+const HEADER: &str = "// Generated from the Rust types of openscad-wasm; do not edit.\n";
+
+/// Declarations of every type the exported functions name, and of the
+/// types those refer to, each exported and with its doc comment.
+pub fn declarations() -> String {
+    let mut collector = Collector::default();
+    collector.visit::<RenderOptions>();
+    collector.visit::<RenderResult>();
+    collector.visit::<Outline>();
+    collector.visit::<Validation>();
+    collector.visit::<Measurement>();
+    collector.visit::<GeometryStats>();
+    collector.visit::<ExportedFile>();
+    collector.visit::<Capabilities>();
+    collector.visit::<Progress>();
+    collector.visit::<MeshChunk>();
+    collector.visit::<StreamResult>();
+    collector.visit::<SessionStats>();
+    collector.visit::<LogLine>();
+    collector.visit::<PickResult>();
+
+    let mut out = String::from(HEADER);
+    for decl in collector.decls {
+        out.push('\n');
+        out.push_str(&decl);
+        out.push('\n');
+    }
+    out
+}
+
+/// Walks a type and its dependencies, declaring each named type once.
+#[derive(Default)]
+struct Collector {
+    seen: Vec<String>,
+    decls: Vec<String>,
+}
+
+impl TypeVisitor for Collector {
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        // Only declarable types have an output path; primitives and
+        // wrappers like Option are inlined. Names, not type ids, identify
+        // a declaration: serde_json's map and value share `JsonValue`.
+        if T::output_path().is_none() || self.seen.contains(&T::ident()) {
+            return;
+        }
+        self.seen.push(T::ident());
+
+        let mut decl = T::DOCS.unwrap_or_default().to_string();
+        decl.push_str("export ");
+        decl.push_str(&T::decl());
+        self.decls.push(decl);
+        T::visit_dependencies(self);
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Types named in `unchecked_*_type` attributes of the exported API.
+    fn named_types() -> Vec<String> {
+        let source = include_str!("lib.rs");
+        let mut names = Vec::new();
+        for attr in ["unchecked_return_type = \"", "unchecked_param_type = \""] {
+            for (start, _) in source.match_indices(attr) {
+                let rest = &source[start + attr.len()..];
+                let ty = &rest[..rest.find('"').unwrap_or(0)];
+                for word in ty.split(|c: char| !c.is_alphanumeric()) {
+                    if word.starts_with(char::is_uppercase) && !names.iter().any(|n| n == word) {
+                        names.push(word.to_string());
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    /// Test every type the exported functions name is declared.
+    #[test]
+    fn test_named_types_declared() {
+        let dts = declarations();
+        let names = named_types();
+        assert!(names.contains(&"RenderResult".to_string()));
+        for name in names {
+            let builtin = ["Promise", "Uint8Array"].contains(&name.as_str());
+            assert!(builtin || dts.contains(&format!("export type {} ", name)), "{} is not declared", name);
+        }
+    }
+
+    /// Test dependencies are declared once, with docs and JS field names.
+    #[test]
+    fn test_dependencies() {
+        let dts = declarations();
+        for name in ["JsonValue", "RenderSuccess", "PreviewMesh", "RenderFailure", "LimitExceeded", "LimitKind", "Construct", "Span", "Position", "Message", "MessageKind", "Diagnostic", "DiagnosticKind", "CsgBackend", "SphereStyle", "NormalMode", "Precision", "Timings", "NodeTiming", "MeshComponent"] {
+            assert_eq!(dts.matches(&format!("export type {} ", name)).count(), 1, "{}", name);
+        }
+        assert!(dts.contains("/**\n * Which limit tripped.\n */"));
+        assert!(dts.contains("triangleCount: number"));
+        assert!(dts.contains("vertices: Float32Array | Float64Array"));
+        assert!(dts.contains("limit: LimitExceeded | null"));
+        assert!(dts.contains("timings: Timings"));
+        assert!(dts.contains("parseMs: number"));
+        assert!(dts.contains("stats: GeometryStats"));
+        assert!(dts.contains("primitives: Record<string, number>"));
+        assert!(dts.contains("highlighted: PreviewMesh | null"));
+        assert!(dts.contains("messages: Array<Message>"));
+        assert!(dts.contains("diagnostics: Array<Diagnostic>"));
+        assert!(dts.contains("importFormats: Array<string>"));
+        assert!(dts.contains("data: Uint8Array"));
+        assert!(dts.contains("components: Array<MeshComponent>"));
+    }
+}